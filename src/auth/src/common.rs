@@ -14,13 +14,21 @@
 
 use std::sync::Arc;
 
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
 use digest::Digest;
-use secrecy::SecretString;
+use secrecy::{ExposeSecret, SecretString};
 use sha1::Sha1;
 use snafu::{ensure, OptionExt};
 
-use crate::error::{IllegalParamSnafu, InvalidConfigSnafu, Result, UserPasswordMismatchSnafu};
+use crate::error::{
+    HashPasswordSnafu, IllegalParamSnafu, InternalStateSnafu, InvalidConfigSnafu, Result,
+    UnsupportedPasswordTypeSnafu, UserPasswordMismatchSnafu,
+};
 use crate::user_info::DefaultUserInfo;
+use crate::user_provider::external_user_provider::ExternalUserProvider;
+use crate::user_provider::ldap_user_provider::{LdapVerifier, LDAP_USER_PROVIDER};
 use crate::user_provider::static_user_provider::{StaticUserProvider, STATIC_USER_PROVIDER};
 use crate::user_provider::watch_file_user_provider::{
     WatchFileUserProvider, WATCH_FILE_USER_PROVIDER,
@@ -49,6 +57,10 @@ pub fn user_provider_from_option(opt: &String) -> Result<UserProviderRef> {
         WATCH_FILE_USER_PROVIDER => {
             WatchFileUserProvider::new(content).map(|p| Arc::new(p) as UserProviderRef)
         }
+        LDAP_USER_PROVIDER => {
+            let verifier = LdapVerifier::new(content)?;
+            Ok(Arc::new(ExternalUserProvider::new(Arc::new(verifier))) as UserProviderRef)
+        }
         _ => InvalidConfigSnafu {
             value: name.to_string(),
             msg: "Invalid UserProviderOption",
@@ -80,6 +92,19 @@ pub fn auth_mysql(
     salt: Salt,
     username: &str,
     save_pwd: &[u8],
+) -> Result<()> {
+    // ref: https://github.com/mysql/mysql-server/blob/a246bad76b9271cb4333634e954040a970222e0a/sql/auth/password.cc#L62
+    let hash_stage_2 = double_sha1(save_pwd);
+    auth_mysql_with_hash_stage_2(auth_data, salt, username, &hash_stage_2)
+}
+
+/// Same as [auth_mysql], but verifies against the saved `SHA1(SHA1(password))`
+/// instead of the plaintext password.
+fn auth_mysql_with_hash_stage_2(
+    auth_data: HashedPassword,
+    salt: Salt,
+    username: &str,
+    hash_stage_2: &[u8],
 ) -> Result<()> {
     ensure!(
         auth_data.len() == 20,
@@ -87,9 +112,7 @@ pub fn auth_mysql(
             msg: "Illegal mysql password length"
         }
     );
-    // ref: https://github.com/mysql/mysql-server/blob/a246bad76b9271cb4333634e954040a970222e0a/sql/auth/password.cc#L62
-    let hash_stage_2 = double_sha1(save_pwd);
-    let tmp = sha1_two(salt, &hash_stage_2);
+    let tmp = sha1_two(salt, hash_stage_2);
     // xor auth_data and tmp
     let mut xor_result = [0u8; 20];
     for i in 0..20 {
@@ -106,6 +129,106 @@ pub fn auth_mysql(
     }
 }
 
+/// The hashes of a password that are saved instead of the password itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordHashes {
+    /// The salted argon2 hash in the PHC string format.
+    pub password_hash: String,
+    /// `SHA1(SHA1(password))` in hex, for the MySQL native password authentication.
+    ///
+    /// It's unsalted and as weak as the MySQL protocol, so it's only kept for users
+    /// who opt in to the MySQL native password authentication.
+    pub mysql_native_password: Option<String>,
+}
+
+impl PasswordHashes {
+    /// Hashes `password` with a random salt, and also keeps the hash for the MySQL
+    /// native password authentication if `mysql_native_password` is set.
+    pub fn new(password: &str, mysql_native_password: bool) -> Result<Self> {
+        ensure!(
+            !password.is_empty(),
+            IllegalParamSnafu {
+                msg: "blank password"
+            }
+        );
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|e| HashPasswordSnafu { msg: e.to_string() }.build())?
+            .to_string();
+
+        Ok(Self {
+            password_hash,
+            mysql_native_password: mysql_native_password
+                .then(|| hex::encode(double_sha1(password.as_bytes()))),
+        })
+    }
+
+    /// Checks whether `password` of `username` matches the hashes.
+    pub fn verify(&self, username: &str, password: Password<'_>) -> Result<()> {
+        match password {
+            Password::PlainText(pwd) => {
+                let parsed = PasswordHash::new(&self.password_hash).map_err(|e| {
+                    InternalStateSnafu {
+                        msg: format!("invalid password hash of user {username}: {e}"),
+                    }
+                    .build()
+                })?;
+                ensure!(
+                    Argon2::default()
+                        .verify_password(pwd.expose_secret().as_bytes(), &parsed)
+                        .is_ok(),
+                    UserPasswordMismatchSnafu { username }
+                );
+                Ok(())
+            }
+            Password::MysqlNativePassword(auth_data, salt) => {
+                let mysql_native_password =
+                    self.mysql_native_password
+                        .as_ref()
+                        .context(UnsupportedPasswordTypeSnafu {
+                            password_type: "mysql_native_password",
+                        })?;
+                let hash_stage_2 = hex::decode(mysql_native_password).map_err(|e| {
+                    InternalStateSnafu {
+                        msg: format!("invalid mysql native password of user {username}: {e}"),
+                    }
+                    .build()
+                })?;
+                auth_mysql_with_hash_stage_2(auth_data, salt, username, &hash_stage_2)
+            }
+            Password::PgMD5(_, _) => UnsupportedPasswordTypeSnafu {
+                password_type: "pg_md5",
+            }
+            .fail(),
+        }
+    }
+}
+
+/// Digests plain text passwords with a random key of the process, so that successful
+/// verifications can be cached without keeping the passwords or their slow hashes.
+pub struct PasswordDigester {
+    key: [u8; 32],
+}
+
+impl Default for PasswordDigester {
+    fn default() -> Self {
+        let mut key = [0; 32];
+        OsRng.fill_bytes(&mut key);
+        Self { key }
+    }
+}
+
+impl PasswordDigester {
+    /// Returns the digest of a plain text `password`, or `None` for other passwords.
+    pub fn digest(&self, password: &Password<'_>) -> Option<Vec<u8>> {
+        match password {
+            Password::PlainText(pwd) => Some(sha1_two(&self.key, pwd.expose_secret().as_bytes())),
+            Password::MysqlNativePassword(_, _) | Password::PgMD5(_, _) => None,
+        }
+    }
+}
+
 fn sha1_two(input_1: &[u8], input_2: &[u8]) -> Vec<u8> {
     let mut hasher = Sha1::new();
     hasher.update(input_1);
@@ -150,4 +273,85 @@ mod tests {
         let sha1_2 = sha1_two("123456".as_bytes(), "654321".as_bytes());
         assert_eq!(sha1_2, sha1_2_answer);
     }
+
+    #[test]
+    fn test_password_hashes() {
+        let hashes = PasswordHashes::new("123456", true).unwrap();
+        assert!(hashes.password_hash.starts_with("$argon2id$"));
+        assert_eq!(
+            Some("6bb4837eb74329105ee4568dda7dc67ed2ca2ad9"),
+            hashes.mysql_native_password.as_deref()
+        );
+        // Salts are random.
+        assert_ne!(
+            hashes.password_hash,
+            PasswordHashes::new("123456", true).unwrap().password_hash
+        );
+        assert!(PasswordHashes::new("", false).is_err());
+
+        hashes
+            .verify("root", Password::PlainText("123456".to_string().into()))
+            .unwrap();
+        assert!(hashes
+            .verify("root", Password::PlainText("654321".to_string().into()))
+            .is_err());
+
+        let salt = b"01234567890123456789";
+        // SHA1(password) XOR SHA1(salt + SHA1(SHA1(password)))
+        let stage_1 = sha1_one(b"123456");
+        let tmp = sha1_two(salt, &double_sha1(b"123456"));
+        let auth_data = stage_1
+            .iter()
+            .zip(tmp.iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        hashes
+            .verify("root", Password::MysqlNativePassword(&auth_data, salt))
+            .unwrap();
+        assert!(hashes
+            .verify(
+                "root",
+                Password::MysqlNativePassword(&auth_data, b"98765432109876543210")
+            )
+            .is_err());
+
+        // The MySQL native password authentication is opt-in.
+        let hashes = PasswordHashes::new("123456", false).unwrap();
+        assert!(hashes.mysql_native_password.is_none());
+        assert!(hashes
+            .verify("root", Password::MysqlNativePassword(&auth_data, salt))
+            .is_err());
+    }
+
+    #[test]
+    fn test_password_digester() {
+        let digester = PasswordDigester::default();
+        let digest = |pwd: &str| digester.digest(&Password::PlainText(pwd.to_string().into()));
+
+        assert_eq!(digest("123456"), digest("123456"));
+        assert_ne!(digest("123456"), digest("654321"));
+        // Keys are random.
+        assert_ne!(
+            digest("123456"),
+            PasswordDigester::default().digest(&Password::PlainText("123456".to_string().into()))
+        );
+        assert!(digester
+            .digest(&Password::MysqlNativePassword(b"", b""))
+            .is_none());
+    }
+
+    #[test]
+    fn test_ldap_user_provider_from_option() {
+        let provider = user_provider_from_option(
+            &"ldap_user_provider:url=ldap://127.0.0.1:389;bind_dn=uid={username},dc=example,dc=com"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(LDAP_USER_PROVIDER, provider.name());
+
+        assert!(user_provider_from_option(
+            &"ldap_user_provider:url=ldap://127.0.0.1:389".to_string()
+        )
+        .is_err());
+    }
 }
@@ -30,6 +30,9 @@ pub enum Error {
     #[snafu(display("Internal state error: {}", msg))]
     InternalState { msg: String },
 
+    #[snafu(display("Failed to hash password: {}", msg))]
+    HashPassword { msg: String, location: Location },
+
     #[snafu(display("IO error"))]
     Io {
         #[snafu(source)]
@@ -43,6 +46,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to authenticate with LDAP server"))]
+    Ldap {
+        #[snafu(source)]
+        error: ldap3::LdapError,
+        location: Location,
+    },
+
     #[snafu(display("User not found, username: {}", username))]
     UserNotFound { username: String },
 
@@ -73,6 +83,19 @@ pub enum Error {
 
     #[snafu(display("User is not authorized to perform this action"))]
     PermissionDenied { location: Location },
+
+    #[snafu(display(
+        "User '{}' doesn't have {} privilege on {}",
+        username,
+        privilege,
+        object
+    ))]
+    PrivilegeDenied {
+        username: String,
+        privilege: String,
+        object: String,
+        location: Location,
+    },
 }
 
 impl ErrorExt for Error {
@@ -83,13 +106,17 @@ impl ErrorExt for Error {
             Error::FileWatch { .. } => StatusCode::InvalidArguments,
             Error::InternalState { .. } => StatusCode::Unexpected,
             Error::Io { .. } => StatusCode::Internal,
+            Error::HashPassword { .. } => StatusCode::Internal,
             Error::AuthBackend { .. } => StatusCode::Internal,
+            Error::Ldap { .. } => StatusCode::Internal,
 
             Error::UserNotFound { .. } => StatusCode::UserNotFound,
             Error::UnsupportedPasswordType { .. } => StatusCode::UnsupportedPasswordType,
             Error::UserPasswordMismatch { .. } => StatusCode::UserPasswordMismatch,
             Error::AccessDenied { .. } => StatusCode::AccessDenied,
-            Error::PermissionDenied { .. } => StatusCode::PermissionDenied,
+            Error::PermissionDenied { .. } | Error::PrivilegeDenied { .. } => {
+                StatusCode::PermissionDenied
+            }
         }
     }
 
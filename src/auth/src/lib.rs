@@ -23,9 +23,13 @@ pub mod tests;
 
 pub use common::{
     auth_mysql, user_provider_from_option, userinfo_by_name, HashedPassword, Identity, Password,
+    PasswordDigester, PasswordHashes,
 };
 pub use permission::{PermissionChecker, PermissionReq, PermissionResp};
 pub use user_info::UserInfo;
+pub use user_provider::external_user_provider::{
+    ExternalUserProvider, ExternalVerifier, ExternalVerifierRef,
+};
 pub use user_provider::UserProvider;
 
 /// pub type alias
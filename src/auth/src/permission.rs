@@ -30,6 +30,8 @@ pub enum PermissionReq<'a> {
     PromStoreWrite,
     PromStoreRead,
     Otlp,
+    /// Administrative operations, e.g. the debug and profiling HTTP APIs.
+    Admin,
 }
 
 #[derive(Debug)]
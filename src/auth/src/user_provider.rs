@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod external_user_provider;
+pub(crate) mod ldap_user_provider;
 pub(crate) mod static_user_provider;
 pub(crate) mod watch_file_user_provider;
 
@@ -36,6 +38,13 @@ use crate::{auth_mysql, UserInfoRef};
 pub trait UserProvider: Send + Sync {
     fn name(&self) -> &str;
 
+    /// Whether the provider can only verify plain text passwords, e.g. it asks an
+    /// external service to verify them. Protocols that hash passwords by default
+    /// should ask clients for plain text passwords instead, over secure connections.
+    fn plain_text_only(&self) -> bool {
+        false
+    }
+
     /// Checks whether a user is valid and allowed to access the database.
     async fn authenticate(&self, id: Identity<'_>, password: Password<'_>) -> Result<UserInfoRef>;
 
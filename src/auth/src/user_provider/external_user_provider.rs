@@ -0,0 +1,218 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use moka::future::Cache;
+use secrecy::{ExposeSecret, SecretString};
+use snafu::ensure;
+
+use crate::error::{IllegalParamSnafu, Result, UnsupportedPasswordTypeSnafu};
+use crate::user_info::DefaultUserInfo;
+use crate::{Identity, Password, PasswordDigester, UserInfoRef, UserProvider};
+
+const VERIFIED_CACHE_MAX_CAPACITY: u64 = 1024;
+/// How long a successful verification is reused before asking the external service again.
+const VERIFIED_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Verifies passwords against an identity service outside GreptimeDB, e.g. LDAP.
+#[async_trait]
+pub trait ExternalVerifier: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Checks the plaintext `password` of `username`. Returns
+    /// [UserPasswordMismatch](crate::error::Error::UserPasswordMismatch) if it's wrong.
+    async fn verify(&self, username: &str, password: &SecretString) -> Result<()>;
+}
+
+pub type ExternalVerifierRef = Arc<dyn ExternalVerifier>;
+
+/// A user provider that delegates password verification to an [ExternalVerifier].
+///
+/// External services need the plaintext password, so hashed passwords like
+/// `mysql_native_password` are rejected. Protocols like HTTP authenticate each
+/// request, so successful verifications are cached for a while, otherwise every
+/// request would make a round trip to the external service.
+pub struct ExternalUserProvider {
+    verifier: ExternalVerifierRef,
+    /// The digests of the passwords that were verified, by user.
+    verified: Cache<String, Vec<u8>>,
+    digester: PasswordDigester,
+}
+
+impl ExternalUserProvider {
+    pub fn new(verifier: ExternalVerifierRef) -> Self {
+        Self {
+            verifier,
+            verified: Cache::builder()
+                .max_capacity(VERIFIED_CACHE_MAX_CAPACITY)
+                .time_to_live(VERIFIED_CACHE_TTL)
+                .build(),
+            digester: PasswordDigester::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl UserProvider for ExternalUserProvider {
+    fn name(&self) -> &str {
+        self.verifier.name()
+    }
+
+    fn plain_text_only(&self) -> bool {
+        true
+    }
+
+    async fn authenticate(&self, id: Identity<'_>, password: Password<'_>) -> Result<UserInfoRef> {
+        let Identity::UserId(username, _) = id;
+        ensure!(
+            !username.is_empty(),
+            IllegalParamSnafu {
+                msg: "blank username"
+            }
+        );
+
+        let digest = self.digester.digest(&password);
+        if let Some(digest) = &digest {
+            if self.verified.get(username).await.as_ref() == Some(digest) {
+                return Ok(DefaultUserInfo::with_name(username));
+            }
+        }
+
+        match password {
+            Password::PlainText(pwd) => {
+                // An empty password makes an unauthenticated bind in some services,
+                // which always succeeds.
+                ensure!(
+                    !pwd.expose_secret().is_empty(),
+                    IllegalParamSnafu {
+                        msg: "blank password"
+                    }
+                );
+                self.verifier.verify(username, &pwd).await?;
+                if let Some(digest) = digest {
+                    self.verified.insert(username.to_string(), digest).await;
+                }
+                Ok(DefaultUserInfo::with_name(username))
+            }
+            Password::MysqlNativePassword(_, _) => UnsupportedPasswordTypeSnafu {
+                password_type: "mysql_native_password",
+            }
+            .fail(),
+            Password::PgMD5(_, _) => UnsupportedPasswordTypeSnafu {
+                password_type: "pg_md5",
+            }
+            .fail(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _user_info: &UserInfoRef,
+    ) -> Result<()> {
+        // default allow all
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+
+    use super::*;
+    use crate::error::UserPasswordMismatchSnafu;
+
+    #[derive(Default)]
+    struct MockVerifier {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ExternalVerifier for MockVerifier {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn verify(&self, username: &str, password: &SecretString) -> Result<()> {
+            let _ = self.calls.fetch_add(1, Ordering::Relaxed);
+            ensure!(
+                username == "root" && password.expose_secret() == "123456",
+                UserPasswordMismatchSnafu { username }
+            );
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_external_user_provider() {
+        let verifier = Arc::new(MockVerifier::default());
+        let provider = ExternalUserProvider::new(verifier.clone());
+        assert_eq!("mock", provider.name());
+        assert!(provider.plain_text_only());
+
+        let user_info = provider
+            .authenticate(
+                Identity::UserId("root", None),
+                Password::PlainText("123456".to_string().into()),
+            )
+            .await
+            .unwrap();
+        assert_eq!("root", user_info.username());
+        assert_eq!(1, verifier.calls.load(Ordering::Relaxed));
+
+        // A verified password is served from the cache.
+        let _ = provider
+            .authenticate(
+                Identity::UserId("root", None),
+                Password::PlainText("123456".to_string().into()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(1, verifier.calls.load(Ordering::Relaxed));
+
+        let err = provider
+            .authenticate(
+                Identity::UserId("root", None),
+                Password::PlainText("654321".to_string().into()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::UserPasswordMismatch, err.status_code());
+        assert_eq!(2, verifier.calls.load(Ordering::Relaxed));
+
+        let err = provider
+            .authenticate(
+                Identity::UserId("root", None),
+                Password::PlainText(String::new().into()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        let err = provider
+            .authenticate(
+                Identity::UserId("root", None),
+                Password::MysqlNativePassword(b"auth_data", b"salt"),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::UnsupportedPasswordType, err.status_code());
+    }
+}
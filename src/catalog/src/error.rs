@@ -203,6 +203,22 @@ pub enum Error {
     #[snafu(display("Failed to upgrade weak catalog manager reference"))]
     UpgradeWeakCatalogManagerRef { location: Location },
 
+    #[snafu(display("Query {} is killed", id))]
+    QueryCancelled { id: u64, location: Location },
+
+    #[snafu(display("Database {} exceeds max_concurrent_queries {}", database, max_queries))]
+    TooManyQueries {
+        database: String,
+        max_queries: usize,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to access the process registry"))]
+    ProcessRegistry {
+        source: common_meta::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("Failed to execute system catalog table scan"))]
     SystemCatalogTableScanExec {
         location: Location,
@@ -314,9 +330,13 @@ impl ErrorExt for Error {
             }
 
             Error::Unimplemented { .. } | Error::NotSupported { .. } => StatusCode::Unsupported,
+            Error::QueryCancelled { .. } => StatusCode::Cancelled,
+            Error::TooManyQueries { .. } => StatusCode::QuotaExceeded,
             Error::QueryAccessDenied { .. } => StatusCode::AccessDenied,
             Error::Datafusion { .. } => StatusCode::EngineExecuteQuery,
-            Error::TableMetadataManager { source, .. } => source.status_code(),
+            Error::TableMetadataManager { source, .. } | Error::ProcessRegistry { source, .. } => {
+                source.status_code()
+            }
             Error::TableCacheNotGet { .. } | Error::GetTableCache { .. } => StatusCode::Internal,
         }
     }
@@ -0,0 +1,73 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_meta::cluster::{ClusterInfo, NodeInfo, NodeStatus, RegionStat};
+use common_meta::DatanodeId;
+use meta_client::client::MetaClient;
+use snafu::ResultExt;
+
+use crate::error::{MetasrvSnafu, Result};
+
+/// Provides runtime information of nodes and regions for `information_schema` tables.
+#[async_trait::async_trait]
+pub trait InformationExtension: Send + Sync {
+    /// Returns all nodes in the cluster.
+    async fn nodes(&self) -> Result<Vec<NodeInfo>>;
+
+    /// Returns statistics of all regions and the id of the datanode they are on.
+    async fn region_stats(&self) -> Result<Vec<(DatanodeId, RegionStat)>>;
+}
+
+pub type InformationExtensionRef = Arc<dyn InformationExtension>;
+
+/// The [InformationExtension] of the distributed mode, which gets information from
+/// the cluster info collected by metasrv.
+pub struct DistributedInformationExtension {
+    meta_client: Arc<MetaClient>,
+}
+
+impl DistributedInformationExtension {
+    pub fn new(meta_client: Arc<MetaClient>) -> Self {
+        Self { meta_client }
+    }
+}
+
+#[async_trait::async_trait]
+impl InformationExtension for DistributedInformationExtension {
+    async fn nodes(&self) -> Result<Vec<NodeInfo>> {
+        self.meta_client
+            .list_nodes(None)
+            .await
+            .context(MetasrvSnafu)
+    }
+
+    async fn region_stats(&self) -> Result<Vec<(DatanodeId, RegionStat)>> {
+        let nodes = self
+            .meta_client
+            .list_nodes(Some(common_meta::cluster::Role::Datanode))
+            .await
+            .context(MetasrvSnafu)?;
+
+        Ok(nodes
+            .into_iter()
+            .filter_map(|node| match node.status {
+                NodeStatus::Datanode(status) => Some((node.peer.id, status.region_stats)),
+                _ => None,
+            })
+            .flat_map(|(datanode_id, stats)| stats.into_iter().map(move |s| (datanode_id, s)))
+            .collect())
+    }
+}
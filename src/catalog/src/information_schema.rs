@@ -12,17 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cluster_info;
 pub mod columns;
+pub mod ingestion_stats;
 pub mod key_column_usage;
 mod memory_table;
 mod partitions;
 mod predicate;
+pub mod processlist;
 mod region_peers;
+mod region_statistics;
 mod runtime_metrics;
 pub mod schemata;
+mod slow_queries;
+mod statements_summary;
 mod table_constraints;
 mod table_names;
 pub mod tables;
+mod views;
 
 use std::collections::HashMap;
 use std::sync::{Arc, Weak};
@@ -47,15 +54,22 @@ pub use table_names::*;
 
 use self::columns::InformationSchemaColumns;
 use crate::error::Result;
+use crate::information_schema::cluster_info::InformationSchemaClusterInfo;
+use crate::information_schema::ingestion_stats::InformationSchemaIngestionStats;
 use crate::information_schema::key_column_usage::InformationSchemaKeyColumnUsage;
 use crate::information_schema::memory_table::{get_schema_columns, MemoryTable};
 use crate::information_schema::partitions::InformationSchemaPartitions;
+use crate::information_schema::processlist::InformationSchemaProcesslist;
 use crate::information_schema::region_peers::InformationSchemaRegionPeers;
+use crate::information_schema::region_statistics::InformationSchemaRegionStatistics;
 use crate::information_schema::runtime_metrics::InformationSchemaMetrics;
 use crate::information_schema::schemata::InformationSchemaSchemata;
+use crate::information_schema::slow_queries::InformationSchemaSlowQueries;
+use crate::information_schema::statements_summary::InformationSchemaStatementsSummary;
 use crate::information_schema::table_constraints::InformationSchemaTableConstraints;
 use crate::information_schema::tables::InformationSchemaTables;
-use crate::CatalogManager;
+use crate::information_schema::views::InformationSchemaViews;
+use crate::{CatalogManager, CatalogManagerRef};
 
 lazy_static! {
     // Memory tables in `information_schema`.
@@ -166,6 +180,14 @@ impl InformationSchemaProvider {
                 REGION_PEERS.to_string(),
                 self.build_table(REGION_PEERS).unwrap(),
             );
+            tables.insert(
+                REGION_STATISTICS.to_string(),
+                self.build_table(REGION_STATISTICS).unwrap(),
+            );
+            tables.insert(
+                CLUSTER_INFO.to_string(),
+                self.build_table(CLUSTER_INFO).unwrap(),
+            );
         }
 
         tables.insert(TABLES.to_string(), self.build_table(TABLES).unwrap());
@@ -179,6 +201,23 @@ impl InformationSchemaProvider {
             TABLE_CONSTRAINTS.to_string(),
             self.build_table(TABLE_CONSTRAINTS).unwrap(),
         );
+        tables.insert(VIEWS.to_string(), self.build_table(VIEWS).unwrap());
+        tables.insert(
+            INGESTION_STATS.to_string(),
+            self.build_table(INGESTION_STATS).unwrap(),
+        );
+        tables.insert(
+            PROCESSLIST.to_string(),
+            self.build_table(PROCESSLIST).unwrap(),
+        );
+        tables.insert(
+            STATEMENTS_SUMMARY.to_string(),
+            self.build_table(STATEMENTS_SUMMARY).unwrap(),
+        );
+        tables.insert(
+            SLOW_QUERIES.to_string(),
+            self.build_table(SLOW_QUERIES).unwrap(),
+        );
 
         // Add memory tables
         for name in MEMORY_TABLES.iter() {
@@ -188,14 +227,43 @@ impl InformationSchemaProvider {
         self.tables = tables;
     }
 
+    /// Returns the `information_schema` table `table` of the catalog as the user `viewer`
+    /// reads it. The `PROCESSLIST` only lists the queries of the user unless the user
+    /// is an admin, other tables are returned as is.
+    pub fn table_for_viewer(
+        catalog_manager: &CatalogManagerRef,
+        catalog_name: &str,
+        table: TableRef,
+        viewer: Option<&str>,
+    ) -> TableRef {
+        let Some(viewer) = viewer else {
+            return table;
+        };
+        if table.table_info().table_id() != consts::INFORMATION_SCHEMA_PROCESSLIST_TABLE_ID {
+            return table;
+        }
+
+        let processlist = Arc::new(
+            InformationSchemaProcesslist::new(
+                catalog_name.to_string(),
+                Arc::downgrade(catalog_manager),
+            )
+            .with_viewer(viewer.to_string()),
+        );
+        Self::new_table(catalog_name.to_string(), processlist)
+    }
+
     fn build_table(&self, name: &str) -> Option<TableRef> {
-        self.information_table(name).map(|table| {
-            let table_info = Self::table_info(self.catalog_name.clone(), &table);
-            let filter_pushdown = FilterPushDownType::Inexact;
-            let data_source = Arc::new(InformationTableDataSource::new(table));
-            let table = Table::new(table_info, filter_pushdown, data_source);
-            Arc::new(table)
-        })
+        self.information_table(name)
+            .map(|table| Self::new_table(self.catalog_name.clone(), table))
+    }
+
+    fn new_table(catalog_name: String, table: InformationTableRef) -> TableRef {
+        let table_info = Self::table_info(catalog_name, &table);
+        let filter_pushdown = FilterPushDownType::Inexact;
+        let data_source = Arc::new(InformationTableDataSource::new(table));
+        let table = Table::new(table_info, filter_pushdown, data_source);
+        Arc::new(table)
     }
 
     fn information_table(&self, name: &str) -> Option<InformationTableRef> {
@@ -251,6 +319,31 @@ impl InformationSchemaProvider {
                 self.catalog_name.clone(),
                 self.catalog_manager.clone(),
             )) as _),
+            VIEWS => Some(Arc::new(InformationSchemaViews::new(
+                self.catalog_name.clone(),
+                self.catalog_manager.clone(),
+            )) as _),
+            INGESTION_STATS => Some(Arc::new(InformationSchemaIngestionStats::new(
+                self.catalog_name.clone(),
+            )) as _),
+            REGION_STATISTICS => Some(Arc::new(InformationSchemaRegionStatistics::new(
+                self.catalog_manager.clone(),
+            )) as _),
+            CLUSTER_INFO => Some(Arc::new(InformationSchemaClusterInfo::new(
+                self.catalog_manager.clone(),
+            )) as _),
+            PROCESSLIST => Some(Arc::new(InformationSchemaProcesslist::new(
+                self.catalog_name.clone(),
+                self.catalog_manager.clone(),
+            )) as _),
+            STATEMENTS_SUMMARY => Some(Arc::new(InformationSchemaStatementsSummary::new(
+                self.catalog_name.clone(),
+                self.catalog_manager.clone(),
+            )) as _),
+            SLOW_QUERIES => Some(Arc::new(InformationSchemaSlowQueries::new(
+                self.catalog_name.clone(),
+                self.catalog_manager.clone(),
+            )) as _),
             _ => None,
         }
     }
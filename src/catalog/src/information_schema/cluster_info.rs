@@ -0,0 +1,243 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Weak};
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_CLUSTER_INFO_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_meta::cluster::NodeInfo;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use common_time::util::current_time_millis;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::value::Value;
+use datatypes::vectors::{
+    Int64VectorBuilder, StringVectorBuilder, TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+};
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{ScanRequest, TableId};
+
+use super::CLUSTER_INFO;
+use crate::error::{
+    CreateRecordBatchSnafu, InternalSnafu, Result, UpgradeWeakCatalogManagerRefSnafu,
+};
+use crate::information_schema::{InformationTable, Predicates};
+use crate::kvbackend::KvBackendCatalogManager;
+use crate::CatalogManager;
+
+const PEER_ID: &str = "peer_id";
+const PEER_TYPE: &str = "peer_type";
+const PEER_ADDR: &str = "peer_addr";
+const START_TIME: &str = "start_time";
+const UPTIME_SECONDS: &str = "uptime_seconds";
+const LAST_ACTIVITY_TIME: &str = "last_activity_time";
+const INIT_CAPACITY: usize = 42;
+
+/// The `CLUSTER_INFO` table provides information about the nodes in the cluster. Including fields:
+///
+/// - `peer_id`: the node id
+/// - `peer_type`: the role of the node, `DATANODE`, `FRONTEND`, `METASRV` or `STANDALONE`
+/// - `peer_addr`: the address of the node
+/// - `start_time`: the start time of the node, null if unknown
+/// - `uptime_seconds`: how long the node has been running, in seconds
+/// - `last_activity_time`: the time of the last heartbeat from the node, null if unknown
+///
+pub(super) struct InformationSchemaClusterInfo {
+    schema: SchemaRef,
+    catalog_manager: Weak<dyn CatalogManager>,
+}
+
+impl InformationSchemaClusterInfo {
+    pub(super) fn new(catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_manager,
+        }
+    }
+
+    pub(crate) fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(PEER_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(PEER_TYPE, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(PEER_ADDR, ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                START_TIME,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new(UPTIME_SECONDS, ConcreteDataType::int64_datatype(), true),
+            ColumnSchema::new(
+                LAST_ACTIVITY_TIME,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaClusterInfoBuilder {
+        InformationSchemaClusterInfoBuilder::new(self.schema.clone(), self.catalog_manager.clone())
+    }
+}
+
+impl InformationTable for InformationSchemaClusterInfo {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_CLUSTER_INFO_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        CLUSTER_INFO
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_cluster_info(Some(request))
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaClusterInfoBuilder {
+    schema: SchemaRef,
+    catalog_manager: Weak<dyn CatalogManager>,
+
+    peer_ids: UInt64VectorBuilder,
+    peer_types: StringVectorBuilder,
+    peer_addrs: StringVectorBuilder,
+    start_times: TimestampMillisecondVectorBuilder,
+    uptime_seconds: Int64VectorBuilder,
+    last_activity_times: TimestampMillisecondVectorBuilder,
+}
+
+impl InformationSchemaClusterInfoBuilder {
+    fn new(schema: SchemaRef, catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema,
+            catalog_manager,
+            peer_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            peer_types: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            peer_addrs: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            start_times: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+            uptime_seconds: Int64VectorBuilder::with_capacity(INIT_CAPACITY),
+            last_activity_times: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+        }
+    }
+
+    /// Construct the `information_schema.cluster_info` virtual table
+    async fn make_cluster_info(&mut self, request: Option<ScanRequest>) -> Result<RecordBatch> {
+        let catalog_manager = self
+            .catalog_manager
+            .upgrade()
+            .context(UpgradeWeakCatalogManagerRefSnafu)?;
+
+        let information_extension = catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+            .and_then(|catalog_manager| catalog_manager.information_extension());
+
+        let predicates = Predicates::from_scan_request(&request);
+
+        if let Some(information_extension) = information_extension {
+            let now = current_time_millis();
+            for node in information_extension.nodes().await? {
+                self.add_node_info(&predicates, &node, now);
+            }
+        }
+
+        self.finish()
+    }
+
+    fn add_node_info(&mut self, predicates: &Predicates, node: &NodeInfo, now: i64) {
+        let peer_type = node.status.role_name();
+
+        let row = [
+            (PEER_ID, &Value::from(node.peer.id)),
+            (PEER_TYPE, &Value::from(peer_type)),
+        ];
+
+        if !predicates.eval(&row) {
+            return;
+        }
+
+        // Zero or negative timestamps mean the node doesn't report them.
+        let start_time = (node.start_time_ms > 0).then_some(node.start_time_ms as i64);
+        let last_activity_time = (node.last_activity_ts > 0).then_some(node.last_activity_ts);
+
+        self.peer_ids.push(Some(node.peer.id));
+        self.peer_types.push(Some(peer_type));
+        self.peer_addrs.push(Some(&node.peer.addr));
+        self.start_times
+            .push(start_time.map(TimestampMillisecond::new));
+        self.uptime_seconds
+            .push(start_time.map(|start| (now - start).max(0) / 1000));
+        self.last_activity_times
+            .push(last_activity_time.map(TimestampMillisecond::new));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.peer_ids.finish()),
+            Arc::new(self.peer_types.finish()),
+            Arc::new(self.peer_addrs.finish()),
+            Arc::new(self.start_times.finish()),
+            Arc::new(self.uptime_seconds.finish()),
+            Arc::new(self.last_activity_times.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaClusterInfo {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_cluster_info(None)
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_INGESTION_STATS_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, MutableVector};
+use datatypes::scalars::ScalarVectorBuilder;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVectorBuilder, UInt64VectorBuilder, VectorRef};
+use prometheus::proto::MetricFamily;
+use snafu::ResultExt;
+use store_api::storage::{ScanRequest, TableId};
+
+use super::{InformationTable, INGESTION_STATS};
+use crate::error::{CreateRecordBatchSnafu, InternalSnafu, Result};
+
+/// Name of the counter of ingested rows, labeled by [INGEST_LABELS].
+pub const INGEST_ROWS_METRIC: &str = "greptime_operator_ingest_schema_rows";
+/// Name of the counter of ingested bytes, labeled by [INGEST_LABELS].
+pub const INGEST_BYTES_METRIC: &str = "greptime_operator_ingest_schema_bytes";
+/// Name of the counter of failed ingestion requests, labeled by [INGEST_LABELS].
+pub const INGEST_ERRORS_METRIC: &str = "greptime_operator_ingest_schema_errors";
+/// Labels of the ingestion metrics.
+pub const INGEST_LABELS: &[&str] = &[CATALOG_LABEL, SCHEMA_LABEL, PROTOCOL_LABEL];
+
+const CATALOG_LABEL: &str = "catalog";
+const SCHEMA_LABEL: &str = "schema";
+const PROTOCOL_LABEL: &str = "protocol";
+
+const TABLE_CATALOG: &str = "table_catalog";
+const TABLE_SCHEMA: &str = "table_schema";
+const PROTOCOL: &str = "protocol";
+const INGESTED_ROWS: &str = "ingested_rows";
+const INGESTED_BYTES: &str = "ingested_bytes";
+const ERRORS: &str = "errors";
+
+/// The `information_schema.ingestion_stats` virtual table.
+/// It exposes the rows, bytes and errors ingested by this node, per schema and protocol.
+pub(super) struct InformationSchemaIngestionStats {
+    schema: SchemaRef,
+    catalog_name: String,
+}
+
+impl InformationSchemaIngestionStats {
+    pub(super) fn new(catalog_name: String) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_name,
+        }
+    }
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(TABLE_CATALOG, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(TABLE_SCHEMA, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(PROTOCOL, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(INGESTED_ROWS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(INGESTED_BYTES, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(ERRORS, ConcreteDataType::uint64_datatype(), false),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaIngestionStatsBuilder {
+        InformationSchemaIngestionStatsBuilder::new(self.schema.clone(), self.catalog_name.clone())
+    }
+}
+
+impl InformationTable for InformationSchemaIngestionStats {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_INGESTION_STATS_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        INGESTION_STATS
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, _request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_ingestion_stats(prometheus::gather())
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+/// Ingestion counters of a (schema, protocol) pair.
+#[derive(Debug, Default, PartialEq)]
+struct IngestionStats {
+    rows: u64,
+    bytes: u64,
+    errors: u64,
+}
+
+struct InformationSchemaIngestionStatsBuilder {
+    schema: SchemaRef,
+    catalog_name: String,
+
+    catalog_names: StringVectorBuilder,
+    schema_names: StringVectorBuilder,
+    protocols: StringVectorBuilder,
+    rows: UInt64VectorBuilder,
+    bytes: UInt64VectorBuilder,
+    errors: UInt64VectorBuilder,
+}
+
+impl InformationSchemaIngestionStatsBuilder {
+    fn new(schema: SchemaRef, catalog_name: String) -> Self {
+        Self {
+            schema,
+            catalog_name,
+            catalog_names: StringVectorBuilder::with_capacity(16),
+            schema_names: StringVectorBuilder::with_capacity(16),
+            protocols: StringVectorBuilder::with_capacity(16),
+            rows: UInt64VectorBuilder::with_capacity(16),
+            bytes: UInt64VectorBuilder::with_capacity(16),
+            errors: UInt64VectorBuilder::with_capacity(16),
+        }
+    }
+
+    fn make_ingestion_stats(&mut self, metric_families: Vec<MetricFamily>) -> Result<RecordBatch> {
+        for ((schema, protocol), stats) in
+            collect_ingestion_stats(&self.catalog_name, &metric_families)
+        {
+            self.catalog_names.push(Some(&self.catalog_name));
+            self.schema_names.push(Some(&schema));
+            self.protocols.push(Some(&protocol));
+            self.rows.push(Some(stats.rows));
+            self.bytes.push(Some(stats.bytes));
+            self.errors.push(Some(stats.errors));
+        }
+
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.catalog_names.finish()),
+            Arc::new(self.schema_names.finish()),
+            Arc::new(self.protocols.finish()),
+            Arc::new(self.rows.finish()),
+            Arc::new(self.bytes.finish()),
+            Arc::new(self.errors.finish()),
+        ];
+
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+/// Collects the ingestion counters of schemas in `catalog` from `metric_families`,
+/// keyed by (schema, protocol).
+fn collect_ingestion_stats(
+    catalog: &str,
+    metric_families: &[MetricFamily],
+) -> BTreeMap<(String, String), IngestionStats> {
+    let mut stats: BTreeMap<(String, String), IngestionStats> = BTreeMap::new();
+
+    for mf in metric_families {
+        let name = mf.get_name();
+        if ![
+            INGEST_ROWS_METRIC,
+            INGEST_BYTES_METRIC,
+            INGEST_ERRORS_METRIC,
+        ]
+        .contains(&name)
+        {
+            continue;
+        }
+
+        for m in mf.get_metric() {
+            let label = |name: &str| {
+                m.get_label()
+                    .iter()
+                    .find(|l| l.get_name() == name)
+                    .map(|l| l.get_value())
+            };
+            let (Some(metric_catalog), Some(schema), Some(protocol)) = (
+                label(CATALOG_LABEL),
+                label(SCHEMA_LABEL),
+                label(PROTOCOL_LABEL),
+            ) else {
+                continue;
+            };
+            if metric_catalog != catalog {
+                continue;
+            }
+
+            let entry = stats
+                .entry((schema.to_string(), protocol.to_string()))
+                .or_default();
+            let value = m.get_counter().get_value() as u64;
+            match name {
+                INGEST_ROWS_METRIC => entry.rows += value,
+                INGEST_BYTES_METRIC => entry.bytes += value,
+                _ => entry.errors += value,
+            }
+        }
+    }
+
+    stats
+}
+
+impl DfPartitionStream for InformationSchemaIngestionStats {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_ingestion_stats(prometheus::gather())
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::{IntCounterVec, Opts, Registry};
+
+    use super::*;
+
+    fn register_counter(registry: &Registry, name: &str) -> IntCounterVec {
+        let counter = IntCounterVec::new(Opts::new(name, name), INGEST_LABELS).unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        counter
+    }
+
+    #[test]
+    fn test_collect_ingestion_stats() {
+        let registry = Registry::new();
+        let rows = register_counter(&registry, INGEST_ROWS_METRIC);
+        let bytes = register_counter(&registry, INGEST_BYTES_METRIC);
+        let errors = register_counter(&registry, INGEST_ERRORS_METRIC);
+
+        rows.with_label_values(&["greptime", "public", "grpc"])
+            .inc_by(10);
+        bytes
+            .with_label_values(&["greptime", "public", "grpc"])
+            .inc_by(100);
+        rows.with_label_values(&["greptime", "db1", "prometheus"])
+            .inc_by(3);
+        errors
+            .with_label_values(&["greptime", "db1", "prometheus"])
+            .inc();
+        rows.with_label_values(&["other", "public", "grpc"])
+            .inc_by(7);
+
+        let stats = collect_ingestion_stats("greptime", &registry.gather());
+        assert_eq!(2, stats.len());
+        assert_eq!(
+            IngestionStats {
+                rows: 10,
+                bytes: 100,
+                errors: 0,
+            },
+            stats[&("public".to_string(), "grpc".to_string())]
+        );
+        assert_eq!(
+            IngestionStats {
+                rows: 3,
+                bytes: 0,
+                errors: 1,
+            },
+            stats[&("db1".to_string(), "prometheus".to_string())]
+        );
+
+        let stats = collect_ingestion_stats("other", &registry.gather());
+        assert_eq!(1, stats.len());
+    }
+}
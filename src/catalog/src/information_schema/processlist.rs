@@ -0,0 +1,295 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Weak};
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_PROCESSLIST_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use common_time::util::current_time_millis;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::value::Value;
+use datatypes::vectors::{
+    Int64VectorBuilder, StringVectorBuilder, TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+};
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{ScanRequest, TableId};
+
+use super::PROCESSLIST;
+use crate::error::{
+    CreateRecordBatchSnafu, InternalSnafu, Result, TableMetadataManagerSnafu,
+    UpgradeWeakCatalogManagerRefSnafu,
+};
+use crate::information_schema::{InformationTable, Predicates};
+use crate::kvbackend::KvBackendCatalogManager;
+use crate::process_manager::{ProcessInfo, ProcessScope};
+use crate::CatalogManager;
+
+pub const ID: &str = "id";
+pub const CATALOG: &str = "catalog";
+pub const SCHEMA: &str = "schema";
+pub const USER: &str = "user";
+pub const QUERY: &str = "query";
+pub const START_TIME: &str = "start_time";
+pub const ELAPSED_MS: &str = "elapsed_ms";
+pub const FRONTEND: &str = "frontend";
+const INIT_CAPACITY: usize = 42;
+
+/// The `PROCESSLIST` table provides the queries running on this node and, if the
+/// node shares its queries through the registry, on other frontends. Including fields:
+///
+/// - `id`: the id of the query, which `KILL QUERY` accepts
+/// - `catalog`: the catalog of the query
+/// - `schema`: the current schema of the query
+/// - `user`: the user who runs the query
+/// - `query`: the query text
+/// - `start_time`: the time when the query starts
+/// - `elapsed_ms`: how long the query has been running, in milliseconds
+/// - `frontend`: the address of the frontend that runs the query
+///
+/// Only queries in the catalog of the table are listed. A table read by a user who
+/// is not an admin only lists the queries of the user.
+pub(super) struct InformationSchemaProcesslist {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+    /// Name of the user who reads the table, `None` to list queries of all users.
+    viewer: Option<String>,
+}
+
+impl InformationSchemaProcesslist {
+    pub(super) fn new(catalog_name: String, catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_name,
+            catalog_manager,
+            viewer: None,
+        }
+    }
+
+    /// Sets the user who reads the table.
+    pub(super) fn with_viewer(mut self, viewer: String) -> Self {
+        self.viewer = Some(viewer);
+        self
+    }
+
+    pub(crate) fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(CATALOG, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(SCHEMA, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(USER, ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(QUERY, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                START_TIME,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new(ELAPSED_MS, ConcreteDataType::int64_datatype(), false),
+            ColumnSchema::new(FRONTEND, ConcreteDataType::string_datatype(), true),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaProcesslistBuilder {
+        InformationSchemaProcesslistBuilder::new(
+            self.schema.clone(),
+            self.catalog_name.clone(),
+            self.catalog_manager.clone(),
+            self.viewer.clone(),
+        )
+    }
+}
+
+impl InformationTable for InformationSchemaProcesslist {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_PROCESSLIST_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        PROCESSLIST
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_processlist(Some(request))
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaProcesslistBuilder {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+    viewer: Option<String>,
+
+    ids: UInt64VectorBuilder,
+    catalogs: StringVectorBuilder,
+    schemas: StringVectorBuilder,
+    users: StringVectorBuilder,
+    queries: StringVectorBuilder,
+    start_times: TimestampMillisecondVectorBuilder,
+    elapsed_ms: Int64VectorBuilder,
+    frontends: StringVectorBuilder,
+}
+
+impl InformationSchemaProcesslistBuilder {
+    fn new(
+        schema: SchemaRef,
+        catalog_name: String,
+        catalog_manager: Weak<dyn CatalogManager>,
+        viewer: Option<String>,
+    ) -> Self {
+        Self {
+            schema,
+            catalog_name,
+            catalog_manager,
+            viewer,
+            ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            catalogs: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            schemas: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            users: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            queries: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            start_times: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+            elapsed_ms: Int64VectorBuilder::with_capacity(INIT_CAPACITY),
+            frontends: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+        }
+    }
+
+    /// Construct the `information_schema.processlist` virtual table
+    async fn make_processlist(&mut self, request: Option<ScanRequest>) -> Result<RecordBatch> {
+        let catalog_manager = self
+            .catalog_manager
+            .upgrade()
+            .context(UpgradeWeakCatalogManagerRefSnafu)?;
+
+        let Some(kv_catalog_manager) = catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+        else {
+            return self.finish();
+        };
+        let Some(process_manager) = kv_catalog_manager.process_manager() else {
+            return self.finish();
+        };
+
+        let scope = match &self.viewer {
+            Some(viewer) => {
+                let is_admin = kv_catalog_manager
+                    .table_metadata_manager_ref()
+                    .privilege_manager()
+                    .is_admin(viewer)
+                    .await
+                    .context(TableMetadataManagerSnafu)?;
+                if is_admin {
+                    ProcessScope::All
+                } else {
+                    ProcessScope::User(Some(viewer.clone()))
+                }
+            }
+            None => ProcessScope::All,
+        };
+        let predicates = Predicates::from_scan_request(&request);
+        let now = current_time_millis();
+        for process in process_manager.list_all().await? {
+            if process.catalog == self.catalog_name && scope.contains(&process) {
+                self.add_process(&predicates, &process, now);
+            }
+        }
+
+        self.finish()
+    }
+
+    fn add_process(&mut self, predicates: &Predicates, process: &ProcessInfo, now: i64) {
+        let row = [
+            (ID, &Value::from(process.id)),
+            (SCHEMA, &Value::from(process.schema.as_str())),
+        ];
+
+        if !predicates.eval(&row) {
+            return;
+        }
+
+        self.ids.push(Some(process.id));
+        self.catalogs.push(Some(&process.catalog));
+        self.schemas.push(Some(&process.schema));
+        self.users.push(process.user.as_deref());
+        self.queries.push(Some(&process.query));
+        self.start_times
+            .push(Some(TimestampMillisecond::new(process.start_timestamp_ms)));
+        self.elapsed_ms
+            .push(Some((now - process.start_timestamp_ms).max(0)));
+        self.frontends.push(process.frontend.as_deref());
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.ids.finish()),
+            Arc::new(self.catalogs.finish()),
+            Arc::new(self.schemas.finish()),
+            Arc::new(self.users.finish()),
+            Arc::new(self.queries.finish()),
+            Arc::new(self.start_times.finish()),
+            Arc::new(self.elapsed_ms.finish()),
+            Arc::new(self.frontends.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaProcesslist {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_processlist(None)
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
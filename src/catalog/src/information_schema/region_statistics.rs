@@ -0,0 +1,254 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Weak};
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_REGION_STATISTICS_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_meta::cluster::RegionStat;
+use common_meta::DatanodeId;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::value::Value;
+use datatypes::vectors::{
+    Int64VectorBuilder, StringVectorBuilder, UInt32VectorBuilder, UInt64VectorBuilder,
+};
+use snafu::{OptionExt, ResultExt};
+use store_api::region_engine::RegionRole;
+use store_api::storage::{ScanRequest, TableId};
+
+use super::REGION_STATISTICS;
+use crate::error::{
+    CreateRecordBatchSnafu, InternalSnafu, Result, UpgradeWeakCatalogManagerRefSnafu,
+};
+use crate::information_schema::{InformationTable, Predicates};
+use crate::kvbackend::KvBackendCatalogManager;
+use crate::CatalogManager;
+
+const REGION_ID: &str = "region_id";
+const TABLE_ID: &str = "table_id";
+const REGION_NUMBER: &str = "region_number";
+const PEER_ID: &str = "peer_id";
+const IS_LEADER: &str = "is_leader";
+const ENGINE: &str = "engine";
+const DISK_SIZE: &str = "disk_size";
+const INIT_CAPACITY: usize = 42;
+
+/// The `REGION_STATISTICS` table provides the latest statistics of regions reported by datanodes. Including fields:
+///
+/// - `region_id`: the region id
+/// - `table_id`: the id of the table the region belongs to
+/// - `region_number`: the region number in the table
+/// - `peer_id`: the id of the datanode that reports the region
+/// - `is_leader`: whether the region is the leader on the datanode
+/// - `engine`: the engine of the region
+/// - `disk_size`: the approximate size of SST files of the region, in bytes
+///
+pub(super) struct InformationSchemaRegionStatistics {
+    schema: SchemaRef,
+    catalog_manager: Weak<dyn CatalogManager>,
+}
+
+impl InformationSchemaRegionStatistics {
+    pub(super) fn new(catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_manager,
+        }
+    }
+
+    pub(crate) fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(REGION_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(TABLE_ID, ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(REGION_NUMBER, ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(PEER_ID, ConcreteDataType::uint64_datatype(), true),
+            ColumnSchema::new(IS_LEADER, ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(ENGINE, ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(DISK_SIZE, ConcreteDataType::int64_datatype(), true),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaRegionStatisticsBuilder {
+        InformationSchemaRegionStatisticsBuilder::new(
+            self.schema.clone(),
+            self.catalog_manager.clone(),
+        )
+    }
+}
+
+impl InformationTable for InformationSchemaRegionStatistics {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_REGION_STATISTICS_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        REGION_STATISTICS
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_region_statistics(Some(request))
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaRegionStatisticsBuilder {
+    schema: SchemaRef,
+    catalog_manager: Weak<dyn CatalogManager>,
+
+    region_ids: UInt64VectorBuilder,
+    table_ids: UInt32VectorBuilder,
+    region_numbers: UInt32VectorBuilder,
+    peer_ids: UInt64VectorBuilder,
+    is_leaders: StringVectorBuilder,
+    engines: StringVectorBuilder,
+    disk_sizes: Int64VectorBuilder,
+}
+
+impl InformationSchemaRegionStatisticsBuilder {
+    fn new(schema: SchemaRef, catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema,
+            catalog_manager,
+            region_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            table_ids: UInt32VectorBuilder::with_capacity(INIT_CAPACITY),
+            region_numbers: UInt32VectorBuilder::with_capacity(INIT_CAPACITY),
+            peer_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            is_leaders: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            engines: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            disk_sizes: Int64VectorBuilder::with_capacity(INIT_CAPACITY),
+        }
+    }
+
+    /// Construct the `information_schema.region_statistics` virtual table
+    async fn make_region_statistics(
+        &mut self,
+        request: Option<ScanRequest>,
+    ) -> Result<RecordBatch> {
+        let catalog_manager = self
+            .catalog_manager
+            .upgrade()
+            .context(UpgradeWeakCatalogManagerRefSnafu)?;
+
+        let information_extension = catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+            .and_then(|catalog_manager| catalog_manager.information_extension());
+
+        let predicates = Predicates::from_scan_request(&request);
+
+        if let Some(information_extension) = information_extension {
+            for (peer_id, stat) in information_extension.region_stats().await? {
+                self.add_region_statistic(&predicates, peer_id, &stat);
+            }
+        }
+
+        self.finish()
+    }
+
+    fn add_region_statistic(
+        &mut self,
+        predicates: &Predicates,
+        peer_id: DatanodeId,
+        stat: &RegionStat,
+    ) {
+        let region_id = stat.id.as_u64();
+        let table_id = stat.id.table_id();
+        let region_number = stat.id.region_number();
+
+        let row = [
+            (REGION_ID, &Value::from(region_id)),
+            (TABLE_ID, &Value::from(table_id)),
+            (PEER_ID, &Value::from(peer_id)),
+        ];
+
+        if !predicates.eval(&row) {
+            return;
+        }
+
+        let is_leader = if stat.role == RegionRole::Leader {
+            "Yes"
+        } else {
+            "No"
+        };
+
+        self.region_ids.push(Some(region_id));
+        self.table_ids.push(Some(table_id));
+        self.region_numbers.push(Some(region_number));
+        self.peer_ids.push(Some(peer_id));
+        self.is_leaders.push(Some(is_leader));
+        self.engines.push(Some(&stat.engine));
+        self.disk_sizes.push(Some(stat.approximate_bytes));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.region_ids.finish()),
+            Arc::new(self.table_ids.finish()),
+            Arc::new(self.region_numbers.finish()),
+            Arc::new(self.peer_ids.finish()),
+            Arc::new(self.is_leaders.finish()),
+            Arc::new(self.engines.finish()),
+            Arc::new(self.disk_sizes.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaRegionStatistics {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_region_statistics(None)
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Weak};
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_SLOW_QUERIES_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::value::Value;
+use datatypes::vectors::{
+    StringVectorBuilder, TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+};
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{ScanRequest, TableId};
+
+use super::SLOW_QUERIES;
+use crate::error::{
+    CreateRecordBatchSnafu, InternalSnafu, Result, UpgradeWeakCatalogManagerRefSnafu,
+};
+use crate::information_schema::{InformationTable, Predicates};
+use crate::kvbackend::KvBackendCatalogManager;
+use crate::slow_queries::SlowQuery;
+use crate::CatalogManager;
+
+pub const SCHEMA: &str = "schema";
+pub const USER: &str = "user";
+pub const QUERY: &str = "query";
+pub const START_TIME: &str = "start_time";
+pub const TOTAL_MS: &str = "total_ms";
+pub const PARSE_MS: &str = "parse_ms";
+pub const PLAN_MS: &str = "plan_ms";
+pub const EXEC_MS: &str = "exec_ms";
+pub const ROWS: &str = "rows";
+pub const SCANNED_BYTES: &str = "scanned_bytes";
+pub const PLAN: &str = "plan";
+const INIT_CAPACITY: usize = 42;
+
+/// The `SLOW_QUERIES` table provides recent queries finished on this node that are
+/// slower than the slow query threshold. Including fields:
+///
+/// - `schema`: the current schema of the query
+/// - `user`: the user who runs the query
+/// - `query`: the normalized query
+/// - `start_time`: the time when the query starts
+/// - `total_ms`: wall time of the query, in milliseconds
+/// - `parse_ms`: time to parse the query, in milliseconds
+/// - `plan_ms`: time until the output of the query is ready, in milliseconds
+/// - `exec_ms`: time to stream the results, in milliseconds
+/// - `rows`: rows returned or affected by the query
+/// - `scanned_bytes`: bytes the query reads from regions
+/// - `plan`: the physical plan with execution metrics, if recording plans is enabled
+///
+/// Only queries in the catalog of the table are listed.
+pub(super) struct InformationSchemaSlowQueries {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+}
+
+impl InformationSchemaSlowQueries {
+    pub(super) fn new(catalog_name: String, catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_name,
+            catalog_manager,
+        }
+    }
+
+    pub(crate) fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(SCHEMA, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(USER, ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(QUERY, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                START_TIME,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new(TOTAL_MS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(PARSE_MS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(PLAN_MS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(EXEC_MS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(ROWS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(SCANNED_BYTES, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(PLAN, ConcreteDataType::string_datatype(), true),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaSlowQueriesBuilder {
+        InformationSchemaSlowQueriesBuilder::new(
+            self.schema.clone(),
+            self.catalog_name.clone(),
+            self.catalog_manager.clone(),
+        )
+    }
+}
+
+impl InformationTable for InformationSchemaSlowQueries {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_SLOW_QUERIES_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        SLOW_QUERIES
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_slow_queries(Some(request))
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaSlowQueriesBuilder {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+
+    schemas: StringVectorBuilder,
+    users: StringVectorBuilder,
+    queries: StringVectorBuilder,
+    start_times: TimestampMillisecondVectorBuilder,
+    total_ms: UInt64VectorBuilder,
+    parse_ms: UInt64VectorBuilder,
+    plan_ms: UInt64VectorBuilder,
+    exec_ms: UInt64VectorBuilder,
+    rows: UInt64VectorBuilder,
+    scanned_bytes: UInt64VectorBuilder,
+    plans: StringVectorBuilder,
+}
+
+impl InformationSchemaSlowQueriesBuilder {
+    fn new(
+        schema: SchemaRef,
+        catalog_name: String,
+        catalog_manager: Weak<dyn CatalogManager>,
+    ) -> Self {
+        Self {
+            schema,
+            catalog_name,
+            catalog_manager,
+            schemas: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            users: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            queries: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            start_times: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+            total_ms: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            parse_ms: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            plan_ms: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            exec_ms: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            rows: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            scanned_bytes: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            plans: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+        }
+    }
+
+    /// Construct the `information_schema.slow_queries` virtual table
+    fn make_slow_queries(&mut self, request: Option<ScanRequest>) -> Result<RecordBatch> {
+        let catalog_manager = self
+            .catalog_manager
+            .upgrade()
+            .context(UpgradeWeakCatalogManagerRefSnafu)?;
+
+        let process_manager = catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+            .and_then(|catalog_manager| catalog_manager.process_manager());
+
+        let predicates = Predicates::from_scan_request(&request);
+
+        if let Some(process_manager) = process_manager {
+            for slow_query in process_manager.slow_queries() {
+                if slow_query.catalog == self.catalog_name {
+                    self.add_slow_query(&predicates, &slow_query);
+                }
+            }
+        }
+
+        self.finish()
+    }
+
+    fn add_slow_query(&mut self, predicates: &Predicates, slow_query: &SlowQuery) {
+        let row = [(SCHEMA, &Value::from(slow_query.schema.as_str()))];
+
+        if !predicates.eval(&row) {
+            return;
+        }
+
+        self.schemas.push(Some(&slow_query.schema));
+        self.users.push(slow_query.user.as_deref());
+        self.queries.push(Some(&slow_query.query));
+        self.start_times
+            .push(Some(TimestampMillisecond::new(slow_query.start_ms)));
+        self.total_ms.push(Some(slow_query.total_ms));
+        self.parse_ms.push(Some(slow_query.parse_ms));
+        self.plan_ms.push(Some(slow_query.plan_ms));
+        self.exec_ms.push(Some(slow_query.exec_ms));
+        self.rows.push(Some(slow_query.rows));
+        self.scanned_bytes.push(Some(slow_query.scanned_bytes));
+        self.plans.push(slow_query.plan.as_deref());
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.schemas.finish()),
+            Arc::new(self.users.finish()),
+            Arc::new(self.queries.finish()),
+            Arc::new(self.start_times.finish()),
+            Arc::new(self.total_ms.finish()),
+            Arc::new(self.parse_ms.finish()),
+            Arc::new(self.plan_ms.finish()),
+            Arc::new(self.exec_ms.finish()),
+            Arc::new(self.rows.finish()),
+            Arc::new(self.scanned_bytes.finish()),
+            Arc::new(self.plans.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaSlowQueries {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_slow_queries(None)
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
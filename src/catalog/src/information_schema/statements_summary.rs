@@ -0,0 +1,318 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Weak};
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_STATEMENTS_SUMMARY_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::value::Value;
+use datatypes::vectors::{
+    StringVectorBuilder, TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+};
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{ScanRequest, TableId};
+
+use super::STATEMENTS_SUMMARY;
+use crate::error::{
+    CreateRecordBatchSnafu, InternalSnafu, Result, UpgradeWeakCatalogManagerRefSnafu,
+};
+use crate::information_schema::{InformationTable, Predicates};
+use crate::kvbackend::KvBackendCatalogManager;
+use crate::statements_summary::StatementSummary;
+use crate::CatalogManager;
+
+pub const SCHEMA: &str = "schema";
+pub const DIGEST: &str = "digest";
+pub const DIGEST_TEXT: &str = "digest_text";
+pub const SAMPLE_QUERY: &str = "sample_query";
+pub const EXEC_COUNT: &str = "exec_count";
+pub const SUM_LATENCY_MS: &str = "sum_latency_ms";
+pub const AVG_LATENCY_MS: &str = "avg_latency_ms";
+pub const MAX_LATENCY_MS: &str = "max_latency_ms";
+pub const SUM_ROWS: &str = "sum_rows";
+pub const AVG_ROWS: &str = "avg_rows";
+pub const SUM_SCANNED_BYTES: &str = "sum_scanned_bytes";
+pub const AVG_SCANNED_BYTES: &str = "avg_scanned_bytes";
+pub const FIRST_SEEN: &str = "first_seen";
+pub const LAST_SEEN: &str = "last_seen";
+const INIT_CAPACITY: usize = 42;
+
+/// The `STATEMENTS_SUMMARY` table provides statistics of queries finished on this node,
+/// aggregated by their digests so slow queries of the same shape are grouped together.
+/// Including fields:
+///
+/// - `schema`: the current schema of the queries
+/// - `digest`: the digest of the queries, as returned by `digest(sql)`
+/// - `digest_text`: the normalized query of the digest
+/// - `sample_query`: the latest query of the digest
+/// - `exec_count`: number of finished queries
+/// - `sum_latency_ms`: total latency of the queries, in milliseconds
+/// - `avg_latency_ms`: average latency of the queries, in milliseconds
+/// - `max_latency_ms`: max latency of the queries, in milliseconds
+/// - `sum_rows`: total rows returned or affected by the queries
+/// - `avg_rows`: average rows returned or affected by the queries
+/// - `sum_scanned_bytes`: total bytes the queries read from regions
+/// - `avg_scanned_bytes`: average bytes the queries read from regions
+/// - `first_seen`: the time when the digest is seen for the first time
+/// - `last_seen`: the time when the digest is seen for the last time
+///
+/// Only queries in the catalog of the table are listed.
+pub(super) struct InformationSchemaStatementsSummary {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+}
+
+impl InformationSchemaStatementsSummary {
+    pub(super) fn new(catalog_name: String, catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_name,
+            catalog_manager,
+        }
+    }
+
+    pub(crate) fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(SCHEMA, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(DIGEST, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(DIGEST_TEXT, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(SAMPLE_QUERY, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(EXEC_COUNT, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(SUM_LATENCY_MS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(AVG_LATENCY_MS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(MAX_LATENCY_MS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(SUM_ROWS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(AVG_ROWS, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(
+                SUM_SCANNED_BYTES,
+                ConcreteDataType::uint64_datatype(),
+                false,
+            ),
+            ColumnSchema::new(
+                AVG_SCANNED_BYTES,
+                ConcreteDataType::uint64_datatype(),
+                false,
+            ),
+            ColumnSchema::new(
+                FIRST_SEEN,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new(
+                LAST_SEEN,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaStatementsSummaryBuilder {
+        InformationSchemaStatementsSummaryBuilder::new(
+            self.schema.clone(),
+            self.catalog_name.clone(),
+            self.catalog_manager.clone(),
+        )
+    }
+}
+
+impl InformationTable for InformationSchemaStatementsSummary {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_STATEMENTS_SUMMARY_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        STATEMENTS_SUMMARY
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_statements_summary(Some(request))
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaStatementsSummaryBuilder {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+
+    schemas: StringVectorBuilder,
+    digests: StringVectorBuilder,
+    digest_texts: StringVectorBuilder,
+    sample_queries: StringVectorBuilder,
+    exec_counts: UInt64VectorBuilder,
+    sum_latencies: UInt64VectorBuilder,
+    avg_latencies: UInt64VectorBuilder,
+    max_latencies: UInt64VectorBuilder,
+    sum_rows: UInt64VectorBuilder,
+    avg_rows: UInt64VectorBuilder,
+    sum_scanned_bytes: UInt64VectorBuilder,
+    avg_scanned_bytes: UInt64VectorBuilder,
+    first_seens: TimestampMillisecondVectorBuilder,
+    last_seens: TimestampMillisecondVectorBuilder,
+}
+
+impl InformationSchemaStatementsSummaryBuilder {
+    fn new(
+        schema: SchemaRef,
+        catalog_name: String,
+        catalog_manager: Weak<dyn CatalogManager>,
+    ) -> Self {
+        Self {
+            schema,
+            catalog_name,
+            catalog_manager,
+            schemas: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            digests: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            digest_texts: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            sample_queries: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            exec_counts: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            sum_latencies: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            avg_latencies: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            max_latencies: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            sum_rows: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            avg_rows: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            sum_scanned_bytes: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            avg_scanned_bytes: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            first_seens: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+            last_seens: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+        }
+    }
+
+    /// Construct the `information_schema.statements_summary` virtual table
+    fn make_statements_summary(&mut self, request: Option<ScanRequest>) -> Result<RecordBatch> {
+        let catalog_manager = self
+            .catalog_manager
+            .upgrade()
+            .context(UpgradeWeakCatalogManagerRefSnafu)?;
+
+        let process_manager = catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+            .and_then(|catalog_manager| catalog_manager.process_manager());
+
+        let predicates = Predicates::from_scan_request(&request);
+
+        if let Some(process_manager) = process_manager {
+            for statement in process_manager.statements_summary() {
+                if statement.catalog == self.catalog_name {
+                    self.add_statement(&predicates, &statement);
+                }
+            }
+        }
+
+        self.finish()
+    }
+
+    fn add_statement(&mut self, predicates: &Predicates, statement: &StatementSummary) {
+        let row = [
+            (SCHEMA, &Value::from(statement.schema.as_str())),
+            (DIGEST, &Value::from(statement.digest.as_str())),
+        ];
+
+        if !predicates.eval(&row) {
+            return;
+        }
+
+        self.schemas.push(Some(&statement.schema));
+        self.digests.push(Some(&statement.digest));
+        self.digest_texts.push(Some(&statement.digest_text));
+        self.sample_queries.push(Some(&statement.sample_query));
+        self.exec_counts.push(Some(statement.exec_count));
+        self.sum_latencies.push(Some(statement.sum_latency_ms));
+        // Summaries always have at least one execution.
+        let exec_count = statement.exec_count.max(1);
+        self.avg_latencies
+            .push(Some(statement.sum_latency_ms / exec_count));
+        self.max_latencies.push(Some(statement.max_latency_ms));
+        self.sum_rows.push(Some(statement.sum_rows));
+        self.avg_rows.push(Some(statement.sum_rows / exec_count));
+        self.sum_scanned_bytes
+            .push(Some(statement.sum_scanned_bytes));
+        self.avg_scanned_bytes
+            .push(Some(statement.sum_scanned_bytes / exec_count));
+        self.first_seens
+            .push(Some(TimestampMillisecond::new(statement.first_seen_ms)));
+        self.last_seens
+            .push(Some(TimestampMillisecond::new(statement.last_seen_ms)));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.schemas.finish()),
+            Arc::new(self.digests.finish()),
+            Arc::new(self.digest_texts.finish()),
+            Arc::new(self.sample_queries.finish()),
+            Arc::new(self.exec_counts.finish()),
+            Arc::new(self.sum_latencies.finish()),
+            Arc::new(self.avg_latencies.finish()),
+            Arc::new(self.max_latencies.finish()),
+            Arc::new(self.sum_rows.finish()),
+            Arc::new(self.avg_rows.finish()),
+            Arc::new(self.sum_scanned_bytes.finish()),
+            Arc::new(self.avg_scanned_bytes.finish()),
+            Arc::new(self.first_seens.finish()),
+            Arc::new(self.last_seens.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl DfPartitionStream for InformationSchemaStatementsSummary {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_statements_summary(None)
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
@@ -42,3 +42,10 @@ pub const RUNTIME_METRICS: &str = "runtime_metrics";
 pub const PARTITIONS: &str = "partitions";
 pub const REGION_PEERS: &str = "greptime_region_peers";
 pub const TABLE_CONSTRAINTS: &str = "table_constraints";
+pub const VIEWS: &str = "views";
+pub const INGESTION_STATS: &str = "ingestion_stats";
+pub const REGION_STATISTICS: &str = "region_statistics";
+pub const CLUSTER_INFO: &str = "cluster_info";
+pub const PROCESSLIST: &str = "processlist";
+pub const STATEMENTS_SUMMARY: &str = "statements_summary";
+pub const SLOW_QUERIES: &str = "slow_queries";
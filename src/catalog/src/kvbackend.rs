@@ -16,5 +16,7 @@ pub use client::{CachedMetaKvBackend, CachedMetaKvBackendBuilder, MetaKvBackend}
 
 mod client;
 mod manager;
+mod snapshot;
 
 pub use manager::KvBackendCatalogManager;
+pub use snapshot::SnapshotKvBackend;
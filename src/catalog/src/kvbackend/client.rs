@@ -35,6 +35,7 @@ use meta_client::client::MetaClient;
 use moka::future::{Cache, CacheBuilder};
 use snafu::{OptionExt, ResultExt};
 
+use crate::kvbackend::SnapshotKvBackend;
 use crate::metrics::{
     METRIC_CATALOG_KV_BATCH_GET, METRIC_CATALOG_KV_GET, METRIC_CATALOG_KV_REMOTE_GET,
 };
@@ -47,15 +48,23 @@ pub struct CachedMetaKvBackendBuilder {
     cache_max_capacity: Option<u64>,
     cache_ttl: Option<Duration>,
     cache_tti: Option<Duration>,
+    snapshot: Option<SnapshotOptions>,
     meta_client: Arc<MetaClient>,
 }
 
+struct SnapshotOptions {
+    snapshot: KvBackendRef,
+    sync_interval: Duration,
+    max_staleness: Duration,
+}
+
 impl CachedMetaKvBackendBuilder {
     pub fn new(meta_client: Arc<MetaClient>) -> Self {
         Self {
             cache_max_capacity: None,
             cache_ttl: None,
             cache_tti: None,
+            snapshot: None,
             meta_client,
         }
     }
@@ -75,6 +84,23 @@ impl CachedMetaKvBackendBuilder {
         self
     }
 
+    /// Keeps a local snapshot of metadata in `snapshot`, which is synced every
+    /// `sync_interval` and serves reads while metasrv is unavailable, if it's synced
+    /// within `max_staleness`.
+    pub fn snapshot(
+        mut self,
+        snapshot: KvBackendRef,
+        sync_interval: Duration,
+        max_staleness: Duration,
+    ) -> Self {
+        self.snapshot.replace(SnapshotOptions {
+            snapshot,
+            sync_interval,
+            max_staleness,
+        });
+        self
+    }
+
     pub fn build(self) -> CachedMetaKvBackend {
         let cache_max_capacity = self
             .cache_max_capacity
@@ -87,9 +113,21 @@ impl CachedMetaKvBackendBuilder {
             .time_to_idle(cache_tti)
             .build();
 
-        let kv_backend = Arc::new(MetaKvBackend {
+        let kv_backend: KvBackendRef = Arc::new(MetaKvBackend {
             client: self.meta_client,
         });
+        let kv_backend: KvBackendRef = match self.snapshot {
+            Some(options) => {
+                let backend = Arc::new(SnapshotKvBackend::new(
+                    kv_backend,
+                    options.snapshot,
+                    options.max_staleness,
+                ));
+                backend.start_sync(options.sync_interval);
+                backend
+            }
+            None => kv_backend,
+        };
         let name = format!("CachedKvBackend({})", kv_backend.name());
         let version = AtomicUsize::new(0);
 
@@ -448,6 +486,13 @@ mod tests {
     use std::sync::Arc;
 
     use async_trait::async_trait;
+    use common_meta::cache_invalidator::{CacheInvalidator, Context};
+    use common_meta::error::Error;
+    use common_meta::key::privilege::{
+        self, GrantObject, GranteeKey, Privilege, PrivilegeGrant, PrivilegeManager, ADMIN_ROLE,
+    };
+    use common_meta::key::TableMetaKey;
+    use common_meta::kv_backend::memory::MemoryKvBackend;
     use common_meta::kv_backend::{KvBackend, TxnService};
     use common_meta::rpc::store::{
         BatchDeleteRequest, BatchDeleteResponse, BatchGetRequest, BatchGetResponse,
@@ -570,6 +615,42 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_invalidate_revoked_privilege() {
+        let kv_backend = Arc::new(MemoryKvBackend::<Error>::default());
+        let frontend_a = Arc::new(CachedMetaKvBackend::wrap(kv_backend.clone()));
+        let frontend_b = Arc::new(CachedMetaKvBackend::wrap(kv_backend));
+        let manager_a = PrivilegeManager::new(frontend_a);
+        let manager_b = PrivilegeManager::new(frontend_b.clone());
+
+        let alice = GranteeKey::user("alice");
+        let object = GrantObject::catalog("greptime");
+        let select = PrivilegeGrant::new(Privilege::Select, object.clone());
+        manager_a.grant_role("root", ADMIN_ROLE).await.unwrap();
+        manager_a.grant(alice, vec![select.clone()]).await.unwrap();
+        assert!(manager_b
+            .check("alice", Privilege::Select, &object)
+            .await
+            .unwrap());
+
+        // Frontend b keeps the cached grant until the revoke is broadcast.
+        manager_a.revoke(alice, &[select]).await.unwrap();
+        assert!(manager_b
+            .check("alice", Privilege::Select, &object)
+            .await
+            .unwrap());
+
+        let ident = privilege::cache_ident(&alice.as_raw_key()).unwrap();
+        frontend_b
+            .invalidate(&Context::default(), vec![ident])
+            .await
+            .unwrap();
+        assert!(!manager_b
+            .check("alice", Privilege::Select, &object)
+            .await
+            .unwrap());
+    }
+
     async fn add_some_vals(kv_backend: &impl KvBackend) {
         kv_backend
             .put(PutRequest {
@@ -29,6 +29,7 @@ use common_meta::key::catalog_name::CatalogNameKey;
 use common_meta::key::schema_name::SchemaNameKey;
 use common_meta::key::table_info::TableInfoValue;
 use common_meta::key::table_name::TableNameKey;
+use common_meta::key::view_info::ViewInfoKey;
 use common_meta::key::{TableMetadataManager, TableMetadataManagerRef};
 use common_meta::kv_backend::KvBackendRef;
 use futures_util::stream::BoxStream;
@@ -46,7 +47,9 @@ use crate::error::{
     InvalidTableInfoInCatalogSnafu, ListCatalogsSnafu, ListSchemasSnafu, ListTablesSnafu, Result,
     TableCacheNotGetSnafu, TableMetadataManagerSnafu,
 };
+use crate::information_extension::InformationExtensionRef;
 use crate::information_schema::InformationSchemaProvider;
+use crate::process_manager::ProcessManagerRef;
 use crate::CatalogManager;
 
 /// Access all existing catalog, schema and tables.
@@ -61,6 +64,10 @@ pub struct KvBackendCatalogManager {
     /// A sub-CatalogManager that handles system tables
     system_catalog: SystemCatalog,
     table_cache: AsyncCache<String, TableRef>,
+    /// Provides runtime information of nodes and regions.
+    information_extension: Option<InformationExtensionRef>,
+    /// Tracks queries running on this node.
+    process_manager: Option<ProcessManagerRef>,
 }
 
 struct TableCacheInvalidator {
@@ -103,6 +110,8 @@ impl KvBackendCatalogManager {
     pub async fn new(
         backend: KvBackendRef,
         multi_cache_invalidator: Arc<MultiCacheInvalidator>,
+        information_extension: Option<InformationExtensionRef>,
+        process_manager: Option<ProcessManagerRef>,
     ) -> Arc<Self> {
         let table_cache: AsyncCache<String, TableRef> = CacheBuilder::new(TABLE_CACHE_MAX_CAPACITY)
             .time_to_live(TABLE_CACHE_TTL)
@@ -124,6 +133,8 @@ impl KvBackendCatalogManager {
                 )),
             },
             table_cache,
+            information_extension,
+            process_manager,
         })
     }
 
@@ -134,6 +145,16 @@ impl KvBackendCatalogManager {
     pub fn table_metadata_manager_ref(&self) -> &TableMetadataManagerRef {
         &self.table_metadata_manager
     }
+
+    /// Returns the [InformationExtensionRef] to get runtime information of the cluster.
+    pub fn information_extension(&self) -> Option<InformationExtensionRef> {
+        self.information_extension.clone()
+    }
+
+    /// Returns the [ProcessManagerRef] that tracks queries running on this node.
+    pub fn process_manager(&self) -> Option<ProcessManagerRef> {
+        self.process_manager.clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -330,6 +351,31 @@ impl CatalogManager for KvBackendCatalogManager {
 
         Box::pin(sys_tables.chain(user_tables))
     }
+
+    async fn view(&self, catalog: &str, schema: &str, view_name: &str) -> Result<Option<String>> {
+        let view_info = self
+            .table_metadata_manager
+            .view_info_manager()
+            .get(ViewInfoKey::new(catalog, schema, view_name))
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        Ok(view_info.map(|v| v.query()))
+    }
+
+    async fn views(&self, catalog: &str, schema: &str) -> Result<Vec<(String, String)>> {
+        let views = self
+            .table_metadata_manager
+            .view_info_manager()
+            .views(catalog, schema)
+            .map_ok(|(name, value)| (name, value.definition))
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(BoxedError::new)
+            .context(ListTablesSnafu { catalog, schema })?;
+
+        Ok(views)
+    }
 }
 
 fn build_table(table_info_value: TableInfoValue) -> Result<TableRef> {
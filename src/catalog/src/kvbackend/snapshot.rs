@@ -0,0 +1,563 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use common_meta::error::{Error, Result};
+use common_meta::key::{
+    CATALOG_NAME_KEY_PREFIX, PRIVILEGE_KEY_PREFIX, SCHEMA_NAME_KEY_PREFIX, TABLE_INFO_KEY_PREFIX,
+    TABLE_NAME_KEY_PREFIX, TABLE_ROUTE_PREFIX, USER_KEY_PREFIX, VIEW_INFO_KEY_PREFIX,
+};
+use common_meta::kv_backend::{KvBackend, KvBackendRef, TxnService};
+use common_meta::range_stream::{PaginationStream, DEFAULT_PAGE_SIZE};
+use common_meta::rpc::store::{
+    BatchDeleteRequest, BatchDeleteResponse, BatchGetRequest, BatchGetResponse, BatchPutRequest,
+    BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse, DeleteRangeRequest,
+    DeleteRangeResponse, PutRequest, PutResponse, RangeRequest, RangeResponse,
+};
+use common_meta::rpc::KeyValue;
+use common_telemetry::{info, warn};
+use common_time::util::current_time_millis;
+use futures::TryStreamExt;
+
+use crate::metrics::{METRIC_CATALOG_KV_SNAPSHOT_GET, METRIC_CATALOG_KV_SNAPSHOT_STALE};
+
+/// Key of the time in millis when the snapshot is last synced with the remote backend.
+const SYNCED_AT_KEY: &[u8] = b"__metadata_snapshot/synced_at";
+
+/// Prefixes of the metadata the frontend reads, which are kept in the snapshot.
+const SNAPSHOT_KEY_PREFIXES: [&str; 6] = [
+    CATALOG_NAME_KEY_PREFIX,
+    SCHEMA_NAME_KEY_PREFIX,
+    TABLE_NAME_KEY_PREFIX,
+    TABLE_INFO_KEY_PREFIX,
+    TABLE_ROUTE_PREFIX,
+    VIEW_INFO_KEY_PREFIX,
+];
+
+/// Prefixes of users and privileges. They are never kept in the snapshot, so password
+/// hashes aren't persisted locally and revoked privileges aren't served as stale data.
+/// Snapshots of older versions may contain them, and they are removed on sync.
+const EXCLUDED_KEY_PREFIXES: [&str; 2] = [PRIVILEGE_KEY_PREFIX, USER_KEY_PREFIX];
+
+/// Returns true if `key` is kept in the snapshot.
+fn is_snapshot_key(key: &[u8]) -> bool {
+    SNAPSHOT_KEY_PREFIXES.iter().any(|prefix| {
+        key.strip_prefix(prefix.as_bytes())
+            .is_some_and(|rest| rest.starts_with(b"/"))
+    })
+}
+
+/// The snapshot is not synced yet, or the synced time is unknown.
+const NOT_SYNCED: i64 = i64::MIN;
+
+/// A [KvBackend] that keeps a local snapshot of metadata in the `remote` backend.
+///
+/// Reads are served by the `remote` backend and never write the snapshot. The snapshot
+/// is synced with the `remote` backend in background by [SnapshotKvBackend::start_sync].
+/// Once the `remote` backend is unavailable, e.g. metasrv is down, reads of keys in
+/// the snapshot fall back to the snapshot so the frontend can keep serving with stale
+/// metadata, as long as the snapshot is synced within `max_staleness`. Reads of other
+/// keys fail. Writes always go to the `remote` backend.
+pub struct SnapshotKvBackend {
+    remote: KvBackendRef,
+    snapshot: KvBackendRef,
+    name: String,
+    max_staleness: Duration,
+    /// Time in millis when the snapshot is last synced, or [NOT_SYNCED].
+    synced_at: AtomicI64,
+}
+
+impl SnapshotKvBackend {
+    pub fn new(remote: KvBackendRef, snapshot: KvBackendRef, max_staleness: Duration) -> Self {
+        let name = format!("SnapshotKvBackend({})", remote.name());
+        Self {
+            remote,
+            snapshot,
+            name,
+            max_staleness,
+            synced_at: AtomicI64::new(NOT_SYNCED),
+        }
+    }
+
+    /// Syncs the snapshot with the remote backend every `interval` in background,
+    /// until the backend is dropped.
+    pub fn start_sync(self: &Arc<Self>, interval: Duration) {
+        let backend = Arc::downgrade(self);
+        let _handle = common_runtime::spawn_bg(async move {
+            sync_loop(backend, interval).await;
+        });
+    }
+
+    /// Replaces the metadata in the snapshot with the metadata in the remote backend.
+    ///
+    /// The remote backend is read in pages of [DEFAULT_PAGE_SIZE] keys.
+    pub async fn sync(&self) -> Result<()> {
+        let mut kvs = Vec::new();
+        let mut removed = Vec::new();
+        for prefix in SNAPSHOT_KEY_PREFIXES {
+            let req = RangeRequest::new().with_prefix(format!("{prefix}/"));
+            let remote_kvs = PaginationStream::new(
+                self.remote.clone(),
+                req.clone(),
+                DEFAULT_PAGE_SIZE,
+                Arc::new(|kv| Ok((kv, ()))),
+            )
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|(kv, _)| kv)
+            .collect::<Vec<_>>();
+            let keys = remote_kvs
+                .iter()
+                .map(|kv| kv.key.as_slice())
+                .collect::<HashSet<_>>();
+            let snapshot = self.snapshot.range(req.with_keys_only()).await?;
+            removed.extend(
+                snapshot
+                    .kvs
+                    .into_iter()
+                    .filter(|kv| !keys.contains(kv.key.as_slice()))
+                    .map(|kv| kv.key),
+            );
+            kvs.extend(remote_kvs);
+        }
+        for prefix in EXCLUDED_KEY_PREFIXES {
+            self.snapshot
+                .delete_range(DeleteRangeRequest::new().with_prefix(format!("{prefix}/")))
+                .await?;
+        }
+
+        if !removed.is_empty() {
+            self.snapshot
+                .batch_delete(BatchDeleteRequest {
+                    keys: removed,
+                    prev_kv: false,
+                })
+                .await?;
+        }
+        let synced_at = current_time_millis();
+        let mut req = BatchPutRequest::new().add_kv(SYNCED_AT_KEY, synced_at.to_string());
+        req.kvs.extend(kvs);
+        self.snapshot.batch_put(req).await?;
+        self.synced_at.store(synced_at, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Saves `kvs` written to the remote backend to the snapshot, if they are kept in
+    /// the snapshot.
+    async fn save(&self, kvs: &[KeyValue]) {
+        let kvs = kvs
+            .iter()
+            .filter(|kv| is_snapshot_key(&kv.key))
+            .cloned()
+            .collect::<Vec<_>>();
+        if kvs.is_empty() {
+            return;
+        }
+        let req = BatchPutRequest {
+            kvs,
+            prev_kv: false,
+        };
+        if let Err(e) = self.snapshot.batch_put(req).await {
+            warn!(e; "Failed to save metadata to the local snapshot");
+        }
+    }
+
+    /// Removes `keys` deleted from the remote backend from the snapshot.
+    async fn remove(&self, keys: Vec<Vec<u8>>) {
+        if keys.is_empty() {
+            return;
+        }
+        let req = BatchDeleteRequest {
+            keys,
+            prev_kv: false,
+        };
+        if let Err(e) = self.snapshot.batch_delete(req).await {
+            warn!(e; "Failed to remove metadata from the local snapshot");
+        }
+    }
+
+    /// Returns the time in millis when the snapshot is last synced, it's read from
+    /// the snapshot if the snapshot isn't synced since the frontend starts.
+    async fn synced_at(&self) -> Option<i64> {
+        let synced_at = self.synced_at.load(Ordering::Relaxed);
+        if synced_at != NOT_SYNCED {
+            return Some(synced_at);
+        }
+
+        let synced_at = self
+            .snapshot
+            .get(SYNCED_AT_KEY)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|kv| String::from_utf8(kv.value).ok()?.parse::<i64>().ok())?;
+        let _ = self.synced_at.compare_exchange(
+            NOT_SYNCED,
+            synced_at,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+        Some(synced_at)
+    }
+
+    /// Returns `err` if the snapshot can't serve reads while the remote backend fails
+    /// with `err`, i.e. it's never synced or it's synced before `max_staleness`.
+    async fn check_stale(&self, err: Error) -> Result<()> {
+        let Some(synced_at) = self.synced_at().await else {
+            warn!(
+                "Failed to read metadata from {}, the local snapshot is never synced",
+                self.remote.name()
+            );
+            return Err(err);
+        };
+        let staleness = Duration::from_millis((current_time_millis() - synced_at).max(0) as u64);
+        if staleness > self.max_staleness {
+            METRIC_CATALOG_KV_SNAPSHOT_STALE.inc();
+            warn!(
+                "Failed to read metadata from {}, the local snapshot synced {:?} ago exceeds the max staleness {:?}",
+                self.remote.name(),
+                staleness,
+                self.max_staleness
+            );
+            return Err(err);
+        }
+
+        METRIC_CATALOG_KV_SNAPSHOT_GET.inc();
+        warn!(
+            err; "Failed to read metadata from {}, serving stale metadata from the local snapshot synced {:?} ago",
+            self.remote.name(),
+            staleness
+        );
+        Ok(())
+    }
+}
+
+async fn sync_loop(backend: Weak<SnapshotKvBackend>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let Some(backend) = backend.upgrade() else {
+            info!("Metadata snapshot backend is dropped, stop syncing");
+            return;
+        };
+        if let Err(e) = backend.sync().await {
+            warn!(e; "Failed to sync the local metadata snapshot");
+        }
+    }
+}
+
+impl TxnService for SnapshotKvBackend {
+    type Error = Error;
+}
+
+#[async_trait::async_trait]
+impl KvBackend for SnapshotKvBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
+        match self.remote.range(req.clone()).await {
+            Ok(resp) => Ok(resp),
+            Err(e) if !is_snapshot_key(&req.key) => Err(e),
+            Err(e) => {
+                self.check_stale(e).await?;
+                self.snapshot.range(req).await
+            }
+        }
+    }
+
+    async fn put(&self, req: PutRequest) -> Result<PutResponse> {
+        let kv = KeyValue {
+            key: req.key.clone(),
+            value: req.value.clone(),
+        };
+        let resp = self.remote.put(req).await?;
+        self.save(&[kv]).await;
+        Ok(resp)
+    }
+
+    async fn batch_put(&self, req: BatchPutRequest) -> Result<BatchPutResponse> {
+        let kvs = req.kvs.clone();
+        let resp = self.remote.batch_put(req).await?;
+        self.save(&kvs).await;
+        Ok(resp)
+    }
+
+    async fn batch_get(&self, req: BatchGetRequest) -> Result<BatchGetResponse> {
+        match self.remote.batch_get(req.clone()).await {
+            Ok(resp) => Ok(resp),
+            Err(e) if !req.keys.iter().all(|key| is_snapshot_key(key)) => Err(e),
+            Err(e) => {
+                self.check_stale(e).await?;
+                self.snapshot.batch_get(req).await
+            }
+        }
+    }
+
+    async fn compare_and_put(&self, req: CompareAndPutRequest) -> Result<CompareAndPutResponse> {
+        let kv = KeyValue {
+            key: req.key.clone(),
+            value: req.value.clone(),
+        };
+        let resp = self.remote.compare_and_put(req).await?;
+        if resp.success {
+            self.save(&[kv]).await;
+        }
+        Ok(resp)
+    }
+
+    async fn delete_range(&self, req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
+        let resp = self.remote.delete_range(req.clone()).await?;
+        if let Err(e) = self.snapshot.delete_range(req).await {
+            warn!(e; "Failed to remove metadata from the local snapshot");
+        }
+        Ok(resp)
+    }
+
+    async fn batch_delete(&self, req: BatchDeleteRequest) -> Result<BatchDeleteResponse> {
+        let keys = req.keys.clone();
+        let resp = self.remote.batch_delete(req).await?;
+        self.remove(keys).await;
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use common_meta::error::UnexpectedSnafu;
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+
+    use super::*;
+
+    /// A kv backend that can be made unavailable.
+    #[derive(Default)]
+    struct FlakyKvBackend {
+        inner: MemoryKvBackend<Error>,
+        unavailable: AtomicBool,
+    }
+
+    impl FlakyKvBackend {
+        fn check(&self) -> Result<()> {
+            if self.unavailable.load(Ordering::Relaxed) {
+                return UnexpectedSnafu {
+                    err_msg: "unavailable",
+                }
+                .fail();
+            }
+            Ok(())
+        }
+    }
+
+    impl TxnService for FlakyKvBackend {
+        type Error = Error;
+    }
+
+    #[async_trait::async_trait]
+    impl KvBackend for FlakyKvBackend {
+        fn name(&self) -> &str {
+            "FlakyKvBackend"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
+            self.check()?;
+            self.inner.range(req).await
+        }
+
+        async fn put(&self, req: PutRequest) -> Result<PutResponse> {
+            self.check()?;
+            self.inner.put(req).await
+        }
+
+        async fn batch_put(&self, req: BatchPutRequest) -> Result<BatchPutResponse> {
+            self.check()?;
+            self.inner.batch_put(req).await
+        }
+
+        async fn batch_get(&self, req: BatchGetRequest) -> Result<BatchGetResponse> {
+            self.check()?;
+            self.inner.batch_get(req).await
+        }
+
+        async fn compare_and_put(
+            &self,
+            req: CompareAndPutRequest,
+        ) -> Result<CompareAndPutResponse> {
+            self.check()?;
+            self.inner.compare_and_put(req).await
+        }
+
+        async fn delete_range(&self, req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
+            self.check()?;
+            self.inner.delete_range(req).await
+        }
+
+        async fn batch_delete(&self, req: BatchDeleteRequest) -> Result<BatchDeleteResponse> {
+            self.check()?;
+            self.inner.batch_delete(req).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_kv_backend() {
+        let remote = Arc::new(FlakyKvBackend::default());
+        let snapshot = Arc::new(MemoryKvBackend::<Error>::new());
+        let backend =
+            SnapshotKvBackend::new(remote.clone(), snapshot.clone(), Duration::from_secs(3600));
+
+        // Reads fail without a synced snapshot.
+        remote.unavailable.store(true, Ordering::Relaxed);
+        assert!(backend.get(b"__table_route/1").await.is_err());
+        remote.unavailable.store(false, Ordering::Relaxed);
+
+        backend
+            .batch_put(
+                BatchPutRequest::new()
+                    .add_kv(b"__table_route/1".to_vec(), b"route1".to_vec())
+                    .add_kv(b"__table_route/2".to_vec(), b"route2".to_vec()),
+            )
+            .await
+            .unwrap();
+        // Removes the key from the remote directly.
+        remote
+            .inner
+            .delete(b"__table_route/2", false)
+            .await
+            .unwrap();
+
+        // Reads don't write the snapshot.
+        let resp = backend
+            .range(RangeRequest::new().with_prefix(b"__table_route/".to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(1, resp.kvs.len());
+        assert!(snapshot.get(b"__table_route/2").await.unwrap().is_some());
+        assert!(snapshot.get(SYNCED_AT_KEY).await.unwrap().is_none());
+
+        // Syncing removes the key from the snapshot.
+        backend.sync().await.unwrap();
+        assert!(snapshot.get(b"__table_route/2").await.unwrap().is_none());
+        assert!(snapshot.get(SYNCED_AT_KEY).await.unwrap().is_some());
+
+        // Reads from the snapshot while the remote is unavailable.
+        remote.unavailable.store(true, Ordering::Relaxed);
+        let kv = backend.get(b"__table_route/1").await.unwrap().unwrap();
+        assert_eq!(b"route1", kv.value.as_slice());
+        let resp = backend
+            .batch_get(BatchGetRequest::new().with_keys(vec![b"__table_route/1".to_vec()]))
+            .await
+            .unwrap();
+        assert_eq!(1, resp.kvs.len());
+
+        // Writes always go to the remote.
+        assert!(backend
+            .put(
+                PutRequest::new()
+                    .with_key(b"__table_route/3".to_vec())
+                    .with_value(b"route3".to_vec())
+            )
+            .await
+            .is_err());
+        assert!(snapshot.get(b"__table_route/3").await.unwrap().is_none());
+
+        // Reads fail once the snapshot is too stale.
+        backend
+            .synced_at
+            .store(current_time_millis() - 7200 * 1000, Ordering::Relaxed);
+        assert!(backend.get(b"__table_route/1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_excludes_users() {
+        let remote = Arc::new(FlakyKvBackend::default());
+        let snapshot = Arc::new(MemoryKvBackend::<Error>::new());
+        // Written by an older version.
+        snapshot
+            .put(
+                PutRequest::new()
+                    .with_key(b"__user/root".to_vec())
+                    .with_value(b"hash".to_vec()),
+            )
+            .await
+            .unwrap();
+        let backend =
+            SnapshotKvBackend::new(remote.clone(), snapshot.clone(), Duration::from_secs(3600));
+
+        let mut req = BatchPutRequest::new().add_kv(b"__user/admin".to_vec(), b"hash".to_vec());
+        // More routes than a page.
+        for i in 0..DEFAULT_PAGE_SIZE + 1 {
+            req = req.add_kv(format!("__table_route/{i}"), b"route".to_vec());
+        }
+        backend.batch_put(req).await.unwrap();
+        assert!(snapshot.get(b"__user/admin").await.unwrap().is_none());
+
+        backend.sync().await.unwrap();
+        assert!(snapshot.get(b"__user/root").await.unwrap().is_none());
+        let resp = snapshot
+            .range(RangeRequest::new().with_prefix(b"__table_route/".to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(DEFAULT_PAGE_SIZE + 1, resp.kvs.len());
+
+        // Users are not read from the snapshot.
+        remote.unavailable.store(true, Ordering::Relaxed);
+        assert!(backend.get(b"__user/admin").await.is_err());
+        assert!(backend.get(b"__table_route/0").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_synced_at_from_snapshot() {
+        let remote = Arc::new(FlakyKvBackend::default());
+        let snapshot = Arc::new(MemoryKvBackend::<Error>::new());
+        snapshot
+            .put(
+                PutRequest::new()
+                    .with_key(b"__table_route/1".to_vec())
+                    .with_value(b"route1".to_vec()),
+            )
+            .await
+            .unwrap();
+        snapshot
+            .put(
+                PutRequest::new()
+                    .with_key(SYNCED_AT_KEY.to_vec())
+                    .with_value(current_time_millis().to_string()),
+            )
+            .await
+            .unwrap();
+
+        // The frontend restarts while the remote is unavailable.
+        remote.unavailable.store(true, Ordering::Relaxed);
+        let backend = SnapshotKvBackend::new(remote, snapshot, Duration::from_secs(3600));
+        let kv = backend.get(b"__table_route/1").await.unwrap().unwrap();
+        assert_eq!(b"route1", kv.value.as_slice());
+    }
+}
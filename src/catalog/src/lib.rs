@@ -28,10 +28,14 @@ use table::TableRef;
 use crate::error::Result;
 
 pub mod error;
+pub mod information_extension;
 pub mod information_schema;
 pub mod kvbackend;
 pub mod memory;
 mod metrics;
+pub mod process_manager;
+pub mod slow_queries;
+pub mod statements_summary;
 pub mod table_source;
 
 #[async_trait::async_trait]
@@ -64,6 +68,13 @@ pub trait CatalogManager: Send + Sync {
         catalog: &'a str,
         schema: &'a str,
     ) -> BoxStream<'a, Result<TableRef>>;
+
+    /// Returns the query to read the view by catalog, schema and view name, which is the
+    /// SQL definition of the view or reading the sink table of a materialized view.
+    async fn view(&self, catalog: &str, schema: &str, view_name: &str) -> Result<Option<String>>;
+
+    /// Returns the names and SQL definitions of all views by catalog and schema.
+    async fn views(&self, catalog: &str, schema: &str) -> Result<Vec<(String, String)>>;
 }
 
 pub type CatalogManagerRef = Arc<dyn CatalogManager>;
@@ -147,6 +147,20 @@ impl CatalogManager for MemoryCatalogManager {
             }
         }));
     }
+
+    async fn view(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _view_name: &str,
+    ) -> Result<Option<String>> {
+        // Views are persisted in the metadata store, which the memory catalog doesn't have.
+        Ok(None)
+    }
+
+    async fn views(&self, _catalog: &str, _schema: &str) -> Result<Vec<(String, String)>> {
+        Ok(vec![])
+    }
 }
 
 impl MemoryCatalogManager {
@@ -34,4 +34,14 @@ lazy_static! {
         register_histogram!("greptime_catalog_kv_get", "catalog kv get").unwrap();
     pub static ref METRIC_CATALOG_KV_BATCH_GET: Histogram =
         register_histogram!("greptime_catalog_kv_batch_get", "catalog kv batch get").unwrap();
+    pub static ref METRIC_CATALOG_KV_SNAPSHOT_GET: IntCounter = register_int_counter!(
+        "greptime_catalog_kv_snapshot_get",
+        "catalog kv get from local snapshot"
+    )
+    .unwrap();
+    pub static ref METRIC_CATALOG_KV_SNAPSHOT_STALE: IntCounter = register_int_counter!(
+        "greptime_catalog_kv_snapshot_stale",
+        "catalog kv get rejected as the local snapshot is too stale"
+    )
+    .unwrap();
 }
@@ -0,0 +1,1082 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of the queries running on this node.
+//!
+//! Frontends of a cluster can share their queries through a registry in the metadata
+//! storage. Each frontend reports its running queries to the registry periodically so
+//! the processlist of any frontend shows queries of all frontends, and a query on
+//! another frontend is killed by leaving a kill marker in the registry.
+//!
+//! The metadata storage has no leases, so each query in the registry carries the time
+//! its lease expires. The owner renews the lease on every report, and any frontend
+//! removes queries whose leases expired, which belong to frontends that are gone.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use common_catalog::build_db_string;
+use common_error::ext::BoxedError;
+use common_meta::kv_backend::KvBackendRef;
+use common_meta::rpc::store::{BatchDeleteRequest, BatchPutRequest, PutRequest, RangeRequest};
+use common_meta::sequence::{SequenceBuilder, SequenceRef};
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::adapter::RecordBatchMetrics;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{OrderOption, RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use common_telemetry::{error, info};
+use common_time::util::current_time_millis;
+use datatypes::schema::SchemaRef;
+use futures::{FutureExt, Stream};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, IntoError, ResultExt};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+use crate::error::{
+    ProcessRegistrySnafu, QueryCancelledSnafu, Result, TooManyQueriesSnafu, ValueDeserializeSnafu,
+};
+use crate::slow_queries::{FinishedQuery, SlowQueries, SlowQuery, SlowQueryOptions};
+use crate::statements_summary::{FinishedStatement, StatementSummary, StatementsSummary};
+
+/// Id of a running query.
+pub type ProcessId = u64;
+
+pub type ProcessManagerRef = Arc<ProcessManager>;
+
+/// Key prefix of queries in the registry.
+const PROCESS_KEY_PREFIX: &str = "__process/";
+/// Key prefix of kill markers in the registry.
+const PROCESS_KILL_KEY_PREFIX: &str = "__process_kill/";
+/// Name of the sequence to allocate blocks of query ids in the cluster.
+const PROCESS_ID_BLOCK_SEQ: &str = "process_id_block";
+/// Number of ids in a block.
+const PROCESS_ID_BLOCK_SIZE: u64 = 4096;
+/// Interval to report running queries to the registry.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// Duration of the lease of a query in the registry. Queries not reported within the
+/// duration belong to a frontend that is gone.
+const LEASE_DURATION: Duration = Duration::from_secs(10);
+
+/// Information of a running query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub id: ProcessId,
+    pub catalog: String,
+    pub schema: String,
+    /// Name of the user who runs the query.
+    pub user: Option<String>,
+    pub query: String,
+    /// Time when the query starts, in milliseconds.
+    pub start_timestamp_ms: i64,
+    /// Address of the frontend that runs the query. It's empty if the query runs
+    /// on a node without the registry.
+    #[serde(default)]
+    pub frontend: Option<String>,
+}
+
+/// Queries a user may see and kill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessScope {
+    /// Queries of all users, for admins.
+    All,
+    /// Queries run by the user.
+    User(Option<String>),
+}
+
+impl ProcessScope {
+    /// Returns true if the query `info` is in the scope.
+    pub fn contains(&self, info: &ProcessInfo) -> bool {
+        match self {
+            ProcessScope::All => true,
+            ProcessScope::User(user) => info.user == *user,
+        }
+    }
+}
+
+/// A query in the registry.
+#[derive(Serialize, Deserialize)]
+struct ReportedProcess {
+    info: ProcessInfo,
+    /// Time when the lease of the query expires, in milliseconds.
+    lease_expire_ms: i64,
+}
+
+impl ReportedProcess {
+    fn is_expired(&self, now_ms: i64) -> bool {
+        self.lease_expire_ms <= now_ms
+    }
+}
+
+fn process_key(id: ProcessId) -> String {
+    format!("{PROCESS_KEY_PREFIX}{id}")
+}
+
+fn process_kill_key(id: ProcessId) -> String {
+    format!("{PROCESS_KILL_KEY_PREFIX}{id}")
+}
+
+/// Returns the id of the query of the kill marker `key`.
+fn parse_process_kill_key(key: &[u8]) -> Option<ProcessId> {
+    String::from_utf8_lossy(key)
+        .strip_prefix(PROCESS_KILL_KEY_PREFIX)
+        .and_then(|id| id.parse().ok())
+}
+
+/// Registry shared by frontends of the cluster.
+struct ProcessRegistry {
+    /// Address of this frontend.
+    frontend: String,
+    kv_backend: KvBackendRef,
+    ids: Arc<ProcessIdAllocator>,
+    /// Queries of this frontend in the registry.
+    reported: Mutex<HashSet<ProcessId>>,
+}
+
+/// Allocates ids of queries from blocks of ids reserved in the cluster, so queries
+/// don't wait on the metadata storage. The next block is reserved in background once
+/// half of the current block is allocated.
+struct ProcessIdAllocator {
+    sequence: SequenceRef,
+    blocks: Mutex<ProcessIdBlocks>,
+}
+
+#[derive(Default)]
+struct ProcessIdBlocks {
+    current: Range<ProcessId>,
+    next: Option<Range<ProcessId>>,
+    /// Whether a task is reserving the next block.
+    reserving: bool,
+}
+
+impl ProcessIdAllocator {
+    fn new(sequence: SequenceRef) -> Self {
+        Self {
+            sequence,
+            blocks: Mutex::default(),
+        }
+    }
+
+    /// Returns the next id. It only waits for a block to be reserved if no reserved id
+    /// is left.
+    async fn next(self: &Arc<Self>) -> Result<ProcessId> {
+        loop {
+            if let Some(id) = self.allocate() {
+                return Ok(id);
+            }
+            self.reserve().await?;
+        }
+    }
+
+    /// Returns the next reserved id, or `None` if no reserved id is left.
+    fn allocate(self: &Arc<Self>) -> Option<ProcessId> {
+        let mut blocks = self.blocks.lock().unwrap();
+        if blocks.current.is_empty() {
+            if let Some(next) = blocks.next.take() {
+                blocks.current = next;
+            }
+        }
+        let id = blocks.current.next();
+
+        let remaining = blocks.current.end - blocks.current.start;
+        if blocks.next.is_none() && !blocks.reserving && remaining <= PROCESS_ID_BLOCK_SIZE / 2 {
+            blocks.reserving = true;
+            let allocator = self.clone();
+            let _handle = common_runtime::spawn_bg(async move {
+                if let Err(e) = allocator.reserve().await {
+                    error!(e; "Failed to reserve a block of query ids");
+                }
+            });
+        }
+        id
+    }
+
+    /// Reserves the next block of ids.
+    async fn reserve(&self) -> Result<()> {
+        let result = self.sequence.next().await;
+        let mut blocks = self.blocks.lock().unwrap();
+        blocks.reserving = false;
+        let block = result.context(ProcessRegistrySnafu)?;
+        let start = block * PROCESS_ID_BLOCK_SIZE;
+        blocks.next = Some(start..start + PROCESS_ID_BLOCK_SIZE);
+        Ok(())
+    }
+}
+
+struct ProcessEntry {
+    info: ProcessInfo,
+    cancellation: CancellationToken,
+    stats: Arc<QueryStats>,
+}
+
+/// Statistics of a running query.
+///
+/// Attach it to the query context as a typed extension so the query engine records
+/// bytes the query reads.
+#[derive(Debug, Default)]
+pub struct QueryStats {
+    rows: AtomicU64,
+    scanned_bytes: AtomicU64,
+    parse_nanos: AtomicU64,
+    plan_nanos: AtomicU64,
+    /// Physical plan of the latest statement of the query.
+    plan: Mutex<Option<PhysicalPlanRef>>,
+}
+
+impl QueryStats {
+    /// Records `rows` returned or affected by the query.
+    pub fn add_rows(&self, rows: u64) {
+        let _ = self.rows.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    /// Records `bytes` the query reads from regions.
+    pub fn add_scanned_bytes(&self, bytes: u64) {
+        let _ = self.scanned_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Records the time to parse the query.
+    pub fn add_parse_elapsed(&self, elapsed: Duration) {
+        let _ = self
+            .parse_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Records the time until the output of a statement is ready.
+    pub fn add_plan_elapsed(&self, elapsed: Duration) {
+        let _ = self
+            .plan_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Sets the physical plan of the statement running now.
+    pub fn set_plan(&self, plan: PhysicalPlanRef) {
+        *self.plan.lock().unwrap() = Some(plan);
+    }
+
+    pub fn rows(&self) -> u64 {
+        self.rows.load(Ordering::Relaxed)
+    }
+
+    pub fn scanned_bytes(&self) -> u64 {
+        self.scanned_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn parse_elapsed(&self) -> Duration {
+        Duration::from_nanos(self.parse_nanos.load(Ordering::Relaxed))
+    }
+
+    pub fn plan_elapsed(&self) -> Duration {
+        Duration::from_nanos(self.plan_nanos.load(Ordering::Relaxed))
+    }
+}
+
+/// Tracks running queries and cancels them on request.
+#[derive(Default)]
+pub struct ProcessManager {
+    next_id: AtomicU64,
+    processes: RwLock<HashMap<ProcessId, ProcessEntry>>,
+    registry: Option<ProcessRegistry>,
+    /// Statistics of finished queries.
+    statements_summary: StatementsSummary,
+    /// Recent queries slower than the threshold.
+    slow_queries: SlowQueries,
+}
+
+impl ProcessManager {
+    /// Creates a manager that only tracks queries of this node.
+    pub fn new(slow_query: SlowQueryOptions) -> Self {
+        Self {
+            slow_queries: SlowQueries::new(slow_query),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a manager that shares queries of the frontend `frontend` with other
+    /// frontends through the registry in `kv_backend`.
+    pub fn with_registry(
+        frontend: String,
+        kv_backend: KvBackendRef,
+        slow_query: SlowQueryOptions,
+    ) -> ProcessManagerRef {
+        // Block 0 is skipped so ids are never 0.
+        let sequence = Arc::new(
+            SequenceBuilder::new(PROCESS_ID_BLOCK_SEQ, kv_backend.clone())
+                .initial(1)
+                .build(),
+        );
+        let manager = Arc::new(Self {
+            registry: Some(ProcessRegistry {
+                frontend,
+                kv_backend,
+                ids: Arc::new(ProcessIdAllocator::new(sequence)),
+                reported: Mutex::new(HashSet::new()),
+            }),
+            slow_queries: SlowQueries::new(slow_query),
+            ..Default::default()
+        });
+
+        let weak_manager = Arc::downgrade(&manager);
+        let _handle = common_runtime::spawn_bg(async move {
+            let mut interval = tokio::time::interval(REPORT_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut last_purge = Instant::now();
+            loop {
+                interval.tick().await;
+                let Some(manager) = weak_manager.upgrade() else {
+                    info!("Process manager is dropped, stop reporting queries");
+                    return;
+                };
+                if let Err(e) = manager.report().await {
+                    error!(e; "Failed to report queries to the registry");
+                }
+                if last_purge.elapsed() >= LEASE_DURATION {
+                    last_purge = Instant::now();
+                    if let Err(e) = manager.purge_expired().await {
+                        error!(e; "Failed to purge expired queries from the registry");
+                    }
+                }
+            }
+        });
+
+        manager
+    }
+
+    /// Registers a running query. The query stays in the registry until the returned
+    /// [Ticket] is dropped.
+    ///
+    /// Fails if `max_running` queries are already running in the database. With the
+    /// registry, queries of other frontends in the registry are counted too, and the
+    /// queries they started since their last report are not. The check and the
+    /// registration happen under the same lock, so concurrent queries of this node can't
+    /// exceed the limit together.
+    pub async fn register(
+        self: &Arc<Self>,
+        catalog: &str,
+        schema: &str,
+        user: Option<String>,
+        query: String,
+        max_running: Option<usize>,
+    ) -> Result<Ticket> {
+        let id = match &self.registry {
+            // Ids must be unique in the cluster.
+            Some(registry) => registry.ids.next().await?,
+            None => self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+        };
+        let remote_running = match (max_running, &self.registry) {
+            (Some(_), Some(registry)) => self
+                .list_remote(registry)
+                .await?
+                .iter()
+                .filter(|info| info.catalog == catalog && info.schema == schema)
+                .count(),
+            _ => 0,
+        };
+        let cancellation = CancellationToken::new();
+        let stats = Arc::new(QueryStats::default());
+        let info = ProcessInfo {
+            id,
+            catalog: catalog.to_string(),
+            schema: schema.to_string(),
+            user,
+            query,
+            start_timestamp_ms: current_time_millis(),
+            frontend: self
+                .registry
+                .as_ref()
+                .map(|registry| registry.frontend.clone()),
+        };
+        let mut processes = self.processes.write().unwrap();
+        if let Some(max_running) = max_running {
+            let running = processes
+                .values()
+                .filter(|entry| entry.info.catalog == catalog && entry.info.schema == schema)
+                .count();
+            ensure!(
+                running + remote_running < max_running,
+                TooManyQueriesSnafu {
+                    database: build_db_string(catalog, schema),
+                    max_queries: max_running,
+                }
+            );
+        }
+        processes.insert(
+            id,
+            ProcessEntry {
+                info,
+                cancellation: cancellation.clone(),
+                stats: stats.clone(),
+            },
+        );
+        drop(processes);
+
+        Ok(Ticket {
+            id,
+            cancellation,
+            stats,
+            manager: Arc::downgrade(self),
+        })
+    }
+
+    /// Returns all running queries of this node, ordered by id.
+    pub fn list(&self) -> Vec<ProcessInfo> {
+        let mut processes = self
+            .processes
+            .read()
+            .unwrap()
+            .values()
+            .map(|entry| entry.info.clone())
+            .collect::<Vec<_>>();
+        processes.sort_unstable_by_key(|info| info.id);
+        processes
+    }
+
+    /// Returns running queries of this node and other frontends in the registry,
+    /// ordered by id.
+    pub async fn list_all(&self) -> Result<Vec<ProcessInfo>> {
+        let mut processes = self.list();
+        if let Some(registry) = &self.registry {
+            processes.extend(self.list_remote(registry).await?);
+            processes.sort_unstable_by_key(|info| info.id);
+        }
+
+        Ok(processes)
+    }
+
+    /// Returns running queries of other frontends in the registry.
+    async fn list_remote(&self, registry: &ProcessRegistry) -> Result<Vec<ProcessInfo>> {
+        let local_ids = self
+            .processes
+            .read()
+            .unwrap()
+            .keys()
+            .copied()
+            .collect::<HashSet<_>>();
+        let reported = registry.reported.lock().unwrap().clone();
+        let now = current_time_millis();
+        let resp = registry
+            .kv_backend
+            .range(RangeRequest::new().with_prefix(PROCESS_KEY_PREFIX))
+            .await
+            .context(ProcessRegistrySnafu)?;
+        let mut processes = Vec::new();
+        for kv in resp.kvs {
+            let process: ReportedProcess =
+                serde_json::from_slice(&kv.value).context(ValueDeserializeSnafu)?;
+            let id = process.info.id;
+            // Queries of this node that finished may not be removed from the registry yet.
+            if local_ids.contains(&id) || reported.contains(&id) || process.is_expired(now) {
+                continue;
+            }
+            processes.push(process.info);
+        }
+
+        Ok(processes)
+    }
+
+    /// Cancels the query `id` in the `catalog` if it is in the `scope`. Returns false if
+    /// the query doesn't exist or is out of the scope.
+    ///
+    /// A query of another frontend is cancelled once the frontend finds the kill marker
+    /// in the registry.
+    pub async fn kill(&self, catalog: &str, id: ProcessId, scope: &ProcessScope) -> Result<bool> {
+        if let Some(entry) = self.processes.read().unwrap().get(&id) {
+            if entry.info.catalog != catalog || !scope.contains(&entry.info) {
+                return Ok(false);
+            }
+            entry.cancellation.cancel();
+            return Ok(true);
+        }
+        let Some(registry) = &self.registry else {
+            return Ok(false);
+        };
+
+        let key = process_key(id);
+        let Some(kv) = registry
+            .kv_backend
+            .get(key.as_bytes())
+            .await
+            .context(ProcessRegistrySnafu)?
+        else {
+            return Ok(false);
+        };
+        let process: ReportedProcess =
+            serde_json::from_slice(&kv.value).context(ValueDeserializeSnafu)?;
+        if process.info.catalog != catalog
+            || !scope.contains(&process.info)
+            || process.is_expired(current_time_millis())
+        {
+            return Ok(false);
+        }
+
+        registry
+            .kv_backend
+            .put(
+                PutRequest::new()
+                    .with_key(process_kill_key(id))
+                    .with_value(vec![]),
+            )
+            .await
+            .context(ProcessRegistrySnafu)?;
+        info!(
+            "Query {} of frontend {:?} is marked as killed",
+            id, process.info.frontend
+        );
+
+        Ok(true)
+    }
+
+    /// Reports running queries of this node to the registry, removes finished queries
+    /// from it and cancels queries with kill markers.
+    async fn report(&self) -> Result<()> {
+        let Some(registry) = &self.registry else {
+            return Ok(());
+        };
+
+        let processes = self.list();
+        let previous = registry.reported.lock().unwrap().clone();
+        let current = processes.iter().map(|info| info.id).collect::<HashSet<_>>();
+        if processes.is_empty() && previous.is_empty() {
+            return Ok(());
+        }
+
+        // Kill markers only exist for reported queries.
+        if !previous.is_empty() {
+            let resp = registry
+                .kv_backend
+                .range(
+                    RangeRequest::new()
+                        .with_prefix(PROCESS_KILL_KEY_PREFIX)
+                        .with_keys_only(),
+                )
+                .await
+                .context(ProcessRegistrySnafu)?;
+            let processes = self.processes.read().unwrap();
+            for kv in resp.kvs {
+                let Some(id) = parse_process_kill_key(&kv.key) else {
+                    continue;
+                };
+                if let Some(entry) = processes.get(&id) {
+                    info!("Kill query {} by the kill marker", id);
+                    entry.cancellation.cancel();
+                }
+            }
+        }
+
+        let now = current_time_millis();
+        let mut put_request = BatchPutRequest::new();
+        for info in processes {
+            let key = process_key(info.id);
+            let process = ReportedProcess {
+                info,
+                lease_expire_ms: now + LEASE_DURATION.as_millis() as i64,
+            };
+            // Safety: The process can always be serialized.
+            let value = serde_json::to_vec(&process).unwrap();
+            put_request = put_request.add_kv(key, value);
+        }
+        if !put_request.kvs.is_empty() {
+            registry
+                .kv_backend
+                .batch_put(put_request)
+                .await
+                .context(ProcessRegistrySnafu)?;
+        }
+
+        let mut delete_request = BatchDeleteRequest::new();
+        for id in previous.difference(&current) {
+            delete_request = delete_request
+                .add_key(process_key(*id))
+                .add_key(process_kill_key(*id));
+        }
+        if !delete_request.keys.is_empty() {
+            registry
+                .kv_backend
+                .batch_delete(delete_request)
+                .await
+                .context(ProcessRegistrySnafu)?;
+        }
+
+        *registry.reported.lock().unwrap() = current;
+
+        Ok(())
+    }
+
+    /// Removes queries whose leases expired from the registry, and kill markers of
+    /// queries that are no longer in the registry.
+    async fn purge_expired(&self) -> Result<()> {
+        let Some(registry) = &self.registry else {
+            return Ok(());
+        };
+
+        // Reads kill markers first. A marker is only written for a query in the registry,
+        // so the query of a marker read here is still in the registry below unless it
+        // is gone.
+        let markers = registry
+            .kv_backend
+            .range(
+                RangeRequest::new()
+                    .with_prefix(PROCESS_KILL_KEY_PREFIX)
+                    .with_keys_only(),
+            )
+            .await
+            .context(ProcessRegistrySnafu)?
+            .kvs;
+        let resp = registry
+            .kv_backend
+            .range(RangeRequest::new().with_prefix(PROCESS_KEY_PREFIX))
+            .await
+            .context(ProcessRegistrySnafu)?;
+
+        let now = current_time_millis();
+        let mut alive = HashSet::with_capacity(resp.kvs.len());
+        let mut delete_request = BatchDeleteRequest::new();
+        for kv in resp.kvs {
+            let process: ReportedProcess =
+                serde_json::from_slice(&kv.value).context(ValueDeserializeSnafu)?;
+            if process.is_expired(now) {
+                delete_request = delete_request.add_key(kv.key);
+            } else {
+                alive.insert(process.info.id);
+            }
+        }
+        for kv in markers {
+            if parse_process_kill_key(&kv.key).is_some_and(|id| !alive.contains(&id)) {
+                delete_request = delete_request.add_key(kv.key);
+            }
+        }
+        if delete_request.keys.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Purge {} expired queries and kill markers from the registry",
+            delete_request.keys.len()
+        );
+        registry
+            .kv_backend
+            .batch_delete(delete_request)
+            .await
+            .context(ProcessRegistrySnafu)?;
+
+        Ok(())
+    }
+
+    /// Returns statistics of queries finished on this node, aggregated by their shapes.
+    pub fn statements_summary(&self) -> Vec<StatementSummary> {
+        self.statements_summary.list()
+    }
+
+    /// Returns recent queries finished on this node that are slower than the
+    /// threshold, the latest first.
+    pub fn slow_queries(&self) -> Vec<SlowQuery> {
+        self.slow_queries.list()
+    }
+
+    fn deregister(&self, id: ProcessId) {
+        let Some(entry) = self.processes.write().unwrap().remove(&id) else {
+            return;
+        };
+        let info = entry.info;
+        let now = current_time_millis();
+        let stats = &entry.stats;
+        let parse = stats.parse_elapsed();
+        self.slow_queries.record(FinishedQuery {
+            catalog: &info.catalog,
+            schema: &info.schema,
+            user: info.user.as_deref(),
+            query: &info.query,
+            start_ms: info.start_timestamp_ms,
+            total: parse + Duration::from_millis((now - info.start_timestamp_ms).max(0) as u64),
+            parse,
+            plan: stats.plan_elapsed(),
+            rows: stats.rows(),
+            scanned_bytes: stats.scanned_bytes(),
+            physical_plan: stats.plan.lock().unwrap().take(),
+        });
+        self.statements_summary.record(FinishedStatement {
+            catalog: info.catalog,
+            schema: info.schema,
+            query: info.query,
+            latency_ms: (now - info.start_timestamp_ms).max(0) as u64,
+            rows: entry.stats.rows(),
+            scanned_bytes: entry.stats.scanned_bytes(),
+            finish_ms: now,
+        });
+    }
+}
+
+/// Handle of a registered query. It removes the query from the [ProcessManager]
+/// once dropped.
+pub struct Ticket {
+    id: ProcessId,
+    cancellation: CancellationToken,
+    stats: Arc<QueryStats>,
+    manager: Weak<ProcessManager>,
+}
+
+impl Ticket {
+    pub fn id(&self) -> ProcessId {
+        self.id
+    }
+
+    /// Returns the statistics of the query.
+    pub fn stats(&self) -> &Arc<QueryStats> {
+        &self.stats
+    }
+
+    /// Runs the `future` until it completes or the query is killed.
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output> {
+        tokio::select! {
+            output = future => Ok(output),
+            _ = self.cancellation.cancelled() => QueryCancelledSnafu { id: self.id }.fail(),
+        }
+    }
+
+    /// Wraps the `stream` so it stops with an error once the query is killed. The
+    /// stream holds the ticket until it is dropped and records rows it returns.
+    pub fn wrap_stream(
+        self: Arc<Self>,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        let cancelled = self.cancellation.clone().cancelled_owned();
+        Box::pin(CancellableStream {
+            stream,
+            cancelled: Box::pin(cancelled),
+            ticket: self,
+        })
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        if let Some(manager) = self.manager.upgrade() {
+            manager.deregister(self.id);
+        }
+    }
+}
+
+/// A stream that stops with an error once its query is killed.
+struct CancellableStream {
+    stream: SendableRecordBatchStream,
+    cancelled: Pin<Box<WaitForCancellationFutureOwned>>,
+    ticket: Arc<Ticket>,
+}
+
+impl RecordBatchStream for CancellableStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn output_ordering(&self) -> Option<&[OrderOption]> {
+        self.stream.output_ordering()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        self.stream.metrics()
+    }
+}
+
+impl Stream for CancellableStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.cancelled.poll_unpin(cx).is_ready() {
+            let error = QueryCancelledSnafu { id: self.ticket.id }.build();
+            return Poll::Ready(Some(Err(ExternalSnafu.into_error(BoxedError::new(error)))));
+        }
+        let poll = self.stream.as_mut().poll_next(cx);
+        if let Poll::Ready(Some(Ok(batch))) = &poll {
+            self.ticket.stats.add_rows(batch.num_rows() as u64);
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+    use common_meta::kv_backend::KvBackend;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_register_and_kill() {
+        let manager = Arc::new(ProcessManager::new(SlowQueryOptions::default()));
+        let ticket1 = manager
+            .register("greptime", "public", None, "SELECT 1".to_string(), None)
+            .await
+            .unwrap();
+        let ticket2 = manager
+            .register(
+                "greptime",
+                "test",
+                Some("root".to_string()),
+                "SELECT 2".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_ne!(ticket1.id(), ticket2.id());
+
+        let processes = manager.list();
+        assert_eq!(2, processes.len());
+        assert_eq!("SELECT 1", processes[0].query);
+        assert_eq!(Some("root"), processes[1].user.as_deref());
+
+        let all = ProcessScope::All;
+        assert!(!manager.kill("other", ticket1.id(), &all).await.unwrap());
+        // Users can only kill their own queries.
+        let root = ProcessScope::User(Some("root".to_string()));
+        assert!(!manager.kill("greptime", ticket1.id(), &root).await.unwrap());
+        assert!(manager.kill("greptime", ticket1.id(), &all).await.unwrap());
+        assert!(ticket1.run(futures::future::pending::<()>()).await.is_err());
+        assert_eq!(1, ticket2.run(async { 1 }).await.unwrap());
+
+        ticket1.stats().add_rows(3);
+        ticket1.stats().add_scanned_bytes(100);
+        drop(ticket1);
+        let processes = manager.list();
+        assert_eq!(1, processes.len());
+        assert_eq!(ticket2.id(), processes[0].id);
+        assert!(!manager
+            .kill("greptime", ticket2.id() + 1, &all)
+            .await
+            .unwrap());
+        assert!(manager.kill("greptime", ticket2.id(), &root).await.unwrap());
+
+        // Finished queries are aggregated in the summary.
+        let statements = manager.statements_summary();
+        assert_eq!(1, statements.len());
+        assert_eq!("SELECT ?", statements[0].digest_text);
+        assert_eq!("public", statements[0].schema);
+        assert_eq!(3, statements[0].sum_rows);
+        assert_eq!(100, statements[0].sum_scanned_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_register_with_max_running() {
+        let manager = Arc::new(ProcessManager::new(SlowQueryOptions::default()));
+        let query = || "SELECT 1".to_string();
+        let ticket = manager
+            .register("greptime", "public", None, query(), Some(1))
+            .await
+            .unwrap();
+        let err = manager
+            .register("greptime", "public", None, query(), Some(1))
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::QuotaExceeded, err.status_code());
+        // Queries of other databases are not counted.
+        let _other = manager
+            .register("greptime", "test", None, query(), Some(1))
+            .await
+            .unwrap();
+
+        drop(ticket);
+        let _ticket = manager
+            .register("greptime", "public", None, query(), Some(1))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_register_with_max_running_in_cluster() {
+        let kv_backend = Arc::new(MemoryKvBackend::<common_meta::error::Error>::new());
+        let manager1 = ProcessManager::with_registry(
+            "fe1".to_string(),
+            kv_backend.clone(),
+            SlowQueryOptions::default(),
+        );
+        let manager2 = ProcessManager::with_registry(
+            "fe2".to_string(),
+            kv_backend,
+            SlowQueryOptions::default(),
+        );
+        let query = || "SELECT 1".to_string();
+
+        let ticket = manager1
+            .register("greptime", "public", None, query(), Some(2))
+            .await
+            .unwrap();
+        manager1.report().await.unwrap();
+        // Counts the reported query of another frontend.
+        let _ticket2 = manager2
+            .register("greptime", "public", None, query(), Some(2))
+            .await
+            .unwrap();
+        let err = manager2
+            .register("greptime", "public", None, query(), Some(2))
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::QuotaExceeded, err.status_code());
+
+        drop(ticket);
+        manager1.report().await.unwrap();
+        let _ticket3 = manager2
+            .register("greptime", "public", None, query(), Some(2))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_slow_queries() {
+        let manager = Arc::new(ProcessManager::new(SlowQueryOptions {
+            enable: true,
+            threshold: Duration::ZERO,
+            ..Default::default()
+        }));
+        let ticket = manager
+            .register("greptime", "public", None, "SELECT 1".to_string(), None)
+            .await
+            .unwrap();
+        ticket.stats().add_parse_elapsed(Duration::from_millis(5));
+        ticket.stats().add_plan_elapsed(Duration::from_millis(10));
+        ticket.stats().add_rows(1);
+        drop(ticket);
+
+        let slow_queries = manager.slow_queries();
+        assert_eq!(1, slow_queries.len());
+        assert_eq!("SELECT ?", slow_queries[0].query);
+        assert_eq!(5, slow_queries[0].parse_ms);
+        assert_eq!(10, slow_queries[0].plan_ms);
+        assert!(slow_queries[0].total_ms >= 15);
+        assert_eq!(1, slow_queries[0].rows);
+    }
+
+    #[tokio::test]
+    async fn test_registry() {
+        let kv_backend = Arc::new(MemoryKvBackend::<common_meta::error::Error>::new());
+        let manager1 = ProcessManager::with_registry(
+            "fe1".to_string(),
+            kv_backend.clone(),
+            SlowQueryOptions::default(),
+        );
+        let manager2 = ProcessManager::with_registry(
+            "fe2".to_string(),
+            kv_backend,
+            SlowQueryOptions::default(),
+        );
+
+        let ticket1 = manager1
+            .register("greptime", "public", None, "SELECT 1".to_string(), None)
+            .await
+            .unwrap();
+        let ticket2 = manager2
+            .register("greptime", "public", None, "SELECT 2".to_string(), None)
+            .await
+            .unwrap();
+        assert_ne!(ticket1.id(), ticket2.id());
+        manager1.report().await.unwrap();
+        manager2.report().await.unwrap();
+
+        let processes = manager2.list_all().await.unwrap();
+        assert_eq!(2, processes.len());
+        let process1 = processes.iter().find(|p| p.id == ticket1.id()).unwrap();
+        assert_eq!(Some("fe1"), process1.frontend.as_deref());
+        assert_eq!("SELECT 1", process1.query);
+
+        // Kills the query of another frontend.
+        let all = ProcessScope::All;
+        assert!(!manager2.kill("other", ticket1.id(), &all).await.unwrap());
+        let root = ProcessScope::User(Some("root".to_string()));
+        assert!(!manager2
+            .kill("greptime", ticket1.id(), &root)
+            .await
+            .unwrap());
+        assert!(manager2.kill("greptime", ticket1.id(), &all).await.unwrap());
+        manager1.report().await.unwrap();
+        assert!(ticket1.run(futures::future::pending::<()>()).await.is_err());
+
+        // Finished queries are removed from the registry.
+        drop(ticket1);
+        manager1.report().await.unwrap();
+        let processes = manager2.list_all().await.unwrap();
+        assert_eq!(1, processes.len());
+        assert_eq!(ticket2.id(), processes[0].id);
+        assert!(!manager2.kill("greptime", 0, &all).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired() {
+        let kv_backend = Arc::new(MemoryKvBackend::<common_meta::error::Error>::new());
+        let manager = ProcessManager::with_registry(
+            "fe1".to_string(),
+            kv_backend.clone(),
+            SlowQueryOptions::default(),
+        );
+        let ticket = manager
+            .register("greptime", "public", None, "SELECT 1".to_string(), None)
+            .await
+            .unwrap();
+        // Ids are allocated from the same block.
+        let next_ticket = manager
+            .register("greptime", "public", None, "SELECT 2".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(ticket.id() + 1, next_ticket.id());
+        assert_eq!(PROCESS_ID_BLOCK_SIZE, ticket.id());
+        manager.report().await.unwrap();
+
+        // A query of a frontend that is gone, killed before the frontend stops.
+        let expired = ReportedProcess {
+            info: ProcessInfo {
+                id: 1,
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                user: None,
+                query: "SELECT 3".to_string(),
+                start_timestamp_ms: 0,
+                frontend: Some("fe2".to_string()),
+            },
+            lease_expire_ms: 0,
+        };
+        let put_request = BatchPutRequest::new()
+            .add_kv(process_key(1), serde_json::to_vec(&expired).unwrap())
+            .add_kv(process_kill_key(1), vec![])
+            .add_kv(process_kill_key(2), vec![])
+            .add_kv(process_kill_key(ticket.id()), vec![]);
+        kv_backend.batch_put(put_request).await.unwrap();
+
+        manager.purge_expired().await.unwrap();
+        assert!(!kv_backend.exists(process_key(1).as_bytes()).await.unwrap());
+        assert!(!kv_backend
+            .exists(process_kill_key(1).as_bytes())
+            .await
+            .unwrap());
+        assert!(!kv_backend
+            .exists(process_kill_key(2).as_bytes())
+            .await
+            .unwrap());
+        // Queries alive and their kill markers are kept.
+        assert!(kv_backend
+            .exists(process_key(ticket.id()).as_bytes())
+            .await
+            .unwrap());
+        assert!(kv_backend
+            .exists(process_kill_key(ticket.id()).as_bytes())
+            .await
+            .unwrap());
+    }
+}
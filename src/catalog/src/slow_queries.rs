@@ -0,0 +1,246 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recent queries slower than a threshold.
+//!
+//! Slow queries are logged to the `slow_query` log target and kept in a ring buffer
+//! so `information_schema.slow_queries` lists the latest ones.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use common_query::physical_plan::{PhysicalPlanAdapter, PhysicalPlanRef};
+use common_telemetry::warn;
+use datafusion::physical_plan::display::DisplayableExecutionPlan;
+use serde::{Deserialize, Serialize};
+use sql::util::normalize_sql;
+
+/// Log target of slow queries.
+pub const SLOW_QUERY_LOG_TARGET: &str = "slow_query";
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SlowQueryOptions {
+    pub enable: bool,
+    /// Queries taking longer than it are slow queries.
+    #[serde(with = "humantime_serde")]
+    pub threshold: Duration,
+    /// Whether to record the physical plan with execution metrics of slow queries.
+    pub record_plan: bool,
+    /// Whether to log slow queries to the `slow_query` log target.
+    pub log: bool,
+    /// Max number of slow queries to keep for `information_schema.slow_queries`.
+    /// Slow queries are not kept if it's 0.
+    pub max_entries: usize,
+}
+
+impl Default for SlowQueryOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            threshold: Duration::from_secs(5),
+            record_plan: false,
+            log: true,
+            max_entries: 1000,
+        }
+    }
+}
+
+/// A query slower than the threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowQuery {
+    pub catalog: String,
+    pub schema: String,
+    pub user: Option<String>,
+    /// The normalized query.
+    pub query: String,
+    /// Time when the query starts, in milliseconds.
+    pub start_ms: i64,
+    /// Wall time of the query, in milliseconds.
+    pub total_ms: u64,
+    /// Time to parse the query, in milliseconds.
+    pub parse_ms: u64,
+    /// Time until the output of the query is ready, in milliseconds. It includes
+    /// executing statements that don't stream their results.
+    pub plan_ms: u64,
+    /// Time to stream the results, in milliseconds.
+    pub exec_ms: u64,
+    /// Rows returned or affected by the query.
+    pub rows: u64,
+    /// Bytes the query reads from regions.
+    pub scanned_bytes: u64,
+    /// The physical plan with execution metrics, if recording plans is enabled.
+    pub plan: Option<String>,
+}
+
+/// A finished query to check.
+#[derive(Debug)]
+pub struct FinishedQuery<'a> {
+    pub catalog: &'a str,
+    pub schema: &'a str,
+    pub user: Option<&'a str>,
+    pub query: &'a str,
+    pub start_ms: i64,
+    pub total: Duration,
+    pub parse: Duration,
+    pub plan: Duration,
+    pub rows: u64,
+    pub scanned_bytes: u64,
+    pub physical_plan: Option<PhysicalPlanRef>,
+}
+
+/// Records queries slower than the threshold.
+#[derive(Default)]
+pub struct SlowQueries {
+    options: SlowQueryOptions,
+    queries: Mutex<VecDeque<SlowQuery>>,
+}
+
+impl SlowQueries {
+    pub fn new(options: SlowQueryOptions) -> Self {
+        Self {
+            options,
+            queries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Records the query if it's slower than the threshold.
+    pub fn record(&self, finished: FinishedQuery) {
+        if !self.options.enable || finished.total < self.options.threshold {
+            return;
+        }
+
+        let total_ms = finished.total.as_millis() as u64;
+        let parse_ms = finished.parse.as_millis() as u64;
+        let plan_ms = finished.plan.as_millis() as u64;
+        let slow_query = SlowQuery {
+            catalog: finished.catalog.to_string(),
+            schema: finished.schema.to_string(),
+            user: finished.user.map(|user| user.to_string()),
+            query: normalize_sql(finished.query),
+            start_ms: finished.start_ms,
+            total_ms,
+            parse_ms,
+            plan_ms,
+            exec_ms: total_ms.saturating_sub(parse_ms + plan_ms),
+            rows: finished.rows,
+            scanned_bytes: finished.scanned_bytes,
+            plan: finished
+                .physical_plan
+                .filter(|_| self.options.record_plan)
+                .map(|plan| display_plan(&plan)),
+        };
+
+        if self.options.log {
+            warn!(
+                target: SLOW_QUERY_LOG_TARGET,
+                "Slow query in {}.{}, total: {}ms, parse: {}ms, plan: {}ms, exec: {}ms, rows: {}, scanned_bytes: {}, query: {}{}",
+                slow_query.catalog,
+                slow_query.schema,
+                slow_query.total_ms,
+                slow_query.parse_ms,
+                slow_query.plan_ms,
+                slow_query.exec_ms,
+                slow_query.rows,
+                slow_query.scanned_bytes,
+                slow_query.query,
+                slow_query
+                    .plan
+                    .as_ref()
+                    .map(|plan| format!(", plan:\n{plan}"))
+                    .unwrap_or_default(),
+            );
+        }
+
+        if self.options.max_entries == 0 {
+            return;
+        }
+        let mut queries = self.queries.lock().unwrap();
+        if queries.len() >= self.options.max_entries {
+            let _ = queries.pop_front();
+        }
+        queries.push_back(slow_query);
+    }
+
+    /// Returns the kept slow queries, the latest first.
+    pub fn list(&self) -> Vec<SlowQuery> {
+        self.queries.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+/// Displays the plan with its execution metrics.
+fn display_plan(plan: &PhysicalPlanRef) -> String {
+    match plan.as_any().downcast_ref::<PhysicalPlanAdapter>() {
+        Some(adapter) => DisplayableExecutionPlan::with_metrics(adapter.df_plan().as_ref())
+            .indent(true)
+            .to_string(),
+        None => format!("{plan:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(query: &str, total_ms: u64) -> FinishedQuery<'_> {
+        FinishedQuery {
+            catalog: "greptime",
+            schema: "public",
+            user: Some("root"),
+            query,
+            start_ms: 1000,
+            total: Duration::from_millis(total_ms),
+            parse: Duration::from_millis(1),
+            plan: Duration::from_millis(2),
+            rows: 3,
+            scanned_bytes: 100,
+            physical_plan: None,
+        }
+    }
+
+    #[test]
+    fn test_record_slow_queries() {
+        let slow_queries = SlowQueries::new(SlowQueryOptions {
+            enable: true,
+            threshold: Duration::from_millis(100),
+            max_entries: 2,
+            ..Default::default()
+        });
+        slow_queries.record(finished("SELECT * FROM t WHERE a = 1", 99));
+        assert!(slow_queries.list().is_empty());
+
+        slow_queries.record(finished("SELECT * FROM t WHERE a = 1", 100));
+        slow_queries.record(finished("SELECT 2", 200));
+        slow_queries.record(finished("SELECT 3", 300));
+        let queries = slow_queries.list();
+        assert_eq!(2, queries.len());
+        assert_eq!("SELECT ?", queries[0].query);
+        assert_eq!(300, queries[0].total_ms);
+        assert_eq!(297, queries[0].exec_ms);
+        assert_eq!(200, queries[1].total_ms);
+        assert_eq!(Some("root"), queries[1].user.as_deref());
+        assert!(queries[1].plan.is_none());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let slow_queries = SlowQueries::new(SlowQueryOptions {
+            threshold: Duration::ZERO,
+            ..Default::default()
+        });
+        slow_queries.record(finished("SELECT 1", 100));
+        assert!(slow_queries.list().is_empty());
+    }
+}
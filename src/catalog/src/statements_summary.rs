@@ -0,0 +1,255 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics of finished queries aggregated by their shapes.
+//!
+//! Finished queries are buffered in a ring buffer and aggregated by their digests
+//! periodically, so normalizing queries doesn't slow down every query.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use sql::util::{normalize_sql, sql_digest};
+
+/// Max number of digests to keep. The least recently seen digest is evicted once
+/// the summary is full.
+const MAX_DIGESTS: usize = 1000;
+/// Max number of finished queries to buffer before aggregating them.
+const MAX_PENDING: usize = 1024;
+/// Interval to aggregate buffered queries, in milliseconds.
+const AGGREGATE_INTERVAL_MS: i64 = 5000;
+
+/// Statistics of queries of the same [digest](sql_digest) in a database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementSummary {
+    pub catalog: String,
+    pub schema: String,
+    pub digest: String,
+    /// The normalized query of the digest.
+    pub digest_text: String,
+    /// The latest query of the digest.
+    pub sample_query: String,
+    pub exec_count: u64,
+    pub sum_latency_ms: u64,
+    pub max_latency_ms: u64,
+    /// Total rows returned or affected by the queries.
+    pub sum_rows: u64,
+    /// Total bytes the queries read from regions.
+    pub sum_scanned_bytes: u64,
+    /// Time when the digest is seen for the first time, in milliseconds.
+    pub first_seen_ms: i64,
+    /// Time when the digest is seen for the last time, in milliseconds.
+    pub last_seen_ms: i64,
+}
+
+/// A finished query to aggregate.
+#[derive(Debug)]
+pub struct FinishedStatement {
+    pub catalog: String,
+    pub schema: String,
+    pub query: String,
+    pub latency_ms: u64,
+    pub rows: u64,
+    pub scanned_bytes: u64,
+    /// Time when the query finishes, in milliseconds.
+    pub finish_ms: i64,
+}
+
+#[derive(Default)]
+struct Pending {
+    statements: VecDeque<FinishedStatement>,
+    last_aggregate_ms: i64,
+}
+
+/// Aggregates statistics of finished queries by catalog, schema and digest.
+#[derive(Default)]
+pub struct StatementsSummary {
+    pending: Mutex<Pending>,
+    statements: Mutex<HashMap<(String, String, String), StatementSummary>>,
+}
+
+impl StatementsSummary {
+    /// Records a finished query.
+    ///
+    /// The query is aggregated once the buffer is full or the aggregate interval elapses.
+    pub fn record(&self, statement: FinishedStatement) {
+        let now_ms = statement.finish_ms;
+        let drained = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.statements.push_back(statement);
+            if pending.statements.len() < MAX_PENDING
+                && now_ms - pending.last_aggregate_ms < AGGREGATE_INTERVAL_MS
+            {
+                return;
+            }
+            pending.last_aggregate_ms = now_ms;
+            std::mem::take(&mut pending.statements)
+        };
+
+        self.aggregate(drained);
+    }
+
+    /// Returns statistics of all digests, the slowest first by total latency.
+    pub fn list(&self) -> Vec<StatementSummary> {
+        let drained = std::mem::take(&mut self.pending.lock().unwrap().statements);
+        self.aggregate(drained);
+
+        let mut statements = self
+            .statements
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        statements.sort_unstable_by(|a, b| {
+            b.sum_latency_ms
+                .cmp(&a.sum_latency_ms)
+                .then_with(|| a.digest.cmp(&b.digest))
+        });
+        statements
+    }
+
+    fn aggregate(&self, finished: VecDeque<FinishedStatement>) {
+        if finished.is_empty() {
+            return;
+        }
+
+        // Normalizes queries without holding the lock.
+        let finished = finished
+            .into_iter()
+            .map(|statement| {
+                let digest_text = normalize_sql(&statement.query);
+                let digest = sql_digest(&statement.query);
+                (statement, digest, digest_text)
+            })
+            .collect::<Vec<_>>();
+
+        let mut statements = self.statements.lock().unwrap();
+        for (statement, digest, digest_text) in finished {
+            let key = (statement.catalog, statement.schema, digest);
+            if !statements.contains_key(&key) && statements.len() >= MAX_DIGESTS {
+                // Safety: The summary is full so it's not empty.
+                let evicted = statements
+                    .iter()
+                    .min_by_key(|(_, summary)| summary.last_seen_ms)
+                    .map(|(key, _)| key.clone())
+                    .unwrap();
+                let _ = statements.remove(&evicted);
+            }
+            let summary = statements
+                .entry(key)
+                .or_insert_with_key(|(catalog, schema, digest)| StatementSummary {
+                    catalog: catalog.clone(),
+                    schema: schema.clone(),
+                    digest: digest.clone(),
+                    digest_text,
+                    sample_query: String::new(),
+                    exec_count: 0,
+                    sum_latency_ms: 0,
+                    max_latency_ms: 0,
+                    sum_rows: 0,
+                    sum_scanned_bytes: 0,
+                    first_seen_ms: statement.finish_ms,
+                    last_seen_ms: statement.finish_ms,
+                });
+            summary.sample_query = statement.query;
+            summary.exec_count += 1;
+            summary.sum_latency_ms += statement.latency_ms;
+            summary.max_latency_ms = summary.max_latency_ms.max(statement.latency_ms);
+            summary.sum_rows += statement.rows;
+            summary.sum_scanned_bytes += statement.scanned_bytes;
+            summary.last_seen_ms = summary.last_seen_ms.max(statement.finish_ms);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(schema: &str, query: &str, latency_ms: u64, finish_ms: i64) -> FinishedStatement {
+        FinishedStatement {
+            catalog: "greptime".to_string(),
+            schema: schema.to_string(),
+            query: query.to_string(),
+            latency_ms,
+            rows: 2,
+            scanned_bytes: 100,
+            finish_ms,
+        }
+    }
+
+    #[test]
+    fn test_statements_summary() {
+        let summary = StatementsSummary::default();
+        summary.record(finished(
+            "public",
+            "SELECT * FROM t_table WHERE a_col = 1",
+            10,
+            1000,
+        ));
+        summary.record(finished(
+            "public",
+            "select * from t_table where a_col = 2",
+            30,
+            2000,
+        ));
+        summary.record(finished("public", "SELECT 1", 5, 3000));
+        summary.record(finished("other", "SELECT 1", 5, 4000));
+
+        let statements = summary.list();
+        assert_eq!(3, statements.len());
+        let slowest = &statements[0];
+        assert_eq!("SELECT * FROM t_table WHERE a_col = ?", slowest.digest_text);
+        assert_eq!(
+            "select * from t_table where a_col = 2",
+            slowest.sample_query
+        );
+        assert_eq!(2, slowest.exec_count);
+        assert_eq!(40, slowest.sum_latency_ms);
+        assert_eq!(30, slowest.max_latency_ms);
+        assert_eq!(4, slowest.sum_rows);
+        assert_eq!(200, slowest.sum_scanned_bytes);
+        assert_eq!(1000, slowest.first_seen_ms);
+        assert_eq!(2000, slowest.last_seen_ms);
+    }
+
+    #[test]
+    fn test_aggregate_periodically() {
+        let summary = StatementsSummary::default();
+        // The first query is aggregated since the summary never aggregates.
+        summary.record(finished("public", "SELECT 1", 1, AGGREGATE_INTERVAL_MS));
+        summary.record(finished("public", "SELECT 1", 1, AGGREGATE_INTERVAL_MS + 1));
+        assert_eq!(1, summary.pending.lock().unwrap().statements.len());
+        assert_eq!(1, summary.statements.lock().unwrap().len());
+
+        summary.record(finished("public", "SELECT 1", 1, AGGREGATE_INTERVAL_MS * 2));
+        assert!(summary.pending.lock().unwrap().statements.is_empty());
+        assert_eq!(3, summary.list()[0].exec_count);
+    }
+
+    #[test]
+    fn test_evict_least_recently_seen() {
+        let summary = StatementsSummary::default();
+        for i in 0..MAX_DIGESTS {
+            summary.record(finished("public", &format!("SELECT c{i}"), 1, i as i64));
+        }
+        summary.record(finished("public", "SELECT c_new", 1, MAX_DIGESTS as i64));
+
+        let statements = summary.list();
+        assert_eq!(MAX_DIGESTS, statements.len());
+        assert!(!statements.iter().any(|s| s.sample_query == "SELECT c0"));
+        assert!(statements.iter().any(|s| s.sample_query == "SELECT c_new"));
+    }
+}
@@ -24,6 +24,7 @@ use snafu::{ensure, OptionExt};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::error::{QueryAccessDeniedSnafu, Result, TableNotExistSnafu};
+use crate::information_schema::InformationSchemaProvider;
 use crate::CatalogManagerRef;
 
 pub struct DfTableSourceProvider {
@@ -32,6 +33,8 @@ pub struct DfTableSourceProvider {
     disallow_cross_catalog_query: bool,
     default_catalog: String,
     default_schema: String,
+    /// Name of the user who runs the query.
+    current_user: Option<String>,
 }
 
 impl DfTableSourceProvider {
@@ -46,6 +49,9 @@ impl DfTableSourceProvider {
             resolved_tables: HashMap::new(),
             default_catalog: query_ctx.current_catalog().to_owned(),
             default_schema: query_ctx.current_schema().to_owned(),
+            current_user: query_ctx
+                .current_user()
+                .map(|user| user.username().to_string()),
         }
     }
 
@@ -96,6 +102,12 @@ impl DfTableSourceProvider {
             .with_context(|| TableNotExistSnafu {
                 table: format_full_table_name(catalog_name, schema_name, table_name),
             })?;
+        let table = InformationSchemaProvider::table_for_viewer(
+            &self.catalog_manager,
+            catalog_name,
+            table,
+            self.current_user.as_deref(),
+        );
 
         let provider = DfTableProviderAdapter::new(table);
         let source = provider_as_source(Arc::new(provider));
@@ -19,7 +19,7 @@ use api::v1::query_request::Query;
 use api::v1::{
     AlterExpr, AuthHeader, CreateTableExpr, DdlRequest, DeleteRequests, DropTableExpr,
     GreptimeRequest, InsertRequests, PromRangeQuery, QueryRequest, RequestHeader,
-    RowInsertRequests, TruncateTableExpr,
+    RowDeleteRequests, RowInsertRequests, TruncateTableExpr,
 };
 use arrow_flight::Ticket;
 use async_stream::stream;
@@ -33,8 +33,12 @@ use common_telemetry::tracing_context::W3cTrace;
 use futures_util::StreamExt;
 use prost::Message;
 use snafu::{ensure, ResultExt};
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
 
-use crate::error::{ConvertFlightDataSnafu, Error, IllegalFlightMessagesSnafu, ServerSnafu};
+use crate::error::{
+    ConvertFlightDataSnafu, Error, IllegalFlightMessagesSnafu, IllegalGrpcClientStateSnafu,
+    ServerSnafu,
+};
 use crate::{error, from_grpc_response, metrics, Client, Result, StreamInserter};
 
 pub const DEFAULT_LOOKBACK_STRING: &str = "5m";
@@ -126,6 +130,21 @@ impl Database {
         });
     }
 
+    /// Sets a gRPC metadata header sent along with write requests, e.g. the insert mode.
+    pub fn set_header(&mut self, key: &'static str, value: &str) -> Result<()> {
+        let value = MetadataValue::try_from(value).map_err(|e| {
+            IllegalGrpcClientStateSnafu {
+                err_msg: format!("invalid value of header {key}: {e}"),
+            }
+            .build()
+        })?;
+        let _ = self
+            .ctx
+            .headers
+            .insert(MetadataKey::from_static(key), value);
+        Ok(())
+    }
+
     pub async fn insert(&self, requests: InsertRequests) -> Result<u32> {
         let _timer = metrics::METRIC_GRPC_INSERT.start_timer();
         self.handle(Request::Inserts(requests)).await
@@ -161,9 +180,15 @@ impl Database {
         self.handle(Request::Deletes(request)).await
     }
 
+    pub async fn row_delete(&self, requests: RowDeleteRequests) -> Result<u32> {
+        let _timer = metrics::METRIC_GRPC_DELETE.start_timer();
+        self.handle(Request::RowDeletes(requests)).await
+    }
+
     async fn handle(&self, request: Request) -> Result<u32> {
         let mut client = self.client.make_database_client()?.inner;
-        let request = self.to_rpc_request(request);
+        let mut request = tonic::Request::new(self.to_rpc_request(request));
+        *request.metadata_mut() = self.ctx.headers.clone();
         let response = client.handle(request).await?.into_inner();
         from_grpc_response(response)
     }
@@ -352,6 +377,7 @@ impl Database {
 #[derive(Default, Debug, Clone)]
 pub struct FlightContext {
     auth_header: Option<AuthHeader>,
+    headers: MetadataMap,
 }
 
 #[cfg(test)]
@@ -45,6 +45,10 @@ enum ExportTarget {
     CreateTable,
     /// Corresponding to `EXPORT TABLE`
     TableData,
+    /// Corresponding to `SHOW CREATE DATABASE ... FULL`
+    Schema,
+    /// Corresponding to `SHOW CREATE USERS`, requires an admin
+    Users,
 }
 
 #[derive(Debug, Default, Parser)]
@@ -307,6 +311,109 @@ impl Export {
         Ok(())
     }
 
+    async fn show_create_database_full(&self, catalog: &str, schema: &str) -> Result<String> {
+        let sql = format!(r#"show create database "{}" full"#, schema);
+        let mut client = self.client.clone();
+        client.set_catalog(catalog);
+        client.set_schema(schema);
+        let result = client
+            .sql(&sql)
+            .await
+            .with_context(|_| RequestDatabaseSnafu { sql })?;
+        let OutputData::Stream(stream) = result.data else {
+            NotDataFromOutputSnafu.fail()?
+        };
+        let record_batch = collect(stream)
+            .await
+            .context(CollectRecordBatchesSnafu)?
+            .pop()
+            .context(EmptyResultSnafu)?;
+        let script = record_batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringVector>()
+            .unwrap()
+            .get_data(0)
+            .unwrap();
+
+        Ok(script.to_string())
+    }
+
+    async fn export_users(&self) -> Result<()> {
+        let sql = "show create users".to_string();
+        let result = self
+            .client
+            .sql(&sql)
+            .await
+            .with_context(|_| RequestDatabaseSnafu { sql })?;
+        let OutputData::Stream(stream) = result.data else {
+            NotDataFromOutputSnafu.fail()?
+        };
+        let record_batch = collect(stream)
+            .await
+            .context(CollectRecordBatchesSnafu)?
+            .pop()
+            .context(EmptyResultSnafu)?;
+        let script = record_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringVector>()
+            .unwrap()
+            .get_data(0)
+            .unwrap();
+
+        tokio::fs::create_dir_all(&self.output_dir)
+            .await
+            .context(FileIoSnafu)?;
+        let output_file = Path::new(&self.output_dir).join("users.sql");
+        tokio::fs::write(output_file, script)
+            .await
+            .context(FileIoSnafu)?;
+        info!("finished exporting users");
+
+        Ok(())
+    }
+
+    async fn export_schema(&self) -> Result<()> {
+        let semaphore = Arc::new(Semaphore::new(self.parallelism));
+        let db_names = self.iter_db_names().await?;
+        let db_count = db_names.len();
+        let mut tasks = Vec::with_capacity(db_names.len());
+        for (catalog, schema) in db_names {
+            let semaphore_moved = semaphore.clone();
+            tasks.push(async move {
+                let _permit = semaphore_moved.acquire().await.unwrap();
+                let script = self.show_create_database_full(&catalog, &schema).await?;
+                tokio::fs::create_dir_all(&self.output_dir)
+                    .await
+                    .context(FileIoSnafu)?;
+                let output_file =
+                    Path::new(&self.output_dir).join(format!("{catalog}-{schema}.schema.sql"));
+                tokio::fs::write(output_file, script)
+                    .await
+                    .context(FileIoSnafu)?;
+                info!("finished exporting schema of {catalog}.{schema}");
+                Ok::<(), Error>(())
+            });
+        }
+
+        let success = futures::future::join_all(tasks)
+            .await
+            .into_iter()
+            .filter(|r| match r {
+                Ok(_) => true,
+                Err(e) => {
+                    error!(e; "export job failed");
+                    false
+                }
+            })
+            .count();
+
+        info!("success {success}/{db_count} jobs");
+
+        Ok(())
+    }
+
     async fn export_table_data(&self) -> Result<()> {
         let semaphore = Arc::new(Semaphore::new(self.parallelism));
         let db_names = self.iter_db_names().await?;
@@ -403,6 +510,8 @@ impl Tool for Export {
         match self.target {
             ExportTarget::CreateTable => self.export_create_table().await,
             ExportTarget::TableData => self.export_table_data().await,
+            ExportTarget::Schema => self.export_schema().await,
+            ExportTarget::Users => self.export_users().await,
         }
     }
 }
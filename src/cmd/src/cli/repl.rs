@@ -256,8 +256,13 @@ async fn create_query_engine(meta_addr: &str) -> Result<DatafusionQueryEngine> {
     let multi_cache_invalidator = Arc::new(MultiCacheInvalidator::with_invalidators(vec![
         cached_meta_backend.clone(),
     ]));
-    let catalog_list =
-        KvBackendCatalogManager::new(cached_meta_backend.clone(), multi_cache_invalidator).await;
+    let catalog_list = KvBackendCatalogManager::new(
+        cached_meta_backend.clone(),
+        multi_cache_invalidator,
+        None,
+        None,
+    )
+    .await;
     let plugins: Plugins = Default::default();
     let state = Arc::new(QueryEngineState::new(
         catalog_list,
@@ -0,0 +1,244 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use clap::Parser;
+use client::api::v1::auth_header::AuthScheme;
+use client::api::v1::Basic;
+use client::{Client, Database, OutputData};
+use common_recordbatch::util::collect;
+use common_telemetry::{info, warn};
+use datatypes::scalars::ScalarVector;
+use datatypes::vectors::{StringVector, Vector};
+use operator::statement::capture::{CaptureBundle, CAPTURE_BUNDLE_VERSION};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::cli::{Instance, Tool};
+use crate::error::{
+    CollectRecordBatchesSnafu, ConnectServerSnafu, EmptyResultSnafu, FileIoSnafu,
+    IllegalConfigSnafu, NotDataFromOutputSnafu, ReplayMismatchSnafu, RequestDatabaseSnafu, Result,
+    SerdeJsonSnafu,
+};
+
+/// Replays a bundle returned by `EXPLAIN CAPTURE`.
+///
+/// It runs the captured query with `EXPLAIN CAPTURE` again under the captured
+/// session settings, and fails if the result differs from the capture. It reads
+/// the tables as they are now, so a different result may come from rows written
+/// since the capture, see [CaptureBundle].
+#[derive(Debug, Default, Parser)]
+pub struct ReplayCommand {
+    /// Server address to connect
+    #[clap(long)]
+    addr: String,
+
+    /// Path to the file holding the bundle.
+    #[clap(long)]
+    bundle: String,
+
+    /// basic authentication for connecting to the server
+    #[clap(long)]
+    auth_basic: Option<String>,
+}
+
+impl ReplayCommand {
+    pub async fn build(&self) -> Result<Instance> {
+        let bundle = tokio::fs::read(&self.bundle).await.context(FileIoSnafu)?;
+        let bundle: CaptureBundle = serde_json::from_slice(&bundle).context(SerdeJsonSnafu)?;
+        ensure!(
+            bundle.version == CAPTURE_BUNDLE_VERSION,
+            IllegalConfigSnafu {
+                msg: format!("unsupported bundle version {}", bundle.version),
+            }
+        );
+
+        let client = Client::with_urls([self.addr.clone()]);
+        client
+            .health_check()
+            .await
+            .with_context(|_| ConnectServerSnafu {
+                addr: self.addr.clone(),
+            })?;
+        let mut database_client = Database::new(
+            bundle.session.catalog.clone(),
+            bundle.session.schema.clone(),
+            client,
+        );
+        database_client.set_timezone(bundle.session.timezone.clone());
+
+        if let Some(auth_basic) = &self.auth_basic {
+            let (username, password) = auth_basic.split_once(':').context(IllegalConfigSnafu {
+                msg: "auth_basic cannot be split by ':'".to_string(),
+            })?;
+            database_client.set_auth(AuthScheme::Basic(Basic {
+                username: username.to_string(),
+                password: password.to_string(),
+            }));
+        }
+
+        Ok(Instance::new(Box::new(Replay {
+            client: database_client,
+            bundle,
+        })))
+    }
+}
+
+pub struct Replay {
+    client: Database,
+    bundle: CaptureBundle,
+}
+
+impl Replay {
+    /// Captures the query of the bundle again.
+    async fn recapture(&self) -> Result<CaptureBundle> {
+        let sql = format!("EXPLAIN CAPTURE {}", self.bundle.sql);
+        let result = self
+            .client
+            .sql(&sql)
+            .await
+            .with_context(|_| RequestDatabaseSnafu { sql })?;
+        let record_batch = match result.data {
+            OutputData::Stream(stream) => collect(stream)
+                .await
+                .context(CollectRecordBatchesSnafu)?
+                .pop(),
+            OutputData::RecordBatches(batches) => batches.take().pop(),
+            OutputData::AffectedRows(_) => NotDataFromOutputSnafu.fail()?,
+        }
+        .context(EmptyResultSnafu)?;
+        let bundle = record_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringVector>()
+            .context(NotDataFromOutputSnafu)?
+            .get_data(0)
+            .context(EmptyResultSnafu)?;
+
+        serde_json::from_str(bundle).context(SerdeJsonSnafu)
+    }
+}
+
+#[async_trait]
+impl Tool for Replay {
+    async fn do_work(&self) -> Result<()> {
+        let replayed = self.recapture().await?;
+        for difference in plan_differences(&self.bundle, &replayed) {
+            warn!("Replayed query differs from the capture: {difference}");
+        }
+
+        let (captured, result) = (&self.bundle.result, &replayed.result);
+        ensure!(
+            captured == result,
+            ReplayMismatchSnafu {
+                reason: format!(
+                    "captured {} rows with digest {}, but got {} rows with digest {}",
+                    captured.rows, captured.digest, result.rows, result.digest
+                ),
+            }
+        );
+        info!(
+            "Replayed query returns the captured result, rows: {}, digest: {}",
+            result.rows, result.digest
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns differences of the tables and the plan between the `captured` and the
+/// `replayed` bundles, which explain a different result.
+fn plan_differences(captured: &CaptureBundle, replayed: &CaptureBundle) -> Vec<String> {
+    let mut differences = Vec::new();
+    if captured.session.type_coercion != replayed.session.type_coercion {
+        differences.push(format!(
+            "type coercion mode {} was {}",
+            replayed.session.type_coercion, captured.session.type_coercion
+        ));
+    }
+    for table in &captured.tables {
+        match replayed.tables.iter().find(|t| t.name == table.name) {
+            Some(t) if t.table_id != table.table_id => differences.push(format!(
+                "table {} has id {}, but was {}",
+                table.name, t.table_id, table.table_id
+            )),
+            Some(t) if t.table_version != table.table_version => differences.push(format!(
+                "table {} is at version {}, but was {}",
+                table.name, t.table_version, table.table_version
+            )),
+            Some(_) => {}
+            None => differences.push(format!("table {} is not scanned", table.name)),
+        }
+    }
+    if captured.plan != replayed.plan {
+        differences.push(format!(
+            "plan is\n{}\nbut was\n{}",
+            replayed.plan, captured.plan
+        ));
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use operator::statement::capture::{CaptureResult, CaptureSession, CaptureTable};
+
+    use super::*;
+
+    fn new_bundle(table_version: u64, plan: &str) -> CaptureBundle {
+        CaptureBundle {
+            version: CAPTURE_BUNDLE_VERSION,
+            captured_at: 0,
+            sql: "SELECT * FROM foo".to_string(),
+            session: CaptureSession {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                timezone: "UTC".to_string(),
+                type_coercion: "default".to_string(),
+            },
+            plan: plan.to_string(),
+            tables: vec![CaptureTable {
+                name: "greptime.public.foo".to_string(),
+                table_id: 1024,
+                table_version,
+                regions: vec![],
+            }],
+            result: CaptureResult {
+                rows: 0,
+                digest: String::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_plan_differences() {
+        let captured = new_bundle(1, "TableScan: foo");
+        assert!(plan_differences(&captured, &captured).is_empty());
+
+        let replayed = new_bundle(2, "TableScan: foo");
+        assert_eq!(
+            vec!["table greptime.public.foo is at version 2, but was 1"],
+            plan_differences(&captured, &replayed)
+        );
+
+        let mut replayed = new_bundle(1, "Limit: 1\n  TableScan: foo");
+        replayed.tables.clear();
+        assert_eq!(
+            vec![
+                "table greptime.public.foo is not scanned".to_string(),
+                "plan is\nLimit: 1\n  TableScan: foo\nbut was\nTableScan: foo".to_string(),
+            ],
+            plan_differences(&captured, &replayed)
+        );
+    }
+}
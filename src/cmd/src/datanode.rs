@@ -237,7 +237,7 @@ impl StartCommand {
 
         let services = DatanodeServiceBuilder::new(&opts)
             .with_default_grpc_server(&datanode.region_server())
-            .enable_http_service()
+            .enable_http_service(&datanode.region_server())
             .build()
             .await
             .context(StartDatanodeSnafu)?;
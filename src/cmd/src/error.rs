@@ -237,6 +237,9 @@ pub enum Error {
         database: String,
     },
 
+    #[snafu(display("Replayed query differs from the capture: {}", reason))]
+    ReplayMismatch { reason: String, location: Location },
+
     #[snafu(display("Failed to create directory {}", dir))]
     CreateDir {
         dir: String,
@@ -301,7 +304,9 @@ impl ErrorExt for Error {
             Error::SubstraitEncodeLogicalPlan { source, .. } => source.status_code(),
             Error::StartCatalogManager { source, .. } => source.status_code(),
 
-            Error::SerdeJson { .. } | Error::FileIo { .. } => StatusCode::Unexpected,
+            Error::SerdeJson { .. } | Error::FileIo { .. } | Error::ReplayMismatch { .. } => {
+                StatusCode::Unexpected
+            }
 
             Error::Other { source, .. } => source.status_code(),
 
@@ -16,10 +16,14 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use catalog::kvbackend::{CachedMetaKvBackendBuilder, KvBackendCatalogManager};
+use auth::UserProviderRef;
+use catalog::information_extension::DistributedInformationExtension;
+use catalog::kvbackend::{CachedMetaKvBackendBuilder, KvBackendCatalogManager, MetaKvBackend};
+use catalog::process_manager::ProcessManager;
 use clap::Parser;
 use client::client_manager::DatanodeClients;
 use common_meta::cache_invalidator::MultiCacheInvalidator;
+use common_meta::ddl::ProcedureExecutorRef;
 use common_meta::heartbeat::handler::parse_mailbox_message::ParseMailboxMessageHandler;
 use common_meta::heartbeat::handler::HandlerGroupExecutor;
 use common_telemetry::logging;
@@ -30,12 +34,16 @@ use frontend::heartbeat::HeartbeatTask;
 use frontend::instance::builder::FrontendBuilder;
 use frontend::instance::{FrontendInstance, Instance as FeInstance};
 use frontend::server::Services;
+use frontend::user_provider::MetaUserProvider;
+use meta_client::client::QueuedProcedureExecutor;
 use meta_client::MetaClientOptions;
 use servers::tls::{TlsMode, TlsOption};
 use servers::Mode;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 
-use crate::error::{self, InitTimezoneSnafu, MissingConfigSnafu, Result, StartFrontendSnafu};
+use crate::error::{
+    self, IllegalConfigSnafu, InitTimezoneSnafu, MissingConfigSnafu, Result, StartFrontendSnafu,
+};
 use crate::options::{CliOptions, Options};
 use crate::App;
 
@@ -229,6 +237,13 @@ impl StartCommand {
         logging::info!("Frontend options: {:#?}", opts);
 
         set_default_timezone(opts.default_timezone.as_deref()).context(InitTimezoneSnafu)?;
+        // Writes through other frontends don't invalidate the cached results.
+        ensure!(
+            !opts.query_cache.enable,
+            IllegalConfigSnafu {
+                msg: "'query_cache' is only supported in standalone mode",
+            }
+        );
 
         let meta_client_options = opts.meta_client.as_ref().context(MissingConfigSnafu {
             msg: "'meta_client'",
@@ -242,18 +257,62 @@ impl StartCommand {
             .await
             .context(StartFrontendSnafu)?;
 
-        let cached_meta_backend = CachedMetaKvBackendBuilder::new(meta_client.clone())
+        let mut builder = CachedMetaKvBackendBuilder::new(meta_client.clone())
             .cache_max_capacity(cache_max_capacity)
             .cache_ttl(cache_ttl)
-            .cache_tti(cache_tti)
-            .build();
+            .cache_tti(cache_tti);
+        // With a local metadata snapshot, the frontend can start in degraded mode
+        // while metasrv is unavailable.
+        let degraded_start = meta_client_options.metadata_snapshot_dir.is_some();
+        let procedure_executor: ProcedureExecutorRef =
+            match &meta_client_options.metadata_snapshot_dir {
+                Some(dir) => {
+                    let snapshot = FeInstance::open_metadata_snapshot(dir.clone())
+                        .context(StartFrontendSnafu)?;
+                    builder = builder.snapshot(
+                        snapshot,
+                        meta_client_options.metadata_snapshot_sync_interval,
+                        meta_client_options.metadata_snapshot_max_staleness,
+                    );
+                    Arc::new(QueuedProcedureExecutor::new(
+                        meta_client.clone(),
+                        meta_client_options.ddl_queue_timeout,
+                    ))
+                }
+                None => meta_client.clone(),
+            };
+        let cached_meta_backend = builder.build();
         let cached_meta_backend = Arc::new(cached_meta_backend);
         let multi_cache_invalidator = Arc::new(MultiCacheInvalidator::with_invalidators(vec![
             cached_meta_backend.clone(),
         ]));
+        if let Some(user_provider) = MetaUserProvider::try_from_option(
+            opts.user_provider.as_deref(),
+            cached_meta_backend.clone(),
+        )
+        .await
+        .context(StartFrontendSnafu)?
+        {
+            multi_cache_invalidator
+                .add_invalidator(user_provider.clone())
+                .await;
+            plugins.insert::<UserProviderRef>(user_provider);
+        }
+        let information_extension =
+            Arc::new(DistributedInformationExtension::new(meta_client.clone()));
+        // Shares running queries with other frontends through metasrv.
+        let process_manager = ProcessManager::with_registry(
+            opts.grpc.addr.clone(),
+            Arc::new(MetaKvBackend {
+                client: meta_client.clone(),
+            }),
+            opts.slow_query.clone(),
+        );
         let catalog_manager = KvBackendCatalogManager::new(
             cached_meta_backend.clone(),
             multi_cache_invalidator.clone(),
+            Some(information_extension),
+            Some(process_manager),
         )
         .await;
 
@@ -268,22 +327,29 @@ impl StartCommand {
             meta_client.clone(),
             opts.heartbeat.clone(),
             Arc::new(executor),
-        );
+        )
+        .with_degraded_start(degraded_start);
 
         let mut instance = FrontendBuilder::new(
             cached_meta_backend.clone(),
             catalog_manager,
             Arc::new(DatanodeClients::default()),
-            meta_client,
+            procedure_executor,
         )
         .with_plugin(plugins.clone())
         .with_cache_invalidator(multi_cache_invalidator)
         .with_heartbeat_task(heartbeat_task)
+        .with_audit_log(opts.audit_log.clone())
         .try_build()
         .await
         .context(StartFrontendSnafu)?;
 
+        let privilege_manager = instance
+            .table_metadata_manager()
+            .privilege_manager()
+            .clone();
         let servers = Services::new(opts.clone(), Arc::new(instance.clone()), plugins)
+            .with_privilege_manager(privilege_manager)
             .build()
             .await
             .context(StartFrontendSnafu)?;
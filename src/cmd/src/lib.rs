@@ -52,9 +52,9 @@ pub async fn start_app(mut app: Box<dyn App>) -> error::Result<()> {
 
     app.start().await?;
 
-    if let Err(e) = tokio::signal::ctrl_c().await {
-        error!("Failed to listen for ctrl-c signal: {}", e);
-        // It's unusual to fail to listen for ctrl-c signal, maybe there's something unexpected in
+    if let Err(e) = wait_for_shutdown_signal().await {
+        error!("Failed to listen for shutdown signal: {}", e);
+        // It's unusual to fail to listen for shutdown signal, maybe there's something unexpected in
         // the underlying system. So we stop the app instead of running nonetheless to let people
         // investigate the issue.
     }
@@ -64,6 +64,29 @@ pub async fn start_app(mut app: Box<dyn App>) -> error::Result<()> {
     Ok(())
 }
 
+/// Waits for ctrl-c, or SIGTERM on unix, which is sent by orchestrators like Kubernetes
+/// during rolling upgrades.
+async fn wait_for_shutdown_signal() -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = sigterm.recv() => {
+                info!("Received SIGTERM");
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await
+    }
+}
+
 pub fn log_versions() {
     // Report app version as gauge.
     APP_VERSION
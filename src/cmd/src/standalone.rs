@@ -16,7 +16,10 @@ use std::sync::Arc;
 use std::{fs, path};
 
 use async_trait::async_trait;
+use auth::UserProviderRef;
 use catalog::kvbackend::KvBackendCatalogManager;
+use catalog::process_manager::ProcessManager;
+use catalog::slow_queries::SlowQueryOptions;
 use clap::Parser;
 use common_catalog::consts::MIN_USER_TABLE_ID;
 use common_config::{metadata_store_dir, KvBackendConfig};
@@ -35,17 +38,28 @@ use common_telemetry::info;
 use common_telemetry::logging::LoggingOptions;
 use common_time::timezone::set_default_timezone;
 use common_wal::config::StandaloneWalConfig;
-use datanode::config::{DatanodeOptions, ProcedureConfig, RegionEngineConfig, StorageConfig};
+use datanode::config::{
+    DatanodeOptions, ProcedureConfig, RegionEngineConfig, ReplicationOptions, StorageConfig,
+};
 use datanode::datanode::{Datanode, DatanodeBuilder};
+use datanode::service::sst_upgrade_router;
 use file_engine::config::EngineConfig as FileEngineConfig;
+use frontend::audit::AuditLogOptions;
 use frontend::frontend::FrontendOptions;
 use frontend::instance::builder::FrontendBuilder;
-use frontend::instance::{FrontendInstance, Instance as FeInstance, StandaloneDatanodeManager};
+use frontend::instance::{
+    FrontendInstance, Instance as FeInstance, StandaloneDatanodeManager,
+    StandaloneInformationExtension,
+};
 use frontend::server::Services;
 use frontend::service_config::{
     GrpcOptions, InfluxdbOptions, MysqlOptions, OpentsdbOptions, PostgresOptions, PromStoreOptions,
+    QueryCacheOptions,
 };
+use frontend::user_provider::MetaUserProvider;
 use mito2::config::MitoConfig;
+use operator::query_cache::QueryCache;
+use query::query_engine::options::QueryEngineOptions;
 use serde::{Deserialize, Serialize};
 use servers::export_metrics::ExportMetricsOption;
 use servers::http::HttpOptions;
@@ -117,7 +131,12 @@ pub struct StandaloneOptions {
     pub user_provider: Option<String>,
     /// Options for different store engines.
     pub region_engine: Vec<RegionEngineConfig>,
+    pub query: QueryEngineOptions,
     pub export_metrics: ExportMetricsOption,
+    pub replication: ReplicationOptions,
+    pub query_cache: QueryCacheOptions,
+    pub audit_log: AuditLogOptions,
+    pub slow_query: SlowQueryOptions,
 }
 
 impl StandaloneOptions {
@@ -145,6 +164,11 @@ impl Default for StandaloneOptions {
             procedure: ProcedureConfig::default(),
             logging: LoggingOptions::default(),
             export_metrics: ExportMetricsOption::default(),
+            replication: ReplicationOptions::default(),
+            query_cache: QueryCacheOptions::default(),
+            audit_log: AuditLogOptions::default(),
+            slow_query: SlowQueryOptions::default(),
+            query: QueryEngineOptions::default(),
             user_provider: None,
             region_engine: vec![
                 RegionEngineConfig::Mito(MitoConfig::default()),
@@ -171,6 +195,10 @@ impl StandaloneOptions {
             user_provider: self.user_provider,
             // Handle the export metrics task run by standalone to frontend for execution
             export_metrics: self.export_metrics,
+            query_cache: self.query_cache,
+            audit_log: self.audit_log,
+            slow_query: self.slow_query,
+            query: self.query,
             ..Default::default()
         }
     }
@@ -182,7 +210,9 @@ impl StandaloneOptions {
             wal: self.wal.into(),
             storage: self.storage,
             region_engine: self.region_engine,
+            query: self.query,
             rpc_addr: self.grpc.addr,
+            replication: self.replication,
             ..Default::default()
         }
     }
@@ -203,6 +233,7 @@ impl App for Instance {
 
     async fn start(&mut self) -> Result<()> {
         self.datanode.start_telemetry();
+        self.datanode.start_replication();
 
         self.procedure_manager
             .start()
@@ -401,15 +432,45 @@ impl StartCommand {
         .context(StartFrontendSnafu)?;
 
         let multi_cache_invalidator = Arc::new(MultiCacheInvalidator::default());
-        let catalog_manager =
-            KvBackendCatalogManager::new(kv_backend.clone(), multi_cache_invalidator.clone()).await;
+        if let Some(user_provider) =
+            MetaUserProvider::try_from_option(fe_opts.user_provider.as_deref(), kv_backend.clone())
+                .await
+                .context(StartFrontendSnafu)?
+        {
+            multi_cache_invalidator
+                .add_invalidator(user_provider.clone())
+                .await;
+            fe_plugins.insert::<UserProviderRef>(user_provider);
+        }
 
         let builder =
             DatanodeBuilder::new(dn_opts, fe_plugins.clone()).with_kv_backend(kv_backend.clone());
         let datanode = builder.build().await.context(StartDatanodeSnafu)?;
 
+        let information_extension = Arc::new(StandaloneInformationExtension::new(
+            datanode.region_server(),
+            fe_opts.grpc.addr.clone(),
+        ));
+        let catalog_manager = KvBackendCatalogManager::new(
+            kv_backend.clone(),
+            multi_cache_invalidator.clone(),
+            Some(information_extension),
+            Some(Arc::new(ProcessManager::new(fe_opts.slow_query.clone()))),
+        )
+        .await;
+
         let datanode_manager = Arc::new(StandaloneDatanodeManager(datanode.region_server()));
 
+        let query_cache = fe_opts
+            .query_cache
+            .enable
+            .then(|| Arc::new(QueryCache::new(fe_opts.query_cache.clone())));
+        if let Some(query_cache) = &query_cache {
+            multi_cache_invalidator
+                .add_invalidator(query_cache.clone())
+                .await;
+        }
+
         let table_id_sequence = Arc::new(
             SequenceBuilder::new("table_id", kv_backend.clone())
                 .initial(MIN_USER_TABLE_ID as u64)
@@ -433,7 +494,7 @@ impl StartCommand {
             table_metadata_manager,
             procedure_manager.clone(),
             datanode_manager.clone(),
-            multi_cache_invalidator,
+            multi_cache_invalidator.clone(),
             table_meta_allocator,
         )
         .await?;
@@ -445,14 +506,27 @@ impl StartCommand {
             ddl_task_executor,
         )
         .with_plugin(fe_plugins.clone())
+        .with_cache_invalidator(multi_cache_invalidator)
+        .with_query_cache(query_cache)
+        .with_audit_log(fe_opts.audit_log.clone())
         .try_build()
         .await
         .context(StartFrontendSnafu)?;
 
-        let servers = Services::new(fe_opts.clone(), Arc::new(frontend.clone()), fe_plugins)
-            .build()
-            .await
-            .context(StartFrontendSnafu)?;
+        let privilege_manager = frontend
+            .table_metadata_manager()
+            .privilege_manager()
+            .clone();
+        let mut servers = Services::new(fe_opts.clone(), Arc::new(frontend.clone()), fe_plugins)
+            .with_privilege_manager(privilege_manager);
+        if let Some(router) = sst_upgrade_router(&datanode.region_server()) {
+            let http_server_builder = servers
+                .http_server_builder(&fe_opts)
+                .context(StartFrontendSnafu)?
+                .with_extra_router(router);
+            servers = servers.with_http_server_builder(http_server_builder);
+        }
+        let servers = servers.build().await.context(StartFrontendSnafu)?;
         frontend
             .build_servers(fe_opts, servers)
             .context(StartFrontendSnafu)?;
@@ -88,6 +88,20 @@ pub const INFORMATION_SCHEMA_PARTITIONS_TABLE_ID: u32 = 28;
 pub const INFORMATION_SCHEMA_REGION_PEERS_TABLE_ID: u32 = 29;
 /// id for information_schema.columns
 pub const INFORMATION_SCHEMA_TABLE_CONSTRAINTS_TABLE_ID: u32 = 30;
+/// id for information_schema.views
+pub const INFORMATION_SCHEMA_VIEWS_TABLE_ID: u32 = 31;
+/// id for information_schema.ingestion_stats
+pub const INFORMATION_SCHEMA_INGESTION_STATS_TABLE_ID: u32 = 32;
+/// id for information_schema.region_statistics
+pub const INFORMATION_SCHEMA_REGION_STATISTICS_TABLE_ID: u32 = 33;
+/// id for information_schema.cluster_info
+pub const INFORMATION_SCHEMA_CLUSTER_INFO_TABLE_ID: u32 = 34;
+/// id for information_schema.processlist
+pub const INFORMATION_SCHEMA_PROCESSLIST_TABLE_ID: u32 = 35;
+/// id for information_schema.statements_summary
+pub const INFORMATION_SCHEMA_STATEMENTS_SUMMARY_TABLE_ID: u32 = 36;
+/// id for information_schema.slow_queries
+pub const INFORMATION_SCHEMA_SLOW_QUERIES_TABLE_ID: u32 = 37;
 /// ----- End of information_schema tables -----
 
 pub const MITO_ENGINE: &str = "mito";
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+
 use common_base::readable_size::ReadableSize;
-use sysinfo::System;
+use sysinfo::{Disks, System};
 
 /// Get the CPU core number of system, aware of cgroups.
 pub fn get_cpus() -> usize {
@@ -39,6 +41,26 @@ pub fn get_sys_total_memory() -> Option<ReadableSize> {
     }
 }
 
+/// Get the available space of the disk that `path` is on.
+///
+/// Returns `None` if the system is unsupported or no disk contains the `path`.
+pub fn get_available_disk_space(path: impl AsRef<Path>) -> Option<ReadableSize> {
+    if !sysinfo::IS_SUPPORTED_SYSTEM {
+        return None;
+    }
+
+    let path = path.as_ref();
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+    // Finds the disk with the longest mount point that contains the path.
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| ReadableSize(disk.available_space()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +74,10 @@ mod tests {
     fn test_get_sys_total_memory() {
         assert!(get_sys_total_memory().unwrap() > ReadableSize::mb(0));
     }
+
+    #[test]
+    fn test_get_available_disk_space() {
+        // A path that doesn't exist can't be resolved to a disk.
+        assert!(get_available_disk_space("not/exist/path").is_none());
+    }
 }
@@ -29,6 +29,7 @@ pub struct Lister {
     source: Source,
     root: String,
     regex: Option<Regex>,
+    recursive: bool,
 }
 
 impl Lister {
@@ -43,15 +44,23 @@ impl Lister {
             source,
             root,
             regex,
+            recursive: false,
         }
     }
 
+    /// Lists files in sub directories, e.g. partitions in hive style layout.
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
     pub async fn list(&self) -> Result<Vec<Entry>> {
         match &self.source {
             Source::Dir => {
                 let streamer = self
                     .object_store
                     .lister_with("/")
+                    .recursive(self.recursive)
                     .await
                     .context(error::ListObjectsSnafu { path: &self.root })?;
 
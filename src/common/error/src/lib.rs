@@ -19,9 +19,46 @@ pub mod format;
 pub mod mock;
 pub mod status_code;
 
+use std::time::Duration;
+
 pub use snafu;
 
 // HACK - these headers are here for shared in gRPC services. For common HTTP headers,
 // please define in `src/servers/src/http/header.rs`.
 pub const GREPTIME_DB_HEADER_ERROR_CODE: &str = "x-greptime-err-code";
 pub const GREPTIME_DB_HEADER_ERROR_MSG: &str = "x-greptime-err-msg";
+
+const RETRY_AFTER_PREFIX: &str = "retry after ";
+
+/// Formats the time to retry a rate limited request in an error message.
+///
+/// Errors only pass their messages between nodes, so servers find the time to
+/// retry by [parse_retry_after].
+pub fn format_retry_after(retry_after: Duration) -> String {
+    format!("{RETRY_AFTER_PREFIX}{}ms", retry_after.as_millis())
+}
+
+/// Parses the time to retry formatted by [format_retry_after] from an error message.
+pub fn parse_retry_after(msg: &str) -> Option<Duration> {
+    let (_, rest) = msg.split_once(RETRY_AFTER_PREFIX)?;
+    let (millis, _) = rest.split_once("ms")?;
+    millis.parse().ok().map(Duration::from_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after() {
+        let msg = format!(
+            "Write requests exceed the rate limit, {}",
+            format_retry_after(Duration::from_millis(1500))
+        );
+        assert_eq!(Some(Duration::from_millis(1500)), parse_retry_after(&msg));
+        assert_eq!(
+            None,
+            parse_retry_after("Write requests exceed the rate limit")
+        );
+    }
+}
@@ -78,6 +78,9 @@ pub enum StatusCode {
 
     /// Rate limit exceeded
     RateLimited = 6001,
+
+    /// Quota of the database exceeded
+    QuotaExceeded = 6002,
     // ====== End of server related status code =======
 
     // ====== Begin of auth related status code =====
@@ -131,6 +134,7 @@ impl StatusCode {
             | StatusCode::TableColumnExists
             | StatusCode::DatabaseNotFound
             | StatusCode::RateLimited
+            | StatusCode::QuotaExceeded
             | StatusCode::UserNotFound
             | StatusCode::UnsupportedPasswordType
             | StatusCode::UserPasswordMismatch
@@ -169,6 +173,7 @@ impl StatusCode {
             | StatusCode::TableColumnExists
             | StatusCode::DatabaseNotFound
             | StatusCode::RateLimited
+            | StatusCode::QuotaExceeded
             | StatusCode::UserNotFound
             | StatusCode::UnsupportedPasswordType
             | StatusCode::UserPasswordMismatch
@@ -20,11 +20,15 @@ use once_cell::sync::Lazy;
 
 use crate::function::FunctionRef;
 use crate::scalars::aggregate::{AggregateFunctionMetaRef, AggregateFunctions};
+use crate::scalars::array::ArrayFunction;
+use crate::scalars::binary::BinaryFunction;
 use crate::scalars::date::DateFunction;
 use crate::scalars::expression::ExpressionFunction;
+use crate::scalars::json::JsonFunction;
 use crate::scalars::math::MathFunction;
 use crate::scalars::numpy::NumpyFunction;
 use crate::scalars::timestamp::TimestampFunction;
+use crate::scalars::trace::TraceFunction;
 use crate::system::SystemFunction;
 use crate::table::TableFunction;
 
@@ -82,6 +86,10 @@ pub static FUNCTION_REGISTRY: Lazy<Arc<FunctionRegistry>> = Lazy::new(|| {
     TimestampFunction::register(&function_registry);
     DateFunction::register(&function_registry);
     ExpressionFunction::register(&function_registry);
+    BinaryFunction::register(&function_registry);
+    ArrayFunction::register(&function_registry);
+    JsonFunction::register(&function_registry);
+    TraceFunction::register(&function_registry);
 
     // Aggregate functions
     AggregateFunctions::register(&function_registry);
@@ -17,9 +17,11 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use common_base::AffectedRows;
 use common_meta::rpc::procedure::{MigrateRegionRequest, ProcedureStateResponse};
+use common_meta::table_name::TableName;
 use common_query::error::Result;
 use common_query::Output;
 use session::context::QueryContextRef;
+use store_api::region_request::CompactOptions;
 use store_api::storage::RegionId;
 use table::requests::{CompactTableRequest, DeleteRequest, FlushTableRequest, InsertRequest};
 
@@ -51,8 +53,12 @@ pub trait TableMutationHandler: Send + Sync {
     async fn compact_region(
         &self,
         region_id: RegionId,
+        compact_options: CompactOptions,
         ctx: QueryContextRef,
     ) -> Result<AffectedRows>;
+
+    /// Invalidates the cached metadata and routes of the table in this node.
+    async fn refresh_table_cache(&self, table_name: TableName, ctx: QueryContextRef) -> Result<()>;
 }
 
 /// A trait for handling procedure service requests in `QueryEngine`.
@@ -13,11 +13,15 @@
 // limitations under the License.
 
 pub mod aggregate;
+pub(crate) mod array;
+pub(crate) mod binary;
 pub(crate) mod date;
 pub mod expression;
+pub(crate) mod json;
 pub mod math;
 pub mod numpy;
 #[cfg(test)]
 pub(crate) mod test;
 pub(crate) mod timestamp;
+pub(crate) mod trace;
 pub mod udf;
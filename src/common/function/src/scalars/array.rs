@@ -0,0 +1,33 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+mod array_contains;
+
+use array_contains::ArrayContainsFunction;
+
+use crate::function_registry::FunctionRegistry;
+
+/// Functions over lists.
+///
+/// Lists only come from queries, e.g. `array_agg`. Tables can't store LIST columns
+/// because the gRPC column types have no list type, and there are no lambda
+/// functions over lists because the SQL parser has no lambda syntax.
+pub(crate) struct ArrayFunction;
+
+impl ArrayFunction {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register(Arc::new(ArrayContainsFunction));
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result, UnsupportedInputDataTypeSnafu};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::types::cast::CastOption;
+use datatypes::types::cast_with_opt;
+use datatypes::value::Value;
+use datatypes::vectors::{BooleanVector, VectorRef};
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+
+/// A function to check whether a list contains the given element.
+/// The element is casted to the item type of the list before comparing.
+/// Returns `NULL` if the list or the element is `NULL`.
+#[derive(Clone, Debug, Default)]
+pub struct ArrayContainsFunction;
+
+const NAME: &str = "array_contains";
+
+impl Function for ArrayContainsFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::boolean_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::any(2, Volatility::Immutable)
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly two, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let (lists, elements) = (&columns[0], &columns[1]);
+        let ConcreteDataType::List(list_type) = lists.data_type() else {
+            return UnsupportedInputDataTypeSnafu {
+                function: NAME,
+                datatypes: columns.iter().map(|c| c.data_type()).collect::<Vec<_>>(),
+            }
+            .fail();
+        };
+        let cast_option = CastOption { strict: false };
+
+        let result = (0..lists.len())
+            .map(|i| {
+                let Value::List(list) = lists.get(i) else {
+                    return None;
+                };
+                let element =
+                    cast_with_opt(elements.get(i), list_type.item_type(), &cast_option).ok()?;
+                if element.is_null() {
+                    return None;
+                }
+                let items = list.items().as_ref()?;
+                Some(items.contains(&element))
+            })
+            .collect::<Vec<_>>();
+        Ok(Arc::new(BooleanVector::from(result)))
+    }
+}
+
+impl fmt::Display for ArrayContainsFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ARRAY_CONTAINS")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::ListValue;
+    use datatypes::vectors::{Int32Vector, ListVectorBuilder, MutableVector};
+
+    use super::*;
+
+    #[test]
+    fn test_array_contains() {
+        let f = ArrayContainsFunction;
+        assert_eq!("array_contains", f.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        let mut builder =
+            ListVectorBuilder::with_type_capacity(ConcreteDataType::int32_datatype(), 4);
+        builder.push_value_ref(
+            Value::List(ListValue::new(
+                Some(Box::new(vec![Value::Int32(1), Value::Int32(2)])),
+                ConcreteDataType::int32_datatype(),
+            ))
+            .as_value_ref(),
+        );
+        builder.push_value_ref(
+            Value::List(ListValue::new(
+                Some(Box::new(vec![Value::Int32(3)])),
+                ConcreteDataType::int32_datatype(),
+            ))
+            .as_value_ref(),
+        );
+        builder.push_null();
+        builder.push_value_ref(
+            Value::List(ListValue::new(
+                Some(Box::new(vec![Value::Int32(4)])),
+                ConcreteDataType::int32_datatype(),
+            ))
+            .as_value_ref(),
+        );
+
+        let args: Vec<VectorRef> = vec![
+            builder.to_vector(),
+            Arc::new(Int32Vector::from(vec![Some(2), Some(2), Some(1), None])),
+        ];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+        assert_eq!(4, vector.len());
+        assert_eq!(Value::Boolean(true), vector.get(0));
+        assert_eq!(Value::Boolean(false), vector.get(1));
+        assert_eq!(Value::Null, vector.get(2));
+        assert_eq!(Value::Null, vector.get(3));
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+mod from_hex;
+mod hex;
+mod length;
+
+use from_hex::FromHexFunction;
+use hex::HexFunction;
+use length::LengthFunction;
+
+use crate::function_registry::FunctionRegistry;
+
+pub(crate) struct BinaryFunction;
+
+impl BinaryFunction {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register(Arc::new(HexFunction));
+        registry.register(Arc::new(FromHexFunction));
+        registry.register(Arc::new(LengthFunction));
+    }
+}
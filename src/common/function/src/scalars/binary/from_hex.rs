@@ -0,0 +1,115 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result, UnsupportedInputDataTypeSnafu};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::ValueRef;
+use datatypes::vectors::{BinaryVector, VectorRef};
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+
+/// A function to decode hexadecimal strings into binary values,
+/// invalid hexadecimal strings are decoded as `NULL`.
+#[derive(Clone, Debug, Default)]
+pub struct FromHexFunction;
+
+const NAME: &str = "from_hex";
+
+impl Function for FromHexFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::binary_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::uniform(
+            1,
+            vec![ConcreteDataType::string_datatype()],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly one, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let vector = &columns[0];
+        match vector.data_type() {
+            ConcreteDataType::String(_) => {
+                let decoded = (0..vector.len())
+                    .map(|i| match vector.get_ref(i) {
+                        ValueRef::String(s) => hex::decode(s).ok(),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                Ok(Arc::new(BinaryVector::from(decoded)))
+            }
+            _ => UnsupportedInputDataTypeSnafu {
+                function: NAME,
+                datatypes: columns.iter().map(|c| c.data_type()).collect::<Vec<_>>(),
+            }
+            .fail(),
+        }
+    }
+}
+
+impl fmt::Display for FromHexFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FROM_HEX")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+    use datatypes::vectors::StringVector;
+
+    use super::*;
+
+    #[test]
+    fn test_from_hex() {
+        let f = FromHexFunction;
+        assert_eq!("from_hex", f.name());
+        assert_eq!(
+            ConcreteDataType::binary_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        let args: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec![
+            Some("ABcd"),
+            None,
+            Some("xyz"),
+        ]))];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::from(vec![0xab_u8, 0xcd]), vector.get(0));
+        assert_eq!(Value::Null, vector.get(1));
+        assert_eq!(Value::Null, vector.get(2));
+    }
+}
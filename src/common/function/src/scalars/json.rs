@@ -0,0 +1,35 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions on JSON documents stored in string columns.
+//!
+//! There is no STRUCT column type, so nested data such as OTLP attribute bags is
+//! ingested as JSON strings and these functions project fields out of them at query
+//! time. A structured type needs a new data type in the engine and a column type in
+//! the gRPC protocol, and is out of the scope of these functions.
+
+use std::sync::Arc;
+mod json_get;
+
+use json_get::JsonGetFunction;
+
+use crate::function_registry::FunctionRegistry;
+
+pub(crate) struct JsonFunction;
+
+impl JsonFunction {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register(Arc::new(JsonGetFunction));
+    }
+}
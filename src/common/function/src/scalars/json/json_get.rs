@@ -0,0 +1,260 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result, UnsupportedInputDataTypeSnafu};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::ValueRef;
+use datatypes::vectors::{StringVector, VectorRef};
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+
+/// A function to project a field out of a JSON document stored in a string column,
+/// e.g. `json_get(body, 'user.name')` returns the field `name` of the object `user`.
+/// The path is a list of keys (or array indexes) separated by `.`. Keys containing `.`,
+/// such as the flat keys of OTLP attributes, are quoted or put in brackets, e.g.
+/// `json_get(log_attributes, '"http.status_code"')` or
+/// `json_get(log_attributes, '["http.status_code"]')`.
+///
+/// String fields are returned as is, other fields are returned in JSON format.
+/// Returns `NULL` if the document is not valid JSON or the path doesn't exist.
+#[derive(Clone, Debug, Default)]
+pub struct JsonGetFunction;
+
+const NAME: &str = "json_get";
+
+impl Function for JsonGetFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::uniform(
+            2,
+            vec![ConcreteDataType::string_datatype()],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly two, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let (documents, paths) = (&columns[0], &columns[1]);
+        match (documents.data_type(), paths.data_type()) {
+            (ConcreteDataType::String(_), ConcreteDataType::String(_)) => {
+                let result = (0..documents.len())
+                    .map(|i| match (documents.get_ref(i), paths.get_ref(i)) {
+                        (ValueRef::String(document), ValueRef::String(path)) => {
+                            json_get(document, path)
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                Ok(Arc::new(StringVector::from(result)))
+            }
+            _ => UnsupportedInputDataTypeSnafu {
+                function: NAME,
+                datatypes: columns.iter().map(|c| c.data_type()).collect::<Vec<_>>(),
+            }
+            .fail(),
+        }
+    }
+}
+
+fn json_get(document: &str, path: &str) -> Option<String> {
+    let keys = parse_path(path)?;
+    let document: serde_json::Value = serde_json::from_str(document).ok()?;
+    let mut value = &document;
+    for key in &keys {
+        value = match value {
+            serde_json::Value::Object(map) => map.get(key)?,
+            serde_json::Value::Array(array) => array.get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Splits `path` into keys separated by `.`. Keys containing `.`, like the keys of
+/// OTLP attributes, can be double quoted, e.g. `"http.status_code"`, or put in
+/// brackets, e.g. `['http.status_code']`. Brackets also hold array indexes, e.g.
+/// `tags[1]`, and `\` escapes a character in quotes.
+///
+/// Returns `None` if the path is malformed.
+fn parse_path(path: &str) -> Option<Vec<String>> {
+    let mut keys = Vec::new();
+    let mut key = String::new();
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !key.is_empty() {
+                    keys.push(std::mem::take(&mut key));
+                }
+            }
+            '"' if key.is_empty() => {
+                keys.push(parse_quoted(&mut chars, '"')?);
+                // A quoted key must end the segment.
+                if !matches!(chars.peek(), None | Some('.') | Some('[')) {
+                    return None;
+                }
+            }
+            '[' => {
+                if !key.is_empty() {
+                    keys.push(std::mem::take(&mut key));
+                }
+                let key_in_brackets = match chars.peek()? {
+                    quote @ ('\'' | '"') => {
+                        let quote = *quote;
+                        let _ = chars.next();
+                        let key = parse_quoted(&mut chars, quote)?;
+                        (chars.next()? == ']').then_some(key)?
+                    }
+                    _ => {
+                        let mut index = String::new();
+                        loop {
+                            match chars.next()? {
+                                ']' => break,
+                                c => index.push(c),
+                            }
+                        }
+                        index.parse::<usize>().ok()?;
+                        index
+                    }
+                };
+                keys.push(key_in_brackets);
+            }
+            c => key.push(c),
+        }
+    }
+    if !key.is_empty() {
+        keys.push(key);
+    }
+
+    Some(keys)
+}
+
+/// Reads a key until the closing `quote`, the opening one is already consumed.
+fn parse_quoted(chars: &mut impl Iterator<Item = char>, quote: char) -> Option<String> {
+    let mut key = String::new();
+    loop {
+        match chars.next()? {
+            '\\' => key.push(chars.next()?),
+            c if c == quote => return Some(key),
+            c => key.push(c),
+        }
+    }
+}
+
+impl fmt::Display for JsonGetFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JSON_GET")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_json_get() {
+        let f = JsonGetFunction;
+        assert_eq!("json_get", f.name());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        let document = r#"{"host":"h1","http":{"status_code":200,"tags":["a","b"]}}"#;
+        let args: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec![
+                Some(document),
+                Some(document),
+                Some(document),
+                Some(document),
+                Some("not json"),
+                None,
+            ])),
+            Arc::new(StringVector::from(vec![
+                Some("host"),
+                Some("http.status_code"),
+                Some("http.tags.1"),
+                Some("http.method"),
+                Some("host"),
+                Some("host"),
+            ])),
+        ];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+        assert_eq!(6, vector.len());
+        assert_eq!(Value::from("h1"), vector.get(0));
+        assert_eq!(Value::from("200"), vector.get(1));
+        assert_eq!(Value::from("b"), vector.get(2));
+        assert_eq!(Value::Null, vector.get(3));
+        assert_eq!(Value::Null, vector.get(4));
+        assert_eq!(Value::Null, vector.get(5));
+    }
+
+    #[test]
+    fn test_json_get_keys_with_dots() {
+        let document =
+            r#"{"http.status_code":200,"http":{"url.path":"/a","tags":["a","b"]},"a\"b":1}"#;
+        let cases = [
+            (r#""http.status_code""#, Some("200")),
+            ("['http.status_code']", Some("200")),
+            (r#"["http.status_code"]"#, Some("200")),
+            (r#"http."url.path""#, Some("/a")),
+            ("http['url.path']", Some("/a")),
+            ("http.tags[1]", Some("b")),
+            ("http['tags'][0]", Some("a")),
+            (r#""a\"b""#, Some("1")),
+            ("http.status_code", None),
+            // malformed paths
+            (r#""http.status_code"#, None),
+            (r#""http"x"#, None),
+            ("http['url.path'", None),
+            ("http.tags[x]", None),
+            ("http.tags[1", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                expected.map(|s| s.to_string()),
+                json_get(document, path),
+                "path: {path}"
+            );
+        }
+    }
+}
@@ -16,10 +16,12 @@ use std::sync::Arc;
 mod greatest;
 mod to_timezone;
 mod to_unixtime;
+mod window;
 
 use greatest::GreatestFunction;
 use to_timezone::ToTimezoneFunction;
 use to_unixtime::ToUnixtimeFunction;
+use window::{HopFunction, SessionFunction};
 
 use crate::function_registry::FunctionRegistry;
 
@@ -30,5 +32,7 @@ impl TimestampFunction {
         registry.register(Arc::new(ToTimezoneFunction));
         registry.register(Arc::new(ToUnixtimeFunction));
         registry.register(Arc::new(GreatestFunction));
+        registry.register(Arc::new(HopFunction));
+        registry.register(Arc::new(SessionFunction));
     }
 }
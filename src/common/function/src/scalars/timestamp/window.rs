@@ -0,0 +1,97 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::vectors::VectorRef;
+
+use crate::function::{Function, FunctionContext};
+
+/// `hop(ts, slide, size)` in the `GROUP BY` of a flow, groups rows by the sliding
+/// windows they belong to.
+///
+/// It's only registered so queries of flows can be planned, the flow turns it into a
+/// window plan. Durations are strings like `'5m'` or milliseconds.
+#[derive(Clone, Debug, Default)]
+pub struct HopFunction;
+
+/// `session(ts, gap)` in the `GROUP BY` of a flow, groups rows into sessions that end
+/// once there is no row for `gap`.
+///
+/// It's only registered so queries of flows can be planned, the flow turns it into a
+/// window plan. The gap is a string like `'5m'` or milliseconds.
+#[derive(Clone, Debug, Default)]
+pub struct SessionFunction;
+
+const HOP_NAME: &str = "hop";
+const SESSION_NAME: &str = "session";
+
+/// Returns the error of evaluating the window function `name` out of a flow.
+fn eval_out_of_flow(name: &str) -> Result<VectorRef> {
+    InvalidFuncArgsSnafu {
+        err_msg: format!("Window function {name} can only be used in the GROUP BY of a flow"),
+    }
+    .fail()
+}
+
+impl Function for HopFunction {
+    fn name(&self) -> &str {
+        HOP_NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::timestamp_millisecond_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::any(3, Volatility::Immutable)
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, _columns: &[VectorRef]) -> Result<VectorRef> {
+        eval_out_of_flow(HOP_NAME)
+    }
+}
+
+impl fmt::Display for HopFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HOP")
+    }
+}
+
+impl Function for SessionFunction {
+    fn name(&self) -> &str {
+        SESSION_NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::timestamp_millisecond_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::any(2, Volatility::Immutable)
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, _columns: &[VectorRef]) -> Result<VectorRef> {
+        eval_out_of_flow(SESSION_NAME)
+    }
+}
+
+impl fmt::Display for SessionFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SESSION")
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result, UnsupportedInputDataTypeSnafu};
+use common_query::prelude::{Signature, Volatility};
+use common_time::Timestamp;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::ValueRef;
+use datatypes::vectors::{Float64Vector, VectorRef};
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+
+/// A function to compute the duration of a span in milliseconds from its start
+/// and end timestamps, e.g. `trace_duration("start", "end")`.
+///
+/// Returns `NULL` if either timestamp is `NULL`.
+#[derive(Clone, Debug, Default)]
+pub struct TraceDurationFunction;
+
+const NAME: &str = "trace_duration";
+
+impl Function for TraceDurationFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::uniform(
+            2,
+            vec![
+                ConcreteDataType::timestamp_second_datatype(),
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                ConcreteDataType::timestamp_microsecond_datatype(),
+                ConcreteDataType::timestamp_nanosecond_datatype(),
+            ],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly two, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let (starts, ends) = (&columns[0], &columns[1]);
+        match (starts.data_type(), ends.data_type()) {
+            (ConcreteDataType::Timestamp(_), ConcreteDataType::Timestamp(_)) => {
+                let result = (0..starts.len())
+                    .map(|i| match (starts.get_ref(i), ends.get_ref(i)) {
+                        (ValueRef::Timestamp(start), ValueRef::Timestamp(end)) => {
+                            duration_in_millisecond(start, end)
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                Ok(Arc::new(Float64Vector::from(result)))
+            }
+            _ => UnsupportedInputDataTypeSnafu {
+                function: NAME,
+                datatypes: columns.iter().map(|c| c.data_type()).collect::<Vec<_>>(),
+            }
+            .fail(),
+        }
+    }
+}
+
+fn duration_in_millisecond(start: Timestamp, end: Timestamp) -> Option<f64> {
+    let duration = end.sub(&start)?;
+    match duration.num_nanoseconds() {
+        Some(nanos) => Some(nanos as f64 / 1_000_000.0),
+        None => Some(duration.num_milliseconds() as f64),
+    }
+}
+
+impl fmt::Display for TraceDurationFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TRACE_DURATION")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+    use datatypes::vectors::{TimestampMillisecondVector, TimestampNanosecondVector};
+
+    use super::*;
+
+    #[test]
+    fn test_trace_duration() {
+        let f = TraceDurationFunction;
+        assert_eq!("trace_duration", f.name());
+        assert_eq!(
+            ConcreteDataType::float64_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(TimestampNanosecondVector::from(vec![
+                Some(1_000_000_000),
+                Some(1_000_000_000),
+                None,
+            ])),
+            Arc::new(TimestampNanosecondVector::from(vec![
+                Some(1_002_500_000),
+                Some(1_000_000_000),
+                Some(1_000_000_000),
+            ])),
+        ];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::from(2.5), vector.get(0));
+        assert_eq!(Value::from(0.0), vector.get(1));
+        assert_eq!(Value::Null, vector.get(2));
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(TimestampMillisecondVector::from(vec![Some(1000)])),
+            Arc::new(TimestampMillisecondVector::from(vec![Some(4000)])),
+        ];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+        assert_eq!(Value::from(3000.0), vector.get(0));
+    }
+}
@@ -34,9 +34,11 @@ impl FunctionState {
         use async_trait::async_trait;
         use common_base::AffectedRows;
         use common_meta::rpc::procedure::{MigrateRegionRequest, ProcedureStateResponse};
+        use common_meta::table_name::TableName;
         use common_query::error::Result;
         use common_query::Output;
         use session::context::QueryContextRef;
+        use store_api::region_request::CompactOptions;
         use store_api::storage::RegionId;
         use table::requests::{
             CompactTableRequest, DeleteRequest, FlushTableRequest, InsertRequest,
@@ -110,10 +112,19 @@ impl FunctionState {
             async fn compact_region(
                 &self,
                 _region_id: RegionId,
+                _compact_options: CompactOptions,
                 _ctx: QueryContextRef,
             ) -> Result<AffectedRows> {
                 Ok(ROWS)
             }
+
+            async fn refresh_table_cache(
+                &self,
+                _table_name: TableName,
+                _ctx: QueryContextRef,
+            ) -> Result<()> {
+                Ok(())
+            }
         }
 
         Self {
@@ -0,0 +1,118 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result, UnsupportedInputDataTypeSnafu};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::ValueRef;
+use datatypes::vectors::{StringVector, VectorRef};
+use snafu::ensure;
+
+use crate::function::{Function, FunctionContext};
+
+/// A function to compute the digest of SQL statements, which is the same for statements
+/// of the same shape, e.g. statements that only differ in literals.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct DigestFunction;
+
+const NAME: &str = "digest";
+
+impl Function for DigestFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![ConcreteDataType::string_datatype()],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly one, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let vector = &columns[0];
+        match vector.data_type() {
+            ConcreteDataType::String(_) => {
+                let digests = (0..vector.len())
+                    .map(|i| match vector.get_ref(i) {
+                        ValueRef::String(sql) => Some(sql::util::sql_digest(sql)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                Ok(Arc::new(StringVector::from(digests)))
+            }
+            _ => UnsupportedInputDataTypeSnafu {
+                function: NAME,
+                datatypes: columns.iter().map(|c| c.data_type()).collect::<Vec<_>>(),
+            }
+            .fail(),
+        }
+    }
+}
+
+impl fmt::Display for DigestFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DIGEST")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_digest() {
+        let f = DigestFunction;
+        assert_eq!("digest", f.name());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        let args: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec![
+            Some("SELECT * FROM t_table WHERE a_col = 1"),
+            Some("select * from t_table where a_col = 2"),
+            None,
+        ]))];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+        assert_eq!(3, vector.len());
+        assert_eq!(
+            Value::from(sql::util::sql_digest(
+                "SELECT * FROM t_table WHERE a_col = ?"
+            )),
+            vector.get(0)
+        );
+        assert_eq!(vector.get(0), vector.get(1));
+        assert_eq!(Value::Null, vector.get(2));
+    }
+}
@@ -15,12 +15,14 @@
 mod flush_compact_region;
 mod flush_compact_table;
 mod migrate_region;
+mod refresh_table_cache;
 
 use std::sync::Arc;
 
 use flush_compact_region::{CompactRegionFunction, FlushRegionFunction};
 use flush_compact_table::{CompactTableFunction, FlushTableFunction};
 use migrate_region::MigrateRegionFunction;
+use refresh_table_cache::RefreshTableCacheFunction;
 
 use crate::function_registry::FunctionRegistry;
 
@@ -35,5 +37,6 @@ impl TableFunction {
         registry.register(Arc::new(CompactRegionFunction));
         registry.register(Arc::new(FlushTableFunction));
         registry.register(Arc::new(CompactTableFunction));
+        registry.register(Arc::new(RefreshTableCacheFunction));
     }
 }
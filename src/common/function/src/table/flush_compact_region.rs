@@ -19,7 +19,7 @@ use common_query::error::Error::ThreadJoin;
 use common_query::error::{
     InvalidFuncArgsSnafu, MissingTableMutationHandlerSnafu, Result, UnsupportedInputDataTypeSnafu,
 };
-use common_query::prelude::{Signature, Volatility};
+use common_query::prelude::{Signature, TypeSignature, Volatility};
 use common_telemetry::error;
 use datatypes::prelude::*;
 use datatypes::vectors::VectorRef;
@@ -31,6 +31,7 @@ use crate::ensure_greptime;
 use crate::function::{Function, FunctionContext};
 use crate::handlers::TableMutationHandlerRef;
 use crate::helper::cast_u64;
+use crate::table::flush_compact_table::parse_compact_options;
 
 macro_rules! define_region_function {
     ($name: expr, $display_name_str: expr, $display_name: ident) => {
@@ -70,18 +71,81 @@ macro_rules! define_region_function {
 
 define_region_function!("FlushRegionFunction", "flush_region", flush_region);
 
-define_region_function!("CompactRegionFunction", "compact_region", compact_region);
+/// A function to compact region, such as `compact_region(region_id)` or
+/// `compact_region(region_id, 'strict_window', '1h')`.
+#[admin_fn(
+    name = "CompactRegionFunction",
+    display_name = "compact_region",
+    sig_fn = "compact_signature",
+    ret = "uint64"
+)]
+pub(crate) async fn compact_region(
+    table_mutation_handler: &TableMutationHandlerRef,
+    query_ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    ensure!(
+        (1..=3).contains(&params.len()),
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect 1 to 3, have: {}",
+                params.len()
+            ),
+        }
+    );
+
+    let Some(region_id) = cast_u64(&params[0])? else {
+        return UnsupportedInputDataTypeSnafu {
+            function: "compact_region",
+            datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+        }
+        .fail();
+    };
+    let compact_options = parse_compact_options("compact_region", &params[1..])?;
+
+    let affected_rows = table_mutation_handler
+        .compact_region(
+            RegionId::from_u64(region_id),
+            compact_options,
+            query_ctx.clone(),
+        )
+        .await?;
+
+    Ok(Value::from(affected_rows as u64))
+}
 
 fn signature() -> Signature {
     Signature::uniform(1, ConcreteDataType::numerics(), Volatility::Immutable)
 }
 
+fn compact_signature() -> Signature {
+    let string = ConcreteDataType::string_datatype();
+    let mut sigs = vec![
+        // compact_region(region_id)
+        TypeSignature::Uniform(1, ConcreteDataType::numerics()),
+    ];
+    for region_id in ConcreteDataType::numerics() {
+        // compact_region(region_id, strategy)
+        sigs.push(TypeSignature::Exact(vec![
+            region_id.clone(),
+            string.clone(),
+        ]));
+        // compact_region(region_id, strategy, window)
+        sigs.push(TypeSignature::Exact(vec![
+            region_id,
+            string.clone(),
+            string.clone(),
+        ]));
+    }
+
+    Signature::one_of(sigs, Volatility::Immutable)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use common_query::prelude::TypeSignature;
-    use datatypes::vectors::UInt64Vector;
+    use datatypes::vectors::{StringVector, UInt64Vector};
 
     use super::*;
 
@@ -144,5 +208,33 @@ mod tests {
 
     define_region_function_test!(flush_region, FlushRegionFunction);
 
-    define_region_function_test!(compact_region, CompactRegionFunction);
+    #[test]
+    fn test_compact_region() {
+        let f = CompactRegionFunction;
+        assert_eq!("compact_region", f.name());
+        assert!(matches!(f.signature(),
+                         Signature {
+                             type_signature: TypeSignature::OneOf(sigs),
+                             volatility: Volatility::Immutable
+                         } if sigs.len() == 1 + 2 * ConcreteDataType::numerics().len()));
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(UInt64Vector::from_slice([99])),
+            Arc::new(StringVector::from(vec!["strict_window"])),
+            Arc::new(StringVector::from(vec!["1d"])),
+        ];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+        let expect: VectorRef = Arc::new(UInt64Vector::from_slice([42]));
+        assert_eq!(expect, result);
+
+        let result = f.eval(FunctionContext::mock(), &args[..1]).unwrap();
+        assert_eq!(expect, result);
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(UInt64Vector::from_slice([99])),
+            Arc::new(StringVector::from(vec!["size_tiered"])),
+        ];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap_err();
+        assert!(result.to_string().contains("unknown compaction strategy"));
+    }
 }
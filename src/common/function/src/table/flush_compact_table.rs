@@ -21,13 +21,14 @@ use common_query::error::{
     InvalidFuncArgsSnafu, MissingTableMutationHandlerSnafu, Result, TableMutationSnafu,
     UnsupportedInputDataTypeSnafu,
 };
-use common_query::prelude::{Signature, Volatility};
+use common_query::prelude::{Signature, TypeSignature, Volatility};
 use common_telemetry::error;
 use datatypes::prelude::*;
 use datatypes::vectors::VectorRef;
 use session::context::QueryContextRef;
 use session::table_name::table_name_to_full_name;
 use snafu::{ensure, Location, OptionExt, ResultExt};
+use store_api::region_request::CompactOptions;
 use table::requests::{CompactTableRequest, FlushTableRequest};
 
 use crate::ensure_greptime;
@@ -90,13 +91,99 @@ define_table_function!(
     FlushTableRequest
 );
 
-define_table_function!(
-    "CompactTableFunction",
-    "compact_table",
-    compact_table,
-    compact,
-    CompactTableRequest
-);
+/// A function to compact table, such as `compact_table(table_name)` or
+/// `compact_table(table_name, 'strict_window', '1h')`.
+#[admin_fn(
+    name = "CompactTableFunction",
+    display_name = "compact_table",
+    sig_fn = "compact_signature",
+    ret = "uint64"
+)]
+pub(crate) async fn compact_table(
+    table_mutation_handler: &TableMutationHandlerRef,
+    query_ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    ensure!(
+        (1..=3).contains(&params.len()),
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect 1 to 3, have: {}",
+                params.len()
+            ),
+        }
+    );
+
+    let ValueRef::String(table_name) = params[0] else {
+        return UnsupportedInputDataTypeSnafu {
+            function: "compact_table",
+            datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+        }
+        .fail();
+    };
+    let compact_options = parse_compact_options("compact_table", &params[1..])?;
+
+    let (catalog_name, schema_name, table_name) = table_name_to_full_name(table_name, &query_ctx)
+        .map_err(BoxedError::new)
+        .context(TableMutationSnafu)?;
+
+    let affected_rows = table_mutation_handler
+        .compact(
+            CompactTableRequest {
+                catalog_name,
+                schema_name,
+                table_name,
+                compact_options,
+            },
+            query_ctx.clone(),
+        )
+        .await?;
+
+    Ok(Value::from(affected_rows as u64))
+}
+
+/// Parses the optional strategy and window arguments of compaction functions,
+/// e.g. `'strict_window', '1h'`.
+pub(crate) fn parse_compact_options(
+    function: &str,
+    params: &[ValueRef<'_>],
+) -> Result<CompactOptions> {
+    let mut strings = Vec::with_capacity(params.len());
+    for param in params {
+        let ValueRef::String(value) = param else {
+            return UnsupportedInputDataTypeSnafu {
+                function,
+                datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+            }
+            .fail();
+        };
+        strings.push(*value);
+    }
+
+    let Some(strategy) = strings.first() else {
+        return Ok(CompactOptions::Regular);
+    };
+    let window_seconds = strings
+        .get(1)
+        .map(|window| {
+            humantime::parse_duration(window)
+                .map(|window| window.as_secs() as i64)
+                .map_err(|e| {
+                    InvalidFuncArgsSnafu {
+                        err_msg: format!("Invalid compaction window '{window}': {e}"),
+                    }
+                    .build()
+                })
+        })
+        .transpose()?;
+
+    CompactOptions::parse(strategy, window_seconds).map_err(|e| {
+        InvalidFuncArgsSnafu {
+            err_msg: e.to_string(),
+        }
+        .build()
+    })
+}
 
 fn signature() -> Signature {
     Signature::uniform(
@@ -106,11 +193,24 @@ fn signature() -> Signature {
     )
 }
 
+fn compact_signature() -> Signature {
+    Signature::one_of(
+        vec![
+            // compact_table(table_name)
+            TypeSignature::Uniform(1, vec![ConcreteDataType::string_datatype()]),
+            // compact_table(table_name, strategy)
+            TypeSignature::Uniform(2, vec![ConcreteDataType::string_datatype()]),
+            // compact_table(table_name, strategy, window)
+            TypeSignature::Uniform(3, vec![ConcreteDataType::string_datatype()]),
+        ],
+        Volatility::Immutable,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use common_query::prelude::TypeSignature;
     use datatypes::vectors::{StringVector, UInt64Vector};
 
     use super::*;
@@ -174,5 +274,79 @@ mod tests {
 
     define_table_function_test!(flush_table, FlushTableFunction);
 
-    define_table_function_test!(compact_table, CompactTableFunction);
+    #[test]
+    fn test_compact_table() {
+        let f = CompactTableFunction;
+        assert_eq!("compact_table", f.name());
+        assert!(matches!(f.signature(),
+                         Signature {
+                             type_signature: TypeSignature::OneOf(sigs),
+                             volatility: Volatility::Immutable
+                         } if sigs.len() == 3));
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["test"])),
+            Arc::new(StringVector::from(vec!["strict_window"])),
+            Arc::new(StringVector::from(vec!["1h"])),
+        ];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+        let expect: VectorRef = Arc::new(UInt64Vector::from_slice([42]));
+        assert_eq!(expect, result);
+
+        let result = f.eval(FunctionContext::mock(), &args[..1]).unwrap();
+        assert_eq!(expect, result);
+
+        let result = f.eval(FunctionContext::default(), &args).unwrap_err();
+        assert_eq!(
+            "Missing TableMutationHandler, not expected",
+            result.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_compact_options() {
+        assert_eq!(
+            CompactOptions::Regular,
+            parse_compact_options("compact_table", &[]).unwrap()
+        );
+        assert_eq!(
+            CompactOptions::Regular,
+            parse_compact_options("compact_table", &[ValueRef::String("regular")]).unwrap()
+        );
+        assert_eq!(
+            CompactOptions::StrictWindow {
+                window_seconds: None
+            },
+            parse_compact_options("compact_table", &[ValueRef::String("strict_window")]).unwrap()
+        );
+        assert_eq!(
+            CompactOptions::StrictWindow {
+                window_seconds: Some(5400)
+            },
+            parse_compact_options(
+                "compact_table",
+                &[
+                    ValueRef::String("strict_window"),
+                    ValueRef::String("1h 30m")
+                ]
+            )
+            .unwrap()
+        );
+
+        assert!(parse_compact_options(
+            "compact_table",
+            &[
+                ValueRef::String("strict_window"),
+                ValueRef::String("an hour")
+            ]
+        )
+        .is_err());
+        assert!(parse_compact_options(
+            "compact_table",
+            &[ValueRef::String("regular"), ValueRef::String("1h")]
+        )
+        .is_err());
+        assert!(parse_compact_options("compact_table", &[ValueRef::String("unknown")]).is_err());
+        assert!(parse_compact_options("compact_table", &[ValueRef::UInt64(1)]).is_err());
+    }
 }
@@ -13,16 +13,17 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::ptr::NonNull;
 use std::sync::Arc;
 
 use api::v1::{AffectedRows, FlightMetadata, Metrics};
-use arrow_flight::utils::flight_data_to_arrow_batch;
 use arrow_flight::{FlightData, SchemaAsIpc};
 use common_base::bytes::Bytes;
 use common_recordbatch::{RecordBatch, RecordBatches};
 use datatypes::arrow;
-use datatypes::arrow::datatypes::Schema as ArrowSchema;
-use datatypes::arrow::ipc::{root_as_message, writer, MessageHeader};
+use datatypes::arrow::buffer::Buffer;
+use datatypes::arrow::datatypes::{DataType as ArrowDataType, IntervalUnit, Schema as ArrowSchema};
+use datatypes::arrow::ipc::{reader, root_as_message, writer, MessageHeader};
 use datatypes::schema::{Schema, SchemaRef};
 use flatbuffers::FlatBufferBuilder;
 use prost::bytes::Bytes as ProstBytes;
@@ -122,6 +123,8 @@ impl FlightEncoder {
 #[derive(Default)]
 pub struct FlightDecoder {
     schema: Option<SchemaRef>,
+    /// Whether the body of record batches can be read in place, without copying.
+    zero_copy: bool,
 }
 
 impl FlightDecoder {
@@ -160,6 +163,11 @@ impl FlightDecoder {
                 let schema =
                     Arc::new(Schema::try_from(arrow_schema).context(ConvertArrowSchemaSnafu)?);
 
+                self.zero_copy = !schema
+                    .arrow_schema()
+                    .fields()
+                    .iter()
+                    .any(|f| requires_large_alignment(f.data_type()));
                 self.schema = Some(schema.clone());
 
                 Ok(FlightMessage::Schema(schema))
@@ -169,15 +177,31 @@ impl FlightDecoder {
                     reason: "Should have decoded schema first!",
                 })?;
                 let arrow_schema = schema.arrow_schema().clone();
-
-                let arrow_batch =
-                    flight_data_to_arrow_batch(&flight_data, arrow_schema, &HashMap::new())
-                        .map_err(|e| {
-                            InvalidFlightDataSnafu {
-                                reason: e.to_string(),
-                            }
-                            .build()
-                        })?;
+                let batch = message
+                    .header_as_record_batch()
+                    .context(InvalidFlightDataSnafu {
+                        reason: "Unable to convert flight data header to a record batch",
+                    })?;
+
+                let body = if self.zero_copy {
+                    buffer_from_bytes(flight_data.data_body)
+                } else {
+                    Buffer::from(flight_data.data_body.as_ref())
+                };
+                let arrow_batch = reader::read_record_batch(
+                    &body,
+                    batch,
+                    arrow_schema,
+                    &HashMap::new(),
+                    None,
+                    &message.version(),
+                )
+                .map_err(|e| {
+                    InvalidFlightDataSnafu {
+                        reason: e.to_string(),
+                    }
+                    .build()
+                })?;
                 let recordbatch = RecordBatch::try_from_df_record_batch(schema, arrow_batch)
                     .context(CreateRecordBatchSnafu)?;
                 Ok(FlightMessage::Recordbatch(recordbatch))
@@ -193,6 +217,42 @@ impl FlightDecoder {
     }
 }
 
+/// Wraps the received `bytes` in an arrow [Buffer] without copying them.
+///
+/// Arrays require their buffers to be aligned to their native types, so the bytes are
+/// copied into a new (aligned) buffer if they don't start at an 8 bytes boundary. IPC
+/// pads every buffer in the body to 8 bytes, which keeps the arrays in it aligned.
+fn buffer_from_bytes(bytes: ProstBytes) -> Buffer {
+    let len = bytes.len();
+    match NonNull::new(bytes.as_ptr() as *mut u8) {
+        Some(ptr) if ptr.as_ptr().align_offset(8) == 0 => {
+            // Safety: the memory region is owned by `bytes`, which lives as long as the buffer.
+            unsafe { Buffer::from_custom_allocation(ptr, len, Arc::new(bytes)) }
+        }
+        _ => Buffer::from(bytes.as_ref()),
+    }
+}
+
+/// Returns true if arrays of `data_type` need an alignment larger than the 8 bytes
+/// guaranteed by IPC.
+fn requires_large_alignment(data_type: &ArrowDataType) -> bool {
+    match data_type {
+        ArrowDataType::Decimal128(_, _)
+        | ArrowDataType::Decimal256(_, _)
+        | ArrowDataType::Interval(IntervalUnit::MonthDayNano)
+        | ArrowDataType::Union(_, _) => true,
+        ArrowDataType::List(field)
+        | ArrowDataType::LargeList(field)
+        | ArrowDataType::FixedSizeList(field, _)
+        | ArrowDataType::Map(field, _) => requires_large_alignment(field.data_type()),
+        ArrowDataType::Struct(fields) => fields
+            .iter()
+            .any(|field| requires_large_alignment(field.data_type())),
+        ArrowDataType::Dictionary(_, value_type) => requires_large_alignment(value_type),
+        _ => false,
+    }
+}
+
 pub fn flight_messages_to_recordbatches(messages: Vec<FlightMessage>) -> Result<RecordBatches> {
     if messages.is_empty() {
         Ok(RecordBatches::empty())
@@ -245,7 +305,7 @@ mod test {
     use datatypes::arrow::datatypes::{DataType, Field};
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::ColumnSchema;
-    use datatypes::vectors::Int32Vector;
+    use datatypes::vectors::{Int32Vector, Int64Vector};
 
     use super::*;
     use crate::Error;
@@ -313,6 +373,53 @@ mod test {
         assert_eq!(actual_batch, batch2);
     }
 
+    #[test]
+    fn test_try_decode_unaligned_body() {
+        let arrow_schema = ArrowSchema::new(vec![Field::new("n", DataType::Int64, true)]);
+        let schema = Arc::new(Schema::try_from(arrow_schema.clone()).unwrap());
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int64Vector::from(vec![Some(1), None, Some(3)])) as _],
+        )
+        .unwrap();
+
+        let flight_data =
+            batches_to_flight_data(&arrow_schema, vec![batch.clone().into_df_record_batch()])
+                .unwrap();
+        let [d1, d2] = flight_data.as_slice() else {
+            unreachable!()
+        };
+
+        let decoder = &mut FlightDecoder::default();
+        let _ = decoder.try_decode(d1.clone()).unwrap();
+        assert!(decoder.zero_copy);
+
+        // moves the body to an unaligned address
+        let mut body = vec![0u8];
+        body.extend_from_slice(&d2.data_body);
+        let mut unaligned = d2.clone();
+        unaligned.data_body = ProstBytes::from(body).slice(1..);
+
+        for data in [d2.clone(), unaligned] {
+            let FlightMessage::Recordbatch(actual_batch) = decoder.try_decode(data).unwrap() else {
+                unreachable!()
+            };
+            assert_eq!(actual_batch, batch);
+        }
+    }
+
+    #[test]
+    fn test_requires_large_alignment() {
+        assert!(!requires_large_alignment(&DataType::Int64));
+        assert!(requires_large_alignment(&DataType::Decimal128(10, 2)));
+        assert!(requires_large_alignment(&DataType::List(Arc::new(
+            Field::new("item", DataType::Decimal128(10, 2), true)
+        ))));
+        assert!(!requires_large_alignment(&DataType::List(Arc::new(
+            Field::new("item", DataType::Utf8, true)
+        ))));
+    }
+
     #[test]
     fn test_flight_messages_to_recordbatches() {
         let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
@@ -21,7 +21,9 @@ use crate::instruction::CacheIdent;
 use crate::key::table_info::TableInfoKey;
 use crate::key::table_name::TableNameKey;
 use crate::key::table_route::TableRouteKey;
-use crate::key::TableMetaKey;
+use crate::key::user::UserKey;
+use crate::key::view_info::ViewInfoKey;
+use crate::key::{TableMetaKey, PRIVILEGE_KEY_PREFIX};
 
 /// KvBackend cache invalidator
 #[async_trait::async_trait]
@@ -107,6 +109,18 @@ where
                     let key: TableNameKey = (&table_name).into();
                     self.invalidate_key(&key.as_raw_key()).await
                 }
+                CacheIdent::ViewName(view_name) => {
+                    let key: ViewInfoKey = (&view_name).into();
+                    self.invalidate_key(&key.as_raw_key()).await
+                }
+                CacheIdent::User(user_name) => {
+                    let key = UserKey::new(&user_name);
+                    self.invalidate_key(&key.as_raw_key()).await
+                }
+                CacheIdent::Privilege(key) => {
+                    let key = format!("{PRIVILEGE_KEY_PREFIX}/{key}");
+                    self.invalidate_key(key.as_bytes()).await
+                }
             }
         }
         Ok(())
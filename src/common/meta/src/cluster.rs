@@ -14,11 +14,14 @@
 
 use std::str::FromStr;
 
+use api::v1::meta::RegionStat as PbRegionStat;
 use common_error::ext::ErrorExt;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt, ResultExt};
+use store_api::region_engine::RegionRole;
+use store_api::storage::RegionId;
 
 use crate::error::{
     DecodeJsonSnafu, EncodeJsonSnafu, Error, FromUtf8Snafu, InvalidNodeInfoKeySnafu,
@@ -86,6 +89,9 @@ pub struct NodeInfo {
     pub last_activity_ts: i64,
     /// The status of the node. Different roles have different node status.
     pub status: NodeStatus,
+    /// Start time of the node in milliseconds, 0 if unknown.
+    #[serde(default)]
+    pub start_time_ms: u64,
 }
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
@@ -100,6 +106,19 @@ pub enum NodeStatus {
     Datanode(DatanodeStatus),
     Frontend(FrontendStatus),
     Metasrv(MetasrvStatus),
+    Standalone,
+}
+
+impl NodeStatus {
+    /// Returns the role name of the node.
+    pub fn role_name(&self) -> &str {
+        match self {
+            NodeStatus::Datanode(_) => "DATANODE",
+            NodeStatus::Frontend(_) => "FRONTEND",
+            NodeStatus::Metasrv(_) => "METASRV",
+            NodeStatus::Standalone => "STANDALONE",
+        }
+    }
 }
 
 /// The status of a datanode.
@@ -113,6 +132,42 @@ pub struct DatanodeStatus {
     pub leader_regions: usize,
     /// How many follower regions on this node.
     pub follower_regions: usize,
+    /// Statistics of regions on this node.
+    #[serde(default)]
+    pub region_stats: Vec<RegionStat>,
+}
+
+/// The statistics of a region reported by the datanode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionStat {
+    /// The region_id.
+    pub id: RegionId,
+    /// The read capacity units during this period
+    pub rcus: i64,
+    /// The write capacity units during this period
+    pub wcus: i64,
+    /// Approximate bytes of this region
+    pub approximate_bytes: i64,
+    /// Approximate number of rows in this region
+    pub approximate_rows: i64,
+    /// The engine name.
+    pub engine: String,
+    /// The region role.
+    pub role: RegionRole,
+}
+
+impl From<PbRegionStat> for RegionStat {
+    fn from(value: PbRegionStat) -> Self {
+        Self {
+            id: RegionId::from_u64(value.region_id),
+            rcus: value.rcus,
+            wcus: value.wcus,
+            approximate_bytes: value.approximate_bytes,
+            approximate_rows: value.approximate_rows,
+            engine: value.engine.to_string(),
+            role: RegionRole::from(value.role()),
+        }
+    }
 }
 
 /// The status of a frontend.
@@ -270,7 +325,9 @@ mod tests {
                 wcus: 2,
                 leader_regions: 3,
                 follower_regions: 4,
+                region_stats: Vec::new(),
             }),
+            start_time_ms: 1,
         };
 
         let node_info_bytes: Vec<u8> = node_info.try_into().unwrap();
@@ -286,7 +343,9 @@ mod tests {
                     wcus: 2,
                     leader_regions: 3,
                     follower_regions: 4,
+                    ..
                 }),
+                start_time_ms: 1,
             }
         );
     }
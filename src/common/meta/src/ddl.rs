@@ -34,8 +34,10 @@ pub mod create_database;
 pub mod create_logical_tables;
 pub mod create_table;
 mod create_table_template;
+pub mod create_view;
 pub mod drop_database;
 pub mod drop_table;
+pub mod drop_view;
 mod physical_table_metadata;
 pub mod table_meta;
 #[cfg(any(test, feature = "testing"))]
@@ -74,6 +76,14 @@ pub trait ProcedureExecutor: Send + Sync {
         ctx: &ExecutorContext,
         pid: &str,
     ) -> Result<ProcedureStateResponse>;
+
+    /// Returns true if the executor runs view tasks.
+    ///
+    /// The metasrv protocol has no view tasks, so only the
+    /// [DdlManager](crate::ddl_manager::DdlManager) in the same process runs them.
+    fn supports_view_tasks(&self) -> bool {
+        false
+    }
 }
 
 pub type ProcedureExecutorRef = Arc<dyn ProcedureExecutor>;
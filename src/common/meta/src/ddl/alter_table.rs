@@ -48,6 +48,7 @@ use crate::lock_key::{CatalogLock, SchemaLock, TableLock, TableNameLock};
 use crate::metrics;
 use crate::rpc::ddl::AlterTableTask;
 use crate::rpc::router::{find_leader_regions, find_leaders};
+use crate::table_name::TableName;
 
 /// The alter table procedure
 pub struct AlterTableProcedure {
@@ -172,25 +173,38 @@ impl AlterTableProcedure {
 
     /// Broadcasts the invalidating table cache instructions.
     async fn on_broadcast(&mut self) -> Result<Status> {
-        // Safety: Checked in `AlterTableProcedure::new`.
-        let alter_kind = self.data.task.alter_table.kind.as_ref().unwrap();
         let cache_invalidator = &self.context.cache_invalidator;
-        let cache_keys = if matches!(alter_kind, Kind::RenameTable { .. }) {
-            vec![CacheIdent::TableName(self.data.table_ref().into())]
-        } else {
-            vec![
-                CacheIdent::TableId(self.data.table_id()),
-                CacheIdent::TableName(self.data.table_ref().into()),
-            ]
-        };
-
         cache_invalidator
-            .invalidate(&Context::default(), cache_keys)
+            .invalidate(&Context::default(), self.cache_idents())
             .await?;
 
         Ok(Status::done())
     }
 
+    /// Returns caches to invalidate after altering the table.
+    ///
+    /// Renaming also invalidates the new table name, in case any frontend
+    /// cached it before the rename.
+    pub(crate) fn cache_idents(&self) -> Vec<CacheIdent> {
+        let table_ref = self.data.table_ref();
+        let mut cache_idents = vec![
+            CacheIdent::TableId(self.data.table_id()),
+            CacheIdent::TableName(table_ref.into()),
+        ];
+
+        // Safety: Checked in `AlterTableProcedure::new`.
+        let alter_kind = self.data.task.alter_table.kind.as_ref().unwrap();
+        if let Kind::RenameTable(RenameTable { new_table_name }) = alter_kind {
+            cache_idents.push(CacheIdent::TableName(TableName::new(
+                table_ref.catalog,
+                table_ref.schema,
+                new_table_name,
+            )));
+        }
+
+        cache_idents
+    }
+
     fn lock_key_inner(&self) -> Vec<StringKey> {
         let mut lock_key = vec![];
         let table_ref = self.data.table_ref();
@@ -202,6 +216,10 @@ impl AlterTableProcedure {
         // Safety: Checked in `AlterTableProcedure::new`.
         let alter_kind = self.data.task.alter_table.kind.as_ref().unwrap();
         if let Kind::RenameTable(RenameTable { new_table_name }) = alter_kind {
+            // Locks both names, the old name is released by renaming.
+            lock_key.push(
+                TableNameLock::new(table_ref.catalog, table_ref.schema, table_ref.table).into(),
+            );
             lock_key.push(
                 TableNameLock::new(table_ref.catalog, table_ref.schema, new_table_name).into(),
             )
@@ -188,6 +188,7 @@ mod tests {
                     follower_peers: vec![],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 }]),
                 HashMap::new(),
             )
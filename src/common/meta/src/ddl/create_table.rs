@@ -38,6 +38,7 @@ use crate::ddl::{DdlContext, TableMetadata, TableMetadataAllocatorContext};
 use crate::error::{self, Result};
 use crate::key::table_name::TableNameKey;
 use crate::key::table_route::{PhysicalTableRouteValue, TableRouteValue};
+use crate::key::view_info::ViewInfoKey;
 use crate::lock_key::{CatalogLock, SchemaLock, TableNameLock};
 use crate::region_keeper::OperatingRegionGuard;
 use crate::rpc::ddl::CreateTableTask;
@@ -150,6 +151,24 @@ impl CreateTableProcedure {
             return Ok(Status::done_with_output(table_id));
         }
 
+        // Views and tables share the namespace.
+        let view_exists = self
+            .context
+            .table_metadata_manager
+            .view_info_manager()
+            .exists(ViewInfoKey::new(
+                &expr.catalog_name,
+                &expr.schema_name,
+                &expr.table_name,
+            ))
+            .await?;
+        ensure!(
+            !view_exists,
+            error::ViewAlreadyExistsSnafu {
+                view_name: self.creator.data.table_ref().to_string(),
+            }
+        );
+
         self.creator.data.state = CreateTableState::DatanodeCreateRegions;
         let TableMetadata {
             table_id,
@@ -0,0 +1,174 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_procedure::error::{FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu};
+use common_procedure::{Context as ProcedureContext, LockKey, Procedure, Status};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use strum::AsRefStr;
+
+use crate::cache_invalidator::Context;
+use crate::ddl::utils::handle_retry_error;
+use crate::ddl::DdlContext;
+use crate::error::{self, Result};
+use crate::instruction::CacheIdent;
+use crate::key::table_name::TableNameKey;
+use crate::key::view_info::ViewInfoKey;
+use crate::lock_key::{CatalogLock, SchemaLock, TableNameLock};
+use crate::rpc::ddl::CreateViewTask;
+
+pub struct CreateViewProcedure {
+    pub context: DdlContext,
+    pub data: CreateViewData,
+}
+
+impl CreateViewProcedure {
+    pub const TYPE_NAME: &'static str = "metasrv-procedure::CreateView";
+
+    pub fn new(task: CreateViewTask, context: DdlContext) -> Self {
+        Self {
+            context,
+            data: CreateViewData {
+                state: CreateViewState::Prepare,
+                task,
+            },
+        }
+    }
+
+    pub fn from_json(json: &str, context: DdlContext) -> ProcedureResult<Self> {
+        let data = serde_json::from_str(json).context(FromJsonSnafu)?;
+
+        Ok(Self { context, data })
+    }
+
+    pub async fn on_prepare(&mut self) -> Result<Status> {
+        let view_name = &self.data.task.view_name;
+        // Views and tables share the namespace.
+        let table_exists = self
+            .context
+            .table_metadata_manager
+            .table_name_manager()
+            .exists(TableNameKey::new(
+                &view_name.catalog_name,
+                &view_name.schema_name,
+                &view_name.table_name,
+            ))
+            .await?;
+        ensure!(
+            !table_exists,
+            error::TableAlreadyExistsSnafu {
+                table_name: view_name.to_string(),
+            }
+        );
+
+        let view_exists = self
+            .context
+            .table_metadata_manager
+            .view_info_manager()
+            .exists(ViewInfoKey::from(view_name))
+            .await?;
+        if view_exists && !self.data.task.or_replace {
+            ensure!(
+                self.data.task.create_if_not_exists,
+                error::ViewAlreadyExistsSnafu {
+                    view_name: view_name.to_string(),
+                }
+            );
+            return Ok(Status::done());
+        }
+
+        self.data.state = CreateViewState::CreateMetadata;
+        Ok(Status::executing(true))
+    }
+
+    pub async fn on_create_metadata(&mut self) -> Result<Status> {
+        let task = &self.data.task;
+        // Overwrites the view written by a previous execution of the procedure.
+        let _ = self
+            .context
+            .table_metadata_manager
+            .view_info_manager()
+            .create(ViewInfoKey::from(&task.view_name), &task.view_info, true)
+            .await?;
+
+        self.data.state = CreateViewState::InvalidateViewCache;
+        Ok(Status::executing(true))
+    }
+
+    pub async fn on_invalidate_view_cache(&mut self) -> Result<Status> {
+        let ctx = Context {
+            subject: Some("Invalidate view cache by creating view".to_string()),
+        };
+        self.context
+            .cache_invalidator
+            .invalidate(
+                &ctx,
+                vec![CacheIdent::ViewName(self.data.task.view_name.clone())],
+            )
+            .await?;
+
+        Ok(Status::done())
+    }
+}
+
+#[async_trait]
+impl Procedure for CreateViewProcedure {
+    fn type_name(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    async fn execute(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<Status> {
+        let state = &self.data.state;
+
+        match state {
+            CreateViewState::Prepare => self.on_prepare().await,
+            CreateViewState::CreateMetadata => self.on_create_metadata().await,
+            CreateViewState::InvalidateViewCache => self.on_invalidate_view_cache().await,
+        }
+        .map_err(handle_retry_error)
+    }
+
+    fn dump(&self) -> ProcedureResult<String> {
+        serde_json::to_string(&self.data).context(ToJsonSnafu)
+    }
+
+    fn lock_key(&self) -> LockKey {
+        let view_name = &self.data.task.view_name;
+
+        LockKey::new(vec![
+            CatalogLock::Read(&view_name.catalog_name).into(),
+            SchemaLock::read(&view_name.catalog_name, &view_name.schema_name).into(),
+            TableNameLock::new(
+                &view_name.catalog_name,
+                &view_name.schema_name,
+                &view_name.table_name,
+            )
+            .into(),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, AsRefStr)]
+pub enum CreateViewState {
+    Prepare,
+    CreateMetadata,
+    InvalidateViewCache,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateViewData {
+    pub state: CreateViewState,
+    pub task: CreateViewTask,
+}
@@ -18,8 +18,8 @@ mod metadata;
 use async_trait::async_trait;
 use common_procedure::error::{FromJsonSnafu, ToJsonSnafu};
 use common_procedure::{
-    Context as ProcedureContext, Error as ProcedureError, LockKey, Procedure,
-    Result as ProcedureResult, Status,
+    Context as ProcedureContext, Error as ProcedureError, LockKey, Procedure, ProcedureId,
+    ProcedureWithId, Result as ProcedureResult, Status,
 };
 use common_telemetry::info;
 use common_telemetry::tracing::warn;
@@ -30,15 +30,19 @@ use table::metadata::TableId;
 use table::table_reference::TableReference;
 
 use self::executor::DropTableExecutor;
+use crate::cache_invalidator::Context;
 use crate::ddl::utils::handle_retry_error;
 use crate::ddl::DdlContext;
 use crate::error::{self, Result};
+use crate::instruction::CacheIdent;
 use crate::key::table_route::TableRouteValue;
-use crate::lock_key::{CatalogLock, SchemaLock, TableLock};
+use crate::key::view_info::{ViewInfoKey, ViewInfoValue};
+use crate::lock_key::{CatalogLock, SchemaLock, TableLock, TableNameLock};
 use crate::metrics;
 use crate::region_keeper::OperatingRegionGuard;
 use crate::rpc::ddl::DropTableTask;
 use crate::rpc::router::{operating_leader_regions, RegionRoute};
+use crate::table_name::TableName;
 
 pub struct DropTableProcedure {
     /// The context of procedure runtime.
@@ -81,6 +85,7 @@ impl DropTableProcedure {
             return Ok(Status::done());
         }
         self.fill_table_metadata().await?;
+        self.fill_dependent_views().await?;
         self.data.state = DropTableState::DeleteMetadata;
 
         Ok(Status::executing(true))
@@ -132,6 +137,17 @@ impl DropTableProcedure {
             .on_delete_metadata(&self.context, table_route_value)
             .await?;
         info!("Deleted table metadata for table {table_id}");
+        // Views are deleted after the table, they are restored with the table if
+        // the procedure rolls back.
+        for (view_name, _) in &self.data.dependent_views {
+            let _ = self
+                .context
+                .table_metadata_manager
+                .view_info_manager()
+                .delete(ViewInfoKey::from(view_name))
+                .await?;
+            info!("Deleted view {view_name} depending on table {table_id}");
+        }
         self.data.state = DropTableState::InvalidateTableCache;
         Ok(Status::executing(true))
     }
@@ -139,6 +155,21 @@ impl DropTableProcedure {
     /// Broadcasts invalidate table cache instruction.
     async fn on_broadcast(&mut self) -> Result<Status> {
         self.executor.invalidate_table_cache(&self.context).await?;
+        if !self.data.dependent_views.is_empty() {
+            let ctx = Context {
+                subject: Some("Invalidate view cache by dropping table".to_string()),
+            };
+            let idents = self
+                .data
+                .dependent_views
+                .iter()
+                .map(|(view_name, _)| CacheIdent::ViewName(view_name.clone()))
+                .collect();
+            self.context
+                .cache_invalidator
+                .invalidate(&ctx, idents)
+                .await?;
+        }
         self.data.state = DropTableState::DatanodeDropRegions;
 
         Ok(Status::executing(true))
@@ -153,7 +184,7 @@ impl DropTableProcedure {
     }
 
     /// Deletes metadata tombstone.
-    async fn on_delete_metadata_tombstone(&self) -> Result<Status> {
+    async fn on_delete_metadata_tombstone(&mut self) -> Result<Status> {
         let table_route_value = &TableRouteValue::new(
             self.data.task.table_id,
             // Safety: checked
@@ -163,7 +194,78 @@ impl DropTableProcedure {
         self.executor
             .on_delete_metadata_tombstone(&self.context, table_route_value)
             .await?;
-        Ok(Status::done())
+        if self.data.sink_tables.is_empty() {
+            return Ok(Status::done());
+        }
+        self.data.state = DropTableState::DropSinkTables;
+        Ok(Status::executing(true))
+    }
+
+    /// Drops the sink tables of the dropped materialized views, one table in a
+    /// subprocedure at a time.
+    async fn on_drop_sink_tables(&mut self, ctx: &ProcedureContext) -> Result<Status> {
+        if let Some(procedure_id) = self.data.sink_table_procedure {
+            let state = ctx
+                .provider
+                .procedure_state(procedure_id)
+                .await
+                .context(error::QueryProcedureSnafu)?;
+            match state {
+                Some(state) if state.is_done() => {
+                    let task = self.data.sink_tables.remove(0);
+                    info!("Dropped sink table {}", task.table_name());
+                    self.data.sink_table_procedure = None;
+                }
+                Some(state) if state.is_failed() => {
+                    return error::UnexpectedSnafu {
+                        err_msg: format!(
+                            "failed to drop sink table {}",
+                            self.data.sink_tables[0].table_name()
+                        ),
+                    }
+                    .fail();
+                }
+                // Waits for the subprocedure.
+                Some(_) => {
+                    return Ok(Status::Suspended {
+                        subprocedures: vec![],
+                        persist: false,
+                    })
+                }
+                // The subprocedure is unknown after restarting, drops the table
+                // again as dropping it is idempotent.
+                None => self.data.sink_table_procedure = None,
+            }
+        }
+
+        let Some(task) = self.data.sink_tables.first() else {
+            return Ok(Status::done());
+        };
+        let procedure = ProcedureWithId::with_random_id(Box::new(DropTableProcedure::new(
+            self.data.cluster_id,
+            task.clone(),
+            self.context.clone(),
+        )));
+        self.data.sink_table_procedure = Some(procedure.id);
+
+        Ok(Status::Suspended {
+            subprocedures: vec![procedure],
+            persist: true,
+        })
+    }
+
+    /// Restores the deleted views.
+    async fn restore_dependent_views(&self) -> Result<()> {
+        for (view_name, view_info) in &self.data.dependent_views {
+            let _ = self
+                .context
+                .table_metadata_manager
+                .view_info_manager()
+                .create(ViewInfoKey::from(view_name), view_info, true)
+                .await?;
+        }
+
+        Ok(())
     }
 }
 
@@ -173,7 +275,7 @@ impl Procedure for DropTableProcedure {
         Self::TYPE_NAME
     }
 
-    async fn execute(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<Status> {
+    async fn execute(&mut self, ctx: &ProcedureContext) -> ProcedureResult<Status> {
         let state = &self.data.state;
         let _timer = metrics::METRIC_META_PROCEDURE_DROP_TABLE
             .with_label_values(&[state.as_ref()])
@@ -185,6 +287,7 @@ impl Procedure for DropTableProcedure {
             DropTableState::InvalidateTableCache => self.on_broadcast().await,
             DropTableState::DatanodeDropRegions => self.on_datanode_drop_regions().await,
             DropTableState::DeleteTombstone => self.on_delete_metadata_tombstone().await,
+            DropTableState::DropSinkTables => self.on_drop_sink_tables(ctx).await,
         }
         .map_err(handle_retry_error)
     }
@@ -199,6 +302,8 @@ impl Procedure for DropTableProcedure {
         let lock_key = vec![
             CatalogLock::Read(table_ref.catalog).into(),
             SchemaLock::read(table_ref.catalog, table_ref.schema).into(),
+            // Serializes with procedures creating a table with the same name.
+            TableNameLock::new(table_ref.catalog, table_ref.schema, table_ref.table).into(),
             TableLock::Write(table_id).into(),
         ];
 
@@ -206,7 +311,11 @@ impl Procedure for DropTableProcedure {
     }
 
     fn rollback_supported(&self) -> bool {
-        !matches!(self.data.state, DropTableState::Prepare)
+        // The table is dropped before dropping the sink tables.
+        !matches!(
+            self.data.state,
+            DropTableState::Prepare | DropTableState::DropSinkTables
+        )
     }
 
     async fn rollback(&mut self, _: &ProcedureContext) -> ProcedureResult<()> {
@@ -223,6 +332,9 @@ impl Procedure for DropTableProcedure {
         );
         self.executor
             .on_restore_metadata(&self.context, table_route_value)
+            .await
+            .map_err(ProcedureError::external)?;
+        self.restore_dependent_views()
             .await
             .map_err(ProcedureError::external)
     }
@@ -235,6 +347,15 @@ pub struct DropTableData {
     pub task: DropTableTask,
     pub physical_region_routes: Vec<RegionRoute>,
     pub physical_table_id: Option<TableId>,
+    /// Views dropped with the table.
+    #[serde(default)]
+    pub dependent_views: Vec<(TableName, ViewInfoValue)>,
+    /// Sink tables of the materialized views in `dependent_views` to drop.
+    #[serde(default)]
+    pub sink_tables: Vec<DropTableTask>,
+    /// The subprocedure dropping the first table in `sink_tables`.
+    #[serde(default)]
+    pub sink_table_procedure: Option<ProcedureId>,
 }
 
 impl DropTableData {
@@ -245,6 +366,9 @@ impl DropTableData {
             task,
             physical_region_routes: vec![],
             physical_table_id: None,
+            dependent_views: vec![],
+            sink_tables: vec![],
+            sink_table_procedure: None,
         }
     }
 
@@ -278,4 +402,6 @@ pub enum DropTableState {
     DatanodeDropRegions,
     /// Deletes metadata tombstone permanently
     DeleteTombstone,
+    /// Drops sink tables of dropped materialized views
+    DropSinkTables,
 }
@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use snafu::ensure;
+
 use crate::ddl::drop_table::DropTableProcedure;
-use crate::error::Result;
+use crate::error::{self, Result};
+use crate::key::table_name::TableNameKey;
+use crate::rpc::ddl::{DropDependents, DropTableTask};
 
 impl DropTableProcedure {
     /// Fetches the table info and physical table route.
@@ -31,4 +35,70 @@ impl DropTableProcedure {
 
         Ok(())
     }
+
+    /// Fetches views reading from the table, including views reading from the sink
+    /// tables of these views if they are materialized.
+    ///
+    /// Returns an error if any view exists but the task restricts dropping them.
+    pub(crate) async fn fill_dependent_views(&mut self) -> Result<()> {
+        if self.data.task.dependents == DropDependents::Ignore {
+            return Ok(());
+        }
+
+        let table_metadata_manager = &self.context.table_metadata_manager;
+        let table_name = self.data.task.table_name();
+        let mut dependent_views = vec![];
+        let mut sink_tables = vec![];
+        let mut pending = vec![(self.data.task.table_id, table_name.clone())];
+        while let Some((table_id, table)) = pending.pop() {
+            let views = table_metadata_manager
+                .view_info_manager()
+                .dependent_views(table_id, &table)
+                .await?;
+            for (view_name, view_info) in views {
+                if view_name == table_name
+                    || dependent_views.iter().any(|(name, _)| *name == view_name)
+                {
+                    continue;
+                }
+                if let Some(sink_table) = view_info.sink_table.clone() {
+                    if let Some(value) = table_metadata_manager
+                        .table_name_manager()
+                        .get(TableNameKey::from(&sink_table))
+                        .await?
+                    {
+                        pending.push((value.table_id(), sink_table.clone()));
+                        sink_tables.push(DropTableTask {
+                            catalog: sink_table.catalog_name,
+                            schema: sink_table.schema_name,
+                            table: sink_table.table_name,
+                            table_id: value.table_id(),
+                            drop_if_exists: true,
+                            dependents: DropDependents::Ignore,
+                        });
+                    }
+                }
+                dependent_views.push((view_name, view_info));
+            }
+        }
+
+        if dependent_views.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            self.data.task.dependents == DropDependents::Cascade,
+            error::TableHasDependentsSnafu {
+                table: table_name.to_string(),
+                dependents: dependent_views
+                    .iter()
+                    .map(|(view, _)| view.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }
+        );
+        self.data.dependent_views = dependent_views;
+        self.data.sink_tables = sink_tables;
+
+        Ok(())
+    }
 }
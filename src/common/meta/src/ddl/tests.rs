@@ -16,5 +16,6 @@ mod alter_logical_tables;
 mod alter_table;
 mod create_logical_tables;
 mod create_table;
+mod create_view;
 mod drop_database;
 mod drop_table;
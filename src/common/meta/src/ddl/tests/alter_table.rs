@@ -34,11 +34,13 @@ use crate::ddl::test_util::create_table::test_create_table_task;
 use crate::ddl::test_util::datanode_handler::{
     DatanodeWatcher, RequestOutdatedErrorDatanodeHandler,
 };
+use crate::instruction::CacheIdent;
 use crate::key::table_name::TableNameKey;
 use crate::key::table_route::TableRouteValue;
 use crate::peer::Peer;
 use crate::rpc::ddl::AlterTableTask;
 use crate::rpc::router::{Region, RegionRoute};
+use crate::table_name::TableName;
 use crate::test_util::{new_ddl_context, MockDatanodeManager};
 
 fn test_rename_alter_table_task(table_name: &str, new_table_name: &str) -> AlterTableTask {
@@ -109,6 +111,7 @@ async fn test_on_submit_alter_request() {
                     follower_peers: vec![Peer::empty(5)],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
                 RegionRoute {
                     region: Region::new_test(RegionId::new(table_id, 2)),
@@ -116,6 +119,7 @@ async fn test_on_submit_alter_request() {
                     follower_peers: vec![Peer::empty(4)],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
                 RegionRoute {
                     region: Region::new_test(RegionId::new(table_id, 3)),
@@ -123,6 +127,7 @@ async fn test_on_submit_alter_request() {
                     follower_peers: vec![],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
             ]),
             HashMap::new(),
@@ -195,6 +200,7 @@ async fn test_on_submit_alter_request_with_outdated_request() {
                     follower_peers: vec![Peer::empty(5)],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
                 RegionRoute {
                     region: Region::new_test(RegionId::new(table_id, 2)),
@@ -202,6 +208,7 @@ async fn test_on_submit_alter_request_with_outdated_request() {
                     follower_peers: vec![Peer::empty(4)],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
                 RegionRoute {
                     region: Region::new_test(RegionId::new(table_id, 3)),
@@ -209,6 +216,7 @@ async fn test_on_submit_alter_request_with_outdated_request() {
                     follower_peers: vec![],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
             ]),
             HashMap::new(),
@@ -285,6 +293,33 @@ async fn test_on_update_metadata_rename() {
     assert_eq!(value.table_id(), table_id);
 }
 
+#[tokio::test]
+async fn test_rename_invalidate_caches() {
+    let datanode_manager = Arc::new(MockDatanodeManager::new(()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let cluster_id = 1;
+    let table_id = 1024;
+    let task = test_rename_alter_table_task("foo", "bar");
+    let procedure = AlterTableProcedure::new(cluster_id, table_id, task, ddl_context).unwrap();
+
+    assert_eq!(
+        vec![
+            CacheIdent::TableId(table_id),
+            CacheIdent::TableName(TableName::new(
+                DEFAULT_CATALOG_NAME,
+                DEFAULT_SCHEMA_NAME,
+                "foo"
+            )),
+            CacheIdent::TableName(TableName::new(
+                DEFAULT_CATALOG_NAME,
+                DEFAULT_SCHEMA_NAME,
+                "bar"
+            )),
+        ],
+        procedure.cache_idents()
+    );
+}
+
 #[tokio::test]
 async fn test_on_update_metadata_add_columns() {
     let datanode_manager = Arc::new(MockDatanodeManager::new(()));
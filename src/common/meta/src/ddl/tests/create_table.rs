@@ -33,6 +33,7 @@ use crate::ddl::test_util::datanode_handler::{
 };
 use crate::error::Error;
 use crate::key::table_route::TableRouteValue;
+use crate::key::view_info::{ViewInfoKey, ViewInfoValue};
 use crate::rpc::ddl::CreateTableTask;
 use crate::test_util::{new_ddl_context, MockDatanodeManager};
 
@@ -102,6 +103,29 @@ async fn test_on_prepare_table_exists_err() {
     assert_eq!(err.status_code(), StatusCode::TableAlreadyExists);
 }
 
+#[tokio::test]
+async fn test_on_prepare_view_exists_err() {
+    let datanode_manager = Arc::new(MockDatanodeManager::new(()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let cluster_id = 1;
+    let task = test_create_table_task("foo");
+    let expr = &task.create_table;
+    ddl_context
+        .table_metadata_manager
+        .view_info_manager()
+        .create(
+            ViewInfoKey::new(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+            &ViewInfoValue::new("SELECT 1".to_string()),
+            false,
+        )
+        .await
+        .unwrap();
+    let mut procedure = CreateTableProcedure::new(cluster_id, task, ddl_context);
+    let err = procedure.on_prepare().await.unwrap_err();
+    assert_matches!(err, Error::ViewAlreadyExists { .. });
+    assert_eq!(err.status_code(), StatusCode::TableAlreadyExists);
+}
+
 #[tokio::test]
 async fn test_on_prepare_with_create_if_table_exists() {
     let datanode_manager = Arc::new(MockDatanodeManager::new(()));
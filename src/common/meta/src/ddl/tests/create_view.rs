@@ -0,0 +1,144 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::assert_matches::assert_matches;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_procedure::{Context as ProcedureContext, Procedure, ProcedureId, Status};
+use common_procedure_test::MockContextProvider;
+
+use crate::ddl::create_view::CreateViewProcedure;
+use crate::ddl::drop_view::DropViewProcedure;
+use crate::ddl::test_util::test_create_physical_table_task;
+use crate::error::Error;
+use crate::key::table_route::TableRouteValue;
+use crate::key::view_info::{ViewInfoKey, ViewInfoValue};
+use crate::rpc::ddl::{CreateViewTask, DropViewTask};
+use crate::table_name::TableName;
+use crate::test_util::{new_ddl_context, MockDatanodeManager};
+
+fn test_create_view_task(name: &str, definition: &str) -> CreateViewTask {
+    CreateViewTask {
+        view_name: TableName::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, name),
+        view_info: ViewInfoValue::new(definition.to_string()),
+        create_if_not_exists: false,
+        or_replace: false,
+    }
+}
+
+async fn execute_procedure(procedure: &mut dyn Procedure) {
+    let ctx = ProcedureContext {
+        procedure_id: ProcedureId::random(),
+        provider: Arc::new(MockContextProvider::default()),
+    };
+    while !procedure.execute(&ctx).await.unwrap().is_done() {}
+}
+
+#[tokio::test]
+async fn test_create_and_drop_view() {
+    let datanode_manager = Arc::new(MockDatanodeManager::new(()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let task = test_create_view_task("foo", "SELECT 1");
+    let view_name = task.view_name.clone();
+
+    let mut procedure = CreateViewProcedure::new(task, ddl_context.clone());
+    execute_procedure(&mut procedure).await;
+    let view_info = ddl_context
+        .table_metadata_manager
+        .view_info_manager()
+        .get(ViewInfoKey::from(&view_name))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!("SELECT 1", view_info.definition);
+
+    // Replaces the view.
+    let mut task = test_create_view_task("foo", "SELECT 2");
+    task.or_replace = true;
+    let mut procedure = CreateViewProcedure::new(task, ddl_context.clone());
+    execute_procedure(&mut procedure).await;
+    let view_info = ddl_context
+        .table_metadata_manager
+        .view_info_manager()
+        .get(ViewInfoKey::from(&view_name))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!("SELECT 2", view_info.definition);
+
+    let task = DropViewTask {
+        view_name: view_name.clone(),
+        drop_if_exists: false,
+    };
+    let mut procedure = DropViewProcedure::new(task, ddl_context.clone());
+    execute_procedure(&mut procedure).await;
+    assert!(!ddl_context
+        .table_metadata_manager
+        .view_info_manager()
+        .exists(ViewInfoKey::from(&view_name))
+        .await
+        .unwrap());
+
+    let task = DropViewTask {
+        view_name,
+        drop_if_exists: false,
+    };
+    let mut procedure = DropViewProcedure::new(task, ddl_context);
+    let err = procedure.on_prepare().await.unwrap_err();
+    assert_matches!(err, Error::ViewNotFound { .. });
+}
+
+#[tokio::test]
+async fn test_on_prepare_view_exists() {
+    let datanode_manager = Arc::new(MockDatanodeManager::new(()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let task = test_create_view_task("foo", "SELECT 1");
+    let mut procedure = CreateViewProcedure::new(task, ddl_context.clone());
+    execute_procedure(&mut procedure).await;
+
+    let task = test_create_view_task("foo", "SELECT 2");
+    let mut procedure = CreateViewProcedure::new(task, ddl_context.clone());
+    let err = procedure.on_prepare().await.unwrap_err();
+    assert_matches!(err, Error::ViewAlreadyExists { .. });
+
+    let mut task = test_create_view_task("foo", "SELECT 2");
+    task.create_if_not_exists = true;
+    let mut procedure = CreateViewProcedure::new(task, ddl_context);
+    let status = procedure.on_prepare().await.unwrap();
+    assert_matches!(status, Status::Done { .. });
+}
+
+#[tokio::test]
+async fn test_on_prepare_table_exists_err() {
+    let datanode_manager = Arc::new(MockDatanodeManager::new(()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let table_task = test_create_physical_table_task("foo");
+    ddl_context
+        .table_metadata_manager
+        .create_table_metadata(
+            table_task.table_info.clone(),
+            TableRouteValue::physical(vec![]),
+            HashMap::new(),
+        )
+        .await
+        .unwrap();
+
+    let mut task = test_create_view_task("foo", "SELECT 1");
+    task.or_replace = true;
+    let mut procedure = CreateViewProcedure::new(task, ddl_context);
+    let err = procedure.on_prepare().await.unwrap_err();
+    assert_matches!(err, Error::TableAlreadyExists { .. });
+}
@@ -19,8 +19,8 @@ use api::v1::region::{region_request, RegionRequest};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
-use common_procedure::{Context as ProcedureContext, Procedure, ProcedureId};
-use common_procedure_test::MockContextProvider;
+use common_procedure::{Context as ProcedureContext, Procedure, ProcedureId, StringKey};
+use common_procedure_test::{execute_procedure_until_done, MockContextProvider};
 use store_api::storage::RegionId;
 use tokio::sync::mpsc;
 
@@ -33,10 +33,13 @@ use crate::ddl::test_util::{
 };
 use crate::ddl::{TableMetadata, TableMetadataAllocatorContext};
 use crate::key::table_route::TableRouteValue;
+use crate::key::view_info::{ViewInfoKey, ViewInfoValue};
 use crate::kv_backend::memory::MemoryKvBackend;
+use crate::lock_key::TableNameLock;
 use crate::peer::Peer;
-use crate::rpc::ddl::DropTableTask;
+use crate::rpc::ddl::{DropDependents, DropTableTask};
 use crate::rpc::router::{Region, RegionRoute};
+use crate::table_name::TableName;
 use crate::test_util::{new_ddl_context, new_ddl_context_with_kv_backend, MockDatanodeManager};
 
 #[tokio::test]
@@ -64,6 +67,7 @@ async fn test_on_prepare_table_not_exists_err() {
         table: "bar".to_string(),
         table_id,
         drop_if_exists: false,
+        dependents: DropDependents::Ignore,
     };
 
     let mut procedure = DropTableProcedure::new(cluster_id, task, ddl_context);
@@ -96,6 +100,7 @@ async fn test_on_prepare_table() {
         table: "bar".to_string(),
         table_id,
         drop_if_exists: true,
+        dependents: DropDependents::Ignore,
     };
 
     // Drop if exists
@@ -108,6 +113,7 @@ async fn test_on_prepare_table() {
         table: table_name.to_string(),
         table_id,
         drop_if_exists: false,
+        dependents: DropDependents::Ignore,
     };
 
     // Drop table
@@ -115,6 +121,28 @@ async fn test_on_prepare_table() {
     procedure.on_prepare().await.unwrap();
 }
 
+#[tokio::test]
+async fn test_lock_key_with_table_name() {
+    let datanode_manager = Arc::new(MockDatanodeManager::new(()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let cluster_id = 1;
+    let task = DropTableTask {
+        catalog: DEFAULT_CATALOG_NAME.to_string(),
+        schema: DEFAULT_SCHEMA_NAME.to_string(),
+        table: "foo".to_string(),
+        table_id: 1024,
+        drop_if_exists: false,
+        dependents: DropDependents::Ignore,
+    };
+
+    let procedure = DropTableProcedure::new(cluster_id, task, ddl_context);
+    let lock_key = procedure.lock_key();
+    // Must conflict with the lock acquired by creating a table with the same name.
+    let expected: StringKey =
+        TableNameLock::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "foo").into();
+    assert!(lock_key.keys_to_lock().any(|key| *key == expected));
+}
+
 #[tokio::test]
 async fn test_on_datanode_drop_regions() {
     let (tx, mut rx) = mpsc::channel(8);
@@ -137,6 +165,7 @@ async fn test_on_datanode_drop_regions() {
                     follower_peers: vec![Peer::empty(5)],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
                 RegionRoute {
                     region: Region::new_test(RegionId::new(table_id, 2)),
@@ -144,6 +173,7 @@ async fn test_on_datanode_drop_regions() {
                     follower_peers: vec![Peer::empty(4)],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
                 RegionRoute {
                     region: Region::new_test(RegionId::new(table_id, 3)),
@@ -151,6 +181,7 @@ async fn test_on_datanode_drop_regions() {
                     follower_peers: vec![],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
             ]),
             HashMap::new(),
@@ -164,6 +195,7 @@ async fn test_on_datanode_drop_regions() {
         table: table_name.to_string(),
         table_id,
         drop_if_exists: false,
+        dependents: DropDependents::Ignore,
     };
     // Drop table
     let mut procedure = DropTableProcedure::new(cluster_id, task, ddl_context);
@@ -253,6 +285,7 @@ async fn test_on_rollback() {
             table: "phy_table".to_string(),
             table_id: physical_table_id,
             drop_if_exists: false,
+            dependents: DropDependents::Ignore,
         };
         let mut procedure = DropTableProcedure::new(cluster_id, task, ddl_context.clone());
         procedure.on_prepare().await.unwrap();
@@ -275,6 +308,7 @@ async fn test_on_rollback() {
         table: "foo".to_string(),
         table_id: table_ids[0],
         drop_if_exists: false,
+        dependents: DropDependents::Ignore,
     };
     let mut procedure = DropTableProcedure::new(cluster_id, task, ddl_context.clone());
     procedure.on_prepare().await.unwrap();
@@ -289,3 +323,74 @@ async fn test_on_rollback() {
     let kvs = kv_backend.dump();
     assert_eq!(kvs, expected_kvs);
 }
+
+#[tokio::test]
+async fn test_drop_table_with_dependent_views() {
+    let datanode_manager = Arc::new(MockDatanodeManager::new(NaiveDatanodeHandler));
+    let kv_backend = Arc::new(MemoryKvBackend::new());
+    let ddl_context = new_ddl_context_with_kv_backend(datanode_manager, kv_backend.clone());
+    let cluster_id = 1;
+    let table_id = 1024;
+    let task = test_create_table_task("foo", table_id);
+    ddl_context
+        .table_metadata_manager
+        .create_table_metadata(
+            task.table_info.clone(),
+            TableRouteValue::physical(vec![]),
+            HashMap::new(),
+        )
+        .await
+        .unwrap();
+    let view_info = ViewInfoValue::new("SELECT * FROM foo".to_string()).with_source_tables(vec![(
+        table_id,
+        TableName::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "foo"),
+    )]);
+    let view_key = ViewInfoKey::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "foo_view");
+    let view_info_manager = ddl_context.table_metadata_manager.view_info_manager();
+    assert!(view_info_manager
+        .create(view_key, &view_info, false)
+        .await
+        .unwrap());
+    let drop_table_task = |dependents| DropTableTask {
+        catalog: DEFAULT_CATALOG_NAME.to_string(),
+        schema: DEFAULT_SCHEMA_NAME.to_string(),
+        table: "foo".to_string(),
+        table_id,
+        drop_if_exists: false,
+        dependents,
+    };
+
+    // Views restrict dropping the table.
+    let mut procedure = DropTableProcedure::new(
+        cluster_id,
+        drop_table_task(DropDependents::Restrict),
+        ddl_context.clone(),
+    );
+    let err = procedure.on_prepare().await.unwrap_err();
+    assert_eq!(err.status_code(), StatusCode::InvalidArguments);
+
+    // Views are deleted with the table, and restored if the procedure rolls back.
+    let expected_kvs = kv_backend.dump();
+    let mut procedure = DropTableProcedure::new(
+        cluster_id,
+        drop_table_task(DropDependents::Cascade),
+        ddl_context.clone(),
+    );
+    procedure.on_prepare().await.unwrap();
+    procedure.on_delete_metadata().await.unwrap();
+    assert!(!view_info_manager.exists(view_key).await.unwrap());
+    let ctx = ProcedureContext {
+        procedure_id: ProcedureId::random(),
+        provider: Arc::new(MockContextProvider::default()),
+    };
+    procedure.rollback(&ctx).await.unwrap();
+    assert_eq!(kv_backend.dump(), expected_kvs);
+
+    let mut procedure = DropTableProcedure::new(
+        cluster_id,
+        drop_table_task(DropDependents::Cascade),
+        ddl_context.clone(),
+    );
+    execute_procedure_until_done(&mut procedure).await;
+    assert!(!view_info_manager.exists(view_key).await.unwrap());
+}
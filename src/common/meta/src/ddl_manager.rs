@@ -29,8 +29,10 @@ use crate::ddl::alter_table::AlterTableProcedure;
 use crate::ddl::create_database::CreateDatabaseProcedure;
 use crate::ddl::create_logical_tables::CreateLogicalTablesProcedure;
 use crate::ddl::create_table::CreateTableProcedure;
+use crate::ddl::create_view::CreateViewProcedure;
 use crate::ddl::drop_database::DropDatabaseProcedure;
 use crate::ddl::drop_table::DropTableProcedure;
+use crate::ddl::drop_view::DropViewProcedure;
 use crate::ddl::table_meta::TableMetadataAllocatorRef;
 use crate::ddl::truncate_table::TruncateTableProcedure;
 use crate::ddl::{utils, DdlContext, ExecutorContext, ProcedureExecutor};
@@ -45,12 +47,12 @@ use crate::key::table_name::TableNameKey;
 use crate::key::{DeserializedValueWithBytes, TableMetadataManagerRef};
 use crate::region_keeper::MemoryRegionKeeperRef;
 use crate::rpc::ddl::DdlTask::{
-    AlterLogicalTables, AlterTable, CreateDatabase, CreateLogicalTables, CreateTable, DropDatabase,
-    DropLogicalTables, DropTable, TruncateTable,
+    AlterLogicalTables, AlterTable, CreateDatabase, CreateLogicalTables, CreateTable, CreateView,
+    DropDatabase, DropLogicalTables, DropTable, DropView, TruncateTable,
 };
 use crate::rpc::ddl::{
-    AlterTableTask, CreateDatabaseTask, CreateTableTask, DropDatabaseTask, DropTableTask,
-    SubmitDdlTaskRequest, SubmitDdlTaskResponse, TruncateTableTask,
+    AlterTableTask, CreateDatabaseTask, CreateTableTask, CreateViewTask, DropDatabaseTask,
+    DropTableTask, DropViewTask, SubmitDdlTaskRequest, SubmitDdlTaskResponse, TruncateTableTask,
 };
 use crate::rpc::procedure;
 use crate::rpc::procedure::{MigrateRegionRequest, MigrateRegionResponse, ProcedureStateResponse};
@@ -188,6 +190,24 @@ impl DdlManager {
                     })
                 },
             ),
+            (
+                CreateViewProcedure::TYPE_NAME,
+                &|context: DdlContext| -> BoxedProcedureLoader {
+                    Box::new(move |json: &str| {
+                        let context = context.clone();
+                        CreateViewProcedure::from_json(json, context).map(|p| Box::new(p) as _)
+                    })
+                },
+            ),
+            (
+                DropViewProcedure::TYPE_NAME,
+                &|context: DdlContext| -> BoxedProcedureLoader {
+                    Box::new(move |json: &str| {
+                        let context = context.clone();
+                        DropViewProcedure::from_json(json, context).map(|p| Box::new(p) as _)
+                    })
+                },
+            ),
         ];
 
         for (type_name, loader_factory) in loaders {
@@ -331,6 +351,34 @@ impl DdlManager {
         self.submit_procedure(procedure_with_id).await
     }
 
+    #[tracing::instrument(skip_all)]
+    /// Submits and executes a create view task.
+    pub async fn submit_create_view_task(
+        &self,
+        _cluster_id: ClusterId,
+        create_view_task: CreateViewTask,
+    ) -> Result<(ProcedureId, Option<Output>)> {
+        let context = self.create_context();
+        let procedure = CreateViewProcedure::new(create_view_task, context);
+        let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
+
+        self.submit_procedure(procedure_with_id).await
+    }
+
+    #[tracing::instrument(skip_all)]
+    /// Submits and executes a drop view task.
+    pub async fn submit_drop_view_task(
+        &self,
+        _cluster_id: ClusterId,
+        drop_view_task: DropViewTask,
+    ) -> Result<(ProcedureId, Option<Output>)> {
+        let context = self.create_context();
+        let procedure = DropViewProcedure::new(drop_view_task, context);
+        let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
+
+        self.submit_procedure(procedure_with_id).await
+    }
+
     #[tracing::instrument(skip_all)]
     /// Submits and executes a truncate table task.
     pub async fn submit_truncate_table_task(
@@ -591,6 +639,44 @@ async fn handle_drop_database_task(
     })
 }
 
+async fn handle_create_view_task(
+    ddl_manager: &DdlManager,
+    cluster_id: ClusterId,
+    create_view_task: CreateViewTask,
+) -> Result<SubmitDdlTaskResponse> {
+    let view_name = create_view_task.view_name.clone();
+    let (id, _) = ddl_manager
+        .submit_create_view_task(cluster_id, create_view_task)
+        .await?;
+
+    let procedure_id = id.to_string();
+    info!("View {view_name} is created via procedure_id {id:?}");
+
+    Ok(SubmitDdlTaskResponse {
+        key: procedure_id.into(),
+        ..Default::default()
+    })
+}
+
+async fn handle_drop_view_task(
+    ddl_manager: &DdlManager,
+    cluster_id: ClusterId,
+    drop_view_task: DropViewTask,
+) -> Result<SubmitDdlTaskResponse> {
+    let view_name = drop_view_task.view_name.clone();
+    let (id, _) = ddl_manager
+        .submit_drop_view_task(cluster_id, drop_view_task)
+        .await?;
+
+    let procedure_id = id.to_string();
+    info!("View {view_name} is dropped via procedure_id {id:?}");
+
+    Ok(SubmitDdlTaskResponse {
+        key: procedure_id.into(),
+        ..Default::default()
+    })
+}
+
 async fn handle_alter_logical_table_tasks(
     ddl_manager: &DdlManager,
     cluster_id: ClusterId,
@@ -630,6 +716,10 @@ async fn handle_alter_logical_table_tasks(
 /// TODO(dennis): let [`DdlManager`] implement [`ProcedureExecutor`] looks weird, find some way to refactor it.
 #[async_trait::async_trait]
 impl ProcedureExecutor for DdlManager {
+    fn supports_view_tasks(&self) -> bool {
+        true
+    }
+
     async fn submit_ddl_task(
         &self,
         ctx: &ExecutorContext,
@@ -670,6 +760,12 @@ impl ProcedureExecutor for DdlManager {
                 DropDatabase(drop_database_task) => {
                     handle_drop_database_task(self, cluster_id, drop_database_task).await
                 }
+                CreateView(create_view_task) => {
+                    handle_create_view_task(self, cluster_id, create_view_task).await
+                }
+                DropView(drop_view_task) => {
+                    handle_drop_view_task(self, cluster_id, drop_view_task).await
+                }
             }
         }
         .trace(span)
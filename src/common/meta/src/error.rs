@@ -241,6 +241,29 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("View already exists, view: {}", view_name))]
+    ViewAlreadyExists {
+        view_name: String,
+        location: Location,
+    },
+
+    #[snafu(display("View not found: '{}'", view_name))]
+    ViewNotFound {
+        view_name: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Cannot drop table `{}` because views depend on it: {}",
+        table,
+        dependents
+    ))]
+    TableHasDependents {
+        table: String,
+        dependents: String,
+        location: Location,
+    },
+
     #[snafu(display("Catalog already exists, catalog: {}", catalog))]
     CatalogAlreadyExists { catalog: String, location: Location },
 
@@ -323,6 +346,12 @@ pub enum Error {
     #[snafu(display("Retry later"))]
     RetryLater { source: BoxedError },
 
+    #[snafu(display("Metasrv is still unavailable after waiting for {:?}", timeout))]
+    MetasrvUnavailable {
+        timeout: std::time::Duration,
+        location: Location,
+    },
+
     #[snafu(display(
         "Failed to encode a wal options to json string, wal_options: {:?}",
         wal_options
@@ -444,7 +473,8 @@ impl ErrorExt for Error {
             | EtcdFailed { .. }
             | EtcdTxnFailed { .. }
             | ConnectEtcd { .. }
-            | CasKeyChanged { .. } => StatusCode::Internal,
+            | CasKeyChanged { .. }
+            | MetasrvUnavailable { .. } => StatusCode::Internal,
 
             SerdeJson { .. }
             | ParseOption { .. }
@@ -494,10 +524,12 @@ impl ErrorExt for Error {
             | EmptyKey { .. }
             | InvalidEngineType { .. }
             | AlterLogicalTablesInvalidArguments { .. }
-            | CreateLogicalTablesInvalidArguments { .. } => StatusCode::InvalidArguments,
+            | CreateLogicalTablesInvalidArguments { .. }
+            | TableHasDependents { .. } => StatusCode::InvalidArguments,
 
             TableNotFound { .. } => StatusCode::TableNotFound,
-            TableAlreadyExists { .. } => StatusCode::TableAlreadyExists,
+            TableAlreadyExists { .. } | ViewAlreadyExists { .. } => StatusCode::TableAlreadyExists,
+            ViewNotFound { .. } => StatusCode::TableNotFound,
 
             SubmitProcedure { source, .. }
             | QueryProcedure { source, .. }
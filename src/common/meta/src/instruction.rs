@@ -149,6 +149,9 @@ pub struct UpgradeRegion {
     /// it's helpful to verify whether the leader region is ready.
     #[serde(with = "humantime_serde")]
     pub wait_for_replay_timeout: Option<Duration>,
+    /// The leader epoch granted to the region once it's upgraded.
+    #[serde(default)]
+    pub leader_epoch: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Display, PartialEq, Eq)]
@@ -156,6 +159,12 @@ pub struct UpgradeRegion {
 pub enum CacheIdent {
     TableId(TableId),
     TableName(TableName),
+    ViewName(TableName),
+    /// The credentials of a user.
+    User(String),
+    /// A privilege key, such as what is granted to a user or a role, without the
+    /// privilege key prefix.
+    Privilege(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Display, PartialEq)]
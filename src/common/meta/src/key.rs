@@ -36,6 +36,16 @@
 //!     - The value is a [TableNameValue] struct; it contains the table id.
 //!     - Used in the table name to table id lookup.
 //!
+//! 6. View info key: `__view_info/{catalog_name}/{schema_name}/{view_name}`
+//!     - The value is a [ViewInfoValue] struct; it contains the SQL definition of the view.
+//!
+//! 7. Privilege key: `__privilege/{user|role}/{name}`
+//!     - The value is a [GranteeValue] struct; it contains the privileges and roles granted to
+//!       the user or role.
+//!
+//! 8. User key: `__user/{user_name}`
+//!     - The value is a [UserValue] struct; it contains the password hashes of the user.
+//!
 //! All keys have related managers. The managers take care of the serialization and deserialization
 //! of keys and values, and the interaction with the underlying KV store backend.
 //!
@@ -45,6 +55,7 @@
 
 pub mod catalog_name;
 pub mod datanode_table;
+pub mod privilege;
 pub mod schema_name;
 pub mod table_info;
 pub mod table_name;
@@ -60,6 +71,8 @@ pub mod test_utils;
 #[allow(dead_code)]
 mod tombstone;
 mod txn_helper;
+pub mod user;
+pub mod view_info;
 
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Debug;
@@ -73,6 +86,7 @@ use common_catalog::consts::{
 use common_telemetry::warn;
 use datanode_table::{DatanodeTableKey, DatanodeTableManager, DatanodeTableValue};
 use lazy_static::lazy_static;
+use privilege::{GranteeKey, GranteeValue, PrivilegeManager};
 use regex::Regex;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -81,6 +95,8 @@ use store_api::storage::RegionNumber;
 use table::metadata::{RawTableInfo, TableId};
 use table_info::{TableInfoKey, TableInfoManager, TableInfoValue};
 use table_name::{TableNameKey, TableNameManager, TableNameValue};
+use user::{UserKey, UserManager, UserValue};
+use view_info::{ViewInfoKey, ViewInfoManager, ViewInfoValue};
 
 use self::catalog_name::{CatalogManager, CatalogNameKey, CatalogNameValue};
 use self::datanode_table::RegionInfo;
@@ -109,6 +125,9 @@ pub const TABLE_NAME_KEY_PREFIX: &str = "__table_name";
 pub const CATALOG_NAME_KEY_PREFIX: &str = "__catalog_name";
 pub const SCHEMA_NAME_KEY_PREFIX: &str = "__schema_name";
 pub const TABLE_ROUTE_PREFIX: &str = "__table_route";
+pub const VIEW_INFO_KEY_PREFIX: &str = "__view_info";
+pub const PRIVILEGE_KEY_PREFIX: &str = "__privilege";
+pub const USER_KEY_PREFIX: &str = "__user";
 
 pub const CACHE_KEY_PREFIXES: [&str; 4] = [
     TABLE_NAME_KEY_PREFIX,
@@ -131,6 +150,14 @@ lazy_static! {
     .unwrap();
 }
 
+lazy_static! {
+    /// VIEW_INFO_KEY: {VIEW_INFO_KEY_PREFIX}/{catalog_name}/{schema_name}/{view_name}
+    static ref VIEW_INFO_KEY_PATTERN: Regex = Regex::new(&format!(
+        "^{VIEW_INFO_KEY_PREFIX}/({NAME_PATTERN})/({NAME_PATTERN})/({NAME_PATTERN})$"
+    ))
+    .unwrap();
+}
+
 lazy_static! {
     /// CATALOG_NAME_KEY: {CATALOG_NAME_KEY_PREFIX}/{catalog_name}
     static ref CATALOG_NAME_KEY_PATTERN: Regex = Regex::new(&format!(
@@ -139,6 +166,12 @@ lazy_static! {
     .unwrap();
 }
 
+lazy_static! {
+    /// USER_KEY: {USER_KEY_PREFIX}/{user_name}
+    static ref USER_KEY_PATTERN: Regex =
+        Regex::new(&format!("^{USER_KEY_PREFIX}/(.+)$")).unwrap();
+}
+
 lazy_static! {
     /// SCHEMA_NAME_KEY: {SCHEMA_NAME_KEY_PREFIX}/{catalog_name}/{schema_name}
     static ref SCHEMA_NAME_KEY_PATTERN:Regex=Regex::new(&format!(
@@ -196,6 +229,9 @@ pub struct TableMetadataManager {
     schema_manager: SchemaManager,
     table_route_manager: TableRouteManager,
     tombstone_manager: TombstoneManager,
+    view_info_manager: ViewInfoManager,
+    privilege_manager: PrivilegeManager,
+    user_manager: UserManager,
     kv_backend: KvBackendRef,
 }
 
@@ -338,6 +374,9 @@ impl TableMetadataManager {
             schema_manager: SchemaManager::new(kv_backend.clone()),
             table_route_manager: TableRouteManager::new(kv_backend.clone()),
             tombstone_manager: TombstoneManager::new(kv_backend.clone()),
+            view_info_manager: ViewInfoManager::new(kv_backend.clone()),
+            privilege_manager: PrivilegeManager::new(kv_backend.clone()),
+            user_manager: UserManager::new(kv_backend.clone()),
             kv_backend,
         }
     }
@@ -386,6 +425,18 @@ impl TableMetadataManager {
         &self.table_route_manager
     }
 
+    pub fn view_info_manager(&self) -> &ViewInfoManager {
+        &self.view_info_manager
+    }
+
+    pub fn privilege_manager(&self) -> &PrivilegeManager {
+        &self.privilege_manager
+    }
+
+    pub fn user_manager(&self) -> &UserManager {
+        &self.user_manager
+    }
+
     #[cfg(feature = "testing")]
     pub fn kv_backend(&self) -> &KvBackendRef {
         &self.kv_backend
@@ -945,7 +996,14 @@ macro_rules! impl_table_meta_key {
     }
 }
 
-impl_table_meta_key!(TableNameKey<'_>, TableInfoKey, DatanodeTableKey);
+impl_table_meta_key!(
+    TableNameKey<'_>,
+    TableInfoKey,
+    DatanodeTableKey,
+    ViewInfoKey<'_>,
+    GranteeKey<'_>,
+    UserKey<'_>
+);
 
 #[macro_export]
 macro_rules! impl_table_meta_value {
@@ -1016,7 +1074,10 @@ macro_rules! impl_optional_meta_value {
 impl_table_meta_value! {
     TableNameValue,
     TableInfoValue,
-    DatanodeTableValue
+    DatanodeTableValue,
+    ViewInfoValue,
+    GranteeValue,
+    UserValue
 }
 
 impl_optional_meta_value! {
@@ -1095,6 +1156,7 @@ mod tests {
             follower_peers: vec![],
             leader_status: None,
             leader_down_since: None,
+            leader_epoch: 0,
         }
     }
 
@@ -1471,6 +1533,7 @@ mod tests {
                 leader_status: Some(RegionStatus::Downgraded),
                 follower_peers: vec![],
                 leader_down_since: Some(current_time_millis()),
+                leader_epoch: 0,
             },
             RegionRoute {
                 region: Region {
@@ -1483,6 +1546,7 @@ mod tests {
                 leader_status: None,
                 follower_peers: vec![],
                 leader_down_since: None,
+                leader_epoch: 0,
             },
         ];
         let table_info: RawTableInfo =
@@ -1698,6 +1762,7 @@ mod tests {
                         follower_peers: vec![Peer::empty(5)],
                         leader_status: None,
                         leader_down_since: None,
+                        leader_epoch: 0,
                     },
                     RegionRoute {
                         region: Region::new_test(RegionId::new(table_id, 2)),
@@ -1705,6 +1770,7 @@ mod tests {
                         follower_peers: vec![Peer::empty(4)],
                         leader_status: None,
                         leader_down_since: None,
+                        leader_epoch: 0,
                     },
                     RegionRoute {
                         region: Region::new_test(RegionId::new(table_id, 3)),
@@ -1712,6 +1778,7 @@ mod tests {
                         follower_peers: vec![],
                         leader_status: None,
                         leader_down_since: None,
+                        leader_epoch: 0,
                     },
                 ]),
                 options,
@@ -1751,6 +1818,7 @@ mod tests {
                         follower_peers: vec![Peer::empty(5)],
                         leader_status: None,
                         leader_down_since: None,
+                        leader_epoch: 0,
                     },
                     RegionRoute {
                         region: Region::new_test(RegionId::new(table_id, 2)),
@@ -1758,6 +1826,7 @@ mod tests {
                         follower_peers: vec![Peer::empty(4)],
                         leader_status: None,
                         leader_down_since: None,
+                        leader_epoch: 0,
                     },
                     RegionRoute {
                         region: Region::new_test(RegionId::new(table_id, 3)),
@@ -1765,6 +1834,7 @@ mod tests {
                         follower_peers: vec![],
                         leader_status: None,
                         leader_down_since: None,
+                        leader_epoch: 0,
                     },
                 ]),
                 options,
@@ -0,0 +1,691 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, UnexpectedSnafu};
+use crate::instruction::CacheIdent;
+use crate::key::{TableMetaKey, TableMetaValue, PRIVILEGE_KEY_PREFIX};
+use crate::kv_backend::KvBackendRef;
+use crate::range_stream::{PaginationStream, DEFAULT_PAGE_SIZE};
+use crate::rpc::store::{CompareAndPutRequest, PutRequest, RangeRequest};
+use crate::rpc::KeyValue;
+
+/// The built-in role whose members have all the privileges, and are the only users
+/// allowed to manage users, roles and grants once privileges are enabled.
+pub const ADMIN_ROLE: &str = "admin";
+
+/// Max times to retry updating the value of a grantee changed concurrently.
+const MAX_UPDATE_RETRIES: usize = 16;
+
+/// A privilege that can be granted to a user or a role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Privilege {
+    Select,
+    Insert,
+    Create,
+    Drop,
+}
+
+impl Privilege {
+    /// All the privileges, what `ALL PRIVILEGES` grants.
+    pub const ALL: [Privilege; 4] = [
+        Privilege::Select,
+        Privilege::Insert,
+        Privilege::Create,
+        Privilege::Drop,
+    ];
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Privilege::Select => write!(f, "SELECT"),
+            Privilege::Insert => write!(f, "INSERT"),
+            Privilege::Create => write!(f, "CREATE"),
+            Privilege::Drop => write!(f, "DROP"),
+        }
+    }
+}
+
+impl FromStr for Privilege {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "SELECT" => Ok(Privilege::Select),
+            "INSERT" => Ok(Privilege::Insert),
+            "CREATE" => Ok(Privilege::Create),
+            "DROP" => Ok(Privilege::Drop),
+            _ => Err(format!("unknown privilege: {s}")),
+        }
+    }
+}
+
+/// The object a privilege is granted on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrantObject {
+    Catalog {
+        catalog: String,
+    },
+    Schema {
+        catalog: String,
+        schema: String,
+    },
+    Table {
+        catalog: String,
+        schema: String,
+        table: String,
+    },
+}
+
+impl GrantObject {
+    pub fn catalog(catalog: impl Into<String>) -> Self {
+        Self::Catalog {
+            catalog: catalog.into(),
+        }
+    }
+
+    pub fn schema(catalog: impl Into<String>, schema: impl Into<String>) -> Self {
+        Self::Schema {
+            catalog: catalog.into(),
+            schema: schema.into(),
+        }
+    }
+
+    pub fn table(
+        catalog: impl Into<String>,
+        schema: impl Into<String>,
+        table: impl Into<String>,
+    ) -> Self {
+        Self::Table {
+            catalog: catalog.into(),
+            schema: schema.into(),
+            table: table.into(),
+        }
+    }
+
+    /// Returns true if the privileges granted on this object apply to `target`,
+    /// e.g. privileges on a schema apply to all the tables in the schema.
+    pub fn covers(&self, target: &GrantObject) -> bool {
+        let (catalog, schema, table) = target.parts();
+        match self {
+            GrantObject::Catalog { catalog: c } => c == catalog,
+            GrantObject::Schema {
+                catalog: c,
+                schema: s,
+            } => c == catalog && Some(s.as_str()) == schema,
+            GrantObject::Table {
+                catalog: c,
+                schema: s,
+                table: t,
+            } => c == catalog && Some(s.as_str()) == schema && Some(t.as_str()) == table,
+        }
+    }
+
+    fn parts(&self) -> (&str, Option<&str>, Option<&str>) {
+        match self {
+            GrantObject::Catalog { catalog } => (catalog, None, None),
+            GrantObject::Schema { catalog, schema } => (catalog, Some(schema), None),
+            GrantObject::Table {
+                catalog,
+                schema,
+                table,
+            } => (catalog, Some(schema), Some(table)),
+        }
+    }
+}
+
+impl Display for GrantObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrantObject::Catalog { catalog } => write!(f, "CATALOG {catalog}"),
+            GrantObject::Schema { catalog, schema } => write!(f, "DATABASE {catalog}.{schema}"),
+            GrantObject::Table {
+                catalog,
+                schema,
+                table,
+            } => write!(f, "TABLE {catalog}.{schema}.{table}"),
+        }
+    }
+}
+
+/// A privilege granted on an object.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PrivilegeGrant {
+    pub privilege: Privilege,
+    pub object: GrantObject,
+}
+
+impl PrivilegeGrant {
+    pub fn new(privilege: Privilege, object: GrantObject) -> Self {
+        Self { privilege, object }
+    }
+}
+
+/// Whether a grantee is a user or a role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GranteeKind {
+    User,
+    Role,
+}
+
+impl Display for GranteeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GranteeKind::User => write!(f, "user"),
+            GranteeKind::Role => write!(f, "role"),
+        }
+    }
+}
+
+/// The key of the privileges granted to a user or a role: `__privilege/{user|role}/{name}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GranteeKey<'a> {
+    pub kind: GranteeKind,
+    pub name: &'a str,
+}
+
+impl<'a> GranteeKey<'a> {
+    pub fn user(name: &'a str) -> Self {
+        Self {
+            kind: GranteeKind::User,
+            name,
+        }
+    }
+
+    pub fn role(name: &'a str) -> Self {
+        Self {
+            kind: GranteeKind::Role,
+            name,
+        }
+    }
+}
+
+impl TableMetaKey for GranteeKey<'_> {
+    fn as_raw_key(&self) -> Vec<u8> {
+        format!("{}/{}/{}", PRIVILEGE_KEY_PREFIX, self.kind, self.name).into_bytes()
+    }
+}
+
+/// The privileges and roles granted to a user or a role.
+///
+/// A role exists iff its value exists, users only have values once anything is granted to them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GranteeValue {
+    #[serde(default)]
+    pub grants: Vec<PrivilegeGrant>,
+    /// Roles granted to a user, always empty for roles.
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+impl GranteeValue {
+    /// Returns true if any of the grants allows `privilege` on `object`.
+    pub fn allows(&self, privilege: Privilege, object: &GrantObject) -> bool {
+        self.grants
+            .iter()
+            .any(|grant| grant.privilege == privilege && grant.object.covers(object))
+    }
+}
+
+/// Manages roles and the privileges granted to users and roles.
+///
+/// Privileges are enabled the first time the [ADMIN_ROLE] is granted to a user.
+/// Before that, all the users have all the privileges. After that, users only have
+/// the privileges granted to them or their roles, and members of the [ADMIN_ROLE]
+/// have all the privileges.
+#[derive(Clone)]
+pub struct PrivilegeManager {
+    kv_backend: KvBackendRef,
+}
+
+impl PrivilegeManager {
+    pub fn new(kv_backend: KvBackendRef) -> Self {
+        Self { kv_backend }
+    }
+
+    /// Creates a role, returns false if the role already exists.
+    pub async fn create_role(&self, role: &str) -> Result<bool> {
+        if role == ADMIN_ROLE {
+            return Ok(false);
+        }
+        let raw_key = GranteeKey::role(role).as_raw_key();
+        let raw_value = GranteeValue::default().try_as_raw_value()?;
+
+        self.kv_backend
+            .put_conditionally(raw_key, raw_value, true)
+            .await
+    }
+
+    /// Drops a role, returns false if the role doesn't exist.
+    ///
+    /// Users granted the role lose its privileges, the role names remaining in
+    /// their values are ignored.
+    pub async fn drop_role(&self, role: &str) -> Result<bool> {
+        let raw_key = GranteeKey::role(role).as_raw_key();
+        let prev = self.kv_backend.delete(&raw_key, true).await?;
+
+        Ok(prev.is_some())
+    }
+
+    /// Removes everything granted to a dropped user.
+    pub async fn drop_user(&self, user: &str) -> Result<()> {
+        let raw_key = GranteeKey::user(user).as_raw_key();
+        let _ = self.kv_backend.delete(&raw_key, false).await?;
+
+        Ok(())
+    }
+
+    pub async fn role_exists(&self, role: &str) -> Result<bool> {
+        if role == ADMIN_ROLE {
+            return Ok(true);
+        }
+        let raw_key = GranteeKey::role(role).as_raw_key();
+        self.kv_backend.exists(&raw_key).await
+    }
+
+    pub async fn get(&self, key: GranteeKey<'_>) -> Result<Option<GranteeValue>> {
+        let raw_key = key.as_raw_key();
+        self.kv_backend
+            .get(&raw_key)
+            .await?
+            .map(|x| GranteeValue::try_from_raw_value(&x.value))
+            .transpose()
+    }
+
+    /// Updates the value of `grantee` by `f`, which returns false if it changes
+    /// nothing. The value is compared and put, so concurrent updates of the same
+    /// grantee are retried on the latest value instead of being lost.
+    async fn update<F>(&self, grantee: GranteeKey<'_>, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut GranteeValue) -> bool,
+    {
+        let raw_key = grantee.as_raw_key();
+        for _ in 0..MAX_UPDATE_RETRIES {
+            let (expect, mut value) = match self.kv_backend.get(&raw_key).await? {
+                Some(kv) => {
+                    let value = GranteeValue::try_from_raw_value(&kv.value)?;
+                    (kv.value, value)
+                }
+                None => (vec![], GranteeValue::default()),
+            };
+            if !f(&mut value) {
+                return Ok(());
+            }
+
+            let req = CompareAndPutRequest::new()
+                .with_key(raw_key.clone())
+                .with_expect(expect)
+                .with_value(value.try_as_raw_value()?);
+            if self.kv_backend.compare_and_put(req).await?.success {
+                return Ok(());
+            }
+        }
+
+        UnexpectedSnafu {
+            err_msg: format!(
+                "Failed to update privileges of {} {} after {} retries",
+                grantee.kind, grantee.name, MAX_UPDATE_RETRIES
+            ),
+        }
+        .fail()
+    }
+
+    /// Grants privileges to `grantee`, the grants already present are skipped.
+    pub async fn grant(&self, grantee: GranteeKey<'_>, grants: Vec<PrivilegeGrant>) -> Result<()> {
+        self.update(grantee, |value| {
+            let len = value.grants.len();
+            for grant in &grants {
+                if !value.grants.contains(grant) {
+                    value.grants.push(grant.clone());
+                }
+            }
+            value.grants.len() != len
+        })
+        .await
+    }
+
+    /// Revokes privileges from `grantee`, the grants not present are skipped.
+    pub async fn revoke(&self, grantee: GranteeKey<'_>, grants: &[PrivilegeGrant]) -> Result<()> {
+        self.update(grantee, |value| {
+            let len = value.grants.len();
+            value.grants.retain(|grant| !grants.contains(grant));
+            value.grants.len() != len
+        })
+        .await
+    }
+
+    /// Grants `role` to `user`, granting the [ADMIN_ROLE] enables privileges.
+    pub async fn grant_role(&self, user: &str, role: &str) -> Result<()> {
+        self.update(GranteeKey::user(user), |value| {
+            if value.roles.iter().any(|r| r == role) {
+                return false;
+            }
+            value.roles.push(role.to_string());
+            true
+        })
+        .await?;
+        if role == ADMIN_ROLE {
+            let req = PutRequest::new()
+                .with_key(enabled_key())
+                .with_value(Vec::new());
+            let _ = self.kv_backend.put(req).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Revokes `role` from `user`.
+    pub async fn revoke_role(&self, user: &str, role: &str) -> Result<()> {
+        self.update(GranteeKey::user(user), |value| {
+            let len = value.roles.len();
+            value.roles.retain(|r| r != role);
+            value.roles.len() != len
+        })
+        .await
+    }
+
+    /// Returns true if privileges are enabled, i.e. the [ADMIN_ROLE] was ever granted.
+    pub async fn is_enabled(&self) -> Result<bool> {
+        self.kv_backend.exists(&enabled_key()).await
+    }
+
+    /// Returns true if `user` is a member of the [ADMIN_ROLE].
+    pub async fn is_admin(&self, user: &str) -> Result<bool> {
+        Ok(self
+            .get(GranteeKey::user(user))
+            .await?
+            .is_some_and(|value| value.roles.iter().any(|role| role == ADMIN_ROLE)))
+    }
+
+    /// Returns true if `user` may manage users, roles and grants, which is any
+    /// user before privileges are enabled, and members of the [ADMIN_ROLE] after.
+    pub async fn can_manage(&self, user: &str) -> Result<bool> {
+        Ok(!self.is_enabled().await? || self.is_admin(user).await?)
+    }
+
+    /// Returns the names of the members of the [ADMIN_ROLE].
+    pub async fn admins(&self) -> Result<Vec<String>> {
+        Ok(self
+            .grantees(GranteeKind::User)
+            .await?
+            .into_iter()
+            .filter(|(_, value)| value.roles.iter().any(|role| role == ADMIN_ROLE))
+            .map(|(name, _)| name)
+            .collect())
+    }
+
+    /// Returns the users or roles of `kind` with what is granted to them, ordered by
+    /// their names. Users nothing was ever granted to are absent, and the built-in
+    /// [ADMIN_ROLE] is never listed as a role.
+    pub async fn grantees(&self, kind: GranteeKind) -> Result<Vec<(String, GranteeValue)>> {
+        let prefix = GranteeKey { kind, name: "" }.as_raw_key();
+        let prefix_len = prefix.len();
+        let req = RangeRequest::new().with_prefix(prefix);
+        let stream = PaginationStream::new(
+            self.kv_backend.clone(),
+            req,
+            DEFAULT_PAGE_SIZE,
+            Arc::new(move |kv: KeyValue| -> Result<(String, GranteeValue)> {
+                let name = String::from_utf8_lossy(&kv.key()[prefix_len..]).to_string();
+                let value = GranteeValue::try_from_raw_value(kv.value())?;
+                Ok((name, value))
+            }),
+        );
+
+        stream.try_collect().await
+    }
+
+    /// Checks whether `user` has `privilege` on `object`, directly or by its roles.
+    ///
+    /// All the users have all the privileges before privileges are enabled. After
+    /// that, users nothing is granted to have no privileges.
+    pub async fn check(
+        &self,
+        user: &str,
+        privilege: Privilege,
+        object: &GrantObject,
+    ) -> Result<bool> {
+        if !self.is_enabled().await? {
+            return Ok(true);
+        }
+        let Some(value) = self.get(GranteeKey::user(user)).await? else {
+            return Ok(false);
+        };
+        if value.roles.iter().any(|role| role == ADMIN_ROLE) || value.allows(privilege, object) {
+            return Ok(true);
+        }
+
+        for role in &value.roles {
+            if let Some(role_value) = self.get(GranteeKey::role(role)).await? {
+                if role_value.allows(privilege, object) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// Returns the [CacheIdent] of a privilege key.
+///
+/// Privileges are written through the KV store instead of procedures, so metasrv
+/// broadcasts the invalidation when it stores them. Otherwise other frontends would
+/// keep using revoked privileges until their caches expire.
+pub fn cache_ident(raw_key: &[u8]) -> Option<CacheIdent> {
+    let key = std::str::from_utf8(raw_key).ok()?;
+    let key = key.strip_prefix(PRIVILEGE_KEY_PREFIX)?.strip_prefix('/')?;
+
+    Some(CacheIdent::Privilege(key.to_string()))
+}
+
+/// The key marking privileges are enabled: `__privilege/enabled`
+fn enabled_key() -> Vec<u8> {
+    format!("{PRIVILEGE_KEY_PREFIX}/enabled").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::kv_backend::memory::MemoryKvBackend;
+
+    #[test]
+    fn test_serde() {
+        let key = GranteeKey::user("alice");
+        assert_eq!(b"__privilege/user/alice", key.as_raw_key().as_slice());
+        let key = GranteeKey::role("reader");
+        assert_eq!(b"__privilege/role/reader", key.as_raw_key().as_slice());
+
+        let value = GranteeValue {
+            grants: vec![PrivilegeGrant::new(
+                Privilege::Select,
+                GrantObject::schema("greptime", "public"),
+            )],
+            roles: vec!["reader".to_string()],
+        };
+        let raw_value = value.try_as_raw_value().unwrap();
+        assert_eq!(GranteeValue::try_from_raw_value(&raw_value).unwrap(), value);
+
+        assert_eq!(
+            Some(CacheIdent::Privilege("user/alice".to_string())),
+            cache_ident(&GranteeKey::user("alice").as_raw_key())
+        );
+        assert_eq!(
+            Some(CacheIdent::Privilege("enabled".to_string())),
+            cache_ident(&enabled_key())
+        );
+        assert_eq!(None, cache_ident(b"__user/alice"));
+
+        assert_eq!(Privilege::Insert, "insert".parse().unwrap());
+        assert!("alter".parse::<Privilege>().is_err());
+    }
+
+    #[test]
+    fn test_grant_object_covers() {
+        let table = GrantObject::table("greptime", "public", "foo");
+        assert!(GrantObject::catalog("greptime").covers(&table));
+        assert!(GrantObject::schema("greptime", "public").covers(&table));
+        assert!(table.covers(&table));
+        assert!(!GrantObject::schema("greptime", "other").covers(&table));
+        assert!(!GrantObject::table("greptime", "public", "bar").covers(&table));
+
+        let schema = GrantObject::schema("greptime", "public");
+        assert!(GrantObject::catalog("greptime").covers(&schema));
+        assert!(!table.covers(&schema));
+        assert!(!GrantObject::catalog("other").covers(&schema));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_update() {
+        let manager = PrivilegeManager::new(Arc::new(MemoryKvBackend::default()));
+        let grantee = GranteeKey::user("alice");
+        let select = PrivilegeGrant::new(Privilege::Select, GrantObject::catalog("greptime"));
+        let insert = PrivilegeGrant::new(Privilege::Insert, GrantObject::catalog("greptime"));
+        manager.grant(grantee, vec![select.clone()]).await.unwrap();
+
+        // Another statement revokes `select` between reading and writing the value.
+        let mut attempts = 0;
+        manager
+            .update(grantee, |value| {
+                attempts += 1;
+                if attempts == 1 {
+                    futures::executor::block_on(manager.revoke(grantee, &[select.clone()]))
+                        .unwrap();
+                }
+                value.grants.push(insert.clone());
+                true
+            })
+            .await
+            .unwrap();
+        assert_eq!(2, attempts);
+        let value = manager.get(grantee).await.unwrap().unwrap();
+        assert_eq!(vec![insert], value.grants);
+    }
+
+    #[tokio::test]
+    async fn test_privilege_manager() {
+        let manager = PrivilegeManager::new(Arc::new(MemoryKvBackend::default()));
+        let table = GrantObject::table("greptime", "public", "foo");
+
+        // All the users have all the privileges before privileges are enabled.
+        assert!(!manager.is_enabled().await.unwrap());
+        assert!(manager.can_manage("alice").await.unwrap());
+        assert!(manager
+            .check("alice", Privilege::Drop, &table)
+            .await
+            .unwrap());
+
+        // Granting the admin role enables privileges.
+        assert!(manager.role_exists(ADMIN_ROLE).await.unwrap());
+        assert!(!manager.create_role(ADMIN_ROLE).await.unwrap());
+        manager.grant_role("root", ADMIN_ROLE).await.unwrap();
+        assert!(manager.is_enabled().await.unwrap());
+        assert_eq!(vec!["root".to_string()], manager.admins().await.unwrap());
+        assert!(manager.can_manage("root").await.unwrap());
+        assert!(manager
+            .check("root", Privilege::Drop, &table)
+            .await
+            .unwrap());
+        // Users without grants are denied.
+        assert!(!manager.can_manage("alice").await.unwrap());
+        assert!(!manager
+            .check("alice", Privilege::Select, &table)
+            .await
+            .unwrap());
+
+        assert!(manager.create_role("reader").await.unwrap());
+        assert!(!manager.create_role("reader").await.unwrap());
+        assert!(manager.role_exists("reader").await.unwrap());
+        manager
+            .grant(
+                GranteeKey::role("reader"),
+                vec![PrivilegeGrant::new(
+                    Privilege::Select,
+                    GrantObject::schema("greptime", "public"),
+                )],
+            )
+            .await
+            .unwrap();
+
+        manager
+            .grant(
+                GranteeKey::user("alice"),
+                vec![PrivilegeGrant::new(Privilege::Insert, table.clone())],
+            )
+            .await
+            .unwrap();
+        assert!(!manager.can_manage("alice").await.unwrap());
+        assert!(manager
+            .check("alice", Privilege::Insert, &table)
+            .await
+            .unwrap());
+        assert!(!manager
+            .check("alice", Privilege::Select, &table)
+            .await
+            .unwrap());
+
+        manager.grant_role("alice", "reader").await.unwrap();
+        assert!(manager
+            .check("alice", Privilege::Select, &table)
+            .await
+            .unwrap());
+        let roles = manager.grantees(GranteeKind::Role).await.unwrap();
+        assert_eq!(
+            vec!["reader"],
+            roles.iter().map(|(r, _)| r).collect::<Vec<_>>()
+        );
+        let users = manager.grantees(GranteeKind::User).await.unwrap();
+        assert_eq!(
+            vec!["alice", "root"],
+            users.iter().map(|(u, _)| u).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["reader".to_string()], users[0].1.roles);
+
+        manager.revoke_role("alice", "reader").await.unwrap();
+        assert!(!manager
+            .check("alice", Privilege::Select, &table)
+            .await
+            .unwrap());
+
+        manager.grant_role("alice", "reader").await.unwrap();
+        assert!(manager.drop_role("reader").await.unwrap());
+        assert!(!manager.drop_role("reader").await.unwrap());
+        assert!(!manager
+            .check("alice", Privilege::Select, &table)
+            .await
+            .unwrap());
+
+        manager
+            .revoke(
+                GranteeKey::user("alice"),
+                &[PrivilegeGrant::new(Privilege::Insert, table.clone())],
+            )
+            .await
+            .unwrap();
+        assert!(!manager
+            .check("alice", Privilege::Insert, &table)
+            .await
+            .unwrap());
+    }
+}
@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use common_base::readable_size::ReadableSize;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use futures::stream::BoxStream;
 use futures::StreamExt;
@@ -32,6 +34,14 @@ use crate::rpc::store::RangeRequest;
 use crate::rpc::KeyValue;
 
 const OPT_KEY_TTL: &str = "ttl";
+const OPT_KEY_AUTO_CREATE_TABLE_PREFIX: &str = "auto_create_table.";
+const OPT_KEY_ENGINE: &str = "engine";
+const OPT_KEY_NAMING: &str = "naming";
+const OPT_KEY_TABLE_OPTIONS_PREFIX: &str = "options.";
+const OPT_KEY_STRICT_INGESTION: &str = "strict_ingestion";
+const OPT_KEY_QUOTA_MAX_CONCURRENT_QUERIES: &str = "quota.max_concurrent_queries";
+const OPT_KEY_QUOTA_MAX_SCAN_BYTES: &str = "quota.max_scan_bytes";
+const OPT_KEY_QUOTA_MAX_INGEST_ROWS_PER_SEC: &str = "quota.max_ingest_rows_per_sec";
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SchemaNameKey<'a> {
@@ -53,6 +63,92 @@ pub struct SchemaNameValue {
     #[serde(default)]
     #[serde(with = "humantime_serde")]
     pub ttl: Option<Duration>,
+    /// Policies of creating tables automatically on writes, by protocol.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub auto_create_table: HashMap<IngestProtocol, AutoCreateTablePolicy>,
+    /// Protocols whose writes are rejected if their field types conflict with
+    /// the types of existing columns, instead of being coerced.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub strict_ingestion: HashSet<IngestProtocol>,
+    /// Resource quotas of the database.
+    #[serde(default, skip_serializing_if = "DatabaseQuota::is_unlimited")]
+    pub quota: DatabaseQuota,
+}
+
+impl SchemaNameValue {
+    /// Returns the auto creation policy of `protocol`, or the default policy if
+    /// it's not configured.
+    pub fn auto_create_table_policy(&self, protocol: IngestProtocol) -> AutoCreateTablePolicy {
+        self.auto_create_table
+            .get(&protocol)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns whether writes of `protocol` are validated strictly against the
+    /// types of existing columns.
+    pub fn is_strict_ingestion(&self, protocol: IngestProtocol) -> bool {
+        self.strict_ingestion.contains(&protocol)
+    }
+
+    /// Returns the database options in `WITH` that create a database with the same
+    /// value, the reverse of converting options to the value.
+    pub fn to_options(&self) -> BTreeMap<String, String> {
+        let mut options = BTreeMap::new();
+        if let Some(ttl) = self.ttl {
+            let _ = options.insert(
+                OPT_KEY_TTL.to_string(),
+                humantime::format_duration(ttl).to_string(),
+            );
+        }
+        for (protocol, policy) in &self.auto_create_table {
+            let prefix = format!("{OPT_KEY_AUTO_CREATE_TABLE_PREFIX}{protocol}");
+            let _ = options.insert(prefix.clone(), policy.enabled.to_string());
+            if let Some(engine) = &policy.engine {
+                let _ = options.insert(format!("{prefix}.{OPT_KEY_ENGINE}"), engine.clone());
+            }
+            let _ = options.insert(
+                format!("{prefix}.{OPT_KEY_NAMING}"),
+                policy.naming.to_string(),
+            );
+            for (key, value) in &policy.table_options {
+                let _ = options.insert(
+                    format!("{prefix}.{OPT_KEY_TABLE_OPTIONS_PREFIX}{key}"),
+                    value.clone(),
+                );
+            }
+        }
+        if !self.strict_ingestion.is_empty() {
+            let mut protocols = self
+                .strict_ingestion
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>();
+            protocols.sort();
+            let _ = options.insert(OPT_KEY_STRICT_INGESTION.to_string(), protocols.join(","));
+        }
+        let quota = &self.quota;
+        if let Some(queries) = quota.max_concurrent_queries {
+            let _ = options.insert(
+                OPT_KEY_QUOTA_MAX_CONCURRENT_QUERIES.to_string(),
+                queries.to_string(),
+            );
+        }
+        if let Some(bytes) = quota.max_scan_bytes {
+            // In bytes, the readable format is rounded.
+            let _ = options.insert(
+                OPT_KEY_QUOTA_MAX_SCAN_BYTES.to_string(),
+                bytes.0.to_string(),
+            );
+        }
+        if let Some(rows) = quota.max_ingest_rows_per_sec {
+            let _ = options.insert(
+                OPT_KEY_QUOTA_MAX_INGEST_ROWS_PER_SEC.to_string(),
+                rows.to_string(),
+            );
+        }
+        options
+    }
 }
 
 impl TryFrom<&HashMap<String, String>> for SchemaNameValue {
@@ -72,7 +168,244 @@ impl TryFrom<&HashMap<String, String>> for SchemaNameValue {
             })
             .transpose()?
             .map(|ttl| ttl.into());
-        Ok(Self { ttl })
+
+        let mut auto_create_table: HashMap<IngestProtocol, AutoCreateTablePolicy> = HashMap::new();
+        for (key, val) in value {
+            let Some(rest) = key.strip_prefix(OPT_KEY_AUTO_CREATE_TABLE_PREFIX) else {
+                continue;
+            };
+            let invalid_option = || {
+                ParseOptionSnafu {
+                    key: key.clone(),
+                    value: val.clone(),
+                }
+                .build()
+            };
+            let (protocol, field) = match rest.split_once('.') {
+                Some((protocol, field)) => (protocol, Some(field)),
+                None => (rest, None),
+            };
+            let protocol = protocol
+                .parse::<IngestProtocol>()
+                .map_err(|_| invalid_option())?;
+            let policy = auto_create_table.entry(protocol).or_default();
+            match field {
+                None => policy.enabled = val.parse().map_err(|_| invalid_option())?,
+                Some(OPT_KEY_ENGINE) => policy.engine = Some(val.clone()),
+                Some(OPT_KEY_NAMING) => {
+                    policy.naming = val.parse().map_err(|_| invalid_option())?
+                }
+                Some(field) => {
+                    let table_option = field
+                        .strip_prefix(OPT_KEY_TABLE_OPTIONS_PREFIX)
+                        .filter(|k| !k.is_empty())
+                        .with_context(|| ParseOptionSnafu {
+                            key: key.clone(),
+                            value: val.clone(),
+                        })?;
+                    let _ = policy
+                        .table_options
+                        .insert(table_option.to_string(), val.clone());
+                }
+            }
+        }
+
+        let strict_ingestion = value
+            .get(OPT_KEY_STRICT_INGESTION)
+            .map(|protocols| {
+                protocols
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| {
+                        p.parse::<IngestProtocol>().map_err(|_| {
+                            ParseOptionSnafu {
+                                key: OPT_KEY_STRICT_INGESTION,
+                                value: protocols.clone(),
+                            }
+                            .build()
+                        })
+                    })
+                    .collect::<Result<HashSet<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let quota = DatabaseQuota {
+            max_concurrent_queries: parse_option(value, OPT_KEY_QUOTA_MAX_CONCURRENT_QUERIES)?,
+            max_scan_bytes: parse_option(value, OPT_KEY_QUOTA_MAX_SCAN_BYTES)?,
+            max_ingest_rows_per_sec: parse_option(value, OPT_KEY_QUOTA_MAX_INGEST_ROWS_PER_SEC)?,
+        };
+
+        Ok(Self {
+            ttl,
+            auto_create_table,
+            strict_ingestion,
+            quota,
+        })
+    }
+}
+
+/// Parses the option of `key` in `options` if it's present.
+fn parse_option<T: FromStr>(options: &HashMap<String, String>, key: &str) -> Result<Option<T>> {
+    options
+        .get(key)
+        .map(|value| {
+            value.parse().map_err(|_| {
+                ParseOptionSnafu {
+                    key,
+                    value: value.clone(),
+                }
+                .build()
+            })
+        })
+        .transpose()
+}
+
+/// Resource quotas of a database, enforced by each frontend. A quota is unlimited if
+/// it's absent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseQuota {
+    /// Max number of queries running concurrently in the database.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_queries: Option<usize>,
+    /// Max bytes a query scans in regions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_scan_bytes: Option<ReadableSize>,
+    /// Max rows ingested into the database per second.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ingest_rows_per_sec: Option<u64>,
+}
+
+impl DatabaseQuota {
+    /// Returns true if none of the quotas is set.
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The protocols that are able to create tables automatically on writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IngestProtocol {
+    Influxdb,
+    Prometheus,
+    Opentsdb,
+}
+
+impl FromStr for IngestProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "influxdb" => Ok(Self::Influxdb),
+            "prometheus" => Ok(Self::Prometheus),
+            "opentsdb" => Ok(Self::Opentsdb),
+            _ => Err(format!("unknown protocol: {s}")),
+        }
+    }
+}
+
+impl Display for IngestProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Influxdb => write!(f, "influxdb"),
+            Self::Prometheus => write!(f, "prometheus"),
+            Self::Opentsdb => write!(f, "opentsdb"),
+        }
+    }
+}
+
+/// How tables are created automatically for writes of a protocol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoCreateTablePolicy {
+    /// Whether missing tables are created, writes to missing tables fail if not.
+    pub enabled: bool,
+    /// The engine of created tables, the default engine is used if absent.
+    pub engine: Option<String>,
+    /// Options of created tables.
+    pub table_options: HashMap<String, String>,
+    /// How table names from the protocol are converted.
+    pub naming: TableNamingRule,
+}
+
+impl Default for AutoCreateTablePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            engine: None,
+            table_options: HashMap::new(),
+            naming: TableNamingRule::default(),
+        }
+    }
+}
+
+/// The rule to convert table names from the protocol to the names of tables.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableNamingRule {
+    /// Keeps the names as they are.
+    #[default]
+    Keep,
+    /// Converts the names to lowercase.
+    Lowercase,
+    /// Replaces characters that are not allowed in table names with `_`.
+    Sanitize,
+}
+
+impl TableNamingRule {
+    /// Converts the `name` by the rule.
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            TableNamingRule::Keep => name.to_string(),
+            TableNamingRule::Lowercase => name.to_lowercase(),
+            TableNamingRule::Sanitize => {
+                let mut sanitized = name
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '-' | '.') {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>();
+                // Table names must not start with a digit or a dot.
+                if sanitized
+                    .chars()
+                    .next()
+                    .map_or(true, |c| c.is_ascii_digit() || c == '.')
+                {
+                    sanitized.insert(0, '_');
+                }
+                sanitized
+            }
+        }
+    }
+}
+
+impl FromStr for TableNamingRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "keep" => Ok(Self::Keep),
+            "lowercase" => Ok(Self::Lowercase),
+            "sanitize" => Ok(Self::Sanitize),
+            _ => Err(format!("unknown naming rule: {s}")),
+        }
+    }
+}
+
+impl Display for TableNamingRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keep => write!(f, "keep"),
+            Self::Lowercase => write!(f, "lowercase"),
+            Self::Sanitize => write!(f, "sanitize"),
+        }
     }
 }
 
@@ -213,6 +546,7 @@ mod tests {
 
         let value = SchemaNameValue {
             ttl: Some(Duration::from_secs(10)),
+            ..Default::default()
         };
         let mut opts: HashMap<String, String> = HashMap::new();
         opts.insert("ttl".to_string(), "10s".to_string());
@@ -227,6 +561,162 @@ mod tests {
         assert!(err_empty.is_err());
     }
 
+    #[test]
+    fn test_auto_create_table_options() {
+        let mut opts: HashMap<String, String> = HashMap::new();
+        opts.insert(
+            "auto_create_table.influxdb".to_string(),
+            "false".to_string(),
+        );
+        opts.insert(
+            "auto_create_table.prometheus.engine".to_string(),
+            "metric".to_string(),
+        );
+        opts.insert(
+            "auto_create_table.prometheus.naming".to_string(),
+            "sanitize".to_string(),
+        );
+        opts.insert(
+            "auto_create_table.prometheus.options.ttl".to_string(),
+            "7d".to_string(),
+        );
+        let value = SchemaNameValue::try_from(&opts).unwrap();
+        assert!(value.ttl.is_none());
+
+        let influxdb = value.auto_create_table_policy(IngestProtocol::Influxdb);
+        assert!(!influxdb.enabled);
+        assert!(influxdb.engine.is_none());
+
+        let prometheus = value.auto_create_table_policy(IngestProtocol::Prometheus);
+        assert!(prometheus.enabled);
+        assert_eq!(Some("metric"), prometheus.engine.as_deref());
+        assert_eq!(TableNamingRule::Sanitize, prometheus.naming);
+        assert_eq!(Some(&"7d".to_string()), prometheus.table_options.get("ttl"));
+
+        assert_eq!(
+            AutoCreateTablePolicy::default(),
+            value.auto_create_table_policy(IngestProtocol::Opentsdb)
+        );
+
+        let raw = value.try_as_raw_value().unwrap();
+        let parsed = SchemaNameValue::try_from_raw_value(&raw).unwrap();
+        assert_eq!(Some(value), parsed);
+
+        for (key, val) in [
+            ("auto_create_table.unknown", "true"),
+            ("auto_create_table.influxdb", "yes"),
+            ("auto_create_table.influxdb.naming", "upper"),
+            ("auto_create_table.influxdb.unknown", "x"),
+            ("auto_create_table.influxdb.options.", "x"),
+        ] {
+            let mut opts: HashMap<String, String> = HashMap::new();
+            opts.insert(key.to_string(), val.to_string());
+            assert!(SchemaNameValue::try_from(&opts).is_err(), "{key}={val}");
+        }
+    }
+
+    #[test]
+    fn test_strict_ingestion_options() {
+        let value = SchemaNameValue::try_from(&HashMap::new()).unwrap();
+        assert!(!value.is_strict_ingestion(IngestProtocol::Influxdb));
+
+        let mut opts: HashMap<String, String> = HashMap::new();
+        opts.insert(
+            "strict_ingestion".to_string(),
+            "InfluxDB, prometheus".to_string(),
+        );
+        let value = SchemaNameValue::try_from(&opts).unwrap();
+        assert!(value.is_strict_ingestion(IngestProtocol::Influxdb));
+        assert!(value.is_strict_ingestion(IngestProtocol::Prometheus));
+        assert!(!value.is_strict_ingestion(IngestProtocol::Opentsdb));
+
+        let raw = value.try_as_raw_value().unwrap();
+        let parsed = SchemaNameValue::try_from_raw_value(&raw).unwrap();
+        assert_eq!(Some(value), parsed);
+
+        let mut opts: HashMap<String, String> = HashMap::new();
+        opts.insert("strict_ingestion".to_string(), "influxdb,mysql".to_string());
+        assert!(SchemaNameValue::try_from(&opts).is_err());
+    }
+
+    #[test]
+    fn test_quota_options() {
+        let value = SchemaNameValue::try_from(&HashMap::new()).unwrap();
+        assert!(value.quota.is_unlimited());
+
+        let mut opts: HashMap<String, String> = HashMap::new();
+        opts.insert("quota.max_concurrent_queries".to_string(), "8".to_string());
+        opts.insert("quota.max_scan_bytes".to_string(), "1GB".to_string());
+        opts.insert(
+            "quota.max_ingest_rows_per_sec".to_string(),
+            "10000".to_string(),
+        );
+        let value = SchemaNameValue::try_from(&opts).unwrap();
+        assert_eq!(
+            DatabaseQuota {
+                max_concurrent_queries: Some(8),
+                max_scan_bytes: Some(ReadableSize::gb(1)),
+                max_ingest_rows_per_sec: Some(10000),
+            },
+            value.quota
+        );
+
+        let raw = value.try_as_raw_value().unwrap();
+        let parsed = SchemaNameValue::try_from_raw_value(&raw).unwrap();
+        assert_eq!(Some(value), parsed);
+
+        for (key, val) in [
+            ("quota.max_concurrent_queries", "-1"),
+            ("quota.max_scan_bytes", "many"),
+            ("quota.max_ingest_rows_per_sec", "1k"),
+        ] {
+            let mut opts: HashMap<String, String> = HashMap::new();
+            opts.insert(key.to_string(), val.to_string());
+            assert!(SchemaNameValue::try_from(&opts).is_err(), "{key}={val}");
+        }
+    }
+
+    #[test]
+    fn test_to_options() {
+        let value = SchemaNameValue::default();
+        assert!(value.to_options().is_empty());
+
+        let opts: HashMap<String, String> = [
+            ("ttl", "1day 2h"),
+            ("auto_create_table.influxdb", "false"),
+            ("auto_create_table.prometheus.engine", "metric"),
+            ("auto_create_table.prometheus.options.ttl", "7d"),
+            ("strict_ingestion", "prometheus,influxdb"),
+            ("quota.max_concurrent_queries", "8"),
+            ("quota.max_scan_bytes", "1.5GB"),
+            ("quota.max_ingest_rows_per_sec", "10000"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let value = SchemaNameValue::try_from(&opts).unwrap();
+        let options = value.to_options();
+        assert_eq!("1day 2h", options["ttl"]);
+        assert_eq!("influxdb,prometheus", options["strict_ingestion"]);
+        assert_eq!("keep", options["auto_create_table.prometheus.naming"]);
+
+        let options: HashMap<_, _> = options.into_iter().collect();
+        assert_eq!(value, SchemaNameValue::try_from(&options).unwrap());
+    }
+
+    #[test]
+    fn test_table_naming_rule() {
+        assert_eq!("Cpu Load", TableNamingRule::Keep.apply("Cpu Load"));
+        assert_eq!("cpu load", TableNamingRule::Lowercase.apply("Cpu Load"));
+        assert_eq!("Cpu_Load", TableNamingRule::Sanitize.apply("Cpu Load"));
+        assert_eq!(
+            "node:cpu.usage",
+            TableNamingRule::Sanitize.apply("node:cpu.usage")
+        );
+        assert_eq!("_1m_load", TableNamingRule::Sanitize.apply("1m/load"));
+        assert_eq!("_", TableNamingRule::Sanitize.apply(""));
+    }
+
     #[tokio::test]
     async fn test_key_exist() {
         let manager = SchemaManager::new(Arc::new(MemoryKvBackend::default()));
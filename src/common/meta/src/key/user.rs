@@ -0,0 +1,230 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Error, InvalidTableMetadataSnafu, Result};
+use crate::instruction::CacheIdent;
+use crate::key::{TableMetaKey, TableMetaValue, USER_KEY_PATTERN, USER_KEY_PREFIX};
+use crate::kv_backend::KvBackendRef;
+use crate::range_stream::{PaginationStream, DEFAULT_PAGE_SIZE};
+use crate::rpc::store::{PutRequest, RangeRequest};
+use crate::rpc::KeyValue;
+
+/// The key of a user: `__user/{name}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserKey<'a> {
+    pub name: &'a str,
+}
+
+impl<'a> UserKey<'a> {
+    pub fn new(name: &'a str) -> Self {
+        Self { name }
+    }
+
+    /// Returns the cache of frontends that writing the user key `raw_key` makes
+    /// stale, or `None` if it's not a user key.
+    ///
+    /// Users are written through the KV store instead of procedures, so metasrv
+    /// broadcasts the invalidation when it stores them.
+    pub fn cache_ident(raw_key: &[u8]) -> Option<CacheIdent> {
+        let key = std::str::from_utf8(raw_key).ok()?;
+        let key = UserKey::try_from(key).ok()?;
+
+        Some(CacheIdent::User(key.name.to_string()))
+    }
+}
+
+impl TableMetaKey for UserKey<'_> {
+    fn as_raw_key(&self) -> Vec<u8> {
+        format!("{}/{}", USER_KEY_PREFIX, self.name).into_bytes()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for UserKey<'a> {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> Result<Self> {
+        let captures = USER_KEY_PATTERN
+            .captures(s)
+            .context(InvalidTableMetadataSnafu {
+                err_msg: format!("Illegal UserKey format: '{s}'"),
+            })?;
+
+        // Safety: pass the regex check above
+        Ok(Self {
+            name: captures.get(1).unwrap().as_str(),
+        })
+    }
+}
+
+/// The credentials of a user, passwords are never stored in plaintext.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserValue {
+    /// The salted password hash in the PHC string format.
+    pub password_hash: String,
+    /// `SHA1(SHA1(password))` in hex, which is required to verify the MySQL
+    /// native password authentication. It's unsalted, so it's only stored for
+    /// users created `IDENTIFIED WITH mysql_native_password`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mysql_native_password: Option<String>,
+}
+
+/// Manages the users created by SQL.
+pub struct UserManager {
+    kv_backend: KvBackendRef,
+}
+
+impl UserManager {
+    pub fn new(kv_backend: KvBackendRef) -> Self {
+        Self { kv_backend }
+    }
+
+    /// Creates a user, returns false if the user already exists.
+    pub async fn create(&self, name: &str, value: &UserValue) -> Result<bool> {
+        let raw_key = UserKey::new(name).as_raw_key();
+        let raw_value = value.try_as_raw_value()?;
+
+        self.kv_backend
+            .put_conditionally(raw_key, raw_value, true)
+            .await
+    }
+
+    /// Updates the credentials of a user, returns false if the user doesn't exist.
+    pub async fn update(&self, name: &str, value: &UserValue) -> Result<bool> {
+        let raw_key = UserKey::new(name).as_raw_key();
+        if !self.kv_backend.exists(&raw_key).await? {
+            return Ok(false);
+        }
+
+        let req = PutRequest::new()
+            .with_key(raw_key)
+            .with_value(value.try_as_raw_value()?);
+        let _ = self.kv_backend.put(req).await?;
+
+        Ok(true)
+    }
+
+    /// Drops a user, returns false if the user doesn't exist.
+    pub async fn delete(&self, name: &str) -> Result<bool> {
+        let raw_key = UserKey::new(name).as_raw_key();
+        let prev = self.kv_backend.delete(&raw_key, true).await?;
+
+        Ok(prev.is_some())
+    }
+
+    pub async fn get(&self, name: &str) -> Result<Option<UserValue>> {
+        let raw_key = UserKey::new(name).as_raw_key();
+        self.kv_backend
+            .get(&raw_key)
+            .await?
+            .map(|x| UserValue::try_from_raw_value(&x.value))
+            .transpose()
+    }
+
+    /// Returns all the users with their credentials, ordered by their names.
+    pub async fn users(&self) -> Result<Vec<(String, UserValue)>> {
+        let req = RangeRequest::new().with_prefix(format!("{USER_KEY_PREFIX}/").into_bytes());
+        let stream = PaginationStream::new(
+            self.kv_backend.clone(),
+            req,
+            DEFAULT_PAGE_SIZE,
+            Arc::new(user_decoder),
+        );
+
+        stream.try_collect().await
+    }
+}
+
+/// Decodes `KeyValue` to ({user_name}, {value})
+fn user_decoder(kv: KeyValue) -> Result<(String, UserValue)> {
+    let str = std::str::from_utf8(&kv.key).context(error::ConvertRawKeySnafu)?;
+    let key = UserKey::try_from(str)?;
+    let value = UserValue::try_from_raw_value(&kv.value)?;
+
+    Ok((key.name.to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::kv_backend::memory::MemoryKvBackend;
+
+    fn user_value(password_hash: &str) -> UserValue {
+        UserValue {
+            password_hash: password_hash.to_string(),
+            mysql_native_password: None,
+        }
+    }
+
+    #[test]
+    fn test_serde() {
+        let key = UserKey::new("alice");
+        assert_eq!(b"__user/alice", key.as_raw_key().as_slice());
+        assert_eq!("__user/alice", key.to_string());
+        assert_eq!(key, UserKey::try_from("__user/alice").unwrap());
+        assert!(UserKey::try_from("__user/").is_err());
+        assert!(UserKey::try_from("__users/alice").is_err());
+        assert_eq!(
+            Some(CacheIdent::User("alice".to_string())),
+            UserKey::cache_ident(&key.as_raw_key())
+        );
+        assert_eq!(None, UserKey::cache_ident(b"__privilege/user/alice"));
+
+        let mut value = user_value("$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA");
+        let raw_value = value.try_as_raw_value().unwrap();
+        assert!(!String::from_utf8(raw_value.clone())
+            .unwrap()
+            .contains("mysql_native_password"));
+        assert_eq!(UserValue::try_from_raw_value(&raw_value).unwrap(), value);
+
+        value.mysql_native_password = Some("6bb4837eb74329105ee4568dda7dc67ed2ca2ad9".to_string());
+        let raw_value = value.try_as_raw_value().unwrap();
+        assert_eq!(UserValue::try_from_raw_value(&raw_value).unwrap(), value);
+    }
+
+    #[tokio::test]
+    async fn test_user_manager() {
+        let manager = UserManager::new(Arc::new(MemoryKvBackend::default()));
+
+        assert!(manager.get("alice").await.unwrap().is_none());
+        assert!(!manager.update("alice", &user_value("a")).await.unwrap());
+
+        assert!(manager.users().await.unwrap().is_empty());
+        assert!(manager.create("alice", &user_value("a")).await.unwrap());
+        assert!(!manager.create("alice", &user_value("b")).await.unwrap());
+        assert!(manager.create("bob", &user_value("a")).await.unwrap());
+        assert_eq!(
+            vec![
+                ("alice".to_string(), user_value("a")),
+                ("bob".to_string(), user_value("a")),
+            ],
+            manager.users().await.unwrap()
+        );
+        assert!(manager.delete("bob").await.unwrap());
+        assert_eq!(Some(user_value("a")), manager.get("alice").await.unwrap());
+
+        assert!(manager.update("alice", &user_value("b")).await.unwrap());
+        assert_eq!(Some(user_value("b")), manager.get("alice").await.unwrap());
+
+        assert!(manager.delete("alice").await.unwrap());
+        assert!(!manager.delete("alice").await.unwrap());
+        assert!(manager.get("alice").await.unwrap().is_none());
+    }
+}
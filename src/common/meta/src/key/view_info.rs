@@ -0,0 +1,449 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use snafu::OptionExt;
+use table::metadata::TableId;
+
+use crate::error::{Error, InvalidTableMetadataSnafu, Result};
+use crate::key::{TableMetaKey, TableMetaValue, VIEW_INFO_KEY_PATTERN, VIEW_INFO_KEY_PREFIX};
+use crate::kv_backend::KvBackendRef;
+use crate::range_stream::{PaginationStream, DEFAULT_PAGE_SIZE};
+use crate::rpc::store::RangeRequest;
+use crate::rpc::KeyValue;
+use crate::table_name::TableName;
+
+/// The key of a view definition: `__view_info/{catalog}/{schema}/{view}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewInfoKey<'a> {
+    pub catalog: &'a str,
+    pub schema: &'a str,
+    pub view: &'a str,
+}
+
+impl<'a> ViewInfoKey<'a> {
+    pub fn new(catalog: &'a str, schema: &'a str, view: &'a str) -> Self {
+        Self {
+            catalog,
+            schema,
+            view,
+        }
+    }
+
+    pub fn prefix_to_view(catalog: &str, schema: &str) -> String {
+        format!("{}/{}/{}/", VIEW_INFO_KEY_PREFIX, catalog, schema)
+    }
+}
+
+impl TableMetaKey for ViewInfoKey<'_> {
+    fn as_raw_key(&self) -> Vec<u8> {
+        format!(
+            "{}{}",
+            Self::prefix_to_view(self.catalog, self.schema),
+            self.view
+        )
+        .into_bytes()
+    }
+}
+
+impl<'a> From<&'a TableName> for ViewInfoKey<'a> {
+    fn from(value: &'a TableName) -> Self {
+        Self {
+            catalog: &value.catalog_name,
+            schema: &value.schema_name,
+            view: &value.table_name,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ViewInfoKey<'a> {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> Result<Self> {
+        let captures = VIEW_INFO_KEY_PATTERN
+            .captures(s)
+            .context(InvalidTableMetadataSnafu {
+                err_msg: format!("Illegal ViewInfoKey format: '{s}'"),
+            })?;
+        // Safety: pass the regex check above
+        Ok(Self {
+            catalog: captures.get(1).unwrap().as_str(),
+            schema: captures.get(2).unwrap().as_str(),
+            view: captures.get(3).unwrap().as_str(),
+        })
+    }
+}
+
+/// The definition of a view.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewInfoValue {
+    /// The SQL query that defines the view.
+    pub definition: String,
+    pub created_on: DateTime<Utc>,
+    /// Whether the results of the view are stored in a sink table of the same name.
+    #[serde(default)]
+    pub materialized: bool,
+    /// Tables the view reads from, with names at the time the view is bound to them.
+    #[serde(default)]
+    pub source_tables: Vec<TableName>,
+    /// Ids of tables in `source_tables`. The view depends on the tables by ids so it
+    /// can be rebound to a table after renaming it.
+    #[serde(default)]
+    pub source_table_ids: Vec<TableId>,
+    /// The table storing the results of a materialized view, `None` for other views.
+    #[serde(default)]
+    pub sink_table: Option<TableName>,
+    /// When the results of a materialized view are computed.
+    #[serde(default)]
+    pub refreshed_on: Option<DateTime<Utc>>,
+}
+
+impl ViewInfoValue {
+    pub fn new(definition: String) -> Self {
+        Self {
+            definition,
+            created_on: Utc::now(),
+            materialized: false,
+            source_tables: Vec::new(),
+            source_table_ids: Vec::new(),
+            sink_table: None,
+            refreshed_on: None,
+        }
+    }
+
+    /// Creates a materialized view whose results are computed into `sink_table` now.
+    pub fn new_materialized(definition: String, sink_table: TableName) -> Self {
+        Self {
+            materialized: true,
+            sink_table: Some(sink_table),
+            refreshed_on: Some(Utc::now()),
+            ..Self::new(definition)
+        }
+    }
+
+    /// Returns the query to plan when reading the view, which reads the sink table
+    /// of a materialized view.
+    pub fn query(&self) -> String {
+        match &self.sink_table {
+            Some(sink_table) => format!(
+                "SELECT * FROM `{}`.`{}`.`{}`",
+                sink_table.catalog_name, sink_table.schema_name, sink_table.table_name
+            ),
+            None => self.definition.clone(),
+        }
+    }
+
+    pub fn with_source_tables(mut self, source_tables: Vec<(TableId, TableName)>) -> Self {
+        (self.source_table_ids, self.source_tables) = source_tables.into_iter().unzip();
+        self
+    }
+
+    /// Returns true if the view reads from the table.
+    ///
+    /// Views created before binding tables by ids are matched by the table name.
+    pub fn depends_on(&self, table_id: TableId, table: &TableName) -> bool {
+        if self.source_table_ids.is_empty() {
+            self.source_tables.contains(table)
+        } else {
+            self.source_table_ids.contains(&table_id)
+        }
+    }
+
+    /// Updates the name of the source table `table_id`.
+    pub fn rename_source_table(&mut self, table_id: TableId, new_name: TableName) {
+        if let Some(pos) = self.source_table_ids.iter().position(|id| *id == table_id) {
+            self.source_tables[pos] = new_name;
+        }
+    }
+}
+
+/// Decodes `KeyValue` to ({view_name}, ViewInfoValue)
+pub fn view_decoder(kv: KeyValue) -> Result<(String, ViewInfoValue)> {
+    let key = std::str::from_utf8(kv.key()).map_err(|e| {
+        InvalidTableMetadataSnafu {
+            err_msg: format!(
+                "ViewInfoKey '{}' is not a valid UTF8 string: {e}",
+                String::from_utf8_lossy(kv.key())
+            ),
+        }
+        .build()
+    })?;
+    let view_name = ViewInfoKey::try_from(key)?.view.to_string();
+    let value = ViewInfoValue::try_from_raw_value(&kv.value)?;
+
+    Ok((view_name, value))
+}
+
+pub struct ViewInfoManager {
+    kv_backend: KvBackendRef,
+}
+
+impl ViewInfoManager {
+    pub fn new(kv_backend: KvBackendRef) -> Self {
+        Self { kv_backend }
+    }
+
+    /// Creates a view, overwrites the existing one if `or_replace` is true.
+    ///
+    /// Returns false if the view already exists and `or_replace` is false.
+    pub async fn create(
+        &self,
+        key: ViewInfoKey<'_>,
+        value: &ViewInfoValue,
+        or_replace: bool,
+    ) -> Result<bool> {
+        let raw_key = key.as_raw_key();
+        let raw_value = value.try_as_raw_value()?;
+
+        self.kv_backend
+            .put_conditionally(raw_key, raw_value, !or_replace)
+            .await
+    }
+
+    pub async fn get(&self, key: ViewInfoKey<'_>) -> Result<Option<ViewInfoValue>> {
+        let raw_key = key.as_raw_key();
+        self.kv_backend
+            .get(&raw_key)
+            .await?
+            .map(|x| ViewInfoValue::try_from_raw_value(&x.value))
+            .transpose()
+    }
+
+    pub async fn exists(&self, key: ViewInfoKey<'_>) -> Result<bool> {
+        let raw_key = key.as_raw_key();
+        self.kv_backend.exists(&raw_key).await
+    }
+
+    /// Deletes a view, returns false if the view doesn't exist.
+    pub async fn delete(&self, key: ViewInfoKey<'_>) -> Result<bool> {
+        let raw_key = key.as_raw_key();
+        let prev = self.kv_backend.delete(&raw_key, true).await?;
+
+        Ok(prev.is_some())
+    }
+
+    /// Returns a view stream, it lists all views belong to the target `catalog` and `schema`.
+    pub fn views(
+        &self,
+        catalog: &str,
+        schema: &str,
+    ) -> BoxStream<'static, Result<(String, ViewInfoValue)>> {
+        let key = ViewInfoKey::prefix_to_view(catalog, schema).into_bytes();
+        let req = RangeRequest::new().with_prefix(key);
+
+        let stream = PaginationStream::new(
+            self.kv_backend.clone(),
+            req,
+            DEFAULT_PAGE_SIZE,
+            Arc::new(view_decoder),
+        );
+
+        Box::pin(stream)
+    }
+
+    /// Returns views in the `catalog` of the `table` that read from the `table`.
+    pub async fn dependent_views(
+        &self,
+        table_id: TableId,
+        table: &TableName,
+    ) -> Result<Vec<(TableName, ViewInfoValue)>> {
+        let views = self.catalog_views(&table.catalog_name).await?;
+        Ok(views
+            .into_iter()
+            .filter(|(_, value)| value.depends_on(table_id, table))
+            .collect())
+    }
+
+    /// Returns views in the `catalog` created before the tables they read from
+    /// are recorded.
+    pub async fn unbound_views(&self, catalog: &str) -> Result<Vec<(TableName, ViewInfoValue)>> {
+        let views = self.catalog_views(catalog).await?;
+        Ok(views
+            .into_iter()
+            .filter(|(_, value)| value.source_tables.is_empty())
+            .collect())
+    }
+
+    async fn catalog_views(&self, catalog: &str) -> Result<Vec<(TableName, ViewInfoValue)>> {
+        let key = format!("{}/{}/", VIEW_INFO_KEY_PREFIX, catalog).into_bytes();
+        let req = RangeRequest::new().with_prefix(key);
+
+        let stream = PaginationStream::new(
+            self.kv_backend.clone(),
+            req,
+            DEFAULT_PAGE_SIZE,
+            Arc::new(dependent_view_decoder),
+        );
+
+        stream.try_collect::<Vec<_>>().await
+    }
+}
+
+/// Decodes `KeyValue` to (view name, ViewInfoValue)
+fn dependent_view_decoder(kv: KeyValue) -> Result<(TableName, ViewInfoValue)> {
+    let key = std::str::from_utf8(kv.key()).map_err(|e| {
+        InvalidTableMetadataSnafu {
+            err_msg: format!(
+                "ViewInfoKey '{}' is not a valid UTF8 string: {e}",
+                String::from_utf8_lossy(kv.key())
+            ),
+        }
+        .build()
+    })?;
+    let key = ViewInfoKey::try_from(key)?;
+    let view_name = TableName::new(key.catalog, key.schema, key.view);
+    let value = ViewInfoValue::try_from_raw_value(&kv.value)?;
+
+    Ok((view_name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kv_backend::memory::MemoryKvBackend;
+
+    #[test]
+    fn test_serde() {
+        let key = ViewInfoKey::new("my_catalog", "my_schema", "my_view");
+        let raw_key = key.as_raw_key();
+        assert_eq!(
+            b"__view_info/my_catalog/my_schema/my_view",
+            raw_key.as_slice()
+        );
+        let parsed = ViewInfoKey::try_from("__view_info/my_catalog/my_schema/my_view").unwrap();
+        assert_eq!(key, parsed);
+        assert!(ViewInfoKey::try_from("__view_info/my_catalog/my_view").is_err());
+
+        let value = ViewInfoValue::new("SELECT 1".to_string());
+        let raw_value = value.try_as_raw_value().unwrap();
+        assert_eq!(
+            ViewInfoValue::try_from_raw_value(&raw_value).unwrap(),
+            value
+        );
+
+        let sink_table = TableName::new("my_catalog", "my_schema", "__mv_my_view__1");
+        let value = ViewInfoValue::new_materialized("SELECT 1".to_string(), sink_table);
+        assert!(value.materialized);
+        assert_eq!(
+            "SELECT * FROM `my_catalog`.`my_schema`.`__mv_my_view__1`",
+            value.query()
+        );
+        let raw_value = value.try_as_raw_value().unwrap();
+        assert_eq!(
+            ViewInfoValue::try_from_raw_value(&raw_value).unwrap(),
+            value
+        );
+
+        // Views created before materialized views are supported.
+        let value = ViewInfoValue::try_from_raw_value(
+            br#"{"definition":"SELECT 1","created_on":"2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(!value.materialized);
+        assert_eq!("SELECT 1", value.query());
+    }
+
+    #[tokio::test]
+    async fn test_view_info_manager() {
+        let manager = ViewInfoManager::new(Arc::new(MemoryKvBackend::default()));
+        let key = ViewInfoKey::new("my_catalog", "my_schema", "my_view");
+        let value = ViewInfoValue::new("SELECT 1".to_string());
+
+        assert!(manager.create(key, &value, false).await.unwrap());
+        assert!(manager.exists(key).await.unwrap());
+
+        let new_value = ViewInfoValue::new("SELECT 2".to_string());
+        assert!(!manager.create(key, &new_value, false).await.unwrap());
+        assert_eq!(manager.get(key).await.unwrap(), Some(value));
+        assert!(manager.create(key, &new_value, true).await.unwrap());
+        assert_eq!(manager.get(key).await.unwrap(), Some(new_value.clone()));
+
+        let other = ViewInfoKey::new("my_catalog", "other_schema", "my_view");
+        manager.create(other, &new_value, false).await.unwrap();
+        let views = manager
+            .views("my_catalog", "my_schema")
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(views, vec![("my_view".to_string(), new_value)]);
+
+        assert!(manager.delete(key).await.unwrap());
+        assert!(!manager.delete(key).await.unwrap());
+        assert!(manager.get(key).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dependent_views() {
+        let manager = ViewInfoManager::new(Arc::new(MemoryKvBackend::default()));
+        let source = TableName::new("my_catalog", "my_schema", "my_table");
+        let other = TableName::new("my_catalog", "my_schema", "other_table");
+
+        let value = ViewInfoValue::new("SELECT * FROM my_table".to_string())
+            .with_source_tables(vec![(1024, source.clone())]);
+        let key = ViewInfoKey::new("my_catalog", "my_schema", "my_view");
+        manager.create(key, &value, false).await.unwrap();
+        let key = ViewInfoKey::new("my_catalog", "other_schema", "other_view");
+        let other_value = ViewInfoValue::new("SELECT * FROM other_table".to_string())
+            .with_source_tables(vec![(1025, other)]);
+        manager.create(key, &other_value, false).await.unwrap();
+
+        let views = manager.dependent_views(1024, &source).await.unwrap();
+        assert_eq!(
+            views,
+            vec![(
+                TableName::new("my_catalog", "my_schema", "my_view"),
+                value.clone()
+            )]
+        );
+        // Views depend on tables by ids.
+        let renamed = TableName::new("my_catalog", "my_schema", "renamed_table");
+        let views = manager.dependent_views(1024, &renamed).await.unwrap();
+        assert_eq!(views.len(), 1);
+        let unknown = TableName::new("my_catalog", "my_schema", "unknown");
+        assert!(manager
+            .dependent_views(1026, &unknown)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Views without ids are matched by names.
+        let legacy = ViewInfoValue {
+            source_table_ids: vec![],
+            ..value
+        };
+        assert!(legacy.depends_on(1026, &source));
+        assert!(!legacy.depends_on(1024, &renamed));
+
+        assert!(manager
+            .unbound_views("my_catalog")
+            .await
+            .unwrap()
+            .is_empty());
+        let key = ViewInfoKey::new("my_catalog", "my_schema", "unbound_view");
+        let unbound = ViewInfoValue::new("SELECT * FROM my_table".to_string());
+        manager.create(key, &unbound, false).await.unwrap();
+        assert_eq!(
+            manager.unbound_views("my_catalog").await.unwrap(),
+            vec![(
+                TableName::new("my_catalog", "my_schema", "unbound_view"),
+                unbound
+            )]
+        );
+    }
+}
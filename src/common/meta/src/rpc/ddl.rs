@@ -37,6 +37,7 @@ use table::metadata::{RawTableInfo, TableId};
 use table::table_reference::TableReference;
 
 use crate::error::{self, Result};
+use crate::key::view_info::ViewInfoValue;
 use crate::table_name::TableName;
 
 #[derive(Debug, Clone)]
@@ -50,6 +51,8 @@ pub enum DdlTask {
     AlterLogicalTables(Vec<AlterTableTask>),
     CreateDatabase(CreateDatabaseTask),
     DropDatabase(DropDatabaseTask),
+    CreateView(CreateViewTask),
+    DropView(DropViewTask),
 }
 
 impl DdlTask {
@@ -85,6 +88,7 @@ impl DdlTask {
         table: String,
         table_id: TableId,
         drop_if_exists: bool,
+        dependents: DropDependents,
     ) -> Self {
         DdlTask::DropTable(DropTableTask {
             catalog,
@@ -92,6 +96,7 @@ impl DdlTask {
             table,
             table_id,
             drop_if_exists,
+            dependents,
         })
     }
 
@@ -117,6 +122,27 @@ impl DdlTask {
         })
     }
 
+    pub fn new_create_view(
+        view_name: TableName,
+        view_info: ViewInfoValue,
+        create_if_not_exists: bool,
+        or_replace: bool,
+    ) -> Self {
+        DdlTask::CreateView(CreateViewTask {
+            view_name,
+            view_info,
+            create_if_not_exists,
+            or_replace,
+        })
+    }
+
+    pub fn new_drop_view(view_name: TableName, drop_if_exists: bool) -> Self {
+        DdlTask::DropView(DropViewTask {
+            view_name,
+            drop_if_exists,
+        })
+    }
+
     pub fn new_alter_table(alter_table: AlterExpr) -> Self {
         DdlTask::AlterTable(AlterTableTask { alter_table })
     }
@@ -225,6 +251,14 @@ impl TryFrom<SubmitDdlTaskRequest> for PbDdlTaskRequest {
             }
             DdlTask::CreateDatabase(task) => Task::CreateDatabaseTask(task.try_into()?),
             DdlTask::DropDatabase(task) => Task::DropDatabaseTask(task.try_into()?),
+            // The protocol has no view tasks yet, frontends of a remote metasrv write
+            // the view metadata themselves, see `ProcedureExecutor::supports_view_tasks`.
+            DdlTask::CreateView(_) | DdlTask::DropView(_) => {
+                return error::UnsupportedSnafu {
+                    operation: "submitting view ddl tasks to metasrv",
+                }
+                .fail()
+            }
         };
 
         Ok(Self {
@@ -283,6 +317,20 @@ pub struct DropTableTask {
     pub table_id: TableId,
     #[serde(default)]
     pub drop_if_exists: bool,
+    #[serde(default)]
+    pub dependents: DropDependents,
+}
+
+/// How to drop views depending on a table when dropping the table.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum DropDependents {
+    /// Leaves the views, e.g. when dropping the sink table of a materialized view.
+    #[default]
+    Ignore,
+    /// Fails if any view depends on the table.
+    Restrict,
+    /// Drops the views, and the sink tables of materialized views among them.
+    Cascade,
 }
 
 impl DropTableTask {
@@ -322,6 +370,9 @@ impl TryFrom<PbDropTableTask> for DropTableTask {
                 })?
                 .id,
             drop_if_exists: drop_table.drop_if_exists,
+            // The message doesn't carry how to drop the views, frontends of a remote
+            // metasrv drop them before submitting the task.
+            dependents: DropDependents::Ignore,
         })
     }
 }
@@ -720,6 +771,20 @@ impl TryFrom<DropDatabaseTask> for PbDropDatabaseTask {
     }
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct CreateViewTask {
+    pub view_name: TableName,
+    pub view_info: ViewInfoValue,
+    pub create_if_not_exists: bool,
+    pub or_replace: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct DropViewTask {
+    pub view_name: TableName,
+    pub drop_if_exists: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -207,6 +207,7 @@ impl TableRoute {
                 follower_peers,
                 leader_status: None,
                 leader_down_since: None,
+                leader_epoch: 0,
             });
         }
 
@@ -265,6 +266,12 @@ pub struct RegionRoute {
     #[serde(default)]
     #[builder(default = "self.default_leader_down_since()")]
     pub leader_down_since: Option<i64>,
+    /// The epoch of the leader, which is bumped each time the leader changes.
+    ///
+    /// It's a fencing token of writes, a datanode rejects writes routed by a stale route.
+    #[serde(default)]
+    #[builder(default)]
+    pub leader_epoch: u64,
 }
 
 impl RegionRouteBuilder {
@@ -286,6 +293,14 @@ pub enum RegionStatus {
     /// - The [Region] is unavailable(e.g., Crashed, Network disconnected).
     /// - The [Region] was planned to migrate to another [Peer].
     Downgraded,
+    /// The leader [Region] only serves reads, e.g., it's the target of a one-way replication.
+    ///
+    /// Meta Server renews its lease as a follower, so the datanode rejects writes to it.
+    ReadOnly,
+    /// The [Region] is going to be closed.
+    ///
+    /// Meta Server stops renewing its lease, so the datanode closes it.
+    Closing,
 }
 
 impl RegionRoute {
@@ -300,6 +315,16 @@ impl RegionRoute {
         matches!(self.leader_status, Some(RegionStatus::Downgraded))
     }
 
+    /// Returns true if the Leader [Region] only serves reads.
+    pub fn is_leader_read_only(&self) -> bool {
+        matches!(self.leader_status, Some(RegionStatus::ReadOnly))
+    }
+
+    /// Returns true if the [Region] is going to be closed.
+    pub fn is_closing(&self) -> bool {
+        matches!(self.leader_status, Some(RegionStatus::Closing))
+    }
+
     /// Marks the Leader [Region] as downgraded.
     ///
     /// We should downgrade a [Region] before deactivating it:
@@ -479,6 +504,7 @@ mod tests {
             follower_peers: vec![Peer::new(2, "a2"), Peer::new(3, "a3")],
             leader_status: None,
             leader_down_since: None,
+            leader_epoch: 0,
         };
 
         assert!(!region_route.is_leader_downgraded());
@@ -488,6 +514,39 @@ mod tests {
         assert!(region_route.is_leader_downgraded());
     }
 
+    #[test]
+    fn test_leader_read_only_and_closing() {
+        let mut region_route = RegionRoute {
+            region: Region {
+                id: 2.into(),
+                name: "r2".to_string(),
+                partition: None,
+                attrs: BTreeMap::new(),
+            },
+            leader_peer: Some(Peer::new(1, "a1")),
+            follower_peers: vec![],
+            leader_status: None,
+            leader_down_since: None,
+            leader_epoch: 0,
+        };
+
+        assert!(region_route.set_leader_status(Some(RegionStatus::ReadOnly)));
+        assert!(region_route.is_leader_read_only());
+        assert!(!region_route.is_leader_downgraded());
+        assert!(region_route.leader_down_since.is_none());
+
+        assert!(region_route.set_leader_status(Some(RegionStatus::Closing)));
+        assert!(region_route.is_closing());
+        assert!(!region_route.is_leader_read_only());
+
+        let encoded = serde_json::to_string(&region_route).unwrap();
+        let decoded: RegionRoute = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(decoded, region_route);
+
+        assert!(region_route.set_leader_status(None));
+        assert!(!region_route.is_closing());
+    }
+
     #[test]
     fn test_region_route_decode() {
         let region_route = RegionRoute {
@@ -501,6 +560,7 @@ mod tests {
             follower_peers: vec![Peer::new(2, "a2"), Peer::new(3, "a3")],
             leader_status: None,
             leader_down_since: None,
+            leader_epoch: 0,
         };
 
         let input = r#"{"region":{"id":2,"name":"r2","partition":null,"attrs":{}},"leader_peer":{"id":1,"addr":"a1"},"follower_peers":[{"id":2,"addr":"a2"},{"id":3,"addr":"a3"}]}"#;
@@ -0,0 +1,79 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::error::{ProfilingNotSupportedSnafu, Result};
+use crate::ProfileSamples;
+
+/// CPU profiler, which is not supported on this platform.
+pub struct Profiler;
+
+impl Profiler {
+    pub async fn start(_frequency: i32) -> Result<Profiler> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub fn try_start(_frequency: i32) -> Result<Option<Profiler>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub fn samples(&self) -> Result<ProfileSamples> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub fn dump_text(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub fn dump_flamegraph(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub fn dump_proto(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+}
+
+impl ProfileSamples {
+    pub fn dump_flamegraph(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub fn dump_proto(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+}
+
+/// CPU profiler utility, which is not supported on this platform.
+#[derive(Debug)]
+pub struct Profiling;
+
+impl Profiling {
+    pub fn new(_duration: Duration, _frequency: i32) -> Profiling {
+        Profiling
+    }
+
+    pub async fn dump_text(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub async fn dump_flamegraph(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+
+    pub async fn dump_proto(&self) -> Result<Vec<u8>> {
+        ProfilingNotSupportedSnafu.fail()
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_macro::stack_trace_debug;
+use snafu::{Location, Snafu};
+
+#[derive(Snafu)]
+#[snafu(visibility(pub))]
+#[stack_trace_debug]
+pub enum Error {
+    #[cfg(unix)]
+    #[snafu(display("Failed to create profiler guard"))]
+    CreateGuard {
+        #[snafu(source)]
+        error: pprof::Error,
+        location: Location,
+    },
+
+    #[cfg(unix)]
+    #[snafu(display("Failed to create report"))]
+    CreateReport {
+        #[snafu(source)]
+        error: pprof::Error,
+        location: Location,
+    },
+
+    #[cfg(unix)]
+    #[snafu(display("Failed to create flamegraph"))]
+    CreateFlamegraph {
+        #[snafu(source)]
+        error: pprof::Error,
+        location: Location,
+    },
+
+    #[cfg(unix)]
+    #[snafu(display("Failed to render flamegraph"))]
+    RenderFlamegraph {
+        #[snafu(source)]
+        error: std::io::Error,
+        location: Location,
+    },
+
+    #[cfg(unix)]
+    #[snafu(display("Failed to create pprof report"))]
+    ReportPprof {
+        #[snafu(source)]
+        error: pprof::Error,
+        location: Location,
+    },
+
+    #[snafu(display("CPU profiling is not supported"))]
+    ProfilingNotSupported { location: Location },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl ErrorExt for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::ProfilingNotSupported { .. } => StatusCode::Unsupported,
+            #[cfg(unix)]
+            _ => StatusCode::Unexpected,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod error;
+
+use std::collections::HashMap;
+
+#[cfg(unix)]
+mod nix;
+#[cfg(unix)]
+pub use nix::{Profiler, Profiling};
+
+#[cfg(not(unix))]
+mod dummy;
+#[cfg(not(unix))]
+pub use dummy::{Profiler, Profiling};
+
+/// Sampled stacks of a profile. Samples of profiles on different nodes can be merged
+/// into one profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileSamples {
+    /// Sample frequency per second.
+    pub frequency: i32,
+    /// Stacks and their numbers of samples. A stack starts with the name of the thread,
+    /// followed by the frames from the root to the leaf.
+    pub stacks: Vec<(Vec<String>, i64)>,
+}
+
+impl ProfileSamples {
+    /// Adds samples of `other` to the profile.
+    pub fn merge(&mut self, other: ProfileSamples) {
+        if self.frequency == 0 {
+            self.frequency = other.frequency;
+        }
+        let mut stacks = std::mem::take(&mut self.stacks)
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        for (stack, count) in other.stacks {
+            *stacks.entry(stack).or_default() += count;
+        }
+        self.stacks = stacks.into_iter().collect();
+        self.stacks.sort_unstable();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(frames: &[&str]) -> Vec<String> {
+        frames.iter().map(|frame| frame.to_string()).collect()
+    }
+
+    #[test]
+    fn test_merge_samples() {
+        let mut samples = ProfileSamples {
+            frequency: 99,
+            stacks: vec![(stack(&["main", "a", "b"]), 2)],
+        };
+        samples.merge(ProfileSamples {
+            frequency: 99,
+            stacks: vec![
+                (stack(&["main", "a", "b"]), 3),
+                (stack(&["worker", "c"]), 1),
+            ],
+        });
+
+        assert_eq!(
+            ProfileSamples {
+                frequency: 99,
+                stacks: vec![
+                    (stack(&["main", "a", "b"]), 5),
+                    (stack(&["worker", "c"]), 1)
+                ],
+            },
+            samples
+        );
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use pprof::protos::{Function, Label, Line, Location, Profile, Sample, ValueType};
+use pprof::{ProfilerGuard, ProfilerGuardBuilder, Report};
+use prost::Message;
+use snafu::ResultExt;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::error::{
+    CreateFlamegraphSnafu, CreateGuardSnafu, CreateReportSnafu, RenderFlamegraphSnafu,
+    ReportPprofSnafu, Result,
+};
+use crate::ProfileSamples;
+
+/// The sampler of the process is global, so only one profile runs at a time.
+static PROFILE_PERMIT: Semaphore = Semaphore::const_new(1);
+
+/// CPU profiler sampling the whole process until it's dropped.
+pub struct Profiler {
+    guard: ProfilerGuard<'static>,
+    frequency: i32,
+    /// Released after the guard stops sampling, as fields drop in order.
+    _permit: SemaphorePermit<'static>,
+}
+
+impl Profiler {
+    /// Starts sampling at `frequency` per second, after the running profile of
+    /// the process, if any, finishes.
+    pub async fn start(frequency: i32) -> Result<Profiler> {
+        let permit = PROFILE_PERMIT
+            .acquire()
+            .await
+            .expect("the profile semaphore is never closed");
+        Self::start_with_permit(frequency, permit)
+    }
+
+    /// Starts sampling at `frequency` per second, returns `None` if a profile of the
+    /// process is running.
+    pub fn try_start(frequency: i32) -> Result<Option<Profiler>> {
+        let Ok(permit) = PROFILE_PERMIT.try_acquire() else {
+            return Ok(None);
+        };
+        Self::start_with_permit(frequency, permit).map(Some)
+    }
+
+    fn start_with_permit(frequency: i32, permit: SemaphorePermit<'static>) -> Result<Profiler> {
+        let guard = ProfilerGuardBuilder::default()
+            .frequency(frequency)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .context(CreateGuardSnafu)?;
+        Ok(Profiler {
+            guard,
+            frequency,
+            _permit: permit,
+        })
+    }
+
+    fn report(&self) -> Result<Report> {
+        self.guard.report().build().context(CreateReportSnafu)
+    }
+
+    /// Returns the samples collected so far, which can be merged with samples of
+    /// other nodes.
+    pub fn samples(&self) -> Result<ProfileSamples> {
+        let report = self.report()?;
+        let stacks = report
+            .data
+            .iter()
+            .map(|(frames, count)| {
+                let mut stack = vec![frames.thread_name.clone()];
+                // Frames are ordered from the leaf to the root.
+                stack.extend(
+                    frames
+                        .frames
+                        .iter()
+                        .rev()
+                        .flat_map(|frame| frame.iter().rev())
+                        .map(|symbol| symbol.to_string()),
+                );
+                (stack, *count as i64)
+            })
+            .collect();
+
+        Ok(ProfileSamples {
+            frequency: self.frequency,
+            stacks,
+        })
+    }
+
+    /// Returns the samples collected so far in simple text format.
+    pub fn dump_text(&self) -> Result<Vec<u8>> {
+        let report = self.report()?;
+        Ok(format!("{:?}", report).into_bytes())
+    }
+
+    /// Returns the samples collected so far as a flamegraph.
+    pub fn dump_flamegraph(&self) -> Result<Vec<u8>> {
+        let mut body: Vec<u8> = Vec::new();
+
+        let report = self.report()?;
+        report
+            .flamegraph(&mut body)
+            .context(CreateFlamegraphSnafu)?;
+
+        Ok(body)
+    }
+
+    /// Returns the samples collected so far as a proto.
+    pub fn dump_proto(&self) -> Result<Vec<u8>> {
+        let report = self.report()?;
+        // Generate google’s pprof format report.
+        let profile = report.pprof().context(ReportPprofSnafu)?;
+        let body = profile.encode_to_vec();
+
+        Ok(body)
+    }
+}
+
+impl ProfileSamples {
+    /// Returns the samples as a flamegraph.
+    pub fn dump_flamegraph(&self) -> Result<Vec<u8>> {
+        let lines = self
+            .stacks
+            .iter()
+            .map(|(stack, count)| format!("{} {}", stack.join(";"), count))
+            .collect::<Vec<_>>();
+        let mut body = Vec::new();
+        inferno::flamegraph::from_lines(
+            &mut inferno::flamegraph::Options::default(),
+            lines.iter().map(String::as_str),
+            &mut body,
+        )
+        .context(RenderFlamegraphSnafu)?;
+
+        Ok(body)
+    }
+
+    /// Returns the samples as a proto in google's pprof format.
+    pub fn dump_proto(&self) -> Result<Vec<u8>> {
+        let mut strings = StringTable::default();
+        // Each frame is a function, with a location of the same id. Keyed by the id of
+        // the name of the function in the string table.
+        let mut functions = HashMap::new();
+        let mut samples = Vec::with_capacity(self.stacks.len());
+        let period = 1_000_000_000 / i64::from(self.frequency.max(1));
+        let thread_key = strings.id("thread");
+        for (stack, count) in &self.stacks {
+            let Some((thread, frames)) = stack.split_first() else {
+                continue;
+            };
+            // Locations of a sample are ordered from the leaf to the root.
+            let location_id = frames
+                .iter()
+                .rev()
+                .map(|frame| {
+                    let next_id = functions.len() as u64 + 1;
+                    *functions.entry(strings.id(frame)).or_insert(next_id)
+                })
+                .collect();
+            samples.push(Sample {
+                location_id,
+                value: vec![*count, count * period],
+                label: vec![Label {
+                    key: thread_key,
+                    str: strings.id(thread),
+                    ..Default::default()
+                }],
+            });
+        }
+
+        let mut functions = functions.into_iter().collect::<Vec<_>>();
+        functions.sort_unstable_by_key(|(_, id)| *id);
+        let (function, location) = functions
+            .into_iter()
+            .map(|(name, id)| {
+                let function = Function {
+                    id,
+                    name,
+                    system_name: name,
+                    ..Default::default()
+                };
+                let location = Location {
+                    id,
+                    line: vec![Line {
+                        function_id: id,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                };
+                (function, location)
+            })
+            .unzip();
+        let cpu_type = ValueType {
+            r#type: strings.id("cpu"),
+            unit: strings.id("nanoseconds"),
+        };
+        let sample_type = vec![
+            ValueType {
+                r#type: strings.id("samples"),
+                unit: strings.id("count"),
+            },
+            cpu_type.clone(),
+        ];
+        let profile = Profile {
+            sample_type,
+            sample: samples,
+            location,
+            function,
+            string_table: strings.strings,
+            period_type: Some(cpu_type),
+            period,
+            ..Default::default()
+        };
+
+        Ok(profile.encode_to_vec())
+    }
+}
+
+/// Strings of a pprof proto, referenced by their indexes. The first string is empty.
+struct StringTable {
+    strings: Vec<String>,
+    ids: HashMap<String, i64>,
+}
+
+impl Default for StringTable {
+    fn default() -> Self {
+        Self {
+            strings: vec![String::new()],
+            ids: HashMap::from([(String::new(), 0)]),
+        }
+    }
+}
+
+impl StringTable {
+    /// Returns the index of `s`, adds it to the table if it's absent.
+    fn id(&mut self, s: &str) -> i64 {
+        if let Some(id) = self.ids.get(s) {
+            return *id;
+        }
+        let id = self.strings.len() as i64;
+        self.strings.push(s.to_string());
+        let _ = self.ids.insert(s.to_string(), id);
+        id
+    }
+}
+
+/// CPU profiler utility.
+// Inspired by https://github.com/datafuselabs/databend/blob/67f445e83cd4eceda98f6c1c114858929d564029/src/common/base/src/base/profiling.rs
+#[derive(Debug)]
+pub struct Profiling {
+    /// Sample duration.
+    duration: Duration,
+    /// Sample frequency.
+    frequency: i32,
+}
+
+impl Profiling {
+    /// Creates a new profiler.
+    pub fn new(duration: Duration, frequency: i32) -> Profiling {
+        Profiling {
+            duration,
+            frequency,
+        }
+    }
+
+    /// Profiles for the sample duration.
+    async fn profile(&self) -> Result<Profiler> {
+        let profiler = Profiler::start(self.frequency).await?;
+        tokio::time::sleep(self.duration).await;
+        Ok(profiler)
+    }
+
+    /// Profiles and returns a generated text report.
+    pub async fn dump_text(&self) -> Result<Vec<u8>> {
+        self.profile().await?.dump_text()
+    }
+
+    /// Profiles and returns a generated flamegraph.
+    pub async fn dump_flamegraph(&self) -> Result<Vec<u8>> {
+        self.profile().await?.dump_flamegraph()
+    }
+
+    /// Profiles and returns a generated proto.
+    pub async fn dump_proto(&self) -> Result<Vec<u8>> {
+        self.profile().await?.dump_proto()
+    }
+}
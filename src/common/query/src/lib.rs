@@ -25,6 +25,7 @@ pub mod columnar_value;
 pub mod error;
 mod function;
 pub mod logical_plan;
+pub mod native_histogram;
 pub mod physical_plan;
 pub mod prelude;
 mod signature;
@@ -51,6 +52,48 @@ pub struct OutputMeta {
     /// May exist for query output. One can retrieve execution metrics from this plan.
     pub plan: Option<Arc<dyn PhysicalPlan>>,
     pub cost: OutputCost,
+    /// Exists for DDL output, tells whether the DDL changes the object.
+    pub ddl: Option<DdlResult>,
+}
+
+/// What a DDL does to its object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DdlStatus {
+    Created,
+    /// The object to create already exists, e.g. `CREATE TABLE IF NOT EXISTS`.
+    AlreadyExists,
+    Altered,
+    Dropped,
+    /// The object to drop doesn't exist, e.g. `DROP TABLE IF EXISTS`.
+    NotFound,
+}
+
+impl DdlStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DdlStatus::Created => "created",
+            DdlStatus::AlreadyExists => "already_exists",
+            DdlStatus::Altered => "altered",
+            DdlStatus::Dropped => "dropped",
+            DdlStatus::NotFound => "not_found",
+        }
+    }
+}
+
+/// Result of a DDL, so declarative tools can converge without matching error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DdlResult {
+    pub status: DdlStatus,
+    /// Version of the object after the DDL, `None` if the object doesn't exist or
+    /// doesn't have versions.
+    pub version: Option<u64>,
+}
+
+impl DdlResult {
+    pub fn new(status: DdlStatus, version: Option<u64>) -> Self {
+        Self { status, version }
+    }
 }
 
 impl Output {
@@ -79,6 +122,13 @@ impl Output {
         Self { data, meta }
     }
 
+    pub fn new_with_ddl_result(affected_rows: OutputRows, ddl: DdlResult) -> Self {
+        Self {
+            data: OutputData::AffectedRows(affected_rows),
+            meta: OutputMeta::new_with_ddl_result(ddl),
+        }
+    }
+
     pub fn extract_rows_and_cost(&self) -> (OutputRows, OutputCost) {
         match self.data {
             OutputData::AffectedRows(rows) => (rows, self.meta.cost),
@@ -103,18 +153,27 @@ impl Debug for OutputData {
 
 impl OutputMeta {
     pub fn new(plan: Option<Arc<dyn PhysicalPlan>>, cost: usize) -> Self {
-        Self { plan, cost }
+        Self {
+            plan,
+            cost,
+            ddl: None,
+        }
     }
 
     pub fn new_with_plan(plan: Arc<dyn PhysicalPlan>) -> Self {
-        Self {
-            plan: Some(plan),
-            cost: 0,
-        }
+        Self::new(Some(plan), 0)
     }
 
     pub fn new_with_cost(cost: usize) -> Self {
-        Self { plan: None, cost }
+        Self::new(None, cost)
+    }
+
+    pub fn new_with_ddl_result(ddl: DdlResult) -> Self {
+        Self {
+            plan: None,
+            cost: 0,
+            ddl: Some(ddl),
+        }
     }
 }
 
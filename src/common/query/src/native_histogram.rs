@@ -0,0 +1,472 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Storage model of Prometheus native histograms.
+//!
+//! A native histogram sample is stored as a binary value encoded from
+//! [NativeHistogram]. Buckets follow the exponential schema of Prometheus:
+//! the upper bound of the positive bucket with index `i` is `2^(i * 2^-schema)`.
+//! Only populated buckets are kept, addressed by [BucketSpan]s.
+
+use std::collections::BTreeMap;
+
+use prost::Message;
+
+/// A span of consecutive buckets.
+///
+/// The offset of the first span is the index of its first bucket, offsets of
+/// following spans are gaps to the end of the previous span.
+#[derive(Clone, Copy, PartialEq, Message)]
+pub struct BucketSpan {
+    #[prost(sint32, tag = "1")]
+    pub offset: i32,
+    #[prost(uint32, tag = "2")]
+    pub length: u32,
+}
+
+/// A native histogram sample with absolute bucket counts.
+#[derive(Clone, PartialEq, Message)]
+pub struct NativeHistogram {
+    /// Total count of observations.
+    #[prost(double, tag = "1")]
+    pub count: f64,
+    /// Sum of observations.
+    #[prost(double, tag = "2")]
+    pub sum: f64,
+    /// Resolution of the buckets, between -4 and 8.
+    #[prost(sint32, tag = "3")]
+    pub schema: i32,
+    /// Width of the zero bucket, observations in `[-zero_threshold, zero_threshold]`
+    /// are counted in the zero bucket.
+    #[prost(double, tag = "4")]
+    pub zero_threshold: f64,
+    #[prost(double, tag = "5")]
+    pub zero_count: f64,
+    #[prost(message, repeated, tag = "6")]
+    pub negative_spans: Vec<BucketSpan>,
+    #[prost(double, repeated, tag = "7")]
+    pub negative_counts: Vec<f64>,
+    #[prost(message, repeated, tag = "8")]
+    pub positive_spans: Vec<BucketSpan>,
+    #[prost(double, repeated, tag = "9")]
+    pub positive_counts: Vec<f64>,
+}
+
+/// A bucket with its boundaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: f64,
+}
+
+impl NativeHistogram {
+    /// Returns all buckets in ascending order of their bounds: negative buckets,
+    /// the zero bucket and then positive buckets.
+    pub fn buckets(&self) -> Vec<Bucket> {
+        let mut buckets =
+            Vec::with_capacity(self.negative_counts.len() + self.positive_counts.len() + 1);
+
+        let mut negative = bucket_indices(&self.negative_spans)
+            .zip(&self.negative_counts)
+            .map(|(index, count)| Bucket {
+                lower: -bucket_upper_bound(index, self.schema),
+                upper: -bucket_upper_bound(index - 1, self.schema),
+                count: *count,
+            })
+            .collect::<Vec<_>>();
+        negative.reverse();
+        buckets.extend(negative);
+
+        if self.zero_count > 0.0 {
+            buckets.push(Bucket {
+                lower: -self.zero_threshold,
+                upper: self.zero_threshold,
+                count: self.zero_count,
+            });
+        }
+
+        buckets.extend(
+            bucket_indices(&self.positive_spans)
+                .zip(&self.positive_counts)
+                .map(|(index, count)| Bucket {
+                    lower: bucket_upper_bound(index - 1, self.schema),
+                    upper: bucket_upper_bound(index, self.schema),
+                    count: *count,
+                }),
+        );
+
+        buckets
+    }
+
+    /// Estimates the `q` quantile, following `histogram_quantile` of Prometheus
+    /// for native histograms. Observations are assumed to be uniformly
+    /// distributed within a bucket.
+    pub fn quantile(&self, q: f64) -> f64 {
+        if q < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        if q > 1.0 {
+            return f64::INFINITY;
+        }
+        if q.is_nan() || self.count == 0.0 {
+            return f64::NAN;
+        }
+
+        let buckets = self.buckets();
+        let Some(mut bucket) = buckets.first().copied() else {
+            return f64::NAN;
+        };
+        let rank = q * self.count;
+        let mut count = 0.0;
+        for b in &buckets {
+            bucket = *b;
+            count += b.count;
+            if count >= rank {
+                break;
+            }
+        }
+
+        // The zero bucket is narrowed if all observations are on one side of it.
+        if bucket.lower < 0.0 && bucket.upper > 0.0 {
+            if self.negative_counts.is_empty() && !self.positive_counts.is_empty() {
+                bucket.lower = 0.0;
+            } else if self.positive_counts.is_empty() && !self.negative_counts.is_empty() {
+                bucket.upper = 0.0;
+            }
+        }
+
+        let rank_in_bucket = rank - (count - bucket.count);
+        bucket.lower + (bucket.upper - bucket.lower) * (rank_in_bucket / bucket.count)
+    }
+
+    /// Returns the sum of this histogram and `other`.
+    ///
+    /// Buckets are merged at the lower resolution of the two schemas, and
+    /// buckets inside the wider zero bucket are merged into the zero bucket.
+    pub fn add(&self, other: &NativeHistogram) -> NativeHistogram {
+        self.combine(other, 1.0)
+    }
+
+    /// Returns the result of subtracting `other` from this histogram.
+    pub fn sub(&self, other: &NativeHistogram) -> NativeHistogram {
+        self.combine(other, -1.0)
+    }
+
+    /// Multiplies all counts and the sum by `factor`.
+    pub fn scale(&mut self, factor: f64) {
+        self.count *= factor;
+        self.sum *= factor;
+        self.zero_count *= factor;
+        self.negative_counts
+            .iter_mut()
+            .chain(self.positive_counts.iter_mut())
+            .for_each(|count| *count *= factor);
+    }
+
+    /// Returns true if this histogram can't be a later sample of the counter
+    /// histogram `previous`, following `DetectReset` of Prometheus.
+    pub fn detect_reset(&self, previous: &NativeHistogram) -> bool {
+        if self.count < previous.count
+            || self.schema > previous.schema
+            || self.zero_threshold < previous.zero_threshold
+        {
+            return true;
+        }
+        let diff = self.sub(previous);
+        diff.zero_count < 0.0
+            || diff
+                .negative_counts
+                .iter()
+                .chain(&diff.positive_counts)
+                .any(|count| *count < 0.0)
+    }
+
+    /// Returns `self + factor * other`.
+    fn combine(&self, other: &NativeHistogram, factor: f64) -> NativeHistogram {
+        let schema = self.schema.min(other.schema);
+        let zero_threshold = self.zero_threshold.max(other.zero_threshold);
+        let mut zero_count = 0.0;
+        let mut negative = BTreeMap::new();
+        let mut positive = BTreeMap::new();
+        for (histogram, factor) in [(self, 1.0), (other, factor)] {
+            zero_count += histogram.zero_count * factor;
+            for (spans, counts, buckets) in [
+                (
+                    &histogram.negative_spans,
+                    &histogram.negative_counts,
+                    &mut negative,
+                ),
+                (
+                    &histogram.positive_spans,
+                    &histogram.positive_counts,
+                    &mut positive,
+                ),
+            ] {
+                for (index, count) in bucket_indices(spans).zip(counts) {
+                    if bucket_upper_bound(index, histogram.schema) <= zero_threshold {
+                        zero_count += count * factor;
+                    } else {
+                        let index = downscale_index(index, histogram.schema - schema);
+                        *buckets.entry(index).or_insert(0.0) += count * factor;
+                    }
+                }
+            }
+        }
+
+        let (negative_spans, negative_counts) = buckets_to_spans(negative);
+        let (positive_spans, positive_counts) = buckets_to_spans(positive);
+        NativeHistogram {
+            count: self.count + other.count * factor,
+            sum: self.sum + other.sum * factor,
+            schema,
+            zero_threshold,
+            zero_count,
+            negative_spans,
+            negative_counts,
+            positive_spans,
+            positive_counts,
+        }
+    }
+}
+
+/// Returns indices of buckets described by `spans`.
+fn bucket_indices(spans: &[BucketSpan]) -> impl Iterator<Item = i32> + '_ {
+    let mut next = 0;
+    spans.iter().flat_map(move |span| {
+        let start = next + span.offset;
+        next = start + span.length as i32;
+        start..next
+    })
+}
+
+/// Converts buckets sorted by their indices to spans and counts.
+fn buckets_to_spans(buckets: BTreeMap<i32, f64>) -> (Vec<BucketSpan>, Vec<f64>) {
+    let mut spans: Vec<BucketSpan> = Vec::new();
+    let mut counts = Vec::with_capacity(buckets.len());
+    // Index after the last bucket of the previous span.
+    let mut next = 0;
+    for (index, count) in buckets {
+        match spans.last_mut() {
+            Some(span) if index == next => span.length += 1,
+            _ => spans.push(BucketSpan {
+                offset: index - next,
+                length: 1,
+            }),
+        }
+        next = index + 1;
+        counts.push(count);
+    }
+    (spans, counts)
+}
+
+/// Returns the index of the bucket containing the bucket at `index` after
+/// reducing the resolution by `delta` schemas.
+fn downscale_index(index: i32, delta: i32) -> i32 {
+    // Bucket `index` covers `(2^((index - 1) * 2^-schema), 2^(index * 2^-schema)]`.
+    ((index - 1) >> delta) + 1
+}
+
+/// Returns the upper bound of the positive bucket at `index`.
+fn bucket_upper_bound(index: i32, schema: i32) -> f64 {
+    2f64.powf(index as f64 * 2f64.powi(-schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_histogram() -> NativeHistogram {
+        // schema 0: bucket i covers (2^(i-1), 2^i].
+        NativeHistogram {
+            count: 10.0,
+            sum: 30.0,
+            schema: 0,
+            zero_threshold: 0.001,
+            zero_count: 0.0,
+            negative_spans: vec![],
+            negative_counts: vec![],
+            // buckets (0.5, 1], (1, 2], (4, 8]
+            positive_spans: vec![
+                BucketSpan {
+                    offset: 0,
+                    length: 2,
+                },
+                BucketSpan {
+                    offset: 1,
+                    length: 1,
+                },
+            ],
+            positive_counts: vec![2.0, 4.0, 4.0],
+        }
+    }
+
+    #[test]
+    fn test_buckets() {
+        let buckets = new_histogram().buckets();
+        assert_eq!(
+            vec![
+                Bucket {
+                    lower: 0.5,
+                    upper: 1.0,
+                    count: 2.0
+                },
+                Bucket {
+                    lower: 1.0,
+                    upper: 2.0,
+                    count: 4.0
+                },
+                Bucket {
+                    lower: 4.0,
+                    upper: 8.0,
+                    count: 4.0
+                },
+            ],
+            buckets
+        );
+    }
+
+    #[test]
+    fn test_quantile() {
+        let histogram = new_histogram();
+        assert_eq!(1.0, histogram.quantile(0.2));
+        assert_eq!(1.5, histogram.quantile(0.4));
+        assert_eq!(6.0, histogram.quantile(0.8));
+        assert_eq!(8.0, histogram.quantile(1.0));
+        assert_eq!(f64::NEG_INFINITY, histogram.quantile(-1.0));
+        assert_eq!(f64::INFINITY, histogram.quantile(2.0));
+        assert!(NativeHistogram::default().quantile(0.5).is_nan());
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let histogram = new_histogram();
+        let bytes = histogram.encode_to_vec();
+        assert_eq!(histogram, NativeHistogram::decode(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_add() {
+        let histogram = new_histogram();
+        let sum = histogram.add(&histogram);
+        assert_eq!(20.0, sum.count);
+        assert_eq!(60.0, sum.sum);
+        assert_eq!(histogram.positive_spans, sum.positive_spans);
+        assert_eq!(vec![4.0, 8.0, 8.0], sum.positive_counts);
+
+        // Buckets of schema 1 are merged into buckets of schema 0.
+        let other = NativeHistogram {
+            count: 3.0,
+            sum: 6.0,
+            schema: 1,
+            zero_threshold: 0.001,
+            // buckets (2^0.5, 2], (2, 2^1.5], (2^1.5, 4]
+            positive_spans: vec![BucketSpan {
+                offset: 2,
+                length: 3,
+            }],
+            positive_counts: vec![1.0, 1.0, 1.0],
+            ..Default::default()
+        };
+        let sum = histogram.add(&other);
+        assert_eq!(0, sum.schema);
+        assert_eq!(13.0, sum.count);
+        assert_eq!(
+            vec![
+                Bucket {
+                    lower: 0.5,
+                    upper: 1.0,
+                    count: 2.0
+                },
+                Bucket {
+                    lower: 1.0,
+                    upper: 2.0,
+                    count: 5.0
+                },
+                Bucket {
+                    lower: 2.0,
+                    upper: 4.0,
+                    count: 2.0
+                },
+                Bucket {
+                    lower: 4.0,
+                    upper: 8.0,
+                    count: 4.0
+                },
+            ],
+            sum.buckets()
+        );
+    }
+
+    #[test]
+    fn test_add_wider_zero_bucket() {
+        let histogram = new_histogram();
+        let other = NativeHistogram {
+            count: 1.0,
+            schema: 0,
+            zero_threshold: 1.0,
+            zero_count: 1.0,
+            ..Default::default()
+        };
+        let sum = histogram.add(&other);
+        assert_eq!(1.0, sum.zero_threshold);
+        // The bucket (0.5, 1] is inside the zero bucket.
+        assert_eq!(3.0, sum.zero_count);
+        assert_eq!(vec![4.0, 4.0], sum.positive_counts);
+    }
+
+    #[test]
+    fn test_sub_and_scale() {
+        let histogram = new_histogram();
+        let mut diff = histogram.add(&histogram).sub(&histogram);
+        assert_eq!(histogram, diff);
+
+        diff.scale(0.5);
+        assert_eq!(5.0, diff.count);
+        assert_eq!(15.0, diff.sum);
+        assert_eq!(vec![1.0, 2.0, 2.0], diff.positive_counts);
+    }
+
+    #[test]
+    fn test_detect_reset() {
+        let histogram = new_histogram();
+        let later = histogram.add(&histogram);
+        assert!(!later.detect_reset(&histogram));
+        assert!(histogram.detect_reset(&later));
+
+        // The count grows but a bucket decreases.
+        let mut moved = histogram.clone();
+        moved.positive_counts = vec![1.0, 5.0, 4.0];
+        assert!(moved.detect_reset(&histogram));
+    }
+
+    #[test]
+    fn test_zero_bucket() {
+        let histogram = NativeHistogram {
+            count: 4.0,
+            sum: 2.0,
+            schema: 0,
+            zero_threshold: 0.5,
+            zero_count: 2.0,
+            positive_spans: vec![BucketSpan {
+                offset: 0,
+                length: 1,
+            }],
+            positive_counts: vec![2.0],
+            ..Default::default()
+        };
+        // Only positive buckets, the zero bucket is treated as [0, 0.5].
+        assert_eq!(0.25, histogram.quantile(0.25));
+        assert_eq!(0.75, histogram.quantile(0.75));
+    }
+}
@@ -16,7 +16,9 @@ use std::any::Any;
 use std::fmt::{self, Debug};
 use std::sync::Arc;
 
-use common_recordbatch::adapter::{DfRecordBatchStreamAdapter, RecordBatchStreamAdapter};
+use common_recordbatch::adapter::{
+    CollectPlanMetrics, DfRecordBatchStreamAdapter, RecordBatchStreamAdapter,
+};
 use common_recordbatch::{DfSendableRecordBatchStream, SendableRecordBatchStream};
 use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
 use datafusion::error::Result as DfResult;
@@ -140,15 +142,20 @@ impl PhysicalPlan for PhysicalPlanAdapter {
         let baseline_metric = BaselineMetrics::new(&self.metric, partition);
 
         let df_plan = self.df_plan.clone();
+        let collect_plan_metrics = context
+            .session_config()
+            .get_extension::<CollectPlanMetrics>()
+            .is_some();
         let stream = df_plan
             .execute(partition, context)
             .context(error::GeneralDataFusionSnafu)?;
-        let adapter = RecordBatchStreamAdapter::try_new_with_metrics_and_df_plan(
+        let mut adapter = RecordBatchStreamAdapter::try_new_with_metrics_and_df_plan(
             stream,
             baseline_metric,
             df_plan,
         )
         .context(error::ConvertDfRecordBatchStreamSnafu)?;
+        adapter.set_collect_plan_metrics(collect_plan_metrics);
 
         Ok(Box::pin(adapter))
     }
@@ -23,6 +23,10 @@ pub use crate::signature::{Signature, TypeSignature, Volatility};
 pub const GREPTIME_TIMESTAMP: &str = "greptime_timestamp";
 /// Default value column name for Prometheus metrics.
 pub const GREPTIME_VALUE: &str = "greptime_value";
+/// Default native histogram column name for Prometheus metrics.
+pub const GREPTIME_HISTOGRAM: &str = "greptime_histogram";
+/// Default column name of exemplar labels for Prometheus metrics.
+pub const GREPTIME_EXEMPLAR_LABELS: &str = "greptime_exemplar_labels";
 /// Default counter column name for OTLP metrics.
 pub const GREPTIME_COUNT: &str = "greptime_count";
 /// Default physical table name
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::Display;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
@@ -22,7 +23,9 @@ use datafusion::arrow::compute::cast;
 use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
 use datafusion::error::Result as DfResult;
 use datafusion::physical_plan::metrics::{BaselineMetrics, MetricValue};
-use datafusion::physical_plan::{ExecutionPlan, RecordBatchStream as DfRecordBatchStream};
+use datafusion::physical_plan::{
+    displayable, ExecutionPlan, RecordBatchStream as DfRecordBatchStream,
+};
 use datafusion_common::arrow::error::ArrowError;
 use datafusion_common::DataFusionError;
 use datatypes::schema::{Schema, SchemaRef};
@@ -168,6 +171,12 @@ impl Stream for DfRecordBatchStreamAdapter {
     }
 }
 
+/// Asks [RecordBatchStreamAdapter]s to collect [PlanMetrics] of their plans. Queries
+/// that report the metrics of each operator, i.e. `EXPLAIN ANALYZE`, put it in the
+/// session config of their task context.
+#[derive(Debug, Default)]
+pub struct CollectPlanMetrics;
+
 /// DataFusion [SendableRecordBatchStream](DfSendableRecordBatchStream) -> Greptime [RecordBatchStream].
 /// The reverse one is [DfRecordBatchStreamAdapter]
 pub struct RecordBatchStreamAdapter {
@@ -176,6 +185,8 @@ pub struct RecordBatchStreamAdapter {
     metrics: Option<BaselineMetrics>,
     /// Aggregated plan-level metrics. Resolved after an [ExecutionPlan] is finished.
     metrics_2: Metrics,
+    /// Whether to also resolve the metrics of each operator of the plan.
+    collect_plan_metrics: bool,
 }
 
 /// Json encoded metrics. Contains metric from a whole plan tree.
@@ -194,6 +205,7 @@ impl RecordBatchStreamAdapter {
             stream,
             metrics: None,
             metrics_2: Metrics::Unavailable,
+            collect_plan_metrics: false,
         })
     }
 
@@ -209,12 +221,19 @@ impl RecordBatchStreamAdapter {
             stream,
             metrics: Some(metrics),
             metrics_2: Metrics::Unresolved(df_plan),
+            collect_plan_metrics: false,
         })
     }
 
     pub fn set_metrics2(&mut self, plan: Arc<dyn ExecutionPlan>) {
         self.metrics_2 = Metrics::Unresolved(plan)
     }
+
+    /// Sets whether to collect [PlanMetrics] of the plan, they're only collected for
+    /// [CollectPlanMetrics] queries.
+    pub fn set_collect_plan_metrics(&mut self, collect: bool) {
+        self.collect_plan_metrics = collect;
+    }
 }
 
 impl RecordBatchStream for RecordBatchStreamAdapter {
@@ -224,7 +243,7 @@ impl RecordBatchStream for RecordBatchStreamAdapter {
 
     fn metrics(&self) -> Option<RecordBatchMetrics> {
         match &self.metrics_2 {
-            Metrics::Resolved(metrics) => Some(*metrics),
+            Metrics::Resolved(metrics) => Some(metrics.clone()),
             Metrics::Unavailable | Metrics::Unresolved(_) => None,
         }
     }
@@ -256,7 +275,7 @@ impl Stream for RecordBatchStreamAdapter {
             Poll::Ready(None) => {
                 if let Metrics::Unresolved(df_plan) = &self.metrics_2 {
                     let mut metrics_holder = RecordBatchMetrics::default();
-                    collect_metrics(df_plan, &mut metrics_holder);
+                    collect_metrics(df_plan, 0, self.collect_plan_metrics, &mut metrics_holder);
                     if metrics_holder.elapsed_compute != 0 || metrics_holder.memory_usage != 0 {
                         self.metrics_2 = Metrics::Resolved(metrics_holder);
                     }
@@ -272,28 +291,117 @@ impl Stream for RecordBatchStreamAdapter {
     }
 }
 
-fn collect_metrics(df_plan: &Arc<dyn ExecutionPlan>, result: &mut RecordBatchMetrics) {
-    if let Some(metrics) = df_plan.metrics() {
+/// Sums up the metrics of `df_plan`, and also collects [PlanMetrics] of each operator
+/// if `with_plan_metrics` is true.
+fn collect_metrics(
+    df_plan: &Arc<dyn ExecutionPlan>,
+    level: usize,
+    with_plan_metrics: bool,
+    result: &mut RecordBatchMetrics,
+) {
+    let metrics = df_plan.metrics();
+    if let Some(metrics) = &metrics {
         metrics.iter().for_each(|m| match m.value() {
             MetricValue::ElapsedCompute(ec) => result.elapsed_compute += ec.value(),
             MetricValue::CurrentMemoryUsage(m) => result.memory_usage += m.value(),
             _ => {}
         });
     }
+    if with_plan_metrics {
+        let mut plan_metrics = PlanMetrics {
+            plan: displayable(df_plan.as_ref()).one_line().to_string(),
+            level,
+            metrics: vec![],
+        };
+        if let Some(metrics) = metrics {
+            for m in metrics.aggregate_by_name().iter() {
+                plan_metrics
+                    .metrics
+                    .push((m.value().name().to_string(), m.value().as_usize()));
+            }
+        }
+        result.plan_metrics.push(plan_metrics);
+    }
 
     for child in df_plan.children() {
-        collect_metrics(&child, result);
+        collect_metrics(&child, level + 1, with_plan_metrics, result);
     }
 }
 
 /// [`RecordBatchMetrics`] carrys metrics value
 /// from datanode to frontend through gRPC
-#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone)]
 pub struct RecordBatchMetrics {
     // cpu consumption in nanoseconds
     pub elapsed_compute: usize,
     // memory used by the plan in bytes
     pub memory_usage: usize,
+    // metrics of each operator in the plan, in pre-order
+    #[serde(default)]
+    pub plan_metrics: Vec<PlanMetrics>,
+    // CPU samples of the node while it runs the plan, only set if the query is profiled.
+    // Each stack starts with the thread name, followed by frames from the root to the leaf.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profile_stacks: Vec<(Vec<String>, i64)>,
+}
+
+/// Metrics of a single operator in the plan tree.
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct PlanMetrics {
+    /// The one-line description of the operator.
+    pub plan: String,
+    /// The depth of the operator in the plan tree.
+    pub level: usize,
+    /// Metric name and value pairs.
+    pub metrics: Vec<(String, usize)>,
+}
+
+impl RecordBatchMetrics {
+    /// Returns bytes read by the scans of the plan, that is the memory used by the leaf
+    /// operators of the plan.
+    ///
+    /// Metrics without plan metrics fall back to the memory used by the whole plan.
+    pub fn scanned_bytes(&self) -> usize {
+        if self.plan_metrics.is_empty() {
+            return self.memory_usage;
+        }
+        self.plan_metrics
+            .iter()
+            .enumerate()
+            .filter(|(i, plan_metrics)| {
+                // Operators are in pre-order, so a leaf isn't followed by a child.
+                self.plan_metrics
+                    .get(i + 1)
+                    .map_or(true, |next| next.level <= plan_metrics.level)
+            })
+            .flat_map(|(_, plan_metrics)| &plan_metrics.metrics)
+            .filter(|(name, _)| name == "mem_used")
+            .map(|(_, value)| value)
+            .sum()
+    }
+}
+
+impl Display for RecordBatchMetrics {
+    /// Formats the plan tree with metrics, indented by level.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for plan_metrics in &self.plan_metrics {
+            write!(
+                f,
+                "{:indent$}{}, metrics=[",
+                "",
+                plan_metrics.plan.trim_end(),
+                indent = plan_metrics.level * 2
+            )?;
+            for (i, (name, value)) in plan_metrics.metrics.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{name}={value}")?;
+            }
+            writeln!(f, "]")?;
+        }
+        Ok(())
+    }
 }
 
 enum AsyncRecordBatchStreamAdapterState {
@@ -471,4 +579,63 @@ mod test {
             "unexpected err {err}"
         );
     }
+
+    #[test]
+    fn test_display_record_batch_metrics() {
+        let metrics = RecordBatchMetrics {
+            elapsed_compute: 100,
+            memory_usage: 0,
+            plan_metrics: vec![
+                PlanMetrics {
+                    plan: "ProjectionExec: expr=[a@0 as a]".to_string(),
+                    level: 0,
+                    metrics: vec![
+                        ("output_rows".to_string(), 2),
+                        ("elapsed_compute".to_string(), 100),
+                    ],
+                },
+                PlanMetrics {
+                    plan: "MemoryExec: partitions=1".to_string(),
+                    level: 1,
+                    metrics: vec![],
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            "ProjectionExec: expr=[a@0 as a], metrics=[output_rows=2, elapsed_compute=100]\n  MemoryExec: partitions=1, metrics=[]\n",
+            metrics.to_string()
+        );
+
+        // metrics from older datanodes don't carry plan metrics
+        let metrics: RecordBatchMetrics =
+            serde_json::from_str(r#"{"elapsed_compute":1,"memory_usage":2}"#).unwrap();
+        assert!(metrics.plan_metrics.is_empty());
+    }
+
+    #[test]
+    fn test_scanned_bytes() {
+        let plan_metrics = |level, mem_used| PlanMetrics {
+            plan: String::new(),
+            level,
+            metrics: vec![("mem_used".to_string(), mem_used)],
+        };
+        // Only the scans at the leaves count.
+        let metrics = RecordBatchMetrics {
+            elapsed_compute: 0,
+            memory_usage: 1000,
+            plan_metrics: vec![
+                plan_metrics(0, 500),
+                plan_metrics(1, 100),
+                plan_metrics(1, 200),
+                plan_metrics(2, 300),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(400, metrics.scanned_bytes());
+
+        let metrics: RecordBatchMetrics =
+            serde_json::from_str(r#"{"elapsed_compute":1,"memory_usage":2}"#).unwrap();
+        assert_eq!(2, metrics.scanned_bytes());
+    }
 }
@@ -126,7 +126,6 @@ impl ErrorExt for Error {
 
             Error::DataTypes { .. }
             | Error::CreateRecordBatches { .. }
-            | Error::PollStream { .. }
             | Error::Format { .. }
             | Error::InitRecordbatchStream { .. }
             | Error::ColumnNotExists { .. }
@@ -135,6 +134,18 @@ impl ErrorExt for Error {
 
             Error::External { source, .. } => source.status_code(),
 
+            Error::PollStream { error, .. } => match error {
+                datafusion::error::DataFusionError::ResourcesExhausted(_) => {
+                    StatusCode::RuntimeResourcesExhausted
+                }
+                // Keeps the status code of errors from inner streams, e.g. streams of regions.
+                datafusion::error::DataFusionError::External(e) => e
+                    .downcast_ref::<Error>()
+                    .map(|e| e.status_code())
+                    .unwrap_or(StatusCode::Internal),
+                _ => StatusCode::Internal,
+            },
+
             Error::UnsupportedOperation { .. } => StatusCode::Unsupported,
 
             Error::SchemaConversion { source, .. } | Error::CastVector { source, .. } => {
@@ -142,6 +142,15 @@ impl RecordBatch {
         self.df_record_batch.num_rows()
     }
 
+    /// Returns a zero-copy slice of `len` rows starting from `offset`.
+    ///
+    /// # Panics
+    /// Panics if `offset + len` exceeds the number of rows.
+    pub fn slice(&self, offset: usize, len: usize) -> Result<RecordBatch> {
+        let columns = self.columns.iter().map(|column| column.slice(offset, len));
+        RecordBatch::new(self.schema.clone(), columns)
+    }
+
     /// Create an iterator to traverse the data by row
     pub fn rows(&self) -> RecordBatchRowIterator<'_> {
         RecordBatchRowIterator::new(self)
@@ -292,6 +301,27 @@ mod tests {
         assert_eq!(*batch.df_record_batch(), converted.into_df_record_batch());
     }
 
+    #[test]
+    fn test_record_batch_slice() {
+        let column_schemas = vec![ColumnSchema::new(
+            "number",
+            ConcreteDataType::uint32_datatype(),
+            false,
+        )];
+        let schema = Arc::new(Schema::try_new(column_schemas).unwrap());
+        let columns = vec![Arc::new(UInt32Vector::from_slice([1, 2, 3, 4])) as VectorRef];
+        let batch = RecordBatch::new(schema.clone(), columns).unwrap();
+
+        let sliced = batch.slice(1, 2).unwrap();
+        assert_eq!(2, sliced.num_rows());
+        assert_eq!(schema, sliced.schema);
+        assert_eq!(
+            Arc::new(UInt32Vector::from_slice([2, 3])) as VectorRef,
+            *sliced.column(0)
+        );
+        assert_eq!(0, batch.slice(4, 0).unwrap().num_rows());
+    }
+
     #[test]
     pub fn test_serialize_recordbatch() {
         let column_schemas = vec![ColumnSchema::new(
@@ -14,9 +14,9 @@
 
 //! logging stuffs, inspired by databend
 use std::env;
-use std::sync::{Arc, Mutex, Once};
+use std::sync::{Arc, Mutex, Once, RwLock};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
@@ -29,7 +29,7 @@ use tracing_log::LogTracer;
 use tracing_subscriber::fmt::Layer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::{filter, EnvFilter, Registry};
+use tracing_subscriber::{filter, reload, EnvFilter, Registry};
 
 use crate::tracing_sampler::{create_sampler, TracingSampleOptions};
 pub use crate::{debug, error, info, trace, warn};
@@ -118,6 +118,75 @@ static GLOBAL_UT_LOG_GUARD: Lazy<Arc<Mutex<Option<Vec<WorkerGuard>>>>> =
 
 const DEFAULT_LOG_TARGETS: &str = "info";
 
+type LogFilterReloader = Box<dyn Fn(filter::Targets) -> Result<(), String> + Send + Sync>;
+
+/// Reloads the filter of the global logging layers.
+static LOG_FILTER_RELOADER: OnceCell<LogFilterReloader> = OnceCell::new();
+
+/// The initial and the current log filter directives.
+static LOG_FILTERS: Lazy<RwLock<(String, String)>> =
+    Lazy::new(|| RwLock::new((String::new(), String::new())));
+
+fn set_log_filter_reloader(
+    initial_filter: &str,
+    reloader: impl Fn(filter::Targets) -> Result<(), String> + Send + Sync + 'static,
+) {
+    if LOG_FILTER_RELOADER.set(Box::new(reloader)).is_ok() {
+        *LOG_FILTERS.write().unwrap() = (initial_filter.to_string(), initial_filter.to_string());
+    }
+}
+
+/// Returns the log filter directives in effect, e.g. `info,mito2=debug`, or `None`
+/// if the global logging is not initialized.
+pub fn log_filter() -> Option<String> {
+    LOG_FILTER_RELOADER.get()?;
+    Some(LOG_FILTERS.read().unwrap().1.clone())
+}
+
+/// Replaces the log filter of the global logging with `directives` at runtime.
+pub fn reload_log_filter(directives: &str) -> Result<(), String> {
+    let reloader = LOG_FILTER_RELOADER
+        .get()
+        .ok_or_else(|| "global logging is not initialized".to_string())?;
+    let targets = directives
+        .parse::<filter::Targets>()
+        .map_err(|e| format!("invalid log filter '{directives}': {e}"))?;
+    reloader(targets)?;
+    LOG_FILTERS.write().unwrap().1 = directives.to_string();
+    Ok(())
+}
+
+/// Restores the log filter the global logging was initialized with.
+pub fn reset_log_filter() -> Result<(), String> {
+    let initial_filter = LOG_FILTERS.read().unwrap().0.clone();
+    reload_log_filter(&initial_filter)
+}
+
+/// Overrides the directives of `current` with `directives` of the same targets,
+/// e.g. merging `mito2=debug` into `info,mito2=warn` gives `info,mito2=debug`.
+pub fn merge_log_filter(current: &str, directives: &str) -> String {
+    fn target(directive: &str) -> &str {
+        directive
+            .rsplit_once('=')
+            .map(|(target, _)| target)
+            .unwrap_or_default()
+    }
+
+    let mut merged = current
+        .split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .collect::<Vec<_>>();
+    for directive in directives.split(',').map(str::trim) {
+        if directive.is_empty() {
+            continue;
+        }
+        merged.retain(|existing| target(existing) != target(directive));
+        merged.push(directive);
+    }
+    merged.join(",")
+}
+
 #[allow(clippy::print_stdout)]
 pub fn init_global_logging(
     app_name: &str,
@@ -201,9 +270,25 @@ pub fn init_global_logging(
             None
         };
 
-        let stdout_logging_layer = stdout_logging_layer.map(|x| x.with_filter(filter.clone()));
+        // Layers are filtered separately so that tokio-console still receives
+        // all the runtime spans, so each filter is reloaded on its own.
+        let (stdout_filter, stdout_filter_handle) = reload::Layer::new(filter.clone());
+        let (file_filter, file_filter_handle) = reload::Layer::new(filter);
+        let stdout_filter_handle = stdout_logging_layer
+            .is_some()
+            .then_some(stdout_filter_handle);
+        set_log_filter_reloader(targets_string, move |targets| {
+            if let Some(handle) = &stdout_filter_handle {
+                handle.reload(targets.clone()).map_err(|e| e.to_string())?;
+            }
+            file_filter_handle
+                .reload(targets)
+                .map_err(|e| e.to_string())
+        });
 
-        let file_logging_layer = file_logging_layer.with_filter(filter);
+        let stdout_logging_layer = stdout_logging_layer.map(|x| x.with_filter(stdout_filter));
+
+        let file_logging_layer = file_logging_layer.with_filter(file_filter);
 
         Registry::default()
             .with(tokio_console_layer)
@@ -215,6 +300,13 @@ pub fn init_global_logging(
     // consume the `tracing_opts`, to avoid "unused" warnings
     let _ = tracing_opts;
 
+    #[cfg(not(feature = "tokio-console"))]
+    let (filter, filter_handle) = reload::Layer::new(filter);
+    #[cfg(not(feature = "tokio-console"))]
+    set_log_filter_reloader(targets_string, move |targets| {
+        filter_handle.reload(targets).map_err(|e| e.to_string())
+    });
+
     #[cfg(not(feature = "tokio-console"))]
     let subscriber = Registry::default()
         .with(filter)
@@ -261,3 +353,22 @@ pub fn init_global_logging(
 
     guards
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_log_filter() {
+        assert_eq!("info,mito2=debug", merge_log_filter("info", "mito2=debug"));
+        assert_eq!(
+            "info,mito2=debug",
+            merge_log_filter("info,mito2=warn", "mito2=debug")
+        );
+        assert_eq!(
+            "mito2=warn,debug,servers=trace",
+            merge_log_filter("info,mito2=warn", " debug, servers=trace")
+        );
+        assert_eq!("info", merge_log_filter("", "info"));
+    }
+}
@@ -14,6 +14,8 @@
 
 //! Datanode configurations
 
+use std::time::Duration;
+
 use common_base::readable_size::ReadableSize;
 use common_grpc::channel_manager::{
     DEFAULT_MAX_GRPC_RECV_MESSAGE_SIZE, DEFAULT_MAX_GRPC_SEND_MESSAGE_SIZE,
@@ -24,12 +26,14 @@ use common_wal::config::DatanodeWalConfig;
 use file_engine::config::EngineConfig as FileEngineConfig;
 use meta_client::MetaClientOptions;
 use mito2::config::MitoConfig;
+use query::query_engine::options::QueryEngineOptions;
 use secrecy::SecretString;
 use serde::{Deserialize, Serialize};
 use servers::export_metrics::ExportMetricsOption;
 use servers::heartbeat_options::HeartbeatOptions;
 use servers::http::HttpOptions;
 use servers::Mode;
+use store_api::region_request::InsertMode;
 
 pub const DEFAULT_OBJECT_STORE_CACHE_SIZE: ReadableSize = ReadableSize::mb(256);
 
@@ -45,18 +49,38 @@ pub enum ObjectStoreConfig {
     Oss(OssConfig),
     Azblob(AzblobConfig),
     Gcs(GcsConfig),
+    Hdfs(HdfsConfig),
+    Webhdfs(WebhdfsConfig),
 }
 
 impl ObjectStoreConfig {
-    pub fn name(&self) -> &'static str {
+    /// Returns the type name of the object store.
+    pub fn provider_name(&self) -> &'static str {
         match self {
             Self::File(_) => "File",
             Self::S3(_) => "S3",
             Self::Oss(_) => "Oss",
             Self::Azblob(_) => "Azblob",
             Self::Gcs(_) => "Gcs",
+            Self::Hdfs(_) => "Hdfs",
+            Self::Webhdfs(_) => "Webhdfs",
         }
     }
+
+    /// Returns the name to reference the object store, e.g. in the `storage`
+    /// table option. Defaults to the type name of the object store.
+    pub fn name(&self) -> &str {
+        let name = match self {
+            Self::File(config) => &config.name,
+            Self::S3(config) => &config.name,
+            Self::Oss(config) => &config.name,
+            Self::Azblob(config) => &config.name,
+            Self::Gcs(config) => &config.name,
+            Self::Hdfs(config) => &config.name,
+            Self::Webhdfs(config) => &config.name,
+        };
+        name.as_deref().unwrap_or_else(|| self.provider_name())
+    }
 }
 
 /// Storage engine config
@@ -82,7 +106,10 @@ impl Default for StorageConfig {
 
 #[derive(Debug, Clone, Serialize, Default, Deserialize, Eq, PartialEq)]
 #[serde(default)]
-pub struct FileConfig {}
+pub struct FileConfig {
+    /// Name of the storage provider.
+    pub name: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -91,11 +118,84 @@ pub struct ObjectStorageCacheConfig {
     pub cache_path: Option<String>,
     /// The cache capacity in bytes
     pub cache_capacity: Option<ReadableSize>,
+    /// The capacity in bytes of the memory tier in front of the local file cache
+    pub cache_memory_capacity: Option<ReadableSize>,
+    /// Whether to prefetch the next range of sequential reads into the local file cache
+    pub cache_prefetch: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ObjectStorageMultipartConfig {
+    /// The size of each part of multipart uploads
+    pub multipart_part_size: Option<ReadableSize>,
+    /// The number of parts to upload concurrently
+    pub multipart_concurrency: Option<usize>,
+}
+
+/// Server side encryption of objects.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerSideEncryption {
+    /// Uses the default encryption of the bucket.
+    #[default]
+    Default,
+    /// Encrypts with keys managed by the storage service, e.g. SSE-S3.
+    Managed,
+    /// Encrypts with keys in the key management service, e.g. SSE-KMS.
+    Kms,
+    /// Encrypts with the key in `customer_key`, e.g. SSE-C.
+    Customer,
+}
+
+/// Where to load the credential of the object storage from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialSource {
+    /// Uses the credential in the config if present, otherwise loads it from the environment.
+    #[default]
+    Auto,
+    /// Only uses the credential in the config.
+    Static,
+    /// Loads the credential from the environment, e.g. environment variables,
+    /// workload identity or the instance metadata service, and refreshes it
+    /// before it expires.
+    Environment,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectStorageSecurityConfig {
+    /// Server side encryption of objects
+    pub server_side_encryption: ServerSideEncryption,
+    /// The key id in the key management service, used by `kms` encryption
+    pub kms_key_id: Option<String>,
+    /// Base64 encoded 256-bit key, used by `customer` encryption
+    #[serde(skip_serializing)]
+    pub customer_key: SecretString,
+    /// Whether the requester pays for requests and data transfer
+    pub request_payer: bool,
+    /// Where to load the credential from
+    pub credential_source: CredentialSource,
+}
+
+impl Default for ObjectStorageSecurityConfig {
+    fn default() -> Self {
+        Self {
+            server_side_encryption: ServerSideEncryption::default(),
+            kms_key_id: None,
+            customer_key: SecretString::from(String::default()),
+            request_payer: false,
+            credential_source: CredentialSource::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct S3Config {
+    /// Name of the storage provider.
+    pub name: Option<String>,
     pub bucket: String,
     pub root: String,
     #[serde(skip_serializing)]
@@ -106,11 +206,17 @@ pub struct S3Config {
     pub region: Option<String>,
     #[serde(flatten)]
     pub cache: ObjectStorageCacheConfig,
+    #[serde(flatten)]
+    pub multipart: ObjectStorageMultipartConfig,
+    #[serde(flatten)]
+    pub security: ObjectStorageSecurityConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct OssConfig {
+    /// Name of the storage provider.
+    pub name: Option<String>,
     pub bucket: String,
     pub root: String,
     #[serde(skip_serializing)]
@@ -120,11 +226,17 @@ pub struct OssConfig {
     pub endpoint: String,
     #[serde(flatten)]
     pub cache: ObjectStorageCacheConfig,
+    #[serde(flatten)]
+    pub multipart: ObjectStorageMultipartConfig,
+    #[serde(flatten)]
+    pub security: ObjectStorageSecurityConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AzblobConfig {
+    /// Name of the storage provider.
+    pub name: Option<String>,
     pub container: String,
     pub root: String,
     #[serde(skip_serializing)]
@@ -135,17 +247,61 @@ pub struct AzblobConfig {
     pub sas_token: Option<String>,
     #[serde(flatten)]
     pub cache: ObjectStorageCacheConfig,
+    #[serde(flatten)]
+    pub multipart: ObjectStorageMultipartConfig,
+    #[serde(flatten)]
+    pub security: ObjectStorageSecurityConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct GcsConfig {
+    /// Name of the storage provider.
+    pub name: Option<String>,
     pub root: String,
     pub bucket: String,
     pub scope: String,
     #[serde(skip_serializing)]
     pub credential_path: SecretString,
+    /// Base64 encoded content of the credential, used if `credential_path` is empty
+    #[serde(skip_serializing)]
+    pub credential: SecretString,
+    pub endpoint: String,
+    #[serde(flatten)]
+    pub cache: ObjectStorageCacheConfig,
+    #[serde(flatten)]
+    pub multipart: ObjectStorageMultipartConfig,
+    #[serde(flatten)]
+    pub security: ObjectStorageSecurityConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct HdfsConfig {
+    /// Name of the storage provider.
+    pub name: Option<String>,
+    /// Address of the name node, e.g. `hdfs://127.0.0.1:9000`
+    pub name_node: String,
+    pub root: String,
+    /// The user to access HDFS as
+    pub user: Option<String>,
+    /// Path of the kerberos ticket cache, enables kerberos authentication if present
+    pub kerberos_ticket_cache_path: Option<String>,
+    #[serde(flatten)]
+    pub cache: ObjectStorageCacheConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhdfsConfig {
+    /// Name of the storage provider.
+    pub name: Option<String>,
+    /// Address of the WebHDFS service of the name node, e.g. `http://127.0.0.1:9870`
     pub endpoint: String,
+    pub root: String,
+    /// Delegation token, e.g. fetched by `hdfs fetchdt` on a kerberized cluster
+    #[serde(skip_serializing)]
+    pub delegation: SecretString,
     #[serde(flatten)]
     pub cache: ObjectStorageCacheConfig,
 }
@@ -153,6 +309,7 @@ pub struct GcsConfig {
 impl Default for S3Config {
     fn default() -> Self {
         Self {
+            name: None,
             bucket: String::default(),
             root: String::default(),
             access_key_id: SecretString::from(String::default()),
@@ -160,6 +317,8 @@ impl Default for S3Config {
             endpoint: Option::default(),
             region: Option::default(),
             cache: ObjectStorageCacheConfig::default(),
+            multipart: ObjectStorageMultipartConfig::default(),
+            security: ObjectStorageSecurityConfig::default(),
         }
     }
 }
@@ -167,12 +326,15 @@ impl Default for S3Config {
 impl Default for OssConfig {
     fn default() -> Self {
         Self {
+            name: None,
             bucket: String::default(),
             root: String::default(),
             access_key_id: SecretString::from(String::default()),
             access_key_secret: SecretString::from(String::default()),
             endpoint: String::default(),
             cache: ObjectStorageCacheConfig::default(),
+            multipart: ObjectStorageMultipartConfig::default(),
+            security: ObjectStorageSecurityConfig::default(),
         }
     }
 }
@@ -180,6 +342,7 @@ impl Default for OssConfig {
 impl Default for AzblobConfig {
     fn default() -> Self {
         Self {
+            name: None,
             container: String::default(),
             root: String::default(),
             account_name: SecretString::from(String::default()),
@@ -187,6 +350,8 @@ impl Default for AzblobConfig {
             endpoint: String::default(),
             sas_token: Option::default(),
             cache: ObjectStorageCacheConfig::default(),
+            multipart: ObjectStorageMultipartConfig::default(),
+            security: ObjectStorageSecurityConfig::default(),
         }
     }
 }
@@ -194,19 +359,61 @@ impl Default for AzblobConfig {
 impl Default for GcsConfig {
     fn default() -> Self {
         Self {
+            name: None,
             root: String::default(),
             bucket: String::default(),
             scope: String::default(),
             credential_path: SecretString::from(String::default()),
+            credential: SecretString::from(String::default()),
             endpoint: String::default(),
             cache: ObjectStorageCacheConfig::default(),
+            multipart: ObjectStorageMultipartConfig::default(),
+            security: ObjectStorageSecurityConfig::default(),
         }
     }
 }
 
+impl Default for WebhdfsConfig {
+    fn default() -> Self {
+        Self {
+            name: None,
+            endpoint: String::default(),
+            root: String::default(),
+            delegation: SecretString::from(String::default()),
+            cache: ObjectStorageCacheConfig::default(),
+        }
+    }
+}
+
+impl ObjectStoreConfig {
+    /// Returns the security options of the object store, `None` for the file store and HDFS.
+    pub fn security(&self) -> Option<&ObjectStorageSecurityConfig> {
+        match self {
+            Self::File(_) | Self::Hdfs(_) | Self::Webhdfs(_) => None,
+            Self::S3(config) => Some(&config.security),
+            Self::Oss(config) => Some(&config.security),
+            Self::Azblob(config) => Some(&config.security),
+            Self::Gcs(config) => Some(&config.security),
+        }
+    }
+
+    /// Returns the endpoint of the object store if it is overridden.
+    pub fn endpoint(&self) -> Option<&str> {
+        let endpoint = match self {
+            Self::File(_) | Self::Hdfs(_) => return None,
+            Self::S3(config) => config.endpoint.as_deref().unwrap_or_default(),
+            Self::Oss(config) => &config.endpoint,
+            Self::Azblob(config) => &config.endpoint,
+            Self::Gcs(config) => &config.endpoint,
+            Self::Webhdfs(config) => &config.endpoint,
+        };
+        (!endpoint.is_empty()).then_some(endpoint)
+    }
+}
+
 impl Default for ObjectStoreConfig {
     fn default() -> Self {
-        ObjectStoreConfig::File(FileConfig {})
+        ObjectStoreConfig::File(FileConfig::default())
     }
 }
 
@@ -231,9 +438,11 @@ pub struct DatanodeOptions {
     pub storage: StorageConfig,
     /// Options for different store engines.
     pub region_engine: Vec<RegionEngineConfig>,
+    pub query: QueryEngineOptions,
     pub logging: LoggingOptions,
     pub enable_telemetry: bool,
     pub export_metrics: ExportMetricsOption,
+    pub replication: ReplicationOptions,
 }
 
 impl Default for DatanodeOptions {
@@ -256,10 +465,12 @@ impl Default for DatanodeOptions {
                 RegionEngineConfig::Mito(MitoConfig::default()),
                 RegionEngineConfig::File(FileEngineConfig::default()),
             ],
+            query: QueryEngineOptions::default(),
             logging: LoggingOptions::default(),
             heartbeat: HeartbeatOptions::datanode_default(),
             enable_telemetry: true,
             export_metrics: ExportMetricsOption::default(),
+            replication: ReplicationOptions::default(),
         }
     }
 }
@@ -283,6 +494,54 @@ pub enum RegionEngineConfig {
     File(FileEngineConfig),
 }
 
+/// How the standby resolves rows that already exist with the same primary key
+/// and timestamp.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// The replicated row replaces the existing one.
+    #[default]
+    Overwrite,
+    /// The existing row on the standby is kept.
+    Ignore,
+}
+
+impl From<ConflictPolicy> for InsertMode {
+    fn from(policy: ConflictPolicy) -> Self {
+        match policy {
+            ConflictPolicy::Overwrite => InsertMode::Overwrite,
+            ConflictPolicy::Ignore => InsertMode::IgnoreDuplicates,
+        }
+    }
+}
+
+/// Options to replicate the WAL of leader regions to a standby cluster.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReplicationOptions {
+    pub enable: bool,
+    /// gRPC addresses of the standby cluster's frontends.
+    pub standby_addrs: Vec<String>,
+    pub conflict_policy: ConflictPolicy,
+    /// Max rows sent to the standby in one request.
+    pub batch_size: usize,
+    /// Interval to read new WAL entries of regions, and to retry regions failed to replicate.
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+}
+
+impl Default for ReplicationOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            standby_addrs: vec![],
+            conflict_policy: ConflictPolicy::default(),
+            batch_size: 4096,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use secrecy::ExposeSecret;
@@ -296,6 +555,26 @@ mod tests {
         let _parsed: DatanodeOptions = toml::from_str(&toml_string).unwrap();
     }
 
+    #[test]
+    fn test_replication_options() {
+        assert_eq!(InsertMode::Overwrite, ConflictPolicy::Overwrite.into());
+        assert_eq!(InsertMode::IgnoreDuplicates, ConflictPolicy::Ignore.into());
+
+        let toml_str = r#"
+            [replication]
+            enable = true
+            standby_addrs = ["10.0.0.1:4001"]
+            conflict_policy = "ignore"
+            poll_interval = "500ms"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let options = opts.replication;
+        assert!(options.enable);
+        assert_eq!(ConflictPolicy::Ignore, options.conflict_policy);
+        assert_eq!(Duration::from_millis(500), options.poll_interval);
+        assert_eq!(ReplicationOptions::default().batch_size, options.batch_size);
+    }
+
     #[test]
     fn test_secstr() {
         let toml_str = r#"
@@ -316,4 +595,130 @@ mod tests {
             _ => unreachable!(),
         }
     }
+    #[test]
+    fn test_storage_provider_name() {
+        let toml_str = r#"
+            [storage]
+            type = "File"
+
+            [[storage.providers]]
+            type = "S3"
+            bucket = "foo"
+
+            [[storage.providers]]
+            name = "warm_s3"
+            type = "S3"
+            bucket = "bar"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        assert_eq!("File", opts.storage.store.name());
+        assert_eq!("S3", opts.storage.providers[0].name());
+        assert_eq!("warm_s3", opts.storage.providers[1].name());
+        assert_eq!("S3", opts.storage.providers[1].provider_name());
+    }
+    #[test]
+    fn test_multipart_config() {
+        let toml_str = r#"
+            [storage]
+            type = "Gcs"
+            bucket = "foo"
+            credential = "base64-credential"
+            multipart_part_size = "16MiB"
+            multipart_concurrency = 4
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        match &opts.storage.store {
+            ObjectStoreConfig::Gcs(cfg) => {
+                assert_eq!("base64-credential", cfg.credential.expose_secret());
+                assert_eq!(
+                    Some(ReadableSize::mb(16)),
+                    cfg.multipart.multipart_part_size
+                );
+                assert_eq!(Some(4), cfg.multipart.multipart_concurrency);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_hdfs_config() {
+        let toml_str = r#"
+            [storage]
+            type = "Hdfs"
+            name_node = "hdfs://127.0.0.1:9000"
+            root = "greptimedb"
+            kerberos_ticket_cache_path = "/tmp/krb5cc_1000"
+
+            [[storage.providers]]
+            type = "Webhdfs"
+            endpoint = "http://127.0.0.1:9870"
+            delegation = "token"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        match &opts.storage.store {
+            ObjectStoreConfig::Hdfs(cfg) => {
+                assert_eq!("hdfs://127.0.0.1:9000", cfg.name_node);
+                assert_eq!(
+                    Some("/tmp/krb5cc_1000"),
+                    cfg.kerberos_ticket_cache_path.as_deref()
+                );
+            }
+            _ => unreachable!(),
+        }
+        match &opts.storage.providers[0] {
+            ObjectStoreConfig::Webhdfs(cfg) => {
+                assert_eq!("token", cfg.delegation.expose_secret());
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(None, opts.storage.store.endpoint());
+        assert_eq!(
+            Some("http://127.0.0.1:9870"),
+            opts.storage.providers[0].endpoint()
+        );
+    }
+
+    #[test]
+    fn test_security_config() {
+        let toml_str = r#"
+            [storage]
+            type = "S3"
+            bucket = "foo"
+            server_side_encryption = "kms"
+            kms_key_id = "arn:aws:kms:us-west-2:123456789012:key/foo"
+            request_payer = true
+            credential_source = "environment"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let security = opts.storage.store.security().unwrap();
+        assert_eq!(ServerSideEncryption::Kms, security.server_side_encryption);
+        assert_eq!(
+            Some("arn:aws:kms:us-west-2:123456789012:key/foo"),
+            security.kms_key_id.as_deref()
+        );
+        assert!(security.request_payer);
+        assert_eq!(CredentialSource::Environment, security.credential_source);
+        assert_eq!(None, opts.storage.store.endpoint());
+
+        let toml_str = r#"
+            [storage]
+            type = "Azblob"
+            container = "foo"
+            endpoint = "https://foo.blob.core.windows.net"
+            server_side_encryption = "customer"
+            customer_key = "base64-key"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let security = opts.storage.store.security().unwrap();
+        assert_eq!(
+            ServerSideEncryption::Customer,
+            security.server_side_encryption
+        );
+        assert_eq!("base64-key", security.customer_key.expose_secret());
+        assert_eq!(CredentialSource::Auto, security.credential_source);
+        assert_eq!(
+            Some("https://foo.blob.core.windows.net"),
+            opts.storage.store.endpoint()
+        );
+    }
 }
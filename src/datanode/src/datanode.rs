@@ -22,6 +22,7 @@ use common_base::Plugins;
 use common_error::ext::BoxedError;
 use common_greptimedb_telemetry::GreptimeDBTelemetryTask;
 use common_meta::key::datanode_table::{DatanodeTableManager, DatanodeTableValue};
+use common_meta::key::table_route::TableRouteStorage;
 use common_meta::kv_backend::KvBackendRef;
 use common_meta::wal_options_allocator::prepare_wal_options;
 pub use common_procedure::options::ProcedureConfig;
@@ -45,7 +46,7 @@ use query::QueryEngineFactory;
 use servers::export_metrics::ExportMetricsTask;
 use servers::server::ServerHandlers;
 use servers::Mode;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::path_utils::{region_dir, WAL_DIR};
 use store_api::region_engine::RegionEngineRef;
 use store_api::region_request::{RegionOpenRequest, RegionRequest};
@@ -55,9 +56,9 @@ use tokio::sync::Notify;
 
 use crate::config::{DatanodeOptions, RegionEngineConfig};
 use crate::error::{
-    BuildMitoEngineSnafu, CreateDirSnafu, GetMetadataSnafu, MissingKvBackendSnafu,
-    MissingNodeIdSnafu, OpenLogStoreSnafu, Result, RuntimeResourceSnafu, ShutdownInstanceSnafu,
-    ShutdownServerSnafu, StartServerSnafu,
+    BuildMitoEngineSnafu, CreateDirSnafu, DuplicateStorageProviderSnafu, GetMetadataSnafu,
+    MissingKvBackendSnafu, MissingNodeIdSnafu, OpenLogStoreSnafu, Result, RuntimeResourceSnafu,
+    ShutdownInstanceSnafu, ShutdownServerSnafu, StartServerSnafu,
 };
 use crate::event_listener::{
     new_region_server_event_channel, NoopRegionServerEventListener, RegionServerEventListenerRef,
@@ -66,6 +67,7 @@ use crate::event_listener::{
 use crate::greptimedb_telemetry::get_greptimedb_telemetry_task;
 use crate::heartbeat::HeartbeatTask;
 use crate::region_server::{DummyTableProviderFactory, RegionServer};
+use crate::replication::ReplicationTask;
 use crate::store;
 
 const OPEN_REGION_PARALLELISM: usize = 16;
@@ -80,6 +82,7 @@ pub struct Datanode {
     leases_notifier: Option<Arc<Notify>>,
     plugins: Plugins,
     export_metrics_task: Option<ExportMetricsTask>,
+    replication_task: Option<ReplicationTask>,
 }
 
 impl Datanode {
@@ -95,6 +98,8 @@ impl Datanode {
             t.start(None).context(StartServerSnafu)?
         }
 
+        self.start_replication();
+
         self.services.start_all().await.context(StartServerSnafu)
     }
 
@@ -108,6 +113,13 @@ impl Datanode {
         }
     }
 
+    /// Starts replicating leader regions to the standby cluster if it's enabled.
+    pub fn start_replication(&self) {
+        if let Some(task) = &self.replication_task {
+            task.start();
+        }
+    }
+
     pub async fn start_heartbeat(&mut self) -> Result<()> {
         if let Some(task) = &self.heartbeat_task {
             // Safety: The event_receiver must exist.
@@ -136,6 +148,9 @@ impl Datanode {
             .context(ShutdownServerSnafu)?;
 
         let _ = self.greptimedb_telemetry_task.stop().await;
+        if let Some(task) = &self.replication_task {
+            task.stop();
+        }
         if let Some(heartbeat_task) = &self.heartbeat_task {
             heartbeat_task
                 .close()
@@ -219,6 +234,9 @@ impl DatanodeBuilder {
             .await
             .context(GetMetadataSnafu)?;
 
+        if controlled_by_metasrv {
+            load_leader_epochs(&region_server, &kv_backend, node_id, &table_values).await?;
+        }
         let open_all_regions =
             open_all_regions(region_server.clone(), table_values, !controlled_by_metasrv);
 
@@ -257,6 +275,14 @@ impl DatanodeBuilder {
             ExportMetricsTask::try_new(&self.opts.export_metrics, Some(&self.plugins))
                 .context(StartServerSnafu)?;
 
+        let replication_task = self.opts.replication.enable.then(|| {
+            ReplicationTask::new(
+                self.opts.replication.clone(),
+                region_server.clone(),
+                kv_backend.clone(),
+            )
+        });
+
         Ok(Datanode {
             services: ServerHandlers::default(),
             heartbeat_task,
@@ -266,6 +292,7 @@ impl DatanodeBuilder {
             leases_notifier,
             plugins: self.plugins.clone(),
             export_metrics_task,
+            replication_task,
         })
     }
 
@@ -342,9 +369,10 @@ impl DatanodeBuilder {
         for engine in &opts.region_engine {
             match engine {
                 RegionEngineConfig::Mito(config) => {
+                    let mut config = config.clone();
+                    config.retain_wal_for_replication = opts.replication.enable;
                     let mito_engine =
-                        Self::build_mito_engine(opts, object_store_manager.clone(), config.clone())
-                            .await?;
+                        Self::build_mito_engine(opts, object_store_manager.clone(), config).await?;
 
                     let metric_engine = MetricEngine::new(mito_engine.clone());
                     engines.push(Arc::new(mito_engine) as _);
@@ -433,6 +461,10 @@ impl DatanodeBuilder {
         let default_name = opts.storage.store.name();
         let mut object_store_manager = ObjectStoreManager::new(default_name, object_store);
         for store in &opts.storage.providers {
+            ensure!(
+                object_store_manager.find(store.name()).is_none(),
+                DuplicateStorageProviderSnafu { name: store.name() }
+            );
             object_store_manager.add(
                 store.name(),
                 store::new_object_store(store.clone(), &opts.storage.data_home).await?,
@@ -442,6 +474,36 @@ impl DatanodeBuilder {
     }
 }
 
+/// Loads the leader epochs of regions led by this datanode from their table routes,
+/// so writes routed by stale routes are fenced by the epochs issued by metasrv.
+async fn load_leader_epochs(
+    region_server: &RegionServer,
+    kv_backend: &KvBackendRef,
+    node_id: u64,
+    table_values: &[DatanodeTableValue],
+) -> Result<()> {
+    let table_ids = table_values
+        .iter()
+        .map(|table_value| table_value.table_id)
+        .collect::<Vec<_>>();
+    let table_routes = TableRouteStorage::new(kv_backend.clone())
+        .batch_get(&table_ids)
+        .await
+        .context(GetMetadataSnafu)?;
+    for table_route in table_routes.iter().flatten() {
+        let Ok(region_routes) = table_route.region_routes() else {
+            // Logical tables have no regions.
+            continue;
+        };
+        for route in region_routes {
+            if route.leader_peer.as_ref().map(|peer| peer.id) == Some(node_id) {
+                region_server.set_leader_epoch(route.region.id, route.leader_epoch);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Open all regions belong to this datanode.
 async fn open_all_regions(
     region_server: RegionServer,
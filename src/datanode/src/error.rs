@@ -20,7 +20,7 @@ use common_error::status_code::StatusCode;
 use common_macro::stack_trace_debug;
 use servers::define_into_tonic_status;
 use snafu::{Location, Snafu};
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, TableId};
 use table::error::Error as TableError;
 
 /// Business error of datanode.
@@ -205,6 +205,16 @@ pub enum Error {
     #[snafu(display("Missing WAL dir config"))]
     MissingWalDirConfig { location: Location },
 
+    #[snafu(display("Duplicate storage provider: {}", name))]
+    DuplicateStorageProvider { name: String, location: Location },
+
+    #[snafu(display("Invalid config of storage provider {}: {}", name, reason))]
+    InvalidStorageConfig {
+        name: String,
+        reason: String,
+        location: Location,
+    },
+
     #[snafu(display("Unexpected, violated: {}", violated))]
     Unexpected {
         violated: String,
@@ -236,6 +246,32 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Write to region {} is routed by a stale leader epoch {}, current epoch: {}",
+        region_id,
+        epoch,
+        current
+    ))]
+    StaleLeaderEpoch {
+        region_id: RegionId,
+        epoch: u64,
+        current: u64,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Region {} is fenced, a newer leader epoch {} is found, current epoch: {}",
+        region_id,
+        epoch,
+        current
+    ))]
+    RegionFenced {
+        region_id: RegionId,
+        epoch: u64,
+        current: u64,
+        location: Location,
+    },
+
     #[snafu(display("Region engine {} is not registered", name))]
     RegionEngineNotFound { name: String, location: Location },
 
@@ -288,6 +324,40 @@ pub enum Error {
         source: mito2::error::Error,
         location: Location,
     },
+
+    #[snafu(display("Failed to read WAL of region {}", region_id))]
+    ReadWal {
+        region_id: RegionId,
+        source: mito2::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to access the replication offset of region {}", region_id))]
+    ReplicationOffset {
+        region_id: RegionId,
+        source: common_meta::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid info of table {}", table_id))]
+    InvalidTableInfo {
+        table_id: TableId,
+        source: datatypes::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to build the create statement of table {}", table_id))]
+    BuildCreateTableStmt {
+        table_id: TableId,
+        source: query::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to send request to the standby cluster"))]
+    RequestStandby {
+        source: client::error::Error,
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -320,6 +390,8 @@ impl ErrorExt for Error {
             | MissingNodeId { .. }
             | ColumnNoneDefaultValue { .. }
             | MissingWalDirConfig { .. }
+            | DuplicateStorageProvider { .. }
+            | InvalidStorageConfig { .. }
             | MissingKvBackend { .. } => StatusCode::InvalidArguments,
 
             PayloadNotExist { .. } | Unexpected { .. } | WatchAsyncTaskChange { .. } => {
@@ -343,6 +415,8 @@ impl ErrorExt for Error {
             RegionNotFound { .. } => StatusCode::RegionNotFound,
             RegionNotReady { .. } => StatusCode::RegionNotReady,
             RegionBusy { .. } => StatusCode::RegionBusy,
+            StaleLeaderEpoch { .. } => StatusCode::RegionNotReady,
+            RegionFenced { .. } => StatusCode::RegionReadonly,
 
             StartServer { source, .. } | ShutdownServer { source, .. } => source.status_code(),
 
@@ -359,6 +433,11 @@ impl ErrorExt for Error {
 
             FindLogicalRegions { source, .. } => source.status_code(),
             BuildMitoEngine { source, .. } => source.status_code(),
+            ReadWal { source, .. } => source.status_code(),
+            ReplicationOffset { source, .. } => source.status_code(),
+            InvalidTableInfo { source, .. } => source.status_code(),
+            BuildCreateTableStmt { source, .. } => source.status_code(),
+            RequestStandby { source, .. } => source.status_code(),
         }
     }
 
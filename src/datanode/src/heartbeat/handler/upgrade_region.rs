@@ -28,6 +28,7 @@ impl HandlerContext {
             region_id,
             last_entry_id,
             wait_for_replay_timeout,
+            leader_epoch,
         }: UpgradeRegion,
     ) -> BoxFuture<'static, InstructionReply> {
         Box::pin(async move {
@@ -39,6 +40,10 @@ impl HandlerContext {
                 });
             };
 
+            if let Some(leader_epoch) = leader_epoch {
+                self.region_server.set_leader_epoch(region_id, leader_epoch);
+            }
+
             if writable {
                 return InstructionReply::UpgradeRegion(UpgradeRegionReply {
                     ready: true,
@@ -151,6 +156,7 @@ mod tests {
                     region_id,
                     last_entry_id: None,
                     wait_for_replay_timeout,
+                    leader_epoch: None,
                 })
                 .await;
             assert_matches!(reply, InstructionReply::UpgradeRegion(_));
@@ -190,6 +196,7 @@ mod tests {
                     region_id,
                     last_entry_id: None,
                     wait_for_replay_timeout,
+                    leader_epoch: None,
                 })
                 .await;
             assert_matches!(reply, InstructionReply::UpgradeRegion(_));
@@ -230,6 +237,7 @@ mod tests {
                     region_id,
                     last_entry_id: None,
                     wait_for_replay_timeout,
+                    leader_epoch: None,
                 })
                 .await;
             assert_matches!(reply, InstructionReply::UpgradeRegion(_));
@@ -273,6 +281,7 @@ mod tests {
                     region_id,
                     last_entry_id: None,
                     wait_for_replay_timeout,
+                    leader_epoch: None,
                 })
                 .await;
             assert_matches!(reply, InstructionReply::UpgradeRegion(_));
@@ -290,6 +299,7 @@ mod tests {
                 region_id,
                 last_entry_id: None,
                 wait_for_replay_timeout: Some(Duration::from_millis(500)),
+                leader_epoch: None,
             })
             .await;
         assert_matches!(reply, InstructionReply::UpgradeRegion(_));
@@ -333,6 +343,7 @@ mod tests {
                 region_id,
                 last_entry_id: None,
                 wait_for_replay_timeout: None,
+                leader_epoch: None,
             })
             .await;
         assert_matches!(reply, InstructionReply::UpgradeRegion(_));
@@ -350,6 +361,7 @@ mod tests {
                 region_id,
                 last_entry_id: None,
                 wait_for_replay_timeout: Some(Duration::from_millis(200)),
+                leader_epoch: None,
             })
             .await;
         assert_matches!(reply, InstructionReply::UpgradeRegion(_));
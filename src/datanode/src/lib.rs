@@ -22,7 +22,10 @@ pub mod event_listener;
 mod greptimedb_telemetry;
 pub mod heartbeat;
 pub mod metrics;
+#[cfg(feature = "pprof")]
+mod profile;
 pub mod region_server;
+pub mod replication;
 pub mod service;
 mod store;
 #[cfg(any(test, feature = "testing"))]
@@ -20,6 +20,8 @@ pub const REGION_REQUEST_TYPE: &str = "datanode_region_request_type";
 
 pub const REGION_ROLE: &str = "region_role";
 pub const REGION_ID: &str = "region_id";
+/// Type of replicated writes label.
+pub const REPLICATION_WRITE_TYPE: &str = "write_type";
 
 lazy_static! {
     /// The elapsed time of handling a request in the region_server.
@@ -41,6 +43,31 @@ lazy_static! {
         &[REGION_ID]
     )
     .unwrap();
+    /// Rows replicated to the standby cluster, by the type of writes.
+    pub static ref REPLICATION_ROWS: IntCounterVec = register_int_counter_vec!(
+        "greptime_datanode_replication_rows",
+        "datanode replicated rows",
+        &[REPLICATION_WRITE_TYPE]
+    )
+    .unwrap();
+    /// Rows not replicated as their tables are dropped.
+    pub static ref REPLICATION_SKIPPED_ROWS: IntCounter = register_int_counter!(
+        "greptime_datanode_replication_skipped_rows",
+        "datanode replication skipped rows of dropped tables"
+    )
+    .unwrap();
+    /// Failures of replicating a region, which are retried in the next round.
+    pub static ref REPLICATION_FAILURES: IntCounter = register_int_counter!(
+        "greptime_datanode_replication_failures",
+        "datanode replication failures"
+    )
+    .unwrap();
+    /// Seconds since the most lagging region replicated all its WAL entries.
+    pub static ref REPLICATION_LAG: Gauge = register_gauge!(
+        "greptime_datanode_replication_lag_seconds",
+        "datanode replication lag in seconds"
+    )
+    .unwrap();
     /// The received region leases via heartbeat.
     pub static ref HEARTBEAT_REGION_LEASES: IntGaugeVec = register_int_gauge_vec!(
         "greptime_heartbeat_region_leases",
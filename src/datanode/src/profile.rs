@@ -0,0 +1,96 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Profiles of region queries, asked by frontends running `EXPLAIN PROFILE`.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use common_pprof::Profiler;
+use common_recordbatch::adapter::RecordBatchMetrics;
+use common_recordbatch::{OrderOption, RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use common_telemetry::warn;
+use datatypes::schema::SchemaRef;
+use futures::{Stream, StreamExt};
+
+/// A stream that samples the process until the inner stream finishes, and returns the
+/// samples in its metrics.
+pub(crate) struct ProfiledStream {
+    stream: SendableRecordBatchStream,
+    profiler: Option<Profiler>,
+    stacks: Vec<(Vec<String>, i64)>,
+}
+
+impl ProfiledStream {
+    /// Profiles the process at `frequency` per second while `stream` runs. Returns
+    /// `stream` as is if another profile of the process is running, e.g. the frontend
+    /// of a standalone instance profiles the whole process.
+    pub(crate) fn wrap(
+        stream: SendableRecordBatchStream,
+        frequency: i32,
+    ) -> SendableRecordBatchStream {
+        match Profiler::try_start(frequency) {
+            Ok(Some(profiler)) => Box::pin(ProfiledStream {
+                stream,
+                profiler: Some(profiler),
+                stacks: Vec::new(),
+            }),
+            Ok(None) => stream,
+            Err(e) => {
+                warn!(e; "Failed to profile the region query");
+                stream
+            }
+        }
+    }
+}
+
+impl RecordBatchStream for ProfiledStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn output_ordering(&self) -> Option<&[OrderOption]> {
+        self.stream.output_ordering()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        if self.stacks.is_empty() {
+            return self.stream.metrics();
+        }
+        let mut metrics = self.stream.metrics().unwrap_or_default();
+        metrics.profile_stacks = self.stacks.clone();
+        Some(metrics)
+    }
+}
+
+impl Stream for ProfiledStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.stream.poll_next_unpin(cx);
+        if let Poll::Ready(None) = poll {
+            if let Some(profiler) = self.profiler.take() {
+                match profiler.samples() {
+                    Ok(samples) => self.stacks = samples.stacks,
+                    Err(e) => warn!(e; "Failed to collect the profile of the region query"),
+                }
+            }
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
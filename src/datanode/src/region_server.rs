@@ -13,12 +13,13 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::sync::{Arc, Mutex, RwLock};
 
-use api::v1::region::{region_request, QueryRequest, RegionResponse};
+use api::v1::region::{region_request, QueryRequest, RegionRequestHeader, RegionResponse};
 use api::v1::{ResponseHeader, Status};
 use arrow_flight::{FlightData, Ticket};
 use async_trait::async_trait;
@@ -29,11 +30,12 @@ use common_meta::datanode_manager::HandleResponse;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::DfPhysicalPlanAdapter;
 use common_query::{DfPhysicalPlan, OutputData};
+use common_recordbatch::adapter::CollectPlanMetrics;
 use common_recordbatch::SendableRecordBatchStream;
 use common_runtime::Runtime;
 use common_telemetry::tracing::{self, info_span};
 use common_telemetry::tracing_context::{FutureExt, TracingContext};
-use common_telemetry::{info, warn};
+use common_telemetry::{error, info, warn};
 use dashmap::DashMap;
 use datafusion::catalog::schema::SchemaProvider;
 use datafusion::catalog::{CatalogList, CatalogProvider};
@@ -50,12 +52,17 @@ use query::QueryEngineRef;
 use servers::error::{self as servers_error, ExecuteGrpcRequestSnafu, Result as ServerResult};
 use servers::grpc::flight::{FlightCraft, FlightRecordBatchStream, TonicStream};
 use servers::grpc::region_server::RegionServerHandler;
-use session::context::{QueryContextBuilder, QueryContextRef};
+#[cfg(feature = "pprof")]
+use session::context::PROFILE_FREQUENCY_KEY;
+use session::context::{QueryContextBuilder, QueryContextRef, PLAN_METRICS_KEY};
 use snafu::{OptionExt, ResultExt};
 use store_api::metadata::RegionMetadataRef;
 use store_api::metric_engine_consts::{METRIC_ENGINE_NAME, PHYSICAL_TABLE_METADATA_KEY};
 use store_api::region_engine::{RegionEngineRef, RegionRole, SetReadonlyResponse};
-use store_api::region_request::{AffectedRows, RegionCloseRequest, RegionRequest};
+use store_api::region_request::{
+    AffectedRows, CompactOptions, InsertMode, LeaderEpochs, RegionCloseRequest,
+    RegionCompactRequest, RegionPutRequest, RegionReplayWalRequest, RegionRequest, WalReplaySource,
+};
 use store_api::storage::{RegionId, ScanRequest};
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::table::scan::StreamScanAdapter;
@@ -114,6 +121,11 @@ impl RegionServer {
         self.inner.register_engine(engine);
     }
 
+    /// Finds a registered engine by its name.
+    pub fn find_engine_by_name(&self, name: &str) -> Option<RegionEngineRef> {
+        self.inner.engines.read().unwrap().get(name).cloned()
+    }
+
     /// Finds the region's engine by its id. If the region is not ready, returns `None`.
     pub fn find_engine(&self, region_id: RegionId) -> Result<Option<RegionEngineRef>> {
         self.inner
@@ -133,6 +145,24 @@ impl RegionServer {
         self.inner.handle_request(region_id, request).await
     }
 
+    /// Handles a write request.
+    ///
+    /// The write is fenced if it's routed by a leader epoch different from the region's,
+    /// see [RegionServer::check_leader_epoch].
+    async fn handle_write_request(
+        &self,
+        region_id: RegionId,
+        request: RegionRequest,
+        leader_epoch: Option<u64>,
+    ) -> Result<HandleResponse> {
+        // Writes from nodes that don't send epochs yet aren't fenced, so clusters can be
+        // upgraded node by node.
+        if let Some(epoch) = leader_epoch {
+            self.check_leader_epoch(region_id, epoch)?;
+        }
+        self.handle_request(region_id, request).await
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn handle_read(&self, request: QueryRequest) -> Result<SendableRecordBatchStream> {
         self.inner.handle_read(request).await
@@ -157,6 +187,51 @@ impl RegionServer {
             .collect()
     }
 
+    /// Sets the leader epoch granted to the region by metasrv.
+    pub fn set_leader_epoch(&self, region_id: RegionId, epoch: u64) {
+        info!("Region {region_id} is granted leader epoch {epoch}");
+        self.inner.leader_epochs.insert(region_id, epoch);
+    }
+
+    /// Checks the leader epoch a write is routed by against the epoch of the region.
+    ///
+    /// - A smaller epoch means the write is routed by a stale route, it's rejected and should be retried.
+    /// - A larger epoch means another leader has been elected after this one, so the region is
+    ///   fenced: it's set to readonly until metasrv grants it the leader role again.
+    ///
+    /// The epoch of a region is granted by metasrv when the region is upgraded to the leader,
+    /// or loaded from its table route when the datanode starts. The region adopts the epoch
+    /// of the first write if metasrv has not granted one, e.g., a region just created.
+    fn check_leader_epoch(&self, region_id: RegionId, epoch: u64) -> Result<()> {
+        let current = *self
+            .inner
+            .leader_epochs
+            .entry(region_id)
+            .or_insert(epoch)
+            .value();
+        match epoch.cmp(&current) {
+            Ordering::Equal => Ok(()),
+            Ordering::Less => error::StaleLeaderEpochSnafu {
+                region_id,
+                epoch,
+                current,
+            }
+            .fail(),
+            Ordering::Greater => {
+                warn!("Fencing region {region_id}, leader epoch {current} is stale, found {epoch}");
+                if let Err(e) = self.set_writable(region_id, false) {
+                    error!(e; "Failed to set region {region_id} to readonly");
+                }
+                error::RegionFencedSnafu {
+                    region_id,
+                    epoch,
+                    current,
+                }
+                .fail()
+            }
+        }
+    }
+
     pub fn is_writable(&self, region_id: RegionId) -> Option<bool> {
         // TODO(weny): Finds a better way.
         self.inner.region_map.get(&region_id).and_then(|engine| {
@@ -218,27 +293,78 @@ impl RegionServer {
 
 #[async_trait]
 impl RegionServerHandler for RegionServer {
-    async fn handle(&self, request: region_request::Body) -> ServerResult<RegionResponse> {
+    async fn handle(
+        &self,
+        header: RegionRequestHeader,
+        request: region_request::Body,
+    ) -> ServerResult<RegionResponse> {
         let is_parallel = matches!(
             request,
             region_request::Body::Inserts(_) | region_request::Body::Deletes(_)
         );
-        let requests = RegionRequest::try_from_request_body(request)
+        let insert_mode = InsertMode::from_header_map(&header.tracing_context)
+            .context(BuildRegionRequestsSnafu)
+            .map_err(BoxedError::new)
+            .context(ExecuteGrpcRequestSnafu)?;
+        // Only compact requests read the compaction options, so malformed options
+        // never fail other requests.
+        let compact_options = if matches!(request, region_request::Body::Compact(_)) {
+            CompactOptions::from_header_map(&header.tracing_context)
+                .context(BuildRegionRequestsSnafu)
+                .map_err(BoxedError::new)
+                .context(ExecuteGrpcRequestSnafu)?
+        } else {
+            CompactOptions::Regular
+        };
+        let leader_epochs = LeaderEpochs::from_header_map(&header.tracing_context)
+            .context(BuildRegionRequestsSnafu)
+            .map_err(BoxedError::new)
+            .context(ExecuteGrpcRequestSnafu)?;
+        let wal_replay = WalReplaySource::from_header_map(&header.tracing_context)
             .context(BuildRegionRequestsSnafu)
             .map_err(BoxedError::new)
             .context(ExecuteGrpcRequestSnafu)?;
+        let requests = RegionRequest::try_from_request_body(request)
+            .context(BuildRegionRequestsSnafu)
+            .map_err(BoxedError::new)
+            .context(ExecuteGrpcRequestSnafu)?
+            .into_iter()
+            .map(|(region_id, request)| match request {
+                // The insert mode, the WAL to replay and options of manual compaction
+                // travel in the header.
+                RegionRequest::Put(_) if wal_replay.is_some() => (
+                    region_id,
+                    RegionRequest::ReplayWal(RegionReplayWalRequest {
+                        // Safety: checked above.
+                        source: wal_replay.clone().unwrap(),
+                    }),
+                ),
+                RegionRequest::Put(put) => (
+                    region_id,
+                    RegionRequest::Put(RegionPutRequest { insert_mode, ..put }),
+                ),
+                RegionRequest::Compact(_) => (
+                    region_id,
+                    RegionRequest::Compact(RegionCompactRequest {
+                        options: compact_options,
+                    }),
+                ),
+                request => (region_id, request),
+            })
+            .collect::<Vec<_>>();
         let tracing_context = TracingContext::from_current_span();
 
         let results = if is_parallel {
             let join_tasks = requests.into_iter().map(|(region_id, req)| {
                 let self_to_move = self.clone();
+                let leader_epoch = leader_epochs.get(region_id);
                 let span = tracing_context.attach(info_span!(
                     "RegionServer::handle_region_request",
                     region_id = region_id.to_string()
                 ));
                 async move {
                     self_to_move
-                        .handle_request(region_id, req)
+                        .handle_write_request(region_id, req, leader_epoch)
                         .trace(span)
                         .await
                 }
@@ -354,6 +480,8 @@ impl Deref for RegionEngineWithStatus {
 struct RegionServerInner {
     engines: RwLock<HashMap<String, RegionEngineRef>>,
     region_map: DashMap<RegionId, RegionEngineWithStatus>,
+    /// Leader epochs of regions, see [RegionServer::check_leader_epoch].
+    leader_epochs: DashMap<RegionId, u64>,
     query_engine: QueryEngineRef,
     runtime: Arc<Runtime>,
     event_listener: RegionServerEventListenerRef,
@@ -390,6 +518,7 @@ impl RegionServerInner {
         Self {
             engines: RwLock::new(HashMap::new()),
             region_map: DashMap::new(),
+            leader_epochs: DashMap::new(),
             query_engine,
             runtime,
             event_listener,
@@ -485,7 +614,8 @@ impl RegionServerInner {
             | RegionRequest::Flush(_)
             | RegionRequest::Compact(_)
             | RegionRequest::Truncate(_)
-            | RegionRequest::Catchup(_) => RegionChange::None,
+            | RegionRequest::Catchup(_)
+            | RegionRequest::ReplayWal(_) => RegionChange::None,
         };
 
         let engine = match self.get_engine(region_id, &region_change)? {
@@ -574,6 +704,7 @@ impl RegionServerInner {
                 self.region_map
                     .remove(&region_id)
                     .map(|(id, engine)| engine.set_writable(id, false));
+                self.leader_epochs.remove(&region_id);
                 self.event_listener.on_region_deregistered(region_id);
             }
         }
@@ -619,12 +750,23 @@ impl RegionServerInner {
             plan,
         } = request;
         let region_id = RegionId::from_u64(region_id);
+        #[cfg(feature = "pprof")]
+        let profile_frequency = header
+            .as_ref()
+            .and_then(|h| h.tracing_context.get(PROFILE_FREQUENCY_KEY))
+            .and_then(|frequency| frequency.parse().ok());
 
         // Build query context from gRPC header
         let ctx: QueryContextRef = header
             .as_ref()
             .map(|h| Arc::new(h.into()))
             .unwrap_or_else(|| QueryContextBuilder::default().build());
+        if header
+            .as_ref()
+            .is_some_and(|h| h.tracing_context.contains_key(PLAN_METRICS_KEY))
+        {
+            let _ = ctx.set_typed_extension(Arc::new(CollectPlanMetrics));
+        }
 
         // build dummy catalog list
         let region_status = self
@@ -660,6 +802,12 @@ impl RegionServerInner {
             OutputData::AffectedRows(_) | OutputData::RecordBatches(_) => {
                 UnsupportedOutputSnafu { expected: "stream" }.fail()
             }
+            #[cfg(feature = "pprof")]
+            OutputData::Stream(stream) => Ok(match profile_frequency {
+                Some(frequency) => crate::profile::ProfiledStream::wrap(stream, frequency),
+                None => stream,
+            }),
+            #[cfg(not(feature = "pprof"))]
             OutputData::Stream(stream) => Ok(stream),
         }
     }
@@ -891,14 +1039,17 @@ mod tests {
 
     use std::assert_matches::assert_matches;
 
+    use api::v1::Rows;
     use common_error::ext::ErrorExt;
     use mito2::test_util::CreateRequestBuilder;
     use store_api::region_engine::RegionEngine;
-    use store_api::region_request::{RegionDropRequest, RegionOpenRequest, RegionTruncateRequest};
+    use store_api::region_request::{
+        RegionDeleteRequest, RegionDropRequest, RegionOpenRequest, RegionTruncateRequest,
+    };
     use store_api::storage::RegionId;
 
     use super::*;
-    use crate::error::Result;
+    use crate::error::{Error, Result};
     use crate::tests::{mock_region_server, MockRegionEngine};
 
     #[tokio::test]
@@ -1035,6 +1186,77 @@ mod tests {
         assert_eq!(err.status_code(), StatusCode::RegionNotReady);
     }
 
+    #[tokio::test]
+    async fn test_check_leader_epoch() {
+        common_telemetry::init_default_ut_logging();
+
+        let mut mock_region_server = mock_region_server();
+        let (engine, _receiver) = MockRegionEngine::new();
+        mock_region_server.register_engine(engine.clone());
+
+        let region_id = RegionId::new(1, 1);
+        mock_region_server
+            .inner
+            .region_map
+            .insert(region_id, RegionEngineWithStatus::Ready(engine));
+
+        // Adopts the epoch of the first write.
+        mock_region_server.check_leader_epoch(region_id, 1).unwrap();
+        mock_region_server.check_leader_epoch(region_id, 1).unwrap();
+
+        mock_region_server.set_leader_epoch(region_id, 3);
+        let err = mock_region_server
+            .check_leader_epoch(region_id, 2)
+            .unwrap_err();
+        assert_matches!(err, Error::StaleLeaderEpoch { current: 3, .. });
+        assert_eq!(err.status_code(), StatusCode::RegionNotReady);
+
+        let err = mock_region_server
+            .check_leader_epoch(region_id, 4)
+            .unwrap_err();
+        assert_matches!(err, Error::RegionFenced { current: 3, .. });
+        assert_eq!(err.status_code(), StatusCode::RegionReadonly);
+    }
+
+    #[tokio::test]
+    async fn test_write_without_leader_epoch() {
+        common_telemetry::init_default_ut_logging();
+
+        let mut mock_region_server = mock_region_server();
+        let (engine, _receiver) = MockRegionEngine::new();
+        mock_region_server.register_engine(engine.clone());
+
+        let region_id = RegionId::new(1, 1);
+        mock_region_server
+            .inner
+            .region_map
+            .insert(region_id, RegionEngineWithStatus::Ready(engine));
+        let delete = || {
+            RegionRequest::Delete(RegionDeleteRequest {
+                rows: Rows::default(),
+            })
+        };
+
+        // Writes without an epoch are accepted, with or without an epoch of the region.
+        mock_region_server
+            .handle_write_request(region_id, delete(), None)
+            .await
+            .unwrap();
+        mock_region_server.set_leader_epoch(region_id, 2);
+        mock_region_server
+            .handle_write_request(region_id, delete(), None)
+            .await
+            .unwrap();
+
+        // Writes with a stale epoch are still rejected.
+        mock_region_server.set_leader_epoch(region_id, 3);
+        let err = mock_region_server
+            .handle_write_request(region_id, delete(), Some(2))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::StaleLeaderEpoch { .. });
+    }
+
     #[tokio::test]
     async fn test_region_request_failed() {
         common_telemetry::init_default_ut_logging();
@@ -0,0 +1,685 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replication of the WAL of leader regions to a standby cluster.
+//!
+//! Each leader region tails its WAL from the last replicated entry, whose id is
+//! kept in the metadata store, and applies the writes to the standby through its
+//! frontends in the order of the WAL. Flushes only delete entries that have been
+//! replicated, so writes are never dropped while the standby is unavailable: the
+//! region lags behind and catches up later. An entry may be applied more than
+//! once if the datanode fails before its offset is saved.
+//!
+//! Tables are created on the standby from their definitions before their first
+//! writes, and new columns are added along with the writes. Other DDL, e.g. drop,
+//! rename or altering column types, is not replicated.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use api::v1::value::ValueData;
+use api::v1::{
+    OpType, Row, RowDeleteRequest, RowDeleteRequests, RowInsertRequest, RowInsertRequests, Rows,
+    WalEntry,
+};
+use client::{Client, Database};
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
+use common_meta::key::{TableMetadataManager, TableMetadataManagerRef};
+use common_meta::kv_backend::KvBackendRef;
+use common_meta::rpc::store::PutRequest;
+use common_telemetry::{info, warn};
+use futures_util::StreamExt;
+use mito2::engine::{MitoEngine, MITO_ENGINE_NAME};
+use mito2::wal::EntryId;
+use servers::grpc::greptime_handler::GREPTIME_DB_HEADER_INSERT_MODE;
+use snafu::{OptionExt, ResultExt};
+use store_api::metric_engine_consts::{
+    DATA_SCHEMA_TABLE_ID_COLUMN_NAME, DATA_SCHEMA_TSID_COLUMN_NAME, METRIC_ENGINE_NAME,
+};
+use store_api::region_engine::RegionRole;
+use store_api::region_request::InsertMode;
+use store_api::storage::{RegionId, TableId};
+use table::metadata::{TableInfo, TableInfoRef};
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::config::ReplicationOptions;
+use crate::error::{
+    BuildCreateTableStmtSnafu, InvalidTableInfoSnafu, ReadWalSnafu, ReplicationOffsetSnafu,
+    RequestStandbySnafu, Result, UnexpectedSnafu,
+};
+use crate::metrics::{
+    REPLICATION_FAILURES, REPLICATION_LAG, REPLICATION_ROWS, REPLICATION_SKIPPED_ROWS,
+};
+use crate::region_server::RegionServer;
+
+/// Prefix of keys of the last replicated WAL entry of regions.
+const REPLICATION_OFFSET_KEY_PREFIX: &str = "__replication_offset";
+
+/// Task to replicate leader regions of the datanode in background.
+pub struct ReplicationTask {
+    options: ReplicationOptions,
+    region_server: RegionServer,
+    kv_backend: KvBackendRef,
+    running: Arc<AtomicBool>,
+}
+
+impl ReplicationTask {
+    pub fn new(
+        options: ReplicationOptions,
+        region_server: RegionServer,
+        kv_backend: KvBackendRef,
+    ) -> Self {
+        Self {
+            options,
+            region_server,
+            kv_backend,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Starts replicating regions in background.
+    pub fn start(&self) {
+        let Some(mito) = self
+            .region_server
+            .find_engine_by_name(MITO_ENGINE_NAME)
+            .and_then(|engine| engine.as_any().downcast_ref::<MitoEngine>().cloned())
+        else {
+            warn!("Mito engine is not registered, skip replication");
+            return;
+        };
+        if self.running.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        info!(
+            "Starting replication to standby {:?}, conflict policy: {:?}",
+            self.options.standby_addrs, self.options.conflict_policy
+        );
+
+        let mut replicator = Replicator {
+            client: Client::with_urls(&self.options.standby_addrs),
+            options: self.options.clone(),
+            mito,
+            region_server: self.region_server.clone(),
+            table_metadata_manager: Arc::new(TableMetadataManager::new(self.kv_backend.clone())),
+            kv_backend: self.kv_backend.clone(),
+            regions: HashMap::new(),
+            created_tables: HashSet::new(),
+        };
+        let running = self.running.clone();
+        let _handle = common_runtime::spawn_bg(async move {
+            let mut interval = tokio::time::interval(replicator.options.poll_interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            while running.load(Ordering::Relaxed) {
+                let _ = interval.tick().await;
+                replicator.replicate_regions().await;
+            }
+            info!("Replication task is stopped");
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Replication progress of a region.
+#[derive(Debug, Clone, Copy)]
+struct RegionProgress {
+    /// Id of the last replicated entry.
+    entry_id: Option<EntryId>,
+    /// When the region last replicated all its entries.
+    caught_up_at: Instant,
+}
+
+struct Replicator {
+    options: ReplicationOptions,
+    client: Client,
+    mito: MitoEngine,
+    region_server: RegionServer,
+    table_metadata_manager: TableMetadataManagerRef,
+    kv_backend: KvBackendRef,
+    /// Progress of leader regions, by the id of the mito region whose WAL is replicated.
+    regions: HashMap<RegionId, RegionProgress>,
+    /// Tables created on the standby.
+    created_tables: HashSet<TableId>,
+}
+
+impl Replicator {
+    async fn replicate_regions(&mut self) {
+        let regions = self
+            .region_server
+            .reportable_regions()
+            .into_iter()
+            .filter(|region| region.role == RegionRole::Leader)
+            .filter_map(|region| match region.engine.as_str() {
+                MITO_ENGINE_NAME => Some((region.region_id, false)),
+                // The data region of a physical region has the same id.
+                METRIC_ENGINE_NAME => Some((region.region_id, true)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        // Regions led by another datanode now reload their progress if they come back.
+        self.regions
+            .retain(|region_id, _| regions.contains_key(region_id));
+
+        for (region_id, is_metric) in regions {
+            if let Err(e) = self.replicate_region(region_id, is_metric).await {
+                REPLICATION_FAILURES.inc();
+                warn!(e; "Failed to replicate region {}, retry later", region_id);
+            }
+        }
+
+        let lag = self
+            .regions
+            .values()
+            .map(|progress| progress.caught_up_at.elapsed())
+            .max()
+            .unwrap_or_default();
+        REPLICATION_LAG.set(lag.as_secs_f64());
+    }
+
+    /// Replicates WAL entries of the region after its last replicated entry.
+    async fn replicate_region(&mut self, region_id: RegionId, is_metric: bool) -> Result<()> {
+        let progress = match self.regions.get(&region_id) {
+            Some(progress) => *progress,
+            None => {
+                let progress = RegionProgress {
+                    entry_id: self.load_offset(region_id).await?,
+                    caught_up_at: Instant::now(),
+                };
+                let _ = self.regions.insert(region_id, progress);
+                progress
+            }
+        };
+        // The region forgets the replicated entry once it's reopened.
+        if let Some(entry_id) = progress.entry_id {
+            self.mito
+                .set_replicated_entry_id(region_id, entry_id)
+                .context(ReadWalSnafu { region_id })?;
+        }
+
+        let started_at = Instant::now();
+        let start_id = progress.entry_id.map_or(0, |entry_id| entry_id + 1);
+        let mut entries = self
+            .mito
+            .scan_wal(region_id, start_id)
+            .context(ReadWalSnafu { region_id })?;
+        let mut batch = WriteBatch::default();
+        while let Some(entry) = entries.next().await {
+            let (entry_id, entry) = entry.context(ReadWalSnafu { region_id })?;
+            batch.push(region_id, entry_id, entry)?;
+            if batch.num_rows >= self.options.batch_size {
+                self.replicate_batch(region_id, is_metric, std::mem::take(&mut batch))
+                    .await?;
+            }
+        }
+        self.replicate_batch(region_id, is_metric, batch).await?;
+
+        if let Some(progress) = self.regions.get_mut(&region_id) {
+            progress.caught_up_at = started_at;
+        }
+        Ok(())
+    }
+
+    /// Applies the batch to the standby and saves its last entry as replicated.
+    async fn replicate_batch(
+        &mut self,
+        region_id: RegionId,
+        is_metric: bool,
+        batch: WriteBatch,
+    ) -> Result<()> {
+        let Some(entry_id) = batch.last_entry_id else {
+            return Ok(());
+        };
+        let mut tables = HashMap::new();
+        for (op_type, rows) in batch.writes {
+            let table_rows = if is_metric {
+                split_logical_rows(region_id, rows)?
+            } else {
+                vec![(region_id.table_id(), rows)]
+            };
+
+            // Writes to tables of each database, in the order of the table rows.
+            let mut databases: Vec<((String, String), Vec<(String, Rows)>)> = Vec::new();
+            for (table_id, rows) in table_rows {
+                if !tables.contains_key(&table_id) {
+                    let table_info = self.table_info(table_id).await?;
+                    let _ = tables.insert(table_id, table_info);
+                }
+                let Some(table_info) = &tables[&table_id] else {
+                    REPLICATION_SKIPPED_ROWS.inc_by(rows.rows.len() as u64);
+                    continue;
+                };
+                let rows = if is_metric {
+                    // Logical tables have a subset of the columns of the physical table.
+                    project_rows(rows, table_info)
+                } else {
+                    rows
+                };
+                if is_metric {
+                    self.create_table(region_id.table_id()).await?;
+                }
+                self.create_table(table_id).await?;
+
+                let database = (
+                    table_info.catalog_name.clone(),
+                    table_info.schema_name.clone(),
+                );
+                let table = (table_info.name.clone(), rows);
+                match databases.iter_mut().find(|(name, _)| *name == database) {
+                    Some((_, tables)) => tables.push(table),
+                    None => databases.push((database, vec![table])),
+                }
+            }
+            for ((catalog, schema), tables) in databases {
+                self.send(&catalog, &schema, op_type, tables).await?;
+            }
+        }
+
+        self.save_offset(region_id, entry_id).await?;
+        self.mito
+            .set_replicated_entry_id(region_id, entry_id)
+            .context(ReadWalSnafu { region_id })?;
+        if let Some(progress) = self.regions.get_mut(&region_id) {
+            progress.entry_id = Some(entry_id);
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        catalog: &str,
+        schema: &str,
+        op_type: OpType,
+        tables: Vec<(String, Rows)>,
+    ) -> Result<()> {
+        let num_rows: u64 = tables.iter().map(|(_, rows)| rows.rows.len() as u64).sum();
+        let mut database = Database::new(catalog, schema, self.client.clone());
+        match op_type {
+            OpType::Put => {
+                let mode = InsertMode::from(self.options.conflict_policy);
+                database
+                    .set_header(GREPTIME_DB_HEADER_INSERT_MODE, mode.as_str())
+                    .context(RequestStandbySnafu)?;
+                let inserts = tables
+                    .into_iter()
+                    .map(|(table_name, rows)| RowInsertRequest {
+                        table_name,
+                        rows: Some(rows),
+                    })
+                    .collect();
+                database
+                    .row_insert(RowInsertRequests { inserts })
+                    .await
+                    .context(RequestStandbySnafu)?;
+            }
+            OpType::Delete => {
+                let deletes = tables
+                    .into_iter()
+                    .map(|(table_name, rows)| RowDeleteRequest {
+                        table_name,
+                        rows: Some(rows),
+                    })
+                    .collect();
+                database
+                    .row_delete(RowDeleteRequests { deletes })
+                    .await
+                    .context(RequestStandbySnafu)?;
+            }
+        }
+        let write_type = match op_type {
+            OpType::Put => "put",
+            OpType::Delete => "delete",
+        };
+        REPLICATION_ROWS
+            .with_label_values(&[write_type])
+            .inc_by(num_rows);
+        Ok(())
+    }
+
+    /// Creates the table and its database on the standby if they don't exist.
+    async fn create_table(&mut self, table_id: TableId) -> Result<()> {
+        if self.created_tables.contains(&table_id) {
+            return Ok(());
+        }
+        let Some(table_info) = self.table_info(table_id).await? else {
+            return Ok(());
+        };
+        let database = Database::new(
+            &table_info.catalog_name,
+            DEFAULT_SCHEMA_NAME,
+            self.client.clone(),
+        );
+        let _ = database
+            .sql(format!(
+                "CREATE DATABASE IF NOT EXISTS \"{}\"",
+                table_info.schema_name
+            ))
+            .await
+            .context(RequestStandbySnafu)?;
+
+        let stmt = query::sql::create_table_stmt(&table_info, '"')
+            .context(BuildCreateTableStmtSnafu { table_id })?;
+        let database = Database::new(
+            &table_info.catalog_name,
+            &table_info.schema_name,
+            self.client.clone(),
+        );
+        let _ = database
+            .sql(stmt.to_string())
+            .await
+            .context(RequestStandbySnafu)?;
+        info!(
+            "Created table {}.{}.{} on the standby",
+            table_info.catalog_name, table_info.schema_name, table_info.name
+        );
+        let _ = self.created_tables.insert(table_id);
+        Ok(())
+    }
+
+    /// Returns the info of the table, or `None` if it's dropped. Tables are looked up by
+    /// id, so writes of renamed tables are replicated under their new names.
+    async fn table_info(&self, table_id: TableId) -> Result<Option<TableInfoRef>> {
+        let Some(value) = self
+            .table_metadata_manager
+            .table_info_manager()
+            .get(table_id)
+            .await
+            .context(crate::error::GetMetadataSnafu)?
+        else {
+            return Ok(None);
+        };
+        let table_info = TableInfo::try_from(value.into_inner().table_info)
+            .context(InvalidTableInfoSnafu { table_id })?;
+        Ok(Some(Arc::new(table_info)))
+    }
+
+    async fn load_offset(&self, region_id: RegionId) -> Result<Option<EntryId>> {
+        let Some(kv) = self
+            .kv_backend
+            .get(offset_key(region_id).as_bytes())
+            .await
+            .context(ReplicationOffsetSnafu { region_id })?
+        else {
+            return Ok(None);
+        };
+        let entry_id = std::str::from_utf8(&kv.value)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .with_context(|| UnexpectedSnafu {
+                violated: format!("invalid replication offset of region {region_id}"),
+            })?;
+        Ok(Some(entry_id))
+    }
+
+    async fn save_offset(&self, region_id: RegionId, entry_id: EntryId) -> Result<()> {
+        let request = PutRequest::new()
+            .with_key(offset_key(region_id))
+            .with_value(entry_id.to_string());
+        let _ = self
+            .kv_backend
+            .put(request)
+            .await
+            .context(ReplicationOffsetSnafu { region_id })?;
+        Ok(())
+    }
+}
+
+fn offset_key(region_id: RegionId) -> String {
+    format!("{REPLICATION_OFFSET_KEY_PREFIX}/{}", region_id.as_u64())
+}
+
+/// Writes of consecutive WAL entries, in the order of the WAL.
+#[derive(Debug, Default)]
+struct WriteBatch {
+    /// Writes of the same type and schema in a row are merged.
+    writes: Vec<(OpType, Rows)>,
+    num_rows: usize,
+    last_entry_id: Option<EntryId>,
+}
+
+impl WriteBatch {
+    fn push(&mut self, region_id: RegionId, entry_id: EntryId, entry: WalEntry) -> Result<()> {
+        for mutation in entry.mutations {
+            let Some(rows) = mutation.rows else {
+                continue;
+            };
+            let op_type =
+                OpType::try_from(mutation.op_type)
+                    .ok()
+                    .with_context(|| UnexpectedSnafu {
+                        violated: format!(
+                            "unknown op type {} in WAL of region {region_id}",
+                            mutation.op_type
+                        ),
+                    })?;
+            self.num_rows += rows.rows.len();
+            match self.writes.last_mut() {
+                Some((last_op_type, last_rows))
+                    if *last_op_type == op_type && last_rows.schema == rows.schema =>
+                {
+                    last_rows.rows.extend(rows.rows);
+                }
+                _ => self.writes.push((op_type, rows)),
+            }
+        }
+        self.last_entry_id = Some(entry_id);
+        Ok(())
+    }
+}
+
+/// Splits rows of a metric engine data region into rows of its logical tables,
+/// without the internal columns.
+fn split_logical_rows(region_id: RegionId, rows: Rows) -> Result<Vec<(TableId, Rows)>> {
+    let table_id_index = rows
+        .schema
+        .iter()
+        .position(|column| column.column_name == DATA_SCHEMA_TABLE_ID_COLUMN_NAME)
+        .with_context(|| UnexpectedSnafu {
+            violated: format!("missing table id column in WAL of region {region_id}"),
+        })?;
+    let indices = rows
+        .schema
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| {
+            column.column_name != DATA_SCHEMA_TABLE_ID_COLUMN_NAME
+                && column.column_name != DATA_SCHEMA_TSID_COLUMN_NAME
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    let schema = indices
+        .iter()
+        .map(|index| rows.schema[*index].clone())
+        .collect::<Vec<_>>();
+
+    let mut tables: Vec<(TableId, Rows)> = Vec::new();
+    for row in rows.rows {
+        let Some(ValueData::U32Value(table_id)) = row.values[table_id_index].value_data else {
+            return UnexpectedSnafu {
+                violated: format!("invalid table id in WAL of region {region_id}"),
+            }
+            .fail();
+        };
+        let row = Row {
+            values: indices
+                .iter()
+                .map(|index| row.values[*index].clone())
+                .collect(),
+        };
+        match tables.iter_mut().find(|(id, _)| *id == table_id) {
+            Some((_, rows)) => rows.rows.push(row),
+            None => tables.push((
+                table_id,
+                Rows {
+                    schema: schema.clone(),
+                    rows: vec![row],
+                },
+            )),
+        }
+    }
+    Ok(tables)
+}
+
+/// Keeps the columns of the table in rows.
+fn project_rows(rows: Rows, table_info: &TableInfo) -> Rows {
+    let table_schema = &table_info.meta.schema;
+    let indices = rows
+        .schema
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| {
+            table_schema
+                .column_schema_by_name(&column.column_name)
+                .is_some()
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    Rows {
+        schema: indices
+            .iter()
+            .map(|index| rows.schema[*index].clone())
+            .collect(),
+        rows: rows
+            .rows
+            .into_iter()
+            .map(|row| Row {
+                values: indices
+                    .iter()
+                    .map(|index| row.values[*index].clone())
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::{ColumnDataType, ColumnSchema, Mutation, SemanticType, Value};
+
+    use super::*;
+
+    fn column(name: &str, datatype: ColumnDataType) -> ColumnSchema {
+        ColumnSchema {
+            column_name: name.to_string(),
+            datatype: datatype as i32,
+            semantic_type: SemanticType::Field as i32,
+            ..Default::default()
+        }
+    }
+
+    fn value(value_data: ValueData) -> Value {
+        Value {
+            value_data: Some(value_data),
+        }
+    }
+
+    fn mutation(op_type: OpType, schema: &[&str], num_rows: usize) -> Mutation {
+        Mutation {
+            op_type: op_type as i32,
+            sequence: 0,
+            rows: Some(Rows {
+                schema: schema
+                    .iter()
+                    .map(|name| column(name, ColumnDataType::Int64))
+                    .collect(),
+                rows: vec![
+                    Row {
+                        values: vec![value(ValueData::I64Value(0)); schema.len()]
+                    };
+                    num_rows
+                ],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_write_batch() {
+        let region_id = RegionId::new(1024, 1);
+        let mut batch = WriteBatch::default();
+        let entry = WalEntry {
+            mutations: vec![
+                mutation(OpType::Put, &["a"], 2),
+                mutation(OpType::Put, &["a"], 3),
+            ],
+        };
+        batch.push(region_id, 1, entry).unwrap();
+        let entry = WalEntry {
+            mutations: vec![
+                mutation(OpType::Put, &["a", "b"], 1),
+                mutation(OpType::Delete, &["a", "b"], 1),
+                mutation(OpType::Put, &["a", "b"], 2),
+            ],
+        };
+        batch.push(region_id, 2, entry).unwrap();
+
+        assert_eq!(Some(2), batch.last_entry_id);
+        assert_eq!(9, batch.num_rows);
+        let writes = batch
+            .writes
+            .iter()
+            .map(|(op_type, rows)| (*op_type, rows.schema.len(), rows.rows.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (OpType::Put, 1, 5),
+                (OpType::Put, 2, 1),
+                (OpType::Delete, 2, 1),
+                (OpType::Put, 2, 2),
+            ],
+            writes
+        );
+    }
+
+    #[test]
+    fn test_split_logical_rows() {
+        let row = |table_id: u32, host: &str| Row {
+            values: vec![
+                value(ValueData::U32Value(table_id)),
+                value(ValueData::U64Value(0)),
+                value(ValueData::StringValue(host.to_string())),
+            ],
+        };
+        let rows = Rows {
+            schema: vec![
+                column(DATA_SCHEMA_TABLE_ID_COLUMN_NAME, ColumnDataType::Uint32),
+                column(DATA_SCHEMA_TSID_COLUMN_NAME, ColumnDataType::Uint64),
+                column("host", ColumnDataType::String),
+            ],
+            rows: vec![row(1025, "a"), row(1026, "b"), row(1025, "c")],
+        };
+
+        let tables = split_logical_rows(RegionId::new(1024, 1), rows).unwrap();
+        let tables = tables
+            .iter()
+            .map(|(table_id, rows)| {
+                let hosts = rows
+                    .rows
+                    .iter()
+                    .map(|row| row.values[0].value_data.clone())
+                    .collect::<Vec<_>>();
+                (*table_id, rows.schema.len(), hosts)
+            })
+            .collect::<Vec<_>>();
+        let host = |host: &str| Some(ValueData::StringValue(host.to_string()));
+        assert_eq!(
+            vec![
+                (1025, 1, vec![host("a"), host("c")]),
+                (1026, 1, vec![host("b")]),
+            ],
+            tables
+        );
+    }
+}
@@ -15,9 +15,13 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use axum::extract::State;
+use axum::{routing, Json, Router};
+use mito2::engine::{MitoEngine, MITO_ENGINE_NAME};
+use mito2::region::SstUpgradeProgress;
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::{GrpcServer, GrpcServerConfig};
-use servers::http::HttpServerBuilder;
+use servers::http::{HttpServerBuilder, HTTP_API_VERSION};
 use servers::metrics_handler::MetricsHandler;
 use servers::server::{ServerHandler, ServerHandlers};
 use snafu::ResultExt;
@@ -30,6 +34,8 @@ pub struct DatanodeServiceBuilder<'a> {
     opts: &'a DatanodeOptions,
     grpc_server: Option<GrpcServer>,
     enable_http_service: bool,
+    /// Routers of the HTTP service besides the default ones.
+    extra_router: Option<Router>,
 }
 
 impl<'a> DatanodeServiceBuilder<'a> {
@@ -38,6 +44,7 @@ impl<'a> DatanodeServiceBuilder<'a> {
             opts,
             grpc_server: None,
             enable_http_service: false,
+            extra_router: None,
         }
     }
 
@@ -54,9 +61,12 @@ impl<'a> DatanodeServiceBuilder<'a> {
         self
     }
 
-    pub fn enable_http_service(self) -> Self {
+    /// Enables the HTTP service, which also serves the progress of upgrading SSTs of
+    /// regions in `region_server`.
+    pub fn enable_http_service(self, region_server: &RegionServer) -> Self {
         Self {
             enable_http_service: true,
+            extra_router: sst_upgrade_router(region_server),
             ..self
         }
     }
@@ -73,10 +83,13 @@ impl<'a> DatanodeServiceBuilder<'a> {
         }
 
         if self.enable_http_service {
-            let http_server = HttpServerBuilder::new(self.opts.http.clone())
+            let mut builder = HttpServerBuilder::new(self.opts.http.clone())
                 .with_metrics_handler(MetricsHandler)
-                .with_greptime_config_options(self.opts.to_toml_string())
-                .build();
+                .with_greptime_config_options(self.opts.to_toml_string());
+            if let Some(router) = self.extra_router.take() {
+                builder = builder.with_extra_router(router);
+            }
+            let http_server = builder.build();
             let addr: SocketAddr = self.opts.http.addr.parse().context(ParseAddrSnafu {
                 addr: &self.opts.http.addr,
             })?;
@@ -101,3 +114,22 @@ impl<'a> DatanodeServiceBuilder<'a> {
             .region_server_handler(Arc::new(region_server.clone()))
     }
 }
+
+/// Returns the router of `GET /v1/sst_upgrade`, which lists the progress of upgrading
+/// SSTs of regions in the mito engine, or `None` if the engine isn't registered.
+pub fn sst_upgrade_router(region_server: &RegionServer) -> Option<Router> {
+    let mito = region_server
+        .find_engine_by_name(MITO_ENGINE_NAME)
+        .and_then(|engine| engine.as_any().downcast_ref::<MitoEngine>().cloned())?;
+    let router = Router::new()
+        .route(
+            &format!("/{HTTP_API_VERSION}/sst_upgrade"),
+            routing::get(sst_upgrade_progress),
+        )
+        .with_state(mito);
+    Some(router)
+}
+
+async fn sst_upgrade_progress(State(mito): State<MitoEngine>) -> Json<Vec<SstUpgradeProgress>> {
+    Json(mito.list_sst_upgrade_progress())
+}
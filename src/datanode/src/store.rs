@@ -17,6 +17,7 @@
 mod azblob;
 mod fs;
 mod gcs;
+mod hdfs;
 mod oss;
 mod s3;
 
@@ -24,21 +25,31 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::{env, path};
 
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
 use common_base::readable_size::ReadableSize;
 use common_telemetry::logging::info;
-use object_store::layers::{LruCacheLayer, RetryLayer};
+use object_store::layers::{LruCacheLayer, MultipartLayer, RetryLayer};
 use object_store::services::Fs;
 use object_store::util::{join_dir, normalize_dir, with_instrument_layers};
 use object_store::{HttpClient, ObjectStore, ObjectStoreBuilder};
+use secrecy::ExposeSecret;
 use snafu::prelude::*;
 
-use crate::config::{ObjectStoreConfig, DEFAULT_OBJECT_STORE_CACHE_SIZE};
+use crate::config::{
+    CredentialSource, ObjectStorageSecurityConfig, ObjectStoreConfig, ServerSideEncryption,
+    DEFAULT_OBJECT_STORE_CACHE_SIZE,
+};
 use crate::error::{self, Result};
 
+/// Length of the key of customer provided encryption, in bytes.
+const CUSTOMER_KEY_LEN: usize = 32;
+
 pub(crate) async fn new_object_store(
     store: ObjectStoreConfig,
     data_home: &str,
 ) -> Result<ObjectStore> {
+    validate_object_store_config(&store)?;
     let data_home = normalize_dir(data_home);
     let object_store = match &store {
         ObjectStoreConfig::File(file_config) => {
@@ -50,11 +61,16 @@ pub(crate) async fn new_object_store(
             azblob::new_azblob_object_store(azblob_config).await
         }
         ObjectStoreConfig::Gcs(gcs_config) => gcs::new_gcs_object_store(gcs_config).await,
+        ObjectStoreConfig::Hdfs(hdfs_config) => hdfs::new_hdfs_object_store(hdfs_config).await,
+        ObjectStoreConfig::Webhdfs(webhdfs_config) => {
+            hdfs::new_webhdfs_object_store(webhdfs_config).await
+        }
     }?;
 
     // Enable retry layer and cache layer for non-fs object storages
     let object_store = if !matches!(store, ObjectStoreConfig::File(..)) {
         let object_store = create_object_store_with_cache(object_store, &store).await?;
+        let object_store = create_object_store_with_multipart(object_store, &store);
         object_store.layer(RetryLayer::new().with_jitter())
     } else {
         object_store
@@ -68,41 +84,22 @@ async fn create_object_store_with_cache(
     object_store: ObjectStore,
     store_config: &ObjectStoreConfig,
 ) -> Result<ObjectStore> {
-    let (cache_path, cache_capacity) = match store_config {
-        ObjectStoreConfig::S3(s3_config) => {
-            let path = s3_config.cache.cache_path.as_ref();
-            let capacity = s3_config
-                .cache
-                .cache_capacity
-                .unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
-            (path, capacity)
-        }
-        ObjectStoreConfig::Oss(oss_config) => {
-            let path = oss_config.cache.cache_path.as_ref();
-            let capacity = oss_config
-                .cache
-                .cache_capacity
-                .unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
-            (path, capacity)
-        }
-        ObjectStoreConfig::Azblob(azblob_config) => {
-            let path = azblob_config.cache.cache_path.as_ref();
-            let capacity = azblob_config
-                .cache
-                .cache_capacity
-                .unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
-            (path, capacity)
-        }
-        ObjectStoreConfig::Gcs(gcs_config) => {
-            let path = gcs_config.cache.cache_path.as_ref();
-            let capacity = gcs_config
-                .cache
-                .cache_capacity
-                .unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
-            (path, capacity)
-        }
-        _ => (None, ReadableSize(0)),
+    let cache_config = match store_config {
+        ObjectStoreConfig::S3(s3_config) => &s3_config.cache,
+        ObjectStoreConfig::Oss(oss_config) => &oss_config.cache,
+        ObjectStoreConfig::Azblob(azblob_config) => &azblob_config.cache,
+        ObjectStoreConfig::Gcs(gcs_config) => &gcs_config.cache,
+        ObjectStoreConfig::Hdfs(hdfs_config) => &hdfs_config.cache,
+        ObjectStoreConfig::Webhdfs(webhdfs_config) => &webhdfs_config.cache,
+        ObjectStoreConfig::File(_) => return Ok(object_store),
     };
+    let cache_path = cache_config.cache_path.as_ref();
+    let cache_capacity = cache_config
+        .cache_capacity
+        .unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
+    let memory_capacity = cache_config
+        .cache_memory_capacity
+        .unwrap_or(ReadableSize(0));
 
     if let Some(path) = cache_path {
         let atomic_temp_dir = join_dir(path, ".tmp/");
@@ -115,11 +112,13 @@ async fn create_object_store_with_cache(
 
         let cache_layer = LruCacheLayer::new(Arc::new(cache_store), cache_capacity.0 as usize)
             .await
-            .context(error::InitBackendSnafu)?;
+            .context(error::InitBackendSnafu)?
+            .with_memory_capacity(memory_capacity.0 as usize)
+            .with_prefetch(cache_config.cache_prefetch);
 
         info!(
-            "Enabled local object storage cache, path: {}, capacity: {}.",
-            path, cache_capacity
+            "Enabled local object storage cache, path: {}, capacity: {}, memory capacity: {}, prefetch: {}.",
+            path, cache_capacity, memory_capacity, cache_config.cache_prefetch
         );
 
         Ok(object_store.layer(cache_layer))
@@ -128,6 +127,229 @@ async fn create_object_store_with_cache(
     }
 }
 
+fn create_object_store_with_multipart(
+    object_store: ObjectStore,
+    store_config: &ObjectStoreConfig,
+) -> ObjectStore {
+    let multipart_config = match store_config {
+        ObjectStoreConfig::S3(s3_config) => &s3_config.multipart,
+        ObjectStoreConfig::Oss(oss_config) => &oss_config.multipart,
+        ObjectStoreConfig::Azblob(azblob_config) => &azblob_config.multipart,
+        ObjectStoreConfig::Gcs(gcs_config) => &gcs_config.multipart,
+        // HDFS streams writes to the data nodes.
+        ObjectStoreConfig::File(_) | ObjectStoreConfig::Hdfs(_) | ObjectStoreConfig::Webhdfs(_) => {
+            return object_store
+        }
+    };
+    if multipart_config.multipart_part_size.is_none()
+        && multipart_config.multipart_concurrency.is_none()
+    {
+        return object_store;
+    }
+
+    let mut multipart_layer = MultipartLayer::default();
+    if let Some(part_size) = multipart_config.multipart_part_size {
+        multipart_layer = multipart_layer.with_part_size(part_size.0 as usize);
+    }
+    if let Some(concurrency) = multipart_config.multipart_concurrency {
+        multipart_layer = multipart_layer.with_concurrency(concurrency);
+    }
+
+    info!(
+        "Enabled multipart options for object storage, part size: {:?}, concurrency: {:?}.",
+        multipart_config.multipart_part_size, multipart_config.multipart_concurrency
+    );
+
+    object_store.layer(multipart_layer)
+}
+
+/// Security features an object storage service supports.
+struct SecuritySupport {
+    managed_encryption: bool,
+    kms_encryption: bool,
+    customer_encryption: bool,
+    request_payer: bool,
+}
+
+impl SecuritySupport {
+    fn of(store: &ObjectStoreConfig) -> Self {
+        match store {
+            ObjectStoreConfig::S3(_) => SecuritySupport {
+                managed_encryption: true,
+                kms_encryption: true,
+                customer_encryption: true,
+                request_payer: true,
+            },
+            ObjectStoreConfig::Oss(_) => SecuritySupport {
+                managed_encryption: true,
+                kms_encryption: true,
+                customer_encryption: false,
+                request_payer: false,
+            },
+            // Azure Blob and GCS always encrypt objects with keys managed by the service.
+            ObjectStoreConfig::Azblob(_) => SecuritySupport {
+                managed_encryption: true,
+                kms_encryption: false,
+                customer_encryption: true,
+                request_payer: false,
+            },
+            ObjectStoreConfig::Gcs(_) => SecuritySupport {
+                managed_encryption: true,
+                kms_encryption: false,
+                customer_encryption: false,
+                request_payer: false,
+            },
+            ObjectStoreConfig::File(_)
+            | ObjectStoreConfig::Hdfs(_)
+            | ObjectStoreConfig::Webhdfs(_) => SecuritySupport {
+                managed_encryption: false,
+                kms_encryption: false,
+                customer_encryption: false,
+                request_payer: false,
+            },
+        }
+    }
+}
+
+/// Returns whether the config of `store` contains a static credential.
+fn has_static_credential(store: &ObjectStoreConfig) -> bool {
+    match store {
+        ObjectStoreConfig::File(_) | ObjectStoreConfig::Hdfs(_) | ObjectStoreConfig::Webhdfs(_) => {
+            false
+        }
+        ObjectStoreConfig::S3(config) => {
+            !config.access_key_id.expose_secret().is_empty()
+                || !config.secret_access_key.expose_secret().is_empty()
+        }
+        ObjectStoreConfig::Oss(config) => {
+            !config.access_key_id.expose_secret().is_empty()
+                || !config.access_key_secret.expose_secret().is_empty()
+        }
+        ObjectStoreConfig::Azblob(config) => {
+            !config.account_key.expose_secret().is_empty() || config.sas_token.is_some()
+        }
+        ObjectStoreConfig::Gcs(config) => {
+            !config.credential_path.expose_secret().is_empty()
+                || !config.credential.expose_secret().is_empty()
+        }
+    }
+}
+
+/// Validates the endpoint and security options of `store`, so a datanode
+/// fails to start instead of ignoring options the service doesn't support.
+pub(crate) fn validate_object_store_config(store: &ObjectStoreConfig) -> Result<()> {
+    let invalid = |reason: String| {
+        error::InvalidStorageConfigSnafu {
+            name: store.name(),
+            reason,
+        }
+        .fail()
+    };
+
+    // Endpoints without scheme default to https.
+    if let Some(endpoint) = store.endpoint() {
+        let valid_scheme = match endpoint.split_once("://") {
+            Some((scheme, _)) => scheme == "http" || scheme == "https",
+            None => true,
+        };
+        if !valid_scheme || endpoint.trim() != endpoint {
+            return invalid(format!("invalid endpoint '{endpoint}'"));
+        }
+    }
+
+    match store {
+        ObjectStoreConfig::Hdfs(config) => return hdfs::validate_hdfs_config(store.name(), config),
+        ObjectStoreConfig::Webhdfs(config) if config.endpoint.is_empty() => {
+            return invalid("endpoint is required".to_string());
+        }
+        _ => {}
+    }
+    let Some(security) = store.security() else {
+        return Ok(());
+    };
+
+    let support = SecuritySupport::of(store);
+    let supported = match security.server_side_encryption {
+        ServerSideEncryption::Default => true,
+        ServerSideEncryption::Managed => support.managed_encryption,
+        ServerSideEncryption::Kms => support.kms_encryption,
+        ServerSideEncryption::Customer => support.customer_encryption,
+    };
+    if !supported {
+        return invalid(format!(
+            "server side encryption {:?} is not supported by {}",
+            security.server_side_encryption,
+            store.provider_name()
+        ));
+    }
+    if security.kms_key_id.is_some() && security.server_side_encryption != ServerSideEncryption::Kms
+    {
+        return invalid("kms_key_id is only used by 'kms' encryption".to_string());
+    }
+    let has_customer_key = !security.customer_key.expose_secret().is_empty();
+    match security.server_side_encryption {
+        ServerSideEncryption::Customer => {
+            let _ = decode_customer_key(store.name(), security)?;
+        }
+        _ if has_customer_key => {
+            return invalid("customer_key is only used by 'customer' encryption".to_string());
+        }
+        _ => {}
+    }
+
+    if security.request_payer && !support.request_payer {
+        return invalid(format!(
+            "request_payer is not supported by {}",
+            store.provider_name()
+        ));
+    }
+
+    match security.credential_source {
+        CredentialSource::Auto => {}
+        CredentialSource::Static if !has_static_credential(store) => {
+            return invalid(
+                "credential_source is 'static' but no credential is configured".to_string(),
+            );
+        }
+        CredentialSource::Environment if has_static_credential(store) => {
+            return invalid(
+                "credential_source is 'environment' but a static credential is configured"
+                    .to_string(),
+            );
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Decodes the key of customer provided encryption of the storage provider `name`.
+pub(crate) fn decode_customer_key(
+    name: &str,
+    security: &ObjectStorageSecurityConfig,
+) -> Result<Vec<u8>> {
+    let key = BASE64_STANDARD
+        .decode(security.customer_key.expose_secret())
+        .map_err(|e| {
+            error::InvalidStorageConfigSnafu {
+                name,
+                reason: format!("customer_key is not valid base64: {e}"),
+            }
+            .build()
+        })?;
+    ensure!(
+        key.len() == CUSTOMER_KEY_LEN,
+        error::InvalidStorageConfigSnafu {
+            name,
+            reason: format!(
+                "customer_key must be {CUSTOMER_KEY_LEN} bytes, got {}",
+                key.len()
+            ),
+        }
+    );
+    Ok(key)
+}
+
 pub(crate) fn clean_temp_dir(dir: &str) -> Result<()> {
     if path::Path::new(&dir).exists() {
         info!("Begin to clean temp storage directory: {}", dir);
@@ -170,3 +392,61 @@ pub(crate) fn build_http_client() -> Result<HttpClient> {
 
     HttpClient::build(http_builder).context(error::InitBackendSnafu)
 }
+
+#[cfg(test)]
+mod tests {
+    use secrecy::SecretString;
+
+    use super::*;
+    use crate::config::{AzblobConfig, GcsConfig, OssConfig, S3Config};
+
+    fn customer_key() -> SecretString {
+        SecretString::from(BASE64_STANDARD.encode([7u8; CUSTOMER_KEY_LEN]))
+    }
+
+    #[test]
+    fn test_validate_object_store_config() {
+        let mut s3 = S3Config::default();
+        s3.security.server_side_encryption = ServerSideEncryption::Kms;
+        s3.security.kms_key_id = Some("key".to_string());
+        s3.security.request_payer = true;
+        validate_object_store_config(&ObjectStoreConfig::S3(s3.clone())).unwrap();
+
+        s3.endpoint = Some("ftp://s3.amazonaws.com".to_string());
+        assert!(validate_object_store_config(&ObjectStoreConfig::S3(s3.clone())).is_err());
+        s3.endpoint = Some("https://s3.amazonaws.com".to_string());
+
+        s3.security.server_side_encryption = ServerSideEncryption::Managed;
+        assert!(validate_object_store_config(&ObjectStoreConfig::S3(s3.clone())).is_err());
+        s3.security.kms_key_id = None;
+        validate_object_store_config(&ObjectStoreConfig::S3(s3.clone())).unwrap();
+
+        s3.security.credential_source = CredentialSource::Static;
+        assert!(validate_object_store_config(&ObjectStoreConfig::S3(s3.clone())).is_err());
+        s3.access_key_id = SecretString::from("id".to_string());
+        validate_object_store_config(&ObjectStoreConfig::S3(s3.clone())).unwrap();
+        s3.security.credential_source = CredentialSource::Environment;
+        assert!(validate_object_store_config(&ObjectStoreConfig::S3(s3)).is_err());
+
+        let mut azblob = AzblobConfig::default();
+        azblob.security.server_side_encryption = ServerSideEncryption::Customer;
+        azblob.security.customer_key = customer_key();
+        validate_object_store_config(&ObjectStoreConfig::Azblob(azblob.clone())).unwrap();
+        azblob.security.customer_key = SecretString::from("c2hvcnQ=".to_string());
+        assert!(validate_object_store_config(&ObjectStoreConfig::Azblob(azblob.clone())).is_err());
+        azblob.security.server_side_encryption = ServerSideEncryption::Kms;
+        azblob.security.customer_key = SecretString::from(String::new());
+        assert!(validate_object_store_config(&ObjectStoreConfig::Azblob(azblob)).is_err());
+
+        let mut oss = OssConfig::default();
+        oss.security.request_payer = true;
+        assert!(validate_object_store_config(&ObjectStoreConfig::Oss(oss)).is_err());
+
+        let mut gcs = GcsConfig::default();
+        gcs.security.credential_source = CredentialSource::Environment;
+        validate_object_store_config(&ObjectStoreConfig::Gcs(gcs.clone())).unwrap();
+        gcs.security.server_side_encryption = ServerSideEncryption::Customer;
+        gcs.security.customer_key = customer_key();
+        assert!(validate_object_store_config(&ObjectStoreConfig::Gcs(gcs)).is_err());
+    }
+}
@@ -18,9 +18,9 @@ use object_store::{util, ObjectStore};
 use secrecy::ExposeSecret;
 use snafu::prelude::*;
 
-use crate::config::AzblobConfig;
+use crate::config::{AzblobConfig, ServerSideEncryption};
 use crate::error::{self, Result};
-use crate::store::build_http_client;
+use crate::store::{build_http_client, decode_customer_key};
 
 pub(crate) async fn new_azblob_object_store(azblob_config: &AzblobConfig) -> Result<ObjectStore> {
     let root = util::normalize_dir(&azblob_config.root);
@@ -43,6 +43,12 @@ pub(crate) async fn new_azblob_object_store(azblob_config: &AzblobConfig) -> Res
         let _ = builder.sas_token(token);
     }
 
+    let security = &azblob_config.security;
+    if security.server_side_encryption == ServerSideEncryption::Customer {
+        let key = decode_customer_key(azblob_config.name.as_deref().unwrap_or("Azblob"), security)?;
+        let _ = builder.server_side_encryption_with_customer_key(&key);
+    }
+
     Ok(ObjectStore::new(builder)
         .context(error::InitBackendSnafu)?
         .finish())
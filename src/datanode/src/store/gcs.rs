@@ -38,6 +38,10 @@ pub(crate) async fn new_gcs_object_store(gcs_config: &GcsConfig) -> Result<Objec
         .endpoint(&gcs_config.endpoint)
         .http_client(build_http_client()?);
 
+    if !gcs_config.credential.expose_secret().is_empty() {
+        builder.credential(gcs_config.credential.expose_secret());
+    }
+
     Ok(ObjectStore::new(builder)
         .context(error::InitBackendSnafu)?
         .finish())
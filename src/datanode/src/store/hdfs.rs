@@ -0,0 +1,131 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_telemetry::logging::info;
+use object_store::services::Webhdfs;
+use object_store::{util, ObjectStore};
+use secrecy::ExposeSecret;
+use snafu::prelude::*;
+
+use crate::config::{HdfsConfig, WebhdfsConfig};
+use crate::error::{self, Result};
+
+/// Validates the config of the native HDFS store.
+pub(crate) fn validate_hdfs_config(name: &str, hdfs_config: &HdfsConfig) -> Result<()> {
+    ensure!(
+        cfg!(feature = "hdfs"),
+        error::InvalidStorageConfigSnafu {
+            name,
+            reason: "HDFS support is not enabled, build with the `hdfs` feature or use `Webhdfs`",
+        }
+    );
+    ensure!(
+        !hdfs_config.name_node.is_empty(),
+        error::InvalidStorageConfigSnafu {
+            name,
+            reason: "name_node is required",
+        }
+    );
+    if let Some(path) = &hdfs_config.kerberos_ticket_cache_path {
+        ensure!(
+            std::path::Path::new(path).exists(),
+            error::InvalidStorageConfigSnafu {
+                name,
+                reason: format!("kerberos ticket cache '{path}' does not exist"),
+            }
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "hdfs")]
+pub(crate) async fn new_hdfs_object_store(hdfs_config: &HdfsConfig) -> Result<ObjectStore> {
+    use object_store::services::Hdfs;
+
+    let root = util::normalize_dir(&hdfs_config.root);
+    info!(
+        "The hdfs name node is: {}, root is: {}, kerberos: {}",
+        hdfs_config.name_node,
+        &root,
+        hdfs_config.kerberos_ticket_cache_path.is_some()
+    );
+
+    let mut builder = Hdfs::default();
+    let _ = builder.root(&root).name_node(&hdfs_config.name_node);
+    if let Some(user) = &hdfs_config.user {
+        let _ = builder.user(user);
+    }
+    if let Some(path) = &hdfs_config.kerberos_ticket_cache_path {
+        let _ = builder.kerberos_ticket_cache_path(path);
+    }
+
+    Ok(ObjectStore::new(builder)
+        .context(error::InitBackendSnafu)?
+        .finish())
+}
+
+#[cfg(not(feature = "hdfs"))]
+pub(crate) async fn new_hdfs_object_store(hdfs_config: &HdfsConfig) -> Result<ObjectStore> {
+    // Rejected by the validation.
+    error::InvalidStorageConfigSnafu {
+        name: hdfs_config.name.as_deref().unwrap_or("Hdfs"),
+        reason: "HDFS support is not enabled",
+    }
+    .fail()
+}
+
+pub(crate) async fn new_webhdfs_object_store(
+    webhdfs_config: &WebhdfsConfig,
+) -> Result<ObjectStore> {
+    let root = util::normalize_dir(&webhdfs_config.root);
+    info!(
+        "The webhdfs endpoint is: {}, root is: {}",
+        webhdfs_config.endpoint, &root
+    );
+
+    let mut builder = Webhdfs::default();
+    let _ = builder.root(&root).endpoint(&webhdfs_config.endpoint);
+    if !webhdfs_config.delegation.expose_secret().is_empty() {
+        let _ = builder.delegation(webhdfs_config.delegation.expose_secret());
+    }
+
+    Ok(ObjectStore::new(builder)
+        .context(error::InitBackendSnafu)?
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_hdfs_config() {
+        let mut config = HdfsConfig {
+            name_node: "hdfs://127.0.0.1:9000".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            cfg!(feature = "hdfs"),
+            validate_hdfs_config("Hdfs", &config).is_ok()
+        );
+
+        config.kerberos_ticket_cache_path = Some("/not/exist/krb5cc".to_string());
+        assert!(validate_hdfs_config("Hdfs", &config).is_err());
+
+        config.name_node.clear();
+        config.kerberos_ticket_cache_path = None;
+        assert!(validate_hdfs_config("Hdfs", &config).is_err());
+    }
+}
@@ -18,7 +18,7 @@ use object_store::{util, ObjectStore};
 use secrecy::ExposeSecret;
 use snafu::prelude::*;
 
-use crate::config::OssConfig;
+use crate::config::{OssConfig, ServerSideEncryption};
 use crate::error::{self, Result};
 use crate::store::build_http_client;
 
@@ -38,6 +38,21 @@ pub(crate) async fn new_oss_object_store(oss_config: &OssConfig) -> Result<Objec
         .access_key_secret(oss_config.access_key_secret.expose_secret())
         .http_client(build_http_client()?);
 
+    let security = &oss_config.security;
+    match security.server_side_encryption {
+        ServerSideEncryption::Managed => {
+            let _ = builder.server_side_encryption("AES256");
+        }
+        ServerSideEncryption::Kms => {
+            let _ = builder.server_side_encryption("KMS");
+            if let Some(key_id) = &security.kms_key_id {
+                let _ = builder.server_side_encryption_key_id(key_id);
+            }
+        }
+        // Validated on startup.
+        ServerSideEncryption::Default | ServerSideEncryption::Customer => {}
+    }
+
     Ok(ObjectStore::new(builder)
         .context(error::InitBackendSnafu)?
         .finish())
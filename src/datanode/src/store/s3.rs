@@ -18,9 +18,9 @@ use object_store::{util, ObjectStore};
 use secrecy::ExposeSecret;
 use snafu::prelude::*;
 
-use crate::config::S3Config;
+use crate::config::{CredentialSource, S3Config, ServerSideEncryption};
 use crate::error::{self, Result};
-use crate::store::build_http_client;
+use crate::store::{build_http_client, decode_customer_key};
 
 pub(crate) async fn new_s3_object_store(s3_config: &S3Config) -> Result<ObjectStore> {
     let root = util::normalize_dir(&s3_config.root);
@@ -45,6 +45,33 @@ pub(crate) async fn new_s3_object_store(s3_config: &S3Config) -> Result<ObjectSt
         let _ = builder.region(s3_config.region.as_ref().unwrap());
     }
 
+    let security = &s3_config.security;
+    match security.server_side_encryption {
+        ServerSideEncryption::Default => {}
+        ServerSideEncryption::Managed => {
+            let _ = builder.server_side_encryption_with_s3_key();
+        }
+        ServerSideEncryption::Kms => match &security.kms_key_id {
+            Some(key_id) => {
+                let _ = builder.server_side_encryption_with_customer_managed_kms_key(key_id);
+            }
+            None => {
+                let _ = builder.server_side_encryption_with_aws_managed_kms_key();
+            }
+        },
+        ServerSideEncryption::Customer => {
+            let key = decode_customer_key(s3_config.name.as_deref().unwrap_or("S3"), security)?;
+            let _ = builder.server_side_encryption_with_customer_key("AES256", &key);
+        }
+    }
+    if security.request_payer {
+        let _ = builder.enable_request_payer();
+    }
+    if security.credential_source == CredentialSource::Static {
+        // Don't fall back to the environment or the instance metadata service.
+        let _ = builder.disable_config_load().disable_ec2_metadata();
+    }
+
     Ok(ObjectStore::new(builder)
         .context(error::InitBackendSnafu)?
         .finish())
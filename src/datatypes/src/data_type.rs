@@ -214,6 +214,43 @@ impl ConcreteDataType {
         matches!(self, ConcreteDataType::Decimal128(_))
     }
 
+    /// Returns true if values of this type can be losslessly converted to `target`,
+    /// i.e. `target` is a wider type of the same family.
+    ///
+    /// Changing a type to itself is also considered widening.
+    pub fn can_widen_to(&self, target: &ConcreteDataType) -> bool {
+        use ConcreteDataType::*;
+
+        if self == target {
+            return true;
+        }
+        matches!(
+            (self, target),
+            (
+                Int8(_),
+                Int16(_) | Int32(_) | Int64(_) | Float32(_) | Float64(_)
+            ) | (Int16(_), Int32(_) | Int64(_) | Float32(_) | Float64(_))
+                | (Int32(_), Int64(_) | Float64(_))
+                | (
+                    UInt8(_),
+                    UInt16(_)
+                        | UInt32(_)
+                        | UInt64(_)
+                        | Int16(_)
+                        | Int32(_)
+                        | Int64(_)
+                        | Float32(_)
+                        | Float64(_)
+                )
+                | (
+                    UInt16(_),
+                    UInt32(_) | UInt64(_) | Int32(_) | Int64(_) | Float32(_) | Float64(_)
+                )
+                | (UInt32(_), UInt64(_) | Int64(_) | Float64(_))
+                | (Float32(_), Float64(_))
+        )
+    }
+
     pub fn numerics() -> Vec<ConcreteDataType> {
         vec![
             ConcreteDataType::int8_datatype(),
@@ -807,6 +844,32 @@ mod tests {
         assert!(!ConcreteDataType::float64_datatype().is_unsigned());
     }
 
+    #[test]
+    fn test_can_widen_to() {
+        let int32 = ConcreteDataType::int32_datatype();
+        assert!(int32.can_widen_to(&int32));
+        assert!(int32.can_widen_to(&ConcreteDataType::int64_datatype()));
+        assert!(int32.can_widen_to(&ConcreteDataType::float64_datatype()));
+        assert!(!int32.can_widen_to(&ConcreteDataType::int16_datatype()));
+        assert!(!int32.can_widen_to(&ConcreteDataType::float32_datatype()));
+        assert!(!int32.can_widen_to(&ConcreteDataType::uint64_datatype()));
+        assert!(!int32.can_widen_to(&ConcreteDataType::string_datatype()));
+
+        assert!(
+            ConcreteDataType::uint32_datatype().can_widen_to(&ConcreteDataType::int64_datatype())
+        );
+        assert!(
+            !ConcreteDataType::uint64_datatype().can_widen_to(&ConcreteDataType::int64_datatype())
+        );
+        assert!(ConcreteDataType::float32_datatype()
+            .can_widen_to(&ConcreteDataType::float64_datatype()));
+        assert!(!ConcreteDataType::float64_datatype()
+            .can_widen_to(&ConcreteDataType::float32_datatype()));
+        assert!(
+            !ConcreteDataType::int64_datatype().can_widen_to(&ConcreteDataType::float64_datatype())
+        );
+    }
+
     #[test]
     fn test_numerics() {
         let nums = ConcreteDataType::numerics();
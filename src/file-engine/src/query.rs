@@ -13,8 +13,9 @@
 // limitations under the License.
 
 pub(crate) mod file_stream;
+mod path_partition;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -24,12 +25,15 @@ use common_error::ext::BoxedError;
 use common_query::prelude::Expr;
 use common_recordbatch::adapter::RecordBatchMetrics;
 use common_recordbatch::error::{CastVectorSnafu, ExternalSnafu, Result as RecordBatchResult};
-use common_recordbatch::{OrderOption, RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use common_recordbatch::{
+    OrderOption, RecordBatch, RecordBatchStream, RecordBatchStreamWrapper,
+    SendableRecordBatchStream,
+};
 use datafusion::logical_expr::utils as df_logical_expr_utils;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
-use datatypes::vectors::VectorRef;
-use futures::Stream;
+use datatypes::vectors::{StringVector, VectorRef};
+use futures::{Stream, StreamExt};
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::ScanRequest;
 
@@ -47,25 +51,42 @@ impl FileRegion {
         let file_projection = self.projection_pushdown_to_file(&request.projection)?;
         let file_filters = self.filters_pushdown_to_file(&request.filters)?;
         let file_schema = Arc::new(Schema::new(self.file_options.file_column_schemas.clone()));
-
-        let file_stream = file_stream::create_stream(
-            &self.format,
-            &CreateScanPlanContext::default(),
-            &ScanPlanConfig {
-                file_schema,
-                files: &self.file_options.files,
-                projection: file_projection.as_ref(),
-                filters: &file_filters,
-                limit: request.limit,
-                store,
-            },
-        )?;
-
         let scan_schema = self.scan_schema(&request.projection)?;
 
-        Ok(Box::pin(FileToScanRegionStream::new(
+        // Columns absent in files are filled by partition values in paths.
+        let partition_columns = scan_schema
+            .column_schemas()
+            .iter()
+            .map(|column| column.name.as_str())
+            .filter(|name| file_schema.column_schema_by_name(name).is_none())
+            .collect::<Vec<_>>();
+        let files = path_partition::prune_files(&self.file_options.files, &request.filters);
+        let streams = path_partition::group_files_by_partition(files, &partition_columns)
+            .into_iter()
+            .map(|(partition_values, files)| {
+                let file_stream = file_stream::create_stream(
+                    &self.format,
+                    &CreateScanPlanContext::default(),
+                    &ScanPlanConfig {
+                        file_schema: file_schema.clone(),
+                        files: &files,
+                        projection: file_projection.as_ref(),
+                        filters: &file_filters,
+                        limit: request.limit,
+                        store: store.clone(),
+                    },
+                )?;
+                Ok(FileToScanRegionStream::new(
+                    scan_schema.clone(),
+                    file_stream,
+                    partition_values,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Box::pin(RecordBatchStreamWrapper::new(
             scan_schema,
-            file_stream,
+            futures::stream::iter(streams).flatten(),
         )))
     }
 
@@ -146,6 +167,8 @@ impl FileRegion {
 struct FileToScanRegionStream {
     scan_schema: SchemaRef,
     file_stream: SendableRecordBatchStream,
+    /// Values of columns absent in files, parsed from file paths.
+    partition_values: HashMap<String, String>,
 }
 
 impl RecordBatchStream for FileToScanRegionStream {
@@ -184,10 +207,15 @@ impl Stream for FileToScanRegionStream {
 }
 
 impl FileToScanRegionStream {
-    fn new(scan_schema: SchemaRef, file_stream: SendableRecordBatchStream) -> Self {
+    fn new(
+        scan_schema: SchemaRef,
+        file_stream: SendableRecordBatchStream,
+        partition_values: HashMap<String, String>,
+    ) -> Self {
         Self {
             scan_schema,
             file_stream,
+            partition_values,
         }
     }
 
@@ -208,7 +236,8 @@ impl FileToScanRegionStream {
     /// This function performs the following operations:
     /// - Projection: Only columns present in scan schema are retained.
     /// - Cast Type: Columns present in both file schema and scan schema but with different types are cast to the type in scan schema.
-    /// - Backfill: Columns present in scan schema but not in file schema are backfilled with default values.
+    /// - Backfill: Columns present in scan schema but not in file schema are backfilled with
+    ///   partition values in file paths, or default values if absent.
     fn convert_record_batch(
         &self,
         file_record_batch: &RecordBatch,
@@ -222,6 +251,10 @@ impl FileToScanRegionStream {
                 let file_column = file_record_batch.column_by_name(&scan_column_schema.name);
                 if let Some(file_column) = file_column {
                     Self::cast_column_type(file_column, &scan_column_schema.data_type)
+                } else if let Some(value) = self.partition_values.get(&scan_column_schema.name) {
+                    let column: VectorRef =
+                        Arc::new(StringVector::from(vec![value.as_str(); file_row_count]));
+                    Self::cast_column_type(&column, &scan_column_schema.data_type)
                 } else {
                     Self::backfill_column(scan_column_schema, file_row_count)
                 }
@@ -0,0 +1,321 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Prunes files by partition values encoded in their paths.
+//!
+//! Files laid out in hive style, e.g. `data/year=2024/month=01/1.parquet`,
+//! carry the partition values of their rows in the path. A file is skipped if
+//! an equality filter on a partition column doesn't match the value in its path.
+//! Table columns absent in files are filled with the partition values.
+//!
+//! Segments are percent-decoded as hive escapes special characters in them, and
+//! values are compared by the type of the literal in the filter, so `month=01`
+//! matches `month = 1`.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+use common_query::prelude::Expr;
+use datafusion::logical_expr::{BinaryExpr, Expr as DfExpr, Operator};
+use datafusion::scalar::ScalarValue;
+
+/// Returns files that may contain rows matching `filters`.
+pub(crate) fn prune_files(files: &[String], filters: &[Expr]) -> Vec<String> {
+    let predicates = filters
+        .iter()
+        .filter_map(|filter| equality_predicate(filter.df_expr()))
+        .collect::<Vec<_>>();
+    if predicates.is_empty() {
+        return files.to_vec();
+    }
+
+    files
+        .iter()
+        .filter(|file| {
+            let partitions = partition_values(file);
+            predicates.iter().all(|(column, literals)| {
+                partitions
+                    .get(column.as_str())
+                    .map(|value| literals.iter().any(|literal| value_matches(value, literal)))
+                    .unwrap_or(true)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Groups `files` by their partition values of `columns`. Files without a
+/// partition value of a column are grouped with files where it is absent.
+pub(crate) fn group_files_by_partition(
+    files: Vec<String>,
+    columns: &[&str],
+) -> Vec<(HashMap<String, String>, Vec<String>)> {
+    let mut groups: BTreeMap<Vec<(String, String)>, Vec<String>> = BTreeMap::new();
+    for file in files {
+        let partitions = partition_values(&file);
+        let key = columns
+            .iter()
+            .filter_map(|column| {
+                partitions
+                    .get(*column)
+                    .map(|value| (column.to_string(), value.clone()))
+            })
+            .collect::<Vec<_>>();
+        groups.entry(key).or_default().push(file);
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, files)| (key.into_iter().collect(), files))
+        .collect()
+}
+
+/// Extracts partition values from `key=value` segments of the directories in `path`.
+fn partition_values(path: &str) -> HashMap<String, String> {
+    let mut segments = path.split('/').collect::<Vec<_>>();
+    // The last segment is the file name.
+    let _ = segments.pop();
+    segments
+        .into_iter()
+        .filter_map(|segment| segment.split_once('='))
+        .map(|(key, value)| {
+            (
+                percent_decode(key).into_owned(),
+                percent_decode(value).into_owned(),
+            )
+        })
+        .collect()
+}
+
+/// Decodes `%XX` escapes in a path segment. The segment is returned as is if
+/// it isn't valid UTF-8 after decoding.
+fn percent_decode(segment: &str) -> Cow<'_, str> {
+    if !segment.contains('%') {
+        return Cow::Borrowed(segment);
+    }
+
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = hex {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(_) => Cow::Borrowed(segment),
+    }
+}
+
+/// Returns false only if the partition `value` in the path can't be equal to
+/// `literal`. Values that can't be parsed as the type of `literal` are kept.
+fn value_matches(value: &str, literal: &ScalarValue) -> bool {
+    match literal {
+        ScalarValue::Utf8(Some(s)) | ScalarValue::LargeUtf8(Some(s)) => value == s,
+        ScalarValue::Int8(Some(v)) => int_matches(value, *v as i64),
+        ScalarValue::Int16(Some(v)) => int_matches(value, *v as i64),
+        ScalarValue::Int32(Some(v)) => int_matches(value, *v as i64),
+        ScalarValue::Int64(Some(v)) => int_matches(value, *v),
+        ScalarValue::UInt8(Some(v)) => uint_matches(value, *v as u64),
+        ScalarValue::UInt16(Some(v)) => uint_matches(value, *v as u64),
+        ScalarValue::UInt32(Some(v)) => uint_matches(value, *v as u64),
+        ScalarValue::UInt64(Some(v)) => uint_matches(value, *v),
+        ScalarValue::Boolean(Some(v)) => match value.to_ascii_lowercase().parse::<bool>() {
+            Ok(value) => value == *v,
+            Err(_) => true,
+        },
+        _ => true,
+    }
+}
+
+fn int_matches(value: &str, literal: i64) -> bool {
+    value.trim().parse::<i64>().map_or(true, |v| v == literal)
+}
+
+fn uint_matches(value: &str, literal: u64) -> bool {
+    value.trim().parse::<u64>().map_or(true, |v| v == literal)
+}
+
+/// Converts `column = literal` or `column IN (literals)` to the column and
+/// accepted literals.
+fn equality_predicate(expr: &DfExpr) -> Option<(String, Vec<ScalarValue>)> {
+    match expr {
+        DfExpr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        }) => match (left.as_ref(), right.as_ref()) {
+            (DfExpr::Column(column), DfExpr::Literal(value))
+            | (DfExpr::Literal(value), DfExpr::Column(column)) => {
+                Some((column.name.clone(), vec![supported_literal(value)?]))
+            }
+            _ => None,
+        },
+        DfExpr::InList(in_list) if !in_list.negated => {
+            let DfExpr::Column(column) = in_list.expr.as_ref() else {
+                return None;
+            };
+            let values = in_list
+                .list
+                .iter()
+                .map(|value| match value {
+                    DfExpr::Literal(value) => supported_literal(value),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((column.name.clone(), values))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the literal if partition values can be compared with it.
+fn supported_literal(value: &ScalarValue) -> Option<ScalarValue> {
+    match value {
+        ScalarValue::Utf8(Some(_))
+        | ScalarValue::LargeUtf8(Some(_))
+        | ScalarValue::Int8(Some(_))
+        | ScalarValue::Int16(Some(_))
+        | ScalarValue::Int32(Some(_))
+        | ScalarValue::Int64(Some(_))
+        | ScalarValue::UInt8(Some(_))
+        | ScalarValue::UInt16(Some(_))
+        | ScalarValue::UInt32(Some(_))
+        | ScalarValue::UInt64(Some(_))
+        | ScalarValue::Boolean(Some(_)) => Some(value.clone()),
+        // Other types may be formatted differently from the path.
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::logical_expr::{col, lit};
+
+    use super::*;
+
+    fn files() -> Vec<String> {
+        vec![
+            "data/year=2023/month=12/1.parquet".to_string(),
+            "data/year=2024/month=01/1.parquet".to_string(),
+            "data/year=2024/month=02/1.parquet".to_string(),
+            "data/other.parquet".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_prune_files() {
+        let filters = vec![Expr::from(col("year").eq(lit(2024)))];
+        assert_eq!(
+            vec![
+                "data/year=2024/month=01/1.parquet",
+                "data/year=2024/month=02/1.parquet",
+                "data/other.parquet",
+            ],
+            prune_files(&files(), &filters)
+        );
+
+        let filters = vec![
+            Expr::from(lit("2024").eq(col("year"))),
+            Expr::from(col("month").in_list(vec![lit("12"), lit("02")], false)),
+        ];
+        assert_eq!(
+            vec!["data/year=2024/month=02/1.parquet", "data/other.parquet"],
+            prune_files(&files(), &filters)
+        );
+    }
+
+    #[test]
+    fn test_group_files_by_partition() {
+        let groups = group_files_by_partition(files(), &["year"]);
+        assert_eq!(
+            vec![
+                (HashMap::new(), vec!["data/other.parquet".to_string()]),
+                (
+                    HashMap::from([("year".to_string(), "2023".to_string())]),
+                    vec!["data/year=2023/month=12/1.parquet".to_string()]
+                ),
+                (
+                    HashMap::from([("year".to_string(), "2024".to_string())]),
+                    vec![
+                        "data/year=2024/month=01/1.parquet".to_string(),
+                        "data/year=2024/month=02/1.parquet".to_string()
+                    ]
+                ),
+            ],
+            groups
+        );
+
+        let groups = group_files_by_partition(files(), &[]);
+        assert_eq!(vec![(HashMap::new(), files())], groups);
+    }
+
+    #[test]
+    fn test_prune_files_typed_values() {
+        let filters = vec![Expr::from(col("month").eq(lit(1)))];
+        assert_eq!(
+            vec!["data/year=2024/month=01/1.parquet", "data/other.parquet",],
+            prune_files(&files(), &filters)
+        );
+
+        // String literals are compared as is.
+        let filters = vec![Expr::from(col("month").eq(lit("1")))];
+        assert_eq!(vec!["data/other.parquet"], prune_files(&files(), &filters));
+
+        // Values not parsable as the literal type are kept.
+        let files = vec!["data/month=unknown/1.parquet".to_string()];
+        let filters = vec![Expr::from(col("month").eq(lit(1)))];
+        assert_eq!(files, prune_files(&files, &filters));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!("a b/c", percent_decode("a%20b%2Fc"));
+        assert_eq!("100%", percent_decode("100%"));
+        assert_eq!("%zz", percent_decode("%zz"));
+        assert_eq!("plain", percent_decode("plain"));
+
+        let files = vec!["data/city=New%20York/1.parquet".to_string()];
+        let filters = vec![Expr::from(col("city").eq(lit("New York")))];
+        assert_eq!(files, prune_files(&files, &filters));
+        let groups = group_files_by_partition(files.clone(), &["city"]);
+        assert_eq!(
+            vec![(
+                HashMap::from([("city".to_string(), "New York".to_string())]),
+                files
+            )],
+            groups
+        );
+    }
+
+    #[test]
+    fn test_prune_files_unsupported_filters() {
+        let filters = vec![
+            Expr::from(col("year").gt(lit(2023))),
+            Expr::from(col("month").in_list(vec![lit("01")], true)),
+        ];
+        assert_eq!(files(), prune_files(&files(), &filters));
+    }
+}
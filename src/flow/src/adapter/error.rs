@@ -80,6 +80,14 @@ pub enum Error {
         context: String,
         location: Location,
     },
+
+    #[snafu(display("Failed to access flow checkpoint: {path}"))]
+    Checkpoint {
+        path: String,
+        #[snafu(source)]
+        error: object_store::Error,
+        location: Location,
+    },
 }
 
 /// Result type for flow module
@@ -97,6 +105,7 @@ impl ErrorExt for Error {
                 StatusCode::PlanQuery
             }
             Self::NoProtoType { .. } => StatusCode::Unexpected,
+            Self::Checkpoint { .. } => StatusCode::StorageUnavailable,
             &Self::NotImplemented { .. } | Self::UnsupportedTemporalFilter { .. } => {
                 StatusCode::Unsupported
             }
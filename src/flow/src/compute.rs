@@ -14,6 +14,7 @@
 
 //! Build and Compute the dataflow
 
+mod checkpoint;
 mod render;
 mod state;
 mod types;
@@ -0,0 +1,145 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checkpoints of dataflow state, persisted in object storage so a flow can resume
+//! from its last checkpoint after the flownode restarts
+
+use object_store::{ErrorKind, ObjectStore};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::adapter::error::{CheckpointSnafu, EvalSnafu, Result};
+use crate::expr::error::InternalSnafu;
+use crate::expr::EvalError;
+use crate::repr::Timestamp;
+use crate::utils::ArrangementCheckpoint;
+
+/// A serializable snapshot of the state of a dataflow, see [`DataflowState::checkpoint`]
+///
+/// [`DataflowState::checkpoint`]: crate::compute::state::DataflowState::checkpoint
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DataflowCheckpoint {
+    /// current time of the dataflow when the checkpoint is taken
+    pub as_of: Timestamp,
+    /// checkpoints of the arrangements of the dataflow, in the order they are rendered
+    pub arrangements: Vec<ArrangementCheckpoint>,
+}
+
+impl DataflowCheckpoint {
+    /// encode the checkpoint into bytes, so it can be persisted
+    pub fn encode(&self) -> std::result::Result<Vec<u8>, EvalError> {
+        serde_json::to_vec(self).map_err(|e| {
+            InternalSnafu {
+                reason: format!("Failed to encode dataflow checkpoint: {e}"),
+            }
+            .build()
+        })
+    }
+
+    /// decode the checkpoint from bytes created by [`DataflowCheckpoint::encode`]
+    pub fn decode(bytes: &[u8]) -> std::result::Result<Self, EvalError> {
+        serde_json::from_slice(bytes).map_err(|e| {
+            InternalSnafu {
+                reason: format!("Failed to decode dataflow checkpoint: {e}"),
+            }
+            .build()
+        })
+    }
+}
+
+/// Persists the last checkpoint of each flow in object storage, one file per flow
+///
+/// A new checkpoint overwrites the last one of the flow, so the file is always
+/// a complete checkpoint
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    /// object store to keep checkpoints in
+    object_store: ObjectStore,
+    /// directory of checkpoints in the object store, ends with `/`
+    dir: String,
+}
+
+impl CheckpointStore {
+    /// create a store keeping checkpoints under `dir` of `object_store`
+    pub fn new(object_store: ObjectStore, dir: &str) -> Self {
+        let dir = if dir.ends_with('/') {
+            dir.to_string()
+        } else {
+            format!("{dir}/")
+        };
+        Self { object_store, dir }
+    }
+
+    /// path of the checkpoint of `flow`
+    fn path(&self, flow: &str) -> String {
+        format!("{}{flow}.checkpoint", self.dir)
+    }
+
+    /// persist `checkpoint` as the last checkpoint of `flow`
+    pub async fn save(&self, flow: &str, checkpoint: &DataflowCheckpoint) -> Result<()> {
+        let bytes = checkpoint.encode().context(EvalSnafu)?;
+        let path = self.path(flow);
+        self.object_store
+            .write(&path, bytes)
+            .await
+            .context(CheckpointSnafu { path })
+    }
+
+    /// load the last checkpoint of `flow`, returns `None` if it has none
+    pub async fn load(&self, flow: &str) -> Result<Option<DataflowCheckpoint>> {
+        let path = self.path(flow);
+        let bytes = match self.object_store.read(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context(CheckpointSnafu { path }),
+        };
+        DataflowCheckpoint::decode(&bytes)
+            .map(Some)
+            .context(EvalSnafu)
+    }
+
+    /// remove the checkpoint of `flow`, e.g. after the flow is dropped
+    pub async fn remove(&self, flow: &str) -> Result<()> {
+        let path = self.path(flow);
+        self.object_store
+            .delete(&path)
+            .await
+            .context(CheckpointSnafu { path })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use object_store::services::Memory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_checkpoint_store() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let store = CheckpointStore::new(object_store, "flow/checkpoints");
+        assert!(store.load("my_flow").await.unwrap().is_none());
+
+        let checkpoint = DataflowCheckpoint {
+            as_of: 42,
+            arrangements: vec![],
+        };
+        store.save("my_flow", &checkpoint).await.unwrap();
+        assert_eq!(Some(checkpoint), store.load("my_flow").await.unwrap());
+        assert!(store.load("other_flow").await.unwrap().is_none());
+
+        store.remove("my_flow").await.unwrap();
+        assert!(store.load("my_flow").await.unwrap().is_none());
+    }
+}
@@ -20,6 +20,7 @@ use std::cell::RefCell;
 use std::collections::{BTreeMap, VecDeque};
 use std::rc::Rc;
 
+use datatypes::value::Value;
 use hydroflow::lattices::cc_traits::Get;
 use hydroflow::scheduled::graph::Hydroflow;
 use hydroflow::scheduled::graph_ext::GraphExt;
@@ -31,12 +32,13 @@ use super::state::Scheduler;
 use crate::adapter::error::{Error, EvalSnafu, InvalidQuerySnafu};
 use crate::compute::state::DataflowState;
 use crate::compute::types::{Arranged, Collection, CollectionBundle, ErrCollector, Toff};
+use crate::expr::error::InternalSnafu;
 use crate::expr::{
     self, EvalError, GlobalId, LocalId, MapFilterProject, MfpPlan, SafeMfpPlan, ScalarExpr,
 };
-use crate::plan::Plan;
-use crate::repr::{self, DiffRow, KeyValDiffRow, Row};
-use crate::utils::{ArrangeHandler, Arrangement};
+use crate::plan::{hop_windows, Plan, WindowKind, WindowPlan};
+use crate::repr::{self, value_to_internal_ts, DiffRow, KeyValDiffRow, Row};
+use crate::utils::{ArrangeHandler, Arrangement, SessionWindows};
 
 /// The Context for build a Operator with id of `GlobalId`
 pub struct Context<'referred, 'df> {
@@ -103,6 +105,7 @@ impl<'referred, 'df> Context<'referred, 'df> {
             Plan::Reduce { .. } => todo!(),
             Plan::Join { .. } => todo!(),
             Plan::Union { .. } => todo!(),
+            Plan::Window { input, window } => self.render_window(input, window),
         }
     }
 
@@ -183,6 +186,10 @@ impl<'referred, 'df> Context<'referred, 'df> {
         let arrange = Arrangement::new();
         let arrange_handler = ArrangeHandler::from(arrange.clone());
         let arrange_handler_inner = ArrangeHandler::from(arrange);
+        // the subgraph writes to the inner arrangement, so it's the state to checkpoint
+        if let Some(arrange) = arrange_handler_inner.clone_future_only() {
+            self.compute_state.register_arrangement(arrange);
+        }
 
         // This closure capture following variables:
         let mfp_plan = MfpPlan::create_from(mfp)?;
@@ -228,6 +235,57 @@ impl<'referred, 'df> Context<'referred, 'df> {
         };
         Ok(bundle)
     }
+
+    /// render Window, append `window_start` and `window_end` of the windows a row belongs to.
+    ///
+    /// `hop` window is stateless and emit a row once for each window it belongs to, while `session` window
+    /// keeps rows of open sessions to merge them, and drop rows of closed sessions every time it runs,
+    /// so `session` window requires `allowed_lateness` in flow options to close sessions
+    pub fn render_window(
+        &mut self,
+        input: Box<Plan>,
+        window: WindowPlan,
+    ) -> Result<CollectionBundle, Error> {
+        let input = self.render_plan(*input)?;
+        let (out_send_port, out_recv_port) = self.df.make_edge::<_, Toff>("window");
+
+        let WindowPlan { ts_col, window } = window;
+        let mut session = match &window {
+            WindowKind::Session { gap, .. } => {
+                let lateness = self.compute_state.lateness();
+                if lateness.allowed_lateness.is_none() {
+                    return InvalidQuerySnafu {
+                        reason: "Session window requires `allowed_lateness` in flow options, otherwise sessions never close",
+                    }
+                    .fail();
+                }
+                Some(SessionWindows::new(*gap, lateness))
+            }
+            WindowKind::Hop { .. } => None,
+        };
+        let now = self.compute_state.current_time_ref();
+        let err_collector = self.err_collector.clone();
+
+        self.df.add_subgraph_in_out(
+            "window",
+            input.collection.into_inner(),
+            out_send_port,
+            move |_ctx, recv, send| {
+                let data = recv.take_inner().into_iter().flat_map(|v| v.into_iter());
+                let now = *now.borrow();
+                let output =
+                    eval_window_core(data, ts_col, &window, session.as_mut(), now, &err_collector);
+                if let Some(session) = session.as_mut() {
+                    session.trunc_closed(now);
+                }
+                send.give(output);
+            },
+        );
+
+        Ok(CollectionBundle::from_collection(Collection::from_port(
+            out_recv_port,
+        )))
+    }
 }
 
 fn mfp_subgraph(
@@ -301,9 +359,81 @@ fn eval_mfp_core(
     all_updates
 }
 
+/// The core of evaluating Window operator, assign each input row to it's windows and
+/// return the output updates, errors of a single row are pushed to `err_collector`
+fn eval_window_core(
+    input: impl IntoIterator<Item = DiffRow>,
+    ts_col: usize,
+    window: &WindowKind,
+    session: Option<&mut SessionWindows>,
+    now: repr::Timestamp,
+    err_collector: &ErrCollector,
+) -> Vec<DiffRow> {
+    let event_ts = |row: &Row| {
+        let value = row.get(ts_col).cloned().with_context(|| InternalSnafu {
+            reason: format!(
+                "Window column {ts_col} not found in row of {} columns",
+                row.len()
+            ),
+        })?;
+        value_to_internal_ts(value)
+    };
+    let with_window = |mut row: Row, (start, end): (repr::Timestamp, repr::Timestamp)| {
+        row.extend([
+            Value::Timestamp(common_time::Timestamp::new_millisecond(start)),
+            Value::Timestamp(common_time::Timestamp::new_millisecond(end)),
+        ]);
+        row
+    };
+
+    let mut updates = Vec::new();
+    for (row, _sys_time, diff) in input {
+        match event_ts(&row) {
+            Ok(ts) => updates.push((ts, row, diff)),
+            Err(err) => err_collector.push_err(err),
+        }
+    }
+
+    match (window, session) {
+        (WindowKind::Hop { slide, size }, _) => updates
+            .into_iter()
+            .flat_map(|(ts, row, diff)| {
+                hop_windows(ts, *slide, *size)
+                    .map(move |w| (with_window(row.clone(), w), now, diff))
+            })
+            .collect_vec(),
+        (WindowKind::Session { key_cols, .. }, Some(session)) => {
+            let updates = updates.into_iter().map(|(ts, row, diff)| {
+                let key = Row::new(
+                    key_cols
+                        .iter()
+                        .filter_map(|i| row.get(*i).cloned())
+                        .collect(),
+                );
+                (key, ts, row, diff)
+            });
+            session
+                .apply_updates(now, updates)
+                .into_iter()
+                .map(|(row, w, diff)| (with_window(row, w), now, diff))
+                .collect_vec()
+        }
+        (WindowKind::Session { .. }, None) => {
+            err_collector.push_err(
+                InternalSnafu {
+                    reason: "Session window without state",
+                }
+                .build(),
+            );
+            vec![]
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::cell::RefCell;
+    use std::collections::HashMap;
     use std::rc::Rc;
 
     use common_time::DateTime;
@@ -313,8 +443,10 @@ mod test {
     use hydroflow::scheduled::handoff::VecHandoff;
 
     use super::*;
+    use crate::compute::checkpoint::DataflowCheckpoint;
     use crate::expr::BinaryFunc;
     use crate::repr::Row;
+    use crate::utils::ALLOWED_LATENESS_KEY;
 
     fn harness_test_ctx<'r, 'h>(
         df: &'r mut Hydroflow<'h>,
@@ -427,6 +559,104 @@ mod test {
         }
     }
 
+    /// test if a dataflow restored from a checkpoint still emits the updates the temporal
+    /// filter scheduled before the checkpoint
+    #[test]
+    fn test_render_mfp_restore_checkpoint() {
+        /// render `rows` through the temporal filter `now <= col(0) < now + 4`, returns
+        /// the output of the last run
+        fn render_temporal_filter(
+            ctx: &mut Context<'_, '_>,
+            rows: Vec<DiffRow>,
+        ) -> Rc<RefCell<Vec<DiffRow>>> {
+            let collection = ctx.render_constant(rows);
+            ctx.insert_global(GlobalId::User(1), collection);
+            let input_plan = Plan::Get {
+                id: expr::Id::Global(GlobalId::User(1)),
+            };
+            let mfp = MapFilterProject::new(1)
+                .filter(vec![
+                    ScalarExpr::Column(0)
+                        .call_unary(expr::UnaryFunc::Cast(ConcreteDataType::datetime_datatype()))
+                        .call_binary(
+                            ScalarExpr::CallUnmaterializable(expr::UnmaterializableFunc::Now),
+                            BinaryFunc::Gte,
+                        ),
+                    ScalarExpr::Column(0)
+                        .call_binary(
+                            ScalarExpr::literal(4i64.into(), ConcreteDataType::int64_datatype()),
+                            BinaryFunc::SubInt64,
+                        )
+                        .call_unary(expr::UnaryFunc::Cast(ConcreteDataType::datetime_datatype()))
+                        .call_binary(
+                            ScalarExpr::CallUnmaterializable(expr::UnmaterializableFunc::Now),
+                            BinaryFunc::Lt,
+                        ),
+                ])
+                .unwrap();
+            let bundle = ctx
+                .render_map_filter_project_into_executable_dataflow(Box::new(input_plan), mfp)
+                .unwrap();
+            let output = Rc::new(RefCell::new(vec![]));
+            let output_inner = output.clone();
+            let _subgraph = ctx.df.add_subgraph_sink(
+                "test_render_constant",
+                bundle.collection.into_inner(),
+                move |_ctx, recv| {
+                    let data = recv.take_inner();
+                    let res = data.into_iter().flat_map(|v| v.into_iter()).collect_vec();
+                    output_inner.borrow_mut().clear();
+                    output_inner.borrow_mut().extend(res);
+                },
+            );
+            output
+        }
+
+        let mut df = Hydroflow::new();
+        let mut state = DataflowState::default();
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+        let rows = vec![
+            (Row::new(vec![1i64.into()]), 1, 1),
+            (Row::new(vec![2i64.into()]), 2, 1),
+            (Row::new(vec![3i64.into()]), 3, 1),
+        ];
+        let output = render_temporal_filter(&mut ctx, rows);
+        drop(ctx);
+        for now in 0i64..3 {
+            state.set_current_ts(now);
+            state.run_available_with_schedule(&mut df);
+        }
+        assert_eq!(*output.borrow(), vec![(Row::new(vec![1i64.into()]), 2, -1)]);
+        let checkpoint = DataflowCheckpoint::decode(&state.checkpoint().encode().unwrap()).unwrap();
+        assert_eq!(2, checkpoint.as_of);
+        assert_eq!(1, checkpoint.arrangements.len());
+
+        // restart the flow, the sources only send new rows
+        let mut df = Hydroflow::new();
+        let mut state = DataflowState::default();
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+        let output = render_temporal_filter(&mut ctx, vec![]);
+        drop(ctx);
+        state.restore(checkpoint).unwrap();
+        assert_eq!(2, state.current_ts());
+
+        let expected_output = BTreeMap::from([
+            (3, vec![(Row::new(vec![2i64.into()]), 3, -1)]),
+            (4, vec![(Row::new(vec![3i64.into()]), 4, -1)]),
+        ]);
+        for now in 3i64..5 {
+            state.set_current_ts(now);
+            state.run_available_with_schedule(&mut df);
+            assert!(state.get_err_collector().inner.borrow().is_empty());
+            assert_eq!(*output.borrow(), expected_output[&now]);
+            output.borrow_mut().clear();
+        }
+
+        // a dataflow with other arrangements can't restore the checkpoint
+        let checkpoint = state.checkpoint();
+        assert!(DataflowState::default().restore(checkpoint).is_err());
+    }
+
     /// test if mfp operator without temporal filter works properly
     /// that is it filter the rows correctly
     #[test]
@@ -477,6 +707,83 @@ mod test {
         df.run_available();
     }
 
+    /// test if hop window operator emits a row once for each window it belongs to
+    #[test]
+    fn test_render_hop_window() {
+        let mut df = Hydroflow::new();
+        let mut state = DataflowState::default();
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        let rows = vec![
+            (Row::new(vec![1i64.into()]), 1, 1),
+            (Row::new(vec![4i64.into()]), 2, 1),
+        ];
+        let collection = ctx.render_constant(rows);
+        ctx.insert_global(GlobalId::User(1), collection);
+        let input_plan = Plan::Get {
+            id: expr::Id::Global(GlobalId::User(1)),
+        };
+        let window = WindowPlan {
+            ts_col: 0,
+            window: WindowKind::hop(2, 4).unwrap(),
+        };
+        let bundle = ctx.render_window(Box::new(input_plan), window).unwrap();
+        let collection = bundle.collection.clone(ctx.df);
+
+        let output = Rc::new(RefCell::new(vec![]));
+        let output_inner = output.clone();
+        ctx.df.add_subgraph_sink(
+            "test_render_hop_window",
+            collection.into_inner(),
+            move |_ctx, recv| {
+                let data = recv.take_inner();
+                output_inner
+                    .borrow_mut()
+                    .extend(data.into_iter().flat_map(|v| v.into_iter()));
+            },
+        );
+        drop(ctx);
+        df.run_available();
+
+        let ts = |t| Value::Timestamp(common_time::Timestamp::new_millisecond(t));
+        let expected = [(1i64, -2, 2), (1, 0, 4), (4, 2, 6), (4, 4, 8)]
+            .into_iter()
+            .map(|(v, start, end)| (Row::new(vec![v.into(), ts(start), ts(end)]), 0, 1))
+            .collect_vec();
+        assert_eq!(*output.borrow(), expected);
+    }
+
+    /// test if session window operator takes `allowed_lateness` from flow options
+    #[test]
+    fn test_render_session_window_lateness() {
+        let window = || WindowPlan {
+            ts_col: 0,
+            window: WindowKind::session(vec![], 10).unwrap(),
+        };
+        let input_plan = || {
+            Box::new(Plan::Get {
+                id: expr::Id::Global(GlobalId::User(1)),
+            })
+        };
+        let rows = vec![(Row::new(vec![1i64.into()]), 1, 1)];
+
+        // sessions never close without `allowed_lateness`
+        let mut df = Hydroflow::new();
+        let mut state = DataflowState::default();
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+        let collection = ctx.render_constant(rows.clone());
+        ctx.insert_global(GlobalId::User(1), collection);
+        assert!(ctx.render_window(input_plan(), window()).is_err());
+
+        let options = HashMap::from([(ALLOWED_LATENESS_KEY.to_string(), "1m".to_string())]);
+        let mut df = Hydroflow::new();
+        let mut state = DataflowState::new(&options).unwrap();
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+        let collection = ctx.render_constant(rows);
+        ctx.insert_global(GlobalId::User(1), collection);
+        assert!(ctx.render_window(input_plan(), window()).is_ok());
+    }
+
     /// test if constant operator works properly
     /// that is it only emit once, not multiple times
     #[test]
@@ -13,14 +13,21 @@
 // limitations under the License.
 
 use std::cell::RefCell;
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::rc::Rc;
 
 use hydroflow::scheduled::graph::Hydroflow;
 use hydroflow::scheduled::SubgraphId;
 
+use crate::compute::checkpoint::DataflowCheckpoint;
 use crate::compute::types::ErrCollector;
+use crate::expr::error::{InternalSnafu, InvalidArgumentSnafu};
+use crate::expr::EvalError;
 use crate::repr::{self, Timestamp};
+use crate::utils::{ArrangeHandler, LatenessOptions};
+
+/// Option key of the flow for how often to checkpoint its state, e.g. `5m`
+pub const CHECKPOINT_INTERVAL_KEY: &str = "checkpoint_interval";
 
 /// input/output of a dataflow
 /// One `ComputeState` manage the input/output/schedule of one `Hydroflow`
@@ -38,9 +45,106 @@ pub struct DataflowState {
     /// error collector local to this `ComputeState`,
     /// useful for distinguishing errors from different `Hydroflow`
     err_collector: ErrCollector,
+    /// watermark related options of the flow, i.e. when can window state be cleaned up
+    lateness: LatenessOptions,
+    /// arrangements of the dataflow in the order they are rendered, which are the state
+    /// to checkpoint
+    arrangements: Vec<ArrangeHandler>,
+    /// interval between checkpoints in the time of the dataflow, `None` to never checkpoint
+    checkpoint_interval: Option<repr::Duration>,
+    /// current time of the dataflow when the last checkpoint is taken or restored
+    last_checkpoint: Option<Timestamp>,
 }
 
 impl DataflowState {
+    /// create the state of a flow with the options in its `WITH` clause, e.g. `allowed_lateness`
+    ///
+    /// Nothing creates flows from `CREATE FLOW` in this crate yet, so the lateness only takes
+    /// effect in session windows rendered with a state built here, see `Context::render_window`
+    pub fn new(flow_options: &HashMap<String, String>) -> Result<Self, EvalError> {
+        let checkpoint_interval = flow_options
+            .get(CHECKPOINT_INTERVAL_KEY)
+            .map(|s| {
+                let d = humantime::parse_duration(s).map_err(|e| {
+                    InvalidArgumentSnafu {
+                        reason: format!("Invalid {CHECKPOINT_INTERVAL_KEY}: {s}, error: {e}"),
+                    }
+                    .build()
+                })?;
+                repr::Duration::try_from(d.as_millis()).map_err(|_| {
+                    InvalidArgumentSnafu {
+                        reason: format!("{CHECKPOINT_INTERVAL_KEY} is too large: {s}"),
+                    }
+                    .build()
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            lateness: LatenessOptions::from_options(flow_options)?,
+            checkpoint_interval,
+            ..Default::default()
+        })
+    }
+
+    /// register an arrangement of the dataflow, so it's included in checkpoints
+    ///
+    /// rendering the same plan registers arrangements in the same order, which is how
+    /// [`DataflowState::restore`] matches checkpoints to arrangements
+    pub fn register_arrangement(&mut self, arrangement: ArrangeHandler) {
+        self.arrangements.push(arrangement);
+    }
+
+    /// take a checkpoint of the state of the dataflow
+    pub fn checkpoint(&mut self) -> DataflowCheckpoint {
+        let as_of = self.current_ts();
+        self.last_checkpoint = Some(as_of);
+        DataflowCheckpoint {
+            as_of,
+            arrangements: self.arrangements.iter().map(|a| a.checkpoint()).collect(),
+        }
+    }
+
+    /// take a checkpoint if the checkpoint interval of the flow has passed since the
+    /// last one, the caller persists it with [`CheckpointStore::save`]
+    ///
+    /// [`CheckpointStore::save`]: crate::compute::checkpoint::CheckpointStore::save
+    pub fn checkpoint_if_due(&mut self) -> Option<DataflowCheckpoint> {
+        let interval = self.checkpoint_interval?;
+        let due = match self.last_checkpoint {
+            Some(last) => self.current_ts() - last >= interval,
+            None => true,
+        };
+        due.then(|| self.checkpoint())
+    }
+
+    /// restore the state of a newly rendered dataflow from `checkpoint`, e.g. one loaded
+    /// with [`CheckpointStore::load`] after restart
+    ///
+    /// fails if the checkpoint is taken from a dataflow with different arrangements,
+    /// i.e. the plan of the flow has changed
+    ///
+    /// [`CheckpointStore::load`]: crate::compute::checkpoint::CheckpointStore::load
+    pub fn restore(&mut self, checkpoint: DataflowCheckpoint) -> Result<(), EvalError> {
+        if checkpoint.arrangements.len() != self.arrangements.len() {
+            return InternalSnafu {
+                reason: format!(
+                    "Checkpoint has {} arrangements, but the dataflow has {}",
+                    checkpoint.arrangements.len(),
+                    self.arrangements.len()
+                ),
+            }
+            .fail();
+        }
+        for (arrangement, arrangement_checkpoint) in
+            self.arrangements.iter().zip(checkpoint.arrangements)
+        {
+            arrangement.restore(arrangement_checkpoint);
+        }
+        self.set_current_ts(checkpoint.as_of);
+        self.last_checkpoint = Some(checkpoint.as_of);
+        Ok(())
+    }
+
     /// schedule all subgraph that need to run with time <= `as_of` and run_available()
     ///
     /// return true if any subgraph actually executed
@@ -83,6 +187,10 @@ impl DataflowState {
     pub fn get_err_collector(&self) -> ErrCollector {
         self.err_collector.clone()
     }
+
+    pub fn lateness(&self) -> LatenessOptions {
+        self.lateness
+    }
 }
 
 #[derive(Clone)]
@@ -104,3 +212,47 @@ impl Scheduler {
         self.cur_subgraph.replace(Some(subgraph));
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::{LateDataPolicy, ALLOWED_LATENESS_KEY, LATE_DATA_POLICY_KEY};
+
+    #[test]
+    fn test_state_from_flow_options() {
+        let state = DataflowState::new(&HashMap::new()).unwrap();
+        assert_eq!(LatenessOptions::default(), state.lateness());
+
+        let options = HashMap::from([
+            (ALLOWED_LATENESS_KEY.to_string(), "5s".to_string()),
+            (LATE_DATA_POLICY_KEY.to_string(), "side_output".to_string()),
+        ]);
+        let state = DataflowState::new(&options).unwrap();
+        assert_eq!(
+            LatenessOptions {
+                allowed_lateness: Some(5000),
+                late_data_policy: LateDataPolicy::SideOutput,
+            },
+            state.lateness()
+        );
+
+        let options = HashMap::from([(ALLOWED_LATENESS_KEY.to_string(), "soon".to_string())]);
+        assert!(DataflowState::new(&options).is_err());
+        let options = HashMap::from([(CHECKPOINT_INTERVAL_KEY.to_string(), "soon".to_string())]);
+        assert!(DataflowState::new(&options).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_if_due() {
+        let mut state = DataflowState::default();
+        assert!(state.checkpoint_if_due().is_none());
+
+        let options = HashMap::from([(CHECKPOINT_INTERVAL_KEY.to_string(), "1s".to_string())]);
+        let mut state = DataflowState::new(&options).unwrap();
+        assert_eq!(0, state.checkpoint_if_due().unwrap().as_of);
+        state.set_current_ts(999);
+        assert!(state.checkpoint_if_due().is_none());
+        state.set_current_ts(1000);
+        assert_eq!(1000, state.checkpoint_if_due().unwrap().as_of);
+    }
+}
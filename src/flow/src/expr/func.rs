@@ -17,7 +17,9 @@
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
-use common_time::DateTime;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use common_time::util::datetime_to_utc;
+use common_time::{DateTime, Timezone};
 use datafusion_expr::Operator;
 use datafusion_substrait::logical_plan::consumer::name_to_op;
 use datatypes::data_type::ConcreteDataType;
@@ -31,8 +33,8 @@ use strum::{EnumIter, IntoEnumIterator};
 
 use crate::adapter::error::{Error, InvalidQuerySnafu, PlanSnafu};
 use crate::expr::error::{
-    CastValueSnafu, DivisionByZeroSnafu, EvalError, InternalSnafu, TryFromValueSnafu,
-    TypeMismatchSnafu,
+    CastValueSnafu, DivisionByZeroSnafu, EvalError, InternalSnafu, OverflowSnafu,
+    TryFromValueSnafu, TypeMismatchSnafu,
 };
 use crate::expr::signature::{GenericFn, Signature};
 use crate::expr::{InvalidArgumentSnafu, ScalarExpr};
@@ -86,6 +88,12 @@ pub enum UnaryFunc {
     IsFalse,
     StepTimestamp,
     Cast(ConcreteDataType),
+    /// Truncates a timestamp to the start of the `unit` it belongs to in the `timezone`,
+    /// or in UTC if the timezone is absent.
+    DateTrunc {
+        unit: DateTruncUnit,
+        timezone: Option<String>,
+    },
 }
 
 impl UnaryFunc {
@@ -117,9 +125,31 @@ impl UnaryFunc {
                 output: to.clone(),
                 generic_fn: GenericFn::Cast,
             },
+            Self::DateTrunc { .. } => Signature {
+                input: smallvec![ConcreteDataType::datetime_datatype()],
+                output: ConcreteDataType::datetime_datatype(),
+                generic_fn: GenericFn::DateTrunc,
+            },
         }
     }
 
+    /// Create a `date_trunc` function from the name of the unit and the timezone of the flow
+    pub fn date_trunc(unit: &str, timezone: Option<&str>) -> Result<Self, Error> {
+        let unit = DateTruncUnit::from_str(unit)?;
+        if let Some(timezone) = timezone {
+            let _ = Timezone::from_tz_string(timezone).map_err(|e| {
+                InvalidQuerySnafu {
+                    reason: format!("Invalid timezone {timezone}: {e}"),
+                }
+                .build()
+            })?;
+        }
+        Ok(Self::DateTrunc {
+            unit,
+            timezone: timezone.map(|tz| tz.to_string()),
+        })
+    }
+
     /// Create a UnaryFunc from a string of the function name and given argument type(optional)
     pub fn from_str_and_type(
         name: &str,
@@ -209,6 +239,106 @@ impl UnaryFunc {
                 })?;
                 Ok(res)
             }
+            Self::DateTrunc { unit, timezone } => {
+                if arg.is_null() {
+                    return Ok(Value::Null);
+                }
+                let ts = value_to_internal_ts(arg)?;
+                let timezone = timezone
+                    .as_deref()
+                    .map(Timezone::from_tz_string)
+                    .transpose()
+                    .map_err(|e| {
+                        InvalidArgumentSnafu {
+                            reason: format!("Invalid timezone: {e}"),
+                        }
+                        .build()
+                    })?;
+                let truncated = unit.truncate(ts, timezone.as_ref())?;
+                Ok(Value::from(DateTime::new(truncated)))
+            }
+        }
+    }
+}
+
+/// Unit to truncate timestamps to, the first argument of `date_trunc`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, Hash)]
+pub enum DateTruncUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl DateTruncUnit {
+    /// Create a DateTruncUnit from the name of the unit
+    pub fn from_str(name: &str) -> Result<Self, Error> {
+        match name.to_lowercase().as_str() {
+            "second" => Ok(Self::Second),
+            "minute" => Ok(Self::Minute),
+            "hour" => Ok(Self::Hour),
+            "day" => Ok(Self::Day),
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            "quarter" => Ok(Self::Quarter),
+            "year" => Ok(Self::Year),
+            _ => InvalidQuerySnafu {
+                reason: format!("Unsupported unit for date_trunc: {}", name),
+            }
+            .fail(),
+        }
+    }
+
+    /// Truncate the timestamp in milliseconds to the start of the unit in the `timezone`,
+    /// so that e.g. days start at the local midnight instead of the midnight in UTC.
+    pub fn truncate(&self, ts: i64, timezone: Option<&Timezone>) -> Result<i64, EvalError> {
+        let datetime = DateTime::new(ts);
+        let local = datetime
+            .to_chrono_datetime_with_timezone(timezone)
+            .context(OverflowSnafu)?;
+        let truncated = self.truncate_local(local).context(OverflowSnafu)?;
+
+        let Some(timezone) = timezone else {
+            return Ok(truncated.timestamp_millis());
+        };
+        // The start of the unit may be skipped by a daylight saving transition, uses the
+        // first valid local time after it instead.
+        let mut candidate = truncated;
+        while candidate <= local {
+            if let Some(utc) = datetime_to_utc(&candidate, timezone).earliest() {
+                return Ok(utc.timestamp_millis());
+            }
+            candidate += Duration::minutes(15);
+        }
+        InvalidArgumentSnafu {
+            reason: format!("Local time {truncated} doesn't exist in timezone {timezone}"),
+        }
+        .fail()
+    }
+
+    fn truncate_local(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        let date = local.date();
+        match self {
+            Self::Second => local.with_nanosecond(0),
+            Self::Minute => date.and_hms_opt(local.hour(), local.minute(), 0),
+            Self::Hour => date.and_hms_opt(local.hour(), 0, 0),
+            Self::Day => date.and_hms_opt(0, 0, 0),
+            Self::Week => {
+                let days = date.weekday().num_days_from_monday();
+                (date - Duration::days(days as i64)).and_hms_opt(0, 0, 0)
+            }
+            Self::Month => {
+                NaiveDate::from_ymd_opt(date.year(), date.month(), 1)?.and_hms_opt(0, 0, 0)
+            }
+            Self::Quarter => {
+                let month = (date.month() - 1) / 3 * 3 + 1;
+                NaiveDate::from_ymd_opt(date.year(), month, 1)?.and_hms_opt(0, 0, 0)
+            }
+            Self::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1)?.and_hms_opt(0, 0, 0),
         }
     }
 }
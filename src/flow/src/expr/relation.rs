@@ -14,6 +14,7 @@
 
 //! Describes an aggregation function and it's input expression.
 
+pub(crate) use accum::FirstLast;
 pub(crate) use func::AggregateFunc;
 use serde::{Deserialize, Serialize};
 
@@ -21,6 +22,7 @@ use crate::expr::ScalarExpr;
 
 mod accum;
 mod func;
+mod sketch;
 
 /// Describes an aggregation expression.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
@@ -34,4 +36,8 @@ pub struct AggregateExpr {
     /// Should the aggregation be applied only to distinct results in each group.
     #[serde(default)]
     pub distinct: bool,
+    /// An expression which extracts from each row the key to order by,
+    /// only used by order-sensitive aggregations like `first_value`/`last_value`.
+    #[serde(default)]
+    pub order_by: Option<ScalarExpr>,
 }
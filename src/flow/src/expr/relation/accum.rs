@@ -17,20 +17,24 @@
 //! Accumulator will only be restore from row and being updated every time dataflow need process a new batch of rows.
 //! So the overhead is acceptable.
 //!
-//! Currently support sum, count, any, all and min/max(with one caveat that min/max can't support delete with aggregate).
+//! Currently support sum, count, any, all, approx_distinct, approx_percentile_cont, first_value/last_value
+//! and min/max(with one caveat that min/max and first_value/last_value can't support delete with aggregate).
+//!
+//! The states of accumulators of parts of a group can be merged into the state of the group, see [`Accum::merge`].
 
 use std::fmt::Display;
 
 use common_decimal::Decimal128;
 use common_time::{Date, DateTime};
 use datatypes::data_type::ConcreteDataType;
-use datatypes::value::{OrderedF32, OrderedF64, OrderedFloat, Value};
+use datatypes::value::{ListValue, OrderedF32, OrderedF64, OrderedFloat, Value};
 use enum_dispatch::enum_dispatch;
 use hydroflow::futures::stream::Concat;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 
 use crate::expr::error::{InternalSnafu, OverflowSnafu, TryFromValueSnafu, TypeMismatchSnafu};
+use crate::expr::relation::sketch::{HyperLogLog, UddSketch};
 use crate::expr::signature::GenericFn;
 use crate::expr::{AggregateFunc, EvalError};
 use crate::repr::Diff;
@@ -526,6 +530,263 @@ impl Accumulator for OrdValue {
     }
 }
 
+/// Accumulates distinct values in a HyperLogLog sketch for `approx_distinct`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Hll {
+    sketch: HyperLogLog,
+}
+
+impl TryFrom<Vec<Value>> for Hll {
+    type Error = EvalError;
+
+    fn try_from(state: Vec<Value>) -> Result<Self, Self::Error> {
+        let bytes = sketch_state(state, "Hll")?;
+        let sketch = HyperLogLog::from_bytes(&bytes).ok_or_else(|| {
+            err_try_from_val(format!("Invalid HyperLogLog of {} bytes", bytes.len()))
+        })?;
+        Ok(Self { sketch })
+    }
+}
+
+impl Accumulator for Hll {
+    fn into_state(self) -> Vec<Value> {
+        vec![Value::from(self.sketch.to_bytes())]
+    }
+
+    /// Null values are ignored
+    fn update(
+        &mut self,
+        aggr_fn: &AggregateFunc,
+        value: Value,
+        diff: Diff,
+    ) -> Result<(), EvalError> {
+        ensure!(
+            matches!(aggr_fn, AggregateFunc::ApproxDistinct),
+            InternalSnafu {
+                reason: format!(
+                    "Hll Accumulator does not support this aggregation function: {:?}",
+                    aggr_fn
+                ),
+            }
+        );
+
+        if !value.is_null() {
+            self.sketch.insert(&value, diff);
+        }
+        Ok(())
+    }
+
+    fn eval(&self, aggr_fn: &AggregateFunc) -> Result<Value, EvalError> {
+        match aggr_fn {
+            AggregateFunc::ApproxDistinct => Ok(Value::from(self.sketch.estimate())),
+            _ => Err(InternalSnafu {
+                reason: format!(
+                    "Hll Accumulator does not support this aggregation function: {:?}",
+                    aggr_fn
+                ),
+            }
+            .build()),
+        }
+    }
+}
+
+/// Accumulates numbers in a UDDSketch for `approx_percentile_cont`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Udd {
+    sketch: UddSketch,
+}
+
+impl TryFrom<Vec<Value>> for Udd {
+    type Error = EvalError;
+
+    fn try_from(state: Vec<Value>) -> Result<Self, Self::Error> {
+        let bytes = sketch_state(state, "Udd")?;
+        let sketch = UddSketch::from_bytes(&bytes).ok_or_else(|| {
+            err_try_from_val(format!("Invalid UDDSketch of {} bytes", bytes.len()))
+        })?;
+        Ok(Self { sketch })
+    }
+}
+
+impl Accumulator for Udd {
+    fn into_state(self) -> Vec<Value> {
+        vec![Value::from(self.sketch.to_bytes())]
+    }
+
+    /// Null values and non-finite numbers are ignored
+    fn update(
+        &mut self,
+        aggr_fn: &AggregateFunc,
+        value: Value,
+        diff: Diff,
+    ) -> Result<(), EvalError> {
+        ensure!(
+            matches!(aggr_fn, AggregateFunc::ApproxPercentileCont(_)),
+            InternalSnafu {
+                reason: format!(
+                    "Udd Accumulator does not support this aggregation function: {:?}",
+                    aggr_fn
+                ),
+            }
+        );
+
+        let x = match value {
+            Value::Int8(x) => f64::from(x),
+            Value::Int16(x) => f64::from(x),
+            Value::Int32(x) => f64::from(x),
+            Value::Int64(x) => x as f64,
+            Value::UInt8(x) => f64::from(x),
+            Value::UInt16(x) => f64::from(x),
+            Value::UInt32(x) => f64::from(x),
+            Value::UInt64(x) => x as f64,
+            Value::Float32(x) => f64::from(*x),
+            Value::Float64(x) => *x,
+            Value::Null => return Ok(()), // ignore null
+            v => {
+                return Err(TypeMismatchSnafu {
+                    expected: ConcreteDataType::float64_datatype(),
+                    actual: v.data_type(),
+                }
+                .build());
+            }
+        };
+        self.sketch.insert(x, diff);
+        Ok(())
+    }
+
+    fn eval(&self, aggr_fn: &AggregateFunc) -> Result<Value, EvalError> {
+        match aggr_fn {
+            AggregateFunc::ApproxPercentileCont(percentile) => Ok(self
+                .sketch
+                .quantile(**percentile)
+                .map(Value::from)
+                .unwrap_or(Value::Null)),
+            _ => Err(InternalSnafu {
+                reason: format!(
+                    "Udd Accumulator does not support this aggregation function: {:?}",
+                    aggr_fn
+                ),
+            }
+            .build()),
+        }
+    }
+}
+
+/// Accumulates the row with the smallest or largest order key for `first_value`/`last_value`.
+///
+/// The input of each row is its order key paired with its value, see [`FirstLast::input`].
+/// Rows with a null order key are ignored, and rows with the same order key are ordered by their values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FirstLast {
+    /// The order key and the value of the boundary row.
+    row: Option<(Value, Value)>,
+}
+
+impl FirstLast {
+    /// Pair the value of a row with its order key as the input of `first_value`/`last_value`
+    pub fn input(order_key: Value, value: Value) -> Value {
+        Value::List(ListValue::new(
+            Some(Box::new(vec![order_key, value])),
+            ConcreteDataType::null_datatype(),
+        ))
+    }
+}
+
+impl TryFrom<Vec<Value>> for FirstLast {
+    type Error = EvalError;
+
+    fn try_from(state: Vec<Value>) -> Result<Self, Self::Error> {
+        ensure!(
+            state.len() == 2,
+            InternalSnafu {
+                reason: "FirstLast Accumulator state should have 2 values",
+            }
+        );
+
+        let mut iter = state.into_iter();
+        let value = iter.next().unwrap();
+        let order_key = iter.next().unwrap();
+        Ok(Self {
+            row: (!order_key.is_null()).then_some((order_key, value)),
+        })
+    }
+}
+
+impl Accumulator for FirstLast {
+    fn into_state(self) -> Vec<Value> {
+        match self.row {
+            Some((order_key, value)) => vec![value, order_key],
+            None => vec![Value::Null, Value::Null],
+        }
+    }
+
+    fn update(
+        &mut self,
+        aggr_fn: &AggregateFunc,
+        value: Value,
+        diff: Diff,
+    ) -> Result<(), EvalError> {
+        ensure!(
+            matches!(
+                aggr_fn,
+                AggregateFunc::FirstValue | AggregateFunc::LastValue
+            ),
+            InternalSnafu {
+                reason: format!(
+                    "FirstLast Accumulator does not support this aggregation function: {:?}",
+                    aggr_fn
+                ),
+            }
+        );
+        ensure!(
+            diff > 0,
+            InternalSnafu {
+                reason: "FirstLast Accumulator does not support non-monotonic input for first_value/last_value aggregation",
+            }
+        );
+
+        let items = match &value {
+            Value::List(list) => list.items().as_deref(),
+            _ => None,
+        };
+        let Some([order_key, value]) = items.map(Vec::as_slice) else {
+            return Err(TypeMismatchSnafu {
+                expected: ConcreteDataType::list_datatype(ConcreteDataType::null_datatype()),
+                actual: value.data_type(),
+            }
+            .build());
+        };
+        if order_key.is_null() {
+            return Ok(());
+        }
+
+        let row = (order_key.clone(), value.clone());
+        self.row = match self.row.take() {
+            Some(cur) if matches!(aggr_fn, AggregateFunc::FirstValue) => Some(cur.min(row)),
+            Some(cur) => Some(cur.max(row)),
+            None => Some(row),
+        };
+        Ok(())
+    }
+
+    fn eval(&self, aggr_fn: &AggregateFunc) -> Result<Value, EvalError> {
+        match aggr_fn {
+            AggregateFunc::FirstValue | AggregateFunc::LastValue => Ok(self
+                .row
+                .as_ref()
+                .map(|(_, value)| value.clone())
+                .unwrap_or(Value::Null)),
+            _ => Err(InternalSnafu {
+                reason: format!(
+                    "FirstLast Accumulator does not support this aggregation function: {:?}",
+                    aggr_fn
+                ),
+            }
+            .build()),
+        }
+    }
+}
+
 /// Accumulates values for the various types of accumulable aggregations.
 ///
 /// We assume that there are not more than 2^32 elements for the aggregation.
@@ -546,6 +807,12 @@ pub enum Accum {
     Float(Float),
     /// Accumulate Values that impl `Ord`
     OrdValue(OrdValue),
+    /// Accumulates distinct values approximately.
+    Hll(Hll),
+    /// Accumulates the distribution of numbers approximately.
+    Udd(Udd),
+    /// Accumulates the row with the smallest or largest order key.
+    FirstLast(FirstLast),
 }
 
 impl Accum {
@@ -581,6 +848,11 @@ impl Accum {
                     non_nulls: 0,
                 })
             }
+            AggregateFunc::ApproxDistinct => Self::from(Hll::default()),
+            AggregateFunc::ApproxPercentileCont(_) => Self::from(Udd::default()),
+            AggregateFunc::FirstValue | AggregateFunc::LastValue => {
+                Self::from(FirstLast::default())
+            }
             f => {
                 return Err(InternalSnafu {
                     reason: format!(
@@ -593,6 +865,54 @@ impl Accum {
         })
     }
 
+    /// Merges `other`, the accumulator of another part of the same group, into `self`,
+    /// as if the values accumulated by `other` were accumulated by `self`.
+    pub fn merge(&mut self, aggr_fn: &AggregateFunc, other: Accum) -> Result<(), EvalError> {
+        match (self, other) {
+            (Accum::Bool(zelf), Accum::Bool(other)) => {
+                zelf.trues += other.trues;
+                zelf.falses += other.falses;
+            }
+            (Accum::SimpleNumber(zelf), Accum::SimpleNumber(other)) => {
+                zelf.accum += other.accum;
+                zelf.non_nulls += other.non_nulls;
+            }
+            (Accum::Float(zelf), Accum::Float(other)) => {
+                zelf.accum += *other.accum;
+                zelf.pos_infs += other.pos_infs;
+                zelf.neg_infs += other.neg_infs;
+                zelf.nans += other.nans;
+                zelf.non_nulls += other.non_nulls;
+            }
+            (Accum::OrdValue(zelf), Accum::OrdValue(other)) => {
+                zelf.val = match (zelf.val.take(), other.val) {
+                    (Some(l), Some(r)) if aggr_fn.is_max() => Some(l.max(r)),
+                    (Some(l), Some(r)) => Some(l.min(r)),
+                    (l, r) => l.or(r),
+                };
+                zelf.non_nulls += other.non_nulls;
+            }
+            (Accum::Hll(zelf), Accum::Hll(other)) => zelf.sketch.merge(&other.sketch),
+            (Accum::Udd(zelf), Accum::Udd(other)) => zelf.sketch.merge(&other.sketch),
+            (Accum::FirstLast(zelf), Accum::FirstLast(other)) => {
+                zelf.row = match (zelf.row.take(), other.row) {
+                    (Some(l), Some(r)) if matches!(aggr_fn, AggregateFunc::FirstValue) => {
+                        Some(l.min(r))
+                    }
+                    (Some(l), Some(r)) => Some(l.max(r)),
+                    (l, r) => l.or(r),
+                };
+            }
+            (zelf, other) => {
+                return Err(InternalSnafu {
+                    reason: format!("Can't merge accumulator {:?} into {:?}", other, zelf),
+                }
+                .build());
+            }
+        }
+        Ok(())
+    }
+
     /// try to convert a vector of value into given aggregate function's accumulator
     pub fn try_into_accum(aggr_fn: &AggregateFunc, state: Vec<Value>) -> Result<Self, EvalError> {
         match aggr_fn {
@@ -612,6 +932,11 @@ impl Accum {
             f if f.is_max() || f.is_min() || matches!(f, AggregateFunc::Count) => {
                 Ok(Self::from(OrdValue::try_from(state)?))
             }
+            AggregateFunc::ApproxDistinct => Ok(Self::from(Hll::try_from(state)?)),
+            AggregateFunc::ApproxPercentileCont(_) => Ok(Self::from(Udd::try_from(state)?)),
+            AggregateFunc::FirstValue | AggregateFunc::LastValue => {
+                Ok(Self::from(FirstLast::try_from(state)?))
+            }
             f => Err(InternalSnafu {
                 reason: format!(
                     "Accumulator does not support this aggregation function: {:?}",
@@ -623,6 +948,24 @@ impl Accum {
     }
 }
 
+/// Unpacks the bytes of a sketch from the state of the accumulator `name`.
+fn sketch_state(state: Vec<Value>, name: &str) -> Result<Vec<u8>, EvalError> {
+    ensure!(
+        state.len() == 1,
+        InternalSnafu {
+            reason: format!("{name} Accumulator state should have 1 value"),
+        }
+    );
+    match state.into_iter().next().unwrap() {
+        Value::Binary(bytes) => Ok(bytes.to_vec()),
+        v => Err(TypeMismatchSnafu {
+            expected: ConcreteDataType::binary_datatype(),
+            actual: v.data_type(),
+        }
+        .build()),
+    }
+}
+
 fn err_try_from_val<T: Display>(reason: T) -> EvalError {
     TryFromValueSnafu {
         msg: reason.to_string(),
@@ -807,6 +1150,153 @@ mod test {
             }
         }
     }
+    #[test]
+    fn test_sketch_accum() {
+        let aggr_fn = AggregateFunc::ApproxDistinct;
+        let mut acc = Accum::new_accum(&aggr_fn).unwrap();
+        acc.update_batch(
+            &aggr_fn,
+            vec![
+                (Value::Int32(1), 1),
+                (Value::Int32(1), 1),
+                (Value::Int32(2), 1),
+                (Value::Null, 1),
+            ],
+        )
+        .unwrap();
+        let mut acc = Accum::try_into_accum(&aggr_fn, acc.into_state()).unwrap();
+        assert_eq!(acc.eval(&aggr_fn).unwrap(), Value::UInt64(2));
+        // the value is counted until all of its duplicates are retracted
+        acc.update(&aggr_fn, Value::Int32(1), -1).unwrap();
+        assert_eq!(acc.eval(&aggr_fn).unwrap(), Value::UInt64(2));
+        acc.update(&aggr_fn, Value::Int32(1), -1).unwrap();
+        assert_eq!(acc.eval(&aggr_fn).unwrap(), Value::UInt64(1));
+        assert!(matches!(
+            Accum::try_into_accum(&aggr_fn, vec![Value::from(vec![0u8; 3])]),
+            Err(EvalError::TryFromValue { .. })
+        ));
+
+        let aggr_fn = AggregateFunc::ApproxPercentileCont(OrderedF64::from(0.5));
+        let mut acc = Accum::new_accum(&aggr_fn).unwrap();
+        assert_eq!(acc.eval(&aggr_fn).unwrap(), Value::Null);
+        acc.update_batch(
+            &aggr_fn,
+            vec![
+                (Value::Int64(1), 1),
+                (Value::Float64(OrderedF64::from(2.0)), 1),
+                (Value::UInt8(3), 1),
+                (Value::Int64(100), 1),
+                (Value::Null, 1),
+            ],
+        )
+        .unwrap();
+        // deletes the outlier
+        acc.update(&aggr_fn, Value::Int64(100), -1).unwrap();
+        let mut acc = Accum::try_into_accum(&aggr_fn, acc.into_state()).unwrap();
+        let Value::Float64(median) = acc.eval(&aggr_fn).unwrap() else {
+            panic!("approx_percentile_cont should return float64");
+        };
+        assert!((median.0 - 2.0).abs() < 0.01);
+        assert!(matches!(
+            acc.update(&aggr_fn, Value::from("a"), 1),
+            Err(EvalError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            acc.update(&AggregateFunc::ApproxDistinct, Value::Int64(1), 1),
+            Err(EvalError::Internal { .. })
+        ));
+    }
+
+    #[test]
+    fn test_merge_accum() {
+        let testcases = [
+            (
+                AggregateFunc::SumInt64,
+                vec![(Value::Int64(1), 1), (Value::Int64(2), 1)],
+                vec![(Value::Int64(3), 1), (Value::Int64(1), -1)],
+                Value::Int64(5),
+            ),
+            (
+                AggregateFunc::MaxInt64,
+                vec![(Value::Int64(1), 1), (Value::Int64(4), 1)],
+                vec![(Value::Int64(3), 1)],
+                Value::Int64(4),
+            ),
+            (
+                AggregateFunc::ApproxDistinct,
+                vec![(Value::Int64(1), 1), (Value::Int64(2), 1)],
+                vec![(Value::Int64(2), 1), (Value::Int64(3), 1)],
+                Value::UInt64(3),
+            ),
+            (
+                AggregateFunc::ApproxPercentileCont(OrderedF64::from(1.0)),
+                vec![(Value::Int64(1), 1), (Value::Int64(2), 1)],
+                vec![(Value::Int64(3), 1), (Value::Int64(2), -1)],
+                Value::Float64(OrderedF64::from(3.0)),
+            ),
+            (
+                AggregateFunc::LastValue,
+                vec![(FirstLast::input(Value::Int64(2), Value::from("b")), 1)],
+                vec![(FirstLast::input(Value::Int64(1), Value::from("a")), 1)],
+                Value::from("b"),
+            ),
+        ];
+        // percentiles are estimated with relative errors
+        let round = |v: Value| match v {
+            Value::Float64(v) => Value::Float64(OrderedF64::from((v.0 * 100.0).round() / 100.0)),
+            v => v,
+        };
+        for (aggr_fn, left, right, expected) in testcases {
+            let states = [left, right].map(|input| {
+                let mut acc = Accum::new_accum(&aggr_fn).unwrap();
+                acc.update_batch(&aggr_fn, input).unwrap();
+                acc.into_state()
+            });
+            let (res, state) = aggr_fn.merge_accumulable(states).unwrap();
+            assert_eq!(expected, round(res), "{aggr_fn:?}");
+            // the merged state is the state of the whole group
+            let (res, _) = aggr_fn.eval_diff_accumulable(state, vec![]).unwrap();
+            assert_eq!(expected, round(res), "{aggr_fn:?}");
+        }
+
+        let mut acc = Accum::new_accum(&AggregateFunc::ApproxDistinct).unwrap();
+        assert!(matches!(
+            acc.merge(
+                &AggregateFunc::ApproxDistinct,
+                Accum::new_accum(&AggregateFunc::SumInt64).unwrap()
+            ),
+            Err(EvalError::Internal { .. })
+        ));
+    }
+
+    #[test]
+    fn test_first_last_accum() {
+        let rows = vec![
+            (FirstLast::input(Value::Int64(2), Value::from("b")), 1),
+            (FirstLast::input(Value::Int64(1), Value::from("a")), 1),
+            (FirstLast::input(Value::Null, Value::from("null")), 1),
+            (FirstLast::input(Value::Int64(3), Value::Null), 1),
+        ];
+        for (aggr_fn, expected) in [
+            (AggregateFunc::FirstValue, Value::from("a")),
+            (AggregateFunc::LastValue, Value::Null),
+        ] {
+            let mut acc = Accum::new_accum(&aggr_fn).unwrap();
+            assert_eq!(acc.eval(&aggr_fn).unwrap(), Value::Null);
+            acc.update_batch(&aggr_fn, rows.clone()).unwrap();
+            let mut acc = Accum::try_into_accum(&aggr_fn, acc.into_state()).unwrap();
+            assert_eq!(acc.eval(&aggr_fn).unwrap(), expected);
+            assert!(matches!(
+                acc.update(&aggr_fn, rows[0].0.clone(), -1),
+                Err(EvalError::Internal { .. })
+            ));
+            assert!(matches!(
+                acc.update(&aggr_fn, Value::Int64(1), 1),
+                Err(EvalError::TypeMismatch { .. })
+            ));
+        }
+    }
+
     #[test]
     fn test_fail_path_accum() {
         {
@@ -41,6 +41,12 @@ use crate::repr::Diff;
 /// `count()->i64`
 ///
 /// `min/max(T)->T`
+///
+/// `approx_distinct(T)->u64`
+///
+/// `approx_percentile_cont(f64, percentile)->f64`
+///
+/// `first_value/last_value(T order by U)->T`
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Hash, EnumIter)]
 pub enum AggregateFunc {
     MaxInt16,
@@ -89,6 +95,15 @@ pub enum AggregateFunc {
     Count,
     Any,
     All,
+
+    /// Estimates the number of distinct values with HyperLogLog.
+    ApproxDistinct,
+    /// Estimates the percentile in `[0, 1]` with UDDSketch.
+    ApproxPercentileCont(OrderedF64),
+    /// The value of the row with the smallest order key.
+    FirstValue,
+    /// The value of the row with the largest order key.
+    LastValue,
 }
 
 impl AggregateFunc {
@@ -129,6 +144,26 @@ impl AggregateFunc {
         let res = accum.eval(self)?;
         Ok((res, accum.into_state()))
     }
+
+    /// Merge the accumulator states of parts of a group into the state of the group,
+    /// e.g. the states of each minute into the state of the hour.
+    ///
+    /// Expect self to be accumulable aggregate function, returns the result and the merged state.
+    ///
+    /// `Plan::Reduce` isn't rendered yet, so no operator rolls states up with this for now.
+    pub fn merge_accumulable<I>(&self, states: I) -> Result<(Value, Vec<Value>), EvalError>
+    where
+        I: IntoIterator<Item = Vec<Value>>,
+    {
+        let mut accum = Accum::new_accum(self)?;
+        for state in states {
+            if !state.is_empty() {
+                accum.merge(self, Accum::try_into_accum(self, state)?)?;
+            }
+        }
+        let res = accum.eval(self)?;
+        Ok((res, accum.into_state()))
+    }
 }
 
 /// Generate signature for each aggregate function
@@ -196,6 +231,10 @@ impl AggregateFunc {
             DfAggrFunc::Count => GenericFn::Count,
             DfAggrFunc::BoolOr => GenericFn::Any,
             DfAggrFunc::BoolAnd => GenericFn::All,
+            DfAggrFunc::ApproxDistinct => GenericFn::ApproxDistinct,
+            DfAggrFunc::ApproxPercentileCont => GenericFn::ApproxPercentile,
+            DfAggrFunc::FirstValue => GenericFn::FirstValue,
+            DfAggrFunc::LastValue => GenericFn::LastValue,
             _ => {
                 return InvalidQuerySnafu {
                     reason: format!("Unknown aggregate function: {}", name),
@@ -203,7 +242,17 @@ impl AggregateFunc {
                 .fail();
             }
         };
-        let input_type = arg_type.unwrap_or_else(ConcreteDataType::null_datatype);
+        let input_type = match (generic_fn, arg_type) {
+            // distinct values of any type can be counted
+            (GenericFn::ApproxDistinct, _) => ConcreteDataType::null_datatype(),
+            // the boundary value is kept as is, whatever its type
+            (GenericFn::FirstValue | GenericFn::LastValue, _) => ConcreteDataType::null_datatype(),
+            // numbers are estimated as float64
+            (GenericFn::ApproxPercentile, Some(t)) if t.is_numeric() => {
+                ConcreteDataType::float64_datatype()
+            }
+            (_, arg_type) => arg_type.unwrap_or_else(ConcreteDataType::null_datatype),
+        };
         rule.get(&(generic_fn, input_type.clone()))
             .cloned()
             .with_context(|| InvalidQuerySnafu {
@@ -215,13 +264,34 @@ impl AggregateFunc {
     }
 
     /// all concrete datatypes with precision types will be returned with largest possible variant
-    /// as a exception, count have a signature of `null -> i64`, but it's actually `anytype -> i64`
+    /// as a exception, count have a signature of `null -> i64`, but it's actually `anytype -> i64`,
+    /// and `first_value`/`last_value` have a signature of `null -> null`, but it's actually `T -> T`
     pub fn signature(&self) -> Signature {
         generate_signature!(self, {
             AggregateFunc::Count => Signature {
                 input: smallvec![ConcreteDataType::null_datatype()],
                 output: ConcreteDataType::int64_datatype(),
                 generic_fn: GenericFn::Count,
+            },
+            AggregateFunc::ApproxDistinct => Signature {
+                input: smallvec![ConcreteDataType::null_datatype()],
+                output: ConcreteDataType::uint64_datatype(),
+                generic_fn: GenericFn::ApproxDistinct,
+            },
+            AggregateFunc::ApproxPercentileCont(_) => Signature {
+                input: smallvec![ConcreteDataType::float64_datatype()],
+                output: ConcreteDataType::float64_datatype(),
+                generic_fn: GenericFn::ApproxPercentile,
+            },
+            AggregateFunc::FirstValue => Signature {
+                input: smallvec![ConcreteDataType::null_datatype()],
+                output: ConcreteDataType::null_datatype(),
+                generic_fn: GenericFn::FirstValue,
+            },
+            AggregateFunc::LastValue => Signature {
+                input: smallvec![ConcreteDataType::null_datatype()],
+                output: ConcreteDataType::null_datatype(),
+                generic_fn: GenericFn::LastValue,
             }
         },[
             MaxInt16 => (int16_datatype, Max),
@@ -0,0 +1,488 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mergeable sketches backing approximate aggregate functions.
+//!
+//! Both sketches can be merged with sketches of other partial groups, so the state of
+//! a group can be computed from the states of its parts, and both count values with a
+//! diff, so values can be removed by counting them with a negative diff.
+//!
+//! Sketches are encoded as LEB128 varints, signed integers are zigzag encoded first:
+//! - [HyperLogLog]: `register`, `rank` and `count` of each non-empty count.
+//! - [UddSketch]: `collapses`, `zeros` and the number of positive buckets, followed by
+//!   `key` and `count` of each positive bucket, then of each negative bucket.
+
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use crate::repr::Diff;
+
+/// Number of bits of the hash used to pick a register.
+const HLL_PRECISION: u32 = 12;
+/// Number of registers, the relative error of the estimation is about `1.04 / sqrt(HLL_REGISTERS)`.
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+/// HyperLogLog sketch estimating the number of distinct values inserted.
+///
+/// Instead of the max rank of each register, the sketch counts the values of each rank
+/// in each register, and the register is the max rank with a positive count. So values
+/// can be removed as long as they were inserted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    /// Counts of values by their registers and ranks.
+    counts: BTreeMap<(u16, u8), Diff>,
+}
+
+impl HyperLogLog {
+    /// Restores a sketch from the bytes returned by [HyperLogLog::to_bytes],
+    /// returns `None` if the bytes aren't a sketch.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        let mut counts = BTreeMap::new();
+        while !bytes.is_empty() {
+            let register = u16::try_from(get_varint(&mut bytes)?).ok()?;
+            let rank = u8::try_from(get_varint(&mut bytes)?).ok()?;
+            let count = get_signed(&mut bytes)?;
+            if usize::from(register) >= HLL_REGISTERS || count == 0 {
+                return None;
+            }
+            let _ = counts.insert((register, rank), count);
+        }
+        Some(Self { counts })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for ((register, rank), count) in &self.counts {
+            put_varint(&mut bytes, u64::from(*register));
+            put_varint(&mut bytes, u64::from(*rank));
+            put_signed(&mut bytes, *count);
+        }
+        bytes
+    }
+
+    /// Counts `value` by `diff`.
+    pub fn insert<T: Hash>(&mut self, value: &T, diff: Diff) {
+        let mut hasher = StableHasher::default();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let register = (hash >> (64 - HLL_PRECISION)) as u16;
+        // The sentinel bit bounds the rank when the remaining bits are all zeros.
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        add_count(&mut self.counts, (register, rank), diff);
+    }
+
+    /// Merges `other` into `self`, as if the values inserted into `other` were inserted into `self`.
+    pub fn merge(&mut self, other: &Self) {
+        for (key, count) in &other.counts {
+            add_count(&mut self.counts, *key, *count);
+        }
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let mut registers = vec![0u8; HLL_REGISTERS];
+        for ((register, rank), count) in &self.counts {
+            if *count > 0 {
+                let register = &mut registers[usize::from(*register)];
+                *register = (*register).max(*rank);
+            }
+        }
+
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = registers
+            .iter()
+            .map(|register| 2f64.powi(-i32::from(*register)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = registers.iter().filter(|r| **r == 0).count();
+        // Linear counting is more accurate for small cardinalities.
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+/// Relative error of a [UddSketch] that was never collapsed.
+const UDD_INITIAL_ALPHA: f64 = 0.001;
+/// Maximum number of buckets of a [UddSketch], more buckets collapse the sketch.
+const UDD_MAX_BUCKETS: usize = 200;
+
+/// UDDSketch estimating quantiles of inserted values with bounded relative error.
+///
+/// A value `x` is counted in the bucket `ceil(log_gamma(|x|))`. When there are too many buckets,
+/// every two adjacent buckets are collapsed into one by squaring `gamma`, which degrades the
+/// relative error. Counting a value with negative diff removes it, since the bucket of a value
+/// after collapsing is the collapsed bucket it was counted in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UddSketch {
+    /// Times the sketch has been collapsed.
+    collapses: u32,
+    /// Counts of zeros.
+    zeros: Diff,
+    /// Counts of positive values by bucket.
+    positives: BTreeMap<i32, Diff>,
+    /// Counts of negative values by bucket of their absolute values.
+    negatives: BTreeMap<i32, Diff>,
+}
+
+impl UddSketch {
+    /// Restores a sketch from the bytes returned by [UddSketch::to_bytes],
+    /// returns `None` if the bytes aren't a sketch.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        let collapses = u32::try_from(get_varint(&mut bytes)?).ok()?;
+        let zeros = get_signed(&mut bytes)?;
+        let num_positives = get_varint(&mut bytes)?;
+        let read_bucket = |bytes: &mut &[u8]| -> Option<(i32, Diff)> {
+            let key = i32::try_from(get_signed(bytes)?).ok()?;
+            let count = get_signed(bytes)?;
+            (count != 0).then_some((key, count))
+        };
+        let mut positives = BTreeMap::new();
+        for _ in 0..num_positives {
+            let (key, count) = read_bucket(&mut bytes)?;
+            let _ = positives.insert(key, count);
+        }
+        let mut negatives = BTreeMap::new();
+        while !bytes.is_empty() {
+            let (key, count) = read_bucket(&mut bytes)?;
+            let _ = negatives.insert(key, count);
+        }
+
+        Some(Self {
+            collapses,
+            zeros,
+            positives,
+            negatives,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        put_varint(&mut bytes, u64::from(self.collapses));
+        put_signed(&mut bytes, self.zeros);
+        put_varint(&mut bytes, self.positives.len() as u64);
+        for (key, count) in self.positives.iter().chain(&self.negatives) {
+            put_signed(&mut bytes, i64::from(*key));
+            put_signed(&mut bytes, *count);
+        }
+        bytes
+    }
+
+    /// Counts `value` by `diff`, non-finite values are ignored.
+    pub fn insert(&mut self, value: f64, diff: Diff) {
+        if !value.is_finite() {
+            return;
+        }
+        if value == 0.0 {
+            self.zeros += diff;
+            return;
+        }
+
+        let key = self.key(value.abs());
+        let buckets = if value > 0.0 {
+            &mut self.positives
+        } else {
+            &mut self.negatives
+        };
+        add_count(buckets, key, diff);
+        self.collapse_to_fit();
+    }
+
+    /// Merges `other` into `self`, as if the values inserted into `other` were inserted into `self`.
+    pub fn merge(&mut self, other: &Self) {
+        let mut other = other.clone();
+        while self.collapses < other.collapses {
+            self.collapse();
+        }
+        while other.collapses < self.collapses {
+            other.collapse();
+        }
+
+        self.zeros += other.zeros;
+        for (key, count) in other.positives {
+            add_count(&mut self.positives, key, count);
+        }
+        for (key, count) in other.negatives {
+            add_count(&mut self.negatives, key, count);
+        }
+        self.collapse_to_fit();
+    }
+
+    pub fn count(&self) -> Diff {
+        self.zeros + self.positives.values().sum::<Diff>() + self.negatives.values().sum::<Diff>()
+    }
+
+    /// Returns the estimated value at `quantile` in `[0, 1]`, or `None` if the sketch is empty.
+    pub fn quantile(&self, quantile: f64) -> Option<f64> {
+        let count = self.count();
+        if count <= 0 {
+            return None;
+        }
+        let rank = (quantile.clamp(0.0, 1.0) * (count - 1) as f64).round() as Diff;
+
+        // Buckets in ascending order of their values.
+        let buckets = self
+            .negatives
+            .iter()
+            .rev()
+            .map(|(key, count)| (-self.value(*key), *count))
+            .chain(std::iter::once((0.0, self.zeros)))
+            .chain(
+                self.positives
+                    .iter()
+                    .map(|(key, count)| (self.value(*key), *count)),
+            );
+        let mut seen = 0;
+        let mut last = None;
+        for (value, count) in buckets {
+            if count <= 0 {
+                continue;
+            }
+            seen += count;
+            last = Some(value);
+            if seen > rank {
+                break;
+            }
+        }
+        last
+    }
+
+    fn gamma(&self) -> f64 {
+        let gamma = (1.0 + UDD_INITIAL_ALPHA) / (1.0 - UDD_INITIAL_ALPHA);
+        gamma.powf(2f64.powi(self.collapses as i32))
+    }
+
+    fn key(&self, value: f64) -> i32 {
+        // Collapses the bucket in the uncollapsed sketch instead of computing the key with
+        // the current `gamma`, so rounding errors can't put the value in a different bucket.
+        let gamma = (1.0 + UDD_INITIAL_ALPHA) / (1.0 - UDD_INITIAL_ALPHA);
+        let key = (value.ln() / gamma.ln()).ceil() as i32;
+        (0..self.collapses).fold(key, |key, _| collapsed_key(key))
+    }
+
+    /// Returns the value representing the bucket `key` with the least relative error.
+    fn value(&self, key: i32) -> f64 {
+        let gamma = self.gamma();
+        2.0 * gamma.powi(key) / (gamma + 1.0)
+    }
+
+    fn collapse_to_fit(&mut self) {
+        while self.positives.len() + self.negatives.len() > UDD_MAX_BUCKETS {
+            self.collapse();
+        }
+    }
+
+    fn collapse(&mut self) {
+        for buckets in [&mut self.positives, &mut self.negatives] {
+            let mut collapsed = BTreeMap::new();
+            for (key, count) in std::mem::take(buckets) {
+                add_count(&mut collapsed, collapsed_key(key), count);
+            }
+            *buckets = collapsed;
+        }
+        self.collapses += 1;
+    }
+}
+
+/// Returns the key of the bucket `key` is collapsed into, i.e. `ceil(key / 2)`.
+fn collapsed_key(key: i32) -> i32 {
+    (key + 1).div_euclid(2)
+}
+
+/// Adds `diff` to the count of `key`, dropping the bucket if it becomes empty.
+fn add_count<K: Ord + Copy>(buckets: &mut BTreeMap<K, Diff>, key: K, diff: Diff) {
+    let count = buckets.entry(key).or_default();
+    *count += diff;
+    if *count == 0 {
+        let _ = buckets.remove(&key);
+    }
+}
+
+fn put_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn put_signed(bytes: &mut Vec<u8>, value: i64) {
+    put_varint(bytes, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+/// Reads a varint from the front of `bytes`, returns `None` if `bytes` ends early or the
+/// varint is longer than 10 bytes.
+fn get_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn get_signed(bytes: &mut &[u8]) -> Option<i64> {
+    let value = get_varint(bytes)?;
+    Some((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+/// FNV-1a hasher, whose hashes don't change between runs unlike the std `DefaultHasher`.
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        // Mixes the bits with the finalizer of splitmix64 since HyperLogLog relies on the
+        // distribution of the leading bits.
+        let mut hash = self.0;
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^ (hash >> 31)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hyper_log_log() {
+        let mut left = HyperLogLog::default();
+        let mut right = HyperLogLog::default();
+        for i in 0..10000 {
+            left.insert(&i, 1);
+            // duplicates are only counted once
+            left.insert(&i, 1);
+            right.insert(&(i + 5000), 1);
+        }
+        let error = |estimate: u64, expected: f64| (estimate as f64 - expected).abs() / expected;
+        assert!(error(left.estimate(), 10000.0) < 0.05);
+
+        left.merge(&right);
+        assert!(error(left.estimate(), 15000.0) < 0.05);
+
+        let restored = HyperLogLog::from_bytes(&left.to_bytes()).unwrap();
+        assert_eq!(left, restored);
+        assert!(HyperLogLog::from_bytes(&[0x80]).is_none());
+        // the register is out of range
+        assert!(HyperLogLog::from_bytes(&[0x80, 0x80, 0x01, 1, 2]).is_none());
+        assert_eq!(HyperLogLog::default().estimate(), 0);
+        assert!(HyperLogLog::from_bytes(&[]).unwrap().counts.is_empty());
+
+        // values are removed after their duplicates are removed
+        for i in 0..10000 {
+            left.insert(&i, -1);
+        }
+        assert!(error(left.estimate(), 15000.0) < 0.05);
+        for i in 0..10000 {
+            left.insert(&i, -1);
+        }
+        assert!(error(left.estimate(), 10000.0) < 0.05);
+        for i in 5000..15000 {
+            left.insert(&i, -1);
+        }
+        assert_eq!(left, HyperLogLog::default());
+    }
+
+    #[test]
+    fn test_udd_sketch() {
+        let mut sketch = UddSketch::default();
+        assert_eq!(sketch.quantile(0.5), None);
+        for i in 1..=1000 {
+            sketch.insert(i as f64, 1);
+        }
+        let error = |estimate: f64, expected: f64| (estimate - expected).abs() / expected.abs();
+        assert!(error(sketch.quantile(0.5).unwrap(), 500.0) < 0.05);
+        assert!(error(sketch.quantile(0.99).unwrap(), 990.0) < 0.05);
+
+        // values can be removed
+        for i in 501..=1000 {
+            sketch.insert(i as f64, -1);
+        }
+        assert_eq!(sketch.count(), 500);
+        assert!(error(sketch.quantile(1.0).unwrap(), 500.0) < 0.05);
+
+        let mut negatives = UddSketch::default();
+        negatives.insert(0.0, 1);
+        for i in 1..=500 {
+            negatives.insert(-i as f64, 1);
+        }
+        negatives.insert(f64::NAN, 1);
+        sketch.merge(&negatives);
+        assert_eq!(sketch.count(), 1001);
+        let restored = UddSketch::from_bytes(&sketch.to_bytes()).unwrap();
+        assert_eq!(sketch, restored);
+        assert_eq!(
+            UddSketch::default(),
+            UddSketch::from_bytes(&UddSketch::default().to_bytes()).unwrap()
+        );
+        assert!(UddSketch::from_bytes(&[]).is_none());
+        // one positive bucket is missing
+        assert!(UddSketch::from_bytes(&[0, 0, 1]).is_none());
+        assert_eq!(sketch.quantile(0.5), Some(0.0));
+        assert!(error(sketch.quantile(0.0).unwrap(), -500.0) < 0.05);
+    }
+
+    #[test]
+    fn test_udd_sketch_collapse() {
+        let mut sketch = UddSketch::default();
+        for i in 0..100000 {
+            sketch.insert(1.0001f64.powi(i * 10), 1);
+        }
+        assert!(sketch.collapses > 0);
+        assert!(sketch.positives.len() <= UDD_MAX_BUCKETS);
+        assert_eq!(sketch.count(), 100000);
+
+        let mut other = UddSketch::default();
+        other.insert(1.0, 1);
+        other.merge(&sketch);
+        assert_eq!(other.collapses, sketch.collapses);
+        assert_eq!(other.count(), 100001);
+
+        // removing values after collapsing empties the sketch
+        for i in 0..100000 {
+            sketch.insert(1.0001f64.powi(i * 10), -1);
+        }
+        assert_eq!(
+            sketch,
+            UddSketch {
+                collapses: sketch.collapses,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -519,4 +519,26 @@ mod test {
         let res = expr.permute_map(&permute_map);
         assert!(matches!(res, Err(Error::InvalidQuery { .. })));
     }
+
+    #[test]
+    fn test_date_trunc_timezone() {
+        let ts = |millis: i64| Value::DateTime(common_time::DateTime::new(millis));
+        // 2024-01-01T20:00:00Z, which is 2024-01-02T04:00:00 in Shanghai
+        let input = [ts(1704139200000)];
+
+        let utc = ScalarExpr::Column(0).call_unary(UnaryFunc::date_trunc("day", None).unwrap());
+        assert_eq!(utc.eval(&input).unwrap(), ts(1704067200000));
+
+        let local = ScalarExpr::Column(0)
+            .call_unary(UnaryFunc::date_trunc("day", Some("Asia/Shanghai")).unwrap());
+        assert_eq!(local.eval(&input).unwrap(), ts(1704124800000));
+
+        // The local midnight of 2018-11-04 is skipped in Sao Paulo, the day starts at 01:00
+        let dst = ScalarExpr::Column(0)
+            .call_unary(UnaryFunc::date_trunc("day", Some("America/Sao_Paulo")).unwrap());
+        assert_eq!(dst.eval(&[ts(1541332800000)]).unwrap(), ts(1541300400000));
+
+        assert!(UnaryFunc::date_trunc("fortnight", None).is_err());
+        assert!(UnaryFunc::date_trunc("day", Some("Mars/Olympus")).is_err());
+    }
 }
@@ -39,6 +39,10 @@ pub enum GenericFn {
     Count,
     Any,
     All,
+    ApproxDistinct,
+    ApproxPercentile,
+    FirstValue,
+    LastValue,
     // unary func
     Not,
     IsNull,
@@ -46,6 +50,7 @@ pub enum GenericFn {
     IsFalse,
     StepTimestamp,
     Cast,
+    DateTrunc,
     // binary func
     Eq,
     NotEq,
@@ -17,11 +17,14 @@
 
 mod join;
 mod reduce;
+mod window;
 
 use datatypes::arrow::ipc::Map;
+use datatypes::data_type::ConcreteDataType;
 use serde::{Deserialize, Serialize};
 
-pub(crate) use self::reduce::{AccumulablePlan, KeyValPlan, ReducePlan};
+pub(crate) use self::reduce::{AccumulablePlan, AggrWithIndex, KeyValPlan, ReducePlan};
+pub(crate) use self::window::{hop_windows, WindowKind, WindowPlan};
 use crate::adapter::error::Error;
 use crate::expr::{
     AggregateExpr, EvalError, Id, LocalId, MapFilterProject, SafeMfpPlan, ScalarExpr, TypedExpr,
@@ -110,6 +113,22 @@ impl TypedPlan {
             plan,
         })
     }
+
+    /// Assign rows of the plan to windows, appending `window_start` and `window_end` columns
+    pub fn window(self, window: WindowPlan) -> Self {
+        let mut typ = self.typ;
+        typ.column_types.extend([
+            ColumnType::new(ConcreteDataType::timestamp_millisecond_datatype(), false),
+            ColumnType::new(ConcreteDataType::timestamp_millisecond_datatype(), false),
+        ]);
+        TypedPlan {
+            typ,
+            plan: Plan::Window {
+                input: Box::new(self.plan),
+                window,
+            },
+        }
+    }
 }
 
 /// TODO(discord9): support `TableFunc`（by define FlatMap that map 1 to n)
@@ -181,4 +200,12 @@ pub enum Plan {
         /// Whether to consolidate the output, e.g., cancel negated records.
         consolidate_output: bool,
     },
+    /// Assign each row of the input to `hop` or `session` windows, appending
+    /// `window_start` and `window_end` columns to it
+    Window {
+        /// The input collection.
+        input: Box<Plan>,
+        /// How to assign windows
+        window: WindowPlan,
+    },
 }
@@ -12,9 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use datatypes::value::Value;
 use serde::{Deserialize, Serialize};
+use snafu::OptionExt;
 
-use crate::expr::{AggregateExpr, Id, LocalId, MapFilterProject, SafeMfpPlan, ScalarExpr};
+use crate::expr::error::InternalSnafu;
+use crate::expr::{
+    AggregateExpr, EvalError, FirstLast, Id, LocalId, MapFilterProject, SafeMfpPlan, ScalarExpr,
+};
+use crate::repr::Row;
 
 /// Describe how to extract key-value pair from a `Row`
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
@@ -44,10 +50,48 @@ pub struct AccumulablePlan {
     /// in order.
     pub full_aggrs: Vec<AggregateExpr>,
     /// All of the non-distinct accumulable aggregates.
-    /// Each element represents:
-    /// (index of aggr output, index of value among inputs, aggr expr)
     /// These will all be rendered together in one dataflow fragment.
-    pub simple_aggrs: Vec<(usize, usize, AggregateExpr)>,
+    pub simple_aggrs: Vec<AggrWithIndex>,
     /// Same as above but for all of the `DISTINCT` accumulable aggregations.
-    pub distinct_aggrs: Vec<(usize, usize, AggregateExpr)>,
+    pub distinct_aggrs: Vec<AggrWithIndex>,
+}
+
+/// An aggregation with the indices of its input and output.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+pub struct AggrWithIndex {
+    /// aggregation expression
+    pub expr: AggregateExpr,
+    /// index of aggr input among input row
+    pub input_idx: usize,
+    /// index of the order key among input row, only for `first_value`/`last_value`
+    pub order_key_idx: Option<usize>,
+    /// index of aggr output among output row
+    pub output_idx: usize,
+}
+
+impl AggrWithIndex {
+    /// Create a new `AggrWithIndex` without order key
+    pub fn new(expr: AggregateExpr, input_idx: usize, output_idx: usize) -> Self {
+        Self {
+            expr,
+            input_idx,
+            order_key_idx: None,
+            output_idx,
+        }
+    }
+
+    /// Get the value to feed the accumulator of the aggregation from an input row,
+    /// which is the value paired with its order key if the aggregation has one.
+    pub fn input_of(&self, row: &Row) -> Result<Value, EvalError> {
+        let get = |idx: usize| {
+            row.get(idx).cloned().with_context(|| InternalSnafu {
+                reason: format!("Expect column {} in input row of length {}", idx, row.len()),
+            })
+        };
+        let value = get(self.input_idx)?;
+        match self.order_key_idx {
+            Some(idx) => Ok(FirstLast::input(get(idx)?, value)),
+            None => Ok(value),
+        }
+    }
 }
@@ -0,0 +1,115 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Plan for assigning rows to `hop` and `session` windows
+
+use serde::{Deserialize, Serialize};
+
+use crate::expr::error::InvalidArgumentSnafu;
+use crate::expr::EvalError;
+use crate::repr::{Duration, Timestamp};
+
+/// Assign each row to the time windows it belongs to.
+///
+/// The output row is the input row with `window_start` and `window_end` appended,
+/// window is the half-open range `[window_start, window_end)`
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+pub struct WindowPlan {
+    /// column of the event timestamp of input row
+    pub ts_col: usize,
+    /// how to assign windows
+    pub window: WindowKind,
+}
+
+/// Kind of window, see [`WindowPlan`]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
+pub enum WindowKind {
+    /// `hop(ts, slide, size)`, windows of length `size` start every `slide`,
+    /// so one row belongs to about `size / slide` windows
+    Hop { slide: Duration, size: Duration },
+    /// `session(ts, gap)`, rows of the same key belong to one window until
+    /// there is no row for `gap`, windows are merged when a row fills the gap between them
+    Session {
+        /// columns identifying which session a row belongs to, i.e. other group by columns
+        key_cols: Vec<usize>,
+        gap: Duration,
+    },
+}
+
+impl WindowKind {
+    /// create a hop window, both `slide` and `size` must be positive
+    pub fn hop(slide: Duration, size: Duration) -> Result<Self, EvalError> {
+        if slide <= 0 || size <= 0 {
+            return InvalidArgumentSnafu {
+                reason: format!(
+                    "hop window expect positive slide and size, got slide={slide}ms, size={size}ms"
+                ),
+            }
+            .fail();
+        }
+        Ok(Self::Hop { slide, size })
+    }
+
+    /// create a session window, `gap` must be positive
+    pub fn session(key_cols: Vec<usize>, gap: Duration) -> Result<Self, EvalError> {
+        if gap <= 0 {
+            return InvalidArgumentSnafu {
+                reason: format!("session window expect positive gap, got gap={gap}ms"),
+            }
+            .fail();
+        }
+        Ok(Self::Session { key_cols, gap })
+    }
+}
+
+/// windows of a hop window that contain `ts`, in ascending order of window start
+///
+/// window starts are aligned to multiples of `slide`
+pub fn hop_windows(
+    ts: Timestamp,
+    slide: Duration,
+    size: Duration,
+) -> impl Iterator<Item = (Timestamp, Timestamp)> {
+    let last_start = ts.div_euclid(slide) * slide;
+    // first window start that is greater than `ts - size`
+    let lower = ts.saturating_sub(size);
+    let first_start = (lower.div_euclid(slide) + 1) * slide;
+    (first_start..=last_start)
+        .step_by(slide as usize)
+        .map(move |start| (start, start.saturating_add(size)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hop_windows() {
+        let windows = |ts| hop_windows(ts, 2, 5).collect::<Vec<_>>();
+        assert_eq!(windows(0), vec![(-4, 1), (-2, 3), (0, 5)]);
+        assert_eq!(windows(4), vec![(0, 5), (2, 7), (4, 9)]);
+        assert_eq!(windows(5), vec![(2, 7), (4, 9)]);
+        assert_eq!(windows(-3), vec![(-6, -1), (-4, 1)]);
+
+        // slide equals to size is a tumble window
+        let tumble = hop_windows(7, 5, 5).collect::<Vec<_>>();
+        assert_eq!(tumble, vec![(5, 10)]);
+        // slide larger than size leave gaps between windows
+        assert_eq!(hop_windows(3, 5, 2).count(), 0);
+
+        assert!(WindowKind::hop(0, 5).is_err());
+        assert!(WindowKind::hop(5, -1).is_err());
+        assert!(WindowKind::session(vec![], 0).is_err());
+    }
+}
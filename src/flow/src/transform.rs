@@ -15,6 +15,7 @@
 //! Transform Substrait into execution plan
 use std::collections::HashMap;
 
+use common_time::Timezone;
 use datatypes::data_type::ConcreteDataType as CDT;
 
 use crate::adapter::error::{Error, NotImplementedSnafu, TableNotFoundSnafu};
@@ -53,6 +54,8 @@ use substrait::substrait_proto::proto::extensions::SimpleExtensionDeclaration;
 /// So in substrait plan, a ref to a function can be a single u32 anchor instead of a full name in string
 pub struct FunctionExtensions {
     anchor_to_name: HashMap<u32, String>,
+    /// Timezone of the flow, time functions like `date_trunc` align their boundaries to it
+    timezone: Option<String>,
 }
 
 impl FunctionExtensions {
@@ -70,13 +73,27 @@ impl FunctionExtensions {
                 None => not_impl_err!("Cannot parse empty extension")?,
             }
         }
-        Ok(Self { anchor_to_name })
+        Ok(Self {
+            anchor_to_name,
+            timezone: None,
+        })
+    }
+
+    /// Set the timezone to evaluate time functions in
+    pub fn with_timezone(mut self, timezone: Option<String>) -> Self {
+        self.timezone = timezone;
+        self
     }
 
     /// Get the name of a function by it's anchor
     pub fn get(&self, anchor: &u32) -> Option<&String> {
         self.anchor_to_name.get(anchor)
     }
+
+    /// Get the timezone to evaluate time functions in
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
 }
 
 /// A context that holds the information of the dataflow
@@ -88,9 +105,24 @@ pub struct DataflowContext {
     name_to_id: HashMap<Vec<String>, GlobalId>,
     /// the schema of the table
     schema: HashMap<GlobalId, RelationType>,
+    /// Timezone of the flow, which defaults to the timezone of the session creating the flow.
+    ///
+    /// Boundaries of time functions like `date_trunc` are aligned to it, so e.g. daily
+    /// rollups follow the local days instead of the days in UTC.
+    timezone: Option<String>,
 }
 
 impl DataflowContext {
+    /// Set the timezone of the flow, usually the timezone of the `QueryContext` creating it
+    pub fn set_timezone(&mut self, timezone: Option<&Timezone>) {
+        self.timezone = timezone.map(|tz| tz.to_string());
+    }
+
+    /// Get the timezone of the flow
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
     /// Retrieves a GlobalId and table schema representing a table previously registered by calling the [register_table] function.
     ///
     /// Returns an error if no table has been registered with the provided names
@@ -140,6 +172,7 @@ mod test {
             id_to_name: HashMap::from([(gid, name.clone())]),
             name_to_id: HashMap::from([(name.clone(), gid)]),
             schema: HashMap::from([(gid, schema)]),
+            timezone: None,
         }
     }
 
@@ -24,7 +24,7 @@ use datafusion_substrait::variation_const::{
 use datatypes::arrow::compute::kernels::window;
 use datatypes::arrow::ipc::Binary;
 use datatypes::data_type::ConcreteDataType as CDT;
-use datatypes::value::Value;
+use datatypes::value::{OrderedF64, Value};
 use hydroflow::futures::future::Map;
 use itertools::Itertools;
 use snafu::{OptionExt, ResultExt};
@@ -42,7 +42,10 @@ use substrait::substrait_proto::proto::function_argument::ArgType;
 use substrait::substrait_proto::proto::r#type::Kind;
 use substrait::substrait_proto::proto::read_rel::ReadType;
 use substrait::substrait_proto::proto::rel::RelType;
-use substrait::substrait_proto::proto::{self, plan_rel, Expression, Plan as SubPlan, Rel};
+use substrait::substrait_proto::proto::sort_field::{SortDirection, SortKind};
+use substrait::substrait_proto::proto::{
+    self, plan_rel, Expression, Plan as SubPlan, Rel, SortField,
+};
 
 use crate::adapter::error::{
     DatatypesSnafu, Error, EvalSnafu, InvalidQuerySnafu, NotImplementedSnafu, PlanSnafu,
@@ -52,7 +55,9 @@ use crate::expr::{
     AggregateExpr, AggregateFunc, BinaryFunc, GlobalId, MapFilterProject, SafeMfpPlan, ScalarExpr,
     TypedExpr, UnaryFunc, UnmaterializableFunc, VariadicFunc,
 };
-use crate::plan::{AccumulablePlan, KeyValPlan, Plan, ReducePlan, TypedPlan};
+use crate::plan::{
+    AccumulablePlan, AggrWithIndex, KeyValPlan, Plan, ReducePlan, TypedPlan, WindowKind, WindowPlan,
+};
 use crate::repr::{self, ColumnType, RelationType};
 use crate::transform::{DataflowContext, FunctionExtensions};
 
@@ -62,12 +67,25 @@ impl TypedExpr {
         groupings: &[Grouping],
         typ: &RelationType,
         extensions: &FunctionExtensions,
-    ) -> Result<Vec<TypedExpr>, Error> {
+    ) -> Result<(Vec<TypedExpr>, Option<WindowPlan>), Error> {
         let _ = ctx;
         let mut group_expr = vec![];
+        let mut window = None;
         match groupings.len() {
             1 => {
                 for e in &groupings[0].grouping_expressions {
+                    if let Some(w) = WindowPlan::from_substrait_rex(e, typ, extensions)? {
+                        if window.is_some() {
+                            return not_impl_err!("Group by more than one window is not supported");
+                        }
+                        window = Some(w);
+                        // the window is assigned to rows before reduce, so group by `window_start` instead
+                        let window_start = ScalarExpr::Column(typ.column_types.len());
+                        let window_start_typ =
+                            ColumnType::new(CDT::timestamp_millisecond_datatype(), false);
+                        group_expr.push(TypedExpr::new(window_start, window_start_typ));
+                        continue;
+                    }
                     let x = TypedExpr::from_substrait_rex(e, typ, extensions)?;
                     group_expr.push(x);
                 }
@@ -78,7 +96,97 @@ impl TypedExpr {
                 );
             }
         };
-        Ok(group_expr)
+
+        // rows are divided into sessions by other group by columns
+        if let Some(WindowPlan {
+            window: WindowKind::Session { key_cols, .. },
+            ..
+        }) = &mut window
+        {
+            for expr in &group_expr {
+                let col = expr.expr.as_column().with_context(|| NotImplementedSnafu {
+                    reason: "Session window with group by non-column expressions is not supported",
+                })?;
+                if col != typ.column_types.len() {
+                    key_cols.push(col);
+                }
+            }
+        }
+        Ok((group_expr, window))
+    }
+}
+
+impl WindowPlan {
+    /// Convert `hop(ts, slide, size)` or `session(ts, gap)` in group by into a WindowPlan,
+    /// return `None` if it's not a window function
+    ///
+    /// durations are literal strings like `'5m'` or milliseconds in integer
+    fn from_substrait_rex(
+        e: &Expression,
+        typ: &RelationType,
+        extensions: &FunctionExtensions,
+    ) -> Result<Option<WindowPlan>, Error> {
+        let Some(RexType::ScalarFunction(f)) = &e.rex_type else {
+            return Ok(None);
+        };
+        let fn_name = match extensions.get(&f.function_reference) {
+            Some(name) if name == "hop" || name == "session" => name.as_str(),
+            _ => return Ok(None),
+        };
+        let args: Vec<TypedExpr> = f
+            .arguments
+            .iter()
+            .map(|arg| match &arg.arg_type {
+                Some(ArgType::Value(e)) => TypedExpr::from_substrait_rex(e, typ, extensions),
+                _ => not_impl_err!("Window function argument non-Value type not supported"),
+            })
+            .try_collect()?;
+
+        let ts_col = match args.first().and_then(|arg| arg.expr.as_column()) {
+            Some(col) => col,
+            None => return not_impl_err!("{fn_name} window on non-column is not supported"),
+        };
+        let durations: Vec<repr::Duration> = args[1..]
+            .iter()
+            .map(|arg| literal_to_duration(fn_name, &arg.expr))
+            .try_collect()?;
+        let window = match (fn_name, durations.as_slice()) {
+            ("hop", [slide, size]) => WindowKind::hop(*slide, *size),
+            ("session", [gap]) => WindowKind::session(vec![], *gap),
+            _ => {
+                return InvalidQuerySnafu {
+                    reason: format!(
+                        "Expect `hop(ts, slide, size)` or `session(ts, gap)`, got {fn_name} with {} arguments",
+                        args.len()
+                    ),
+                }
+                .fail()
+            }
+        }
+        .context(EvalSnafu)?;
+        Ok(Some(WindowPlan { ts_col, window }))
+    }
+}
+
+/// Parse a duration argument of window function, either a string like `'5m'` or milliseconds
+fn literal_to_duration(fn_name: &str, expr: &ScalarExpr) -> Result<repr::Duration, Error> {
+    match expr {
+        ScalarExpr::Literal(Value::String(s), _) => {
+            let d = humantime::parse_duration(s.as_utf8()).map_err(|e| {
+                InvalidQuerySnafu {
+                    reason: format!("Invalid duration {} of {fn_name}: {e}", s.as_utf8()),
+                }
+                .build()
+            })?;
+            repr::Duration::try_from(d.as_millis()).map_err(|_| {
+                InvalidQuerySnafu {
+                    reason: format!("Duration {} of {fn_name} is too large", s.as_utf8()),
+                }
+                .build()
+            })
+        }
+        ScalarExpr::Literal(Value::Int64(ms), _) => Ok(*ms),
+        _ => not_impl_err!("{fn_name} with non-literal duration is not supported"),
     }
 }
 
@@ -88,7 +196,7 @@ impl AggregateExpr {
         measures: &[Measure],
         typ: &RelationType,
         extensions: &FunctionExtensions,
-    ) -> Result<Vec<AggregateExpr>, Error> {
+    ) -> Result<Vec<(AggregateExpr, ColumnType)>, Error> {
         let _ = ctx;
         let mut aggr_exprs = vec![];
 
@@ -106,9 +214,9 @@ impl AggregateExpr {
                         _ if f.invocation == AggregationInvocation::All as i32 => false,
                         _ => false,
                     };
+                    let order_by = order_by_from_substrait_sorts(&f.sorts, typ, extensions)?;
                     AggregateExpr::from_substrait_agg_func(
-                        f, typ, extensions, filter, // TODO(discord9): impl order_by
-                        &None, distinct,
+                        f, typ, extensions, filter, &order_by, distinct,
                     )
                 }
                 None => not_impl_err!("Aggregate without aggregate function is not supported"),
@@ -118,18 +226,19 @@ impl AggregateExpr {
         Ok(aggr_exprs)
     }
 
-    /// Convert AggregateFunction into Flow's AggregateExpr
+    /// Convert AggregateFunction into Flow's AggregateExpr and the type of its output
+    ///
+    /// `order_by` is the order key and whether it's descending, only used by `first_value`/`last_value`
     pub fn from_substrait_agg_func(
         f: &proto::AggregateFunction,
         input_schema: &RelationType,
         extensions: &FunctionExtensions,
         filter: &Option<TypedExpr>,
-        order_by: &Option<Vec<TypedExpr>>,
+        order_by: &Option<(TypedExpr, bool)>,
         distinct: bool,
-    ) -> Result<AggregateExpr, Error> {
+    ) -> Result<(AggregateExpr, ColumnType), Error> {
         // TODO(discord9): impl filter
         let _ = filter;
-        let _ = order_by;
         let mut args = vec![];
         for arg in &f.arguments {
             let arg_expr = match &arg.arg_type {
@@ -156,14 +265,114 @@ impl AggregateExpr {
                 f.function_reference
             ),
         }?;
-        Ok(AggregateExpr {
+        let (func, order_by) = match (func, order_by) {
+            // the percentile is given as the second argument, not as an input of each row
+            (AggregateFunc::ApproxPercentileCont(_), _) => (
+                AggregateFunc::ApproxPercentileCont(percentile_from_arg(args.get(1))?),
+                None,
+            ),
+            // `first_value(x ORDER BY k DESC)` is `last_value(x ORDER BY k)` and vice versa
+            (AggregateFunc::FirstValue, Some((key, descending))) => {
+                let func = if *descending {
+                    AggregateFunc::LastValue
+                } else {
+                    AggregateFunc::FirstValue
+                };
+                (func, Some(key.expr.clone()))
+            }
+            (AggregateFunc::LastValue, Some((key, descending))) => {
+                let func = if *descending {
+                    AggregateFunc::FirstValue
+                } else {
+                    AggregateFunc::LastValue
+                };
+                (func, Some(key.expr.clone()))
+            }
+            (func @ (AggregateFunc::FirstValue | AggregateFunc::LastValue), None) => {
+                return InvalidQuerySnafu {
+                    reason: format!("{:?} requires an ORDER BY in flow", func),
+                }
+                .fail();
+            }
+            (func, _) => (func, None),
+        };
+        // the boundary value is kept as is, so `first_value`/`last_value` output the type of its input
+        let output_type = match func {
+            AggregateFunc::FirstValue | AggregateFunc::LastValue => arg.typ.scalar_type.clone(),
+            _ => func.signature().output.clone(),
+        };
+        let aggr = AggregateExpr {
             func,
             expr: arg.expr.clone(),
             distinct,
-        })
+            order_by,
+        };
+        Ok((aggr, ColumnType::new_nullable(output_type)))
     }
 }
 
+/// Gets the order key of an aggregate function and whether it's descending from its sort fields,
+/// only ordering by a single key is supported.
+fn order_by_from_substrait_sorts(
+    sorts: &[SortField],
+    typ: &RelationType,
+    extensions: &FunctionExtensions,
+) -> Result<Option<(TypedExpr, bool)>, Error> {
+    let sort = match sorts {
+        [] => return Ok(None),
+        [sort] => sort,
+        _ => return not_impl_err!("Aggregate function ordered by multiple keys is not supported"),
+    };
+    let Some(expr) = &sort.expr else {
+        return not_impl_err!("Aggregate function ordered by nothing is not supported");
+    };
+    let key = TypedExpr::from_substrait_rex(expr, typ, extensions)?;
+    let descending = matches!(
+        sort.sort_kind,
+        Some(SortKind::Direction(d))
+            if d == SortDirection::DescNullsFirst as i32 || d == SortDirection::DescNullsLast as i32
+    );
+    Ok(Some((key, descending)))
+}
+
+/// Gets the percentile of `approx_percentile_cont` from its argument, which must be
+/// a literal number in `[0, 1]`.
+fn percentile_from_arg(arg: Option<&TypedExpr>) -> Result<OrderedF64, Error> {
+    let literal = arg.and_then(|arg| match &arg.expr {
+        // integer literals are cast to float by the planner
+        ScalarExpr::CallUnary {
+            func: UnaryFunc::Cast(_),
+            expr,
+        } => expr.as_literal(),
+        expr => expr.as_literal(),
+    });
+    let percentile = match literal {
+        Some(Value::Float64(p)) => p.0,
+        Some(Value::Float32(p)) => f64::from(p.0),
+        Some(Value::Int8(p)) => f64::from(p),
+        Some(Value::Int16(p)) => f64::from(p),
+        Some(Value::Int32(p)) => f64::from(p),
+        Some(Value::Int64(p)) => p as f64,
+        Some(Value::UInt8(p)) => f64::from(p),
+        Some(Value::UInt16(p)) => f64::from(p),
+        Some(Value::UInt32(p)) => f64::from(p),
+        Some(Value::UInt64(p)) => p as f64,
+        _ => {
+            return InvalidQuerySnafu {
+                reason: "approx_percentile_cont requires a literal percentile",
+            }
+            .fail();
+        }
+    };
+    if !(0.0..=1.0).contains(&percentile) {
+        return InvalidQuerySnafu {
+            reason: format!("Percentile must be in [0, 1], got {percentile}"),
+        }
+        .fail();
+    }
+    Ok(OrderedF64::from(percentile))
+}
+
 impl KeyValPlan {
     /// Generate KeyValPlan from AggregateExpr and group_exprs
     ///
@@ -186,10 +395,16 @@ impl KeyValPlan {
         // val_plan is extracted from aggr_exprs to give aggr function it's necessary input
         // and since aggr func need inputs that is column ref, we just add a prefix mfp to transform any expr that is not into a column ref
         let val_plan = {
-            let need_mfp = aggr_exprs.iter().any(|agg| agg.expr.as_column().is_none());
+            let need_mfp = aggr_exprs.iter().any(|agg| {
+                agg.expr.as_column().is_none()
+                    || agg
+                        .order_by
+                        .as_ref()
+                        .is_some_and(|key| key.as_column().is_none())
+            });
             if need_mfp {
                 // create mfp from aggr_expr, and modify aggr_expr to use the output column of mfp
-                let input_exprs = aggr_exprs
+                let mut input_exprs = aggr_exprs
                     .iter_mut()
                     .enumerate()
                     .map(|(idx, aggr)| {
@@ -198,11 +413,19 @@ impl KeyValPlan {
                         ret
                     })
                     .collect_vec();
-                let aggr_arity = aggr_exprs.len();
+                // order keys are placed after all the inputs of aggr func
+                for aggr in aggr_exprs.iter_mut() {
+                    if let Some(key) = &mut aggr.order_by {
+                        let ret = key.clone();
+                        *key = ScalarExpr::Column(input_exprs.len());
+                        input_exprs.push(ret);
+                    }
+                }
+                let val_arity = input_exprs.len();
 
                 MapFilterProject::new(input_arity)
                     .map(input_exprs)?
-                    .project(input_arity..input_arity + aggr_arity)?
+                    .project(input_arity..input_arity + val_arity)?
             } else {
                 // simply take all inputs as value
                 MapFilterProject::new(input_arity)
@@ -228,11 +451,17 @@ impl TypedPlan {
             return not_impl_err!("Aggregate without an input is not supported");
         };
 
-        let group_expr =
+        let (group_expr, window) =
             TypedExpr::from_substrait_agg_grouping(ctx, &agg.groupings, &input.typ, extensions)?;
+        let input = match window {
+            Some(window) => input.window(window),
+            None => input,
+        };
 
-        let mut aggr_exprs =
-            AggregateExpr::from_substrait_agg_measures(ctx, &agg.measures, &input.typ, extensions)?;
+        let (mut aggr_exprs, aggr_types): (Vec<_>, Vec<_>) =
+            AggregateExpr::from_substrait_agg_measures(ctx, &agg.measures, &input.typ, extensions)?
+                .into_iter()
+                .unzip();
 
         let key_val_plan = KeyValPlan::from_substrait_gen_key_val_plan(
             &mut aggr_exprs,
@@ -247,11 +476,7 @@ impl TypedPlan {
                 output_types.push(expr.typ.clone());
             }
 
-            for aggr in &aggr_exprs {
-                output_types.push(ColumnType::new_nullable(
-                    aggr.func.signature().output.clone(),
-                ));
-            }
+            output_types.extend(aggr_types);
             RelationType::new(output_types)
         };
 
@@ -264,10 +489,25 @@ impl TypedPlan {
             let input_column = aggr_expr.expr.as_column().with_context(|| PlanSnafu {
                 reason: "Expect aggregate argument to be transformed into a column at this point",
             })?;
+            let order_key_column = aggr_expr
+                .order_by
+                .as_ref()
+                .map(|key| {
+                    key.as_column().with_context(|| PlanSnafu {
+                        reason: "Expect order key to be transformed into a column at this point",
+                    })
+                })
+                .transpose()?;
+            let aggr = AggrWithIndex {
+                expr: aggr_expr.clone(),
+                input_idx: input_column,
+                order_key_idx: order_key_column,
+                output_idx: output_column,
+            };
             if aggr_expr.distinct {
-                distinct_aggrs.push((output_column, input_column, aggr_expr.clone()));
+                distinct_aggrs.push(aggr);
             } else {
-                simple_aggrs.push((output_column, input_column, aggr_expr.clone()));
+                simple_aggrs.push(aggr);
             }
         }
         let accum_plan = AccumulablePlan {
@@ -290,6 +530,7 @@ impl TypedPlan {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::expr::FirstLast;
     use crate::plan::{Plan, TypedPlan};
     use crate::repr::{self, ColumnType, RelationType};
     use crate::transform::test::{create_test_ctx, create_test_query_engine, sql_to_substrait};
@@ -307,6 +548,7 @@ mod test {
             func: AggregateFunc::SumUInt32,
             expr: ScalarExpr::Column(0),
             distinct: false,
+            order_by: None,
         };
         let expected = TypedPlan {
             typ: RelationType::new(vec![ColumnType::new(CDT::uint32_datatype(), true)]),
@@ -327,7 +569,7 @@ mod test {
                     },
                     reduce_plan: ReducePlan::Accumulable(AccumulablePlan {
                         full_aggrs: vec![aggr_expr.clone()],
-                        simple_aggrs: vec![(0, 0, aggr_expr.clone())],
+                        simple_aggrs: vec![AggrWithIndex::new(aggr_expr.clone(), 0, 0)],
                         distinct_aggrs: vec![],
                     }),
                 }),
@@ -341,6 +583,217 @@ mod test {
         assert_eq!(flow_plan.unwrap(), expected);
     }
 
+    #[tokio::test]
+    async fn test_approx_aggr() {
+        let engine = create_test_query_engine();
+        let testcases = [
+            (
+                "SELECT approx_distinct(number) FROM numbers",
+                AggregateFunc::ApproxDistinct,
+            ),
+            (
+                "SELECT approx_percentile_cont(number, 0.9) FROM numbers",
+                AggregateFunc::ApproxPercentileCont(OrderedF64::from(0.9)),
+            ),
+            (
+                "SELECT approx_percentile_cont(number, 1) FROM numbers",
+                AggregateFunc::ApproxPercentileCont(OrderedF64::from(1.0)),
+            ),
+        ];
+        for (sql, func) in testcases {
+            let plan = sql_to_substrait(engine.clone(), sql).await;
+            let mut ctx = create_test_ctx();
+            let flow_plan = TypedPlan::from_substrait_plan(&mut ctx, &plan).unwrap();
+
+            let Plan::Mfp { input, .. } = flow_plan.plan else {
+                panic!("Expect Mfp, got {:?}", flow_plan.plan);
+            };
+            let Plan::Reduce {
+                reduce_plan: ReducePlan::Accumulable(reduce_plan),
+                ..
+            } = *input
+            else {
+                panic!("Expect accumulable Reduce, got {:?}", input);
+            };
+            assert_eq!(reduce_plan.full_aggrs[0].func, func);
+        }
+    }
+
+    #[test]
+    fn test_percentile_from_arg() {
+        let arg = |expr| TypedExpr::new(expr, ColumnType::new(CDT::float64_datatype(), false));
+        let percentile = |expr| percentile_from_arg(Some(&arg(expr))).map(|p| p.0);
+
+        assert_eq!(
+            0.5,
+            percentile(ScalarExpr::Literal(
+                Value::from(0.5f64),
+                CDT::float64_datatype()
+            ))
+            .unwrap()
+        );
+        assert_eq!(
+            1.0,
+            percentile(ScalarExpr::Literal(Value::Int64(1), CDT::int64_datatype())).unwrap()
+        );
+        assert_eq!(
+            0.0,
+            percentile(ScalarExpr::CallUnary {
+                func: UnaryFunc::Cast(CDT::float64_datatype()),
+                expr: Box::new(ScalarExpr::Literal(Value::UInt8(0), CDT::uint8_datatype())),
+            })
+            .unwrap()
+        );
+        assert!(percentile(ScalarExpr::Literal(Value::Int64(2), CDT::int64_datatype())).is_err());
+        assert!(percentile(ScalarExpr::Column(0)).is_err());
+        assert!(percentile_from_arg(None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_first_last_value() {
+        let engine = create_test_query_engine();
+        let testcases = [
+            (
+                "SELECT first_value(number ORDER BY number) FROM numbers",
+                AggregateFunc::FirstValue,
+            ),
+            (
+                "SELECT first_value(number ORDER BY number DESC) FROM numbers",
+                AggregateFunc::LastValue,
+            ),
+            (
+                "SELECT last_value(number ORDER BY number) FROM numbers",
+                AggregateFunc::LastValue,
+            ),
+        ];
+        for (sql, func) in testcases {
+            let plan = sql_to_substrait(engine.clone(), sql).await;
+            let mut ctx = create_test_ctx();
+            let flow_plan = TypedPlan::from_substrait_plan(&mut ctx, &plan).unwrap();
+            assert_eq!(
+                flow_plan.typ,
+                RelationType::new(vec![ColumnType::new(CDT::uint32_datatype(), true)])
+            );
+
+            let Plan::Mfp { input, .. } = flow_plan.plan else {
+                panic!("Expect Mfp, got {:?}", flow_plan.plan);
+            };
+            let Plan::Reduce {
+                reduce_plan: ReducePlan::Accumulable(reduce_plan),
+                ..
+            } = *input
+            else {
+                panic!("Expect accumulable Reduce, got {:?}", input);
+            };
+            let aggr_expr = AggregateExpr {
+                func,
+                expr: ScalarExpr::Column(0),
+                distinct: false,
+                order_by: Some(ScalarExpr::Column(0)),
+            };
+            assert_eq!(reduce_plan.full_aggrs, vec![aggr_expr]);
+        }
+
+        let plan =
+            sql_to_substrait(engine.clone(), "SELECT first_value(number) FROM numbers").await;
+        let mut ctx = create_test_ctx();
+        assert!(TypedPlan::from_substrait_plan(&mut ctx, &plan).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_first_last_value_eval() {
+        let engine = create_test_query_engine();
+        let sql = "SELECT last_value(number + 1 ORDER BY number DESC) FROM numbers";
+        let plan = sql_to_substrait(engine.clone(), sql).await;
+        let mut ctx = create_test_ctx();
+        let flow_plan = TypedPlan::from_substrait_plan(&mut ctx, &plan).unwrap();
+
+        let Plan::Mfp { input, .. } = flow_plan.plan else {
+            panic!("Expect Mfp, got {:?}", flow_plan.plan);
+        };
+        let Plan::Reduce {
+            reduce_plan: ReducePlan::Accumulable(reduce_plan),
+            ..
+        } = *input
+        else {
+            panic!("Expect accumulable Reduce, got {:?}", input);
+        };
+        let [aggr] = &reduce_plan.simple_aggrs[..] else {
+            panic!("Expect one aggregation, got {:?}", reduce_plan.simple_aggrs);
+        };
+        assert_eq!(aggr.expr.func, AggregateFunc::FirstValue);
+        let order_key_idx = aggr.order_key_idx.unwrap();
+        assert_ne!(aggr.input_idx, order_key_idx);
+
+        // the value and the order key are taken from their own columns of each row
+        let rows = [(3, "c"), (1, "a"), (2, "b")].map(|(order_key, value)| {
+            let mut row = vec![Value::Null; aggr.input_idx.max(order_key_idx) + 1];
+            row[aggr.input_idx] = Value::from(value);
+            row[order_key_idx] = Value::Int64(order_key);
+            repr::Row::new(row)
+        });
+        let value_diffs = rows.iter().map(|row| (aggr.input_of(row).unwrap(), 1));
+        let (res, state) = aggr
+            .expr
+            .func
+            .eval_diff_accumulable(vec![], value_diffs)
+            .unwrap();
+        assert_eq!(res, Value::from("a"));
+
+        // a short row misses the columns of the aggregation
+        assert!(aggr.input_of(&repr::Row::empty()).is_err());
+        let value = FirstLast::input(Value::Int64(0), Value::from("z"));
+        let (res, _) = aggr
+            .expr
+            .func
+            .eval_diff_accumulable(state, vec![(value, 1)])
+            .unwrap();
+        assert_eq!(res, Value::from("z"));
+    }
+
+    /// find the window plan below the reduce of the flow plan
+    fn find_window_plan(plan: &Plan) -> Option<&WindowPlan> {
+        match plan {
+            Plan::Mfp { input, .. } | Plan::Reduce { input, .. } => find_window_plan(input),
+            Plan::Window { window, .. } => Some(window),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hop_window_group_by() {
+        let engine = create_test_query_engine();
+        let sql = "SELECT sum(number) FROM numbers GROUP BY hop(number, '1s', '2s')";
+        let plan = sql_to_substrait(engine.clone(), sql).await;
+
+        let mut ctx = create_test_ctx();
+        let flow_plan = TypedPlan::from_substrait_plan(&mut ctx, &plan).unwrap();
+        assert_eq!(
+            find_window_plan(&flow_plan.plan),
+            Some(&WindowPlan {
+                ts_col: 0,
+                window: WindowKind::hop(1000, 2000).unwrap(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_window_group_by() {
+        let engine = create_test_query_engine();
+        let sql = "SELECT sum(number) FROM numbers GROUP BY session(number, 5000)";
+        let plan = sql_to_substrait(engine.clone(), sql).await;
+
+        let mut ctx = create_test_ctx();
+        let flow_plan = TypedPlan::from_substrait_plan(&mut ctx, &plan).unwrap();
+        assert_eq!(
+            find_window_plan(&flow_plan.plan),
+            Some(&WindowPlan {
+                ts_col: 0,
+                window: WindowKind::session(vec![], 5000).unwrap(),
+            })
+        );
+    }
+
     #[tokio::test]
     async fn test_sum_group_by() {
         let engine = create_test_query_engine();
@@ -354,6 +807,7 @@ mod test {
             func: AggregateFunc::SumUInt32,
             expr: ScalarExpr::Column(0),
             distinct: false,
+            order_by: None,
         };
         let expected = TypedPlan {
             typ: RelationType::new(vec![
@@ -379,7 +833,7 @@ mod test {
                     },
                     reduce_plan: ReducePlan::Accumulable(AccumulablePlan {
                         full_aggrs: vec![aggr_expr.clone()],
-                        simple_aggrs: vec![(0, 0, aggr_expr.clone())],
+                        simple_aggrs: vec![AggrWithIndex::new(aggr_expr.clone(), 0, 0)],
                         distinct_aggrs: vec![],
                     }),
                 }),
@@ -407,6 +861,7 @@ mod test {
             func: AggregateFunc::SumUInt32,
             expr: ScalarExpr::Column(0),
             distinct: false,
+            order_by: None,
         };
         let expected = TypedPlan {
             typ: RelationType::new(vec![ColumnType::new(CDT::uint32_datatype(), true)]),
@@ -430,7 +885,7 @@ mod test {
                     },
                     reduce_plan: ReducePlan::Accumulable(AccumulablePlan {
                         full_aggrs: vec![aggr_expr.clone()],
-                        simple_aggrs: vec![(0, 0, aggr_expr.clone())],
+                        simple_aggrs: vec![AggrWithIndex::new(aggr_expr.clone(), 0, 0)],
                         distinct_aggrs: vec![],
                     }),
                 }),
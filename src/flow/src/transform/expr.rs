@@ -15,6 +15,7 @@
 #![warn(unused_imports)]
 
 use datatypes::data_type::ConcreteDataType as CDT;
+use datatypes::value::Value;
 use itertools::Itertools;
 use snafu::{OptionExt, ResultExt};
 use substrait::substrait_proto::proto::expression::field_reference::ReferenceType::DirectReference;
@@ -79,6 +80,18 @@ impl TypedExpr {
             .unzip();
 
         match arg_len {
+            // `date_trunc(unit, ts)` truncates in the timezone of the flow
+            2 if fn_name == "date_trunc" => {
+                let ScalarExpr::Literal(Value::String(unit), _) = &arg_exprs[0] else {
+                    return not_impl_err!("date_trunc with a non-literal unit is not supported");
+                };
+                let func = UnaryFunc::date_trunc(unit.as_utf8(), extensions.timezone())?;
+                let ret_type = ColumnType::new_nullable(func.signature().output.clone());
+                Ok(TypedExpr::new(
+                    arg_exprs[1].clone().call_unary(func),
+                    ret_type,
+                ))
+            }
             // because variadic function can also have 1 arguments, we need to check if it's a variadic function first
             1 if VariadicFunc::from_str_and_types(fn_name, &arg_types).is_err() => {
                 let func = UnaryFunc::from_str_and_type(fn_name, None)?;
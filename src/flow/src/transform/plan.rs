@@ -32,7 +32,8 @@ impl TypedPlan {
         plan: &SubPlan,
     ) -> Result<TypedPlan, Error> {
         // Register function extension
-        let function_extension = FunctionExtensions::try_from_proto(&plan.extensions)?;
+        let function_extension = FunctionExtensions::try_from_proto(&plan.extensions)?
+            .with_timezone(ctx.timezone().map(|tz| tz.to_string()));
 
         // Parse relations
         match plan.relations.len() {
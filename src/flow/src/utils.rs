@@ -14,8 +14,9 @@
 
 //! utilities for managing state of dataflow execution
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::Bound;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use itertools::Itertools;
@@ -23,7 +24,7 @@ use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
 use tokio::sync::{Mutex, RwLock};
 
-use crate::expr::error::InternalSnafu;
+use crate::expr::error::{InternalSnafu, InvalidArgumentSnafu};
 use crate::expr::{EvalError, ScalarExpr};
 use crate::repr::{value_to_internal_ts, Diff, DiffRow, Duration, KeyValDiffRow, Row, Timestamp};
 
@@ -33,6 +34,84 @@ pub type Batch = BTreeMap<Row, SmallVec<[DiffRow; 2]>>;
 /// A spine of batches, arranged by timestamp
 pub type Spine = BTreeMap<Timestamp, Batch>;
 
+/// Option key of the flow for how late a row can arrive before its key is expired, i.e. `WITH (allowed_lateness = '5m')`
+pub const ALLOWED_LATENESS_KEY: &str = "allowed_lateness";
+/// Option key of the flow for how to handle rows arriving later than allowed lateness
+pub const LATE_DATA_POLICY_KEY: &str = "late_data_policy";
+
+/// How to handle rows whose key already expired(arrived later than the allowed lateness)
+#[derive(
+    Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize,
+)]
+pub enum LateDataPolicy {
+    /// silently drop late rows
+    #[default]
+    Drop,
+    /// keep late rows aside, so they can be written to an errors table, see [`Arrangement::take_late_updates`]
+    SideOutput,
+    /// apply late rows anyway, so the window they belong to is re-emitted
+    Reemit,
+}
+
+impl FromStr for LateDataPolicy {
+    type Err = EvalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "drop" => Ok(Self::Drop),
+            "side_output" => Ok(Self::SideOutput),
+            "reemit" => Ok(Self::Reemit),
+            _ => InvalidArgumentSnafu {
+                reason: format!(
+                    "Unknown late data policy: {s}, expect one of `drop`, `side_output`, `reemit`"
+                ),
+            }
+            .fail(),
+        }
+    }
+}
+
+/// Watermark related options of a flow, parsed from the `WITH` clause of the flow
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct LatenessOptions {
+    /// duration after which a key is considered expired, `None` means never expire
+    pub allowed_lateness: Option<Duration>,
+    /// how to handle rows arriving later than `allowed_lateness`
+    pub late_data_policy: LateDataPolicy,
+}
+
+impl LatenessOptions {
+    /// parse lateness options from flow options, unknown keys are ignored
+    pub fn from_options(options: &HashMap<String, String>) -> Result<Self, EvalError> {
+        let allowed_lateness = options
+            .get(ALLOWED_LATENESS_KEY)
+            .map(|s| {
+                let d = humantime::parse_duration(s).map_err(|e| {
+                    InvalidArgumentSnafu {
+                        reason: format!("Invalid {ALLOWED_LATENESS_KEY}: {s}, error: {e}"),
+                    }
+                    .build()
+                })?;
+                Duration::try_from(d.as_millis()).map_err(|_| {
+                    InvalidArgumentSnafu {
+                        reason: format!("{ALLOWED_LATENESS_KEY} is too large: {s}"),
+                    }
+                    .build()
+                })
+            })
+            .transpose()?;
+        let late_data_policy = options
+            .get(LATE_DATA_POLICY_KEY)
+            .map(|s| s.parse())
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self {
+            allowed_lateness,
+            late_data_policy,
+        })
+    }
+}
+
 /// Determine when should a key expire according to it's event timestamp in key,
 /// if a key is expired, any future updates to it should be ignored
 /// Note that key is expired by it's event timestamp(contained in the key), not by the time it's inserted(system timestamp)
@@ -44,9 +123,30 @@ pub struct KeyExpiryManager {
     key_expiration_duration: Option<Duration>,
     /// using this to get timestamp from key row
     event_timestamp_from_row: Option<ScalarExpr>,
+    /// how to handle updates to keys that are already expired
+    #[serde(default)]
+    late_data_policy: LateDataPolicy,
 }
 
 impl KeyExpiryManager {
+    /// create a expiry manager that expires keys by event timestamp extracted by `event_timestamp_from_row`
+    ///
+    /// No rendered operator attaches an expiry manager to its arrangement yet, session windows
+    /// close sessions with [`SessionWindows`] instead
+    pub fn new(options: LatenessOptions, event_timestamp_from_row: Option<ScalarExpr>) -> Self {
+        Self {
+            event_ts_to_key: Default::default(),
+            key_expiration_duration: options.allowed_lateness,
+            event_timestamp_from_row,
+            late_data_policy: options.late_data_policy,
+        }
+    }
+
+    /// get the policy for handling late updates
+    pub fn late_data_policy(&self) -> LateDataPolicy {
+        self.late_data_policy
+    }
+
     /// extract event timestamp from key row
     ///
     /// if no expire state is set, return None
@@ -100,6 +200,203 @@ impl KeyExpiryManager {
     }
 }
 
+/// Rows of one session key arranged by event timestamp, `(event ts, row) -> diff`
+type SessionRows = BTreeMap<(Timestamp, Row), Diff>;
+
+/// State of a session window operator, keeps rows of sessions that are not closed yet
+///
+/// A session is `[first event ts, last event ts + gap)` of rows with the same key, when a row
+/// falls into the gap of two sessions they are merged, so rows of both sessions are retracted
+/// and emitted again with the bounds of the merged session.
+///
+/// A session is closed once its end is no later than `now - allowed_lateness`, rows of closed
+/// sessions are removed from state by [`SessionWindows::trunc_closed`], and updates arriving for
+/// them are handled by the [`LateDataPolicy`]. Without `allowed_lateness` sessions never close.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SessionWindows {
+    /// max gap between two rows of the same session
+    gap: Duration,
+    /// when a session is closed and how to handle updates to closed sessions
+    lateness: LatenessOptions,
+    /// session key -> rows of its open sessions
+    sessions: BTreeMap<Row, SessionRows>,
+    /// late updates kept aside by [`LateDataPolicy::SideOutput`], not part of the state
+    late_updates: Vec<DiffRow>,
+}
+
+impl SessionWindows {
+    /// create an empty session window state
+    pub fn new(gap: Duration, lateness: LatenessOptions) -> Self {
+        Self {
+            gap,
+            lateness,
+            sessions: Default::default(),
+            late_updates: Vec::new(),
+        }
+    }
+
+    /// take all late updates kept aside since last call, see [`LateDataPolicy::SideOutput`]
+    pub fn take_late_updates(&mut self) -> Vec<DiffRow> {
+        std::mem::take(&mut self.late_updates)
+    }
+
+    /// sessions closed before this time, `None` if sessions never close
+    fn watermark(&self, now: Timestamp) -> Option<Timestamp> {
+        self.lateness.allowed_lateness.map(|d| now - d)
+    }
+
+    /// apply updates of `(session key, event ts, row, diff)` at time `now`
+    ///
+    /// return the changes of output, that is rows with the bounds of the session they belong to,
+    /// as `(row, (window_start, window_end), diff)`
+    pub fn apply_updates(
+        &mut self,
+        now: Timestamp,
+        updates: impl IntoIterator<Item = (Row, Timestamp, Row, Diff)>,
+    ) -> Vec<(Row, (Timestamp, Timestamp), Diff)> {
+        let watermark = self.watermark(now);
+        let mut updates_by_key: BTreeMap<Row, Vec<(Timestamp, Row, Diff)>> = BTreeMap::new();
+        for (key, ts, row, diff) in updates {
+            if watermark.map_or(false, |w| ts < w) {
+                match self.lateness.late_data_policy {
+                    LateDataPolicy::Drop => continue,
+                    LateDataPolicy::SideOutput => {
+                        self.late_updates.push((row, now, diff));
+                        continue;
+                    }
+                    // closed sessions are already gone, so late row can only join open sessions
+                    LateDataPolicy::Reemit => (),
+                }
+            }
+            updates_by_key.entry(key).or_default().push((ts, row, diff));
+        }
+
+        let mut changes = BTreeMap::new();
+        for (key, updates) in updates_by_key {
+            let rows = self.sessions.entry(key.clone()).or_default();
+            // only sessions around the updated rows change, so other sessions are left as they are
+            let (lo, hi) = updates
+                .iter()
+                .fold((Timestamp::MAX, Timestamp::MIN), |(lo, hi), (ts, _, _)| {
+                    (lo.min(*ts), hi.max(*ts))
+                });
+            let range = Self::session_range(rows, lo, hi, self.gap);
+            for (row, diff) in Self::rows_with_session(rows, range, self.gap) {
+                *changes.entry(row).or_insert(0) -= diff;
+            }
+            for (ts, row, diff) in updates {
+                let k = (ts, row);
+                let entry = rows.entry(k.clone()).or_insert(0);
+                *entry += diff;
+                if *entry == 0 {
+                    rows.remove(&k);
+                }
+            }
+            for (row, diff) in Self::rows_with_session(rows, range, self.gap) {
+                *changes.entry(row).or_insert(0) += diff;
+            }
+            if rows.is_empty() {
+                self.sessions.remove(&key);
+            }
+        }
+        changes
+            .into_iter()
+            .filter(|(_, diff)| *diff != 0)
+            .map(|((row, window), diff)| (row, window, diff))
+            .collect()
+    }
+
+    /// remove rows of sessions that are closed by `now`, intended for reducing memory usage
+    pub fn trunc_closed(&mut self, now: Timestamp) {
+        let Some(watermark) = self.watermark(now) else {
+            return;
+        };
+        let gap = self.gap;
+        self.sessions.retain(|_key, rows| {
+            // sessions are in ascending order, so only a prefix of them can be closed
+            let open_from = Self::split_sessions(rows.keys().map(|(ts, _row)| *ts), gap)
+                .into_iter()
+                .find(|(_start, end)| *end > watermark)
+                .map(|(start, _end)| start);
+            match open_from {
+                Some(start) => {
+                    let open = rows.split_off(&(start, Row::empty()));
+                    *rows = open;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// split ascending event timestamps of rows into sessions `[start, end)` in ascending order
+    fn split_sessions(
+        timestamps: impl IntoIterator<Item = Timestamp>,
+        gap: Duration,
+    ) -> Vec<(Timestamp, Timestamp)> {
+        let mut sessions: Vec<(Timestamp, Timestamp)> = Vec::new();
+        for ts in timestamps {
+            let end = ts.saturating_add(gap);
+            match sessions.last_mut() {
+                Some((_start, last_end)) if ts < *last_end => *last_end = end,
+                _ => sessions.push((ts, end)),
+            }
+        }
+        sessions
+    }
+
+    /// event timestamps `[first, last]` of rows in the sessions that rows in `[lo, hi]` may join
+    ///
+    /// rows before and after the range are at least `gap` away from it, so sessions out of
+    /// the range are not changed by updating rows in `[lo, hi]`
+    fn session_range(
+        rows: &SessionRows,
+        lo: Timestamp,
+        hi: Timestamp,
+        gap: Duration,
+    ) -> (Timestamp, Timestamp) {
+        let mut first = lo;
+        for (ts, _row) in rows.range(..(lo, Row::empty())).rev().map(|(k, _)| k) {
+            if first >= ts.saturating_add(gap) {
+                break;
+            }
+            first = *ts;
+        }
+        let mut last = hi;
+        for (ts, _row) in rows.range((hi, Row::empty())..).map(|(k, _)| k) {
+            if *ts >= last.saturating_add(gap) {
+                break;
+            }
+            last = last.max(*ts);
+        }
+        (first, last)
+    }
+
+    /// pair each row with event timestamp in `[first, last]` with the bounds of the session it
+    /// belongs to, the range must not split a session, see [`SessionWindows::session_range`]
+    fn rows_with_session(
+        rows: &SessionRows,
+        (first, last): (Timestamp, Timestamp),
+        gap: Duration,
+    ) -> Vec<((Row, (Timestamp, Timestamp)), Diff)> {
+        let rows = rows
+            .range((first, Row::empty())..)
+            .take_while(|((ts, _row), _diff)| *ts <= last)
+            .collect_vec();
+        let mut sessions = Self::split_sessions(rows.iter().map(|((ts, _row), _diff)| *ts), gap)
+            .into_iter()
+            .peekable();
+        let mut ret = Vec::with_capacity(rows.len());
+        for ((ts, row), diff) in rows {
+            while sessions.next_if(|(_start, end)| *ts >= *end).is_some() {}
+            if let Some(session) = sessions.peek() {
+                ret.push(((row.clone(), *session), *diff));
+            }
+        }
+        ret
+    }
+}
+
 /// A shared state of key-value pair for various state
 /// in dataflow execution
 ///
@@ -138,6 +435,8 @@ pub struct Arrangement {
     expire_state: Option<KeyExpiryManager>,
     /// the time that the last compaction happened, also know as current time
     last_compaction_time: Option<Timestamp>,
+    /// updates to expired keys kept aside by [`LateDataPolicy::SideOutput`], not part of the state
+    late_updates: Vec<KeyValDiffRow>,
 }
 
 impl Arrangement {
@@ -149,9 +448,15 @@ impl Arrangement {
             is_written: false,
             expire_state: None,
             last_compaction_time: None,
+            late_updates: Vec::new(),
         }
     }
 
+    /// take all late updates kept aside since last call, see [`LateDataPolicy::SideOutput`]
+    pub fn take_late_updates(&mut self) -> Vec<KeyValDiffRow> {
+        std::mem::take(&mut self.late_updates)
+    }
+
     /// apply updates into spine, all updates should have timestamps that are larger than spine's first key
     ///
     /// return the maximum expire time(already expire by how much time) of all updates if any keys is already expired
@@ -165,11 +470,18 @@ impl Arrangement {
             self.is_written = true;
         }
         for ((key, val), ts, diff) in updates {
-            // keep rows with expired event timestamp from being updated
+            // keep rows with expired event timestamp from being updated, unless asked to re-emit
             if let Some(s) = &mut self.expire_state {
                 if let Some(late_by) = s.update_event_ts(now, &key)? {
                     max_late_by = Some(max_late_by.map_or(late_by, |v| v.max(late_by)));
-                    continue;
+                    match s.late_data_policy {
+                        LateDataPolicy::Drop => continue,
+                        LateDataPolicy::SideOutput => {
+                            self.late_updates.push(((key, val), ts, diff));
+                            continue;
+                        }
+                        LateDataPolicy::Reemit => (),
+                    }
                 }
             }
 
@@ -277,7 +589,18 @@ impl Arrangement {
                 if let Some(s) = &mut self.expire_state {
                     if let Some(late_by) = s.update_event_ts(now, &key)? {
                         max_late_by = Some(max_late_by.map_or(late_by, |v| v.max(late_by)));
-                        continue;
+                        match s.late_data_policy {
+                            LateDataPolicy::Drop => continue,
+                            LateDataPolicy::SideOutput => {
+                                self.late_updates.extend(
+                                    updates
+                                        .into_iter()
+                                        .map(|(val, ts, diff)| ((key.clone(), val), ts, diff)),
+                                );
+                                continue;
+                            }
+                            LateDataPolicy::Reemit => (),
+                        }
                     }
                 }
                 // if diff cancel out each other, then remove the key
@@ -453,6 +776,85 @@ fn compact_diff_row(old_row: Option<DiffRow>, new_row: &DiffRow) -> Option<DiffR
     }
 }
 
+/// A serializable snapshot of an [`Arrangement`], used to checkpoint the state of
+/// a dataflow and restore it after restart
+///
+/// Maps keyed by rows are flattened into vectors, since formats like json only support string keys
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ArrangementCheckpoint {
+    /// flattened spine of the arrangement, in time -> (key -> (new_val, diff)) order
+    spine: Vec<(Timestamp, Vec<(Row, SmallVec<[DiffRow; 2]>)>)>,
+    /// see [`Arrangement::full_arrangement`]
+    full_arrangement: bool,
+    /// see [`Arrangement::is_written`]
+    is_written: bool,
+    /// see [`Arrangement::expire_state`]
+    expire_state: Option<KeyExpiryManager>,
+    /// see [`Arrangement::last_compaction_time`]
+    last_compaction_time: Option<Timestamp>,
+}
+
+impl ArrangementCheckpoint {
+    /// encode the checkpoint into bytes, so it can be persisted
+    pub fn encode(&self) -> Result<Vec<u8>, EvalError> {
+        serde_json::to_vec(self).map_err(|e| {
+            InternalSnafu {
+                reason: format!("Failed to encode arrangement checkpoint: {e}"),
+            }
+            .build()
+        })
+    }
+
+    /// decode the checkpoint from bytes created by [`ArrangementCheckpoint::encode`]
+    pub fn decode(bytes: &[u8]) -> Result<Self, EvalError> {
+        serde_json::from_slice(bytes).map_err(|e| {
+            InternalSnafu {
+                reason: format!("Failed to decode arrangement checkpoint: {e}"),
+            }
+            .build()
+        })
+    }
+}
+
+impl From<&Arrangement> for ArrangementCheckpoint {
+    fn from(arr: &Arrangement) -> Self {
+        Self {
+            spine: arr
+                .spine
+                .iter()
+                .map(|(ts, batch)| {
+                    let batch = batch
+                        .iter()
+                        .map(|(key, updates)| (key.clone(), updates.clone()))
+                        .collect();
+                    (*ts, batch)
+                })
+                .collect(),
+            full_arrangement: arr.full_arrangement,
+            is_written: arr.is_written,
+            expire_state: arr.expire_state.clone(),
+            last_compaction_time: arr.last_compaction_time,
+        }
+    }
+}
+
+impl From<ArrangementCheckpoint> for Arrangement {
+    fn from(checkpoint: ArrangementCheckpoint) -> Self {
+        Self {
+            spine: checkpoint
+                .spine
+                .into_iter()
+                .map(|(ts, batch)| (ts, batch.into_iter().collect()))
+                .collect(),
+            full_arrangement: checkpoint.full_arrangement,
+            is_written: checkpoint.is_written,
+            expire_state: checkpoint.expire_state,
+            last_compaction_time: checkpoint.last_compaction_time,
+            late_updates: Vec::new(),
+        }
+    }
+}
+
 /// A handler to the inner Arrangement, can be cloned and shared, useful for query it's inner state
 #[derive(Debug)]
 pub struct ArrangeHandler {
@@ -476,6 +878,18 @@ impl ArrangeHandler {
         self.inner.blocking_read()
     }
 
+    /// take a checkpoint of current state of the arrangement
+    pub fn checkpoint(&self) -> ArrangementCheckpoint {
+        ArrangementCheckpoint::from(&*self.read())
+    }
+
+    /// restore the arrangement from a checkpoint, replacing its current state
+    ///
+    /// all handlers sharing this arrangement will see the restored state
+    pub fn restore(&self, checkpoint: ArrangementCheckpoint) {
+        *self.write() = Arrangement::from(checkpoint);
+    }
+
     /// clone the handler, but only keep the future updates
     ///
     /// it's a cheap operation, since it's `Arc-ed` and only clone the `Arc`
@@ -535,6 +949,49 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_checkpoint_restore() {
+        let mut arr = Arrangement::new();
+        arr.full_arrangement = true;
+        let arr = ArrangeHandler::from(arr);
+        let key = Row::new(vec![1i64.into()]);
+        {
+            let mut arr = arr.write();
+            let updates: Vec<KeyValDiffRow> = vec![
+                ((key.clone(), Row::new(vec![2.into()])), 1, 1),
+                ((key.clone(), Row::new(vec![2.into()])), 2, -1),
+                ((key.clone(), Row::new(vec![3.into()])), 2, 1),
+                (
+                    (Row::new(vec![2i64.into()]), Row::new(vec![4.into()])),
+                    5,
+                    1,
+                ),
+            ];
+            arr.apply_updates(0, updates).unwrap();
+            arr.compaction_to(2).unwrap();
+        }
+
+        let bytes = arr.checkpoint().encode().unwrap();
+        let checkpoint = ArrangementCheckpoint::decode(&bytes).unwrap();
+        assert_eq!(checkpoint, arr.checkpoint());
+
+        let restored = ArrangeHandler::from(Arrangement::new());
+        restored.restore(checkpoint);
+        assert_eq!(*restored.read(), *arr.read());
+        assert_eq!(
+            restored.read().get(2, &key),
+            Some((Row::new(vec![3.into()]), 2, 1))
+        );
+        assert_eq!(
+            restored.read().get_updates_in_range(3..=5),
+            vec![(
+                (Row::new(vec![2i64.into()]), Row::new(vec![4.into()])),
+                5,
+                1
+            )]
+        );
+    }
+
     #[test]
     fn only_save_future_updates() {
         // mfp operator's temporal filter need to record future updates so that it can delete on time
@@ -582,6 +1039,7 @@ mod test {
             event_ts_to_key: Default::default(),
             key_expiration_duration: Some(10),
             event_timestamp_from_row: Some(ScalarExpr::Column(0)),
+            late_data_policy: LateDataPolicy::Drop,
         };
         let expire_state = Some(expire_state);
         arr.expire_state = expire_state;
@@ -647,6 +1105,7 @@ mod test {
             event_ts_to_key: Default::default(),
             key_expiration_duration: Some(10),
             event_timestamp_from_row: Some(ScalarExpr::Column(0)),
+            late_data_policy: LateDataPolicy::Drop,
         };
         let expire_state = Some(expire_state);
         arr.expire_state = expire_state;
@@ -693,6 +1152,144 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_late_data_policy() {
+        let late = (
+            (Row::new(vec![1i64.into()]), Row::new(vec![2.into()])),
+            1,
+            1,
+        );
+        let on_time = (
+            (Row::new(vec![5i64.into()]), Row::new(vec![3.into()])),
+            5,
+            1,
+        );
+        let new_arr = |policy| {
+            let mut arr = Arrangement::new();
+            arr.expire_state = Some(KeyExpiryManager::new(
+                LatenessOptions {
+                    allowed_lateness: Some(3),
+                    late_data_policy: policy,
+                },
+                Some(ScalarExpr::Column(0)),
+            ));
+            arr
+        };
+
+        let mut arr = new_arr(LateDataPolicy::Drop);
+        let late_by = arr
+            .apply_updates(5, vec![late.clone(), on_time.clone()])
+            .unwrap();
+        assert_eq!(late_by, Some(1));
+        assert_eq!(arr.get_updates_in_range(..), vec![on_time.clone()]);
+        assert!(arr.take_late_updates().is_empty());
+
+        let mut arr = new_arr(LateDataPolicy::SideOutput);
+        let late_by = arr
+            .apply_updates(5, vec![late.clone(), on_time.clone()])
+            .unwrap();
+        assert_eq!(late_by, Some(1));
+        assert_eq!(arr.get_updates_in_range(..), vec![on_time.clone()]);
+        assert_eq!(arr.take_late_updates(), vec![late.clone()]);
+        assert!(arr.take_late_updates().is_empty());
+
+        let mut arr = new_arr(LateDataPolicy::Reemit);
+        let late_by = arr
+            .apply_updates(5, vec![late.clone(), on_time.clone()])
+            .unwrap();
+        assert_eq!(late_by, Some(1));
+        assert_eq!(arr.get_updates_in_range(..), vec![late, on_time]);
+        assert!(arr.take_late_updates().is_empty());
+    }
+
+    #[test]
+    fn test_session_windows() {
+        let key = Row::new(vec![1i64.into()]);
+        let row = |ts: i64| Row::new(vec![ts.into()]);
+        let update = |ts: i64, diff| (key.clone(), ts, row(ts), diff);
+        let mut state = SessionWindows::new(
+            10,
+            LatenessOptions {
+                allowed_lateness: Some(20),
+                late_data_policy: LateDataPolicy::Drop,
+            },
+        );
+
+        let output = state.apply_updates(0, vec![update(0, 1), update(5, 1), update(20, 1)]);
+        assert_eq!(
+            output,
+            vec![
+                (row(0), (0, 15), 1),
+                (row(5), (0, 15), 1),
+                (row(20), (20, 30), 1),
+            ]
+        );
+
+        // fill the gap, so two sessions are merged into one
+        let merged = vec![
+            (row(0), (0, 15), -1),
+            (row(0), (0, 30), 1),
+            (row(5), (0, 15), -1),
+            (row(5), (0, 30), 1),
+            (row(12), (0, 30), 1),
+            (row(20), (0, 30), 1),
+            (row(20), (20, 30), -1),
+        ];
+        let output = state.apply_updates(0, vec![update(12, 1)]);
+        assert_eq!(output, merged);
+
+        // and split again when the row is deleted
+        let output = state.apply_updates(0, vec![update(12, -1)]);
+        let split = merged
+            .into_iter()
+            .map(|(row, window, diff)| (row, window, -diff))
+            .collect_vec();
+        assert_eq!(output, split);
+
+        // session [0, 15) is closed by `40 - 20`, so it's removed and late rows to it are dropped
+        state.trunc_closed(40);
+        assert_eq!(state.sessions[&key].len(), 1);
+        assert!(state.apply_updates(40, vec![update(10, 1)]).is_empty());
+        assert!(state.take_late_updates().is_empty());
+
+        let output = state.apply_updates(40, vec![update(25, 1)]);
+        assert_eq!(
+            output,
+            vec![
+                (row(20), (20, 30), -1),
+                (row(20), (20, 35), 1),
+                (row(25), (20, 35), 1),
+            ]
+        );
+
+        state.trunc_closed(100);
+        assert!(state.sessions.is_empty());
+    }
+
+    #[test]
+    fn test_lateness_options() {
+        let options = LatenessOptions::from_options(&HashMap::new()).unwrap();
+        assert_eq!(options, LatenessOptions::default());
+
+        let options = HashMap::from([
+            (ALLOWED_LATENESS_KEY.to_string(), "5m".to_string()),
+            (LATE_DATA_POLICY_KEY.to_string(), "side_output".to_string()),
+        ]);
+        let options = LatenessOptions::from_options(&options).unwrap();
+        assert_eq!(
+            options,
+            LatenessOptions {
+                allowed_lateness: Some(5 * 60 * 1000),
+                late_data_policy: LateDataPolicy::SideOutput,
+            }
+        );
+
+        let options = HashMap::from([(ALLOWED_LATENESS_KEY.to_string(), "5x".to_string())]);
+        assert!(LatenessOptions::from_options(&options).is_err());
+        let options = HashMap::from([(LATE_DATA_POLICY_KEY.to_string(), "ignore".to_string())]);
+        assert!(LatenessOptions::from_options(&options).is_err());
+    }
+
     /// test if split_lte get ranges that are not aligned with batch boundaries
     /// this split_lte can correctly retrieve all updates in the range, including updates that are in the batches
     /// near the boundary of input range
@@ -0,0 +1,615 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Audit log of the statements executed by the frontend.
+//!
+//! Statements are recorded asynchronously: the query path only enqueues a record.
+//! When the queue is full, the record is dropped and counted by default, so a slow
+//! or failing audit log never stalls requests, see [AuditLogQueueFullPolicy].
+
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use api::v1::value::ValueData;
+use api::v1::{
+    ColumnDataType, ColumnSchema, Row, RowInsertRequest, RowInsertRequests, Rows, SemanticType,
+    Value,
+};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_PRIVATE_SCHEMA_NAME};
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_query::{Output, OutputData};
+use common_telemetry::{debug, info, warn};
+use common_time::util::current_time_millis;
+use operator::insert::InserterRef;
+use query::metrics::OnDone;
+use serde::{Deserialize, Serialize};
+use session::context::{QueryContext, QueryContextRef};
+use snafu::ResultExt;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+use crate::error::{InitAuditLogSnafu, Result};
+use crate::instance::StatementExecutorRef;
+use crate::metrics::{AUDIT_LOG_DROPPED_RECORDS, AUDIT_LOG_RECORDS};
+
+/// Table in `greptime_private` that stores the audit log.
+pub const AUDIT_LOG_TABLE_NAME: &str = "audit_log";
+/// Prefix of the audit log files.
+const AUDIT_LOG_FILE_PREFIX: &str = "audit";
+/// Max records written in one batch.
+const BATCH_SIZE: usize = 256;
+/// Max time a record is buffered before it is written.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Type of statements that fail before they are parsed.
+pub const UNPARSED_STATEMENT_TYPE: &str = "Unparsed";
+
+/// Where the audit log is written to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLogTarget {
+    /// JSON lines in rotating files under `dir`.
+    #[default]
+    File,
+    /// The `greptime_private.audit_log` table.
+    Table,
+}
+
+/// How often the audit log file rotates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLogRotation {
+    Hourly,
+    #[default]
+    Daily,
+}
+
+/// How statements are redacted in the audit log.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLogRedaction {
+    /// Records statements as they are.
+    None,
+    /// Masks credentials like access keys in statements.
+    #[default]
+    Secrets,
+    /// Only records the type of statements.
+    Statement,
+}
+
+/// What a request does when the queue of pending audit records is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLogQueueFullPolicy {
+    /// Drops the record and counts it in the dropped records metric, so requests
+    /// never wait for the audit log.
+    #[default]
+    Drop,
+    /// Waits for space in the queue, so no record is lost, but every request stalls
+    /// while the audit log is slow or failing.
+    Block,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct AuditLogOptions {
+    pub enable: bool,
+    pub target: AuditLogTarget,
+    /// Directory of the audit log files.
+    pub dir: String,
+    pub rotation: AuditLogRotation,
+    /// Max audit log files to keep, keeps all files if it's 0.
+    pub max_files: usize,
+    /// Percentage of successful statements to record. Failed statements are always recorded.
+    pub sample_percent: u8,
+    pub redaction: AuditLogRedaction,
+    /// Capacity of the queue of pending records.
+    pub queue_size: usize,
+    pub queue_full_policy: AuditLogQueueFullPolicy,
+}
+
+impl Default for AuditLogOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            target: AuditLogTarget::default(),
+            dir: "/tmp/greptimedb/audit".to_string(),
+            rotation: AuditLogRotation::default(),
+            max_files: 30,
+            sample_percent: 100,
+            redaction: AuditLogRedaction::default(),
+            queue_size: 1024,
+            queue_full_policy: AuditLogQueueFullPolicy::default(),
+        }
+    }
+}
+
+/// A statement executed by the frontend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Time the statement finished in milliseconds.
+    pub timestamp: i64,
+    pub user: Option<String>,
+    pub client_addr: Option<String>,
+    pub catalog: String,
+    pub schema: String,
+    pub statement_type: String,
+    pub statement: Option<String>,
+    pub duration_ms: u64,
+    /// Rows affected or returned, `None` if the result is streamed.
+    pub affected_rows: Option<u64>,
+    /// Status code of the result, `0` on success.
+    pub result_code: u32,
+}
+
+pub type AuditLoggerRef = Arc<AuditLogger>;
+
+/// Records the executed statements to the audit log in the background.
+pub struct AuditLogger {
+    options: AuditLogOptions,
+    sender: Sender<AuditRecord>,
+    /// Number of successful statements seen, used for sampling.
+    successes: AtomicU64,
+}
+
+impl AuditLogger {
+    /// Starts the background task writing the audit log.
+    pub fn start(
+        options: AuditLogOptions,
+        inserter: InserterRef,
+        statement_executor: StatementExecutorRef,
+    ) -> Result<AuditLoggerRef> {
+        let (sender, receiver) = mpsc::channel(options.queue_size.max(1));
+        info!("Starting audit log, options: {:?}", options);
+        let writer = match options.target {
+            AuditLogTarget::File => AuditLogWriter::new_file(&options)?,
+            AuditLogTarget::Table => AuditLogWriter::Table {
+                inserter,
+                statement_executor,
+            },
+        };
+        let _handle = common_runtime::spawn_bg(writer.run(receiver));
+
+        Ok(Arc::new(Self {
+            options,
+            sender,
+            successes: AtomicU64::new(0),
+        }))
+    }
+
+    /// Records the `result` of a request started at `start` and returns it.
+    ///
+    /// A streamed output is recorded once the stream is terminated, so the duration
+    /// covers the execution of the request.
+    pub async fn audit<E: ErrorExt>(
+        &self,
+        statement_type: &str,
+        statement: Option<&str>,
+        query_ctx: &QueryContextRef,
+        start: Instant,
+        result: std::result::Result<Output, E>,
+    ) -> std::result::Result<Output, E> {
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                self.log(
+                    statement_type,
+                    statement,
+                    query_ctx,
+                    start.elapsed(),
+                    None,
+                    e.status_code(),
+                )
+                .await;
+                return Err(e);
+            }
+        };
+
+        let affected_rows = match &output.data {
+            OutputData::AffectedRows(rows) => Some(*rows as u64),
+            OutputData::RecordBatches(batches) => Some(
+                batches
+                    .iter()
+                    .map(|batch| batch.num_rows() as u64)
+                    .sum::<u64>(),
+            ),
+            OutputData::Stream(_) => None,
+        };
+        if affected_rows.is_none() {
+            if !self.sampled() {
+                return Ok(output);
+            }
+            let record = new_record(
+                statement_type,
+                statement.and_then(|statement| self.redact(statement)),
+                query_ctx,
+                Duration::ZERO,
+                None,
+                StatusCode::Success,
+            );
+            return Ok(self.audit_stream(record, start, output));
+        }
+
+        self.log(
+            statement_type,
+            statement,
+            query_ctx,
+            start.elapsed(),
+            affected_rows,
+            StatusCode::Success,
+        )
+        .await;
+        Ok(output)
+    }
+
+    /// Records a request of `statement_type` finished with `status_code` if it's sampled.
+    pub async fn log(
+        &self,
+        statement_type: &str,
+        statement: Option<&str>,
+        query_ctx: &QueryContextRef,
+        elapsed: Duration,
+        affected_rows: Option<u64>,
+        status_code: StatusCode,
+    ) {
+        if status_code == StatusCode::Success && !self.sampled() {
+            return;
+        }
+
+        let record = new_record(
+            statement_type,
+            statement.and_then(|statement| self.redact(statement)),
+            query_ctx,
+            elapsed,
+            affected_rows,
+            status_code,
+        );
+        send_record(&self.sender, self.options.queue_full_policy, record).await;
+    }
+
+    /// Sends the `record` once the stream of the `output` is terminated.
+    fn audit_stream(&self, mut record: AuditRecord, start: Instant, output: Output) -> Output {
+        let OutputData::Stream(stream) = output.data else {
+            return output;
+        };
+        let sender = self.sender.clone();
+        let policy = self.options.queue_full_policy;
+        let stream = OnDone::new(stream, move || {
+            record.timestamp = current_time_millis();
+            record.duration_ms = start.elapsed().as_millis() as u64;
+            let _handle = common_runtime::spawn_bg(async move {
+                send_record(&sender, policy, record).await;
+            });
+        });
+        Output::new(OutputData::Stream(Box::pin(stream)), output.meta)
+    }
+
+    /// Samples `sample_percent` of every 100 successful statements.
+    fn sampled(&self) -> bool {
+        let n = self.successes.fetch_add(1, Ordering::Relaxed);
+        n % 100 < self.options.sample_percent as u64
+    }
+
+    fn redact(&self, statement: &str) -> Option<String> {
+        match self.options.redaction {
+            AuditLogRedaction::None => Some(statement.to_string()),
+            AuditLogRedaction::Secrets => Some(sql::util::redact_sql_secrets(statement)),
+            AuditLogRedaction::Statement => None,
+        }
+    }
+}
+
+/// Sends the `record` to the audit log task, handling a full queue by the `policy`.
+async fn send_record(
+    sender: &Sender<AuditRecord>,
+    policy: AuditLogQueueFullPolicy,
+    record: AuditRecord,
+) {
+    let result = match policy {
+        AuditLogQueueFullPolicy::Drop => sender.try_send(record),
+        AuditLogQueueFullPolicy::Block => {
+            if sender.capacity() == 0 {
+                debug!("The audit log queue is full, wait for space");
+            }
+            sender
+                .send(record)
+                .await
+                .map_err(|e| TrySendError::Closed(e.0))
+        }
+    };
+    match result {
+        Ok(()) => AUDIT_LOG_RECORDS.inc(),
+        Err(TrySendError::Full(record)) => {
+            debug!("The audit log queue is full, drop the record {:?}", record);
+            AUDIT_LOG_DROPPED_RECORDS.inc();
+        }
+        Err(TrySendError::Closed(record)) => {
+            warn!(
+                "The audit log task is stopped, drop the record {:?}",
+                record
+            );
+            AUDIT_LOG_DROPPED_RECORDS.inc();
+        }
+    }
+}
+
+fn new_record(
+    statement_type: &str,
+    statement: Option<String>,
+    query_ctx: &QueryContextRef,
+    elapsed: Duration,
+    affected_rows: Option<u64>,
+    status_code: StatusCode,
+) -> AuditRecord {
+    AuditRecord {
+        timestamp: current_time_millis(),
+        user: query_ctx
+            .current_user()
+            .map(|user| user.username().to_string()),
+        client_addr: query_ctx
+            .conn_info()
+            .and_then(|conn_info| conn_info.client_addr)
+            .map(|addr| addr.to_string()),
+        catalog: query_ctx.current_catalog().to_string(),
+        schema: query_ctx.current_schema().to_string(),
+        statement_type: statement_type.to_string(),
+        statement,
+        duration_ms: elapsed.as_millis() as u64,
+        affected_rows,
+        result_code: status_code as u32,
+    }
+}
+
+enum AuditLogWriter {
+    File {
+        writer: NonBlocking,
+        _guard: WorkerGuard,
+    },
+    Table {
+        inserter: InserterRef,
+        statement_executor: StatementExecutorRef,
+    },
+}
+
+impl AuditLogWriter {
+    fn new_file(options: &AuditLogOptions) -> Result<Self> {
+        let rotation = match options.rotation {
+            AuditLogRotation::Hourly => Rotation::HOURLY,
+            AuditLogRotation::Daily => Rotation::DAILY,
+        };
+        let mut builder = RollingFileAppender::builder()
+            .rotation(rotation)
+            .filename_prefix(AUDIT_LOG_FILE_PREFIX);
+        if options.max_files > 0 {
+            builder = builder.max_log_files(options.max_files);
+        }
+        let appender = builder
+            .build(&options.dir)
+            .context(InitAuditLogSnafu { dir: &options.dir })?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+
+        Ok(AuditLogWriter::File {
+            writer,
+            _guard: guard,
+        })
+    }
+
+    async fn run(mut self, mut receiver: Receiver<AuditRecord>) {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        let mut pending = Vec::with_capacity(BATCH_SIZE);
+        loop {
+            tokio::select! {
+                record = receiver.recv() => {
+                    let Some(record) = record else {
+                        break;
+                    };
+                    pending.push(record);
+                    if pending.len() >= BATCH_SIZE {
+                        self.write(std::mem::take(&mut pending)).await;
+                    }
+                }
+                _ = interval.tick() => {
+                    if !pending.is_empty() {
+                        self.write(std::mem::take(&mut pending)).await;
+                    }
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            self.write(pending).await;
+        }
+        info!("The audit log task is stopped");
+    }
+
+    async fn write(&mut self, records: Vec<AuditRecord>) {
+        match self {
+            AuditLogWriter::File { writer, .. } => {
+                for record in records {
+                    let mut line = match serde_json::to_vec(&record) {
+                        Ok(line) => line,
+                        Err(e) => {
+                            warn!(e; "Failed to encode audit record {:?}", record);
+                            continue;
+                        }
+                    };
+                    line.push(b'\n');
+                    if let Err(e) = writer.write_all(&line) {
+                        warn!(e; "Failed to write audit log");
+                    }
+                }
+            }
+            AuditLogWriter::Table {
+                inserter,
+                statement_executor,
+            } => {
+                let num_records = records.len();
+                let requests = RowInsertRequests {
+                    inserts: vec![new_row_insert_request(records)],
+                };
+                let ctx = QueryContext::with(DEFAULT_CATALOG_NAME, DEFAULT_PRIVATE_SCHEMA_NAME);
+                if let Err(e) = inserter
+                    .handle_row_inserts(requests, ctx, statement_executor.as_ref())
+                    .await
+                {
+                    warn!(e; "Failed to write {} records to the audit log table", num_records);
+                    AUDIT_LOG_DROPPED_RECORDS.inc_by(num_records as u64);
+                }
+            }
+        }
+    }
+}
+
+fn new_row_insert_request(records: Vec<AuditRecord>) -> RowInsertRequest {
+    let column = |name: &str, datatype: ColumnDataType, semantic_type: SemanticType| ColumnSchema {
+        column_name: name.to_string(),
+        datatype: datatype as i32,
+        semantic_type: semantic_type as i32,
+        ..Default::default()
+    };
+    let schema = vec![
+        column(
+            "ts",
+            ColumnDataType::TimestampMillisecond,
+            SemanticType::Timestamp,
+        ),
+        column("catalog", ColumnDataType::String, SemanticType::Tag),
+        column("schema", ColumnDataType::String, SemanticType::Tag),
+        column("user", ColumnDataType::String, SemanticType::Field),
+        column("client_addr", ColumnDataType::String, SemanticType::Field),
+        column(
+            "statement_type",
+            ColumnDataType::String,
+            SemanticType::Field,
+        ),
+        column("statement", ColumnDataType::String, SemanticType::Field),
+        column("duration_ms", ColumnDataType::Uint64, SemanticType::Field),
+        column("affected_rows", ColumnDataType::Uint64, SemanticType::Field),
+        column("result_code", ColumnDataType::Uint32, SemanticType::Field),
+    ];
+
+    let value = |value_data: Option<ValueData>| Value { value_data };
+    let rows = records
+        .into_iter()
+        .map(|record| Row {
+            values: vec![
+                value(Some(ValueData::TimestampMillisecondValue(record.timestamp))),
+                value(Some(ValueData::StringValue(record.catalog))),
+                value(Some(ValueData::StringValue(record.schema))),
+                value(record.user.map(ValueData::StringValue)),
+                value(record.client_addr.map(ValueData::StringValue)),
+                value(Some(ValueData::StringValue(record.statement_type))),
+                value(record.statement.map(ValueData::StringValue)),
+                value(Some(ValueData::U64Value(record.duration_ms))),
+                value(record.affected_rows.map(ValueData::U64Value)),
+                value(Some(ValueData::U32Value(record.result_code))),
+            ],
+        })
+        .collect();
+
+    RowInsertRequest {
+        table_name: AUDIT_LOG_TABLE_NAME.to_string(),
+        rows: Some(Rows { schema, rows }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_record() {
+        let query_ctx = QueryContext::arc();
+        let record = new_record(
+            "Insert",
+            Some("INSERT INTO t VALUES (1)".to_string()),
+            &query_ctx,
+            Duration::from_millis(42),
+            Some(3),
+            StatusCode::Success,
+        );
+        assert_eq!(
+            AuditRecord {
+                timestamp: record.timestamp,
+                user: None,
+                client_addr: None,
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: "public".to_string(),
+                statement_type: "Insert".to_string(),
+                statement: Some("INSERT INTO t VALUES (1)".to_string()),
+                duration_ms: 42,
+                affected_rows: Some(3),
+                result_code: StatusCode::Success as u32,
+            },
+            record
+        );
+
+        let request = new_row_insert_request(vec![record]);
+        assert_eq!(AUDIT_LOG_TABLE_NAME, request.table_name);
+        let rows = request.rows.unwrap();
+        assert_eq!(rows.schema.len(), rows.rows[0].values.len());
+    }
+
+    #[test]
+    fn test_deserialize_options() {
+        let options: AuditLogOptions = toml::from_str(
+            r#"
+            enable = true
+            target = "table"
+            rotation = "hourly"
+            redaction = "statement"
+            "#,
+        )
+        .unwrap();
+        assert!(options.enable);
+        assert_eq!(AuditLogTarget::Table, options.target);
+        assert_eq!(AuditLogRotation::Hourly, options.rotation);
+        assert_eq!(AuditLogRedaction::Statement, options.redaction);
+        assert_eq!(100, options.sample_percent);
+        assert_eq!(AuditLogQueueFullPolicy::Drop, options.queue_full_policy);
+    }
+
+    #[tokio::test]
+    async fn test_send_record_to_full_queue() {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let record = new_record(
+            "Query",
+            None,
+            &QueryContext::arc(),
+            Duration::ZERO,
+            None,
+            StatusCode::Success,
+        );
+        send_record(&sender, AuditLogQueueFullPolicy::Drop, record.clone()).await;
+        // The queue is full, so the record is dropped instead of waiting.
+        let dropped = AUDIT_LOG_DROPPED_RECORDS.get();
+        send_record(&sender, AuditLogQueueFullPolicy::Drop, record.clone()).await;
+        assert!(AUDIT_LOG_DROPPED_RECORDS.get() > dropped);
+        assert_eq!(record, receiver.recv().await.unwrap());
+        assert!(receiver.try_recv().is_err());
+
+        // The block policy waits until the queue has space.
+        send_record(&sender, AuditLogQueueFullPolicy::Block, record.clone()).await;
+        let blocked = send_record(&sender, AuditLogQueueFullPolicy::Block, record.clone());
+        tokio::pin!(blocked);
+        assert!(futures::poll!(&mut blocked).is_pending());
+        assert_eq!(record, receiver.recv().await.unwrap());
+        blocked.await;
+        assert_eq!(record, receiver.recv().await.unwrap());
+    }
+}
@@ -50,6 +50,18 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to check privileges"))]
+    CheckPrivilege {
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("Failed to initialize users of the user provider"))]
+    InitUsers {
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
     #[snafu(display("Failed to query"))]
     RequestQuery {
         location: Location,
@@ -266,6 +278,14 @@ pub enum Error {
         #[snafu(source)]
         error: toml::ser::Error,
     },
+
+    #[snafu(display("Failed to create audit log in {}", dir))]
+    InitAuditLog {
+        dir: String,
+        #[snafu(source)]
+        error: tracing_appender::rolling::InitError,
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -313,9 +333,12 @@ impl ErrorExt for Error {
 
             Error::OpenRaftEngineBackend { .. } => StatusCode::StorageUnavailable,
 
-            Error::RequestQuery { source, .. } => source.status_code(),
+            Error::RequestQuery { source, .. }
+            | Error::CheckPrivilege { source, .. }
+            | Error::InitUsers { source, .. } => source.status_code(),
 
             Error::FindDatanode { .. }
+            | Error::InitAuditLog { .. }
             | Error::VectorToGrpcColumn { .. }
             | Error::InvalidRegionRequest { .. } => StatusCode::Internal,
 
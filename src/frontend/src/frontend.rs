@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
+use catalog::slow_queries::SlowQueryOptions;
 use common_telemetry::logging::LoggingOptions;
 use meta_client::MetaClientOptions;
+use query::query_engine::options::QueryEngineOptions;
 use serde::{Deserialize, Serialize};
 use servers::export_metrics::ExportMetricsOption;
 use servers::heartbeat_options::HeartbeatOptions;
@@ -21,10 +25,11 @@ use servers::http::HttpOptions;
 use servers::Mode;
 use snafu::prelude::*;
 
+use crate::audit::AuditLogOptions;
 use crate::error::{Result, TomlFormatSnafu};
 use crate::service_config::{
     DatanodeOptions, GrpcOptions, InfluxdbOptions, MysqlOptions, OpentsdbOptions, OtlpOptions,
-    PostgresOptions, PromStoreOptions,
+    PostgresOptions, PromStoreOptions, QueryCacheOptions,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -33,6 +38,9 @@ pub struct FrontendOptions {
     pub mode: Mode,
     pub node_id: Option<String>,
     pub default_timezone: Option<String>,
+    /// Max time to wait for in-flight queries to finish on shutdown.
+    #[serde(with = "humantime_serde")]
+    pub shutdown_timeout: Duration,
     pub heartbeat: HeartbeatOptions,
     pub http: HttpOptions,
     pub grpc: GrpcOptions,
@@ -42,11 +50,15 @@ pub struct FrontendOptions {
     pub influxdb: InfluxdbOptions,
     pub prom_store: PromStoreOptions,
     pub otlp: OtlpOptions,
+    pub query: QueryEngineOptions,
     pub meta_client: Option<MetaClientOptions>,
     pub logging: LoggingOptions,
     pub datanode: DatanodeOptions,
     pub user_provider: Option<String>,
     pub export_metrics: ExportMetricsOption,
+    pub query_cache: QueryCacheOptions,
+    pub audit_log: AuditLogOptions,
+    pub slow_query: SlowQueryOptions,
 }
 
 impl Default for FrontendOptions {
@@ -55,6 +67,7 @@ impl Default for FrontendOptions {
             mode: Mode::Standalone,
             node_id: None,
             default_timezone: None,
+            shutdown_timeout: Duration::from_secs(30),
             heartbeat: HeartbeatOptions::frontend_default(),
             http: HttpOptions::default(),
             grpc: GrpcOptions::default(),
@@ -64,11 +77,15 @@ impl Default for FrontendOptions {
             influxdb: InfluxdbOptions::default(),
             prom_store: PromStoreOptions::default(),
             otlp: OtlpOptions::default(),
+            query: QueryEngineOptions::default(),
             meta_client: None,
             logging: LoggingOptions::default(),
             datanode: DatanodeOptions::default(),
             user_provider: None,
             export_metrics: ExportMetricsOption::default(),
+            query_cache: QueryCacheOptions::default(),
+            audit_log: AuditLogOptions::default(),
+            slow_query: SlowQueryOptions::default(),
         }
     }
 }
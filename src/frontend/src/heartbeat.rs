@@ -20,7 +20,7 @@ use common_meta::heartbeat::handler::{
 };
 use common_meta::heartbeat::mailbox::{HeartbeatMailbox, MailboxRef, OutgoingMessage};
 use common_meta::heartbeat::utils::outgoing_message_to_mailbox_message;
-use common_telemetry::{debug, error, info};
+use common_telemetry::{debug, error, info, warn};
 use meta_client::client::{HeartbeatSender, HeartbeatStream, MetaClient};
 use servers::heartbeat_options::HeartbeatOptions;
 use snafu::ResultExt;
@@ -39,6 +39,7 @@ pub struct HeartbeatTask {
     report_interval: u64,
     retry_interval: u64,
     resp_handler_executor: HeartbeatResponseHandlerExecutorRef,
+    degraded_start: bool,
 }
 
 impl HeartbeatTask {
@@ -52,10 +53,35 @@ impl HeartbeatTask {
             report_interval: heartbeat_opts.interval.as_millis() as u64,
             retry_interval: heartbeat_opts.retry_interval.as_millis() as u64,
             resp_handler_executor,
+            degraded_start: false,
+        }
+    }
+
+    /// Allows the frontend to start while metasrv is unavailable, the heartbeat
+    /// connection is established in the background once metasrv is back.
+    pub fn with_degraded_start(self, degraded_start: bool) -> Self {
+        Self {
+            degraded_start,
+            ..self
         }
     }
 
     pub async fn start(&self) -> Result<()> {
+        match self.connect().await {
+            Err(e) if self.degraded_start => {
+                warn!(e; "Metasrv is unavailable, starting frontend in degraded mode");
+                let capture_self = self.clone();
+                let retry_interval = Duration::from_millis(self.retry_interval);
+                let _handle = common_runtime::spawn_bg(async move {
+                    capture_self.start_with_retry(retry_interval).await;
+                });
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    async fn connect(&self) -> Result<()> {
         let (req_sender, resp_stream) = self
             .meta_client
             .heartbeat()
@@ -169,7 +195,7 @@ impl HeartbeatTask {
 
             info!("Try to re-establish the heartbeat connection to metasrv.");
 
-            if self.start().await.is_ok() {
+            if self.connect().await.is_ok() {
                 break;
             }
         }
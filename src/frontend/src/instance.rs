@@ -17,16 +17,20 @@ mod grpc;
 mod influxdb;
 mod opentsdb;
 mod otlp;
+mod privilege;
 mod prom_store;
 mod region_query;
 mod script;
 pub mod standalone;
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use api::prom_store::remote::Query as RemoteQuery;
 use api::v1::meta::Role;
 use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use catalog::process_manager::{ProcessManagerRef, Ticket};
 use catalog::CatalogManagerRef;
 use client::OutputData;
 use common_base::Plugins;
@@ -40,14 +44,16 @@ use common_procedure::local::{LocalManager, ManagerConfig};
 use common_procedure::options::ProcedureConfig;
 use common_procedure::ProcedureManagerRef;
 use common_query::Output;
-use common_telemetry::{debug, error, info, tracing};
+use common_telemetry::{debug, error, info, tracing, warn};
 use log_store::raft_engine::RaftEngineBackend;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOptions;
 use operator::delete::DeleterRef;
 use operator::insert::InserterRef;
+use operator::quota::QuotaManagerRef;
 use operator::statement::StatementExecutor;
 use prometheus::HistogramTimer;
+use query::dist_plan::ScanBytesLimit;
 use query::metrics::OnDone;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::plan::LogicalPlan;
@@ -61,6 +67,7 @@ use servers::export_metrics::ExportMetricsTask;
 use servers::interceptor::{
     PromQueryInterceptor, PromQueryInterceptorRef, SqlQueryInterceptor, SqlQueryInterceptorRef,
 };
+use servers::prom_store::exemplar_table_name;
 use servers::prometheus_handler::PrometheusHandler;
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
@@ -77,9 +84,11 @@ use sql::parser::{ParseOptions, ParserContext};
 use sql::statements::copy::{CopyDatabase, CopyTable};
 use sql::statements::statement::Statement;
 use sqlparser::ast::ObjectName;
-pub use standalone::StandaloneDatanodeManager;
+pub use standalone::{StandaloneDatanodeManager, StandaloneInformationExtension};
+use table::metadata::TableVersion;
 
 use self::prom_store::ExportMetricHandler;
+use crate::audit::{AuditLoggerRef, UNPARSED_STATEMENT_TYPE};
 use crate::error::{
     self, Error, ExecLogicalPlanSnafu, ExecutePromqlSnafu, ExternalSnafu, ParseSqlSnafu,
     PermissionSnafu, PlanStatementSnafu, Result, SqlExecInterceptedSnafu, StartServerSnafu,
@@ -87,6 +96,7 @@ use crate::error::{
 };
 use crate::frontend::{FrontendOptions, TomlSerializable};
 use crate::heartbeat::HeartbeatTask;
+use crate::instance::privilege::promql_privileges;
 use crate::script::ScriptExecutor;
 
 #[async_trait]
@@ -122,6 +132,11 @@ pub struct Instance {
     deleter: DeleterRef,
     export_metrics_task: Option<ExportMetricsTask>,
     table_metadata_manager: TableMetadataManagerRef,
+    process_manager: Option<ProcessManagerRef>,
+    quota_manager: QuotaManagerRef,
+    audit_logger: Option<AuditLoggerRef>,
+    /// Max time to wait for in-flight queries to finish on shutdown.
+    shutdown_timeout: Duration,
 }
 
 impl Instance {
@@ -191,6 +206,23 @@ impl Instance {
         Ok((kv_backend, procedure_manager))
     }
 
+    /// Opens the local metadata snapshot in `dir`, which serves reads while metasrv
+    /// is unavailable.
+    pub fn open_metadata_snapshot(dir: String) -> Result<KvBackendRef> {
+        let kv_backend_config = KvBackendConfig::default();
+        let kv_backend = RaftEngineBackend::try_open_with_cfg(Config {
+            dir,
+            purge_threshold: ReadableSize(kv_backend_config.purge_threshold.0),
+            recovery_mode: RecoveryMode::TolerateTailCorruption,
+            target_file_size: ReadableSize(kv_backend_config.file_size.0),
+            ..Default::default()
+        })
+        .map_err(BoxedError::new)
+        .context(error::OpenRaftEngineBackendSnafu)?;
+
+        Ok(Arc::new(kv_backend))
+    }
+
     pub fn build_servers(
         &mut self,
         opts: impl Into<FrontendOptions> + TomlSerializable,
@@ -201,6 +233,7 @@ impl Instance {
             ExportMetricsTask::try_new(&opts.export_metrics, Some(&self.plugins))
                 .context(StartServerSnafu)?;
 
+        self.shutdown_timeout = opts.shutdown_timeout;
         self.servers = servers;
         Ok(())
     }
@@ -215,11 +248,39 @@ impl Instance {
 
     pub async fn shutdown(&self) -> Result<()> {
         self.servers
-            .shutdown_all()
+            .shutdown_all_gracefully(self.wait_for_running_queries())
             .await
             .context(error::ShutdownServerSnafu)
     }
 
+    /// Waits for running queries to finish, at most for `shutdown_timeout`.
+    async fn wait_for_running_queries(&self) {
+        let Some(process_manager) = &self.process_manager else {
+            return;
+        };
+
+        let wait = async {
+            loop {
+                let num_running = process_manager.list().len();
+                if num_running == 0 {
+                    return;
+                }
+                debug!("Waiting for {} running queries to finish", num_running);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        };
+        if tokio::time::timeout(self.shutdown_timeout, wait)
+            .await
+            .is_err()
+        {
+            warn!(
+                "{} queries are still running after {:?}, shutting down anyway",
+                process_manager.list().len(),
+                self.shutdown_timeout
+            );
+        }
+    }
+
     pub fn server_handlers(&self) -> &ServerHandlers {
         &self.servers
     }
@@ -263,8 +324,79 @@ fn parse_stmt(sql: &str, dialect: &(dyn Dialect + Send + Sync)) -> Result<Vec<St
 }
 
 impl Instance {
+    /// Records the `result` of a request started at `start` to the audit log if it's
+    /// enabled, and returns the result.
+    pub(crate) async fn audit<E: ErrorExt>(
+        &self,
+        statement_type: &str,
+        statement: Option<&str>,
+        query_ctx: &QueryContextRef,
+        start: Instant,
+        result: std::result::Result<Output, E>,
+    ) -> std::result::Result<Output, E> {
+        match &self.audit_logger {
+            Some(audit_logger) => {
+                audit_logger
+                    .audit(statement_type, statement, query_ctx, start, result)
+                    .await
+            }
+            None => result,
+        }
+    }
+
+    /// Registers the query so it shows in the processlist and can be killed, and
+    /// applies the quotas of the database to it.
+    ///
+    /// Returns `None` if the query runs untracked. The process manager checks
+    /// `max_concurrent_queries` when it registers the query, so frontends without a
+    /// process manager don't limit concurrent queries.
+    async fn register_query(
+        &self,
+        query: String,
+        query_ctx: &QueryContextRef,
+    ) -> Result<Option<Arc<Ticket>>> {
+        let quota = self
+            .quota_manager
+            .quota(query_ctx.current_catalog(), query_ctx.current_schema())
+            .await
+            .context(TableOperationSnafu)?;
+        if let Some(max_scan_bytes) = quota.max_scan_bytes {
+            let _ = query_ctx
+                .set_typed_extension(Arc::new(ScanBytesLimit::new(max_scan_bytes.as_bytes())));
+        }
+
+        let Some(process_manager) = &self.process_manager else {
+            return Ok(None);
+        };
+        let result = process_manager
+            .register(
+                query_ctx.current_catalog(),
+                query_ctx.current_schema(),
+                query_ctx
+                    .current_user()
+                    .map(|user| user.username().to_string()),
+                query,
+                quota.max_concurrent_queries,
+            )
+            .await;
+        let ticket = match result {
+            Ok(ticket) => Arc::new(ticket),
+            Err(e @ catalog::error::Error::TooManyQueries { .. }) => {
+                return Err(e).context(error::CatalogSnafu);
+            }
+            Err(e) => {
+                warn!(e; "Failed to register query, run it untracked");
+                return Ok(None);
+            }
+        };
+        // The query engine records bytes the query reads in the stats.
+        let _ = query_ctx.set_typed_extension(ticket.stats().clone());
+        Ok(Some(ticket))
+    }
+
     async fn query_statement(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
         check_permission(self.plugins.clone(), &stmt, &query_ctx)?;
+        self.check_privileges(&stmt, &query_ctx).await?;
 
         let stmt = QueryStatement::Sql(stmt);
         self.statement_executor
@@ -280,27 +412,82 @@ impl SqlQueryHandler for Instance {
 
     #[tracing::instrument(skip_all)]
     async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
+        let received = Instant::now();
         let query_interceptor_opt = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
         let query_interceptor = query_interceptor_opt.as_ref();
         let query = match query_interceptor.pre_parsing(query, query_ctx.clone()) {
             Ok(q) => q,
-            Err(e) => return vec![Err(e)],
+            Err(e) => {
+                let result = self
+                    .audit(
+                        UNPARSED_STATEMENT_TYPE,
+                        Some(query),
+                        &query_ctx,
+                        received,
+                        Err(e),
+                    )
+                    .await;
+                return vec![result];
+            }
         };
 
         let checker_ref = self.plugins.get::<PermissionCheckerRef>();
         let checker = checker_ref.as_ref();
 
+        let parse_start = Instant::now();
         match parse_stmt(query.as_ref(), query_ctx.sql_dialect())
             .and_then(|stmts| query_interceptor.post_parsing(stmts, query_ctx.clone()))
         {
             Ok(stmts) => {
+                let parse_elapsed = parse_start.elapsed();
+                // Audits each statement with its own text if the query can be split into
+                // the parsed statements.
+                let texts = sql::util::split_statements(query.as_ref())
+                    .filter(|texts| texts.len() == stmts.len());
+                let text_of = |index: usize| match &texts {
+                    Some(texts) => texts[index].as_str(),
+                    None => query.as_ref(),
+                };
+
+                let ticket = match self
+                    .register_query(sql::util::redact_sql_secrets(query.as_ref()), &query_ctx)
+                    .await
+                {
+                    Ok(ticket) => ticket,
+                    Err(e) => {
+                        let stmt_type = stmts
+                            .first()
+                            .map_or(UNPARSED_STATEMENT_TYPE, |stmt| stmt.into());
+                        let result = self
+                            .audit(
+                                stmt_type,
+                                Some(query.as_ref()),
+                                &query_ctx,
+                                received,
+                                Err(e),
+                            )
+                            .await;
+                        return vec![result];
+                    }
+                };
+                if let Some(ticket) = &ticket {
+                    ticket.stats().add_parse_elapsed(parse_elapsed);
+                }
+
                 let mut results = Vec::with_capacity(stmts.len());
-                for stmt in stmts {
+                for (index, stmt) in stmts.into_iter().enumerate() {
+                    let start = Instant::now();
+                    let stmt_type: &'static str = (&stmt).into();
+                    let stmt_text = text_of(index);
+
                     // TODO(sunng87): figure out at which stage we can call
                     // this hook after ArrowFlight adoption. We need to provide
                     // LogicalPlan as to this hook.
                     if let Err(e) = query_interceptor.pre_execute(&stmt, None, query_ctx.clone()) {
-                        results.push(Err(e));
+                        let result = self
+                            .audit(stmt_type, Some(stmt_text), &query_ctx, start, Err(e))
+                            .await;
+                        results.push(result);
                         break;
                     }
 
@@ -311,11 +498,29 @@ impl SqlQueryHandler for Instance {
                         )
                         .context(PermissionSnafu)
                     {
-                        results.push(Err(e));
+                        let result = self
+                            .audit(stmt_type, Some(stmt_text), &query_ctx, start, Err(e))
+                            .await;
+                        results.push(result);
                         break;
                     }
 
-                    match self.query_statement(stmt, query_ctx.clone()).await {
+                    let result = match &ticket {
+                        Some(ticket) => ticket
+                            .run(self.query_statement(stmt, query_ctx.clone()))
+                            .await
+                            .context(error::CatalogSnafu)
+                            .and_then(|result| result)
+                            .map(|output| {
+                                ticket.stats().add_plan_elapsed(start.elapsed());
+                                track_output(ticket, output)
+                            }),
+                        None => self.query_statement(stmt, query_ctx.clone()).await,
+                    };
+                    let result = self
+                        .audit(stmt_type, Some(stmt_text), &query_ctx, start, result)
+                        .await;
+                    match result {
                         Ok(output) => {
                             let output_result =
                                 query_interceptor.post_execute(output, query_ctx.clone());
@@ -337,18 +542,36 @@ impl SqlQueryHandler for Instance {
                 results
             }
             Err(e) => {
-                vec![Err(e)]
+                let result = self
+                    .audit(
+                        UNPARSED_STATEMENT_TYPE,
+                        Some(query.as_ref()),
+                        &query_ctx,
+                        received,
+                        Err(e),
+                    )
+                    .await;
+                vec![result]
             }
         }
     }
 
     async fn do_exec_plan(&self, plan: LogicalPlan, query_ctx: QueryContextRef) -> Result<Output> {
+        let ticket = self
+            .register_query(plan.display_indent().to_string(), &query_ctx)
+            .await?;
         // plan should be prepared before exec
         // we'll do check there
-        self.query_engine
-            .execute(plan, query_ctx)
-            .await
-            .context(ExecLogicalPlanSnafu)
+        let execute = self.query_engine.execute(plan, query_ctx);
+        match &ticket {
+            Some(ticket) => ticket
+                .run(execute)
+                .await
+                .context(error::CatalogSnafu)?
+                .context(ExecLogicalPlanSnafu)
+                .map(|output| track_output(ticket, output)),
+            None => execute.await.context(ExecLogicalPlanSnafu),
+        }
     }
 
     #[tracing::instrument(skip_all)]
@@ -403,6 +626,43 @@ impl SqlQueryHandler for Instance {
             .await
             .context(error::CatalogSnafu)
     }
+
+    async fn table_version(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+    ) -> Result<Option<TableVersion>> {
+        let table = self
+            .catalog_manager
+            .table(catalog, schema, table)
+            .await
+            .context(error::CatalogSnafu)?;
+        Ok(table.map(|table| table.table_info().ident.version))
+    }
+}
+
+/// Records the rows and the plan of the `output` in the stats of the query. A stream
+/// holds the `ticket` until it is exhausted, so the query stays in the processlist.
+fn track_output(ticket: &Arc<Ticket>, output: Output) -> Output {
+    if let Some(plan) = &output.meta.plan {
+        ticket.stats().set_plan(plan.clone());
+    }
+    match output.data {
+        OutputData::Stream(stream) => Output::new(
+            OutputData::Stream(ticket.clone().wrap_stream(stream)),
+            output.meta,
+        ),
+        OutputData::AffectedRows(rows) => {
+            ticket.stats().add_rows(rows as u64);
+            output
+        }
+        OutputData::RecordBatches(ref batches) => {
+            let rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+            ticket.stats().add_rows(rows as u64);
+            output
+        }
+    }
 }
 
 /// Attaches a timer to the output and observes it once the output is exhausted.
@@ -418,10 +678,8 @@ pub fn attach_timer(output: Output, timer: HistogramTimer) -> Output {
     }
 }
 
-#[async_trait]
-impl PrometheusHandler for Instance {
-    #[tracing::instrument(skip_all)]
-    async fn do_query(
+impl Instance {
+    async fn handle_promql_query(
         &self,
         query: &PromQuery,
         query_ctx: QueryContextRef,
@@ -436,6 +694,12 @@ impl PrometheusHandler for Instance {
             .as_ref()
             .check_permission(query_ctx.current_user(), PermissionReq::PromQuery)
             .context(AuthSnafu)?;
+        self.check_request_privileges(promql_privileges(&query.query, &query_ctx), &query_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .with_context(|_| ExecuteQuerySnafu {
+                query: format!("{query:?}"),
+            })?;
 
         let stmt = QueryLanguageParser::parse_promql(query, &query_ctx).with_context(|_| {
             ParsePromQLSnafu {
@@ -443,17 +707,95 @@ impl PrometheusHandler for Instance {
             }
         })?;
 
-        let output = self
-            .statement_executor
-            .execute_stmt(stmt, query_ctx.clone())
+        let ticket = self
+            .register_query(query.query.clone(), &query_ctx)
             .await
             .map_err(BoxedError::new)
             .with_context(|_| ExecuteQuerySnafu {
                 query: format!("{query:?}"),
             })?;
+        let execute = self
+            .statement_executor
+            .execute_stmt(stmt, query_ctx.clone());
+        let output = match &ticket {
+            Some(ticket) => ticket
+                .run(execute)
+                .await
+                .map_err(BoxedError::new)
+                .and_then(|result| result.map_err(BoxedError::new))
+                .map(|output| track_output(ticket, output)),
+            None => execute.await.map_err(BoxedError::new),
+        }
+        .with_context(|_| ExecuteQuerySnafu {
+            query: format!("{query:?}"),
+        })?;
 
         Ok(interceptor.post_execute(output, query_ctx)?)
     }
+}
+
+#[async_trait]
+impl PrometheusHandler for Instance {
+    #[tracing::instrument(skip_all)]
+    async fn do_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let start = Instant::now();
+        let result = self.handle_promql_query(query, query_ctx.clone()).await;
+        self.audit("Promql", Some(&query.query), &query_ctx, start, result)
+            .await
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn query_exemplars(
+        &self,
+        query: &RemoteQuery,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        self.plugins
+            .get::<PermissionCheckerRef>()
+            .as_ref()
+            .check_permission(query_ctx.current_user(), PermissionReq::PromQuery)
+            .context(AuthSnafu)?;
+
+        let table_name = exemplar_table_name(&servers::prom_store::table_name(query)?);
+        self.handle_remote_query(
+            &query_ctx,
+            query_ctx.current_catalog(),
+            query_ctx.current_schema(),
+            &table_name,
+            query,
+            false,
+        )
+        .await
+        .map_err(BoxedError::new)
+        .with_context(|_| ExecuteQuerySnafu {
+            query: format!("{query:?}"),
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn query_series(
+        &self,
+        query: &RemoteQuery,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        self.plugins
+            .get::<PermissionCheckerRef>()
+            .as_ref()
+            .check_permission(query_ctx.current_user(), PermissionReq::PromQuery)
+            .context(AuthSnafu)?;
+
+        let table_name = servers::prom_store::table_name(query)?;
+        self.handle_series_query(&query_ctx, &table_name, query)
+            .await
+            .map_err(BoxedError::new)
+            .with_context(|_| ExecuteQuerySnafu {
+                query: format!("{query:?}"),
+            })
+    }
 
     fn catalog_manager(&self) -> CatalogManagerRef {
         self.catalog_manager.clone()
@@ -487,15 +829,33 @@ pub fn check_permission(
 
     match stmt {
         // These are executed by query engine, and will be checked there.
-        Statement::Query(_) | Statement::Explain(_) | Statement::Tql(_) | Statement::Delete(_) => {}
+        Statement::Query(_)
+        | Statement::Explain(_)
+        | Statement::ExplainProfile(_)
+        | Statement::ExplainCapture(_)
+        | Statement::Tql(_)
+        | Statement::Delete(_) => {}
         // database ops won't be checked
-        Statement::CreateDatabase(_) | Statement::ShowDatabases(_) | Statement::DropDatabase(_) => {
-        }
+        Statement::CreateDatabase(_)
+        | Statement::ShowDatabases(_)
+        | Statement::ShowCreateDatabase(_)
+        | Statement::DropDatabase(_) => {}
         // show create table and alter are not supported yet
         Statement::ShowCreateTable(_) | Statement::CreateExternalTable(_) | Statement::Alter(_) => {
         }
         // set/show variable now only alter/show variable in session
         Statement::SetVariables(_) | Statement::ShowVariables(_) => {}
+        // the processlist is scoped to the current catalog
+        Statement::ShowProcesslist(_) | Statement::KillQuery(_) => {}
+        // users, roles and grants are not scoped to a catalog
+        Statement::CreateRole(_)
+        | Statement::DropRole(_)
+        | Statement::Grant(_)
+        | Statement::Revoke(_)
+        | Statement::CreateUser(_)
+        | Statement::AlterUser(_)
+        | Statement::DropUser(_)
+        | Statement::ShowCreateUsers(_) => {}
 
         Statement::Insert(insert) => {
             validate_param(insert.table_name(), query_ctx)?;
@@ -510,6 +870,21 @@ pub fn check_permission(
         Statement::DropTable(drop_stmt) => {
             validate_param(drop_stmt.table_name(), query_ctx)?;
         }
+        Statement::CreateView(stmt) => {
+            validate_param(&stmt.name, query_ctx)?;
+        }
+        Statement::DropView(stmt) => {
+            validate_param(stmt.view_name(), query_ctx)?;
+        }
+        Statement::CreateMaterializedView(stmt) => {
+            validate_param(&stmt.name, query_ctx)?;
+        }
+        Statement::DropMaterializedView(stmt) => {
+            validate_param(stmt.view_name(), query_ctx)?;
+        }
+        Statement::RefreshMaterializedView(stmt) => {
+            validate_param(stmt.view_name(), query_ctx)?;
+        }
         Statement::ShowTables(stmt) => {
             validate_db_permission!(stmt, query_ctx);
         }
@@ -534,6 +909,9 @@ pub fn check_permission(
                 CopyDatabase::From(stmt) => validate_param(&stmt.database_name, query_ctx)?,
             }
         }
+        Statement::BackupDatabase(stmt) | Statement::RestoreDatabase(stmt) => {
+            validate_param(&stmt.database_name, query_ctx)?
+        }
         Statement::TruncateTable(stmt) => {
             validate_param(stmt.table_name(), query_ctx)?;
         }
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
+use catalog::kvbackend::KvBackendCatalogManager;
 use catalog::CatalogManagerRef;
 use common_base::Plugins;
 use common_meta::cache_invalidator::{CacheInvalidatorRef, DummyCacheInvalidator};
@@ -24,6 +26,8 @@ use common_meta::kv_backend::KvBackendRef;
 use operator::delete::Deleter;
 use operator::insert::Inserter;
 use operator::procedure::ProcedureServiceOperator;
+use operator::query_cache::QueryCacheRef;
+use operator::quota::QuotaManager;
 use operator::request::Requester;
 use operator::statement::StatementExecutor;
 use operator::table::TableMutationOperator;
@@ -31,6 +35,7 @@ use partition::manager::PartitionRuleManager;
 use query::QueryEngineFactory;
 use servers::server::ServerHandlers;
 
+use crate::audit::{AuditLogOptions, AuditLogger};
 use crate::error::Result;
 use crate::heartbeat::HeartbeatTask;
 use crate::instance::region_query::FrontendRegionQueryHandler;
@@ -46,6 +51,8 @@ pub struct FrontendBuilder {
     plugins: Option<Plugins>,
     procedure_executor: ProcedureExecutorRef,
     heartbeat_task: Option<HeartbeatTask>,
+    query_cache: Option<QueryCacheRef>,
+    audit_log: Option<AuditLogOptions>,
 }
 
 impl FrontendBuilder {
@@ -63,6 +70,8 @@ impl FrontendBuilder {
             plugins: None,
             procedure_executor,
             heartbeat_task: None,
+            query_cache: None,
+            audit_log: None,
         }
     }
 
@@ -87,6 +96,20 @@ impl FrontendBuilder {
         }
     }
 
+    pub fn with_query_cache(self, query_cache: Option<QueryCacheRef>) -> Self {
+        Self {
+            query_cache,
+            ..self
+        }
+    }
+
+    pub fn with_audit_log(self, audit_log: AuditLogOptions) -> Self {
+        Self {
+            audit_log: Some(audit_log),
+            ..self
+        }
+    }
+
     pub async fn try_build(self) -> Result<Instance> {
         let kv_backend = self.kv_backend;
         let datanode_manager = self.datanode_manager;
@@ -101,16 +124,25 @@ impl FrontendBuilder {
         let region_query_handler =
             FrontendRegionQueryHandler::arc(partition_manager.clone(), datanode_manager.clone());
 
-        let inserter = Arc::new(Inserter::new(
+        let table_metadata_manager = Arc::new(TableMetadataManager::new(kv_backend.clone()));
+        let quota_manager = Arc::new(QuotaManager::new(table_metadata_manager.clone()));
+        let mut inserter = Inserter::new(
             self.catalog_manager.clone(),
             partition_manager.clone(),
             datanode_manager.clone(),
-        ));
-        let deleter = Arc::new(Deleter::new(
+        )
+        .with_quota_manager(quota_manager.clone());
+        let mut deleter = Deleter::new(
             self.catalog_manager.clone(),
             partition_manager.clone(),
             datanode_manager.clone(),
-        ));
+        );
+        if let Some(query_cache) = &self.query_cache {
+            inserter = inserter.with_query_cache(query_cache.clone());
+            deleter = deleter.with_query_cache(query_cache.clone());
+        }
+        let inserter = Arc::new(inserter);
+        let deleter = Arc::new(deleter);
         let requester = Arc::new(Requester::new(
             self.catalog_manager.clone(),
             partition_manager,
@@ -119,7 +151,10 @@ impl FrontendBuilder {
         let table_mutation_handler = Arc::new(TableMutationOperator::new(
             inserter.clone(),
             deleter.clone(),
-            requester,
+            requester.clone(),
+            self.catalog_manager.clone(),
+            table_metadata_manager.clone(),
+            cache_invalidator.clone(),
         ));
 
         let procedure_service_handler = Arc::new(ProcedureServiceOperator::new(
@@ -140,17 +175,36 @@ impl FrontendBuilder {
             ScriptExecutor::new(self.catalog_manager.clone(), query_engine.clone()).await?,
         );
 
-        let statement_executor = Arc::new(StatementExecutor::new(
+        let mut statement_executor = StatementExecutor::new(
             self.catalog_manager.clone(),
             query_engine.clone(),
             self.procedure_executor,
             kv_backend.clone(),
             cache_invalidator,
             inserter.clone(),
-        ));
+            requester,
+        );
+        if let Some(query_cache) = self.query_cache {
+            statement_executor = statement_executor.with_query_cache(query_cache);
+        }
+        let statement_executor = Arc::new(statement_executor);
 
         plugins.insert::<StatementExecutorRef>(statement_executor.clone());
 
+        let audit_logger = self
+            .audit_log
+            .filter(|options| options.enable)
+            .map(|options| {
+                AuditLogger::start(options, inserter.clone(), statement_executor.clone())
+            })
+            .transpose()?;
+
+        let process_manager = self
+            .catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+            .and_then(|catalog_manager| catalog_manager.process_manager());
+
         Ok(Instance {
             catalog_manager: self.catalog_manager,
             script_executor,
@@ -162,7 +216,12 @@ impl FrontendBuilder {
             inserter,
             deleter,
             export_metrics_task: None,
-            table_metadata_manager: Arc::new(TableMetadataManager::new(kv_backend)),
+            table_metadata_manager,
+            process_manager,
+            quota_manager,
+            audit_logger,
+            // Configured by `build_servers`.
+            shutdown_timeout: Duration::ZERO,
         })
     }
 }
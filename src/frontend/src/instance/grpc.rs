@@ -12,15 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::time::Instant;
+
 use api::v1::ddl_request::{Expr as DdlExpr, Expr};
 use api::v1::greptime_request::Request;
 use api::v1::query_request::Query;
 use api::v1::{DeleteRequests, InsertRequests, RowDeleteRequests, RowInsertRequests};
 use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use common_meta::key::privilege::Privilege;
+use common_meta::key::schema_name::IngestProtocol;
 use common_meta::table_name::TableName;
 use common_query::Output;
 use common_telemetry::tracing;
+use operator::statement::create_table_output;
 use query::parser::PromQuery;
 use servers::interceptor::{GrpcQueryInterceptor, GrpcQueryInterceptorRef};
 use servers::query_handler::grpc::GrpcQueryHandler;
@@ -32,6 +38,7 @@ use crate::error::{
     Error, IncompleteGrpcRequestSnafu, NotSupportedSnafu, PermissionSnafu, Result,
     TableOperationSnafu,
 };
+use crate::instance::privilege::{grpc_privileges, table_privileges};
 use crate::instance::{attach_timer, Instance};
 use crate::metrics::{GRPC_HANDLE_PROMQL_ELAPSED, GRPC_HANDLE_SQL_ELAPSED};
 
@@ -40,6 +47,19 @@ impl GrpcQueryHandler for Instance {
     type Error = Error;
 
     async fn do_query(&self, request: Request, ctx: QueryContextRef) -> Result<Output> {
+        // SQL and PromQL queries are audited by their handlers.
+        let Some((request_type, target)) = grpc_request_audit(&request) else {
+            return self.handle_grpc_request(request, ctx).await;
+        };
+        let start = Instant::now();
+        let result = self.handle_grpc_request(request, ctx.clone()).await;
+        self.audit(request_type, target.as_deref(), &ctx, start, result)
+            .await
+    }
+}
+
+impl Instance {
+    async fn handle_grpc_request(&self, request: Request, ctx: QueryContextRef) -> Result<Output> {
         let interceptor_ref = self.plugins.get::<GrpcQueryInterceptorRef<Error>>();
         let interceptor = interceptor_ref.as_ref();
         interceptor.pre_execute(&request, ctx.clone())?;
@@ -49,6 +69,8 @@ impl GrpcQueryHandler for Instance {
             .as_ref()
             .check_permission(ctx.current_user(), PermissionReq::GrpcRequest(&request))
             .context(PermissionSnafu)?;
+        self.check_request_privileges(grpc_privileges(&request, &ctx), &ctx)
+            .await?;
 
         let output = match request {
             Request::Inserts(requests) => self.handle_inserts(requests, ctx.clone()).await?,
@@ -110,11 +132,11 @@ impl GrpcQueryHandler for Instance {
                 match expr {
                     DdlExpr::CreateTable(mut expr) => {
                         // TODO(weny): supports to create multiple region table.
-                        let _ = self
+                        let (table, status) = self
                             .statement_executor
-                            .create_table_inner(&mut expr, None, &ctx)
+                            .create_table_with_status(&mut expr, None, &ctx)
                             .await?;
-                        Output::new_with_affected_rows(0)
+                        create_table_output(&table, status)
                     }
                     DdlExpr::Alter(expr) => self.statement_executor.alter_table_inner(expr).await?,
                     DdlExpr::CreateDatabase(expr) => {
@@ -123,6 +145,7 @@ impl GrpcQueryHandler for Instance {
                                 ctx.current_catalog(),
                                 &expr.schema_name,
                                 expr.create_if_not_exists,
+                                HashMap::new(),
                             )
                             .await?
                     }
@@ -130,7 +153,7 @@ impl GrpcQueryHandler for Instance {
                         let table_name =
                             TableName::new(&expr.catalog_name, &expr.schema_name, &expr.table_name);
                         self.statement_executor
-                            .drop_table(table_name, expr.drop_if_exists)
+                            .drop_table(table_name, expr.drop_if_exists, false)
                             .await?
                     }
                     DdlExpr::TruncateTable(expr) => {
@@ -147,6 +170,71 @@ impl GrpcQueryHandler for Instance {
     }
 }
 
+/// Returns the type and target tables of the `request` to audit, or `None` if the
+/// `request` is a SQL or PromQL query.
+fn grpc_request_audit(request: &Request) -> Option<(&'static str, Option<String>)> {
+    let join = |tables: Vec<&str>| Some(tables.join(", "));
+    let audit = match request {
+        Request::Inserts(requests) => (
+            "GrpcInserts",
+            join(
+                requests
+                    .inserts
+                    .iter()
+                    .map(|r| r.table_name.as_str())
+                    .collect(),
+            ),
+        ),
+        Request::RowInserts(requests) => (
+            "GrpcRowInserts",
+            join(
+                requests
+                    .inserts
+                    .iter()
+                    .map(|r| r.table_name.as_str())
+                    .collect(),
+            ),
+        ),
+        Request::Deletes(requests) => (
+            "GrpcDeletes",
+            join(
+                requests
+                    .deletes
+                    .iter()
+                    .map(|r| r.table_name.as_str())
+                    .collect(),
+            ),
+        ),
+        Request::RowDeletes(requests) => (
+            "GrpcRowDeletes",
+            join(
+                requests
+                    .deletes
+                    .iter()
+                    .map(|r| r.table_name.as_str())
+                    .collect(),
+            ),
+        ),
+        Request::Query(query_request) => match &query_request.query {
+            Some(Query::Sql(_)) | Some(Query::PromRangeQuery(_)) => return None,
+            Some(Query::LogicalPlan(_)) | None => ("GrpcQuery", None),
+        },
+        Request::Ddl(request) => match &request.expr {
+            Some(DdlExpr::CreateTable(expr)) => ("GrpcCreateTable", Some(expr.table_name.clone())),
+            Some(DdlExpr::Alter(expr)) => ("GrpcAlterTable", Some(expr.table_name.clone())),
+            Some(DdlExpr::CreateDatabase(expr)) => {
+                ("GrpcCreateDatabase", Some(expr.schema_name.clone()))
+            }
+            Some(DdlExpr::DropTable(expr)) => ("GrpcDropTable", Some(expr.table_name.clone())),
+            Some(DdlExpr::TruncateTable(expr)) => {
+                ("GrpcTruncateTable", Some(expr.table_name.clone()))
+            }
+            None => ("GrpcDdl", None),
+        },
+    };
+    Some(audit)
+}
+
 fn fill_catalog_and_schema_from_context(ddl_expr: &mut DdlExpr, ctx: &QueryContextRef) {
     let catalog = ctx.current_catalog();
     let schema = ctx.current_schema();
@@ -180,12 +268,39 @@ fn fill_catalog_and_schema_from_context(ddl_expr: &mut DdlExpr, ctx: &QueryConte
 }
 
 impl Instance {
+    /// Checks the privileges to write the tables of `requests`, all the write
+    /// protocols go through this check.
+    async fn check_row_insert_privileges(
+        &self,
+        requests: &RowInsertRequests,
+        ctx: &QueryContextRef,
+    ) -> Result<()> {
+        self.check_request_privileges(
+            table_privileges(
+                Privilege::Insert,
+                requests.inserts.iter().map(|r| r.table_name.as_str()),
+                ctx,
+            ),
+            ctx,
+        )
+        .await
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn handle_inserts(
         &self,
         requests: InsertRequests,
         ctx: QueryContextRef,
     ) -> Result<Output> {
+        self.check_request_privileges(
+            table_privileges(
+                Privilege::Insert,
+                requests.inserts.iter().map(|r| r.table_name.as_str()),
+                &ctx,
+            ),
+            &ctx,
+        )
+        .await?;
         self.inserter
             .handle_column_inserts(requests, ctx, self.statement_executor.as_ref())
             .await
@@ -198,21 +313,49 @@ impl Instance {
         requests: RowInsertRequests,
         ctx: QueryContextRef,
     ) -> Result<Output> {
+        self.check_row_insert_privileges(&requests, &ctx).await?;
         self.inserter
             .handle_row_inserts(requests, ctx, self.statement_executor.as_ref())
             .await
             .context(TableOperationSnafu)
     }
 
+    #[tracing::instrument(skip_all)]
+    pub async fn handle_protocol_row_inserts(
+        &self,
+        requests: RowInsertRequests,
+        ctx: QueryContextRef,
+        protocol: IngestProtocol,
+    ) -> Result<Output> {
+        self.check_row_insert_privileges(&requests, &ctx).await?;
+        self.inserter
+            .handle_protocol_row_inserts(
+                requests,
+                ctx,
+                Some(protocol),
+                self.statement_executor.as_ref(),
+            )
+            .await
+            .context(TableOperationSnafu)
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn handle_metric_row_inserts(
         &self,
         requests: RowInsertRequests,
         ctx: QueryContextRef,
+        protocol: IngestProtocol,
         physical_table: String,
     ) -> Result<Output> {
+        self.check_row_insert_privileges(&requests, &ctx).await?;
         self.inserter
-            .handle_metric_row_inserts(requests, ctx, &self.statement_executor, physical_table)
+            .handle_metric_row_inserts(
+                requests,
+                ctx,
+                Some(protocol),
+                &self.statement_executor,
+                physical_table,
+            )
             .await
             .context(TableOperationSnafu)
     }
@@ -223,6 +366,15 @@ impl Instance {
         requests: DeleteRequests,
         ctx: QueryContextRef,
     ) -> Result<Output> {
+        self.check_request_privileges(
+            table_privileges(
+                Privilege::Insert,
+                requests.deletes.iter().map(|r| r.table_name.as_str()),
+                &ctx,
+            ),
+            &ctx,
+        )
+        .await?;
         self.deleter
             .handle_column_deletes(requests, ctx)
             .await
@@ -235,6 +387,15 @@ impl Instance {
         requests: RowDeleteRequests,
         ctx: QueryContextRef,
     ) -> Result<Output> {
+        self.check_request_privileges(
+            table_privileges(
+                Privilege::Insert,
+                requests.deletes.iter().map(|r| r.table_name.as_str()),
+                &ctx,
+            ),
+            &ctx,
+        )
+        .await?;
         self.deleter
             .handle_row_deletes(requests, ctx)
             .await
@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Instant;
+
 use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
 use client::Output;
 use common_error::ext::BoxedError;
+use common_meta::key::schema_name::IngestProtocol;
 use servers::error::{AuthSnafu, Error};
 use servers::influxdb::InfluxdbRequest;
 use servers::interceptor::{LineProtocolInterceptor, LineProtocolInterceptorRef};
@@ -31,6 +34,18 @@ impl InfluxdbLineProtocolHandler for Instance {
         &self,
         request: InfluxdbRequest,
         ctx: QueryContextRef,
+    ) -> servers::error::Result<Output> {
+        let start = Instant::now();
+        let result = self.handle_influxdb_request(request, ctx.clone()).await;
+        self.audit("InfluxdbWrite", None, &ctx, start, result).await
+    }
+}
+
+impl Instance {
+    async fn handle_influxdb_request(
+        &self,
+        request: InfluxdbRequest,
+        ctx: QueryContextRef,
     ) -> servers::error::Result<Output> {
         self.plugins
             .get::<PermissionCheckerRef>()
@@ -42,7 +57,7 @@ impl InfluxdbLineProtocolHandler for Instance {
         interceptor_ref.pre_execute(&request.lines, ctx.clone())?;
 
         let requests = request.try_into()?;
-        self.handle_row_inserts(requests, ctx)
+        self.handle_protocol_row_inserts(requests, ctx, IngestProtocol::Influxdb)
             .await
             .map_err(BoxedError::new)
             .context(servers::error::ExecuteGrpcQuerySnafu)
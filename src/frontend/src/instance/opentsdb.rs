@@ -15,6 +15,7 @@
 use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
 use common_error::ext::BoxedError;
+use common_meta::key::schema_name::IngestProtocol;
 use common_telemetry::tracing;
 use servers::error as server_error;
 use servers::error::AuthSnafu;
@@ -42,7 +43,7 @@ impl OpentsdbProtocolHandler for Instance {
 
         let (requests, _) = data_point_to_grpc_row_insert_requests(data_points)?;
         let output = self
-            .handle_row_inserts(requests, ctx)
+            .handle_protocol_row_inserts(requests, ctx, IngestProtocol::Opentsdb)
             .await
             .map_err(BoxedError::new)
             .context(servers::error::ExecuteGrpcQuerySnafu)?;
@@ -0,0 +1,580 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+
+use api::v1::ddl_request::Expr as DdlExpr;
+use api::v1::greptime_request::Request;
+use auth::error::PrivilegeDeniedSnafu;
+use common_error::ext::BoxedError;
+use common_meta::key::privilege::{GrantObject, Privilege};
+use promql_parser::label::{MatchOp, METRIC_NAME};
+use promql_parser::parser::{
+    AggregateExpr, BinaryExpr, Call, Expr as PromqlExpr, MatrixSelector, ParenExpr, SubqueryExpr,
+    UnaryExpr, VectorSelector,
+};
+use session::context::QueryContextRef;
+use session::table_name::table_idents_to_full_name;
+use snafu::ResultExt;
+use sql::parser::ParserContext;
+use sql::statements::copy::{Copy, CopyDatabase, CopyTable};
+use sql::statements::statement::Statement;
+use sql::statements::tql::Tql;
+use sqlparser::ast::{ObjectName, Query, Visit, Visitor};
+
+use crate::error::{CheckPrivilegeSnafu, ExternalSnafu, PermissionSnafu, Result};
+use crate::instance::Instance;
+
+impl Instance {
+    /// Checks whether the current user has the privileges `stmt` requires.
+    ///
+    /// Once privileges are enabled, only members of the admin role can manage
+    /// and show users, roles and grants.
+    pub(crate) async fn check_privileges(
+        &self,
+        stmt: &Statement,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let Some(user) = query_ctx.current_user() else {
+            return Ok(());
+        };
+        let username = user.username();
+        let manager = self.table_metadata_manager.privilege_manager();
+
+        // Users can always change their own passwords.
+        if let Statement::AlterUser(alter_user) = stmt {
+            if alter_user.name.value == username {
+                return Ok(());
+            }
+        }
+
+        if matches!(
+            stmt,
+            Statement::CreateRole(_)
+                | Statement::DropRole(_)
+                | Statement::Grant(_)
+                | Statement::Revoke(_)
+                | Statement::CreateUser(_)
+                | Statement::AlterUser(_)
+                | Statement::DropUser(_)
+                | Statement::ShowCreateUsers(_)
+        ) {
+            let can_manage = manager
+                .can_manage(username)
+                .await
+                .context(CheckPrivilegeSnafu)?;
+            if !can_manage {
+                return PrivilegeDeniedSnafu {
+                    username,
+                    privilege: "GRANT",
+                    object: "users, roles and privileges",
+                }
+                .fail()
+                .context(PermissionSnafu);
+            }
+            return Ok(());
+        }
+
+        self.check_request_privileges(required_privileges(stmt, query_ctx)?, query_ctx)
+            .await
+    }
+
+    /// Checks whether the current user has `privileges`. All the protocols check
+    /// the privileges of their requests here.
+    pub(crate) async fn check_request_privileges(
+        &self,
+        privileges: Vec<(Privilege, GrantObject)>,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let Some(user) = query_ctx.current_user() else {
+            return Ok(());
+        };
+        let username = user.username();
+        let manager = self.table_metadata_manager.privilege_manager();
+
+        for (privilege, object) in privileges {
+            let allowed = manager
+                .check(username, privilege, &object)
+                .await
+                .context(CheckPrivilegeSnafu)?;
+            if !allowed {
+                return PrivilegeDeniedSnafu {
+                    username,
+                    privilege: privilege.to_string(),
+                    object: object.to_string(),
+                }
+                .fail()
+                .context(PermissionSnafu);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the privileges `stmt` requires on the objects it accesses.
+///
+/// Reading requires `SELECT`, writing and deleting data requires `INSERT`,
+/// creating and altering requires `CREATE`, dropping and truncating requires `DROP`.
+fn required_privileges(
+    stmt: &Statement,
+    query_ctx: &QueryContextRef,
+) -> Result<Vec<(Privilege, GrantObject)>> {
+    let select = |objects: Vec<GrantObject>| {
+        objects
+            .into_iter()
+            .map(|object| (Privilege::Select, object))
+            .collect::<Vec<_>>()
+    };
+
+    let privileges = match stmt {
+        Statement::Query(query) => select(relations(&query.inner, query_ctx)?),
+        Statement::Explain(explain) => select(relations(&explain.inner, query_ctx)?),
+        Statement::ExplainProfile(explain) => select(relations(&explain.query.inner, query_ctx)?),
+        Statement::ExplainCapture(explain) => select(relations(&explain.query.inner, query_ctx)?),
+        Statement::Delete(delete) => relations(&delete.inner, query_ctx)?
+            .into_iter()
+            .map(|object| (Privilege::Insert, object))
+            .collect(),
+        Statement::Insert(insert) => {
+            let target = table_object(insert.table_name(), query_ctx)?;
+            let mut privileges = select(
+                relations(&insert.inner, query_ctx)?
+                    .into_iter()
+                    .filter(|object| *object != target)
+                    .collect(),
+            );
+            privileges.push((Privilege::Insert, target));
+            privileges
+        }
+        Statement::CreateTable(stmt) => {
+            vec![(Privilege::Create, table_object(&stmt.name, query_ctx)?)]
+        }
+        Statement::CreateExternalTable(stmt) => {
+            vec![(Privilege::Create, table_object(&stmt.name, query_ctx)?)]
+        }
+        Statement::CreateTableLike(stmt) => vec![
+            (
+                Privilege::Create,
+                table_object(&stmt.table_name, query_ctx)?,
+            ),
+            (
+                Privilege::Select,
+                table_object(&stmt.source_name, query_ctx)?,
+            ),
+        ],
+        Statement::CreateView(stmt) => {
+            let mut privileges = select(relations(&stmt.query.inner, query_ctx)?);
+            privileges.push((Privilege::Create, table_object(&stmt.name, query_ctx)?));
+            privileges
+        }
+        Statement::CreateMaterializedView(stmt) => {
+            let mut privileges = select(relations(&stmt.query.inner, query_ctx)?);
+            privileges.push((Privilege::Create, table_object(&stmt.name, query_ctx)?));
+            privileges
+        }
+        Statement::Alter(stmt) => {
+            vec![(
+                Privilege::Create,
+                table_object(stmt.table_name(), query_ctx)?,
+            )]
+        }
+        Statement::CreateDatabase(stmt) => {
+            vec![(Privilege::Create, database_object(&stmt.name, query_ctx)?)]
+        }
+        Statement::DropDatabase(stmt) => {
+            vec![(Privilege::Drop, database_object(stmt.name(), query_ctx)?)]
+        }
+        Statement::DropTable(stmt) => {
+            vec![(Privilege::Drop, table_object(stmt.table_name(), query_ctx)?)]
+        }
+        Statement::DropView(stmt) => {
+            vec![(Privilege::Drop, table_object(stmt.view_name(), query_ctx)?)]
+        }
+        Statement::DropMaterializedView(stmt) => {
+            vec![(Privilege::Drop, table_object(stmt.view_name(), query_ctx)?)]
+        }
+        Statement::TruncateTable(stmt) => {
+            vec![(Privilege::Drop, table_object(stmt.table_name(), query_ctx)?)]
+        }
+        Statement::RefreshMaterializedView(stmt) => {
+            vec![(
+                Privilege::Insert,
+                table_object(stmt.view_name(), query_ctx)?,
+            )]
+        }
+        Statement::ShowCreateTable(stmt) => {
+            vec![(
+                Privilege::Select,
+                table_object(&stmt.table_name, query_ctx)?,
+            )]
+        }
+        Statement::ShowCreateDatabase(stmt) => {
+            vec![(
+                Privilege::Select,
+                database_object(&stmt.database_name, query_ctx)?,
+            )]
+        }
+        Statement::DescribeTable(stmt) => {
+            vec![(Privilege::Select, table_object(stmt.name(), query_ctx)?)]
+        }
+        Statement::Copy(Copy::CopyTable(CopyTable::To(stmt))) => {
+            vec![(
+                Privilege::Select,
+                table_object(&stmt.table_name, query_ctx)?,
+            )]
+        }
+        Statement::Copy(Copy::CopyTable(CopyTable::From(stmt))) => {
+            vec![(
+                Privilege::Insert,
+                table_object(&stmt.table_name, query_ctx)?,
+            )]
+        }
+        Statement::Copy(Copy::CopyDatabase(CopyDatabase::To(stmt)))
+        | Statement::BackupDatabase(stmt) => {
+            vec![(
+                Privilege::Select,
+                database_object(&stmt.database_name, query_ctx)?,
+            )]
+        }
+        Statement::Copy(Copy::CopyDatabase(CopyDatabase::From(stmt)))
+        | Statement::RestoreDatabase(stmt) => vec![
+            (
+                Privilege::Create,
+                database_object(&stmt.database_name, query_ctx)?,
+            ),
+            (
+                Privilege::Insert,
+                database_object(&stmt.database_name, query_ctx)?,
+            ),
+        ],
+        Statement::Tql(tql) => {
+            let query = match tql {
+                Tql::Eval(eval) => &eval.query,
+                Tql::Explain(explain) => &explain.query,
+                Tql::Analyze(analyze) => &analyze.query,
+            };
+            promql_privileges(query, query_ctx)
+        }
+        Statement::ShowDatabases(_)
+        | Statement::ShowTables(_)
+        | Statement::ShowColumns(_)
+        | Statement::ShowIndex(_)
+        | Statement::SetVariables(_)
+        | Statement::ShowVariables(_)
+        | Statement::ShowProcesslist(_)
+        | Statement::KillQuery(_)
+        | Statement::CreateRole(_)
+        | Statement::DropRole(_)
+        | Statement::Grant(_)
+        | Statement::Revoke(_)
+        | Statement::CreateUser(_)
+        | Statement::AlterUser(_)
+        | Statement::DropUser(_)
+        | Statement::ShowCreateUsers(_) => vec![],
+    };
+
+    Ok(privileges)
+}
+
+/// Returns the privileges to read the metrics a PromQL `query` selects.
+///
+/// Selectors without a metric name may read any table, so they require `SELECT`
+/// on the current database. Unparsable queries require nothing as they fail later.
+pub(crate) fn promql_privileges(
+    query: &str,
+    query_ctx: &QueryContextRef,
+) -> Vec<(Privilege, GrantObject)> {
+    let Ok(expr) = promql_parser::parser::parse(query) else {
+        return vec![];
+    };
+    let mut selectors = Vec::new();
+    collect_vector_selectors(&expr, &mut selectors);
+
+    let mut privileges = Vec::with_capacity(selectors.len());
+    for VectorSelector { name, matchers, .. } in selectors {
+        // Only an equality matcher on the metric name narrows the selector to one table.
+        let metric = name.clone().or_else(|| {
+            matchers
+                .matchers
+                .iter()
+                .find(|m| m.name == METRIC_NAME && m.op == MatchOp::Equal)
+                .map(|m| m.value.clone())
+        });
+        let object = match metric {
+            Some(metric) => GrantObject::table(
+                query_ctx.current_catalog(),
+                query_ctx.current_schema(),
+                metric,
+            ),
+            None => GrantObject::schema(query_ctx.current_catalog(), query_ctx.current_schema()),
+        };
+        let privilege = (Privilege::Select, object);
+        if !privileges.contains(&privilege) {
+            privileges.push(privilege);
+        }
+    }
+
+    privileges
+}
+
+/// Collects all vector selectors inside `expr`.
+fn collect_vector_selectors<'a>(expr: &'a PromqlExpr, selectors: &mut Vec<&'a VectorSelector>) {
+    match expr {
+        PromqlExpr::Aggregate(AggregateExpr { expr, .. })
+        | PromqlExpr::Unary(UnaryExpr { expr })
+        | PromqlExpr::Paren(ParenExpr { expr })
+        | PromqlExpr::Subquery(SubqueryExpr { expr, .. }) => {
+            collect_vector_selectors(expr, selectors)
+        }
+        PromqlExpr::Binary(BinaryExpr { lhs, rhs, .. }) => {
+            collect_vector_selectors(lhs, selectors);
+            collect_vector_selectors(rhs, selectors);
+        }
+        PromqlExpr::VectorSelector(vs) | PromqlExpr::MatrixSelector(MatrixSelector { vs, .. }) => {
+            selectors.push(vs)
+        }
+        PromqlExpr::Call(Call { args, .. }) => args
+            .args
+            .iter()
+            .for_each(|arg| collect_vector_selectors(arg, selectors)),
+        PromqlExpr::NumberLiteral(_) | PromqlExpr::StringLiteral(_) | PromqlExpr::Extension(_) => {}
+    }
+}
+
+/// Returns `privilege` on each table in `table_names` of the current database.
+pub(crate) fn table_privileges<'a>(
+    privilege: Privilege,
+    table_names: impl IntoIterator<Item = &'a str>,
+    query_ctx: &QueryContextRef,
+) -> Vec<(Privilege, GrantObject)> {
+    let mut privileges = Vec::new();
+    for table_name in table_names {
+        let object = GrantObject::table(
+            query_ctx.current_catalog(),
+            query_ctx.current_schema(),
+            table_name,
+        );
+        let privilege = (privilege, object);
+        if !privileges.contains(&privilege) {
+            privileges.push(privilege);
+        }
+    }
+    privileges
+}
+
+/// Returns the privileges a gRPC `request` requires. Writes and queries in it are
+/// checked by the paths executing them, so only DDLs require privileges here.
+pub(crate) fn grpc_privileges(
+    request: &Request,
+    query_ctx: &QueryContextRef,
+) -> Vec<(Privilege, GrantObject)> {
+    let or_current = |name: &str, current: &str| {
+        if name.is_empty() {
+            current.to_string()
+        } else {
+            name.to_string()
+        }
+    };
+    let table = |catalog: &str, schema: &str, table: &str| {
+        GrantObject::table(
+            or_current(catalog, query_ctx.current_catalog()),
+            or_current(schema, query_ctx.current_schema()),
+            table,
+        )
+    };
+
+    let Request::Ddl(request) = request else {
+        return vec![];
+    };
+    match &request.expr {
+        Some(DdlExpr::CreateTable(expr)) => vec![(
+            Privilege::Create,
+            table(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+        )],
+        Some(DdlExpr::Alter(expr)) => vec![(
+            Privilege::Create,
+            table(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+        )],
+        Some(DdlExpr::CreateDatabase(expr)) => vec![(
+            Privilege::Create,
+            GrantObject::schema(query_ctx.current_catalog(), &expr.schema_name),
+        )],
+        Some(DdlExpr::DropTable(expr)) => vec![(
+            Privilege::Drop,
+            table(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+        )],
+        Some(DdlExpr::TruncateTable(expr)) => vec![(
+            Privilege::Drop,
+            table(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+        )],
+        None => vec![],
+    }
+}
+
+fn table_object(name: &ObjectName, query_ctx: &QueryContextRef) -> Result<GrantObject> {
+    let (catalog, schema, table) = table_idents_to_full_name(name, query_ctx)
+        .map_err(BoxedError::new)
+        .context(ExternalSnafu)?;
+
+    Ok(GrantObject::table(catalog, schema, table))
+}
+
+fn database_object(name: &ObjectName, query_ctx: &QueryContextRef) -> Result<GrantObject> {
+    let object = match &name.0[..] {
+        [catalog, database] => GrantObject::schema(&catalog.value, &database.value),
+        _ => GrantObject::schema(
+            query_ctx.current_catalog(),
+            name.0
+                .last()
+                .map(|ident| ident.value.as_str())
+                .unwrap_or_default(),
+        ),
+    };
+
+    Ok(object)
+}
+
+/// Returns the tables `node` reads, common table expressions are excluded.
+fn relations<V: Visit>(node: &V, query_ctx: &QueryContextRef) -> Result<Vec<GrantObject>> {
+    let mut collector = RelationCollector::default();
+    let _ = node.visit(&mut collector);
+
+    let mut objects = Vec::with_capacity(collector.relations.len());
+    for relation in collector.relations {
+        let relation = ParserContext::canonicalize_object_name(relation);
+        if let [name] = &relation.0[..] {
+            if collector.ctes.contains(&name.value) {
+                continue;
+            }
+        }
+        let object = table_object(&relation, query_ctx)?;
+        if !objects.contains(&object) {
+            objects.push(object);
+        }
+    }
+
+    Ok(objects)
+}
+
+#[derive(Default)]
+struct RelationCollector {
+    relations: Vec<ObjectName>,
+    ctes: HashSet<String>,
+}
+
+impl Visitor for RelationCollector {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<Self::Break> {
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                let alias = ParserContext::canonicalize_identifier(cte.alias.name.clone());
+                let _ = self.ctes.insert(alias.value);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<Self::Break> {
+        self.relations.push(relation.clone());
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use session::context::QueryContext;
+    use sql::dialect::GreptimeDbDialect;
+
+    use super::*;
+    use crate::instance::parse_stmt;
+
+    fn privileges_of(sql: &str) -> Vec<(Privilege, GrantObject)> {
+        let query_ctx = QueryContext::with("greptime", "public");
+        let stmt = parse_stmt(sql, &GreptimeDbDialect {}).unwrap().remove(0);
+        required_privileges(&stmt, &query_ctx).unwrap()
+    }
+
+    #[test]
+    fn test_required_privileges() {
+        let foo = GrantObject::table("greptime", "public", "foo");
+        let bar = GrantObject::table("greptime", "my_db", "bar");
+
+        assert_eq!(
+            vec![
+                (Privilege::Select, foo.clone()),
+                (Privilege::Select, bar.clone())
+            ],
+            privileges_of("SELECT * FROM foo JOIN my_db.Bar ON foo.a = bar.a")
+        );
+        assert_eq!(
+            vec![(Privilege::Select, foo.clone())],
+            privileges_of("WITH t AS (SELECT * FROM foo) SELECT * FROM t")
+        );
+        assert_eq!(
+            vec![
+                (Privilege::Select, bar.clone()),
+                (Privilege::Insert, foo.clone())
+            ],
+            privileges_of("INSERT INTO foo SELECT * FROM my_db.bar")
+        );
+        assert_eq!(
+            vec![(Privilege::Insert, foo.clone())],
+            privileges_of("DELETE FROM foo WHERE ts = 1")
+        );
+        assert_eq!(
+            vec![(Privilege::Drop, bar.clone())],
+            privileges_of("DROP TABLE my_db.bar")
+        );
+        assert_eq!(
+            vec![(Privilege::Create, foo)],
+            privileges_of("CREATE TABLE foo (ts TIMESTAMP TIME INDEX)")
+        );
+        assert_eq!(
+            vec![(Privilege::Create, GrantObject::schema("greptime", "my_db"))],
+            privileges_of("CREATE DATABASE my_db")
+        );
+        assert!(privileges_of("SHOW TABLES").is_empty());
+        assert!(privileges_of("GRANT SELECT ON foo TO alice").is_empty());
+        assert_eq!(
+            vec![(
+                Privilege::Select,
+                GrantObject::table("greptime", "public", "http_requests")
+            )],
+            privileges_of("TQL EVAL (0, 10, '5s') rate(http_requests[5m])")
+        );
+    }
+
+    #[test]
+    fn test_promql_privileges() {
+        let query_ctx = QueryContext::with("greptime", "public");
+        let foo = GrantObject::table("greptime", "public", "foo");
+        let bar = GrantObject::table("greptime", "public", "bar");
+
+        assert_eq!(
+            vec![(Privilege::Select, foo.clone()), (Privilege::Select, bar)],
+            promql_privileges("sum(rate(foo[5m])) / bar + foo", &query_ctx)
+        );
+        assert_eq!(
+            vec![(Privilege::Select, foo)],
+            promql_privileges(r#"{__name__="foo", job="a"}"#, &query_ctx)
+        );
+        // Selectors without a metric name may read any table.
+        assert_eq!(
+            vec![(Privilege::Select, GrantObject::schema("greptime", "public"))],
+            promql_privileges(r#"{__name__=~"fo.*"}"#, &query_ctx)
+        );
+    }
+}
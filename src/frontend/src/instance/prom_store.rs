@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use api::prom_store::remote::read_request::ResponseType;
 use api::prom_store::remote::{Query, QueryResult, ReadRequest, ReadResponse};
@@ -22,21 +23,28 @@ use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
 use client::OutputData;
 use common_catalog::format_full_table_name;
-use common_error::ext::BoxedError;
+use common_error::ext::{BoxedError, ErrorExt};
+use common_error::status_code::StatusCode;
+use common_meta::key::privilege::{GrantObject, Privilege};
+use common_meta::key::schema_name::IngestProtocol;
 use common_query::prelude::GREPTIME_PHYSICAL_TABLE;
 use common_query::Output;
-use common_recordbatch::RecordBatches;
+use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 use common_telemetry::{logging, tracing};
+use futures::stream::{self, StreamExt};
 use operator::insert::InserterRef;
 use operator::statement::StatementExecutor;
 use prost::Message;
 use servers::error::{self, AuthSnafu, Result as ServerResult};
-use servers::http::header::{collect_plan_metrics, CONTENT_ENCODING_SNAPPY, CONTENT_TYPE_PROTOBUF};
+use servers::http::header::{
+    collect_plan_metrics, CONTENT_ENCODING_SNAPPY, CONTENT_TYPE_PROTOBUF,
+    CONTENT_TYPE_STREAMED_PROTOBUF,
+};
 use servers::http::prom_store::PHYSICAL_TABLE_PARAM;
 use servers::interceptor::{PromStoreProtocolInterceptor, PromStoreProtocolInterceptorRef};
 use servers::prom_store::{self, Metrics};
 use servers::query_handler::{
-    PromStoreProtocolHandler, PromStoreProtocolHandlerRef, PromStoreResponse,
+    PromStoreProtocolHandler, PromStoreProtocolHandlerRef, PromStoreResponse, PromStoreResponseBody,
 };
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
@@ -48,11 +56,11 @@ use crate::error::{
 use crate::instance::Instance;
 
 const SAMPLES_RESPONSE_TYPE: i32 = ResponseType::Samples as i32;
+const STREAMED_XOR_CHUNKS_RESPONSE_TYPE: i32 = ResponseType::StreamedXorChunks as i32;
 
 #[inline]
 fn is_supported(response_type: i32) -> bool {
-    // Only supports samples response right now
-    response_type == SAMPLES_RESPONSE_TYPE
+    response_type == SAMPLES_RESPONSE_TYPE || response_type == STREAMED_XOR_CHUNKS_RESPONSE_TYPE
 }
 
 /// Negotiating the content type of the remote read response.
@@ -78,28 +86,50 @@ fn negotiate_response_type(accepted_response_types: &[i32]) -> ServerResult<Resp
     Ok(ResponseType::try_from(*response_type).unwrap())
 }
 
-async fn to_query_result(table_name: &str, output: Output) -> ServerResult<QueryResult> {
-    let OutputData::Stream(stream) = output.data else {
-        unreachable!()
-    };
+fn output_stream(output: Output) -> SendableRecordBatchStream {
+    match output.data {
+        OutputData::Stream(stream) => stream,
+        OutputData::RecordBatches(recordbatches) => recordbatches.as_stream(),
+        OutputData::AffectedRows(_) => unreachable!(),
+    }
+}
+
+async fn to_query_result(
+    table_name: &str,
+    query: &Query,
+    output: Output,
+) -> ServerResult<QueryResult> {
+    let stream = output_stream(output);
     let recordbatches = RecordBatches::try_collect(stream)
         .await
         .context(error::CollectRecordbatchSnafu)?;
-    Ok(QueryResult {
-        timeseries: prom_store::recordbatches_to_timeseries(table_name, recordbatches)?,
-    })
+    let timeseries = if prom_store::is_series_query(query) {
+        prom_store::recordbatches_to_series(table_name, recordbatches)
+    } else {
+        prom_store::recordbatches_to_timeseries(table_name, recordbatches)?
+    };
+    Ok(QueryResult { timeseries })
 }
 
 impl Instance {
     #[tracing::instrument(skip_all)]
-    async fn handle_remote_query(
+    pub(crate) async fn handle_remote_query(
         &self,
         ctx: &QueryContextRef,
         catalog_name: &str,
         schema_name: &str,
         table_name: &str,
         query: &Query,
+        series_ordered: bool,
     ) -> Result<Output> {
+        self.check_request_privileges(
+            vec![(
+                Privilege::Select,
+                GrantObject::table(catalog_name, schema_name, table_name),
+            )],
+            ctx,
+        )
+        .await?;
         let table = self
             .catalog_manager
             .table(catalog_name, schema_name, table_name)
@@ -116,8 +146,12 @@ impl Instance {
                 table_name: format_full_table_name(catalog_name, schema_name, table_name),
             })?;
 
-        let logical_plan =
-            prom_store::query_to_plan(dataframe, query).context(PromStoreRemoteQueryPlanSnafu)?;
+        let logical_plan = if series_ordered {
+            prom_store::query_to_series_ordered_plan(dataframe, query)
+        } else {
+            prom_store::query_to_plan(dataframe, query)
+        }
+        .context(PromStoreRemoteQueryPlanSnafu)?;
 
         logging::debug!(
             "Prometheus remote read, table: {}, logical plan: {}",
@@ -131,11 +165,67 @@ impl Instance {
             .context(ExecLogicalPlanSnafu)
     }
 
+    /// Queries label sets of series in the table selected by the series `query`.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn handle_series_query(
+        &self,
+        ctx: &QueryContextRef,
+        table_name: &str,
+        query: &Query,
+    ) -> Result<Output> {
+        let catalog_name = ctx.current_catalog();
+        let schema_name = ctx.current_schema();
+        self.check_request_privileges(
+            vec![(
+                Privilege::Select,
+                GrantObject::table(catalog_name, schema_name, table_name),
+            )],
+            ctx,
+        )
+        .await?;
+        let table = self
+            .catalog_manager
+            .table(catalog_name, schema_name, table_name)
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: format_full_table_name(catalog_name, schema_name, table_name),
+            })?;
+
+        let table_info = table.table_info();
+        // A table without time index has no series.
+        let Some(time_index) = table_info.meta.schema.timestamp_column() else {
+            return Ok(Output::new_with_record_batches(RecordBatches::empty()));
+        };
+        let time_index = time_index.name.clone();
+        let tag_columns = table_info
+            .meta
+            .row_key_column_names()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let dataframe = self
+            .query_engine
+            .read_table(table)
+            .with_context(|_| ReadTableSnafu {
+                table_name: format_full_table_name(catalog_name, schema_name, table_name),
+            })?;
+        let logical_plan =
+            prom_store::series_query_to_plan(dataframe, query, &time_index, &tag_columns)
+                .context(PromStoreRemoteQueryPlanSnafu)?;
+
+        self.query_engine
+            .execute(logical_plan, ctx.clone())
+            .await
+            .context(ExecLogicalPlanSnafu)
+    }
+
     #[tracing::instrument(skip_all)]
     async fn handle_remote_queries(
         &self,
         ctx: QueryContextRef,
         queries: &[Query],
+        series_ordered: bool,
     ) -> ServerResult<Vec<(String, Output)>> {
         let mut results = Vec::with_capacity(queries.len());
 
@@ -146,7 +236,14 @@ impl Instance {
             let table_name = prom_store::table_name(query)?;
 
             let output = self
-                .handle_remote_query(&ctx, catalog_name, schema_name, &table_name, query)
+                .handle_remote_query(
+                    &ctx,
+                    catalog_name,
+                    schema_name,
+                    &table_name,
+                    query,
+                    series_ordered,
+                )
                 .await
                 .map_err(BoxedError::new)
                 .with_context(|_| error::ExecuteQuerySnafu {
@@ -166,6 +263,58 @@ impl PromStoreProtocolHandler for Instance {
         request: RowInsertRequests,
         ctx: QueryContextRef,
         with_metric_engine: bool,
+    ) -> ServerResult<Output> {
+        let start = Instant::now();
+        let result = self
+            .handle_remote_write(request, ctx.clone(), with_metric_engine)
+            .await;
+        self.audit("PromStoreWrite", None, &ctx, start, result)
+            .await
+    }
+
+    async fn read(
+        &self,
+        request: ReadRequest,
+        ctx: QueryContextRef,
+    ) -> ServerResult<PromStoreResponse> {
+        let start = Instant::now();
+        let tables = request
+            .queries
+            .iter()
+            .filter_map(|query| prom_store::table_name(query).ok())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let result = self.handle_remote_read(request, ctx.clone()).await;
+        if let Some(audit_logger) = &self.audit_logger {
+            let status_code = match &result {
+                Ok(_) => StatusCode::Success,
+                Err(e) => e.status_code(),
+            };
+            audit_logger
+                .log(
+                    "PromStoreRead",
+                    Some(&tables),
+                    &ctx,
+                    start.elapsed(),
+                    None,
+                    status_code,
+                )
+                .await;
+        }
+        result
+    }
+
+    async fn ingest_metrics(&self, _metrics: Metrics) -> ServerResult<()> {
+        todo!();
+    }
+}
+
+impl Instance {
+    async fn handle_remote_write(
+        &self,
+        request: RowInsertRequests,
+        ctx: QueryContextRef,
+        with_metric_engine: bool,
     ) -> ServerResult<Output> {
         self.plugins
             .get::<PermissionCheckerRef>()
@@ -178,12 +327,17 @@ impl PromStoreProtocolHandler for Instance {
                 .extension(PHYSICAL_TABLE_PARAM)
                 .unwrap_or(GREPTIME_PHYSICAL_TABLE)
                 .to_string();
-            self.handle_metric_row_inserts(request, ctx.clone(), physical_table.to_string())
-                .await
-                .map_err(BoxedError::new)
-                .context(error::ExecuteGrpcQuerySnafu)?
+            self.handle_metric_row_inserts(
+                request,
+                ctx.clone(),
+                IngestProtocol::Prometheus,
+                physical_table.to_string(),
+            )
+            .await
+            .map_err(BoxedError::new)
+            .context(error::ExecuteGrpcQuerySnafu)?
         } else {
-            self.handle_row_inserts(request, ctx.clone())
+            self.handle_protocol_row_inserts(request, ctx.clone(), IngestProtocol::Prometheus)
                 .await
                 .map_err(BoxedError::new)
                 .context(error::ExecuteGrpcQuerySnafu)?
@@ -192,7 +346,7 @@ impl PromStoreProtocolHandler for Instance {
         Ok(output)
     }
 
-    async fn read(
+    async fn handle_remote_read(
         &self,
         request: ReadRequest,
         ctx: QueryContextRef,
@@ -209,47 +363,66 @@ impl PromStoreProtocolHandler for Instance {
 
         let response_type = negotiate_response_type(&request.accepted_response_types)?;
 
-        // TODO(dennis): use read_hints to speedup query if possible
-        let results = self.handle_remote_queries(ctx, &request.queries).await?;
-
-        match response_type {
-            ResponseType::Samples => {
-                let mut query_results = Vec::with_capacity(results.len());
-                let mut map = HashMap::new();
-                for (table_name, output) in results {
-                    let plan = output.meta.plan.clone();
-                    query_results.push(to_query_result(&table_name, output).await?);
-                    if let Some(ref plan) = plan {
-                        collect_plan_metrics(plan.clone(), &mut [&mut map]);
-                    }
-                }
-
-                let response = ReadResponse {
-                    results: query_results,
-                };
-
-                let resp_metrics = map
-                    .into_iter()
-                    .map(|(k, v)| (k, v.into()))
-                    .collect::<HashMap<_, _>>();
-
-                // TODO(dennis): may consume too much memory, adds flow control
-                Ok(PromStoreResponse {
-                    content_type: CONTENT_TYPE_PROTOBUF.clone(),
-                    content_encoding: CONTENT_ENCODING_SNAPPY.clone(),
-                    resp_metrics,
-                    body: prom_store::snappy_compress(&response.encode_to_vec())?,
+        if response_type == ResponseType::StreamedXorChunks {
+            let results = self
+                .handle_remote_queries(ctx, &request.queries, true)
+                .await?;
+            // Queries are executed while the body is written, so the response carries no
+            // metrics of their plans.
+            let frames = results
+                .into_iter()
+                .zip(&request.queries)
+                .enumerate()
+                .map(|(query_index, ((table_name, output), query))| {
+                    prom_store::chunked_frame_stream(
+                        table_name,
+                        query_index as i64,
+                        prom_store::is_series_query(query),
+                        output_stream(output),
+                    )
                 })
+                .collect::<Vec<_>>();
+
+            return Ok(PromStoreResponse {
+                content_type: CONTENT_TYPE_STREAMED_PROTOBUF.clone(),
+                content_encoding: None,
+                resp_metrics: HashMap::new(),
+                body: PromStoreResponseBody::Stream(stream::iter(frames).flatten().boxed()),
+            });
+        }
+
+        let results = self
+            .handle_remote_queries(ctx, &request.queries, false)
+            .await?;
+
+        let mut query_results = Vec::with_capacity(results.len());
+        let mut map = HashMap::new();
+        for ((table_name, output), query) in results.into_iter().zip(&request.queries) {
+            let plan = output.meta.plan.clone();
+            query_results.push(to_query_result(&table_name, query, output).await?);
+            if let Some(ref plan) = plan {
+                collect_plan_metrics(plan.clone(), &mut [&mut map]);
             }
-            ResponseType::StreamedXorChunks => error::NotSupportedSnafu {
-                feat: "streamed remote read",
-            }
-            .fail(),
         }
-    }
 
-    async fn ingest_metrics(&self, _metrics: Metrics) -> ServerResult<()> {
-        todo!();
+        let resp_metrics = map
+            .into_iter()
+            .map(|(k, v)| (k, v.into()))
+            .collect::<HashMap<_, _>>();
+
+        let response = ReadResponse {
+            results: query_results,
+        };
+
+        // TODO(dennis): may consume too much memory, adds flow control
+        Ok(PromStoreResponse {
+            content_type: CONTENT_TYPE_PROTOBUF.clone(),
+            content_encoding: Some(CONTENT_ENCODING_SNAPPY.clone()),
+            resp_metrics,
+            body: PromStoreResponseBody::Bytes(prom_store::snappy_compress(
+                &response.encode_to_vec(),
+            )?),
+        })
     }
 }
 
@@ -286,6 +459,7 @@ impl PromStoreProtocolHandler for ExportMetricHandler {
             .handle_metric_row_inserts(
                 request,
                 ctx,
+                None,
                 &self.statement_executor,
                 GREPTIME_PHYSICAL_TABLE.to_string(),
             )
@@ -16,11 +16,15 @@ use std::sync::Arc;
 
 use api::v1::region::{QueryRequest, RegionRequest, RegionResponse};
 use async_trait::async_trait;
+use catalog::error::Result as CatalogResult;
+use catalog::information_extension::InformationExtension;
 use client::region::check_response_header;
 use common_error::ext::BoxedError;
+use common_meta::cluster::{NodeInfo, NodeStatus, RegionStat};
 use common_meta::datanode_manager::{Datanode, DatanodeManager, DatanodeRef, HandleResponse};
 use common_meta::error::{self as meta_error, Result as MetaResult};
 use common_meta::peer::Peer;
+use common_meta::DatanodeId;
 use common_recordbatch::SendableRecordBatchStream;
 use common_telemetry::tracing;
 use common_telemetry::tracing_context::{FutureExt, TracingContext};
@@ -55,7 +59,7 @@ impl RegionInvoker {
         })?;
 
         self.region_server
-            .handle(body)
+            .handle(request.header.unwrap_or_default(), body)
             .await
             .context(InvokeRegionServerSnafu)
     }
@@ -97,3 +101,57 @@ impl Datanode for RegionInvoker {
             .context(meta_error::ExternalSnafu)
     }
 }
+
+/// The [InformationExtension] of the standalone mode, which reports itself as the only
+/// node and gets region statistics from the local region server.
+pub struct StandaloneInformationExtension {
+    region_server: RegionServer,
+    peer: Peer,
+    start_time_ms: u64,
+}
+
+impl StandaloneInformationExtension {
+    pub fn new(region_server: RegionServer, addr: String) -> Self {
+        Self {
+            region_server,
+            peer: Peer { id: 0, addr },
+            start_time_ms: common_time::util::current_time_millis() as u64,
+        }
+    }
+}
+
+#[async_trait]
+impl InformationExtension for StandaloneInformationExtension {
+    async fn nodes(&self) -> CatalogResult<Vec<NodeInfo>> {
+        Ok(vec![NodeInfo {
+            peer: self.peer.clone(),
+            last_activity_ts: common_time::util::current_time_millis(),
+            status: NodeStatus::Standalone,
+            start_time_ms: self.start_time_ms,
+        }])
+    }
+
+    async fn region_stats(&self) -> CatalogResult<Vec<(DatanodeId, RegionStat)>> {
+        let mut region_stats = Vec::new();
+        for stat in self.region_server.reportable_regions() {
+            let approximate_bytes = self
+                .region_server
+                .region_disk_usage(stat.region_id)
+                .await
+                .unwrap_or(0);
+            region_stats.push((
+                self.peer.id,
+                RegionStat {
+                    id: stat.region_id,
+                    rcus: 0,
+                    wcus: 0,
+                    approximate_bytes,
+                    approximate_rows: 0,
+                    engine: stat.engine,
+                    role: stat.role,
+                },
+            ));
+        }
+        Ok(region_stats)
+    }
+}
@@ -14,6 +14,7 @@
 
 #![feature(assert_matches)]
 
+pub mod audit;
 pub mod error;
 pub mod frontend;
 pub mod heartbeat;
@@ -22,3 +23,4 @@ pub(crate) mod metrics;
 mod script;
 pub mod server;
 pub mod service_config;
+pub mod user_provider;
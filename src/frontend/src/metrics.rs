@@ -51,4 +51,21 @@ lazy_static! {
         "frontend otlp traces rows"
     )
     .unwrap();
+    pub static ref OTLP_LOGS_ROWS: IntCounter = register_int_counter!(
+        "greptime_frontend_otlp_logs_rows",
+        "frontend otlp logs rows"
+    )
+    .unwrap();
+    /// Counter of statements enqueued to the audit log.
+    pub static ref AUDIT_LOG_RECORDS: IntCounter = register_int_counter!(
+        "greptime_frontend_audit_log_records",
+        "frontend audit log records"
+    )
+    .unwrap();
+    /// Counter of audit records dropped as the audit log task is stopped or the write failed.
+    pub static ref AUDIT_LOG_DROPPED_RECORDS: IntCounter = register_int_counter!(
+        "greptime_frontend_audit_log_dropped_records",
+        "frontend audit log dropped records"
+    )
+    .unwrap();
 }
@@ -17,15 +17,18 @@ use std::sync::Arc;
 
 use auth::UserProviderRef;
 use common_base::Plugins;
+use common_meta::key::privilege::PrivilegeManager;
 use common_runtime::Builder as RuntimeBuilder;
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::greptime_handler::GreptimeRequestHandler;
 use servers::grpc::{GrpcServer, GrpcServerConfig};
+use servers::http::tables::TableApiState;
 use servers::http::{HttpServer, HttpServerBuilder};
 use servers::metrics_handler::MetricsHandler;
 use servers::mysql::server::{MysqlServer, MysqlSpawnConfig, MysqlSpawnRef};
 use servers::opentsdb::OpentsdbServer;
 use servers::postgres::PostgresServer;
+use servers::prom_relabel::RelabelRules;
 use servers::query_handler::grpc::ServerGrpcQueryHandlerAdapter;
 use servers::query_handler::sql::ServerSqlQueryHandlerAdapter;
 use servers::server::{Server, ServerHandlers};
@@ -46,6 +49,7 @@ where
     instance: Arc<U>,
     grpc_server_builder: Option<GrpcServerBuilder>,
     http_server_builder: Option<HttpServerBuilder>,
+    privilege_manager: Option<PrivilegeManager>,
     plugins: Plugins,
 }
 
@@ -60,6 +64,7 @@ where
             instance,
             grpc_server_builder: None,
             http_server_builder: None,
+            privilege_manager: None,
             plugins,
         }
     }
@@ -81,11 +86,17 @@ where
         Ok(GrpcServerBuilder::new(grpc_config, grpc_runtime))
     }
 
-    pub fn http_server_builder(&self, opts: &FrontendOptions) -> HttpServerBuilder {
-        let mut builder = HttpServerBuilder::new(opts.http.clone()).with_sql_handler(
-            ServerSqlQueryHandlerAdapter::arc(self.instance.clone()),
-            Some(self.instance.clone()),
-        );
+    pub fn http_server_builder(&self, opts: &FrontendOptions) -> Result<HttpServerBuilder> {
+        let mut builder = HttpServerBuilder::new(opts.http.clone())
+            .with_sql_handler(
+                ServerSqlQueryHandlerAdapter::arc(self.instance.clone()),
+                Some(self.instance.clone()),
+            )
+            .with_table_handler(TableApiState {
+                catalog_manager: self.instance.catalog_manager().clone(),
+                user_provider: self.plugins.get::<UserProviderRef>(),
+                privilege_manager: self.privilege_manager.clone(),
+            });
 
         if let Some(user_provider) = self.plugins.get::<UserProviderRef>() {
             builder = builder.with_user_provider(user_provider);
@@ -100,11 +111,14 @@ where
         }
 
         if opts.prom_store.enable {
+            let relabel_rules = RelabelRules::try_new(&opts.prom_store.relabel_configs)
+                .context(StartServerSnafu)?;
             builder = builder
                 .with_prom_handler(
                     self.instance.clone(),
                     opts.prom_store.with_metric_engine,
                     opts.http.is_strict_mode,
+                    Arc::new(relabel_rules),
                 )
                 .with_prometheus_handler(self.instance.clone());
         }
@@ -112,7 +126,7 @@ where
         if opts.otlp.enable {
             builder = builder.with_otlp_handler(self.instance.clone());
         }
-        builder
+        Ok(builder)
     }
 
     pub fn with_grpc_server_builder(self, builder: GrpcServerBuilder) -> Self {
@@ -129,6 +143,15 @@ where
         }
     }
 
+    /// Sets the privilege manager that decides who is an admin allowed to call
+    /// the debug APIs of the HTTP server.
+    pub fn with_privilege_manager(self, privilege_manager: PrivilegeManager) -> Self {
+        Self {
+            privilege_manager: Some(privilege_manager),
+            ..self
+        }
+    }
+
     fn build_grpc_server(&mut self, opts: &FrontendOptions) -> Result<GrpcServer> {
         let builder = if let Some(builder) = self.grpc_server_builder.take() {
             builder
@@ -157,9 +180,13 @@ where
         let builder = if let Some(builder) = self.http_server_builder.take() {
             builder
         } else {
-            self.http_server_builder(opts)
+            self.http_server_builder(opts)?
         };
 
+        let builder = match self.privilege_manager.clone() {
+            Some(privilege_manager) => builder.with_privilege_manager(privilege_manager),
+            None => builder,
+        };
         let http_server = builder
             .with_metrics_handler(MetricsHandler)
             .with_plugins(self.plugins.clone())
@@ -13,11 +13,15 @@
 // limitations under the License.
 
 use serde::{Deserialize, Serialize};
+use servers::prom_relabel::RelabelConfig;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PromStoreOptions {
     pub enable: bool,
     pub with_metric_engine: bool,
+    /// Relabel rules applied to remote write series before storage.
+    #[serde(default)]
+    pub relabel_configs: Vec<RelabelConfig>,
 }
 
 impl Default for PromStoreOptions {
@@ -25,6 +29,7 @@ impl Default for PromStoreOptions {
         Self {
             enable: true,
             with_metric_engine: true,
+            relabel_configs: Vec::new(),
         }
     }
 }
@@ -37,6 +42,7 @@ mod tests {
     fn test_prom_store_options() {
         let default = PromStoreOptions::default();
         assert!(default.enable);
-        assert!(default.with_metric_engine)
+        assert!(default.with_metric_engine);
+        assert!(default.relabel_configs.is_empty());
     }
 }
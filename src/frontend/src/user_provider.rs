@@ -0,0 +1,283 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use auth::error::{AuthBackendSnafu, IllegalParamSnafu, InvalidConfigSnafu, UserNotFoundSnafu};
+use auth::{Identity, Password, PasswordDigester, PasswordHashes, UserInfoRef, UserProvider};
+use common_error::ext::BoxedError;
+use common_meta::cache_invalidator::{CacheInvalidator, Context};
+use common_meta::instruction::CacheIdent;
+use common_meta::key::user::{UserManager, UserValue};
+use common_meta::kv_backend::KvBackendRef;
+use common_telemetry::info;
+use moka::future::Cache;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{IllegalAuthConfigSnafu, InitUsersSnafu, Result};
+
+/// The name of [MetaUserProvider] in the `user_provider` option.
+pub const META_USER_PROVIDER: &str = "meta_user_provider";
+
+const USER_CACHE_MAX_CAPACITY: u64 = 1024;
+/// How long a successful verification of a plain text password is reused.
+const VERIFIED_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// A [UserProvider] of the users managed by `CREATE USER`, `ALTER USER` and `DROP USER`,
+/// whose credentials are stored in the metadata KV backend.
+///
+/// Credentials are cached until they are invalidated by [CacheIdent::User]. Protocols
+/// like HTTP authenticate each request, so successful verifications of plain text
+/// passwords are cached too, otherwise argon2 would run for every request.
+pub struct MetaUserProvider {
+    user_manager: UserManager,
+    cache: Cache<String, Option<Arc<PasswordHashes>>>,
+    /// The digests of the passwords that were verified, by user.
+    verified: Cache<String, Vec<u8>>,
+    digester: PasswordDigester,
+}
+
+impl MetaUserProvider {
+    pub fn new(kv_backend: KvBackendRef) -> Self {
+        Self {
+            user_manager: UserManager::new(kv_backend),
+            cache: Cache::new(USER_CACHE_MAX_CAPACITY),
+            verified: Cache::builder()
+                .max_capacity(USER_CACHE_MAX_CAPACITY)
+                .time_to_live(VERIFIED_CACHE_TTL)
+                .build(),
+            digester: PasswordDigester::default(),
+        }
+    }
+
+    /// Builds the provider if `option` selects it, returns `None` otherwise.
+    ///
+    /// The option is in format `meta_user_provider[:user=pwd[,user=pwd]]`. The listed users
+    /// are created on startup if they don't exist yet, so that there are users to log in with.
+    /// They don't opt in to the MySQL native password authentication.
+    pub async fn try_from_option(
+        option: Option<&str>,
+        kv_backend: KvBackendRef,
+    ) -> Result<Option<Arc<Self>>> {
+        let Some(option) = option else {
+            return Ok(None);
+        };
+        let (name, content) = option.split_once(':').unwrap_or((option, ""));
+        if name != META_USER_PROVIDER {
+            return Ok(None);
+        }
+
+        let provider = Self::new(kv_backend);
+        for kv in content.split(',').filter(|kv| !kv.is_empty()) {
+            let (username, password) = kv
+                .split_once('=')
+                .context(InvalidConfigSnafu {
+                    value: kv.to_string(),
+                    msg: "MetaUserProviderOption values must be in format `user=pwd[,user=pwd]`",
+                })
+                .context(IllegalAuthConfigSnafu)?;
+            let hashes = PasswordHashes::new(password, false).context(IllegalAuthConfigSnafu)?;
+            if provider
+                .user_manager
+                .create(username, &to_user_value(hashes))
+                .await
+                .context(InitUsersSnafu)?
+            {
+                info!("Created user {username} of {META_USER_PROVIDER}");
+            }
+        }
+
+        Ok(Some(Arc::new(provider)))
+    }
+
+    async fn password_hashes(
+        &self,
+        username: &str,
+    ) -> auth::error::Result<Option<Arc<PasswordHashes>>> {
+        if let Some(hashes) = self.cache.get(username).await {
+            return Ok(hashes);
+        }
+
+        let hashes = self
+            .user_manager
+            .get(username)
+            .await
+            .map_err(BoxedError::new)
+            .context(AuthBackendSnafu)?
+            .map(|value| {
+                Arc::new(PasswordHashes {
+                    password_hash: value.password_hash,
+                    mysql_native_password: value.mysql_native_password,
+                })
+            });
+        self.cache
+            .insert(username.to_string(), hashes.clone())
+            .await;
+
+        Ok(hashes)
+    }
+}
+
+/// Converts `hashes` to the value stored in the metadata.
+fn to_user_value(hashes: PasswordHashes) -> UserValue {
+    UserValue {
+        password_hash: hashes.password_hash,
+        mysql_native_password: hashes.mysql_native_password,
+    }
+}
+
+#[async_trait]
+impl UserProvider for MetaUserProvider {
+    fn name(&self) -> &str {
+        META_USER_PROVIDER
+    }
+
+    async fn authenticate(
+        &self,
+        id: Identity<'_>,
+        password: Password<'_>,
+    ) -> auth::error::Result<UserInfoRef> {
+        let Identity::UserId(username, _) = id;
+        ensure!(
+            !username.is_empty(),
+            IllegalParamSnafu {
+                msg: "blank username"
+            }
+        );
+        let digest = self.digester.digest(&password);
+        if let Some(digest) = &digest {
+            if self.verified.get(username).await.as_ref() == Some(digest) {
+                return Ok(auth::userinfo_by_name(Some(username.to_string())));
+            }
+        }
+
+        let hashes = self
+            .password_hashes(username)
+            .await?
+            .context(UserNotFoundSnafu { username })?;
+        hashes.verify(username, password)?;
+        if let Some(digest) = digest {
+            self.verified.insert(username.to_string(), digest).await;
+        }
+
+        Ok(auth::userinfo_by_name(Some(username.to_string())))
+    }
+
+    async fn authorize(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _user_info: &UserInfoRef,
+    ) -> auth::error::Result<()> {
+        // Privileges are checked per statement.
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CacheInvalidator for MetaUserProvider {
+    async fn invalidate(
+        &self,
+        _ctx: &Context,
+        caches: Vec<CacheIdent>,
+    ) -> common_meta::error::Result<()> {
+        for cache in caches {
+            if let CacheIdent::User(username) = cache {
+                self.cache.invalidate(&username).await;
+                self.verified.invalidate(&username).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+
+    use super::*;
+
+    async fn authenticate(provider: &MetaUserProvider, username: &str, password: &str) -> bool {
+        provider
+            .authenticate(
+                Identity::UserId(username, None),
+                Password::PlainText(password.to_string().into()),
+            )
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_meta_user_provider() {
+        let kv_backend: KvBackendRef = Arc::new(MemoryKvBackend::default());
+        assert!(MetaUserProvider::try_from_option(None, kv_backend.clone())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(MetaUserProvider::try_from_option(
+            Some("static_user_provider:cmd:root=123456"),
+            kv_backend.clone()
+        )
+        .await
+        .unwrap()
+        .is_none());
+        assert!(MetaUserProvider::try_from_option(
+            Some("meta_user_provider:root"),
+            kv_backend.clone()
+        )
+        .await
+        .is_err());
+
+        let provider = MetaUserProvider::try_from_option(
+            Some("meta_user_provider:root=123456"),
+            kv_backend.clone(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(authenticate(&provider, "root", "123456").await);
+        assert!(!authenticate(&provider, "root", "654321").await);
+        assert!(!authenticate(&provider, "alice", "123456").await);
+
+        // Initial users are not reset on restarts.
+        let user_manager = UserManager::new(kv_backend.clone());
+        let hashes = PasswordHashes::new("654321", false).unwrap();
+        assert!(user_manager
+            .update("root", &to_user_value(hashes))
+            .await
+            .unwrap());
+        let restarted = MetaUserProvider::try_from_option(
+            Some("meta_user_provider:root=123456"),
+            kv_backend.clone(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(authenticate(&restarted, "root", "654321").await);
+
+        // Cached credentials are used until they are invalidated.
+        assert!(authenticate(&provider, "root", "123456").await);
+        assert!(!authenticate(&provider, "root", "654321").await);
+        provider
+            .invalidate(
+                &Context::default(),
+                vec![CacheIdent::User("root".to_string())],
+            )
+            .await
+            .unwrap();
+        assert!(!authenticate(&provider, "root", "123456").await);
+        assert!(authenticate(&provider, "root", "654321").await);
+    }
+}
@@ -17,6 +17,7 @@ mod heartbeat;
 mod load_balance;
 mod lock;
 mod procedure;
+mod queued;
 
 mod cluster;
 mod store;
@@ -49,6 +50,7 @@ use snafu::{OptionExt, ResultExt};
 use store::Client as StoreClient;
 
 pub use self::heartbeat::{HeartbeatSender, HeartbeatStream};
+pub use self::queued::QueuedProcedureExecutor;
 use crate::error::{
     ConvertMetaRequestSnafu, ConvertMetaResponseSnafu, Error, NotStartedSnafu, Result,
 };
@@ -271,11 +273,13 @@ impl ClusterInfo for MetaClient {
                     peer,
                     last_activity_ts,
                     status: NodeStatus::Metasrv(MetasrvStatus { is_leader: false }),
+                    start_time_ms: 0,
                 })
                 .chain(leader.into_iter().map(|leader| NodeInfo {
                     peer: leader,
                     last_activity_ts,
                     status: NodeStatus::Metasrv(MetasrvStatus { is_leader: true }),
+                    start_time_ms: 0,
                 }))
                 .collect::<Vec<_>>()
         } else {
@@ -27,7 +27,7 @@ use common_telemetry::{info, warn};
 use snafu::{ensure, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
-use tonic::Status;
+use tonic::{Code, Status};
 
 use crate::client::ask_leader::AskLeader;
 use crate::client::{util, Id};
@@ -162,6 +162,8 @@ impl Inner {
     {
         let ask_leader = self.ask_leader()?;
         let mut times = 0;
+        // Whether a request may have reached the leader without a response.
+        let mut maybe_delivered = false;
 
         while times < self.max_retry {
             if let Some(leader) = &ask_leader.get_leader() {
@@ -181,6 +183,7 @@ impl Inner {
                         // The leader may be unreachable.
                         if util::is_unreachable(&status) {
                             warn!("Failed to {task} to {leader}, source: {status}");
+                            maybe_delivered |= status.code() == Code::DeadlineExceeded;
                             let leader = ask_leader.ask_leader().await?;
                             info!("Procedure client updated to new leader addr: {leader}");
                             times += 1;
@@ -195,6 +198,7 @@ impl Inner {
             }
         }
 
+        ensure!(!maybe_delivered, error::ResponseTimeoutSnafu { task });
         error::RetryTimesExceededSnafu {
             msg: "Failed to {task}",
             times: self.max_retry,
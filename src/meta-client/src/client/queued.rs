@@ -0,0 +1,220 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_error::ext::BoxedError;
+use common_meta::ddl::{ExecutorContext, ProcedureExecutor};
+use common_meta::error::{self as meta_error, Result as MetaResult};
+use common_meta::rpc::ddl::{DdlTask, SubmitDdlTaskRequest, SubmitDdlTaskResponse};
+use common_meta::rpc::procedure::{
+    MigrateRegionRequest, MigrateRegionResponse, ProcedureStateResponse,
+};
+use common_telemetry::{info, warn};
+use snafu::ResultExt;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::client::MetaClient;
+use crate::error::Result;
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A [ProcedureExecutor] that queues DDL tasks while metasrv is unavailable.
+///
+/// Queued tasks are submitted one by one in order once metasrv is reachable again.
+/// A task fails with [MetasrvUnavailable](meta_error::Error::MetasrvUnavailable)
+/// if metasrv is still unreachable after `timeout`.
+///
+/// A failed submit may still have reached metasrv, so a task is only resubmitted
+/// in a form that is safe to run twice, see [make_idempotent].
+pub struct QueuedProcedureExecutor {
+    client: Arc<MetaClient>,
+    timeout: Duration,
+    queue: Mutex<()>,
+}
+
+impl QueuedProcedureExecutor {
+    pub fn new(client: Arc<MetaClient>, timeout: Duration) -> Self {
+        Self {
+            client,
+            timeout,
+            queue: Mutex::new(()),
+        }
+    }
+
+    async fn submit(&self, mut request: SubmitDdlTaskRequest) -> MetaResult<SubmitDdlTaskResponse> {
+        let mut queued = None;
+        loop {
+            let e = match self.client.submit_ddl_task(request.clone()).await {
+                Err(e) if e.is_unavailable() => e,
+                result => {
+                    if queued.is_some() {
+                        info!("Submitted the queued DDL task after metasrv is available");
+                    }
+                    return result
+                        .map_err(BoxedError::new)
+                        .context(meta_error::ExternalSnafu);
+                }
+            };
+            if e.may_be_delivered() && !make_idempotent(&mut request.task) {
+                warn!(e; "Metasrv may have received the DDL task that can't be resubmitted safely");
+                return Err(e)
+                    .map_err(BoxedError::new)
+                    .context(meta_error::ExternalSnafu);
+            }
+
+            let deadline = match &queued {
+                Some((_guard, deadline)) => *deadline,
+                None => {
+                    warn!(e; "Metasrv is unavailable, queue the DDL task");
+                    let guard = self.queue.lock().await;
+                    queued = Some((guard, Instant::now() + self.timeout));
+                    // Tasks ahead in the queue may have waited for a while, retries at once.
+                    continue;
+                }
+            };
+            if Instant::now() + RETRY_INTERVAL > deadline {
+                return meta_error::MetasrvUnavailableSnafu {
+                    timeout: self.timeout,
+                }
+                .fail();
+            }
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+}
+
+/// Turns `task` into a task that succeeds if it's already done, so resubmitting
+/// it is safe. Returns false if `task` can't be made idempotent.
+fn make_idempotent(task: &mut DdlTask) -> bool {
+    match task {
+        DdlTask::CreateTable(task) => task.create_table.create_if_not_exists = true,
+        DdlTask::CreateLogicalTables(tasks) => tasks
+            .iter_mut()
+            .for_each(|task| task.create_table.create_if_not_exists = true),
+        DdlTask::DropTable(task) => task.drop_if_exists = true,
+        DdlTask::DropLogicalTables(tasks) => {
+            tasks.iter_mut().for_each(|task| task.drop_if_exists = true)
+        }
+        DdlTask::CreateDatabase(task) => task.create_if_not_exists = true,
+        DdlTask::DropDatabase(task) => task.drop_if_exists = true,
+        DdlTask::AlterTable(_)
+        | DdlTask::AlterLogicalTables(_)
+        | DdlTask::TruncateTable(_)
+        | DdlTask::CreateView(_)
+        | DdlTask::DropView(_) => return false,
+    }
+    true
+}
+
+#[async_trait::async_trait]
+impl ProcedureExecutor for QueuedProcedureExecutor {
+    async fn submit_ddl_task(
+        &self,
+        _ctx: &ExecutorContext,
+        request: SubmitDdlTaskRequest,
+    ) -> MetaResult<SubmitDdlTaskResponse> {
+        self.submit(request).await
+    }
+
+    async fn migrate_region(
+        &self,
+        _ctx: &ExecutorContext,
+        request: MigrateRegionRequest,
+    ) -> MetaResult<MigrateRegionResponse> {
+        self.client
+            .migrate_region(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
+
+    async fn query_procedure_state(
+        &self,
+        _ctx: &ExecutorContext,
+        pid: &str,
+    ) -> MetaResult<ProcedureStateResponse> {
+        self.client
+            .query_procedure_state(pid)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::meta::Role;
+    use common_meta::rpc::ddl::DropDependents;
+
+    use super::*;
+    use crate::client::MetaClientBuilder;
+
+    #[tokio::test]
+    async fn test_queue_while_metasrv_unavailable() {
+        let mut client = MetaClientBuilder::new(0, 0, Role::Frontend)
+            .enable_heartbeat()
+            .enable_procedure()
+            .build();
+        // Nothing listens on the port.
+        client.start(&["127.0.0.1:1"]).await.unwrap();
+        let executor = QueuedProcedureExecutor::new(Arc::new(client), Duration::ZERO);
+
+        let request = SubmitDdlTaskRequest {
+            task: DdlTask::new_truncate_table(
+                "greptime".to_string(),
+                "public".to_string(),
+                "foo".to_string(),
+                1024,
+            ),
+        };
+        let err = executor
+            .submit_ddl_task(&ExecutorContext::default(), request)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, meta_error::Error::MetasrvUnavailable { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_make_idempotent() {
+        let mut task = DdlTask::new_drop_table(
+            "greptime".to_string(),
+            "public".to_string(),
+            "foo".to_string(),
+            1024,
+            false,
+            DropDependents::Ignore,
+        );
+        assert!(make_idempotent(&mut task));
+        assert!(matches!(task, DdlTask::DropTable(task) if task.drop_if_exists));
+
+        let mut task =
+            DdlTask::new_create_database("greptime".to_string(), "db".to_string(), false, None);
+        assert!(make_idempotent(&mut task));
+        assert!(matches!(task, DdlTask::CreateDatabase(task) if task.create_if_not_exists));
+
+        let mut task = DdlTask::new_truncate_table(
+            "greptime".to_string(),
+            "public".to_string(),
+            "foo".to_string(),
+            1024,
+        );
+        assert!(!make_idempotent(&mut task));
+    }
+}
@@ -77,11 +77,38 @@ pub enum Error {
 
     #[snafu(display("Retry exceeded max times({}), message: {}", times, msg))]
     RetryTimesExceeded { times: usize, msg: String },
+
+    #[snafu(display(
+        "Failed to {} as metasrv doesn't respond in time, the request may have been received",
+        task
+    ))]
+    ResponseTimeout { task: String, location: Location },
 }
 
 #[allow(dead_code)]
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Returns true if the error means metasrv can't be reached.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Error::AskLeader { .. }
+                | Error::NoLeader { .. }
+                | Error::AskLeaderTimeout { .. }
+                | Error::CreateChannel { .. }
+                | Error::RetryTimesExceeded { .. }
+                | Error::ResponseTimeout { .. }
+        )
+    }
+
+    /// Returns true if the request may have reached metasrv before it fails, e.g. a
+    /// call times out after the request is sent.
+    pub fn may_be_delivered(&self) -> bool {
+        matches!(self, Error::ResponseTimeout { .. })
+    }
+}
+
 impl ErrorExt for Error {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -97,7 +124,8 @@ impl ErrorExt for Error {
             | Error::SendHeartbeat { .. }
             | Error::CreateHeartbeatStream { .. }
             | Error::CreateChannel { .. }
-            | Error::RetryTimesExceeded { .. } => StatusCode::Internal,
+            | Error::RetryTimesExceeded { .. }
+            | Error::ResponseTimeout { .. } => StatusCode::Internal,
 
             Error::MetaServer { code, .. } => *code,
 
@@ -44,6 +44,23 @@ pub struct MetaClientOptions {
     #[serde(default = "default_metadata_cache_tti")]
     #[serde(with = "humantime_serde")]
     pub metadata_cache_tti: Duration,
+    /// Directory of the local metadata snapshot. If set, the frontend can start and
+    /// serve reads with the snapshot while metasrv is unavailable.
+    #[serde(default)]
+    pub metadata_snapshot_dir: Option<String>,
+    /// How often the local metadata snapshot is synced with metasrv.
+    #[serde(default = "default_metadata_snapshot_sync_interval")]
+    #[serde(with = "humantime_serde")]
+    pub metadata_snapshot_sync_interval: Duration,
+    /// Reads fail instead of being served with the local metadata snapshot if the
+    /// snapshot isn't synced within this duration.
+    #[serde(default = "default_metadata_snapshot_max_staleness")]
+    #[serde(with = "humantime_serde")]
+    pub metadata_snapshot_max_staleness: Duration,
+    /// How long DDL waits for metasrv to become available while it's unavailable.
+    #[serde(default = "default_ddl_queue_timeout")]
+    #[serde(with = "humantime_serde")]
+    pub ddl_queue_timeout: Duration,
 }
 
 fn default_heartbeat_timeout() -> Duration {
@@ -74,6 +91,18 @@ fn default_metadata_cache_tti() -> Duration {
     Duration::from_secs(300u64)
 }
 
+fn default_metadata_snapshot_sync_interval() -> Duration {
+    Duration::from_secs(60u64)
+}
+
+fn default_metadata_snapshot_max_staleness() -> Duration {
+    Duration::from_secs(3600u64)
+}
+
+fn default_ddl_queue_timeout() -> Duration {
+    Duration::from_secs(60u64)
+}
+
 impl Default for MetaClientOptions {
     fn default() -> Self {
         Self {
@@ -86,6 +115,10 @@ impl Default for MetaClientOptions {
             metadata_cache_max_capacity: default_metadata_cache_max_capacity(),
             metadata_cache_ttl: default_metadata_cache_ttl(),
             metadata_cache_tti: default_metadata_cache_tti(),
+            metadata_snapshot_dir: None,
+            metadata_snapshot_sync_interval: default_metadata_snapshot_sync_interval(),
+            metadata_snapshot_max_staleness: default_metadata_snapshot_max_staleness(),
+            ddl_queue_timeout: default_ddl_queue_timeout(),
         }
     }
 }
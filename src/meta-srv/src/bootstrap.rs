@@ -29,12 +29,14 @@ use etcd_client::Client;
 use futures::future;
 use servers::configurator::ConfiguratorRef;
 use servers::export_metrics::ExportMetricsTask;
+use servers::grpc::{create_health_service, create_reflection_service};
 use servers::http::{HttpServer, HttpServerBuilder};
 use servers::metrics_handler::MetricsHandler;
 use servers::server::Server;
 use snafu::ResultExt;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tonic::server::NamedService;
 use tonic::transport::server::{Router, TcpIncoming};
 
 use crate::election::etcd::EtcdElection;
@@ -101,7 +103,7 @@ impl MetasrvInstance {
 
         self.signal_sender = Some(tx);
 
-        let mut router = router(self.metasrv.clone());
+        let mut router = router(self.metasrv.clone()).await;
         if let Some(configurator) = self.metasrv.plugins().get::<ConfiguratorRef>() {
             router = configurator.config_grpc(router);
         }
@@ -167,7 +169,17 @@ pub async fn bootstrap_metasrv_with_router(
     Ok(())
 }
 
-pub fn router(metasrv: Metasrv) -> Router {
+pub async fn router(metasrv: Metasrv) -> Router {
+    let service_names = [
+        <HeartbeatServer<Metasrv> as NamedService>::NAME,
+        <StoreServer<Metasrv> as NamedService>::NAME,
+        <ClusterServer<Metasrv> as NamedService>::NAME,
+        <LockServer<Metasrv> as NamedService>::NAME,
+        <ProcedureServiceServer<Metasrv> as NamedService>::NAME,
+    ];
+    // The reporter only needs to be kept for changing the serving status.
+    let (_health_reporter, health_service) = create_health_service(&service_names).await;
+
     tonic::transport::Server::builder()
         .accept_http1(true) // for admin services
         .add_service(HeartbeatServer::new(metasrv.clone()))
@@ -175,6 +187,8 @@ pub fn router(metasrv: Metasrv) -> Router {
         .add_service(ClusterServer::new(metasrv.clone()))
         .add_service(LockServer::new(metasrv.clone()))
         .add_service(ProcedureServiceServer::new(metasrv.clone()))
+        .add_service(health_service)
+        .add_service(create_reflection_service(&service_names))
         .add_service(admin::make_admin_service(metasrv))
 }
 
@@ -55,6 +55,19 @@ pub enum Error {
         region_id: RegionId,
     },
 
+    #[snafu(display(
+        "Failed to close the region: {} on peer: {}, error: {:?}",
+        region_id,
+        peer_id,
+        error
+    ))]
+    CloseRegion {
+        location: Location,
+        peer_id: DatanodeId,
+        region_id: RegionId,
+        error: Option<String>,
+    },
+
     #[snafu(display("Failed to init ddl manager"))]
     InitDdlManager {
         location: Location,
@@ -754,6 +767,7 @@ impl ErrorExt for Error {
             | Error::Txn { .. }
             | Error::TableIdChanged { .. }
             | Error::RegionOpeningRace { .. }
+            | Error::CloseRegion { .. }
             | Error::RegionRouteNotFound { .. }
             | Error::MigrationAbort { .. }
             | Error::MigrationRunning { .. } => StatusCode::Unexpected,
@@ -48,6 +48,7 @@ impl HeartbeatHandler for CollectFrontendClusterInfoHandler {
             peer,
             last_activity_ts: common_time::util::current_time_millis(),
             status: NodeStatus::Frontend(FrontendStatus {}),
+            start_time_ms: 0,
         };
 
         save_to_mem_store(key, value, ctx).await?;
@@ -94,7 +95,9 @@ impl HeartbeatHandler for CollectDatanodeClusterInfoHandler {
                 wcus: stat.wcus,
                 leader_regions,
                 follower_regions,
+                region_stats: stat.region_stats.clone(),
             }),
+            start_time_ms: stat.node_epoch,
         };
 
         save_to_mem_store(key, value, ctx).await?;
@@ -15,6 +15,7 @@
 use std::collections::HashSet;
 
 use api::v1::meta::HeartbeatRequest;
+pub use common_meta::cluster::RegionStat;
 use common_time::util as time_util;
 use serde::{Deserialize, Serialize};
 use store_api::region_engine::RegionRole;
@@ -42,24 +43,6 @@ pub struct Stat {
     pub node_epoch: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RegionStat {
-    /// The region_id.
-    pub id: RegionId,
-    /// The read capacity units during this period
-    pub rcus: i64,
-    /// The write capacity units during this period
-    pub wcus: i64,
-    /// Approximate bytes of this region
-    pub approximate_bytes: i64,
-    /// Approximate number of rows in this region
-    pub approximate_rows: i64,
-    /// The engine name.
-    pub engine: String,
-    /// The region role.
-    pub role: RegionRole,
-}
-
 impl Stat {
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -107,8 +90,8 @@ impl TryFrom<HeartbeatRequest> for Stat {
             (Some(header), Some(peer)) => {
                 let region_stats = region_stats
                     .into_iter()
-                    .map(RegionStat::try_from)
-                    .collect::<Result<Vec<_>, _>>()?;
+                    .map(RegionStat::from)
+                    .collect::<Vec<_>>();
 
                 Ok(Self {
                     timestamp_millis: time_util::current_time_millis(),
@@ -132,22 +115,6 @@ impl TryFrom<HeartbeatRequest> for Stat {
     }
 }
 
-impl TryFrom<api::v1::meta::RegionStat> for RegionStat {
-    type Error = Error;
-
-    fn try_from(value: api::v1::meta::RegionStat) -> Result<Self, Self::Error> {
-        Ok(Self {
-            id: RegionId::from_u64(value.region_id),
-            rcus: value.rcus,
-            wcus: value.wcus,
-            approximate_bytes: value.approximate_bytes,
-            approximate_rows: value.approximate_rows,
-            engine: value.engine.to_string(),
-            role: RegionRole::from(value.role()),
-        })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use crate::handler::node_stat::Stat;
@@ -297,6 +297,7 @@ mod test {
                 follower_peers: vec![follower_peer.clone()],
                 leader_status: Some(RegionStatus::Downgraded),
                 leader_down_since: Some(1),
+                leader_epoch: 0,
             },
             RegionRoute {
                 region: Region::new_test(another_region_id),
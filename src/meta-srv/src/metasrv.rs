@@ -51,6 +51,7 @@ use crate::failure_detector::PhiAccrualFailureDetectorOptions;
 use crate::handler::HeartbeatHandlerGroup;
 use crate::lease::lookup_alive_datanode_peer;
 use crate::lock::DistLockRef;
+use crate::procedure::partition_retention::PartitionRetentionManagerRef;
 use crate::procedure::region_migration::manager::RegionMigrationManagerRef;
 use crate::pubsub::{PublishRef, SubscribeManagerRef};
 use crate::selector::{Selector, SelectorType};
@@ -76,6 +77,8 @@ pub struct MetasrvOptions {
     pub use_memory_store: bool,
     /// Whether to enable region failover.
     pub enable_region_failover: bool,
+    /// The options of dropping partitions past TTL.
+    pub partition_retention: PartitionRetentionOptions,
     /// The HTTP server options.
     pub http: HttpOptions,
     /// The logging options.
@@ -125,6 +128,7 @@ impl Default for MetasrvOptions {
             selector: SelectorType::default(),
             use_memory_store: false,
             enable_region_failover: false,
+            partition_retention: PartitionRetentionOptions::default(),
             http: HttpOptions::default(),
             logging: LoggingOptions {
                 dir: format!("{METASRV_HOME}/logs"),
@@ -159,6 +163,27 @@ pub struct MetasrvInfo {
     pub server_addr: String,
 }
 
+/// Options of dropping partitions past the TTL of their tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartitionRetentionOptions {
+    /// Whether to truncate regions of partitions past TTL, if tables are partitioned by
+    /// their time index.
+    pub enable: bool,
+    /// The interval to check partitions past TTL.
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+}
+
+impl Default for PartitionRetentionOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            check_interval: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
 // Options for datanode.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct DatanodeOptions {
@@ -299,6 +324,7 @@ pub struct Metasrv {
     memory_region_keeper: MemoryRegionKeeperRef,
     greptimedb_telemetry_task: Arc<GreptimeDBTelemetryTask>,
     region_migration_manager: RegionMigrationManagerRef,
+    partition_retention_manager: Option<PartitionRetentionManagerRef>,
 
     plugins: Plugins,
 }
@@ -409,6 +435,14 @@ impl Metasrv {
                 .context(StartProcedureManagerSnafu)?;
         }
 
+        if let Some(partition_retention_manager) = &self.partition_retention_manager {
+            partition_retention_manager.clone().run(
+                self.options.partition_retention.check_interval,
+                self.state.clone(),
+                self.started.clone(),
+            );
+        }
+
         info!("Metasrv started");
         Ok(())
     }
@@ -59,6 +59,7 @@ use crate::lock::DistLockRef;
 use crate::metasrv::{
     ElectionRef, Metasrv, MetasrvInfo, MetasrvOptions, SelectorContext, SelectorRef, TABLE_ID_SEQ,
 };
+use crate::procedure::partition_retention::PartitionRetentionManager;
 use crate::procedure::region_failover::RegionFailoverManager;
 use crate::procedure::region_migration::manager::RegionMigrationManager;
 use crate::procedure::region_migration::DefaultContextFactory;
@@ -234,9 +235,10 @@ impl MetasrvBuilder {
 
         let opening_region_keeper = Arc::new(MemoryRegionKeeper::default());
 
+        let datanode_manager = datanode_manager.unwrap_or_else(|| build_datanode_clients(&options));
         let ddl_manager = build_ddl_manager(
             &options,
-            datanode_manager,
+            datanode_manager.clone(),
             &procedure_manager,
             &mailbox,
             &table_metadata_manager,
@@ -255,6 +257,18 @@ impl MetasrvBuilder {
         ));
         region_migration_manager.try_start()?;
 
+        let partition_retention_manager = if options.partition_retention.enable {
+            let manager = Arc::new(PartitionRetentionManager::new(
+                procedure_manager.clone(),
+                table_metadata_manager.clone(),
+                datanode_manager,
+            ));
+            manager.try_start()?;
+            Some(manager)
+        } else {
+            None
+        };
+
         let handler_group = match handler_group {
             Some(handler_group) => handler_group,
             None => {
@@ -346,6 +360,7 @@ impl MetasrvBuilder {
             plugins: plugins.unwrap_or_else(Plugins::default),
             memory_region_keeper: opening_region_keeper,
             region_migration_manager,
+            partition_retention_manager,
         })
     }
 }
@@ -390,26 +405,27 @@ fn build_procedure_manager(
     Arc::new(LocalManager::new(manager_config, Arc::new(state_store)))
 }
 
+fn build_datanode_clients(options: &MetasrvOptions) -> DatanodeManagerRef {
+    let datanode_client_channel_config = ChannelConfig::new()
+        .timeout(Duration::from_millis(
+            options.datanode.client_options.timeout_millis,
+        ))
+        .connect_timeout(Duration::from_millis(
+            options.datanode.client_options.connect_timeout_millis,
+        ))
+        .tcp_nodelay(options.datanode.client_options.tcp_nodelay);
+    Arc::new(DatanodeClients::new(datanode_client_channel_config))
+}
+
 fn build_ddl_manager(
     options: &MetasrvOptions,
-    datanode_clients: Option<DatanodeManagerRef>,
+    datanode_clients: DatanodeManagerRef,
     procedure_manager: &ProcedureManagerRef,
     mailbox: &MailboxRef,
     table_metadata_manager: &TableMetadataManagerRef,
     table_metadata_allocator: &TableMetadataAllocatorRef,
     memory_region_keeper: &MemoryRegionKeeperRef,
 ) -> Result<DdlManagerRef> {
-    let datanode_clients = datanode_clients.unwrap_or_else(|| {
-        let datanode_client_channel_config = ChannelConfig::new()
-            .timeout(Duration::from_millis(
-                options.datanode.client_options.timeout_millis,
-            ))
-            .connect_timeout(Duration::from_millis(
-                options.datanode.client_options.connect_timeout_millis,
-            ))
-            .tcp_nodelay(options.datanode.client_options.tcp_nodelay);
-        Arc::new(DatanodeClients::new(datanode_client_channel_config))
-    });
     let cache_invalidator = Arc::new(MetasrvCacheInvalidator::new(
         mailbox.clone(),
         MetasrvInfo {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod partition_retention;
 pub mod region_failover;
 pub mod region_migration;
 #[cfg(test)]
@@ -0,0 +1,541 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Drops data of partitions past the TTL of their tables.
+//!
+//! If a table is partitioned by its time index, a partition whose upper bound of the time
+//! index is before `now - ttl` only contains expired rows. Instead of filtering out the rows
+//! in compaction, the [PartitionRetentionManager] periodically truncates regions of such
+//! partitions by the [PartitionRetentionProcedure]. The regions are kept so the partition
+//! rule still covers all values of the table.
+
+use std::collections::HashSet;
+use std::ops::Bound;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use api::v1::region::{
+    region_request, RegionRequest, RegionRequestHeader, TruncateRequest as PbTruncateRegionRequest,
+};
+use async_trait::async_trait;
+use common_catalog::consts::MITO_ENGINE;
+use common_error::ext::BoxedError;
+use common_meta::datanode_manager::DatanodeManagerRef;
+use common_meta::key::TableMetadataManagerRef;
+use common_meta::lock_key::TableLock;
+use common_meta::rpc::router::{find_leader_regions, find_leaders};
+use common_procedure::error::{FromJsonSnafu, ToJsonSnafu};
+use common_procedure::{
+    watcher, Context as ProcedureContext, Error as ProcedureError, LockKey, Procedure,
+    ProcedureManagerRef, ProcedureWithId, Result as ProcedureResult, Status,
+};
+use common_telemetry::tracing_context::TracingContext;
+use common_telemetry::{error, info, warn};
+use common_time::Timestamp;
+use datatypes::value::Value;
+use futures::future::join_all;
+use futures::TryStreamExt;
+use partition::partition::{PartitionBound, PartitionDef};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use store_api::storage::{RegionId, RegionNumber};
+use table::metadata::{RawTableInfo, TableId};
+use tokio::time::MissedTickBehavior;
+
+use crate::error::{self, Result};
+use crate::state::{State, StateRef};
+
+/// Context shared by [PartitionRetentionProcedure]s.
+#[derive(Clone)]
+pub struct PartitionRetentionContext {
+    table_metadata_manager: TableMetadataManagerRef,
+    datanode_manager: DatanodeManagerRef,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PartitionRetentionData {
+    table_id: TableId,
+    /// Regions of partitions past TTL.
+    regions: Vec<RegionNumber>,
+}
+
+/// Truncates regions of partitions past the TTL of a table.
+pub struct PartitionRetentionProcedure {
+    data: PartitionRetentionData,
+    context: PartitionRetentionContext,
+}
+
+impl PartitionRetentionProcedure {
+    pub const TYPE_NAME: &'static str = "metasrv-procedure::PartitionRetention";
+
+    fn new(
+        table_id: TableId,
+        regions: Vec<RegionNumber>,
+        context: PartitionRetentionContext,
+    ) -> Self {
+        Self {
+            data: PartitionRetentionData { table_id, regions },
+            context,
+        }
+    }
+
+    fn from_json(json: &str, context: PartitionRetentionContext) -> ProcedureResult<Self> {
+        let data = serde_json::from_str(json).context(FromJsonSnafu)?;
+        Ok(Self { data, context })
+    }
+
+    async fn truncate_regions(&self) -> Result<()> {
+        let table_id = self.data.table_id;
+        let Some(table_route) = self
+            .context
+            .table_metadata_manager
+            .table_route_manager()
+            .table_route_storage()
+            .get(table_id)
+            .await
+            .context(error::TableMetadataManagerSnafu)?
+        else {
+            // The table has been dropped.
+            return Ok(());
+        };
+        let region_routes = table_route
+            .region_routes()
+            .context(error::TableMetadataManagerSnafu)?;
+
+        let mut tasks = Vec::with_capacity(self.data.regions.len());
+        for peer in find_leaders(region_routes) {
+            let datanode = self.context.datanode_manager.datanode(&peer).await;
+            for region in find_leader_regions(region_routes, &peer) {
+                if !self.data.regions.contains(&region) {
+                    continue;
+                }
+                let region_id = RegionId::new(table_id, region);
+                let request = RegionRequest {
+                    header: Some(RegionRequestHeader {
+                        tracing_context: TracingContext::from_current_span().to_w3c(),
+                        ..Default::default()
+                    }),
+                    body: Some(region_request::Body::Truncate(PbTruncateRegionRequest {
+                        region_id: region_id.as_u64(),
+                    })),
+                };
+
+                let datanode = datanode.clone();
+                let peer = peer.clone();
+                tasks.push(async move {
+                    info!("Truncating region {region_id} past TTL on Datanode {peer:?}");
+                    datanode
+                        .handle(request)
+                        .await
+                        .map_err(BoxedError::new)
+                        .context(error::OperateRegionSnafu { peer })
+                });
+            }
+        }
+
+        join_all(tasks)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Procedure for PartitionRetentionProcedure {
+    fn type_name(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    async fn execute(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<Status> {
+        self.truncate_regions().await.map_err(|e| {
+            // Datanodes may be unavailable for a while.
+            if e.is_retryable() || matches!(e, error::Error::OperateRegion { .. }) {
+                ProcedureError::retry_later(e)
+            } else {
+                ProcedureError::external(e)
+            }
+        })?;
+        Ok(Status::done())
+    }
+
+    fn dump(&self) -> ProcedureResult<String> {
+        serde_json::to_string(&self.data).context(ToJsonSnafu)
+    }
+
+    fn lock_key(&self) -> LockKey {
+        LockKey::single(TableLock::Write(self.data.table_id))
+    }
+}
+
+pub type PartitionRetentionManagerRef = Arc<PartitionRetentionManager>;
+
+/// Periodically finds partitions past TTL and truncates their regions.
+pub struct PartitionRetentionManager {
+    procedure_manager: ProcedureManagerRef,
+    context: PartitionRetentionContext,
+    /// Regions truncated since the metasrv started, which aren't truncated again. Rows
+    /// written to them later are still removed by compaction. Regions of dropped tables
+    /// are removed in the next check.
+    truncated: Mutex<HashSet<RegionId>>,
+}
+
+impl PartitionRetentionManager {
+    pub fn new(
+        procedure_manager: ProcedureManagerRef,
+        table_metadata_manager: TableMetadataManagerRef,
+        datanode_manager: DatanodeManagerRef,
+    ) -> Self {
+        Self {
+            procedure_manager,
+            context: PartitionRetentionContext {
+                table_metadata_manager,
+                datanode_manager,
+            },
+            truncated: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Registers the loader of [PartitionRetentionProcedure].
+    pub(crate) fn try_start(&self) -> Result<()> {
+        let context = self.context.clone();
+        self.procedure_manager
+            .register_loader(
+                PartitionRetentionProcedure::TYPE_NAME,
+                Box::new(move |json| {
+                    PartitionRetentionProcedure::from_json(json, context.clone())
+                        .map(|p| Box::new(p) as _)
+                }),
+            )
+            .context(error::RegisterProcedureLoaderSnafu {
+                type_name: PartitionRetentionProcedure::TYPE_NAME,
+            })
+    }
+
+    /// Checks partitions of all tables every `interval` while the metasrv is the leader.
+    pub(crate) fn run(
+        self: Arc<Self>,
+        interval: Duration,
+        state: StateRef,
+        started: Arc<AtomicBool>,
+    ) {
+        let _handle = common_runtime::spawn_bg(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            while started.load(Ordering::Relaxed) {
+                let _ = ticker.tick().await;
+                let is_leader = matches!(*state.read().unwrap(), State::Leader(_));
+                if !is_leader {
+                    continue;
+                }
+                if let Err(e) = self.check_tables().await {
+                    error!(e; "Failed to check partitions past TTL");
+                }
+            }
+        });
+    }
+
+    async fn check_tables(&self) -> Result<()> {
+        let manager = &self.context.table_metadata_manager;
+        let mut table_ids = HashSet::new();
+        let catalogs = manager
+            .catalog_manager()
+            .catalog_names()
+            .try_collect::<Vec<_>>()
+            .await
+            .context(error::TableMetadataManagerSnafu)?;
+        for catalog in catalogs {
+            let schemas = manager
+                .schema_manager()
+                .schema_names(&catalog)
+                .try_collect::<Vec<_>>()
+                .await
+                .context(error::TableMetadataManagerSnafu)?;
+            for schema in schemas {
+                let tables = manager
+                    .table_name_manager()
+                    .tables(&catalog, &schema)
+                    .try_collect::<Vec<_>>()
+                    .await
+                    .context(error::TableMetadataManagerSnafu)?;
+                for (table_name, table) in tables {
+                    let _ = table_ids.insert(table.table_id());
+                    if let Err(e) = self.check_table(table.table_id()).await {
+                        error!(e; "Failed to drop partitions past TTL of table {catalog}.{schema}.{table_name}");
+                    }
+                }
+            }
+        }
+
+        self.truncated
+            .lock()
+            .unwrap()
+            .retain(|region_id| table_ids.contains(&region_id.table_id()));
+        Ok(())
+    }
+
+    /// Truncates regions of partitions past TTL of the table `table_id`.
+    async fn check_table(&self, table_id: TableId) -> Result<()> {
+        let manager = &self.context.table_metadata_manager;
+        let Some(table_info) = manager
+            .table_info_manager()
+            .get(table_id)
+            .await
+            .context(error::TableMetadataManagerSnafu)?
+        else {
+            return Ok(());
+        };
+        let table_info = &table_info.table_info;
+        let (Some(ttl), Some(time_index)) = (table_info.meta.options.ttl, time_index(table_info))
+        else {
+            return Ok(());
+        };
+        // Other engines may not support truncating regions, e.g. regions of metric engine
+        // are shared by logical tables.
+        if table_info.meta.engine != MITO_ENGINE {
+            return Ok(());
+        }
+        let Ok(expire_time) = Timestamp::current_millis().sub_duration(ttl) else {
+            return Ok(());
+        };
+
+        let Some(table_route) = manager
+            .table_route_manager()
+            .table_route_storage()
+            .get(table_id)
+            .await
+            .context(error::TableMetadataManagerSnafu)?
+        else {
+            return Ok(());
+        };
+        let region_routes = table_route
+            .region_routes()
+            .context(error::TableMetadataManagerSnafu)?;
+        let mut regions = Vec::new();
+        {
+            let mut truncated = self.truncated.lock().unwrap();
+            // Forgets regions removed from the table.
+            truncated.retain(|region_id| {
+                region_id.table_id() != table_id
+                    || region_routes
+                        .iter()
+                        .any(|route| route.region.id == *region_id)
+            });
+            for route in region_routes {
+                let region_id = route.region.id;
+                let Some(partition) = route.region.partition.clone() else {
+                    continue;
+                };
+                if truncated.contains(&region_id) {
+                    continue;
+                }
+                match PartitionDef::try_from(partition) {
+                    Ok(partition) if is_expired(&partition, time_index, &expire_time) => {
+                        regions.push(region_id.region_number());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!(e; "Failed to decode partition of region {region_id}"),
+                }
+            }
+        }
+        if regions.is_empty() {
+            return Ok(());
+        }
+
+        let procedure =
+            PartitionRetentionProcedure::new(table_id, regions.clone(), self.context.clone());
+        let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
+        let procedure_id = procedure_with_id.id;
+        info!("Starting partition retention procedure {procedure_id} for table {table_id}, regions: {regions:?}");
+        let mut watcher = self
+            .procedure_manager
+            .submit(procedure_with_id)
+            .await
+            .context(error::SubmitProcedureSnafu)?;
+        watcher::wait(&mut watcher)
+            .await
+            .context(error::WaitProcedureSnafu)?;
+
+        self.truncated.lock().unwrap().extend(
+            regions
+                .into_iter()
+                .map(|region| RegionId::new(table_id, region)),
+        );
+        Ok(())
+    }
+}
+
+fn time_index(table_info: &RawTableInfo) -> Option<&str> {
+    let schema = &table_info.meta.schema;
+    schema
+        .timestamp_index
+        .and_then(|index| schema.column_schemas.get(index))
+        .map(|column| column.name.as_str())
+}
+
+/// Returns whether all rows in `partition` are before `expire_time` by the time index.
+fn is_expired(partition: &PartitionDef, time_index: &str, expire_time: &Timestamp) -> bool {
+    let [PartitionBound::Expr(expr)] = &partition.partition_bounds()[..] else {
+        return false;
+    };
+    match expr.upper_bound(time_index) {
+        Bound::Excluded(Value::Timestamp(ts)) => ts <= *expire_time,
+        Bound::Included(Value::Timestamp(ts)) => ts < *expire_time,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common_meta::ddl::test_util::create_table::test_create_table_task;
+    use common_meta::ddl::test_util::datanode_handler::DatanodeWatcher;
+    use common_meta::key::table_route::TableRouteValue;
+    use common_meta::key::TableMetadataManager;
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+    use common_meta::peer::Peer;
+    use common_meta::rpc::router::{Partition as MetaPartition, Region, RegionRoute};
+    use common_meta::state_store::KvStateStore;
+    use common_meta::table_name::TableName;
+    use common_meta::test_util::MockDatanodeManager;
+    use common_procedure::local::{LocalManager, ManagerConfig};
+    use partition::expr::{Operand, PartitionExpr, RestrictedOp};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn partition(op: RestrictedOp, ts: i64) -> PartitionDef {
+        let expr = PartitionExpr::new(
+            Operand::Column("ts".to_string()),
+            op,
+            Operand::Value(Value::Timestamp(Timestamp::new_millisecond(ts))),
+        );
+        PartitionDef::new(vec!["ts".to_string()], vec![PartitionBound::Expr(expr)])
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let expire_time = Timestamp::new_second(10);
+        assert!(is_expired(
+            &partition(RestrictedOp::Lt, 10000),
+            "ts",
+            &expire_time
+        ));
+        assert!(!is_expired(
+            &partition(RestrictedOp::LtEq, 10000),
+            "ts",
+            &expire_time
+        ));
+        assert!(is_expired(
+            &partition(RestrictedOp::LtEq, 9999),
+            "ts",
+            &expire_time
+        ));
+        assert!(!is_expired(
+            &partition(RestrictedOp::GtEq, 0),
+            "ts",
+            &expire_time
+        ));
+        assert!(!is_expired(
+            &partition(RestrictedOp::Lt, 10000),
+            "host",
+            &expire_time
+        ));
+    }
+
+    fn region_route(
+        table_id: TableId,
+        region: RegionNumber,
+        partition: PartitionDef,
+    ) -> RegionRoute {
+        RegionRoute {
+            region: Region {
+                id: RegionId::new(table_id, region),
+                partition: Some(MetaPartition::try_from(partition).unwrap()),
+                ..Default::default()
+            },
+            leader_peer: Some(Peer::empty(1)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_table() {
+        let kv_backend = Arc::new(MemoryKvBackend::new());
+        let table_metadata_manager = Arc::new(TableMetadataManager::new(kv_backend.clone()));
+        let state_store = Arc::new(KvStateStore::new(kv_backend));
+        let procedure_manager: ProcedureManagerRef =
+            Arc::new(LocalManager::new(ManagerConfig::default(), state_store));
+        procedure_manager.start().await.unwrap();
+        let (tx, mut rx) = mpsc::channel(8);
+        let datanode_manager = Arc::new(MockDatanodeManager::new(DatanodeWatcher(tx)));
+        let manager = PartitionRetentionManager::new(
+            procedure_manager,
+            table_metadata_manager.clone(),
+            datanode_manager,
+        );
+        manager.try_start().unwrap();
+
+        let table_id = 1024;
+        let mut table_info = test_create_table_task("foo", table_id).table_info;
+        table_info.meta.engine = MITO_ENGINE.to_string();
+        table_info.meta.options.ttl = Some(Duration::from_secs(3600));
+        let expire_time = Timestamp::current_millis()
+            .sub_duration(Duration::from_secs(7200))
+            .unwrap()
+            .value();
+        let table_route = TableRouteValue::physical(vec![
+            region_route(table_id, 1, partition(RestrictedOp::Lt, expire_time)),
+            region_route(table_id, 2, partition(RestrictedOp::GtEq, expire_time)),
+        ]);
+        let table_name = TableName::new(
+            &table_info.catalog_name,
+            &table_info.schema_name,
+            &table_info.name,
+        );
+        table_metadata_manager
+            .create_table_metadata(table_info, table_route.clone(), HashMap::new())
+            .await
+            .unwrap();
+
+        // Only truncates the region of the expired partition.
+        manager.check_table(table_id).await.unwrap();
+        let (peer, request) = rx.try_recv().unwrap();
+        assert_eq!(Peer::empty(1), peer);
+        let Some(region_request::Body::Truncate(truncate)) = request.body else {
+            panic!("Unexpected request: {request:?}");
+        };
+        assert_eq!(RegionId::new(table_id, 1).as_u64(), truncate.region_id);
+        assert!(rx.try_recv().is_err());
+
+        // Doesn't truncate the region again.
+        manager.check_table(table_id).await.unwrap();
+        assert!(rx.try_recv().is_err());
+        assert!(manager
+            .truncated
+            .lock()
+            .unwrap()
+            .contains(&RegionId::new(table_id, 1)));
+
+        // Forgets regions of the dropped table.
+        table_metadata_manager
+            .destroy_table_metadata(table_id, &table_name, &table_route)
+            .await
+            .unwrap();
+        manager.check_tables().await.unwrap();
+        assert!(manager.truncated.lock().unwrap().is_empty());
+    }
+}
@@ -74,6 +74,10 @@ pub struct PersistentContext {
     /// The timeout of waiting for a candidate to replay the WAL.
     #[serde(with = "humantime_serde", default = "default_replay_timeout")]
     replay_timeout: Duration,
+    /// Whether the leader region was [ReadOnly](common_meta::rpc::router::RegionStatus::ReadOnly)
+    /// before the migration. The status is restored when the migration ends or rolls back.
+    #[serde(default)]
+    leader_read_only: bool,
 }
 
 fn default_replay_timeout() -> Duration {
@@ -487,7 +491,7 @@ mod tests {
         let procedure = RegionMigrationProcedure::new(persistent_context, context);
 
         let serialized = procedure.dump().unwrap();
-        let expected = r#"{"persistent_ctx":{"catalog":"greptime","schema":"public","cluster_id":0,"from_peer":{"id":1,"addr":""},"to_peer":{"id":2,"addr":""},"region_id":4398046511105,"replay_timeout":"1s"},"state":{"region_migration_state":"RegionMigrationStart"}}"#;
+        let expected = r#"{"persistent_ctx":{"catalog":"greptime","schema":"public","cluster_id":0,"from_peer":{"id":1,"addr":""},"to_peer":{"id":2,"addr":""},"region_id":4398046511105,"replay_timeout":"1s","leader_read_only":false},"state":{"region_migration_state":"RegionMigrationStart"}}"#;
         assert_eq!(expected, serialized);
     }
 
@@ -233,6 +233,7 @@ mod tests {
             region_id: RegionId::new(1024, 1),
             cluster_id: 0,
             replay_timeout: Duration::from_millis(1000),
+            leader_read_only: false,
         }
     }
 
@@ -299,6 +299,7 @@ impl RegionMigrationManager {
                 from_peer,
                 to_peer,
                 replay_timeout,
+                leader_read_only: false,
             },
             self.context_factory.clone(),
         );
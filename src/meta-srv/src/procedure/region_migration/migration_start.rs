@@ -51,6 +51,8 @@ impl State for RegionMigrationStart {
     async fn next(&mut self, ctx: &mut Context) -> Result<(Box<dyn State>, Status)> {
         let region_id = ctx.persistent_ctx.region_id;
         let region_route = self.retrieve_region_route(ctx, region_id).await?;
+        // The leader status is replaced by the downgraded status during the migration.
+        ctx.persistent_ctx.leader_read_only = region_route.is_leader_read_only();
         let to_peer = &ctx.persistent_ctx.to_peer;
         let from_peer = &ctx.persistent_ctx.from_peer;
 
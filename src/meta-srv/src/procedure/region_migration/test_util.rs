@@ -285,6 +285,7 @@ pub fn new_persistent_context(from: u64, to: u64, region_id: RegionId) -> Persis
         region_id,
         cluster_id: 0,
         replay_timeout: Duration::from_millis(1000),
+        leader_read_only: false,
     }
 }
 
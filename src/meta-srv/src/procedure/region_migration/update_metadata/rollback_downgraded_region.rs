@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use common_error::ext::BoxedError;
+use common_meta::rpc::router::RegionStatus;
 use snafu::ResultExt;
 
 use crate::error::{self, Result};
@@ -32,12 +33,17 @@ impl UpdateMetadata {
         let table_metadata_manager = ctx.table_metadata_manager.clone();
         let region_id = ctx.region_id();
         let table_id = region_id.table_id();
+        // Restores the read-only status if any.
+        let leader_status = ctx
+            .persistent_ctx
+            .leader_read_only
+            .then_some(RegionStatus::ReadOnly);
         let current_table_route_value = ctx.get_table_route_value().await?;
 
         if let Err(err) = table_metadata_manager
             .update_leader_region_status(table_id, current_table_route_value, |route| {
                 if route.region.id == region_id {
-                    Some(None)
+                    Some(leader_status)
                 } else {
                     None
                 }
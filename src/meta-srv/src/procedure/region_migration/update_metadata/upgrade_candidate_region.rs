@@ -14,7 +14,7 @@
 
 use common_error::ext::BoxedError;
 use common_meta::key::datanode_table::RegionInfo;
-use common_meta::rpc::router::{region_distribution, RegionRoute};
+use common_meta::rpc::router::{region_distribution, RegionRoute, RegionStatus};
 use common_telemetry::{info, warn};
 use snafu::{ensure, OptionExt, ResultExt};
 
@@ -42,8 +42,12 @@ impl UpdateMetadata {
             .find(|route| route.region.id == region_id)
             .context(error::RegionRouteNotFoundSnafu { region_id })?;
 
-        // Removes downgraded status.
-        region_route.set_leader_status(None);
+        // Removes downgraded status, restoring the read-only status if any.
+        region_route.set_leader_status(
+            ctx.persistent_ctx
+                .leader_read_only
+                .then_some(RegionStatus::ReadOnly),
+        );
 
         let candidate = &ctx.persistent_ctx.to_peer;
         let expected_old_leader = &ctx.persistent_ctx.from_peer;
@@ -59,6 +63,8 @@ impl UpdateMetadata {
             );
 
         region_route.leader_peer = Some(candidate.clone());
+        // Must be the same as the epoch granted to the candidate in the upgrade instruction.
+        region_route.leader_epoch += 1;
         info!(
             "Upgrading candidate region to leader region: {:?} for region: {}",
             candidate, region_id
@@ -287,6 +293,7 @@ mod tests {
             follower_peers: vec![Peer::empty(2), Peer::empty(3)],
             leader_status: Some(RegionStatus::Downgraded),
             leader_down_since: Some(current_time_millis()),
+            leader_epoch: 0,
         }];
 
         env.create_physical_table_metadata(table_info, region_routes)
@@ -301,6 +308,38 @@ mod tests {
         assert!(new_region_routes[0].leader_down_since.is_none());
         assert_eq!(new_region_routes[0].follower_peers, vec![Peer::empty(3)]);
         assert_eq!(new_region_routes[0].leader_peer.as_ref().unwrap().id, 2);
+        assert_eq!(new_region_routes[0].leader_epoch, 1);
+    }
+
+    #[tokio::test]
+    async fn test_build_upgrade_candidate_region_metadata_read_only() {
+        let state = UpdateMetadata::Upgrade;
+        let env = TestingEnv::new();
+        let mut persistent_context = new_persistent_context();
+        persistent_context.leader_read_only = true;
+        let mut ctx = env.context_factory().new_context(persistent_context);
+
+        let table_info = new_test_table_info(1024, vec![1]).into();
+        let region_routes = vec![RegionRoute {
+            region: Region::new_test(RegionId::new(1024, 1)),
+            leader_peer: Some(Peer::empty(1)),
+            follower_peers: vec![Peer::empty(2)],
+            leader_status: Some(RegionStatus::Downgraded),
+            leader_down_since: Some(current_time_millis()),
+            leader_epoch: 0,
+        }];
+
+        env.create_physical_table_metadata(table_info, region_routes)
+            .await;
+
+        let new_region_routes = state
+            .build_upgrade_candidate_region_metadata(&mut ctx)
+            .await
+            .unwrap();
+
+        assert!(new_region_routes[0].is_leader_read_only());
+        assert!(new_region_routes[0].leader_down_since.is_none());
+        assert_eq!(new_region_routes[0].leader_peer.as_ref().unwrap().id, 2);
     }
 
     #[tokio::test]
@@ -320,6 +359,7 @@ mod tests {
                 follower_peers: vec![Peer::empty(5), Peer::empty(3)],
                 leader_status: Some(RegionStatus::Downgraded),
                 leader_down_since: Some(current_time_millis()),
+                leader_epoch: 0,
             },
             RegionRoute {
                 region: Region::new_test(RegionId::new(table_id, 2)),
@@ -383,6 +423,7 @@ mod tests {
             follower_peers: vec![Peer::empty(2), Peer::empty(3)],
             leader_status: None,
             leader_down_since: None,
+            leader_epoch: 0,
         }];
 
         env.create_physical_table_metadata(table_info, region_routes)
@@ -407,6 +448,7 @@ mod tests {
             follower_peers: vec![Peer::empty(2), Peer::empty(3)],
             leader_status: None,
             leader_down_since: None,
+            leader_epoch: 0,
         }];
 
         env.create_physical_table_metadata(table_info, region_routes)
@@ -431,6 +473,7 @@ mod tests {
             follower_peers: vec![Peer::empty(2), Peer::empty(3)],
             leader_status: Some(RegionStatus::Downgraded),
             leader_down_since: None,
+            leader_epoch: 0,
         }];
 
         env.create_physical_table_metadata(table_info, region_routes)
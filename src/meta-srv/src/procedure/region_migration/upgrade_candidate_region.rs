@@ -21,7 +21,7 @@ use common_meta::instruction::{Instruction, InstructionReply, UpgradeRegion, Upg
 use common_procedure::Status;
 use common_telemetry::warn;
 use serde::{Deserialize, Serialize};
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use tokio::time::sleep;
 
 use super::update_metadata::UpdateMetadata;
@@ -58,7 +58,8 @@ impl Default for UpgradeCandidateRegion {
 #[typetag::serde]
 impl State for UpgradeCandidateRegion {
     async fn next(&mut self, ctx: &mut Context) -> Result<(Box<dyn State>, Status)> {
-        if self.upgrade_region_with_retry(ctx).await {
+        let leader_epoch = Self::next_leader_epoch(ctx).await?;
+        if self.upgrade_region_with_retry(ctx, leader_epoch).await {
             Ok((Box::new(UpdateMetadata::Upgrade), Status::executing(false)))
         } else {
             Ok((Box::new(UpdateMetadata::Rollback), Status::executing(false)))
@@ -80,8 +81,29 @@ impl UpgradeCandidateRegion {
         self.replay_timeout + UpgradeCandidateRegion::UPGRADE_CANDIDATE_REGION_RTT
     }
 
+    /// Returns the leader epoch granted to the candidate region.
+    ///
+    /// The epoch is bumped once the candidate is upgraded to the leader in the table route,
+    /// see [UpdateMetadata::Upgrade].
+    async fn next_leader_epoch(ctx: &mut Context) -> Result<u64> {
+        let region_id = ctx.region_id();
+        let table_route_value = ctx.get_table_route_value().await?;
+        let region_route = table_route_value
+            .region_routes()
+            .context(error::UnexpectedLogicalRouteTableSnafu {
+                err_msg: format!(
+                    "TableRoute of region {region_id} is a non-physical TableRouteValue."
+                ),
+            })?
+            .iter()
+            .find(|route| route.region.id == region_id)
+            .context(error::RegionRouteNotFoundSnafu { region_id })?;
+
+        Ok(region_route.leader_epoch + 1)
+    }
+
     /// Builds upgrade region instruction.
-    fn build_upgrade_region_instruction(&self, ctx: &Context) -> Instruction {
+    fn build_upgrade_region_instruction(&self, ctx: &Context, leader_epoch: u64) -> Instruction {
         let pc = &ctx.persistent_ctx;
         let region_id = pc.region_id;
         let last_entry_id = ctx.volatile_ctx.leader_region_last_entry_id;
@@ -90,6 +112,7 @@ impl UpgradeCandidateRegion {
             region_id,
             last_entry_id,
             wait_for_replay_timeout: Some(self.replay_timeout),
+            leader_epoch: Some(leader_epoch),
         })
     }
 
@@ -191,8 +214,8 @@ impl UpgradeCandidateRegion {
     /// Upgrades a candidate region.
     ///
     /// Returns true if the candidate region is upgraded successfully.
-    async fn upgrade_region_with_retry(&self, ctx: &Context) -> bool {
-        let upgrade_instruction = self.build_upgrade_region_instruction(ctx);
+    async fn upgrade_region_with_retry(&self, ctx: &Context, leader_epoch: u64) -> bool {
+        let upgrade_instruction = self.build_upgrade_region_instruction(ctx, leader_epoch);
 
         let mut retry = 0;
         let mut upgraded = false;
@@ -220,7 +243,9 @@ impl UpgradeCandidateRegion {
 mod tests {
     use std::assert_matches::assert_matches;
 
+    use common_meta::key::test_utils::new_test_table_info;
     use common_meta::peer::Peer;
+    use common_meta::rpc::router::{Region, RegionRoute};
     use store_api::storage::RegionId;
 
     use super::*;
@@ -239,6 +264,7 @@ mod tests {
             region_id: RegionId::new(1024, 1),
             cluster_id: 0,
             replay_timeout: Duration::from_millis(1000),
+            leader_read_only: false,
         }
     }
 
@@ -249,7 +275,7 @@ mod tests {
         let env = TestingEnv::new();
         let ctx = env.context_factory().new_context(persistent_context);
 
-        let instruction = &state.build_upgrade_region_instruction(&ctx);
+        let instruction = &state.build_upgrade_region_instruction(&ctx, 1);
         let err = state.upgrade_region(&ctx, instruction).await.unwrap_err();
 
         assert_matches!(err, Error::PusherNotFound { .. });
@@ -274,7 +300,7 @@ mod tests {
 
         drop(rx);
 
-        let instruction = &state.build_upgrade_region_instruction(&ctx);
+        let instruction = &state.build_upgrade_region_instruction(&ctx, 1);
         let err = state.upgrade_region(&ctx, instruction).await.unwrap_err();
 
         assert_matches!(err, Error::PushMessage { .. });
@@ -300,7 +326,7 @@ mod tests {
 
         send_mock_reply(mailbox, rx, |id| Ok(new_close_region_reply(id)));
 
-        let instruction = &state.build_upgrade_region_instruction(&ctx);
+        let instruction = &state.build_upgrade_region_instruction(&ctx, 1);
         let err = state.upgrade_region(&ctx, instruction).await.unwrap_err();
         assert_matches!(err, Error::UnexpectedInstructionReply { .. });
         assert!(!err.is_retryable());
@@ -333,7 +359,7 @@ mod tests {
             ))
         });
 
-        let instruction = &state.build_upgrade_region_instruction(&ctx);
+        let instruction = &state.build_upgrade_region_instruction(&ctx, 1);
         let err = state.upgrade_region(&ctx, instruction).await.unwrap_err();
 
         assert_matches!(err, Error::RetryLater { .. });
@@ -362,7 +388,7 @@ mod tests {
             Ok(new_upgrade_region_reply(id, true, false, None))
         });
 
-        let instruction = &state.build_upgrade_region_instruction(&ctx);
+        let instruction = &state.build_upgrade_region_instruction(&ctx, 1);
         let err = state.upgrade_region(&ctx, instruction).await.unwrap_err();
 
         assert_matches!(err, Error::Unexpected { .. });
@@ -395,7 +421,7 @@ mod tests {
             Ok(new_upgrade_region_reply(id, false, true, None))
         });
 
-        let instruction = &state.build_upgrade_region_instruction(&ctx);
+        let instruction = &state.build_upgrade_region_instruction(&ctx, 1);
         let err = state.upgrade_region(&ctx, instruction).await.unwrap_err();
 
         assert_matches!(err, Error::RetryLater { .. });
@@ -416,10 +442,41 @@ mod tests {
             Ok(new_upgrade_region_reply(id, false, true, None))
         });
 
-        let instruction = &state.build_upgrade_region_instruction(&ctx);
+        let instruction = &state.build_upgrade_region_instruction(&ctx, 1);
         state.upgrade_region(&ctx, instruction).await.unwrap();
     }
 
+    async fn prepare_table_metadata(env: &TestingEnv, leader_epoch: u64) {
+        let table_info = new_test_table_info(1024, vec![1]).into();
+        let region_routes = vec![RegionRoute {
+            region: Region::new_test(RegionId::new(1024, 1)),
+            leader_peer: Some(Peer::empty(1)),
+            follower_peers: vec![Peer::empty(2)],
+            leader_epoch,
+            ..Default::default()
+        }];
+        env.create_physical_table_metadata(table_info, region_routes)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_next_leader_epoch() {
+        let persistent_context = new_persistent_context();
+        let env = TestingEnv::new();
+        let mut ctx = env.context_factory().new_context(persistent_context);
+
+        let err = UpgradeCandidateRegion::next_leader_epoch(&mut ctx)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TableRouteNotFound { .. });
+
+        prepare_table_metadata(&env, 2).await;
+        let leader_epoch = UpgradeCandidateRegion::next_leader_epoch(&mut ctx)
+            .await
+            .unwrap();
+        assert_eq!(leader_epoch, 3);
+    }
+
     #[tokio::test]
     async fn test_upgrade_region_with_retry_ok() {
         let mut state = Box::<UpgradeCandidateRegion>::default();
@@ -429,6 +486,7 @@ mod tests {
 
         let mut env = TestingEnv::new();
         let mut ctx = env.context_factory().new_context(persistent_context);
+        prepare_table_metadata(&env, 0).await;
         let mailbox_ctx = env.mailbox_context();
         let mailbox = mailbox_ctx.mailbox().clone();
 
@@ -488,6 +546,7 @@ mod tests {
 
         let mut env = TestingEnv::new();
         let mut ctx = env.context_factory().new_context(persistent_context);
+        prepare_table_metadata(&env, 0).await;
         let mailbox_ctx = env.mailbox_context();
         let mailbox = mailbox_ctx.mailbox().clone();
 
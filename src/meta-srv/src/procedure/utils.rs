@@ -18,7 +18,7 @@ pub mod mock {
     use std::sync::Arc;
 
     use api::v1::region::region_server::RegionServer;
-    use api::v1::region::{region_request, RegionResponse};
+    use api::v1::region::{region_request, RegionRequestHeader, RegionResponse};
     use api::v1::{ResponseHeader, Status as PbStatus};
     use async_trait::async_trait;
     use client::Client;
@@ -81,6 +81,7 @@ pub mod mock {
     impl RegionServerHandler for EchoRegionServer {
         async fn handle(
             &self,
+            _header: RegionRequestHeader,
             request: region_request::Body,
         ) -> servers::error::Result<RegionResponse> {
             self.received_requests.send(request).await.unwrap();
@@ -58,14 +58,20 @@ fn renew_region_lease_via_region_route(
     datanode_id: DatanodeId,
     region_id: RegionId,
 ) -> Option<(RegionId, RegionRole)> {
+    // Stops renewing the lease of a closing region, so datanodes close it.
+    if region_route.is_closing() {
+        return None;
+    }
+
     // If it's a leader region on this datanode.
     if let Some(leader) = &region_route.leader_peer {
         if leader.id == datanode_id {
-            let region_role = if region_route.is_leader_downgraded() {
-                RegionRole::Follower
-            } else {
-                RegionRole::Leader
-            };
+            let region_role =
+                if region_route.is_leader_downgraded() || region_route.is_leader_read_only() {
+                    RegionRole::Follower
+                } else {
+                    RegionRole::Leader
+                };
 
             return Some((region_id, region_role));
         }
@@ -254,6 +260,21 @@ mod tests {
             renew_region_lease_via_region_route(&region_route, leader_peer_id, region_id),
             Some((region_id, RegionRole::Follower))
         );
+
+        region_route.leader_status = Some(RegionStatus::ReadOnly);
+        // The read-only leader region on the datanode.
+        assert_eq!(
+            renew_region_lease_via_region_route(&region_route, leader_peer_id, region_id),
+            Some((region_id, RegionRole::Follower))
+        );
+
+        region_route.leader_status = Some(RegionStatus::Closing);
+        // The closing region isn't renewed on any datanode.
+        for peer_id in [leader_peer_id, follower_peer_id] {
+            assert!(
+                renew_region_lease_via_region_route(&region_route, peer_id, region_id).is_none()
+            );
+        }
     }
 
     #[tokio::test]
@@ -19,6 +19,7 @@ mod maintenance;
 mod meta;
 mod node_lease;
 mod region_migration;
+mod region_mode;
 mod route;
 mod util;
 
@@ -98,6 +99,14 @@ pub fn make_admin_service(metasrv: Metasrv) -> Admin {
     };
     let router = router.route("/region-migration", handler);
 
+    let handler = region_mode::RegionModeHandler {
+        table_metadata_manager: metasrv.table_metadata_manager().clone(),
+        meta_peer_client: metasrv.meta_peer_client().clone(),
+        mailbox: metasrv.mailbox().clone(),
+        server_addr: metasrv.options().server_addr.clone(),
+    };
+    let router = router.route("/region-mode", handler);
+
     let router = router.route(
         "/maintenance",
         maintenance::MaintenanceHandler {
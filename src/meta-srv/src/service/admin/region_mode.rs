@@ -0,0 +1,349 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::time::Duration;
+
+use api::v1::meta::MailboxMessage;
+use common_meta::distributed_time_constants::REGION_LEASE_SECS;
+use common_meta::instruction::{Instruction, InstructionReply, SimpleReply};
+use common_meta::key::datanode_table::DatanodeTableKey;
+use common_meta::key::TableMetadataManagerRef;
+use common_meta::peer::Peer;
+use common_meta::rpc::router::RegionStatus;
+use common_meta::{ClusterId, RegionIdent};
+use common_telemetry::{info, warn};
+use serde::Serialize;
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::RegionId;
+use tonic::codegen::http;
+
+use super::HttpHandler;
+use crate::cluster::MetaPeerClientRef;
+use crate::error::{self, Error, Result};
+use crate::handler::HeartbeatMailbox;
+use crate::service::admin::util::get_value;
+use crate::service::mailbox::{Channel, MailboxRef};
+
+/// The handler of setting the read/write mode of a region.
+///
+/// The mode is kept as the leader status of the region route. Datanodes enforce it
+/// through region leases: a read-only region is leased as a follower. A closing
+/// region isn't leased anymore, and it's closed on its datanodes explicitly. A closed
+/// region can't be switched to another mode.
+pub struct RegionModeHandler {
+    pub table_metadata_manager: TableMetadataManagerRef,
+    pub meta_peer_client: MetaPeerClientRef,
+    pub mailbox: MailboxRef,
+    pub server_addr: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RegionMode {
+    Writable,
+    ReadOnly,
+    Closing,
+}
+
+impl RegionMode {
+    fn leader_status(&self) -> Option<RegionStatus> {
+        match self {
+            RegionMode::Writable => None,
+            RegionMode::ReadOnly => Some(RegionStatus::ReadOnly),
+            RegionMode::Closing => Some(RegionStatus::Closing),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SetRegionModeRequest {
+    cluster_id: ClusterId,
+    region_id: RegionId,
+    mode: RegionMode,
+}
+
+#[derive(Debug, Serialize)]
+struct SetRegionModeResponse {
+    region_id: u64,
+    mode: RegionMode,
+}
+
+impl TryFrom<&HashMap<String, String>> for SetRegionModeRequest {
+    type Error = Error;
+
+    fn try_from(params: &HashMap<String, String>) -> Result<Self> {
+        let cluster_id = match params.get("cluster_id") {
+            Some(cluster_id) => cluster_id.parse().context(error::ParseNumSnafu {
+                err_msg: format!("invalid cluster_id: {cluster_id}"),
+            })?,
+            None => 0,
+        };
+        let region_id = get_value(params, "region_id")?;
+        let region_id = region_id.parse::<u64>().context(error::ParseNumSnafu {
+            err_msg: format!("invalid region_id: {region_id}"),
+        })?;
+
+        let mode = match get_value(params, "mode")?.to_ascii_lowercase().as_str() {
+            "writable" => RegionMode::Writable,
+            "readonly" | "read_only" => RegionMode::ReadOnly,
+            "closing" => RegionMode::Closing,
+            mode => {
+                return error::InvalidArgumentsSnafu {
+                    err_msg: format!(
+                        "invalid mode: {mode}, expected `writable`, `readonly` or `closing`"
+                    ),
+                }
+                .fail()
+            }
+        };
+
+        Ok(SetRegionModeRequest {
+            cluster_id,
+            region_id: RegionId::from_u64(region_id),
+            mode,
+        })
+    }
+}
+
+impl RegionModeHandler {
+    async fn set_region_mode(&self, request: SetRegionModeRequest) -> Result<()> {
+        ensure!(
+            self.meta_peer_client.is_leader(),
+            error::UnexpectedSnafu {
+                violated: "Trying to set the region mode on non-leader meta server"
+            }
+        );
+
+        let SetRegionModeRequest {
+            cluster_id,
+            region_id,
+            mode,
+        } = request;
+        let table_id = region_id.table_id();
+        let current_table_route_value = self
+            .table_metadata_manager
+            .table_route_manager()
+            .table_route_storage()
+            .get_raw(table_id)
+            .await
+            .context(error::TableMetadataManagerSnafu)?
+            .context(error::TableRouteNotFoundSnafu { table_id })?;
+
+        let region_route = current_table_route_value
+            .region_route(region_id)
+            .context(error::TableMetadataManagerSnafu)?
+            .context(error::RegionRouteNotFoundSnafu { region_id })?;
+        // The downgraded status belongs to region migration and failover procedures.
+        ensure!(
+            !region_route.is_leader_downgraded(),
+            error::InvalidArgumentsSnafu {
+                err_msg: format!("region {region_id} is being migrated"),
+            }
+        );
+        // Closing a region again retries closing it on its datanodes.
+        ensure!(
+            !region_route.is_closing() || mode == RegionMode::Closing,
+            error::InvalidArgumentsSnafu {
+                err_msg: format!("region {region_id} is closed"),
+            }
+        );
+        let peers = region_route
+            .leader_peer
+            .iter()
+            .chain(region_route.follower_peers.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let leader_status = mode.leader_status();
+        self.table_metadata_manager
+            .update_leader_region_status(table_id, &current_table_route_value, |route| {
+                (route.region.id == region_id).then_some(leader_status)
+            })
+            .await
+            .context(error::TableMetadataManagerSnafu)?;
+
+        if mode == RegionMode::Closing {
+            for peer in peers {
+                self.close_region(cluster_id, region_id, &peer).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Closes the region on the datanode `peer`.
+    ///
+    /// The lease of the region isn't renewed anymore, so it's considered closed if the
+    /// datanode doesn't reply within a lease: its region alive keeper closes it then.
+    async fn close_region(
+        &self,
+        cluster_id: ClusterId,
+        region_id: RegionId,
+        peer: &Peer,
+    ) -> Result<()> {
+        let table_id = region_id.table_id();
+        let datanode_table = self
+            .table_metadata_manager
+            .datanode_table_manager()
+            .get(&DatanodeTableKey {
+                datanode_id: peer.id,
+                table_id,
+            })
+            .await
+            .context(error::TableMetadataManagerSnafu)?
+            .context(error::DatanodeTableNotFoundSnafu {
+                table_id,
+                datanode_id: peer.id,
+            })?;
+
+        let instruction = Instruction::CloseRegion(RegionIdent {
+            cluster_id,
+            datanode_id: peer.id,
+            table_id,
+            region_number: region_id.region_number(),
+            engine: datanode_table.region_info.engine,
+        });
+        let msg = MailboxMessage::json_message(
+            &format!("Close region: {region_id}"),
+            &format!("Metasrv@{}", self.server_addr),
+            &format!("Datanode-{}@{}", peer.id, peer.addr),
+            common_time::util::current_time_millis(),
+            &instruction,
+        )
+        .with_context(|_| error::SerializeToJsonSnafu {
+            input: instruction.to_string(),
+        })?;
+
+        let ch = Channel::Datanode(peer.id);
+        let timeout = Duration::from_secs(REGION_LEASE_SECS);
+        let receiver = match self.mailbox.send(&ch, msg, timeout).await {
+            Ok(receiver) => receiver,
+            Err(Error::PusherNotFound { .. }) => {
+                warn!("Datanode {peer:?} is unreachable, region {region_id} is closed once its lease expires");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        match receiver.await? {
+            Ok(msg) => {
+                let reply = HeartbeatMailbox::json_reply(&msg)?;
+                let InstructionReply::CloseRegion(SimpleReply { result, error }) = reply else {
+                    return error::UnexpectedInstructionReplySnafu {
+                        mailbox_message: msg.to_string(),
+                        reason: "expect close region reply",
+                    }
+                    .fail();
+                };
+                ensure!(
+                    result,
+                    error::CloseRegionSnafu {
+                        peer_id: peer.id,
+                        region_id,
+                        error,
+                    }
+                );
+                info!("Region {region_id} is closed on datanode {peer:?}");
+                Ok(())
+            }
+            Err(Error::MailboxTimeout { .. }) => {
+                warn!("Closing region {region_id} on datanode {peer:?} timed out, its lease has expired");
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for RegionModeHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        _: http::Method,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let request = SetRegionModeRequest::try_from(params)?;
+        let response = SetRegionModeResponse {
+            region_id: request.region_id.as_u64(),
+            mode: request.mode,
+        };
+
+        self.set_region_mode(request).await?;
+
+        http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(serde_json::to_string(&response).with_context(|_| {
+                error::SerializeToJsonSnafu {
+                    input: format!("{response:?}"),
+                }
+            })?)
+            .context(error::InvalidHttpBodySnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_set_region_mode_req() {
+        let region_id = RegionId::new(1024, 1);
+        for (mode, expected) in [
+            ("writable", RegionMode::Writable),
+            ("READONLY", RegionMode::ReadOnly),
+            ("read_only", RegionMode::ReadOnly),
+            ("closing", RegionMode::Closing),
+        ] {
+            let params = HashMap::from([
+                ("region_id".to_string(), region_id.as_u64().to_string()),
+                ("mode".to_string(), mode.to_string()),
+            ]);
+            assert_eq!(
+                SetRegionModeRequest {
+                    cluster_id: 0,
+                    region_id,
+                    mode: expected,
+                },
+                SetRegionModeRequest::try_from(&params).unwrap()
+            );
+        }
+
+        let params = HashMap::from([
+            ("region_id".to_string(), region_id.as_u64().to_string()),
+            ("mode".to_string(), "frozen".to_string()),
+        ]);
+        let err = SetRegionModeRequest::try_from(&params).unwrap_err();
+        assert_matches!(err, error::Error::InvalidArguments { .. });
+
+        let params = HashMap::from([("mode".to_string(), "writable".to_string())]);
+        let err = SetRegionModeRequest::try_from(&params).unwrap_err();
+        assert_matches!(err, error::Error::MissingRequiredParameter { .. });
+    }
+
+    #[test]
+    fn test_region_mode_leader_status() {
+        assert_eq!(None, RegionMode::Writable.leader_status());
+        assert_eq!(
+            Some(RegionStatus::ReadOnly),
+            RegionMode::ReadOnly.leader_status()
+        );
+        assert_eq!(
+            Some(RegionStatus::Closing),
+            RegionMode::Closing.leader_status()
+        );
+    }
+}
@@ -24,15 +24,21 @@ use api::v1::meta::{
     PutResponse as PbPutResponse, RangeRequest as PbRangeRequest, RangeResponse as PbRangeResponse,
     ResponseHeader,
 };
+use common_meta::cache_invalidator::{CacheInvalidator, Context};
+use common_meta::instruction::CacheIdent;
+use common_meta::key::privilege;
+use common_meta::key::user::UserKey;
 use common_meta::rpc::store::{
     BatchDeleteRequest, BatchGetRequest, BatchPutRequest, CompareAndPutRequest, DeleteRangeRequest,
     PutRequest, RangeRequest,
 };
+use common_telemetry::warn;
 use snafu::{OptionExt, ResultExt};
 use tonic::{Request, Response};
 
+use crate::cache_invalidator::MetasrvCacheInvalidator;
 use crate::error::{self, MissingRequestHeaderSnafu};
-use crate::metasrv::Metasrv;
+use crate::metasrv::{Metasrv, MetasrvInfo};
 use crate::metrics::METRIC_META_KV_REQUEST_ELAPSED;
 use crate::service::GrpcResult;
 
@@ -79,12 +85,14 @@ impl store_server::Store for Metasrv {
             .start_timer();
 
         let req: PutRequest = req.into();
+        let cache = cache_ident(&req.key);
 
         let res = self
             .kv_backend()
             .put(req)
             .await
             .context(error::KvBackendSnafu)?;
+        self.invalidate_cache(cache).await;
 
         let res = res.to_proto_resp(ResponseHeader::success(cluster_id));
         Ok(Response::new(res))
@@ -205,12 +213,16 @@ impl store_server::Store for Metasrv {
             .start_timer();
 
         let req: CompareAndPutRequest = req.into();
+        let cache = cache_ident(&req.key);
 
         let res = self
             .kv_backend()
             .compare_and_put(req)
             .await
             .context(error::KvBackendSnafu)?;
+        if res.success {
+            self.invalidate_cache(cache).await;
+        }
 
         let res = res.to_proto_resp(ResponseHeader::success(cluster_id));
         Ok(Response::new(res))
@@ -238,18 +250,48 @@ impl store_server::Store for Metasrv {
             .start_timer();
 
         let req: DeleteRangeRequest = req.into();
+        let cache = cache_ident(&req.key).filter(|_| req.range_end.is_empty());
 
         let res = self
             .kv_backend()
             .delete_range(req)
             .await
             .context(error::KvBackendSnafu)?;
+        self.invalidate_cache(cache).await;
 
         let res = res.to_proto_resp(ResponseHeader::success(cluster_id));
         Ok(Response::new(res))
     }
 }
 
+impl Metasrv {
+    /// Broadcasts the invalidation of `cache` to frontends, so that they reload the
+    /// changed metadata.
+    async fn invalidate_cache(&self, cache: Option<CacheIdent>) {
+        let Some(cache) = cache else {
+            return;
+        };
+        let invalidator = MetasrvCacheInvalidator::new(
+            self.mailbox().clone(),
+            MetasrvInfo {
+                server_addr: self.options().server_addr.clone(),
+            },
+        );
+        let ctx = Context {
+            subject: Some(format!("Invalidate {cache}")),
+        };
+        if let Err(e) = invalidator.invalidate(&ctx, vec![cache]).await {
+            warn!(e; "Failed to invalidate cache");
+        }
+    }
+}
+
+/// Returns the cache of the key written through the KV store to invalidate, which
+/// are the keys of users and privileges.
+fn cache_ident(raw_key: &[u8]) -> Option<CacheIdent> {
+    UserKey::cache_ident(raw_key).or_else(|| privilege::cache_ident(raw_key))
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
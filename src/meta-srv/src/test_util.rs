@@ -51,6 +51,7 @@ pub(crate) fn new_region_route(region_id: u64, peers: &[Peer], leader_node: u64)
         follower_peers: vec![],
         leader_status: None,
         leader_down_since: None,
+        leader_epoch: 0,
     }
 }
 
@@ -135,6 +136,7 @@ pub(crate) async fn prepare_table_region_and_info_value(
         follower_peers: vec![],
         leader_status: None,
         leader_down_since: None,
+        leader_epoch: 0,
     };
 
     // Region distribution:
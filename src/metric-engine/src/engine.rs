@@ -143,7 +143,8 @@ impl RegionEngine for MetricEngine {
             RegionRequest::Delete(_)
             | RegionRequest::Flush(_)
             | RegionRequest::Compact(_)
-            | RegionRequest::Truncate(_) => UnsupportedRegionRequestSnafu { request }.fail(),
+            | RegionRequest::Truncate(_)
+            | RegionRequest::ReplayWal(_) => UnsupportedRegionRequestSnafu { request }.fail(),
             // It always Ok(0), all data is the latest.
             RegionRequest::Catchup(_) => Ok(0),
         };
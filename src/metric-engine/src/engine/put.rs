@@ -195,7 +195,7 @@ impl MetricEngineInner {
 mod tests {
     use common_recordbatch::RecordBatches;
     use store_api::region_engine::RegionEngine;
-    use store_api::region_request::RegionRequest;
+    use store_api::region_request::{InsertMode, RegionRequest};
     use store_api::storage::ScanRequest;
 
     use super::*;
@@ -211,6 +211,7 @@ mod tests {
         let rows = test_util::build_rows(1, 5);
         let request = RegionRequest::Put(RegionPutRequest {
             rows: Rows { schema, rows },
+            insert_mode: InsertMode::Overwrite,
         });
 
         // write data
@@ -284,6 +285,7 @@ mod tests {
         let rows = test_util::build_rows(3, 100);
         let request = RegionRequest::Put(RegionPutRequest {
             rows: Rows { schema, rows },
+            insert_mode: InsertMode::Overwrite,
         });
 
         // write data
@@ -305,6 +307,7 @@ mod tests {
         let rows = test_util::build_rows(1, 100);
         let request = RegionRequest::Put(RegionPutRequest {
             rows: Rows { schema, rows },
+            insert_mode: InsertMode::Overwrite,
         });
 
         engine
@@ -324,6 +327,7 @@ mod tests {
         let rows = test_util::build_rows(1, 100);
         let request = RegionRequest::Put(RegionPutRequest {
             rows: Rows { schema, rows },
+            insert_mode: InsertMode::Overwrite,
         });
 
         engine
@@ -29,7 +29,7 @@ use store_api::metric_engine_consts::{
     METADATA_SCHEMA_VALUE_COLUMN_NAME,
 };
 use store_api::region_engine::RegionEngine;
-use store_api::region_request::{RegionDeleteRequest, RegionPutRequest};
+use store_api::region_request::{InsertMode, RegionDeleteRequest, RegionPutRequest};
 use store_api::storage::{RegionId, ScanRequest};
 
 use crate::error::{
@@ -348,6 +348,7 @@ impl MetadataRegion {
             filters: vec![],
             output_ordering: None,
             limit: None,
+            sequence: None,
         };
         let record_batch_stream = self
             .mito
@@ -405,6 +406,7 @@ impl MetadataRegion {
             filters: vec![filter_expr.into()],
             output_ordering: None,
             limit: None,
+            sequence: None,
         }
     }
 
@@ -446,7 +448,10 @@ impl MetadataRegion {
             }],
         };
 
-        RegionPutRequest { rows }
+        RegionPutRequest {
+            rows,
+            insert_mode: InsertMode::Overwrite,
+        }
     }
 
     fn build_delete_request(keys: &[String]) -> RegionDeleteRequest {
@@ -565,6 +570,7 @@ mod test {
             filters: vec![expected_filter_expr.into()],
             output_ordering: None,
             limit: None,
+            sequence: None,
         };
         let actual_scan_request = MetadataRegion::build_read_request(key);
         assert_eq!(actual_scan_request, expected_scan_request);
@@ -20,7 +20,7 @@ use object_store::ObjectStore;
 use snafu::ResultExt;
 use store_api::metadata::RegionMetadataRef;
 
-use crate::cache::write_cache::SstUploadRequest;
+use crate::cache::write_cache::{SstUploadRequest, UploadReceiver};
 use crate::cache::CacheManagerRef;
 use crate::error::{CleanDirSnafu, DeleteIndexSnafu, DeleteSstSnafu, OpenDalSnafu, Result};
 use crate::read::Source;
@@ -164,6 +164,47 @@ impl AccessLayer {
 
         Ok(sst_info)
     }
+
+    /// Writes a SST like [AccessLayer::write_sst()], but the write cache may upload
+    /// the SST in background.
+    ///
+    /// Returns the info of the SST and a receiver of the background upload. The SST
+    /// isn't in the object store until the upload finishes.
+    pub(crate) async fn stage_sst(
+        &self,
+        request: SstWriteRequest,
+        write_opts: &WriteOptions,
+    ) -> Result<Option<(SstInfo, Option<UploadReceiver>)>> {
+        let cache_manager = request.cache_manager.clone();
+        let Some(write_cache) = cache_manager.write_cache() else {
+            let sst_info = self.write_sst(request, write_opts).await?;
+            return Ok(sst_info.map(|sst_info| (sst_info, None)));
+        };
+
+        let region_id = request.metadata.region_id;
+        let file_id = request.file_id;
+        let staged = write_cache
+            .write_and_stage_sst(
+                request,
+                SstUploadRequest {
+                    upload_path: location::sst_file_path(&self.region_dir, file_id),
+                    index_upload_path: location::index_file_path(&self.region_dir, file_id),
+                    remote_store: self.object_store.clone(),
+                },
+                write_opts,
+            )
+            .await?;
+
+        // Put parquet metadata to cache manager.
+        if let Some((sst_info, _)) = &staged {
+            if let Some(parquet_metadata) = &sst_info.file_metadata {
+                cache_manager.put_parquet_meta_data(region_id, file_id, parquet_metadata.clone())
+            }
+        }
+
+        Ok(staged)
+    }
+
     /// Returns whether the file exists in the object store.
     pub(crate) async fn is_exist(&self, file_meta: &FileMeta) -> Result<bool> {
         let path = location::sst_file_path(&self.region_dir, file_meta.file_id);
@@ -14,8 +14,9 @@
 
 //! A cache for files.
 
+use std::collections::HashMap;
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use bytes::Bytes;
@@ -50,6 +51,13 @@ pub(crate) struct FileCache {
     ///
     /// File id is enough to identity a file uniquely.
     memory_index: Cache<IndexKey, IndexValue>,
+    /// Files pinned in the local store, e.g. files waiting to upload.
+    ///
+    /// They are not tracked by the `memory_index` so they are never evicted, but
+    /// they count against the capacity.
+    pinned_files: Mutex<HashMap<IndexKey, IndexValue>>,
+    /// Capacity of the cache in bytes.
+    capacity: u64,
 }
 
 pub(crate) type FileCacheRef = Arc<FileCache>;
@@ -92,6 +100,8 @@ impl FileCache {
         FileCache {
             local_store,
             memory_index,
+            pinned_files: Mutex::new(HashMap::new()),
+            capacity: capacity.as_bytes(),
         }
     }
 
@@ -105,11 +115,109 @@ impl FileCache {
         self.memory_index.insert(key, value).await;
     }
 
-    /// Reads a file from the cache.
-    pub(crate) async fn reader(&self, key: IndexKey) -> Option<Reader> {
+    /// Pins files in the cache so they won't be evicted until [FileCache::unpin()],
+    /// and evicts cached files to leave room for them.
+    ///
+    /// Returns false and pins nothing if the pinned files would exceed the capacity.
+    /// The `WriteCache` should ensure the files are in the correct path.
+    pub(crate) async fn try_pin(&self, files: &[(IndexKey, IndexValue)]) -> bool {
+        let pinned_bytes = {
+            let mut pinned_files = self.pinned_files.lock().unwrap();
+            let pinned_bytes = pinned_size(&pinned_files)
+                + files
+                    .iter()
+                    .map(|(_, value)| u64::from(value.file_size))
+                    .sum::<u64>();
+            if pinned_bytes > self.capacity {
+                return false;
+            }
+            for (key, value) in files {
+                CACHE_BYTES
+                    .with_label_values(&[FILE_TYPE])
+                    .add(value.file_size.into());
+                if let Some(old) = pinned_files.insert(*key, value.clone()) {
+                    CACHE_BYTES
+                        .with_label_values(&[FILE_TYPE])
+                        .sub(old.file_size.into());
+                }
+            }
+            pinned_bytes
+        };
+
+        self.evict_for_pinned(pinned_bytes).await;
+        true
+    }
+
+    /// Evicts cached files until the cached and the pinned files fit in the capacity.
+    async fn evict_for_pinned(&self, pinned_bytes: u64) {
+        let fits = |cache: &Cache<IndexKey, IndexValue>| {
+            cache.weighted_size() + pinned_bytes <= self.capacity
+        };
+        self.memory_index.run_pending_tasks().await;
+        if fits(&self.memory_index) {
+            return;
+        }
+
+        // The index only evicts files when it exceeds the whole capacity, so
+        // we evict files explicitly.
+        let keys = self
+            .memory_index
+            .iter()
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+        for key in keys {
+            self.memory_index.invalidate(&key).await;
+            self.memory_index.run_pending_tasks().await;
+            if fits(&self.memory_index) {
+                break;
+            }
+        }
+    }
+
+    /// Unpins a file and puts it into the cache index so it can be evicted.
+    pub(crate) async fn unpin(&self, key: IndexKey) {
+        let value = self.pinned_files.lock().unwrap().remove(&key);
+        if let Some(value) = value {
+            CACHE_BYTES
+                .with_label_values(&[FILE_TYPE])
+                .sub(value.file_size.into());
+            self.put(key, value).await;
+        }
+    }
+
+    /// Unpins a file and deletes it from the local store.
+    pub(crate) async fn remove_pinned(&self, key: IndexKey) {
+        let value = self.pinned_files.lock().unwrap().remove(&key);
+        if let Some(value) = value {
+            CACHE_BYTES
+                .with_label_values(&[FILE_TYPE])
+                .sub(value.file_size.into());
+        }
+        let file_path = self.cache_file_path(key);
+        if let Err(e) = self.local_store.delete(&file_path).await {
+            warn!(e; "Failed to delete a pinned file {}", file_path);
+        }
+    }
+
+    /// Returns true if the file is pinned.
+    pub(crate) fn is_pinned(&self, key: &IndexKey) -> bool {
+        self.pinned_files.lock().unwrap().contains_key(key)
+    }
+
+    /// Gets the index value of a pinned or cached file.
+    async fn get_index_value(&self, key: &IndexKey) -> Option<IndexValue> {
+        let pinned = self.pinned_files.lock().unwrap().get(key).cloned();
+        if pinned.is_some() {
+            return pinned;
+        }
         // We must use `get()` to update the estimator of the cache.
         // See https://docs.rs/moka/latest/moka/future/struct.Cache.html#method.contains_key
-        if self.memory_index.get(&key).await.is_none() {
+        self.memory_index.get(key).await
+    }
+
+    /// Reads a file from the cache.
+    pub(crate) async fn reader(&self, key: IndexKey) -> Option<Reader> {
+        if self.get_index_value(&key).await.is_none() {
             CACHE_MISS.with_label_values(&[FILE_TYPE]).inc();
             return None;
         }
@@ -140,7 +248,7 @@ impl FileCache {
         key: IndexKey,
         ranges: &[Range<u64>],
     ) -> Option<Vec<Bytes>> {
-        if self.memory_index.get(&key).await.is_none() {
+        if self.get_index_value(&key).await.is_none() {
             CACHE_MISS.with_label_values(&[FILE_TYPE]).inc();
             return None;
         }
@@ -232,7 +340,7 @@ impl FileCache {
     /// If the file is not in the cache or fail to load metadata, return None.
     pub(crate) async fn get_parquet_meta_data(&self, key: IndexKey) -> Option<ParquetMetaData> {
         // Check if file cache contrains the key
-        if let Some(index_value) = self.memory_index.get(&key).await {
+        if let Some(index_value) = self.get_index_value(&key).await {
             // Load metadata from file cache
             let local_store = self.local_store();
             let file_path = self.cache_file_path(key);
@@ -278,6 +386,14 @@ impl FileCache {
     }
 }
 
+/// Returns the total size of `pinned_files`.
+fn pinned_size(pinned_files: &HashMap<IndexKey, IndexValue>) -> u64 {
+    pinned_files
+        .values()
+        .map(|value| u64::from(value.file_size))
+        .sum()
+}
+
 /// Key of file cache index.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) struct IndexKey {
@@ -425,6 +541,72 @@ mod tests {
         assert_eq!(0, cache.memory_index.weighted_size());
     }
 
+    #[tokio::test]
+    async fn test_file_cache_pin() {
+        let dir = create_temp_dir("");
+        let local_store = new_fs_store(dir.path().to_str().unwrap());
+
+        let cache = FileCache::new(local_store.clone(), ReadableSize::mb(10));
+        let region_id = RegionId::new(2000, 0);
+        let key = IndexKey::new(region_id, FileId::random(), FileType::Parquet);
+        let file_path = cache.cache_file_path(key);
+        local_store
+            .write(&file_path, b"hello".as_slice())
+            .await
+            .unwrap();
+
+        assert!(cache.try_pin(&[(key, IndexValue { file_size: 5 })]).await);
+        assert!(cache.is_pinned(&key));
+        assert!(!cache.contains_key(&key));
+        // Reads the pinned file.
+        let mut reader = cache.reader(key).await.unwrap();
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).await.unwrap();
+        assert_eq!("hello", buf);
+
+        // Removes the pinned file.
+        cache.remove_pinned(key).await;
+        assert!(!cache.is_pinned(&key));
+        assert!(!local_store.is_exist(&file_path).await.unwrap());
+        assert!(cache.reader(key).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_cache_pin_capacity() {
+        let dir = create_temp_dir("");
+        let local_store = new_fs_store(dir.path().to_str().unwrap());
+
+        let cache = FileCache::new(local_store.clone(), ReadableSize(10));
+        let region_id = RegionId::new(2000, 0);
+        let cached_key = IndexKey::new(region_id, FileId::random(), FileType::Parquet);
+        local_store
+            .write(&cache.cache_file_path(cached_key), b"hello".as_slice())
+            .await
+            .unwrap();
+        cache.put(cached_key, IndexValue { file_size: 5 }).await;
+
+        // Pinning files evicts cached files to leave room for them.
+        let key = IndexKey::new(region_id, FileId::random(), FileType::Parquet);
+        assert!(cache.try_pin(&[(key, IndexValue { file_size: 8 })]).await);
+        assert!(!cache.contains_key(&cached_key));
+
+        // Pinned files can't exceed the capacity.
+        let other_key = IndexKey::new(region_id, FileId::random(), FileType::Parquet);
+        assert!(
+            !cache
+                .try_pin(&[(other_key, IndexValue { file_size: 3 })])
+                .await
+        );
+        assert!(!cache.is_pinned(&other_key));
+
+        cache.unpin(key).await;
+        assert!(
+            cache
+                .try_pin(&[(other_key, IndexValue { file_size: 3 })])
+                .await
+        );
+    }
+
     #[tokio::test]
     async fn test_file_cache_file_removed() {
         let dir = create_temp_dir("");
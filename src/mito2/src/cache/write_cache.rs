@@ -14,24 +14,39 @@
 
 //! A write-through cache for remote object stores.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use common_base::readable_size::ReadableSize;
-use common_telemetry::{debug, info};
+use common_telemetry::{debug, info, warn};
+use futures::TryStreamExt;
 use object_store::manager::ObjectStoreManagerRef;
+use object_store::util::join_path;
 use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
+use store_api::storage::RegionId;
+use tokio::sync::oneshot;
 
 use crate::access_layer::{new_fs_object_store, SstWriteRequest};
 use crate::cache::file_cache::{FileCache, FileCacheRef, FileType, IndexKey, IndexValue};
 use crate::error::{self, Result};
-use crate::metrics::{FLUSH_ELAPSED, UPLOAD_BYTES_TOTAL};
+use crate::metrics::{FLUSH_ELAPSED, UPLOAD_BYTES_TOTAL, UPLOAD_FAILURE_TOTAL, UPLOAD_PENDING};
+use crate::sst::file::FileId;
 use crate::sst::index::intermediate::IntermediateManager;
 use crate::sst::index::IndexerBuilder;
 use crate::sst::parquet::writer::ParquetWriter;
 use crate::sst::parquet::{SstInfo, WriteOptions};
 use crate::sst::{DEFAULT_WRITE_BUFFER_SIZE, DEFAULT_WRITE_CONCURRENCY};
 
+/// Subdirectory of markers of pending uploads.
+const PENDING_DIR: &str = "pending/";
+/// Initial delay before retrying a failed upload.
+const UPLOAD_RETRY_INITIAL_DELAY: Duration = Duration::from_secs(1);
+/// Max delay before retrying a failed upload.
+const UPLOAD_RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
 /// A cache for uploading files to remote object stores.
 ///
 /// It keeps files in local disk and then sends files to object stores.
@@ -44,6 +59,10 @@ pub struct WriteCache {
     object_store_manager: ObjectStoreManagerRef,
     /// Intermediate manager for inverted index.
     intermediate_manager: IntermediateManager,
+    /// Uploads files in background instead of waiting for the upload.
+    async_upload: bool,
+    /// Uploader of files.
+    uploader: Uploader,
 }
 
 pub type WriteCacheRef = Arc<WriteCache>;
@@ -51,19 +70,28 @@ pub type WriteCacheRef = Arc<WriteCache>;
 impl WriteCache {
     /// Create the cache with a `local_store` to cache files and a
     /// `object_store_manager` for all object stores.
+    ///
+    /// It removes files staged by uploads that didn't finish before the cache is closed.
     pub async fn new(
         local_store: ObjectStore,
         object_store_manager: ObjectStoreManagerRef,
         cache_capacity: ReadableSize,
         intermediate_manager: IntermediateManager,
     ) -> Result<Self> {
-        let file_cache = FileCache::new(local_store, cache_capacity);
+        let file_cache = Arc::new(FileCache::new(local_store, cache_capacity));
+        // Removes staged files before recovering the index so they won't be cached.
+        clean_staged_files(&file_cache).await?;
         file_cache.recover().await?;
 
         Ok(Self {
-            file_cache: Arc::new(file_cache),
+            file_cache: file_cache.clone(),
             object_store_manager,
             intermediate_manager,
+            async_upload: false,
+            uploader: Uploader {
+                file_cache,
+                pending_uploads: Arc::new(Mutex::new(HashMap::new())),
+            },
         })
     }
 
@@ -86,17 +114,98 @@ impl WriteCache {
         .await
     }
 
+    /// Sets whether to upload files in background.
+    ///
+    /// If enabled, [WriteCache::write_and_stage_sst()] returns once files are
+    /// staged in the local store and uploads them later.
+    pub fn with_async_upload(mut self, async_upload: bool) -> Self {
+        self.async_upload = async_upload;
+        self
+    }
+
     /// Returns the file cache of the write cache.
     pub(crate) fn file_cache(&self) -> FileCacheRef {
         self.file_cache.clone()
     }
 
+    /// Marks the pending upload of the file as removed.
+    ///
+    /// The uploader deletes the file once it finishes. Returns false if the
+    /// file has no pending upload.
+    pub(crate) fn remove_pending_upload(&self, region_id: RegionId, file_id: FileId) -> bool {
+        let mut pending_uploads = self.uploader.pending_uploads.lock().unwrap();
+        match pending_uploads.get_mut(&(region_id, file_id)) {
+            Some(pending) => {
+                pending.removed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Writes SST to the cache and then uploads it to the remote object store.
     pub(crate) async fn write_and_upload_sst(
         &self,
         write_request: SstWriteRequest,
         upload_request: SstUploadRequest,
         write_opts: &WriteOptions,
+    ) -> Result<Option<SstInfo>> {
+        let region_id = write_request.metadata.region_id;
+        let file_id = write_request.file_id;
+        let Some(sst_info) = self.write_to_cache(write_request, write_opts).await? else {
+            // No data need to upload.
+            return Ok(None);
+        };
+
+        self.upload_sst(region_id, file_id, &sst_info, &upload_request)
+            .await?;
+
+        Ok(Some(sst_info))
+    }
+
+    /// Writes SST to the cache and uploads it to the remote object store in background
+    /// if async upload is enabled.
+    ///
+    /// Returns the info of the SST and a receiver of the background upload. The SST
+    /// is uploaded before returning if async upload is disabled or the cache is full
+    /// of files waiting to upload, so the caller slows down until uploads catch up.
+    pub(crate) async fn write_and_stage_sst(
+        &self,
+        write_request: SstWriteRequest,
+        upload_request: SstUploadRequest,
+        write_opts: &WriteOptions,
+    ) -> Result<Option<(SstInfo, Option<UploadReceiver>)>> {
+        let region_id = write_request.metadata.region_id;
+        let file_id = write_request.file_id;
+        let Some(sst_info) = self.write_to_cache(write_request, write_opts).await? else {
+            // No data need to upload.
+            return Ok(None);
+        };
+
+        if self.async_upload {
+            if let Some(receiver) = self
+                .stage_upload(region_id, file_id, &sst_info, &upload_request)
+                .await?
+            {
+                return Ok(Some((sst_info, Some(receiver))));
+            }
+            debug!(
+                "Write cache is full of pending uploads, upload file in foreground, region: {}, file: {}",
+                region_id, file_id
+            );
+        }
+
+        self.upload_sst(region_id, file_id, &sst_info, &upload_request)
+            .await?;
+
+        Ok(Some((sst_info, None)))
+    }
+
+    /// Writes SST to the cache.
+    async fn write_to_cache(
+        &self,
+        write_request: SstWriteRequest,
+        write_opts: &WriteOptions,
     ) -> Result<Option<SstInfo>> {
         let timer = FLUSH_ELAPSED
             .with_label_values(&["write_sst"])
@@ -133,32 +242,176 @@ impl WriteCache {
 
         timer.stop_and_record();
 
-        // Upload sst file to remote object store.
-        let Some(sst_info) = sst_info else {
-            // No data need to upload.
-            return Ok(None);
-        };
+        Ok(sst_info)
+    }
+
+    /// Uploads the SST in the cache to the remote object store.
+    async fn upload_sst(
+        &self,
+        region_id: RegionId,
+        file_id: FileId,
+        sst_info: &SstInfo,
+        upload_request: &SstUploadRequest,
+    ) -> Result<()> {
+        let parquet_key = IndexKey::new(region_id, file_id, FileType::Parquet);
+        let puffin_key = IndexKey::new(region_id, file_id, FileType::Puffin);
 
         let parquet_path = &upload_request.upload_path;
         let remote_store = &upload_request.remote_store;
-        self.upload(parquet_key, parquet_path, remote_store).await?;
+        let file_size = self
+            .uploader
+            .upload(parquet_key, parquet_path, remote_store)
+            .await?;
+        // Register to file cache
+        self.file_cache
+            .put(
+                parquet_key,
+                IndexValue {
+                    file_size: file_size as _,
+                },
+            )
+            .await;
 
         if sst_info.inverted_index_available {
-            let puffin_key = IndexKey::new(region_id, file_id, FileType::Puffin);
             let puffin_path = &upload_request.index_upload_path;
-            self.upload(puffin_key, puffin_path, remote_store).await?;
+            let file_size = self
+                .uploader
+                .upload(puffin_key, puffin_path, remote_store)
+                .await?;
+            self.file_cache
+                .put(
+                    puffin_key,
+                    IndexValue {
+                        file_size: file_size as _,
+                    },
+                )
+                .await;
         }
 
-        Ok(Some(sst_info))
+        Ok(())
+    }
+
+    /// Pins the staged files and uploads them in background.
+    ///
+    /// Returns `None` if the pinned files would exceed the capacity of the cache.
+    async fn stage_upload(
+        &self,
+        region_id: RegionId,
+        file_id: FileId,
+        sst_info: &SstInfo,
+        upload_request: &SstUploadRequest,
+    ) -> Result<Option<UploadReceiver>> {
+        let marker = UploadMarker {
+            region_id,
+            file_id,
+            upload_path: upload_request.upload_path.clone(),
+            index_upload_path: sst_info
+                .inverted_index_available
+                .then(|| upload_request.index_upload_path.clone()),
+        };
+        // Persists the marker so we can remove the staged files after restart.
+        let marker_data = serde_json::to_vec(&marker).context(error::SerdeJsonSnafu)?;
+        let local_store = self.file_cache.local_store();
+        local_store
+            .write(&marker.path(), marker_data)
+            .await
+            .context(error::OpenDalSnafu)?;
+
+        let mut staged_files = vec![(
+            IndexKey::new(region_id, file_id, FileType::Parquet),
+            IndexValue {
+                file_size: sst_info.file_size as _,
+            },
+        )];
+        if sst_info.inverted_index_available {
+            staged_files.push((
+                IndexKey::new(region_id, file_id, FileType::Puffin),
+                IndexValue {
+                    file_size: sst_info.index_file_size as _,
+                },
+            ));
+        }
+        if !self.file_cache.try_pin(&staged_files).await {
+            local_store
+                .delete(&marker.path())
+                .await
+                .context(error::OpenDalSnafu)?;
+            return Ok(None);
+        }
+
+        Ok(Some(
+            self.uploader
+                .spawn(marker, upload_request.remote_store.clone()),
+        ))
+    }
+}
+
+/// Marker of a SST staged in the write cache and waiting for uploading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UploadMarker {
+    region_id: RegionId,
+    file_id: FileId,
+    /// Path to upload the file.
+    upload_path: String,
+    /// Path to upload the index file, `None` if the SST has no index.
+    index_upload_path: Option<String>,
+}
+
+impl UploadMarker {
+    /// Returns the path of the marker in the local store.
+    ///
+    /// The file name format is `{region_id}.{file_id}.json`
+    fn path(&self) -> String {
+        join_path(
+            PENDING_DIR,
+            &format!("{}.{}.json", self.region_id.as_u64(), self.file_id),
+        )
+    }
+
+    /// Returns keys of files to upload.
+    fn keys(&self) -> Vec<IndexKey> {
+        let mut keys = vec![IndexKey::new(
+            self.region_id,
+            self.file_id,
+            FileType::Parquet,
+        )];
+        if self.index_upload_path.is_some() {
+            keys.push(IndexKey::new(
+                self.region_id,
+                self.file_id,
+                FileType::Puffin,
+            ));
+        }
+        keys
     }
+}
+
+/// State of a pending upload.
+#[derive(Debug, Default)]
+struct PendingUpload {
+    /// Whether the file is removed before the upload finishes.
+    removed: bool,
+}
 
+/// Uploads files from the file cache to remote object stores.
+#[derive(Clone)]
+struct Uploader {
+    /// Local file cache.
+    file_cache: FileCacheRef,
+    /// Uploads in progress.
+    pending_uploads: Arc<Mutex<HashMap<(RegionId, FileId), PendingUpload>>>,
+}
+
+impl Uploader {
     /// Uploads a Parquet file or a Puffin file to the remote object store.
+    ///
+    /// Returns the number of bytes uploaded.
     async fn upload(
         &self,
         index_key: IndexKey,
         upload_path: &str,
         remote_store: &ObjectStore,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         let region_id = index_key.region_id;
         let file_id = index_key.file_id;
         let file_type = index_key.file_type;
@@ -207,14 +460,166 @@ impl WriteCache {
             timer.stop_and_record()
         );
 
-        let index_value = IndexValue {
-            file_size: bytes_written as _,
+        Ok(bytes_written)
+    }
+
+    /// Uploads files of the `marker` in background.
+    ///
+    /// Returns a receiver of the result of the upload.
+    fn spawn(&self, marker: UploadMarker, remote_store: ObjectStore) -> UploadReceiver {
+        self.pending_uploads
+            .lock()
+            .unwrap()
+            .insert((marker.region_id, marker.file_id), PendingUpload::default());
+        UPLOAD_PENDING.inc();
+
+        let (sender, receiver) = oneshot::channel();
+        let uploader = self.clone();
+        common_runtime::spawn_bg(async move {
+            let result = uploader.upload_pending(marker, remote_store).await;
+            // The receiver may be dropped if the flush is cancelled.
+            let _ = sender.send(result);
+        });
+        receiver
+    }
+
+    /// Uploads files of the `marker` until success or the file is removed.
+    ///
+    /// Returns an error if the file is removed before the upload finishes.
+    async fn upload_pending(&self, marker: UploadMarker, remote_store: ObjectStore) -> Result<()> {
+        let pending_key = (marker.region_id, marker.file_id);
+        let mut delay = UPLOAD_RETRY_INITIAL_DELAY;
+        let uploaded = loop {
+            if self.is_removed(&pending_key) {
+                break false;
+            }
+            match self.upload_files(&marker, &remote_store).await {
+                Ok(()) => break true,
+                Err(e) => {
+                    UPLOAD_FAILURE_TOTAL.inc();
+                    warn!(
+                        e; "Failed to upload file, region: {}, file: {}, retry after {:?}",
+                        marker.region_id, marker.file_id, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(UPLOAD_RETRY_MAX_DELAY);
+                }
+            }
+        };
+
+        let removed = self
+            .pending_uploads
+            .lock()
+            .unwrap()
+            .remove(&pending_key)
+            .map(|pending| pending.removed)
+            .unwrap_or(false);
+        UPLOAD_PENDING.dec();
+
+        let result = if removed {
+            // The file is purged while uploading.
+            if uploaded {
+                self.delete_remote_files(&marker, &remote_store).await;
+            }
+            for key in marker.keys() {
+                self.file_cache.remove_pinned(key).await;
+            }
+            error::UploadCancelledSnafu {
+                region_id: marker.region_id,
+                file_id: marker.file_id,
+            }
+            .fail()
+        } else {
+            // The file is durable in the remote store and can be evicted now.
+            for key in marker.keys() {
+                self.file_cache.unpin(key).await;
+            }
+            Ok(())
         };
-        // Register to file cache
-        self.file_cache.put(index_key, index_value).await;
 
+        if let Err(e) = self.file_cache.local_store().delete(&marker.path()).await {
+            warn!(e; "Failed to delete upload marker {}", marker.path());
+        }
+        result
+    }
+
+    /// Returns true if the pending file is removed.
+    fn is_removed(&self, pending_key: &(RegionId, FileId)) -> bool {
+        self.pending_uploads
+            .lock()
+            .unwrap()
+            .get(pending_key)
+            .map(|pending| pending.removed)
+            .unwrap_or(false)
+    }
+
+    /// Uploads all files of the `marker`.
+    async fn upload_files(&self, marker: &UploadMarker, remote_store: &ObjectStore) -> Result<()> {
+        let parquet_key = IndexKey::new(marker.region_id, marker.file_id, FileType::Parquet);
+        self.upload(parquet_key, &marker.upload_path, remote_store)
+            .await?;
+        if let Some(index_upload_path) = &marker.index_upload_path {
+            let puffin_key = IndexKey::new(marker.region_id, marker.file_id, FileType::Puffin);
+            self.upload(puffin_key, index_upload_path, remote_store)
+                .await?;
+        }
         Ok(())
     }
+
+    /// Deletes uploaded files of the `marker` from the remote store.
+    async fn delete_remote_files(&self, marker: &UploadMarker, remote_store: &ObjectStore) {
+        let paths = std::iter::once(&marker.upload_path).chain(marker.index_upload_path.iter());
+        for path in paths {
+            if let Err(e) = remote_store.delete(path).await {
+                warn!(
+                    e; "Failed to delete uploaded file {}, region: {}, file: {}",
+                    path, marker.region_id, marker.file_id
+                );
+            }
+        }
+    }
+}
+
+/// Removes files staged by uploads that didn't finish and their markers.
+///
+/// The manifest never references these files and the WAL still has their data,
+/// so the region replays the data instead of uploading the files.
+async fn clean_staged_files(file_cache: &FileCache) -> Result<()> {
+    let local_store = file_cache.local_store();
+    let mut lister = local_store
+        .lister_with(PENDING_DIR)
+        .await
+        .context(error::OpenDalSnafu)?;
+    while let Some(entry) = lister.try_next().await.context(error::OpenDalSnafu)? {
+        if !entry.metadata().is_file() {
+            continue;
+        }
+        let data = local_store
+            .read(entry.path())
+            .await
+            .context(error::OpenDalSnafu)?;
+        match serde_json::from_slice::<UploadMarker>(&data) {
+            Ok(marker) => {
+                info!(
+                    "Remove staged file, region: {}, file: {}",
+                    marker.region_id, marker.file_id
+                );
+                for key in marker.keys() {
+                    local_store
+                        .delete(&file_cache.cache_file_path(key))
+                        .await
+                        .context(error::OpenDalSnafu)?;
+                }
+            }
+            Err(e) => warn!(e; "Failed to parse upload marker {}", entry.path()),
+        }
+        local_store
+            .delete(entry.path())
+            .await
+            .context(error::OpenDalSnafu)?;
+    }
+
+    Ok(())
 }
 
 /// Request to write and upload a SST.
@@ -227,10 +632,13 @@ pub struct SstUploadRequest {
     pub remote_store: ObjectStore,
 }
 
+/// Receiver of the result of a background upload.
+pub(crate) type UploadReceiver = oneshot::Receiver<Result<()>>;
+
 #[cfg(test)]
 mod tests {
-
     use common_test_util::temp_dir::create_temp_dir;
+    use object_store::util::join_dir;
 
     use super::*;
     use crate::cache::test_util::new_fs_store;
@@ -325,6 +733,175 @@ mod tests {
         assert_eq!(remote_index_data, cache_index_data);
     }
 
+    async fn new_async_write_cache(
+        env: &TestEnv,
+        local_store: ObjectStore,
+        capacity: ReadableSize,
+    ) -> WriteCache {
+        let intm_mgr =
+            IntermediateManager::init_fs(join_dir(&env.data_home().display().to_string(), "intm"))
+                .await
+                .unwrap();
+        WriteCache::new(
+            local_store,
+            env.get_object_store_manager().unwrap(),
+            capacity,
+            intm_mgr,
+        )
+        .await
+        .unwrap()
+        .with_async_upload(true)
+    }
+
+    fn new_write_request(file_id: FileId) -> SstWriteRequest {
+        let source = new_source(&[
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+        ]);
+        SstWriteRequest {
+            file_id,
+            metadata: Arc::new(sst_region_metadata()),
+            source,
+            storage: None,
+            create_inverted_index: true,
+            mem_threshold_index_create: None,
+            index_write_buffer_size: None,
+            cache_manager: Default::default(),
+            index_options: IndexOptions::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_and_stage_sst() {
+        let mut env = TestEnv::new();
+        let mock_store = env.init_object_store_manager();
+        let file_id = FileId::random();
+        let upload_path = sst_file_path("test", file_id);
+        let index_upload_path = index_file_path("test", file_id);
+
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let write_cache =
+            new_async_write_cache(&env, local_store.clone(), ReadableSize::mb(10)).await;
+
+        let region_id = sst_region_metadata().region_id;
+        let upload_request = SstUploadRequest {
+            upload_path: upload_path.clone(),
+            index_upload_path: index_upload_path.clone(),
+            remote_store: mock_store.clone(),
+        };
+        let write_opts = WriteOptions {
+            row_group_size: 512,
+            ..Default::default()
+        };
+        let (_, receiver) = write_cache
+            .write_and_stage_sst(new_write_request(file_id), upload_request, &write_opts)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Waits until the file is uploaded and unpinned.
+        receiver.unwrap().await.unwrap().unwrap();
+        let key = IndexKey::new(region_id, file_id, FileType::Parquet);
+        let index_key = IndexKey::new(region_id, file_id, FileType::Puffin);
+        assert!(!write_cache.file_cache.is_pinned(&key));
+        assert!(!write_cache.file_cache.is_pinned(&index_key));
+        assert!(write_cache.file_cache.contains_key(&key));
+        assert!(write_cache.file_cache.contains_key(&index_key));
+        assert!(!write_cache.remove_pending_upload(region_id, file_id));
+
+        let remote_data = mock_store.read(&upload_path).await.unwrap();
+        let cache_data = local_store
+            .read(&write_cache.file_cache.cache_file_path(key))
+            .await
+            .unwrap();
+        assert_eq!(remote_data, cache_data);
+        let remote_index_data = mock_store.read(&index_upload_path).await.unwrap();
+        let cache_index_data = local_store
+            .read(&write_cache.file_cache.cache_file_path(index_key))
+            .await
+            .unwrap();
+        assert_eq!(remote_index_data, cache_index_data);
+
+        // The marker is removed after upload.
+        let marker = UploadMarker {
+            region_id,
+            file_id,
+            upload_path,
+            index_upload_path: Some(index_upload_path),
+        };
+        assert!(!local_store.is_exist(&marker.path()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_write_and_stage_sst_cache_full() {
+        let mut env = TestEnv::new();
+        let mock_store = env.init_object_store_manager();
+        let file_id = FileId::random();
+        let upload_path = sst_file_path("test", file_id);
+
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        // The cache is too small to pin the file.
+        let write_cache = new_async_write_cache(&env, local_store, ReadableSize(1)).await;
+
+        let upload_request = SstUploadRequest {
+            upload_path: upload_path.clone(),
+            index_upload_path: index_file_path("test", file_id),
+            remote_store: mock_store.clone(),
+        };
+        let (_, receiver) = write_cache
+            .write_and_stage_sst(
+                new_write_request(file_id),
+                upload_request,
+                &WriteOptions::default(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The file is uploaded before returning.
+        assert!(receiver.is_none());
+        assert!(mock_store.is_exist(&upload_path).await.unwrap());
+        let key = IndexKey::new(sst_region_metadata().region_id, file_id, FileType::Parquet);
+        assert!(!write_cache.file_cache.is_pinned(&key));
+    }
+
+    #[tokio::test]
+    async fn test_clean_staged_files() {
+        let mut env = TestEnv::new();
+        env.init_object_store_manager();
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let file_cache = FileCache::new(local_store.clone(), ReadableSize::mb(10));
+
+        let region_id = sst_region_metadata().region_id;
+        let file_id = FileId::random();
+        let marker = UploadMarker {
+            region_id,
+            file_id,
+            upload_path: sst_file_path("test", file_id),
+            index_upload_path: None,
+        };
+        let key = IndexKey::new(region_id, file_id, FileType::Parquet);
+        let file_path = file_cache.cache_file_path(key);
+        local_store
+            .write(&file_path, b"hello".as_slice())
+            .await
+            .unwrap();
+        local_store
+            .write(&marker.path(), serde_json::to_vec(&marker).unwrap())
+            .await
+            .unwrap();
+
+        // Opening the cache removes the staged file and the marker.
+        let write_cache =
+            new_async_write_cache(&env, local_store.clone(), ReadableSize::mb(10)).await;
+        assert!(!write_cache.file_cache.contains_key(&key));
+        assert!(!local_store.is_exist(&file_path).await.unwrap());
+        assert!(!local_store.is_exist(&marker.path()).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_read_metadata_from_write_cache() {
         let mut env = TestEnv::new();
@@ -13,33 +13,44 @@
 // limitations under the License.
 
 mod picker;
+mod size_tiered;
+mod task;
 #[cfg(test)]
 mod test_util;
 mod twcs;
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use common_telemetry::{debug, error};
-pub use picker::CompactionPickerRef;
+use common_telemetry::{debug, error, info};
+use common_time::Timestamp;
+pub use picker::{CompactionStrategy, CompactionStrategyRef, PickerOutput};
 use snafu::ResultExt;
+use store_api::region_request::CompactOptions;
 use store_api::storage::RegionId;
 use tokio::sync::mpsc::{self, Sender};
 
 use crate::access_layer::AccessLayerRef;
 use crate::cache::CacheManagerRef;
+use crate::compaction::picker::CompactionTask;
+use crate::compaction::size_tiered::SizeTieredPicker;
+use crate::compaction::task::{CompactionOutput, CompactionTaskImpl};
 use crate::compaction::twcs::TwcsPicker;
 use crate::config::MitoConfig;
 use crate::error::{
     CompactRegionSnafu, Error, RegionClosedSnafu, RegionDroppedSnafu, RegionTruncatedSnafu, Result,
+    SnapshotPinnedSnafu,
 };
 use crate::metrics::COMPACTION_STAGE_ELAPSED;
 use crate::region::options::CompactionOptions;
-use crate::region::version::{VersionControlRef, VersionRef};
+use crate::region::version::{Version, VersionControlRef, VersionRef};
 use crate::request::{OptionOutputTx, OutputTx, WorkerRequest};
 use crate::schedule::scheduler::SchedulerRef;
+use crate::sst::file::{FileHandle, FileId};
 use crate::sst::file_purger::FilePurgerRef;
+use crate::sst::version::LevelMeta;
+use crate::sst::SST_FORMAT_VERSION;
 
 /// Region compaction request.
 pub struct CompactionRequest {
@@ -54,6 +65,8 @@ pub struct CompactionRequest {
     /// Start time of compaction task.
     pub(crate) start_time: Instant,
     pub(crate) cache_manager: CacheManagerRef,
+    /// Options of the manual compaction.
+    pub(crate) options: CompactOptions,
 }
 
 impl CompactionRequest {
@@ -69,15 +82,136 @@ impl CompactionRequest {
     }
 }
 
-/// Builds compaction picker according to [CompactionOptions].
-pub fn compaction_options_to_picker(strategy: &CompactionOptions) -> CompactionPickerRef {
-    match strategy {
+/// Builds compaction strategy according to [CompactionOptions].
+pub fn compaction_options_to_strategy(options: &CompactionOptions) -> CompactionStrategyRef {
+    match options {
         CompactionOptions::Twcs(twcs_opts) => Arc::new(TwcsPicker::new(
             twcs_opts.max_active_window_files,
             twcs_opts.max_inactive_window_files,
             twcs_opts.time_window_seconds(),
         )) as Arc<_>,
+        CompactionOptions::SizeTiered(size_tiered_opts) => Arc::new(SizeTieredPicker::new(
+            size_tiered_opts.min_threshold,
+            size_tiered_opts.max_threshold,
+            size_tiered_opts.min_file_size.as_bytes(),
+            size_tiered_opts.tier_ratio,
+        )) as Arc<_>,
+    }
+}
+
+/// Builds compaction strategy for the request.
+///
+/// Uses the compaction options of the region unless the request asks for a
+/// specific strategy.
+fn new_strategy(request: &CompactionRequest) -> CompactionStrategyRef {
+    match request.options {
+        CompactOptions::Regular => {
+            compaction_options_to_strategy(&request.current_version.options.compaction)
+        }
+        CompactOptions::StrictWindow { window_seconds } => {
+            Arc::new(TwcsPicker::new_strict_window(window_seconds)) as Arc<_>
+        }
+    }
+}
+
+/// Picks files to compact by the `strategy` and builds the compaction task.
+///
+/// Returns `None` and notifies all waiters if there is nothing to compact.
+fn pick_compaction_task(
+    strategy: &dyn CompactionStrategy,
+    request: CompactionRequest,
+) -> Option<Box<dyn CompactionTask>> {
+    let CompactionRequest {
+        engine_config,
+        current_version,
+        access_layer,
+        request_sender,
+        waiters,
+        file_purger,
+        start_time,
+        cache_manager,
+        options: _,
+    } = request;
+    let region_id = current_version.metadata.region_id;
+
+    let expired_ssts = get_expired_ssts(
+        current_version.ssts.levels(),
+        current_version.options.ttl,
+        Timestamp::current_millis(),
+    );
+    if !expired_ssts.is_empty() {
+        info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
+        // here we mark expired SSTs as compacting to avoid them being picked.
+        expired_ssts.iter().for_each(|f| f.set_compacting(true));
+    }
+
+    let PickerOutput {
+        mut outputs,
+        time_window_size,
+    } = strategy.pick(&current_version);
+    if outputs.is_empty() && expired_ssts.is_empty() && engine_config.enable_sst_upgrade {
+        // The region has nothing to compact, so we upgrade its SSTs in old formats.
+        outputs = pick_outdated_ssts(&current_version);
+    }
+    if outputs.is_empty() && expired_ssts.is_empty() {
+        // Nothing to compact, we are done. Notifies all waiters as we consume the compaction request.
+        for waiter in waiters {
+            waiter.send(Ok(0));
+        }
+        return None;
     }
+
+    let task = CompactionTaskImpl {
+        engine_config,
+        region_id,
+        metadata: current_version.metadata.clone(),
+        sst_layer: access_layer,
+        outputs,
+        expired_ssts,
+        compaction_time_window: time_window_size,
+        request_sender,
+        waiters,
+        file_purger,
+        start_time,
+        cache_manager,
+        storage: current_version.options.storage.clone(),
+        index_options: current_version.options.index_options.clone(),
+        append_mode: current_version.options.append_mode,
+        merge_mode: current_version.options.merge_mode,
+    };
+    Some(Box::new(task))
+}
+
+/// Picks a SST in an old format version to rewrite it in the current version.
+///
+/// The output keeps the level of the input so the rewrite is transparent to the
+/// compaction strategy. We rewrite one file at a time and the scheduler picks the
+/// next one after the task finishes.
+fn pick_outdated_ssts(version: &Version) -> Vec<CompactionOutput> {
+    let mut outdated = version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files.values().map(|file| (level.level, file)))
+        .filter(|(_, file)| file.is_outdated() && !file.compacting());
+    let Some((level, file)) = outdated.next() else {
+        return Vec::new();
+    };
+
+    info!(
+        "Region {} upgrades SST {} from format version {} to {}, {} outdated SSTs remaining",
+        version.metadata.region_id,
+        file.file_id(),
+        file.format_version(),
+        SST_FORMAT_VERSION,
+        outdated.count(),
+    );
+
+    vec![CompactionOutput {
+        output_file_id: FileId::random(),
+        output_level: level,
+        inputs: vec![file.clone()],
+    }]
 }
 
 /// Compaction scheduler tracks and manages compaction tasks.
@@ -112,11 +246,12 @@ impl CompactionScheduler {
         access_layer: &AccessLayerRef,
         file_purger: &FilePurgerRef,
         waiter: OptionOutputTx,
+        options: CompactOptions,
         engine_config: Arc<MitoConfig>,
     ) -> Result<()> {
         if let Some(status) = self.region_status.get_mut(&region_id) {
             // Region is compacting. Add the waiter to pending list.
-            status.merge_waiter(waiter);
+            status.merge_waiter(waiter, options);
             return Ok(());
         }
 
@@ -130,6 +265,7 @@ impl CompactionScheduler {
         let request = status.new_compaction_request(
             self.request_sender.clone(),
             waiter,
+            options,
             engine_config,
             self.cache_manager.clone(),
         );
@@ -150,6 +286,7 @@ impl CompactionScheduler {
         let request = status.new_compaction_request(
             self.request_sender.clone(),
             OptionOutputTx::none(),
+            CompactOptions::Regular,
             engine_config,
             self.cache_manager.clone(),
         );
@@ -192,21 +329,30 @@ impl CompactionScheduler {
         );
     }
 
+    /// Notifies the scheduler that the region has pinned snapshots, so pending
+    /// compactions are cancelled.
+    pub(crate) fn on_snapshot_pinned(&mut self, region_id: RegionId) {
+        self.remove_region_on_failure(
+            region_id,
+            Arc::new(SnapshotPinnedSnafu { region_id }.build()),
+        );
+    }
+
     /// Schedules a compaction request.
     ///
     /// If the region has nothing to compact, it removes the region from the status map.
     fn schedule_compaction_request(&mut self, request: CompactionRequest) -> Result<()> {
-        let picker = compaction_options_to_picker(&request.current_version.options.compaction);
+        let strategy = new_strategy(&request);
         let region_id = request.region_id();
         debug!(
             "Pick compaction strategy {:?} for region: {}",
-            picker, region_id
+            strategy, region_id
         );
 
         let pick_timer = COMPACTION_STAGE_ELAPSED
             .with_label_values(&["pick"])
             .start_timer();
-        let Some(mut task) = picker.pick(request) else {
+        let Some(mut task) = pick_compaction_task(strategy.as_ref(), request) else {
             // Nothing to compact, remove it from the region status map.
             self.region_status.remove(&region_id);
             return Ok(());
@@ -249,6 +395,8 @@ impl Drop for CompactionScheduler {
 /// Pending compaction tasks.
 struct PendingCompaction {
     waiters: Vec<OutputTx>,
+    /// Options of the latest pending manual compaction that is not regular.
+    options: CompactOptions,
 }
 
 impl PendingCompaction {
@@ -300,14 +448,18 @@ impl CompactionStatus {
         }
     }
 
-    /// Merge the watier to the pending compaction.
-    fn merge_waiter(&mut self, waiter: OptionOutputTx) {
+    /// Merge the watier and the options to the pending compaction.
+    fn merge_waiter(&mut self, waiter: OptionOutputTx, options: CompactOptions) {
         let pending = self
             .pending_compaction
             .get_or_insert_with(|| PendingCompaction {
                 waiters: Vec::new(),
+                options: CompactOptions::Regular,
             });
         pending.push_waiter(waiter);
+        if options != CompactOptions::Regular {
+            pending.options = options;
+        }
     }
 
     fn on_failure(self, err: Arc<Error>) {
@@ -318,11 +470,13 @@ impl CompactionStatus {
 
     /// Creates a new compaction request for compaction picker.
     ///
-    /// It consumes all pending compaction waiters.
+    /// It consumes all pending compaction waiters. The request uses options of
+    /// the pending compaction if `options` is regular.
     fn new_compaction_request(
         &mut self,
         request_sender: Sender<WorkerRequest>,
         waiter: OptionOutputTx,
+        options: CompactOptions,
         engine_config: Arc<MitoConfig>,
         cache_manager: CacheManagerRef,
     ) -> CompactionRequest {
@@ -337,10 +491,14 @@ impl CompactionStatus {
             file_purger: self.file_purger.clone(),
             start_time,
             cache_manager,
+            options,
         };
 
         if let Some(pending) = self.pending_compaction.take() {
             req.waiters = pending.waiters;
+            if options == CompactOptions::Regular {
+                req.options = pending.options;
+            }
         }
         req.push_waiter(waiter);
 
@@ -348,6 +506,30 @@ impl CompactionStatus {
     }
 }
 
+/// Finds all expired SSTs across levels.
+pub(crate) fn get_expired_ssts(
+    levels: &[LevelMeta],
+    ttl: Option<Duration>,
+    now: Timestamp,
+) -> Vec<FileHandle> {
+    let Some(ttl) = ttl else {
+        return vec![];
+    };
+
+    let expire_time = match now.sub_duration(ttl) {
+        Ok(expire_time) => expire_time,
+        Err(e) => {
+            error!(e; "Failed to calculate region TTL expire time");
+            return vec![];
+        }
+    };
+
+    levels
+        .iter()
+        .flat_map(|l| l.get_expired_files(&expire_time).into_iter())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Mutex;
@@ -378,6 +560,7 @@ mod tests {
                 &env.access_layer,
                 &purger,
                 waiter,
+                CompactOptions::Regular,
                 Arc::new(MitoConfig::default()),
             )
             .unwrap();
@@ -396,6 +579,7 @@ mod tests {
                 &env.access_layer,
                 &purger,
                 waiter,
+                CompactOptions::Regular,
                 Arc::new(MitoConfig::default()),
             )
             .unwrap();
@@ -404,6 +588,63 @@ mod tests {
         assert!(scheduler.region_status.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_schedule_sst_upgrade() {
+        let job_scheduler = Arc::new(VecScheduler::default());
+        let env = SchedulerEnv::new().await.scheduler(job_scheduler.clone());
+        let (tx, _rx) = mpsc::channel(4);
+        let mut scheduler = env.mock_compaction_scheduler(tx);
+        let mut builder = VersionControlBuilder::new();
+        let purger = builder.file_purger();
+        let region_id = builder.region_id();
+
+        // Only one file in an old format version, picker won't compact it.
+        let version_control =
+            Arc::new(builder.push_l0_file_with_format_version(0, 1000, 0).build());
+        let version = version_control.current().version;
+        assert_eq!((1, 1), version.ssts.num_files_and_outdated());
+
+        // Don't upgrade the file by default.
+        scheduler
+            .schedule_compaction(
+                region_id,
+                &version_control,
+                &env.access_layer,
+                &purger,
+                OptionOutputTx::none(),
+                CompactOptions::Regular,
+                Arc::new(MitoConfig::default()),
+            )
+            .unwrap();
+        assert!(scheduler.region_status.is_empty());
+        assert_eq!(0, job_scheduler.num_jobs());
+
+        let outputs = pick_outdated_ssts(&version);
+        assert_eq!(1, outputs.len());
+        assert_eq!(0, outputs[0].output_level);
+        assert_eq!(1, outputs[0].inputs.len());
+        assert_eq!(0, outputs[0].inputs[0].format_version());
+
+        let config = MitoConfig {
+            enable_sst_upgrade: true,
+            ..Default::default()
+        };
+        scheduler
+            .schedule_compaction(
+                region_id,
+                &version_control,
+                &env.access_layer,
+                &purger,
+                OptionOutputTx::none(),
+                CompactOptions::Regular,
+                Arc::new(config),
+            )
+            .unwrap();
+        // Should schedule 1 task to rewrite the file.
+        assert_eq!(1, scheduler.region_status.len());
+        assert_eq!(1, job_scheduler.num_jobs());
+    }
+
     #[derive(Default)]
     struct VecScheduler {
         jobs: Mutex<Vec<Job>>,
@@ -455,6 +696,7 @@ mod tests {
                 &env.access_layer,
                 &purger,
                 OptionOutputTx::none(),
+                CompactOptions::Regular,
                 Arc::new(MitoConfig::default()),
             )
             .unwrap();
@@ -483,6 +725,7 @@ mod tests {
                 &env.access_layer,
                 &purger,
                 OptionOutputTx::none(),
+                CompactOptions::Regular,
                 Arc::new(MitoConfig::default()),
             )
             .unwrap();
@@ -514,6 +757,7 @@ mod tests {
                 &env.access_layer,
                 &purger,
                 OptionOutputTx::none(),
+                CompactOptions::Regular,
                 Arc::new(MitoConfig::default()),
             )
             .unwrap();
@@ -15,17 +15,33 @@
 use std::fmt::Debug;
 use std::sync::Arc;
 
-use crate::compaction::CompactionRequest;
+use crate::compaction::task::CompactionOutput;
+use crate::region::version::Version;
 
-pub type CompactionPickerRef = Arc<dyn Picker + Send + Sync>;
+pub type CompactionStrategyRef = Arc<dyn CompactionStrategy>;
 
 #[async_trait::async_trait]
 pub trait CompactionTask: Debug + Send + Sync + 'static {
     async fn run(&mut self);
 }
 
-/// Picker picks input SST files and builds the compaction task.
-/// Different compaction strategy may implement different pickers.
-pub trait Picker: Debug + Send + 'static {
-    fn pick(&self, req: CompactionRequest) -> Option<Box<dyn CompactionTask>>;
+/// Files to compact picked by a [CompactionStrategy].
+#[derive(Debug, Default)]
+pub struct PickerOutput {
+    /// Files to merge and their outputs.
+    pub(crate) outputs: Vec<CompactionOutput>,
+    /// Time window (in seconds) the strategy used to pick files. The region
+    /// remembers it as its compaction time window if it is present.
+    pub(crate) time_window_size: Option<i64>,
+}
+
+/// Compaction strategy decides which SST files of a region to merge.
+///
+/// The scheduler removes expired SST files and builds the compaction task from
+/// the [PickerOutput], so a strategy only needs to group the files. The
+/// strategy of a region is chosen by the compaction options of the region,
+/// which can be changed without reopening the region.
+pub trait CompactionStrategy: Debug + Send + Sync + 'static {
+    /// Picks files to merge from the `version` of a region.
+    fn pick(&self, version: &Version) -> PickerOutput;
 }
@@ -0,0 +1,193 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use common_telemetry::debug;
+
+use crate::compaction::picker::{CompactionStrategy, PickerOutput};
+use crate::compaction::task::CompactionOutput;
+use crate::region::version::Version;
+use crate::sst::file::{FileHandle, FileId};
+use crate::sst::version::LevelMeta;
+
+/// `SizeTieredPicker` groups files of similar sizes into tiers and merges the
+/// files in a tier once the tier has enough files.
+///
+/// Files smaller than `min_file_size` are in tier 0. Tier `n` holds files whose
+/// size is in `[min_file_size * tier_ratio^(n-1), min_file_size * tier_ratio^n)`.
+#[derive(Debug)]
+pub struct SizeTieredPicker {
+    /// Min number of files in a tier to trigger a compaction.
+    min_threshold: usize,
+    /// Max number of files to merge into one output.
+    max_threshold: usize,
+    /// Size of the smallest tier in bytes.
+    min_file_size: u64,
+    /// Size ratio between two adjacent tiers.
+    tier_ratio: u64,
+}
+
+impl SizeTieredPicker {
+    pub fn new(
+        min_threshold: usize,
+        max_threshold: usize,
+        min_file_size: u64,
+        tier_ratio: usize,
+    ) -> Self {
+        // Merging less than two files is meaningless.
+        let min_threshold = min_threshold.max(2);
+        Self {
+            min_threshold,
+            max_threshold: max_threshold.max(min_threshold),
+            min_file_size: min_file_size.max(1),
+            tier_ratio: (tier_ratio as u64).max(2),
+        }
+    }
+
+    /// Returns the tier of a file with `file_size` bytes.
+    fn tier_of(&self, file_size: u64) -> u32 {
+        let mut tier = 0;
+        let mut bound = self.min_file_size;
+        while file_size >= bound {
+            tier += 1;
+            match bound.checked_mul(self.tier_ratio) {
+                Some(next) => bound = next,
+                None => break,
+            }
+        }
+        tier
+    }
+
+    /// Builds compaction outputs from files.
+    ///
+    /// Files in the same tier are merged from the smallest one, at most
+    /// `max_threshold` files in each output.
+    fn build_output<'a>(
+        &self,
+        files: impl Iterator<Item = &'a FileHandle>,
+    ) -> Vec<CompactionOutput> {
+        let mut tiers: BTreeMap<u32, Vec<FileHandle>> = BTreeMap::new();
+        for file in files.filter(|f| !f.compacting()) {
+            tiers
+                .entry(self.tier_of(file.size()))
+                .or_default()
+                .push(file.clone());
+        }
+
+        let mut outputs = vec![];
+        for (tier, mut files) in tiers {
+            if files.len() < self.min_threshold {
+                debug!(
+                    "No enough files in tier {}, current: {}, min_threshold: {}",
+                    tier,
+                    files.len(),
+                    self.min_threshold
+                );
+                continue;
+            }
+
+            files.sort_unstable_by_key(FileHandle::size);
+            for inputs in files.chunks(self.max_threshold) {
+                if inputs.len() < self.min_threshold {
+                    break;
+                }
+                outputs.push(CompactionOutput {
+                    output_file_id: FileId::random(),
+                    output_level: 1,
+                    inputs: inputs.to_vec(),
+                });
+            }
+        }
+        outputs
+    }
+}
+
+impl CompactionStrategy for SizeTieredPicker {
+    fn pick(&self, version: &Version) -> PickerOutput {
+        let outputs = self.build_output(version.ssts.levels().iter().flat_map(LevelMeta::files));
+
+        // The picker doesn't group files by time window so it keeps the time window
+        // of the region unchanged.
+        PickerOutput {
+            outputs,
+            time_window_size: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::compaction::test_util::new_file_handle_with_size;
+
+    #[test]
+    fn test_tier_of() {
+        let picker = SizeTieredPicker::new(4, 32, 100, 4);
+        assert_eq!(0, picker.tier_of(0));
+        assert_eq!(0, picker.tier_of(99));
+        assert_eq!(1, picker.tier_of(100));
+        assert_eq!(1, picker.tier_of(399));
+        assert_eq!(2, picker.tier_of(400));
+        assert_eq!(3, picker.tier_of(1600));
+        assert_eq!(29, picker.tier_of(u64::MAX));
+    }
+
+    #[test]
+    fn test_build_size_tiered_output() {
+        let picker = SizeTieredPicker::new(3, 4, 100, 4);
+        let sizes = [10, 20, 30, 40, 50, 60, 200, 300, 1000];
+        let files = sizes
+            .iter()
+            .map(|size| new_file_handle_with_size(FileId::random(), 0, 999, 0, *size))
+            .collect::<Vec<_>>();
+
+        let outputs = picker.build_output(files.iter());
+        let inputs = outputs
+            .iter()
+            .map(|output| {
+                assert_eq!(1, output.output_level);
+                output
+                    .inputs
+                    .iter()
+                    .map(|f| f.file_id())
+                    .collect::<HashSet<_>>()
+            })
+            .collect::<Vec<_>>();
+        // Tier 0 has 6 files, the remaining 2 files are not enough for another output.
+        // Tier 1 and tier 2 don't have enough files.
+        assert_eq!(
+            vec![files[..4]
+                .iter()
+                .map(|f| f.file_id())
+                .collect::<HashSet<_>>()],
+            inputs
+        );
+
+        // Skips compacting files.
+        files[0].set_compacting(true);
+        let outputs = picker.build_output(files.iter());
+        assert_eq!(1, outputs.len());
+        assert_eq!(
+            files[1..5].iter().map(|f| f.file_id()).collect::<Vec<_>>(),
+            outputs[0]
+                .inputs
+                .iter()
+                .map(|f| f.file_id())
+                .collect::<Vec<_>>()
+        );
+    }
+}
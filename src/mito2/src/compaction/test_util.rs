@@ -15,6 +15,7 @@
 use common_time::Timestamp;
 
 use crate::sst::file::{FileHandle, FileId, FileMeta, Level};
+use crate::sst::SST_FORMAT_VERSION;
 use crate::test_util::new_noop_file_purger;
 
 /// Test util to create file handles.
@@ -23,6 +24,17 @@ pub fn new_file_handle(
     start_ts_millis: i64,
     end_ts_millis: i64,
     level: Level,
+) -> FileHandle {
+    new_file_handle_with_size(file_id, start_ts_millis, end_ts_millis, level, 0)
+}
+
+/// Test util to create file handles with specific file size.
+pub fn new_file_handle_with_size(
+    file_id: FileId,
+    start_ts_millis: i64,
+    end_ts_millis: i64,
+    level: Level,
+    file_size: u64,
 ) -> FileHandle {
     let file_purger = new_noop_file_purger();
     FileHandle::new(
@@ -34,9 +46,10 @@ pub fn new_file_handle(
                 Timestamp::new_millisecond(end_ts_millis),
             ),
             level,
-            file_size: 0,
+            file_size,
             available_indexes: Default::default(),
             index_file_size: 0,
+            format_version: SST_FORMAT_VERSION,
         },
         file_purger,
     )
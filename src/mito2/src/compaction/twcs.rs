@@ -14,40 +14,17 @@
 
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Formatter};
-use std::sync::Arc;
-use std::time::{Duration, Instant};
 
-use common_telemetry::{debug, error, info};
+use common_telemetry::{debug, info};
 use common_time::timestamp::TimeUnit;
 use common_time::timestamp_millis::BucketAligned;
 use common_time::Timestamp;
-use smallvec::SmallVec;
-use snafu::ResultExt;
-use store_api::metadata::RegionMetadataRef;
-use store_api::storage::RegionId;
-use tokio::sync::mpsc;
-
-use crate::access_layer::{AccessLayerRef, SstWriteRequest};
-use crate::cache::CacheManagerRef;
-use crate::compaction::picker::{CompactionTask, Picker};
-use crate::compaction::CompactionRequest;
-use crate::config::MitoConfig;
-use crate::error::{self, CompactRegionSnafu};
-use crate::metrics::{COMPACTION_FAILURE_COUNT, COMPACTION_STAGE_ELAPSED};
-use crate::read::projection::ProjectionMapper;
-use crate::read::scan_region::ScanInput;
-use crate::read::seq_scan::SeqScan;
-use crate::read::{BoxedBatchReader, Source};
-use crate::region::options::IndexOptions;
-use crate::request::{
-    BackgroundNotify, CompactionFailed, CompactionFinished, OutputTx, WorkerRequest,
-};
-use crate::sst::file::{FileHandle, FileId, FileMeta, IndexType, Level};
-use crate::sst::file_purger::FilePurgerRef;
-use crate::sst::parquet::WriteOptions;
-use crate::sst::version::LevelMeta;
 
-const MAX_PARALLEL_COMPACTION: usize = 8;
+use crate::compaction::picker::{CompactionStrategy, PickerOutput};
+use crate::compaction::task::CompactionOutput;
+use crate::region::version::Version;
+use crate::sst::file::{FileHandle, FileId};
+use crate::sst::version::LevelMeta;
 
 /// `TwcsPicker` picks files of which the max timestamp are in the same time window as compaction
 /// candidates.
@@ -55,6 +32,8 @@ pub struct TwcsPicker {
     max_active_window_files: usize,
     max_inactive_window_files: usize,
     time_window_seconds: Option<i64>,
+    /// Whether `time_window_seconds` takes precedence over the time window of the region.
+    override_region_window: bool,
 }
 
 impl Debug for TwcsPicker {
@@ -62,6 +41,8 @@ impl Debug for TwcsPicker {
         f.debug_struct("TwcsPicker")
             .field("max_active_window_files", &self.max_active_window_files)
             .field("max_inactive_window_files", &self.max_inactive_window_files)
+            .field("time_window_seconds", &self.time_window_seconds)
+            .field("override_region_window", &self.override_region_window)
             .finish()
     }
 }
@@ -76,6 +57,20 @@ impl TwcsPicker {
             max_inactive_window_files,
             max_active_window_files,
             time_window_seconds,
+            override_region_window: false,
+        }
+    }
+
+    /// Creates a picker that compacts files in each time window into one file.
+    ///
+    /// The picker uses `time_window_seconds` as the time window if it is present,
+    /// otherwise the time window of the region.
+    pub fn new_strict_window(time_window_seconds: Option<i64>) -> Self {
+        Self {
+            max_active_window_files: 1,
+            max_inactive_window_files: 1,
+            time_window_seconds,
+            override_region_window: true,
         }
     }
 
@@ -122,76 +117,44 @@ impl TwcsPicker {
     }
 }
 
-impl Picker for TwcsPicker {
-    fn pick(&self, req: CompactionRequest) -> Option<Box<dyn CompactionTask>> {
-        let CompactionRequest {
-            engine_config,
-            current_version,
-            access_layer,
-            request_sender,
-            waiters,
-            file_purger,
-            start_time,
-            cache_manager,
-        } = req;
-
-        let region_metadata = current_version.metadata.clone();
-        let region_id = region_metadata.region_id;
-
-        let levels = current_version.ssts.levels();
-        let ttl = current_version.options.ttl;
-        let expired_ssts = get_expired_ssts(levels, ttl, Timestamp::current_millis());
-        if !expired_ssts.is_empty() {
-            info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
-            // here we mark expired SSTs as compacting to avoid them being picked.
-            expired_ssts.iter().for_each(|f| f.set_compacting(true));
-        }
+impl CompactionStrategy for TwcsPicker {
+    fn pick(&self, version: &Version) -> PickerOutput {
+        let region_id = version.metadata.region_id;
+        let levels = version.ssts.levels();
 
-        let compaction_time_window = current_version
+        let compaction_time_window = version
             .compaction_time_window
             .map(|window| window.as_secs() as i64);
-        let time_window_size = compaction_time_window
-            .or(self.time_window_seconds)
-            .unwrap_or_else(|| {
-                let inferred = infer_time_bucket(levels[0].files());
-                info!(
-                    "Compaction window for region {} is not present, inferring from files: {:?}",
-                    region_id, inferred
-                );
-                inferred
-            });
+        let time_window_size = if self.override_region_window {
+            self.time_window_seconds.or(compaction_time_window)
+        } else {
+            compaction_time_window.or(self.time_window_seconds)
+        }
+        .unwrap_or_else(|| {
+            let inferred = infer_time_bucket(levels[0].files());
+            info!(
+                "Compaction window for region {} is not present, inferring from files: {:?}",
+                region_id, inferred
+            );
+            inferred
+        });
 
         // Find active window from files in level 0.
         let active_window = find_latest_window_in_seconds(levels[0].files(), time_window_size);
-        // Assign files to windows
-        let windows = assign_to_windows(levels.iter().flat_map(LevelMeta::files), time_window_size);
+        // Assign files to windows, skipping files that are compacting or not persisted yet.
+        let windows = assign_to_windows(
+            levels
+                .iter()
+                .flat_map(LevelMeta::files)
+                .filter(|f| !f.compacting()),
+            time_window_size,
+        );
         let outputs = self.build_output(&windows, active_window);
 
-        if outputs.is_empty() && expired_ssts.is_empty() {
-            // Nothing to compact, we are done. Notifies all waiters as we consume the compaction request.
-            for waiter in waiters {
-                waiter.send(Ok(0));
-            }
-            return None;
-        }
-        let task = TwcsCompactionTask {
-            engine_config,
-            region_id,
-            metadata: region_metadata,
-            sst_layer: access_layer,
+        PickerOutput {
             outputs,
-            expired_ssts,
-            compaction_time_window: Some(time_window_size),
-            request_sender,
-            waiters,
-            file_purger,
-            start_time,
-            cache_manager,
-            storage: current_version.options.storage.clone(),
-            index_options: current_version.options.index_options.clone(),
-            append_mode: current_version.options.append_mode,
-        };
-        Some(Box::new(task))
+            time_window_size: Some(time_window_size),
+        }
     }
 }
 
@@ -237,247 +200,6 @@ fn find_latest_window_in_seconds<'a>(
         .and_then(|ts| ts.value().align_to_ceil_by_bucket(time_window_size))
 }
 
-pub(crate) struct TwcsCompactionTask {
-    pub engine_config: Arc<MitoConfig>,
-    pub region_id: RegionId,
-    pub metadata: RegionMetadataRef,
-    pub sst_layer: AccessLayerRef,
-    pub outputs: Vec<CompactionOutput>,
-    pub expired_ssts: Vec<FileHandle>,
-    pub compaction_time_window: Option<i64>,
-    pub file_purger: FilePurgerRef,
-    /// Request sender to notify the worker.
-    pub(crate) request_sender: mpsc::Sender<WorkerRequest>,
-    /// Senders that are used to notify waiters waiting for pending compaction tasks.
-    pub waiters: Vec<OutputTx>,
-    /// Start time of compaction task
-    pub start_time: Instant,
-    pub(crate) cache_manager: CacheManagerRef,
-    /// Target storage of the region.
-    pub(crate) storage: Option<String>,
-    /// Index options of the region.
-    pub(crate) index_options: IndexOptions,
-    /// The region is using append mode.
-    pub(crate) append_mode: bool,
-}
-
-impl Debug for TwcsCompactionTask {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("TwcsCompactionTask")
-            .field("region_id", &self.region_id)
-            .field("outputs", &self.outputs)
-            .field("expired_ssts", &self.expired_ssts)
-            .field("compaction_time_window", &self.compaction_time_window)
-            .field("append_mode", &self.append_mode)
-            .finish()
-    }
-}
-
-impl Drop for TwcsCompactionTask {
-    fn drop(&mut self) {
-        self.mark_files_compacting(false)
-    }
-}
-
-impl TwcsCompactionTask {
-    fn mark_files_compacting(&self, compacting: bool) {
-        self.outputs
-            .iter()
-            .flat_map(|o| o.inputs.iter())
-            .for_each(|f| f.set_compacting(compacting))
-    }
-
-    /// Merges all SST files.
-    /// Returns `(output files, input files)`.
-    async fn merge_ssts(&mut self) -> error::Result<(Vec<FileMeta>, Vec<FileMeta>)> {
-        let mut futs = Vec::with_capacity(self.outputs.len());
-        let mut compacted_inputs =
-            Vec::with_capacity(self.outputs.iter().map(|o| o.inputs.len()).sum());
-
-        for output in self.outputs.drain(..) {
-            compacted_inputs.extend(output.inputs.iter().map(FileHandle::meta));
-
-            info!(
-                "Compaction region {} output [{}]-> {}",
-                self.region_id,
-                output
-                    .inputs
-                    .iter()
-                    .map(|f| f.file_id().to_string())
-                    .collect::<Vec<_>>()
-                    .join(","),
-                output.output_file_id
-            );
-
-            let write_opts = WriteOptions {
-                write_buffer_size: self.engine_config.sst_write_buffer_size,
-                ..Default::default()
-            };
-            let create_inverted_index = self
-                .engine_config
-                .inverted_index
-                .create_on_compaction
-                .auto();
-            let mem_threshold_index_create = self
-                .engine_config
-                .inverted_index
-                .mem_threshold_on_create
-                .map(|m| m.as_bytes() as _);
-            let index_write_buffer_size = Some(
-                self.engine_config
-                    .inverted_index
-                    .write_buffer_size
-                    .as_bytes() as usize,
-            );
-
-            let metadata = self.metadata.clone();
-            let sst_layer = self.sst_layer.clone();
-            let region_id = self.region_id;
-            let file_id = output.output_file_id;
-            let cache_manager = self.cache_manager.clone();
-            let storage = self.storage.clone();
-            let index_options = self.index_options.clone();
-            let append_mode = self.append_mode;
-            futs.push(async move {
-                let reader = build_sst_reader(
-                    metadata.clone(),
-                    sst_layer.clone(),
-                    &output.inputs,
-                    append_mode,
-                )
-                .await?;
-                let file_meta_opt = sst_layer
-                    .write_sst(
-                        SstWriteRequest {
-                            file_id,
-                            metadata,
-                            source: Source::Reader(reader),
-                            cache_manager,
-                            storage,
-                            create_inverted_index,
-                            mem_threshold_index_create,
-                            index_write_buffer_size,
-                            index_options,
-                        },
-                        &write_opts,
-                    )
-                    .await?
-                    .map(|sst_info| FileMeta {
-                        region_id,
-                        file_id,
-                        time_range: sst_info.time_range,
-                        level: output.output_level,
-                        file_size: sst_info.file_size,
-                        available_indexes: sst_info
-                            .inverted_index_available
-                            .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
-                            .unwrap_or_default(),
-                        index_file_size: sst_info.index_file_size,
-                    });
-                Ok(file_meta_opt)
-            });
-        }
-
-        let mut output_files = Vec::with_capacity(futs.len());
-        while !futs.is_empty() {
-            let mut task_chunk = Vec::with_capacity(MAX_PARALLEL_COMPACTION);
-            for _ in 0..MAX_PARALLEL_COMPACTION {
-                if let Some(task) = futs.pop() {
-                    task_chunk.push(common_runtime::spawn_bg(task));
-                }
-            }
-            let metas = futures::future::try_join_all(task_chunk)
-                .await
-                .context(error::JoinSnafu)?
-                .into_iter()
-                .collect::<error::Result<Vec<_>>>()?;
-            output_files.extend(metas.into_iter().flatten());
-        }
-
-        let inputs = compacted_inputs.into_iter().collect();
-        Ok((output_files, inputs))
-    }
-
-    async fn handle_compaction(&mut self) -> error::Result<(Vec<FileMeta>, Vec<FileMeta>)> {
-        self.mark_files_compacting(true);
-        let merge_timer = COMPACTION_STAGE_ELAPSED
-            .with_label_values(&["merge"])
-            .start_timer();
-        let (output, mut compacted) = self.merge_ssts().await.map_err(|e| {
-            error!(e; "Failed to compact region: {}", self.region_id);
-            merge_timer.stop_and_discard();
-            e
-        })?;
-        compacted.extend(self.expired_ssts.iter().map(FileHandle::meta));
-        Ok((output, compacted))
-    }
-
-    /// Handles compaction failure, notifies all waiters.
-    fn on_failure(&mut self, err: Arc<error::Error>) {
-        COMPACTION_FAILURE_COUNT.inc();
-        for waiter in self.waiters.drain(..) {
-            waiter.send(Err(err.clone()).context(CompactRegionSnafu {
-                region_id: self.region_id,
-            }));
-        }
-    }
-
-    /// Notifies region worker to handle post-compaction tasks.
-    async fn send_to_worker(&self, request: WorkerRequest) {
-        if let Err(e) = self.request_sender.send(request).await {
-            error!(
-                "Failed to notify compaction job status for region {}, request: {:?}",
-                self.region_id, e.0
-            );
-        }
-    }
-}
-
-#[async_trait::async_trait]
-impl CompactionTask for TwcsCompactionTask {
-    async fn run(&mut self) {
-        let notify = match self.handle_compaction().await {
-            Ok((added, deleted)) => {
-                info!(
-                    "Compacted SST files, input: {:?}, output: {:?}, window: {:?}, waiter_num: {}",
-                    deleted,
-                    added,
-                    self.compaction_time_window,
-                    self.waiters.len(),
-                );
-
-                BackgroundNotify::CompactionFinished(CompactionFinished {
-                    region_id: self.region_id,
-                    compaction_outputs: added,
-                    compacted_files: deleted,
-                    senders: std::mem::take(&mut self.waiters),
-                    file_purger: self.file_purger.clone(),
-                    compaction_time_window: self
-                        .compaction_time_window
-                        .map(|seconds| Duration::from_secs(seconds as u64)),
-                    start_time: self.start_time,
-                })
-            }
-            Err(e) => {
-                error!(e; "Failed to compact region, region id: {}", self.region_id);
-                let err = Arc::new(e);
-                // notify compaction waiters
-                self.on_failure(err.clone());
-                BackgroundNotify::CompactionFailed(CompactionFailed {
-                    region_id: self.region_id,
-                    err,
-                })
-            }
-        };
-
-        self.send_to_worker(WorkerRequest::Background {
-            region_id: self.region_id,
-            notify,
-        })
-        .await;
-    }
-}
-
 /// Infers the suitable time bucket duration.
 /// Now it simply find the max and min timestamp across all SSTs in level and fit the time span
 /// into time bucket.
@@ -541,54 +263,6 @@ pub(crate) const TIME_BUCKETS: TimeBuckets = TimeBuckets([
     10 * 365 * 24 * 60 * 60, // ten years
 ]);
 
-/// Finds all expired SSTs across levels.
-fn get_expired_ssts(
-    levels: &[LevelMeta],
-    ttl: Option<Duration>,
-    now: Timestamp,
-) -> Vec<FileHandle> {
-    let Some(ttl) = ttl else {
-        return vec![];
-    };
-
-    let expire_time = match now.sub_duration(ttl) {
-        Ok(expire_time) => expire_time,
-        Err(e) => {
-            error!(e; "Failed to calculate region TTL expire time");
-            return vec![];
-        }
-    };
-
-    levels
-        .iter()
-        .flat_map(|l| l.get_expired_files(&expire_time).into_iter())
-        .collect()
-}
-
-#[derive(Debug)]
-pub(crate) struct CompactionOutput {
-    pub output_file_id: FileId,
-    /// Compaction output file level.
-    pub output_level: Level,
-    /// Compaction input files.
-    pub inputs: Vec<FileHandle>,
-}
-
-/// Builds [BoxedBatchReader] that reads all SST files and yields batches in primary key order.
-async fn build_sst_reader(
-    metadata: RegionMetadataRef,
-    sst_layer: AccessLayerRef,
-    inputs: &[FileHandle],
-    append_mode: bool,
-) -> error::Result<BoxedBatchReader> {
-    let scan_input = ScanInput::new(sst_layer, ProjectionMapper::all(&metadata)?)
-        .with_files(inputs.to_vec())
-        .with_append_mode(append_mode)
-        // We ignore file not found error during compaction.
-        .with_ignore_file_not_found(true);
-    SeqScan::new(scan_input).build_reader().await
-}
-
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -757,6 +431,34 @@ mod tests {
         .check();
     }
 
+    #[test]
+    fn test_build_strict_window_output() {
+        let file_ids = (0..3).map(|_| FileId::random()).collect::<Vec<_>>();
+        let files = [
+            new_file_handle(file_ids[0], -2000, -3, 0),
+            new_file_handle(file_ids[1], 0, 2999, 0),
+            new_file_handle(file_ids[2], 50, 2998, 0),
+        ];
+        let windows = assign_to_windows(files.iter(), 3);
+        let active_window = find_latest_window_in_seconds(files.iter(), 3);
+
+        // The regular picker keeps the active window as is.
+        let output = TwcsPicker::new(4, 1, None).build_output(&windows, active_window);
+        assert!(output.is_empty());
+
+        // The strict window picker compacts every window with more than one file.
+        let output = TwcsPicker::new_strict_window(Some(3)).build_output(&windows, active_window);
+        assert_eq!(1, output.len());
+        assert_eq!(
+            HashSet::from([file_ids[1], file_ids[2]]),
+            output[0]
+                .inputs
+                .iter()
+                .map(|f| f.file_id())
+                .collect::<HashSet<_>>()
+        );
+    }
+
     #[test]
     fn test_time_bucket() {
         assert_eq!(TIME_BUCKETS.get(0), TIME_BUCKETS.fit_time_bucket(1));
@@ -64,6 +64,14 @@ pub struct MitoConfig {
     // Background job configs:
     /// Max number of running background jobs (default 4).
     pub max_background_jobs: usize,
+    /// Whether to rewrite SSTs in old format versions to the current version
+    /// while the region has nothing to compact (default false).
+    pub enable_sst_upgrade: bool,
+    /// Max time a snapshot pins its region, which doesn't compact while it has pinned
+    /// snapshots (default 30 min). Pins older than it are released even if their
+    /// snapshots are alive.
+    #[serde(with = "humantime_serde")]
+    pub max_snapshot_pin_duration: Duration,
 
     // Flush configs:
     /// Interval to auto flush a region if it has not flushed yet (default 30 min).
@@ -73,6 +81,21 @@ pub struct MitoConfig {
     pub global_write_buffer_size: ReadableSize,
     /// Global write buffer size threshold to reject write requests.
     pub global_write_buffer_reject_size: ReadableSize,
+    /// Min available space of the disk that stores the data home to accept
+    /// write requests. Setting it to 0 to disable the check, which is the default
+    /// as the disk doesn't hold SST files if the storage is a remote object store.
+    pub min_free_disk_space: ReadableSize,
+    /// Warns if the number of series in a flushed region is more than this factor
+    /// times of the previous flush. Setting it to 0 to disable the check.
+    pub series_growth_alert_factor: usize,
+    /// Max rows to write to the engine per second. Setting it to 0 to disable the limit.
+    pub max_write_rows_per_second: u64,
+    /// Max bytes to write to the engine per second. Setting it to 0 to disable the limit.
+    pub max_write_bytes_per_second: ReadableSize,
+    /// How to handle write requests exceeding rate limits of the engine or tables.
+    pub write_rate_limit_policy: WriteRateLimitPolicy,
+    /// Max size of write requests held by each region worker under the block policy.
+    pub write_rate_limit_buffer_size: ReadableSize,
 
     // Cache configs:
     /// Cache size for SST metadata. Setting it to 0 to disable the cache.
@@ -87,6 +110,9 @@ pub struct MitoConfig {
     pub experimental_write_cache_path: String,
     /// Capacity for write cache.
     pub experimental_write_cache_size: ReadableSize,
+    /// Whether to upload flushed SSTs from the write cache in background and release
+    /// memtables before the upload finishes. A flush still finishes after the upload.
+    pub experimental_write_cache_async_upload: bool,
 
     // Other configs:
     /// Buffer size for SST writing.
@@ -100,6 +126,11 @@ pub struct MitoConfig {
     pub parallel_scan_channel_size: usize,
     /// Whether to allow stale entries read during replay.
     pub allow_stale_entries: bool,
+    /// Whether to keep WAL entries until they are replicated, see
+    /// [MitoEngine::set_replicated_entry_id](crate::engine::MitoEngine::set_replicated_entry_id).
+    /// It's set by the datanode when replication is enabled.
+    #[serde(skip)]
+    pub retain_wal_for_replication: bool,
 
     /// Inverted index configs.
     pub inverted_index: InvertedIndexConfig,
@@ -117,19 +148,29 @@ impl Default for MitoConfig {
             manifest_checkpoint_distance: 10,
             compress_manifest: false,
             max_background_jobs: DEFAULT_MAX_BG_JOB,
+            enable_sst_upgrade: false,
+            max_snapshot_pin_duration: Duration::from_secs(30 * 60),
             auto_flush_interval: Duration::from_secs(30 * 60),
             global_write_buffer_size: ReadableSize::gb(1),
             global_write_buffer_reject_size: ReadableSize::gb(2),
+            min_free_disk_space: ReadableSize(0),
+            series_growth_alert_factor: 4,
+            max_write_rows_per_second: 0,
+            max_write_bytes_per_second: ReadableSize(0),
+            write_rate_limit_policy: WriteRateLimitPolicy::default(),
+            write_rate_limit_buffer_size: ReadableSize::mb(64),
             sst_meta_cache_size: ReadableSize::mb(128),
             vector_cache_size: ReadableSize::mb(512),
             page_cache_size: ReadableSize::mb(512),
             enable_experimental_write_cache: false,
             experimental_write_cache_path: String::new(),
             experimental_write_cache_size: ReadableSize::mb(512),
+            experimental_write_cache_async_upload: false,
             sst_write_buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
             scan_parallelism: divide_num_cpus(4),
             parallel_scan_channel_size: DEFAULT_SCAN_CHANNEL_SIZE,
             allow_stale_entries: false,
+            retain_wal_for_replication: false,
             inverted_index: InvertedIndexConfig::default(),
             memtable: MemtableConfig::default(),
         };
@@ -236,6 +277,17 @@ impl MitoConfig {
     }
 }
 
+/// Policy to handle write requests exceeding rate limits.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteRateLimitPolicy {
+    /// Holds requests in the region worker until the limits allow them.
+    #[default]
+    Block,
+    /// Rejects requests with the time to retry.
+    Reject,
+}
+
 /// Operational mode for certain actions.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
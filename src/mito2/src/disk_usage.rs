@@ -0,0 +1,183 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Disk usage tracking to stop ingestion when the disk or a table quota is full.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use common_base::readable_size::ReadableSize;
+use common_telemetry::{info, warn};
+use common_time::util::current_time_millis;
+use store_api::storage::TableId;
+
+use crate::region::RegionMapRef;
+
+/// Interval to refresh the disk usage.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+pub(crate) type DiskUsageManagerRef = Arc<DiskUsageManager>;
+
+/// Tracks the available space of the disk and the disk usage of tables with quotas.
+///
+/// Workers reject write requests while the disk is full or the table exceeds its quota,
+/// but reads, flushes and compactions still work. Write requests refresh the usage
+/// lazily, at most once per [REFRESH_INTERVAL] unless the usage is invalidated.
+#[derive(Debug)]
+pub(crate) struct DiskUsageManager {
+    /// Path on the disk to check.
+    path: String,
+    /// Min available space of the disk to accept write requests.
+    min_free_space: ReadableSize,
+    /// Regions of all workers.
+    region_maps: Vec<RegionMapRef>,
+    /// Last time to refresh the usage.
+    last_refresh_millis: AtomicI64,
+    /// Whether the available space of the disk is below `min_free_space`.
+    disk_full: AtomicBool,
+    /// Tables that exceed their quotas.
+    exceeded_tables: RwLock<HashSet<TableId>>,
+}
+
+impl DiskUsageManager {
+    /// Creates a manager that checks the disk `path` and tables of regions in `region_maps`.
+    pub(crate) fn new(
+        path: String,
+        min_free_space: ReadableSize,
+        region_maps: Vec<RegionMapRef>,
+    ) -> Self {
+        if min_free_space.as_bytes() > 0
+            && common_config::utils::get_available_disk_space(&path).is_none()
+        {
+            warn!(
+                "Unable to get available space of the disk for {}, skip checking it",
+                path
+            );
+        }
+
+        Self {
+            path,
+            min_free_space,
+            region_maps,
+            last_refresh_millis: AtomicI64::new(0),
+            disk_full: AtomicBool::new(false),
+            exceeded_tables: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Returns the min available space of the disk to accept write requests.
+    pub(crate) fn min_free_space(&self) -> ReadableSize {
+        self.min_free_space
+    }
+
+    /// Returns true if the available space of the disk is below the limit.
+    pub(crate) fn is_disk_full(&self) -> bool {
+        self.disk_full.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if the table exceeds its quota.
+    pub(crate) fn is_quota_exceeded(&self, table_id: TableId) -> bool {
+        self.exceeded_tables.read().unwrap().contains(&table_id)
+    }
+
+    /// Invalidates the usage so the next write request refreshes it.
+    ///
+    /// Workers call it once SST files of a region change.
+    pub(crate) fn invalidate(&self) {
+        self.last_refresh_millis.store(0, Ordering::Relaxed);
+    }
+
+    /// Refreshes the usage if it is stale.
+    pub(crate) fn maybe_refresh(&self) {
+        let now = current_time_millis();
+        let last = self.last_refresh_millis.load(Ordering::Relaxed);
+        if now - last < REFRESH_INTERVAL.as_millis() as i64 {
+            return;
+        }
+        // Only one worker refreshes the usage.
+        if self
+            .last_refresh_millis
+            .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return;
+        }
+
+        self.refresh();
+    }
+
+    /// Refreshes the available space of the disk and the usage of tables.
+    pub(crate) fn refresh(&self) {
+        self.refresh_disk();
+        self.refresh_tables();
+    }
+
+    fn refresh_disk(&self) {
+        if self.min_free_space.as_bytes() == 0 {
+            return;
+        }
+        let Some(available) = common_config::utils::get_available_disk_space(&self.path) else {
+            return;
+        };
+
+        let is_full = available < self.min_free_space;
+        if self.disk_full.swap(is_full, Ordering::Relaxed) == is_full {
+            return;
+        }
+        if is_full {
+            warn!(
+                "Available space of the disk for {} is {}, below {}, rejecting write requests",
+                self.path, available, self.min_free_space
+            );
+        } else {
+            info!(
+                "Available space of the disk for {} is {}, accepting write requests",
+                self.path, available
+            );
+        }
+    }
+
+    fn refresh_tables(&self) {
+        // Sums SST usage of tables with quotas.
+        let mut usages: HashMap<TableId, (u64, ReadableSize)> = HashMap::new();
+        for regions in &self.region_maps {
+            for region in regions.list_regions() {
+                let version = region.version();
+                let Some(quota) = version.options.disk_quota else {
+                    continue;
+                };
+                let usage = usages
+                    .entry(region.region_id.table_id())
+                    .or_insert((0, quota));
+                usage.0 += version.ssts.sst_usage();
+            }
+        }
+
+        let exceeded_tables: HashSet<_> = usages
+            .into_iter()
+            .filter(|(_, (usage, quota))| *usage >= quota.as_bytes())
+            .map(|(table_id, _)| table_id)
+            .collect();
+        let mut current = self.exceeded_tables.write().unwrap();
+        for table_id in exceeded_tables.difference(&current) {
+            warn!(
+                "Table {} exceeds its disk quota, rejecting write requests",
+                table_id
+            );
+        }
+        *current = exceeded_tables;
+    }
+}
@@ -34,17 +34,29 @@ mod drop_test;
 mod filter_deleted_test;
 #[cfg(test)]
 mod flush_test;
+#[cfg(test)]
+mod insert_mode_test;
 #[cfg(any(test, feature = "test"))]
 pub mod listener;
 #[cfg(test)]
+mod merge_mode_test;
+#[cfg(test)]
 mod open_test;
 #[cfg(test)]
+mod out_of_order_test;
+#[cfg(test)]
 mod parallel_test;
 #[cfg(test)]
 mod projection_test;
 #[cfg(test)]
 mod prune_test;
 #[cfg(test)]
+mod rate_limit_test;
+#[cfg(test)]
+mod replay_wal_test;
+#[cfg(test)]
+mod replication_test;
+#[cfg(test)]
 mod set_readonly_test;
 #[cfg(test)]
 mod truncate_test;
@@ -53,26 +65,39 @@ use std::any::Any;
 use std::sync::Arc;
 use std::time::Instant;
 
+use api::v1::{RowInsertRequest, Rows};
+use async_stream::try_stream;
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_recordbatch::SendableRecordBatchStream;
 use common_telemetry::tracing;
+use futures::StreamExt;
 use object_store::manager::ObjectStoreManagerRef;
+use prost::Message;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::metadata::RegionMetadataRef;
+use store_api::mito_engine_options::{
+    FLUSHED_ENTRY_ID_EXTENSION_KEY, OUT_OF_ORDER_ROWS_EXTENSION_KEY_PREFIX,
+    WAL_REPLAYED_EXTENSION_KEY,
+};
 use store_api::region_engine::{RegionEngine, RegionHandleResult, RegionRole, SetReadonlyResponse};
 use store_api::region_request::{AffectedRows, RegionRequest};
 use store_api::storage::{RegionId, ScanRequest};
 use tokio::sync::oneshot;
 
 use crate::config::MitoConfig;
-use crate::error::{InvalidRequestSnafu, RecvSnafu, RegionNotFoundSnafu, Result};
+use crate::error::{
+    InvalidRequestSnafu, RecvSnafu, RegionNotFoundSnafu, Result, SnapshotNotRetainedSnafu,
+};
 use crate::manifest::action::RegionEdit;
 use crate::metrics::HANDLE_REQUEST_ELAPSED;
 use crate::read::scan_region::{ScanParallism, ScanRegion, Scanner};
-use crate::region::RegionUsage;
+use crate::region::options::OutOfOrderPolicy;
+use crate::region::snapshot::RegionSnapshot;
+use crate::region::{RegionUsage, SstUpgradeProgress};
 use crate::request::WorkerRequest;
+use crate::wal::{EntryId, Wal, WalEntryStream, WalReaderRef};
 use crate::worker::WorkerGroup;
 
 pub const MITO_ENGINE_NAME: &str = "mito";
@@ -94,7 +119,9 @@ impl MitoEngine {
         config.sanitize(data_home)?;
 
         Ok(MitoEngine {
-            inner: Arc::new(EngineInner::new(config, log_store, object_store_manager).await?),
+            inner: Arc::new(
+                EngineInner::new(data_home, config, log_store, object_store_manager).await?,
+            ),
         })
     }
 
@@ -114,6 +141,84 @@ impl MitoEngine {
         Ok(region.region_usage().await)
     }
 
+    /// Returns the progress of upgrading SSTs of the region to the current format version.
+    pub fn get_sst_upgrade_progress(&self, region_id: RegionId) -> Result<SstUpgradeProgress> {
+        let region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+
+        Ok(region.sst_upgrade_progress())
+    }
+
+    /// Returns the progress of upgrading SSTs of all regions, ordered by region id.
+    pub fn list_sst_upgrade_progress(&self) -> Vec<SstUpgradeProgress> {
+        let mut progress = self
+            .inner
+            .workers
+            .list_regions()
+            .iter()
+            .map(|region| region.sst_upgrade_progress())
+            .collect::<Vec<_>>();
+        progress.sort_unstable_by_key(|progress| progress.region_id.as_u64());
+        progress
+    }
+
+    /// Pins a snapshot of the region at the sequence of the last committed write.
+    ///
+    /// Scan requests with the sequence of the snapshot read the region as of the
+    /// snapshot, excluding later writes, until the snapshot is dropped or expires
+    /// after [MitoConfig::max_snapshot_pin_duration]. The region doesn't compact
+    /// while it has snapshots.
+    pub async fn pin_snapshot(&self, region_id: RegionId) -> Result<RegionSnapshot> {
+        let (sender, receiver) = oneshot::channel();
+        let request = WorkerRequest::PinSnapshot { region_id, sender };
+        self.inner
+            .workers
+            .submit_to_worker(region_id, request)
+            .await?;
+        receiver.await.context(RecvSnafu)?
+    }
+
+    /// Returns the WAL entries of the region from `start_id` (inclusive), in the order
+    /// they are written. The stream ends at the last entry written before it starts.
+    pub fn scan_wal(
+        &self,
+        region_id: RegionId,
+        start_id: EntryId,
+    ) -> Result<WalEntryStream<'static>> {
+        let region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        let wal = self.inner.wal.clone();
+        let wal_options = region.wal_options.clone();
+        let stream = try_stream!({
+            let mut entries = wal.scan(region_id, start_id, &wal_options)?;
+            while let Some(entry) = entries.next().await {
+                yield entry?;
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Sets the id of the last WAL entry of the region replicated to the standby cluster.
+    ///
+    /// If [MitoConfig::retain_wal_for_replication] is set, flushes never delete WAL
+    /// entries after it.
+    pub fn set_replicated_entry_id(&self, region_id: RegionId, entry_id: EntryId) -> Result<()> {
+        let region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        region.set_replicated_entry_id(entry_id);
+        Ok(())
+    }
+
     /// Returns a scanner to scan for `request`.
     fn scanner(&self, region_id: RegionId, request: ScanRequest) -> Result<Scanner> {
         self.scan_region(region_id, request)?.scanner()
@@ -172,6 +277,7 @@ fn is_valid_region_edit(edit: &RegionEdit) -> bool {
                 compaction_time_window: None,
                 flushed_entry_id: None,
                 flushed_sequence: None,
+                retained_flushes: None,
             }
         )
 }
@@ -180,6 +286,8 @@ fn is_valid_region_edit(edit: &RegionEdit) -> bool {
 struct EngineInner {
     /// Region workers group.
     workers: WorkerGroup,
+    /// Reader of the WAL shared by workers.
+    wal: WalReaderRef,
     /// Config of the engine.
     config: Arc<MitoConfig>,
 }
@@ -187,13 +295,17 @@ struct EngineInner {
 impl EngineInner {
     /// Returns a new [EngineInner] with specific `config`, `log_store` and `object_store`.
     async fn new<S: LogStore>(
+        data_home: &str,
         config: MitoConfig,
         log_store: Arc<S>,
         object_store_manager: ObjectStoreManagerRef,
     ) -> Result<EngineInner> {
         let config = Arc::new(config);
+        let wal = Arc::new(Wal::new(log_store.clone()));
         Ok(EngineInner {
-            workers: WorkerGroup::start(config.clone(), log_store, object_store_manager).await?,
+            workers: WorkerGroup::start(data_home, config.clone(), log_store, object_store_manager)
+                .await?,
+            wal,
             config,
         })
     }
@@ -227,6 +339,57 @@ impl EngineInner {
         receiver.await.context(RecvSnafu)?
     }
 
+    /// Takes rows out of order from the put `rows` by the out-of-order options of the
+    /// region. Returns the request to write them to the dead-letter table, if any.
+    ///
+    /// This runs before the request reaches the worker, so watermarks are advanced
+    /// even if the write fails later.
+    fn split_out_of_order(
+        &self,
+        region_id: RegionId,
+        rows: &mut Rows,
+    ) -> Result<Option<RowInsertRequest>> {
+        // The worker reports the region not found.
+        let Some(region) = self.workers.get_region(region_id) else {
+            return Ok(None);
+        };
+        let version = region.version();
+        let out_of_order = &version.options.out_of_order;
+        let reject = match out_of_order.policy {
+            OutOfOrderPolicy::Accept => return Ok(None),
+            OutOfOrderPolicy::Reject => true,
+            OutOfOrderPolicy::DeadLetter => false,
+        };
+        // Region options ensure the window under other policies.
+        let Some(window) = out_of_order.window else {
+            return Ok(None);
+        };
+
+        let late = region
+            .watermarks
+            .advance(region_id, &version, rows, window, reject)?;
+        if late.is_empty() {
+            return Ok(None);
+        }
+        let mut late = late.into_iter().peekable();
+        let mut late_rows = Vec::with_capacity(late.len());
+        for (index, row) in std::mem::take(&mut rows.rows).into_iter().enumerate() {
+            if late.next_if_eq(&index).is_some() {
+                late_rows.push(row);
+            } else {
+                rows.rows.push(row);
+            }
+        }
+
+        Ok(Some(RowInsertRequest {
+            table_name: out_of_order.dead_letter_table.clone().unwrap_or_default(),
+            rows: Some(Rows {
+                schema: rows.schema.clone(),
+                rows: late_rows,
+            }),
+        }))
+    }
+
     /// Handles the scan `request` and returns a [ScanRegion].
     fn handle_query(&self, region_id: RegionId, request: ScanRequest) -> Result<ScanRegion> {
         let query_start = Instant::now();
@@ -235,7 +398,19 @@ impl EngineInner {
             .workers
             .get_region(region_id)
             .context(RegionNotFoundSnafu { region_id })?;
-        let version = region.version();
+        let version_data = region.version_control.current();
+        if let Some(sequence) = request.sequence {
+            // Rows of older sequences may be replaced by later writes unless a snapshot
+            // retains them.
+            ensure!(
+                sequence >= version_data.committed_sequence || region.snapshots.is_pinned(sequence),
+                SnapshotNotRetainedSnafu {
+                    region_id,
+                    sequence
+                }
+            );
+        }
+        let version = version_data.version;
         // Get cache.
         let cache_manager = self.workers.cache_manager();
         let scan_parallelism = ScanParallism {
@@ -298,17 +473,60 @@ impl RegionEngine for MitoEngine {
     async fn handle_request(
         &self,
         region_id: RegionId,
-        request: RegionRequest,
+        mut request: RegionRequest,
     ) -> Result<RegionHandleResult, BoxedError> {
         let _timer = HANDLE_REQUEST_ELAPSED
             .with_label_values(&[request.request_type()])
             .start_timer();
 
-        self.inner
-            .handle_request(region_id, request)
-            .await
-            .map(RegionHandleResult::new)
-            .map_err(BoxedError::new)
+        let is_flush = matches!(request, RegionRequest::Flush(_));
+        let is_replay_wal = matches!(request, RegionRequest::ReplayWal(_));
+        let dead_letters = match &mut request {
+            RegionRequest::Put(put) => self
+                .inner
+                .split_out_of_order(region_id, &mut put.rows)
+                .map_err(BoxedError::new)?,
+            _ => None,
+        };
+        let affected_rows = match &request {
+            // All rows are out of order.
+            RegionRequest::Put(put) if dead_letters.is_some() && put.rows.rows.is_empty() => 0,
+            _ => self
+                .inner
+                .handle_request(region_id, request)
+                .await
+                .map_err(BoxedError::new)?,
+        };
+        let mut result = RegionHandleResult::new(affected_rows);
+        // Reports out-of-order rows to write them to the dead-letter table. Keys are
+        // distinct by region as the region server merges extensions of regions.
+        if let Some(dead_letters) = dead_letters {
+            let _ = result.extension.insert(
+                format!(
+                    "{OUT_OF_ORDER_ROWS_EXTENSION_KEY_PREFIX}{}",
+                    region_id.as_u64()
+                ),
+                dead_letters.encode_to_vec(),
+            );
+        }
+        // Reports the flushed entry id so callers know which WAL entries the
+        // flushed data covers.
+        if is_flush {
+            if let Some(region) = self.inner.workers.get_region(region_id) {
+                let flushed_entry_id = region.version().flushed_entry_id;
+                let _ = result.extension.insert(
+                    FLUSHED_ENTRY_ID_EXTENSION_KEY.to_string(),
+                    flushed_entry_id.to_le_bytes().to_vec(),
+                );
+            }
+        }
+        // Tells callers the WAL is replayed instead of an empty put.
+        if is_replay_wal {
+            let _ = result
+                .extension
+                .insert(WAL_REPLAYED_EXTENSION_KEY.to_string(), Vec::new());
+        }
+        Ok(result)
     }
 
     /// Handle substrait query and return a stream of record batches
@@ -396,9 +614,11 @@ impl MitoEngine {
         config.sanitize(data_home)?;
 
         let config = Arc::new(config);
+        let wal = Arc::new(Wal::new(log_store.clone()));
         Ok(MitoEngine {
             inner: Arc::new(EngineInner {
                 workers: WorkerGroup::start_for_test(
+                    data_home,
                     config.clone(),
                     log_store,
                     object_store_manager,
@@ -407,6 +627,7 @@ impl MitoEngine {
                     time_provider,
                 )
                 .await?,
+                wal,
                 config,
             }),
         })
@@ -434,6 +655,7 @@ mod tests {
             compaction_time_window: None,
             flushed_entry_id: None,
             flushed_sequence: None,
+            retained_flushes: None,
         };
         assert!(is_valid_region_edit(&edit));
 
@@ -444,6 +666,7 @@ mod tests {
             compaction_time_window: None,
             flushed_entry_id: None,
             flushed_sequence: None,
+            retained_flushes: None,
         };
         assert!(!is_valid_region_edit(&edit));
 
@@ -454,6 +677,7 @@ mod tests {
             compaction_time_window: None,
             flushed_entry_id: None,
             flushed_sequence: None,
+            retained_flushes: None,
         };
         assert!(!is_valid_region_edit(&edit));
 
@@ -464,6 +688,7 @@ mod tests {
             compaction_time_window: Some(Duration::from_secs(1)),
             flushed_entry_id: None,
             flushed_sequence: None,
+            retained_flushes: None,
         };
         assert!(!is_valid_region_edit(&edit));
         let edit = RegionEdit {
@@ -472,6 +697,7 @@ mod tests {
             compaction_time_window: None,
             flushed_entry_id: Some(1),
             flushed_sequence: None,
+            retained_flushes: None,
         };
         assert!(!is_valid_region_edit(&edit));
         let edit = RegionEdit {
@@ -480,6 +706,7 @@ mod tests {
             compaction_time_window: None,
             flushed_entry_id: None,
             flushed_sequence: Some(1),
+            retained_flushes: None,
         };
         assert!(!is_valid_region_edit(&edit));
     }
@@ -24,7 +24,8 @@ use datatypes::schema::ColumnSchema;
 use store_api::metadata::ColumnMetadata;
 use store_api::region_engine::RegionEngine;
 use store_api::region_request::{
-    AddColumn, AddColumnLocation, AlterKind, RegionAlterRequest, RegionOpenRequest, RegionRequest,
+    AddColumn, AddColumnLocation, AlterKind, ChangeColumnType, RegionAlterRequest,
+    RegionOpenRequest, RegionRequest,
 };
 use store_api::storage::{RegionId, ScanRequest};
 
@@ -300,3 +301,99 @@ async fn test_alter_region_retry() {
     assert_eq!(1, version_data.version.flushed_entry_id);
     assert_eq!(2, version_data.version.flushed_sequence);
 }
+
+#[tokio::test]
+async fn test_alter_change_column_type() {
+    common_telemetry::init_default_ut_logging();
+
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let mut request = CreateRequestBuilder::new().build();
+    // Uses int32 for field_0.
+    for column in &mut request.column_metadatas {
+        if column.column_schema.name == "field_0" {
+            column.column_schema.data_type = ConcreteDataType::int32_datatype();
+        }
+    }
+
+    let column_schemas = rows_schema(&request);
+    let region_dir = request.region_dir.clone();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: (0..3)
+            .map(|i| Row {
+                values: vec![
+                    api::v1::Value {
+                        value_data: Some(ValueData::StringValue(i.to_string())),
+                    },
+                    api::v1::Value {
+                        value_data: Some(ValueData::I32Value(i)),
+                    },
+                    api::v1::Value {
+                        value_data: Some(ValueData::TimestampMillisecondValue(i as i64 * 1000)),
+                    },
+                ],
+            })
+            .collect(),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let change_type = || RegionAlterRequest {
+        schema_version: 0,
+        kind: AlterKind::ChangeColumnTypes {
+            columns: vec![ChangeColumnType {
+                column_name: "field_0".to_string(),
+                target_type: ConcreteDataType::int64_datatype(),
+            }],
+        },
+    };
+    engine
+        .handle_request(region_id, RegionRequest::Alter(change_type()))
+        .await
+        .unwrap();
+
+    let region = engine.get_region(region_id).unwrap();
+    assert_eq!(
+        ConcreteDataType::int64_datatype(),
+        region
+            .metadata()
+            .column_by_name("field_0")
+            .unwrap()
+            .column_schema
+            .data_type
+    );
+
+    // The SST is written in int32, values are casted on read.
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0       | 1970-01-01T00:00:00 |
+| 1     | 1       | 1970-01-01T00:00:01 |
+| 2     | 2       | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    scan_check_after_alter(&engine, region_id, expected).await;
+
+    // Reopen region.
+    let engine = env.reopen_engine(engine, MitoConfig::default()).await;
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Open(RegionOpenRequest {
+                engine: String::new(),
+                region_dir,
+                options: HashMap::default(),
+                skip_wal_replay: false,
+            }),
+        )
+        .await
+        .unwrap();
+    scan_check_after_alter(&engine, region_id, expected).await;
+}
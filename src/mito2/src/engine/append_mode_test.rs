@@ -87,6 +87,58 @@ async fn test_append_mode_write_query() {
     assert_eq!(expected, batches.pretty_print().unwrap());
 }
 
+#[tokio::test]
+async fn test_append_mode_partition_tree() {
+    common_telemetry::init_default_ut_logging();
+
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("append_mode", "true")
+        .insert_option("memtable.type", "partition_tree")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // rows 0, 1, 1
+    let mut rows = build_rows(0, 2);
+    rows.append(&mut build_rows(1, 2));
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows,
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    // rows 1, 2
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(1, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    // Duplicate rows in both the memtable and the SST are kept.
+    let request = ScanRequest::default();
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+}
+
 #[tokio::test]
 async fn test_append_mode_compaction() {
     let mut env = TestEnv::new();
@@ -137,7 +189,10 @@ async fn test_append_mode_compaction() {
     flush_region(&engine, region_id, None).await;
 
     let output = engine
-        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
         .await
         .unwrap();
     assert_eq!(output.affected_rows, 0);
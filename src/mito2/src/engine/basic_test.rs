@@ -15,15 +15,21 @@
 //! Basic tests for mito engine.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use api::v1::value::ValueData;
 use api::v1::Rows;
 use common_base::readable_size::ReadableSize;
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
-use common_recordbatch::RecordBatches;
+use common_query::logical_plan::Expr;
+use common_recordbatch::{OrderOption, RecordBatchStream, RecordBatches};
+use datafusion_expr::{col, lit};
+use datatypes::arrow::compute::SortOptions;
 use datatypes::prelude::ConcreteDataType;
-use store_api::region_request::{RegionOpenRequest, RegionPutRequest};
+use store_api::region_request::{
+    InsertMode, RegionCompactRequest, RegionOpenRequest, RegionPutRequest,
+};
 use store_api::storage::RegionId;
 
 use super::*;
@@ -177,6 +183,156 @@ async fn test_write_query_region() {
     assert_eq!(expected, batches.pretty_print().unwrap());
 }
 
+#[tokio::test]
+async fn test_scan_with_sequence() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+
+    let column_schemas = rows_schema(&request);
+    let delete_schema = delete_rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("2", 2, 3, 20),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let snapshot = engine.pin_snapshot(region_id).await.unwrap();
+
+    // Writes after the snapshot add, overwrite and delete rows.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(3, 5),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("1", 1, 2, 10),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("2", 2, 3, 30),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let rows = Rows {
+        schema: delete_schema,
+        rows: build_delete_rows_for_key("0", 0, 1),
+    };
+    delete_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    // The region doesn't compact while it has snapshots.
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RegionBusy, err.status_code());
+
+    let request = ScanRequest {
+        sequence: Some(snapshot.sequence()),
+        ..Default::default()
+    };
+    let stream = engine
+        .handle_query(region_id, request.clone())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 20.0    | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 1     | 10.0    | 1970-01-01T00:00:01 |
+| 2     | 30.0    | 1970-01-01T00:00:02 |
+| 3     | 3.0     | 1970-01-01T00:00:03 |
+| 4     | 4.0     | 1970-01-01T00:00:04 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // Rows of the snapshot are not retained once it is dropped.
+    drop(snapshot);
+    let err = engine.handle_query(region_id, request).await.err().unwrap();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+}
+
+#[tokio::test]
+async fn test_expired_snapshot() {
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            max_snapshot_pin_duration: Duration::ZERO,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let snapshot = engine.pin_snapshot(region_id).await.unwrap();
+    assert!(snapshot.is_expired());
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    // An expired snapshot neither blocks compaction nor retains its rows.
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+    let request = ScanRequest {
+        sequence: Some(snapshot.sequence()),
+        ..Default::default()
+    };
+    let err = engine.handle_query(region_id, request).await.err().unwrap();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+}
+
 #[tokio::test]
 async fn test_different_order() {
     let mut env = TestEnv::new();
@@ -500,7 +656,13 @@ async fn test_absent_and_invalid_columns() {
         rows,
     };
     let err = engine
-        .handle_request(region_id, RegionRequest::Put(RegionPutRequest { rows }))
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows,
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
         .await
         .unwrap_err();
     assert_eq!(StatusCode::InvalidArguments, err.status_code());
@@ -557,6 +719,33 @@ async fn test_region_usage() {
     assert!(region_stat.disk_usage() >= 4028);
 }
 
+#[tokio::test]
+async fn test_list_sst_upgrade_progress() {
+    let mut env = TestEnv::with_prefix("sst_upgrade_progress");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("a", 0, 10, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    // New SSTs are in the current format version.
+    let progress = engine.list_sst_upgrade_progress();
+    assert_eq!(1, progress.len());
+    assert_eq!(region_id, progress[0].region_id);
+    assert_eq!(1, progress[0].num_files);
+    assert_eq!(0, progress[0].num_outdated_files);
+}
+
 #[tokio::test]
 async fn test_engine_with_write_cache() {
     common_telemetry::init_default_ut_logging();
@@ -598,3 +787,102 @@ async fn test_engine_with_write_cache() {
 +-------+---------+---------------------+";
     assert_eq!(expected, batches.pretty_print().unwrap());
 }
+
+#[tokio::test]
+async fn test_engine_with_async_upload_write_cache() {
+    common_telemetry::init_default_ut_logging();
+
+    let mut env = TestEnv::new();
+    let path = env.data_home().to_str().unwrap().to_string();
+    let mut mito_config = MitoConfig::default().enable_write_cache(path, ReadableSize::mb(512));
+    mito_config.experimental_write_cache_async_upload = true;
+    let engine = env.create_engine(mito_config).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 0, 3, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    flush_region(&engine, region_id, None).await;
+
+    // The flush finishes after the SST is uploaded and written to the manifest.
+    let region = engine.get_region(region_id).unwrap();
+    let manifest = region.manifest_manager.read().await.manifest();
+    assert_eq!(1, manifest.files.len());
+    assert_eq!(1, manifest.flushed_entry_id);
+    for file in manifest.files.values() {
+        assert!(region.access_layer.is_exist(file).await.unwrap());
+    }
+    assert!(region.version().memtables.is_empty());
+
+    let request = ScanRequest::default();
+    let scanner = engine.scanner(region_id, request).unwrap();
+
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 0.0     | 1970-01-01T00:00:00 |
+| a     | 1.0     | 1970-01-01T00:00:01 |
+| a     | 2.0     | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_seq_scan_output_ordering() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let ts_ordering = vec![OrderOption {
+        name: "ts".to_string(),
+        options: SortOptions {
+            descending: false,
+            nulls_first: false,
+        },
+    }];
+    let tag_filter = || vec![Expr::from(col("tag_0").eq(lit("a")))];
+
+    // Rows of different series are not sorted by time index.
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    let stream = scanner.scan().await.unwrap();
+    assert_eq!(None, stream.output_ordering());
+
+    // Rows of one series are sorted by time index.
+    let request = ScanRequest {
+        filters: tag_filter(),
+        ..Default::default()
+    };
+    let scanner = engine.scanner(region_id, request).unwrap();
+    let stream = scanner.scan().await.unwrap();
+    assert_eq!(Some(ts_ordering.as_slice()), stream.output_ordering());
+
+    // The time index is not projected.
+    let request = ScanRequest {
+        projection: Some(vec![0, 1]),
+        filters: tag_filter(),
+        ..Default::default()
+    };
+    let scanner = engine.scanner(region_id, request).unwrap();
+    let stream = scanner.scan().await.unwrap();
+    assert_eq!(None, stream.output_ordering());
+}
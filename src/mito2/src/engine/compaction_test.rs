@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -21,17 +22,21 @@ use datatypes::prelude::ScalarVector;
 use datatypes::vectors::TimestampMillisecondVector;
 use store_api::region_engine::RegionEngine;
 use store_api::region_request::{
-    RegionCompactRequest, RegionDeleteRequest, RegionFlushRequest, RegionRequest,
+    AlterKind, CompactOptions, RegionAlterRequest, RegionCompactRequest, RegionDeleteRequest,
+    RegionFlushRequest, RegionRequest,
 };
 use store_api::storage::{RegionId, ScanRequest};
 use tokio::sync::Notify;
 
 use crate::config::MitoConfig;
+use crate::engine::flush_test::MockTimeProvider;
 use crate::engine::listener::CompactionListener;
 use crate::engine::MitoEngine;
 use crate::test_util::{
-    build_rows_for_key, column_metadata_to_column_schema, put_rows, CreateRequestBuilder, TestEnv,
+    build_rows_for_key, column_metadata_to_column_schema, put_rows, reopen_region,
+    CreateRequestBuilder, TestEnv,
 };
+use crate::worker::MAX_INITIAL_CHECK_DELAY_SECS;
 
 async fn put_and_flush(
     engine: &MitoEngine,
@@ -131,7 +136,10 @@ async fn test_compaction_region() {
     put_and_flush(&engine, region_id, &column_schemas, 15..25).await;
 
     let result = engine
-        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
         .await
         .unwrap();
     assert_eq!(result.affected_rows, 0);
@@ -149,6 +157,154 @@ async fn test_compaction_region() {
     assert_eq!((0..25).map(|v| v * 1000).collect::<Vec<_>>(), vec);
 }
 
+#[tokio::test]
+async fn test_compaction_region_strict_window() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Flush 2 SSTs in the active window.
+    put_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+    put_and_flush(&engine, region_id, &column_schemas, 10..20).await;
+
+    // The regular compaction keeps files in the active window.
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(2, scanner.num_files());
+
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest {
+                options: CompactOptions::StrictWindow {
+                    window_seconds: Some(3600),
+                },
+            }),
+        )
+        .await
+        .unwrap();
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(
+        1,
+        scanner.num_files(),
+        "unexpected files: {:?}",
+        scanner.file_ids()
+    );
+    let stream = scanner.scan().await.unwrap();
+
+    let vec = collect_stream_ts(stream).await;
+    assert_eq!((0..20).map(|v| v * 1000).collect::<Vec<_>>(), vec);
+}
+
+#[tokio::test]
+async fn test_compaction_region_alter_strategy() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Flush 3 SSTs in the active window.
+    put_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+    put_and_flush(&engine, region_id, &column_schemas, 10..20).await;
+    put_and_flush(&engine, region_id, &column_schemas, 20..30).await;
+
+    // TWCS keeps up to 4 files in the active window.
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(3, scanner.num_files());
+
+    // Only compaction options can be altered.
+    let alter_options = |options: &[(&str, &str)]| {
+        RegionRequest::Alter(RegionAlterRequest {
+            schema_version: 0,
+            kind: AlterKind::ChangeRegionOptions {
+                options: options
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            },
+        })
+    };
+    engine
+        .handle_request(region_id, alter_options(&[("ttl", "7d")]))
+        .await
+        .unwrap_err();
+
+    // Switches to the size-tiered strategy without reopening the region.
+    engine
+        .handle_request(
+            region_id,
+            alter_options(&[
+                ("compaction.type", "size_tiered"),
+                ("compaction.size_tiered.min_threshold", "2"),
+            ]),
+        )
+        .await
+        .unwrap();
+    // Altering options doesn't change the schema.
+    assert_eq!(
+        0,
+        engine.get_metadata(region_id).await.unwrap().schema_version
+    );
+    // The altered options are kept after reopening.
+    reopen_region(&engine, region_id, region_dir, true, HashMap::new()).await;
+
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(
+        1,
+        scanner.num_files(),
+        "unexpected files: {:?}",
+        scanner.file_ids()
+    );
+    let stream = scanner.scan().await.unwrap();
+
+    let vec = collect_stream_ts(stream).await;
+    assert_eq!((0..30).map(|v| v * 1000).collect::<Vec<_>>(), vec);
+}
+
 // For issue https://github.com/GreptimeTeam/greptimedb/issues/3633
 #[tokio::test]
 async fn test_readonly_during_compaction() {
@@ -218,3 +374,49 @@ async fn test_readonly_during_compaction() {
     let vec = collect_stream_ts(stream).await;
     assert_eq!((0..20).map(|v| v * 1000).collect::<Vec<_>>(), vec);
 }
+
+#[tokio::test]
+async fn test_purge_expired_files_periodically() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let listener = Arc::new(CompactionListener::default());
+    let now = common_time::util::current_time_millis();
+    let time_provider = Arc::new(MockTimeProvider::new(now));
+    let engine = env
+        .create_engine_with_time(
+            MitoConfig::default(),
+            None,
+            Some(listener.clone()),
+            time_provider.clone(),
+        )
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("ttl", "1h")
+        .build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Rows in 1970 are wholly past the TTL.
+    put_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(1, scanner.num_files());
+
+    // Triggers the periodical tasks of the worker.
+    time_provider.set_elapsed((MAX_INITIAL_CHECK_DELAY_SECS as i64 + 1) * 1000);
+    listener.wait_handle_finished().await;
+    listener.wake();
+
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(0, scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    assert!(collect_stream_ts(stream).await.is_empty());
+}
@@ -19,10 +19,12 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use api::v1::Rows;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
 use common_recordbatch::RecordBatches;
 use common_time::util::current_time_millis;
 use store_api::region_engine::RegionEngine;
-use store_api::region_request::RegionRequest;
+use store_api::region_request::{InsertMode, RegionPutRequest, RegionRequest};
 use store_api::storage::{RegionId, ScanRequest};
 
 use crate::config::MitoConfig;
@@ -197,6 +199,61 @@ async fn test_write_stall() {
     assert_eq!(expected, batches.pretty_print().unwrap());
 }
 
+#[tokio::test]
+async fn test_reject_write_exceeding_disk_quota() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("disk_quota", "1B")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    // The quota is exceeded once the region has SSTs.
+    flush_region(&engine, region_id, None).await;
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows,
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RuntimeResourcesExhausted, err.status_code());
+
+    // We can still read the region.
+    let request = ScanRequest::default();
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
 #[tokio::test]
 async fn test_flush_empty() {
     let mut env = TestEnv::new();
@@ -279,7 +336,7 @@ async fn test_flush_reopen_region() {
 }
 
 #[derive(Debug)]
-struct MockTimeProvider {
+pub(crate) struct MockTimeProvider {
     now: AtomicI64,
     elapsed: AtomicI64,
 }
@@ -299,18 +356,18 @@ impl TimeProvider for MockTimeProvider {
 }
 
 impl MockTimeProvider {
-    fn new(now: i64) -> Self {
+    pub(crate) fn new(now: i64) -> Self {
         Self {
             now: AtomicI64::new(now),
             elapsed: AtomicI64::new(0),
         }
     }
 
-    fn set_now(&self, now: i64) {
+    pub(crate) fn set_now(&self, now: i64) {
         self.now.store(now, Ordering::Relaxed);
     }
 
-    fn set_elapsed(&self, elapsed: i64) {
+    pub(crate) fn set_elapsed(&self, elapsed: i64) {
         self.elapsed.store(elapsed, Ordering::Relaxed);
     }
 }
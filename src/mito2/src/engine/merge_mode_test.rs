@@ -0,0 +1,125 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for merge mode.
+
+use api::v1::value::ValueData;
+use api::v1::{Row, Rows};
+use common_recordbatch::RecordBatches;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionCompactRequest, RegionRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::test_util::{flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv};
+
+/// Builds rows of `key` with two nullable fields.
+fn build_rows_with_fields(key: &str, rows: &[(i64, Option<f64>, Option<f64>)]) -> Vec<Row> {
+    let f64_value = |value: Option<f64>| api::v1::Value {
+        value_data: value.map(ValueData::F64Value),
+    };
+
+    rows.iter()
+        .map(|(ts, field_0, field_1)| Row {
+            values: vec![
+                api::v1::Value {
+                    value_data: Some(ValueData::StringValue(key.to_string())),
+                },
+                f64_value(*field_0),
+                f64_value(*field_1),
+                api::v1::Value {
+                    value_data: Some(ValueData::TimestampMillisecondValue(*ts * 1000)),
+                },
+            ],
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_merge_mode_last_non_null() {
+    common_telemetry::init_default_ut_logging();
+
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .field_num(2)
+        .insert_option("merge_mode", "last_non_null")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_with_fields(
+            "a",
+            &[
+                (1, Some(1.0), None),
+                (2, Some(2.0), Some(2.0)),
+                (3, None, None),
+            ],
+        ),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    // Partial updates in the memtable.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_with_fields("a", &[(1, None, Some(11.0)), (2, Some(22.0), None)]),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_with_fields("a", &[(1, None, None), (3, None, Some(33.0))]),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let expected = "\
++-------+---------+---------+---------------------+
+| tag_0 | field_0 | field_1 | ts                  |
++-------+---------+---------+---------------------+
+| a     | 1.0     | 11.0    | 1970-01-01T00:00:01 |
+| a     | 22.0    | 2.0     | 1970-01-01T00:00:02 |
+| a     |         | 33.0    | 1970-01-01T00:00:03 |
++-------+---------+---------+---------------------+";
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // Compaction keeps the merged values.
+    flush_region(&engine, region_id, None).await;
+    let output = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(output.affected_rows, 0);
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
@@ -0,0 +1,240 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for handling out-of-order rows.
+
+use std::collections::HashMap;
+
+use api::v1::{RowInsertRequest, Rows};
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use prost::Message;
+use store_api::mito_engine_options::OUT_OF_ORDER_ROWS_EXTENSION_KEY_PREFIX;
+use store_api::region_request::{InsertMode, RegionPutRequest, RegionRequest};
+use store_api::storage::RegionId;
+
+use crate::config::MitoConfig;
+use crate::test_util::{
+    build_rows, build_rows_for_key, flush_region, put_rows, reopen_region, rows_schema,
+    CreateRequestBuilder, TestEnv,
+};
+
+#[tokio::test]
+async fn test_reject_out_of_order_rows() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let options = HashMap::from([
+        ("out_of_order.policy".to_string(), "reject".to_string()),
+        ("out_of_order.window".to_string(), "10s".to_string()),
+    ]);
+    let mut builder = CreateRequestBuilder::new();
+    for (key, value) in &options {
+        builder = builder.insert_option(key, value);
+    }
+    let request = builder.build();
+    let region_dir = request.region_dir.clone();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // The watermark is 102s.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(100, 103),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(80, 81),
+    };
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows,
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+    // Rows within the window are accepted.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(92, 93),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    // The watermark comes from SST files after reopening the region.
+    flush_region(&engine, region_id, None).await;
+    reopen_region(&engine, region_id, region_dir, true, options).await;
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(91, 92),
+    };
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows,
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+}
+
+#[tokio::test]
+async fn test_accept_out_of_order_rows_by_default() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("out_of_order.window", "10s")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(100, 103),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(0, 1),
+    };
+    put_rows(&engine, region_id, rows).await;
+}
+
+#[tokio::test]
+async fn test_out_of_order_watermark_per_series() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("out_of_order.policy", "reject")
+        .insert_option("out_of_order.window", "10s")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 100, 101, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("b", 150, 151, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    // Series "a" is checked by its own watermark, not the one of series "b".
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 95, 96, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("b", 130, 131, 0),
+    };
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows,
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+}
+
+#[tokio::test]
+async fn test_dead_letter_out_of_order_rows() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("out_of_order.policy", "dead_letter")
+        .insert_option("out_of_order.window", "10s")
+        .insert_option("out_of_order.dead_letter_table", "late_rows")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 100, 101, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    // Rows at 80s and 81s are out of order.
+    let mut rows = build_rows_for_key("a", 80, 82, 0);
+    rows.extend(build_rows_for_key("a", 95, 96, 0));
+    let result = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows: Rows {
+                    schema: column_schemas,
+                    rows,
+                },
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(1, result.affected_rows);
+
+    let key = format!(
+        "{OUT_OF_ORDER_ROWS_EXTENSION_KEY_PREFIX}{}",
+        region_id.as_u64()
+    );
+    let request = RowInsertRequest::decode(result.extension[&key].as_slice()).unwrap();
+    assert_eq!("late_rows", request.table_name);
+    assert_eq!(
+        build_rows_for_key("a", 80, 82, 0),
+        request.rows.unwrap().rows
+    );
+}
@@ -0,0 +1,149 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for write rate limits.
+
+use std::time::{Duration, Instant};
+
+use api::v1::Rows;
+use common_base::readable_size::ReadableSize;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use store_api::region_request::{InsertMode, RegionPutRequest, RegionRequest};
+use store_api::storage::RegionId;
+
+use crate::config::{MitoConfig, WriteRateLimitPolicy};
+use crate::test_util::{build_rows, put_rows, rows_schema, CreateRequestBuilder, TestEnv};
+
+#[tokio::test]
+async fn test_reject_write_exceeding_table_rate_limit() {
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            write_rate_limit_policy: WriteRateLimitPolicy::Reject,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("write_rate_limit.rows_per_second", "3")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows,
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RateLimited, err.status_code());
+}
+
+#[tokio::test]
+async fn test_block_write_exceeding_table_rate_limit() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("write_rate_limit.rows_per_second", "100")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 150),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    // The request waits about 500ms until the table has quotas.
+    let start = Instant::now();
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(150, 160),
+    };
+    put_rows(&engine, region_id, rows).await;
+    assert!(start.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_reject_write_exceeding_throttle_buffer() {
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            write_rate_limit_buffer_size: ReadableSize(1),
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("write_rate_limit.rows_per_second", "3")
+        .build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    // The worker can't hold the throttled request in the buffer.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows,
+                insert_mode: InsertMode::Overwrite,
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RateLimited, err.status_code());
+    assert!(common_error::parse_retry_after(&err.output_msg()).is_some());
+}
@@ -0,0 +1,239 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use store_api::mito_engine_options::{FLUSHED_ENTRY_ID_EXTENSION_KEY, WAL_REPLAYED_EXTENSION_KEY};
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{
+    RegionFlushRequest, RegionReplayWalRequest, RegionRequest, WalReplaySource,
+};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::test_util::{
+    build_rows, delete_rows, delete_rows_schema, flush_region, put_rows, reopen_region,
+    rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+#[tokio::test]
+async fn test_replay_wal_after_flushed_entry() {
+    let mut env = TestEnv::with_prefix("replay-wal");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let source_region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .region_dir("source")
+        .insert_option("wal.retain_period", "1h")
+        .build();
+    let column_schemas = rows_schema(&request);
+    let delete_schema = delete_rows_schema(&request);
+    engine
+        .handle_request(source_region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        source_region_id,
+        Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(0, 3),
+        },
+    )
+    .await;
+    let result = engine
+        .handle_request(
+            source_region_id,
+            RegionRequest::Flush(RegionFlushRequest::default()),
+        )
+        .await
+        .unwrap();
+    let flushed_entry_id = u64::from_le_bytes(
+        result.extension[FLUSHED_ENTRY_ID_EXTENSION_KEY]
+            .as_slice()
+            .try_into()
+            .unwrap(),
+    );
+    assert_eq!(1, flushed_entry_id);
+    // Entries after the flushed entry are replayed in order.
+    put_rows(
+        &engine,
+        source_region_id,
+        Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(3, 5),
+        },
+    )
+    .await;
+    delete_rows(
+        &engine,
+        source_region_id,
+        Rows {
+            schema: delete_schema,
+            rows: build_rows(2, 4)
+                .into_iter()
+                .map(|mut row| {
+                    row.values.remove(1);
+                    row
+                })
+                .collect(),
+        },
+    )
+    .await;
+
+    let region_id = RegionId::new(2, 1);
+    let request = CreateRequestBuilder::new().region_dir("restored").build();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(0, 3),
+        },
+    )
+    .await;
+    let mut source = WalReplaySource {
+        table_id: 1,
+        region_wal_options: HashMap::new(),
+        region_entry_ids: HashMap::from([(1, flushed_entry_id)]),
+        until: None,
+    };
+    let result = engine
+        .handle_request(
+            region_id,
+            RegionRequest::ReplayWal(RegionReplayWalRequest {
+                source: source.clone(),
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(4, result.affected_rows);
+    assert!(result.extension.contains_key(WAL_REPLAYED_EXTENSION_KEY));
+
+    let stream = engine
+        .scanner(region_id, ScanRequest::default())
+        .unwrap()
+        .scan()
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 4     | 4.0     | 1970-01-01T00:00:04 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // Rows after `until` are skipped, the put at 4s here.
+    let region_id = RegionId::new(3, 1);
+    let request = CreateRequestBuilder::new()
+        .region_dir("restored_until")
+        .build();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(0, 3),
+        },
+    )
+    .await;
+    source.until = Some(3000);
+    let result = engine
+        .handle_request(
+            region_id,
+            RegionRequest::ReplayWal(RegionReplayWalRequest { source }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(3, result.affected_rows);
+
+    let stream = engine
+        .scanner(region_id, ScanRequest::default())
+        .unwrap()
+        .scan()
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_retained_flushes_after_reopen() {
+    let mut env = TestEnv::with_prefix("retained-flushes");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("wal.retain_period", "1h")
+        .build();
+    let region_dir = request.region_dir.clone();
+    let options = request.options.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    for i in 0..2 {
+        put_rows(
+            &engine,
+            region_id,
+            Rows {
+                schema: column_schemas.clone(),
+                rows: build_rows(i, i + 1),
+            },
+        )
+        .await;
+        flush_region(&engine, region_id, None).await;
+        // Flushes before reopening are still retained.
+        reopen_region(
+            &engine,
+            region_id,
+            region_dir.clone(),
+            true,
+            options.clone(),
+        )
+        .await;
+
+        let region = engine.get_region(region_id).unwrap();
+        let manifest = region.manifest_manager.read().await.manifest();
+        let entry_ids = manifest
+            .retained_flushes
+            .iter()
+            .map(|(_, entry_id)| *entry_id)
+            .collect::<Vec<_>>();
+        assert_eq!((1..=i as u64 + 1).collect::<Vec<_>>(), entry_ids);
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for reading the WAL to replicate.
+
+use api::v1::Rows;
+use futures::TryStreamExt;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::RegionRequest;
+use store_api::storage::RegionId;
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_rows, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+/// Returns the ids and the number of rows of WAL entries of the region.
+async fn scan_wal(engine: &MitoEngine, region_id: RegionId) -> Vec<(u64, usize)> {
+    let entries = engine
+        .scan_wal(region_id, 0)
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    entries
+        .into_iter()
+        .map(|(entry_id, entry)| {
+            let num_rows = entry
+                .mutations
+                .iter()
+                .filter_map(|mutation| mutation.rows.as_ref())
+                .map(|rows| rows.rows.len())
+                .sum();
+            (entry_id, num_rows)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_retain_wal_for_replication() {
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            retain_wal_for_replication: true,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(0, 3),
+        },
+    )
+    .await;
+    // Nothing is replicated, the flush keeps all entries.
+    flush_region(&engine, region_id, None).await;
+    assert_eq!(vec![(1, 3)], scan_wal(&engine, region_id).await);
+
+    engine.set_replicated_entry_id(region_id, 1).unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(3, 5),
+        },
+    )
+    .await;
+    // Only entries that are replicated are deleted.
+    flush_region(&engine, region_id, None).await;
+    assert_eq!(vec![(2, 2)], scan_wal(&engine, region_id).await);
+}
@@ -16,7 +16,7 @@ use api::v1::Rows;
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
 use store_api::region_engine::{RegionEngine, SetReadonlyResponse};
-use store_api::region_request::{RegionPutRequest, RegionRequest};
+use store_api::region_request::{InsertMode, RegionPutRequest, RegionRequest};
 use store_api::storage::RegionId;
 
 use crate::config::MitoConfig;
@@ -61,7 +61,10 @@ async fn test_set_readonly_gracefully() {
     let error = engine
         .handle_request(
             region_id,
-            RegionRequest::Put(RegionPutRequest { rows: rows.clone() }),
+            RegionRequest::Put(RegionPutRequest {
+                rows: rows.clone(),
+                insert_mode: InsertMode::Overwrite,
+            }),
         )
         .await
         .unwrap_err();
@@ -14,19 +14,22 @@
 
 use std::any::Any;
 use std::sync::Arc;
+use std::time::Duration;
 
+use common_base::readable_size::ReadableSize;
 use common_datasource::compression::CompressionType;
 use common_error::ext::{BoxedError, ErrorExt};
 use common_error::status_code::StatusCode;
 use common_macro::stack_trace_debug;
 use common_runtime::JoinError;
+use common_time::Timestamp;
 use datatypes::arrow::error::ArrowError;
 use datatypes::prelude::ConcreteDataType;
 use object_store::ErrorKind;
 use prost::{DecodeError, EncodeError};
 use snafu::{Location, Snafu};
 use store_api::manifest::ManifestVersion;
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, SequenceNumber};
 
 use crate::cache::file_cache::FileType;
 use crate::sst::file::FileId;
@@ -355,6 +358,23 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Region {} doesn't compact while snapshots are pinned", region_id))]
+    SnapshotPinned {
+        region_id: RegionId,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Rows of region {} at sequence {} are not retained, pin a snapshot before scanning",
+        region_id,
+        sequence
+    ))]
+    SnapshotNotRetained {
+        region_id: RegionId,
+        sequence: SequenceNumber,
+        location: Location,
+    },
+
     #[snafu(display(
         "Engine write buffer is full, rejecting write requests of region {}",
         region_id,
@@ -364,6 +384,53 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Available space of the disk is below {}, rejecting write requests of region {}",
+        min_free_space,
+        region_id,
+    ))]
+    DiskFull {
+        region_id: RegionId,
+        min_free_space: ReadableSize,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Table {} exceeds its disk quota, rejecting write requests of region {}",
+        region_id.table_id(),
+        region_id,
+    ))]
+    DiskQuotaExceeded {
+        region_id: RegionId,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Region {} rejects out-of-order rows, timestamp {} is older than the watermark {} by more than {:?}",
+        region_id,
+        timestamp.to_iso8601_string(),
+        watermark.to_iso8601_string(),
+        window,
+    ))]
+    OutOfOrderWrite {
+        region_id: RegionId,
+        timestamp: Timestamp,
+        watermark: Timestamp,
+        window: Duration,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Write requests of region {} exceed the rate limit, {}",
+        region_id,
+        common_error::format_retry_after(*retry_after),
+    ))]
+    WriteRateLimited {
+        region_id: RegionId,
+        retry_after: Duration,
+        location: Location,
+    },
+
     #[snafu(display("Failed to compact region {}", region_id))]
     CompactRegion {
         region_id: RegionId,
@@ -544,12 +611,30 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Upload of file is cancelled as the file is removed, region_id: {}, file_id: {}",
+        region_id,
+        file_id,
+    ))]
+    UploadCancelled {
+        region_id: RegionId,
+        file_id: FileId,
+        location: Location,
+    },
+
     #[snafu(display("Failed to filter record batch"))]
     FilterRecordBatch {
         source: common_recordbatch::error::Error,
         location: Location,
     },
 
+    #[snafu(display("Failed to read existing rows of region {}", region_id))]
+    ReadExistingRows {
+        region_id: RegionId,
+        source: common_recordbatch::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("BiError, first: {first}, second: {second}"))]
     BiError {
         first: Box<Error>,
@@ -665,7 +750,12 @@ impl ErrorExt for Error {
             RegionDropped { .. } => StatusCode::Cancelled,
             RegionClosed { .. } => StatusCode::Cancelled,
             RegionTruncated { .. } => StatusCode::Cancelled,
+            SnapshotPinned { .. } => StatusCode::RegionBusy,
+            SnapshotNotRetained { .. } => StatusCode::InvalidArguments,
             RejectWrite { .. } => StatusCode::StorageUnavailable,
+            DiskFull { .. } | DiskQuotaExceeded { .. } => StatusCode::RuntimeResourcesExhausted,
+            OutOfOrderWrite { .. } => StatusCode::InvalidArguments,
+            WriteRateLimited { .. } => StatusCode::RateLimited,
             CompactRegion { source, .. } => source.status_code(),
             CompatReader { .. } => StatusCode::Unexpected,
             InvalidRegionRequest { source, .. } => source.status_code(),
@@ -686,7 +776,9 @@ impl ErrorExt for Error {
             InvalidConfig { .. } => StatusCode::InvalidArguments,
             StaleLogEntry { .. } => StatusCode::Unexpected,
             FilterRecordBatch { source, .. } => source.status_code(),
+            ReadExistingRows { source, .. } => source.status_code(),
             Upload { .. } => StatusCode::StorageUnavailable,
+            UploadCancelled { .. } => StatusCode::Cancelled,
             BiError { .. } => StatusCode::Internal,
             EncodeMemtable { .. } | ReadDataPart { .. } => StatusCode::Internal,
             ChecksumMismatch { .. } => StatusCode::Unexpected,
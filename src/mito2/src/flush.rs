@@ -26,23 +26,26 @@ use strum::IntoStaticStr;
 use tokio::sync::mpsc;
 
 use crate::access_layer::{AccessLayerRef, SstWriteRequest};
+use crate::cache::write_cache::UploadReceiver;
 use crate::cache::CacheManagerRef;
 use crate::config::MitoConfig;
 use crate::error::{
-    Error, FlushRegionSnafu, RegionClosedSnafu, RegionDroppedSnafu, RegionTruncatedSnafu, Result,
+    Error, FlushRegionSnafu, RecvSnafu, RegionClosedSnafu, RegionDroppedSnafu,
+    RegionTruncatedSnafu, Result,
 };
 use crate::metrics::{FLUSH_BYTES_TOTAL, FLUSH_ELAPSED, FLUSH_ERRORS_TOTAL, FLUSH_REQUESTS_TOTAL};
 use crate::read::Source;
 use crate::region::options::IndexOptions;
 use crate::region::version::{VersionControlData, VersionControlRef, VersionRef};
 use crate::request::{
-    BackgroundNotify, FlushFailed, FlushFinished, OptionOutputTx, OutputTx, SenderDdlRequest,
-    SenderWriteRequest, WorkerRequest,
+    BackgroundNotify, FlushFailed, FlushFinished, FlushStaged, OptionOutputTx, OutputTx,
+    SenderDdlRequest, SenderWriteRequest, WorkerRequest,
 };
 use crate::schedule::scheduler::{Job, SchedulerRef};
 use crate::sst::file::{FileId, FileMeta, IndexType};
 use crate::sst::file_purger::FilePurgerRef;
 use crate::sst::parquet::WriteOptions;
+use crate::sst::SST_FORMAT_VERSION;
 use crate::worker::WorkerListener;
 
 /// Global write buffer (memtable) manager.
@@ -251,15 +254,11 @@ impl RegionFlushTask {
         let timer = FLUSH_ELAPSED.with_label_values(&["total"]).start_timer();
         self.listener.on_flush_begin(self.region_id).await;
 
-        let worker_request = match self.flush_memtables(&version_data.version).await {
-            Ok(file_metas) => {
-                let memtables_to_remove = version_data
-                    .version
-                    .memtables
-                    .immutables()
-                    .iter()
-                    .map(|m| m.id())
-                    .collect();
+        let worker_request = match self.flush_and_wait_uploads(&version_data).await {
+            Ok((file_metas, staged)) => {
+                let immutables = version_data.version.memtables.immutables();
+                let memtables_to_remove = immutables.iter().map(|m| m.id()).collect();
+                let num_series = immutables.iter().map(|m| m.stats().num_series()).sum();
 
                 let flush_finished = FlushFinished {
                     region_id: self.region_id,
@@ -268,6 +267,8 @@ impl RegionFlushTask {
                     flushed_entry_id: version_data.last_entry_id,
                     flushed_sequence: version_data.committed_sequence,
                     memtables_to_remove,
+                    num_series,
+                    staged,
                     senders: std::mem::take(&mut self.senders),
                     file_purger: self.file_purger.clone(),
                     _timer: timer,
@@ -293,8 +294,55 @@ impl RegionFlushTask {
         self.send_worker_request(worker_request).await;
     }
 
+    /// Flushes memtables and waits until the SSTs are uploaded to the object store.
+    ///
+    /// If the SSTs are uploading in background, the worker adds them to the version
+    /// to release the memtables meanwhile. Then the flushed files are returned
+    /// empty with `true` as the worker already has them.
+    async fn flush_and_wait_uploads(
+        &self,
+        version_data: &VersionControlData,
+    ) -> Result<(Vec<FileMeta>, bool)> {
+        let (file_metas, uploads) = self.flush_memtables(&version_data.version).await?;
+        if uploads.is_empty() {
+            return Ok((file_metas, false));
+        }
+
+        let immutables = version_data.version.memtables.immutables();
+        let flush_staged = FlushStaged {
+            region_id: self.region_id,
+            file_metas,
+            flushed_entry_id: version_data.last_entry_id,
+            memtables_to_remove: immutables.iter().map(|m| m.id()).collect(),
+            num_series: immutables.iter().map(|m| m.stats().num_series()).sum(),
+            file_purger: self.file_purger.clone(),
+        };
+        self.send_worker_request(WorkerRequest::Background {
+            region_id: self.region_id,
+            notify: BackgroundNotify::FlushStaged(flush_staged),
+        })
+        .await;
+
+        // The worker writes the files to the manifest and obsoletes the WAL only
+        // after the files are durable in the object store.
+        let timer = FLUSH_ELAPSED
+            .with_label_values(&["wait_uploads"])
+            .start_timer();
+        for upload in uploads {
+            upload.await.context(RecvSnafu)??;
+        }
+        timer.stop_and_record();
+
+        Ok((Vec::new(), true))
+    }
+
     /// Flushes memtables to level 0 SSTs.
-    async fn flush_memtables(&self, version: &VersionRef) -> Result<Vec<FileMeta>> {
+    ///
+    /// Returns the flushed files and receivers of uploads running in background.
+    async fn flush_memtables(
+        &self,
+        version: &VersionRef,
+    ) -> Result<(Vec<FileMeta>, Vec<UploadReceiver>)> {
         let timer = FLUSH_ELAPSED
             .with_label_values(&["flush_memtables"])
             .start_timer();
@@ -309,6 +357,7 @@ impl RegionFlushTask {
 
         let memtables = version.memtables.immutables();
         let mut file_metas = Vec::with_capacity(memtables.len());
+        let mut uploads = Vec::new();
         let mut flushed_bytes = 0;
         for mem in memtables {
             if mem.is_empty() {
@@ -344,14 +393,15 @@ impl RegionFlushTask {
                 index_write_buffer_size,
                 index_options: self.index_options.clone(),
             };
-            let Some(sst_info) = self
+            let Some((sst_info, upload)) = self
                 .access_layer
-                .write_sst(write_request, &write_opts)
+                .stage_sst(write_request, &write_opts)
                 .await?
             else {
                 // No data written.
                 continue;
             };
+            uploads.extend(upload);
 
             flushed_bytes += sst_info.file_size;
             let file_meta = FileMeta {
@@ -365,6 +415,7 @@ impl RegionFlushTask {
                     .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
                     .unwrap_or_default(),
                 index_file_size: sst_info.index_file_size,
+                format_version: SST_FORMAT_VERSION,
             };
             file_metas.push(file_meta);
         }
@@ -382,7 +433,7 @@ impl RegionFlushTask {
             timer.stop_and_record(),
         );
 
-        Ok(file_metas)
+        Ok((file_metas, uploads))
     }
 
     /// Notify flush job status.
@@ -27,12 +27,14 @@ mod access_layer;
 mod cache;
 mod compaction;
 pub mod config;
+mod disk_usage;
 pub mod engine;
 pub mod error;
 pub mod flush;
 pub mod manifest;
 pub mod memtable;
 mod metrics;
+mod rate_limit;
 pub mod read;
 pub mod region;
 mod region_write_ctx;
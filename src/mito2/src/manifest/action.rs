@@ -39,6 +39,8 @@ pub enum RegionMetaAction {
     Remove(RegionRemove),
     /// Truncate the region.
     Truncate(RegionTruncate),
+    /// Change region's options that can be altered in place.
+    ChangeOptions(RegionChangeOptions),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -47,6 +49,12 @@ pub struct RegionChange {
     pub metadata: RegionMetadataRef,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegionChangeOptions {
+    /// The altered options, which replace all altered options before.
+    pub options: HashMap<String, String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RegionEdit {
     pub files_to_add: Vec<FileMeta>,
@@ -55,6 +63,10 @@ pub struct RegionEdit {
     pub compaction_time_window: Option<Duration>,
     pub flushed_entry_id: Option<EntryId>,
     pub flushed_sequence: Option<SequenceNumber>,
+    /// Flushes whose WAL entries are retained after the edit, as the flush time in
+    /// millis and the flushed entry id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retained_flushes: Option<Vec<(i64, EntryId)>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -90,6 +102,14 @@ pub struct RegionManifest {
     /// Inferred compaction time window.
     #[serde(with = "humantime_serde")]
     pub compaction_time_window: Option<Duration>,
+    /// Options altered after the region is created, which take precedence over
+    /// options to open the region.
+    #[serde(default)]
+    pub altered_options: HashMap<String, String>,
+    /// Flushes whose WAL entries are retained, as the flush time in millis and the
+    /// flushed entry id.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retained_flushes: Vec<(i64, EntryId)>,
 }
 
 #[derive(Debug, Default)]
@@ -101,6 +121,8 @@ pub struct RegionManifestBuilder {
     manifest_version: ManifestVersion,
     truncated_entry_id: Option<EntryId>,
     compaction_time_window: Option<Duration>,
+    altered_options: HashMap<String, String>,
+    retained_flushes: Vec<(i64, EntryId)>,
 }
 
 impl RegionManifestBuilder {
@@ -115,6 +137,8 @@ impl RegionManifestBuilder {
                 flushed_sequence: s.flushed_sequence,
                 truncated_entry_id: s.truncated_entry_id,
                 compaction_time_window: s.compaction_time_window,
+                altered_options: s.altered_options,
+                retained_flushes: s.retained_flushes,
             }
         } else {
             Default::default()
@@ -143,6 +167,18 @@ impl RegionManifestBuilder {
         if let Some(window) = edit.compaction_time_window {
             self.compaction_time_window = Some(window);
         }
+        if let Some(retained_flushes) = edit.retained_flushes {
+            self.retained_flushes = retained_flushes;
+        }
+    }
+
+    pub fn apply_change_options(
+        &mut self,
+        manifest_version: ManifestVersion,
+        change: RegionChangeOptions,
+    ) {
+        self.manifest_version = manifest_version;
+        self.altered_options = change.options;
     }
 
     pub fn apply_truncate(&mut self, manifest_version: ManifestVersion, truncate: RegionTruncate) {
@@ -151,6 +187,7 @@ impl RegionManifestBuilder {
         self.flushed_sequence = truncate.truncated_sequence;
         self.truncated_entry_id = Some(truncate.truncated_entry_id);
         self.files.clear();
+        self.retained_flushes.clear();
     }
 
     /// Check if the builder keeps a [RegionMetadata](store_api::metadata::RegionMetadata).
@@ -168,6 +205,8 @@ impl RegionManifestBuilder {
             manifest_version: self.manifest_version,
             truncated_entry_id: self.truncated_entry_id,
             compaction_time_window: self.compaction_time_window,
+            altered_options: self.altered_options,
+            retained_flushes: self.retained_flushes,
         })
     }
 }
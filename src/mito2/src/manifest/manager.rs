@@ -229,6 +229,9 @@ impl RegionManifestManager {
                     RegionMetaAction::Truncate(action) => {
                         manifest_builder.apply_truncate(manifest_version, action);
                     }
+                    RegionMetaAction::ChangeOptions(action) => {
+                        manifest_builder.apply_change_options(manifest_version, action);
+                    }
                 }
             }
         }
@@ -297,6 +300,9 @@ impl RegionManifestManager {
                 RegionMetaAction::Truncate(action) => {
                     manifest_builder.apply_truncate(version, action);
                 }
+                RegionMetaAction::ChangeOptions(action) => {
+                    manifest_builder.apply_change_options(version, action);
+                }
             }
         }
         let new_manifest = manifest_builder.try_build()?;
@@ -419,6 +425,9 @@ impl RegionManifestManager {
                     RegionMetaAction::Truncate(action) => {
                         manifest_builder.apply_truncate(version, action);
                     }
+                    RegionMetaAction::ChangeOptions(action) => {
+                        manifest_builder.apply_change_options(version, action);
+                    }
                 }
             }
             last_version = version;
@@ -639,6 +648,7 @@ mod test {
                         compaction_time_window: None,
                         flushed_entry_id: None,
                         flushed_sequence: None,
+                        retained_flushes: None,
                     },
                 )]))
                 .await
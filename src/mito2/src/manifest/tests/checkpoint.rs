@@ -26,6 +26,7 @@ use crate::manifest::action::{
 use crate::manifest::manager::RegionManifestManager;
 use crate::manifest::tests::utils::basic_region_metadata;
 use crate::sst::file::{FileId, FileMeta};
+use crate::sst::SST_FORMAT_VERSION;
 use crate::test_util::TestEnv;
 
 async fn build_manager(
@@ -61,6 +62,7 @@ fn nop_action() -> RegionMetaActionList {
         compaction_time_window: None,
         flushed_entry_id: None,
         flushed_sequence: None,
+        retained_flushes: None,
     })])
 }
 
@@ -212,6 +214,7 @@ async fn checkpoint_with_different_compression_types() {
             file_size: 1024000,
             available_indexes: Default::default(),
             index_file_size: 0,
+            format_version: SST_FORMAT_VERSION,
         };
         let action = RegionMetaActionList::new(vec![RegionMetaAction::Edit(RegionEdit {
             files_to_add: vec![file_meta],
@@ -219,6 +222,7 @@ async fn checkpoint_with_different_compression_types() {
             compaction_time_window: None,
             flushed_entry_id: None,
             flushed_sequence: None,
+            retained_flushes: None,
         })]);
         actions.push(action);
     }
@@ -66,6 +66,8 @@ pub struct MemtableStats {
     estimated_bytes: usize,
     /// The time range that this memtable contains.
     time_range: Option<(Timestamp, Timestamp)>,
+    /// Number of series (primary keys) in this memtable.
+    num_series: usize,
 }
 
 impl MemtableStats {
@@ -78,6 +80,11 @@ impl MemtableStats {
     pub fn time_range(&self) -> Option<(Timestamp, Timestamp)> {
         self.time_range
     }
+
+    /// Returns the number of series in the memtable.
+    pub fn num_series(&self) -> usize {
+        self.num_series
+    }
 }
 
 pub type BoxedBatchIterator = Box<dyn Iterator<Item = Result<Batch>> + Send>;
@@ -174,6 +174,7 @@ impl Memtable for PartitionTreeMemtable {
             return MemtableStats {
                 estimated_bytes,
                 time_range: None,
+                num_series: 0,
             };
         }
 
@@ -191,6 +192,7 @@ impl Memtable for PartitionTreeMemtable {
         MemtableStats {
             estimated_bytes,
             time_range: Some((min_timestamp, max_timestamp)),
+            num_series: self.tree.num_series(),
         }
     }
 
@@ -357,6 +359,7 @@ mod tests {
             )),
             stats.time_range()
         );
+        assert_eq!(1, stats.num_series());
     }
 
     #[test]
@@ -510,6 +513,7 @@ mod tests {
         let iter = memtable.iter(None, None).unwrap();
         let read = collect_iter_timestamps(iter);
         assert_eq!(expect, read);
+        assert_eq!(16, memtable.stats().num_series());
     }
 
     #[test]
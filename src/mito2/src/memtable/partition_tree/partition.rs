@@ -234,6 +234,12 @@ impl Partition {
         inner.num_rows > 0
     }
 
+    /// Returns the number of primary keys in the partition.
+    pub fn num_series(&self) -> usize {
+        let inner = self.inner.read().unwrap();
+        inner.pk_to_pk_id.len()
+    }
+
     /// Gets the stats of the partition.
     pub(crate) fn stats(&self) -> PartitionStats {
         let inner = self.inner.read().unwrap();
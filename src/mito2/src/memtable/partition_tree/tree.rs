@@ -247,6 +247,18 @@ impl PartitionTree {
         partitions.values().all(|part| !part.has_data())
     }
 
+    /// Returns the number of primary keys in the tree.
+    ///
+    /// The tree also counts keys inherited from the tree it forks from.
+    pub fn num_series(&self) -> usize {
+        if self.metadata.primary_key.is_empty() {
+            return usize::from(!self.is_empty());
+        }
+
+        let partitions = self.partitions.read().unwrap();
+        partitions.values().map(|part| part.num_series()).sum()
+    }
+
     /// Marks the tree as immutable.
     ///
     /// Once the tree becomes immutable, callers should not write to it again.
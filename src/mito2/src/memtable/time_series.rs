@@ -260,6 +260,7 @@ impl Memtable for TimeSeriesMemtable {
             return MemtableStats {
                 estimated_bytes,
                 time_range: None,
+                num_series: 0,
             };
         }
         let ts_type = self
@@ -275,6 +276,7 @@ impl Memtable for TimeSeriesMemtable {
         MemtableStats {
             estimated_bytes,
             time_range: Some((min_timestamp, max_timestamp)),
+            num_series: self.series_set.series.read().unwrap().len(),
         }
     }
 
@@ -1220,6 +1222,7 @@ mod tests {
             )),
             stats.time_range()
         );
+        assert_eq!(1, stats.num_series());
     }
 
     #[test]
@@ -66,6 +66,9 @@ lazy_static! {
     /// Histogram of flushed bytes.
     pub static ref FLUSH_BYTES_TOTAL: IntCounter =
         register_int_counter!("greptime_mito_flush_bytes_total", "mito flush bytes total").unwrap();
+    /// Counter of flushes whose number of series grows too fast.
+    pub static ref SERIES_GROWTH_ALERT_TOTAL: IntCounter =
+        register_int_counter!("greptime_mito_series_growth_alert_total", "mito series growth alert total").unwrap();
     // ------ End of flush related metrics
 
 
@@ -76,6 +79,9 @@ lazy_static! {
     /// Counter of rejected write requests.
     pub static ref WRITE_REJECT_TOTAL: IntCounter =
         register_int_counter!("greptime_mito_write_reject_total", "mito write reject total").unwrap();
+    /// Counter of write requests held by the rate limiter.
+    pub static ref WRITE_THROTTLE_TOTAL: IntCounter =
+        register_int_counter!("greptime_mito_write_throttle_total", "mito write throttle total").unwrap();
     /// Elapsed time of each write stage.
     pub static ref WRITE_STAGE_ELAPSED: HistogramVec = register_histogram_vec!(
             "greptime_mito_write_stage_elapsed",
@@ -185,6 +191,18 @@ lazy_static! {
         "mito upload bytes total",
     )
     .unwrap();
+    /// Number of files waiting to upload in background.
+    pub static ref UPLOAD_PENDING: IntGauge = register_int_gauge!(
+        "greptime_mito_upload_pending",
+        "mito upload pending",
+    )
+    .unwrap();
+    /// Counter of failed uploads.
+    pub static ref UPLOAD_FAILURE_TOTAL: IntCounter = register_int_counter!(
+        "greptime_mito_upload_failure_total",
+        "mito upload failure total",
+    )
+    .unwrap();
     // ------- End of cache metrics.
 
     // Index metrics.
@@ -0,0 +1,273 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rate limiter to throttle write requests of the engine and tables.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use common_base::readable_size::ReadableSize;
+use store_api::storage::TableId;
+
+use crate::region::options::WriteRateLimitOptions;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+
+pub(crate) type WriteRateLimiterRef = Arc<WriteRateLimiter>;
+
+/// Limits rows and bytes written to the engine and each table per second.
+///
+/// Each limit is a token bucket that allows bursts of one second. A request is allowed
+/// while buckets it draws from are not empty, so a request larger than a bucket still
+/// passes and following requests wait until the bucket refills.
+///
+/// Buckets are atomics, so concurrent workers don't serialize on the limiter. Checking
+/// and consuming several buckets is not atomic as a whole, so concurrent requests
+/// may exceed a limit slightly.
+#[derive(Debug)]
+pub(crate) struct WriteRateLimiter {
+    /// Limit of the engine.
+    engine: RateLimit,
+    /// Limits of tables. The lock is only held exclusively to add or update a table.
+    tables: RwLock<HashMap<TableId, Arc<RateLimit>>>,
+}
+
+impl WriteRateLimiter {
+    /// Creates a limiter with limits of the engine. Setting a limit to 0 to disable it.
+    pub(crate) fn new(rows_per_second: u64, bytes_per_second: ReadableSize) -> Self {
+        Self {
+            engine: RateLimit::new(
+                Some(rows_per_second).filter(|v| *v > 0),
+                Some(bytes_per_second.as_bytes()).filter(|v| *v > 0),
+                0,
+            ),
+            tables: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Acquires quotas to write `rows` and `bytes` to the table with `table_limit`.
+    ///
+    /// Returns the time to wait before retrying if the engine or the table
+    /// exceeds its limit. Quotas are only consumed if the request is allowed.
+    pub(crate) fn acquire(
+        &self,
+        table_id: TableId,
+        table_limit: &WriteRateLimitOptions,
+        rows: u64,
+        bytes: u64,
+        now_millis: i64,
+    ) -> Option<Duration> {
+        if self.engine.is_unlimited() && table_limit.is_unlimited() {
+            return None;
+        }
+
+        let now_micros = now_millis * 1000;
+        let table = (!table_limit.is_unlimited())
+            .then(|| self.table_limit(table_id, table_limit, now_micros));
+        let engine_wait = self.engine.wait_time(now_micros);
+        let table_wait = table.as_ref().and_then(|table| table.wait_time(now_micros));
+        let wait = engine_wait.max(table_wait);
+        if wait.is_none() {
+            self.engine.consume(rows, bytes, now_micros);
+            if let Some(table) = table {
+                table.consume(rows, bytes, now_micros);
+            }
+        }
+
+        wait
+    }
+
+    /// Removes the limit of the table, such as after the table is dropped.
+    pub(crate) fn remove_table(&self, table_id: TableId) {
+        self.tables.write().unwrap().remove(&table_id);
+    }
+
+    /// Returns the limit of the table. Resets the limit if its rates change.
+    fn table_limit(
+        &self,
+        table_id: TableId,
+        options: &WriteRateLimitOptions,
+        now_micros: i64,
+    ) -> Arc<RateLimit> {
+        let rows_per_second = options.rows_per_second;
+        let bytes_per_second = options.bytes_per_second.map(|v| v.as_bytes());
+        if let Some(limit) = self.tables.read().unwrap().get(&table_id) {
+            if limit.has_rates(rows_per_second, bytes_per_second) {
+                return limit.clone();
+            }
+        }
+
+        let mut tables = self.tables.write().unwrap();
+        let limit = tables.entry(table_id).or_insert_with(|| {
+            Arc::new(RateLimit::new(
+                rows_per_second,
+                bytes_per_second,
+                now_micros,
+            ))
+        });
+        if !limit.has_rates(rows_per_second, bytes_per_second) {
+            *limit = Arc::new(RateLimit::new(
+                rows_per_second,
+                bytes_per_second,
+                now_micros,
+            ));
+        }
+        limit.clone()
+    }
+}
+
+/// Limits of rows and bytes per second.
+#[derive(Debug)]
+struct RateLimit {
+    rows: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl RateLimit {
+    fn new(rows_per_second: Option<u64>, bytes_per_second: Option<u64>, now_micros: i64) -> Self {
+        Self {
+            rows: rows_per_second.map(|rate| TokenBucket::new(rate, now_micros)),
+            bytes: bytes_per_second.map(|rate| TokenBucket::new(rate, now_micros)),
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.rows.is_none() && self.bytes.is_none()
+    }
+
+    fn has_rates(&self, rows_per_second: Option<u64>, bytes_per_second: Option<u64>) -> bool {
+        self.rows.as_ref().map(|bucket| bucket.rate) == rows_per_second
+            && self.bytes.as_ref().map(|bucket| bucket.rate) == bytes_per_second
+    }
+
+    /// Returns the time to wait until all buckets have tokens.
+    fn wait_time(&self, now_micros: i64) -> Option<Duration> {
+        [&self.rows, &self.bytes]
+            .into_iter()
+            .flatten()
+            .filter_map(|bucket| bucket.wait_time(now_micros))
+            .max()
+    }
+
+    fn consume(&self, rows: u64, bytes: u64, now_micros: i64) {
+        if let Some(bucket) = &self.rows {
+            bucket.consume(rows, now_micros);
+        }
+        if let Some(bucket) = &self.bytes {
+            bucket.consume(bytes, now_micros);
+        }
+    }
+}
+
+/// Token bucket whose capacity is the amount of one second.
+///
+/// Instead of tokens, the bucket keeps the time it becomes full again, so refilling
+/// and consuming are single atomic operations. The bucket has tokens while it
+/// becomes full in less than one second.
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens to add per second.
+    rate: u64,
+    /// Time in micros when the bucket becomes full. It's later than one second from
+    /// now if requests consume more tokens than available.
+    full_at_micros: AtomicI64,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now_micros: i64) -> Self {
+        Self {
+            rate,
+            full_at_micros: AtomicI64::new(now_micros),
+        }
+    }
+
+    /// Returns the time to wait until the bucket has tokens.
+    fn wait_time(&self, now_micros: i64) -> Option<Duration> {
+        let full_at = self.full_at_micros.load(Ordering::Relaxed);
+        let deficit = full_at - now_micros - MICROS_PER_SECOND;
+        if deficit < 0 {
+            return None;
+        }
+        Some(Duration::from_millis(deficit as u64 / 1000 + 1))
+    }
+
+    fn consume(&self, amount: u64, now_micros: i64) {
+        let micros = amount as u128 * MICROS_PER_SECOND as u128 / self.rate as u128;
+        let micros = micros.min(i64::MAX as u128) as i64;
+        let _ = self
+            .full_at_micros
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |full_at| {
+                Some(full_at.max(now_micros).saturating_add(micros))
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_limit(rows: Option<u64>, bytes: Option<u64>) -> WriteRateLimitOptions {
+        WriteRateLimitOptions {
+            rows_per_second: rows,
+            bytes_per_second: bytes.map(ReadableSize),
+        }
+    }
+
+    #[test]
+    fn test_engine_limit() {
+        let limiter = WriteRateLimiter::new(100, ReadableSize(0));
+        let unlimited = table_limit(None, None);
+
+        assert_eq!(None, limiter.acquire(1, &unlimited, 60, 1024, 0));
+        // The request larger than available tokens is allowed.
+        assert_eq!(None, limiter.acquire(2, &unlimited, 60, 1024, 0));
+        let wait = limiter.acquire(1, &unlimited, 10, 1024, 0).unwrap();
+        assert_eq!(Duration::from_millis(201), wait);
+        assert!(limiter.acquire(1, &unlimited, 10, 1024, 200).is_some());
+        assert_eq!(None, limiter.acquire(1, &unlimited, 10, 1024, 201));
+    }
+
+    #[test]
+    fn test_table_limit() {
+        let limiter = WriteRateLimiter::new(0, ReadableSize(0));
+        let limit = table_limit(None, Some(1000));
+
+        assert_eq!(None, limiter.acquire(1, &limit, 1, 1000, 0));
+        assert!(limiter.acquire(1, &limit, 1, 1000, 0).is_some());
+        // Other tables are not limited.
+        assert_eq!(
+            None,
+            limiter.acquire(2, &table_limit(None, None), 1, 1000, 0)
+        );
+        assert_eq!(None, limiter.acquire(3, &limit, 1, 1000, 0));
+        // The bucket refills after one second.
+        assert_eq!(None, limiter.acquire(1, &limit, 1, 1000, 1000));
+
+        // Changing the limit resets the buckets.
+        assert!(limiter.acquire(1, &limit, 1, 1000, 1000).is_some());
+        let limit = table_limit(Some(10), Some(2000));
+        assert_eq!(None, limiter.acquire(1, &limit, 1, 1000, 1000));
+        // Removing the limit.
+        assert_eq!(
+            None,
+            limiter.acquire(1, &table_limit(None, None), 1, 1000, 1000)
+        );
+
+        // Dropping the table removes its limit.
+        limiter.remove_table(1);
+        assert!(!limiter.tables.read().unwrap().contains_key(&1));
+    }
+}
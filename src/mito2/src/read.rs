@@ -15,6 +15,7 @@
 //! Common structs and utilities for reading data.
 
 pub mod compat;
+pub(crate) mod dedup;
 pub mod merge;
 pub mod projection;
 pub(crate) mod scan_region;
@@ -27,7 +28,7 @@ use std::sync::Arc;
 use api::v1::OpType;
 use async_trait::async_trait;
 use common_time::Timestamp;
-use datafusion_common::arrow::array::UInt8Array;
+use datafusion_common::arrow::array::{UInt64Array, UInt8Array};
 use datatypes::arrow;
 use datatypes::arrow::array::{Array, ArrayRef};
 use datatypes::arrow::compute::SortOptions;
@@ -303,6 +304,16 @@ impl Batch {
         self.filter(&BooleanVector::from(predicate))
     }
 
+    /// Removes rows whose sequence is greater than `sequence`.
+    pub fn filter_by_sequence(&mut self, sequence: SequenceNumber) -> Result<()> {
+        // Safety: sequence column is not null.
+        let array = self.sequences.as_arrow();
+        let rhs = UInt64Array::new_scalar(sequence);
+        let predicate =
+            arrow::compute::kernels::cmp::lt_eq(array, &rhs).context(ComputeArrowSnafu)?;
+        self.filter(&BooleanVector::from(predicate))
+    }
+
     // Applies the `predicate` to the batch.
     // Safety: We know the array type so we unwrap on casting.
     pub fn filter(&mut self, predicate: &BooleanVector) -> Result<()> {
@@ -956,6 +967,27 @@ mod tests {
         assert_eq!(expect, batch);
     }
 
+    #[test]
+    fn test_filter_by_sequence() {
+        let mut batch = new_batch(
+            &[1, 2, 3, 4],
+            &[11, 12, 13, 14],
+            &[OpType::Put, OpType::Delete, OpType::Put, OpType::Put],
+            &[21, 22, 23, 24],
+        );
+        batch.filter_by_sequence(12).unwrap();
+        let expect = new_batch(
+            &[1, 2],
+            &[11, 12],
+            &[OpType::Put, OpType::Delete],
+            &[21, 22],
+        );
+        assert_eq!(expect, batch);
+
+        batch.filter_by_sequence(10).unwrap();
+        assert!(batch.is_empty());
+    }
+
     #[test]
     fn test_filter() {
         // Filters put only.
@@ -16,13 +16,14 @@
 
 use std::collections::HashMap;
 
+use datatypes::prelude::ConcreteDataType;
 use datatypes::value::Value;
 use datatypes::vectors::VectorRef;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::metadata::{RegionMetadata, RegionMetadataRef};
 use store_api::storage::ColumnId;
 
-use crate::error::{CompatReaderSnafu, CreateDefaultSnafu, Result};
+use crate::error::{CompatReaderSnafu, ComputeVectorSnafu, CreateDefaultSnafu, Result};
 use crate::read::projection::ProjectionMapper;
 use crate::read::{Batch, BatchColumn, BatchReader};
 use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
@@ -69,7 +70,7 @@ impl<R: BatchReader> BatchReader for CompatReader<R> {
             batch = compat_pk.compat(batch)?;
         }
         if let Some(compat_fields) = &self.compat_fields {
-            batch = compat_fields.compat(batch);
+            batch = compat_fields.compat(batch)?;
         }
 
         Ok(Some(batch))
@@ -78,20 +79,18 @@ impl<R: BatchReader> BatchReader for CompatReader<R> {
 
 /// Returns true if `left` and `right` have same columns to read.
 ///
-/// It only consider column ids.
+/// It only consider column ids and data types.
 pub(crate) fn has_same_columns(left: &RegionMetadata, right: &RegionMetadata) -> bool {
     if left.column_metadatas.len() != right.column_metadatas.len() {
         return false;
     }
 
     for (left_col, right_col) in left.column_metadatas.iter().zip(&right.column_metadatas) {
-        if left_col.column_id != right_col.column_id {
+        if left_col.column_id != right_col.column_id
+            || left_col.column_schema.data_type != right_col.column_schema.data_type
+        {
             return false;
         }
-        debug_assert_eq!(
-            left_col.column_schema.data_type,
-            right_col.column_schema.data_type
-        );
         debug_assert_eq!(left_col.semantic_type, right_col.semantic_type);
     }
 
@@ -142,8 +141,7 @@ struct CompatFields {
 
 impl CompatFields {
     /// Make fields of the `batch` compatible.
-    #[must_use]
-    fn compat(&self, batch: Batch) -> Batch {
+    fn compat(&self, batch: Batch) -> Result<Batch> {
         debug_assert_eq!(self.actual_fields.len(), batch.fields().len());
         debug_assert!(self
             .actual_fields
@@ -156,22 +154,30 @@ impl CompatFields {
             .index_or_defaults
             .iter()
             .map(|index_or_default| match index_or_default {
-                IndexOrDefault::Index(index) => batch.fields()[*index].clone(),
+                IndexOrDefault::Index(index) => Ok(batch.fields()[*index].clone()),
+                IndexOrDefault::Cast { index, to_type } => {
+                    let column = &batch.fields()[*index];
+                    let data = column.data.cast(to_type).context(ComputeVectorSnafu)?;
+                    Ok(BatchColumn {
+                        column_id: column.column_id,
+                        data,
+                    })
+                }
                 IndexOrDefault::DefaultValue {
                     column_id,
                     default_vector,
                 } => {
                     let data = default_vector.replicate(&[len]);
-                    BatchColumn {
+                    Ok(BatchColumn {
                         column_id: *column_id,
                         data,
-                    }
+                    })
                 }
             })
-            .collect();
+            .collect::<Result<Vec<_>>>()?;
 
         // Safety: We ensure all columns have the same length and the new batch should be valid.
-        batch.with_fields(fields).unwrap()
+        Ok(batch.with_fields(fields).unwrap())
     }
 }
 
@@ -241,7 +247,9 @@ fn may_compat_fields(
 ) -> Result<Option<CompatFields>> {
     let expect_fields = mapper.batch_fields();
     let actual_fields = Batch::projected_fields(actual, mapper.column_ids());
-    if expect_fields == actual_fields {
+    if expect_fields == actual_fields
+        && !has_type_changed(mapper.metadata(), actual, &actual_fields)
+    {
         return Ok(None);
     }
 
@@ -256,7 +264,27 @@ fn may_compat_fields(
         .map(|column_id| {
             if let Some(index) = source_field_index.get(column_id) {
                 // Source has this field.
-                Ok(IndexOrDefault::Index(*index))
+                // Safety: mapper and source must have this column.
+                let expect_type = &mapper
+                    .metadata()
+                    .column_by_id(*column_id)
+                    .unwrap()
+                    .column_schema
+                    .data_type;
+                let actual_type = &actual
+                    .column_by_id(*column_id)
+                    .unwrap()
+                    .column_schema
+                    .data_type;
+                if expect_type == actual_type {
+                    Ok(IndexOrDefault::Index(*index))
+                } else {
+                    // The type of the field has been changed, casts the old values.
+                    Ok(IndexOrDefault::Cast {
+                        index: *index,
+                        to_type: expect_type.clone(),
+                    })
+                }
             } else {
                 // Safety: mapper must have this column.
                 let column = mapper.metadata().column_by_id(*column_id).unwrap();
@@ -289,11 +317,34 @@ fn may_compat_fields(
     }))
 }
 
+/// Returns true if any field in `fields` has different data types in
+/// `expect` and `actual`.
+fn has_type_changed(expect: &RegionMetadata, actual: &RegionMetadata, fields: &[ColumnId]) -> bool {
+    fields.iter().any(|column_id| {
+        match (
+            expect.column_by_id(*column_id),
+            actual.column_by_id(*column_id),
+        ) {
+            (Some(expect_col), Some(actual_col)) => {
+                expect_col.column_schema.data_type != actual_col.column_schema.data_type
+            }
+            _ => false,
+        }
+    })
+}
+
 /// Index in source batch or a default value to fill a column.
 #[derive(Debug)]
 enum IndexOrDefault {
     /// Index of the column in source batch.
     Index(usize),
+    /// Index of the column in source batch whose values need to cast to `to_type`.
+    Cast {
+        /// Index of the column in source batch.
+        index: usize,
+        /// Type to cast to.
+        to_type: ConcreteDataType,
+    },
     /// Default value for the column.
     DefaultValue {
         /// Id of the column.
@@ -311,8 +362,11 @@ mod tests {
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::ColumnSchema;
     use datatypes::value::ValueRef;
-    use datatypes::vectors::{Int64Vector, TimestampMillisecondVector, UInt64Vector, UInt8Vector};
+    use datatypes::vectors::{
+        Int32Vector, Int64Vector, TimestampMillisecondVector, UInt64Vector, UInt8Vector,
+    };
     use store_api::metadata::{ColumnMetadata, RegionMetadataBuilder};
+    use store_api::region_request::{AlterKind, ChangeColumnType};
     use store_api::storage::RegionId;
 
     use super::*;
@@ -605,4 +659,50 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    async fn test_compat_reader_change_type() {
+        let expect_meta = Arc::new(new_metadata(
+            &[
+                (0, SemanticType::Timestamp),
+                (1, SemanticType::Tag),
+                (2, SemanticType::Field),
+            ],
+            &[1],
+        ));
+        // The reader has field_2 in int32.
+        let mut builder = RegionMetadataBuilder::from_existing(expect_meta.as_ref().clone());
+        builder
+            .alter(AlterKind::ChangeColumnTypes {
+                columns: vec![ChangeColumnType {
+                    column_name: "field_2".to_string(),
+                    target_type: ConcreteDataType::int32_datatype(),
+                }],
+            })
+            .unwrap();
+        let reader_meta = Arc::new(builder.build().unwrap());
+        assert!(!has_same_columns(&expect_meta, &reader_meta));
+
+        let mapper = ProjectionMapper::all(&expect_meta).unwrap();
+        let k1 = encode_key(&[Some("a")]);
+        let source_batch = Batch::new(
+            k1.clone(),
+            Arc::new(TimestampMillisecondVector::from_values(1000..1003)),
+            Arc::new(UInt64Vector::from_values(0..3)),
+            Arc::new(UInt8Vector::from_vec(vec![OpType::Put as u8; 3])),
+            vec![BatchColumn {
+                column_id: 2,
+                data: Arc::new(Int32Vector::from_vec(vec![2; 3])),
+            }],
+        )
+        .unwrap();
+        let source_reader = VecBatchReader::new(&[source_batch]);
+
+        let mut compat_reader = CompatReader::new(&mapper, reader_meta, source_reader).unwrap();
+        check_reader_result(
+            &mut compat_reader,
+            &[new_batch(&k1, &[(2, false)], 1000, 3)],
+        )
+        .await;
+    }
 }
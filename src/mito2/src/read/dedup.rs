@@ -0,0 +1,349 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reader to merge rows with the same primary key and timestamp.
+
+use api::v1::OpType;
+use async_trait::async_trait;
+use common_telemetry::debug;
+use datatypes::vectors::UInt32Vector;
+use snafu::ResultExt;
+
+use crate::error::{ComputeVectorSnafu, Result};
+use crate::metrics::MERGE_FILTER_ROWS_TOTAL;
+use crate::read::{Batch, BatchColumn, BatchReader};
+
+/// Reader to merge rows with the same primary key and timestamp under the
+/// `last_non_null` merge mode.
+///
+/// For each key, it keeps the last row and fills its null fields by the last
+/// non-null values of older rows. Rows older than a deletion marker are ignored.
+///
+/// The source must yield rows sorted by primary key, timestamp and sequence desc
+/// without removing duplicate rows, e.g. a [MergeReader](crate::read::merge::MergeReader)
+/// that doesn't dedup.
+pub(crate) struct LastNonNullReader<R> {
+    source: R,
+    /// Remove deletion markers.
+    filter_deleted: bool,
+    /// Rows of the last timestamp in the previous batch. The next batch may have
+    /// more rows of the same timestamp.
+    buffer: Option<Batch>,
+    /// Batch fetched from the source but not processed yet.
+    pending: Option<Batch>,
+    /// Number of rows merged into other rows.
+    num_merged_rows: usize,
+}
+
+impl<R> LastNonNullReader<R> {
+    /// Creates a new reader to merge rows from the `source`.
+    pub(crate) fn new(source: R, filter_deleted: bool) -> Self {
+        Self {
+            source,
+            filter_deleted,
+            buffer: None,
+            pending: None,
+            num_merged_rows: 0,
+        }
+    }
+
+    /// Merges duplicate rows in the `batch` and removes deletion markers if necessary.
+    fn merge_rows(&mut self, mut batch: Batch) -> Result<Option<Batch>> {
+        let num_rows = batch.num_rows();
+        // Safety: We don't merge empty batches.
+        let timestamps = batch.timestamps_native().unwrap();
+        if timestamps.windows(2).any(|w| w[0] == w[1]) {
+            batch = merge_duplicate_rows(&batch)?;
+            self.num_merged_rows += num_rows - batch.num_rows();
+        }
+        if self.filter_deleted {
+            batch.filter_deleted()?;
+        }
+
+        if batch.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(batch))
+        }
+    }
+}
+
+#[async_trait]
+impl<R: BatchReader> BatchReader for LastNonNullReader<R> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        loop {
+            let batch = match self.pending.take() {
+                Some(batch) => batch,
+                None => match self.source.next_batch().await? {
+                    Some(batch) => batch,
+                    None => {
+                        // The source is exhausted, merges the remaining rows.
+                        let Some(buffer) = self.buffer.take() else {
+                            return Ok(None);
+                        };
+                        return self.merge_rows(buffer);
+                    }
+                },
+            };
+            if batch.is_empty() {
+                continue;
+            }
+
+            let batch = match self.buffer.take() {
+                Some(buffer) if buffer.primary_key() == batch.primary_key() => {
+                    Batch::concat(vec![buffer, batch])?
+                }
+                Some(buffer) => {
+                    // The batch has another primary key so the buffer won't have more rows.
+                    self.pending = Some(batch);
+                    if let Some(output) = self.merge_rows(buffer)? {
+                        return Ok(Some(output));
+                    }
+                    continue;
+                }
+                None => batch,
+            };
+
+            // Buffers rows of the last timestamp.
+            // Safety: The batch is not empty.
+            let timestamps = batch.timestamps_native().unwrap();
+            let last = timestamps[timestamps.len() - 1];
+            let last_start = timestamps
+                .iter()
+                .rposition(|ts| *ts != last)
+                .map(|pos| pos + 1)
+                .unwrap_or(0);
+            self.buffer = Some(batch.slice(last_start, batch.num_rows() - last_start));
+            if last_start == 0 {
+                continue;
+            }
+            if let Some(output) = self.merge_rows(batch.slice(0, last_start))? {
+                return Ok(Some(output));
+            }
+        }
+    }
+}
+
+impl<R> Drop for LastNonNullReader<R> {
+    fn drop(&mut self) {
+        debug!(
+            "Last non-null reader finished, num_merged_rows: {}",
+            self.num_merged_rows
+        );
+
+        MERGE_FILTER_ROWS_TOTAL
+            .with_label_values(&["dedup"])
+            .inc_by(self.num_merged_rows as u64);
+    }
+}
+
+/// Merges rows with the same timestamp in the `batch` into one row.
+///
+/// The merged row takes the timestamp, sequence and op type of the last row. Each of
+/// its fields is the first non-null value among the last row and older rows until
+/// a deletion marker.
+fn merge_duplicate_rows(batch: &Batch) -> Result<Batch> {
+    // Safety: The batch is not empty.
+    let timestamps = batch.timestamps_native().unwrap();
+    let op_types = batch.op_types().as_arrow().values();
+
+    // Index of the latest row of each timestamp.
+    let mut row_indices = Vec::new();
+    // Index of the row to take for each field and timestamp.
+    let mut field_indices = vec![Vec::new(); batch.fields().len()];
+    let mut start = 0;
+    while start < timestamps.len() {
+        let end = start
+            + timestamps[start..]
+                .iter()
+                .take_while(|ts| **ts == timestamps[start])
+                .count();
+        row_indices.push(start as u32);
+
+        // Older rows than a deletion marker are deleted.
+        let visible_end = if op_types[start] == OpType::Delete as u8 {
+            start + 1
+        } else {
+            (start + 1..end)
+                .find(|i| op_types[*i] == OpType::Delete as u8)
+                .unwrap_or(end)
+        };
+        for (column, indices) in batch.fields().iter().zip(&mut field_indices) {
+            let index = (start..visible_end)
+                .find(|i| !column.data.is_null(*i))
+                .unwrap_or(start);
+            indices.push(index as u32);
+        }
+
+        start = end;
+    }
+
+    let fields = batch
+        .fields()
+        .iter()
+        .zip(field_indices)
+        .map(|(column, indices)| {
+            let indices = UInt32Vector::from_vec(indices);
+            Ok(BatchColumn {
+                column_id: column.column_id,
+                data: column.data.take(&indices).context(ComputeVectorSnafu)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut output = Batch {
+        fields: Vec::new(),
+        ..batch.clone()
+    };
+    output.take_in_place(&UInt32Vector::from_vec(row_indices))?;
+    output.fields = fields;
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::arrow::array::{TimestampMillisecondArray, UInt64Array, UInt8Array};
+
+    use super::*;
+    use crate::read::BatchBuilder;
+    use crate::test_util::{check_reader_result, VecBatchReader};
+
+    fn new_batch(
+        primary_key: &[u8],
+        timestamps: &[i64],
+        sequences: &[u64],
+        op_types: &[OpType],
+        fields: &[&[Option<u64>]],
+    ) -> Batch {
+        let mut builder = BatchBuilder::new(primary_key.to_vec());
+        builder
+            .timestamps_array(Arc::new(TimestampMillisecondArray::from_iter_values(
+                timestamps.iter().copied(),
+            )))
+            .unwrap()
+            .sequences_array(Arc::new(UInt64Array::from_iter_values(
+                sequences.iter().copied(),
+            )))
+            .unwrap()
+            .op_types_array(Arc::new(UInt8Array::from_iter_values(
+                op_types.iter().map(|v| *v as u8),
+            )))
+            .unwrap();
+        for (i, field) in fields.iter().enumerate() {
+            builder
+                .push_field_array(i as u32 + 1, Arc::new(UInt64Array::from(field.to_vec())))
+                .unwrap();
+        }
+        builder.build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_last_non_null_no_duplicate() {
+        let input = [
+            new_batch(
+                b"k1",
+                &[1, 2],
+                &[11, 12],
+                &[OpType::Put, OpType::Delete],
+                &[&[Some(21), None]],
+            ),
+            new_batch(b"k2", &[1], &[13], &[OpType::Put], &[&[None]]),
+        ];
+        let mut reader = LastNonNullReader::new(VecBatchReader::new(&input), true);
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(b"k1", &[1], &[11], &[OpType::Put], &[&[Some(21)]]),
+                new_batch(b"k2", &[1], &[13], &[OpType::Put], &[&[None]]),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_last_non_null_merge() {
+        let input = [
+            new_batch(
+                b"k1",
+                &[1, 1, 1, 2],
+                &[13, 12, 11, 14],
+                &[OpType::Put, OpType::Put, OpType::Put, OpType::Put],
+                &[
+                    &[None, Some(22), Some(21), Some(24)],
+                    &[None, None, Some(31), None],
+                ],
+            ),
+            // Rows of the last timestamp span batches.
+            new_batch(
+                b"k1",
+                &[2, 3, 3],
+                &[10, 16, 15],
+                &[OpType::Put, OpType::Put, OpType::Delete],
+                &[&[Some(20), None, Some(25)], &[Some(30), None, Some(35)]],
+            ),
+            new_batch(
+                b"k2",
+                &[1, 1],
+                &[12, 11],
+                &[OpType::Delete, OpType::Put],
+                &[&[None, Some(21)], &[None, Some(31)]],
+            ),
+        ];
+        let mut reader = LastNonNullReader::new(VecBatchReader::new(&input), true);
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(
+                    b"k1",
+                    &[1],
+                    &[13],
+                    &[OpType::Put],
+                    &[&[Some(22)], &[Some(31)]],
+                ),
+                new_batch(
+                    b"k1",
+                    &[2],
+                    &[14],
+                    &[OpType::Put],
+                    &[&[Some(24)], &[Some(30)]],
+                ),
+                // Values older than the deletion marker are ignored.
+                new_batch(b"k1", &[3], &[16], &[OpType::Put], &[&[None], &[None]]),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_last_non_null_keep_deleted() {
+        let input = [new_batch(
+            b"k1",
+            &[1, 1, 2],
+            &[12, 11, 13],
+            &[OpType::Delete, OpType::Put, OpType::Delete],
+            &[&[None, Some(21), None]],
+        )];
+        let mut reader = LastNonNullReader::new(VecBatchReader::new(&input), false);
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(b"k1", &[1], &[12], &[OpType::Delete], &[&[None]]),
+                new_batch(b"k1", &[2], &[13], &[OpType::Delete], &[&[None]]),
+            ],
+        )
+        .await;
+    }
+}
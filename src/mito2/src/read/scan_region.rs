@@ -14,13 +14,20 @@
 
 //! Scans a region according to the scan request.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Instant;
 
-use common_recordbatch::SendableRecordBatchStream;
+use api::v1::SemanticType;
+use async_trait::async_trait;
+use common_query::logical_plan::Expr;
+use common_recordbatch::{OrderOption, SendableRecordBatchStream};
 use common_telemetry::{debug, error, warn};
 use common_time::range::TimestampRange;
-use store_api::storage::ScanRequest;
+use datafusion_expr::utils::expr_to_columns;
+use datafusion_expr::{BinaryExpr, Expr as DfExpr, Operator};
+use datatypes::arrow::compute::SortOptions;
+use store_api::storage::{ScanRequest, SequenceNumber};
 use table::predicate::{Predicate, TimeRangePredicateBuilder};
 use tokio::sync::{mpsc, Semaphore};
 use tokio_stream::wrappers::ReceiverStream;
@@ -35,7 +42,8 @@ use crate::read::compat::CompatReader;
 use crate::read::projection::ProjectionMapper;
 use crate::read::seq_scan::SeqScan;
 use crate::read::unordered_scan::UnorderedScan;
-use crate::read::{compat, Batch, Source};
+use crate::read::{compat, Batch, BatchReader, Source};
+use crate::region::options::MergeMode;
 use crate::region::version::VersionRef;
 use crate::sst::file::FileHandle;
 use crate::sst::index::applier::builder::SstIndexApplierBuilder;
@@ -204,7 +212,8 @@ impl ScanRegion {
 
     /// Scan sequentially.
     pub(crate) fn seq_scan(self) -> Result<SeqScan> {
-        let input = self.scan_input(true)?;
+        let output_ordering = self.seq_scan_output_ordering();
+        let input = self.scan_input(true)?.with_output_ordering(output_ordering);
         let seq_scan = SeqScan::new(input);
 
         Ok(seq_scan)
@@ -269,7 +278,7 @@ impl ScanRegion {
         );
 
         let index_applier = self.build_index_applier();
-        let predicate = Predicate::new(self.request.filters.clone());
+        let predicate = Predicate::new(self.filters_to_push_down());
         // The mapper always computes projected column ids as the schema of SSTs may change.
         let mapper = match &self.request.projection {
             Some(p) => ProjectionMapper::new(&self.version.metadata, p.iter().copied())?,
@@ -286,10 +295,82 @@ impl ScanRegion {
             .with_parallelism(self.parallelism)
             .with_start_time(self.start_time)
             .with_append_mode(self.version.options.append_mode)
-            .with_filter_deleted(filter_deleted);
+            .with_filter_deleted(filter_deleted)
+            .with_merge_mode(self.version.options.merge_mode)
+            .with_sequence(self.request.sequence);
         Ok(input)
     }
 
+    /// Returns filters to push down to memtables and SSTs.
+    ///
+    /// Under the `last_non_null` merge mode, filters on fields may remove older rows
+    /// that fill null fields of newer rows, so we only push down filters on tags and
+    /// the time index. The query engine still applies all filters.
+    fn filters_to_push_down(&self) -> Vec<Expr> {
+        if self.version.options.merge_mode != MergeMode::LastNonNull {
+            return self.request.filters.clone();
+        }
+
+        let metadata = &self.version.metadata;
+        self.request
+            .filters
+            .iter()
+            .filter(|expr| {
+                let mut columns = HashSet::new();
+                if expr_to_columns(expr.df_expr(), &mut columns).is_err() {
+                    return false;
+                }
+                columns.iter().all(|column| {
+                    metadata
+                        .column_by_name(&column.name)
+                        .map(|column| column.semantic_type != SemanticType::Field)
+                        .unwrap_or(false)
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the ordering of rows returned by the sequential scan that the query
+    /// engine can use.
+    ///
+    /// The sequential scan returns rows sorted by primary key and time index, so rows
+    /// are sorted by time index if they belong to one series. This is true if the region
+    /// has no primary key or filters fix each primary key column to one value. Rows of
+    /// other series may not be filtered out by the scan but they are always removed by
+    /// the filter above the scan.
+    fn seq_scan_output_ordering(&self) -> Option<Vec<OrderOption>> {
+        let metadata = &self.version.metadata;
+        let time_index = metadata.time_index_column();
+        if let Some(projection) = &self.request.projection {
+            let index = metadata.column_index_by_id(time_index.column_id)?;
+            if !projection.contains(&index) {
+                return None;
+            }
+        }
+
+        let fixed_columns: HashSet<_> = self
+            .request
+            .filters
+            .iter()
+            .filter_map(|expr| column_equals_literal(expr.df_expr()))
+            .collect();
+        if !metadata
+            .primary_key_columns()
+            .all(|column| fixed_columns.contains(column.column_schema.name.as_str()))
+        {
+            return None;
+        }
+
+        Some(vec![OrderOption {
+            name: time_index.column_schema.name.clone(),
+            options: SortOptions {
+                descending: false,
+                nulls_first: false,
+            },
+        }])
+    }
+
     /// Build time range predicate from filters.
     fn build_time_range_predicate(&self) -> TimestampRange {
         let time_index = self.version.metadata.time_index_column();
@@ -365,6 +446,27 @@ fn file_in_range(file: &FileHandle, predicate: &TimestampRange) -> bool {
     file_ts_range.intersects(predicate)
 }
 
+/// Returns the name of the column if the `expr` is `column = literal`.
+fn column_equals_literal(expr: &DfExpr) -> Option<&str> {
+    let DfExpr::BinaryExpr(BinaryExpr {
+        left,
+        op: Operator::Eq,
+        right,
+    }) = expr
+    else {
+        return None;
+    };
+    match (left.as_ref(), right.as_ref()) {
+        (DfExpr::Column(column), DfExpr::Literal(value))
+        | (DfExpr::Literal(value), DfExpr::Column(column))
+            if !value.is_null() =>
+        {
+            Some(&column.name)
+        }
+        _ => None,
+    }
+}
+
 /// Common input for different scanners.
 pub(crate) struct ScanInput {
     /// Region SST access layer.
@@ -393,6 +495,12 @@ pub(crate) struct ScanInput {
     pub(crate) append_mode: bool,
     /// Whether to remove deletion markers.
     pub(crate) filter_deleted: bool,
+    /// Mode to merge duplicate rows.
+    pub(crate) merge_mode: MergeMode,
+    /// Max sequence of rows to read.
+    sequence: Option<SequenceNumber>,
+    /// Ordering of the output rows the query engine can use.
+    pub(crate) output_ordering: Option<Vec<OrderOption>>,
 }
 
 impl ScanInput {
@@ -413,6 +521,9 @@ impl ScanInput {
             query_start: None,
             append_mode: false,
             filter_deleted: true,
+            merge_mode: MergeMode::default(),
+            sequence: None,
+            output_ordering: None,
         }
     }
 
@@ -493,6 +604,27 @@ impl ScanInput {
         self
     }
 
+    /// Sets the mode to merge duplicate rows.
+    #[must_use]
+    pub(crate) fn with_merge_mode(mut self, merge_mode: MergeMode) -> Self {
+        self.merge_mode = merge_mode;
+        self
+    }
+
+    /// Sets the max sequence of rows to read.
+    #[must_use]
+    pub(crate) fn with_sequence(mut self, sequence: Option<SequenceNumber>) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Sets the ordering of the output rows.
+    #[must_use]
+    pub(crate) fn with_output_ordering(mut self, ordering: Option<Vec<OrderOption>>) -> Self {
+        self.output_ordering = ordering;
+        self
+    }
+
     /// Builds and returns sources to read.
     pub(crate) async fn build_sources(&self) -> Result<Vec<Source>> {
         let mut sources = Vec::with_capacity(self.memtables.len() + self.files.len());
@@ -509,6 +641,7 @@ impl ScanInput {
                 .projection(Some(self.mapper.column_ids().to_vec()))
                 .cache(self.cache_manager.clone())
                 .index_applier(self.index_applier.clone())
+                .expected_metadata(Some(self.mapper.metadata().clone()))
                 .build()
                 .await;
             let reader = match maybe_reader {
@@ -535,6 +668,16 @@ impl ScanInput {
 
         READ_SST_COUNT.observe(self.files.len() as f64);
 
+        if let Some(sequence) = self.sequence {
+            // Removes rows written after the snapshot before merging sources, so they
+            // don't shadow older rows.
+            let sources = sources
+                .into_iter()
+                .map(|source| Source::Reader(Box::new(SequenceFilterReader { source, sequence })))
+                .collect();
+            return Ok(sources);
+        }
+
         Ok(sources)
     }
 
@@ -607,3 +750,23 @@ impl ScanInput {
         self.files.iter().map(|file| file.file_id()).collect()
     }
 }
+
+/// Reader that removes rows whose sequence is greater than `sequence`.
+struct SequenceFilterReader {
+    source: Source,
+    sequence: SequenceNumber,
+}
+
+#[async_trait]
+impl BatchReader for SequenceFilterReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(mut batch) = self.source.next_batch().await? {
+            batch.filter_by_sequence(self.sequence)?;
+            if !batch.is_empty() {
+                return Ok(Some(batch));
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -26,10 +26,12 @@ use snafu::ResultExt;
 use crate::cache::CacheManager;
 use crate::error::Result;
 use crate::metrics::{READ_BATCHES_RETURN, READ_ROWS_RETURN, READ_STAGE_ELAPSED};
+use crate::read::dedup::LastNonNullReader;
 use crate::read::merge::MergeReaderBuilder;
 use crate::read::projection::ProjectionMapper;
 use crate::read::scan_region::ScanInput;
-use crate::read::{BatchReader, BoxedBatchReader};
+use crate::read::{BatchReader, BoxedBatchReader, Source};
+use crate::region::options::MergeMode;
 
 /// Scans a region and returns rows in a sorted sequence.
 ///
@@ -92,33 +94,47 @@ impl SeqScan {
                 mapper.metadata().region_id, metrics, use_parallel, parallelism,
             );
         };
-        let stream = Box::pin(RecordBatchStreamWrapper::new(
-            self.input.mapper.output_schema(),
-            Box::pin(stream),
-        ));
+        let mut stream =
+            RecordBatchStreamWrapper::new(self.input.mapper.output_schema(), Box::pin(stream));
+        stream.output_ordering = self.input.output_ordering.clone();
 
-        Ok(stream)
+        Ok(Box::pin(stream))
     }
 
     /// Builds a [BoxedBatchReader] from sequential scan.
     pub async fn build_reader(&self) -> Result<BoxedBatchReader> {
         // Scans all memtables and SSTs. Builds a merge reader to merge results.
         let sources = self.input.build_sources().await?;
-        let dedup = !self.input.append_mode;
-        let mut builder =
-            MergeReaderBuilder::from_sources(sources, dedup, self.input.filter_deleted);
-        let reader = builder.build().await?;
-        Ok(Box::new(reader))
+        self.build_merge_reader(sources).await
     }
 
     /// Builds a [BoxedBatchReader] that can scan memtables and SSTs in parallel.
     async fn build_parallel_reader(&self) -> Result<BoxedBatchReader> {
         let sources = self.input.build_parallel_sources().await?;
-        let dedup = !self.input.append_mode;
-        let mut builder =
-            MergeReaderBuilder::from_sources(sources, dedup, self.input.filter_deleted);
-        let reader = builder.build().await?;
-        Ok(Box::new(reader))
+        self.build_merge_reader(sources).await
+    }
+
+    /// Builds a reader to merge `sources` by the merge mode of the region.
+    async fn build_merge_reader(&self, sources: Vec<Source>) -> Result<BoxedBatchReader> {
+        match self.input.merge_mode {
+            MergeMode::LastRow => {
+                let dedup = !self.input.append_mode;
+                let mut builder =
+                    MergeReaderBuilder::from_sources(sources, dedup, self.input.filter_deleted);
+                let reader = builder.build().await?;
+                Ok(Box::new(reader))
+            }
+            MergeMode::LastNonNull => {
+                // The merge reader keeps duplicate rows and deletion markers so the
+                // last non-null reader can merge them.
+                let mut builder = MergeReaderBuilder::from_sources(sources, false, false);
+                let reader = builder.build().await?;
+                Ok(Box::new(LastNonNullReader::new(
+                    reader,
+                    self.input.filter_deleted,
+                )))
+            }
+        }
     }
 
     /// Returns whether to use a parallel reader.
@@ -16,17 +16,21 @@
 
 pub(crate) mod opener;
 pub mod options;
+pub mod snapshot;
 pub(crate) mod version;
+pub(crate) mod watermark;
 
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
-use std::sync::{Arc, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use common_telemetry::info;
 use common_wal::options::WalOptions;
+use serde::Serialize;
 use snafu::{ensure, OptionExt};
 use store_api::metadata::RegionMetadataRef;
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, SequenceNumber};
 use tokio::sync::RwLock as TokioRwLock;
 
 use crate::access_layer::AccessLayerRef;
@@ -34,10 +38,14 @@ use crate::error::{RegionNotFoundSnafu, RegionReadonlySnafu, Result};
 use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
 use crate::manifest::manager::RegionManifestManager;
 use crate::memtable::{MemtableBuilderRef, MemtableId};
+use crate::region::snapshot::PinnedSnapshotsRef;
 use crate::region::version::{VersionControlRef, VersionRef};
+use crate::region::watermark::Watermarks;
 use crate::request::OnFailure;
+use crate::sst::file::FileMeta;
 use crate::sst::file_purger::FilePurgerRef;
 use crate::time_provider::TimeProviderRef;
+use crate::wal::EntryId;
 
 /// This is the approximate factor to estimate the size of wal.
 const ESTIMATED_WAL_FACTOR: f32 = 0.42825;
@@ -57,6 +65,59 @@ impl RegionUsage {
     }
 }
 
+/// Progress of upgrading SSTs of a region to the current format version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SstUpgradeProgress {
+    pub region_id: RegionId,
+    /// Number of SST files in the region.
+    pub num_files: usize,
+    /// Number of SST files in old format versions.
+    pub num_outdated_files: usize,
+}
+
+/// Flushes of a region whose WAL entries are retained.
+#[derive(Debug, Default)]
+pub(crate) struct RetainedFlushes {
+    /// Flushes in time order, as the flush time in millis and the flushed entry id.
+    flushes: VecDeque<(i64, EntryId)>,
+    /// The latest entry id of expired flushes.
+    expired_entry_id: Option<EntryId>,
+}
+
+impl RetainedFlushes {
+    /// Creates the flushes persisted in the manifest.
+    pub(crate) fn new(flushes: Vec<(i64, EntryId)>) -> Self {
+        Self {
+            flushes: flushes.into(),
+            expired_entry_id: None,
+        }
+    }
+
+    /// Records the flush at `now_millis` and returns the latest entry id flushed at least
+    /// `retain_period` ago, if any. Flushes up to the returned one are no longer retained.
+    fn push_and_expire(
+        &mut self,
+        now_millis: i64,
+        flushed_entry_id: EntryId,
+        retain_period: Duration,
+    ) -> Option<EntryId> {
+        self.flushes.push_back((now_millis, flushed_entry_id));
+        let expire_millis = now_millis.saturating_sub(retain_period.as_millis() as i64);
+        let mut expired = None;
+        while let Some((flush_millis, entry_id)) = self.flushes.front().copied() {
+            if flush_millis > expire_millis {
+                break;
+            }
+            expired = Some(entry_id);
+            let _ = self.flushes.pop_front();
+        }
+        if expired.is_some() {
+            self.expired_entry_id = expired;
+        }
+        expired
+    }
+}
+
 /// Metadata and runtime status of a region.
 ///
 /// Writing and reading a region follow a single-writer-multi-reader rule:
@@ -82,12 +143,24 @@ pub(crate) struct MitoRegion {
     pub(crate) wal_options: WalOptions,
     /// Last flush time in millis.
     last_flush_millis: AtomicI64,
+    /// Number of series in memtables of the last flush.
+    last_flush_series: AtomicUsize,
+    /// Flushes whose WAL entries are retained.
+    retained_flushes: Mutex<RetainedFlushes>,
+    /// Id of the last WAL entry replicated to the standby cluster.
+    replicated_entry_id: Mutex<Option<EntryId>>,
+    /// Files flushed to the version but not to the manifest as they are still uploading.
+    unpersisted_files: Mutex<Vec<FileMeta>>,
     /// Whether the region is writable.
     writable: AtomicBool,
     /// Provider to get current time.
     time_provider: TimeProviderRef,
     /// Memtable builder for the region.
     pub(crate) memtable_builder: MemtableBuilderRef,
+    /// Sequences pinned by snapshots of the region.
+    pub(crate) snapshots: PinnedSnapshotsRef,
+    /// Watermarks to find out-of-order rows written to the region.
+    pub(crate) watermarks: Watermarks,
 }
 
 pub(crate) type MitoRegionRef = Arc<MitoRegion>;
@@ -128,6 +201,54 @@ impl MitoRegion {
         self.last_flush_millis.store(now, Ordering::Relaxed);
     }
 
+    /// Records the flush of entries up to `flushed_entry_id` if the region retains its
+    /// WAL, and returns the flushes still retained to persist in the manifest.
+    ///
+    /// Returns `None` if the region doesn't retain its WAL.
+    pub(crate) fn record_flush(&self, flushed_entry_id: EntryId) -> Option<Vec<(i64, EntryId)>> {
+        let retain_period = self.version().options.wal_retain_period?;
+        let now = self.time_provider.current_time_millis();
+        let mut retained = self.retained_flushes.lock().unwrap();
+        let _ = retained.push_and_expire(now, flushed_entry_id, retain_period);
+        Some(retained.flushes.iter().copied().collect())
+    }
+
+    /// Returns the entry id up to which WAL entries can be deleted after flushing
+    /// entries up to `flushed_entry_id`, or `None` if all entries are still retained.
+    ///
+    /// Entries are kept for the `wal.retain_period` of the region after they are
+    /// flushed, as recorded by [MitoRegion::record_flush()]. The flushes are persisted
+    /// in the manifest, so reopening the region keeps retaining their entries.
+    ///
+    /// If `retain_for_replication` is true, entries that are not replicated yet are
+    /// kept too, including all entries before the replicated entry is known.
+    pub(crate) fn obsolete_entry_id(
+        &self,
+        flushed_entry_id: EntryId,
+        retain_for_replication: bool,
+    ) -> Option<EntryId> {
+        let entry_id = match self.version().options.wal_retain_period {
+            Some(_) => self.retained_flushes.lock().unwrap().expired_entry_id?,
+            None => flushed_entry_id,
+        };
+        if !retain_for_replication {
+            return Some(entry_id);
+        }
+        let replicated_entry_id = (*self.replicated_entry_id.lock().unwrap())?;
+        Some(entry_id.min(replicated_entry_id))
+    }
+
+    /// Sets the id of the last WAL entry replicated to the standby cluster.
+    pub(crate) fn set_replicated_entry_id(&self, entry_id: EntryId) {
+        *self.replicated_entry_id.lock().unwrap() = Some(entry_id);
+    }
+
+    /// Updates the number of series in flushed memtables and returns the
+    /// number of the previous flush.
+    pub(crate) fn swap_flush_series(&self, num_series: usize) -> usize {
+        self.last_flush_series.swap(num_series, Ordering::Relaxed)
+    }
+
     /// Returns whether the region is writable.
     pub(crate) fn is_writable(&self) -> bool {
         self.writable.load(Ordering::Relaxed)
@@ -165,6 +286,17 @@ impl MitoRegion {
         }
     }
 
+    /// Returns the progress of upgrading SSTs to the current format version.
+    pub(crate) fn sst_upgrade_progress(&self) -> SstUpgradeProgress {
+        let (num_files, num_outdated_files) = self.version().ssts.num_files_and_outdated();
+
+        SstUpgradeProgress {
+            region_id: self.region_id,
+            num_files,
+            num_outdated_files,
+        }
+    }
+
     /// Estimated WAL size in bytes.
     /// Use the memtables size to estimate the size of wal.
     fn estimated_wal_usage(&self, memtable_usage: u64) -> u64 {
@@ -191,6 +323,108 @@ impl MitoRegion {
             .apply_edit(edit, memtables_to_remove, self.file_purger.clone());
         Ok(())
     }
+
+    /// Adds files of a flush that are still uploading to the region's version and
+    /// removes the flushed memtables, without writing the manifest.
+    ///
+    /// The files are marked as compacting so compactions won't remove them before
+    /// [MitoRegion::persist_staged_files()] writes them to the manifest.
+    pub(crate) fn apply_staged_edit(
+        &self,
+        file_metas: Vec<FileMeta>,
+        memtables_to_remove: &[MemtableId],
+    ) {
+        info!(
+            "Applying staged files {:?} to region {}",
+            file_metas, self.region_id
+        );
+
+        let edit = RegionEdit {
+            files_to_add: file_metas.clone(),
+            files_to_remove: Vec::new(),
+            compaction_time_window: None,
+            flushed_entry_id: None,
+            flushed_sequence: None,
+            retained_flushes: None,
+        };
+        self.version_control
+            .apply_edit(edit, memtables_to_remove, self.file_purger.clone());
+        self.set_files_compacting(&file_metas, true);
+        self.unpersisted_files.lock().unwrap().extend(file_metas);
+    }
+
+    /// Writes the staged files to the manifest once they are uploaded.
+    ///
+    /// Files removed from the version meanwhile, e.g. by truncating the region, are
+    /// skipped. The staged files are kept for the next flush if the manifest fails
+    /// to update.
+    pub(crate) async fn persist_staged_files(
+        &self,
+        flushed_entry_id: EntryId,
+        flushed_sequence: SequenceNumber,
+        retained_flushes: Option<Vec<(i64, EntryId)>>,
+    ) -> Result<()> {
+        let staged = std::mem::take(&mut *self.unpersisted_files.lock().unwrap());
+        let version = self.version();
+        let files_to_add = staged
+            .iter()
+            .filter(|file| {
+                version.ssts.levels()[file.level as usize]
+                    .files
+                    .contains_key(&file.file_id)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let edit = RegionEdit {
+            files_to_add: files_to_add.clone(),
+            files_to_remove: Vec::new(),
+            compaction_time_window: None,
+            flushed_entry_id: Some(flushed_entry_id),
+            flushed_sequence: Some(flushed_sequence),
+            retained_flushes,
+        };
+        info!("Applying {edit:?} to region {}", self.region_id);
+        let result = self
+            .manifest_manager
+            .write()
+            .await
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                edit,
+            )))
+            .await;
+        if let Err(e) = result {
+            self.unpersisted_files.lock().unwrap().extend(staged);
+            return Err(e);
+        }
+
+        // The files are already in the version.
+        let edit = RegionEdit {
+            files_to_add: Vec::new(),
+            files_to_remove: Vec::new(),
+            compaction_time_window: None,
+            flushed_entry_id: Some(flushed_entry_id),
+            flushed_sequence: Some(flushed_sequence),
+            retained_flushes: None,
+        };
+        self.version_control
+            .apply_edit(edit, &[], self.file_purger.clone());
+        self.set_files_compacting(&files_to_add, false);
+        Ok(())
+    }
+
+    /// Sets whether the files in the current version are compacting.
+    fn set_files_compacting(&self, files: &[FileMeta], compacting: bool) {
+        let version = self.version();
+        for file in files {
+            if let Some(handle) = version.ssts.levels()[file.level as usize]
+                .files
+                .get(&file.file_id)
+            {
+                handle.set_compacting(compacting);
+            }
+        }
+    }
 }
 
 /// Regions indexed by ids.
@@ -265,3 +499,24 @@ impl RegionMap {
 }
 
 pub(crate) type RegionMapRef = Arc<RegionMap>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retained_flushes() {
+        let period = Duration::from_secs(10);
+        let mut flushes = RetainedFlushes::default();
+        assert_eq!(None, flushes.push_and_expire(1000, 10, period));
+        assert_eq!(None, flushes.push_and_expire(5000, 20, period));
+        assert_eq!(Some(10), flushes.push_and_expire(11000, 30, period));
+        assert_eq!(Some(30), flushes.push_and_expire(30000, 40, period));
+        assert_eq!(1, flushes.flushes.len());
+        assert_eq!(Some(30), flushes.expired_entry_id);
+
+        assert_eq!(Some(50), flushes.push_and_expire(30000, 50, Duration::ZERO));
+        assert!(flushes.flushes.is_empty());
+        assert_eq!(Some(50), flushes.expired_entry_id);
+    }
+}
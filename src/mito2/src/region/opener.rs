@@ -15,8 +15,8 @@
 //! Region opener.
 
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicI64};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize};
+use std::sync::{Arc, Mutex};
 
 use common_telemetry::{debug, error, info, warn};
 use common_wal::options::WalOptions;
@@ -41,7 +41,8 @@ use crate::memtable::time_partition::TimePartitions;
 use crate::memtable::MemtableBuilderProvider;
 use crate::region::options::RegionOptions;
 use crate::region::version::{VersionBuilder, VersionControl, VersionControlRef};
-use crate::region::MitoRegion;
+use crate::region::watermark::Watermarks;
+use crate::region::{MitoRegion, RetainedFlushes};
 use crate::region_write_ctx::RegionWriteCtx;
 use crate::request::OptionOutputTx;
 use crate::schedule::scheduler::SchedulerRef;
@@ -174,7 +175,7 @@ impl RegionOpener {
 
         let memtable_builder = self
             .memtable_builder_provider
-            .builder_for_options(options.memtable.as_ref(), !options.append_mode);
+            .builder_for_options(options.memtable.as_ref(), options.memtable_dedup());
         // Initial memtable id is 0.
         let part_duration = options.compaction.time_window();
         let mutable = Arc::new(TimePartitions::new(
@@ -211,10 +212,16 @@ impl RegionOpener {
             )),
             wal_options,
             last_flush_millis: AtomicI64::new(time_provider.current_time_millis()),
+            last_flush_series: AtomicUsize::new(0),
+            retained_flushes: Mutex::new(RetainedFlushes::default()),
+            replicated_entry_id: Mutex::new(None),
+            unpersisted_files: Mutex::new(Vec::new()),
             // Region is writable after it is created.
             writable: AtomicBool::new(true),
             time_provider,
             memtable_builder,
+            snapshots: Arc::default(),
+            watermarks: Watermarks::default(),
         })
     }
 
@@ -255,7 +262,7 @@ impl RegionOpener {
         config: &MitoConfig,
         wal: &Wal<S>,
     ) -> Result<Option<MitoRegion>> {
-        let region_options = self.options.as_ref().unwrap().clone();
+        let mut region_options = self.options.as_ref().unwrap().clone();
         let wal_options = region_options.wal_options.clone();
 
         let region_manifest_options = self.manifest_options(config, &region_options)?;
@@ -266,11 +273,17 @@ impl RegionOpener {
 
         let manifest = manifest_manager.manifest();
         let metadata = manifest.metadata.clone();
+        if !manifest.altered_options.is_empty() {
+            region_options = region_options.with_altered(&manifest.altered_options)?;
+        }
 
         let region_id = self.region_id;
         let object_store = self.object_store(&region_options.storage)?.clone();
 
-        debug!("Open region {} with options: {:?}", region_id, self.options);
+        debug!(
+            "Open region {} with options: {:?}",
+            region_id, region_options
+        );
 
         let access_layer = Arc::new(AccessLayer::new(
             self.region_dir.clone(),
@@ -284,7 +297,7 @@ impl RegionOpener {
         ));
         let memtable_builder = self.memtable_builder_provider.builder_for_options(
             region_options.memtable.as_ref(),
-            !region_options.append_mode,
+            region_options.memtable_dedup(),
         );
         // Initial memtable id is 0.
         let part_duration = region_options.compaction.time_window();
@@ -335,10 +348,16 @@ impl RegionOpener {
             file_purger,
             wal_options,
             last_flush_millis: AtomicI64::new(time_provider.current_time_millis()),
+            last_flush_series: AtomicUsize::new(0),
+            retained_flushes: Mutex::new(RetainedFlushes::new(manifest.retained_flushes.clone())),
+            replicated_entry_id: Mutex::new(None),
+            unpersisted_files: Mutex::new(Vec::new()),
             // Region is always opened in read only mode.
             writable: AtomicBool::new(false),
             time_provider,
             memtable_builder,
+            snapshots: Arc::default(),
+            watermarks: Watermarks::default(),
         };
         Ok(Some(region))
     }
@@ -50,10 +50,40 @@ pub struct RegionOptions {
     pub append_mode: bool,
     /// Wal options.
     pub wal_options: WalOptions,
+    /// How long to keep WAL entries after flush for recovery. `None` deletes them
+    /// once flushed.
+    pub wal_retain_period: Option<Duration>,
     /// Index options.
     pub index_options: IndexOptions,
     /// Memtable options.
     pub memtable: Option<MemtableOptions>,
+    /// Max disk space of SST files of the table. The engine rejects write requests
+    /// to the table once its regions on the engine use more space.
+    pub disk_quota: Option<ReadableSize>,
+    /// How to merge rows with the same primary key and timestamp.
+    pub merge_mode: MergeMode,
+    /// How to handle rows older than the watermarks of their series.
+    pub out_of_order: OutOfOrderOptions,
+    /// Max rate to write the table.
+    pub write_rate_limit: WriteRateLimitOptions,
+}
+
+impl RegionOptions {
+    /// Returns true if memtables of the region should remove duplicate rows.
+    ///
+    /// Memtables keep duplicate rows under the `last_non_null` merge mode so
+    /// readers can fill null fields from older rows.
+    pub(crate) fn memtable_dedup(&self) -> bool {
+        !self.append_mode && self.merge_mode == MergeMode::LastRow
+    }
+
+    /// Returns the options with options altered by `altered` replaced.
+    ///
+    /// Only compaction options can be altered, and they replace all compaction options.
+    pub(crate) fn with_altered(mut self, altered: &HashMap<String, String>) -> Result<Self> {
+        self.compaction = RegionOptions::try_from(altered)?.compaction;
+        Ok(self)
+    }
 }
 
 impl TryFrom<&HashMap<String, String>> for RegionOptions {
@@ -89,18 +119,114 @@ impl TryFrom<&HashMap<String, String>> for RegionOptions {
             None
         };
 
+        ensure!(
+            !(options.append_mode && options.merge_mode == MergeMode::LastNonNull),
+            InvalidRegionOptionsSnafu {
+                reason: "merge_mode last_non_null is not allowed under append mode",
+            }
+        );
+        ensure!(
+            options.out_of_order_policy == OutOfOrderPolicy::Accept
+                || options.out_of_order_window.is_some(),
+            InvalidRegionOptionsSnafu {
+                reason: "out_of_order.window is required by the out_of_order.policy",
+            }
+        );
+        ensure!(
+            (options.out_of_order_policy == OutOfOrderPolicy::DeadLetter)
+                == options.out_of_order_dead_letter_table.is_some(),
+            InvalidRegionOptionsSnafu {
+                reason: "out_of_order.dead_letter_table is required by and only allowed under the dead_letter policy",
+            }
+        );
+
         Ok(RegionOptions {
             ttl: options.ttl,
             compaction,
             storage: options.storage,
             append_mode: options.append_mode,
             wal_options,
+            wal_retain_period: options.wal_retain_period,
             index_options,
             memtable,
+            disk_quota: options.disk_quota,
+            merge_mode: options.merge_mode,
+            out_of_order: OutOfOrderOptions {
+                window: options.out_of_order_window,
+                policy: options.out_of_order_policy,
+                dead_letter_table: options.out_of_order_dead_letter_table,
+            },
+            write_rate_limit: WriteRateLimitOptions {
+                rows_per_second: options.write_rate_limit_rows_per_second,
+                bytes_per_second: options.write_rate_limit_bytes_per_second,
+            },
         })
     }
 }
 
+/// Mode to merge rows with the same primary key and timestamp.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// Keeps the last row.
+    #[default]
+    LastRow,
+    /// Keeps the last row but fills its null fields by the last non-null
+    /// values of older rows.
+    LastNonNull,
+}
+
+/// Options to handle out-of-order rows.
+///
+/// A row is out of order if its timestamp is older than the watermark of its series
+/// minus the `window`. See [Watermarks](crate::region::watermark::Watermarks) for how
+/// watermarks are tracked.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct OutOfOrderOptions {
+    /// Tolerance of out-of-order rows. Required by policies other than `accept`.
+    #[serde(with = "humantime_serde")]
+    pub window: Option<Duration>,
+    /// What to do with out-of-order rows.
+    pub policy: OutOfOrderPolicy,
+    /// Table to write out-of-order rows to under the `dead_letter` policy. The name
+    /// may be qualified by a schema, otherwise it's in the schema of the write.
+    pub dead_letter_table: Option<String>,
+}
+
+/// Max rate to write a table on the engine.
+///
+/// Regions of the table on the same engine share the limit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct WriteRateLimitOptions {
+    /// Max rows to write per second.
+    pub rows_per_second: Option<u64>,
+    /// Max bytes to write per second.
+    pub bytes_per_second: Option<ReadableSize>,
+}
+
+impl WriteRateLimitOptions {
+    /// Returns true if there is no limit.
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.rows_per_second.is_none() && self.bytes_per_second.is_none()
+    }
+}
+
+/// Policy to handle out-of-order rows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutOfOrderPolicy {
+    /// Accepts out-of-order rows. Memtables partition rows by time so these rows
+    /// are written to the memtable of their own time partition.
+    #[default]
+    Accept,
+    /// Rejects write requests containing out-of-order rows.
+    Reject,
+    /// Writes out-of-order rows to the dead-letter table instead of the region.
+    DeadLetter,
+}
+
 /// Options for compactions
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(tag = "compaction.type")]
@@ -109,12 +235,16 @@ pub enum CompactionOptions {
     /// Time window compaction strategy.
     #[serde(with = "prefix_twcs")]
     Twcs(TwcsOptions),
+    /// Size-tiered compaction strategy.
+    #[serde(with = "prefix_size_tiered")]
+    SizeTiered(SizeTieredOptions),
 }
 
 impl CompactionOptions {
     pub(crate) fn time_window(&self) -> Option<Duration> {
         match self {
             CompactionOptions::Twcs(opts) => opts.time_window,
+            CompactionOptions::SizeTiered(_) => None,
         }
     }
 }
@@ -167,6 +297,37 @@ impl Default for TwcsOptions {
     }
 }
 
+/// Size-tiered compaction options.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SizeTieredOptions {
+    /// Min num of files in a tier to trigger a compaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub min_threshold: usize,
+    /// Max num of files to merge in one compaction output.
+    #[serde_as(as = "DisplayFromStr")]
+    pub max_threshold: usize,
+    /// Files smaller than this size are in the smallest tier.
+    pub min_file_size: ReadableSize,
+    /// Size ratio between two adjacent tiers.
+    #[serde_as(as = "DisplayFromStr")]
+    pub tier_ratio: usize,
+}
+
+with_prefix!(prefix_size_tiered "compaction.size_tiered.");
+
+impl Default for SizeTieredOptions {
+    fn default() -> Self {
+        Self {
+            min_threshold: 4,
+            max_threshold: 32,
+            min_file_size: ReadableSize::mb(32),
+            tier_ratio: 4,
+        }
+    }
+}
+
 /// We need to define a new struct without enum fields as `#[serde(default)]` does not
 /// support external tagging.
 #[serde_as]
@@ -179,6 +340,23 @@ struct RegionOptionsWithoutEnum {
     storage: Option<String>,
     #[serde_as(as = "DisplayFromStr")]
     append_mode: bool,
+    #[serde(with = "humantime_serde")]
+    #[serde(rename = "wal.retain_period")]
+    wal_retain_period: Option<Duration>,
+    disk_quota: Option<ReadableSize>,
+    merge_mode: MergeMode,
+    #[serde(with = "humantime_serde")]
+    #[serde(rename = "out_of_order.window")]
+    out_of_order_window: Option<Duration>,
+    #[serde(rename = "out_of_order.policy")]
+    out_of_order_policy: OutOfOrderPolicy,
+    #[serde(rename = "out_of_order.dead_letter_table")]
+    out_of_order_dead_letter_table: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(rename = "write_rate_limit.rows_per_second")]
+    write_rate_limit_rows_per_second: Option<u64>,
+    #[serde(rename = "write_rate_limit.bytes_per_second")]
+    write_rate_limit_bytes_per_second: Option<ReadableSize>,
 }
 
 impl Default for RegionOptionsWithoutEnum {
@@ -188,6 +366,14 @@ impl Default for RegionOptionsWithoutEnum {
             ttl: options.ttl,
             storage: options.storage,
             append_mode: options.append_mode,
+            wal_retain_period: options.wal_retain_period,
+            disk_quota: options.disk_quota,
+            merge_mode: options.merge_mode,
+            out_of_order_window: options.out_of_order.window,
+            out_of_order_policy: options.out_of_order.policy,
+            out_of_order_dead_letter_table: options.out_of_order.dead_letter_table,
+            write_rate_limit_rows_per_second: options.write_rate_limit.rows_per_second,
+            write_rate_limit_bytes_per_second: options.write_rate_limit.bytes_per_second,
         }
     }
 }
@@ -407,6 +593,26 @@ mod tests {
         assert_eq!(expect, options);
     }
 
+    #[test]
+    fn test_with_size_tiered_compaction() {
+        let map = make_map(&[
+            ("compaction.size_tiered.min_threshold", "8"),
+            ("compaction.size_tiered.min_file_size", "64MB"),
+            ("compaction.type", "size_tiered"),
+        ]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            compaction: CompactionOptions::SizeTiered(SizeTieredOptions {
+                min_threshold: 8,
+                min_file_size: ReadableSize::mb(64),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+        assert_eq!(None, options.compaction.time_window());
+    }
+
     fn test_with_wal_options(wal_options: &WalOptions) -> bool {
         let encoded_wal_options = serde_json::to_string(&wal_options).unwrap();
         let map = make_map(&[(WAL_OPTIONS_KEY, &encoded_wal_options)]);
@@ -500,6 +706,13 @@ mod tests {
             ("memtable.partition_tree.index_max_keys_per_shard", "2048"),
             ("memtable.partition_tree.data_freeze_threshold", "2048"),
             ("memtable.partition_tree.fork_dictionary_bytes", "128M"),
+            ("wal.retain_period", "1d"),
+            ("disk_quota", "10GB"),
+            ("merge_mode", "last_row"),
+            ("out_of_order.window", "1h"),
+            ("out_of_order.policy", "reject"),
+            ("write_rate_limit.rows_per_second", "10000"),
+            ("write_rate_limit.bytes_per_second", "10MB"),
         ]);
         let options = RegionOptions::try_from(&map).unwrap();
         let expect = RegionOptions {
@@ -512,6 +725,7 @@ mod tests {
             storage: Some("S3".to_string()),
             append_mode: true,
             wal_options,
+            wal_retain_period: Some(Duration::from_secs(3600 * 24)),
             index_options: IndexOptions {
                 inverted_index: InvertedIndexOptions {
                     ignore_column_ids: vec![1, 2, 3],
@@ -523,7 +737,92 @@ mod tests {
                 data_freeze_threshold: 2048,
                 fork_dictionary_bytes: ReadableSize::mb(128),
             })),
+            disk_quota: Some(ReadableSize::gb(10)),
+            merge_mode: MergeMode::LastRow,
+            out_of_order: OutOfOrderOptions {
+                window: Some(Duration::from_secs(3600)),
+                policy: OutOfOrderPolicy::Reject,
+                dead_letter_table: None,
+            },
+            write_rate_limit: WriteRateLimitOptions {
+                rows_per_second: Some(10000),
+                bytes_per_second: Some(ReadableSize::mb(10)),
+            },
         };
         assert_eq!(expect, options);
     }
+
+    #[test]
+    fn test_with_merge_mode() {
+        let map = make_map(&[("merge_mode", "last_non_null")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(MergeMode::LastNonNull, options.merge_mode);
+
+        let map = make_map(&[("merge_mode", "first_row")]);
+        let err = RegionOptions::try_from(&map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        let map = make_map(&[("merge_mode", "last_non_null"), ("append_mode", "true")]);
+        let err = RegionOptions::try_from(&map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
+    #[test]
+    fn test_with_out_of_order() {
+        let map = make_map(&[
+            ("out_of_order.policy", "dead_letter"),
+            ("out_of_order.window", "10m"),
+            ("out_of_order.dead_letter_table", "late_rows"),
+        ]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(
+            OutOfOrderOptions {
+                window: Some(Duration::from_secs(600)),
+                policy: OutOfOrderPolicy::DeadLetter,
+                dead_letter_table: Some("late_rows".to_string()),
+            },
+            options.out_of_order
+        );
+
+        let map = make_map(&[("out_of_order.window", "10m")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(
+            OutOfOrderOptions {
+                window: Some(Duration::from_secs(600)),
+                policy: OutOfOrderPolicy::Accept,
+                dead_letter_table: None,
+            },
+            options.out_of_order
+        );
+
+        // The window is required.
+        let map = make_map(&[("out_of_order.policy", "reject")]);
+        let err = RegionOptions::try_from(&map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        // The dead-letter table is required.
+        let map = make_map(&[
+            ("out_of_order.policy", "dead_letter"),
+            ("out_of_order.window", "10m"),
+        ]);
+        let err = RegionOptions::try_from(&map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
+    #[test]
+    fn test_with_write_rate_limit() {
+        let map = make_map(&[("write_rate_limit.rows_per_second", "100")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(
+            WriteRateLimitOptions {
+                rows_per_second: Some(100),
+                bytes_per_second: None,
+            },
+            options.write_rate_limit
+        );
+
+        let map = make_map(&[("write_rate_limit.rows_per_second", "a lot")]);
+        let err = RegionOptions::try_from(&map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
 }
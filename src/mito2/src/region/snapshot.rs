@@ -0,0 +1,183 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Snapshots of a region pinned at a sequence.
+//!
+//! Memtables and compaction deduplicate rows with the same key, so the version of a
+//! row visible at an older sequence may be gone. A region keeps the versions of rows
+//! visible at its pinned sequences:
+//! - The worker freezes the mutable memtable when it pins a sequence, so rows written
+//!   before and after the sequence are never in the same memtable or flushed SST.
+//! - The region doesn't compact while any sequence is pinned.
+//!
+//! A pin expires after `max_snapshot_pin_duration` of the engine config, so a leaked
+//! snapshot can't block compaction and the purge of expired SSTs forever.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use common_telemetry::warn;
+use store_api::storage::SequenceNumber;
+
+/// Sequences pinned by snapshots of a region, with the deadlines of the snapshots of each.
+#[derive(Debug, Default)]
+pub(crate) struct PinnedSnapshots(Mutex<BTreeMap<SequenceNumber, Vec<Instant>>>);
+
+pub(crate) type PinnedSnapshotsRef = Arc<PinnedSnapshots>;
+
+impl PinnedSnapshots {
+    /// Pins `sequence` until the returned snapshot is dropped or `max_duration` elapses.
+    pub(crate) fn pin(
+        self: &Arc<Self>,
+        sequence: SequenceNumber,
+        max_duration: Duration,
+    ) -> RegionSnapshot {
+        let deadline = Instant::now() + max_duration;
+        self.0
+            .lock()
+            .unwrap()
+            .entry(sequence)
+            .or_default()
+            .push(deadline);
+
+        RegionSnapshot {
+            sequence,
+            deadline,
+            pinned: self.clone(),
+        }
+    }
+
+    /// Returns true if `sequence` is pinned by a snapshot that hasn't expired.
+    pub(crate) fn is_pinned(&self, sequence: SequenceNumber) -> bool {
+        let mut pinned = self.0.lock().unwrap();
+        Self::release_expired(&mut pinned);
+        pinned.contains_key(&sequence)
+    }
+
+    /// Returns true if no sequence is pinned by a snapshot that hasn't expired.
+    pub(crate) fn is_empty(&self) -> bool {
+        let mut pinned = self.0.lock().unwrap();
+        Self::release_expired(&mut pinned);
+        pinned.is_empty()
+    }
+
+    /// Releases pins past their deadlines, even if their snapshots are alive.
+    fn release_expired(pinned: &mut BTreeMap<SequenceNumber, Vec<Instant>>) {
+        let now = Instant::now();
+        pinned.retain(|sequence, deadlines| {
+            let before = deadlines.len();
+            deadlines.retain(|deadline| *deadline > now);
+            if deadlines.len() < before {
+                warn!(
+                    "Release {} expired snapshots pinned at sequence {}",
+                    before - deadlines.len(),
+                    sequence
+                );
+            }
+            !deadlines.is_empty()
+        });
+    }
+
+    fn unpin(&self, sequence: SequenceNumber, deadline: Instant) {
+        let mut pinned = self.0.lock().unwrap();
+        if let Some(deadlines) = pinned.get_mut(&sequence) {
+            // The pin is gone if it has already expired.
+            if let Some(pos) = deadlines.iter().position(|d| *d == deadline) {
+                deadlines.swap_remove(pos);
+            }
+            if deadlines.is_empty() {
+                pinned.remove(&sequence);
+            }
+        }
+    }
+}
+
+/// Snapshot of a region at a sequence.
+///
+/// Scan requests with the sequence of the snapshot read the rows visible at that
+/// sequence as long as the snapshot is alive and hasn't expired. The region releases
+/// the snapshot when it is dropped, when it expires, or when the region is reopened.
+#[derive(Debug)]
+pub struct RegionSnapshot {
+    sequence: SequenceNumber,
+    deadline: Instant,
+    pinned: PinnedSnapshotsRef,
+}
+
+impl RegionSnapshot {
+    /// Returns the sequence of the snapshot.
+    pub fn sequence(&self) -> SequenceNumber {
+        self.sequence
+    }
+
+    /// Returns true if the snapshot has expired, so scans with its sequence may fail.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+impl Drop for RegionSnapshot {
+    fn drop(&mut self) {
+        self.pinned.unpin(self.sequence, self.deadline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_snapshots() {
+        let pinned = Arc::new(PinnedSnapshots::default());
+        assert!(pinned.is_empty());
+
+        let max_duration = Duration::from_secs(60);
+        let first = pinned.pin(10, max_duration);
+        let second = pinned.pin(10, max_duration);
+        let third = pinned.pin(20, max_duration);
+        assert_eq!(10, first.sequence());
+        assert!(pinned.is_pinned(10));
+        assert!(pinned.is_pinned(20));
+        assert!(!pinned.is_pinned(15));
+
+        drop(first);
+        assert!(pinned.is_pinned(10));
+        drop(second);
+        assert!(!pinned.is_pinned(10));
+        drop(third);
+        assert!(pinned.is_empty());
+    }
+
+    #[test]
+    fn test_expired_snapshots() {
+        let pinned = Arc::new(PinnedSnapshots::default());
+
+        let alive = pinned.pin(10, Duration::from_secs(60));
+        let expired = pinned.pin(20, Duration::ZERO);
+        assert!(!alive.is_expired());
+        assert!(expired.is_expired());
+        assert!(pinned.is_pinned(10));
+        assert!(!pinned.is_pinned(20));
+
+        drop(alive);
+        assert!(pinned.is_empty());
+        // Dropping an expired snapshot releases nothing else.
+        let another = pinned.pin(20, Duration::from_secs(60));
+        drop(expired);
+        assert!(pinned.is_pinned(20));
+        drop(another);
+        assert!(pinned.is_empty());
+    }
+}
@@ -26,6 +26,7 @@
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+use common_time::Timestamp;
 use store_api::metadata::RegionMetadataRef;
 use store_api::storage::SequenceNumber;
 
@@ -168,6 +169,19 @@ impl VersionControl {
         version_data.version = new_version;
     }
 
+    /// Alter options of the region.
+    pub(crate) fn alter_options(&self, options: RegionOptions) {
+        let version = self.current().version;
+        let new_version = Arc::new(
+            VersionBuilder::from_version(version)
+                .options(options)
+                .build(),
+        );
+
+        let mut version_data = self.data.write().unwrap();
+        version_data.version = new_version;
+    }
+
     /// Truncate current version.
     pub(crate) fn truncate(
         &self,
@@ -248,6 +262,30 @@ pub(crate) struct Version {
 
 pub(crate) type VersionRef = Arc<Version>;
 
+impl Version {
+    /// Returns the max timestamp of rows in memtables and SST files.
+    ///
+    /// Returns `None` if the region has no data.
+    pub(crate) fn watermark(&self) -> Option<Timestamp> {
+        let memtable_max = self
+            .memtables
+            .list_memtables()
+            .iter()
+            .filter_map(|memtable| memtable.stats().time_range())
+            .map(|(_, max)| max)
+            .max();
+        let file_max = self
+            .ssts
+            .levels()
+            .iter()
+            .flat_map(|level| level.files())
+            .map(|file| file.time_range().1)
+            .max();
+
+        memtable_max.max(file_max)
+    }
+}
+
 /// Version builder.
 pub(crate) struct VersionBuilder {
     metadata: RegionMetadataRef,
@@ -0,0 +1,152 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watermarks of a region to find out-of-order rows.
+//!
+//! The watermark of a series is the max timestamp of rows written to the series since
+//! the region is opened. Series not written since then use the watermark of the region,
+//! which starts from the max timestamp in memtables and SST files of the region.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use api::v1::value::ValueData;
+use api::v1::{Rows, SemanticType};
+use common_time::Timestamp;
+use prost::Message;
+use snafu::ensure;
+use store_api::storage::RegionId;
+
+use crate::error::{OutOfOrderWriteSnafu, Result};
+use crate::region::version::Version;
+
+/// Watermarks of a region, loaded from the version on first use.
+#[derive(Debug, Default)]
+pub(crate) struct Watermarks(Mutex<Option<WatermarksInner>>);
+
+#[derive(Debug, Default)]
+struct WatermarksInner {
+    /// Max timestamp of the region.
+    region: Option<Timestamp>,
+    /// Max timestamp of each series, keyed by its encoded tag values.
+    series: HashMap<Vec<u8>, Timestamp>,
+}
+
+impl Watermarks {
+    /// Returns indices of `rows` older than the watermarks of their series minus the
+    /// `window`, and advances the watermarks by other rows.
+    ///
+    /// If `reject` is true, returns an error on out-of-order rows and leaves the
+    /// watermarks unchanged.
+    pub(crate) fn advance(
+        &self,
+        region_id: RegionId,
+        version: &Version,
+        rows: &Rows,
+        window: Duration,
+        reject: bool,
+    ) -> Result<Vec<usize>> {
+        let Some(time_index) = rows
+            .schema
+            .iter()
+            .position(|column| column.semantic_type == SemanticType::Timestamp as i32)
+        else {
+            return Ok(Vec::new());
+        };
+        let tags = rows
+            .schema
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.semantic_type == SemanticType::Tag as i32)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        let mut inner = self.0.lock().unwrap();
+        let inner = inner.get_or_insert_with(|| WatermarksInner {
+            region: version.watermark(),
+            series: HashMap::new(),
+        });
+
+        // Watermarks advanced by the rows, applied after all rows are checked.
+        let mut advanced = HashMap::new();
+        let mut region = inner.region;
+        let mut out_of_order = Vec::new();
+        for (index, row) in rows.rows.iter().enumerate() {
+            let Some(timestamp) = row
+                .values
+                .get(time_index)
+                .and_then(|value| value.value_data.as_ref())
+                .and_then(timestamp_of_value)
+            else {
+                continue;
+            };
+            let mut key = Vec::new();
+            for tag in &tags {
+                if let Some(value) = row.values.get(*tag) {
+                    key.extend(value.encode_length_delimited_to_vec());
+                }
+            }
+
+            let series_watermark = advanced
+                .get(&key)
+                .or_else(|| inner.series.get(&key))
+                .copied();
+            let watermark = series_watermark.or(region);
+            // The watermark is too small to have out-of-order rows if it underflows.
+            if let Some(threshold) = watermark.and_then(|w| w.sub_duration(window).ok()) {
+                if timestamp < threshold {
+                    ensure!(
+                        !reject,
+                        OutOfOrderWriteSnafu {
+                            region_id,
+                            timestamp,
+                            // Safety: the threshold comes from the watermark.
+                            watermark: watermark.unwrap(),
+                            window,
+                        }
+                    );
+                    out_of_order.push(index);
+                    continue;
+                }
+            }
+
+            if series_watermark.map_or(true, |w| timestamp > w) {
+                let _ = advanced.insert(key, timestamp);
+            }
+            if region.map_or(true, |w| timestamp > w) {
+                region = Some(timestamp);
+            }
+        }
+
+        inner.series.extend(advanced);
+        inner.region = region;
+        Ok(out_of_order)
+    }
+
+    /// Forgets all watermarks, such as after the region is truncated.
+    pub(crate) fn reset(&self) {
+        *self.0.lock().unwrap() = Some(WatermarksInner::default());
+    }
+}
+
+fn timestamp_of_value(value: &ValueData) -> Option<Timestamp> {
+    match value {
+        ValueData::TimestampSecondValue(v) => Some(Timestamp::new_second(*v)),
+        ValueData::TimestampMillisecondValue(v) => Some(Timestamp::new_millisecond(*v)),
+        ValueData::TimestampMicrosecondValue(v) => Some(Timestamp::new_microsecond(*v)),
+        ValueData::TimestampNanosecondValue(v) => Some(Timestamp::new_nanosecond(*v)),
+        _ => None,
+    }
+}
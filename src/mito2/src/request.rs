@@ -32,9 +32,9 @@ use snafu::{ensure, OptionExt, ResultExt};
 use store_api::metadata::{ColumnMetadata, RegionMetadata};
 use store_api::region_engine::SetReadonlyResponse;
 use store_api::region_request::{
-    AffectedRows, RegionAlterRequest, RegionCatchupRequest, RegionCloseRequest,
+    AffectedRows, InsertMode, RegionAlterRequest, RegionCatchupRequest, RegionCloseRequest,
     RegionCompactRequest, RegionCreateRequest, RegionDropRequest, RegionFlushRequest,
-    RegionOpenRequest, RegionRequest, RegionTruncateRequest,
+    RegionOpenRequest, RegionReplayWalRequest, RegionRequest, RegionTruncateRequest,
 };
 use store_api::storage::{RegionId, SequenceNumber};
 use tokio::sync::oneshot::{self, Receiver, Sender};
@@ -46,9 +46,11 @@ use crate::error::{
 use crate::manifest::action::RegionEdit;
 use crate::memtable::MemtableId;
 use crate::metrics::COMPACTION_ELAPSED_TOTAL;
+use crate::region::snapshot::RegionSnapshot;
 use crate::sst::file::FileMeta;
 use crate::sst::file_purger::{FilePurgerRef, PurgeRequest};
 use crate::wal::EntryId;
+use crate::worker::ExistingRowsRead;
 
 /// Request to write a region.
 #[derive(Debug)]
@@ -59,6 +61,8 @@ pub struct WriteRequest {
     pub op_type: OpType,
     /// Rows to write.
     pub rows: Rows,
+    /// How put rows whose primary key and timestamp already exist are treated.
+    pub insert_mode: InsertMode,
     /// Map column name to column index in `rows`.
     name_to_index: HashMap<String, usize>,
     /// Whether each column has null.
@@ -110,11 +114,19 @@ impl WriteRequest {
             region_id,
             op_type,
             rows,
+            insert_mode: InsertMode::Overwrite,
             name_to_index,
             has_null,
         })
     }
 
+    /// Sets the insert mode of the request.
+    #[must_use]
+    pub fn with_insert_mode(mut self, insert_mode: InsertMode) -> Self {
+        self.insert_mode = insert_mode;
+        self
+    }
+
     /// Returns estimated size of the request.
     pub(crate) fn estimated_size(&self) -> usize {
         let row_size = self
@@ -498,6 +510,12 @@ pub(crate) enum WorkerRequest {
         edit: RegionEdit,
         tx: Sender<Result<()>>,
     },
+
+    /// Pins a snapshot of a region at its committed sequence.
+    PinSnapshot {
+        region_id: RegionId,
+        sender: Sender<Result<RegionSnapshot>>,
+    },
 }
 
 impl WorkerRequest {
@@ -509,7 +527,8 @@ impl WorkerRequest {
         let (sender, receiver) = oneshot::channel();
         let worker_request = match value {
             RegionRequest::Put(v) => {
-                let write_request = WriteRequest::new(region_id, OpType::Put, v.rows)?;
+                let write_request = WriteRequest::new(region_id, OpType::Put, v.rows)?
+                    .with_insert_mode(v.insert_mode);
                 WorkerRequest::Write(SenderWriteRequest {
                     sender: sender.into(),
                     request: write_request,
@@ -567,6 +586,11 @@ impl WorkerRequest {
                 sender: sender.into(),
                 request: DdlRequest::Catchup(v),
             }),
+            RegionRequest::ReplayWal(v) => WorkerRequest::Ddl(SenderDdlRequest {
+                region_id,
+                sender: sender.into(),
+                request: DdlRequest::ReplayWal(v),
+            }),
         };
 
         Ok((worker_request, receiver))
@@ -596,6 +620,7 @@ pub(crate) enum DdlRequest {
     Compact(RegionCompactRequest),
     Truncate(RegionTruncateRequest),
     Catchup(RegionCatchupRequest),
+    ReplayWal(RegionReplayWalRequest),
 }
 
 /// Sender and Ddl request.
@@ -612,6 +637,8 @@ pub(crate) struct SenderDdlRequest {
 /// Notification from a background job.
 #[derive(Debug)]
 pub(crate) enum BackgroundNotify {
+    /// Flush has written SSTs that are still uploading.
+    FlushStaged(FlushStaged),
     /// Flush has finished.
     FlushFinished(FlushFinished),
     /// Flush has failed.
@@ -620,6 +647,8 @@ pub(crate) enum BackgroundNotify {
     CompactionFinished(CompactionFinished),
     /// Compaction has failed.
     CompactionFailed(CompactionFailed),
+    /// Existing rows of a region are read for write requests.
+    ExistingRowsRead(ExistingRowsRead),
 }
 
 /// Notifies a flush job is finished.
@@ -635,6 +664,10 @@ pub(crate) struct FlushFinished {
     pub(crate) flushed_sequence: SequenceNumber,
     /// Id of memtables to remove.
     pub(crate) memtables_to_remove: SmallVec<[MemtableId; 2]>,
+    /// Number of series in flushed memtables.
+    pub(crate) num_series: usize,
+    /// Whether the SSTs are already applied to the version by a [FlushStaged].
+    pub(crate) staged: bool,
     /// Flush result senders.
     pub(crate) senders: Vec<OutputTx>,
     /// File purger for cleaning files on failure.
@@ -669,6 +702,37 @@ impl OnFailure for FlushFinished {
     }
 }
 
+/// Notifies a flush job has written SSTs that are still uploading to the object store.
+///
+/// The worker adds the SSTs to the version to release the memtables, and writes them
+/// to the manifest once the job sends the [FlushFinished] after the uploads.
+#[derive(Debug)]
+pub(crate) struct FlushStaged {
+    /// Region id.
+    pub(crate) region_id: RegionId,
+    /// Meta of the staged SSTs.
+    pub(crate) file_metas: Vec<FileMeta>,
+    /// Entry id of flushed data.
+    pub(crate) flushed_entry_id: EntryId,
+    /// Id of memtables to remove.
+    pub(crate) memtables_to_remove: SmallVec<[MemtableId; 2]>,
+    /// Number of series in flushed memtables.
+    pub(crate) num_series: usize,
+    /// File purger for cleaning files on failure.
+    pub(crate) file_purger: FilePurgerRef,
+}
+
+impl OnFailure for FlushStaged {
+    fn on_failure(&mut self, _err: Error) {
+        // Clean staged files. The flush job notifies waiters.
+        for file in &self.file_metas {
+            self.file_purger.send_request(PurgeRequest {
+                file_meta: file.clone(),
+            });
+        }
+    }
+}
+
 /// Notifies a flush job is failed.
 #[derive(Debug)]
 pub(crate) struct FlushFailed {
@@ -23,6 +23,16 @@ pub mod location;
 pub mod parquet;
 pub(crate) mod version;
 
+/// Current version of the SST format.
+///
+/// Bumps it once the format of new SSTs changes, e.g. new statistics or encodings,
+/// so the engine knows which files to upgrade.
+pub const SST_FORMAT_VERSION: u32 = 1;
+
+/// Version of SSTs written before the engine records the version. They are in the
+/// same format as version 1, so they are not upgraded until the format changes.
+pub(crate) const SST_INITIAL_FORMAT_VERSION: u32 = 1;
+
 /// Default write buffer size, it should be greater than the default minimum upload part of S3 (5mb).
 pub const DEFAULT_WRITE_BUFFER_SIZE: ReadableSize = ReadableSize::mb(8);
 
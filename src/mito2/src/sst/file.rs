@@ -27,7 +27,7 @@ use store_api::storage::RegionId;
 use uuid::Uuid;
 
 use crate::sst::file_purger::{FilePurgerRef, PurgeRequest};
-use crate::sst::location;
+use crate::sst::{location, SST_FORMAT_VERSION, SST_INITIAL_FORMAT_VERSION};
 
 /// Type to store SST level.
 pub type Level = u8;
@@ -100,6 +100,16 @@ pub struct FileMeta {
     pub available_indexes: SmallVec<[IndexType; 4]>,
     /// Size of the index file.
     pub index_file_size: u64,
+    /// Version of the SST format.
+    ///
+    /// Files written before the engine records the version are
+    /// [SST_INITIAL_FORMAT_VERSION].
+    #[serde(default = "initial_format_version")]
+    pub format_version: u32,
+}
+
+fn initial_format_version() -> u32 {
+    SST_INITIAL_FORMAT_VERSION
 }
 
 /// Type of index.
@@ -162,6 +172,21 @@ impl FileHandle {
         self.inner.meta.time_range
     }
 
+    /// Returns the size of the file in bytes.
+    pub fn size(&self) -> u64 {
+        self.inner.meta.file_size
+    }
+
+    /// Returns the format version of the file.
+    pub fn format_version(&self) -> u32 {
+        self.inner.meta.format_version
+    }
+
+    /// Returns true if the file is written in an old format version.
+    pub fn is_outdated(&self) -> bool {
+        self.inner.meta.format_version < SST_FORMAT_VERSION
+    }
+
     /// Mark the file as deleted and will delete it on drop asynchronously
     pub fn mark_deleted(&self) {
         self.inner.deleted.store(true, Ordering::Relaxed);
@@ -255,6 +280,7 @@ mod tests {
             file_size: 0,
             available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
             index_file_size: 0,
+            format_version: SST_FORMAT_VERSION,
         }
     }
 
@@ -271,10 +297,14 @@ mod tests {
         let json_file_meta = "{\"region_id\":0,\"file_id\":\"bc5896ec-e4d8-4017-a80d-f2de73188d55\",\
         \"time_range\":[{\"value\":0,\"unit\":\"Millisecond\"},{\"value\":0,\"unit\":\"Millisecond\"}],\
         \"available_indexes\":[\"InvertedIndex\"],\"level\":0}";
-        let file_meta = create_file_meta(
-            FileId::from_str("bc5896ec-e4d8-4017-a80d-f2de73188d55").unwrap(),
-            0,
-        );
+        // Files without the format version are in the initial format version.
+        let file_meta = FileMeta {
+            format_version: SST_INITIAL_FORMAT_VERSION,
+            ..create_file_meta(
+                FileId::from_str("bc5896ec-e4d8-4017-a80d-f2de73188d55").unwrap(),
+                0,
+            )
+        };
         let deserialized_file_meta: FileMeta = serde_json::from_str(json_file_meta).unwrap();
         assert_eq!(file_meta, deserialized_file_meta);
     }
@@ -75,6 +75,17 @@ impl FilePurger for LocalFilePurger {
         // Remove meta of the file from cache.
         if let Some(cache) = &self.cache_manager {
             cache.remove_parquet_meta_data(file_meta.region_id, file_meta.file_id);
+
+            // The uploader deletes the file after the upload finishes.
+            if let Some(write_cache) = cache.write_cache() {
+                if write_cache.remove_pending_upload(file_meta.region_id, file_meta.file_id) {
+                    info!(
+                        "SST file is still uploading, delete it after upload, file_id: {}, region: {}",
+                        file_meta.file_id, file_meta.region_id
+                    );
+                    return;
+                }
+            }
         }
 
         if let Err(e) = self.scheduler.schedule(Box::pin(async move {
@@ -106,7 +117,7 @@ mod tests {
     use crate::schedule::scheduler::{LocalScheduler, Scheduler};
     use crate::sst::file::{FileHandle, FileId, FileMeta, FileTimeRange, IndexType};
     use crate::sst::index::intermediate::IntermediateManager;
-    use crate::sst::location;
+    use crate::sst::{location, SST_FORMAT_VERSION};
 
     #[tokio::test]
     async fn test_file_purge() {
@@ -141,6 +152,7 @@ mod tests {
                     file_size: 4096,
                     available_indexes: Default::default(),
                     index_file_size: 0,
+                    format_version: SST_FORMAT_VERSION,
                 },
                 file_purger,
             );
@@ -192,6 +204,7 @@ mod tests {
                     file_size: 4096,
                     available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
                     index_file_size: 4096,
+                    format_version: SST_FORMAT_VERSION,
                 },
                 file_purger,
             );
@@ -37,6 +37,12 @@ pub const PARQUET_METADATA_KEY: &str = "greptime:metadata";
 
 /// Default batch size to read parquet files.
 pub(crate) const DEFAULT_READ_BATCH_SIZE: usize = 1024;
+/// Target size in bytes of a batch read from parquet files.
+pub(crate) const READ_BATCH_BYTES: usize = 1024 * 1024;
+/// Minimum number of rows of a batch read from parquet files.
+pub(crate) const MIN_READ_BATCH_SIZE: usize = 64;
+/// Maximum number of rows of a batch read from parquet files.
+pub(crate) const MAX_READ_BATCH_SIZE: usize = 8 * DEFAULT_READ_BATCH_SIZE;
 /// Default row group size for parquet files.
 const DEFAULT_ROW_GROUP_SIZE: usize = 100 * DEFAULT_READ_BATCH_SIZE;
 
@@ -81,12 +87,16 @@ mod tests {
     use common_time::Timestamp;
     use datafusion_common::{Column, ScalarValue};
     use datafusion_expr::{BinaryExpr, Expr, Operator};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+    use parquet::arrow::ProjectionMask;
+    use store_api::metadata::RegionMetadataBuilder;
     use table::predicate::Predicate;
 
     use super::*;
     use crate::cache::{CacheManager, PageKey};
     use crate::sst::index::Indexer;
-    use crate::sst::parquet::reader::ParquetReaderBuilder;
+    use crate::sst::parquet::reader::{adaptive_batch_size, batch_size_of, ParquetReaderBuilder};
     use crate::sst::parquet::writer::ParquetWriter;
     use crate::test_util::sst_util::{
         assert_parquet_metadata_eq, new_batch_by_range, new_source, sst_file_handle,
@@ -150,6 +160,71 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_adaptive_batch_size() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 100)]);
+        let write_opts = WriteOptions::default();
+
+        let mut writer = ParquetWriter::new(
+            file_path,
+            metadata,
+            object_store.clone(),
+            Indexer::default(),
+        );
+        writer
+            .write_all(source, &write_opts)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle.clone(), object_store);
+        let reader = builder.build().await.unwrap();
+        let parquet_meta = reader.parquet_metadata();
+        let row_group = parquet_meta.row_group(0);
+        // Rows in the test region are narrow.
+        assert_eq!(
+            MAX_READ_BATCH_SIZE,
+            adaptive_batch_size(row_group, &ProjectionMask::all())
+        );
+        assert_eq!(
+            MAX_READ_BATCH_SIZE,
+            adaptive_batch_size(
+                row_group,
+                &ProjectionMask::leaves(row_group.schema_descr(), [])
+            )
+        );
+    }
+
+    #[test]
+    fn test_batch_size_of() {
+        // Empty row groups use the default size.
+        assert_eq!(DEFAULT_READ_BATCH_SIZE, batch_size_of(0, 0));
+        // Narrow rows are clamped to the max size.
+        assert_eq!(MAX_READ_BATCH_SIZE, batch_size_of(1000, 1000));
+        // Wide rows are clamped to the min size.
+        assert_eq!(
+            MIN_READ_BATCH_SIZE,
+            batch_size_of(10, 10 * READ_BATCH_BYTES as i64)
+        );
+        // Rows of 1 KiB and 512 B fill a batch of about `READ_BATCH_BYTES`.
+        assert_eq!(1024, batch_size_of(1000, 1000 * 1024));
+        assert_eq!(2048, batch_size_of(1000, 1000 * 512));
+        // Boundaries of the range are kept.
+        assert_eq!(
+            MIN_READ_BATCH_SIZE,
+            batch_size_of(1, (READ_BATCH_BYTES / MIN_READ_BATCH_SIZE) as i64)
+        );
+        assert_eq!(
+            MAX_READ_BATCH_SIZE,
+            batch_size_of(1, (READ_BATCH_BYTES / MAX_READ_BATCH_SIZE) as i64)
+        );
+    }
+
     #[tokio::test]
     async fn test_read_with_cache() {
         let mut env = TestEnv::new();
@@ -398,4 +473,64 @@ mod tests {
         let mut reader = builder.build().await.unwrap();
         check_reader_result(&mut reader, &[new_batch_by_range(&["b", "h"], 150, 200)]).await;
     }
+
+    #[tokio::test]
+    async fn test_read_with_changed_column_type() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+            new_batch_by_range(&["b", "h"], 100, 200),
+        ]);
+        // Use a small row group size for test.
+        let write_opts = WriteOptions {
+            row_group_size: 50,
+            ..Default::default()
+        };
+        // Prepare data.
+        let mut writer = ParquetWriter::new(
+            file_path,
+            metadata.clone(),
+            object_store.clone(),
+            Indexer::default(),
+        );
+        writer
+            .write_all(source, &write_opts)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The type of field_0 changes to float64 after the SST is written.
+        let mut builder = RegionMetadataBuilder::new(metadata.region_id);
+        for mut column in metadata.column_metadatas.clone() {
+            if column.column_schema.name == "field_0" {
+                column.column_schema =
+                    ColumnSchema::new("field_0", ConcreteDataType::float64_datatype(), true);
+            }
+            builder.push_column_metadata(column);
+        }
+        builder.primary_key(metadata.primary_key.clone());
+        let expected_metadata = Arc::new(builder.build().unwrap());
+
+        // Predicate
+        let predicate = Some(Predicate::new(vec![Expr::BinaryExpr(BinaryExpr {
+            left: Box::new(Expr::Column(Column {
+                relation: None,
+                name: "field_0".to_string(),
+            })),
+            op: Operator::GtEq,
+            right: Box::new(Expr::Literal(ScalarValue::Float64(Some(150.0)))),
+        })
+        .into()]));
+
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle.clone(), object_store)
+            .predicate(predicate)
+            .expected_metadata(Some(expected_metadata));
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(&mut reader, &[new_batch_by_range(&["b", "h"], 150, 200)]).await;
+    }
 }
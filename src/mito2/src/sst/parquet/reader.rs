@@ -31,7 +31,7 @@ use itertools::Itertools;
 use object_store::ObjectStore;
 use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, RowSelection};
 use parquet::arrow::{parquet_to_arrow_field_levels, FieldLevels, ProjectionMask};
-use parquet::file::metadata::ParquetMetaData;
+use parquet::file::metadata::{ParquetMetaData, RowGroupMetaData};
 use parquet::format::KeyValue;
 use snafu::{OptionExt, ResultExt};
 use store_api::metadata::{RegionMetadata, RegionMetadataRef};
@@ -56,7 +56,10 @@ use crate::sst::parquet::metadata::MetadataLoader;
 use crate::sst::parquet::row_group::InMemoryRowGroup;
 use crate::sst::parquet::row_selection::row_selection_from_row_ranges;
 use crate::sst::parquet::stats::RowGroupPruningStats;
-use crate::sst::parquet::{DEFAULT_READ_BATCH_SIZE, PARQUET_METADATA_KEY};
+use crate::sst::parquet::{
+    DEFAULT_READ_BATCH_SIZE, MAX_READ_BATCH_SIZE, MIN_READ_BATCH_SIZE, PARQUET_METADATA_KEY,
+    READ_BATCH_BYTES,
+};
 
 /// Parquet SST reader builder.
 pub(crate) struct ParquetReaderBuilder {
@@ -77,6 +80,11 @@ pub(crate) struct ParquetReaderBuilder {
     cache_manager: Option<CacheManagerRef>,
     /// Index applier.
     index_applier: Option<SstIndexApplierRef>,
+    /// Metadata of the region to read.
+    ///
+    /// The SST may be written before the type of a column changes, so the
+    /// reader prunes row groups against this metadata if it is present.
+    expected_metadata: Option<RegionMetadataRef>,
 }
 
 impl ParquetReaderBuilder {
@@ -95,6 +103,7 @@ impl ParquetReaderBuilder {
             projection: None,
             cache_manager: None,
             index_applier: None,
+            expected_metadata: None,
         }
     }
 
@@ -131,6 +140,13 @@ impl ParquetReaderBuilder {
         self
     }
 
+    /// Attaches the expected metadata of the region to the builder.
+    #[must_use]
+    pub fn expected_metadata(mut self, expected_metadata: Option<RegionMetadataRef>) -> Self {
+        self.expected_metadata = expected_metadata;
+        self
+    }
+
     /// Builds and initializes a [ParquetReader].
     ///
     /// This needs to perform IO operation.
@@ -392,9 +408,19 @@ impl ParquetReaderBuilder {
             .collect();
 
         let row_groups = parquet_meta.row_groups();
-        let stats = RowGroupPruningStats::new(row_groups, read_format, column_ids);
+        let stats = RowGroupPruningStats::new(
+            row_groups,
+            read_format,
+            self.expected_metadata.clone(),
+            column_ids,
+        );
+        let prune_schema = self
+            .expected_metadata
+            .as_ref()
+            .map(|metadata| metadata.schema.arrow_schema())
+            .unwrap_or_else(|| region_meta.schema.arrow_schema());
         let row_groups = predicate
-            .prune_with_stats(&stats, region_meta.schema.arrow_schema())
+            .prune_with_stats(&stats, prune_schema)
             .iter()
             .zip(0..num_row_groups)
             .filter(|&(mask, _)| *mask)
@@ -486,10 +512,12 @@ impl RowGroupReaderBuilder {
 
         // Builds the parquet reader.
         // Now the row selection is None.
+        let batch_size =
+            adaptive_batch_size(self.parquet_meta.row_group(row_group_idx), &self.projection);
         ParquetRecordBatchReader::try_new_with_row_groups(
             &self.field_levels,
             &row_group,
-            DEFAULT_READ_BATCH_SIZE,
+            batch_size,
             row_selection,
         )
         .context(ReadParquetSnafu {
@@ -498,6 +526,35 @@ impl RowGroupReaderBuilder {
     }
 }
 
+/// Returns the number of rows per batch to read the row group, so each batch takes
+/// about [READ_BATCH_BYTES] bytes.
+///
+/// The row width is estimated from the uncompressed size of projected columns.
+pub(crate) fn adaptive_batch_size(
+    row_group: &RowGroupMetaData,
+    projection: &ProjectionMask,
+) -> usize {
+    let projected_bytes: i64 = row_group
+        .columns()
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| projection.leaf_included(*idx))
+        .map(|(_, column)| column.uncompressed_size())
+        .sum();
+    batch_size_of(row_group.num_rows(), projected_bytes)
+}
+
+/// Returns the number of rows per batch for `num_rows` rows taking `bytes` bytes,
+/// clamped between [MIN_READ_BATCH_SIZE] and [MAX_READ_BATCH_SIZE].
+pub(crate) fn batch_size_of(num_rows: i64, bytes: i64) -> usize {
+    if num_rows <= 0 {
+        return DEFAULT_READ_BATCH_SIZE;
+    }
+
+    let row_width = (bytes / num_rows).max(1) as usize;
+    (READ_BATCH_BYTES / row_width).clamp(MIN_READ_BATCH_SIZE, MAX_READ_BATCH_SIZE)
+}
+
 /// Parquet batch reader to read our SST format.
 pub struct ParquetReader {
     /// Indices of row groups to read, along with their respective row selections.
@@ -20,7 +20,9 @@ use std::collections::HashSet;
 use datafusion::physical_optimizer::pruning::PruningStatistics;
 use datafusion_common::Column;
 use datatypes::arrow::array::ArrayRef;
+use datatypes::arrow::compute;
 use parquet::file::metadata::RowGroupMetaData;
+use store_api::metadata::RegionMetadataRef;
 use store_api::storage::ColumnId;
 
 use crate::sst::parquet::format::ReadFormat;
@@ -31,6 +33,10 @@ pub(crate) struct RowGroupPruningStats<'a, T> {
     row_groups: &'a [T],
     /// Helper to read the SST.
     read_format: &'a ReadFormat,
+    /// Metadata of the region to read.
+    ///
+    /// Types of columns in this metadata may differ from the SST's.
+    expected_metadata: Option<RegionMetadataRef>,
     /// Projected column ids to read.
     ///
     /// We need column ids to distinguish different columns with the same name.
@@ -43,11 +49,13 @@ impl<'a, T> RowGroupPruningStats<'a, T> {
     pub(crate) fn new(
         row_groups: &'a [T],
         read_format: &'a ReadFormat,
+        expected_metadata: Option<RegionMetadataRef>,
         column_ids: HashSet<ColumnId>,
     ) -> Self {
         Self {
             row_groups,
             read_format,
+            expected_metadata,
             column_ids,
         }
     }
@@ -55,22 +63,52 @@ impl<'a, T> RowGroupPruningStats<'a, T> {
     /// Returns the column id of specific column name if we need to read it.
     fn column_id_to_prune(&self, name: &str) -> Option<ColumnId> {
         // Only use stats when the column to read has the same id as the column in the SST.
-        self.read_format
-            .metadata()
+        let metadata = self
+            .expected_metadata
+            .as_ref()
+            .unwrap_or_else(|| self.read_format.metadata());
+        metadata
             .column_by_name(name)
             .and_then(|col| self.column_ids.get(&col.column_id).copied())
     }
+
+    /// Casts the stats of a column to the type in the expected metadata.
+    ///
+    /// Only lossless widening is allowed when altering a column type, so the
+    /// casted min/max values still bound the values in the row group.
+    fn compat_values(&self, column_id: ColumnId, values: Option<ArrayRef>) -> Option<ArrayRef> {
+        let values = values?;
+        let Some(metadata) = &self.expected_metadata else {
+            return Some(values);
+        };
+        let target_type = metadata
+            .column_by_id(column_id)?
+            .column_schema
+            .data_type
+            .as_arrow_type();
+        if values.data_type() == &target_type {
+            return Some(values);
+        }
+        // Don't prune by this column if we can't convert its stats.
+        compute::cast(&values, &target_type).ok()
+    }
 }
 
 impl<'a, T: Borrow<RowGroupMetaData>> PruningStatistics for RowGroupPruningStats<'a, T> {
     fn min_values(&self, column: &Column) -> Option<ArrayRef> {
         let column_id = self.column_id_to_prune(&column.name)?;
-        self.read_format.min_values(self.row_groups, column_id)
+        self.compat_values(
+            column_id,
+            self.read_format.min_values(self.row_groups, column_id),
+        )
     }
 
     fn max_values(&self, column: &Column) -> Option<ArrayRef> {
         let column_id = self.column_id_to_prune(&column.name)?;
-        self.read_format.max_values(self.row_groups, column_id)
+        self.compat_values(
+            column_id,
+            self.read_format.max_values(self.row_groups, column_id),
+        )
     }
 
     fn num_containers(&self) -> usize {
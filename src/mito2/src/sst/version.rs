@@ -84,6 +84,19 @@ impl SstVersion {
         }
     }
 
+    /// Returns the number of SST files and the number of files in old format versions.
+    pub(crate) fn num_files_and_outdated(&self) -> (usize, usize) {
+        self.levels
+            .iter()
+            .flat_map(|level_meta| level_meta.files.values())
+            .fold((0, 0), |(num_files, num_outdated), file_handle| {
+                (
+                    num_files + 1,
+                    num_outdated + file_handle.is_outdated() as usize,
+                )
+            })
+    }
+
     /// Returns SST files'space occupied in current version.
     pub(crate) fn sst_usage(&self) -> u64 {
         self.levels
@@ -124,13 +137,13 @@ impl LevelMeta {
         }
     }
 
-    /// Returns expired SSTs from current level.
+    /// Returns expired SSTs from current level, skipping files that are compacting.
     pub fn get_expired_files(&self, expire_time: &Timestamp) -> Vec<FileHandle> {
         self.files
             .values()
             .filter(|v| {
                 let (_, end) = v.time_range();
-                &end < expire_time
+                &end < expire_time && !v.compacting()
             })
             .cloned()
             .collect()
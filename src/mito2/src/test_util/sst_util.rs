@@ -28,6 +28,7 @@ use store_api::storage::RegionId;
 use crate::read::{Batch, Source};
 use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
 use crate::sst::file::{FileHandle, FileId, FileMeta};
+use crate::sst::SST_FORMAT_VERSION;
 use crate::test_util::{new_batch_builder, new_noop_file_purger, VecBatchReader};
 
 /// Test region id.
@@ -111,6 +112,7 @@ pub fn sst_file_handle(start_ms: i64, end_ms: i64) -> FileHandle {
             file_size: 0,
             available_indexes: Default::default(),
             index_file_size: 0,
+            format_version: SST_FORMAT_VERSION,
         },
         file_purger,
     )
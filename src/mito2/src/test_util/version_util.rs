@@ -29,6 +29,7 @@ use crate::memtable::time_partition::TimePartitions;
 use crate::region::version::{Version, VersionBuilder, VersionControl};
 use crate::sst::file::{FileId, FileMeta};
 use crate::sst::file_purger::FilePurgerRef;
+use crate::sst::SST_FORMAT_VERSION;
 use crate::test_util::memtable_util::EmptyMemtableBuilder;
 use crate::test_util::new_noop_file_purger;
 
@@ -80,6 +81,15 @@ impl VersionControlBuilder {
     }
 
     pub(crate) fn push_l0_file(&mut self, start_ms: i64, end_ms: i64) -> &mut Self {
+        self.push_l0_file_with_format_version(start_ms, end_ms, SST_FORMAT_VERSION)
+    }
+
+    pub(crate) fn push_l0_file_with_format_version(
+        &mut self,
+        start_ms: i64,
+        end_ms: i64,
+        format_version: u32,
+    ) -> &mut Self {
         let file_id = FileId::random();
         self.files.insert(
             file_id,
@@ -94,6 +104,7 @@ impl VersionControlBuilder {
                 file_size: 0, // We don't care file size.
                 available_indexes: Default::default(),
                 index_file_size: 0,
+                format_version,
             },
         );
         self
@@ -141,6 +152,7 @@ pub(crate) fn apply_edit(
                 file_size: 0, // We don't care file size.
                 available_indexes: Default::default(),
                 index_file_size: 0,
+                format_version: SST_FORMAT_VERSION,
             }
         })
         .collect();
@@ -152,6 +164,7 @@ pub(crate) fn apply_edit(
             compaction_time_window: None,
             flushed_entry_id: None,
             flushed_sequence: None,
+            retained_flushes: None,
         },
         &[],
         purger,
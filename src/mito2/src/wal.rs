@@ -42,12 +42,20 @@ pub type WalEntryStream<'a> = BoxStream<'a, Result<(EntryId, WalEntry)>>;
 /// Write ahead log.
 ///
 /// All regions in the engine shares the same WAL instance.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Wal<S> {
     /// The underlying log store.
     store: Arc<S>,
 }
 
+impl<S> Clone for Wal<S> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+        }
+    }
+}
+
 impl<S> Wal<S> {
     /// Creates a new [Wal] from the log store.
     pub fn new(store: Arc<S>) -> Self {
@@ -112,6 +120,30 @@ impl<S: LogStore> Wal<S> {
     }
 }
 
+/// Reads entries from the WAL regardless of the type of its log store.
+pub(crate) trait WalReader: Send + Sync {
+    /// Scan entries of specific region starting from `start_id` (inclusive).
+    fn scan<'a>(
+        &'a self,
+        region_id: RegionId,
+        start_id: EntryId,
+        wal_options: &'a WalOptions,
+    ) -> Result<WalEntryStream<'a>>;
+}
+
+pub(crate) type WalReaderRef = Arc<dyn WalReader>;
+
+impl<S: LogStore> WalReader for Wal<S> {
+    fn scan<'a>(
+        &'a self,
+        region_id: RegionId,
+        start_id: EntryId,
+        wal_options: &'a WalOptions,
+    ) -> Result<WalEntryStream<'a>> {
+        Wal::scan(self, region_id, start_id, wal_options)
+    }
+}
+
 /// Decode Wal entry from log store.
 fn decode_entry<E: Entry>(region_id: RegionId, entry: E) -> Result<(EntryId, WalEntry)> {
     let entry_id = entry.id();
@@ -21,10 +21,13 @@ mod handle_compaction;
 mod handle_create;
 mod handle_drop;
 mod handle_flush;
+mod handle_insert_mode;
 mod handle_open;
+mod handle_replay_wal;
 mod handle_truncate;
 mod handle_write;
 
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -32,12 +35,13 @@ use std::time::Duration;
 use common_runtime::JoinHandle;
 use common_telemetry::{error, info, warn};
 use futures::future::try_join_all;
+pub(crate) use handle_insert_mode::ExistingRowsRead;
 use object_store::manager::ObjectStoreManagerRef;
 use rand::{thread_rng, Rng};
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::region_engine::SetReadonlyResponse;
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, TableId};
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, oneshot, Mutex};
 
@@ -45,10 +49,15 @@ use crate::cache::write_cache::{WriteCache, WriteCacheRef};
 use crate::cache::{CacheManager, CacheManagerRef};
 use crate::compaction::CompactionScheduler;
 use crate::config::MitoConfig;
-use crate::error::{InvalidRequestSnafu, JoinSnafu, Result, WorkerStoppedSnafu};
+use crate::disk_usage::{DiskUsageManager, DiskUsageManagerRef};
+use crate::error::{
+    InvalidRequestSnafu, JoinSnafu, RegionNotFoundSnafu, Result, WorkerStoppedSnafu,
+};
 use crate::flush::{FlushScheduler, WriteBufferManagerImpl, WriteBufferManagerRef};
 use crate::manifest::action::RegionEdit;
 use crate::memtable::MemtableBuilderProvider;
+use crate::rate_limit::{WriteRateLimiter, WriteRateLimiterRef};
+use crate::region::snapshot::RegionSnapshot;
 use crate::region::{MitoRegionRef, RegionMap, RegionMapRef};
 use crate::request::{
     BackgroundNotify, DdlRequest, SenderDdlRequest, SenderWriteRequest, WorkerRequest,
@@ -121,6 +130,7 @@ impl WorkerGroup {
     ///
     /// The number of workers should be power of two.
     pub(crate) async fn start<S: LogStore>(
+        data_home: &str,
         config: Arc<MitoConfig>,
         log_store: Arc<S>,
         object_store_manager: ObjectStoreManagerRef,
@@ -151,12 +161,25 @@ impl WorkerGroup {
                 .build(),
         );
         let time_provider = Arc::new(StdTimeProvider);
+        let region_maps = new_region_maps(config.num_workers);
+        let disk_usage_manager = Arc::new(DiskUsageManager::new(
+            data_home.to_string(),
+            config.min_free_disk_space,
+            region_maps.clone(),
+        ));
+        let write_rate_limiter = Arc::new(WriteRateLimiter::new(
+            config.max_write_rows_per_second,
+            config.max_write_bytes_per_second,
+        ));
 
-        let workers = (0..config.num_workers)
-            .map(|id| {
+        let workers = region_maps
+            .into_iter()
+            .enumerate()
+            .map(|(id, regions)| {
                 WorkerStarter {
                     id: id as WorkerId,
                     config: config.clone(),
+                    regions,
                     log_store: log_store.clone(),
                     object_store_manager: object_store_manager.clone(),
                     write_buffer_manager: write_buffer_manager.clone(),
@@ -166,6 +189,8 @@ impl WorkerGroup {
                     cache_manager: cache_manager.clone(),
                     intermediate_manager: intermediate_manager.clone(),
                     time_provider: time_provider.clone(),
+                    disk_usage_manager: disk_usage_manager.clone(),
+                    write_rate_limiter: write_rate_limiter.clone(),
                 }
                 .start()
             })
@@ -214,6 +239,14 @@ impl WorkerGroup {
         self.worker(region_id).get_region(region_id)
     }
 
+    /// Returns all regions of the group.
+    pub(crate) fn list_regions(&self) -> Vec<MitoRegionRef> {
+        self.workers
+            .iter()
+            .flat_map(|worker| worker.regions.list_regions())
+            .collect()
+    }
+
     /// Returns cache of the group.
     pub(crate) fn cache_manager(&self) -> CacheManagerRef {
         self.cache_manager.clone()
@@ -234,6 +267,7 @@ impl WorkerGroup {
     ///
     /// The number of workers should be power of two.
     pub(crate) async fn start_for_test<S: LogStore>(
+        data_home: &str,
         config: Arc<MitoConfig>,
         log_store: Arc<S>,
         object_store_manager: ObjectStoreManagerRef,
@@ -266,11 +300,24 @@ impl WorkerGroup {
                 .write_cache(write_cache)
                 .build(),
         );
-        let workers = (0..config.num_workers)
-            .map(|id| {
+        let region_maps = new_region_maps(config.num_workers);
+        let disk_usage_manager = Arc::new(DiskUsageManager::new(
+            data_home.to_string(),
+            config.min_free_disk_space,
+            region_maps.clone(),
+        ));
+        let write_rate_limiter = Arc::new(WriteRateLimiter::new(
+            config.max_write_rows_per_second,
+            config.max_write_bytes_per_second,
+        ));
+        let workers = region_maps
+            .into_iter()
+            .enumerate()
+            .map(|(id, regions)| {
                 WorkerStarter {
                     id: id as WorkerId,
                     config: config.clone(),
+                    regions,
                     log_store: log_store.clone(),
                     object_store_manager: object_store_manager.clone(),
                     write_buffer_manager: write_buffer_manager.clone(),
@@ -280,6 +327,8 @@ impl WorkerGroup {
                     cache_manager: cache_manager.clone(),
                     intermediate_manager: intermediate_manager.clone(),
                     time_provider: time_provider.clone(),
+                    disk_usage_manager: disk_usage_manager.clone(),
+                    write_rate_limiter: write_rate_limiter.clone(),
                 }
                 .start()
             })
@@ -299,6 +348,13 @@ impl WorkerGroup {
     }
 }
 
+/// Creates a [RegionMap] for each worker.
+fn new_region_maps(num_workers: usize) -> Vec<RegionMapRef> {
+    (0..num_workers)
+        .map(|_| Arc::new(RegionMap::default()))
+        .collect()
+}
+
 fn region_id_to_index(id: RegionId, num_workers: usize) -> usize {
     ((id.table_id() as usize % num_workers) + (id.region_number() as usize % num_workers))
         % num_workers
@@ -322,7 +378,8 @@ async fn write_cache_from_config(
         config.experimental_write_cache_size,
         intermediate_manager,
     )
-    .await?;
+    .await?
+    .with_async_upload(config.experimental_write_cache_async_upload);
     Ok(Some(Arc::new(cache)))
 }
 
@@ -336,6 +393,7 @@ pub(crate) fn worker_init_check_delay() -> Duration {
 struct WorkerStarter<S> {
     id: WorkerId,
     config: Arc<MitoConfig>,
+    regions: RegionMapRef,
     log_store: Arc<S>,
     object_store_manager: ObjectStoreManagerRef,
     write_buffer_manager: WriteBufferManagerRef,
@@ -345,12 +403,14 @@ struct WorkerStarter<S> {
     cache_manager: CacheManagerRef,
     intermediate_manager: IntermediateManager,
     time_provider: TimeProviderRef,
+    disk_usage_manager: DiskUsageManagerRef,
+    write_rate_limiter: WriteRateLimiterRef,
 }
 
 impl<S: LogStore> WorkerStarter<S> {
     /// Starts a region worker and its background thread.
     fn start(self) -> RegionWorker {
-        let regions = Arc::new(RegionMap::default());
+        let regions = self.regions;
         let (sender, receiver) = mpsc::channel(self.config.worker_channel_size);
 
         let running = Arc::new(AtomicBool::new(true));
@@ -383,6 +443,10 @@ impl<S: LogStore> WorkerStarter<S> {
             intermediate_manager: self.intermediate_manager,
             time_provider: self.time_provider,
             last_periodical_check_millis: now,
+            disk_usage_manager: self.disk_usage_manager,
+            write_rate_limiter: self.write_rate_limiter,
+            throttled_requests: ThrottledRequests::default(),
+            reading_existing_rows: HashMap::new(),
         };
         let handle = common_runtime::spawn_write(async move {
             worker_thread.run().await;
@@ -503,6 +567,62 @@ impl StalledRequests {
     }
 }
 
+/// Buffer for write requests held by the write rate limiter.
+#[derive(Default)]
+pub(crate) struct ThrottledRequests {
+    /// Throttled requests in arrival order.
+    requests: Vec<SenderWriteRequest>,
+    /// Tables of throttled requests. Later requests of these tables are also
+    /// throttled to keep the order of writes.
+    tables: HashSet<TableId>,
+    /// Earliest time in millis to retry throttled requests.
+    retry_at_millis: Option<i64>,
+    /// Estimated size of throttled requests.
+    estimated_size: usize,
+}
+
+impl ThrottledRequests {
+    /// Holds the `request` until `retry_at_millis`, or until other throttled
+    /// requests are retried if it's `None`.
+    pub(crate) fn push(&mut self, request: SenderWriteRequest, retry_at_millis: Option<i64>) {
+        self.tables.insert(request.request.region_id.table_id());
+        self.estimated_size += request.request.estimated_size();
+        self.requests.push(request);
+        if let Some(retry_at) = retry_at_millis {
+            self.retry_at_millis = Some(self.retry_at_millis.map_or(retry_at, |v| v.min(retry_at)));
+        }
+    }
+
+    /// Returns true if requests of the table are throttled.
+    pub(crate) fn contains_table(&self, table_id: TableId) -> bool {
+        self.tables.contains(&table_id)
+    }
+
+    /// Returns the estimated size of throttled requests.
+    pub(crate) fn estimated_size(&self) -> usize {
+        self.estimated_size
+    }
+
+    /// Returns the time to wait before retrying throttled requests.
+    fn wait_duration(&self, now_millis: i64) -> Option<Duration> {
+        self.retry_at_millis
+            .map(|retry_at| Duration::from_millis((retry_at - now_millis).max(0) as u64))
+    }
+
+    /// Takes all requests if it's time to retry them.
+    fn take_ready(&mut self, now_millis: i64) -> Vec<SenderWriteRequest> {
+        match self.retry_at_millis {
+            Some(retry_at) if retry_at <= now_millis => {
+                self.tables.clear();
+                self.retry_at_millis = None;
+                self.estimated_size = 0;
+                std::mem::take(&mut self.requests)
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// Background worker loop to handle requests.
 struct RegionWorkerLoop<S> {
     /// Id of the worker.
@@ -545,6 +665,14 @@ struct RegionWorkerLoop<S> {
     time_provider: TimeProviderRef,
     /// Last time to check regions periodically.
     last_periodical_check_millis: i64,
+    /// Tracks the disk usage to reject writes.
+    disk_usage_manager: DiskUsageManagerRef,
+    /// Limits the write rate of the engine and tables.
+    write_rate_limiter: WriteRateLimiterRef,
+    /// Write requests held by the rate limiter.
+    throttled_requests: ThrottledRequests,
+    /// Regions reading their existing rows, and write requests to them held meanwhile.
+    reading_existing_rows: HashMap<RegionId, Vec<SenderWriteRequest>>,
 }
 
 impl<S: LogStore> RegionWorkerLoop<S> {
@@ -564,13 +692,20 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             // Clear the buffer before handling next batch of requests.
             buffer.clear();
 
-            let max_wait_time = self.time_provider.wait_duration(CHECK_REGION_INTERVAL);
+            let mut max_wait_time = self.time_provider.wait_duration(CHECK_REGION_INTERVAL);
+            if let Some(wait) = self
+                .throttled_requests
+                .wait_duration(self.time_provider.current_time_millis())
+            {
+                max_wait_time = max_wait_time.min(wait);
+            }
             match tokio::time::timeout(max_wait_time, self.receiver.recv()).await {
                 Ok(Some(request)) => buffer.push(request),
                 // The channel is disconnected.
                 Ok(None) => break,
                 Err(_) => {
-                    // Timeout. Checks periodical tasks.
+                    // Timeout. Retries throttled requests and checks periodical tasks.
+                    self.handle_throttled_requests().await;
                     self.handle_periodical_tasks();
                     continue;
                 }
@@ -588,6 +723,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
 
             self.handle_requests(&mut buffer).await;
 
+            self.handle_throttled_requests().await;
             self.handle_periodical_tasks();
         }
 
@@ -627,6 +763,9 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                         warn!("Failed to send edit region error to caller, error: {e:?}");
                     }
                 }
+                WorkerRequest::PinSnapshot { region_id, sender } => {
+                    let _ = sender.send(self.pin_snapshot(region_id));
+                }
                 // We receive a stop signal, but we still want to process remaining
                 // requests. The worker thread will then check the running flag and
                 // then exit.
@@ -665,18 +804,30 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                         .await;
                     continue;
                 }
-                DdlRequest::Compact(_) => {
-                    self.handle_compaction_request(ddl.region_id, ddl.sender);
+                DdlRequest::Compact(req) => {
+                    self.handle_compaction_request(ddl.region_id, req.options, ddl.sender);
                     continue;
                 }
                 DdlRequest::Truncate(_) => self.handle_truncate_request(ddl.region_id).await,
                 DdlRequest::Catchup(req) => self.handle_catchup_request(ddl.region_id, req).await,
+                DdlRequest::ReplayWal(req) => {
+                    self.handle_replay_wal_request(ddl.region_id, req, ddl.sender);
+                    continue;
+                }
             };
 
             ddl.sender.send(res);
         }
     }
 
+    /// Retries throttled write requests if it's time to retry them.
+    async fn handle_throttled_requests(&mut self) {
+        let requests = self
+            .throttled_requests
+            .take_ready(self.time_provider.current_time_millis());
+        self.handle_write_requests(requests, true).await;
+    }
+
     /// Handle periodical tasks such as region auto flush.
     fn handle_periodical_tasks(&mut self) {
         let interval = CHECK_REGION_INTERVAL.as_millis() as i64;
@@ -693,11 +844,14 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         if let Err(e) = self.flush_periodically() {
             error!(e; "Failed to flush regions periodically");
         }
+
+        self.compact_periodically();
     }
 
     /// Handles region background request
     async fn handle_background_notify(&mut self, region_id: RegionId, notify: BackgroundNotify) {
         match notify {
+            BackgroundNotify::FlushStaged(req) => self.handle_flush_staged(region_id, req).await,
             BackgroundNotify::FlushFinished(req) => {
                 self.handle_flush_finished(region_id, req).await
             }
@@ -706,6 +860,9 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 self.handle_compaction_finished(region_id, req).await
             }
             BackgroundNotify::CompactionFailed(req) => self.handle_compaction_failure(req).await,
+            BackgroundNotify::ExistingRowsRead(req) => {
+                self.handle_existing_rows_read(region_id, req).await
+            }
         }
     }
 
@@ -745,6 +902,24 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         // Applying region edit directly has nothing to do with memtables (at least for now).
         region.apply_edit(edit, &[]).await
     }
+
+    /// Pins a snapshot of the region at its committed sequence.
+    ///
+    /// Writes are handled by this worker too, so no write can commit between reading
+    /// the sequence and freezing the mutable memtable. Rows written after the snapshot
+    /// then go to a new memtable and never replace rows visible to the snapshot.
+    fn pin_snapshot(&self, region_id: RegionId) -> Result<RegionSnapshot> {
+        let region = self
+            .regions
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        let sequence = region.version_control.current().committed_sequence;
+        region.version_control.freeze_mutable()?;
+
+        Ok(region
+            .snapshots
+            .pin(sequence, self.config.max_snapshot_pin_duration))
+    }
 }
 
 impl<S> RegionWorkerLoop<S> {
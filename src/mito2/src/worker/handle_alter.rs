@@ -14,19 +14,23 @@
 
 //! Handling alter related requests.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_telemetry::{debug, error, info};
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use store_api::metadata::{RegionMetadata, RegionMetadataBuilder, RegionMetadataRef};
-use store_api::region_request::RegionAlterRequest;
+use store_api::region_request::{AlterKind, RegionAlterRequest};
 use store_api::storage::RegionId;
 
 use crate::error::{
-    InvalidMetadataSnafu, InvalidRegionRequestSchemaVersionSnafu, InvalidRegionRequestSnafu, Result,
+    InvalidMetadataSnafu, InvalidRegionOptionsSnafu, InvalidRegionRequestSchemaVersionSnafu,
+    InvalidRegionRequestSnafu, Result,
 };
 use crate::flush::FlushReason;
-use crate::manifest::action::{RegionChange, RegionMetaAction, RegionMetaActionList};
+use crate::manifest::action::{
+    RegionChange, RegionChangeOptions, RegionMetaAction, RegionMetaActionList,
+};
 use crate::region::version::Version;
 use crate::region::MitoRegionRef;
 use crate::request::{DdlRequest, OptionOutputTx, SenderDdlRequest};
@@ -79,6 +83,25 @@ impl<S> RegionWorkerLoop<S> {
             return;
         }
 
+        // Changing options doesn't touch the schema so we can apply it directly.
+        if let AlterKind::ChangeRegionOptions { options } = &request.kind {
+            match alter_region_options(&region, &version, options).await {
+                Ok(()) => {
+                    info!(
+                        "Options of region {} are altered to {:?}",
+                        region_id,
+                        region.version().options
+                    );
+                    sender.send(Ok(0));
+                }
+                Err(e) => {
+                    error!(e; "Failed to alter region options, region_id: {}", region_id);
+                    sender.send(Err(e));
+                }
+            }
+            return;
+        }
+
         // Checks whether we can alter the region directly.
         if !version.memtables.is_empty() {
             // If memtable is not empty, we can't alter it directly and need to flush
@@ -152,6 +175,41 @@ async fn alter_region_schema(
     Ok(())
 }
 
+/// Alter options of the region in place.
+///
+/// Only compaction options can be altered, and the new compaction options replace
+/// all compaction options of the region. The options are persisted to the manifest,
+/// so the region still uses them after reopening.
+async fn alter_region_options(
+    region: &MitoRegionRef,
+    version: &Version,
+    options: &HashMap<String, String>,
+) -> Result<()> {
+    for key in options.keys() {
+        ensure!(
+            key.starts_with("compaction."),
+            InvalidRegionOptionsSnafu {
+                reason: format!("option {} can't be altered", key),
+            }
+        );
+    }
+
+    let new_options = version.options.clone().with_altered(options)?;
+    let change = RegionChangeOptions {
+        options: options.clone(),
+    };
+    let action_list = RegionMetaActionList::with_action(RegionMetaAction::ChangeOptions(change));
+    region
+        .manifest_manager
+        .write()
+        .await
+        .update(action_list)
+        .await?;
+
+    region.version_control.alter_options(new_options);
+    Ok(())
+}
+
 /// Creates a metadata after applying the alter `request` to the old `metadata`.
 ///
 /// Returns an error if the `request` is invalid.
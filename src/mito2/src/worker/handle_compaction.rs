@@ -13,9 +13,13 @@
 // limitations under the License.
 
 use common_telemetry::{error, info, warn};
+use common_time::Timestamp;
 use store_api::logstore::LogStore;
+use store_api::region_request::CompactOptions;
 use store_api::storage::RegionId;
 
+use crate::compaction::get_expired_ssts;
+use crate::error::SnapshotPinnedSnafu;
 use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
 use crate::metrics::{COMPACTION_REQUEST_COUNT, COMPACTION_STAGE_ELAPSED};
 use crate::request::{CompactionFailed, CompactionFinished, OnFailure, OptionOutputTx};
@@ -26,11 +30,16 @@ impl<S: LogStore> RegionWorkerLoop<S> {
     pub(crate) fn handle_compaction_request(
         &mut self,
         region_id: RegionId,
+        options: CompactOptions,
         mut sender: OptionOutputTx,
     ) {
         let Some(region) = self.regions.writable_region_or(region_id, &mut sender) else {
             return;
         };
+        if !region.snapshots.is_empty() {
+            sender.send(SnapshotPinnedSnafu { region_id }.fail());
+            return;
+        }
         COMPACTION_REQUEST_COUNT.inc();
         if let Err(e) = self.compaction_scheduler.schedule_compaction(
             region.region_id,
@@ -38,6 +47,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             &region.access_layer,
             &region.file_purger,
             sender,
+            options,
             self.config.clone(),
         ) {
             error!(e; "Failed to schedule compaction task for region: {}", region_id);
@@ -76,6 +86,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 compaction_time_window: request.compaction_time_window,
                 flushed_entry_id: None,
                 flushed_sequence: None,
+                retained_flushes: None,
             };
             let action_list =
                 RegionMetaActionList::with_action(RegionMetaAction::Edit(edit.clone()));
@@ -97,12 +108,52 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 .version_control
                 .apply_edit(edit, &[], region.file_purger.clone());
         }
+        self.disk_usage_manager.invalidate();
         // compaction finished.
         request.on_success();
 
         // Schedule next compaction if necessary.
-        self.compaction_scheduler
-            .on_compaction_finished(region_id, self.config.clone());
+        if region.snapshots.is_empty() {
+            self.compaction_scheduler
+                .on_compaction_finished(region_id, self.config.clone());
+        } else {
+            self.compaction_scheduler.on_snapshot_pinned(region_id);
+        }
+    }
+
+    /// Schedules compaction for writable regions that have SST files wholly past
+    /// their TTL, so the compaction task removes them from the manifest and the
+    /// file purger deletes them.
+    ///
+    /// Also schedules compaction for regions that have SSTs in old format versions
+    /// if the SST upgrade is enabled.
+    pub(crate) fn compact_periodically(&mut self) {
+        let now = Timestamp::current_millis();
+        for region in self.regions.list_regions() {
+            if !region.is_writable() || !region.snapshots.is_empty() {
+                continue;
+            }
+            let version = region.version();
+            let has_expired =
+                !get_expired_ssts(version.ssts.levels(), version.options.ttl, now).is_empty();
+            let has_outdated =
+                self.config.enable_sst_upgrade && version.ssts.num_files_and_outdated().1 > 0;
+            if !has_expired && !has_outdated {
+                continue;
+            }
+
+            if let Err(e) = self.compaction_scheduler.schedule_compaction(
+                region.region_id,
+                &region.version_control,
+                &region.access_layer,
+                &region.file_purger,
+                OptionOutputTx::none(),
+                CompactOptions::Regular,
+                self.config.clone(),
+            ) {
+                error!(e; "Failed to schedule compaction periodically, region: {}", region.region_id);
+            }
+        }
     }
 
     /// When compaction fails, we simply log the error.
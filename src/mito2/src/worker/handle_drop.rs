@@ -59,11 +59,15 @@ impl<S> RegionWorkerLoop<S> {
         self.flush_scheduler.on_region_dropped(region_id);
         // Notifies compaction scheduler.
         self.compaction_scheduler.on_region_dropped(region_id);
+        // Regions of the table are dropped together, so the limit of the table is no
+        // longer needed.
+        self.write_rate_limiter.remove_table(region_id.table_id());
 
         // mark region version as dropped
         region
             .version_control
             .mark_dropped(&region.memtable_builder);
+        self.disk_usage_manager.invalidate();
         info!(
             "Region {} is dropped logically, but some files are not deleted yet",
             region_id
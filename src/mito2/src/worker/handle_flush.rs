@@ -16,19 +16,23 @@
 
 use std::sync::Arc;
 
-use common_telemetry::{error, info, warn};
+use common_telemetry::{debug, error, info, warn};
 use store_api::logstore::LogStore;
-use store_api::region_request::RegionFlushRequest;
+use store_api::region_request::{CompactOptions, RegionFlushRequest};
 use store_api::storage::RegionId;
 
 use crate::config::MitoConfig;
 use crate::error::{RegionTruncatedSnafu, Result};
 use crate::flush::{FlushReason, RegionFlushTask};
 use crate::manifest::action::RegionEdit;
+use crate::metrics::SERIES_GROWTH_ALERT_TOTAL;
 use crate::region::MitoRegionRef;
-use crate::request::{FlushFailed, FlushFinished, OnFailure, OptionOutputTx};
+use crate::request::{FlushFailed, FlushFinished, FlushStaged, OnFailure, OptionOutputTx};
 use crate::worker::RegionWorkerLoop;
 
+/// Min number of series in flushed memtables to warn about series growth.
+const MIN_SERIES_TO_ALERT: usize = 10000;
+
 impl<S> RegionWorkerLoop<S> {
     /// Handles manual flush request.
     pub(crate) async fn handle_flush_request(
@@ -181,9 +185,69 @@ impl<S> RegionWorkerLoop<S> {
             index_options: region.version().options.index_options.clone(),
         }
     }
+
+    /// Warns if the number of series in flushed memtables grows too fast
+    /// compared to the previous flush of the region.
+    fn check_series_growth(&self, region: &MitoRegionRef, num_series: usize) {
+        let last_num_series = region.swap_flush_series(num_series);
+        let factor = self.config.series_growth_alert_factor;
+        if factor == 0 || last_num_series == 0 || num_series < MIN_SERIES_TO_ALERT {
+            return;
+        }
+
+        if num_series > last_num_series.saturating_mul(factor) {
+            SERIES_GROWTH_ALERT_TOTAL.inc();
+            warn!(
+                "Number of series in region {} of table {} grows from {} to {} since last flush, the primary key cardinality may explode",
+                region.region_id,
+                region.region_id.table_id(),
+                last_num_series,
+                num_series
+            );
+        }
+    }
 }
 
 impl<S: LogStore> RegionWorkerLoop<S> {
+    /// On region flush job writing SSTs that are still uploading.
+    ///
+    /// Adds the SSTs to the version to release the flushed memtables. The region
+    /// keeps flushing until the job finishes, so the manifest and the WAL are
+    /// updated in [RegionWorkerLoop::handle_flush_finished()].
+    pub(crate) async fn handle_flush_staged(
+        &mut self,
+        region_id: RegionId,
+        mut request: FlushStaged,
+    ) {
+        let Some(region) = self.regions.writable_region_or(region_id, &mut request) else {
+            warn!(
+                "Unable to stage the flushed files for a read only region {}",
+                region_id
+            );
+            return;
+        };
+
+        // The flush task before truncating the region fails immediately.
+        let version_data = region.version_control.current();
+        if let Some(truncated_entry_id) = version_data.version.truncated_entry_id {
+            if truncated_entry_id >= request.flushed_entry_id {
+                request.on_failure(RegionTruncatedSnafu { region_id }.build());
+                return;
+            }
+        }
+
+        region.apply_staged_edit(
+            std::mem::take(&mut request.file_metas),
+            &request.memtables_to_remove,
+        );
+        self.check_series_growth(&region, request.num_series);
+
+        // Handle stalled requests as the memtables are released.
+        let stalled = std::mem::take(&mut self.stalled_requests);
+        // We already stalled these requests, don't stall them again.
+        self.handle_write_requests(stalled.requests, false).await;
+    }
+
     /// On region flush job finished.
     pub(crate) async fn handle_flush_finished(
         &mut self,
@@ -207,35 +271,64 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             }
         }
 
-        // Write region edit to manifest.
-        let edit = RegionEdit {
-            files_to_add: std::mem::take(&mut request.file_metas),
-            files_to_remove: Vec::new(),
-            compaction_time_window: None,
-            flushed_entry_id: Some(request.flushed_entry_id),
-            flushed_sequence: Some(request.flushed_sequence),
+        // Write region edit to manifest, with the flushes whose WAL is retained.
+        let retained_flushes = region.record_flush(request.flushed_entry_id);
+        let result = if request.staged {
+            // The files are uploaded and already in the version.
+            region
+                .persist_staged_files(
+                    request.flushed_entry_id,
+                    request.flushed_sequence,
+                    retained_flushes,
+                )
+                .await
+        } else {
+            let edit = RegionEdit {
+                files_to_add: std::mem::take(&mut request.file_metas),
+                files_to_remove: Vec::new(),
+                compaction_time_window: None,
+                flushed_entry_id: Some(request.flushed_entry_id),
+                flushed_sequence: Some(request.flushed_sequence),
+                retained_flushes,
+            };
+            region.apply_edit(edit, &request.memtables_to_remove).await
         };
-        if let Err(e) = region.apply_edit(edit, &request.memtables_to_remove).await {
+        if let Err(e) = result {
             error!(e; "Failed to write manifest, region: {}", region_id);
             request.on_failure(e);
             return;
         }
 
         region.update_flush_millis();
+        self.disk_usage_manager.invalidate();
+        if !request.staged {
+            self.check_series_growth(&region, request.num_series);
+        }
 
-        // Delete wal.
-        info!(
-            "Region {} flush finished, tries to bump wal to {}",
-            region_id, request.flushed_entry_id
-        );
-        if let Err(e) = self
-            .wal
-            .obsolete(region_id, request.flushed_entry_id, &region.wal_options)
-            .await
-        {
-            error!(e; "Failed to write wal, region: {}", region_id);
-            request.on_failure(e);
-            return;
+        // Delete wal, except entries still retained for recovery or replication.
+        match region.obsolete_entry_id(
+            request.flushed_entry_id,
+            self.config.retain_wal_for_replication,
+        ) {
+            Some(entry_id) => {
+                info!(
+                    "Region {} flush finished, tries to bump wal to {}",
+                    region_id, entry_id
+                );
+                if let Err(e) = self
+                    .wal
+                    .obsolete(region_id, entry_id, &region.wal_options)
+                    .await
+                {
+                    error!(e; "Failed to write wal, region: {}", region_id);
+                    request.on_failure(e);
+                    return;
+                }
+            }
+            None => info!(
+                "Region {} flush finished, retains wal before {}",
+                region_id, request.flushed_entry_id
+            ),
         }
 
         // Notifies waiters and observes the flush timer.
@@ -256,13 +349,19 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         // We already stalled these requests, don't stall them again.
         self.handle_write_requests(stalled.requests, false).await;
 
-        // Schedules compaction.
-        if let Err(e) = self.compaction_scheduler.schedule_compaction(
+        // Schedules compaction, unless snapshots need the rows it would remove.
+        if !region.snapshots.is_empty() {
+            debug!(
+                "Skip compaction after flush as region {} has pinned snapshots",
+                region.region_id
+            );
+        } else if let Err(e) = self.compaction_scheduler.schedule_compaction(
             region.region_id,
             &region.version_control,
             &region.access_layer,
             &region.file_purger,
             OptionOutputTx::none(),
+            CompactOptions::Regular,
             self.config.clone(),
         ) {
             warn!(
@@ -0,0 +1,517 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handling write requests in [InsertMode::IgnoreDuplicates].
+//!
+//! The worker reads the existing rows of a region in background, and holds later
+//! writes to the region until the rows are read. Then rows that already exist are
+//! dropped from put requests, and no other write to the region can happen in between.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use api::helper::pb_value_to_value_ref;
+use api::v1::{OpType, Row, Rows};
+use common_query::logical_plan::Expr;
+use common_recordbatch::RecordBatch;
+use common_telemetry::error;
+use datafusion_common::Column;
+use datafusion_expr::{lit, Expr as DfExpr};
+use datatypes::value::Value;
+use futures::TryStreamExt;
+use snafu::ResultExt;
+use store_api::logstore::LogStore;
+use store_api::region_request::InsertMode;
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::cache::CacheManagerRef;
+use crate::error::{ConvertValueSnafu, Error, ReadExistingRowsSnafu, Result, WriteGroupSnafu};
+use crate::read::scan_region::ScanRegion;
+use crate::region::version::VersionRef;
+use crate::region::MitoRegionRef;
+use crate::request::{BackgroundNotify, SenderWriteRequest, WorkerRequest, WriteRequest};
+use crate::worker::RegionWorkerLoop;
+
+/// The primary key and the timestamp of a row.
+type RowKey = Vec<Value>;
+
+/// Notifies the existing rows of a region are read for write requests.
+#[derive(Debug)]
+pub(crate) struct ExistingRowsRead {
+    /// The region whose rows are read.
+    region: MitoRegionRef,
+    /// The version of the region the rows are read from.
+    version: VersionRef,
+    /// Write requests to the region in arrival order.
+    requests: Vec<SenderWriteRequest>,
+    /// Keys of the existing rows the requests write.
+    keys: std::result::Result<ExistingKeys, Arc<Error>>,
+}
+
+impl<S: LogStore> RegionWorkerLoop<S> {
+    /// Takes the requests of regions that have put requests in
+    /// [InsertMode::IgnoreDuplicates] from `write_requests`, and reads the existing
+    /// rows of these regions in background.
+    ///
+    /// Requests to regions that are reading their rows are held until the rows are
+    /// read, so the rows can't change in between.
+    pub(crate) fn read_existing_rows(&mut self, write_requests: &mut Vec<SenderWriteRequest>) {
+        let regions = write_requests
+            .iter()
+            .filter(|req| is_ignore_duplicates(&req.request))
+            .map(|req| req.request.region_id)
+            // Requests of throttled tables are held by the rate limiter first. They read
+            // the rows once they are retried, as the held requests aren't written yet.
+            .filter(|region_id| !self.throttled_requests.contains_table(region_id.table_id()))
+            .collect::<HashSet<_>>();
+        if regions.is_empty() && self.reading_existing_rows.is_empty() {
+            return;
+        }
+
+        let mut to_read: HashMap<_, Vec<_>> = HashMap::with_capacity(regions.len());
+        for sender_req in std::mem::take(write_requests) {
+            let region_id = sender_req.request.region_id;
+            if let Some(held) = self.reading_existing_rows.get_mut(&region_id) {
+                held.push(sender_req);
+            } else if regions.contains(&region_id) {
+                to_read.entry(region_id).or_default().push(sender_req);
+            } else {
+                write_requests.push(sender_req);
+            }
+        }
+
+        for (region_id, requests) in to_read {
+            // Leaves requests to unknown regions to the caller to reject.
+            let Some(region) = self.regions.get_region(region_id) else {
+                write_requests.extend(requests);
+                continue;
+            };
+            self.reading_existing_rows.insert(region_id, Vec::new());
+
+            let version = region.version();
+            let cache_manager = self.cache_manager.clone();
+            let sender = self.sender.clone();
+            common_runtime::spawn_read(async move {
+                let keys = read_existing_keys(&region, &version, cache_manager, &requests)
+                    .await
+                    .map_err(Arc::new);
+                if let Err(e) = &keys {
+                    error!(e; "Failed to read existing rows of region {}", region_id);
+                }
+                let notify = ExistingRowsRead {
+                    region,
+                    version,
+                    requests,
+                    keys,
+                };
+                if sender
+                    .send(WorkerRequest::Background {
+                        region_id,
+                        notify: BackgroundNotify::ExistingRowsRead(notify),
+                    })
+                    .await
+                    .is_err()
+                {
+                    error!(
+                        "Failed to notify existing rows of region {} are read, worker stopped",
+                        region_id
+                    );
+                }
+            });
+        }
+    }
+
+    /// Writes the requests that read existing rows of a region, and requests held
+    /// meanwhile.
+    pub(crate) async fn handle_existing_rows_read(
+        &mut self,
+        region_id: RegionId,
+        read: ExistingRowsRead,
+    ) {
+        let held = self
+            .reading_existing_rows
+            .remove(&region_id)
+            .unwrap_or_default();
+        let ExistingRowsRead {
+            region,
+            version,
+            mut requests,
+            keys,
+        } = read;
+
+        // If the region is altered, truncated or reopened meanwhile, the requests
+        // read the rows again.
+        let unchanged = self.regions.get_region(region_id).is_some_and(|current| {
+            let current_version = current.version();
+            Arc::ptr_eq(&region, &current)
+                && current_version.metadata.schema_version == version.metadata.schema_version
+                && current_version.truncated_entry_id == version.truncated_entry_id
+        });
+        if unchanged {
+            requests = remove_existing_rows(requests, keys);
+        }
+        requests.extend(held);
+
+        self.handle_write_requests(requests, true).await;
+    }
+}
+
+/// Drops rows that exist in the region from put requests in
+/// [InsertMode::IgnoreDuplicates], which then write the remaining rows as usual.
+/// Rows written by earlier requests count as existing, so they are dropped too.
+///
+/// Requests that ignore duplicates fail if the existing rows can't be read.
+fn remove_existing_rows(
+    requests: Vec<SenderWriteRequest>,
+    keys: std::result::Result<ExistingKeys, Arc<Error>>,
+) -> Vec<SenderWriteRequest> {
+    let mut keys = match keys {
+        Ok(keys) => keys,
+        Err(e) => {
+            let mut remaining = Vec::with_capacity(requests.len());
+            for sender_req in requests {
+                if is_ignore_duplicates(&sender_req.request) {
+                    sender_req
+                        .sender
+                        .send(Err(e.clone()).context(WriteGroupSnafu));
+                } else {
+                    remaining.push(sender_req);
+                }
+            }
+            return remaining;
+        }
+    };
+
+    let mut remaining = Vec::with_capacity(requests.len());
+    for mut sender_req in requests {
+        let request = &mut sender_req.request;
+        let Some(key_indices) = keys.key_indices(request) else {
+            // Leaves the malformed request to the write context to reject.
+            remaining.push(sender_req);
+            continue;
+        };
+        let rows = std::mem::take(&mut request.rows);
+        let row_keys = rows
+            .rows
+            .iter()
+            .map(|row| row_key(&rows, row, &key_indices))
+            .collect::<Vec<_>>();
+        request.rows = match (request.op_type, request.insert_mode) {
+            (OpType::Put, InsertMode::IgnoreDuplicates) => {
+                // Remaining rows are new, so the request writes them as usual.
+                request.insert_mode = InsertMode::Overwrite;
+                retain_new_rows(rows, row_keys, &mut keys.keys)
+            }
+            (OpType::Put, InsertMode::Overwrite) => {
+                keys.keys.extend(row_keys);
+                rows
+            }
+            (OpType::Delete, _) => {
+                for key in &row_keys {
+                    keys.keys.remove(key);
+                }
+                rows
+            }
+        };
+        remaining.push(sender_req);
+    }
+    remaining
+}
+
+/// Reads the keys of rows in `version` of `region` that put requests in
+/// [InsertMode::IgnoreDuplicates] in `requests` write.
+///
+/// Only keys of these requests are kept, so the memory used is bounded by the
+/// size of the requests instead of the rows in the region.
+async fn read_existing_keys(
+    region: &MitoRegionRef,
+    version: &VersionRef,
+    cache_manager: CacheManagerRef,
+    requests: &[SenderWriteRequest],
+) -> Result<ExistingKeys> {
+    let metadata = &version.metadata;
+    let time_index = metadata.time_index_column();
+    let key_names = metadata
+        .primary_key_columns()
+        .map(|c| c.column_schema.name.clone())
+        .chain(std::iter::once(time_index.column_schema.name.clone()))
+        .collect::<Vec<_>>();
+    let mut existing = ExistingKeys {
+        key_names,
+        keys: HashSet::new(),
+    };
+
+    let candidates = requests
+        .iter()
+        .map(|req| &req.request)
+        .filter(|request| is_ignore_duplicates(request))
+        .filter_map(|request| {
+            let key_indices = existing.key_indices(request)?;
+            Some(
+                request
+                    .rows
+                    .rows
+                    .iter()
+                    .map(move |row| row_key(&request.rows, row, &key_indices)),
+            )
+        })
+        .flatten()
+        .collect::<HashSet<_>>();
+    let timestamps = candidates.iter().filter_map(|key| match key.last() {
+        Some(Value::Timestamp(ts)) => Some(*ts),
+        _ => None,
+    });
+    let (Some(min), Some(max)) = (timestamps.clone().min(), timestamps.max()) else {
+        return Ok(existing);
+    };
+
+    let ts_type = &time_index.column_schema.data_type;
+    let ts_column = DfExpr::Column(Column::from_name(&time_index.column_schema.name));
+    let min = Value::Timestamp(min)
+        .try_to_scalar_value(ts_type)
+        .context(ConvertValueSnafu)?;
+    let max = Value::Timestamp(max)
+        .try_to_scalar_value(ts_type)
+        .context(ConvertValueSnafu)?;
+    let request = ScanRequest {
+        projection: Some(
+            existing
+                .key_names
+                .iter()
+                .filter_map(|name| metadata.schema.column_index_by_name(name))
+                .collect(),
+        ),
+        filters: vec![
+            Expr::from(ts_column.clone().gt_eq(lit(min))),
+            Expr::from(ts_column.lt_eq(lit(max))),
+        ],
+        ..Default::default()
+    };
+    let mut stream = ScanRegion::new(
+        version.clone(),
+        region.access_layer.clone(),
+        request,
+        Some(cache_manager),
+    )
+    .scanner()?
+    .scan()
+    .await?;
+    while let Some(batch) = stream.try_next().await.context(ReadExistingRowsSnafu {
+        region_id: region.region_id,
+    })? {
+        existing.extend_from_batch(&batch, &candidates);
+    }
+
+    Ok(existing)
+}
+
+/// Keys of rows that exist in a region.
+#[derive(Debug)]
+struct ExistingKeys {
+    /// Names of the primary key columns and the time index, the time index is the last.
+    key_names: Vec<String>,
+    keys: HashSet<RowKey>,
+}
+
+impl ExistingKeys {
+    /// Returns the index of each key column in `request`, or `None` if the time index
+    /// is absent. Absent primary key columns are filled with null, so they map to `None`.
+    fn key_indices(&self, request: &WriteRequest) -> Option<Vec<Option<usize>>> {
+        let indices = self
+            .key_names
+            .iter()
+            .map(|name| request.column_index_by_name(name))
+            .collect::<Vec<_>>();
+        indices.last().copied().flatten()?;
+        Some(indices)
+    }
+
+    /// Adds keys of rows in `batch` that are in `candidates`.
+    fn extend_from_batch(&mut self, batch: &RecordBatch, candidates: &HashSet<RowKey>) {
+        let columns = self
+            .key_names
+            .iter()
+            .map(|name| batch.column_by_name(name))
+            .collect::<Vec<_>>();
+        for row in 0..batch.num_rows() {
+            let key = columns
+                .iter()
+                .map(|column| column.map(|c| c.get(row)).unwrap_or(Value::Null))
+                .collect::<Vec<_>>();
+            if candidates.contains(&key) {
+                self.keys.insert(key);
+            }
+        }
+    }
+}
+
+fn is_ignore_duplicates(request: &WriteRequest) -> bool {
+    request.op_type == OpType::Put && request.insert_mode == InsertMode::IgnoreDuplicates
+}
+
+fn row_key(rows: &Rows, row: &Row, key_indices: &[Option<usize>]) -> RowKey {
+    key_indices
+        .iter()
+        .map(|index| match index {
+            Some(i) => {
+                pb_value_to_value_ref(&row.values[*i], &rows.schema[*i].datatype_extension).into()
+            }
+            None => Value::Null,
+        })
+        .collect()
+}
+
+/// Keeps the rows whose key is not in `existing`, adding kept keys to `existing`.
+fn retain_new_rows(rows: Rows, keys: Vec<RowKey>, existing: &mut HashSet<RowKey>) -> Rows {
+    let Rows { schema, rows } = rows;
+    let rows = rows
+        .into_iter()
+        .zip(keys)
+        .filter_map(|(row, key)| existing.insert(key).then_some(row))
+        .collect();
+    Rows { schema, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::value::ValueData;
+    use api::v1::{ColumnDataType, ColumnSchema, SemanticType};
+    use common_time::Timestamp;
+
+    use super::*;
+    use crate::request::OptionOutputTx;
+
+    fn new_rows(rows: &[(&str, i64)]) -> Rows {
+        Rows {
+            schema: vec![
+                ColumnSchema {
+                    column_name: "host".to_string(),
+                    datatype: ColumnDataType::String as i32,
+                    semantic_type: SemanticType::Tag as i32,
+                    ..Default::default()
+                },
+                ColumnSchema {
+                    column_name: "ts".to_string(),
+                    datatype: ColumnDataType::TimestampMillisecond as i32,
+                    semantic_type: SemanticType::Timestamp as i32,
+                    ..Default::default()
+                },
+            ],
+            rows: rows
+                .iter()
+                .map(|(host, ts)| Row {
+                    values: vec![
+                        api::v1::Value {
+                            value_data: Some(ValueData::StringValue(host.to_string())),
+                        },
+                        api::v1::Value {
+                            value_data: Some(ValueData::TimestampMillisecondValue(*ts)),
+                        },
+                    ],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_retain_new_rows() {
+        let rows = new_rows(&[("a", 1), ("b", 1), ("a", 2), ("a", 1)]);
+        let request = WriteRequest::new(RegionId::new(1, 1), OpType::Put, rows).unwrap();
+        let existing = ExistingKeys {
+            key_names: vec!["host".to_string(), "ts".to_string()],
+            keys: HashSet::new(),
+        };
+        let key_indices = existing.key_indices(&request).unwrap();
+        let keys = request
+            .rows
+            .rows
+            .iter()
+            .map(|row| row_key(&request.rows, row, &key_indices))
+            .collect::<Vec<_>>();
+
+        let mut existing = HashSet::from([vec![
+            Value::from("b"),
+            Value::Timestamp(Timestamp::new_millisecond(1)),
+        ]]);
+        let rows = retain_new_rows(request.rows, keys, &mut existing);
+        assert_eq!(new_rows(&[("a", 1), ("a", 2)]), rows);
+    }
+
+    #[test]
+    fn test_key_indices() {
+        let request =
+            WriteRequest::new(RegionId::new(1, 1), OpType::Put, new_rows(&[("a", 1)])).unwrap();
+        let mut existing = ExistingKeys {
+            key_names: vec!["dc".to_string(), "host".to_string(), "ts".to_string()],
+            keys: HashSet::new(),
+        };
+        let indices = existing.key_indices(&request).unwrap();
+        assert_eq!(vec![None, Some(0), Some(1)], indices);
+        assert_eq!(
+            vec![
+                Value::Null,
+                Value::from("a"),
+                Value::Timestamp(Timestamp::new_millisecond(1))
+            ],
+            row_key(&request.rows, &request.rows.rows[0], &indices)
+        );
+
+        existing.key_names = vec!["host".to_string(), "time".to_string()];
+        assert!(existing.key_indices(&request).is_none());
+    }
+
+    #[test]
+    fn test_remove_existing_rows() {
+        let new_request = |op_type, insert_mode, rows| SenderWriteRequest {
+            sender: OptionOutputTx::none(),
+            request: WriteRequest::new(RegionId::new(1, 1), op_type, new_rows(rows))
+                .unwrap()
+                .with_insert_mode(insert_mode),
+        };
+        let requests = vec![
+            new_request(
+                OpType::Put,
+                InsertMode::IgnoreDuplicates,
+                &[("a", 1), ("b", 1)],
+            ),
+            new_request(OpType::Delete, InsertMode::Overwrite, &[("a", 1)]),
+            new_request(
+                OpType::Put,
+                InsertMode::IgnoreDuplicates,
+                &[("a", 1), ("b", 1)],
+            ),
+        ];
+        let keys = ExistingKeys {
+            key_names: vec!["host".to_string(), "ts".to_string()],
+            keys: HashSet::from([vec![
+                Value::from("b"),
+                Value::Timestamp(Timestamp::new_millisecond(1)),
+            ]]),
+        };
+
+        let requests = remove_existing_rows(requests, Ok(keys));
+        let rows = requests
+            .iter()
+            .map(|req| (req.request.insert_mode, req.request.rows.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (InsertMode::Overwrite, new_rows(&[("a", 1)])),
+                (InsertMode::Overwrite, new_rows(&[("a", 1)])),
+                (InsertMode::Overwrite, new_rows(&[("a", 1)])),
+            ],
+            rows
+        );
+    }
+}
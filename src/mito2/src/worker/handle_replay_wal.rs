@@ -0,0 +1,184 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handling replay WAL request.
+
+use api::helper::pb_value_to_value_ref;
+use api::v1::{OpType, Rows, SemanticType};
+use common_telemetry::{error, info};
+use common_time::Timestamp;
+use common_wal::options::WalOptions;
+use datatypes::value::ValueRef;
+use futures::StreamExt;
+use snafu::{OptionExt, ResultExt};
+use store_api::logstore::LogStore;
+use store_api::region_request::{AffectedRows, RegionReplayWalRequest, WalReplaySource};
+use store_api::storage::RegionId;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+use crate::error::{InvalidRequestSnafu, JsonOptionsSnafu, RecvSnafu, Result, WorkerStoppedSnafu};
+use crate::request::{OptionOutputTx, SenderWriteRequest, WorkerRequest, WriteRequest};
+use crate::wal::Wal;
+use crate::worker::{RegionWorkerLoop, WorkerId};
+
+impl<S: LogStore> RegionWorkerLoop<S> {
+    /// Replays the WAL of the source region in background.
+    ///
+    /// Entries are sent back to the worker as write requests one by one, so the
+    /// worker keeps serving other requests while the region replays.
+    pub(crate) fn handle_replay_wal_request(
+        &mut self,
+        region_id: RegionId,
+        request: RegionReplayWalRequest,
+        mut sender: OptionOutputTx,
+    ) {
+        let Some(region) = self.regions.writable_region_or(region_id, &mut sender) else {
+            return;
+        };
+        let source = request.source;
+        let region_number = region_id.region_number();
+        let wal_options = match source_wal_options(region_id, &source, &region.wal_options) {
+            Ok(wal_options) => wal_options,
+            Err(e) => {
+                sender.send(Err(e));
+                return;
+            }
+        };
+        let start_id = source
+            .region_entry_ids
+            .get(&region_number)
+            .map_or(0, |entry_id| entry_id + 1);
+        let source_region_id = RegionId::new(source.table_id, region_number);
+        let until = source.until.map(Timestamp::new_millisecond);
+
+        let wal = self.wal.clone();
+        let worker_sender = self.sender.clone();
+        let worker_id = self.id;
+        common_runtime::spawn_write(async move {
+            let result = replay_wal(
+                &wal,
+                &worker_sender,
+                worker_id,
+                source_region_id,
+                region_id,
+                start_id,
+                until,
+                &wal_options,
+            )
+            .await;
+            match &result {
+                Ok(rows) => info!(
+                    "Replay WAL of region {} from entry {} into region {}, rows replayed: {}",
+                    source_region_id, start_id, region_id, rows
+                ),
+                Err(e) => error!(
+                    e; "Failed to replay WAL of region {} into region {}",
+                    source_region_id, region_id
+                ),
+            }
+            sender.send(result);
+        });
+    }
+}
+
+/// Returns the WAL options of the source region.
+///
+/// The source region must have WAL options if the region uses a remote WAL, as
+/// the topic of the source region can't be derived.
+fn source_wal_options(
+    region_id: RegionId,
+    source: &WalReplaySource,
+    region_wal_options: &WalOptions,
+) -> Result<WalOptions> {
+    match source.region_wal_options.get(&region_id.region_number()) {
+        Some(options) => serde_json::from_str(options).context(JsonOptionsSnafu),
+        None => match region_wal_options {
+            WalOptions::RaftEngine => Ok(WalOptions::RaftEngine),
+            WalOptions::Kafka(_) => InvalidRequestSnafu {
+                region_id,
+                reason: "WAL options of the source region to replay are missing",
+            }
+            .fail(),
+        },
+    }
+}
+
+/// Writes entries of the source region from `start_id` into the region through the
+/// worker, in the order of the WAL. Rows whose time index is after `until` are skipped.
+#[allow(clippy::too_many_arguments)]
+async fn replay_wal<S: LogStore>(
+    wal: &Wal<S>,
+    worker_sender: &Sender<WorkerRequest>,
+    worker_id: WorkerId,
+    source_region_id: RegionId,
+    region_id: RegionId,
+    start_id: u64,
+    until: Option<Timestamp>,
+    wal_options: &WalOptions,
+) -> Result<AffectedRows> {
+    let mut rows_replayed = 0;
+    let mut wal_stream = wal.scan(source_region_id, start_id, wal_options)?;
+    while let Some(res) = wal_stream.next().await {
+        let (_, entry) = res?;
+        for mutation in entry.mutations {
+            let Some(mut rows) = mutation.rows else {
+                continue;
+            };
+            if let Some(until) = until {
+                retain_rows_until(&mut rows, until);
+            }
+            if rows.rows.is_empty() {
+                continue;
+            }
+            let op_type =
+                OpType::try_from(mutation.op_type)
+                    .ok()
+                    .with_context(|| InvalidRequestSnafu {
+                        region_id,
+                        reason: format!("unknown op type {} in WAL", mutation.op_type),
+                    })?;
+
+            let (tx, rx) = oneshot::channel();
+            let request = WorkerRequest::Write(SenderWriteRequest {
+                sender: tx.into(),
+                request: WriteRequest::new(region_id, op_type, rows)?,
+            });
+            if worker_sender.send(request).await.is_err() {
+                return WorkerStoppedSnafu { id: worker_id }.fail();
+            }
+            rows_replayed += rx.await.context(RecvSnafu)??;
+        }
+    }
+
+    Ok(rows_replayed)
+}
+
+/// Keeps the rows whose time index is at or before `until`.
+fn retain_rows_until(rows: &mut Rows, until: Timestamp) {
+    let Some(index) = rows
+        .schema
+        .iter()
+        .position(|column| column.semantic_type == SemanticType::Timestamp as i32)
+    else {
+        return;
+    };
+    let column = &rows.schema[index];
+    rows.rows.retain(|row| {
+        match pb_value_to_value_ref(&row.values[index], &column.datatype_extension) {
+            ValueRef::Timestamp(ts) => ts <= until,
+            _ => true,
+        }
+    });
+}
@@ -62,6 +62,9 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             truncated_sequence,
             &region.memtable_builder,
         );
+        // Rows written before the truncation no longer make later rows out of order.
+        region.watermarks.reset();
+        self.disk_usage_manager.invalidate();
 
         // Make all data obsolete.
         self.wal
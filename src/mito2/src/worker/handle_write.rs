@@ -16,16 +16,23 @@
 
 use std::collections::{hash_map, HashMap};
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::OpType;
+use common_base::readable_size::ReadableSize;
 use snafu::ensure;
 use store_api::logstore::LogStore;
 use store_api::metadata::RegionMetadata;
 use store_api::storage::RegionId;
 
-use crate::error::{InvalidRequestSnafu, RejectWriteSnafu, Result};
+use crate::config::WriteRateLimitPolicy;
+use crate::error::{
+    DiskFullSnafu, DiskQuotaExceededSnafu, InvalidRequestSnafu, RejectWriteSnafu, Result,
+    WriteRateLimitedSnafu,
+};
 use crate::metrics::{
     WRITE_REJECT_TOTAL, WRITE_ROWS_TOTAL, WRITE_STAGE_ELAPSED, WRITE_STALL_TOTAL,
+    WRITE_THROTTLE_TOTAL,
 };
 use crate::region_write_ctx::RegionWriteCtx;
 use crate::request::{SenderWriteRequest, WriteRequest};
@@ -54,6 +61,13 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             return;
         }
 
+        self.disk_usage_manager.maybe_refresh();
+        if self.disk_usage_manager.is_disk_full() {
+            // The disk is full, reject write requests until the space is released.
+            reject_disk_full_requests(write_requests, self.disk_usage_manager.min_free_space());
+            return;
+        }
+
         if self.write_buffer_manager.should_stall() && allow_stall {
             WRITE_STALL_TOTAL.inc_by(write_requests.len() as u64);
 
@@ -62,6 +76,13 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             return;
         }
 
+        // Requests that ignore duplicates read existing rows in background, and are
+        // written once the rows are read.
+        self.read_existing_rows(&mut write_requests);
+        if write_requests.is_empty() {
+            return;
+        }
+
         // Prepare write context.
         let mut region_ctxs = {
             let _timer = WRITE_STAGE_ELAPSED
@@ -142,6 +163,23 @@ impl<S> RegionWorkerLoop<S> {
                 continue;
             }
 
+            // Rejects requests if the table exceeds its disk quota.
+            if self
+                .disk_usage_manager
+                .is_quota_exceeded(region_id.table_id())
+            {
+                WRITE_REJECT_TOTAL.inc();
+                sender_req
+                    .sender
+                    .send(DiskQuotaExceededSnafu { region_id }.fail());
+                continue;
+            }
+
+            // Throttles requests if the engine or the table writes too fast.
+            let Some(mut sender_req) = self.maybe_throttle_write(sender_req) else {
+                continue;
+            };
+
             // Checks whether the region exists and is it stalling.
             if let hash_map::Entry::Vacant(e) = region_ctxs.entry(region_id) {
                 let Some(region) = self
@@ -200,6 +238,91 @@ impl<S> RegionWorkerLoop<S> {
         self.write_buffer_manager.memory_usage() + self.stalled_requests.estimated_size
             >= self.config.global_write_buffer_reject_size.as_bytes() as usize
     }
+
+    /// Checks the request against write rate limits.
+    ///
+    /// Returns the request if it's allowed. Otherwise, holds or rejects the request
+    /// according to the policy and returns `None`.
+    fn maybe_throttle_write(
+        &mut self,
+        sender_req: SenderWriteRequest,
+    ) -> Option<SenderWriteRequest> {
+        let region_id = sender_req.request.region_id;
+        let now = self.time_provider.current_time_millis();
+        let policy = self.config.write_rate_limit_policy;
+        if policy == WriteRateLimitPolicy::Block
+            && self.throttled_requests.contains_table(region_id.table_id())
+        {
+            // Keeps the order of requests of the same table.
+            self.hold_throttled_write(sender_req, None, now);
+            return None;
+        }
+
+        let Some(region) = self.regions.get_region(region_id) else {
+            // Leaves the request to the caller to report the error.
+            return Some(sender_req);
+        };
+        let table_limit = region.version().options.write_rate_limit;
+        let Some(wait) = self.write_rate_limiter.acquire(
+            region_id.table_id(),
+            &table_limit,
+            sender_req.request.rows.rows.len() as u64,
+            sender_req.request.estimated_size() as u64,
+            now,
+        ) else {
+            return Some(sender_req);
+        };
+
+        match policy {
+            WriteRateLimitPolicy::Block => {
+                self.hold_throttled_write(sender_req, Some(now + wait.as_millis() as i64), now);
+            }
+            WriteRateLimitPolicy::Reject => {
+                WRITE_REJECT_TOTAL.inc();
+                sender_req.sender.send(
+                    WriteRateLimitedSnafu {
+                        region_id,
+                        retry_after: wait,
+                    }
+                    .fail(),
+                );
+            }
+        }
+        None
+    }
+
+    /// Holds the throttled request until `retry_at_millis`, or until other throttled
+    /// requests are retried if it's `None`.
+    ///
+    /// Rejects the request with the time to retry if held requests exceed the buffer
+    /// size, so clients get backpressure instead of the worker buffering without bound.
+    fn hold_throttled_write(
+        &mut self,
+        sender_req: SenderWriteRequest,
+        retry_at_millis: Option<i64>,
+        now_millis: i64,
+    ) {
+        let buffer_size = self.config.write_rate_limit_buffer_size.as_bytes() as usize;
+        if self.throttled_requests.estimated_size() + sender_req.request.estimated_size()
+            > buffer_size
+        {
+            let retry_at = retry_at_millis
+                .or(self.throttled_requests.retry_at_millis)
+                .unwrap_or(now_millis);
+            WRITE_REJECT_TOTAL.inc();
+            sender_req.sender.send(
+                WriteRateLimitedSnafu {
+                    region_id: sender_req.request.region_id,
+                    retry_after: Duration::from_millis((retry_at - now_millis).max(1) as u64),
+                }
+                .fail(),
+            );
+            return;
+        }
+
+        WRITE_THROTTLE_TOTAL.inc();
+        self.throttled_requests.push(sender_req, retry_at_millis);
+    }
 }
 
 /// Send rejected error to all `write_requests`.
@@ -216,6 +339,24 @@ fn reject_write_requests(write_requests: Vec<SenderWriteRequest>) {
     }
 }
 
+/// Send disk full error to all `write_requests`.
+fn reject_disk_full_requests(
+    write_requests: Vec<SenderWriteRequest>,
+    min_free_space: ReadableSize,
+) {
+    WRITE_REJECT_TOTAL.inc_by(write_requests.len() as u64);
+
+    for req in write_requests {
+        req.sender.send(
+            DiskFullSnafu {
+                region_id: req.request.region_id,
+                min_free_space,
+            }
+            .fail(),
+        );
+    }
+}
+
 /// Checks the schema and fill missing columns.
 fn maybe_fill_missing_columns(request: &mut WriteRequest, metadata: &RegionMetadata) -> Result<()> {
     if let Err(e) = request.check_schema(metadata) {
@@ -13,8 +13,10 @@
 // limitations under the License.
 
 mod lru_cache;
+mod multipart;
 mod prometheus;
 
 pub use lru_cache::*;
+pub use multipart::MultipartLayer;
 pub use opendal::layers::*;
 pub use prometheus::PrometheusMetricsLayer;
@@ -46,6 +46,21 @@ impl<C: Accessor + Clone> LruCacheLayer<C> {
         Ok(Self { read_cache })
     }
 
+    /// Enables the memory tier in front of the local file cache, which keeps up to
+    /// `capacity` bytes of hot reads in memory.
+    pub fn with_memory_capacity(self, capacity: usize) -> Self {
+        Self {
+            read_cache: self.read_cache.with_memory_capacity(capacity),
+        }
+    }
+
+    /// Enables prefetching the next range of sequential reads into the local file cache.
+    pub fn with_prefetch(self, enable: bool) -> Self {
+        Self {
+            read_cache: self.read_cache.with_prefetch(enable),
+        }
+    }
+
     /// Returns true when the local cache contains the specific file
     pub async fn contains_file(&self, path: &str) -> bool {
         self.read_cache.contains_file(path).await
@@ -55,6 +70,11 @@ impl<C: Accessor + Clone> LruCacheLayer<C> {
     pub async fn read_cache_stat(&self) -> (u64, u64) {
         self.read_cache.stat().await
     }
+
+    /// Returns the memory tier statistics info `(EntryCount, SizeInBytes)`.
+    pub async fn memory_cache_stat(&self) -> (u64, u64) {
+        self.read_cache.memory_stat().await
+    }
 }
 
 impl<I: Accessor, C: Accessor + Clone> Layer<I> for LruCacheLayer<C> {
@@ -62,7 +82,7 @@ impl<I: Accessor, C: Accessor + Clone> Layer<I> for LruCacheLayer<C> {
 
     fn layer(&self, inner: I) -> Self::LayeredAccessor {
         LruCacheAccessor {
-            inner,
+            inner: Arc::new(inner),
             read_cache: self.read_cache.clone(),
         }
     }
@@ -70,7 +90,7 @@ impl<I: Accessor, C: Accessor + Clone> Layer<I> for LruCacheLayer<C> {
 
 #[derive(Debug)]
 pub struct LruCacheAccessor<I, C: Clone> {
-    inner: I,
+    inner: Arc<I>,
     read_cache: ReadCache<C>,
 }
 
@@ -14,19 +14,24 @@
 
 use std::sync::Arc;
 
+use bytes::Bytes;
 use common_telemetry::logging::debug;
 use futures::FutureExt;
 use moka::future::Cache;
 use moka::notification::ListenerFuture;
-use opendal::raw::oio::{ListExt, Read, ReadExt, Reader, WriteExt};
-use opendal::raw::{Accessor, OpDelete, OpList, OpRead, OpStat, OpWrite, RpRead};
+use opendal::raw::oio::{Cursor, ListExt, Read, ReadExt, Reader, WriteExt};
+use opendal::raw::{Accessor, BytesRange, OpDelete, OpList, OpRead, OpStat, OpWrite, RpRead};
 use opendal::{Error as OpendalError, ErrorKind, Result};
 
 use crate::metrics::{
     OBJECT_STORE_LRU_CACHE_BYTES, OBJECT_STORE_LRU_CACHE_ENTRIES, OBJECT_STORE_LRU_CACHE_HIT,
-    OBJECT_STORE_LRU_CACHE_MISS, OBJECT_STORE_READ_ERROR,
+    OBJECT_STORE_LRU_CACHE_MISS, OBJECT_STORE_MEMORY_CACHE_BYTES, OBJECT_STORE_PREFETCH,
+    OBJECT_STORE_READ_ERROR,
 };
 
+/// Max number of paths to track for sequential read detection.
+const MAX_TRACKED_READS: u64 = 4096;
+
 /// Cache value for read file
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 enum ReadResult {
@@ -67,6 +72,10 @@ pub(crate) struct ReadCache<C: Clone> {
     file_cache: Arc<C>,
     /// Local memory cache to track local cache files
     mem_cache: Cache<String, ReadResult>,
+    /// Optional memory tier in front of the local file cache, holding the content of hot reads.
+    mem_tier: Option<Cache<String, Bytes>>,
+    /// Next expected offset of recently read paths, set when prefetch is enabled.
+    read_offsets: Option<Cache<String, u64>>,
 }
 
 impl<C: Accessor + Clone> ReadCache<C> {
@@ -104,9 +113,34 @@ impl<C: Accessor + Clone> ReadCache<C> {
                 .async_eviction_listener(eviction_listener)
                 .support_invalidation_closures()
                 .build(),
+            mem_tier: None,
+            read_offsets: None,
         }
     }
 
+    /// Enables the memory tier with capacity in bytes, disabled if `capacity` is 0.
+    pub(crate) fn with_memory_capacity(mut self, capacity: usize) -> Self {
+        self.mem_tier = (capacity > 0).then(|| {
+            Cache::builder()
+                .max_capacity(capacity as u64)
+                .weigher(|_key, value: &Bytes| -> u32 {
+                    value.len().try_into().unwrap_or(u32::MAX)
+                })
+                .eviction_listener(|_key, value: Bytes, _cause| {
+                    OBJECT_STORE_MEMORY_CACHE_BYTES.sub(value.len() as i64);
+                })
+                .support_invalidation_closures()
+                .build()
+        });
+        self
+    }
+
+    /// Enables prefetching the next range of sequential reads into the local file cache.
+    pub(crate) fn with_prefetch(mut self, enable: bool) -> Self {
+        self.read_offsets = enable.then(|| Cache::new(MAX_TRACKED_READS));
+        self
+    }
+
     /// Returns the cache's entry count and total approximate entry size in bytes.
     pub(crate) async fn stat(&self) -> (u64, u64) {
         self.mem_cache.run_pending_tasks().await;
@@ -114,16 +148,31 @@ impl<C: Accessor + Clone> ReadCache<C> {
         (self.mem_cache.entry_count(), self.mem_cache.weighted_size())
     }
 
+    /// Returns the memory tier's entry count and total approximate entry size in bytes.
+    pub(crate) async fn memory_stat(&self) -> (u64, u64) {
+        match &self.mem_tier {
+            Some(mem_tier) => {
+                mem_tier.run_pending_tasks().await;
+                (mem_tier.entry_count(), mem_tier.weighted_size())
+            }
+            None => (0, 0),
+        }
+    }
+
     /// Invalidate all cache items which key starts with `prefix`.
     pub(crate) async fn invalidate_entries_with_prefix(&self, prefix: String) {
-        // Safety: always ok when building cache with `support_invalidation_closures`.
-        self.mem_cache
-            .invalidate_entries_if(move |k: &String, &_v| k.starts_with(&prefix))
-            .ok();
+        self.blocking_invalidate_entries_with_prefix(prefix)
     }
 
     /// Blocking version of `invalidate_entries_with_prefix`.
     pub(crate) fn blocking_invalidate_entries_with_prefix(&self, prefix: String) {
+        if let Some(mem_tier) = &self.mem_tier {
+            let prefix = prefix.clone();
+            // Safety: always ok when building cache with `support_invalidation_closures`.
+            mem_tier
+                .invalidate_entries_if(move |k: &String, _v| k.starts_with(&prefix))
+                .ok();
+        }
         // Safety: always ok when building cache with `support_invalidation_closures`.
         self.mem_cache
             .invalidate_entries_if(move |k: &String, &_v| k.starts_with(&prefix))
@@ -170,7 +219,7 @@ impl<C: Accessor + Clone> ReadCache<C> {
     /// and cache the result locally.
     pub(crate) async fn read<I>(
         &self,
-        inner: &I,
+        inner: &Arc<I>,
         path: &str,
         args: OpRead,
     ) -> Result<(RpRead, Box<dyn Read>)>
@@ -181,8 +230,17 @@ impl<C: Accessor + Clone> ReadCache<C> {
             return inner.read(path, args).await.map(to_output_reader);
         }
 
+        self.prefetch_next(inner, path, &args).await;
+
         let read_key = read_cache_key(path, &args);
 
+        if let Some(bytes) = self.get_from_memory(&read_key).await {
+            OBJECT_STORE_LRU_CACHE_HIT
+                .with_label_values(&["memory"])
+                .inc();
+            return Ok(bytes_reader(bytes));
+        }
+
         let read_result = self
             .mem_cache
             .try_get_with(
@@ -201,7 +259,15 @@ impl<C: Accessor + Clone> ReadCache<C> {
                         OBJECT_STORE_LRU_CACHE_HIT
                             .with_label_values(&["success"])
                             .inc();
-                        Ok(to_output_reader(ret))
+                        match &self.mem_tier {
+                            Some(mem_tier) => {
+                                let bytes = read_to_bytes(ret.1).await?;
+                                OBJECT_STORE_MEMORY_CACHE_BYTES.add(bytes.len() as i64);
+                                mem_tier.insert(read_key, bytes.clone()).await;
+                                Ok(bytes_reader(bytes))
+                            }
+                            None => Ok(to_output_reader(ret)),
+                        }
                     }
                     Err(_) => {
                         OBJECT_STORE_LRU_CACHE_MISS.inc();
@@ -222,6 +288,58 @@ impl<C: Accessor + Clone> ReadCache<C> {
         }
     }
 
+    /// Returns the content of `read_key` from the memory tier.
+    async fn get_from_memory(&self, read_key: &str) -> Option<Bytes> {
+        self.mem_tier.as_ref()?.get(read_key).await
+    }
+
+    /// Prefetches the range following `args` into the local file cache in background
+    /// if the read continues the previous read of the same path.
+    async fn prefetch_next<I>(&self, inner: &Arc<I>, path: &str, args: &OpRead)
+    where
+        I: Accessor,
+    {
+        let Some(read_offsets) = &self.read_offsets else {
+            return;
+        };
+        let Some((next_offset, sequential)) =
+            next_read_offset(read_offsets.get(path).await, args.range())
+        else {
+            return;
+        };
+        read_offsets.insert(path.to_string(), next_offset).await;
+        if !sequential {
+            return;
+        }
+
+        let size = next_offset - args.range().offset().unwrap_or_default();
+        let next_args = OpRead::new().with_range(BytesRange::new(Some(next_offset), Some(size)));
+        let read_key = read_cache_key(path, &next_args);
+        if self.mem_cache.contains_key(&read_key) {
+            return;
+        }
+
+        OBJECT_STORE_PREFETCH.inc();
+        let read_cache = self.clone();
+        let inner = inner.clone();
+        let path = path.to_string();
+        let _handle = tokio::spawn(async move {
+            let result = read_cache
+                .mem_cache
+                .try_get_with(
+                    read_key.clone(),
+                    read_cache.read_remote(inner.as_ref(), &read_key, &path, next_args),
+                )
+                .await;
+            if let Err(e) = result {
+                debug!(
+                    "Failed to prefetch `{}` of `{}`, err: {}",
+                    read_key, path, e
+                );
+            }
+        });
+    }
+
     async fn try_write_cache<I>(&self, mut reader: I::Reader, read_key: &str) -> Result<usize>
     where
         I: Accessor,
@@ -281,6 +399,27 @@ impl<C: Accessor + Clone> ReadCache<C> {
     }
 }
 
+/// Returns the offset following the read `range` and whether the read continues
+/// from `last_offset`, or `None` if the range is unbounded.
+fn next_read_offset(last_offset: Option<u64>, range: BytesRange) -> Option<(u64, bool)> {
+    let offset = range.offset()?;
+    let size = range.size()?;
+    Some((offset + size, last_offset == Some(offset)))
+}
+
+async fn read_to_bytes<R: Read>(mut reader: R) -> Result<Bytes> {
+    let mut buf = Vec::new();
+    while let Some(bytes) = reader.next().await {
+        buf.extend_from_slice(&bytes?);
+    }
+    Ok(buf.into())
+}
+
+fn bytes_reader(bytes: Bytes) -> (RpRead, Reader) {
+    let rp = RpRead::new().with_size(Some(bytes.len() as u64));
+    (rp, Box::new(Cursor::from(bytes)))
+}
+
 fn to_output_reader<R: Read + 'static>(input: (RpRead, R)) -> (RpRead, Reader) {
     (input.0, Box::new(input.1))
 }
@@ -299,4 +438,16 @@ mod tests {
         assert!(!can_cache("test/__last_checkpoint"));
         assert!(!can_cache("a/b/c/__last_checkpoint"));
     }
+
+    #[test]
+    fn test_next_read_offset() {
+        let range = BytesRange::new(Some(0), Some(10));
+        assert_eq!(Some((10, false)), next_read_offset(None, range));
+        let range = BytesRange::new(Some(10), Some(10));
+        assert_eq!(Some((20, true)), next_read_offset(Some(10), range));
+        let range = BytesRange::new(Some(30), Some(10));
+        assert_eq!(Some((40, false)), next_read_offset(Some(20), range));
+        let range = BytesRange::new(Some(10), None);
+        assert_eq!(None, next_read_offset(Some(10), range));
+    }
 }
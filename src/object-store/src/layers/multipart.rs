@@ -0,0 +1,114 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use opendal::raw::{
+    Accessor, Layer, LayeredAccessor, OpList, OpRead, OpWrite, RpList, RpRead, RpWrite,
+};
+use opendal::Result;
+
+/// An opendal layer to override the part size and the concurrency of multipart writes.
+///
+/// Backends have different limits for parts, e.g. GCS requires a part size of multiple
+/// of 256KiB, so the layer applies options of a specific backend to all writers.
+#[derive(Debug, Clone, Default)]
+pub struct MultipartLayer {
+    part_size: Option<usize>,
+    concurrency: Option<usize>,
+}
+
+impl MultipartLayer {
+    /// Sets the size in bytes of each part to upload.
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        self.part_size = Some(part_size);
+        self
+    }
+
+    /// Sets the number of parts to upload concurrently.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+}
+
+impl<I: Accessor> Layer<I> for MultipartLayer {
+    type LayeredAccessor = MultipartAccessor<I>;
+
+    fn layer(&self, inner: I) -> Self::LayeredAccessor {
+        MultipartAccessor {
+            inner,
+            part_size: self.part_size,
+            concurrency: self.concurrency,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MultipartAccessor<I> {
+    inner: I,
+    part_size: Option<usize>,
+    concurrency: Option<usize>,
+}
+
+impl<I> MultipartAccessor<I> {
+    /// Applies multipart options to the write `args`.
+    fn write_args(&self, mut args: OpWrite) -> OpWrite {
+        if let Some(part_size) = self.part_size {
+            args = args.with_buffer(part_size);
+        }
+        if let Some(concurrency) = self.concurrency {
+            args = args.with_concurrent(concurrency);
+        }
+        args
+    }
+}
+
+#[async_trait]
+impl<I: Accessor> LayeredAccessor for MultipartAccessor<I> {
+    type Inner = I;
+    type Reader = I::Reader;
+    type BlockingReader = I::BlockingReader;
+    type Writer = I::Writer;
+    type BlockingWriter = I::BlockingWriter;
+    type Lister = I::Lister;
+    type BlockingLister = I::BlockingLister;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner.write(path, self.write_args(args)).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Lister)> {
+        self.inner.list(path, args).await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, self.write_args(args))
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingLister)> {
+        self.inner.blocking_list(path, args)
+    }
+}
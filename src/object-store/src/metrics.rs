@@ -47,4 +47,14 @@ lazy_static! {
     pub static ref OBJECT_STORE_LRU_CACHE_BYTES: IntGauge =
         register_int_gauge!("greptime_object_store_lru_cache_bytes",  "object store lru cache bytes")
             .unwrap();
+
+    /// Memory tier size in bytes
+    pub static ref OBJECT_STORE_MEMORY_CACHE_BYTES: IntGauge =
+        register_int_gauge!("greptime_object_store_memory_cache_bytes", "object store memory cache bytes")
+            .unwrap();
+
+    /// Prefetch counter of sequential reads
+    pub static ref OBJECT_STORE_PREFETCH: IntCounter =
+        register_int_counter!("greptime_object_store_prefetch", "object store prefetch")
+            .unwrap();
 }
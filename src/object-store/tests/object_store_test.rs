@@ -18,7 +18,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use common_telemetry::logging;
 use common_test_util::temp_dir::create_temp_dir;
-use object_store::layers::LruCacheLayer;
+use object_store::layers::{LruCacheLayer, MultipartLayer};
 use object_store::services::{Fs, S3};
 use object_store::test_util::TempFolder;
 use object_store::{ObjectStore, ObjectStoreBuilder};
@@ -194,14 +194,14 @@ async fn test_azblob_backend() -> Result<()> {
 #[tokio::test]
 async fn test_gcs_backend() -> Result<()> {
     logging::init_default_ut_logging();
-    if let Ok(container) = env::var("GT_AZBLOB_CONTAINER") {
-        if !container.is_empty() {
-            logging::info!("Running azblob test.");
+    if let Ok(bucket) = env::var("GT_GCS_BUCKET") {
+        if !bucket.is_empty() {
+            logging::info!("Running gcs test.");
 
             let mut builder = Gcs::default();
             builder
                 .root(&uuid::Uuid::new_v4().to_string())
-                .bucket(&env::var("GT_GCS_BUCKET").unwrap())
+                .bucket(&bucket)
                 .scope(&env::var("GT_GCS_SCOPE").unwrap())
                 .credential_path(&env::var("GT_GCS_CREDENTIAL_PATH").unwrap())
                 .endpoint(&env::var("GT_GCS_ENDPOINT").unwrap());
@@ -217,6 +217,37 @@ async fn test_gcs_backend() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_file_backend_with_multipart_layer() -> Result<()> {
+    logging::init_default_ut_logging();
+
+    let data_dir = create_temp_dir("test_file_backend_with_multipart_layer");
+    let tmp_dir = create_temp_dir("test_file_backend_with_multipart_layer");
+    let mut builder = Fs::default();
+    let _ = builder
+        .root(&data_dir.path().to_string_lossy())
+        .atomic_write_dir(&tmp_dir.path().to_string_lossy());
+
+    let store = ObjectStore::new(builder).unwrap().finish().layer(
+        MultipartLayer::default()
+            .with_part_size(4)
+            .with_concurrency(2),
+    );
+
+    test_object_crud(&store).await?;
+    test_object_list(&store).await?;
+
+    // Writes the file in multiple parts.
+    let mut writer = store.writer("test_multipart").await?;
+    writer.write("Hello, ").await?;
+    writer.write("multipart!").await?;
+    writer.close().await?;
+    let bs = store.read("test_multipart").await?;
+    assert_eq!("Hello, multipart!", String::from_utf8(bs)?);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_file_backend_with_lru_cache() -> Result<()> {
     logging::init_default_ut_logging();
@@ -458,3 +489,67 @@ async fn test_object_store_cache_policy() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_object_store_cache_tiers() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+    let root_dir = create_temp_dir("test_object_store_cache_tiers");
+    let store = OperatorBuilder::new(
+        Fs::default()
+            .root(&root_dir.path().to_string_lossy())
+            .atomic_write_dir(&root_dir.path().to_string_lossy())
+            .build()
+            .unwrap(),
+    )
+    .finish();
+
+    let cache_dir = create_temp_dir("test_object_store_cache_tiers_cache");
+    let atomic_temp_dir = create_temp_dir("test_object_store_cache_tiers_cache_tmp");
+    let mut builder = Fs::default();
+    let _ = builder
+        .root(&cache_dir.path().to_string_lossy())
+        .atomic_write_dir(&atomic_temp_dir.path().to_string_lossy());
+    let file_cache = Arc::new(builder.build().unwrap());
+
+    let cache_layer = LruCacheLayer::new(Arc::new(file_cache), 1024)
+        .await
+        .unwrap()
+        .with_memory_capacity(1024)
+        .with_prefetch(true);
+    let store = store.layer(cache_layer.clone());
+
+    let p1 = "test_file1";
+    store.write(p1, "Hello, object1!").await.unwrap();
+
+    // The first read fills both the local file cache and the memory tier,
+    // then the following reads are served from memory.
+    for _ in 0..3 {
+        let bs = store.read_with(p1).range(0..5).await?;
+        assert_eq!("Hello", String::from_utf8(bs)?);
+    }
+    assert_eq!(cache_layer.memory_cache_stat().await, (1, 5));
+
+    // Writing the file invalidates both tiers.
+    store.write(p1, "Hi, object1!").await.unwrap();
+    assert_eq!(cache_layer.memory_cache_stat().await, (0, 0));
+    assert_eq!(cache_layer.read_cache_stat().await, (0, 0));
+    let bs = store.read_with(p1).range(0..5).await?;
+    assert_eq!("Hi, o", String::from_utf8(bs)?);
+
+    // The sequential read prefetches the next range.
+    let _ = store.read_with(p1).range(5..8).await?;
+    let mut prefetched = false;
+    for _ in 0..50 {
+        if cache_layer
+            .contains_file("6d29752bdc6e4d5ba5483b96615d6c48.cache-bytes=8-10")
+            .await
+        {
+            prefetched = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(prefetched);
+
+    Ok(())
+}
@@ -27,6 +27,7 @@ use futures_util::future;
 use partition::manager::PartitionRuleManagerRef;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
+use store_api::region_request::LeaderEpochs;
 use table::requests::DeleteRequest as TableDeleteRequest;
 use table::TableRef;
 
@@ -34,6 +35,7 @@ use crate::error::{
     CatalogSnafu, FindRegionLeaderSnafu, InvalidDeleteRequestSnafu, JoinTaskSnafu,
     MissingTimeIndexColumnSnafu, RequestDeletesSnafu, Result, TableNotFoundSnafu,
 };
+use crate::query_cache::{written_tables, QueryCacheRef};
 use crate::region_req_factory::RegionRequestFactory;
 use crate::req_convert::delete::{ColumnToRow, RowToRegion, TableToRegion};
 
@@ -41,6 +43,7 @@ pub struct Deleter {
     catalog_manager: CatalogManagerRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_manager: DatanodeManagerRef,
+    query_cache: Option<QueryCacheRef>,
 }
 
 pub type DeleterRef = Arc<Deleter>;
@@ -55,6 +58,15 @@ impl Deleter {
             catalog_manager,
             partition_manager,
             datanode_manager,
+            query_cache: None,
+        }
+    }
+
+    /// Invalidates cached query results of tables this deleter deletes from.
+    pub fn with_query_cache(self, query_cache: QueryCacheRef) -> Self {
+        Self {
+            query_cache: Some(query_cache),
+            ..self
         }
     }
 
@@ -126,12 +138,16 @@ impl Deleter {
             dbname: ctx.get_db_string(),
         });
 
+        let written_tables = self
+            .query_cache
+            .as_ref()
+            .map(|_| written_tables(requests.requests.iter().map(|r| r.region_id)));
         let tasks = self
             .group_requests_by_peer(requests)
             .await?
             .into_iter()
-            .map(|(peer, deletes)| {
-                let request = request_factory.build_delete(deletes);
+            .map(|(peer, (deletes, leader_epochs))| {
+                let request = request_factory.build_delete(deletes, &leader_epochs);
                 let datanode_manager = self.datanode_manager.clone();
                 common_runtime::spawn_write(async move {
                     datanode_manager
@@ -143,6 +159,10 @@ impl Deleter {
                 })
             });
         let results = future::try_join_all(tasks).await.context(JoinTaskSnafu)?;
+        // Some regions may apply the deletes even if others fail.
+        if let (Some(query_cache), Some(tables)) = (&self.query_cache, written_tables) {
+            query_cache.invalidate_tables(tables);
+        }
 
         let affected_rows = results
             .into_iter()
@@ -155,16 +175,19 @@ impl Deleter {
     async fn group_requests_by_peer(
         &self,
         requests: RegionDeleteRequests,
-    ) -> Result<HashMap<Peer, RegionDeleteRequests>> {
-        let mut deletes: HashMap<Peer, RegionDeleteRequests> = HashMap::new();
+    ) -> Result<HashMap<Peer, (RegionDeleteRequests, LeaderEpochs)>> {
+        let mut deletes: HashMap<Peer, (RegionDeleteRequests, LeaderEpochs)> = HashMap::new();
 
         for req in requests.requests {
-            let peer = self
+            let region_id = req.region_id.into();
+            let (peer, leader_epoch) = self
                 .partition_manager
-                .find_region_leader(req.region_id.into())
+                .find_region_leader_with_epoch(region_id)
                 .await
                 .context(FindRegionLeaderSnafu)?;
-            deletes.entry(peer).or_default().requests.push(req);
+            let (requests, leader_epochs) = deletes.entry(peer).or_default();
+            requests.requests.push(req);
+            leader_epochs.insert(region_id, leader_epoch);
         }
 
         Ok(deletes)
@@ -31,6 +31,79 @@ pub enum Error {
     #[snafu(display("Table already exists: `{}`", table))]
     TableAlreadyExists { table: String, location: Location },
 
+    #[snafu(display(
+        "Table `{}` already exists with a different schema: {}",
+        table,
+        mismatches
+    ))]
+    TableSchemaMismatch {
+        table: String,
+        mismatches: String,
+        location: Location,
+    },
+
+    #[snafu(display("View already exists: `{}`", view))]
+    ViewAlreadyExists { view: String, location: Location },
+
+    #[snafu(display("View not found: `{}`", view))]
+    ViewNotFound { view: String, location: Location },
+
+    #[snafu(display("Role already exists: `{}`", role))]
+    RoleAlreadyExists { role: String, location: Location },
+
+    #[snafu(display("Role not found: `{}`", role))]
+    RoleNotFound { role: String, location: Location },
+
+    #[snafu(display("Cannot remove the last member of the admin role: `{}`", user))]
+    LastAdmin { user: String, location: Location },
+
+    #[snafu(display("Cannot drop the built-in role: `{}`", role))]
+    DropBuiltinRole { role: String, location: Location },
+
+    #[snafu(display("User already exists: `{}`", user))]
+    UserAlreadyExists { user: String, location: Location },
+
+    #[snafu(display("User not found: `{}`", user))]
+    UserNotFound { user: String, location: Location },
+
+    #[snafu(display("Failed to hash the password of user `{}`", user))]
+    HashPassword {
+        user: String,
+        location: Location,
+        source: auth::error::Error,
+    },
+
+    #[snafu(display(
+        "Cannot drop table `{}` because views depend on it: {}",
+        table,
+        dependents
+    ))]
+    TableHasDependents {
+        table: String,
+        dependents: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Table is renamed to `{}` but failed to rebind view `{}` to it: {}",
+        table,
+        view,
+        reason
+    ))]
+    RebindView {
+        table: String,
+        view: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid materialized view `{}`: {}", view, reason))]
+    InvalidMaterializedView {
+        view: String,
+        reason: String,
+        location: Location,
+    },
+
     #[snafu(display("Failed to invalidate table cache"))]
     InvalidateTableCache {
         location: Location,
@@ -61,6 +134,13 @@ pub enum Error {
         source: common_meta::error::Error,
     },
 
+    #[snafu(display("Failed to decode out-of-order rows from the region response"))]
+    DecodeOutOfOrderRows {
+        #[snafu(source)]
+        error: prost::DecodeError,
+        location: Location,
+    },
+
     #[snafu(display("Failed to delete data"))]
     RequestDeletes {
         location: Location,
@@ -115,12 +195,44 @@ pub enum Error {
     #[snafu(display("Invalid InsertRequest, reason: {}", reason))]
     InvalidInsertRequest { reason: String, location: Location },
 
+    #[snafu(display(
+        "Column `{}` of table `{}` is of type {}, but got values of type {} in rows {:?}",
+        column,
+        table_name,
+        expected,
+        actual,
+        rows
+    ))]
+    IncompatibleFieldType {
+        table_name: String,
+        column: String,
+        expected: String,
+        actual: String,
+        rows: Vec<usize>,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid insert mode"))]
+    InvalidInsertMode {
+        location: Location,
+        source: store_api::metadata::MetadataError,
+    },
+
     #[snafu(display("Invalid DeleteRequest, reason: {}", reason))]
     InvalidDeleteRequest { reason: String, location: Location },
 
     #[snafu(display("Table not found: {}", table_name))]
     TableNotFound { table_name: String },
 
+    #[snafu(display(
+        "Table not found: {}, creating tables automatically is disabled",
+        table_name
+    ))]
+    AutoCreateTableDisabled {
+        table_name: String,
+        location: Location,
+    },
+
     #[snafu(display("Failed to join task"))]
     JoinTask {
         #[snafu(source)]
@@ -159,6 +271,20 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to find region routes for table {}", table_id))]
+    FindRegionRoutes {
+        table_id: store_api::storage::TableId,
+        source: partition::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to optimize logical plan"))]
+    OptimizeLogicalPlan {
+        #[snafu(source)]
+        error: datafusion_common::DataFusionError,
+        location: Location,
+    },
+
     #[snafu(display("Failed to create table info"))]
     CreateTableInfo {
         location: Location,
@@ -237,6 +363,12 @@ pub enum Error {
     #[snafu(display("Not supported: {}", feat))]
     NotSupported { feat: String },
 
+    #[snafu(display("Unknown query id: {}", id))]
+    ProcessNotFound { id: u64, location: Location },
+
+    #[snafu(display("Quota exceeded: {}", reason))]
+    QuotaExceeded { reason: String, location: Location },
+
     #[snafu(display("Failed to find new columns on insertion"))]
     FindNewColumnsOnInsertion {
         location: Location,
@@ -353,6 +485,14 @@ pub enum Error {
         error: object_store::Error,
     },
 
+    #[snafu(display("Failed to write object to path: {}", path))]
+    WriteObject {
+        path: String,
+        location: Location,
+        #[snafu(source)]
+        error: object_store::Error,
+    },
+
     #[snafu(display("Failed to read record batch"))]
     ReadDfRecordBatch {
         #[snafu(source)]
@@ -430,6 +570,20 @@ pub enum Error {
     #[snafu(display("Invalid COPY DATABASE location, must end with '/': {}", value))]
     InvalidCopyDatabasePath { value: String, location: Location },
 
+    #[snafu(display("A backup already exists in {}", value))]
+    BackupAlreadyExists { value: String, location: Location },
+
+    #[snafu(display("No backup found in {}", value))]
+    BackupNotFound { value: String, location: Location },
+
+    #[snafu(display("Failed to decode backup manifest {}", path))]
+    DecodeBackupManifest {
+        path: String,
+        #[snafu(source)]
+        error: serde_json::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("Table metadata manager error"))]
     TableMetadataManager {
         source: common_meta::error::Error,
@@ -448,6 +602,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[cfg(feature = "pprof")]
+    #[snafu(display("Failed to profile the statement"))]
+    Profile {
+        source: common_pprof::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("Missing insert body"))]
     MissingInsertBody {
         source: sql::error::Error,
@@ -551,6 +712,8 @@ impl ErrorExt for Error {
             Error::InvalidSql { .. }
             | Error::InvalidConfigValue { .. }
             | Error::InvalidInsertRequest { .. }
+            | Error::IncompatibleFieldType { .. }
+            | Error::InvalidInsertMode { .. }
             | Error::InvalidDeleteRequest { .. }
             | Error::IllegalPrimaryKeysDef { .. }
             | Error::SchemaNotFound { .. }
@@ -568,9 +731,25 @@ impl ErrorExt for Error {
             | Error::InferFileTableSchema { .. }
             | Error::SchemaIncompatible { .. }
             | Error::UnsupportedRegionRequest { .. }
-            | Error::InvalidTableName { .. } => StatusCode::InvalidArguments,
-
-            Error::TableAlreadyExists { .. } => StatusCode::TableAlreadyExists,
+            | Error::InvalidTableName { .. }
+            | Error::InvalidMaterializedView { .. }
+            | Error::TableHasDependents { .. }
+            | Error::RebindView { .. }
+            | Error::RoleAlreadyExists { .. }
+            | Error::RoleNotFound { .. }
+            | Error::LastAdmin { .. }
+            | Error::DropBuiltinRole { .. }
+            | Error::UserAlreadyExists { .. }
+            | Error::UserNotFound { .. }
+            | Error::ProcessNotFound { .. } => StatusCode::InvalidArguments,
+
+            Error::QuotaExceeded { .. } => StatusCode::QuotaExceeded,
+
+            Error::HashPassword { source, .. } => source.status_code(),
+
+            Error::TableAlreadyExists { .. }
+            | Error::TableSchemaMismatch { .. }
+            | Error::ViewAlreadyExists { .. } => StatusCode::TableAlreadyExists,
 
             Error::NotSupported { .. } => StatusCode::Unsupported,
 
@@ -595,6 +774,7 @@ impl ErrorExt for Error {
             | Error::IntoVectors { source, .. } => source.status_code(),
 
             Error::RequestInserts { source, .. } => source.status_code(),
+            Error::DecodeOutOfOrderRows { .. } => StatusCode::Internal,
             Error::RequestRegion { source, .. } => source.status_code(),
             Error::RequestDeletes { source, .. } => source.status_code(),
 
@@ -605,12 +785,15 @@ impl ErrorExt for Error {
             Error::MissingTimeIndexColumn { source, .. } => source.status_code(),
 
             Error::BuildDfLogicalPlan { .. }
+            | Error::OptimizeLogicalPlan { .. }
             | Error::BuildTableMeta { .. }
             | Error::MissingInsertBody { .. } => StatusCode::Internal,
 
             Error::EncodeJson { .. } => StatusCode::Unexpected,
 
-            Error::TableNotFound { .. } => StatusCode::TableNotFound,
+            Error::TableNotFound { .. }
+            | Error::ViewNotFound { .. }
+            | Error::AutoCreateTableDisabled { .. } => StatusCode::TableNotFound,
 
             Error::JoinTask { .. } => StatusCode::Internal,
 
@@ -638,11 +821,13 @@ impl ErrorExt for Error {
             | Error::FindTablePartitionRule { source, .. }
             | Error::SplitInsert { source, .. }
             | Error::SplitDelete { source, .. }
-            | Error::FindRegionLeader { source, .. } => source.status_code(),
+            | Error::FindRegionLeader { source, .. }
+            | Error::FindRegionRoutes { source, .. } => source.status_code(),
 
             Error::UnrecognizedTableOption { .. } => StatusCode::InvalidArguments,
 
             Error::ReadObject { .. }
+            | Error::WriteObject { .. }
             | Error::ReadParquetMetadata { .. }
             | Error::ReadOrc { .. } => StatusCode::StorageUnavailable,
 
@@ -651,9 +836,14 @@ impl ErrorExt for Error {
             | Error::BuildBackend { source, .. } => source.status_code(),
 
             Error::ExecuteDdl { source, .. } => source.status_code(),
-            Error::InvalidCopyParameter { .. } | Error::InvalidCopyDatabasePath { .. } => {
-                StatusCode::InvalidArguments
-            }
+            Error::InvalidCopyParameter { .. }
+            | Error::InvalidCopyDatabasePath { .. }
+            | Error::BackupAlreadyExists { .. }
+            | Error::BackupNotFound { .. }
+            | Error::DecodeBackupManifest { .. } => StatusCode::InvalidArguments,
+
+            #[cfg(feature = "pprof")]
+            Error::Profile { source, .. } => source.status_code(),
 
             Error::ReadRecordBatch { source, .. } | Error::BuildColumnVectors { source, .. } => {
                 source.status_code()
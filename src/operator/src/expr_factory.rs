@@ -32,12 +32,12 @@ use query::sql::{
 use session::context::QueryContextRef;
 use session::table_name::table_idents_to_full_name;
 use snafu::{ensure, ResultExt};
-use sql::ast::{ColumnDef, ColumnOption, TableConstraint};
+use sql::ast::{ColumnDef, ColumnOption, DataType as SqlDataType, TableConstraint};
 use sql::statements::alter::{AlterTable, AlterTableOperation};
 use sql::statements::create::{CreateExternalTable, CreateTable, TIME_INDEX};
 use sql::statements::{column_def_to_schema, sql_column_def_to_grpc_column_def};
 use sql::util::to_lowercase_options_map;
-use table::requests::{TableOptions, FILE_TABLE_META_KEY};
+use table::requests::{TableOptions, CASE_INSENSITIVE_COLUMNS_KEY, FILE_TABLE_META_KEY};
 use table::table_reference::TableReference;
 
 use crate::error::{
@@ -189,11 +189,26 @@ pub fn create_to_expr(create: &CreateTable, query_ctx: QueryContextRef) -> Resul
             .context(ExternalSnafu)?;
 
     let time_index = find_time_index(&create.constraints)?;
-    let table_options = HashMap::from(
+    let mut table_options = HashMap::from(
         &TableOptions::try_from(&to_lowercase_options_map(&create.options))
             .context(UnrecognizedTableOptionSnafu)?,
     );
 
+    let case_insensitive_columns = find_case_insensitive_columns(&create.columns)?;
+    if !case_insensitive_columns.is_empty() {
+        let columns = table_options
+            .entry(CASE_INSENSITIVE_COLUMNS_KEY.to_string())
+            .or_default();
+        for column in case_insensitive_columns {
+            if !columns.split(',').any(|c| c.trim() == column) {
+                if !columns.is_empty() {
+                    columns.push(',');
+                }
+                columns.push_str(&column);
+            }
+        }
+    }
+
     let primary_keys = find_primary_keys(&create.columns, &create.constraints)?;
 
     let expr = CreateTableExpr {
@@ -217,6 +232,44 @@ pub fn create_to_expr(create: &CreateTable, query_ctx: QueryContextRef) -> Resul
     Ok(expr)
 }
 
+/// Finds string columns declared with a case-insensitive collation, e.g.
+/// `host STRING COLLATE utf8mb4_general_ci`.
+fn find_case_insensitive_columns(columns: &[ColumnDef]) -> Result<Vec<String>> {
+    let mut case_insensitive_columns = Vec::new();
+    for column in columns {
+        let Some(collation) = &column.collation else {
+            continue;
+        };
+        let collation = collation.to_string().to_lowercase();
+        if collation.ends_with("_bin") || collation.ends_with("_cs") {
+            continue;
+        }
+        ensure!(
+            collation.ends_with("_ci"),
+            InvalidSqlSnafu {
+                err_msg: format!("Unsupported collation: {collation}"),
+            }
+        );
+        ensure!(
+            matches!(
+                column.data_type,
+                SqlDataType::String
+                    | SqlDataType::Text
+                    | SqlDataType::Varchar(_)
+                    | SqlDataType::Char(_)
+            ),
+            InvalidSqlSnafu {
+                err_msg: format!(
+                    "Collation {collation} is only supported on string columns, column: {}",
+                    column.name
+                ),
+            }
+        );
+        case_insensitive_columns.push(column.name.value.clone());
+    }
+    Ok(case_insensitive_columns)
+}
+
 fn find_primary_keys(
     columns: &[ColumnDef],
     constraints: &[TableConstraint],
@@ -450,6 +503,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_to_expr_with_collation() {
+        let sql = "CREATE TABLE monitor (host STRING COLLATE utf8mb4_general_ci, idc STRING COLLATE utf8mb4_bin, ts TIMESTAMP, TIME INDEX (ts)) ENGINE=mito;";
+        let stmt =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap()
+                .pop()
+                .unwrap();
+
+        let Statement::CreateTable(create_table) = stmt else {
+            unreachable!()
+        };
+        let expr = create_to_expr(&create_table, QueryContext::arc()).unwrap();
+        assert_eq!(
+            "host",
+            expr.table_options
+                .get(CASE_INSENSITIVE_COLUMNS_KEY)
+                .unwrap()
+        );
+
+        let sql = "CREATE TABLE monitor (v DOUBLE COLLATE utf8mb4_general_ci, ts TIMESTAMP, TIME INDEX (ts)) ENGINE=mito;";
+        let stmt =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap()
+                .pop()
+                .unwrap();
+
+        let Statement::CreateTable(create_table) = stmt else {
+            unreachable!()
+        };
+        assert!(create_to_expr(&create_table, QueryContext::arc()).is_err());
+    }
+
     #[test]
     fn test_create_to_expr_with_default_timestamp_value() {
         let sql = "CREATE TABLE monitor (v double,ts TIMESTAMP default '2024-01-30T00:01:01',TIME INDEX (ts)) engine=mito;";
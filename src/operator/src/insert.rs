@@ -15,8 +15,10 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use api::helper::ColumnDataTypeWrapper;
 use api::v1::alter_expr::Kind;
 use api::v1::region::{InsertRequests as RegionInsertRequests, RegionRequestHeader};
+use api::v1::value::ValueData;
 use api::v1::{
     AlterExpr, ColumnDataType, ColumnSchema, CreateTableExpr, InsertRequests, RowInsertRequest,
     RowInsertRequests, SemanticType,
@@ -26,30 +28,40 @@ use client::{OutputData, OutputMeta};
 use common_catalog::consts::default_engine;
 use common_grpc_expr::util::{extract_new_columns, ColumnExpr};
 use common_meta::datanode_manager::{AffectedRows, DatanodeManagerRef};
+use common_meta::key::schema_name::{AutoCreateTablePolicy, IngestProtocol};
 use common_meta::peer::Peer;
 use common_query::prelude::{GREPTIME_TIMESTAMP, GREPTIME_VALUE};
 use common_query::Output;
 use common_telemetry::tracing_context::TracingContext;
-use common_telemetry::{error, info};
+use common_telemetry::{error, info, warn};
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
 use datatypes::schema::Schema;
 use futures_util::future;
 use meter_macros::write_meter;
 use partition::manager::PartitionRuleManagerRef;
-use session::context::QueryContextRef;
+use prost::Message;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::prelude::*;
 use sql::statements::insert::Insert;
 use store_api::metric_engine_consts::{
     LOGICAL_TABLE_METADATA_KEY, METRIC_ENGINE_NAME, PHYSICAL_TABLE_METADATA_KEY,
 };
+use store_api::mito_engine_options::OUT_OF_ORDER_ROWS_EXTENSION_KEY_PREFIX;
+use store_api::region_request::{InsertMode, LeaderEpochs, INSERT_MODE_KEY};
 use table::requests::InsertRequest as TableInsertRequest;
 use table::table_reference::TableReference;
 use table::TableRef;
 
 use crate::error::{
-    CatalogSnafu, FindNewColumnsOnInsertionSnafu, FindRegionLeaderSnafu, InvalidInsertRequestSnafu,
-    JoinTaskSnafu, RequestInsertsSnafu, Result, TableNotFoundSnafu,
+    AutoCreateTableDisabledSnafu, CatalogSnafu, DecodeOutOfOrderRowsSnafu,
+    FindNewColumnsOnInsertionSnafu, FindRegionLeaderSnafu, IncompatibleFieldTypeSnafu,
+    InvalidInsertModeSnafu, InvalidInsertRequestSnafu, JoinTaskSnafu, RequestInsertsSnafu, Result,
+    TableNotFoundSnafu,
 };
 use crate::expr_factory::CreateExprFactory;
+use crate::query_cache::{written_tables, QueryCacheRef};
+use crate::quota::QuotaManagerRef;
 use crate::region_req_factory::RegionRequestFactory;
 use crate::req_convert::insert::{ColumnToRow, RowToRegion, StatementToRegion, TableToRegion};
 use crate::statement::StatementExecutor;
@@ -58,6 +70,8 @@ pub struct Inserter {
     catalog_manager: CatalogManagerRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_manager: DatanodeManagerRef,
+    quota_manager: Option<QuotaManagerRef>,
+    query_cache: Option<QueryCacheRef>,
 }
 
 pub type InserterRef = Arc<Inserter>;
@@ -72,6 +86,24 @@ impl Inserter {
             catalog_manager,
             partition_manager,
             datanode_manager,
+            quota_manager: None,
+            query_cache: None,
+        }
+    }
+
+    /// Rejects inserts exceeding the ingestion quotas of databases.
+    pub fn with_quota_manager(self, quota_manager: QuotaManagerRef) -> Self {
+        Self {
+            quota_manager: Some(quota_manager),
+            ..self
+        }
+    }
+
+    /// Invalidates cached query results of tables this inserter writes to.
+    pub fn with_query_cache(self, query_cache: QueryCacheRef) -> Self {
+        Self {
+            query_cache: Some(query_cache),
+            ..self
         }
     }
 
@@ -88,8 +120,33 @@ impl Inserter {
 
     pub async fn handle_row_inserts(
         &self,
-        mut requests: RowInsertRequests,
+        requests: RowInsertRequests,
+        ctx: QueryContextRef,
+        statement_executor: &StatementExecutor,
+    ) -> Result<Output> {
+        self.handle_protocol_row_inserts(requests, ctx, None, statement_executor)
+            .await
+    }
+
+    /// Handle row inserts request from `protocol`, tables are created on demand
+    /// according to the auto creation policy of the protocol in the schema.
+    pub async fn handle_protocol_row_inserts(
+        &self,
+        requests: RowInsertRequests,
         ctx: QueryContextRef,
+        protocol: Option<IngestProtocol>,
+        statement_executor: &StatementExecutor,
+    ) -> Result<Output> {
+        self.do_protocol_row_inserts(requests, &ctx, protocol, statement_executor)
+            .await
+            .inspect_err(|_| observe_ingest_error(&ctx))
+    }
+
+    async fn do_protocol_row_inserts(
+        &self,
+        mut requests: RowInsertRequests,
+        ctx: &QueryContextRef,
+        protocol: Option<IngestProtocol>,
         statement_executor: &StatementExecutor,
     ) -> Result<Output> {
         // remove empty requests
@@ -101,24 +158,52 @@ impl Inserter {
         });
         validate_column_count_match(&requests)?;
 
-        self.create_or_alter_tables_on_demand(&requests, &ctx, None, statement_executor)
+        let policy = self
+            .ingest_policy(&mut requests, ctx, protocol, statement_executor)
             .await?;
+        self.check_field_types(&mut requests, ctx, policy.strict)
+            .await?;
+        self.create_or_alter_tables_on_demand(
+            &requests,
+            ctx,
+            None,
+            &policy.auto_create_table,
+            statement_executor,
+        )
+        .await?;
         let inserts = RowToRegion::new(
             self.catalog_manager.as_ref(),
             self.partition_manager.as_ref(),
-            &ctx,
+            ctx,
         )
         .convert(requests)
         .await?;
 
-        self.do_request(inserts, &ctx).await
+        self.do_request(inserts, ctx).await
     }
 
     /// Handle row inserts request with metric engine.
+    ///
+    /// The engine and table options of the auto creation policy don't apply as
+    /// logical tables are always created on the physical table.
     pub async fn handle_metric_row_inserts(
         &self,
-        mut requests: RowInsertRequests,
+        requests: RowInsertRequests,
         ctx: QueryContextRef,
+        protocol: Option<IngestProtocol>,
+        statement_executor: &StatementExecutor,
+        physical_table: String,
+    ) -> Result<Output> {
+        self.do_metric_row_inserts(requests, &ctx, protocol, statement_executor, physical_table)
+            .await
+            .inspect_err(|_| observe_ingest_error(&ctx))
+    }
+
+    async fn do_metric_row_inserts(
+        &self,
+        mut requests: RowInsertRequests,
+        ctx: &QueryContextRef,
+        protocol: Option<IngestProtocol>,
         statement_executor: &StatementExecutor,
         physical_table: String,
     ) -> Result<Output> {
@@ -131,30 +216,46 @@ impl Inserter {
         });
         validate_column_count_match(&requests)?;
 
+        let policy = self
+            .ingest_policy(&mut requests, ctx, protocol, statement_executor)
+            .await?;
+        self.check_field_types(&mut requests, ctx, policy.strict)
+            .await?;
+
         // check and create physical table
-        self.create_physical_table_on_demand(&ctx, physical_table.clone(), statement_executor)
+        self.create_physical_table_on_demand(ctx, physical_table.clone(), statement_executor)
             .await?;
 
         // check and create logical tables
         self.create_or_alter_tables_on_demand(
             &requests,
-            &ctx,
+            ctx,
             Some(physical_table.to_string()),
+            &policy.auto_create_table,
             statement_executor,
         )
         .await?;
-        let inserts =
-            RowToRegion::new(self.catalog_manager.as_ref(), &self.partition_manager, &ctx)
-                .convert(requests)
-                .await?;
+        let inserts = RowToRegion::new(self.catalog_manager.as_ref(), &self.partition_manager, ctx)
+            .convert(requests)
+            .await?;
 
-        self.do_request(inserts, &ctx).await
+        self.do_request(inserts, ctx).await
     }
 
     pub async fn handle_table_insert(
         &self,
         request: TableInsertRequest,
         ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.do_table_insert(request, &ctx)
+            .await
+            .inspect_err(|_| observe_ingest_error(&ctx))
+    }
+
+    async fn do_table_insert(
+        &self,
+        request: TableInsertRequest,
+        ctx: &QueryContextRef,
     ) -> Result<Output> {
         let catalog = request.catalog_name.as_str();
         let schema = request.schema_name.as_str();
@@ -169,7 +270,7 @@ impl Inserter {
             .convert(request)
             .await?;
 
-        self.do_request(inserts, &ctx).await
+        self.do_request(inserts, ctx).await
     }
 
     pub async fn handle_statement_insert(
@@ -177,6 +278,12 @@ impl Inserter {
         insert: &Insert,
         ctx: &QueryContextRef,
     ) -> Result<Output> {
+        self.do_statement_insert(insert, ctx)
+            .await
+            .inspect_err(|_| observe_ingest_error(ctx))
+    }
+
+    async fn do_statement_insert(&self, insert: &Insert, ctx: &QueryContextRef) -> Result<Output> {
         let inserts =
             StatementToRegion::new(self.catalog_manager.as_ref(), &self.partition_manager, ctx)
                 .convert(insert, ctx)
@@ -187,23 +294,120 @@ impl Inserter {
 }
 
 impl Inserter {
+    /// Gets the ingestion policy of `protocol` and renames the tables in
+    /// `requests` by its naming rule.
+    async fn ingest_policy(
+        &self,
+        requests: &mut RowInsertRequests,
+        ctx: &QueryContextRef,
+        protocol: Option<IngestProtocol>,
+        statement_executor: &StatementExecutor,
+    ) -> Result<IngestPolicy> {
+        let Some(protocol) = protocol else {
+            return Ok(IngestPolicy::default());
+        };
+        let options = statement_executor
+            .schema_options(ctx.current_catalog(), ctx.current_schema())
+            .await?;
+        let policy = IngestPolicy {
+            auto_create_table: options.auto_create_table_policy(protocol),
+            strict: options.is_strict_ingestion(protocol),
+        };
+        for req in &mut requests.inserts {
+            req.table_name = policy.auto_create_table.naming.apply(&req.table_name);
+        }
+        Ok(policy)
+    }
+
+    /// Checks the types of fields in `requests` against the columns of existing tables.
+    ///
+    /// Timestamps are converted to the precision of the columns. Numeric fields are
+    /// widened to the column types unless `strict` is set, values that can't be
+    /// widened exactly fail the request and other conflicts are left to the regions
+    /// to reject. In strict mode, any other conflict fails the whole request before
+    /// tables are altered.
+    async fn check_field_types(
+        &self,
+        requests: &mut RowInsertRequests,
+        ctx: &QueryContextRef,
+        strict: bool,
+    ) -> Result<()> {
+        for req in &mut requests.inserts {
+            let table = self
+                .get_table(ctx.current_catalog(), ctx.current_schema(), &req.table_name)
+                .await?;
+            if let Some(table) = table {
+                align_request_with_table(req, &table.schema(), strict)?;
+            }
+        }
+        Ok(())
+    }
+
     async fn do_request(
         &self,
         requests: RegionInsertRequests,
         ctx: &QueryContextRef,
     ) -> Result<Output> {
+        if let Some(quota_manager) = &self.quota_manager {
+            let rows = requests
+                .requests
+                .iter()
+                .map(|r| r.rows.as_ref().map_or(0, |rows| rows.rows.len() as u64))
+                .sum();
+            quota_manager.admit_ingest(ctx, rows).await?;
+        }
+
         let write_cost = write_meter!(ctx.current_catalog(), ctx.current_schema(), requests);
-        let request_factory = RegionRequestFactory::new(RegionRequestHeader {
+        let ingested_bytes = requests.encoded_len();
+        let mut header = RegionRequestHeader {
             tracing_context: TracingContext::from_current_span().to_w3c(),
             dbname: ctx.get_db_string(),
-        });
+        };
+        if let Some(mode) = ctx.extension(INSERT_MODE_KEY) {
+            let mode = InsertMode::parse(mode).context(InvalidInsertModeSnafu)?;
+            mode.to_header_map(&mut header.tracing_context);
+        }
+        let request_factory = RegionRequestFactory::new(header);
 
+        let (mut affected_rows, dead_letters) =
+            self.send_requests(requests, &request_factory).await?;
+        if !dead_letters.is_empty() {
+            affected_rows += self
+                .write_dead_letters(dead_letters, ctx, &request_factory)
+                .await?;
+        }
+        crate::metrics::DIST_INGEST_ROW_COUNT.inc_by(affected_rows as u64);
+        let protocol = ctx.channel().to_string();
+        let labels = [ctx.current_catalog(), ctx.current_schema(), &protocol];
+        crate::metrics::INGEST_ROWS_BY_SCHEMA
+            .with_label_values(&labels)
+            .inc_by(affected_rows as u64);
+        crate::metrics::INGEST_BYTES_BY_SCHEMA
+            .with_label_values(&labels)
+            .inc_by(ingested_bytes as u64);
+        Ok(Output::new(
+            OutputData::AffectedRows(affected_rows),
+            OutputMeta::new_with_cost(write_cost as _),
+        ))
+    }
+
+    /// Sends `requests` to datanodes. Returns the affected rows and the out-of-order
+    /// rows that regions route to their dead-letter tables.
+    async fn send_requests(
+        &self,
+        requests: RegionInsertRequests,
+        request_factory: &RegionRequestFactory,
+    ) -> Result<(AffectedRows, Vec<RowInsertRequest>)> {
+        let written_tables = self
+            .query_cache
+            .as_ref()
+            .map(|_| written_tables(requests.requests.iter().map(|r| r.region_id)));
         let tasks = self
             .group_requests_by_peer(requests)
             .await?
             .into_iter()
-            .map(|(peer, inserts)| {
-                let request = request_factory.build_insert(inserts);
+            .map(|(peer, (inserts, leader_epochs))| {
+                let request = request_factory.build_insert(inserts, &leader_epochs);
                 let datanode_manager = self.datanode_manager.clone();
                 common_runtime::spawn_write(async move {
                     datanode_manager
@@ -215,31 +419,93 @@ impl Inserter {
                 })
             });
         let results = future::try_join_all(tasks).await.context(JoinTaskSnafu)?;
+        // Some regions may apply the inserts even if others fail.
+        if let (Some(query_cache), Some(tables)) = (&self.query_cache, written_tables) {
+            query_cache.invalidate_tables(tables);
+        }
 
-        let affected_rows = results
-            .into_iter()
-            .map(|resp| resp.map(|r| r.affected_rows))
-            .sum::<Result<AffectedRows>>()?;
-        crate::metrics::DIST_INGEST_ROW_COUNT.inc_by(affected_rows as u64);
-        Ok(Output::new(
-            OutputData::AffectedRows(affected_rows),
-            OutputMeta::new_with_cost(write_cost as _),
-        ))
+        let mut affected_rows = 0;
+        let mut dead_letters = Vec::new();
+        for result in results {
+            let response = result?;
+            affected_rows += response.affected_rows;
+            for (key, value) in response.extension {
+                if key.starts_with(OUT_OF_ORDER_ROWS_EXTENSION_KEY_PREFIX) {
+                    let request = RowInsertRequest::decode(value.as_slice())
+                        .context(DecodeOutOfOrderRowsSnafu)?;
+                    dead_letters.push(request);
+                }
+            }
+        }
+        Ok((affected_rows, dead_letters))
+    }
+
+    /// Writes out-of-order rows to the dead-letter tables of their regions.
+    ///
+    /// A dead-letter table may be qualified by a schema, otherwise it's in the schema
+    /// of the write. Rows out of order in a dead-letter table itself are not routed
+    /// again.
+    async fn write_dead_letters(
+        &self,
+        dead_letters: Vec<RowInsertRequest>,
+        ctx: &QueryContextRef,
+        request_factory: &RegionRequestFactory,
+    ) -> Result<AffectedRows> {
+        let mut inserts_by_schema: HashMap<String, Vec<RowInsertRequest>> = HashMap::new();
+        for mut request in dead_letters {
+            let schema = match request.table_name.split_once('.') {
+                Some((schema, table)) => {
+                    let schema = schema.to_string();
+                    request.table_name = table.to_string();
+                    schema
+                }
+                None => ctx.current_schema().to_string(),
+            };
+            inserts_by_schema.entry(schema).or_default().push(request);
+        }
+
+        let mut affected_rows = 0;
+        for (schema, inserts) in inserts_by_schema {
+            let schema_ctx = QueryContext::with(ctx.current_catalog(), &schema);
+            let requests = RowToRegion::new(
+                self.catalog_manager.as_ref(),
+                self.partition_manager.as_ref(),
+                &schema_ctx,
+            )
+            .convert(RowInsertRequests { inserts })
+            .await?;
+            let (rows, dead_letters) = self.send_requests(requests, request_factory).await?;
+            if !dead_letters.is_empty() {
+                warn!(
+                    "Dropped out-of-order rows of dead-letter tables in schema {}: {}",
+                    schema,
+                    dead_letters
+                        .iter()
+                        .map(|r| r.rows.as_ref().map_or(0, |rows| rows.rows.len()))
+                        .sum::<usize>()
+                );
+            }
+            affected_rows += rows;
+        }
+        Ok(affected_rows)
     }
 
     async fn group_requests_by_peer(
         &self,
         requests: RegionInsertRequests,
-    ) -> Result<HashMap<Peer, RegionInsertRequests>> {
-        let mut inserts: HashMap<Peer, RegionInsertRequests> = HashMap::new();
+    ) -> Result<HashMap<Peer, (RegionInsertRequests, LeaderEpochs)>> {
+        let mut inserts: HashMap<Peer, (RegionInsertRequests, LeaderEpochs)> = HashMap::new();
 
         for req in requests.requests {
-            let peer = self
+            let region_id = req.region_id.into();
+            let (peer, leader_epoch) = self
                 .partition_manager
-                .find_region_leader(req.region_id.into())
+                .find_region_leader_with_epoch(region_id)
                 .await
                 .context(FindRegionLeaderSnafu)?;
-            inserts.entry(peer).or_default().requests.push(req);
+            let (requests, leader_epochs) = inserts.entry(peer).or_default();
+            requests.requests.push(req);
+            leader_epochs.insert(region_id, leader_epoch);
         }
 
         Ok(inserts)
@@ -253,6 +519,7 @@ impl Inserter {
         requests: &RowInsertRequests,
         ctx: &QueryContextRef,
         on_physical_table: Option<String>,
+        policy: &AutoCreateTablePolicy,
         statement_executor: &StatementExecutor,
     ) -> Result<()> {
         let mut create_tables = vec![];
@@ -271,6 +538,16 @@ impl Inserter {
                     }
                 }
                 None => {
+                    ensure!(
+                        policy.enabled,
+                        AutoCreateTableDisabledSnafu {
+                            table_name: common_catalog::format_full_table_name(
+                                catalog,
+                                schema,
+                                &req.table_name,
+                            ),
+                        }
+                    );
                     create_tables.push(req);
                 }
             }
@@ -295,7 +572,8 @@ impl Inserter {
             }
         } else {
             for req in create_tables {
-                self.create_table(req, ctx, statement_executor).await?;
+                self.create_table(req, ctx, policy, statement_executor)
+                    .await?;
             }
             for alter_expr in alter_tables.into_iter() {
                 statement_executor.alter_table_inner(alter_expr).await?;
@@ -410,6 +688,7 @@ impl Inserter {
         &self,
         req: &RowInsertRequest,
         ctx: &QueryContextRef,
+        policy: &AutoCreateTablePolicy,
         statement_executor: &StatementExecutor,
     ) -> Result<()> {
         let table_ref =
@@ -417,6 +696,12 @@ impl Inserter {
 
         let request_schema = req.rows.as_ref().unwrap().schema.as_slice();
         let create_table_expr = &mut build_create_table_expr(&table_ref, request_schema)?;
+        if let Some(engine) = &policy.engine {
+            create_table_expr.engine = engine.clone();
+        }
+        create_table_expr
+            .table_options
+            .extend(policy.table_options.clone());
 
         info!("Table `{table_ref}` does not exist, try creating table");
 
@@ -501,6 +786,189 @@ impl Inserter {
     }
 }
 
+/// How writes of a protocol are ingested into the schema.
+#[derive(Debug, Default)]
+struct IngestPolicy {
+    auto_create_table: AutoCreateTablePolicy,
+    /// Whether writes whose field types conflict with existing columns are rejected.
+    strict: bool,
+}
+
+/// Max number of conflicting rows reported when a strict check fails.
+const MAX_REPORTED_ROWS: usize = 10;
+
+/// Aligns the field types of `req` with the columns in `table_schema`, see
+/// [Inserter::check_field_types].
+fn align_request_with_table(
+    req: &mut RowInsertRequest,
+    table_schema: &Schema,
+    strict: bool,
+) -> Result<()> {
+    let rows = req.rows.as_mut().unwrap();
+    for (index, column) in rows.schema.iter_mut().enumerate() {
+        let Some(table_column) = table_schema.column_schema_by_name(&column.column_name) else {
+            continue;
+        };
+        let Ok(expected) = ColumnDataTypeWrapper::try_from(table_column.data_type.clone()) else {
+            continue;
+        };
+        let expected = expected.datatype();
+        if column.datatype == expected as i32 {
+            continue;
+        }
+        let actual = ColumnDataType::try_from(column.datatype).ok();
+        let conflict_error = |rows: Vec<usize>| {
+            IncompatibleFieldTypeSnafu {
+                table_name: &req.table_name,
+                column: &column.column_name,
+                expected: format!("{expected:?}"),
+                actual: actual
+                    .map(|t| format!("{t:?}"))
+                    .unwrap_or_else(|| column.datatype.to_string()),
+                rows,
+            }
+            .build()
+        };
+
+        // Timestamps of different precisions are compatible in both modes.
+        let precision_only = actual.is_some_and(|actual| {
+            timestamp_unit(actual).is_some() && timestamp_unit(expected).is_some()
+        });
+        if strict && !precision_only {
+            let conflicting_rows = rows
+                .rows
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| row.values[index].value_data.is_some())
+                .map(|(i, _)| i)
+                .take(MAX_REPORTED_ROWS)
+                .collect();
+            return Err(conflict_error(conflicting_rows));
+        }
+        if !precision_only && !actual.is_some_and(|actual| can_widen(actual, expected)) {
+            continue;
+        }
+
+        let mut converted = Vec::with_capacity(rows.rows.len());
+        let mut lossy_rows = Vec::new();
+        for (i, row) in rows.rows.iter().enumerate() {
+            match &row.values[index].value_data {
+                Some(value) => match convert_value(value, expected) {
+                    Some(value) => converted.push(Some(value)),
+                    None => lossy_rows.push(i),
+                },
+                None => converted.push(None),
+            }
+        }
+        // Values that can't be converted exactly fail the request instead of
+        // being stored with a silently changed value.
+        if !lossy_rows.is_empty() {
+            lossy_rows.truncate(MAX_REPORTED_ROWS);
+            return Err(conflict_error(lossy_rows));
+        }
+        for (row, value) in rows.rows.iter_mut().zip(converted) {
+            row.values[index].value_data = value;
+        }
+        column.datatype = expected as i32;
+    }
+    Ok(())
+}
+
+/// Returns whether values of type `from` can be widened to type `to`.
+///
+/// Widening 64-bit integers to `Float64` is exact only for values within the
+/// 53-bit mantissa, larger values are rejected by [convert_value].
+fn can_widen(from: ColumnDataType, to: ColumnDataType) -> bool {
+    use ColumnDataType::*;
+
+    matches!(
+        (from, to),
+        (Int8 | Int16 | Int32 | Uint8 | Uint16 | Uint32, Int64)
+            | (
+                Int8 | Int16 | Int32 | Int64 | Uint8 | Uint16 | Uint32 | Uint64 | Float32,
+                Float64
+            )
+    )
+}
+
+/// Returns the time unit of the timestamp type `datatype`.
+fn timestamp_unit(datatype: ColumnDataType) -> Option<TimeUnit> {
+    match datatype {
+        ColumnDataType::TimestampSecond => Some(TimeUnit::Second),
+        ColumnDataType::TimestampMillisecond => Some(TimeUnit::Millisecond),
+        ColumnDataType::TimestampMicrosecond => Some(TimeUnit::Microsecond),
+        ColumnDataType::TimestampNanosecond => Some(TimeUnit::Nanosecond),
+        _ => None,
+    }
+}
+
+/// Max magnitude of integers a `f64` represents exactly.
+const MAX_EXACT_F64_INTEGER: u64 = 1 << f64::MANTISSA_DIGITS;
+
+/// Converts `value` to type `to`, returns `None` if it can't be converted exactly.
+///
+/// Timestamps converted to a coarser precision are truncated like the timestamps
+/// the protocols parse with a coarser precision.
+fn convert_value(value: &ValueData, to: ColumnDataType) -> Option<ValueData> {
+    if let Some(unit) = timestamp_unit(to) {
+        let timestamp = match value {
+            ValueData::TimestampSecondValue(v) => Timestamp::new_second(*v),
+            ValueData::TimestampMillisecondValue(v) => Timestamp::new_millisecond(*v),
+            ValueData::TimestampMicrosecondValue(v) => Timestamp::new_microsecond(*v),
+            ValueData::TimestampNanosecondValue(v) => Timestamp::new_nanosecond(*v),
+            _ => return None,
+        };
+        let value = timestamp.convert_to(unit)?.value();
+        return Some(match unit {
+            TimeUnit::Second => ValueData::TimestampSecondValue(value),
+            TimeUnit::Millisecond => ValueData::TimestampMillisecondValue(value),
+            TimeUnit::Microsecond => ValueData::TimestampMicrosecondValue(value),
+            TimeUnit::Nanosecond => ValueData::TimestampNanosecondValue(value),
+        });
+    }
+
+    let widened = match (to, value) {
+        (
+            ColumnDataType::Int64,
+            ValueData::I8Value(v) | ValueData::I16Value(v) | ValueData::I32Value(v),
+        ) => ValueData::I64Value(*v as i64),
+        (
+            ColumnDataType::Int64,
+            ValueData::U8Value(v) | ValueData::U16Value(v) | ValueData::U32Value(v),
+        ) => ValueData::I64Value(*v as i64),
+        (
+            ColumnDataType::Float64,
+            ValueData::I8Value(v) | ValueData::I16Value(v) | ValueData::I32Value(v),
+        ) => ValueData::F64Value(*v as f64),
+        (
+            ColumnDataType::Float64,
+            ValueData::U8Value(v) | ValueData::U16Value(v) | ValueData::U32Value(v),
+        ) => ValueData::F64Value(*v as f64),
+        (ColumnDataType::Float64, ValueData::I64Value(v))
+            if v.unsigned_abs() <= MAX_EXACT_F64_INTEGER =>
+        {
+            ValueData::F64Value(*v as f64)
+        }
+        (ColumnDataType::Float64, ValueData::U64Value(v)) if *v <= MAX_EXACT_F64_INTEGER => {
+            ValueData::F64Value(*v as f64)
+        }
+        (ColumnDataType::Float64, ValueData::F32Value(v)) => ValueData::F64Value(*v as f64),
+        _ => return None,
+    };
+    Some(widened)
+}
+
+/// Counts a failed ingestion request, labeled by the schema and protocol of `ctx`.
+fn observe_ingest_error(ctx: &QueryContextRef) {
+    crate::metrics::INGEST_ERRORS_BY_SCHEMA
+        .with_label_values(&[
+            ctx.current_catalog(),
+            ctx.current_schema(),
+            &ctx.channel().to_string(),
+        ])
+        .inc();
+}
+
 fn validate_column_count_match(requests: &RowInsertRequests) -> Result<()> {
     for request in &requests.inserts {
         let rows = request.rows.as_ref().unwrap();
@@ -605,4 +1073,159 @@ mod tests {
         // Neither of the above cases.
         assert!(validate_required_columns(request_schema, &schema).is_err());
     }
+
+    fn field_request(
+        columns: &[(&str, ColumnDataType)],
+        rows: Vec<Vec<Option<ValueData>>>,
+    ) -> RowInsertRequest {
+        RowInsertRequest {
+            table_name: "t".to_string(),
+            rows: Some(api::v1::Rows {
+                schema: columns
+                    .iter()
+                    .map(|(name, datatype)| ColumnSchema {
+                        column_name: name.to_string(),
+                        datatype: *datatype as i32,
+                        semantic_type: SemanticType::Field as i32,
+                        datatype_extension: None,
+                    })
+                    .collect(),
+                rows: rows
+                    .into_iter()
+                    .map(|values| api::v1::Row {
+                        values: values
+                            .into_iter()
+                            .map(|value_data| api::v1::Value { value_data })
+                            .collect(),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_align_request_with_table() {
+        let schema = Schema::new(vec![
+            DtColumnSchema::new("value", ConcreteDataType::float64_datatype(), true),
+            DtColumnSchema::new("count", ConcreteDataType::int64_datatype(), true),
+            DtColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+        ]);
+        let new_request = || {
+            field_request(
+                &[
+                    ("value", ColumnDataType::Int64),
+                    ("count", ColumnDataType::Int32),
+                    ("new", ColumnDataType::Boolean),
+                ],
+                vec![
+                    vec![
+                        Some(ValueData::I64Value(1)),
+                        Some(ValueData::I32Value(2)),
+                        None,
+                    ],
+                    vec![None, Some(ValueData::I32Value(3)), None],
+                    vec![
+                        Some(ValueData::I64Value(4)),
+                        None,
+                        Some(ValueData::BoolValue(true)),
+                    ],
+                ],
+            )
+        };
+
+        let mut req = new_request();
+        align_request_with_table(&mut req, &schema, false).unwrap();
+        let rows = req.rows.unwrap();
+        assert_eq!(ColumnDataType::Float64 as i32, rows.schema[0].datatype);
+        assert_eq!(ColumnDataType::Int64 as i32, rows.schema[1].datatype);
+        assert_eq!(ColumnDataType::Boolean as i32, rows.schema[2].datatype);
+        assert_eq!(
+            Some(ValueData::F64Value(1.0)),
+            rows.rows[0].values[0].value_data
+        );
+        assert_eq!(None, rows.rows[1].values[0].value_data);
+        assert_eq!(
+            Some(ValueData::I64Value(3)),
+            rows.rows[1].values[1].value_data
+        );
+
+        let mut req = new_request();
+        let err = align_request_with_table(&mut req, &schema, true).unwrap_err();
+        assert_eq!(
+            "Column `value` of table `t` is of type Float64, but got values of type Int64 in rows [0, 2]",
+            err.to_string()
+        );
+
+        // Conflicts that can't be widened are left to the regions in non-strict mode.
+        let mut req = field_request(
+            &[("host", ColumnDataType::Int64)],
+            vec![vec![Some(ValueData::I64Value(1))]],
+        );
+        align_request_with_table(&mut req, &schema, false).unwrap();
+        assert_eq!(
+            ColumnDataType::Int64 as i32,
+            req.rows.as_ref().unwrap().schema[0].datatype
+        );
+        assert!(align_request_with_table(&mut req, &schema, true).is_err());
+
+        // Integers a f64 can't represent exactly are rejected.
+        let mut req = field_request(
+            &[("value", ColumnDataType::Int64)],
+            vec![
+                vec![Some(ValueData::I64Value(1 << 53))],
+                vec![Some(ValueData::I64Value((1 << 53) + 1))],
+            ],
+        );
+        let err = align_request_with_table(&mut req, &schema, false).unwrap_err();
+        assert_eq!(
+            "Column `value` of table `t` is of type Float64, but got values of type Int64 in rows [1]",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_align_timestamp_precision() {
+        let schema = Schema::new(vec![DtColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )]);
+        let new_request = |value| {
+            field_request(
+                &[("ts", ColumnDataType::TimestampNanosecond)],
+                vec![
+                    vec![Some(ValueData::TimestampNanosecondValue(value))],
+                    vec![None],
+                ],
+            )
+        };
+
+        // Timestamp precisions are compatible in strict mode too.
+        for strict in [false, true] {
+            let mut req = new_request(1_500_000_001);
+            align_request_with_table(&mut req, &schema, strict).unwrap();
+            let rows = req.rows.unwrap();
+            assert_eq!(
+                ColumnDataType::TimestampMillisecond as i32,
+                rows.schema[0].datatype
+            );
+            assert_eq!(
+                Some(ValueData::TimestampMillisecondValue(1500)),
+                rows.rows[0].values[0].value_data
+            );
+            assert_eq!(None, rows.rows[1].values[0].value_data);
+        }
+
+        // Timestamps overflowing the column precision are rejected.
+        let schema = Schema::new(vec![DtColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_nanosecond_datatype(),
+            false,
+        )]);
+        let mut req = field_request(
+            &[("ts", ColumnDataType::TimestampSecond)],
+            vec![vec![Some(ValueData::TimestampSecondValue(i64::MAX))]],
+        );
+        assert!(align_request_with_table(&mut req, &schema, true).is_err());
+    }
 }
@@ -20,6 +20,8 @@ pub mod expr_factory;
 pub mod insert;
 pub mod metrics;
 pub mod procedure;
+pub mod query_cache;
+pub mod quota;
 pub mod region_req_factory;
 pub mod req_convert;
 pub mod request;
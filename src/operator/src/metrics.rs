@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use catalog::information_schema::ingestion_stats::{
+    INGEST_BYTES_METRIC, INGEST_ERRORS_METRIC, INGEST_LABELS, INGEST_ROWS_METRIC,
+};
 use lazy_static::lazy_static;
 use prometheus::*;
 
@@ -36,9 +39,40 @@ lazy_static! {
         "table operator ingest rows"
     )
     .unwrap();
+    /// Ingested rows by catalog, schema and protocol.
+    pub static ref INGEST_ROWS_BY_SCHEMA: IntCounterVec = register_int_counter_vec!(
+        INGEST_ROWS_METRIC,
+        "ingested rows per schema",
+        INGEST_LABELS
+    )
+    .unwrap();
+    /// Ingested bytes by catalog, schema and protocol.
+    pub static ref INGEST_BYTES_BY_SCHEMA: IntCounterVec = register_int_counter_vec!(
+        INGEST_BYTES_METRIC,
+        "ingested bytes per schema",
+        INGEST_LABELS
+    )
+    .unwrap();
+    /// Failed ingestion requests by catalog, schema and protocol.
+    pub static ref INGEST_ERRORS_BY_SCHEMA: IntCounterVec = register_int_counter_vec!(
+        INGEST_ERRORS_METRIC,
+        "failed ingestion requests per schema",
+        INGEST_LABELS
+    )
+    .unwrap();
     pub static ref DIST_DELETE_ROW_COUNT: IntCounter = register_int_counter!(
         "greptime_table_operator_delete_rows",
         "table operator delete rows"
     )
     .unwrap();
+    pub static ref QUERY_CACHE_HIT: IntCounter = register_int_counter!(
+        "greptime_table_operator_query_cache_hit",
+        "table operator query cache hit"
+    )
+    .unwrap();
+    pub static ref QUERY_CACHE_MISS: IntCounter = register_int_counter!(
+        "greptime_table_operator_query_cache_miss",
+        "table operator query cache miss"
+    )
+    .unwrap();
 }
@@ -0,0 +1,471 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Frontend-side cache of query results.
+//!
+//! Dashboards usually issue the same queries every few seconds. The cache keys a
+//! query by its normalized SQL and the catalog, schema and timezone of the session,
+//! and reuses the result for at most `ttl`.
+//!
+//! Each cached result records the versions of the tables it reads. A table's version
+//! is bumped once the frontend writes to the table or receives a cache invalidation
+//! of the table, e.g. after DDL, so later lookups miss the stale result. Writes through
+//! other frontends don't bump the versions, so the cache is only supported in
+//! standalone mode.
+//!
+//! Queries whose results may change while the tables are not changed, e.g. queries
+//! calling `now()`, are never cached.
+
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+use common_base::readable_size::ReadableSize;
+use common_meta::cache_invalidator::{CacheInvalidator, Context};
+use common_meta::instruction::CacheIdent;
+use common_query::{Output, OutputData};
+use common_recordbatch::adapter::RecordBatchMetrics;
+use common_recordbatch::{
+    OrderOption, RecordBatch, RecordBatchStream, RecordBatches, SendableRecordBatchStream,
+};
+use common_telemetry::debug;
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
+use datafusion_expr::{Expr, LogicalPlan as DfLogicalPlan, Volatility};
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use moka::sync::Cache;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use store_api::storage::{RegionId, TableId};
+
+use crate::metrics::{QUERY_CACHE_HIT, QUERY_CACHE_MISS};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct QueryCacheOptions {
+    pub enable: bool,
+    /// Max total size of cached results.
+    pub capacity: ReadableSize,
+    /// Results larger than it are not cached.
+    pub max_result_size: ReadableSize,
+    /// Max time a result is reused.
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+}
+
+impl Default for QueryCacheOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            capacity: ReadableSize::mb(64),
+            max_result_size: ReadableSize::mb(1),
+            ttl: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct QueryCacheKey {
+    catalog: String,
+    schema: String,
+    timezone: String,
+    sql: String,
+}
+
+/// Versions of tables a result reads.
+pub(crate) type TableVersions = Vec<(TableId, u64)>;
+
+struct CachedResult {
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    table_versions: TableVersions,
+}
+
+pub type QueryCacheRef = Arc<QueryCache>;
+
+pub struct QueryCache {
+    options: QueryCacheOptions,
+    cache: Cache<QueryCacheKey, Arc<CachedResult>>,
+    table_versions: RwLock<HashMap<TableId, u64>>,
+}
+
+impl QueryCache {
+    pub fn new(options: QueryCacheOptions) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(options.capacity.as_bytes())
+            .weigher(|_, result: &Arc<CachedResult>| {
+                let size = result
+                    .batches
+                    .iter()
+                    .map(|batch| batch.df_record_batch().get_array_memory_size())
+                    .sum::<usize>();
+                size.try_into().unwrap_or(u32::MAX)
+            })
+            .time_to_live(options.ttl)
+            .build();
+
+        Self {
+            options,
+            cache,
+            table_versions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the key of the normalized `sql` issued in the session.
+    pub(crate) fn key(&self, sql: String, query_ctx: &QueryContextRef) -> QueryCacheKey {
+        QueryCacheKey {
+            catalog: query_ctx.current_catalog().to_string(),
+            schema: query_ctx.current_schema().to_string(),
+            timezone: query_ctx.timezone().to_string(),
+            sql,
+        }
+    }
+
+    /// Returns the cached result of the `key` if the tables it reads are not changed.
+    pub(crate) fn get(&self, key: &QueryCacheKey) -> Option<Output> {
+        let Some(result) = self.cache.get(key) else {
+            QUERY_CACHE_MISS.inc();
+            return None;
+        };
+        if result.table_versions
+            != self.table_versions(result.table_versions.iter().map(|(id, _)| *id))
+        {
+            self.cache.invalidate(key);
+            QUERY_CACHE_MISS.inc();
+            return None;
+        }
+
+        QUERY_CACHE_HIT.inc();
+        // Safety: the batches are in the same schema as they come from the same stream.
+        let batches =
+            RecordBatches::try_new(result.schema.clone(), result.batches.clone()).unwrap();
+        Some(Output::new_with_record_batches(batches))
+    }
+
+    /// Returns the current versions of the tables.
+    pub(crate) fn table_versions(
+        &self,
+        table_ids: impl IntoIterator<Item = TableId>,
+    ) -> TableVersions {
+        let versions = self.table_versions.read().unwrap();
+        table_ids
+            .into_iter()
+            .map(|id| (id, versions.get(&id).copied().unwrap_or_default()))
+            .collect()
+    }
+
+    /// Caches the result in the `output` for the `key` once it's fully read.
+    ///
+    /// `table_versions` must be taken before the query runs, so writes during the
+    /// query invalidate the result.
+    pub(crate) fn cache_output(
+        self: &Arc<Self>,
+        key: QueryCacheKey,
+        table_versions: TableVersions,
+        output: Output,
+    ) -> Output {
+        match output.data {
+            OutputData::Stream(stream) => Output::new(
+                OutputData::Stream(Box::pin(CachingStream {
+                    stream,
+                    cache: self.clone(),
+                    key: Some(key),
+                    table_versions,
+                    batches: vec![],
+                    size: 0,
+                })),
+                output.meta,
+            ),
+            OutputData::RecordBatches(batches) => {
+                let schema = batches.schema();
+                let batches = batches.take();
+                self.put(key, schema, batches.clone(), table_versions);
+                // Safety: the batches are from a valid `RecordBatches`.
+                let batches = RecordBatches::try_new(schema, batches).unwrap();
+                Output::new(OutputData::RecordBatches(batches), output.meta)
+            }
+            OutputData::AffectedRows(_) => output,
+        }
+    }
+
+    fn put(
+        &self,
+        key: QueryCacheKey,
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+        table_versions: TableVersions,
+    ) {
+        let size = batches
+            .iter()
+            .map(|batch| batch.df_record_batch().get_array_memory_size())
+            .sum::<usize>();
+        if size as u64 > self.options.max_result_size.as_bytes() {
+            debug!(
+                "Result of query {} is too large to cache: {} bytes",
+                key.sql, size
+            );
+            return;
+        }
+
+        self.cache.insert(
+            key,
+            Arc::new(CachedResult {
+                schema,
+                batches,
+                table_versions,
+            }),
+        );
+    }
+
+    /// Bumps versions of the tables so cached results reading them are stale.
+    pub fn invalidate_tables(&self, table_ids: impl IntoIterator<Item = TableId>) {
+        let mut versions = self.table_versions.write().unwrap();
+        for id in table_ids {
+            *versions.entry(id).or_default() += 1;
+        }
+    }
+}
+
+/// Returns true if the `plan` returns the same result as long as the tables it
+/// reads are not changed.
+///
+/// Plans calling functions that may return different results for the same
+/// arguments, e.g. `now()` and `random()`, are not. Neither are plans with
+/// subqueries, as the tables they read are not tracked.
+pub(crate) fn is_deterministic(plan: &DfLogicalPlan) -> bool {
+    let mut deterministic = true;
+    let _ = plan.apply(&mut |node| {
+        for expr in node.expressions() {
+            let _ = expr.apply(&mut |expr| {
+                deterministic &= match expr {
+                    Expr::ScalarFunction(func) => func.fun.volatility() == Volatility::Immutable,
+                    Expr::ScalarUDF(func) => func.fun.signature.volatility == Volatility::Immutable,
+                    Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_) => false,
+                    _ => true,
+                };
+                Ok(continue_if(deterministic))
+            });
+        }
+        Ok(continue_if(deterministic))
+    });
+    deterministic
+}
+
+fn continue_if(condition: bool) -> VisitRecursion {
+    if condition {
+        VisitRecursion::Continue
+    } else {
+        VisitRecursion::Stop
+    }
+}
+
+/// Returns ids of tables the regions belong to.
+pub(crate) fn written_tables(region_ids: impl Iterator<Item = u64>) -> HashSet<TableId> {
+    region_ids
+        .map(|region_id| RegionId::from_u64(region_id).table_id())
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl CacheInvalidator for QueryCache {
+    async fn invalidate(
+        &self,
+        _ctx: &Context,
+        caches: Vec<CacheIdent>,
+    ) -> common_meta::error::Result<()> {
+        for cache in caches {
+            match cache {
+                CacheIdent::TableId(table_id) => self.invalidate_tables([table_id]),
+                // The same SQL may read another table or view after renaming or
+                // replacing one.
+                CacheIdent::TableName(_) | CacheIdent::ViewName(_) => self.cache.invalidate_all(),
+                CacheIdent::User(_) | CacheIdent::Privilege(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A stream that caches its batches once it's fully read.
+struct CachingStream {
+    stream: SendableRecordBatchStream,
+    cache: QueryCacheRef,
+    /// Key of the result, `None` once the result is cached or too large to cache.
+    key: Option<QueryCacheKey>,
+    table_versions: TableVersions,
+    batches: Vec<RecordBatch>,
+    size: usize,
+}
+
+impl RecordBatchStream for CachingStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn output_ordering(&self) -> Option<&[OrderOption]> {
+        self.stream.output_ordering()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        self.stream.metrics()
+    }
+}
+
+impl Stream for CachingStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.stream.as_mut().poll_next(cx);
+        if self.key.is_none() {
+            return poll;
+        }
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                self.size += batch.df_record_batch().get_array_memory_size();
+                if self.size as u64 > self.cache.options.max_result_size.as_bytes() {
+                    // Stops buffering batches of a result too large to cache.
+                    self.key = None;
+                    self.batches.clear();
+                } else {
+                    self.batches.push(batch.clone());
+                }
+            }
+            // Doesn't cache a failed query.
+            Poll::Ready(Some(Err(_))) => {
+                self.key = None;
+                self.batches.clear();
+            }
+            Poll::Ready(None) => {
+                // Safety: checked above.
+                let key = self.key.take().unwrap();
+                let batches = std::mem::take(&mut self.batches);
+                let table_versions = std::mem::take(&mut self.table_versions);
+                self.cache
+                    .put(key, self.stream.schema(), batches, table_versions);
+            }
+            Poll::Pending => {}
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_expr::expr_fn::{now, random};
+    use datafusion_expr::{lit, LogicalPlanBuilder};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::Int32Vector;
+    use futures::TryStreamExt;
+    use session::context::QueryContext;
+
+    use super::*;
+
+    fn new_batches() -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "v",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice([1, 2])) as _],
+        )
+        .unwrap();
+        RecordBatches::try_new(schema, vec![batch]).unwrap()
+    }
+
+    async fn collect(output: Output) -> RecordBatches {
+        match output.data {
+            OutputData::Stream(stream) => {
+                let schema = stream.schema();
+                let batches = stream.try_collect::<Vec<_>>().await.unwrap();
+                RecordBatches::try_new(schema, batches).unwrap()
+            }
+            OutputData::RecordBatches(batches) => batches,
+            OutputData::AffectedRows(_) => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_cache() {
+        let cache = Arc::new(QueryCache::new(QueryCacheOptions {
+            enable: true,
+            ttl: Duration::from_secs(3600),
+            ..Default::default()
+        }));
+        let ctx = QueryContext::arc();
+        let key = cache.key("SELECT * FROM t".to_string(), &ctx);
+        assert!(cache.get(&key).is_none());
+        // The same SQL in another schema reads another table.
+        let other_key = cache.key(
+            "SELECT * FROM t".to_string(),
+            &QueryContext::with("greptime", "other"),
+        );
+        assert_ne!(key, other_key);
+
+        let versions = cache.table_versions([1024]);
+        let stream = new_batches().as_stream();
+        let output = cache.cache_output(key.clone(), versions, Output::new_with_stream(stream));
+        // The result is cached once it's fully read.
+        assert!(cache.get(&key).is_none());
+        assert_eq!(new_batches(), collect(output).await);
+        assert_eq!(new_batches(), collect(cache.get(&key).unwrap()).await);
+        assert!(cache.get(&other_key).is_none());
+
+        // Writes to the table invalidate the result.
+        cache.invalidate_tables([1025]);
+        assert!(cache.get(&key).is_some());
+        cache.invalidate_tables([1024]);
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_cache_too_large() {
+        let cache = Arc::new(QueryCache::new(QueryCacheOptions {
+            enable: true,
+            max_result_size: ReadableSize(1),
+            ttl: Duration::from_secs(3600),
+            ..Default::default()
+        }));
+        let key = cache.key("SELECT * FROM t".to_string(), &QueryContext::arc());
+        let output = cache.cache_output(
+            key.clone(),
+            vec![],
+            Output::new_with_record_batches(new_batches()),
+        );
+        assert_eq!(new_batches(), collect(output).await);
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_is_deterministic() {
+        let plan = |expr| {
+            LogicalPlanBuilder::empty(true)
+                .project(vec![expr])
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+        assert!(is_deterministic(&plan(lit(1) + lit(2))));
+        assert!(!is_deterministic(&plan(now())));
+        assert!(!is_deterministic(&plan(random() + lit(1.0))));
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Quotas of databases enforced by the frontend.
+
+use std::sync::Arc;
+
+use common_meta::key::schema_name::{DatabaseQuota, SchemaNameKey};
+use common_meta::key::TableMetadataManagerRef;
+use common_meta::rpc::store::CompareAndPutRequest;
+use common_time::util::current_time_millis;
+use session::context::QueryContextRef;
+use snafu::{ensure, ResultExt};
+
+use crate::error::{QuotaExceededSnafu, Result, TableMetadataManagerSnafu};
+
+pub type QuotaManagerRef = Arc<QuotaManager>;
+
+/// Key prefix of the ingestion counters of databases in the metadata storage.
+const INGEST_COUNTER_KEY_PREFIX: &str = "__quota_ingest";
+/// Max attempts to update a counter that other frontends update concurrently.
+const MAX_COUNTER_UPDATE_ATTEMPTS: usize = 16;
+
+/// Enforces the quotas of databases on requests of the frontend.
+///
+/// Quotas are set by the `quota.*` options of databases. Frontends share the counters
+/// of ingested rows in the metadata storage, so the quotas apply to the whole cluster.
+pub struct QuotaManager {
+    table_metadata_manager: TableMetadataManagerRef,
+}
+
+/// Rows ingested into a database in a period of one second.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct IngestCounter {
+    /// Start of the period in seconds.
+    period: i64,
+    rows: u64,
+}
+
+impl IngestCounter {
+    fn key(catalog: &str, schema: &str) -> String {
+        format!("{INGEST_COUNTER_KEY_PREFIX}/{catalog}/{schema}")
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut value = Vec::with_capacity(16);
+        value.extend_from_slice(&self.period.to_le_bytes());
+        value.extend_from_slice(&self.rows.to_le_bytes());
+        value
+    }
+
+    /// Decodes the counter, an invalid counter is overwritten by the next update.
+    fn decode(value: &[u8]) -> Self {
+        let (Some(period), Some(rows)) = (value.get(..8), value.get(8..16)) else {
+            return Self::default();
+        };
+        // Safety: both slices have 8 bytes.
+        Self {
+            period: i64::from_le_bytes(period.try_into().unwrap()),
+            rows: u64::from_le_bytes(rows.try_into().unwrap()),
+        }
+    }
+}
+
+impl QuotaManager {
+    pub fn new(table_metadata_manager: TableMetadataManagerRef) -> Self {
+        Self {
+            table_metadata_manager,
+        }
+    }
+
+    /// Returns the quota of the database.
+    pub async fn quota(&self, catalog: &str, schema: &str) -> Result<DatabaseQuota> {
+        let value = self
+            .table_metadata_manager
+            .schema_manager()
+            .get(SchemaNameKey::new(catalog, schema))
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        Ok(value.map(|value| value.quota).unwrap_or_default())
+    }
+
+    /// Admits `rows` rows into the database of `ctx`, fails if the database would
+    /// ingest more than `max_ingest_rows_per_sec` rows in the current second.
+    ///
+    /// The rows are added to the counter of the database in the metadata storage by
+    /// compare and put, so all frontends count the rows they admit in one counter.
+    pub async fn admit_ingest(&self, ctx: &QueryContextRef, rows: u64) -> Result<()> {
+        let quota = self
+            .quota(ctx.current_catalog(), ctx.current_schema())
+            .await?;
+        let Some(max_rows) = quota.max_ingest_rows_per_sec else {
+            return Ok(());
+        };
+
+        let now = current_time_millis() / 1000;
+        let db = ctx.get_db_string();
+        let key = IngestCounter::key(ctx.current_catalog(), ctx.current_schema()).into_bytes();
+        let kv_backend = self.table_metadata_manager.kv_backend();
+        let mut current = kv_backend
+            .get(&key)
+            .await
+            .context(TableMetadataManagerSnafu)?
+            .map(|kv| kv.value);
+        for _ in 0..MAX_COUNTER_UPDATE_ATTEMPTS {
+            let counter = current
+                .as_deref()
+                .map(IngestCounter::decode)
+                .unwrap_or_default();
+            // Keeps the period of a frontend whose clock is ahead, so frontends don't
+            // reset the counter of each other.
+            let counter = if counter.period >= now {
+                counter
+            } else {
+                IngestCounter {
+                    period: now,
+                    rows: 0,
+                }
+            };
+            ensure!(
+                counter.rows + rows <= max_rows,
+                QuotaExceededSnafu {
+                    reason: format!("max_ingest_rows_per_sec {max_rows} of database {db}"),
+                }
+            );
+
+            let updated = IngestCounter {
+                rows: counter.rows + rows,
+                ..counter
+            };
+            let resp = kv_backend
+                .compare_and_put(CompareAndPutRequest {
+                    key: key.clone(),
+                    expect: current.clone().unwrap_or_default(),
+                    value: updated.encode(),
+                })
+                .await
+                .context(TableMetadataManagerSnafu)?;
+            if resp.success {
+                return Ok(());
+            }
+            current = resp.prev_kv.map(|kv| kv.value);
+        }
+
+        QuotaExceededSnafu {
+            reason: format!(
+                "max_ingest_rows_per_sec {max_rows} of database {db}, too many concurrent writes to count"
+            ),
+        }
+        .fail()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+    use common_meta::key::schema_name::SchemaNameValue;
+    use common_meta::key::TableMetadataManager;
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+    use common_meta::rpc::store::PutRequest;
+    use session::context::QueryContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_admit_ingest() {
+        let table_metadata_manager = Arc::new(TableMetadataManager::new(Arc::new(
+            MemoryKvBackend::default(),
+        )));
+        let mut options = HashMap::new();
+        options.insert(
+            "quota.max_ingest_rows_per_sec".to_string(),
+            "100".to_string(),
+        );
+        table_metadata_manager
+            .schema_manager()
+            .create(
+                SchemaNameKey::new("greptime", "limited"),
+                Some(SchemaNameValue::try_from(&options).unwrap()),
+                false,
+            )
+            .await
+            .unwrap();
+        // Two frontends share the counters.
+        let manager = QuotaManager::new(table_metadata_manager.clone());
+        let another = QuotaManager::new(table_metadata_manager.clone());
+
+        // Databases without quotas are unlimited.
+        let ctx = QueryContext::with("greptime", "public");
+        manager.admit_ingest(&ctx, 1000).await.unwrap();
+
+        let ctx = QueryContext::with("greptime", "limited");
+        // Pins the period in the future so the test doesn't cross seconds.
+        table_metadata_manager
+            .kv_backend()
+            .put(
+                PutRequest::new()
+                    .with_key(IngestCounter::key("greptime", "limited"))
+                    .with_value(
+                        IngestCounter {
+                            period: current_time_millis() / 1000 + 3600,
+                            rows: 60,
+                        }
+                        .encode(),
+                    ),
+            )
+            .await
+            .unwrap();
+        manager.admit_ingest(&ctx, 30).await.unwrap();
+        another.admit_ingest(&ctx, 10).await.unwrap();
+        let err = manager.admit_ingest(&ctx, 1).await.unwrap_err();
+        assert_eq!(StatusCode::QuotaExceeded, err.status_code());
+        let err = another.admit_ingest(&ctx, 1).await.unwrap_err();
+        assert_eq!(StatusCode::QuotaExceeded, err.status_code());
+    }
+
+    #[test]
+    fn test_ingest_counter_codec() {
+        let counter = IngestCounter {
+            period: 1700000000,
+            rows: 42,
+        };
+        assert_eq!(counter, IngestCounter::decode(&counter.encode()));
+        assert_eq!(IngestCounter::default(), IngestCounter::decode(b"invalid"));
+    }
+}
@@ -17,6 +17,7 @@ use api::v1::region::{
     DeleteRequests as RegionDeleteRequests, InsertRequests as RegionInsertRequests, RegionRequest,
     RegionRequestHeader,
 };
+use store_api::region_request::LeaderEpochs;
 
 pub struct RegionRequestFactory {
     header: RegionRequestHeader,
@@ -27,16 +28,24 @@ impl RegionRequestFactory {
         Self { header }
     }
 
-    pub fn build_insert(&self, requests: RegionInsertRequests) -> RegionRequest {
+    pub fn build_insert(
+        &self,
+        requests: RegionInsertRequests,
+        leader_epochs: &LeaderEpochs,
+    ) -> RegionRequest {
         RegionRequest {
-            header: Some(self.header.clone()),
+            header: Some(self.header_with_epochs(leader_epochs)),
             body: Some(Body::Inserts(requests)),
         }
     }
 
-    pub fn build_delete(&self, requests: RegionDeleteRequests) -> RegionRequest {
+    pub fn build_delete(
+        &self,
+        requests: RegionDeleteRequests,
+        leader_epochs: &LeaderEpochs,
+    ) -> RegionRequest {
         RegionRequest {
-            header: Some(self.header.clone()),
+            header: Some(self.header_with_epochs(leader_epochs)),
             body: Some(Body::Deletes(requests)),
         }
     }
@@ -47,4 +56,11 @@ impl RegionRequestFactory {
             body: Some(body),
         }
     }
+
+    /// Writes are fenced by the leader epochs of the routes they're routed by.
+    fn header_with_epochs(&self, leader_epochs: &LeaderEpochs) -> RegionRequestHeader {
+        let mut header = self.header.clone();
+        leader_epochs.to_header_map(&mut header.tracing_context);
+        header
+    }
 }
@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::v1::region::region_request::Body as RegionRequestBody;
-use api::v1::region::{CompactRequest, FlushRequest, RegionRequestHeader};
+use api::v1::region::{
+    CompactRequest, FlushRequest, InsertRequest, InsertRequests, RegionRequestHeader,
+};
+use api::v1::Rows;
 use catalog::CatalogManagerRef;
 use common_catalog::build_db_string;
-use common_meta::datanode_manager::{AffectedRows, DatanodeManagerRef};
+use common_meta::datanode_manager::{AffectedRows, DatanodeManagerRef, HandleResponse};
 use common_meta::peer::Peer;
 use common_telemetry::logging::{error, info};
 use common_telemetry::tracing_context::TracingContext;
@@ -26,12 +30,16 @@ use futures_util::future;
 use partition::manager::{PartitionInfo, PartitionRuleManagerRef};
 use session::context::QueryContextRef;
 use snafu::prelude::*;
-use store_api::storage::RegionId;
+use store_api::logstore::entry;
+use store_api::mito_engine_options::{FLUSHED_ENTRY_ID_EXTENSION_KEY, WAL_REPLAYED_EXTENSION_KEY};
+use store_api::region_request::{CompactOptions, WalReplaySource};
+use store_api::storage::{RegionId, RegionNumber};
 use table::requests::{CompactTableRequest, FlushTableRequest};
 
 use crate::error::{
     CatalogSnafu, FindRegionLeaderSnafu, FindTablePartitionRuleSnafu, JoinTaskSnafu,
-    RequestRegionSnafu, Result, TableNotFoundSnafu, UnsupportedRegionRequestSnafu,
+    NotSupportedSnafu, RequestRegionSnafu, Result, TableNotFoundSnafu,
+    UnsupportedRegionRequestSnafu,
 };
 use crate::region_req_factory::RegionRequestFactory;
 
@@ -85,11 +93,117 @@ impl Requester {
         self.do_request(
             requests,
             Some(build_db_string(&request.catalog_name, &request.schema_name)),
+            HashMap::new(),
             &ctx,
         )
         .await
     }
 
+    /// Flushes the table and returns the flushed entry id of each region.
+    ///
+    /// Regions whose engine doesn't report the flushed entry id are absent.
+    pub async fn handle_table_flush_entry_ids(
+        &self,
+        request: FlushTableRequest,
+        ctx: QueryContextRef,
+    ) -> Result<HashMap<RegionNumber, entry::Id>> {
+        let partitions = self
+            .get_table_partitions(
+                &request.catalog_name,
+                &request.schema_name,
+                &request.table_name,
+            )
+            .await?;
+        let region_ids = partitions
+            .iter()
+            .map(|partition| partition.id)
+            .collect::<Vec<_>>();
+        let requests = region_ids
+            .iter()
+            .map(|region_id| {
+                RegionRequestBody::Flush(FlushRequest {
+                    region_id: (*region_id).into(),
+                })
+            })
+            .collect();
+
+        info!("Handle table flush request for entry ids: {:?}", request);
+        let responses = self
+            .do_request_with_responses(
+                requests,
+                Some(build_db_string(&request.catalog_name, &request.schema_name)),
+                HashMap::new(),
+                &ctx,
+            )
+            .await?;
+
+        Ok(region_ids
+            .into_iter()
+            .zip(responses)
+            .filter_map(|(region_id, response)| {
+                let value = response.extension.get(FLUSHED_ENTRY_ID_EXTENSION_KEY)?;
+                let entry_id = u64::from_le_bytes(value.as_slice().try_into().ok()?);
+                Some((region_id.region_number(), entry_id))
+            })
+            .collect())
+    }
+
+    /// Replays the WAL in `source` into regions of the table with the same region
+    /// numbers and returns the number of rows replayed.
+    pub async fn handle_table_wal_replay(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table_name: &str,
+        source: &WalReplaySource,
+        ctx: QueryContextRef,
+    ) -> Result<AffectedRows> {
+        let partitions = self
+            .get_table_partitions(catalog, schema, table_name)
+            .await?;
+
+        // The region request has no body for the replay, so it travels in the
+        // string map of the header of an empty put request.
+        let requests = partitions
+            .into_iter()
+            .map(|partition| {
+                RegionRequestBody::Inserts(InsertRequests {
+                    requests: vec![InsertRequest {
+                        region_id: partition.id.into(),
+                        rows: Some(Rows::default()),
+                    }],
+                })
+            })
+            .collect();
+        let mut header_map = HashMap::new();
+        source.to_header_map(&mut header_map);
+
+        info!(
+            "Handle table WAL replay request, table: {}.{}.{}, source: {:?}",
+            catalog, schema, table_name, source
+        );
+        let responses = self
+            .do_request_with_responses(
+                requests,
+                Some(build_db_string(catalog, schema)),
+                header_map,
+                &ctx,
+            )
+            .await?;
+        // Datanodes that don't know the replay put the empty rows and succeed, so the
+        // replay fails instead of silently restoring nothing.
+        ensure!(
+            responses
+                .iter()
+                .all(|response| response.extension.contains_key(WAL_REPLAYED_EXTENSION_KEY)),
+            NotSupportedSnafu {
+                feat: "replaying WAL on datanodes that don't support it",
+            }
+        );
+
+        Ok(responses.iter().map(|resp| resp.affected_rows).sum())
+    }
+
     /// Handle the request to compact table.
     pub async fn handle_table_compaction(
         &self,
@@ -115,9 +229,12 @@ impl Requester {
 
         info!("Handle table manual compaction request: {:?}", request);
 
+        let mut header_map = HashMap::new();
+        request.compact_options.to_header_map(&mut header_map);
         self.do_request(
             requests,
             Some(build_db_string(&request.catalog_name, &request.schema_name)),
+            header_map,
             &ctx,
         )
         .await
@@ -134,21 +251,25 @@ impl Requester {
         });
 
         info!("Handle region manual flush request: {region_id}");
-        self.do_request(vec![request], None, &ctx).await
+        self.do_request(vec![request], None, HashMap::new(), &ctx)
+            .await
     }
 
     /// Handle the request to compact the region.
     pub async fn handle_region_compaction(
         &self,
         region_id: RegionId,
+        compact_options: CompactOptions,
         ctx: QueryContextRef,
     ) -> Result<AffectedRows> {
         let request = RegionRequestBody::Compact(CompactRequest {
             region_id: region_id.into(),
         });
 
-        info!("Handle region manual compaction request: {region_id}");
-        self.do_request(vec![request], None, &ctx).await
+        info!("Handle region manual compaction request: {region_id}, options: {compact_options:?}");
+        let mut header_map = HashMap::new();
+        compact_options.to_header_map(&mut header_map);
+        self.do_request(vec![request], None, header_map, &ctx).await
     }
 }
 
@@ -157,12 +278,33 @@ impl Requester {
         &self,
         requests: Vec<RegionRequestBody>,
         db_string: Option<String>,
+        header_map: HashMap<String, String>,
         ctx: &QueryContextRef,
     ) -> Result<AffectedRows> {
-        let request_factory = RegionRequestFactory::new(RegionRequestHeader {
+        let responses = self
+            .do_request_with_responses(requests, db_string, header_map, ctx)
+            .await?;
+
+        Ok(responses.iter().map(|resp| resp.affected_rows).sum())
+    }
+
+    /// Sends `requests` to their region leaders and returns their responses in order.
+    ///
+    /// Options without a field in the requests, e.g. options of compact requests,
+    /// travel in the string map alongside the tracing context as `header_map`.
+    async fn do_request_with_responses(
+        &self,
+        requests: Vec<RegionRequestBody>,
+        db_string: Option<String>,
+        header_map: HashMap<String, String>,
+        ctx: &QueryContextRef,
+    ) -> Result<Vec<HandleResponse>> {
+        let mut header = RegionRequestHeader {
             tracing_context: TracingContext::from_current_span().to_w3c(),
             dbname: db_string.unwrap_or_else(|| ctx.get_db_string()),
-        });
+        };
+        header.tracing_context.extend(header_map);
+        let request_factory = RegionRequestFactory::new(header);
 
         let tasks = requests.into_iter().map(|req_body| {
             let request = request_factory.build_request(req_body.clone());
@@ -181,12 +323,7 @@ impl Requester {
         });
         let results = future::try_join_all(tasks).await.context(JoinTaskSnafu)?;
 
-        let affected_rows = results
-            .into_iter()
-            .map(|resp| resp.map(|r| r.affected_rows))
-            .sum::<Result<AffectedRows>>()?;
-
-        Ok(affected_rows)
+        results.into_iter().collect()
     }
 
     async fn find_region_leader_by_request(
@@ -196,6 +333,7 @@ impl Requester {
         let region_id = match req {
             RegionRequestBody::Flush(req) => req.region_id,
             RegionRequestBody::Compact(req) => req.region_id,
+            RegionRequestBody::Inserts(req) if req.requests.len() == 1 => req.requests[0].region_id,
             _ => {
                 error!("Unsupported region request: {:?}", req);
                 return UnsupportedRegionRequestSnafu {}.fail();
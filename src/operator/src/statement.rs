@@ -12,15 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod backup;
+pub mod capture;
 mod copy_database;
 mod copy_table_from;
 mod copy_table_to;
 mod ddl;
 mod describe;
 mod dml;
+mod kill;
+mod materialized_view;
+mod privilege;
+mod profile;
 mod set;
 mod show;
 mod tql;
+mod user;
 
 use std::sync::Arc;
 
@@ -35,6 +42,7 @@ use common_query::Output;
 use common_telemetry::tracing;
 use common_time::range::TimestampRange;
 use common_time::Timestamp;
+pub use ddl::create_table_output;
 use partition::manager::{PartitionRuleManager, PartitionRuleManagerRef};
 use query::parser::QueryStatement;
 use query::plan::LogicalPlan;
@@ -47,16 +55,23 @@ use sql::statements::statement::Statement;
 use sql::statements::OptionMap;
 use sql::util::format_raw_object_name;
 use sqlparser::ast::ObjectName;
+use table::metadata::TableType;
 use table::requests::{CopyDatabaseRequest, CopyDirection, CopyTableRequest};
 use table::table_reference::TableReference;
 use table::TableRef;
 
-use self::set::{set_bytea_output, set_datestyle, set_timezone, validate_client_encoding};
+use self::set::{
+    set_bytea_output, set_datestyle, set_query_memory_limit, set_timezone, set_type_coercion,
+    validate_client_encoding,
+};
 use crate::error::{
     self, CatalogSnafu, ExecLogicalPlanSnafu, ExternalSnafu, InvalidSqlSnafu, NotSupportedSnafu,
     PlanStatementSnafu, Result, TableNotFoundSnafu,
 };
 use crate::insert::InserterRef;
+use crate::query_cache::{is_deterministic, QueryCacheRef};
+use crate::request::RequesterRef;
+use crate::statement::capture::scanned_tables;
 use crate::statement::copy_database::{COPY_DATABASE_TIME_END_KEY, COPY_DATABASE_TIME_START_KEY};
 
 #[derive(Clone)]
@@ -68,6 +83,8 @@ pub struct StatementExecutor {
     partition_manager: PartitionRuleManagerRef,
     cache_invalidator: CacheInvalidatorRef,
     inserter: InserterRef,
+    requester: RequesterRef,
+    query_cache: Option<QueryCacheRef>,
 }
 
 impl StatementExecutor {
@@ -78,6 +95,7 @@ impl StatementExecutor {
         kv_backend: KvBackendRef,
         cache_invalidator: CacheInvalidatorRef,
         inserter: InserterRef,
+        requester: RequesterRef,
     ) -> Self {
         Self {
             catalog_manager,
@@ -87,6 +105,16 @@ impl StatementExecutor {
             partition_manager: Arc::new(PartitionRuleManager::new(kv_backend)),
             cache_invalidator,
             inserter,
+            requester,
+            query_cache: None,
+        }
+    }
+
+    /// Reuses results of the same queries in the `query_cache`.
+    pub fn with_query_cache(self, query_cache: QueryCacheRef) -> Self {
+        Self {
+            query_cache: Some(query_cache),
+            ..self
         }
     }
 
@@ -104,10 +132,28 @@ impl StatementExecutor {
 
     pub async fn execute_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
         match stmt {
-            Statement::Query(_) | Statement::Explain(_) | Statement::Delete(_) => {
+            Statement::Query(query) => {
+                let stmt = match self
+                    .rewrite_by_materialized_view(&query, &query_ctx)
+                    .await?
+                {
+                    Some(stmt) => stmt,
+                    None => Statement::Query(query),
+                };
+                match &self.query_cache {
+                    Some(query_cache) => self.plan_exec_cached(stmt, query_cache, query_ctx).await,
+                    None => self.plan_exec(QueryStatement::Sql(stmt), query_ctx).await,
+                }
+            }
+
+            Statement::Explain(_) | Statement::Delete(_) => {
                 self.plan_exec(QueryStatement::Sql(stmt), query_ctx).await
             }
 
+            Statement::ExplainProfile(stmt) => self.explain_profile(stmt, query_ctx).await,
+
+            Statement::ExplainCapture(stmt) => self.explain_capture(stmt, query_ctx).await,
+
             Statement::Insert(insert) => self.insert(insert, query_ctx).await,
 
             Statement::Tql(tql) => self.execute_tql(tql, query_ctx).await,
@@ -148,17 +194,31 @@ impl StatementExecutor {
                 }
             }
 
+            Statement::BackupDatabase(arg) => {
+                self.backup_database(to_copy_database_request(arg, &query_ctx)?, query_ctx)
+                    .await
+            }
+
+            Statement::RestoreDatabase(arg) => {
+                self.restore_database(to_copy_database_request(arg, &query_ctx)?, query_ctx)
+                    .await
+            }
+
             Statement::CreateTable(stmt) => {
-                let _ = self.create_table(stmt, query_ctx).await?;
-                Ok(Output::new_with_affected_rows(0))
+                let (table, status) = self.create_table(stmt, query_ctx).await?;
+                Ok(create_table_output(&table, status))
             }
             Statement::CreateTableLike(stmt) => {
-                let _ = self.create_table_like(stmt, query_ctx).await?;
-                Ok(Output::new_with_affected_rows(0))
+                let (table, status) = self.create_table_like(stmt, query_ctx).await?;
+                Ok(create_table_output(&table, status))
             }
             Statement::CreateExternalTable(stmt) => {
-                let _ = self.create_external_table(stmt, query_ctx).await?;
-                Ok(Output::new_with_affected_rows(0))
+                let (table, status) = self.create_external_table(stmt, query_ctx).await?;
+                Ok(create_table_output(&table, status))
+            }
+            Statement::CreateView(stmt) => self.create_view(stmt, query_ctx).await,
+            Statement::CreateMaterializedView(stmt) => {
+                self.create_materialized_view(stmt, query_ctx).await
             }
             Statement::Alter(alter_table) => self.alter_table(alter_table, query_ctx).await,
             Statement::DropTable(stmt) => {
@@ -167,7 +227,33 @@ impl StatementExecutor {
                         .map_err(BoxedError::new)
                         .context(error::ExternalSnafu)?;
                 let table_name = TableName::new(catalog, schema, table);
-                self.drop_table(table_name, stmt.drop_if_exists()).await
+                self.drop_table(table_name, stmt.drop_if_exists(), stmt.cascade())
+                    .await
+            }
+            Statement::DropView(stmt) => {
+                let (catalog, schema, view) =
+                    table_idents_to_full_name(stmt.view_name(), &query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let view_name = TableName::new(catalog, schema, view);
+                self.drop_view(view_name, stmt.drop_if_exists()).await
+            }
+            Statement::DropMaterializedView(stmt) => {
+                let (catalog, schema, view) =
+                    table_idents_to_full_name(stmt.view_name(), &query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let view_name = TableName::new(catalog, schema, view);
+                self.drop_materialized_view(view_name, stmt.drop_if_exists())
+                    .await
+            }
+            Statement::RefreshMaterializedView(stmt) => {
+                let (catalog, schema, view) =
+                    table_idents_to_full_name(stmt.view_name(), &query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let view_name = TableName::new(catalog, schema, view);
+                self.refresh_materialized_view(view_name).await
             }
             Statement::DropDatabase(stmt) => {
                 self.drop_database(
@@ -190,6 +276,7 @@ impl StatementExecutor {
                     query_ctx.current_catalog(),
                     &format_raw_object_name(&stmt.name),
                     stmt.if_not_exists,
+                    stmt.options.into_map(),
                 )
                 .await
             }
@@ -210,6 +297,8 @@ impl StatementExecutor {
                 self.show_create_table(table_name, table_ref, query_ctx)
                     .await
             }
+            Statement::ShowCreateDatabase(stmt) => self.show_create_database(stmt, query_ctx).await,
+            Statement::ShowCreateUsers(_) => self.show_create_users(query_ctx).await,
             Statement::SetVariables(set_var) => {
                 let var_name = set_var.variable.to_string().to_uppercase();
                 match var_name.as_str() {
@@ -223,6 +312,10 @@ impl StatementExecutor {
                     "DATESTYLE" => set_datestyle(set_var.value, query_ctx)?,
 
                     "CLIENT_ENCODING" => validate_client_encoding(set_var)?,
+
+                    "TYPE_COERCION" => set_type_coercion(set_var.value, query_ctx)?,
+
+                    "QUERY_MEMORY_LIMIT" => set_query_memory_limit(set_var.value, query_ctx)?,
                     _ => {
                         return NotSupportedSnafu {
                             feat: format!("Unsupported set variable {}", var_name),
@@ -233,10 +326,19 @@ impl StatementExecutor {
                 Ok(Output::new_with_affected_rows(0))
             }
             Statement::ShowVariables(show_variable) => self.show_variable(show_variable, query_ctx),
+            Statement::ShowProcesslist(stmt) => self.show_processlist(stmt, query_ctx).await,
+            Statement::KillQuery(stmt) => self.kill_query(stmt, query_ctx).await,
             Statement::ShowColumns(show_columns) => {
                 self.show_columns(show_columns, query_ctx).await
             }
             Statement::ShowIndex(show_index) => self.show_index(show_index, query_ctx).await,
+            Statement::CreateRole(stmt) => self.create_role(stmt).await,
+            Statement::DropRole(stmt) => self.drop_role(stmt).await,
+            Statement::Grant(stmt) => self.grant(stmt, query_ctx).await,
+            Statement::Revoke(stmt) => self.revoke(stmt, query_ctx).await,
+            Statement::CreateUser(stmt) => self.create_user(stmt).await,
+            Statement::AlterUser(stmt) => self.alter_user(stmt).await,
+            Statement::DropUser(stmt) => self.drop_user(stmt).await,
         }
     }
 
@@ -261,6 +363,48 @@ impl StatementExecutor {
             .context(ExecLogicalPlanSnafu)
     }
 
+    /// Executes the query, or returns the cached result of the same query.
+    ///
+    /// Only results of queries reading base tables are cached, other tables, e.g.
+    /// tables in `information_schema`, change without writes.
+    #[tracing::instrument(skip_all)]
+    async fn plan_exec_cached(
+        &self,
+        stmt: Statement,
+        query_cache: &QueryCacheRef,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let key = query_cache.key(stmt.to_string(), &query_ctx);
+        if let Some(output) = query_cache.get(&key) {
+            return Ok(output);
+        }
+
+        let plan = self
+            .plan(QueryStatement::Sql(stmt), query_ctx.clone())
+            .await?;
+        let LogicalPlan::DfPlan(df_plan) = &plan;
+        let tables = scanned_tables(df_plan);
+        let cacheable = !tables.is_empty()
+            && tables
+                .iter()
+                .all(|table| table.table_info().table_type == TableType::Base)
+            && is_deterministic(df_plan);
+        // Takes the versions before the query runs so writes during the query
+        // invalidate the result.
+        let table_versions =
+            query_cache.table_versions(tables.iter().map(|table| table.table_info().table_id()));
+
+        let output = self
+            .query_engine
+            .execute(plan, query_ctx)
+            .await
+            .context(ExecLogicalPlanSnafu)?;
+        if !cacheable {
+            return Ok(output);
+        }
+        Ok(query_cache.cache_output(key, table_versions, output))
+    }
+
     async fn get_table(&self, table_ref: &TableReference<'_>) -> Result<TableRef> {
         let TableReference {
             catalog,
@@ -0,0 +1,485 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use client::{Output, OutputData, OutputMeta};
+use common_datasource::file_format::FORMAT_TYPE;
+use common_datasource::object_store::build_backend;
+use common_meta::key::datanode_table::DatanodeTableKey;
+use common_telemetry::{info, tracing, warn};
+use common_time::range::TimestampRange;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::logstore::entry;
+use store_api::mito_engine_options::WAL_RETAIN_PERIOD_KEY;
+use store_api::region_request::WalReplaySource;
+use store_api::storage::{RegionNumber, TableId};
+use table::requests::{CopyDatabaseRequest, FlushTableRequest};
+
+use crate::error::{
+    self, CatalogSnafu, InvalidCopyDatabasePathSnafu, Result, TableMetadataManagerSnafu,
+};
+use crate::statement::StatementExecutor;
+
+/// Option of `RESTORE DATABASE` to recover tables with retained WAL by replaying it.
+pub(crate) const RESTORE_REPLAY_WAL_KEY: &str = "replay_wal";
+/// Option of `RESTORE DATABASE` to replay rows from the WAL up to a timestamp by their
+/// time index.
+pub(crate) const RESTORE_UNTIL_KEY: &str = "until";
+
+/// Name of the file describing a backup. It's written after all tables are exported,
+/// so a location without it doesn't hold a complete backup.
+const BACKUP_MANIFEST_FILE: &str = "backup_manifest";
+const BACKUP_MANIFEST_VERSION: u32 = 1;
+const DEFAULT_BACKUP_FORMAT: &str = "parquet";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct BackupManifest {
+    version: u32,
+    catalog_name: String,
+    schema_name: String,
+    /// The cutoff timestamp in milliseconds. All tables are exported with rows
+    /// whose time index is before it.
+    ///
+    /// It's not a snapshot: tables are exported one by one, so rows written before
+    /// the cutoff while the backup runs, e.g. late rows or updates, may or may not be
+    /// exported.
+    cutoff_time: i64,
+    /// Format of exported data files.
+    format: String,
+    tables: Vec<String>,
+    rows: usize,
+    /// Tables whose WAL is retained, they can be recovered to the last entry of their
+    /// WAL by replaying it.
+    ///
+    /// These tables are exported with all rows regardless of the cutoff, after the WAL
+    /// entry ids to replay after are recorded, so replaying the WAL never misses rows.
+    #[serde(default)]
+    retained_wal: HashMap<String, RetainedWal>,
+}
+
+/// Where to find the retained WAL of a backed up table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RetainedWal {
+    table_id: TableId,
+    region_wal_options: HashMap<RegionNumber, String>,
+    /// Flushed entry id of each region before the table is exported.
+    #[serde(default)]
+    region_entry_ids: HashMap<RegionNumber, entry::Id>,
+}
+
+impl RetainedWal {
+    fn replay_source(&self, until: Option<Timestamp>) -> WalReplaySource {
+        WalReplaySource {
+            table_id: self.table_id,
+            region_wal_options: self.region_wal_options.clone(),
+            region_entry_ids: self.region_entry_ids.clone(),
+            until: until.map(|ts| ts.value()),
+        }
+    }
+}
+
+impl StatementExecutor {
+    /// Exports schemas and data of the database with rows before a cutoff timestamp.
+    ///
+    /// The backup isn't a consistent snapshot, see [BackupManifest::cutoff_time].
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn backup_database(
+        &self,
+        mut req: CopyDatabaseRequest,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        ensure!(
+            req.location.ends_with('/'),
+            InvalidCopyDatabasePathSnafu {
+                value: req.location,
+            }
+        );
+        let object_store =
+            build_backend(&req.location, &req.connection).context(error::BuildBackendSnafu)?;
+        ensure!(
+            !object_store
+                .is_exist(BACKUP_MANIFEST_FILE)
+                .await
+                .context(error::ReadObjectSnafu {
+                    path: BACKUP_MANIFEST_FILE,
+                })?,
+            error::BackupAlreadyExistsSnafu {
+                value: req.location,
+            }
+        );
+
+        // All tables are cut at the same timestamp.
+        let cutoff_time = Timestamp::current_millis();
+        req.time_range = Some(cutoff_range(req.time_range, cutoff_time));
+        let format = req
+            .with
+            .get(FORMAT_TYPE)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_BACKUP_FORMAT.to_string());
+        req.with.insert(FORMAT_TYPE.to_string(), format.clone());
+
+        info!(
+            "Backup database {}.{} to {} with rows before {}",
+            req.catalog_name,
+            req.schema_name,
+            req.location,
+            cutoff_time.to_iso8601_string()
+        );
+        let tables = self
+            .catalog_manager
+            .table_names(&req.catalog_name, &req.schema_name)
+            .await
+            .context(CatalogSnafu)?;
+        let retained_wal = self
+            .retained_wal(&req.catalog_name, &req.schema_name, &tables, &ctx)
+            .await?;
+        let manifest = BackupManifest {
+            version: BACKUP_MANIFEST_VERSION,
+            catalog_name: req.catalog_name.clone(),
+            schema_name: req.schema_name.clone(),
+            cutoff_time: cutoff_time.value(),
+            format,
+            tables,
+            rows: 0,
+            retained_wal,
+        };
+
+        let uncut_tables = manifest
+            .retained_wal
+            .keys()
+            .cloned()
+            .collect::<HashSet<_>>();
+        let output = self.export_database(req, &uncut_tables, ctx).await?;
+        let (rows, _) = output.extract_rows_and_cost();
+        let manifest = BackupManifest { rows, ..manifest };
+        let manifest = serde_json::to_string(&manifest).context(error::EncodeJsonSnafu)?;
+        object_store
+            .write(BACKUP_MANIFEST_FILE, manifest)
+            .await
+            .context(error::WriteObjectSnafu {
+                path: BACKUP_MANIFEST_FILE,
+            })?;
+
+        Ok(Output::new_with_affected_rows(rows))
+    }
+
+    /// Restores the database from a backup made by [StatementExecutor::backup_database].
+    ///
+    /// If [RESTORE_REPLAY_WAL_KEY] is true, tables with retained WAL also replay their WAL
+    /// after the data is imported. [RESTORE_UNTIL_KEY] limits the replayed rows to those
+    /// whose time index is at or before the timestamp, rows in the backup are all imported.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn restore_database(
+        &self,
+        mut req: CopyDatabaseRequest,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        ensure!(
+            req.location.ends_with('/'),
+            InvalidCopyDatabasePathSnafu {
+                value: req.location,
+            }
+        );
+        let object_store =
+            build_backend(&req.location, &req.connection).context(error::BuildBackendSnafu)?;
+        let manifest = read_manifest(&object_store, &req.location).await?;
+
+        info!(
+            "Restore database {}.{} from {}, backup of {}.{} with rows before {}",
+            req.catalog_name,
+            req.schema_name,
+            req.location,
+            manifest.catalog_name,
+            manifest.schema_name,
+            Timestamp::new_millisecond(manifest.cutoff_time).to_iso8601_string()
+        );
+        let replay_wal = req
+            .with
+            .get(RESTORE_REPLAY_WAL_KEY)
+            .map(|value| {
+                bool::from_str(value).map_err(|_| {
+                    error::InvalidCopyParameterSnafu {
+                        key: RESTORE_REPLAY_WAL_KEY,
+                        value,
+                    }
+                    .build()
+                })
+            })
+            .transpose()?
+            .unwrap_or(false);
+        let until = req
+            .with
+            .get(RESTORE_UNTIL_KEY)
+            .map(|value| {
+                Timestamp::from_str(value, Some(&ctx.timezone()))
+                    .ok()
+                    // It only limits the replay.
+                    .filter(|_| replay_wal)
+                    .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+                    .with_context(|| error::InvalidCopyParameterSnafu {
+                        key: RESTORE_UNTIL_KEY,
+                        value,
+                    })
+            })
+            .transpose()?;
+        req.with
+            .insert(FORMAT_TYPE.to_string(), manifest.format.clone());
+        let catalog = req.catalog_name.clone();
+        let schema = req.schema_name.clone();
+        let output = self.copy_database_from(req, ctx.clone()).await?;
+        if !replay_wal {
+            return Ok(output);
+        }
+
+        // Replays after importing the data, so rows from the WAL overwrite older ones.
+        for table in &manifest.tables {
+            if !manifest.retained_wal.contains_key(table) {
+                warn!(
+                    "Table {} doesn't retain WAL, restores it to the backup cutoff",
+                    table
+                );
+            }
+        }
+        let (mut rows, cost) = output.extract_rows_and_cost();
+        for (table, retained_wal) in &manifest.retained_wal {
+            rows += self
+                .requester
+                .handle_table_wal_replay(
+                    &catalog,
+                    &schema,
+                    table,
+                    &retained_wal.replay_source(until),
+                    ctx.clone(),
+                )
+                .await?;
+        }
+        Ok(Output::new(
+            OutputData::AffectedRows(rows),
+            OutputMeta::new_with_cost(cost),
+        ))
+    }
+
+    /// Returns the retained WAL of `tables` that set [WAL_RETAIN_PERIOD_KEY].
+    ///
+    /// Flushes these tables to record the entry ids their WAL is replayed after.
+    async fn retained_wal(
+        &self,
+        catalog: &str,
+        schema: &str,
+        tables: &[String],
+        ctx: &QueryContextRef,
+    ) -> Result<HashMap<String, RetainedWal>> {
+        let mut retained_wal = HashMap::new();
+        for table_name in tables {
+            let Some(table) = self
+                .catalog_manager
+                .table(catalog, schema, table_name)
+                .await
+                .context(CatalogSnafu)?
+            else {
+                continue;
+            };
+            let table_info = table.table_info();
+            if !table_info
+                .meta
+                .options
+                .extra_options
+                .contains_key(WAL_RETAIN_PERIOD_KEY)
+            {
+                continue;
+            }
+
+            let table_id = table_info.table_id();
+            let region_wal_options = self.region_wal_options(table_id).await?;
+            let region_entry_ids = self
+                .requester
+                .handle_table_flush_entry_ids(
+                    FlushTableRequest {
+                        catalog_name: catalog.to_string(),
+                        schema_name: schema.to_string(),
+                        table_name: table_name.clone(),
+                    },
+                    ctx.clone(),
+                )
+                .await?;
+            let _ = retained_wal.insert(
+                table_name.clone(),
+                RetainedWal {
+                    table_id,
+                    region_wal_options,
+                    region_entry_ids,
+                },
+            );
+        }
+        Ok(retained_wal)
+    }
+
+    async fn region_wal_options(&self, table_id: TableId) -> Result<HashMap<RegionNumber, String>> {
+        let distribution = self
+            .table_metadata_manager
+            .table_route_manager()
+            .get_region_distribution(table_id)
+            .await
+            .context(TableMetadataManagerSnafu)?
+            .unwrap_or_default();
+
+        let mut region_wal_options = HashMap::new();
+        for datanode_id in distribution.keys() {
+            let value = self
+                .table_metadata_manager
+                .datanode_table_manager()
+                .get(&DatanodeTableKey::new(*datanode_id, table_id))
+                .await
+                .context(TableMetadataManagerSnafu)?;
+            if let Some(value) = value {
+                region_wal_options.extend(value.region_info.region_wal_options);
+            }
+        }
+        Ok(region_wal_options)
+    }
+}
+
+/// Limits `time_range` to rows before `cutoff_time`.
+fn cutoff_range(time_range: Option<TimestampRange>, cutoff_time: Timestamp) -> TimestampRange {
+    let cutoff = TimestampRange::until_end(cutoff_time, false);
+    match time_range {
+        Some(time_range) => time_range.and(&cutoff),
+        None => cutoff,
+    }
+}
+
+async fn read_manifest(object_store: &ObjectStore, location: &str) -> Result<BackupManifest> {
+    let exists =
+        object_store
+            .is_exist(BACKUP_MANIFEST_FILE)
+            .await
+            .context(error::ReadObjectSnafu {
+                path: BACKUP_MANIFEST_FILE,
+            })?;
+    ensure!(exists, error::BackupNotFoundSnafu { value: location });
+
+    let bytes = object_store
+        .read(BACKUP_MANIFEST_FILE)
+        .await
+        .context(error::ReadObjectSnafu {
+            path: BACKUP_MANIFEST_FILE,
+        })?;
+    let manifest: BackupManifest =
+        serde_json::from_slice(&bytes).context(error::DecodeBackupManifestSnafu {
+            path: BACKUP_MANIFEST_FILE,
+        })?;
+    ensure!(
+        manifest.version == BACKUP_MANIFEST_VERSION,
+        error::BackupNotFoundSnafu { value: location }
+    );
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::services::Fs;
+    use object_store::util::normalize_dir;
+
+    use super::*;
+
+    #[test]
+    fn test_cutoff_range() {
+        let cutoff = Timestamp::new_millisecond(1000);
+        assert_eq!(
+            TimestampRange::until_end(cutoff, false),
+            cutoff_range(None, cutoff)
+        );
+
+        let start = Timestamp::new_millisecond(100);
+        assert_eq!(
+            TimestampRange::new(start, cutoff).unwrap(),
+            cutoff_range(Some(TimestampRange::from_start(start)), cutoff)
+        );
+
+        let end = Timestamp::new_millisecond(500);
+        assert_eq!(
+            TimestampRange::until_end(end, false),
+            cutoff_range(Some(TimestampRange::until_end(end, false)), cutoff)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_manifest() {
+        let dir = common_test_util::temp_dir::create_temp_dir("test_read_backup_manifest");
+        let mut builder = Fs::default();
+        let _ = builder.root(&normalize_dir(dir.path().to_str().unwrap()));
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+
+        let err = read_manifest(&object_store, "/backup/").await.unwrap_err();
+        assert!(matches!(err, error::Error::BackupNotFound { .. }));
+
+        let manifest = BackupManifest {
+            version: BACKUP_MANIFEST_VERSION,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            cutoff_time: 1000,
+            format: "parquet".to_string(),
+            tables: vec!["foo".to_string()],
+            rows: 10,
+            retained_wal: HashMap::new(),
+        };
+        object_store
+            .write(
+                BACKUP_MANIFEST_FILE,
+                serde_json::to_string(&manifest).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            manifest,
+            read_manifest(&object_store, "/backup/").await.unwrap()
+        );
+
+        object_store
+            .write(BACKUP_MANIFEST_FILE, "not a manifest")
+            .await
+            .unwrap();
+        let err = read_manifest(&object_store, "/backup/").await.unwrap_err();
+        assert!(matches!(err, error::Error::DecodeBackupManifest { .. }));
+    }
+
+    #[test]
+    fn test_retained_wal() {
+        let retained_wal = RetainedWal {
+            table_id: 1024,
+            region_wal_options: HashMap::new(),
+            region_entry_ids: HashMap::from([(0, 10)]),
+        };
+        assert_eq!(
+            WalReplaySource {
+                table_id: 1024,
+                region_wal_options: HashMap::new(),
+                region_entry_ids: HashMap::from([(0, 10)]),
+                until: Some(1000),
+            },
+            retained_wal.replay_source(Some(Timestamp::new_second(1)))
+        );
+
+        // Manifests written before entry ids were recorded replay all entries.
+        let retained_wal: RetainedWal =
+            serde_json::from_str(r#"{"table_id":1024,"region_wal_options":{}}"#).unwrap();
+        assert!(retained_wal.region_entry_ids.is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `EXPLAIN CAPTURE` and the bundle it records to replay a query.
+
+use std::sync::Arc;
+
+use common_query::{Output, OutputData};
+use common_recordbatch::{RecordBatch, RecordBatches};
+use common_telemetry::tracing;
+use common_time::util::current_time_millis;
+use datafusion::datasource::DefaultTableSource;
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
+use datafusion_expr::LogicalPlan as DfLogicalPlan;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{StringVector, VectorRef};
+use query::parser::QueryStatement;
+use query::plan::LogicalPlan;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use sql::statements::explain::ExplainCapture;
+use sql::statements::statement::Statement;
+use table::metadata::TableType;
+use table::table::adapter::DfTableProviderAdapter;
+use table::TableRef;
+
+use crate::error::{
+    BuildColumnVectorsSnafu, EncodeJsonSnafu, ExecLogicalPlanSnafu, FindRegionRoutesSnafu,
+    OptimizeLogicalPlanSnafu, ReadRecordBatchSnafu, Result,
+};
+use crate::statement::StatementExecutor;
+
+/// Name of the column holding the bundle.
+pub const BUNDLE_COLUMN: &str = "bundle";
+pub const CAPTURE_BUNDLE_VERSION: u32 = 1;
+
+/// Everything needed to replay a query when debugging wrong results.
+///
+/// To replay a bundle, run `greptime cli replay` with it against a cluster holding
+/// the involved tables. It captures the query again with the same session settings
+/// and compares the tables, the plan and the result.
+///
+/// The replay reads the tables as they are when it runs, so it only reproduces the
+/// result if their rows haven't changed since the capture. No restore brings the
+/// tables back to the capture time: `RESTORE DATABASE ... WITH (until = ...)`
+/// selects replayed rows by their time index, not by when they were written. The
+/// bundle doesn't record manifest or SST versions of regions either, as datanodes
+/// don't expose them to the frontend. So the replay can only explain a different
+/// result by differences of the plan or the table versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureBundle {
+    pub version: u32,
+    /// The time in milliseconds the query was run.
+    pub captured_at: i64,
+    pub sql: String,
+    pub session: CaptureSession,
+    /// The optimized logical plan.
+    pub plan: String,
+    pub tables: Vec<CaptureTable>,
+    pub result: CaptureResult,
+}
+
+/// Session settings affecting query results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureSession {
+    pub catalog: String,
+    pub schema: String,
+    pub timezone: String,
+    pub type_coercion: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureTable {
+    pub name: String,
+    pub table_id: u32,
+    /// Version of the table metadata.
+    pub table_version: u64,
+    pub regions: Vec<CaptureRegion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureRegion {
+    pub region_id: u64,
+    /// Address of the datanode serving the region, `None` if it has no leader.
+    pub leader: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureResult {
+    pub rows: usize,
+    /// Digest of the rows, independent of the row order.
+    pub digest: String,
+}
+
+impl StatementExecutor {
+    /// Runs the query of `EXPLAIN CAPTURE` and returns a [CaptureBundle] encoded
+    /// in json in a single row.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn explain_capture(
+        &self,
+        stmt: ExplainCapture,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let captured_at = current_time_millis();
+        let sql = stmt.query.inner.to_string();
+        let plan = self
+            .plan(
+                QueryStatement::Sql(Statement::Query(stmt.query)),
+                query_ctx.clone(),
+            )
+            .await?;
+        let LogicalPlan::DfPlan(df_plan) = &plan;
+        let optimized = self
+            .query_engine
+            .engine_context(query_ctx.clone())
+            .state()
+            .optimize(df_plan)
+            .context(OptimizeLogicalPlanSnafu)?;
+        let tables = self.capture_tables(df_plan).await?;
+
+        let output = self
+            .query_engine
+            .execute(plan, query_ctx.clone())
+            .await
+            .context(ExecLogicalPlanSnafu)?;
+        let batches = match output.data {
+            OutputData::Stream(stream) => common_recordbatch::util::collect(stream)
+                .await
+                .context(ReadRecordBatchSnafu)?,
+            OutputData::RecordBatches(batches) => batches.take(),
+            OutputData::AffectedRows(_) => vec![],
+        };
+
+        let bundle = CaptureBundle {
+            version: CAPTURE_BUNDLE_VERSION,
+            captured_at,
+            sql,
+            session: CaptureSession {
+                catalog: query_ctx.current_catalog().to_string(),
+                schema: query_ctx.current_schema().to_string(),
+                timezone: query_ctx.timezone().to_string(),
+                type_coercion: format!(
+                    "{:?}",
+                    query_ctx.configuration_parameter().type_coercion_mode()
+                )
+                .to_lowercase(),
+            },
+            plan: optimized.display_indent().to_string(),
+            tables,
+            result: capture_result(&batches),
+        };
+        let bundle = serde_json::to_string_pretty(&bundle).context(EncodeJsonSnafu)?;
+
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            BUNDLE_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        )]));
+        let column: VectorRef = Arc::new(StringVector::from(vec![bundle]));
+        let records = RecordBatches::try_from_columns(schema, vec![column])
+            .context(BuildColumnVectorsSnafu)?;
+        Ok(Output::new_with_record_batches(records))
+    }
+
+    /// Collects versions and regions of the tables `plan` scans.
+    async fn capture_tables(&self, plan: &DfLogicalPlan) -> Result<Vec<CaptureTable>> {
+        let scanned = scanned_tables(plan);
+        let mut tables = Vec::with_capacity(scanned.len());
+        for table in scanned {
+            let info = table.table_info();
+            let regions = if info.table_type == TableType::Base {
+                self.partition_manager
+                    .find_region_routes(info.table_id())
+                    .await
+                    .context(FindRegionRoutesSnafu {
+                        table_id: info.table_id(),
+                    })?
+                    .into_iter()
+                    .map(|route| CaptureRegion {
+                        region_id: route.region.id.as_u64(),
+                        leader: route.leader_peer.map(|peer| peer.addr),
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+            tables.push(CaptureTable {
+                name: info.full_table_name(),
+                table_id: info.table_id(),
+                table_version: info.ident.version,
+                regions,
+            });
+        }
+        Ok(tables)
+    }
+}
+
+fn capture_result(batches: &[RecordBatch]) -> CaptureResult {
+    let mut rows = batches
+        .iter()
+        .flat_map(|batch| batch.rows())
+        .map(|row| {
+            row.iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join("\t")
+        })
+        .collect::<Vec<_>>();
+    rows.sort_unstable();
+
+    CaptureResult {
+        rows: rows.len(),
+        digest: format!("{:016x}", fnv1a64(rows.iter().map(|row| row.as_bytes()))),
+    }
+}
+
+/// FNV-1a hash over `rows`, it's stable across builds so bundles captured by
+/// different versions can be compared.
+fn fnv1a64<'a>(rows: impl Iterator<Item = &'a [u8]>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    for row in rows {
+        for byte in row.iter().chain(b"\n") {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// Returns the tables `plan` scans, without duplicates.
+pub(crate) fn scanned_tables(plan: &DfLogicalPlan) -> Vec<TableRef> {
+    let mut scanned: Vec<TableRef> = vec![];
+    let _ = plan.apply(&mut |node| {
+        if let DfLogicalPlan::TableScan(scan) = node {
+            if let Some(table) = scan
+                .source
+                .as_any()
+                .downcast_ref::<DefaultTableSource>()
+                .and_then(|source| {
+                    source
+                        .table_provider
+                        .as_any()
+                        .downcast_ref::<DfTableProviderAdapter>()
+                })
+                .map(|provider| provider.table())
+            {
+                let table_id = table.table_info().table_id();
+                if scanned
+                    .iter()
+                    .all(|t| t.table_info().table_id() != table_id)
+                {
+                    scanned.push(table);
+                }
+            }
+        }
+        Ok(VisitRecursion::Continue)
+    });
+    scanned
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::vectors::Int32Vector;
+
+    use super::*;
+
+    #[test]
+    fn test_capture_result_ignores_row_order() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batch = |values: Vec<i32>| {
+            RecordBatch::new(
+                schema.clone(),
+                vec![Arc::new(Int32Vector::from_vec(values)) as VectorRef],
+            )
+            .unwrap()
+        };
+
+        let result = capture_result(&[batch(vec![1, 2]), batch(vec![3])]);
+        assert_eq!(3, result.rows);
+        assert_eq!(result, capture_result(&[batch(vec![3, 2, 1])]));
+        assert_ne!(result, capture_result(&[batch(vec![1, 2, 4])]));
+        assert_eq!(
+            format!("{:016x}", fnv1a64(std::iter::empty())),
+            capture_result(&[]).digest
+        );
+    }
+}
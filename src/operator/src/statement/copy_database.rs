@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::path::Path;
 use std::str::FromStr;
 
@@ -20,19 +21,28 @@ use common_datasource::file_format::Format;
 use common_datasource::lister::{Lister, Source};
 use common_datasource::object_store::build_backend;
 use common_telemetry::{debug, error, info, tracing};
-use object_store::Entry;
+use object_store::{Entry, ObjectStore};
 use regex::Regex;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
+use sql::parser::{ParseOptions, ParserContext};
+use sql::statements::create::CreateTable;
+use sql::statements::statement::Statement;
+use sqlparser::ast::{Ident, ObjectName};
+use store_api::metric_engine_consts::LOGICAL_TABLE_METADATA_KEY;
 use table::requests::{CopyDatabaseRequest, CopyDirection, CopyTableRequest};
+use table::TableRef;
 
 use crate::error;
 use crate::error::{CatalogSnafu, InvalidCopyDatabasePathSnafu};
+use crate::statement::show::create_partitions_stmt;
 use crate::statement::StatementExecutor;
 
 pub(crate) const COPY_DATABASE_TIME_START_KEY: &str = "start_time";
 pub(crate) const COPY_DATABASE_TIME_END_KEY: &str = "end_time";
 pub(crate) const CONTINUE_ON_ERROR_KEY: &str = "continue_on_error";
+/// Suffix of files containing `CREATE TABLE` statements of exported tables.
+const CREATE_TABLE_FILE_SUFFIX: &str = ".sql";
 
 impl StatementExecutor {
     #[tracing::instrument(skip_all)]
@@ -40,6 +50,17 @@ impl StatementExecutor {
         &self,
         req: CopyDatabaseRequest,
         ctx: QueryContextRef,
+    ) -> error::Result<Output> {
+        self.export_database(req, &HashSet::new(), ctx).await
+    }
+
+    /// Exports data of the database like [StatementExecutor::copy_database_to], but
+    /// exports all rows of `uncut_tables` regardless of the time range.
+    pub(crate) async fn export_database(
+        &self,
+        req: CopyDatabaseRequest,
+        uncut_tables: &HashSet<String>,
+        ctx: QueryContextRef,
     ) -> error::Result<Output> {
         // location must end with / so that every table is exported to a file.
         ensure!(
@@ -62,6 +83,8 @@ impl StatementExecutor {
         let suffix = Format::try_from(&req.with)
             .context(error::ParseFileFormatSnafu)?
             .suffix();
+        let object_store =
+            build_backend(&req.location, &req.connection).context(error::BuildBackendSnafu)?;
 
         let mut exported_rows = 0;
         for table_name in table_names {
@@ -70,6 +93,17 @@ impl StatementExecutor {
             if table_name == "numbers" {
                 continue;
             }
+            let Some(table) = self
+                .catalog_manager
+                .table(&req.catalog_name, &req.schema_name, &table_name)
+                .await
+                .context(CatalogSnafu)?
+            else {
+                continue;
+            };
+            self.export_create_table(&object_store, &table, &ctx)
+                .await?;
+
             let mut table_file = req.location.clone();
             table_file.push_str(&table_name);
             table_file.push_str(suffix);
@@ -78,6 +112,11 @@ impl StatementExecutor {
                 req.catalog_name, req.schema_name, table_name, table_file
             );
 
+            let timestamp_range = if uncut_tables.contains(&table_name) {
+                None
+            } else {
+                req.time_range
+            };
             let exported = self
                 .copy_table_to(
                     CopyTableRequest {
@@ -89,7 +128,7 @@ impl StatementExecutor {
                         connection: req.connection.clone(),
                         pattern: None,
                         direction: CopyDirection::Export,
-                        timestamp_range: req.time_range,
+                        timestamp_range,
                     },
                     ctx.clone(),
                 )
@@ -122,14 +161,17 @@ impl StatementExecutor {
             .context(error::ParseFileFormatSnafu)?
             .suffix();
 
-        let entries = list_files_to_copy(&req, suffix).await?;
-
         let continue_on_error = req
             .with
             .get(CONTINUE_ON_ERROR_KEY)
             .and_then(|v| bool::from_str(v).ok())
             .unwrap_or(false);
 
+        self.create_tables_from_files(&req, continue_on_error, &ctx)
+            .await?;
+
+        let entries = list_files_to_copy(&req, suffix).await?;
+
         let mut rows_inserted = 0;
         let mut insert_cost = 0;
 
@@ -180,6 +222,105 @@ impl StatementExecutor {
     }
 }
 
+impl StatementExecutor {
+    /// Writes the `CREATE TABLE` statement of `table` to `{table_name}.sql`.
+    async fn export_create_table(
+        &self,
+        object_store: &ObjectStore,
+        table: &TableRef,
+        ctx: &QueryContextRef,
+    ) -> error::Result<()> {
+        let table_info = table.table_info();
+        let partitions = self
+            .partition_manager
+            .find_table_partitions(table_info.table_id())
+            .await
+            .context(error::FindTablePartitionRuleSnafu {
+                table_name: &table_info.name,
+            })?;
+        let partitions = create_partitions_stmt(partitions)?;
+        let sql = query::sql::create_table_sql(table, partitions, ctx.quote_style())
+            .context(error::ExecuteStatementSnafu)?;
+
+        let path = format!("{}{}", table_info.name, CREATE_TABLE_FILE_SUFFIX);
+        object_store
+            .write(&path, sql)
+            .await
+            .context(error::WriteObjectSnafu { path })
+    }
+
+    /// Creates tables absent in the database from exported `CREATE TABLE` statements.
+    async fn create_tables_from_files(
+        &self,
+        req: &CopyDatabaseRequest,
+        continue_on_error: bool,
+        ctx: &QueryContextRef,
+    ) -> error::Result<()> {
+        let object_store =
+            build_backend(&req.location, &req.connection).context(error::BuildBackendSnafu)?;
+        let entries = list_files_to_copy(req, CREATE_TABLE_FILE_SUFFIX).await?;
+
+        let mut stmts = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match read_create_table(&object_store, &entry, ctx).await {
+                Ok(stmt) => stmts.extend(stmt),
+                Err(err) if continue_on_error => {
+                    error!(err; "Failed to read create table statement from file: {:?}", entry);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        // Physical tables must be created before their logical tables.
+        stmts.sort_by_key(is_logical_table);
+
+        for mut stmt in stmts {
+            let Some(table_name) = stmt.name.0.last().cloned() else {
+                continue;
+            };
+            stmt.if_not_exists = true;
+            stmt.name = ObjectName(vec![
+                Ident::new(&req.catalog_name),
+                Ident::new(&req.schema_name),
+                table_name.clone(),
+            ]);
+            if let Err(err) = self.create_table(stmt, ctx.clone()).await {
+                if continue_on_error {
+                    error!(err; "Failed to create table: {}", table_name);
+                } else {
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the `CREATE TABLE` statement from the file `entry`.
+async fn read_create_table(
+    object_store: &ObjectStore,
+    entry: &Entry,
+    ctx: &QueryContextRef,
+) -> error::Result<Option<CreateTable>> {
+    let sql = object_store
+        .read(entry.path())
+        .await
+        .context(error::ReadObjectSnafu { path: entry.path() })?;
+    let sql = String::from_utf8_lossy(&sql);
+    let stmts =
+        ParserContext::create_with_dialect(&sql, ctx.sql_dialect(), ParseOptions::default())
+            .context(error::ParseSqlSnafu)?;
+    Ok(stmts.into_iter().find_map(|stmt| match stmt {
+        Statement::CreateTable(stmt) => Some(stmt),
+        _ => None,
+    }))
+}
+
+pub(crate) fn is_logical_table(stmt: &CreateTable) -> bool {
+    stmt.options
+        .iter()
+        .any(|option| option.name.value == LOGICAL_TABLE_METADATA_KEY)
+}
+
 /// Parses table names from files' names.
 fn parse_file_name_to_copy(e: &Entry) -> error::Result<String> {
     Path::new(e.name())
@@ -196,7 +337,8 @@ async fn list_files_to_copy(req: &CopyDatabaseRequest, suffix: &str) -> error::R
     let object_store =
         build_backend(&req.location, &req.connection).context(error::BuildBackendSnafu)?;
 
-    let pattern = Regex::try_from(format!(".*{}", suffix)).context(error::BuildRegexSnafu)?;
+    let pattern =
+        Regex::try_from(format!(".*{}$", regex::escape(suffix))).context(error::BuildRegexSnafu)?;
     let lister = Lister::new(
         object_store.clone(),
         Source::Dir,
@@ -16,22 +16,24 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
+use api::v1::alter_expr::Kind;
 use api::v1::{column_def, AlterExpr, CreateTableExpr};
 use catalog::CatalogManagerRef;
 use chrono::Utc;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_catalog::format_full_table_name;
-use common_error::ext::BoxedError;
+use common_error::ext::{BoxedError, ErrorExt};
 use common_meta::cache_invalidator::Context;
 use common_meta::ddl::ExecutorContext;
 use common_meta::instruction::CacheIdent;
 use common_meta::key::schema_name::{SchemaNameKey, SchemaNameValue};
+use common_meta::key::view_info::{ViewInfoKey, ViewInfoValue};
 use common_meta::key::NAME_PATTERN;
-use common_meta::rpc::ddl::{DdlTask, SubmitDdlTaskRequest, SubmitDdlTaskResponse};
+use common_meta::rpc::ddl::{DdlTask, DropDependents, SubmitDdlTaskRequest, SubmitDdlTaskResponse};
 use common_meta::rpc::router::{Partition, Partition as MetaPartition};
 use common_meta::table_name::TableName;
-use common_query::Output;
-use common_telemetry::{info, tracing};
+use common_query::{DdlResult, DdlStatus, Output};
+use common_telemetry::{info, tracing, warn};
 use common_time::Timezone;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::RawSchema;
@@ -39,18 +41,27 @@ use datatypes::value::Value;
 use lazy_static::lazy_static;
 use partition::expr::{Operand, PartitionExpr, RestrictedOp};
 use partition::partition::{PartitionBound, PartitionDef};
+use query::parser::QueryStatement;
+use query::plan::LogicalPlan;
 use query::sql::create_table_stmt;
 use regex::Regex;
-use session::context::QueryContextRef;
+use session::context::{QueryContext, QueryContextRef};
 use session::table_name::table_idents_to_full_name;
 use snafu::{ensure, IntoError, OptionExt, ResultExt};
+use sql::dialect::GreptimeDbDialect;
+use sql::parser::{ParseOptions, ParserContext};
 use sql::statements::alter::AlterTable;
-use sql::statements::create::{CreateExternalTable, CreateTable, CreateTableLike, Partitions};
+use sql::statements::create::{
+    CreateExternalTable, CreateTable, CreateTableLike, CreateView, Partitions,
+};
 use sql::statements::sql_value_to_value;
+use sql::statements::statement::Statement;
 use sqlparser::ast::{Expr, Ident, Value as ParserValue};
 use store_api::metric_engine_consts::{LOGICAL_TABLE_METADATA_KEY, METRIC_ENGINE_NAME};
 use table::dist_table::DistTable;
-use table::metadata::{self, RawTableInfo, RawTableMeta, TableId, TableInfo, TableType};
+use table::metadata::{
+    self, RawTableInfo, RawTableMeta, TableId, TableInfo, TableInfoRef, TableType,
+};
 use table::requests::{AlterKind, AlterTableRequest, TableOptions};
 use table::TableRef;
 
@@ -61,11 +72,18 @@ use crate::error::{
     DdlWithMultiSchemasSnafu, DeserializePartitionSnafu, EmptyDdlExprSnafu,
     InvalidPartitionColumnsSnafu, InvalidPartitionRuleSnafu, InvalidTableNameSnafu,
     ParseSqlValueSnafu, Result, SchemaNotFoundSnafu, TableAlreadyExistsSnafu,
-    TableMetadataManagerSnafu, TableNotFoundSnafu, UnrecognizedTableOptionSnafu,
+    TableHasDependentsSnafu, TableMetadataManagerSnafu, TableNotFoundSnafu,
+    UnrecognizedTableOptionSnafu, ViewAlreadyExistsSnafu, ViewNotFoundSnafu,
 };
 use crate::expr_factory;
+use crate::statement::capture::scanned_tables;
+use crate::statement::materialized_view::parse_definition;
 use crate::statement::show::create_partitions_stmt;
 
+/// Query context extension to fail `CREATE TABLE IF NOT EXISTS` when the existing table's
+/// schema differs from the statement, instead of only warning about it.
+pub const STRICT_IF_NOT_EXISTS_EXTENSION: &str = "strict_if_not_exists";
+
 lazy_static! {
     static ref NAME_PATTERN_REG: Regex = Regex::new(&format!("^{NAME_PATTERN}$")).unwrap();
 }
@@ -75,10 +93,15 @@ impl StatementExecutor {
         self.catalog_manager.clone()
     }
 
+    /// Creates the table and returns it with whether it is created or already exists.
     #[tracing::instrument(skip_all)]
-    pub async fn create_table(&self, stmt: CreateTable, ctx: QueryContextRef) -> Result<TableRef> {
+    pub async fn create_table(
+        &self,
+        stmt: CreateTable,
+        ctx: QueryContextRef,
+    ) -> Result<(TableRef, DdlStatus)> {
         let create_expr = &mut expr_factory::create_to_expr(&stmt, ctx.clone())?;
-        self.create_table_inner(create_expr, stmt.partitions, &ctx)
+        self.create_table_with_status(create_expr, stmt.partitions, &ctx)
             .await
     }
 
@@ -87,7 +110,7 @@ impl StatementExecutor {
         &self,
         stmt: CreateTableLike,
         ctx: QueryContextRef,
-    ) -> Result<TableRef> {
+    ) -> Result<(TableRef, DdlStatus)> {
         let (catalog, schema, table) = table_idents_to_full_name(&stmt.source_name, &ctx)
             .map_err(BoxedError::new)
             .context(error::ExternalSnafu)?;
@@ -119,7 +142,8 @@ impl StatementExecutor {
         });
 
         let create_expr = &mut expr_factory::create_to_expr(&create_stmt, ctx.clone())?;
-        self.create_table_inner(create_expr, partitions, &ctx).await
+        self.create_table_with_status(create_expr, partitions, &ctx)
+            .await
     }
 
     #[tracing::instrument(skip_all)]
@@ -127,9 +151,9 @@ impl StatementExecutor {
         &self,
         create_expr: CreateExternalTable,
         ctx: QueryContextRef,
-    ) -> Result<TableRef> {
+    ) -> Result<(TableRef, DdlStatus)> {
         let create_expr = &mut expr_factory::create_external_expr(create_expr, ctx.clone()).await?;
-        self.create_table_inner(create_expr, None, &ctx).await
+        self.create_table_with_status(create_expr, None, &ctx).await
     }
 
     #[tracing::instrument(skip_all)]
@@ -139,20 +163,37 @@ impl StatementExecutor {
         partitions: Option<Partitions>,
         query_ctx: &QueryContextRef,
     ) -> Result<TableRef> {
+        self.create_table_with_status(create_table, partitions, query_ctx)
+            .await
+            .map(|(table, _)| table)
+    }
+
+    /// Creates the table and returns it with whether it is created or already exists,
+    /// as told by the lookup of the table before creating it.
+    #[tracing::instrument(skip_all)]
+    pub async fn create_table_with_status(
+        &self,
+        create_table: &mut CreateTableExpr,
+        partitions: Option<Partitions>,
+        query_ctx: &QueryContextRef,
+    ) -> Result<(TableRef, DdlStatus)> {
         // Check if is creating logical table
         if create_table.engine == METRIC_ENGINE_NAME
             && create_table
                 .table_options
                 .contains_key(LOGICAL_TABLE_METADATA_KEY)
         {
-            return self
+            // The procedure creating logical tables skips existing tables, it doesn't
+            // tell whether the table already exists.
+            let table = self
                 .create_logical_tables(&[create_table.clone()])
                 .await?
                 .into_iter()
                 .next()
                 .context(error::UnexpectedSnafu {
                     violated: "expected to create a logical table",
-                });
+                })?;
+            return Ok((table, DdlStatus::Created));
         }
 
         let _timer = crate::metrics::DIST_CREATE_TABLE.start_timer();
@@ -185,7 +226,32 @@ impl StatementExecutor {
             .context(CatalogSnafu)?
         {
             return if create_table.create_if_not_exists {
-                Ok(table)
+                let mismatches = find_schema_mismatches(create_table, &table.table_info())?;
+                if !mismatches.is_empty() {
+                    let table_name = format_full_table_name(
+                        &create_table.catalog_name,
+                        &create_table.schema_name,
+                        &create_table.table_name,
+                    );
+                    let strict = query_ctx
+                        .extension(STRICT_IF_NOT_EXISTS_EXTENSION)
+                        .map(|v| v.eq_ignore_ascii_case("true"))
+                        .unwrap_or(false);
+                    ensure!(
+                        !strict,
+                        error::TableSchemaMismatchSnafu {
+                            table: table_name,
+                            mismatches: mismatches.join("; "),
+                        }
+                    );
+                    let warning = format!(
+                        "Table {table_name} already exists with a different schema, mismatches: {}",
+                        mismatches.join("; ")
+                    );
+                    warn!("{warning}");
+                    query_ctx.add_warning(warning);
+                }
+                Ok((table, DdlStatus::AlreadyExists))
             } else {
                 TableAlreadyExistsSnafu {
                     table: format_full_table_name(
@@ -233,7 +299,7 @@ impl StatementExecutor {
 
         let table = DistTable::table(table_info);
 
-        Ok(table)
+        Ok((table, DdlStatus::Created))
     }
 
     #[tracing::instrument(skip_all)]
@@ -336,8 +402,74 @@ impl StatementExecutor {
         Ok(Output::new_with_affected_rows(0))
     }
 
+    /// Drops the table, views reading from it are dropped too if `cascade` is true,
+    /// otherwise it fails if any view reads from it.
     #[tracing::instrument(skip_all)]
-    pub async fn drop_table(&self, table_name: TableName, drop_if_exists: bool) -> Result<Output> {
+    pub async fn drop_table(
+        &self,
+        table_name: TableName,
+        drop_if_exists: bool,
+        cascade: bool,
+    ) -> Result<Output> {
+        self.bind_unbound_views(&table_name.catalog_name).await?;
+        let dependents = if cascade {
+            DropDependents::Cascade
+        } else {
+            DropDependents::Restrict
+        };
+        self.drop_table_inner(table_name, drop_if_exists, dependents)
+            .await
+    }
+
+    /// Records the tables read by views in the `catalog` created before these
+    /// tables are recorded, so dropping the tables takes the views into account.
+    ///
+    /// Views failing to be planned are skipped.
+    async fn bind_unbound_views(&self, catalog: &str) -> Result<()> {
+        let view_info_manager = self.table_metadata_manager.view_info_manager();
+        let views = view_info_manager
+            .unbound_views(catalog)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        for (view_name, view_info) in views {
+            let source_tables = match self.plan_view(&view_name, &view_info.definition).await {
+                Ok(plan) => source_tables(&plan),
+                Err(e) => {
+                    warn!(e; "Failed to plan view {view_name}, skip recording its source tables");
+                    continue;
+                }
+            };
+            if source_tables.is_empty() {
+                continue;
+            }
+
+            let view_info = view_info.with_source_tables(source_tables);
+            let _ = view_info_manager
+                .create(ViewInfoKey::from(&view_name), &view_info, true)
+                .await
+                .context(TableMetadataManagerSnafu)?;
+            info!("Recorded source tables of view {view_name}");
+        }
+
+        Ok(())
+    }
+
+    async fn plan_view(&self, view_name: &TableName, definition: &str) -> Result<LogicalPlan> {
+        let query = parse_definition(view_name, definition)?;
+        let view_ctx = QueryContext::with(&view_name.catalog_name, &view_name.schema_name);
+        self.plan(
+            QueryStatement::Sql(Statement::Query(Box::new(query))),
+            view_ctx,
+        )
+        .await
+    }
+
+    pub(crate) async fn drop_table_inner(
+        &self,
+        table_name: TableName,
+        drop_if_exists: bool,
+        dependents: DropDependents,
+    ) -> Result<Output> {
         if let Some(table) = self
             .catalog_manager
             .table(
@@ -348,26 +480,24 @@ impl StatementExecutor {
             .await
             .context(CatalogSnafu)?
         {
+            if !self.procedure_executor.supports_view_tasks() {
+                // A remote metasrv can't drop views, the frontend drops them instead.
+                self.drop_dependent_views(&table_name, dependents).await?;
+            }
             let table_id = table.table_info().table_id();
-            self.drop_table_procedure(&table_name, table_id, drop_if_exists)
+            self.drop_table_by_id(&table_name, table_id, drop_if_exists, dependents)
                 .await?;
 
-            // Invalidates local cache ASAP.
-            self.cache_invalidator
-                .invalidate(
-                    &Context::default(),
-                    vec![
-                        CacheIdent::TableId(table_id),
-                        CacheIdent::TableName(table_name.clone()),
-                    ],
-                )
-                .await
-                .context(error::InvalidateTableCacheSnafu)?;
-
-            Ok(Output::new_with_affected_rows(0))
+            Ok(Output::new_with_ddl_result(
+                0,
+                DdlResult::new(DdlStatus::Dropped, None),
+            ))
         } else if drop_if_exists {
             // DROP TABLE IF EXISTS meets table not found - ignored
-            Ok(Output::new_with_affected_rows(0))
+            Ok(Output::new_with_ddl_result(
+                0,
+                DdlResult::new(DdlStatus::NotFound, None),
+            ))
         } else {
             Err(TableNotFoundSnafu {
                 table_name: table_name.to_string(),
@@ -376,6 +506,169 @@ impl StatementExecutor {
         }
     }
 
+    async fn drop_table_by_id(
+        &self,
+        table_name: &TableName,
+        table_id: TableId,
+        drop_if_exists: bool,
+        dependents: DropDependents,
+    ) -> Result<()> {
+        self.drop_table_procedure(table_name, table_id, drop_if_exists, dependents)
+            .await?;
+
+        // Invalidates local cache ASAP.
+        self.cache_invalidator
+            .invalidate(
+                &Context::default(),
+                vec![
+                    CacheIdent::TableId(table_id),
+                    CacheIdent::TableName(table_name.clone()),
+                ],
+            )
+            .await
+            .context(error::InvalidateTableCacheSnafu)
+    }
+
+    /// Drops views depending on the table if `dependents` is
+    /// [Cascade](DropDependents::Cascade), or returns an error if any view depends
+    /// on the table and `dependents` is [Restrict](DropDependents::Restrict).
+    ///
+    /// Only used when the drop table procedure can't drop the views.
+    async fn drop_dependent_views(
+        &self,
+        table_name: &TableName,
+        dependents: DropDependents,
+    ) -> Result<()> {
+        if dependents == DropDependents::Ignore {
+            return Ok(());
+        }
+        let views = self.dependent_views(table_name).await?;
+        if views.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            dependents == DropDependents::Cascade,
+            TableHasDependentsSnafu {
+                table: table_name.to_string(),
+                dependents: views
+                    .iter()
+                    .map(|(view, _)| view.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }
+        );
+
+        for (view_name, view_info) in views {
+            self.drop_view_metadata(&view_name, true).await?;
+            if let Some(sink_table) = &view_info.sink_table {
+                if let Some(table) = self
+                    .catalog_manager
+                    .table(
+                        &sink_table.catalog_name,
+                        &sink_table.schema_name,
+                        &sink_table.table_name,
+                    )
+                    .await
+                    .context(CatalogSnafu)?
+                {
+                    let table_id = table.table_info().table_id();
+                    self.drop_table_by_id(sink_table, table_id, true, DropDependents::Ignore)
+                        .await?;
+                }
+            }
+            info!("Dropped view {view_name} depending on table {table_name}");
+        }
+
+        Ok(())
+    }
+
+    /// Returns views reading from the table, including views reading from the sink
+    /// tables of these views if they are materialized.
+    async fn dependent_views(
+        &self,
+        table_name: &TableName,
+    ) -> Result<Vec<(TableName, ViewInfoValue)>> {
+        let mut dependents: Vec<(TableName, ViewInfoValue)> = vec![];
+        let mut pending = vec![table_name.clone()];
+        while let Some(table) = pending.pop() {
+            let Some(source) = self
+                .catalog_manager
+                .table(&table.catalog_name, &table.schema_name, &table.table_name)
+                .await
+                .context(CatalogSnafu)?
+            else {
+                continue;
+            };
+            let views = self
+                .table_metadata_manager
+                .view_info_manager()
+                .dependent_views(source.table_info().table_id(), &table)
+                .await
+                .context(TableMetadataManagerSnafu)?;
+            for (view_name, view_info) in views {
+                if view_name == *table_name || dependents.iter().any(|(name, _)| *name == view_name)
+                {
+                    continue;
+                }
+                if let Some(sink_table) = &view_info.sink_table {
+                    pending.push(sink_table.clone());
+                }
+                dependents.push((view_name, view_info));
+            }
+        }
+
+        Ok(dependents)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn create_view(
+        &self,
+        stmt: CreateView,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, view) = table_idents_to_full_name(&stmt.name, &query_ctx)
+            .map_err(BoxedError::new)
+            .context(error::ExternalSnafu)?;
+        let view_name = TableName::new(catalog, schema, view);
+
+        // Plans the query once to make sure the definition is valid.
+        let view_ctx = QueryContext::with(&view_name.catalog_name, &view_name.schema_name);
+        let plan = self
+            .plan(
+                QueryStatement::Sql(Statement::Query(stmt.query.clone())),
+                view_ctx,
+            )
+            .await?;
+
+        let view_info =
+            ViewInfoValue::new(stmt.query.to_string()).with_source_tables(source_tables(&plan));
+        self.create_view_inner(view_name, view_info, stmt.if_not_exists, stmt.or_replace)
+            .await?;
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn drop_view(&self, view_name: TableName, drop_if_exists: bool) -> Result<Output> {
+        let view_info = self
+            .table_metadata_manager
+            .view_info_manager()
+            .get(ViewInfoKey::from(&view_name))
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(
+            !view_info.is_some_and(|v| v.materialized),
+            error::InvalidMaterializedViewSnafu {
+                view: view_name.to_string(),
+                reason: "use DROP MATERIALIZED VIEW to drop it",
+            }
+        );
+
+        self.drop_view_inner(view_name, drop_if_exists).await?;
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn drop_database(
         &self,
@@ -392,10 +685,16 @@ impl StatementExecutor {
             self.drop_database_procedure(catalog, schema, drop_if_exists)
                 .await?;
 
-            Ok(Output::new_with_affected_rows(0))
+            Ok(Output::new_with_ddl_result(
+                0,
+                DdlResult::new(DdlStatus::Dropped, None),
+            ))
         } else if drop_if_exists {
             // DROP TABLE IF EXISTS meets table not found - ignored
-            Ok(Output::new_with_affected_rows(0))
+            Ok(Output::new_with_ddl_result(
+                0,
+                DdlResult::new(DdlStatus::NotFound, None),
+            ))
         } else {
             Err(SchemaNotFoundSnafu {
                 schema_info: schema,
@@ -420,6 +719,9 @@ impl StatementExecutor {
             })?;
         let table_id = table.table_info().table_id();
         self.truncate_table_procedure(&table_name, table_id).await?;
+        if let Some(query_cache) = &self.query_cache {
+            query_cache.invalidate_tables([table_id]);
+        }
 
         Ok(Output::new_with_affected_rows(0))
     }
@@ -496,6 +798,12 @@ impl StatementExecutor {
         let table_id = table.table_info().ident.table_id;
         self.verify_alter(table_id, table.table_info(), expr.clone())?;
 
+        let old_name = TableName::new(&catalog_name, &schema_name, &table_name);
+        let new_table_name = match &expr.kind {
+            Some(Kind::RenameTable(rename)) => Some(rename.new_table_name.clone()),
+            _ => None,
+        };
+
         info!(
             "Table info before alter is {:?}, expr: {:?}",
             table.table_info(),
@@ -563,7 +871,68 @@ impl StatementExecutor {
             .await
             .context(error::InvalidateTableCacheSnafu)?;
 
-        Ok(Output::new_with_affected_rows(0))
+        if let Some(new_table_name) = new_table_name {
+            let new_name = TableName::new(
+                &old_name.catalog_name,
+                &old_name.schema_name,
+                new_table_name,
+            );
+            self.rebind_dependent_views(table_id, &old_name, new_name)
+                .await?;
+        }
+
+        let version = self
+            .table_metadata_manager
+            .table_info_manager()
+            .get(table_id)
+            .await
+            .context(TableMetadataManagerSnafu)?
+            .map(|info| info.table_info.ident.version);
+        Ok(Output::new_with_ddl_result(
+            0,
+            DdlResult::new(DdlStatus::Altered, version),
+        ))
+    }
+
+    /// Rebinds views reading from the table `table_id` after the table is renamed
+    /// from `old_name` to `new_name`, by renaming the table in their definitions.
+    async fn rebind_dependent_views(
+        &self,
+        table_id: TableId,
+        old_name: &TableName,
+        new_name: TableName,
+    ) -> Result<()> {
+        let views = self
+            .table_metadata_manager
+            .view_info_manager()
+            .dependent_views(table_id, old_name)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        for (view_name, mut view_info) in views {
+            view_info.definition =
+                rename_table_in_definition(&view_name, &view_info.definition, old_name, &new_name)
+                    .map_err(|e| {
+                        error::RebindViewSnafu {
+                            table: new_name.to_string(),
+                            view: view_name.to_string(),
+                            reason: e.output_msg(),
+                        }
+                        .build()
+                    })?;
+            view_info.rename_source_table(table_id, new_name.clone());
+
+            let _ = self
+                .table_metadata_manager
+                .view_info_manager()
+                .create(ViewInfoKey::from(&view_name), &view_info, true)
+                .await
+                .context(TableMetadataManagerSnafu)?;
+            self.invalidate_view_cache(&view_name).await?;
+            info!("Rebound view {view_name} from table {old_name} to {new_name}");
+        }
+
+        Ok(())
     }
 
     async fn create_table_procedure(
@@ -617,6 +986,7 @@ impl StatementExecutor {
         table_name: &TableName,
         table_id: TableId,
         drop_if_exists: bool,
+        dependents: DropDependents,
     ) -> Result<SubmitDdlTaskResponse> {
         let request = SubmitDdlTaskRequest {
             task: DdlTask::new_drop_table(
@@ -625,6 +995,7 @@ impl StatementExecutor {
                 table_name.table_name.to_string(),
                 table_id,
                 drop_if_exists,
+                dependents,
             ),
         };
 
@@ -650,6 +1021,120 @@ impl StatementExecutor {
             .context(error::ExecuteDdlSnafu)
     }
 
+    /// Creates the view by the procedure if the executor runs view tasks, otherwise
+    /// writes the view metadata from the frontend.
+    pub(crate) async fn create_view_inner(
+        &self,
+        view_name: TableName,
+        view_info: ViewInfoValue,
+        create_if_not_exists: bool,
+        or_replace: bool,
+    ) -> Result<()> {
+        if self.procedure_executor.supports_view_tasks() {
+            let _ = self
+                .create_view_procedure(view_name, view_info, create_if_not_exists, or_replace)
+                .await?;
+            return Ok(());
+        }
+
+        ensure!(
+            !self
+                .catalog_manager
+                .table_exists(
+                    &view_name.catalog_name,
+                    &view_name.schema_name,
+                    &view_name.table_name,
+                )
+                .await
+                .context(CatalogSnafu)?,
+            TableAlreadyExistsSnafu {
+                table: view_name.to_string(),
+            }
+        );
+        let created = self
+            .table_metadata_manager
+            .view_info_manager()
+            .create(ViewInfoKey::from(&view_name), &view_info, or_replace)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        if !created {
+            ensure!(
+                create_if_not_exists,
+                ViewAlreadyExistsSnafu {
+                    view: view_name.to_string(),
+                }
+            );
+            return Ok(());
+        }
+        self.invalidate_view_cache(&view_name).await
+    }
+
+    /// Drops the view by the procedure if the executor runs view tasks, otherwise
+    /// deletes the view metadata from the frontend.
+    pub(crate) async fn drop_view_inner(
+        &self,
+        view_name: TableName,
+        drop_if_exists: bool,
+    ) -> Result<()> {
+        if self.procedure_executor.supports_view_tasks() {
+            let _ = self.drop_view_procedure(view_name, drop_if_exists).await?;
+            return Ok(());
+        }
+        self.drop_view_metadata(&view_name, drop_if_exists).await
+    }
+
+    async fn drop_view_metadata(&self, view_name: &TableName, drop_if_exists: bool) -> Result<()> {
+        let deleted = self
+            .table_metadata_manager
+            .view_info_manager()
+            .delete(ViewInfoKey::from(view_name))
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        if !deleted {
+            // DROP VIEW IF EXISTS meets view not found - ignored
+            ensure!(
+                drop_if_exists,
+                ViewNotFoundSnafu {
+                    view: view_name.to_string(),
+                }
+            );
+            return Ok(());
+        }
+        self.invalidate_view_cache(view_name).await
+    }
+
+    async fn create_view_procedure(
+        &self,
+        view_name: TableName,
+        view_info: ViewInfoValue,
+        create_if_not_exists: bool,
+        or_replace: bool,
+    ) -> Result<SubmitDdlTaskResponse> {
+        let request = SubmitDdlTaskRequest {
+            task: DdlTask::new_create_view(view_name, view_info, create_if_not_exists, or_replace),
+        };
+
+        self.procedure_executor
+            .submit_ddl_task(&ExecutorContext::default(), request)
+            .await
+            .context(error::ExecuteDdlSnafu)
+    }
+
+    async fn drop_view_procedure(
+        &self,
+        view_name: TableName,
+        drop_if_exists: bool,
+    ) -> Result<SubmitDdlTaskResponse> {
+        let request = SubmitDdlTaskRequest {
+            task: DdlTask::new_drop_view(view_name, drop_if_exists),
+        };
+
+        self.procedure_executor
+            .submit_ddl_task(&ExecutorContext::default(), request)
+            .await
+            .context(error::ExecuteDdlSnafu)
+    }
+
     async fn truncate_table_procedure(
         &self,
         table_name: &TableName,
@@ -676,6 +1161,7 @@ impl StatementExecutor {
         catalog: &str,
         database: &str,
         create_if_not_exists: bool,
+        options: HashMap<String, String>,
     ) -> Result<Output> {
         ensure!(
             NAME_PATTERN_REG.is_match(catalog),
@@ -697,29 +1183,61 @@ impl StatementExecutor {
             .await
             .context(CatalogSnafu)?
         {
+            // Validates the options before submitting the procedure.
+            let _ = SchemaNameValue::try_from(&options).context(TableMetadataManagerSnafu)?;
             self.create_database_procedure(
                 catalog.to_string(),
                 database.to_string(),
                 create_if_not_exists,
+                options,
             )
             .await?;
 
-            Ok(Output::new_with_affected_rows(1))
+            Ok(Output::new_with_ddl_result(
+                1,
+                DdlResult::new(DdlStatus::Created, None),
+            ))
         } else if create_if_not_exists {
-            Ok(Output::new_with_affected_rows(1))
+            Ok(Output::new_with_ddl_result(
+                1,
+                DdlResult::new(DdlStatus::AlreadyExists, None),
+            ))
         } else {
             error::SchemaExistsSnafu { name: database }.fail()
         }
     }
 
+    /// Returns the options of the schema, or the default options if the schema
+    /// doesn't have any.
+    pub(crate) async fn schema_options(
+        &self,
+        catalog: &str,
+        schema: &str,
+    ) -> Result<SchemaNameValue> {
+        let value = self
+            .table_metadata_manager
+            .schema_manager()
+            .get(SchemaNameKey::new(catalog, schema))
+            .await
+            .context(TableMetadataManagerSnafu)?
+            .unwrap_or_default();
+        Ok(value)
+    }
+
     async fn create_database_procedure(
         &self,
         catalog: String,
         database: String,
         create_if_not_exists: bool,
+        options: HashMap<String, String>,
     ) -> Result<SubmitDdlTaskResponse> {
         let request = SubmitDdlTaskRequest {
-            task: DdlTask::new_create_database(catalog, database, create_if_not_exists, None),
+            task: DdlTask::new_create_database(
+                catalog,
+                database,
+                create_if_not_exists,
+                Some(options),
+            ),
         };
 
         self.procedure_executor
@@ -729,6 +1247,72 @@ impl StatementExecutor {
     }
 }
 
+/// Returns the output of creating the `table` with the `status` of the DDL.
+pub fn create_table_output(table: &TableRef, status: DdlStatus) -> Output {
+    Output::new_with_ddl_result(
+        0,
+        DdlResult::new(status, Some(table.table_info().ident.version)),
+    )
+}
+
+/// Compares the columns of `create_table` with an existing table, returns a description of each
+/// mismatched column. Returns an empty vec if they have the same schema.
+fn find_schema_mismatches(
+    create_table: &CreateTableExpr,
+    table_info: &TableInfoRef,
+) -> Result<Vec<String>> {
+    let schema = &table_info.meta.schema;
+    let mut mismatches = Vec::new();
+
+    for column in &create_table.column_defs {
+        let Some(column_schema) = schema.column_schema_by_name(&column.name) else {
+            mismatches.push(format!("column `{}` does not exist", column.name));
+            continue;
+        };
+        let data_type = ConcreteDataType::from(
+            ColumnDataTypeWrapper::try_new(column.data_type, column.datatype_extension.clone())
+                .context(ColumnDataTypeSnafu)?,
+        );
+        if data_type != column_schema.data_type {
+            mismatches.push(format!(
+                "column `{}` has type {}, expected {}",
+                column.name, column_schema.data_type, data_type
+            ));
+        }
+    }
+    for column_schema in schema.column_schemas() {
+        if !create_table
+            .column_defs
+            .iter()
+            .any(|c| c.name == column_schema.name)
+        {
+            mismatches.push(format!("column `{}` is not expected", column_schema.name));
+        }
+    }
+
+    let time_index = schema.timestamp_column().map(|c| c.name.as_str());
+    if time_index != Some(create_table.time_index.as_str()) {
+        mismatches.push(format!(
+            "time index is {:?}, expected `{}`",
+            time_index, create_table.time_index
+        ));
+    }
+    let primary_keys = table_info
+        .meta
+        .primary_key_indices
+        .iter()
+        .map(|i| schema.column_name_by_index(*i))
+        .collect::<Vec<_>>();
+    if primary_keys != create_table.primary_keys {
+        mismatches.push(format!(
+            "primary keys are {:?}, expected {:?}",
+            primary_keys, create_table.primary_keys
+        ));
+    }
+
+    Ok(mismatches)
+}
+
 fn validate_partition_columns(
     create_table: &CreateTableExpr,
     partition_cols: &[String],
@@ -992,12 +1576,58 @@ fn merge_options(mut table_opts: TableOptions, schema_opts: SchemaNameValue) ->
     table_opts
 }
 
+/// Returns names of the tables the `plan` reads from.
+pub(crate) fn source_tables(plan: &LogicalPlan) -> Vec<(TableId, TableName)> {
+    let LogicalPlan::DfPlan(plan) = plan;
+    scanned_tables(plan)
+        .iter()
+        .map(|table| {
+            let info = table.table_info();
+            (
+                info.table_id(),
+                TableName::new(&info.catalog_name, &info.schema_name, &info.name),
+            )
+        })
+        .collect()
+}
+
+/// Replaces references to `old_name` in the definition of the view `view_name`
+/// with `new_name`.
+fn rename_table_in_definition(
+    view_name: &TableName,
+    definition: &str,
+    old_name: &TableName,
+    new_name: &TableName,
+) -> Result<String> {
+    let mut stmts = ParserContext::create_with_dialect(
+        definition,
+        &GreptimeDbDialect {},
+        ParseOptions::default(),
+    )
+    .context(error::ParseSqlSnafu)?;
+    let Some(Statement::Query(mut query)) = stmts.pop() else {
+        return error::UnexpectedSnafu {
+            violated: format!("the definition of view {view_name} is not a query"),
+        }
+        .fail();
+    };
+
+    // Table names in the definition are resolved against the schema of the view.
+    let view_ctx = QueryContext::with(&view_name.catalog_name, &view_name.schema_name);
+    let _ = query.rename_tables(|name| {
+        let (catalog, schema, table) = table_idents_to_full_name(name, &view_ctx).ok()?;
+        (catalog == old_name.catalog_name
+            && schema == old_name.schema_name
+            && table == old_name.table_name)
+            .then(|| new_name.table_name.clone())
+    });
+
+    Ok(query.to_string())
+}
+
 #[cfg(test)]
 mod test {
-    use session::context::{QueryContext, QueryContextBuilder};
-    use sql::dialect::GreptimeDbDialect;
-    use sql::parser::{ParseOptions, ParserContext};
-    use sql::statements::statement::Statement;
+    use session::context::QueryContextBuilder;
 
     use super::*;
     use crate::expr_factory;
@@ -1034,6 +1664,51 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_find_schema_mismatches() {
+        let to_expr = |sql| {
+            let result = ParserContext::create_with_dialect(
+                sql,
+                &GreptimeDbDialect {},
+                ParseOptions::default(),
+            )
+            .unwrap();
+            match &result[0] {
+                Statement::CreateTable(c) => {
+                    expr_factory::create_to_expr(c, QueryContext::arc()).unwrap()
+                }
+                _ => unreachable!(),
+            }
+        };
+        let existing = to_expr(
+            "CREATE TABLE monitor (host STRING, cpu DOUBLE, ts TIMESTAMP TIME INDEX, PRIMARY KEY (host))",
+        );
+        let table_info: TableInfo = create_table_info(&existing, vec![], Default::default())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let table_info = Arc::new(table_info);
+
+        let same = to_expr(
+            "CREATE TABLE IF NOT EXISTS monitor (host STRING, cpu DOUBLE, ts TIMESTAMP TIME INDEX, PRIMARY KEY (host))",
+        );
+        assert!(find_schema_mismatches(&same, &table_info)
+            .unwrap()
+            .is_empty());
+
+        let different = to_expr(
+            "CREATE TABLE IF NOT EXISTS monitor (host STRING, cpu BIGINT, memory DOUBLE, ts TIMESTAMP TIME INDEX)",
+        );
+        assert_eq!(
+            find_schema_mismatches(&different, &table_info).unwrap(),
+            vec![
+                "column `cpu` has type Float64, expected Int64".to_string(),
+                "column `memory` does not exist".to_string(),
+                "primary keys are [\"host\"], expected []".to_string(),
+            ]
+        );
+    }
+
     #[tokio::test]
     #[ignore = "TODO(ruihang): WIP new partition rule"]
     async fn test_parse_partitions() {
@@ -1083,4 +1758,28 @@ ENGINE=mito",
             }
         }
     }
+
+    #[test]
+    fn test_rename_table_in_definition() {
+        let view_name = TableName::new("greptime", "public", "v");
+        let old_name = TableName::new("greptime", "public", "t");
+        let new_name = TableName::new("greptime", "public", "T2");
+
+        let definition = rename_table_in_definition(
+            &view_name,
+            "SELECT * FROM t JOIN public.t AS a ON t.x = a.x JOIN other.t ON true",
+            &old_name,
+            &new_name,
+        )
+        .unwrap();
+        // Tables in other schemas are kept.
+        assert_eq!(
+            "SELECT * FROM \"T2\" JOIN public.\"T2\" AS a ON \"T2\".x = a.x JOIN other.t ON true",
+            definition
+        );
+
+        let err = rename_table_in_definition(&view_name, "DELETE FROM t", &old_name, &new_name)
+            .unwrap_err();
+        assert!(matches!(err, error::Error::Unexpected { .. }));
+    }
 }
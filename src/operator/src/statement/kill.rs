@@ -0,0 +1,75 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use catalog::kvbackend::KvBackendCatalogManager;
+use catalog::process_manager::ProcessScope;
+use common_query::Output;
+use common_telemetry::tracing;
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::statements::kill::KillQuery;
+
+use crate::error::{
+    CatalogSnafu, NotSupportedSnafu, ProcessNotFoundSnafu, Result, TableMetadataManagerSnafu,
+};
+use crate::statement::StatementExecutor;
+
+impl StatementExecutor {
+    #[tracing::instrument(skip_all)]
+    pub(super) async fn kill_query(
+        &self,
+        stmt: KillQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let process_manager = self
+            .catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+            .and_then(|catalog_manager| catalog_manager.process_manager())
+            .context(NotSupportedSnafu {
+                feat: "KILL QUERY on this node",
+            })?;
+
+        let scope = self.process_scope(&query_ctx).await?;
+        let killed = process_manager
+            .kill(query_ctx.current_catalog(), stmt.id, &scope)
+            .await
+            .context(CatalogSnafu)?;
+        // Queries of other users are invisible to non-admins, so they are not found either.
+        ensure!(killed, ProcessNotFoundSnafu { id: stmt.id });
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    /// Returns the queries the current user may see and kill, which are all queries
+    /// for admins and the queries of the user for others.
+    async fn process_scope(&self, query_ctx: &QueryContextRef) -> Result<ProcessScope> {
+        let Some(user) = query_ctx.current_user() else {
+            return Ok(ProcessScope::All);
+        };
+        let username = user.username();
+        let is_admin = self
+            .table_metadata_manager
+            .privilege_manager()
+            .is_admin(username)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        Ok(if is_admin {
+            ProcessScope::All
+        } else {
+            ProcessScope::User(Some(username.to_string()))
+        })
+    }
+}
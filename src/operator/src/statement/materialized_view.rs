@@ -0,0 +1,497 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::helper::ColumnDataTypeWrapper;
+use api::v1::{ColumnSchema as PbColumnSchema, CreateTableExpr, SemanticType};
+use chrono::Utc;
+use common_catalog::consts::default_engine;
+use common_error::ext::BoxedError;
+use common_grpc_expr::util::{build_create_table_expr, ColumnExpr};
+use common_meta::cache_invalidator::Context;
+use common_meta::instruction::CacheIdent;
+use common_meta::key::view_info::{ViewInfoKey, ViewInfoValue};
+use common_meta::rpc::ddl::DropDependents;
+use common_meta::table_name::TableName;
+use common_query::Output;
+use common_telemetry::{info, tracing, warn};
+use datatypes::schema::Schema;
+use futures::TryStreamExt;
+use query::parser::QueryStatement;
+use session::context::{QueryContext, QueryContextRef};
+use session::table_name::table_idents_to_full_name;
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::dialect::GreptimeDbDialect;
+use sql::parser::{ParseOptions, ParserContext};
+use sql::statements::create::CreateMaterializedView;
+use sql::statements::query::Query;
+use sql::statements::statement::Statement;
+use sqlparser::ast::{Ident, ObjectName};
+use store_api::mito_engine_options::APPEND_MODE_KEY;
+use table::metadata::TableId;
+use table::table_reference::TableReference;
+
+use super::StatementExecutor;
+use crate::error::{
+    self, BuildCreateExprOnInsertionSnafu, CatalogSnafu, ColumnDataTypeSnafu,
+    InvalidMaterializedViewSnafu, ParseSqlSnafu, PlanStatementSnafu, Result,
+    TableAlreadyExistsSnafu, TableMetadataManagerSnafu, ViewAlreadyExistsSnafu, ViewNotFoundSnafu,
+};
+use crate::statement::ddl::source_tables;
+
+/// Query context extension to opt in to rewriting queries to read materialized views.
+/// The value is the max staleness, e.g. `5m`, of the results a query may read instead
+/// of computing them.
+pub const MATERIALIZED_VIEW_MAX_STALENESS_EXTENSION: &str = "materialized_view_max_staleness";
+
+/// Materialized views store the results of their queries in sink tables, reading
+/// a materialized view is reading its sink table.
+///
+/// Every string column in the results is a tag and the first timestamp column is
+/// the time index of the sink table, the results must have a timestamp column.
+/// Sink tables are in append mode so rows with the same tags and time are kept.
+///
+/// Results are snapshots computed on CREATE and REFRESH, nothing keeps them fresh
+/// between refreshes. Queries are only rewritten to read a materialized view if
+/// their text is the definition of the view, see [MATERIALIZED_VIEW_MAX_STALENESS_EXTENSION].
+impl StatementExecutor {
+    #[tracing::instrument(skip_all)]
+    pub async fn create_materialized_view(
+        &self,
+        stmt: CreateMaterializedView,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, view) = table_idents_to_full_name(&stmt.name, &query_ctx)
+            .map_err(BoxedError::new)
+            .context(error::ExternalSnafu)?;
+        let view_name = TableName::new(catalog, schema, view);
+
+        if self.view_info(&view_name).await?.is_some() {
+            ensure!(
+                stmt.if_not_exists,
+                ViewAlreadyExistsSnafu {
+                    view: view_name.to_string(),
+                }
+            );
+            return Ok(Output::new_with_affected_rows(0));
+        }
+        ensure!(
+            !self
+                .catalog_manager
+                .table_exists(
+                    &view_name.catalog_name,
+                    &view_name.schema_name,
+                    &view_name.table_name,
+                )
+                .await
+                .context(CatalogSnafu)?,
+            TableAlreadyExistsSnafu {
+                table: view_name.to_string(),
+            }
+        );
+
+        let definition = stmt.query.to_string();
+        let (sink_table, source_tables) = self.create_sink_table(&view_name, &stmt.query).await?;
+        let view_info = ViewInfoValue::new_materialized(definition, sink_table.clone())
+            .with_source_tables(source_tables);
+        let output = match self
+            .bind_sink_table(&view_name, &sink_table, view_info, false)
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                self.drop_sink_table(&sink_table).await;
+                return Err(e);
+            }
+        };
+        info!("Created materialized view {view_name} in sink table {sink_table}");
+
+        Ok(output)
+    }
+
+    /// Recomputes the results of the materialized view into a new sink table, then
+    /// switches the view to the new sink table and drops the old one, so readers
+    /// always observe complete results.
+    #[tracing::instrument(skip_all)]
+    pub async fn refresh_materialized_view(&self, view_name: TableName) -> Result<Output> {
+        let (view_info, old_sink_table) = self.materialized_view_info(&view_name).await?;
+
+        let query = parse_definition(&view_name, &view_info.definition)?;
+        let (sink_table, source_tables) = self.create_sink_table(&view_name, &query).await?;
+        let new_view_info =
+            ViewInfoValue::new_materialized(view_info.definition.clone(), sink_table.clone())
+                .with_source_tables(source_tables);
+        let new_view_info = ViewInfoValue {
+            created_on: view_info.created_on,
+            ..new_view_info
+        };
+        let output = match self
+            .bind_sink_table(&view_name, &sink_table, new_view_info, true)
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                self.drop_sink_table(&sink_table).await;
+                return Err(e);
+            }
+        };
+        let _ = self
+            .drop_table_inner(old_sink_table, true, DropDependents::Ignore)
+            .await?;
+        info!("Refreshed materialized view {view_name} in sink table {sink_table}");
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn drop_materialized_view(
+        &self,
+        view_name: TableName,
+        drop_if_exists: bool,
+    ) -> Result<Output> {
+        if self.view_info(&view_name).await?.is_none() {
+            // DROP MATERIALIZED VIEW IF EXISTS meets view not found - ignored
+            ensure!(
+                drop_if_exists,
+                ViewNotFoundSnafu {
+                    view: view_name.to_string(),
+                }
+            );
+            return Ok(Output::new_with_affected_rows(0));
+        }
+        let (_, sink_table) = self.materialized_view_info(&view_name).await?;
+        // Views reading from the materialized view read from its sink table, which
+        // is dropped first so the view is left if any of them exists.
+        let output = self
+            .drop_table_inner(sink_table, true, DropDependents::Restrict)
+            .await?;
+        self.drop_view_inner(view_name, false).await?;
+
+        Ok(output)
+    }
+
+    /// Rewrites the query to read a materialized view in the current schema if the
+    /// query is the definition of the view.
+    ///
+    /// Queries are only rewritten if the session opts in with
+    /// [MATERIALIZED_VIEW_MAX_STALENESS_EXTENSION] and the results of the view are
+    /// computed within the max staleness.
+    pub(crate) async fn rewrite_by_materialized_view(
+        &self,
+        query: &Query,
+        query_ctx: &QueryContextRef,
+    ) -> Result<Option<Statement>> {
+        let Some(max_staleness) = query_ctx.extension(MATERIALIZED_VIEW_MAX_STALENESS_EXTENSION)
+        else {
+            return Ok(None);
+        };
+        let max_staleness = humantime::parse_duration(max_staleness)
+            .ok()
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+            .with_context(|| error::InvalidSqlSnafu {
+                err_msg: format!(
+                    "invalid {MATERIALIZED_VIEW_MAX_STALENESS_EXTENSION}: {max_staleness}"
+                ),
+            })?;
+        let catalog = query_ctx.current_catalog();
+        let schema = query_ctx.current_schema();
+        let definition = query.to_string();
+        let now = Utc::now();
+
+        let view = self
+            .table_metadata_manager
+            .view_info_manager()
+            .views(catalog, schema)
+            .try_filter(|(_, value)| {
+                let fresh = value
+                    .refreshed_on
+                    .is_some_and(|refreshed_on| now - refreshed_on <= max_staleness);
+                futures::future::ready(
+                    value.materialized && value.definition == definition && fresh,
+                )
+            })
+            .try_next()
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        let Some((view, _)) = view else {
+            return Ok(None);
+        };
+
+        let view_name = TableName::new(catalog, schema, view);
+        let sql = format!("SELECT * FROM {}", quoted_object_name(&view_name));
+        let mut stmts = ParserContext::create_with_dialect(
+            &sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default(),
+        )
+        .context(ParseSqlSnafu)?;
+        Ok(stmts.pop())
+    }
+
+    /// Creates an empty sink table for the results of the materialized view `view_name`
+    /// defined by `query`, returns the sink table and the tables the query reads from.
+    async fn create_sink_table(
+        &self,
+        view_name: &TableName,
+        query: &Query,
+    ) -> Result<(TableName, Vec<(TableId, TableName)>)> {
+        let view_ctx = QueryContext::with(&view_name.catalog_name, &view_name.schema_name);
+        let plan = self
+            .plan(
+                QueryStatement::Sql(Statement::Query(Box::new(query.clone()))),
+                view_ctx.clone(),
+            )
+            .await?;
+        let output_schema = plan.schema().context(PlanStatementSnafu)?;
+
+        let sink_table = TableName::new(
+            &view_name.catalog_name,
+            &view_name.schema_name,
+            format!(
+                "__mv_{}__{}",
+                view_name.table_name,
+                Utc::now().timestamp_millis()
+            ),
+        );
+        let mut create_table_expr = build_sink_table_expr(view_name, &sink_table, &output_schema)?;
+        let _ = self
+            .create_table_inner(&mut create_table_expr, None, &view_ctx)
+            .await?;
+
+        Ok((sink_table, source_tables(&plan)))
+    }
+
+    /// Computes the results of the materialized view into its sink table, then points
+    /// the view to the sink table.
+    async fn bind_sink_table(
+        &self,
+        view_name: &TableName,
+        sink_table: &TableName,
+        view_info: ViewInfoValue,
+        or_replace: bool,
+    ) -> Result<Output> {
+        let output = self
+            .fill_sink_table(view_name, sink_table, &view_info.definition)
+            .await?;
+        self.create_view_inner(view_name.clone(), view_info, false, or_replace)
+            .await?;
+        Ok(output)
+    }
+
+    /// Computes the results of the materialized view into its sink table.
+    async fn fill_sink_table(
+        &self,
+        view_name: &TableName,
+        sink_table: &TableName,
+        definition: &str,
+    ) -> Result<Output> {
+        let sql = format!(
+            "INSERT INTO {} {}",
+            quoted_object_name(sink_table),
+            definition
+        );
+        let mut stmts = ParserContext::create_with_dialect(
+            &sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default(),
+        )
+        .context(ParseSqlSnafu)?;
+        let Some(Statement::Insert(insert)) = stmts.pop() else {
+            return InvalidMaterializedViewSnafu {
+                view: view_name.to_string(),
+                reason: "the definition is not a query",
+            }
+            .fail();
+        };
+
+        let view_ctx = QueryContext::with(&view_name.catalog_name, &view_name.schema_name);
+        self.insert(insert, view_ctx).await
+    }
+
+    /// Drops the sink table of a materialized view that fails to be created or refreshed.
+    async fn drop_sink_table(&self, sink_table: &TableName) {
+        if let Err(e) = self
+            .drop_table_inner(sink_table.clone(), true, DropDependents::Ignore)
+            .await
+        {
+            warn!(e; "Failed to drop sink table {sink_table}");
+        }
+    }
+
+    async fn view_info(&self, view_name: &TableName) -> Result<Option<ViewInfoValue>> {
+        self.table_metadata_manager
+            .view_info_manager()
+            .get(ViewInfoKey::from(view_name))
+            .await
+            .context(TableMetadataManagerSnafu)
+    }
+
+    /// Returns the info and the sink table of the materialized view.
+    async fn materialized_view_info(
+        &self,
+        view_name: &TableName,
+    ) -> Result<(ViewInfoValue, TableName)> {
+        let view_info = self
+            .view_info(view_name)
+            .await?
+            .with_context(|| ViewNotFoundSnafu {
+                view: view_name.to_string(),
+            })?;
+        let sink_table =
+            view_info
+                .sink_table
+                .clone()
+                .with_context(|| InvalidMaterializedViewSnafu {
+                    view: view_name.to_string(),
+                    reason: "it's not a materialized view",
+                })?;
+        Ok((view_info, sink_table))
+    }
+
+    pub(crate) async fn invalidate_view_cache(&self, view_name: &TableName) -> Result<()> {
+        // Invalidates local cache ASAP.
+        self.cache_invalidator
+            .invalidate(
+                &Context::default(),
+                vec![CacheIdent::ViewName(view_name.clone())],
+            )
+            .await
+            .context(error::InvalidateTableCacheSnafu)
+    }
+}
+
+/// Parses the definition of the view `view_name`.
+pub(crate) fn parse_definition(view_name: &TableName, definition: &str) -> Result<Query> {
+    let mut stmts = ParserContext::create_with_dialect(
+        definition,
+        &GreptimeDbDialect {},
+        ParseOptions::default(),
+    )
+    .context(ParseSqlSnafu)?;
+    match stmts.pop() {
+        Some(Statement::Query(query)) if stmts.is_empty() => Ok(*query),
+        _ => InvalidMaterializedViewSnafu {
+            view: view_name.to_string(),
+            reason: "the definition is not a query",
+        }
+        .fail(),
+    }
+}
+
+fn quoted_object_name(name: &TableName) -> ObjectName {
+    ObjectName(vec![
+        Ident::with_quote('`', &name.catalog_name),
+        Ident::with_quote('`', &name.schema_name),
+        Ident::with_quote('`', &name.table_name),
+    ])
+}
+
+/// Builds the expr to create the sink table `sink_table` of the materialized view
+/// `view_name` whose query outputs `schema`.
+fn build_sink_table_expr(
+    view_name: &TableName,
+    sink_table: &TableName,
+    schema: &Schema,
+) -> Result<CreateTableExpr> {
+    let mut has_time_index = false;
+    let column_schemas = schema
+        .column_schemas()
+        .iter()
+        .map(|column_schema| {
+            let (datatype, datatype_extension) =
+                ColumnDataTypeWrapper::try_from(column_schema.data_type.clone())
+                    .context(ColumnDataTypeSnafu)?
+                    .to_parts();
+            let semantic_type = if column_schema.data_type.is_timestamp() && !has_time_index {
+                has_time_index = true;
+                SemanticType::Timestamp
+            } else if column_schema.data_type.is_string() {
+                SemanticType::Tag
+            } else {
+                SemanticType::Field
+            };
+            Ok(PbColumnSchema {
+                column_name: column_schema.name.clone(),
+                datatype: datatype.into(),
+                semantic_type: semantic_type.into(),
+                datatype_extension,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        has_time_index,
+        InvalidMaterializedViewSnafu {
+            view: view_name.to_string(),
+            reason: "the query must output a timestamp column as the time index",
+        }
+    );
+
+    let table_ref = TableReference::full(
+        &sink_table.catalog_name,
+        &sink_table.schema_name,
+        &sink_table.table_name,
+    );
+    let mut expr = build_create_table_expr(
+        None,
+        &table_ref,
+        ColumnExpr::from_column_schemas(&column_schemas),
+        default_engine(),
+        &format!("Created for materialized view {view_name}"),
+    )
+    .context(BuildCreateExprOnInsertionSnafu)?;
+    // The results may have rows with the same tags and time index.
+    let _ = expr
+        .table_options
+        .insert(APPEND_MODE_KEY.to_string(), "true".to_string());
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+
+    use super::*;
+
+    #[test]
+    fn test_build_sink_table_expr() {
+        let view_name = TableName::new("greptime", "public", "mv");
+        let sink_table = TableName::new("greptime", "public", "__mv_mv__1");
+        let schema = Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new(
+                "ts2",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new("max_cpu", ConcreteDataType::float64_datatype(), true),
+        ]);
+        let expr = build_sink_table_expr(&view_name, &sink_table, &schema).unwrap();
+        assert_eq!("__mv_mv__1", expr.table_name);
+        assert_eq!("true", expr.table_options[APPEND_MODE_KEY]);
+        assert_eq!("ts", expr.time_index);
+        assert_eq!(vec!["host".to_string()], expr.primary_keys);
+        assert_eq!(4, expr.column_defs.len());
+
+        let schema = Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("max_cpu", ConcreteDataType::float64_datatype(), true),
+        ]);
+        assert!(build_sink_table_expr(&view_name, &sink_table, &schema).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use common_error::ext::BoxedError;
+use common_meta::key::privilege::{
+    GrantObject as MetaGrantObject, GranteeKey, Privilege as MetaPrivilege, PrivilegeGrant,
+    PrivilegeManager, ADMIN_ROLE,
+};
+use common_query::Output;
+use common_telemetry::{info, tracing};
+use session::context::QueryContextRef;
+use session::table_name::table_idents_to_full_name;
+use snafu::{ensure, ResultExt};
+use sql::statements::privilege::{
+    CreateRole, DropRole, Grant, GrantObject, Grantable, Privilege, Revoke,
+};
+
+use super::{idents_to_full_database_name, StatementExecutor};
+use crate::error::{
+    self, DropBuiltinRoleSnafu, LastAdminSnafu, Result, RoleAlreadyExistsSnafu, RoleNotFoundSnafu,
+    TableMetadataManagerSnafu,
+};
+
+/// Roles and grants are stored in the metadata store, privileges are granted on
+/// catalogs, databases or tables and are checked by the frontend before executing
+/// requests of any protocol.
+///
+/// The built-in [ADMIN_ROLE] can't be dropped, and its last member can't be removed,
+/// so privileges can always be managed once enabled.
+impl StatementExecutor {
+    #[tracing::instrument(skip_all)]
+    pub async fn create_role(&self, stmt: CreateRole) -> Result<Output> {
+        let role = &stmt.name.value;
+        let created = self
+            .privilege_manager()
+            .create_role(role)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(
+            created || stmt.if_not_exists,
+            RoleAlreadyExistsSnafu { role }
+        );
+        if created {
+            info!("Role {role} is created");
+        }
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn drop_role(&self, stmt: DropRole) -> Result<Output> {
+        let role = &stmt.name.value;
+        ensure!(role != ADMIN_ROLE, DropBuiltinRoleSnafu { role });
+        let dropped = self
+            .privilege_manager()
+            .drop_role(role)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(dropped || stmt.if_exists, RoleNotFoundSnafu { role });
+        if dropped {
+            info!("Role {role} is dropped");
+        }
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn grant(&self, stmt: Grant, query_ctx: QueryContextRef) -> Result<Output> {
+        let grantee = &stmt.grantee.value;
+        match stmt.grantable {
+            Grantable::Privileges { privileges, object } => {
+                let grants = to_privilege_grants(&privileges, &object, &query_ctx)?;
+                let key = self.grantee_key(grantee).await?;
+                self.privilege_manager()
+                    .grant(key, grants)
+                    .await
+                    .context(TableMetadataManagerSnafu)?;
+            }
+            Grantable::Role(role) => {
+                self.ensure_role_exists(&role.value).await?;
+                self.privilege_manager()
+                    .grant_role(grantee, &role.value)
+                    .await
+                    .context(TableMetadataManagerSnafu)?;
+            }
+        }
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn revoke(&self, stmt: Revoke, query_ctx: QueryContextRef) -> Result<Output> {
+        let grantee = &stmt.grantee.value;
+        match stmt.grantable {
+            Grantable::Privileges { privileges, object } => {
+                let grants = to_privilege_grants(&privileges, &object, &query_ctx)?;
+                let key = self.grantee_key(grantee).await?;
+                self.privilege_manager()
+                    .revoke(key, &grants)
+                    .await
+                    .context(TableMetadataManagerSnafu)?;
+            }
+            Grantable::Role(role) => {
+                if role.value == ADMIN_ROLE {
+                    self.ensure_not_last_admin(grantee).await?;
+                }
+                self.privilege_manager()
+                    .revoke_role(grantee, &role.value)
+                    .await
+                    .context(TableMetadataManagerSnafu)?;
+            }
+        }
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    fn privilege_manager(&self) -> &PrivilegeManager {
+        self.table_metadata_manager.privilege_manager()
+    }
+
+    /// Returns the key of `grantee`, which is a role if the role exists, or a user otherwise.
+    async fn grantee_key<'a>(&self, grantee: &'a str) -> Result<GranteeKey<'a>> {
+        let is_role = self
+            .privilege_manager()
+            .role_exists(grantee)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        Ok(if is_role {
+            GranteeKey::role(grantee)
+        } else {
+            GranteeKey::user(grantee)
+        })
+    }
+
+    /// Ensures the [ADMIN_ROLE] has members other than `user`.
+    pub(crate) async fn ensure_not_last_admin(&self, user: &str) -> Result<()> {
+        let admins = self
+            .privilege_manager()
+            .admins()
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(
+            !admins.iter().any(|admin| admin == user) || admins.len() > 1,
+            LastAdminSnafu { user }
+        );
+
+        Ok(())
+    }
+
+    async fn ensure_role_exists(&self, role: &str) -> Result<()> {
+        let exists = self
+            .privilege_manager()
+            .role_exists(role)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(exists, RoleNotFoundSnafu { role });
+
+        Ok(())
+    }
+}
+
+/// Converts `privileges` on `object` to grants stored in the metadata, names
+/// of the object are resolved against `query_ctx`.
+fn to_privilege_grants(
+    privileges: &[Privilege],
+    object: &GrantObject,
+    query_ctx: &QueryContextRef,
+) -> Result<Vec<PrivilegeGrant>> {
+    let object = match object {
+        GrantObject::Catalog(catalog) => MetaGrantObject::catalog(&catalog.value),
+        GrantObject::Database(database) => {
+            let (catalog, schema) = idents_to_full_database_name(database, query_ctx)?;
+            MetaGrantObject::schema(catalog, schema)
+        }
+        GrantObject::Table(table) => {
+            let (catalog, schema, table) = table_idents_to_full_name(table, query_ctx)
+                .map_err(BoxedError::new)
+                .context(error::ExternalSnafu)?;
+            MetaGrantObject::table(catalog, schema, table)
+        }
+    };
+
+    Ok(privileges
+        .iter()
+        .map(|privilege| PrivilegeGrant::new(to_meta_privilege(*privilege), object.clone()))
+        .collect())
+}
+
+fn to_meta_privilege(privilege: Privilege) -> MetaPrivilege {
+    match privilege {
+        Privilege::Select => MetaPrivilege::Select,
+        Privilege::Insert => MetaPrivilege::Insert,
+        Privilege::Create => MetaPrivilege::Create,
+        Privilege::Drop => MetaPrivilege::Drop,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use session::context::QueryContext;
+    use sqlparser::ast::{Ident, ObjectName};
+
+    use super::*;
+
+    #[test]
+    fn test_to_privilege_grants() {
+        let query_ctx = QueryContext::with("greptime", "public");
+
+        let grants = to_privilege_grants(
+            &[Privilege::Select, Privilege::Insert],
+            &GrantObject::Table(ObjectName(vec![Ident::new("foo")])),
+            &query_ctx,
+        )
+        .unwrap();
+        let object = MetaGrantObject::table("greptime", "public", "foo");
+        assert_eq!(
+            vec![
+                PrivilegeGrant::new(MetaPrivilege::Select, object.clone()),
+                PrivilegeGrant::new(MetaPrivilege::Insert, object),
+            ],
+            grants
+        );
+
+        let grants = to_privilege_grants(
+            &[Privilege::Drop],
+            &GrantObject::Database(ObjectName(vec![Ident::new("my_db")])),
+            &query_ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![PrivilegeGrant::new(
+                MetaPrivilege::Drop,
+                MetaGrantObject::schema("greptime", "my_db")
+            )],
+            grants
+        );
+
+        let grants = to_privilege_grants(
+            &[Privilege::Create],
+            &GrantObject::Catalog(Ident::new("other")),
+            &query_ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![PrivilegeGrant::new(
+                MetaPrivilege::Create,
+                MetaGrantObject::catalog("other")
+            )],
+            grants
+        );
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "pprof")]
+mod executor {
+    use std::sync::Arc;
+
+    use common_pprof::{ProfileSamples, Profiler};
+    use common_query::{Output, OutputData};
+    use common_recordbatch::RecordBatches;
+    use common_telemetry::tracing;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{BinaryVector, StringVector, VectorRef};
+    use query::dist_plan::RegionProfiles;
+    use query::parser::QueryStatement;
+    use session::context::QueryContextRef;
+    use snafu::ResultExt;
+    use sql::statements::explain::{ExplainProfile, ProfileFormat};
+    use sql::statements::query::Query;
+    use sql::statements::statement::Statement;
+
+    use crate::error::{BuildColumnVectorsSnafu, ProfileSnafu, ReadRecordBatchSnafu, Result};
+    use crate::statement::StatementExecutor;
+
+    /// Sample frequency per second.
+    const PROFILE_FREQUENCY: i32 = 99;
+    /// Name of the column holding the profile.
+    const PROFILE_COLUMN: &str = "profile";
+
+    impl StatementExecutor {
+        /// Runs the query of `EXPLAIN PROFILE` with CPU sampling and returns the
+        /// profile in a single row.
+        ///
+        /// The whole process is sampled while the query runs, so samples of
+        /// concurrent queries on this node are also included. Only one profile
+        /// runs at a time, so the query waits for the running one to finish.
+        ///
+        /// Datanodes sample their processes while they scan regions for the query,
+        /// and their samples are merged into the profile. A datanode that is already
+        /// profiling another query doesn't sample its scans.
+        #[tracing::instrument(skip_all)]
+        pub(crate) async fn explain_profile(
+            &self,
+            stmt: ExplainProfile,
+            query_ctx: QueryContextRef,
+        ) -> Result<Output> {
+            let profiler = Profiler::start(PROFILE_FREQUENCY)
+                .await
+                .context(ProfileSnafu)?;
+            let region_profiles = Arc::new(RegionProfiles::new(PROFILE_FREQUENCY));
+            let _ = query_ctx.set_typed_extension(region_profiles.clone());
+            let result = self.run_profiled_query(stmt.query, query_ctx.clone()).await;
+            let _ = query_ctx.remove_typed_extension::<RegionProfiles>();
+            result?;
+
+            let mut samples = profiler.samples().context(ProfileSnafu)?;
+            samples.merge(ProfileSamples {
+                frequency: PROFILE_FREQUENCY,
+                stacks: region_profiles.take_stacks(),
+            });
+            let (data_type, column): (_, VectorRef) = match stmt.format {
+                ProfileFormat::Flamegraph => {
+                    let svg = samples.dump_flamegraph().context(ProfileSnafu)?;
+                    (
+                        ConcreteDataType::string_datatype(),
+                        Arc::new(StringVector::from(vec![
+                            String::from_utf8_lossy(&svg).into_owned()
+                        ])),
+                    )
+                }
+                ProfileFormat::Pprof => {
+                    let proto = samples.dump_proto().context(ProfileSnafu)?;
+                    (
+                        ConcreteDataType::binary_datatype(),
+                        Arc::new(BinaryVector::from(vec![proto])),
+                    )
+                }
+            };
+            let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+                PROFILE_COLUMN,
+                data_type,
+                false,
+            )]));
+            let records = RecordBatches::try_from_columns(schema, vec![column])
+                .context(BuildColumnVectorsSnafu)?;
+            Ok(Output::new_with_record_batches(records))
+        }
+
+        /// Runs `query` and drains its output, so the whole execution is sampled.
+        async fn run_profiled_query(
+            &self,
+            query: Box<Query>,
+            query_ctx: QueryContextRef,
+        ) -> Result<()> {
+            let output = self
+                .plan_exec(QueryStatement::Sql(Statement::Query(query)), query_ctx)
+                .await?;
+            if let OutputData::Stream(stream) = output.data {
+                let _ = common_recordbatch::util::collect(stream)
+                    .await
+                    .context(ReadRecordBatchSnafu)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(feature = "pprof"))]
+mod executor {
+    use common_query::Output;
+    use session::context::QueryContextRef;
+    use sql::statements::explain::ExplainProfile;
+
+    use crate::error::{NotSupportedSnafu, Result};
+    use crate::statement::StatementExecutor;
+
+    impl StatementExecutor {
+        pub(crate) async fn explain_profile(
+            &self,
+            _stmt: ExplainProfile,
+            _query_ctx: QueryContextRef,
+        ) -> Result<Output> {
+            NotSupportedSnafu {
+                feat: "EXPLAIN PROFILE without the 'pprof' feature",
+            }
+            .fail()
+        }
+    }
+}
@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
+
+use common_base::readable_size::ReadableSize;
 use common_time::Timezone;
 use session::context::QueryContextRef;
-use session::session_config::{PGByteaOutputValue, PGDateOrder, PGDateTimeStyle};
+use session::session_config::{PGByteaOutputValue, PGDateOrder, PGDateTimeStyle, TypeCoercionMode};
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::ast::{Expr, Ident, Value};
 use sql::statements::set_variables::SetVariables;
@@ -67,6 +70,57 @@ pub fn set_bytea_output(exprs: Vec<Expr>, ctx: QueryContextRef) -> Result<()> {
     Ok(())
 }
 
+pub fn set_type_coercion(exprs: Vec<Expr>, ctx: QueryContextRef) -> Result<()> {
+    let Some((var_value, [])) = exprs.split_first() else {
+        return (NotSupportedSnafu {
+            feat: "Set variable value must have one and only one value for type_coercion",
+        })
+        .fail();
+    };
+    let Expr::Value(value) = var_value else {
+        return (NotSupportedSnafu {
+            feat: "Set variable value must be a value",
+        })
+        .fail();
+    };
+    ctx.configuration_parameter().set_type_coercion_mode(
+        TypeCoercionMode::try_from(value.clone()).context(InvalidConfigValueSnafu)?,
+    );
+    Ok(())
+}
+
+/// Sets the memory budget of each query in the session, e.g. `SET query_memory_limit = '1GB'`.
+///
+/// `0` disables the budget of the session and `'default'` restores the default budget. The
+/// query engine still bounds the budget by its max budget.
+pub fn set_query_memory_limit(exprs: Vec<Expr>, ctx: QueryContextRef) -> Result<()> {
+    let Some((var_value, [])) = exprs.split_first() else {
+        return (NotSupportedSnafu {
+            feat: "Set variable value must have one and only one value for query_memory_limit",
+        })
+        .fail();
+    };
+    let limit = match var_value {
+        Expr::Value(Value::Number(n, _)) => n.parse::<u64>().ok(),
+        Expr::Value(Value::SingleQuotedString(s))
+        | Expr::Value(Value::DoubleQuotedString(s))
+        | Expr::Identifier(Ident { value: s, .. }) => {
+            if s.eq_ignore_ascii_case("default") {
+                ctx.configuration_parameter().set_query_memory_limit(None);
+                return Ok(());
+            }
+            ReadableSize::from_str(s).ok().map(|size| size.as_bytes())
+        }
+        _ => None,
+    };
+    let limit = limit.with_context(|| InvalidSqlSnafu {
+        err_msg: format!("invalid query memory limit: {}", var_value),
+    })?;
+    ctx.configuration_parameter()
+        .set_query_memory_limit(Some(limit));
+    Ok(())
+}
+
 pub fn validate_client_encoding(set: SetVariables) -> Result<()> {
     let Some((encoding, [])) = set.value.split_first() else {
         return InvalidSqlSnafu {
@@ -12,19 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+
+use common_meta::key::schema_name::SchemaNameKey;
 use common_meta::table_name::TableName;
 use common_query::Output;
 use common_telemetry::tracing;
+use futures::TryStreamExt;
 use partition::manager::PartitionInfo;
 use partition::partition::PartitionBound;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
-use sql::ast::Ident;
+use snafu::{OptionExt, ResultExt};
+use sql::ast::{Ident, ObjectName};
 use sql::statements::create::Partitions;
-use sql::statements::show::{ShowColumns, ShowDatabases, ShowIndex, ShowTables, ShowVariables};
+use sql::statements::show::{
+    ShowColumns, ShowCreateDatabase, ShowDatabases, ShowIndex, ShowProcesslist, ShowTables,
+    ShowVariables,
+};
+use table::metadata::TableType;
 use table::TableRef;
 
-use crate::error::{self, ExecuteStatementSnafu, Result};
+use crate::error::{
+    self, CatalogSnafu, ExecuteStatementSnafu, Result, SchemaNotFoundSnafu,
+    TableMetadataManagerSnafu,
+};
+use crate::statement::copy_database::is_logical_table;
 use crate::statement::StatementExecutor;
 
 impl StatementExecutor {
@@ -93,12 +105,150 @@ impl StatementExecutor {
             .context(error::ExecuteStatementSnafu)
     }
 
+    #[tracing::instrument(skip_all)]
+    pub(super) async fn show_create_database(
+        &self,
+        stmt: ShowCreateDatabase,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let catalog = query_ctx.current_catalog();
+        let database = stmt.database_name.0[0].value.clone();
+        let options = self
+            .table_metadata_manager
+            .schema_manager()
+            .get(SchemaNameKey::new(catalog, &database))
+            .await
+            .context(TableMetadataManagerSnafu)?
+            .context(SchemaNotFoundSnafu {
+                schema_info: &database,
+            })?
+            .to_options();
+
+        let quote_style = query_ctx.quote_style();
+        let create_database = query::sql::create_database_sql(&database, &options, quote_style);
+        let sql = if stmt.full {
+            self.create_database_script(catalog, &database, create_database, quote_style)
+                .await?
+        } else {
+            create_database
+        };
+
+        query::sql::show_create_database(&database, sql).context(ExecuteStatementSnafu)
+    }
+
+    /// Returns a script of `create_database` followed by statements creating the tables,
+    /// views and materialized views in `catalog.schema`, which can be replayed to recreate
+    /// the database elsewhere. Materialized views are the only continuous queries, i.e.
+    /// flows, a database has.
+    ///
+    /// Users, roles and grants belong to the cluster rather than a database, see
+    /// `SHOW CREATE USERS`.
+    async fn create_database_script(
+        &self,
+        catalog: &str,
+        schema: &str,
+        create_database: String,
+        quote_style: char,
+    ) -> Result<String> {
+        let mut views = self
+            .table_metadata_manager
+            .view_info_manager()
+            .views(catalog, schema)
+            .try_collect::<Vec<_>>()
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        // Views must be created after the views they read from.
+        views.sort_by_key(|(_, view)| view.created_on);
+        let sink_tables = views
+            .iter()
+            .filter_map(|(_, view)| view.sink_table.as_ref())
+            .map(|sink_table| sink_table.table_name.clone())
+            .collect::<HashSet<_>>();
+
+        let mut tables = Vec::new();
+        let table_names = self
+            .catalog_manager
+            .table_names(catalog, schema)
+            .await
+            .context(CatalogSnafu)?;
+        for table_name in table_names {
+            // Sink tables are created along with their materialized views.
+            if sink_tables.contains(&table_name) {
+                continue;
+            }
+            let Some(table) = self
+                .catalog_manager
+                .table(catalog, schema, &table_name)
+                .await
+                .context(CatalogSnafu)?
+            else {
+                continue;
+            };
+            if table.table_type() != TableType::Base {
+                continue;
+            }
+
+            let table_info = table.table_info();
+            let partitions = self
+                .partition_manager
+                .find_table_partitions(table_info.table_id())
+                .await
+                .context(error::FindTablePartitionRuleSnafu {
+                    table_name: &table_name,
+                })?;
+            let mut stmt = query::sql::create_table_stmt(&table_info, quote_style)
+                .context(ExecuteStatementSnafu)?;
+            stmt.name = qualified_name(schema, &table_name, quote_style);
+            stmt.partitions = create_partitions_stmt(partitions)?.map(|mut partitions| {
+                partitions.set_quote(quote_style);
+                partitions
+            });
+            tables.push(stmt);
+        }
+        // Physical tables must be created before their logical tables.
+        tables.sort_by_key(is_logical_table);
+
+        let mut stmts = vec![create_database];
+        stmts.extend(tables.iter().map(|table| table.to_string()));
+        stmts.extend(views.iter().map(|(name, view)| {
+            format!(
+                "CREATE {}VIEW IF NOT EXISTS {} AS {}",
+                if view.materialized {
+                    "MATERIALIZED "
+                } else {
+                    ""
+                },
+                qualified_name(schema, name, quote_style),
+                view.definition
+            )
+        }));
+        Ok(stmts.join(";\n\n") + ";\n")
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub(super) async fn show_processlist(
+        &self,
+        stmt: ShowProcesslist,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        query::sql::show_processlist(stmt, &self.query_engine, &self.catalog_manager, query_ctx)
+            .await
+            .context(ExecuteStatementSnafu)
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn show_variable(&self, stmt: ShowVariables, query_ctx: QueryContextRef) -> Result<Output> {
         query::sql::show_variable(stmt, query_ctx).context(error::ExecuteStatementSnafu)
     }
 }
 
+fn qualified_name(schema: &str, name: &str, quote_style: char) -> ObjectName {
+    ObjectName(vec![
+        Ident::with_quote(quote_style, schema),
+        Ident::with_quote(quote_style, name),
+    ])
+}
+
 pub(crate) fn create_partitions_stmt(partitions: Vec<PartitionInfo>) -> Result<Option<Partitions>> {
     if partitions.is_empty() {
         return Ok(None);
@@ -0,0 +1,330 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use auth::PasswordHashes;
+use common_meta::cache_invalidator::Context;
+use common_meta::instruction::CacheIdent;
+use common_meta::key::privilege::{GrantObject, GranteeKind, GranteeValue, ADMIN_ROLE};
+use common_meta::key::user::{UserManager, UserValue};
+use common_query::Output;
+use common_telemetry::{info, tracing};
+use session::context::QueryContextRef;
+use snafu::{ensure, ResultExt};
+use sql::ast::Ident;
+use sql::statements::user::{AlterUser, CreateUser, DropUser, Password, MYSQL_NATIVE_PASSWORD};
+
+use super::StatementExecutor;
+use crate::error::{
+    self, ExecuteStatementSnafu, HashPasswordSnafu, Result, TableMetadataManagerSnafu,
+    UserAlreadyExistsSnafu, UserNotFoundSnafu,
+};
+
+/// Users are stored in the metadata store with hashed passwords, frontends with
+/// the meta user provider authenticate them.
+impl StatementExecutor {
+    #[tracing::instrument(skip_all)]
+    pub async fn create_user(&self, stmt: CreateUser) -> Result<Output> {
+        let user = &stmt.name.value;
+        let value = user_value(user, stmt.password, stmt.mysql_native_password)?;
+        let created = self
+            .user_manager()
+            .create(user, &value)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(
+            created || stmt.if_not_exists,
+            UserAlreadyExistsSnafu { user }
+        );
+        if created {
+            self.invalidate_user(user).await?;
+            info!("User {user} is created");
+        }
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn alter_user(&self, stmt: AlterUser) -> Result<Output> {
+        let user = &stmt.name.value;
+        let value = user_value(user, stmt.password, stmt.mysql_native_password)?;
+        let updated = self
+            .user_manager()
+            .update(user, &value)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(updated, UserNotFoundSnafu { user });
+        self.invalidate_user(user).await?;
+        info!("Password of user {user} is changed");
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn drop_user(&self, stmt: DropUser) -> Result<Output> {
+        let user = &stmt.name.value;
+        self.ensure_not_last_admin(user).await?;
+        let dropped = self
+            .user_manager()
+            .delete(user)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(dropped || stmt.if_exists, UserNotFoundSnafu { user });
+        if dropped {
+            self.table_metadata_manager
+                .privilege_manager()
+                .drop_user(user)
+                .await
+                .context(TableMetadataManagerSnafu)?;
+            self.invalidate_user(user).await?;
+            info!("User {user} is dropped");
+        }
+
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    /// Shows a script recreating the users, roles and grants of the cluster, only
+    /// users who can manage privileges are allowed to run it.
+    #[tracing::instrument(skip_all)]
+    pub async fn show_create_users(&self, query_ctx: QueryContextRef) -> Result<Output> {
+        let users = self
+            .user_manager()
+            .users()
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        let privilege_manager = self.table_metadata_manager.privilege_manager();
+        let roles = privilege_manager
+            .grantees(GranteeKind::Role)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        let grantees = privilege_manager
+            .grantees(GranteeKind::User)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        let script = create_users_script(&users, &roles, &grantees, query_ctx.quote_style());
+        query::sql::show_create_users(script).context(ExecuteStatementSnafu)
+    }
+
+    fn user_manager(&self) -> &UserManager {
+        self.table_metadata_manager.user_manager()
+    }
+
+    /// Invalidates the cached credentials of `user` in this frontend, metasrv
+    /// broadcasts the invalidation to other frontends.
+    async fn invalidate_user(&self, user: &str) -> Result<()> {
+        self.cache_invalidator
+            .invalidate(
+                &Context::default(),
+                vec![CacheIdent::User(user.to_string())],
+            )
+            .await
+            .context(error::InvalidateTableCacheSnafu)
+    }
+}
+
+fn user_value(user: &str, password: Password, mysql_native_password: bool) -> Result<UserValue> {
+    match password {
+        Password::Plain(password) => {
+            let hashes = PasswordHashes::new(&password, mysql_native_password)
+                .context(HashPasswordSnafu { user })?;
+
+            Ok(UserValue {
+                password_hash: hashes.password_hash,
+                mysql_native_password: hashes.mysql_native_password,
+            })
+        }
+        // The parser ensures the hash of the MySQL native password is present iff enabled.
+        Password::Hashed {
+            password_hash,
+            mysql_native_password,
+        } => Ok(UserValue {
+            password_hash,
+            mysql_native_password,
+        }),
+    }
+}
+
+/// Returns statements creating `roles` and `users` and granting them what `roles` and
+/// `grantees` record, in an order they can be replayed in another cluster.
+///
+/// Passwords are restored from their hashes. The [ADMIN_ROLE] is granted last, as
+/// granting it enables privileges and the statements after it may be denied.
+fn create_users_script(
+    users: &[(String, UserValue)],
+    roles: &[(String, GranteeValue)],
+    grantees: &[(String, GranteeValue)],
+    quote_style: char,
+) -> String {
+    let ident = |name: &str| Ident::with_quote(quote_style, name);
+    let mut stmts = Vec::new();
+    for (role, _) in roles {
+        stmts.push(format!("CREATE ROLE IF NOT EXISTS {}", ident(role)));
+    }
+    for (user, value) in users {
+        let mut stmt = format!("CREATE USER IF NOT EXISTS {} IDENTIFIED ", ident(user));
+        if value.mysql_native_password.is_some() {
+            stmt.push_str(&format!("WITH {MYSQL_NATIVE_PASSWORD} "));
+        }
+        stmt.push_str(&format!("AS {}", string_literal(&value.password_hash)));
+        if let Some(hash) = &value.mysql_native_password {
+            stmt.push_str(&format!(" {}", string_literal(hash)));
+        }
+        stmts.push(stmt);
+    }
+    for (grantee, value) in roles.iter().chain(grantees) {
+        for grant in &value.grants {
+            stmts.push(format!(
+                "GRANT {} ON {} TO {}",
+                grant.privilege,
+                grant_object_sql(&grant.object, quote_style),
+                ident(grantee)
+            ));
+        }
+    }
+    let mut role_grants = grantees
+        .iter()
+        .flat_map(|(user, value)| value.roles.iter().map(move |role| (user, role)))
+        .collect::<Vec<_>>();
+    role_grants.sort_by_key(|(_, role)| *role == ADMIN_ROLE);
+    for (user, role) in role_grants {
+        stmts.push(format!("GRANT {} TO {}", ident(role), ident(user)));
+    }
+
+    stmts.iter().map(|stmt| format!("{stmt};\n")).collect()
+}
+
+fn grant_object_sql(object: &GrantObject, quote_style: char) -> String {
+    let ident = |name: &str| Ident::with_quote(quote_style, name);
+    match object {
+        GrantObject::Catalog { catalog } => format!("CATALOG {}", ident(catalog)),
+        GrantObject::Schema { catalog, schema } => {
+            format!("DATABASE {}.{}", ident(catalog), ident(schema))
+        }
+        GrantObject::Table {
+            catalog,
+            schema,
+            table,
+        } => format!(
+            "TABLE {}.{}.{}",
+            ident(catalog),
+            ident(schema),
+            ident(table)
+        ),
+    }
+}
+
+fn string_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use common_meta::key::privilege::{Privilege, PrivilegeGrant};
+
+    use super::*;
+
+    fn plain(password: &str) -> Password {
+        Password::Plain(password.to_string())
+    }
+
+    #[test]
+    fn test_user_value() {
+        let value = user_value("alice", plain("123456"), false).unwrap();
+        assert!(value.password_hash.starts_with("$argon2"));
+        assert!(!value.password_hash.contains("123456"));
+        assert!(value.mysql_native_password.is_none());
+
+        let value = user_value("alice", plain("123456"), true).unwrap();
+        assert_eq!(
+            Some("6bb4837eb74329105ee4568dda7dc67ed2ca2ad9"),
+            value.mysql_native_password.as_deref()
+        );
+
+        assert!(user_value("alice", plain(""), false).is_err());
+
+        let hashed = Password::Hashed {
+            password_hash: "$argon2id$hash".to_string(),
+            mysql_native_password: Some("6bb4".to_string()),
+        };
+        assert_eq!(
+            UserValue {
+                password_hash: "$argon2id$hash".to_string(),
+                mysql_native_password: Some("6bb4".to_string()),
+            },
+            user_value("alice", hashed, true).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_create_users_script() {
+        let users = vec![
+            (
+                "alice".to_string(),
+                UserValue {
+                    password_hash: "$argon2id$a".to_string(),
+                    mysql_native_password: None,
+                },
+            ),
+            (
+                "root".to_string(),
+                UserValue {
+                    password_hash: "$argon2id$r".to_string(),
+                    mysql_native_password: Some("6bb4".to_string()),
+                },
+            ),
+        ];
+        let roles = vec![(
+            "reader".to_string(),
+            GranteeValue {
+                grants: vec![PrivilegeGrant::new(
+                    Privilege::Select,
+                    GrantObject::schema("greptime", "public"),
+                )],
+                roles: vec![],
+            },
+        )];
+        let grantees = vec![
+            (
+                "alice".to_string(),
+                GranteeValue {
+                    grants: vec![PrivilegeGrant::new(
+                        Privilege::Insert,
+                        GrantObject::table("greptime", "public", "foo"),
+                    )],
+                    roles: vec!["reader".to_string()],
+                },
+            ),
+            (
+                "root".to_string(),
+                GranteeValue {
+                    grants: vec![],
+                    roles: vec![ADMIN_ROLE.to_string(), "reader".to_string()],
+                },
+            ),
+        ];
+
+        let script = create_users_script(&users, &roles, &grantees, '`');
+        assert_eq!(
+            r#"CREATE ROLE IF NOT EXISTS `reader`;
+CREATE USER IF NOT EXISTS `alice` IDENTIFIED AS '$argon2id$a';
+CREATE USER IF NOT EXISTS `root` IDENTIFIED WITH mysql_native_password AS '$argon2id$r' '6bb4';
+GRANT SELECT ON DATABASE `greptime`.`public` TO `reader`;
+GRANT INSERT ON TABLE `greptime`.`public`.`foo` TO `alice`;
+GRANT `reader` TO `alice`;
+GRANT `reader` TO `root`;
+GRANT `admin` TO `root`;
+"#,
+            script
+        );
+    }
+}
@@ -13,14 +13,21 @@
 // limitations under the License.
 
 use async_trait::async_trait;
+use catalog::CatalogManagerRef;
 use client::Output;
 use common_base::AffectedRows;
 use common_error::ext::BoxedError;
 use common_function::handlers::TableMutationHandler;
+use common_meta::cache_invalidator::{CacheInvalidatorRef, Context};
+use common_meta::instruction::CacheIdent;
+use common_meta::key::TableMetadataManagerRef;
+use common_meta::table_name::TableName;
 use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
+use common_telemetry::info;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
+use store_api::region_request::CompactOptions;
 use store_api::storage::RegionId;
 use table::requests::{
     CompactTableRequest, DeleteRequest as TableDeleteRequest, FlushTableRequest,
@@ -28,6 +35,9 @@ use table::requests::{
 };
 
 use crate::delete::DeleterRef;
+use crate::error::{
+    CatalogSnafu, InvalidateTableCacheSnafu, Result, TableMetadataManagerSnafu, TableNotFoundSnafu,
+};
 use crate::insert::InserterRef;
 use crate::request::RequesterRef;
 
@@ -35,15 +45,77 @@ pub struct TableMutationOperator {
     inserter: InserterRef,
     deleter: DeleterRef,
     requester: RequesterRef,
+    catalog_manager: CatalogManagerRef,
+    table_metadata_manager: TableMetadataManagerRef,
+    cache_invalidator: CacheInvalidatorRef,
 }
 
 impl TableMutationOperator {
-    pub fn new(inserter: InserterRef, deleter: DeleterRef, requester: RequesterRef) -> Self {
+    pub fn new(
+        inserter: InserterRef,
+        deleter: DeleterRef,
+        requester: RequesterRef,
+        catalog_manager: CatalogManagerRef,
+        table_metadata_manager: TableMetadataManagerRef,
+        cache_invalidator: CacheInvalidatorRef,
+    ) -> Self {
         Self {
             inserter,
             deleter,
             requester,
+            catalog_manager,
+            table_metadata_manager,
+            cache_invalidator,
+        }
+    }
+
+    /// Invalidates the cached name, info and route of the table. Also invalidates
+    /// the route of the physical table if the table is a logical table.
+    async fn invalidate_table_cache(&self, table_name: TableName) -> Result<()> {
+        let ctx = Context::default();
+        // Invalidates the name first so we don't resolve the table by a stale id.
+        self.cache_invalidator
+            .invalidate(&ctx, vec![CacheIdent::TableName(table_name.clone())])
+            .await
+            .context(InvalidateTableCacheSnafu)?;
+
+        let table = self
+            .catalog_manager
+            .table(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            )
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: table_name.to_string(),
+            })?;
+        let table_id = table.table_info().table_id();
+        self.cache_invalidator
+            .invalidate(&ctx, vec![CacheIdent::TableId(table_id)])
+            .await
+            .context(InvalidateTableCacheSnafu)?;
+
+        let physical_table_id = self
+            .table_metadata_manager
+            .table_route_manager()
+            .get_physical_table_id(table_id)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        if physical_table_id != table_id {
+            self.cache_invalidator
+                .invalidate(&ctx, vec![CacheIdent::TableId(physical_table_id)])
+                .await
+                .context(InvalidateTableCacheSnafu)?;
         }
+
+        info!(
+            "Refreshed the cache of table {}, table id: {}, physical table id: {}",
+            table_name, table_id, physical_table_id
+        );
+
+        Ok(())
     }
 }
 
@@ -112,10 +184,22 @@ impl TableMutationHandler for TableMutationOperator {
     async fn compact_region(
         &self,
         region_id: RegionId,
+        compact_options: CompactOptions,
         ctx: QueryContextRef,
     ) -> QueryResult<AffectedRows> {
         self.requester
-            .handle_region_compaction(region_id, ctx)
+            .handle_region_compaction(region_id, compact_options, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::TableMutationSnafu)
+    }
+
+    async fn refresh_table_cache(
+        &self,
+        table_name: TableName,
+        _ctx: QueryContextRef,
+    ) -> QueryResult<()> {
+        self.invalidate_table_cache(table_name)
             .await
             .map_err(BoxedError::new)
             .context(query_error::TableMutationSnafu)
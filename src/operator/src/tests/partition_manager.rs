@@ -34,7 +34,7 @@ use partition::manager::{PartitionRuleManager, PartitionRuleManagerRef};
 use partition::partition::{PartitionBound, PartitionDef};
 use partition::range::RangePartitionRule;
 use partition::PartitionRuleRef;
-use store_api::storage::RegionNumber;
+use store_api::storage::{RegionId, RegionNumber};
 use table::metadata::{TableInfo, TableInfoBuilder, TableMetaBuilder};
 
 pub fn new_test_table_info(
@@ -132,6 +132,7 @@ pub(crate) async fn create_partition_rule_manager(
                     follower_peers: vec![],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
                 RegionRoute {
                     region: Region {
@@ -163,6 +164,7 @@ pub(crate) async fn create_partition_rule_manager(
                     follower_peers: vec![],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 2,
                 },
                 RegionRoute {
                     region: Region {
@@ -186,6 +188,7 @@ pub(crate) async fn create_partition_rule_manager(
                     follower_peers: vec![],
                     leader_status: None,
                     leader_down_since: None,
+                    leader_epoch: 0,
                 },
             ]),
             region_wal_options.clone(),
@@ -242,6 +245,30 @@ async fn test_find_partition_rule() {
     assert_eq!(range_columns_rule.regions(), &vec![1, 2, 3]);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_find_region_leader_with_epoch() {
+    let partition_manager =
+        create_partition_rule_manager(Arc::new(MemoryKvBackend::default())).await;
+
+    let (peer, leader_epoch) = partition_manager
+        .find_region_leader_with_epoch(RegionId::new(1, 2))
+        .await
+        .unwrap();
+    assert_eq!(peer, Peer::new(2, ""));
+    assert_eq!(leader_epoch, 2);
+
+    let (_, leader_epoch) = partition_manager
+        .find_region_leader_with_epoch(RegionId::new(1, 3))
+        .await
+        .unwrap();
+    assert_eq!(leader_epoch, 0);
+
+    assert!(partition_manager
+        .find_region_leader_with_epoch(RegionId::new(1, 4))
+        .await
+        .is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_find_regions() {
     let kv_backend = MetaKvBackend {
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
+use std::ops::Bound;
+
 use datatypes::value::Value;
 use serde::{Deserialize, Serialize};
 use sql::statements::value_to_sql_value;
@@ -114,4 +117,122 @@ impl PartitionExpr {
             right: Box::new(rhs),
         }
     }
+
+    /// Returns the upper bound of `column` in rows satisfying the expression, or
+    /// [Bound::Unbounded] if the expression doesn't bound the column.
+    pub fn upper_bound(&self, column: &str) -> Bound<Value> {
+        match (&*self.lhs, &self.op, &*self.rhs) {
+            (Operand::Expr(lhs), RestrictedOp::And, Operand::Expr(rhs)) => {
+                min_upper_bound(lhs.upper_bound(column), rhs.upper_bound(column))
+            }
+            (Operand::Expr(lhs), RestrictedOp::Or, Operand::Expr(rhs)) => {
+                max_upper_bound(lhs.upper_bound(column), rhs.upper_bound(column))
+            }
+            (Operand::Column(c), op, Operand::Value(v)) if c == column => match op {
+                RestrictedOp::Lt => Bound::Excluded(v.clone()),
+                RestrictedOp::LtEq | RestrictedOp::Eq => Bound::Included(v.clone()),
+                _ => Bound::Unbounded,
+            },
+            (Operand::Value(v), op, Operand::Column(c)) if c == column => match op {
+                RestrictedOp::Gt => Bound::Excluded(v.clone()),
+                RestrictedOp::GtEq | RestrictedOp::Eq => Bound::Included(v.clone()),
+                _ => Bound::Unbounded,
+            },
+            _ => Bound::Unbounded,
+        }
+    }
+}
+
+/// Returns the tighter one of two upper bounds.
+fn min_upper_bound(lhs: Bound<Value>, rhs: Bound<Value>) -> Bound<Value> {
+    match (&lhs, &rhs) {
+        (Bound::Unbounded, _) => rhs,
+        (_, Bound::Unbounded) => lhs,
+        _ => {
+            if cmp_upper_bound(&lhs, &rhs) == Ordering::Greater {
+                rhs
+            } else {
+                lhs
+            }
+        }
+    }
+}
+
+/// Returns the looser one of two upper bounds.
+fn max_upper_bound(lhs: Bound<Value>, rhs: Bound<Value>) -> Bound<Value> {
+    match (&lhs, &rhs) {
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => Bound::Unbounded,
+        _ => {
+            if cmp_upper_bound(&lhs, &rhs) == Ordering::Less {
+                rhs
+            } else {
+                lhs
+            }
+        }
+    }
+}
+
+/// Compares two bounded upper bounds, an excluded bound is less than an included one of the same value.
+fn cmp_upper_bound(lhs: &Bound<Value>, rhs: &Bound<Value>) -> Ordering {
+    match (lhs, rhs) {
+        (Bound::Excluded(l), Bound::Excluded(r)) | (Bound::Included(l), Bound::Included(r)) => {
+            l.cmp(r)
+        }
+        (Bound::Excluded(l), Bound::Included(r)) => l.cmp(r).then(Ordering::Less),
+        (Bound::Included(l), Bound::Excluded(r)) => l.cmp(r).then(Ordering::Greater),
+        _ => unreachable!("unbounded bounds are handled by callers"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn col_op_val(op: RestrictedOp, value: i64) -> Operand {
+        Operand::Expr(PartitionExpr::new(
+            Operand::Column("ts".to_string()),
+            op,
+            Operand::Value(Value::Int64(value)),
+        ))
+    }
+
+    #[test]
+    fn test_upper_bound() {
+        // ts >= 10 AND ts < 20
+        let expr = PartitionExpr::new(
+            col_op_val(RestrictedOp::GtEq, 10),
+            RestrictedOp::And,
+            col_op_val(RestrictedOp::Lt, 20),
+        );
+        assert_eq!(expr.upper_bound("ts"), Bound::Excluded(Value::Int64(20)));
+        assert_eq!(expr.upper_bound("host"), Bound::Unbounded);
+
+        // (ts < 20 AND ts <= 20) OR 10 > ts
+        let expr = PartitionExpr::new(
+            Operand::Expr(PartitionExpr::new(
+                col_op_val(RestrictedOp::Lt, 20),
+                RestrictedOp::And,
+                col_op_val(RestrictedOp::LtEq, 20),
+            )),
+            RestrictedOp::Or,
+            Operand::Expr(PartitionExpr::new(
+                Operand::Value(Value::Int64(10)),
+                RestrictedOp::Gt,
+                Operand::Column("ts".to_string()),
+            )),
+        );
+        assert_eq!(expr.upper_bound("ts"), Bound::Excluded(Value::Int64(20)));
+
+        // ts <= 20 OR host = 'a'
+        let expr = PartitionExpr::new(
+            col_op_val(RestrictedOp::LtEq, 20),
+            RestrictedOp::Or,
+            Operand::Expr(PartitionExpr::new(
+                Operand::Column("host".to_string()),
+                RestrictedOp::Eq,
+                Operand::Value(Value::from("a")),
+            )),
+        );
+        assert_eq!(expr.upper_bound("ts"), Bound::Unbounded);
+    }
 }
@@ -238,6 +238,20 @@ impl PartitionRuleManager {
         )
     }
 
+    /// Finds the leader of the region and the epoch of the leader.
+    pub async fn find_region_leader_with_epoch(&self, region_id: RegionId) -> Result<(Peer, u64)> {
+        let region_routes = self.find_region_routes(region_id.table_id()).await?;
+
+        region_routes
+            .into_iter()
+            .find(|route| route.region.id.region_number() == region_id.region_number())
+            .and_then(|route| Some((route.leader_peer?, route.leader_epoch)))
+            .context(FindLeaderSnafu {
+                region_id,
+                table_id: region_id.table_id(),
+            })
+    }
+
     pub async fn split_rows(
         &self,
         table_id: TableId,
@@ -15,9 +15,12 @@
 use common_base::Plugins;
 use datanode::config::DatanodeOptions;
 use datanode::error::Result;
+use query::query_engine::options::QueryEngineOptions;
 
-pub async fn setup_datanode_plugins(_opts: &mut DatanodeOptions) -> Result<Plugins> {
-    Ok(Plugins::new())
+pub async fn setup_datanode_plugins(opts: &mut DatanodeOptions) -> Result<Plugins> {
+    let plugins = Plugins::new();
+    plugins.insert::<QueryEngineOptions>(opts.query.clone());
+    Ok(plugins)
 }
 
 pub async fn start_datanode_plugins(_plugins: Plugins) -> Result<()> {
@@ -16,12 +16,20 @@ use auth::UserProviderRef;
 use common_base::Plugins;
 use frontend::error::{IllegalAuthConfigSnafu, Result};
 use frontend::frontend::FrontendOptions;
+use frontend::user_provider::META_USER_PROVIDER;
+use query::query_engine::options::QueryEngineOptions;
 use snafu::ResultExt;
 
 pub async fn setup_frontend_plugins(opts: &FrontendOptions) -> Result<Plugins> {
     let plugins = Plugins::new();
+    plugins.insert::<QueryEngineOptions>(opts.query.clone());
 
-    if let Some(user_provider) = opts.user_provider.as_ref() {
+    // The meta user provider is built on the metadata KV backend, when the frontend is built.
+    if let Some(user_provider) = opts
+        .user_provider
+        .as_ref()
+        .filter(|p| p.split(':').next() != Some(META_USER_PROVIDER))
+    {
         let provider =
             auth::user_provider_from_option(user_provider).context(IllegalAuthConfigSnafu)?;
         plugins.insert::<UserProviderRef>(provider);
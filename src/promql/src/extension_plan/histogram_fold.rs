@@ -14,13 +14,14 @@
 
 use std::any::Any;
 use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use std::sync::Arc;
 use std::task::Poll;
 use std::time::Instant;
 
 use common_recordbatch::RecordBatch as GtRecordBatch;
 use common_telemetry::warn;
-use datafusion::arrow::array::AsArray;
+use datafusion::arrow::array::{Array, AsArray};
 use datafusion::arrow::compute::{self, concat_batches, SortOptions};
 use datafusion::arrow::datatypes::{DataType, Float64Type, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
@@ -50,10 +51,10 @@ use futures::{ready, Stream, StreamExt};
 /// - other columns will be sampled every `bucket_num` element, but their types won't change.
 ///
 /// Due to the folding or sampling, the output rows number will become `input_rows` / `bucket_num`.
+/// Series may have different buckets, and buckets with the same bound are merged.
 ///
 /// # Requirement
 /// - Input should be sorted on `<tag list>, ts, le ASC`.
-/// - The value set of `le` should be same. I.e., buckets of every series should be same.
 ///
 /// [1]: https://prometheus.io/docs/concepts/metric_types/#histogram
 #[derive(Debug, PartialEq, Hash, Eq)]
@@ -324,7 +325,6 @@ impl ExecutionPlan for HistogramFoldExec {
             field_column_index: self.field_column_index,
             quantile: self.quantile,
             normal_indices: normal_indices.into_iter().collect(),
-            bucket_size: None,
             input_buffer: vec![],
             input,
             output_schema,
@@ -398,7 +398,6 @@ pub struct HistogramFoldStream {
     quantile: f64,
     /// Columns need not folding. This indices is based on input schema
     normal_indices: Vec<usize>,
-    bucket_size: Option<usize>,
     /// Expected output batch size
     batch_size: usize,
     output_schema: SchemaRef,
@@ -439,7 +438,10 @@ impl Stream for HistogramFoldStream {
                     self.metric.elapsed_compute().add_elapsed(timer);
                     break Poll::Ready(Some(result));
                 }
-                None => break Poll::Ready(self.take_output_buf()?.map(Ok)),
+                None => {
+                    self.fold_buf(true)?;
+                    break Poll::Ready(self.take_output_buf()?.map(Ok));
+                }
             }
         };
         self.metric.record_poll(poll)
@@ -452,17 +454,8 @@ impl HistogramFoldStream {
         &mut self,
         input: RecordBatch,
     ) -> DataFusionResult<Option<DataFusionResult<RecordBatch>>> {
-        let Some(bucket_num) = self.calculate_bucket_num(&input)? else {
-            return Ok(None);
-        };
-
-        if self.input_buffered_rows + input.num_rows() < bucket_num {
-            // not enough rows to fold
-            self.push_input_buf(input);
-            return Ok(None);
-        }
-
-        self.fold_buf(bucket_num, input)?;
+        self.push_input_buf(input);
+        self.fold_buf(false)?;
         if self.output_buffered_rows >= self.batch_size {
             return Ok(self.take_output_buf()?.map(Ok));
         }
@@ -493,74 +486,128 @@ impl HistogramFoldStream {
         Ok(builders)
     }
 
-    fn calculate_bucket_num(&mut self, batch: &RecordBatch) -> DataFusionResult<Option<usize>> {
-        if let Some(size) = self.bucket_size {
-            return Ok(Some(size));
+    /// Fold record batches from input buffer and put to output buffer
+    ///
+    /// Consecutive rows with the same tags and timestamp form one histogram, so
+    /// series with different bucket layouts can be folded together. The last
+    /// histogram may continue in the next batch, it stays in the input buffer
+    /// unless `flush` is set.
+    fn fold_buf(&mut self, flush: bool) -> DataFusionResult<()> {
+        if self.input_buffered_rows == 0 {
+            return Ok(());
         }
+        // TODO(ruihang): this concat is avoidable.
+        let batch = concat_batches(&self.input.schema(), self.input_buffer.drain(..).as_ref())?;
+        let group_columns = self
+            .normal_indices
+            .iter()
+            .map(|index| batch.column(*index).clone())
+            .collect::<Vec<_>>();
+        let group_ranges = if group_columns.is_empty() {
+            vec![0..batch.num_rows()]
+        } else {
+            compute::partition(&group_columns)?.ranges()
+        };
+        let mut ranges = self.split_by_positive_inf(&batch, group_ranges)?;
+        let remaining = if flush { None } else { ranges.pop() };
 
-        let inf_pos = self.find_positive_inf(batch)?;
-        if inf_pos == batch.num_rows() {
-            // no positive inf found, append to buffer and wait for next batch
-            self.push_input_buf(batch.clone());
-            return Ok(None);
+        let gt_schema = GtSchema::try_from(self.input.schema()).unwrap();
+        let gt_batch =
+            GtRecordBatch::try_from_df_record_batch(Arc::new(gt_schema), batch.clone()).unwrap();
+        for range in ranges {
+            self.fold_histogram(&gt_batch, range)?;
         }
 
-        // else we found the positive inf.
-        // calculate the bucket size
-        let bucket_size = inf_pos + self.input_buffered_rows + 1;
-        Ok(Some(bucket_size))
-    }
+        self.input_buffered_rows = 0;
+        if let Some(range) = remaining {
+            self.push_input_buf(batch.slice(range.start, range.len()));
+        }
 
-    /// Fold record batches from input buffer and put to output buffer
-    fn fold_buf(&mut self, bucket_num: usize, input: RecordBatch) -> DataFusionResult<()> {
-        self.push_input_buf(input);
-        // TODO(ruihang): this concat is avoidable.
-        let batch = concat_batches(&self.input.schema(), self.input_buffer.drain(..).as_ref())?;
-        let mut remaining_rows = self.input_buffered_rows;
-        let mut cursor = 0;
+        Ok(())
+    }
 
-        let gt_schema = GtSchema::try_from(self.input.schema()).unwrap();
-        let batch = GtRecordBatch::try_from_df_record_batch(Arc::new(gt_schema), batch).unwrap();
+    /// Split each range after its `+Inf` buckets, which end a histogram. Consecutive
+    /// `+Inf` buckets belong to the same histogram.
+    fn split_by_positive_inf(
+        &self,
+        batch: &RecordBatch,
+        group_ranges: Vec<Range<usize>>,
+    ) -> DataFusionResult<Vec<Range<usize>>> {
+        let string_le_array = batch.column(self.le_column_index);
+        let float_le_array = compute::cast(&string_le_array, &DataType::Float64).map_err(|e| {
+            DataFusionError::Execution(format!(
+                "cannot cast {} array to float64 array: {:?}",
+                string_le_array.data_type(),
+                e
+            ))
+        })?;
+        let le_as_f64_array = float_le_array
+            .as_primitive_opt::<Float64Type>()
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "expect a float64 array, but found {}",
+                    float_le_array.data_type()
+                ))
+            })?;
+        let is_positive_inf = |row: usize| {
+            le_as_f64_array.is_valid(row) && le_as_f64_array.value(row) == f64::INFINITY
+        };
 
-        while remaining_rows >= bucket_num {
-            // "sample" normal columns
-            for normal_index in &self.normal_indices {
-                let val = batch.column(*normal_index).get(cursor);
-                self.output_buffer[*normal_index].push_value_ref(val.as_value_ref());
+        let mut ranges = Vec::with_capacity(group_ranges.len());
+        for range in group_ranges {
+            let mut start = range.start;
+            for row in range.clone() {
+                if is_positive_inf(row) && (row + 1 == range.end || !is_positive_inf(row + 1)) {
+                    ranges.push(start..row + 1);
+                    start = row + 1;
+                }
             }
-            // "fold" `le` and field columns
-            let le_array = batch.column(self.le_column_index);
-            let field_array = batch.column(self.field_column_index);
-            let mut bucket = vec![];
-            let mut counters = vec![];
-            for bias in 0..bucket_num {
-                let le_str_val = le_array.get(cursor + bias);
-                let le_str_val_ref = le_str_val.as_value_ref();
-                let le_str = le_str_val_ref
-                    .as_string()
-                    .unwrap()
-                    .expect("le column should not be nullable");
-                let le = le_str.parse::<f64>().unwrap();
-                bucket.push(le);
-
-                let counter = field_array
-                    .get(cursor + bias)
-                    .as_value_ref()
-                    .as_f64()
-                    .unwrap()
-                    .expect("field column should not be nullable");
-                counters.push(counter);
+            if start < range.end {
+                ranges.push(start..range.end);
             }
-            let result = Self::evaluate_row(self.quantile, &bucket, &counters)?;
-            self.output_buffer[self.field_column_index].push_value_ref(ValueRef::from(result));
-            cursor += bucket_num;
-            remaining_rows -= bucket_num;
-            self.output_buffered_rows += 1;
         }
+        Ok(ranges)
+    }
 
-        let remaining_input_batch = batch.into_df_record_batch().slice(cursor, remaining_rows);
-        self.input_buffered_rows = remaining_input_batch.num_rows();
-        self.input_buffer.push(remaining_input_batch);
+    /// Fold rows in `range` that form one histogram into one output row
+    fn fold_histogram(
+        &mut self,
+        batch: &GtRecordBatch,
+        range: Range<usize>,
+    ) -> DataFusionResult<()> {
+        // "sample" normal columns
+        for normal_index in &self.normal_indices {
+            let val = batch.column(*normal_index).get(range.start);
+            self.output_buffer[*normal_index].push_value_ref(val.as_value_ref());
+        }
+        // "fold" `le` and field columns
+        let le_array = batch.column(self.le_column_index);
+        let field_array = batch.column(self.field_column_index);
+        let mut bucket = Vec::with_capacity(range.len());
+        let mut counters = Vec::with_capacity(range.len());
+        for row in range {
+            let le_str_val = le_array.get(row);
+            let le_str_val_ref = le_str_val.as_value_ref();
+            let le_str = le_str_val_ref
+                .as_string()
+                .unwrap()
+                .expect("le column should not be nullable");
+            let le = le_str.parse::<f64>().map_err(|e| {
+                DataFusionError::Execution(format!("invalid le value {le_str}: {e}"))
+            })?;
+            bucket.push(le);
+
+            let counter = field_array
+                .get(row)
+                .as_value_ref()
+                .as_f64()
+                .unwrap()
+                .expect("field column should not be nullable");
+            counters.push(counter);
+        }
+        let result = Self::evaluate_row(self.quantile, &bucket, &counters)?;
+        self.output_buffer[self.field_column_index].push_value_ref(ValueRef::from(result));
+        self.output_buffered_rows += 1;
 
         Ok(())
     }
@@ -597,43 +644,6 @@ impl HistogramFoldStream {
             .map_err(DataFusionError::ArrowError)
     }
 
-    /// Find the first `+Inf` which indicates the end of the bucket group
-    ///
-    /// If the return value equals to batch's num_rows means the it's not found
-    /// in this batch
-    fn find_positive_inf(&self, batch: &RecordBatch) -> DataFusionResult<usize> {
-        // fuse this function. It should not be called when the
-        // bucket size is already know.
-        if let Some(bucket_size) = self.bucket_size {
-            return Ok(bucket_size);
-        }
-        let string_le_array = batch.column(self.le_column_index);
-        let float_le_array = compute::cast(&string_le_array, &DataType::Float64).map_err(|e| {
-            DataFusionError::Execution(format!(
-                "cannot cast {} array to float64 array: {:?}",
-                string_le_array.data_type(),
-                e
-            ))
-        })?;
-        let le_as_f64_array = float_le_array
-            .as_primitive_opt::<Float64Type>()
-            .ok_or_else(|| {
-                DataFusionError::Execution(format!(
-                    "expect a float64 array, but found {}",
-                    float_le_array.data_type()
-                ))
-            })?;
-        for (i, v) in le_as_f64_array.iter().enumerate() {
-            if let Some(v) = v
-                && v == f64::INFINITY
-            {
-                return Ok(i);
-            }
-        }
-
-        Ok(batch.num_rows())
-    }
-
     /// Evaluate the field column and return the result
     fn evaluate_row(quantile: f64, bucket: &[f64], counter: &[f64]) -> DataFusionResult<f64> {
         // check bucket
@@ -650,6 +660,17 @@ impl HistogramFoldStream {
                 "bucket and counter should have the same length".to_string(),
             ));
         }
+        // merge buckets with the same upper bound
+        let coalesced;
+        let (bucket, counter) = if bucket.windows(2).any(|w| w[0] == w[1]) {
+            coalesced = Self::coalesce_buckets(bucket, counter);
+            (coalesced.0.as_slice(), coalesced.1.as_slice())
+        } else {
+            (bucket, counter)
+        };
+        if bucket.len() <= 1 {
+            return Ok(f64::NAN);
+        }
         // check quantile
         if quantile < 0.0 {
             return Ok(f64::NEG_INFINITY);
@@ -685,6 +706,25 @@ impl HistogramFoldStream {
                     * (expected_pos - lower_count))
         }
     }
+
+    /// Merge buckets with the same upper bound by summing their counters.
+    ///
+    /// Series may spell the same `le` differently, like "1" and "1.0", so their
+    /// buckets stay apart after aggregations like `sum by (le)`. Like Prometheus,
+    /// they are treated as one bucket.
+    fn coalesce_buckets(bucket: &[f64], counter: &[f64]) -> (Vec<f64>, Vec<f64>) {
+        let mut merged_bucket: Vec<f64> = Vec::with_capacity(bucket.len());
+        let mut merged_counter: Vec<f64> = Vec::with_capacity(counter.len());
+        for (le, count) in bucket.iter().zip(counter) {
+            if merged_bucket.last() == Some(le) {
+                *merged_counter.last_mut().unwrap() += count;
+            } else {
+                merged_bucket.push(*le);
+                merged_counter.push(*count);
+            }
+        }
+        (merged_bucket, merged_counter)
+    }
 }
 
 #[cfg(test)]
@@ -801,6 +841,60 @@ mod test {
         assert_eq!(result_literal, expected);
     }
 
+    #[tokio::test]
+    async fn fold_different_bucket_layouts() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("le", DataType::Utf8, true),
+            Field::new("val", DataType::Float64, true),
+        ]));
+        let host_column = Arc::new(StringArray::from(vec![
+            "host_1", "host_1", "host_2", "host_2", "host_2",
+        ])) as _;
+        let le_column = Arc::new(StringArray::from(vec!["1", "+Inf", "1", "2", "+Inf"])) as _;
+        let val_column = Arc::new(Float64Array::from(vec![2.0, 4.0, 1.0, 2.0, 2.0])) as _;
+        let data =
+            RecordBatch::try_new(schema.clone(), vec![host_column, le_column, val_column]).unwrap();
+        let memory_exec = Arc::new(MemoryExec::try_new(&[vec![data]], schema, None).unwrap());
+        let output_schema = Arc::new(
+            (*HistogramFold::convert_schema(
+                &Arc::new(memory_exec.schema().to_dfschema().unwrap()),
+                "le",
+            )
+            .unwrap()
+            .as_ref())
+            .clone()
+            .into(),
+        );
+        let fold_exec = Arc::new(HistogramFoldExec {
+            le_column_index: 1,
+            field_column_index: 2,
+            quantile: 0.75,
+            ts_column_index: 9999, // not exist but doesn't matter
+            input: memory_exec,
+            output_schema,
+            metric: ExecutionPlanMetricsSet::new(),
+        });
+
+        let session_context = SessionContext::default();
+        let result = datafusion::physical_plan::collect(fold_exec, session_context.task_ctx())
+            .await
+            .unwrap();
+        let result_literal = datatypes::arrow::util::pretty::pretty_format_batches(&result)
+            .unwrap()
+            .to_string();
+
+        let expected = String::from(
+            "+--------+-----+
+| host   | val |
++--------+-----+
+| host_1 | 1.0 |
+| host_2 | 1.5 |
++--------+-----+",
+        );
+        assert_eq!(result_literal, expected);
+    }
+
     #[test]
     fn confirm_schema() {
         let input_schema = Schema::new(vec![
@@ -908,6 +1002,15 @@ mod test {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn evaluate_coalesced_buckets() {
+        // buckets of "1" and "1.0" from different series
+        let bucket = [1.0, 1.0, f64::INFINITY, f64::INFINITY];
+        let counters = [1.0, 2.0, 2.0, 2.0];
+        let result = HistogramFoldStream::evaluate_row(0.75, &bucket, &counters).unwrap();
+        assert_eq!(1.0, result);
+    }
+
     #[test]
     fn evaluate_small_fraction() {
         let bucket = [0.0, 2.0, 4.0, 6.0, f64::INFINITY];
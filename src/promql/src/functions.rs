@@ -18,6 +18,9 @@ mod deriv;
 mod extrapolate_rate;
 mod holt_winters;
 mod idelta;
+mod native_histogram_quantile;
+mod native_histogram_rate;
+mod native_histogram_sum;
 mod predict_linear;
 mod quantile;
 mod resets;
@@ -36,6 +39,11 @@ pub use deriv::Deriv;
 pub use extrapolate_rate::{Delta, Increase, Rate};
 pub use holt_winters::HoltWinters;
 pub use idelta::IDelta;
+pub use native_histogram_quantile::NativeHistogramQuantile;
+pub use native_histogram_rate::{
+    NativeHistogramDelta, NativeHistogramIncrease, NativeHistogramRate,
+};
+pub use native_histogram_sum::NativeHistogramSum;
 pub use predict_linear::PredictLinear;
 pub use quantile::QuantileOverTime;
 pub use resets::Resets;
@@ -145,7 +145,7 @@ impl<const IS_COUNTER: bool, const IS_RATE: bool> ExtrapolatedRate<IS_COUNTER, I
         Ok(result)
     }
 
-    fn extrapolate_factor(
+    pub(crate) fn extrapolate_factor(
         timestamps: &[Millisecond],
         range_end: Millisecond,
         range_length: Millisecond,
@@ -0,0 +1,123 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use common_query::native_histogram::NativeHistogram;
+use datafusion::arrow::array::{BinaryArray, Float64Array};
+use datafusion::common::DataFusionError;
+use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::physical_plan::ColumnarValue;
+use datatypes::arrow::array::Array;
+use datatypes::arrow::datatypes::DataType;
+use prost::Message;
+
+use crate::functions::extract_array;
+
+/// `histogram_quantile` over native histograms encoded in binary.
+pub struct NativeHistogramQuantile {
+    quantile: f64,
+}
+
+impl NativeHistogramQuantile {
+    fn new(quantile: f64) -> Self {
+        Self { quantile }
+    }
+
+    pub const fn name() -> &'static str {
+        "prom_native_histogram_quantile"
+    }
+
+    pub fn scalar_udf(quantile: f64) -> ScalarUDF {
+        ScalarUDF {
+            name: Self::name().to_string(),
+            signature: Signature::new(
+                TypeSignature::Exact(vec![DataType::Binary]),
+                Volatility::Immutable,
+            ),
+            return_type: Arc::new(|_| Ok(Arc::new(DataType::Float64))),
+            fun: Arc::new(move |input| Self::new(quantile).calc(input)),
+        }
+    }
+
+    fn calc(&self, input: &[ColumnarValue]) -> Result<ColumnarValue, DataFusionError> {
+        assert_eq!(input.len(), 1);
+        let array = extract_array(&input[0])?;
+        let histograms = array
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "{}: expect Binary as histogram array's type, found {}",
+                    Self::name(),
+                    array.data_type()
+                ))
+            })?;
+
+        let result = histograms
+            .iter()
+            .map(|bytes| {
+                bytes
+                    .map(|bytes| {
+                        NativeHistogram::decode(bytes)
+                            .map(|histogram| histogram.quantile(self.quantile))
+                            .map_err(|e| {
+                                DataFusionError::Execution(format!(
+                                    "{}: failed to decode native histogram: {}",
+                                    Self::name(),
+                                    e
+                                ))
+                            })
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Float64Array, _>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(result)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::native_histogram::BucketSpan;
+    use datafusion::arrow::array::BinaryArray;
+
+    use super::*;
+
+    #[test]
+    fn calc_native_histogram_quantile() {
+        let histogram = NativeHistogram {
+            count: 4.0,
+            sum: 6.0,
+            schema: 0,
+            positive_spans: vec![BucketSpan {
+                offset: 1,
+                length: 1,
+            }],
+            positive_counts: vec![4.0],
+            ..Default::default()
+        }
+        .encode_to_vec();
+        let input = ColumnarValue::Array(Arc::new(BinaryArray::from(vec![
+            Some(histogram.as_slice()),
+            None,
+        ])));
+
+        let output = NativeHistogramQuantile::new(0.5).calc(&[input]).unwrap();
+        let ColumnarValue::Array(output) = output else {
+            unreachable!()
+        };
+        let output = output.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(&Float64Array::from(vec![Some(1.5), None]), output);
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of `rate`, `increase` and `delta` over native histograms.
+
+use std::sync::Arc;
+
+use common_query::native_histogram::NativeHistogram;
+use datafusion::arrow::array::{BinaryArray, TimestampMillisecondArray};
+use datafusion::arrow::datatypes::TimeUnit;
+use datafusion::common::DataFusionError;
+use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::physical_plan::ColumnarValue;
+use datatypes::arrow::array::Array;
+use datatypes::arrow::datatypes::DataType;
+use prost::Message;
+
+use crate::functions::extract_array;
+use crate::functions::extrapolate_rate::ExtrapolatedRate;
+use crate::range_array::RangeArray;
+
+pub type NativeHistogramDelta = NativeHistogramExtrapolatedRate<false, false>;
+pub type NativeHistogramRate = NativeHistogramExtrapolatedRate<true, true>;
+pub type NativeHistogramIncrease = NativeHistogramExtrapolatedRate<true, false>;
+
+/// [ExtrapolatedRate] over native histograms, from `histogramRate` in Prometheus.
+///
+/// The result is a native histogram whose buckets are extrapolated with the
+/// factor computed from the total count.
+#[derive(Debug)]
+pub struct NativeHistogramExtrapolatedRate<const IS_COUNTER: bool, const IS_RATE: bool> {
+    /// Range duration in millisecond
+    range_length: i64,
+}
+
+impl<const IS_COUNTER: bool, const IS_RATE: bool>
+    NativeHistogramExtrapolatedRate<IS_COUNTER, IS_RATE>
+{
+    fn new(range_length: i64) -> Self {
+        Self { range_length }
+    }
+
+    pub fn name() -> &'static str {
+        match (IS_COUNTER, IS_RATE) {
+            (true, true) => "prom_native_histogram_rate",
+            (true, false) => "prom_native_histogram_increase",
+            _ => "prom_native_histogram_delta",
+        }
+    }
+
+    pub fn scalar_udf(range_length: i64) -> ScalarUDF {
+        ScalarUDF {
+            name: Self::name().to_string(),
+            signature: Signature::new(
+                TypeSignature::Exact(Self::input_type()),
+                Volatility::Immutable,
+            ),
+            return_type: Arc::new(|_| Ok(Arc::new(DataType::Binary))),
+            fun: Arc::new(move |input| Self::new(range_length).calc(input)),
+        }
+    }
+
+    fn input_type() -> Vec<DataType> {
+        vec![
+            // timestamp range vector
+            RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None)),
+            // histogram range vector
+            RangeArray::convert_data_type(DataType::Binary),
+            // timestamp vector
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+        ]
+    }
+
+    fn calc(&self, input: &[ColumnarValue]) -> Result<ColumnarValue, DataFusionError> {
+        assert_eq!(input.len(), 3);
+
+        let ts_array = extract_array(&input[0])?;
+        let ts_range = RangeArray::try_new(ts_array.to_data().into())?;
+        let value_array = extract_array(&input[1])?;
+        let value_range = RangeArray::try_new(value_array.to_data().into())?;
+        let ts = extract_array(&input[2])?;
+        let ts = ts
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+
+        let mut result_array = Vec::with_capacity(ts_range.len());
+        for index in 0..ts_range.len() {
+            let timestamps = ts_range.get(index).unwrap();
+            let timestamps = timestamps
+                .as_any()
+                .downcast_ref::<TimestampMillisecondArray>()
+                .unwrap()
+                .values();
+            let values = value_range.get(index).unwrap();
+            let histograms = values
+                .as_any()
+                .downcast_ref::<BinaryArray>()
+                .ok_or_else(|| {
+                    DataFusionError::Execution(format!(
+                        "{}: expect Binary as histogram array's type, found {}",
+                        Self::name(),
+                        values.data_type()
+                    ))
+                })?
+                .iter()
+                .flatten()
+                .map(|bytes| {
+                    NativeHistogram::decode(bytes).map_err(|e| {
+                        DataFusionError::Execution(format!(
+                            "{}: failed to decode native histogram: {}",
+                            Self::name(),
+                            e
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if histograms.len() < 2 || histograms.len() != timestamps.len() {
+                result_array.push(None);
+                continue;
+            }
+
+            let first = histograms.first().unwrap();
+            let mut result = histograms.last().unwrap().sub(first);
+            if IS_COUNTER {
+                for window in histograms.windows(2) {
+                    if window[1].detect_reset(&window[0]) {
+                        result = result.add(&window[0]);
+                    }
+                }
+            }
+
+            let mut factor = ExtrapolatedRate::<IS_COUNTER, IS_RATE>::extrapolate_factor(
+                timestamps,
+                ts.value(index),
+                self.range_length,
+                first.count,
+                result.count,
+            );
+            if IS_RATE {
+                // safety: range_length is checked to be non-zero in the planner.
+                factor /= self.range_length as f64 / 1000.0;
+            }
+            result.scale(factor);
+
+            result_array.push(Some(result.encode_to_vec()));
+        }
+
+        let result = ColumnarValue::Array(Arc::new(BinaryArray::from_iter(result_array)));
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::native_histogram::BucketSpan;
+    use datafusion::arrow::array::ArrayRef;
+
+    use super::*;
+
+    fn new_histogram(counts: [f64; 2]) -> Vec<u8> {
+        NativeHistogram {
+            count: counts.iter().sum(),
+            sum: counts.iter().sum(),
+            schema: 0,
+            positive_spans: vec![BucketSpan {
+                offset: 0,
+                length: 2,
+            }],
+            positive_counts: counts.to_vec(),
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
+    fn run<const IS_COUNTER: bool, const IS_RATE: bool>(
+        histograms: Vec<Vec<u8>>,
+    ) -> Option<NativeHistogram> {
+        let len = histograms.len();
+        let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+            (1..=len as i64).map(|ts| Some(ts * 1000)),
+        ));
+        let values_array = Arc::new(BinaryArray::from_iter_values(histograms));
+        let ranges = [(0, len as u32)];
+        let ts_range = RangeArray::from_ranges(ts_array, ranges).unwrap();
+        let value_range = RangeArray::from_ranges(values_array, ranges).unwrap();
+        let timestamps: ArrayRef = Arc::new(TimestampMillisecondArray::from_iter([Some(
+            len as i64 * 1000,
+        )]));
+        let input = vec![
+            ColumnarValue::Array(Arc::new(ts_range.into_dict())),
+            ColumnarValue::Array(Arc::new(value_range.into_dict())),
+            ColumnarValue::Array(timestamps),
+        ];
+        // The range covers all samples, so nothing is extrapolated.
+        let output =
+            NativeHistogramExtrapolatedRate::<IS_COUNTER, IS_RATE>::new((len as i64 - 1) * 1000)
+                .calc(&input)
+                .unwrap();
+        let output = extract_array(&output).unwrap();
+        let output = output.as_any().downcast_ref::<BinaryArray>().unwrap();
+        output
+            .iter()
+            .next()
+            .unwrap()
+            .map(|bytes| NativeHistogram::decode(bytes).unwrap())
+    }
+
+    #[test]
+    fn increase_native_histograms() {
+        let result = run::<true, false>(vec![
+            new_histogram([1.0, 1.0]),
+            new_histogram([2.0, 3.0]),
+            new_histogram([4.0, 4.0]),
+        ])
+        .unwrap();
+        assert_eq!(6.0, result.count);
+        assert_eq!(vec![3.0, 3.0], result.positive_counts);
+    }
+
+    #[test]
+    fn increase_native_histograms_with_reset() {
+        let result = run::<true, false>(vec![
+            new_histogram([2.0, 2.0]),
+            new_histogram([3.0, 4.0]),
+            new_histogram([1.0, 1.0]),
+        ])
+        .unwrap();
+        // The counter resets at the last sample: (1 + 3 - 2, 1 + 4 - 2).
+        assert_eq!(vec![2.0, 3.0], result.positive_counts);
+    }
+
+    #[test]
+    fn rate_native_histograms() {
+        let result =
+            run::<true, true>(vec![new_histogram([1.0, 1.0]), new_histogram([3.0, 5.0])]).unwrap();
+        assert_eq!(vec![2.0, 4.0], result.positive_counts);
+
+        assert!(run::<true, true>(vec![new_histogram([1.0, 1.0])]).is_none());
+    }
+}
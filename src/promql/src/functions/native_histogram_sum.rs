@@ -0,0 +1,142 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_query::native_histogram::NativeHistogram;
+use datafusion::arrow::array::{ArrayRef, BinaryArray};
+use datafusion::common::{DataFusionError, ScalarValue};
+use datafusion::logical_expr::{Accumulator, AggregateUDF, Signature, TypeSignature, Volatility};
+use datatypes::arrow::array::Array;
+use datatypes::arrow::datatypes::DataType;
+use prost::Message;
+
+/// `sum` aggregation over native histograms encoded in binary.
+#[derive(Debug, Default)]
+pub struct NativeHistogramSum {
+    sum: Option<NativeHistogram>,
+}
+
+impl NativeHistogramSum {
+    pub const fn name() -> &'static str {
+        "prom_native_histogram_sum"
+    }
+
+    pub fn aggregate_udf() -> AggregateUDF {
+        AggregateUDF::new(
+            Self::name(),
+            &Signature::new(
+                TypeSignature::Exact(vec![DataType::Binary]),
+                Volatility::Immutable,
+            ),
+            &(Arc::new(|_: &[DataType]| Ok(Arc::new(DataType::Binary))) as _),
+            &(Arc::new(|_: &DataType| Ok(Box::<Self>::default() as _)) as _),
+            &(Arc::new(|_: &DataType| Ok(Arc::new(vec![DataType::Binary]))) as _),
+        )
+    }
+
+    fn merge(&mut self, array: &ArrayRef) -> Result<(), DataFusionError> {
+        let histograms = array
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "{}: expect Binary as histogram array's type, found {}",
+                    Self::name(),
+                    array.data_type()
+                ))
+            })?;
+
+        for bytes in histograms.iter().flatten() {
+            let histogram = NativeHistogram::decode(bytes).map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "{}: failed to decode native histogram: {}",
+                    Self::name(),
+                    e
+                ))
+            })?;
+            self.sum = Some(match &self.sum {
+                Some(sum) => sum.add(&histogram),
+                None => histogram,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Accumulator for NativeHistogramSum {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<(), DataFusionError> {
+        self.merge(&values[0])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<(), DataFusionError> {
+        self.merge(&states[0])
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>, DataFusionError> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue, DataFusionError> {
+        Ok(ScalarValue::Binary(
+            self.sum.as_ref().map(|sum| sum.encode_to_vec()),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.sum.as_ref().map_or(0, |sum| sum.encoded_len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::native_histogram::BucketSpan;
+
+    use super::*;
+
+    #[test]
+    fn sum_native_histograms() {
+        let histogram = NativeHistogram {
+            count: 3.0,
+            sum: 3.0,
+            schema: 0,
+            positive_spans: vec![BucketSpan {
+                offset: 0,
+                length: 2,
+            }],
+            positive_counts: vec![1.0, 2.0],
+            ..Default::default()
+        }
+        .encode_to_vec();
+        let input: ArrayRef = Arc::new(BinaryArray::from(vec![
+            Some(histogram.as_slice()),
+            None,
+            Some(histogram.as_slice()),
+        ]));
+
+        let mut accumulator = NativeHistogramSum::default();
+        accumulator.update_batch(&[input]).unwrap();
+        let state = accumulator.state().unwrap()[0].to_array();
+        let mut merged = NativeHistogramSum::default();
+        merged.merge_batch(&[state]).unwrap();
+
+        let ScalarValue::Binary(Some(bytes)) = merged.evaluate().unwrap() else {
+            unreachable!()
+        };
+        let sum = NativeHistogram::decode(bytes.as_slice()).unwrap();
+        assert_eq!(6.0, sum.count);
+        assert_eq!(vec![2.0, 4.0], sum.positive_counts);
+    }
+}
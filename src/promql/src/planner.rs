@@ -15,14 +15,16 @@
 use std::collections::{BTreeSet, HashSet, VecDeque};
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::UNIX_EPOCH;
+use std::time::{Duration, UNIX_EPOCH};
 
 use async_recursion::async_recursion;
 use catalog::table_source::DfTableSourceProvider;
-use common_query::prelude::GREPTIME_VALUE;
+use common_query::prelude::{GREPTIME_HISTOGRAM, GREPTIME_VALUE};
 use datafusion::common::{DFSchemaRef, OwnedTableReference, Result as DfResult};
 use datafusion::datasource::DefaultTableSource;
-use datafusion::logical_expr::expr::{AggregateFunction, Alias, ScalarFunction, ScalarUDF};
+use datafusion::logical_expr::expr::{
+    AggregateFunction, AggregateUDF, Alias, ScalarFunction, ScalarUDF,
+};
 use datafusion::logical_expr::expr_rewriter::normalize_cols;
 use datafusion::logical_expr::{
     AggregateFunction as AggregateFunctionEnum, BinaryExpr, BuiltinScalarFunction, Cast, Extension,
@@ -33,10 +35,10 @@ use datafusion::prelude as df_prelude;
 use datafusion::prelude::{Column, Expr as DfExpr, JoinType};
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::TableReference;
-use datatypes::arrow::datatypes::DataType as ArrowDataType;
+use datatypes::arrow::datatypes::{DataType as ArrowDataType, TimeUnit as ArrowTimeUnit};
 use promql_parser::label::{MatchOp, Matcher, Matchers, METRIC_NAME};
 use promql_parser::parser::{
-    token, AggregateExpr, BinModifier, BinaryExpr as PromBinaryExpr, Call, EvalStmt,
+    token, AggregateExpr, AtModifier, BinModifier, BinaryExpr as PromBinaryExpr, Call, EvalStmt,
     Expr as PromExpr, Function, FunctionArgs as PromFunctionArgs, LabelModifier, MatrixSelector,
     NumberLiteral, Offset, ParenExpr, StringLiteral, SubqueryExpr, TokenType, UnaryExpr,
     VectorMatchCardinality, VectorSelector,
@@ -58,8 +60,10 @@ use crate::extension_plan::{
 };
 use crate::functions::{
     AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, HoltWinters, IDelta,
-    Increase, LastOverTime, MaxOverTime, MinOverTime, PredictLinear, PresentOverTime,
-    QuantileOverTime, Rate, Resets, StddevOverTime, StdvarOverTime, SumOverTime,
+    Increase, LastOverTime, MaxOverTime, MinOverTime, NativeHistogramDelta,
+    NativeHistogramIncrease, NativeHistogramQuantile, NativeHistogramRate, NativeHistogramSum,
+    PredictLinear, PresentOverTime, QuantileOverTime, Rate, Resets, StddevOverTime, StdvarOverTime,
+    SumOverTime,
 };
 
 /// `time()` function in PromQL.
@@ -99,6 +103,8 @@ struct PromPlannerContext {
     schema_name: Option<String>,
     /// The range in millisecond of range selector. None if there is no range selector.
     range: Option<Millisecond>,
+    /// Whether field columns hold native histograms.
+    native_histogram: bool,
 }
 
 impl PromPlannerContext {
@@ -121,6 +127,7 @@ impl PromPlannerContext {
         self.field_column_matcher = None;
         self.schema_name = None;
         self.range = None;
+        self.native_histogram = false;
     }
 
     /// Reset table name and schema to empty
@@ -379,10 +386,16 @@ impl PromPlanner {
                 }
             }
             PromExpr::Paren(ParenExpr { expr }) => self.prom_expr_to_plan(*expr.clone()).await?,
-            PromExpr::Subquery(SubqueryExpr { .. }) => UnsupportedExprSnafu {
-                name: "Prom Subquery",
+            PromExpr::Subquery(SubqueryExpr {
+                expr,
+                offset,
+                range,
+                step,
+                ..
+            }) => {
+                self.subquery_to_plan(expr, offset, range, step.as_ref())
+                    .await?
             }
-            .fail()?,
             PromExpr::NumberLiteral(NumberLiteral { val }) => {
                 self.ctx.time_index_column = Some(DEFAULT_TIME_INDEX_COLUMN.to_string());
                 self.ctx.field_columns = vec![DEFAULT_FIELD_COLUMN.to_string()];
@@ -423,11 +436,18 @@ impl PromPlanner {
                     ),
                 })
             }
+            PromExpr::VectorSelector(VectorSelector { at: Some(at), .. }) => {
+                let mut selector = prom_expr.clone();
+                if let PromExpr::VectorSelector(vs) = &mut selector {
+                    vs.at = None;
+                }
+                self.plan_at(at, selector).await?
+            }
             PromExpr::VectorSelector(VectorSelector {
                 name,
                 offset,
                 matchers,
-                at: _,
+                at: None,
             }) => {
                 let matchers = self.preprocess_label_matchers(matchers, name)?;
                 self.setup_context().await?;
@@ -486,6 +506,23 @@ impl PromPlanner {
                     node: Arc::new(manipulate),
                 })
             }
+            PromExpr::Call(Call { func, args }) if Self::call_at_modifier(args).is_some() => {
+                // Safety: checked in the guard
+                let at = Self::call_at_modifier(args).unwrap();
+                let mut args = args.clone();
+                for arg in args.args.iter_mut() {
+                    match arg.as_mut() {
+                        PromExpr::MatrixSelector(MatrixSelector { vs, .. }) => vs.at = None,
+                        PromExpr::Subquery(subquery) => subquery.at = None,
+                        _ => {}
+                    }
+                }
+                let call = PromExpr::Call(Call {
+                    func: func.clone(),
+                    args,
+                });
+                self.plan_at(&at, call).await?
+            }
             PromExpr::Call(Call { func, args }) => {
                 // some special functions that are not expression but a plan
                 match func.name {
@@ -565,6 +602,153 @@ impl PromPlanner {
         Ok(res)
     }
 
+    /// Returns the `@` modifier of the range vector argument of a function call, if any.
+    fn call_at_modifier(args: &PromFunctionArgs) -> Option<AtModifier> {
+        args.args.iter().find_map(|arg| match arg.as_ref() {
+            PromExpr::MatrixSelector(MatrixSelector { vs, .. }) => vs.at.clone(),
+            PromExpr::Subquery(SubqueryExpr { at, .. }) => at.clone(),
+            _ => None,
+        })
+    }
+
+    /// Plan an expression with `@` modifier.
+    ///
+    /// The expression is evaluated only once at the timestamp of `at`. The result is
+    /// then broadcasted to every evaluation step of the query.
+    async fn plan_at(&mut self, at: &AtModifier, expr: PromExpr) -> Result<LogicalPlan> {
+        let at_ms = match at {
+            AtModifier::Start => self.ctx.start,
+            AtModifier::End => self.ctx.end,
+            AtModifier::At(time) => match time.duration_since(UNIX_EPOCH) {
+                Ok(duration) => duration.as_millis() as Millisecond,
+                Err(e) => -(e.duration().as_millis() as Millisecond),
+            },
+        };
+
+        let outer = (self.ctx.start, self.ctx.end);
+        (self.ctx.start, self.ctx.end) = (at_ms, at_ms);
+        let input = self.prom_expr_to_plan(expr).await;
+        (self.ctx.start, self.ctx.end) = outer;
+        let input = input?;
+
+        let time_index =
+            self.ctx
+                .time_index_column
+                .clone()
+                .with_context(|| TimeIndexNotFoundSnafu {
+                    table: self.ctx.table_name.clone().unwrap_or_default(),
+                })?;
+        let other_columns = input
+            .schema()
+            .fields()
+            .iter()
+            .filter(|field| field.name() != &time_index)
+            .map(|field| DfExpr::Column(Column::from_name(field.name())))
+            .collect::<Vec<_>>();
+        let steps = LogicalPlan::Extension(Extension {
+            node: Arc::new(
+                EmptyMetric::new(
+                    self.ctx.start,
+                    self.ctx.end,
+                    self.ctx.interval,
+                    time_index.clone(),
+                    DEFAULT_FIELD_COLUMN.to_string(),
+                    None,
+                )
+                .context(DataFusionPlanningSnafu)?,
+            ),
+        });
+
+        let mut output_columns = vec![DfExpr::Column(Column::from_name(time_index))];
+        output_columns.extend(other_columns.iter().cloned());
+        LogicalPlanBuilder::from(input)
+            .project(other_columns)
+            .context(DataFusionPlanningSnafu)?
+            .cross_join(steps)
+            .context(DataFusionPlanningSnafu)?
+            .project(output_columns)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Plan a subquery like `<expr>[<range>:<step>] offset <offset>`.
+    ///
+    /// The inner expression is evaluated as a range query at the resolution of `step`
+    /// (or the query's interval if omitted), covering `range` before the outer start.
+    /// Its result is then divided by series and manipulated into range vectors as
+    /// a matrix selector would do.
+    async fn subquery_to_plan(
+        &mut self,
+        expr: &PromExpr,
+        offset: &Option<Offset>,
+        range: &Duration,
+        step: Option<&Duration>,
+    ) -> Result<LogicalPlan> {
+        ensure!(!range.is_zero(), ZeroRangeSelectorSnafu);
+        let range_ms = range.as_millis() as Millisecond;
+        let offset_ms = match offset {
+            Some(Offset::Pos(duration)) => duration.as_millis() as Millisecond,
+            Some(Offset::Neg(duration)) => -(duration.as_millis() as Millisecond),
+            None => 0,
+        };
+        let step_ms = match step {
+            Some(step) if !step.is_zero() => step.as_millis() as Millisecond,
+            _ => self.ctx.interval,
+        };
+
+        // evaluate the inner expression at the subquery's resolution. Like Prometheus,
+        // the evaluation timestamps are aligned to multiples of step.
+        let outer = (self.ctx.start, self.ctx.end, self.ctx.interval);
+        let inner_start = self.ctx.start - offset_ms - range_ms;
+        self.ctx.start = (inner_start + step_ms - 1).div_euclid(step_ms) * step_ms;
+        self.ctx.end = self.ctx.end - offset_ms;
+        self.ctx.interval = step_ms;
+        let inner = self.prom_expr_to_plan(expr.clone()).await;
+        (self.ctx.start, self.ctx.end, self.ctx.interval) = outer;
+        let inner = inner?;
+
+        let time_index =
+            self.ctx
+                .time_index_column
+                .clone()
+                .with_context(|| TimeIndexNotFoundSnafu {
+                    table: self.ctx.table_name.clone().unwrap_or_default(),
+                })?;
+        self.ctx.range = Some(range_ms);
+
+        let sort_plan = LogicalPlanBuilder::from(inner)
+            .sort(self.create_tag_and_time_index_column_sort_exprs()?)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+        let divide_plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(SeriesDivide::new(self.ctx.tag_columns.clone(), sort_plan)),
+        });
+        let normalize = LogicalPlan::Extension(Extension {
+            node: Arc::new(SeriesNormalize::new(
+                offset_ms,
+                time_index.clone(),
+                true,
+                divide_plan,
+            )),
+        });
+        let manipulate = RangeManipulate::new(
+            self.ctx.start,
+            self.ctx.end,
+            self.ctx.interval,
+            range_ms,
+            time_index,
+            self.ctx.field_columns.clone(),
+            normalize,
+        )
+        .context(DataFusionPlanningSnafu)?;
+
+        Ok(LogicalPlan::Extension(Extension {
+            node: Arc::new(manipulate),
+        }))
+    }
+
     /// Extract metric name from `__name__` matcher and set it into [PromPlannerContext].
     /// Returns a new [Matchers] that doesn't contain metric name matcher.
     ///
@@ -891,13 +1075,51 @@ impl PromPlanner {
             .await
             .context(CatalogSnafu)?;
         // Safety: `scan_filters` is not empty.
-        let result = LogicalPlanBuilder::scan(table_ref, provider, None)
+        let mut builder = LogicalPlanBuilder::scan(table_ref.clone(), provider, None)
             .context(DataFusionPlanningSnafu)?
             .filter(conjunction(filter).unwrap())
-            .context(DataFusionPlanningSnafu)?
-            .build()
             .context(DataFusionPlanningSnafu)?;
-        Ok(result)
+
+        // PromQL plans work on millisecond timestamps. Cast the time index if the
+        // table is defined with another precision (e.g. nanosecond).
+        let time_index =
+            self.ctx
+                .time_index_column
+                .clone()
+                .with_context(|| TimeIndexNotFoundSnafu {
+                    table: table_ref.to_quoted_string(),
+                })?;
+        let time_index_type = builder
+            .schema()
+            .field_with_unqualified_name(&time_index)
+            .context(DataFusionPlanningSnafu)?
+            .data_type()
+            .clone();
+        if !matches!(
+            time_index_type,
+            ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, _)
+        ) {
+            let exprs = builder
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| {
+                    let col = DfExpr::Column(field.qualified_column());
+                    if field.name() == &time_index {
+                        DfExpr::Cast(Cast {
+                            expr: Box::new(col),
+                            data_type: ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, None),
+                        })
+                        .alias(&time_index)
+                    } else {
+                        col
+                    }
+                })
+                .collect::<Vec<_>>();
+            builder = builder.project(exprs).context(DataFusionPlanningSnafu)?;
+        }
+
+        builder.build().context(DataFusionPlanningSnafu)
     }
 
     /// Setup [PromPlannerContext]'s state fields.
@@ -929,13 +1151,19 @@ impl PromPlanner {
         self.ctx.time_index_column = Some(time_index);
 
         // set values columns
-        let values = table
+        let values: Vec<_> = table
             .table_info()
             .meta
             .field_column_names()
             .cloned()
             .collect();
-        self.ctx.field_columns = values;
+        // native histograms are computed separately from float values
+        self.ctx.native_histogram = values.iter().any(|c| c == GREPTIME_HISTOGRAM);
+        self.ctx.field_columns = if self.ctx.native_histogram {
+            vec![GREPTIME_HISTOGRAM.to_string()]
+        } else {
+            values
+        };
 
         // set primary key (tag) columns
         let tags = table
@@ -998,6 +1226,21 @@ impl PromPlanner {
         let field_column_pos = 0;
         let mut exprs = Vec::with_capacity(self.ctx.field_columns.len());
         let scalar_func = match func.name {
+            _ if self.ctx.native_histogram => {
+                let range = self.ctx.range.context(ExpectRangeSelectorSnafu)?;
+                let fun = match func.name {
+                    "increase" => NativeHistogramIncrease::scalar_udf(range),
+                    "rate" => NativeHistogramRate::scalar_udf(range),
+                    "delta" => NativeHistogramDelta::scalar_udf(range),
+                    _ => {
+                        return UnsupportedExprSnafu {
+                            name: format!("{} over native histograms", func.name),
+                        }
+                        .fail()
+                    }
+                };
+                ScalarFunc::ExtrapolateUdf(fun)
+            }
             "increase" => ScalarFunc::ExtrapolateUdf(Increase::scalar_udf(
                 self.ctx.range.context(ExpectRangeSelectorSnafu)?,
             )),
@@ -1274,6 +1517,10 @@ impl PromPlanner {
         op: TokenType,
         input_plan: &LogicalPlan,
     ) -> Result<Vec<DfExpr>> {
+        if self.ctx.native_histogram {
+            return self.create_native_histogram_aggregate_exprs(op, input_plan);
+        }
+
         let aggr = match op.id() {
             token::T_SUM => AggregateFunctionEnum::Sum,
             token::T_AVG => AggregateFunctionEnum::Avg,
@@ -1320,6 +1567,49 @@ impl PromPlanner {
         Ok(exprs)
     }
 
+    /// Create aggregate exprs over native histograms. Only `sum` is supported.
+    ///
+    /// # Side effect
+    ///
+    /// This method will update value columns in context like [Self::create_aggregate_exprs].
+    fn create_native_histogram_aggregate_exprs(
+        &mut self,
+        op: TokenType,
+        input_plan: &LogicalPlan,
+    ) -> Result<Vec<DfExpr>> {
+        if op.id() != token::T_SUM {
+            return UnsupportedExprSnafu {
+                name: format!("{op:?} over native histograms"),
+            }
+            .fail();
+        }
+
+        let fun = Arc::new(NativeHistogramSum::aggregate_udf());
+        let exprs: Vec<DfExpr> = self
+            .ctx
+            .field_columns
+            .iter()
+            .map(|col| {
+                DfExpr::AggregateUDF(AggregateUDF {
+                    fun: fun.clone(),
+                    args: vec![DfExpr::Column(Column::from_name(col))],
+                    filter: None,
+                    order_by: None,
+                })
+            })
+            .collect();
+
+        let normalized_exprs =
+            normalize_cols(exprs.iter().cloned(), input_plan).context(DataFusionPlanningSnafu)?;
+        self.ctx.field_columns = normalized_exprs
+            .iter()
+            .map(|expr| expr.display_name())
+            .collect::<DfResult<Vec<_>>>()
+            .context(DataFusionPlanningSnafu)?;
+
+        Ok(exprs)
+    }
+
     /// Create a [SPECIAL_HISTOGRAM_QUANTILE] plan.
     async fn create_histogram_plan(&mut self, args: &PromFunctionArgs) -> Result<LogicalPlan> {
         if args.args.len() != 2 {
@@ -1336,6 +1626,9 @@ impl PromPlanner {
         let input = args.args[1].as_ref().clone();
         let input_plan = self.prom_expr_to_plan(input).await?;
 
+        if self.ctx.native_histogram {
+            return self.create_native_histogram_plan(phi, input_plan);
+        }
         if !self.ctx.has_le_tag() {
             return ColumnNotFoundSnafu {
                 col: LE_COLUMN_NAME.to_string(),
@@ -1373,6 +1666,25 @@ impl PromPlanner {
         }))
     }
 
+    /// Create a [SPECIAL_HISTOGRAM_QUANTILE] plan over native histograms. The
+    /// quantile is computed from each histogram sample directly, so the input
+    /// can be a selector, a range function or an aggregation of histograms.
+    fn create_native_histogram_plan(
+        &mut self,
+        phi: f64,
+        input_plan: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        let fun = Arc::new(NativeHistogramQuantile::scalar_udf(phi));
+        let plan = self.projection_for_each_field_column(input_plan, |col| {
+            Ok(DfExpr::ScalarUDF(ScalarUDF {
+                fun: fun.clone(),
+                args: vec![DfExpr::Column(Column::from_name(col))],
+            }))
+        })?;
+        self.ctx.native_histogram = false;
+        Ok(plan)
+    }
+
     /// Create a [SPECIAL_VECTOR_FUNCTION] plan
     async fn create_vector_plan(&mut self, args: &PromFunctionArgs) -> Result<LogicalPlan> {
         if args.args.len() != 1 {
@@ -2052,6 +2364,21 @@ mod test {
         table_name_tuples: &[(String, String)],
         num_tag: usize,
         num_field: usize,
+    ) -> DfTableSourceProvider {
+        build_test_table_provider_with_ts_type(
+            table_name_tuples,
+            num_tag,
+            num_field,
+            ConcreteDataType::timestamp_millisecond_datatype(),
+        )
+        .await
+    }
+
+    async fn build_test_table_provider_with_ts_type(
+        table_name_tuples: &[(String, String)],
+        num_tag: usize,
+        num_field: usize,
+        ts_type: ConcreteDataType,
     ) -> DfTableSourceProvider {
         let catalog_list = MemoryCatalogManager::with_default_setup();
         for (schema_name, table_name) in table_name_tuples {
@@ -2064,12 +2391,8 @@ mod test {
                 ));
             }
             columns.push(
-                ColumnSchema::new(
-                    "timestamp".to_string(),
-                    ConcreteDataType::timestamp_millisecond_datatype(),
-                    false,
-                )
-                .with_time_index(true),
+                ColumnSchema::new("timestamp".to_string(), ts_type.clone(), false)
+                    .with_time_index(true),
             );
             for i in 0..num_field {
                 columns.push(ColumnSchema::new(
@@ -2159,11 +2482,183 @@ mod test {
         assert_eq!(plan.display_indent_schema().to_string(), expected);
     }
 
+    #[tokio::test]
+    async fn subquery() {
+        let prom_expr = parser::parse("max_over_time(rate(some_metric[5m])[30m:1m])").unwrap();
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let table_provider = build_test_table_provider(
+            &[(DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string())],
+            1,
+            1,
+        )
+        .await;
+        let plan = PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+            .await
+            .unwrap()
+            .display_indent()
+            .to_string();
+
+        // the outer range vector is built over the subquery output
+        assert!(plan.contains(
+            "PromRangeManipulate: req range=[0..100000000], interval=[5000], eval range=[1800000]"
+        ));
+        assert!(plan.contains(
+            "PromSeriesNormalize: offset=[0], time index=[timestamp], filter NaN: [true]"
+        ));
+        // the inner query is evaluated at the resolution of step
+        assert!(plan.contains(
+            "PromRangeManipulate: req range=[-1800000..100000000], interval=[60000], eval range=[300000]"
+        ));
+    }
+
+    #[tokio::test]
+    async fn subquery_with_offset() {
+        let prom_expr = parser::parse("max_over_time(some_metric[30m:1m] offset 1h)").unwrap();
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let table_provider = build_test_table_provider(
+            &[(DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string())],
+            1,
+            1,
+        )
+        .await;
+        let plan = PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+            .await
+            .unwrap()
+            .display_indent()
+            .to_string();
+
+        assert!(plan.contains(
+            "PromSeriesNormalize: offset=[3600000], time index=[timestamp], filter NaN: [true]"
+        ));
+        assert!(plan.contains(
+            "PromInstantManipulate: range=[-5400000..96400000], lookback=[1000], interval=[60000]"
+        ));
+    }
+
+    async fn plan_for_test(promql: &str) -> LogicalPlan {
+        let prom_expr = parser::parse(promql).unwrap();
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let table_provider = build_test_table_provider(
+            &[(DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string())],
+            1,
+            1,
+        )
+        .await;
+        PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn at_modifier() {
+        let plan = plan_for_test("some_metric @ 100")
+            .await
+            .display_indent()
+            .to_string();
+
+        // evaluated once at the `@` timestamp
+        assert!(plan.contains(
+            "PromInstantManipulate: range=[100000..100000], lookback=[1000], interval=[5000]"
+        ));
+        // then broadcasted to all steps
+        assert!(plan.contains("CrossJoin"));
+        assert!(plan.contains("EmptyMetric: range=[0..100000000], interval=[5000]"));
+    }
+
+    #[tokio::test]
+    async fn at_modifier_in_range_function() {
+        let plan = plan_for_test("rate(some_metric[5m] @ end())")
+            .await
+            .display_indent()
+            .to_string();
+
+        assert!(plan.contains(
+            "PromRangeManipulate: req range=[100000000..100000000], interval=[5000], eval range=[300000]"
+        ));
+        assert!(plan.contains("EmptyMetric: range=[0..100000000], interval=[5000]"));
+    }
+
+    #[tokio::test]
+    async fn negative_offset() {
+        let plan = plan_for_test("some_metric offset -1m")
+            .await
+            .display_indent()
+            .to_string();
+
+        assert!(plan.contains("PromSeriesNormalize: offset=[-60000]"));
+        assert!(plan.contains("some_metric.timestamp >= TimestampMillisecond(59000, None)"));
+    }
+
     #[tokio::test]
     async fn single_abs() {
         do_single_instant_function_call("abs", "abs").await;
     }
 
+    #[tokio::test]
+    async fn nanosecond_time_index() {
+        let prom_expr = parser::parse("some_metric{tag_0!=\"bar\"}").unwrap();
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let table_provider = build_test_table_provider_with_ts_type(
+            &[(DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string())],
+            1,
+            1,
+            ConcreteDataType::timestamp_nanosecond_datatype(),
+        )
+        .await;
+        let plan = PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+            .await
+            .unwrap();
+
+        let time_index = plan
+            .schema()
+            .field_with_unqualified_name("timestamp")
+            .unwrap();
+        assert_eq!(
+            time_index.data_type(),
+            &ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, None)
+        );
+        assert!(plan
+            .display_indent()
+            .to_string()
+            .contains("CAST(some_metric.timestamp AS Timestamp(Millisecond, None)) AS timestamp"));
+    }
+
     #[tokio::test]
     #[should_panic]
     async fn single_absent() {
@@ -2880,4 +3375,91 @@ mod test {
             assert!(plan.is_err(), "query: {:?}", query);
         }
     }
+
+    async fn native_histogram_plan_for_test(promql: &str) -> Result<LogicalPlan> {
+        let prom_expr = parser::parse(promql).unwrap();
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let catalog_list = MemoryCatalogManager::with_default_setup();
+        let columns = vec![
+            ColumnSchema::new("tag_0", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                "timestamp",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+            ColumnSchema::new(
+                GREPTIME_HISTOGRAM,
+                ConcreteDataType::binary_datatype(),
+                true,
+            ),
+        ];
+        let table_meta = TableMetaBuilder::default()
+            .schema(Arc::new(Schema::new(columns)))
+            .primary_key_indices(vec![0])
+            .value_indices(vec![2])
+            .next_column_id(1024)
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::default()
+            .name("some_histogram")
+            .meta(table_meta)
+            .build()
+            .unwrap();
+        assert!(catalog_list
+            .register_table_sync(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: "some_histogram".to_string(),
+                table_id: 1024,
+                table: EmptyTable::from_table_info(&table_info),
+            })
+            .is_ok());
+        let table_provider =
+            DfTableSourceProvider::new(catalog_list, false, QueryContext::arc().as_ref());
+
+        PromPlanner::stmt_to_plan(table_provider, eval_stmt).await
+    }
+
+    #[tokio::test]
+    async fn native_histogram_quantile() {
+        let plan = native_histogram_plan_for_test("histogram_quantile(0.9, some_histogram)")
+            .await
+            .unwrap()
+            .display_indent_schema()
+            .to_string();
+        assert!(plan.contains("prom_native_histogram_quantile(greptime_histogram)"));
+
+        let plan = native_histogram_plan_for_test(
+            "histogram_quantile(0.9, sum by (tag_0) (rate(some_histogram[5m])))",
+        )
+        .await
+        .unwrap()
+        .display_indent_schema()
+        .to_string();
+        assert!(plan.contains("prom_native_histogram_rate"));
+        assert!(plan.contains("prom_native_histogram_sum"));
+        assert!(plan.contains("prom_native_histogram_quantile"));
+    }
+
+    #[tokio::test]
+    async fn native_histogram_unsupported() {
+        for query in [
+            "avg(some_histogram)",
+            "avg_over_time(some_histogram[5m])",
+            "histogram_quantile(0.9, avg(rate(some_histogram[5m])))",
+        ] {
+            let plan = native_histogram_plan_for_test(query).await;
+            assert!(plan.is_err(), "query: {:?}", query);
+        }
+    }
 }
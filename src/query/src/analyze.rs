@@ -0,0 +1,311 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Customized `ANALYZE` plan that also reports the metrics of the plans
+//! executed on datanodes.
+
+use std::any::Any;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arrow::array::StringBuilder;
+use arrow::record_batch::RecordBatch;
+use arrow_schema::SchemaRef;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchMetrics;
+use datafusion::physical_plan::display::DisplayableExecutionPlan;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    accept, DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanVisitor, Partitioning,
+    SendableRecordBatchStream,
+};
+use datafusion_common::{DataFusionError, Result, Statistics};
+use datafusion_physical_expr::PhysicalSortExpr;
+use futures::{StreamExt, TryStreamExt};
+use store_api::storage::RegionId;
+
+use crate::dist_plan::MergeScanExec;
+
+/// Executes the input plan like DataFusion's `AnalyzeExec`, and additionally
+/// outputs the per-operator metrics collected from the regions scanned by
+/// [MergeScanExec].
+///
+/// Metrics of regions that executed the same plan are summed up. The verbose
+/// output also lists the metrics of each region.
+///
+/// The output schema is the same as `AnalyzeExec`'s, i.e. `plan_type` and `plan`.
+#[derive(Debug)]
+pub struct DistAnalyzeExec {
+    input: Arc<dyn ExecutionPlan>,
+    schema: SchemaRef,
+    verbose: bool,
+}
+
+impl DistAnalyzeExec {
+    pub fn new(input: Arc<dyn ExecutionPlan>, schema: SchemaRef, verbose: bool) -> Self {
+        Self {
+            input,
+            schema,
+            verbose,
+        }
+    }
+}
+
+impl DisplayAs for DistAnalyzeExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "DistAnalyzeExec")
+    }
+}
+
+impl ExecutionPlan for DistAnalyzeExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Internal(format!(
+                "DistAnalyzeExec expects 1 child, got {}",
+                children.len()
+            )));
+        }
+        Ok(Arc::new(Self::new(
+            children.remove(0),
+            self.schema.clone(),
+            self.verbose,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "DistAnalyzeExec invalid partition. Expected 0, got {partition}"
+            )));
+        }
+
+        let input = self.input.clone();
+        let schema = self.schema.clone();
+        let verbose = self.verbose;
+        let output = futures::stream::once(async move {
+            let start = Instant::now();
+            let partition_count = input.output_partitioning().partition_count();
+            let counts = (0..partition_count).map(|partition| {
+                let input = input.clone();
+                let context = context.clone();
+                async move {
+                    input
+                        .execute(partition, context)?
+                        .try_fold(0, |rows, batch| async move { Ok(rows + batch.num_rows()) })
+                        .await
+                }
+            });
+            let total_rows = futures::future::try_join_all(counts).await?.iter().sum();
+
+            create_output_batch(&input, schema, verbose, total_rows, start.elapsed())
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            output.boxed(),
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Builds the output of `ANALYZE`, one row for the frontend stage and one row for
+/// each distinct plan executed by the regions of [MergeScanExec]s in `input`.
+fn create_output_batch(
+    input: &Arc<dyn ExecutionPlan>,
+    schema: SchemaRef,
+    verbose: bool,
+    total_rows: usize,
+    duration: Duration,
+) -> Result<RecordBatch> {
+    let mut plan_type = StringBuilder::new();
+    let mut plan = StringBuilder::new();
+
+    plan_type.append_value("Plan with Metrics");
+    plan.append_value(
+        DisplayableExecutionPlan::with_metrics(input.as_ref())
+            .indent(verbose)
+            .to_string(),
+    );
+
+    let mut visitor = MergeScanCollector::default();
+    accept(input.as_ref(), &mut visitor)?;
+    for (region_ids, metrics) in aggregate_region_metrics(&visitor.sub_stage_metrics) {
+        plan_type.append_value(format!(
+            "Plan with Metrics of {} Region(s)",
+            region_ids.len()
+        ));
+        plan.append_value(metrics.to_string());
+    }
+
+    if verbose {
+        for (region_id, metrics) in &visitor.sub_stage_metrics {
+            plan_type.append_value(format!("Region {region_id} Plan with Metrics"));
+            plan.append_value(metrics.to_string());
+        }
+
+        plan_type.append_value("Output Rows");
+        plan.append_value(total_rows.to_string());
+
+        plan_type.append_value("Duration");
+        plan.append_value(format!("{duration:?}"));
+    }
+
+    RecordBatch::try_new(
+        schema,
+        vec![Arc::new(plan_type.finish()), Arc::new(plan.finish())],
+    )
+    .map_err(DataFusionError::ArrowError)
+}
+
+/// Groups regions that executed the same plan, and sums the metrics of each operator
+/// of the plan over the regions of the group. Groups are in the order of their first
+/// region.
+fn aggregate_region_metrics(
+    sub_stage_metrics: &[(RegionId, RecordBatchMetrics)],
+) -> Vec<(Vec<RegionId>, RecordBatchMetrics)> {
+    let mut groups: Vec<(Vec<RegionId>, RecordBatchMetrics)> = Vec::new();
+    for (region_id, metrics) in sub_stage_metrics {
+        let same_plan = |aggregated: &RecordBatchMetrics| {
+            aggregated.plan_metrics.len() == metrics.plan_metrics.len()
+                && aggregated
+                    .plan_metrics
+                    .iter()
+                    .zip(&metrics.plan_metrics)
+                    .all(|(a, b)| a.plan == b.plan && a.level == b.level)
+        };
+        let Some((region_ids, aggregated)) = groups
+            .iter_mut()
+            .find(|(_, aggregated)| same_plan(aggregated))
+        else {
+            groups.push((vec![*region_id], metrics.clone()));
+            continue;
+        };
+
+        region_ids.push(*region_id);
+        aggregated.elapsed_compute += metrics.elapsed_compute;
+        aggregated.memory_usage += metrics.memory_usage;
+        for (operator, region_operator) in aggregated
+            .plan_metrics
+            .iter_mut()
+            .zip(&metrics.plan_metrics)
+        {
+            for (name, value) in &region_operator.metrics {
+                match operator.metrics.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, total)) => *total += value,
+                    None => operator.metrics.push((name.clone(), *value)),
+                }
+            }
+        }
+    }
+    groups
+}
+
+/// Collects the sub stage metrics of all [MergeScanExec] in a plan tree.
+#[derive(Default)]
+struct MergeScanCollector {
+    sub_stage_metrics: Vec<(RegionId, RecordBatchMetrics)>,
+}
+
+impl ExecutionPlanVisitor for MergeScanCollector {
+    type Error = DataFusionError;
+
+    fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool> {
+        if let Some(merge_scan) = plan.as_any().downcast_ref::<MergeScanExec>() {
+            self.sub_stage_metrics
+                .extend(merge_scan.sub_stage_metrics());
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_recordbatch::adapter::PlanMetrics;
+
+    use super::*;
+
+    fn region_metrics(scan: &str, rows: usize) -> RecordBatchMetrics {
+        RecordBatchMetrics {
+            elapsed_compute: 10,
+            memory_usage: 100,
+            plan_metrics: vec![
+                PlanMetrics {
+                    plan: "ProjectionExec".to_string(),
+                    level: 0,
+                    metrics: vec![("output_rows".to_string(), rows)],
+                },
+                PlanMetrics {
+                    plan: scan.to_string(),
+                    level: 1,
+                    metrics: vec![("output_rows".to_string(), rows)],
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_aggregate_region_metrics() {
+        let sub_stage_metrics = vec![
+            (RegionId::new(1, 0), region_metrics("SeqScan", 3)),
+            (RegionId::new(1, 1), region_metrics("UnorderedScan", 5)),
+            (RegionId::new(1, 2), region_metrics("SeqScan", 4)),
+        ];
+
+        let groups = aggregate_region_metrics(&sub_stage_metrics);
+        assert_eq!(2, groups.len());
+        assert_eq!(vec![RegionId::new(1, 0), RegionId::new(1, 2)], groups[0].0);
+        assert_eq!(20, groups[0].1.elapsed_compute);
+        assert_eq!(200, groups[0].1.memory_usage);
+        assert_eq!(
+            vec![("output_rows".to_string(), 7)],
+            groups[0].1.plan_metrics[1].metrics
+        );
+        assert_eq!(vec![RegionId::new(1, 1)], groups[1].0);
+        assert_eq!(
+            vec![("output_rows".to_string(), 5)],
+            groups[1].1.plan_metrics[1].metrics
+        );
+    }
+}
@@ -29,14 +29,16 @@ use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlan, PhysicalPlanAdapter};
 use common_query::prelude::ScalarUdf;
 use common_query::{Output, OutputData, OutputMeta};
-use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::adapter::{CollectPlanMetrics, RecordBatchStreamAdapter};
 use common_recordbatch::{EmptyRecordBatchStream, SendableRecordBatchStream};
 use common_telemetry::tracing;
 use datafusion::physical_plan::analyze::AnalyzeExec;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion::physical_plan::ExecutionPlan;
-use datafusion_common::ResolvedTableReference;
-use datafusion_expr::{DmlStatement, LogicalPlan as DfLogicalPlan, WriteOp};
+use datafusion_common::{Column, ResolvedTableReference};
+use datafusion_expr::{
+    DmlStatement, Expr as DfExpr, LogicalPlan as DfLogicalPlan, LogicalPlanBuilder, WriteOp,
+};
 use datatypes::prelude::VectorRef;
 use futures_util::StreamExt;
 use session::context::QueryContextRef;
@@ -44,6 +46,7 @@ use snafu::{ensure, OptionExt, ResultExt};
 use table::requests::{DeleteRequest, InsertRequest};
 use table::TableRef;
 
+use crate::analyze::DistAnalyzeExec;
 use crate::dataframe::DataFrame;
 pub use crate::datafusion::planner::DfContextProviderAdapter;
 use crate::error::{
@@ -118,8 +121,12 @@ impl DatafusionQueryEngine {
         let table_name = dml.table_name.resolve(default_catalog, default_schema);
         let table = self.find_table(&table_name).await?;
 
+        let input = match dml.op {
+            WriteOp::Delete => Self::project_delete_keys(&table, (*dml.input).clone())?,
+            _ => (*dml.input).clone(),
+        };
         let output = self
-            .exec_query_plan(LogicalPlan::DfPlan((*dml.input).clone()), query_ctx.clone())
+            .exec_query_plan(LogicalPlan::DfPlan(input), query_ctx.clone())
             .await?;
         let mut stream = match output.data {
             OutputData::RecordBatches(batches) => batches.as_stream(),
@@ -160,6 +167,33 @@ impl DatafusionQueryEngine {
         ))
     }
 
+    /// Projects the primary key and time index columns from the `input` of a DELETE
+    /// statement.
+    ///
+    /// Deleting rows only requires their keys, so the scan can skip field columns that
+    /// the predicate doesn't reference.
+    fn project_delete_keys(table: &TableRef, input: DfLogicalPlan) -> Result<DfLogicalPlan> {
+        let table_info = table.table_info();
+        let table_schema = table.schema();
+        let ts_column = table_schema
+            .timestamp_column()
+            .map(|x| &x.name)
+            .with_context(|| MissingTimestampColumnSnafu {
+                table_name: table_info.name.clone(),
+            })?;
+        let key_columns = table_info
+            .meta
+            .row_key_column_names()
+            .chain(std::iter::once(ts_column))
+            .map(|name| DfExpr::Column(Column::from_name(name)))
+            .collect::<Vec<_>>();
+
+        LogicalPlanBuilder::from(input)
+            .project(key_columns)
+            .and_then(|builder| builder.build())
+            .context(DataFusionSnafu)
+    }
+
     #[tracing::instrument(skip_all)]
     async fn delete<'a>(
         &self,
@@ -397,7 +431,7 @@ impl PhysicalOptimizer for DatafusionQueryEngine {
             .df_plan();
 
         // skip optimize AnalyzeExec plan
-        let optimized_plan =
+        let optimized_plan: Arc<dyn ExecutionPlan> =
             if let Some(analyze_plan) = df_plan.as_any().downcast_ref::<AnalyzeExec>() {
                 let mut new_plan = analyze_plan.input().clone();
                 for optimizer in state.physical_optimizers() {
@@ -405,9 +439,15 @@ impl PhysicalOptimizer for DatafusionQueryEngine {
                         .optimize(new_plan, config)
                         .context(DataFusionSnafu)?;
                 }
-                Arc::new(analyze_plan.clone())
-                    .with_new_children(vec![new_plan])
-                    .unwrap()
+                // Regions report the metrics of each operator to the analyze plan.
+                let _ = ctx
+                    .query_ctx()
+                    .set_typed_extension(Arc::new(CollectPlanMetrics));
+                Arc::new(DistAnalyzeExec::new(
+                    new_plan,
+                    analyze_plan.schema(),
+                    analyze_plan.verbose(),
+                ))
             } else {
                 let mut new_plan = df_plan;
                 for optimizer in state.physical_optimizers() {
@@ -434,6 +474,10 @@ impl QueryExecutor for DatafusionQueryEngine {
     ) -> Result<SendableRecordBatchStream> {
         let exec_timer = metrics::EXEC_PLAN_ELAPSED.start_timer();
         let task_ctx = ctx.build_task_ctx();
+        let collect_plan_metrics = task_ctx
+            .session_config()
+            .get_extension::<CollectPlanMetrics>()
+            .is_some();
 
         match plan.output_partitioning().partition_count() {
             0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
@@ -443,12 +487,15 @@ impl QueryExecutor for DatafusionQueryEngine {
                     .context(error::ExecutePhysicalPlanSnafu)
                     .map_err(BoxedError::new)
                     .context(QueryExecutionSnafu)?;
+                let plan = plan.clone();
                 let stream = OnDone::new(stream, move || {
                     exec_timer.observe_duration();
+                    metrics::observe_plan_metrics(&plan);
                 });
                 Ok(Box::pin(stream))
             }
             _ => {
+                let origin_plan = plan.clone();
                 let df_plan = Arc::new(DfPhysicalPlanAdapter(plan.clone()));
                 // merge into a single partition
                 let plan = CoalescePartitionsExec::new(df_plan.clone());
@@ -464,8 +511,10 @@ impl QueryExecutor for DatafusionQueryEngine {
                     .map_err(BoxedError::new)
                     .context(QueryExecutionSnafu)?;
                 stream.set_metrics2(df_plan);
+                stream.set_collect_plan_metrics(collect_plan_metrics);
                 let stream = OnDone::new(Box::pin(stream), move || {
                     exec_timer.observe_duration();
+                    metrics::observe_plan_metrics(&origin_plan);
                 });
                 Ok(Box::pin(stream))
             }
@@ -21,6 +21,8 @@ use catalog::table_source::DfTableSourceProvider;
 use common_function::scalars::udf::create_udf;
 use common_query::logical_plan::create_aggregate_function;
 use datafusion::catalog::TableReference;
+use datafusion::datasource::provider_as_source;
+use datafusion::datasource::view::ViewTable;
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::SessionState;
 use datafusion::physical_plan::udaf::AggregateUDF;
@@ -28,15 +30,26 @@ use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::sql::planner::ContextProvider;
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::{DataFusionError, OwnedTableReference};
-use datafusion_expr::{TableSource, WindowUDF};
+use datafusion_expr::{LogicalPlan, TableSource, WindowUDF};
 use datafusion_physical_expr::var_provider::{is_system_variables, VarType};
 use datafusion_sql::parser::Statement as DfStatement;
-use session::context::QueryContextRef;
-use snafu::ResultExt;
+use datafusion_sql::planner::{ParserOptions, SqlToRel};
+use futures::future::BoxFuture;
+use session::context::{QueryContextBuilder, QueryContextRef};
+use snafu::{ensure, ResultExt};
+use sql::dialect::GreptimeDbDialect;
+use sql::parser::{ParseOptions, ParserContext};
+use sql::statements::statement::Statement;
 
-use crate::error::{CatalogSnafu, DataFusionSnafu, Result};
+use crate::error::{
+    CatalogSnafu, DataFusionSnafu, InvalidViewSnafu, PlanSqlSnafu, Result, SqlSnafu,
+};
 use crate::query_engine::QueryEngineState;
 
+/// Views can be defined upon other views, the nesting depth is limited to
+/// prevent endless recursion caused by views referencing each other.
+const MAX_VIEW_DEPTH: usize = 16;
+
 pub struct DfContextProviderAdapter {
     engine_state: Arc<QueryEngineState>,
     session_state: SessionState,
@@ -52,28 +65,49 @@ impl DfContextProviderAdapter {
         df_stmt: Option<&DfStatement>,
         query_ctx: QueryContextRef,
     ) -> Result<Self> {
-        let table_names = if let Some(df_stmt) = df_stmt {
-            session_state
-                .resolve_table_references(df_stmt)
-                .context(DataFusionSnafu)?
-        } else {
-            vec![]
-        };
+        Self::try_new_with_depth(engine_state, session_state, df_stmt, query_ctx, 0).await
+    }
 
-        let mut table_provider = DfTableSourceProvider::new(
-            engine_state.catalog_manager().clone(),
-            engine_state.disallow_cross_catalog_query(),
-            query_ctx.as_ref(),
-        );
+    /// Creates the adapter for a statement inside `view_depth` levels of views.
+    fn try_new_with_depth<'a>(
+        engine_state: Arc<QueryEngineState>,
+        session_state: SessionState,
+        df_stmt: Option<&'a DfStatement>,
+        query_ctx: QueryContextRef,
+        view_depth: usize,
+    ) -> BoxFuture<'a, Result<Self>> {
+        Box::pin(async move {
+            let table_names = if let Some(df_stmt) = df_stmt {
+                session_state
+                    .resolve_table_references(df_stmt)
+                    .context(DataFusionSnafu)?
+            } else {
+                vec![]
+            };
 
-        let tables = resolve_tables(table_names, &mut table_provider).await?;
+            let mut table_provider = DfTableSourceProvider::new(
+                engine_state.catalog_manager().clone(),
+                engine_state.disallow_cross_catalog_query(),
+                query_ctx.as_ref(),
+            );
+
+            let tables = resolve_tables(
+                table_names,
+                &mut table_provider,
+                &engine_state,
+                &session_state,
+                &query_ctx,
+                view_depth,
+            )
+            .await?;
 
-        Ok(Self {
-            engine_state,
-            session_state,
-            tables,
-            table_provider,
-            query_ctx,
+            Ok(Self {
+                engine_state,
+                session_state,
+                tables,
+                table_provider,
+                query_ctx,
+            })
         })
     }
 }
@@ -81,6 +115,10 @@ impl DfContextProviderAdapter {
 async fn resolve_tables(
     table_names: Vec<OwnedTableReference>,
     table_provider: &mut DfTableSourceProvider,
+    engine_state: &Arc<QueryEngineState>,
+    session_state: &SessionState,
+    query_ctx: &QueryContextRef,
+    view_depth: usize,
 ) -> Result<HashMap<String, Arc<dyn TableSource>>> {
     let mut tables = HashMap::with_capacity(table_names.len());
 
@@ -88,19 +126,102 @@ async fn resolve_tables(
         let resolved_name = table_provider
             .resolve_table_ref(table_name.clone())
             .context(CatalogSnafu)?;
+        let (catalog, schema, table) = (
+            resolved_name.catalog.to_string(),
+            resolved_name.schema.to_string(),
+            resolved_name.table.to_string(),
+        );
 
         if let Entry::Vacant(v) = tables.entry(resolved_name.to_string()) {
             // Try our best to resolve the tables here, but we don't return an error if table is not found,
-            // because the table name may be a temporary name of CTE or view, they can't be found until plan
+            // because the table name may be a temporary name of CTE, it can't be found until plan
             // execution.
             if let Ok(table) = table_provider.resolve_table(table_name).await {
                 let _ = v.insert(table);
+            } else if let Some(definition) = engine_state
+                .catalog_manager()
+                .view(&catalog, &schema, &table)
+                .await
+                .context(CatalogSnafu)?
+            {
+                let view_ctx = QueryContextBuilder::default()
+                    .current_catalog(catalog)
+                    .current_schema(schema)
+                    .timezone(query_ctx.timezone())
+                    .build();
+                let view_name = v.key().clone();
+                let view = resolve_view(
+                    engine_state,
+                    session_state,
+                    &view_name,
+                    definition,
+                    view_ctx,
+                    view_depth + 1,
+                )
+                .await?;
+                let _ = v.insert(view);
             }
         }
     }
     Ok(tables)
 }
 
+/// Plans the definition of a view, table names in the definition are resolved
+/// against the catalog and schema of the view rather than the current session.
+async fn resolve_view(
+    engine_state: &Arc<QueryEngineState>,
+    session_state: &SessionState,
+    view_name: &str,
+    definition: String,
+    view_ctx: QueryContextRef,
+    view_depth: usize,
+) -> Result<Arc<dyn TableSource>> {
+    ensure!(
+        view_depth <= MAX_VIEW_DEPTH,
+        InvalidViewSnafu {
+            view: view_name,
+            reason: format!("views are nested more than {MAX_VIEW_DEPTH} levels"),
+        }
+    );
+
+    let mut stmts = ParserContext::create_with_dialect(
+        &definition,
+        &GreptimeDbDialect {},
+        ParseOptions::default(),
+    )
+    .context(SqlSnafu)?;
+    ensure!(
+        stmts.len() == 1 && matches!(stmts[0], Statement::Query(_)),
+        InvalidViewSnafu {
+            view: view_name,
+            reason: "the definition must be a single query",
+        }
+    );
+    let stmt = stmts.remove(0);
+    let df_stmt = (&stmt).try_into().context(SqlSnafu)?;
+
+    let context_provider = DfContextProviderAdapter::try_new_with_depth(
+        engine_state.clone(),
+        session_state.clone(),
+        Some(&df_stmt),
+        view_ctx,
+        view_depth,
+    )
+    .await?;
+
+    let config_options = session_state.config().options();
+    let parser_options = ParserOptions {
+        enable_ident_normalization: config_options.sql_parser.enable_ident_normalization,
+        parse_float_as_decimal: config_options.sql_parser.parse_float_as_decimal,
+    };
+    let plan: LogicalPlan = SqlToRel::new_with_options(&context_provider, parser_options)
+        .statement_to_plan(df_stmt)
+        .context(PlanSqlSnafu)?;
+
+    let view = ViewTable::try_new(plan, Some(definition)).context(DataFusionSnafu)?;
+    Ok(provider_as_source(Arc::new(view)))
+}
+
 impl ContextProvider for DfContextProviderAdapter {
     fn get_table_provider(&self, name: TableReference) -> DfResult<Arc<dyn TableSource>> {
         let table_ref = self.table_provider.resolve_table_ref(name)?;
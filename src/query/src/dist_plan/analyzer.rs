@@ -305,8 +305,12 @@ mod test {
     use datafusion::datasource::DefaultTableSource;
     use datafusion_common::JoinType;
     use datafusion_expr::{avg, col, lit, Expr, LogicalPlanBuilder};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
     use table::table::adapter::DfTableProviderAdapter;
     use table::table::numbers::NumbersTable;
+    use table::test_util::EmptyTable;
 
     use super::*;
 
@@ -363,6 +367,81 @@ mod test {
         assert_eq!(expected, format!("{:?}", result));
     }
 
+    /// Creates a source of a table partitioned by the `host` column.
+    fn partitioned_table_source() -> Arc<DefaultTableSource> {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ]));
+        let table_meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .value_indices(vec![1])
+            .next_column_id(1024)
+            .partition_key_indices(vec![0])
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::default()
+            .name("t")
+            .meta(table_meta)
+            .build()
+            .unwrap();
+        let table = EmptyTable::from_table_info(&table_info);
+        Arc::new(DefaultTableSource::new(Arc::new(
+            DfTableProviderAdapter::new(table),
+        )))
+    }
+
+    #[test]
+    fn transform_partition_wise_aggregator() {
+        let config = ConfigOptions::default();
+
+        // Group keys contain the partition column.
+        let plan =
+            LogicalPlanBuilder::scan_with_filters("t", partitioned_table_source(), None, vec![])
+                .unwrap()
+                .aggregate(vec![col("host"), col("ts")], vec![avg(col("cpu"))])
+                .unwrap()
+                .build()
+                .unwrap();
+        let result = DistPlannerAnalyzer {}.analyze(plan, &config).unwrap();
+        let expected = "MergeScan [is_placeholder=false]";
+        assert_eq!(expected, format!("{:?}", result));
+
+        // Group keys don't contain the partition column.
+        let plan =
+            LogicalPlanBuilder::scan_with_filters("t", partitioned_table_source(), None, vec![])
+                .unwrap()
+                .aggregate(vec![col("ts")], vec![avg(col("cpu"))])
+                .unwrap()
+                .build()
+                .unwrap();
+        let result = DistPlannerAnalyzer {}.analyze(plan, &config).unwrap();
+        let expected = "Aggregate: groupBy=[[t.ts]], aggr=[[AVG(t.cpu)]]\
+            \n  MergeScan [is_placeholder=false]";
+        assert_eq!(expected, format!("{:?}", result));
+
+        // Rows of different partitions may be in the same group of an expression
+        // on the partition column.
+        let plan =
+            LogicalPlanBuilder::scan_with_filters("t", partitioned_table_source(), None, vec![])
+                .unwrap()
+                .aggregate(vec![col("host").is_null()], vec![avg(col("cpu"))])
+                .unwrap()
+                .build()
+                .unwrap();
+        let result = DistPlannerAnalyzer {}.analyze(plan, &config).unwrap();
+        let expected = "Aggregate: groupBy=[[t.host IS NULL]], aggr=[[AVG(t.cpu)]]\
+            \n  MergeScan [is_placeholder=false]";
+        assert_eq!(expected, format!("{:?}", result));
+    }
+
     #[test]
     fn transform_distinct_order() {
         let numbers_table = NumbersTable::table(0);
@@ -446,4 +525,46 @@ mod test {
             \n      MergeScan [is_placeholder=false]";
         assert_eq!(expected, format!("{:?}", result));
     }
+
+    #[test]
+    fn transform_intersect_except() {
+        let new_plans = || {
+            let left_source = Arc::new(DefaultTableSource::new(Arc::new(
+                DfTableProviderAdapter::new(NumbersTable::table(0)),
+            )));
+            let right_source = Arc::new(DefaultTableSource::new(Arc::new(
+                DfTableProviderAdapter::new(NumbersTable::table(1)),
+            )));
+            let left_plan = LogicalPlanBuilder::scan_with_filters("t", left_source, None, vec![])
+                .unwrap()
+                .build()
+                .unwrap();
+            let right_plan = LogicalPlanBuilder::scan_with_filters("t", right_source, None, vec![])
+                .unwrap()
+                .alias("right")
+                .unwrap()
+                .build()
+                .unwrap();
+            (left_plan, right_plan)
+        };
+        let config = ConfigOptions::default();
+
+        let (left_plan, right_plan) = new_plans();
+        let plan = LogicalPlanBuilder::intersect(left_plan, right_plan, true).unwrap();
+        let result = DistPlannerAnalyzer {}.analyze(plan, &config).unwrap();
+        let expected = "LeftSemi Join: t.number = right.number\
+            \n  MergeScan [is_placeholder=false]\
+            \n  SubqueryAlias: right\
+            \n    MergeScan [is_placeholder=false]";
+        assert_eq!(expected, format!("{:?}", result));
+
+        let (left_plan, right_plan) = new_plans();
+        let plan = LogicalPlanBuilder::except(left_plan, right_plan, true).unwrap();
+        let result = DistPlannerAnalyzer {}.analyze(plan, &config).unwrap();
+        let expected = "LeftAnti Join: t.number = right.number\
+            \n  MergeScan [is_placeholder=false]\
+            \n  SubqueryAlias: right\
+            \n    MergeScan [is_placeholder=false]";
+        assert_eq!(expected, format!("{:?}", result));
+    }
 }
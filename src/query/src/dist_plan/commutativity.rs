@@ -15,7 +15,6 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use datafusion_expr::utils::exprlist_to_columns;
 use datafusion_expr::{Expr, LogicalPlan, UserDefinedLogicalNode};
 use promql::extension_plan::{
     EmptyMetric, InstantManipulate, RangeManipulate, SeriesDivide, SeriesNormalize,
@@ -55,6 +54,9 @@ impl Categorizer {
             LogicalPlan::Filter(filter) => Self::check_expr(&filter.predicate),
             LogicalPlan::Window(_) => Commutativity::Unimplemented,
             LogicalPlan::Aggregate(aggr) => {
+                // Each group only has rows from one partition if the group keys contain
+                // all partition columns. So datanodes can aggregate the full groups and
+                // the frontend only needs to merge the results.
                 if Self::check_partition(&aggr.group_expr, &partition_cols) {
                     return Commutativity::Commutative;
                 }
@@ -171,22 +173,21 @@ impl Categorizer {
 
     /// Return true if the given expr and partition cols satisfied the rule.
     /// In this case the plan can be treated as fully commutative.
+    ///
+    /// Partition columns must be referenced directly. Rows of different partitions
+    /// may have the same value of an expression on a partition column, e.g. `a % 2`.
     fn check_partition(exprs: &[Expr], partition_cols: &[String]) -> bool {
-        let mut ref_cols = HashSet::new();
-        if exprlist_to_columns(exprs, &mut ref_cols).is_err() {
-            return false;
-        }
-        let ref_cols = ref_cols
-            .into_iter()
-            .map(|c| c.flat_name())
+        let ref_cols = exprs
+            .iter()
+            .filter_map(|expr| match expr {
+                Expr::Column(column) => Some(column.name.as_str()),
+                _ => None,
+            })
             .collect::<HashSet<_>>();
-        for col in partition_cols {
-            if !ref_cols.contains(col) {
-                return false;
-            }
-        }
 
-        true
+        partition_cols
+            .iter()
+            .all(|col| ref_cols.contains(col.as_str()))
     }
 }
 
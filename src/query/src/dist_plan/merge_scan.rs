@@ -13,18 +13,23 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use arrow_schema::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
 use async_stream::stream;
+use catalog::process_manager::QueryStats;
 use common_base::bytes::Bytes;
+use common_base::readable_size::ReadableSize;
 use common_catalog::parse_catalog_and_schema_from_db_string;
 use common_error::ext::BoxedError;
 use common_meta::table_name::TableName;
 use common_plugins::GREPTIME_EXEC_READ_COST;
 use common_query::physical_plan::TaskContext;
-use common_recordbatch::adapter::DfRecordBatchStreamAdapter;
+use common_recordbatch::adapter::{
+    CollectPlanMetrics, DfRecordBatchStreamAdapter, RecordBatchMetrics,
+};
 use common_recordbatch::error::ExternalSnafu;
 use common_recordbatch::{
     DfSendableRecordBatchStream, RecordBatch, RecordBatchStreamWrapper, SendableRecordBatchStream,
@@ -42,12 +47,14 @@ use futures_util::StreamExt;
 use greptime_proto::v1::region::{QueryRequest, RegionRequestHeader};
 use meter_core::data::ReadItem;
 use meter_macros::read_meter;
-use snafu::ResultExt;
+use session::context::{PLAN_METRICS_KEY, PROFILE_FREQUENCY_KEY, QUERY_MEMORY_LIMIT_KEY};
+use snafu::{ensure, ResultExt};
 use store_api::storage::RegionId;
 use tokio::time::Instant;
 
-use crate::error::ConvertSchemaSnafu;
+use crate::error::{ConvertSchemaSnafu, QuotaExceededSnafu};
 use crate::metrics::{MERGE_SCAN_ERRORS_TOTAL, MERGE_SCAN_POLL_ELAPSED, MERGE_SCAN_REGIONS};
+use crate::query_engine::memory_pool::QueryMemoryLimit;
 use crate::region_query::RegionQueryHandlerRef;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
@@ -115,6 +122,80 @@ impl MergeScanLogicalPlan {
     }
 }
 
+/// Max bytes a query scans in regions, shared by all [MergeScanExec]s of the query.
+///
+/// Attach it to the query context as a typed extension to limit the query. Regions
+/// report bytes their scans read once their streams finish. While a region streams, the
+/// bytes of the batches it returns are checked against the limit, so the query stops
+/// in the middle of a region that returns too many bytes.
+#[derive(Debug)]
+pub struct ScanBytesLimit {
+    limit: u64,
+    scanned: AtomicU64,
+}
+
+impl ScanBytesLimit {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            scanned: AtomicU64::new(0),
+        }
+    }
+
+    /// Records `bytes` scanned in regions, fails if the query scans more than the limit.
+    fn record(&self, bytes: u64) -> crate::error::Result<()> {
+        let scanned = self.scanned.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.ensure_within(scanned)
+    }
+
+    /// Fails if the query would exceed the limit after scanning `pending` bytes in a
+    /// region that is still streaming. The bytes are not recorded.
+    fn check_pending(&self, pending: u64) -> crate::error::Result<()> {
+        self.ensure_within(self.scanned.load(Ordering::Relaxed) + pending)
+    }
+
+    fn ensure_within(&self, scanned: u64) -> crate::error::Result<()> {
+        ensure!(
+            scanned <= self.limit,
+            QuotaExceededSnafu {
+                reason: format!("max_scan_bytes {}", ReadableSize(self.limit)),
+            }
+        );
+        Ok(())
+    }
+}
+
+/// CPU profiles of the regions a query scans, shared by all [MergeScanExec]s of the query.
+///
+/// Attach it to the query context as a typed extension to ask datanodes to profile their
+/// scans of the query at `frequency` per second. A datanode that is already profiling,
+/// e.g. the datanode of a standalone instance whose frontend profiles the process,
+/// doesn't return a profile.
+#[derive(Debug)]
+pub struct RegionProfiles {
+    frequency: i32,
+    /// Stacks sampled by regions and their numbers of samples.
+    stacks: Mutex<Vec<(Vec<String>, i64)>>,
+}
+
+impl RegionProfiles {
+    pub fn new(frequency: i32) -> Self {
+        Self {
+            frequency,
+            stacks: Mutex::default(),
+        }
+    }
+
+    /// Takes the stacks sampled by the regions so far.
+    pub fn take_stacks(&self) -> Vec<(Vec<String>, i64)> {
+        std::mem::take(&mut self.stacks.lock().unwrap())
+    }
+
+    fn add_stacks(&self, stacks: Vec<(Vec<String>, i64)>) {
+        self.stacks.lock().unwrap().extend(stacks);
+    }
+}
+
 pub struct MergeScanExec {
     table: TableName,
     regions: Vec<RegionId>,
@@ -123,6 +204,9 @@ pub struct MergeScanExec {
     arrow_schema: ArrowSchemaRef,
     region_query_handler: RegionQueryHandlerRef,
     metric: ExecutionPlanMetricsSet,
+    /// Metrics of the plan executed on each region, collected after the region stream is finished.
+    /// They're reset each time the plan is executed.
+    sub_stage_metrics: Arc<Mutex<Vec<(RegionId, RecordBatchMetrics)>>>,
 }
 
 impl std::fmt::Debug for MergeScanExec {
@@ -154,15 +238,34 @@ impl MergeScanExec {
             arrow_schema: arrow_schema_without_metadata,
             region_query_handler,
             metric: ExecutionPlanMetricsSet::new(),
+            sub_stage_metrics: Arc::default(),
         })
     }
 
+    /// Returns the metrics reported by each region, in the order regions are scanned.
+    pub fn sub_stage_metrics(&self) -> Vec<(RegionId, RecordBatchMetrics)> {
+        self.sub_stage_metrics.lock().unwrap().clone()
+    }
+
     pub fn to_stream(&self, context: Arc<TaskContext>) -> Result<SendableRecordBatchStream> {
         let substrait_plan = self.substrait_plan.to_vec();
         let regions = self.regions.clone();
         let region_query_handler = self.region_query_handler.clone();
         let metric = MergeScanMetric::new(&self.metric);
         let schema = Self::arrow_schema_to_schema(self.schema())?;
+        // Metrics of a previous execution are stale.
+        self.sub_stage_metrics.lock().unwrap().clear();
+        let sub_stage_metrics = self.sub_stage_metrics.clone();
+        let scan_bytes_limit = context.session_config().get_extension::<ScanBytesLimit>();
+        let query_stats = context.session_config().get_extension::<QueryStats>();
+        let memory_limit = context.session_config().get_extension::<QueryMemoryLimit>();
+        let region_profiles = context.session_config().get_extension::<RegionProfiles>();
+        // Bytes scanned by regions are summed from the metrics of their leaf operators.
+        let plan_metrics = scan_bytes_limit.is_some()
+            || context
+                .session_config()
+                .get_extension::<CollectPlanMetrics>()
+                .is_some();
 
         let dbname = context.task_id().unwrap_or_default();
         let tracing_context = TracingContext::from_json(context.session_id().as_str());
@@ -174,11 +277,28 @@ impl MergeScanExec {
             let mut first_consume_timer = Some(metric.first_consume_time().timer());
 
             for region_id in regions {
+                let mut header = RegionRequestHeader {
+                    tracing_context: tracing_context.to_w3c(),
+                    dbname: dbname.clone(),
+                };
+                if let Some(limit) = &memory_limit {
+                    let _ = header
+                        .tracing_context
+                        .insert(QUERY_MEMORY_LIMIT_KEY.to_string(), limit.0.to_string());
+                }
+                if let Some(profiles) = &region_profiles {
+                    let _ = header.tracing_context.insert(
+                        PROFILE_FREQUENCY_KEY.to_string(),
+                        profiles.frequency.to_string(),
+                    );
+                }
+                if plan_metrics {
+                    let _ = header
+                        .tracing_context
+                        .insert(PLAN_METRICS_KEY.to_string(), true.to_string());
+                }
                 let request = QueryRequest {
-                    header: Some(RegionRequestHeader {
-                        tracing_context: tracing_context.to_w3c(),
-                        dbname: dbname.clone(),
-                    }),
+                    header: Some(header),
                     region_id: region_id.into(),
                     plan: substrait_plan.clone(),
                 };
@@ -194,6 +314,8 @@ impl MergeScanExec {
                 ready_timer.stop();
 
                 let mut poll_duration = Duration::new(0, 0);
+                // Bytes of batches the region returns, a lower bound of bytes it scans.
+                let mut received_bytes = 0u64;
 
                 let mut poll_timer = Instant::now();
                 while let Some(batch) = stream.next().await {
@@ -201,6 +323,13 @@ impl MergeScanExec {
                     poll_duration += poll_elapsed;
 
                     let batch = batch?;
+                    if let Some(limit) = &scan_bytes_limit {
+                        received_bytes += batch.df_record_batch().get_array_memory_size() as u64;
+                        limit
+                            .check_pending(received_bytes)
+                            .map_err(BoxedError::new)
+                            .context(ExternalSnafu)?;
+                    }
                     // reconstruct batch using `self.schema`
                     // to remove metadata and correct column name
                     let batch = RecordBatch::new(schema.clone(), batch.columns().iter().cloned())?;
@@ -212,7 +341,10 @@ impl MergeScanExec {
                     // reset poll timer
                     poll_timer = Instant::now();
                 }
-                if let Some(metrics) = stream.metrics() {
+                if let Some(mut metrics) = stream.metrics() {
+                    if let Some(profiles) = &region_profiles {
+                        profiles.add_stacks(std::mem::take(&mut metrics.profile_stacks));
+                    }
                     let (c, s) = parse_catalog_and_schema_from_db_string(&dbname);
                     let value = read_meter!(
                         c,
@@ -223,6 +355,24 @@ impl MergeScanExec {
                         }
                     );
                     metric.record_greptime_exec_cost(value as usize);
+                    // Counts bytes the scans of the region read, not bytes of the
+                    // batches it returns after filters and aggregations.
+                    let scanned_bytes = metrics.scanned_bytes() as u64;
+                    sub_stage_metrics.lock().unwrap().push((region_id, metrics));
+                    if let Some(stats) = &query_stats {
+                        stats.add_scanned_bytes(scanned_bytes);
+                    }
+                    if let Some(limit) = &scan_bytes_limit {
+                        limit
+                            .record(scanned_bytes.max(received_bytes))
+                            .map_err(BoxedError::new)
+                            .context(ExternalSnafu)?;
+                    }
+                } else if let Some(limit) = &scan_bytes_limit {
+                    limit
+                        .record(received_bytes)
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?;
                 }
 
                 MERGE_SCAN_POLL_ELAPSED.observe(poll_duration.as_secs_f64());
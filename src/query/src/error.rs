@@ -54,6 +54,13 @@ pub enum Error {
     #[snafu(display("Table not found: {}", table))]
     TableNotFound { table: String, location: Location },
 
+    #[snafu(display("Invalid view `{}`: {}", view, reason))]
+    InvalidView {
+        view: String,
+        reason: String,
+        location: Location,
+    },
+
     #[snafu(display("Failed to create RecordBatch"))]
     CreateRecordBatch {
         source: common_recordbatch::error::Error,
@@ -114,6 +121,14 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to parse bool `{}`", raw))]
+    ParseBool {
+        raw: String,
+        #[snafu(source)]
+        error: std::str::ParseBoolError,
+        location: Location,
+    },
+
     #[snafu(display("DataFusion error"))]
     DataFusion {
         #[snafu(source)]
@@ -244,6 +259,9 @@ pub enum Error {
 
     #[snafu(display("Range Query: {}", msg))]
     RangeQuery { msg: String, location: Location },
+
+    #[snafu(display("Quota exceeded: {}", reason))]
+    QuotaExceeded { reason: String, location: Location },
 }
 
 impl ErrorExt for Error {
@@ -264,13 +282,15 @@ impl ErrorExt for Error {
             | ParseTimestamp { .. }
             | InvalidTimestamp { .. }
             | ParseFloat { .. }
+            | ParseBool { .. }
             | MissingRequiredField { .. }
             | BuildRegex { .. }
             | ConvertSchema { .. }
             | AddSystemTimeOverflow { .. }
             | ColumnSchemaIncompatible { .. }
             | UnsupportedVariable { .. }
-            | ColumnSchemaNoDefault { .. } => StatusCode::InvalidArguments,
+            | ColumnSchemaNoDefault { .. }
+            | InvalidView { .. } => StatusCode::InvalidArguments,
 
             BuildBackend { .. } | ListObjects { .. } => StatusCode::StorageUnavailable,
             EncodeSubstraitLogicalPlan { source, .. } => source.status_code(),
@@ -296,6 +316,7 @@ impl ErrorExt for Error {
             RegionQuery { source, .. } => source.status_code(),
             TableMutation { source, .. } => source.status_code(),
             MissingTableMutationHandler { .. } => StatusCode::Unexpected,
+            QuotaExceeded { .. } => StatusCode::QuotaExceeded,
         }
     }
 
@@ -13,10 +13,14 @@
 // limitations under the License.
 
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlan, PhysicalPlanAdapter};
 use common_recordbatch::adapter::RecordBatchMetrics;
 use common_recordbatch::{OrderOption, RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use datafusion::physical_plan::metrics::MetricValue;
+use datafusion::physical_plan::{displayable, ExecutionPlan};
 use datatypes::schema::SchemaRef;
 use futures::Stream;
 use futures_util::ready;
@@ -57,6 +61,87 @@ lazy_static! {
         "query merge scan errors total"
     )
     .unwrap();
+
+    /// Compute time of each physical operator, collected once the plan is finished.
+    pub static ref OPERATOR_ELAPSED_COMPUTE: HistogramVec = register_histogram_vec!(
+        "greptime_query_operator_elapsed_compute",
+        "query physical operator compute time elapsed",
+        &["operator"],
+        vec![0.0001, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 60.0]
+    )
+    .unwrap();
+    /// Output rows of each physical operator.
+    pub static ref OPERATOR_OUTPUT_ROWS: IntCounterVec = register_int_counter_vec!(
+        "greptime_query_operator_output_rows_total",
+        "query physical operator output rows total",
+        &["operator"]
+    )
+    .unwrap();
+    pub static ref SPILL_COUNT_TOTAL: IntCounter = register_int_counter!(
+        "greptime_query_spill_count_total",
+        "query spill count total"
+    )
+    .unwrap();
+    pub static ref SPILLED_BYTES_TOTAL: IntCounter = register_int_counter!(
+        "greptime_query_spilled_bytes_total",
+        "query spilled bytes total"
+    )
+    .unwrap();
+    /// Counter of queries aborted to release memory for other queries.
+    pub static ref MEMORY_ABORTED_QUERIES_TOTAL: IntCounter = register_int_counter!(
+        "greptime_query_memory_aborted_queries_total",
+        "query memory aborted queries total"
+    )
+    .unwrap();
+}
+
+/// Exports the execution metrics of a finished `plan` to prometheus.
+pub fn observe_plan_metrics(plan: &Arc<dyn PhysicalPlan>) {
+    let df_plan = match plan.as_any().downcast_ref::<PhysicalPlanAdapter>() {
+        Some(adapter) => adapter.df_plan(),
+        None => Arc::new(DfPhysicalPlanAdapter(plan.clone())),
+    };
+    observe_df_plan_metrics(&df_plan);
+}
+
+fn observe_df_plan_metrics(plan: &Arc<dyn ExecutionPlan>) {
+    if let Some(metrics) = plan.metrics() {
+        let operator = operator_name(plan);
+        let mut elapsed_compute = 0;
+        let mut output_rows = 0;
+        for metric in metrics.iter() {
+            match metric.value() {
+                MetricValue::ElapsedCompute(time) => elapsed_compute += time.value(),
+                MetricValue::OutputRows(count) => output_rows += count.value(),
+                MetricValue::SpillCount(count) => SPILL_COUNT_TOTAL.inc_by(count.value() as u64),
+                MetricValue::SpilledBytes(count) => {
+                    SPILLED_BYTES_TOTAL.inc_by(count.value() as u64)
+                }
+                _ => {}
+            }
+        }
+        OPERATOR_ELAPSED_COMPUTE
+            .with_label_values(&[&operator])
+            .observe(elapsed_compute as f64 / 1_000_000_000.0);
+        OPERATOR_OUTPUT_ROWS
+            .with_label_values(&[&operator])
+            .inc_by(output_rows as u64);
+    }
+
+    for child in plan.children() {
+        observe_df_plan_metrics(&child);
+    }
+}
+
+/// Returns the operator name of `plan`, e.g. "ProjectionExec" for
+/// "ProjectionExec: expr=[a]".
+fn operator_name(plan: &Arc<dyn ExecutionPlan>) -> String {
+    displayable(plan.as_ref())
+        .one_line()
+        .to_string()
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect()
 }
 
 /// A stream to call the callback once a RecordBatch stream is done.
@@ -108,3 +193,143 @@ impl<F: FnOnce() + Unpin> Stream for OnDone<F> {
         self.stream.size_hint()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::time::Duration;
+
+    use common_query::physical_plan::TaskContext;
+    use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
+    use datafusion::physical_plan::{
+        DisplayAs, DisplayFormatType, Partitioning, SendableRecordBatchStream as DfStream,
+    };
+    use datafusion_common::{DataFusionError, Result as DfResult, Statistics};
+    use datafusion_physical_expr::PhysicalSortExpr;
+    use datatypes::schema::Schema;
+
+    use super::*;
+
+    /// A finished plan with known metrics.
+    #[derive(Debug)]
+    struct FinishedExec {
+        name: &'static str,
+        metrics: ExecutionPlanMetricsSet,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    }
+
+    impl FinishedExec {
+        fn new(
+            name: &'static str,
+            output_rows: usize,
+            elapsed_compute: Duration,
+            children: Vec<Arc<dyn ExecutionPlan>>,
+        ) -> Arc<dyn ExecutionPlan> {
+            let metrics = ExecutionPlanMetricsSet::new();
+            // Metrics of two partitions are summed.
+            for partition in 0..2 {
+                MetricBuilder::new(&metrics)
+                    .output_rows(partition)
+                    .add(output_rows / 2);
+                MetricBuilder::new(&metrics)
+                    .elapsed_compute(partition)
+                    .add_duration(elapsed_compute / 2);
+            }
+            Arc::new(Self {
+                name,
+                metrics,
+                children,
+            })
+        }
+    }
+
+    impl DisplayAs for FinishedExec {
+        fn fmt_as(&self, _t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}: partitions=2", self.name)
+        }
+    }
+
+    impl ExecutionPlan for FinishedExec {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> arrow_schema::SchemaRef {
+            Arc::new(arrow_schema::Schema::empty())
+        }
+
+        fn output_partitioning(&self) -> Partitioning {
+            Partitioning::UnknownPartitioning(2)
+        }
+
+        fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+            None
+        }
+
+        fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+            self.children.clone()
+        }
+
+        fn with_new_children(
+            self: Arc<Self>,
+            _children: Vec<Arc<dyn ExecutionPlan>>,
+        ) -> DfResult<Arc<dyn ExecutionPlan>> {
+            Ok(self)
+        }
+
+        fn execute(&self, _partition: usize, _context: Arc<TaskContext>) -> DfResult<DfStream> {
+            Err(DataFusionError::NotImplemented(
+                "FinishedExec has finished".to_string(),
+            ))
+        }
+
+        fn metrics(&self) -> Option<MetricsSet> {
+            Some(self.metrics.clone_inner())
+        }
+
+        fn statistics(&self) -> Statistics {
+            Statistics::default()
+        }
+    }
+
+    #[test]
+    fn test_observe_plan_metrics() {
+        // Operator names are unique to this test as the metrics are global.
+        let scan = FinishedExec::new(
+            "ObserveTestScanExec",
+            100,
+            Duration::from_millis(200),
+            vec![],
+        );
+        let root = FinishedExec::new(
+            "ObserveTestRootExec",
+            10,
+            Duration::from_millis(50),
+            vec![scan],
+        );
+        let plan: Arc<dyn PhysicalPlan> = Arc::new(PhysicalPlanAdapter::new(
+            Arc::new(Schema::new(vec![])),
+            root,
+        ));
+
+        observe_plan_metrics(&plan);
+
+        for (operator, rows, seconds) in [
+            ("ObserveTestRootExec", 10, 0.05),
+            ("ObserveTestScanExec", 100, 0.2),
+        ] {
+            let elapsed = OPERATOR_ELAPSED_COMPUTE.with_label_values(&[operator]);
+            assert_eq!(1, elapsed.get_sample_count(), "{operator}");
+            assert!(
+                (elapsed.get_sample_sum() - seconds).abs() < 1e-9,
+                "{operator}: {}",
+                elapsed.get_sample_sum()
+            );
+            assert_eq!(
+                rows,
+                OPERATOR_OUTPUT_ROWS.with_label_values(&[operator]).get(),
+                "{operator}"
+            );
+        }
+    }
+}
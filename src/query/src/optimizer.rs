@@ -34,6 +34,7 @@ pub trait ExtensionAnalyzerRule {
     fn name(&self) -> &str;
 }
 
+pub mod case_insensitive;
 pub mod order_hint;
 pub mod string_normalization;
 pub mod type_conversion;
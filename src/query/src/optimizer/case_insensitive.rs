@@ -0,0 +1,380 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use datafusion::config::ConfigOptions;
+use datafusion::datasource::DefaultTableSource;
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRewriter, VisitRecursion};
+use datafusion_common::{Column, Result, ScalarValue};
+use datafusion_expr::expr::{InList, Like};
+use datafusion_expr::{lit, lower, BinaryExpr, Expr, Filter, LogicalPlan, Operator};
+use datafusion_optimizer::analyzer::AnalyzerRule;
+use table::table::adapter::DfTableProviderAdapter;
+
+/// CaseInsensitiveRule rewrites comparisons on columns listed in the
+/// `case_insensitive_columns` table option to compare lowercased values, e.g.
+/// `host = 'Web'` becomes `lower(host) = lower('Web')`, and `LIKE` on these
+/// columns to `ILIKE`.
+///
+/// Filters on lowercased values can't prune data by indexes or statistics. So when
+/// the column is compared with an ASCII string, the rule also adds a range filter
+/// on the bare column that all matching values satisfy, e.g. `host = 'Web'` becomes
+/// `host >= 'WEB' AND host <= 'web' AND lower(host) = lower('Web')`. In byte order,
+/// an uppercase ASCII letter sorts before its lowercase letter, so the case variants
+/// of a string sort between its uppercase and its lowercase. This assumes that
+/// lowercasing never makes a value sort before itself, which holds for ASCII but not
+/// for a few other characters like the Kelvin sign.
+///
+/// Only filters are rewritten, grouping still uses the original values.
+pub struct CaseInsensitiveRule;
+
+impl AnalyzerRule for CaseInsensitiveRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        let columns = collect_case_insensitive_columns(&plan)?;
+        if columns.is_empty() {
+            return Ok(plan);
+        }
+
+        plan.transform(&|plan| match plan {
+            LogicalPlan::Filter(filter) => {
+                let mut rewriter = CaseInsensitiveRewriter { columns: &columns };
+                let predicate = filter.predicate.clone().rewrite(&mut rewriter)?;
+                Ok(Transformed::Yes(LogicalPlan::Filter(Filter::try_new(
+                    predicate,
+                    filter.input,
+                )?)))
+            }
+            plan => Ok(Transformed::No(plan)),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "CaseInsensitiveRule"
+    }
+}
+
+/// Collects case-insensitive columns of all tables scanned in `plan`.
+fn collect_case_insensitive_columns(plan: &LogicalPlan) -> Result<Vec<Column>> {
+    let mut columns = vec![];
+    let _ = plan.apply(&mut |plan| {
+        if let LogicalPlan::TableScan(scan) = plan {
+            if let Some(adapter) = scan
+                .source
+                .as_any()
+                .downcast_ref::<DefaultTableSource>()
+                .and_then(|source| {
+                    source
+                        .table_provider
+                        .as_any()
+                        .downcast_ref::<DfTableProviderAdapter>()
+                })
+            {
+                let table_info = adapter.table().table_info();
+                columns.extend(
+                    table_info
+                        .meta
+                        .options
+                        .case_insensitive_columns()
+                        .into_iter()
+                        .map(|name| Column::new(Some(scan.table_name.clone()), name)),
+                );
+            }
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(columns)
+}
+
+struct CaseInsensitiveRewriter<'a> {
+    columns: &'a [Column],
+}
+
+impl<'a> CaseInsensitiveRewriter<'a> {
+    fn is_case_insensitive(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Column(column) => self.columns.iter().any(|c| {
+                c.name == column.name
+                    && (column.relation.is_none() || c.relation == column.relation)
+            }),
+            _ => false,
+        }
+    }
+
+    /// Returns a range filter on the bare column that all values satisfying the
+    /// case-insensitive comparison `left op right` satisfy.
+    fn comparison_prefilter(&self, left: &Expr, op: Operator, right: &Expr) -> Option<Expr> {
+        let (column, op, value) = match (ascii_literal(left), ascii_literal(right)) {
+            (None, Some(value)) if self.is_case_insensitive(left) => (left, op, value),
+            (Some(value), None) if self.is_case_insensitive(right) => (right, op.swap()?, value),
+            _ => return None,
+        };
+        let column = column.clone();
+        match op {
+            Operator::Eq => Some(
+                column
+                    .clone()
+                    .gt_eq(lit(value.to_ascii_uppercase()))
+                    .and(column.lt_eq(lit(value.to_ascii_lowercase()))),
+            ),
+            // A value sorts before its lowercase, so `lower(v) < s` implies `v < lower(s)`.
+            // There is no such bound for `>`, e.g. `lower('A') > '_'` but `'A' < '_'`.
+            Operator::Lt => Some(column.lt(lit(value.to_ascii_lowercase()))),
+            Operator::LtEq => Some(column.lt_eq(lit(value.to_ascii_lowercase()))),
+            _ => None,
+        }
+    }
+
+    /// Returns a range filter on the bare column that all values in the case-insensitive
+    /// `list` satisfy.
+    fn in_list_prefilter(column: &Expr, list: &[Expr]) -> Option<Expr> {
+        let values = list.iter().map(ascii_literal).collect::<Option<Vec<_>>>()?;
+        let low = values.iter().map(|v| v.to_ascii_uppercase()).min()?;
+        let high = values.iter().map(|v| v.to_ascii_lowercase()).max()?;
+        Some(
+            column
+                .clone()
+                .gt_eq(lit(low))
+                .and(column.clone().lt_eq(lit(high))),
+        )
+    }
+
+    /// Returns a range filter on the bare column that all values case-insensitively
+    /// matching `pattern` satisfy, if the pattern starts with a literal prefix.
+    fn like_prefilter(column: &Expr, pattern: &Expr, escape_char: Option<char>) -> Option<Expr> {
+        let pattern = ascii_literal(pattern)?;
+        let prefix = pattern
+            .chars()
+            .take_while(|c| !matches!(c, '%' | '_') && Some(*c) != escape_char)
+            .collect::<String>();
+        let mut high = prefix.to_ascii_lowercase();
+        // Values starting with a case variant of the prefix sort before the lowercase
+        // prefix with its last character incremented.
+        let last = high.pop()?;
+        high.push(char::from_u32(last as u32 + 1)?);
+        Some(
+            column
+                .clone()
+                .gt_eq(lit(prefix.to_ascii_uppercase()))
+                .and(column.clone().lt(lit(high))),
+        )
+    }
+}
+
+/// Returns the string of an ASCII string literal.
+fn ascii_literal(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Literal(ScalarValue::Utf8(Some(s)) | ScalarValue::LargeUtf8(Some(s)))
+            if s.is_ascii() =>
+        {
+            Some(s.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// Adds `prefilter` before `expr`. The prefilter is implied by `expr`, so the result
+/// is equivalent to `expr`.
+fn with_prefilter(expr: Expr, prefilter: Option<Expr>) -> Expr {
+    match prefilter {
+        Some(prefilter) => prefilter.and(expr),
+        None => expr,
+    }
+}
+
+impl<'a> TreeNodeRewriter for CaseInsensitiveRewriter<'a> {
+    type N = Expr;
+
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        let new_expr = match expr {
+            Expr::BinaryExpr(BinaryExpr { left, op, right })
+                if matches!(
+                    op,
+                    Operator::Eq
+                        | Operator::NotEq
+                        | Operator::Lt
+                        | Operator::LtEq
+                        | Operator::Gt
+                        | Operator::GtEq
+                ) && (self.is_case_insensitive(&left) || self.is_case_insensitive(&right)) =>
+            {
+                let prefilter = self.comparison_prefilter(&left, op, &right);
+                let compare = Expr::BinaryExpr(BinaryExpr {
+                    left: Box::new(lower(*left)),
+                    op,
+                    right: Box::new(lower(*right)),
+                });
+                with_prefilter(compare, prefilter)
+            }
+            Expr::InList(InList {
+                expr,
+                list,
+                negated,
+            }) if self.is_case_insensitive(&expr) => {
+                let prefilter = if negated {
+                    None
+                } else {
+                    Self::in_list_prefilter(&expr, &list)
+                };
+                let in_list = Expr::InList(InList {
+                    expr: Box::new(lower(*expr)),
+                    list: list.into_iter().map(lower).collect(),
+                    negated,
+                });
+                with_prefilter(in_list, prefilter)
+            }
+            Expr::Like(Like {
+                negated,
+                expr,
+                pattern,
+                escape_char,
+                case_insensitive: false,
+            }) if self.is_case_insensitive(&expr) => {
+                let prefilter = if negated {
+                    None
+                } else {
+                    Self::like_prefilter(&expr, &pattern, escape_char)
+                };
+                let like = Expr::Like(Like {
+                    negated,
+                    expr,
+                    pattern,
+                    escape_char,
+                    case_insensitive: true,
+                });
+                with_prefilter(like, prefilter)
+            }
+            expr => expr,
+        };
+        Ok(new_expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion_expr::{col, lit, LogicalPlanBuilder};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use table::requests::CASE_INSENSITIVE_COLUMNS_KEY;
+    use table::test_util::table_info::test_table_info;
+    use table::test_util::EmptyTable;
+
+    use super::*;
+
+    fn scan_plan_builder() -> LogicalPlanBuilder {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("idc", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ]));
+        let mut table_info = test_table_info(1, "t", "public", "greptime", schema);
+        let _ = table_info
+            .meta
+            .options
+            .extra_options
+            .insert(CASE_INSENSITIVE_COLUMNS_KEY.to_string(), "host".to_string());
+        let table = EmptyTable::from_table_info(&table_info);
+        let source = Arc::new(DefaultTableSource::new(Arc::new(
+            DfTableProviderAdapter::new(table),
+        )));
+        LogicalPlanBuilder::scan("t", source, None).unwrap()
+    }
+
+    #[test]
+    fn test_rewrite_case_insensitive_filter() {
+        let plan = scan_plan_builder()
+            .filter(
+                col("host")
+                    .eq(lit("Web"))
+                    .and(col("idc").not_eq(lit("Hz")))
+                    .and(col("host").in_list(vec![lit("A"), lit("b")], false)),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let plan = CaseInsensitiveRule
+            .analyze(plan, &ConfigOptions::default())
+            .unwrap();
+        assert_eq!(
+            "Filter: t.host >= Utf8(\"WEB\") AND t.host <= Utf8(\"web\") AND lower(t.host) = lower(Utf8(\"Web\")) AND t.idc != Utf8(\"Hz\") AND t.host >= Utf8(\"A\") AND t.host <= Utf8(\"b\") AND lower(t.host) IN ([lower(Utf8(\"A\")), lower(Utf8(\"b\"))])\
+            \n  TableScan: t",
+            format!("{plan:?}")
+        );
+    }
+
+    #[test]
+    fn test_rewrite_case_insensitive_range_and_like() {
+        let plan = scan_plan_builder()
+            .filter(
+                lit("Web")
+                    .gt(col("host"))
+                    .and(col("host").gt_eq(lit("a")))
+                    .and(col("host").like(lit("We%b")))
+                    .and(col("host").not_like(lit("x%"))),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let plan = CaseInsensitiveRule
+            .analyze(plan, &ConfigOptions::default())
+            .unwrap();
+        assert_eq!(
+            "Filter: t.host < Utf8(\"web\") AND lower(Utf8(\"Web\")) > lower(t.host) AND lower(t.host) >= lower(Utf8(\"a\")) AND t.host >= Utf8(\"WE\") AND t.host < Utf8(\"wf\") AND t.host ILIKE Utf8(\"We%b\") AND t.host NOT ILIKE Utf8(\"x%\")\
+            \n  TableScan: t",
+            format!("{plan:?}")
+        );
+    }
+
+    #[test]
+    fn test_skip_prefilter_for_non_ascii() {
+        let plan = scan_plan_builder()
+            .filter(
+                col("host")
+                    .eq(lit("Straße"))
+                    .and(col("host").like(lit("%web"))),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let plan = CaseInsensitiveRule
+            .analyze(plan, &ConfigOptions::default())
+            .unwrap();
+        assert_eq!(
+            "Filter: lower(t.host) = lower(Utf8(\"Straße\")) AND t.host ILIKE Utf8(\"%web\")\
+            \n  TableScan: t",
+            format!("{plan:?}")
+        );
+    }
+
+    #[test]
+    fn test_keep_case_sensitive_filter() {
+        let plan = scan_plan_builder()
+            .filter(col("idc").eq(lit("Hz")))
+            .unwrap()
+            .build()
+            .unwrap();
+        let plan = CaseInsensitiveRule
+            .analyze(plan, &ConfigOptions::default())
+            .unwrap();
+        assert_eq!(
+            "Filter: t.idc = Utf8(\"Hz\")\n  TableScan: t",
+            format!("{plan:?}")
+        );
+    }
+}
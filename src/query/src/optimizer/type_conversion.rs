@@ -16,7 +16,7 @@ use common_time::timestamp::{TimeUnit, Timestamp};
 use common_time::Timezone;
 use datafusion::config::ConfigOptions;
 use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRewriter};
-use datafusion_common::{DFSchemaRef, DataFusionError, Result, ScalarValue};
+use datafusion_common::{Column, DFSchemaRef, DataFusionError, Result, ScalarValue};
 use datafusion_expr::expr::InList;
 use datafusion_expr::{
     Between, BinaryExpr, Expr, ExprSchemable, Filter, LogicalPlan, Operator, TableScan,
@@ -24,6 +24,7 @@ use datafusion_expr::{
 use datatypes::arrow::compute;
 use datatypes::arrow::datatypes::DataType;
 use session::context::QueryContextRef;
+use session::session_config::TypeCoercionMode;
 
 use crate::optimizer::ExtensionAnalyzerRule;
 use crate::QueryEngineContext;
@@ -31,8 +32,14 @@ use crate::QueryEngineContext;
 /// TypeConversionRule converts some literal values in logical plan to other types according
 /// to data type of corresponding columns.
 /// Specifically:
-/// - string literal of timestamp is converted to `Expr::Literal(ScalarValue::TimestampMillis)`
+/// - string literal of timestamp is converted to a timestamp literal with the precision of
+///   the column it compares with
+/// - timestamp literal is converted to `Expr::Literal(ScalarValue::TimestampMillis)` if this
+///   doesn't lose precision
 /// - string literal of boolean is converted to `Expr::Literal(ScalarValue::Boolean)`
+///
+/// Whether a string literal is allowed to be implicitly casted is decided by the
+/// [TypeCoercionMode] of the session, see [implicit_string_cast].
 pub struct TypeConversionRule;
 
 impl ExtensionAnalyzerRule for TypeConversionRule {
@@ -146,8 +153,8 @@ impl TypeConverter {
         target_type: &DataType,
     ) -> Result<ScalarValue> {
         match (target_type, value) {
-            (DataType::Timestamp(_, _), ScalarValue::Utf8(Some(v))) => {
-                string_to_timestamp_ms(v, Some(self.query_ctx.timezone().as_ref()))
+            (DataType::Timestamp(unit, _), ScalarValue::Utf8(Some(v))) => {
+                string_to_timestamp(v, Some(self.query_ctx.timezone().as_ref()), unit.into())
             }
             (DataType::Boolean, ScalarValue::Utf8(Some(v))) => match v.to_lowercase().as_str() {
                 "true" => Ok(ScalarValue::Boolean(Some(true))),
@@ -178,10 +185,28 @@ impl TypeConverter {
         };
 
         // only try to convert timestamp or boolean types
-        if !matches!(target_type, DataType::Timestamp(_, _) | DataType::Boolean) {
+        if !matches!(target_type, DataType::Timestamp(_, _) | DataType::Boolean)
+            && !target_type.is_numeric()
+        {
             return Ok((left.clone(), right.clone()));
         }
 
+        if let (Expr::Column(col), Expr::Literal(value))
+        | (Expr::Literal(value), Expr::Column(col)) = (left, right)
+        {
+            if matches!(value, ScalarValue::Utf8(Some(_))) {
+                let mode = self
+                    .query_ctx
+                    .configuration_parameter()
+                    .type_coercion_mode();
+                if !implicit_string_cast(mode, col, target_type)? {
+                    return Ok((left.clone(), right.clone()));
+                }
+            } else if target_type.is_numeric() {
+                return Ok((left.clone(), right.clone()));
+            }
+        }
+
         match (left, right) {
             (Expr::Column(col), Expr::Literal(value)) => {
                 let casted_right = self.cast_scalar_value(value, target_type)?;
@@ -279,29 +304,71 @@ impl TreeNodeRewriter for TypeConverter {
     }
 }
 
+/// The implicit cast matrix for string literals compared with `column` of `target_type`.
+///
+/// | target type        | strict | lenient            |
+/// |--------------------|--------|--------------------|
+/// | timestamp, boolean | reject | cast               |
+/// | numeric            | reject | left to DataFusion |
+///
+/// Returns whether the literal should be casted to `target_type`.
+fn implicit_string_cast(
+    mode: TypeCoercionMode,
+    column: &Column,
+    target_type: &DataType,
+) -> Result<bool> {
+    match mode {
+        TypeCoercionMode::Lenient => Ok(matches!(
+            target_type,
+            DataType::Timestamp(_, _) | DataType::Boolean
+        )),
+        TypeCoercionMode::Strict => Err(DataFusionError::Plan(format!(
+            "column:{column:?}. Comparing string with {target_type:?} requires an explicit cast under strict type coercion",
+        ))),
+    }
+}
+
+/// Converts the timestamp literal to millisecond. Literals with sub-millisecond part are
+/// left untouched, otherwise comparing them with a micro or nanosecond column truncates.
 fn timestamp_to_timestamp_ms_expr(val: i64, unit: TimeUnit) -> Expr {
     let timestamp = match unit {
-        TimeUnit::Second => val * 1_000,
-        TimeUnit::Millisecond => val,
-        TimeUnit::Microsecond => val / 1_000,
-        TimeUnit::Nanosecond => val / 1_000 / 1_000,
+        TimeUnit::Second => val.checked_mul(1_000),
+        TimeUnit::Millisecond => Some(val),
+        TimeUnit::Microsecond => (val % 1_000 == 0).then(|| val / 1_000),
+        TimeUnit::Nanosecond => (val % 1_000_000 == 0).then(|| val / 1_000_000),
     };
 
-    Expr::Literal(ScalarValue::TimestampMillisecond(Some(timestamp), None))
+    match timestamp {
+        Some(timestamp) => Expr::Literal(ScalarValue::TimestampMillisecond(Some(timestamp), None)),
+        None => Expr::Literal(timestamp_to_scalar(Timestamp::new(val, unit))),
+    }
 }
 
-fn string_to_timestamp_ms(string: &str, timezone: Option<&Timezone>) -> Result<ScalarValue> {
+/// Parses the string to a timestamp of `unit`.
+fn string_to_timestamp(
+    string: &str,
+    timezone: Option<&Timezone>,
+    unit: TimeUnit,
+) -> Result<ScalarValue> {
     let ts = Timestamp::from_str(string, timezone)
         .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    let ts = ts.convert_to(unit).ok_or_else(|| {
+        DataFusionError::Plan(format!(
+            "Timestamp {string} is out of range for time unit {unit:?}"
+        ))
+    })?;
 
+    Ok(timestamp_to_scalar(ts))
+}
+
+fn timestamp_to_scalar(ts: Timestamp) -> ScalarValue {
     let value = Some(ts.value());
-    let scalar = match ts.unit() {
+    match ts.unit() {
         TimeUnit::Second => ScalarValue::TimestampSecond(value, None),
         TimeUnit::Millisecond => ScalarValue::TimestampMillisecond(value, None),
         TimeUnit::Microsecond => ScalarValue::TimestampMicrosecond(value, None),
         TimeUnit::Nanosecond => ScalarValue::TimestampNanosecond(value, None),
-    };
-    Ok(scalar)
+    }
 }
 
 #[cfg(test)]
@@ -310,7 +377,7 @@ mod tests {
     use std::sync::Arc;
 
     use datafusion::logical_expr::expr::AggregateFunction as AggrExpr;
-    use datafusion_common::{Column, DFField, DFSchema};
+    use datafusion_common::{DFField, DFSchema};
     use datafusion_expr::{AggregateFunction, LogicalPlanBuilder};
     use datafusion_sql::TableReference;
     use session::context::QueryContext;
@@ -318,33 +385,46 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_string_to_timestamp_ms() {
+    fn test_string_to_timestamp() {
         assert_eq!(
-            string_to_timestamp_ms("2022-02-02 19:00:00+08:00", None).unwrap(),
+            string_to_timestamp("2022-02-02 19:00:00+08:00", None, TimeUnit::Second).unwrap(),
             ScalarValue::TimestampSecond(Some(1643799600), None)
         );
         assert_eq!(
-            string_to_timestamp_ms("2009-02-13 23:31:30Z", None).unwrap(),
+            string_to_timestamp("2009-02-13 23:31:30Z", None, TimeUnit::Second).unwrap(),
             ScalarValue::TimestampSecond(Some(1234567890), None)
         );
 
         assert_eq!(
-            string_to_timestamp_ms(
+            string_to_timestamp(
                 "2009-02-13 23:31:30",
-                Some(&Timezone::from_tz_string("Asia/Shanghai").unwrap())
+                Some(&Timezone::from_tz_string("Asia/Shanghai").unwrap()),
+                TimeUnit::Second
             )
             .unwrap(),
             ScalarValue::TimestampSecond(Some(1234567890 - 8 * 3600), None)
         );
 
         assert_eq!(
-            string_to_timestamp_ms(
+            string_to_timestamp(
                 "2009-02-13 23:31:30",
-                Some(&Timezone::from_tz_string("-8:00").unwrap())
+                Some(&Timezone::from_tz_string("-8:00").unwrap()),
+                TimeUnit::Second
             )
             .unwrap(),
             ScalarValue::TimestampSecond(Some(1234567890 + 8 * 3600), None)
         );
+
+        assert_eq!(
+            string_to_timestamp("2009-02-13 23:31:30.123456789Z", None, TimeUnit::Nanosecond)
+                .unwrap(),
+            ScalarValue::TimestampNanosecond(Some(1234567890123456789), None)
+        );
+        assert_eq!(
+            string_to_timestamp("2009-02-13 23:31:30Z", None, TimeUnit::Millisecond).unwrap(),
+            ScalarValue::TimestampMillisecond(Some(1234567890000), None)
+        );
+        assert!(string_to_timestamp("2300-01-01 00:00:00Z", None, TimeUnit::Nanosecond).is_err());
     }
 
     #[test]
@@ -361,12 +441,12 @@ mod tests {
 
         assert_eq!(
             timestamp_to_timestamp_ms_expr(123, TimeUnit::Microsecond),
-            Expr::Literal(ScalarValue::TimestampMillisecond(Some(0), None))
+            Expr::Literal(ScalarValue::TimestampMicrosecond(Some(123), None))
         );
 
         assert_eq!(
             timestamp_to_timestamp_ms_expr(1230, TimeUnit::Microsecond),
-            Expr::Literal(ScalarValue::TimestampMillisecond(Some(1), None))
+            Expr::Literal(ScalarValue::TimestampMicrosecond(Some(1230), None))
         );
 
         assert_eq!(
@@ -376,7 +456,7 @@ mod tests {
 
         assert_eq!(
             timestamp_to_timestamp_ms_expr(1230, TimeUnit::Nanosecond),
-            Expr::Literal(ScalarValue::TimestampMillisecond(Some(0), None))
+            Expr::Literal(ScalarValue::TimestampNanosecond(Some(1230), None))
         );
         assert_eq!(
             timestamp_to_timestamp_ms_expr(123_000_000, TimeUnit::Nanosecond),
@@ -406,10 +486,9 @@ mod tests {
         };
 
         assert_eq!(
-            Expr::Column(Column::from_name("ts")).gt(Expr::Literal(ScalarValue::TimestampSecond(
-                Some(1599514949),
-                None
-            ))),
+            Expr::Column(Column::from_name("ts")).gt(Expr::Literal(
+                ScalarValue::TimestampMillisecond(Some(1599514949000), None)
+            )),
             converter
                 .mutate(
                     Expr::Column(Column::from_name("ts")).gt(Expr::Literal(ScalarValue::Utf8(
@@ -420,6 +499,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_convert_nanosecond_timestamp() {
+        use datatypes::arrow::datatypes::TimeUnit as ArrowTimeUnit;
+
+        let schema = Arc::new(
+            DFSchema::new_with_metadata(
+                vec![DFField::new(
+                    None::<TableReference>,
+                    "ts",
+                    DataType::Timestamp(ArrowTimeUnit::Nanosecond, None),
+                    true,
+                )],
+                HashMap::new(),
+            )
+            .unwrap(),
+        );
+        let mut converter = TypeConverter {
+            schema,
+            query_ctx: QueryContext::arc(),
+        };
+
+        // string literal keeps the nanosecond part
+        assert_eq!(
+            Expr::Column(Column::from_name("ts")).gt(Expr::Literal(
+                ScalarValue::TimestampNanosecond(Some(1599514949123456789), None)
+            )),
+            converter
+                .mutate(
+                    Expr::Column(Column::from_name("ts")).gt(Expr::Literal(ScalarValue::Utf8(
+                        Some("2020-09-08T05:42:29.123456789+08:00".to_string()),
+                    )))
+                )
+                .unwrap()
+        );
+
+        // timestamp literal with sub-millisecond part is not truncated
+        let literal = Expr::Literal(ScalarValue::TimestampNanosecond(
+            Some(1599514949123456789),
+            None,
+        ));
+        assert_eq!(literal, converter.mutate(literal.clone()).unwrap());
+    }
+
     #[test]
     fn test_convert_bool() {
         let col_name = "is_valid";
@@ -452,6 +574,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strict_type_coercion() {
+        use datatypes::arrow::datatypes::TimeUnit as ArrowTimeUnit;
+
+        let schema = Arc::new(
+            DFSchema::new_with_metadata(
+                vec![
+                    DFField::new(
+                        None::<TableReference>,
+                        "ts",
+                        DataType::Timestamp(ArrowTimeUnit::Millisecond, None),
+                        true,
+                    ),
+                    DFField::new(None::<TableReference>, "n", DataType::Int64, true),
+                ],
+                HashMap::new(),
+            )
+            .unwrap(),
+        );
+        let query_ctx = QueryContext::arc();
+        query_ctx
+            .configuration_parameter()
+            .set_type_coercion_mode(TypeCoercionMode::Strict);
+        let mut converter = TypeConverter { schema, query_ctx };
+
+        assert!(converter
+            .mutate(
+                Expr::Column(Column::from_name("ts")).gt(Expr::Literal(ScalarValue::Utf8(Some(
+                    "2020-09-08T05:42:29+08:00".to_string()
+                ))))
+            )
+            .is_err());
+        assert!(converter
+            .mutate(
+                Expr::Literal(ScalarValue::Utf8(Some("1".to_string())))
+                    .lt(Expr::Column(Column::from_name("n")))
+            )
+            .is_err());
+
+        // literals of the column type are left untouched
+        let expr =
+            Expr::Column(Column::from_name("n")).eq(Expr::Literal(ScalarValue::Int64(Some(1))));
+        assert_eq!(expr, converter.mutate(expr.clone()).unwrap());
+    }
+
     #[test]
     fn test_retrieve_type_from_aggr_plan() {
         let plan =
@@ -492,8 +659,8 @@ mod tests {
             .unwrap();
         let expected = String::from(
             "Aggregate: groupBy=[[]], aggr=[[COUNT(column1)]]\
-            \n  Filter: TimestampSecond(-28800, None) <= column3\
-            \n    Filter: column3 > TimestampSecond(-28800, None)\
+            \n  Filter: TimestampMillisecond(-28800000, None) <= column3\
+            \n    Filter: column3 > TimestampMillisecond(-28800000, None)\
             \n      Values: (Int64(1), Float64(1), TimestampMillisecond(1, None))",
         );
         assert_eq!(format!("{}", transformed_plan.display_indent()), expected);
@@ -14,10 +14,17 @@
 
 use std::sync::Arc;
 
+use catalog::process_manager::QueryStats;
+use common_recordbatch::adapter::CollectPlanMetrics;
 use common_telemetry::tracing_context::TracingContext;
 use datafusion::execution::context::{SessionState, TaskContext};
 use session::context::QueryContextRef;
 
+use crate::dist_plan::{RegionProfiles, ScanBytesLimit};
+use crate::query_engine::memory_pool::{
+    query_memory_limit, runtime_with_query_limit, QueryMemoryLimit, QueryMemoryRegistry,
+};
+
 #[derive(Debug)]
 pub struct QueryEngineContext {
     state: SessionState,
@@ -47,14 +54,53 @@ impl QueryEngineContext {
         // pass tracing context in session_id
         let session_id = tracing_context.to_json();
 
+        // Limits of the query are passed to the physical plans in the session config.
+        let mut config = state.config().clone();
+        if let Some(limit) = self.query_ctx.typed_extension::<ScanBytesLimit>() {
+            config = config.with_extension(limit);
+        }
+        if let Some(stats) = self.query_ctx.typed_extension::<QueryStats>() {
+            config = config.with_extension(stats);
+        }
+        if let Some(profiles) = self.query_ctx.typed_extension::<RegionProfiles>() {
+            config = config.with_extension(profiles);
+        }
+        if let Some(collect) = self.query_ctx.typed_extension::<CollectPlanMetrics>() {
+            config = config.with_extension(collect);
+        }
+
+        // The session can lower the memory limit of the engine. Merge scans pass the
+        // limit of the query to the regions they scan.
+        let memory_limit = query_memory_limit(
+            self.query_ctx
+                .configuration_parameter()
+                .query_memory_limit(),
+            config
+                .get_extension::<QueryMemoryLimit>()
+                .map_or(0, |limit| limit.0),
+        );
+        if memory_limit > 0 {
+            config = config.with_extension(Arc::new(QueryMemoryLimit(memory_limit)));
+        }
+        let registry = config.get_extension::<QueryMemoryRegistry>();
+        let runtime = if memory_limit > 0 || registry.is_some() {
+            Arc::new(runtime_with_query_limit(
+                state.runtime_env(),
+                memory_limit,
+                registry,
+            ))
+        } else {
+            state.runtime_env().clone()
+        };
+
         Arc::new(TaskContext::new(
             Some(dbname),
             session_id,
-            state.config().clone(),
+            config,
             state.scalar_functions().clone(),
             state.aggregate_functions().clone(),
             state.window_functions().clone(),
-            state.runtime_env().clone(),
+            runtime,
         ))
     }
 
@@ -0,0 +1,452 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory pools to limit the memory of queries.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use common_telemetry::warn;
+use datafusion::error::{DataFusionError, Result as DfResult};
+use datafusion::execution::disk_manager::DiskManagerConfig;
+use datafusion::execution::memory_pool::{
+    FairSpillPool, MemoryConsumer, MemoryPool, MemoryReservation,
+};
+use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+
+use crate::metrics::MEMORY_ABORTED_QUERIES_TOTAL;
+use crate::query_engine::options::QueryEngineOptions;
+
+/// Memory budget of queries in bytes, stored in the session config.
+///
+/// The config of the engine holds the max budget of each query configured by the
+/// admin, and the config of a task holds the budget of its query.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct QueryMemoryLimit(pub(crate) usize);
+
+/// Returns the memory budget of a query whose session sets `session_limit`, no limit if
+/// it's 0.
+///
+/// Sessions can only lower the budget below `max`, the max budget of the engine. A
+/// session that disables its limit still gets `max`.
+pub(crate) fn query_memory_limit(session_limit: Option<u64>, max: usize) -> usize {
+    match session_limit {
+        None | Some(0) => max,
+        Some(limit) if max == 0 => limit as usize,
+        Some(limit) => (limit as usize).min(max),
+    }
+}
+
+/// Creates the runtime shared by all queries of the engine.
+///
+/// Falls back to the default runtime without a memory limit if the spill directory
+/// is unavailable.
+pub(crate) fn new_runtime_env(options: &QueryEngineOptions) -> RuntimeEnv {
+    let mut config = RuntimeConfig::new();
+    if options.max_memory.as_bytes() > 0 {
+        // The fair pool allows spillable operators like sorts to spill before
+        // other operators run out of memory.
+        config = config.with_memory_pool(Arc::new(FairSpillPool::new(
+            options.max_memory.as_bytes() as usize,
+        )));
+    }
+    if let Some(spill_dir) = &options.spill_dir {
+        config = config.with_disk_manager(DiskManagerConfig::NewSpecified(vec![spill_dir.into()]));
+    }
+
+    RuntimeEnv::new(config).unwrap_or_else(|e| {
+        warn!(e; "Failed to create the runtime of the query engine, options: {:?}", options);
+        RuntimeEnv::default()
+    })
+}
+
+/// Returns a runtime that shares everything with the `runtime` but limits the memory of
+/// a query to `limit` bytes, no limit if it's 0.
+///
+/// The query registers itself to the `registry` until the runtime is dropped.
+pub(crate) fn runtime_with_query_limit(
+    runtime: &RuntimeEnv,
+    limit: usize,
+    registry: Option<Arc<QueryMemoryRegistry>>,
+) -> RuntimeEnv {
+    let limit = if limit == 0 { usize::MAX } else { limit };
+    RuntimeEnv {
+        memory_pool: Arc::new(QueryMemoryPool::new(
+            runtime.memory_pool.clone(),
+            limit,
+            registry,
+        )),
+        disk_manager: runtime.disk_manager.clone(),
+        cache_manager: runtime.cache_manager.clone(),
+        object_store_registry: runtime.object_store_registry.clone(),
+    }
+}
+
+/// Memory usage of a running query.
+#[derive(Debug, Default)]
+struct QueryMemoryUsage {
+    used: AtomicUsize,
+    /// Whether the query is aborted to release memory for other queries.
+    aborted: AtomicBool,
+}
+
+/// Running queries that share the memory of the engine.
+///
+/// Once the memory reserved by all queries exceeds the pressure threshold, each query
+/// is entitled to a fair share of the memory and the query using the most memory
+/// beyond its share is aborted, so the node fails a single query instead of running
+/// out of memory.
+///
+/// Memory of aborted queries doesn't count towards the pressure as they are about to
+/// release it, otherwise the next allocation would abort another query before the
+/// aborted one fails. Region scans reserve the batches they yield, so scans fail soon
+/// after their queries are aborted.
+#[derive(Debug)]
+pub(crate) struct QueryMemoryRegistry {
+    capacity: usize,
+    pressure_threshold: usize,
+    queries: Mutex<Vec<Arc<QueryMemoryUsage>>>,
+}
+
+impl QueryMemoryRegistry {
+    /// Returns the registry of the engine, `None` if fair sharing is disabled.
+    pub(crate) fn new(options: &QueryEngineOptions) -> Option<Self> {
+        let capacity = options.max_memory.as_bytes() as usize;
+        if capacity == 0 || options.memory_pressure_percent == 0 {
+            return None;
+        }
+        let percent = options.memory_pressure_percent.min(100) as usize;
+
+        Some(Self {
+            capacity,
+            pressure_threshold: capacity / 100 * percent,
+            queries: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn register(&self) -> Arc<QueryMemoryUsage> {
+        let usage = Arc::new(QueryMemoryUsage::default());
+        self.queries.lock().unwrap().push(usage.clone());
+        usage
+    }
+
+    fn unregister(&self, usage: &Arc<QueryMemoryUsage>) {
+        self.queries
+            .lock()
+            .unwrap()
+            .retain(|query| !Arc::ptr_eq(query, usage));
+    }
+
+    /// Checks whether `current` can grow once all queries reserve `reserved` bytes.
+    ///
+    /// Returns an error if `current` is the query to abort.
+    fn check_pressure(&self, reserved: usize, current: &Arc<QueryMemoryUsage>) -> DfResult<()> {
+        if reserved <= self.pressure_threshold {
+            return Ok(());
+        }
+
+        let queries = self.queries.lock().unwrap();
+        let (aborted, running): (Vec<_>, Vec<_>) = queries
+            .iter()
+            .partition(|query| query.aborted.load(Ordering::Relaxed));
+        let releasing = aborted
+            .iter()
+            .map(|query| query.used.load(Ordering::Relaxed))
+            .sum::<usize>();
+        let reserved = reserved.saturating_sub(releasing);
+        if reserved <= self.pressure_threshold {
+            return Ok(());
+        }
+
+        let fair_share = self.capacity / running.len().max(1);
+        let Some(largest) = running
+            .into_iter()
+            .max_by_key(|query| query.used.load(Ordering::Relaxed))
+        else {
+            return Ok(());
+        };
+        let largest_used = largest.used.load(Ordering::Relaxed);
+        if largest_used <= fair_share {
+            return Ok(());
+        }
+
+        // The aborted query fails on its next allocation.
+        largest.aborted.store(true, Ordering::Relaxed);
+        MEMORY_ABORTED_QUERIES_TOTAL.inc();
+        if Arc::ptr_eq(largest, current) {
+            return Err(DataFusionError::ResourcesExhausted(format!(
+                "Query is aborted as it uses the most memory, used: {}, fair share: {}, reserved by all queries: {}",
+                largest_used, fair_share, reserved
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Memory pool of a query.
+///
+/// It limits the memory reserved by the query while all queries still share the
+/// memory of the `inner` pool.
+#[derive(Debug)]
+struct QueryMemoryPool {
+    inner: Arc<dyn MemoryPool>,
+    limit: usize,
+    usage: Arc<QueryMemoryUsage>,
+    registry: Option<Arc<QueryMemoryRegistry>>,
+}
+
+impl QueryMemoryPool {
+    fn new(
+        inner: Arc<dyn MemoryPool>,
+        limit: usize,
+        registry: Option<Arc<QueryMemoryRegistry>>,
+    ) -> Self {
+        let usage = match &registry {
+            Some(registry) => registry.register(),
+            None => Arc::new(QueryMemoryUsage::default()),
+        };
+        Self {
+            inner,
+            limit,
+            usage,
+            registry,
+        }
+    }
+}
+
+impl Drop for QueryMemoryPool {
+    fn drop(&mut self) {
+        if let Some(registry) = &self.registry {
+            registry.unregister(&self.usage);
+        }
+    }
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional);
+        self.usage.used.fetch_add(additional, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink);
+        self.usage.used.fetch_sub(shrink, Ordering::Relaxed);
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> DfResult<()> {
+        if self.usage.aborted.load(Ordering::Relaxed) {
+            return Err(DataFusionError::ResourcesExhausted(format!(
+                "Failed to allocate {} bytes for {}, the query is aborted as the node is running out of memory",
+                additional,
+                reservation.consumer().name(),
+            )));
+        }
+
+        self.usage
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(additional)
+                    .filter(|new_used| *new_used <= self.limit)
+            })
+            .map_err(|used| {
+                DataFusionError::ResourcesExhausted(format!(
+                    "Failed to allocate {} bytes for {}, the query has allocated {} bytes, limit: {}",
+                    additional,
+                    reservation.consumer().name(),
+                    used,
+                    self.limit
+                ))
+            })?;
+
+        let result = match &self.registry {
+            Some(registry) => registry
+                .check_pressure(self.inner.reserved() + additional, &self.usage)
+                .and_then(|_| self.inner.try_grow(reservation, additional)),
+            None => self.inner.try_grow(reservation, additional),
+        };
+        if let Err(e) = result {
+            self.usage.used.fetch_sub(additional, Ordering::Relaxed);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.usage.used.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_base::readable_size::ReadableSize;
+    use datafusion::execution::memory_pool::GreedyMemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn test_query_memory_pool() {
+        let inner: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(100));
+        let pool: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(inner.clone(), 50, None));
+
+        let mut reservation = MemoryConsumer::new("sort").register(&pool);
+        reservation.try_grow(40).unwrap();
+        assert_eq!(40, pool.reserved());
+        assert_eq!(40, inner.reserved());
+
+        // Exceeds the limit of the query.
+        let err = reservation.try_grow(20).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_eq!(40, pool.reserved());
+
+        reservation.shrink(30);
+        reservation.try_grow(20).unwrap();
+        assert_eq!(30, pool.reserved());
+
+        // Another query shares the inner pool.
+        let other: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(inner.clone(), 100, None));
+        let mut other_reservation = MemoryConsumer::new("aggregate").register(&other);
+        other_reservation.try_grow(70).unwrap();
+        assert!(other_reservation.try_grow(1).is_err());
+        assert_eq!(70, other.reserved());
+
+        drop(reservation);
+        drop(other_reservation);
+        assert_eq!(0, pool.reserved());
+        assert_eq!(0, inner.reserved());
+    }
+
+    #[test]
+    fn test_query_memory_limit() {
+        // No max budget.
+        assert_eq!(0, query_memory_limit(None, 0));
+        assert_eq!(0, query_memory_limit(Some(0), 0));
+        assert_eq!(200, query_memory_limit(Some(200), 0));
+        // Sessions can't exceed the max budget.
+        assert_eq!(100, query_memory_limit(None, 100));
+        assert_eq!(100, query_memory_limit(Some(0), 100));
+        assert_eq!(100, query_memory_limit(Some(200), 100));
+        assert_eq!(50, query_memory_limit(Some(50), 100));
+    }
+
+    #[test]
+    fn test_abort_largest_query() {
+        let options = QueryEngineOptions {
+            max_memory: ReadableSize(100),
+            memory_pressure_percent: 80,
+            ..Default::default()
+        };
+        let registry = Arc::new(QueryMemoryRegistry::new(&options).unwrap());
+        let inner: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(100));
+        let large: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(
+            inner.clone(),
+            usize::MAX,
+            Some(registry.clone()),
+        ));
+        let small: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(
+            inner.clone(),
+            usize::MAX,
+            Some(registry.clone()),
+        ));
+
+        let mut large_reservation = MemoryConsumer::new("sort").register(&large);
+        let mut small_reservation = MemoryConsumer::new("aggregate").register(&small);
+        // Below the pressure threshold, a query can use more than its fair share.
+        large_reservation.try_grow(70).unwrap();
+        small_reservation.try_grow(10).unwrap();
+
+        // Exceeds the threshold, the large query is aborted.
+        small_reservation.try_grow(5).unwrap();
+        assert!(large_reservation.try_grow(1).is_err());
+
+        // The small query still gets memory after the large one releases its memory.
+        drop(large_reservation);
+        drop(large);
+        assert_eq!(1, registry.queries.lock().unwrap().len());
+        small_reservation.try_grow(40).unwrap();
+        assert_eq!(55, inner.reserved());
+    }
+
+    #[test]
+    fn test_no_cascading_abort() {
+        let options = QueryEngineOptions {
+            max_memory: ReadableSize(100),
+            memory_pressure_percent: 80,
+            ..Default::default()
+        };
+        let registry = Arc::new(QueryMemoryRegistry::new(&options).unwrap());
+        let inner: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(100));
+        let pools = (0..3)
+            .map(|_| {
+                Arc::new(QueryMemoryPool::new(
+                    inner.clone(),
+                    usize::MAX,
+                    Some(registry.clone()),
+                )) as Arc<dyn MemoryPool>
+            })
+            .collect::<Vec<_>>();
+        let mut reservations = pools
+            .iter()
+            .map(|pool| MemoryConsumer::new("sort").register(pool))
+            .collect::<Vec<_>>();
+
+        reservations[0].try_grow(45).unwrap();
+        reservations[1].try_grow(35).unwrap();
+        // Exceeds the threshold, the first query is aborted.
+        reservations[2].try_grow(5).unwrap();
+        assert!(reservations[0].try_grow(1).is_err());
+
+        // The second query also uses more than its fair share, but memory of the
+        // aborted query is about to be released.
+        reservations[2].try_grow(1).unwrap();
+        reservations[1].try_grow(1).unwrap();
+        assert_eq!(87, inner.reserved());
+    }
+
+    #[test]
+    fn test_abort_current_query() {
+        let options = QueryEngineOptions {
+            max_memory: ReadableSize(100),
+            memory_pressure_percent: 50,
+            ..Default::default()
+        };
+        let registry = Arc::new(QueryMemoryRegistry::new(&options).unwrap());
+        let inner: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(100));
+        let first: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(
+            inner.clone(),
+            usize::MAX,
+            Some(registry.clone()),
+        ));
+        let second: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(
+            inner.clone(),
+            usize::MAX,
+            Some(registry),
+        ));
+
+        let mut first_reservation = MemoryConsumer::new("sort").register(&first);
+        let _second_reservation = MemoryConsumer::new("aggregate").register(&second);
+        first_reservation.try_grow(50).unwrap();
+        let err = first_reservation.try_grow(10).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_eq!(50, first.reserved());
+        assert_eq!(50, inner.reserved());
+    }
+}
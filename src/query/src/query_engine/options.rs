@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_base::readable_size::ReadableSize;
+use serde::{Deserialize, Serialize};
 use session::context::QueryContextRef;
 use snafu::ensure;
 
@@ -22,6 +24,38 @@ pub struct QueryOptions {
     pub disallow_cross_catalog_query: bool,
 }
 
+/// Options of the memory used by the query engine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryEngineOptions {
+    /// Max memory of all running queries, no limit if it's 0.
+    ///
+    /// Sorts and aggregations spill to disk once they run out of memory.
+    pub max_memory: ReadableSize,
+    /// Max memory budget of each query, no limit if it's 0.
+    ///
+    /// Sessions can lower it by `SET query_memory_limit`.
+    pub query_memory_limit: ReadableSize,
+    /// Percentage of `max_memory` above which running queries fair share the memory,
+    /// disabled if it's 0.
+    ///
+    /// The query using the most memory beyond its fair share is aborted.
+    pub memory_pressure_percent: u8,
+    /// Directory to spill the data, uses the temp directory of the OS if it's not set.
+    pub spill_dir: Option<String>,
+}
+
+impl Default for QueryEngineOptions {
+    fn default() -> Self {
+        Self {
+            max_memory: ReadableSize(0),
+            query_memory_limit: ReadableSize(0),
+            memory_pressure_percent: 0,
+            spill_dir: None,
+        }
+    }
+}
+
 // TODO(shuiyisong): remove one method after #559 is done
 pub fn validate_catalog_and_schema(
     catalog: &str,
@@ -30,7 +30,6 @@ use datafusion::catalog::MemoryCatalogList;
 use datafusion::dataframe::DataFrame;
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::{QueryPlanner, SessionConfig, SessionState};
-use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion::physical_planner::{DefaultPhysicalPlanner, ExtensionPlanner, PhysicalPlanner};
 use datafusion_expr::LogicalPlan as DfLogicalPlan;
@@ -43,11 +42,13 @@ use table::table::adapter::DfTableProviderAdapter;
 use table::TableRef;
 
 use crate::dist_plan::{DistExtensionPlanner, DistPlannerAnalyzer};
+use crate::optimizer::case_insensitive::CaseInsensitiveRule;
 use crate::optimizer::order_hint::OrderHintRule;
 use crate::optimizer::string_normalization::StringNormalizationRule;
 use crate::optimizer::type_conversion::TypeConversionRule;
 use crate::optimizer::ExtensionAnalyzerRule;
-use crate::query_engine::options::QueryOptions;
+use crate::query_engine::memory_pool::{new_runtime_env, QueryMemoryLimit, QueryMemoryRegistry};
+use crate::query_engine::options::{QueryEngineOptions, QueryOptions};
 use crate::range_select::planner::RangeSelectPlanner;
 use crate::region_query::RegionQueryHandlerRef;
 use crate::QueryEngineContext;
@@ -84,8 +85,17 @@ impl QueryEngineState {
         with_dist_planner: bool,
         plugins: Plugins,
     ) -> Self {
-        let runtime_env = Arc::new(RuntimeEnv::default());
-        let session_config = SessionConfig::new().with_create_default_catalog_and_schema(false);
+        let options = plugins.get::<QueryEngineOptions>().unwrap_or_default();
+        let runtime_env = Arc::new(new_runtime_env(&options));
+        let mut session_config = SessionConfig::new().with_create_default_catalog_and_schema(false);
+        if options.query_memory_limit.as_bytes() > 0 {
+            session_config = session_config.with_extension(Arc::new(QueryMemoryLimit(
+                options.query_memory_limit.as_bytes() as usize,
+            )));
+        }
+        if let Some(registry) = QueryMemoryRegistry::new(&options) {
+            session_config = session_config.with_extension(Arc::new(registry));
+        }
         // Apply extension rules
         let mut extension_rules = Vec::new();
         // The [`TypeConversionRule`] must be at first
@@ -93,6 +103,7 @@ impl QueryEngineState {
         // Apply the datafusion rules
         let mut analyzer = Analyzer::new();
         analyzer.rules.insert(0, Arc::new(StringNormalizationRule));
+        analyzer.rules.insert(0, Arc::new(CaseInsensitiveRule));
         Self::remove_analyzer_rule(&mut analyzer.rules, CountWildcardRule {}.name());
         analyzer.rules.insert(0, Arc::new(CountWildcardRule {}));
         if with_dist_planner {
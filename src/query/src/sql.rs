@@ -14,13 +14,15 @@
 
 mod show_create_table;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use catalog::information_schema::{
-    columns, key_column_usage, schemata, tables, COLUMNS, KEY_COLUMN_USAGE, SCHEMATA, TABLES,
+    columns, key_column_usage, processlist, schemata, tables, InformationSchemaProvider, COLUMNS,
+    KEY_COLUMN_USAGE, PROCESSLIST, SCHEMATA, TABLES,
 };
 use catalog::CatalogManagerRef;
+use common_base::readable_size::ReadableSize;
 use common_catalog::consts::{
     INFORMATION_SCHEMA_NAME, SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_PRIMARY_KEY,
     SEMANTIC_TYPE_TIME_INDEX,
@@ -48,11 +50,12 @@ use regex::Regex;
 use session::context::QueryContextRef;
 pub use show_create_table::create_table_stmt;
 use snafu::{ensure, OptionExt, ResultExt};
+use sql::ast::Ident;
 use sql::statements::create::Partitions;
 use sql::statements::show::{
-    ShowColumns, ShowDatabases, ShowIndex, ShowKind, ShowTables, ShowVariables,
+    ShowColumns, ShowDatabases, ShowIndex, ShowKind, ShowProcesslist, ShowTables, ShowVariables,
 };
-use table::requests::{FILE_TABLE_LOCATION_KEY, FILE_TABLE_PATTERN_KEY};
+use table::requests::{FILE_TABLE_LOCATION_KEY, FILE_TABLE_PATTERN_KEY, FILE_TABLE_RECURSIVE_KEY};
 use table::TableRef;
 
 use crate::dataframe::DataFrame;
@@ -133,6 +136,25 @@ static SHOW_CREATE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
     ]))
 });
 
+static SHOW_CREATE_DATABASE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new("Database", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "Create Database",
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ]))
+});
+
+static SHOW_CREATE_USERS_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![ColumnSchema::new(
+        "Create Users",
+        ConcreteDataType::string_datatype(),
+        false,
+    )]))
+});
+
 fn null() -> Expr {
     lit(ScalarValue::Null)
 }
@@ -199,6 +221,13 @@ async fn query_from_information_schema_table(
                 table_name,
             ),
         })?;
+    let viewer = query_ctx.current_user();
+    let table = InformationSchemaProvider::table_for_viewer(
+        catalog_manager,
+        query_ctx.current_catalog(),
+        table,
+        viewer.as_ref().map(|user| user.username()),
+    );
 
     let DataFrame::DataFusion(dataframe) = query_engine.read_table(table)?;
 
@@ -469,6 +498,40 @@ pub async fn show_tables(
     .await
 }
 
+/// Execute `SHOW [FULL] PROCESSLIST` statement.
+pub async fn show_processlist(
+    _stmt: ShowProcesslist,
+    query_engine: &QueryEngineRef,
+    catalog_manager: &CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    let projects = vec![
+        (processlist::ID, "Id"),
+        (processlist::CATALOG, "Catalog"),
+        (processlist::SCHEMA, "Schema"),
+        (processlist::USER, "User"),
+        (processlist::START_TIME, "Start_time"),
+        (processlist::ELAPSED_MS, "Elapsed_ms"),
+        (processlist::FRONTEND, "Frontend"),
+        (processlist::QUERY, "Query"),
+    ];
+    let sort = vec![col(processlist::ID).sort(true, true)];
+
+    query_from_information_schema_table(
+        query_engine,
+        catalog_manager,
+        query_ctx,
+        PROCESSLIST,
+        vec![],
+        projects,
+        vec![],
+        None,
+        sort,
+        ShowKind::All,
+    )
+    .await
+}
+
 pub fn show_variable(stmt: ShowVariables, query_ctx: QueryContextRef) -> Result<Output> {
     let variable = stmt.variable.to_string().to_uppercase();
     let value = match variable.as_str() {
@@ -478,6 +541,11 @@ pub fn show_variable(stmt: ShowVariables, query_ctx: QueryContextRef) -> Result<
             let (style, order) = *query_ctx.configuration_parameter().pg_datetime_style();
             format!("{}, {}", style, order)
         }
+        "QUERY_MEMORY_LIMIT" => query_ctx
+            .configuration_parameter()
+            .query_memory_limit()
+            .map(|limit| ReadableSize(limit).to_string())
+            .unwrap_or_else(|| "default".to_string()),
         _ => return UnsupportedVariableSnafu { name: variable }.fail(),
     };
     let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
@@ -501,24 +569,69 @@ pub fn show_create_table(
     let table_info = table.table_info();
     let table_name = &table_info.name;
 
-    let quote_style = query_ctx.quote_style();
+    let sql = create_table_sql(&table, partitions, query_ctx.quote_style())?;
+    let columns = vec![
+        Arc::new(StringVector::from(vec![table_name.clone()])) as _,
+        Arc::new(StringVector::from(vec![sql])) as _,
+    ];
+    let records = RecordBatches::try_from_columns(SHOW_CREATE_TABLE_OUTPUT_SCHEMA.clone(), columns)
+        .context(error::CreateRecordBatchSnafu)?;
+
+    Ok(Output::new_with_record_batches(records))
+}
 
-    let mut stmt = create_table_stmt(&table_info, quote_style)?;
+/// Returns the `CREATE TABLE` statement of `table`.
+pub fn create_table_sql(
+    table: &TableRef,
+    partitions: Option<Partitions>,
+    quote_style: char,
+) -> Result<String> {
+    let mut stmt = create_table_stmt(&table.table_info(), quote_style)?;
     stmt.partitions = partitions.map(|mut p| {
         p.set_quote(quote_style);
         p
     });
-    let sql = format!("{}", stmt);
+    Ok(stmt.to_string())
+}
+
+pub fn show_create_database(database_name: &str, sql: String) -> Result<Output> {
     let columns = vec![
-        Arc::new(StringVector::from(vec![table_name.clone()])) as _,
+        Arc::new(StringVector::from(vec![database_name.to_string()])) as _,
         Arc::new(StringVector::from(vec![sql])) as _,
     ];
-    let records = RecordBatches::try_from_columns(SHOW_CREATE_TABLE_OUTPUT_SCHEMA.clone(), columns)
+    let records =
+        RecordBatches::try_from_columns(SHOW_CREATE_DATABASE_OUTPUT_SCHEMA.clone(), columns)
+            .context(error::CreateRecordBatchSnafu)?;
+
+    Ok(Output::new_with_record_batches(records))
+}
+
+pub fn show_create_users(sql: String) -> Result<Output> {
+    let columns = vec![Arc::new(StringVector::from(vec![sql])) as _];
+    let records = RecordBatches::try_from_columns(SHOW_CREATE_USERS_OUTPUT_SCHEMA.clone(), columns)
         .context(error::CreateRecordBatchSnafu)?;
 
     Ok(Output::new_with_record_batches(records))
 }
 
+/// Returns the `CREATE DATABASE` statement of `database_name` with `options` in `WITH`.
+pub fn create_database_sql(
+    database_name: &str,
+    options: &BTreeMap<String, String>,
+    quote_style: char,
+) -> String {
+    let name = Ident::with_quote(quote_style, database_name);
+    if options.is_empty() {
+        return format!("CREATE DATABASE IF NOT EXISTS {name}");
+    }
+    let options = options
+        .iter()
+        .map(|(k, v)| format!("  '{}' = '{}'", k, v.replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("CREATE DATABASE IF NOT EXISTS {name} WITH(\n{options}\n)")
+}
+
 pub fn describe_table(table: TableRef) -> Result<Output> {
     let table_info = table.table_info();
     let columns_schemas = table_info.meta.schema.column_schemas();
@@ -627,8 +740,15 @@ pub async fn prepare_file_table_files(
         .map(|x| Regex::new(x))
         .transpose()
         .context(error::BuildRegexSnafu)?;
+    // Only lists files in sub directories, e.g. partitions in hive style layout, if asked,
+    // so existing tables keep reading the same files.
+    let recursive = options
+        .get(FILE_TABLE_RECURSIVE_KEY)
+        .map(|x| x.parse::<bool>().context(error::ParseBoolSnafu { raw: x }))
+        .transpose()?
+        .unwrap_or(false);
     let object_store = build_backend(url, options).context(error::BuildBackendSnafu)?;
-    let lister = Lister::new(object_store.clone(), source, dir, regex);
+    let lister = Lister::new(object_store.clone(), source, dir, regex).with_recursive(recursive);
     // If we scan files in a directory every time the database restarts,
     // then it might lead to a potential undefined behavior:
     // If a user adds a file with an incompatible schema to that directory,
@@ -773,7 +893,7 @@ mod test {
     use table::test_util::MemTable;
     use table::TableRef;
 
-    use super::show_variable;
+    use super::{create_database_sql, show_variable};
     use crate::error;
     use crate::error::Result;
     use crate::sql::{
@@ -869,6 +989,26 @@ mod test {
         assert!(exec_show_variable("SYSTEM TIME ZONE", "Asia/Shanghai").is_err());
     }
 
+    #[test]
+    fn test_create_database_sql() {
+        assert_eq!(
+            "CREATE DATABASE IF NOT EXISTS `my db`",
+            create_database_sql("my db", &Default::default(), '`')
+        );
+
+        let options = [("ttl", "7days"), ("auto_create_table.influxdb", "false")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(
+            r#"CREATE DATABASE IF NOT EXISTS "test" WITH(
+  'auto_create_table.influxdb' = 'false',
+  'ttl' = '7days'
+)"#,
+            create_database_sql("test", &options, '"')
+        );
+    }
+
     fn exec_show_variable(variable: &str, tz: &str) -> Result<String> {
         let stmt = ShowVariables {
             variable: ObjectName(vec![Ident::new(variable)]),
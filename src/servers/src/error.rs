@@ -128,6 +128,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to get the version of table {}", table))]
+    GetTableVersion {
+        table: String,
+        location: Location,
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to describe statement"))]
     DescribeStatement { source: BoxedError },
 
@@ -243,6 +250,9 @@ pub enum Error {
     #[snafu(display("Invalid export metrics config, msg: {}", msg))]
     InvalidExportMetricsConfig { msg: String, location: Location },
 
+    #[snafu(display("Invalid prometheus relabel config, msg: {}", msg))]
+    InvalidRelabelConfig { msg: String, location: Location },
+
     #[snafu(display("Failed to compress prometheus remote request"))]
     CompressPromRemoteRequest {
         location: Location,
@@ -272,6 +282,20 @@ pub enum Error {
         source: auth::error::Error,
     },
 
+    #[snafu(display("Failed to check whether user {} is an admin", username))]
+    CheckAdmin {
+        username: String,
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("Failed to check privileges of user {}", username))]
+    CheckPrivilege {
+        username: String,
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
     #[snafu(display("Not found http or grpc authorization header"))]
     NotFoundAuthHeader {},
 
@@ -318,6 +342,9 @@ pub enum Error {
     #[snafu(display("Cannot find requested database: {}-{}", catalog, schema))]
     DatabaseNotFound { catalog: String, schema: String },
 
+    #[snafu(display("Table not found: {}", table))]
+    TableNotFound { table: String, location: Location },
+
     #[cfg(feature = "mem-prof")]
     #[snafu(display("Failed to dump profile data"))]
     DumpProfileData {
@@ -378,9 +405,7 @@ pub enum Error {
 
     #[cfg(feature = "pprof")]
     #[snafu(display("Failed to dump pprof data"))]
-    DumpPprof {
-        source: crate::http::pprof::nix::Error,
-    },
+    DumpPprof { source: common_pprof::error::Error },
 
     #[cfg(not(windows))]
     #[snafu(display("Failed to update jemalloc metrics"))]
@@ -499,7 +524,8 @@ impl ErrorExt for Error {
             | ExecutePlan { source, .. }
             | ExecuteGrpcQuery { source, .. }
             | ExecuteGrpcRequest { source, .. }
-            | CheckDatabaseValidity { source, .. } => source.status_code(),
+            | CheckDatabaseValidity { source, .. }
+            | GetTableVersion { source, .. } => source.status_code(),
 
             NotSupported { .. }
             | InvalidParameter { .. }
@@ -515,6 +541,7 @@ impl ErrorExt for Error {
             | DecompressZstdPromRemoteRequest { .. }
             | InvalidPromRemoteRequest { .. }
             | InvalidExportMetricsConfig { .. }
+            | InvalidRelabelConfig { .. }
             | InvalidFlightTicket { .. }
             | InvalidPrepareStatement { .. }
             | DataFrame { .. }
@@ -533,6 +560,7 @@ impl ErrorExt for Error {
             Hyper { .. } => StatusCode::Unknown,
             TlsRequired { .. } => StatusCode::Unknown,
             Auth { source, .. } => source.status_code(),
+            CheckAdmin { source, .. } | CheckPrivilege { source, .. } => source.status_code(),
             DescribeStatement { source } => source.status_code(),
 
             NotFoundAuthHeader { .. } | NotFoundInfluxAuth { .. } => StatusCode::AuthHeaderNotFound,
@@ -543,6 +571,7 @@ impl ErrorExt for Error {
             | InvalidAuthHeaderInvalidUtf8Value { .. } => StatusCode::InvalidAuthHeader,
 
             DatabaseNotFound { .. } => StatusCode::DatabaseNotFound,
+            TableNotFound { .. } => StatusCode::TableNotFound,
             #[cfg(feature = "mem-prof")]
             DumpProfileData { source, .. } => source.status_code(),
 
@@ -604,6 +633,7 @@ pub fn status_to_tonic_code(status_code: StatusCode) -> Code {
         StatusCode::StorageUnavailable | StatusCode::RegionNotReady => Code::Unavailable,
         StatusCode::RuntimeResourcesExhausted
         | StatusCode::RateLimited
+        | StatusCode::QuotaExceeded
         | StatusCode::RegionBusy => Code::ResourceExhausted,
         StatusCode::UnsupportedPasswordType
         | StatusCode::UserPasswordMismatch
@@ -654,10 +684,10 @@ impl From<std::io::Error> for Error {
     }
 }
 
-impl IntoResponse for Error {
-    fn into_response(self) -> Response {
-        let error_msg = self.output_msg();
-        let status = match self {
+impl Error {
+    /// Returns the HTTP status code to respond with for this error.
+    pub fn http_status_code(&self) -> HttpStatusCode {
+        match self {
             Error::InfluxdbLineProtocol { .. }
             | Error::InfluxdbLinesWrite { .. }
             | Error::PromSeriesWrite { .. }
@@ -670,16 +700,25 @@ impl IntoResponse for Error {
             | Error::InvalidPromRemoteRequest { .. }
             | Error::InvalidQuery { .. }
             | Error::TimePrecision { .. } => HttpStatusCode::BAD_REQUEST,
-            _ => {
-                if self.status_code().should_log_error() {
-                    error!(self; "Failed to handle HTTP request: ");
-                } else {
-                    debug!("Failed to handle HTTP request: {self}");
-                }
+            Error::DatabaseNotFound { .. } | Error::TableNotFound { .. } => {
+                HttpStatusCode::NOT_FOUND
+            }
+            _ => HttpStatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
 
-                HttpStatusCode::INTERNAL_SERVER_ERROR
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let error_msg = self.output_msg();
+        let status = self.http_status_code();
+        if status == HttpStatusCode::INTERNAL_SERVER_ERROR {
+            if self.status_code().should_log_error() {
+                error!(self; "Failed to handle HTTP request: ");
+            } else {
+                debug!("Failed to handle HTTP request: {self}");
             }
-        };
+        }
         let body = Json(json!({
             "error": error_msg,
         }));
@@ -36,8 +36,11 @@ use snafu::{ensure, OptionExt, ResultExt};
 use tokio::net::TcpListener;
 use tokio::sync::oneshot::{self, Receiver, Sender};
 use tokio::sync::Mutex;
+use tonic::server::NamedService;
 use tonic::transport::server::{Routes, TcpIncoming};
 use tonic::{Request, Response, Status};
+use tonic_health::server::{Health, HealthReporter, HealthServer};
+use tonic_health::ServingStatus;
 use tonic_reflection::server::{ServerReflection, ServerReflectionServer};
 
 use crate::error::{
@@ -56,6 +59,11 @@ pub struct GrpcServer {
     serve_state: Mutex<Option<Receiver<Result<()>>>>,
     // handlers
     routes: Mutex<Option<Routes>>,
+    /// Names of the services in `routes`, which are exposed through the health
+    /// and reflection services.
+    service_names: Vec<&'static str>,
+    /// Reporter of the standard health service. Only present if the gRPC server is started.
+    health_reporter: Mutex<Option<HealthReporter>>,
 }
 
 /// Grpc Server configuration
@@ -82,13 +90,9 @@ impl GrpcServer {
     }
 
     pub fn create_reflection_service(&self) -> ServerReflectionServer<impl ServerReflection> {
-        tonic_reflection::server::Builder::configure()
-            .register_encoded_file_descriptor_set(api::v1::GREPTIME_GRPC_DESC)
-            .with_service_name("greptime.v1.GreptimeDatabase")
-            .with_service_name("greptime.v1.HealthCheck")
-            .with_service_name("greptime.v1.RegionServer")
-            .build()
-            .unwrap()
+        let mut service_names = self.service_names.clone();
+        service_names.push(<HealthCheckServer<HealthCheckHandler> as NamedService>::NAME);
+        create_reflection_service(&service_names)
     }
 
     pub async fn wait_for_serve(&self) -> Result<()> {
@@ -108,6 +112,38 @@ impl GrpcServer {
     }
 }
 
+/// Creates the standard gRPC health service (`grpc.health.v1.Health`) used by
+/// probes like `grpc-health-probe`. The server and each of `service_names` are
+/// reported as serving.
+pub async fn create_health_service(
+    service_names: &[&str],
+) -> (HealthReporter, HealthServer<impl Health>) {
+    let (mut reporter, service) = tonic_health::server::health_reporter();
+    for service_name in service_names {
+        reporter
+            .set_service_status(service_name, ServingStatus::Serving)
+            .await;
+    }
+    (reporter, service)
+}
+
+/// Creates the gRPC server reflection service, which lists `service_names` and
+/// the health and reflection services themselves for tools like `grpcurl`.
+pub fn create_reflection_service(
+    service_names: &[&str],
+) -> ServerReflectionServer<impl ServerReflection> {
+    let mut builder = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(api::v1::GREPTIME_GRPC_DESC)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_reflection::pb::FILE_DESCRIPTOR_SET)
+        .with_service_name(HEALTH_SERVICE_NAME)
+        .with_service_name(REFLECTION_SERVICE_NAME);
+    for service_name in service_names {
+        builder = builder.with_service_name(*service_name);
+    }
+    builder.build().unwrap()
+}
+
 pub struct HealthCheckHandler;
 
 #[async_trait]
@@ -122,9 +158,19 @@ impl HealthCheck for HealthCheckHandler {
 
 pub const GRPC_SERVER: &str = "GRPC_SERVER";
 
+const HEALTH_SERVICE_NAME: &str = "grpc.health.v1.Health";
+const REFLECTION_SERVICE_NAME: &str = "grpc.reflection.v1alpha.ServerReflection";
+
 #[async_trait]
 impl Server for GrpcServer {
     async fn shutdown(&self) -> Result<()> {
+        // Lets probes see the server as not serving while it is draining.
+        if let Some(mut health_reporter) = self.health_reporter.lock().await.take() {
+            health_reporter
+                .set_service_status("", ServingStatus::NotServing)
+                .await;
+        }
+
         let mut shutdown_tx = self.shutdown_tx.lock().await;
         if let Some(tx) = shutdown_tx.take() {
             if tx.send(()).is_err() {
@@ -173,10 +219,14 @@ impl Server for GrpcServer {
             .layer(MetricsMiddlewareLayer)
             .into_inner();
 
+        let (health_reporter, health_service) = create_health_service(&self.service_names).await;
+        *self.health_reporter.lock().await = Some(health_reporter);
+
         let builder = tonic::transport::Server::builder()
             .layer(metrics_layer)
             .add_routes(routes)
             .add_service(self.create_healthcheck_service())
+            .add_service(health_service)
             .add_service(self.create_reflection_service());
 
         let (serve_state_tx, serve_state_rx) = oneshot::channel();
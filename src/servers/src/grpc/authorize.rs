@@ -18,7 +18,7 @@ use std::task::{Context, Poll};
 
 use auth::UserProviderRef;
 use hyper::Body;
-use session::context::QueryContext;
+use session::context::{Channel, QueryContextBuilder};
 use tonic::body::BoxBody;
 use tonic::transport::NamedService;
 use tower::{Layer, Service};
@@ -104,7 +104,11 @@ async fn do_auth<T>(
 ) -> Result<(), tonic::Status> {
     let (catalog, schema) = extract_catalog_and_schema(req);
 
-    let query_ctx = QueryContext::with(&catalog, &schema);
+    let query_ctx = QueryContextBuilder::default()
+        .current_catalog(catalog)
+        .current_schema(schema)
+        .channel(Channel::Grpc)
+        .build();
 
     let Some(user_provider) = user_provider else {
         query_ctx.set_current_user(Some(auth::userinfo_by_name(None)));
@@ -20,9 +20,11 @@ use api::v1::region::region_server::RegionServer;
 use arrow_flight::flight_service_server::FlightServiceServer;
 use auth::UserProviderRef;
 use common_runtime::Runtime;
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_server::LogsServiceServer;
 use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_server::MetricsServiceServer;
 use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::TraceServiceServer;
 use tokio::sync::Mutex;
+use tonic::server::NamedService;
 use tonic::transport::server::RoutesBuilder;
 use tower::ServiceBuilder;
 
@@ -38,25 +40,32 @@ use crate::prometheus_handler::PrometheusHandlerRef;
 use crate::query_handler::OpenTelemetryProtocolHandlerRef;
 
 /// Add a gRPC service (`service`) to a `builder`([RoutesBuilder]).
-/// This macro will automatically add some gRPC properties to the service.
+/// This macro will automatically add some gRPC properties to the service,
+/// and expose it through the health and reflection services.
 #[macro_export]
 macro_rules! add_service {
     ($builder: ident, $service: expr) => {
         let max_recv_message_size = $builder.config().max_recv_message_size;
         let max_send_message_size = $builder.config().max_send_message_size;
 
-        $builder.routes_builder_mut().add_service(
-            $service
-                .max_decoding_message_size(max_recv_message_size)
-                .max_encoding_message_size(max_send_message_size),
-        )
+        let service = $service
+            .max_decoding_message_size(max_recv_message_size)
+            .max_encoding_message_size(max_send_message_size);
+        $builder.add_service_name($crate::grpc::builder::service_name(&service));
+        $builder.routes_builder_mut().add_service(service)
     };
 }
 
+/// Returns the fully qualified name of a gRPC `service`.
+pub fn service_name<S: NamedService>(_service: &S) -> &'static str {
+    S::NAME
+}
+
 pub struct GrpcServerBuilder {
     config: GrpcServerConfig,
     runtime: Arc<Runtime>,
     routes_builder: RoutesBuilder,
+    service_names: Vec<&'static str>,
 }
 
 impl GrpcServerBuilder {
@@ -65,6 +74,7 @@ impl GrpcServerBuilder {
             config,
             runtime,
             routes_builder: RoutesBuilder::default(),
+            service_names: Vec::new(),
         }
     }
 
@@ -131,13 +141,25 @@ impl GrpcServerBuilder {
         self.routes_builder.add_service(trace_server);
 
         let metrics_server = ServiceBuilder::new()
-            .layer(AuthMiddlewareLayer::with(user_provider))
-            .service(MetricsServiceServer::new(OtlpService::new(otlp_handler)));
+            .layer(AuthMiddlewareLayer::with(user_provider.clone()))
+            .service(MetricsServiceServer::new(OtlpService::new(
+                otlp_handler.clone(),
+            )));
         self.routes_builder.add_service(metrics_server);
 
+        let logs_server = ServiceBuilder::new()
+            .layer(AuthMiddlewareLayer::with(user_provider))
+            .service(LogsServiceServer::new(OtlpService::new(otlp_handler)));
+        self.routes_builder.add_service(logs_server);
+
         self
     }
 
+    /// Exposes the service named `service_name` through the health and reflection services.
+    pub fn add_service_name(&mut self, service_name: &'static str) {
+        self.service_names.push(service_name);
+    }
+
     pub fn routes_builder_mut(&mut self) -> &mut RoutesBuilder {
         &mut self.routes_builder
     }
@@ -147,6 +169,8 @@ impl GrpcServerBuilder {
             routes: Mutex::new(Some(self.routes_builder.routes())),
             shutdown_tx: Mutex::new(None),
             serve_state: Mutex::new(None),
+            service_names: self.service_names,
+            health_reporter: Mutex::new(None),
         }
     }
 }
@@ -21,7 +21,7 @@ use common_query::OutputData;
 use futures::StreamExt;
 use tonic::{Request, Response, Status, Streaming};
 
-use crate::grpc::greptime_handler::GreptimeRequestHandler;
+use crate::grpc::greptime_handler::{set_ddl_result, GreptimeRequestHandler};
 use crate::grpc::TonicResult;
 
 pub(crate) struct DatabaseService {
@@ -40,8 +40,10 @@ impl GreptimeDatabase for DatabaseService {
         &self,
         request: Request<GreptimeRequest>,
     ) -> TonicResult<Response<GreptimeResponse>> {
+        let metadata = request.metadata().clone();
         let request = request.into_inner();
-        let output = self.handler.handle_request(request).await?;
+        let output = self.handler.handle_request(request, &metadata).await?;
+        let ddl = output.meta.ddl;
         let message = match output.data {
             OutputData::AffectedRows(rows) => GreptimeResponse {
                 header: Some(ResponseHeader {
@@ -56,7 +58,11 @@ impl GreptimeDatabase for DatabaseService {
                 return Err(Status::unimplemented("GreptimeDatabase::Handle for query"));
             }
         };
-        Ok(Response::new(message))
+        let mut response = Response::new(message);
+        if let Some(ddl) = &ddl {
+            set_ddl_result(response.metadata_mut(), ddl);
+        }
+        Ok(response)
     }
 
     async fn handle_requests(
@@ -65,10 +71,11 @@ impl GreptimeDatabase for DatabaseService {
     ) -> Result<Response<GreptimeResponse>, Status> {
         let mut affected_rows = 0;
 
+        let metadata = request.metadata().clone();
         let mut stream = request.into_inner();
         while let Some(request) = stream.next().await {
             let request = request?;
-            let output = self.handler.handle_request(request).await?;
+            let output = self.handler.handle_request(request, &metadata).await?;
             match output.data {
                 OutputData::AffectedRows(rows) => affected_rows += rows,
                 OutputData::Stream(_) | OutputData::RecordBatches(_) => {
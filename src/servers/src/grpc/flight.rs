@@ -35,7 +35,7 @@ use tonic::{Request, Response, Status, Streaming};
 
 use crate::error;
 pub use crate::grpc::flight::stream::FlightRecordBatchStream;
-use crate::grpc::greptime_handler::{get_request_type, GreptimeRequestHandler};
+use crate::grpc::greptime_handler::{get_request_type, set_ddl_result, GreptimeRequestHandler};
 use crate::grpc::TonicResult;
 
 pub type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + Sync + 'static>>;
@@ -149,6 +149,7 @@ impl FlightCraft for GreptimeRequestHandler {
         &self,
         request: Request<Ticket>,
     ) -> TonicResult<Response<TonicStream<FlightData>>> {
+        let metadata = request.metadata().clone();
         let ticket = request.into_inner().ticket;
         let request =
             GreptimeRequest::decode(ticket.as_ref()).context(error::InvalidFlightTicketSnafu)?;
@@ -160,10 +161,15 @@ impl FlightCraft for GreptimeRequestHandler {
             request_type = get_request_type(&request)
         );
         async {
-            let output = self.handle_request(request).await?;
+            let output = self.handle_request(request, &metadata).await?;
+            let ddl = output.meta.ddl;
             let stream: Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send + Sync>> =
                 to_flight_data_stream(output, TracingContext::from_current_span());
-            Ok(Response::new(stream))
+            let mut response = Response::new(stream);
+            if let Some(ddl) = &ddl {
+                set_ddl_result(response.metadata_mut(), ddl);
+            }
+            Ok(response)
         }
         .trace(span)
         .await
@@ -25,19 +25,30 @@ use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_catalog::parse_catalog_and_schema_from_db_string;
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
-use common_query::Output;
+use common_query::{DdlResult, Output};
 use common_runtime::Runtime;
 use common_telemetry::tracing_context::{FutureExt, TracingContext};
 use common_telemetry::{logging, tracing};
 use common_time::timezone::parse_timezone;
-use session::context::{QueryContextBuilder, QueryContextRef};
+use session::context::{sql_dialect_by_name, Channel, QueryContextBuilder, QueryContextRef};
 use snafu::{OptionExt, ResultExt};
+use tonic::metadata::{MetadataMap, MetadataValue};
 
 use crate::error::Error::UnsupportedAuthScheme;
 use crate::error::{AuthSnafu, InvalidQuerySnafu, JoinTaskSnafu, NotFoundAuthHeaderSnafu, Result};
+use crate::http::header::constants::GREPTIME_DB_HEADER_DIALECT;
 use crate::metrics::{METRIC_AUTH_FAILURE, METRIC_SERVER_GRPC_DB_REQUEST_TIMER};
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
 
+/// gRPC metadata choosing how inserts treat rows that already exist, either `overwrite`
+/// (the default) or `ignore`. It's kept in the query context under the same key.
+pub const GREPTIME_DB_HEADER_INSERT_MODE: &str = "x-greptime-insert-mode";
+/// gRPC response metadata telling what a DDL does to its object, e.g. `created` or
+/// `already_exists`.
+pub const GREPTIME_DB_HEADER_DDL_STATUS: &str = "x-greptime-ddl-status";
+/// gRPC response metadata of the version of the object after a DDL.
+pub const GREPTIME_DB_HEADER_DDL_VERSION: &str = "x-greptime-ddl-version";
+
 #[derive(Clone)]
 pub struct GreptimeRequestHandler {
     handler: ServerGrpcQueryHandlerRef,
@@ -59,13 +70,19 @@ impl GreptimeRequestHandler {
     }
 
     #[tracing::instrument(skip_all, fields(protocol = "grpc", request_type = get_request_type(&request)))]
-    pub(crate) async fn handle_request(&self, request: GreptimeRequest) -> Result<Output> {
+    pub(crate) async fn handle_request(
+        &self,
+        request: GreptimeRequest,
+        metadata: &MetadataMap,
+    ) -> Result<Output> {
         let query = request.request.context(InvalidQuerySnafu {
             reason: "Expecting non-empty GreptimeRequest.",
         })?;
 
         let header = request.header.as_ref();
         let query_ctx = create_query_context(header);
+        let query_ctx = with_insert_mode(query_ctx, metadata);
+        let query_ctx = with_sql_dialect(query_ctx, metadata)?;
         let user_info = auth(self.user_provider.clone(), header, &query_ctx).await?;
         query_ctx.set_current_user(user_info);
 
@@ -156,6 +173,46 @@ pub(crate) async fn auth(
     })
 }
 
+/// Sets the result of a DDL into the gRPC response metadata.
+pub(crate) fn set_ddl_result(metadata: &mut MetadataMap, ddl: &DdlResult) {
+    let _ = metadata.insert(
+        GREPTIME_DB_HEADER_DDL_STATUS,
+        MetadataValue::from_static(ddl.status.as_str()),
+    );
+    if let Some(version) = ddl.version {
+        let _ = metadata.insert(GREPTIME_DB_HEADER_DDL_VERSION, MetadataValue::from(version));
+    }
+}
+
+/// Copies the insert mode from gRPC metadata into the query context, if present.
+fn with_insert_mode(query_ctx: QueryContextRef, metadata: &MetadataMap) -> QueryContextRef {
+    let Some(mode) = metadata
+        .get(GREPTIME_DB_HEADER_INSERT_MODE)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return query_ctx;
+    };
+    let mut query_ctx = query_ctx.as_ref().clone();
+    query_ctx.set_extension(GREPTIME_DB_HEADER_INSERT_MODE, mode);
+    Arc::new(query_ctx)
+}
+
+/// Sets the SQL dialect from gRPC metadata into the query context, if present.
+fn with_sql_dialect(query_ctx: QueryContextRef, metadata: &MetadataMap) -> Result<QueryContextRef> {
+    let Some(name) = metadata
+        .get(GREPTIME_DB_HEADER_DIALECT)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return Ok(query_ctx);
+    };
+    let sql_dialect = sql_dialect_by_name(name).with_context(|| InvalidQuerySnafu {
+        reason: format!("unknown SQL dialect: {name}"),
+    })?;
+    let mut query_ctx = query_ctx.as_ref().clone();
+    query_ctx.set_sql_dialect(sql_dialect);
+    Ok(Arc::new(query_ctx))
+}
+
 pub(crate) fn create_query_context(header: Option<&RequestHeader>) -> QueryContextRef {
     let (catalog, schema) = header
         .map(|header| {
@@ -189,6 +246,7 @@ pub(crate) fn create_query_context(header: Option<&RequestHeader>) -> QueryConte
         .current_catalog(catalog)
         .current_schema(schema)
         .timezone(Arc::new(timezone))
+        .channel(Channel::Grpc)
         .build()
 }
 
@@ -14,6 +14,10 @@
 
 use std::result::Result as StdResult;
 
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_server::LogsService;
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
 use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_server::MetricsService;
 use opentelemetry_proto::tonic::collector::metrics::v1::{
     ExportMetricsServiceRequest, ExportMetricsServiceResponse,
@@ -27,6 +31,7 @@ use snafu::OptionExt;
 use tonic::{Request, Response, Status};
 
 use crate::error;
+use crate::otlp::logs::table_name_from_headers;
 use crate::query_handler::OpenTelemetryProtocolHandlerRef;
 
 pub struct OtlpService {
@@ -80,3 +85,25 @@ impl MetricsService for OtlpService {
         }))
     }
 }
+
+#[async_trait::async_trait]
+impl LogsService for OtlpService {
+    async fn export(
+        &self,
+        request: Request<ExportLogsServiceRequest>,
+    ) -> StdResult<Response<ExportLogsServiceResponse>, Status> {
+        let (headers, extensions, req) = request.into_parts();
+
+        let ctx = extensions
+            .get::<QueryContextRef>()
+            .cloned()
+            .context(error::MissingQueryContextSnafu)?;
+        let table_name = table_name_from_headers(&headers.into_headers())?;
+
+        let _ = self.handler.logs(req, table_name, ctx).await?;
+
+        Ok(Response::new(ExportLogsServiceResponse {
+            partial_success: None,
+        }))
+    }
+}
@@ -15,7 +15,7 @@
 use std::sync::Arc;
 
 use api::v1::region::region_server::Region as RegionServer;
-use api::v1::region::{region_request, RegionRequest, RegionResponse};
+use api::v1::region::{region_request, RegionRequest, RegionRequestHeader, RegionResponse};
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
 use common_runtime::Runtime;
@@ -30,7 +30,11 @@ use crate::grpc::TonicResult;
 
 #[async_trait]
 pub trait RegionServerHandler: Send + Sync {
-    async fn handle(&self, request: region_request::Body) -> Result<RegionResponse>;
+    async fn handle(
+        &self,
+        header: RegionRequestHeader,
+        request: region_request::Body,
+    ) -> Result<RegionResponse>;
 }
 
 pub type RegionServerHandlerRef = Arc<dyn RegionServerHandler>;
@@ -47,14 +51,10 @@ impl RegionServerRequestHandler {
     }
 
     async fn handle(&self, request: RegionRequest) -> Result<RegionResponse> {
-        let tracing_context = TracingContext::from_w3c(
-            &request
-                .header
-                .context(InvalidQuerySnafu {
-                    reason: "Expecting non-empty region request header.",
-                })?
-                .tracing_context,
-        );
+        let header = request.header.context(InvalidQuerySnafu {
+            reason: "Expecting non-empty region request header.",
+        })?;
+        let tracing_context = TracingContext::from_w3c(&header.tracing_context);
         let query = request.body.context(InvalidQuerySnafu {
             reason: "Expecting non-empty region request body.",
         })?;
@@ -70,7 +70,7 @@ impl RegionServerRequestHandler {
         // 2. avoid the handler blocks the gRPC runtime incidentally.
         let handle = self.runtime.spawn(async move {
             handler
-                .handle(query)
+                .handle(header, query)
                 .trace(tracing_context.attach(info_span!("RegionServerRequestHandler::handle")))
                 .await
                 .map_err(|e| {
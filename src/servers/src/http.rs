@@ -15,21 +15,26 @@
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::net::SocketAddr;
-use std::sync::Mutex as StdMutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
 
 use aide::axum::{routing as apirouting, ApiRouter, IntoApiResponse};
 use aide::openapi::{Info, OpenApi, Server as OpenAPIServer};
 use aide::OperationOutput;
 use async_trait::async_trait;
-use auth::UserProviderRef;
+use auth::{PermissionCheckerRef, UserProviderRef};
 use axum::error_handling::HandleErrorLayer;
-use axum::extract::DefaultBodyLimit;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::header::CONNECTION;
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
 use axum::response::{Html, IntoResponse, Json, Response};
 use axum::{middleware, routing, BoxError, Extension, Router};
 use common_base::readable_size::ReadableSize;
 use common_base::Plugins;
 use common_error::status_code::StatusCode;
+use common_meta::key::privilege::PrivilegeManager;
 use common_recordbatch::RecordBatch;
 use common_telemetry::logging::{error, info};
 use common_time::timestamp::TimeUnit;
@@ -48,22 +53,26 @@ use tower::ServiceBuilder;
 use tower_http::decompression::RequestDecompressionLayer;
 use tower_http::trace::TraceLayer;
 
-use self::authorize::AuthState;
+use self::authorize::{AdminState, AuthState};
 use self::table_result::TableResponse;
 use crate::configurator::ConfiguratorRef;
 use crate::error::{AlreadyStartedSnafu, Error, HyperSnafu, Result, ToJsonSnafu};
 use crate::http::arrow_result::ArrowResponse;
 use crate::http::csv_result::CsvResponse;
+use crate::http::cursor::{CursorManager, CursorState};
 use crate::http::error_result::ErrorResponse;
 use crate::http::greptime_result_v1::GreptimedbV1Response;
 use crate::http::influxdb::{influxdb_health, influxdb_ping, influxdb_write_v1, influxdb_write_v2};
 use crate::http::influxdb_result_v1::InfluxdbV1Response;
 use crate::http::prometheus::{
-    build_info_query, format_query, instant_query, label_values_query, labels_query, range_query,
-    series_query,
+    build_info_query, format_query, instant_query, label_values_query, labels_query,
+    query_exemplars, range_query, series_query,
 };
+use crate::http::stream_result::StreamResponse;
+use crate::http::tables::TableApiState;
 use crate::metrics::http_metrics_layer;
 use crate::metrics_handler::MetricsHandler;
+use crate::prom_relabel::RelabelRulesRef;
 use crate::prometheus_handler::PrometheusHandlerRef;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::query_handler::{
@@ -76,6 +85,7 @@ pub mod authorize;
 pub mod handler;
 pub mod header;
 pub mod influxdb;
+pub mod log_level;
 pub mod mem_prof;
 pub mod opentsdb;
 pub mod otlp;
@@ -87,18 +97,24 @@ pub mod script;
 
 pub mod arrow_result;
 pub mod csv_result;
+pub mod cursor;
 #[cfg(feature = "dashboard")]
 mod dashboard;
 pub mod error_result;
 pub mod greptime_result_v1;
 pub mod influxdb_result_v1;
+pub mod stream_result;
 pub mod table_result;
+pub mod tables;
 
 #[cfg(any(test, feature = "testing"))]
 pub mod test_helpers;
 
 pub const HTTP_API_VERSION: &str = "v1";
 pub const HTTP_API_PREFIX: &str = "/v1/";
+/// Prefix of the table APIs, followed by the database name.
+pub const HTTP_TABLES_PREFIX: &str = "/v1/tables/";
+pub const HTTP_DEBUG_PREFIX: &str = "/debug/";
 /// Default http body limit (64M).
 const DEFAULT_BODY_LIMIT: ReadableSize = ReadableSize::mb(64);
 
@@ -109,7 +125,11 @@ pub static PUBLIC_APIS: [&str; 2] = ["/v1/influxdb/ping", "/v1/influxdb/health"]
 pub struct HttpServer {
     router: StdMutex<Router>,
     shutdown_tx: Mutex<Option<Sender<()>>>,
+    /// Whether the server is shutting down and draining connections.
+    draining: Arc<AtomicBool>,
     user_provider: Option<UserProviderRef>,
+    /// Decides who is an admin allowed to call the debug APIs.
+    privilege_manager: Option<PrivilegeManager>,
 
     // plugins
     plugins: Plugins,
@@ -215,6 +235,17 @@ impl HttpRecordsOutput {
     }
 }
 
+/// Converts a value to JSON for HTTP outputs. Binary values are encoded as
+/// hexadecimal strings instead of arrays of bytes.
+pub(crate) fn value_to_json(
+    value: datatypes::value::Value,
+) -> std::result::Result<Value, serde_json::Error> {
+    match value {
+        datatypes::value::Value::Binary(_) => Ok(Value::String(value.to_string())),
+        value => Value::try_from(value),
+    }
+}
+
 impl HttpRecordsOutput {
     pub(crate) fn try_new(
         schema: SchemaRef,
@@ -234,7 +265,7 @@ impl HttpRecordsOutput {
                 for row in recordbatch.rows() {
                     let value_row = row
                         .into_iter()
-                        .map(Value::try_from)
+                        .map(value_to_json)
                         .collect::<std::result::Result<Vec<Value>, _>>()
                         .context(ToJsonSnafu)?;
 
@@ -251,11 +282,27 @@ impl HttpRecordsOutput {
     }
 }
 
+/// Output of a DDL, the affected rows with whether the DDL changes its object so
+/// declarative tools can converge without matching error messages.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
+pub struct HttpDdlOutput {
+    pub affectedrows: usize,
+    /// What the DDL does to the object, e.g. `created` or `already_exists`.
+    pub status: String,
+    /// Version of the object after the DDL.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum GreptimeQueryOutput {
     AffectedRows(usize),
     Records(HttpRecordsOutput),
+    /// Serialized as `{"affectedrows": 0, "status": "created", ...}`, so clients reading
+    /// the affected rows of DDL keep working.
+    #[serde(untagged)]
+    Ddl(HttpDdlOutput),
 }
 
 /// It allows the results of SQL queries to be presented in different formats.
@@ -267,6 +314,8 @@ pub enum ResponseFormat {
     #[default]
     GreptimedbV1,
     InfluxdbV1,
+    /// Newline-delimited JSON, which is always streamed.
+    Ndjson,
 }
 
 impl ResponseFormat {
@@ -277,6 +326,7 @@ impl ResponseFormat {
             "table" => Some(ResponseFormat::Table),
             "greptimedb_v1" => Some(ResponseFormat::GreptimedbV1),
             "influxdb_v1" => Some(ResponseFormat::InfluxdbV1),
+            "ndjson" => Some(ResponseFormat::Ndjson),
             _ => None,
         }
     }
@@ -288,6 +338,7 @@ impl ResponseFormat {
             ResponseFormat::Table => "table",
             ResponseFormat::GreptimedbV1 => "greptimedb_v1",
             ResponseFormat::InfluxdbV1 => "influxdb_v1",
+            ResponseFormat::Ndjson => "ndjson",
         }
     }
 }
@@ -344,6 +395,9 @@ pub enum HttpResponse {
     Error(ErrorResponse),
     GreptimedbV1(GreptimedbV1Response),
     InfluxdbV1(InfluxdbV1Response),
+    #[serde(skip)]
+    #[schemars(skip)]
+    Stream(StreamResponse),
 }
 
 impl HttpResponse {
@@ -354,6 +408,7 @@ impl HttpResponse {
             HttpResponse::Table(resp) => resp.with_execution_time(execution_time).into(),
             HttpResponse::GreptimedbV1(resp) => resp.with_execution_time(execution_time).into(),
             HttpResponse::InfluxdbV1(resp) => resp.with_execution_time(execution_time).into(),
+            HttpResponse::Stream(resp) => resp.with_execution_time(execution_time).into(),
             HttpResponse::Error(resp) => resp.with_execution_time(execution_time).into(),
         }
     }
@@ -367,6 +422,7 @@ impl IntoResponse for HttpResponse {
             HttpResponse::Table(resp) => resp.into_response(),
             HttpResponse::GreptimedbV1(resp) => resp.into_response(),
             HttpResponse::InfluxdbV1(resp) => resp.into_response(),
+            HttpResponse::Stream(resp) => resp.into_response(),
             HttpResponse::Error(resp) => resp.into_response(),
         }
     }
@@ -376,6 +432,10 @@ impl OperationOutput for HttpResponse {
     type Inner = Response;
 }
 
+impl OperationOutput for Error {
+    type Inner = Response;
+}
+
 impl From<ArrowResponse> for HttpResponse {
     fn from(value: ArrowResponse) -> Self {
         HttpResponse::Arrow(value)
@@ -394,6 +454,12 @@ impl From<TableResponse> for HttpResponse {
     }
 }
 
+impl From<StreamResponse> for HttpResponse {
+    fn from(value: StreamResponse) -> Self {
+        HttpResponse::Stream(value)
+    }
+}
+
 impl From<ErrorResponse> for HttpResponse {
     fn from(value: ErrorResponse) -> Self {
         HttpResponse::Error(value)
@@ -436,6 +502,7 @@ pub struct HttpServerBuilder {
     options: HttpOptions,
     plugins: Plugins,
     user_provider: Option<UserProviderRef>,
+    privilege_manager: Option<PrivilegeManager>,
     api: OpenApi,
     router: Router,
 }
@@ -459,6 +526,7 @@ impl HttpServerBuilder {
             options,
             plugins: Plugins::default(),
             user_provider: None,
+            privilege_manager: None,
             api,
             router: Router::new(),
         }
@@ -470,16 +538,34 @@ impl HttpServerBuilder {
         script_handler: Option<ScriptHandlerRef>,
     ) -> Self {
         let sql_router = HttpServer::route_sql(ApiState {
-            sql_handler,
+            sql_handler: sql_handler.clone(),
             script_handler,
         })
         .finish_api(&mut self.api)
         .layer(Extension(self.api.clone()));
+        let cursor_router = HttpServer::route_cursor(CursorState {
+            sql_handler,
+            cursors: Arc::new(CursorManager::default()),
+        });
 
         Self {
             router: self
                 .router
-                .nest(&format!("/{HTTP_API_VERSION}"), sql_router),
+                .nest(&format!("/{HTTP_API_VERSION}"), sql_router)
+                .nest(&format!("/{HTTP_API_VERSION}/cursor"), cursor_router),
+            ..self
+        }
+    }
+
+    pub fn with_table_handler(mut self, table_state: TableApiState) -> Self {
+        let table_router = HttpServer::route_tables(table_state)
+            .finish_api(&mut self.api)
+            .layer(Extension(self.api.clone()));
+
+        Self {
+            router: self
+                .router
+                .nest(&format!("/{HTTP_API_VERSION}/tables"), table_router),
             ..self
         }
     }
@@ -509,11 +595,17 @@ impl HttpServerBuilder {
         handler: PromStoreProtocolHandlerRef,
         prom_store_with_metric_engine: bool,
         is_strict_mode: bool,
+        relabel_rules: RelabelRulesRef,
     ) -> Self {
         Self {
             router: self.router.nest(
                 &format!("/{HTTP_API_VERSION}/prometheus"),
-                HttpServer::route_prom(handler, prom_store_with_metric_engine, is_strict_mode),
+                HttpServer::route_prom(
+                    handler,
+                    prom_store_with_metric_engine,
+                    is_strict_mode,
+                    relabel_rules,
+                ),
             ),
             ..self
         }
@@ -546,6 +638,13 @@ impl HttpServerBuilder {
         }
     }
 
+    pub fn with_privilege_manager(self, privilege_manager: PrivilegeManager) -> Self {
+        Self {
+            privilege_manager: Some(privilege_manager),
+            ..self
+        }
+    }
+
     pub fn with_metrics_handler(self, handler: MetricsHandler) -> Self {
         Self {
             router: self.router.nest("", HttpServer::route_metrics(handler)),
@@ -580,7 +679,9 @@ impl HttpServerBuilder {
         HttpServer {
             options: self.options,
             user_provider: self.user_provider,
+            privilege_manager: self.privilege_manager,
             shutdown_tx: Mutex::new(None),
+            draining: Arc::new(AtomicBool::new(false)),
             plugins: self.plugins,
             router: StdMutex::new(self.router),
         }
@@ -630,6 +731,10 @@ impl HttpServer {
                 ServiceBuilder::new()
                     .layer(HandleErrorLayer::new(handle_error))
                     .layer(TraceLayer::new_for_http())
+                    .layer(middleware::from_fn_with_state(
+                        self.draining.clone(),
+                        close_connection_if_draining,
+                    ))
                     .layer(TimeoutLayer::new(self.options.timeout))
                     .layer(DefaultBodyLimit::max(
                         self.options
@@ -645,6 +750,12 @@ impl HttpServer {
                     )),
             )
             // Handlers for debug, we don't expect a timeout.
+            .merge(self.route_debug())
+    }
+
+    /// Debug and profiling handlers, which are only available to admin users.
+    fn route_debug<S: Clone + Send + Sync + 'static>(&self) -> Router<S> {
+        Router::new()
             .nest(
                 &format!("/{HTTP_API_VERSION}/prof"),
                 Router::new()
@@ -657,6 +768,20 @@ impl HttpServer {
                         routing::get(mem_prof::mem_prof_handler).post(mem_prof::mem_prof_handler),
                     ),
             )
+            .route(
+                &format!("{HTTP_DEBUG_PREFIX}log_level"),
+                routing::get(log_level::get_log_level)
+                    .put(log_level::set_log_level)
+                    .post(log_level::set_log_level),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                AdminState::new(
+                    self.user_provider.clone(),
+                    self.plugins.get::<PermissionCheckerRef>(),
+                    self.privilege_manager.clone(),
+                ),
+                authorize::check_http_admin,
+            ))
     }
 
     fn route_metrics<S>(metrics_handler: MetricsHandler) -> Router<S> {
@@ -684,6 +809,28 @@ impl HttpServer {
             .with_state(api_state)
     }
 
+    fn route_tables<S>(table_state: TableApiState) -> ApiRouter<S> {
+        ApiRouter::new()
+            .api_route("/", apirouting::get(tables::list_databases))
+            .api_route("/:db", apirouting::get(tables::list_tables))
+            .api_route(
+                "/:db/:table",
+                apirouting::get_with(tables::table_schema, tables::table_schema_docs),
+            )
+            .with_state(table_state)
+    }
+
+    fn route_cursor<S>(cursor_state: CursorState) -> Router<S> {
+        Router::new()
+            .route(
+                "/",
+                routing::post(cursor::open_cursor).get(cursor::open_cursor),
+            )
+            .route("/:cursor_id/fetch", routing::get(cursor::fetch_cursor))
+            .route("/:cursor_id", routing::delete(cursor::close_cursor))
+            .with_state(cursor_state)
+    }
+
     fn route_prometheus<S>(prometheus_handler: PrometheusHandlerRef) -> Router<S> {
         Router::new()
             .route(
@@ -695,6 +842,10 @@ impl HttpServer {
             .route("/query_range", routing::post(range_query).get(range_query))
             .route("/labels", routing::post(labels_query).get(labels_query))
             .route("/series", routing::post(series_query).get(series_query))
+            .route(
+                "/query_exemplars",
+                routing::post(query_exemplars).get(query_exemplars),
+            )
             .route(
                 "/label/:label_name/values",
                 routing::get(label_values_query),
@@ -706,6 +857,7 @@ impl HttpServer {
         prom_handler: PromStoreProtocolHandlerRef,
         prom_store_with_metric_engine: bool,
         is_strict_mode: bool,
+        relabel_rules: RelabelRulesRef,
     ) -> Router<S> {
         let mut router = Router::new().route("/read", routing::post(prom_store::remote_read));
         match (prom_store_with_metric_engine, is_strict_mode) {
@@ -731,7 +883,9 @@ impl HttpServer {
                 )
             }
         }
-        router.with_state(prom_handler)
+        router
+            .layer(Extension(relabel_rules))
+            .with_state(prom_handler)
     }
 
     fn route_influxdb<S>(influxdb_handler: InfluxdbLineProtocolHandlerRef) -> Router<S> {
@@ -758,6 +912,7 @@ impl HttpServer {
         Router::new()
             .route("/v1/metrics", routing::post(otlp::metrics))
             .route("/v1/traces", routing::post(otlp::traces))
+            .route("/v1/logs", routing::post(otlp::logs))
             .with_state(otlp_handler)
     }
 
@@ -773,8 +928,10 @@ pub const HTTP_SERVER: &str = "HTTP_SERVER";
 #[async_trait]
 impl Server for HttpServer {
     async fn shutdown(&self) -> Result<()> {
+        self.draining.store(true, Ordering::Relaxed);
         let mut shutdown_tx = self.shutdown_tx.lock().await;
         if let Some(tx) = shutdown_tx.take() {
+            // The server stops accepting new connections and waits for in-flight requests.
             if tx.send(()).is_err() {
                 info!("Receiver dropped, the HTTP server has already existed");
             }
@@ -826,6 +983,22 @@ impl Server for HttpServer {
     }
 }
 
+/// Asks clients to close the connection while the server is draining, so they reconnect
+/// to other servers instead of reusing the connection.
+async fn close_connection_if_draining<B>(
+    State(draining): State<Arc<AtomicBool>>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let mut response = next.run(req).await;
+    if draining.load(Ordering::Relaxed) {
+        let _ = response
+            .headers_mut()
+            .insert(CONNECTION, HeaderValue::from_static("close"));
+    }
+    response
+}
+
 /// handle error middleware
 async fn handle_error(err: BoxError) -> Json<HttpResponse> {
     error!(err; "Unhandled internal error");
@@ -856,6 +1029,7 @@ mod test {
     use query::plan::LogicalPlan;
     use query::query_engine::DescribeResult;
     use session::context::QueryContextRef;
+    use table::metadata::TableVersion;
     use tokio::sync::mpsc;
 
     use super::*;
@@ -916,6 +1090,15 @@ mod test {
         async fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
             Ok(true)
         }
+
+        async fn table_version(
+            &self,
+            _catalog: &str,
+            _schema: &str,
+            _table: &str,
+        ) -> Result<Option<TableVersion>> {
+            Ok(None)
+        }
     }
 
     fn timeout() -> TimeoutLayer {
@@ -951,6 +1134,40 @@ mod test {
         assert_eq!(Duration::from_secs(30), default.timeout)
     }
 
+    #[test]
+    fn test_serialize_ddl_output() {
+        let output = GreptimeQueryOutput::Ddl(HttpDdlOutput {
+            affectedrows: 0,
+            status: "created".to_string(),
+            version: Some(0),
+        });
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            serde_json::json!({"affectedrows": 0, "status": "created", "version": 0}),
+            json
+        );
+        assert_eq!(output, serde_json::from_value(json).unwrap());
+
+        let json = serde_json::to_value(GreptimeQueryOutput::AffectedRows(1)).unwrap();
+        assert_eq!(serde_json::json!({"affectedrows": 1}), json);
+        assert_eq!(
+            GreptimeQueryOutput::AffectedRows(1),
+            serde_json::from_value(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_value_to_json() {
+        assert_eq!(
+            serde_json::json!("0aff"),
+            value_to_json(datatypes::value::Value::Binary(vec![0x0a, 0xff].into())).unwrap()
+        );
+        assert_eq!(
+            serde_json::json!("hello"),
+            value_to_json(datatypes::value::Value::from("hello")).unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_http_server_request_timeout() {
         let (tx, _rx) = mpsc::channel(100);
@@ -960,6 +1177,26 @@ mod test {
         assert_eq!(res.status(), StatusCode::REQUEST_TIMEOUT);
     }
 
+    #[tokio::test]
+    async fn test_http_server_close_connection_when_draining() {
+        let (tx, _rx) = mpsc::channel(100);
+        let instance = Arc::new(DummyInstance { _tx: tx });
+        let sql_instance = ServerSqlQueryHandlerAdapter::arc(instance);
+        let server = HttpServerBuilder::new(HttpOptions::default())
+            .with_sql_handler(sql_instance, None)
+            .build();
+        let client = TestClient::new(server.build(server.make_app()));
+
+        let res = client.get("/health").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(CONNECTION).is_none());
+
+        server.draining.store(true, Ordering::Relaxed);
+        let res = client.get("/health").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(CONNECTION).unwrap(), "close");
+    }
+
     #[tokio::test]
     async fn test_schema_for_empty_response() {
         let column_schemas = vec![
@@ -1021,6 +1258,7 @@ mod test {
                 ResponseFormat::Table => TableResponse::from_output(outputs).await,
                 ResponseFormat::GreptimedbV1 => GreptimedbV1Response::from_output(outputs).await,
                 ResponseFormat::InfluxdbV1 => InfluxdbV1Response::from_output(outputs, None).await,
+                ResponseFormat::Ndjson => unreachable!(),
             };
 
             match json_resp {
@@ -1090,6 +1328,7 @@ mod test {
                     assert_eq!(rb.num_columns(), 2);
                     assert_eq!(rb.num_rows(), 4);
                 }
+                HttpResponse::Stream(_) => unreachable!(),
                 HttpResponse::Error(err) => unreachable!("{err:?}"),
             }
         }
@@ -14,32 +14,42 @@
 
 use std::sync::Arc;
 
-use ::auth::UserProviderRef;
+use ::auth::error::{PermissionDeniedSnafu, PrivilegeDeniedSnafu};
+use ::auth::{
+    PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionResp, UserInfoRef,
+    UserProviderRef,
+};
 use axum::extract::State;
 use axum::http::{self, Request, StatusCode};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
-use common_catalog::consts::DEFAULT_SCHEMA_NAME;
-use common_catalog::parse_catalog_and_schema_from_db_string;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_catalog::parse_optional_catalog_and_schema_from_db_string;
 use common_error::ext::ErrorExt;
+use common_meta::key::privilege::{PrivilegeManager, ADMIN_ROLE};
 use common_telemetry::warn;
 use common_time::timezone::parse_timezone;
 use common_time::Timezone;
 use headers::Header;
 use secrecy::SecretString;
-use session::context::QueryContextBuilder;
+use session::context::{sql_dialect_by_name, Channel, QueryContextBuilder, QueryContextRef};
 use snafu::{ensure, OptionExt, ResultExt};
+use sql::dialect::Dialect;
 
-use super::header::{GreptimeDbName, GREPTIME_TIMEZONE_HEADER_NAME};
+use super::header::{
+    GreptimeDbName, GREPTIME_DB_HEADER_DIALECT, GREPTIME_DB_HEADER_INFLUXDB_ORG_AS_CATALOG,
+    GREPTIME_TIMEZONE_HEADER_NAME,
+};
 use super::PUBLIC_APIS;
 use crate::error::{
     self, InvalidAuthHeaderInvisibleASCIISnafu, InvalidAuthHeaderSnafu, InvalidParameterSnafu,
     NotFoundInfluxAuthSnafu, Result, UnsupportedAuthSchemeSnafu, UrlDecodeSnafu,
 };
 use crate::http::error_result::ErrorResponse;
-use crate::http::HTTP_API_PREFIX;
+use crate::http::influxdb::InfluxdbV2ErrorResponse;
+use crate::http::{HTTP_API_PREFIX, HTTP_API_VERSION, HTTP_DEBUG_PREFIX, HTTP_TABLES_PREFIX};
 use crate::influxdb::{is_influxdb_request, is_influxdb_v2_request};
 
 /// AuthState is a holder state for [`UserProviderRef`]
@@ -55,6 +65,29 @@ impl AuthState {
     }
 }
 
+/// AdminState is a holder state for [`check_http_admin`], which authenticates
+/// the request and then requires the user to be an admin.
+#[derive(Clone)]
+pub struct AdminState {
+    user_provider: Option<UserProviderRef>,
+    permission_checker: Option<PermissionCheckerRef>,
+    privilege_manager: Option<PrivilegeManager>,
+}
+
+impl AdminState {
+    pub fn new(
+        user_provider: Option<UserProviderRef>,
+        permission_checker: Option<PermissionCheckerRef>,
+        privilege_manager: Option<PrivilegeManager>,
+    ) -> Self {
+        Self {
+            user_provider,
+            permission_checker,
+            privilege_manager,
+        }
+    }
+}
+
 pub async fn inner_auth<B>(
     user_provider: Option<UserProviderRef>,
     mut req: Request<B>,
@@ -63,13 +96,24 @@ pub async fn inner_auth<B>(
     let (catalog, schema) = extract_catalog_and_schema(&req);
     // TODO(ruihang): move this out of auth module
     let timezone = Arc::new(extract_timezone(&req));
-    let query_ctx_builder = QueryContextBuilder::default()
+    let sql_dialect = match extract_sql_dialect(&req) {
+        Ok(sql_dialect) => sql_dialect,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, ErrorResponse::from_error(e)).into_response())
+        }
+    };
+    let mut query_ctx_builder = QueryContextBuilder::default()
         .current_catalog(catalog.clone())
         .current_schema(schema.clone())
-        .timezone(timezone);
+        .timezone(timezone)
+        .channel(extract_channel(&req));
+    if let Some(sql_dialect) = sql_dialect {
+        query_ctx_builder = query_ctx_builder.sql_dialect(sql_dialect);
+    }
 
     let query_ctx = query_ctx_builder.build();
     let need_auth = need_auth(&req);
+    let is_influxdb_v2 = is_influxdb_v2_request(&req);
 
     // 2. check if auth is needed
     let user_provider = if let Some(user_provider) = user_provider.filter(|_| need_auth) {
@@ -88,7 +132,7 @@ pub async fn inner_auth<B>(
             crate::metrics::METRIC_AUTH_FAILURE
                 .with_label_values(&[e.status_code().as_ref()])
                 .inc();
-            return Err(err_response(e));
+            return Err(err_response(is_influxdb_v2, e));
         }
     };
 
@@ -112,7 +156,7 @@ pub async fn inner_auth<B>(
             crate::metrics::METRIC_AUTH_FAILURE
                 .with_label_values(&[e.status_code().as_ref()])
                 .inc();
-            Err(err_response(e))
+            Err(err_response(is_influxdb_v2, e))
         }
     }
 }
@@ -128,7 +172,98 @@ pub async fn check_http_auth<B>(
     }
 }
 
-fn err_response(err: impl ErrorExt) -> Response {
+pub async fn check_http_admin<B>(
+    State(admin_state): State<AdminState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    match inner_admin_auth(admin_state, req).await {
+        Ok(req) => next.run(req).await,
+        Err(resp) => resp,
+    }
+}
+
+/// Authenticates the request and ensures the user is an admin.
+///
+/// Without a user provider, requests aren't authenticated, so the admin APIs are
+/// open like other APIs, e.g. on datanodes and metasrv.
+pub async fn inner_admin_auth<B>(
+    admin_state: AdminState,
+    req: Request<B>,
+) -> std::result::Result<Request<B>, Response> {
+    let forbidden = |e: error::Error| {
+        warn!("admin permission denied: {}", e);
+        (StatusCode::FORBIDDEN, ErrorResponse::from_error(e)).into_response()
+    };
+    let Some(user_provider) = admin_state.user_provider else {
+        return Ok(req);
+    };
+    let req = inner_auth(Some(user_provider), req).await?;
+    let user_info = req
+        .extensions()
+        .get::<QueryContextRef>()
+        .and_then(|query_ctx| query_ctx.current_user());
+    match ensure_admin(
+        admin_state.permission_checker.as_ref(),
+        admin_state.privilege_manager.as_ref(),
+        user_info,
+    )
+    .await
+    {
+        Ok(()) => Ok(req),
+        Err(e) => Err(forbidden(e)),
+    }
+}
+
+/// Ensures the user is an admin.
+///
+/// The [PermissionChecker] plugin decides if it is installed, otherwise only
+/// members of the [ADMIN_ROLE] are admins. Nodes without privileges, e.g. datanodes
+/// and metasrv, only authenticate users, so all authenticated users are admins.
+async fn ensure_admin(
+    permission_checker: Option<&PermissionCheckerRef>,
+    privilege_manager: Option<&PrivilegeManager>,
+    user_info: Option<UserInfoRef>,
+) -> Result<()> {
+    if let Some(checker) = permission_checker {
+        return match checker
+            .check_permission(user_info, PermissionReq::Admin)
+            .context(error::AuthSnafu)?
+        {
+            PermissionResp::Allow => Ok(()),
+            PermissionResp::Reject => PermissionDeniedSnafu.fail().context(error::AuthSnafu),
+        };
+    }
+
+    let user_info = user_info
+        .context(PermissionDeniedSnafu)
+        .context(error::AuthSnafu)?;
+    let username = user_info.username();
+    let is_admin = match privilege_manager {
+        Some(manager) => manager
+            .is_admin(username)
+            .await
+            .context(error::CheckAdminSnafu { username })?,
+        None => true,
+    };
+    if !is_admin {
+        return PrivilegeDeniedSnafu {
+            username,
+            privilege: ADMIN_ROLE,
+            object: "the debug APIs",
+        }
+        .fail()
+        .context(error::AuthSnafu);
+    }
+
+    Ok(())
+}
+
+fn err_response(is_influxdb_v2: bool, err: impl ErrorExt) -> Response {
+    if is_influxdb_v2 {
+        return InfluxdbV2ErrorResponse::new(StatusCode::UNAUTHORIZED, err.output_msg())
+            .into_response();
+    }
     (StatusCode::UNAUTHORIZED, ErrorResponse::from_error(err)).into_response()
 }
 
@@ -139,6 +274,7 @@ pub fn extract_catalog_and_schema<B>(request: &Request<B>) -> (String, String) {
         .get(GreptimeDbName::name())
         // eat this invalid ascii error and give user the final IllegalParam error
         .and_then(|header| header.to_str().ok())
+        .or_else(|| extract_db_from_path(request.uri().path()))
         .or_else(|| {
             let query = request.uri().query().unwrap_or_default();
             if is_influxdb_v2_request(request) {
@@ -149,7 +285,46 @@ pub fn extract_catalog_and_schema<B>(request: &Request<B>) -> (String, String) {
         })
         .unwrap_or(DEFAULT_SCHEMA_NAME);
 
-    parse_catalog_and_schema_from_db_string(dbname)
+    let (catalog, schema) = parse_optional_catalog_and_schema_from_db_string(dbname);
+    // InfluxDB v2 organizations are mapped to catalogs if the client opts in, as
+    // clients usually send an org that isn't a catalog.
+    let catalog = catalog.or_else(|| {
+        if is_influxdb_v2_request(request) && is_org_as_catalog(request) {
+            let query = request.uri().query().unwrap_or_default();
+            extract_org_from_query(query).map(|org| org.to_lowercase())
+        } else {
+            None
+        }
+    });
+    (
+        catalog.unwrap_or_else(|| DEFAULT_CATALOG_NAME.to_string()),
+        schema,
+    )
+}
+
+fn is_org_as_catalog<B>(request: &Request<B>) -> bool {
+    request
+        .headers()
+        .get(&GREPTIME_DB_HEADER_INFLUXDB_ORG_AS_CATALOG)
+        .and_then(|header| header.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+}
+
+/// Returns the protocol of the request by the API it calls.
+fn extract_channel<B>(request: &Request<B>) -> Channel {
+    let path = request.uri().path();
+    let api_path = |name: &str| path.starts_with(&format!("/{HTTP_API_VERSION}/{name}"));
+    if is_influxdb_request(request) {
+        Channel::Influx
+    } else if api_path("prometheus") {
+        Channel::Prometheus
+    } else if api_path("opentsdb") {
+        Channel::Opentsdb
+    } else if api_path("otlp") {
+        Channel::Otlp
+    } else {
+        Channel::Http
+    }
 }
 
 fn extract_timezone<B>(request: &Request<B>) -> Timezone {
@@ -163,6 +338,24 @@ fn extract_timezone<B>(request: &Request<B>) -> Timezone {
     parse_timezone(Some(timezone))
 }
 
+/// Extracts the SQL dialect from the header or the `dialect` query parameter.
+fn extract_sql_dialect<B>(request: &Request<B>) -> Result<Option<Arc<dyn Dialect + Send + Sync>>> {
+    let name = request
+        .headers()
+        .get(&GREPTIME_DB_HEADER_DIALECT)
+        .and_then(|header| header.to_str().ok())
+        .or_else(|| extract_param_from_query(request.uri().query().unwrap_or_default(), "dialect"));
+    let Some(name) = name else {
+        return Ok(None);
+    };
+
+    sql_dialect_by_name(name)
+        .map(Some)
+        .with_context(|| InvalidParameterSnafu {
+            reason: format!("unknown SQL dialect: {name}"),
+        })
+}
+
 fn get_influxdb_credentials<B>(request: &Request<B>) -> Result<Option<(Username, Password)>> {
     // compat with influxdb v2 and v1
     if let Some(header) = request.headers().get(http::header::AUTHORIZATION) {
@@ -279,7 +472,7 @@ fn need_auth<B>(req: &Request<B>) -> bool {
         }
     }
 
-    path.starts_with(HTTP_API_PREFIX)
+    path.starts_with(HTTP_API_PREFIX) || path.starts_with(HTTP_DEBUG_PREFIX)
 }
 
 fn extract_param_from_query<'a>(query: &'a str, param: &'a str) -> Option<&'a str> {
@@ -296,12 +489,25 @@ fn extract_db_from_query(query: &str) -> Option<&str> {
     extract_param_from_query(query, "db")
 }
 
+/// The table APIs take the database from the path, e.g. `/v1/tables/{db}/{table}`,
+/// so it's authorized like the `db` parameter.
+fn extract_db_from_path(path: &str) -> Option<&str> {
+    path.strip_prefix(HTTP_TABLES_PREFIX)
+        .and_then(|rest| rest.split('/').next())
+        .filter(|db| !db.is_empty())
+}
+
 /// InfluxDB v2 uses "bucket" instead of "db"
 /// https://docs.influxdata.com/influxdb/v1/tools/api/#apiv2write-http-endpoint
 fn extract_bucket_from_query(query: &str) -> Option<&str> {
     extract_param_from_query(query, "bucket")
 }
 
+/// InfluxDB v2 uses "org" to specify the organization, which is mapped to catalog
+fn extract_org_from_query(query: &str) -> Option<&str> {
+    extract_param_from_query(query, "org")
+}
+
 fn extract_influxdb_user_from_query(query: &str) -> (Option<&str>, Option<&str>) {
     let mut username = None;
     let mut password = None;
@@ -346,6 +552,13 @@ mod tests {
             .unwrap();
 
         assert!(need_auth(&req));
+
+        let req = Request::builder()
+            .uri("http://127.0.0.1/debug/log_level")
+            .body(())
+            .unwrap();
+
+        assert!(need_auth(&req));
     }
 
     #[test]
@@ -437,6 +650,128 @@ mod tests {
             extract_db_from_query("name=bar&db=foo&name=bar"),
             Some("foo")
         );
+
+        assert_matches!(extract_db_from_path("/v1/sql"), None);
+        assert_matches!(extract_db_from_path("/v1/tables"), None);
+        assert_matches!(extract_db_from_path("/v1/tables/"), None);
+        assert_matches!(extract_db_from_path("/v1/tables/foo"), Some("foo"));
+        assert_matches!(extract_db_from_path("/v1/tables/foo/bar"), Some("foo"));
+    }
+
+    #[test]
+    fn test_extract_sql_dialect() {
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql")
+            .body(())
+            .unwrap();
+        assert!(extract_sql_dialect(&req).unwrap().is_none());
+
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql?dialect=mysql")
+            .body(())
+            .unwrap();
+        let dialect = extract_sql_dialect(&req).unwrap().unwrap();
+        assert!(dialect.is_delimited_identifier_start('`'));
+
+        // The header takes precedence over the query parameter.
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql?dialect=mysql")
+            .header(GREPTIME_DB_HEADER_DIALECT.clone(), "postgres")
+            .body(())
+            .unwrap();
+        let dialect = extract_sql_dialect(&req).unwrap().unwrap();
+        assert!(!dialect.is_delimited_identifier_start('`'));
+
+        let req = Request::builder()
+            .uri("http://127.0.0.1/v1/sql?dialect=oracle")
+            .body(())
+            .unwrap();
+        assert_matches!(
+            extract_sql_dialect(&req),
+            Err(error::Error::InvalidParameter { .. })
+        );
+    }
+
+    #[test]
+    fn test_extract_channel() {
+        let channel = |uri: &str| extract_channel(&Request::builder().uri(uri).body(()).unwrap());
+        assert_eq!(Channel::Http, channel("http://127.0.0.1/v1/sql"));
+        assert_eq!(
+            Channel::Prometheus,
+            channel("http://127.0.0.1/v1/prometheus/write")
+        );
+        assert_eq!(
+            Channel::Prometheus,
+            channel("http://127.0.0.1/v1/prometheus/api/v1/query")
+        );
+        assert_eq!(
+            Channel::Influx,
+            channel("http://127.0.0.1/v1/influxdb/write")
+        );
+        assert_eq!(
+            Channel::Opentsdb,
+            channel("http://127.0.0.1/v1/opentsdb/api/put")
+        );
+        assert_eq!(
+            Channel::Otlp,
+            channel("http://127.0.0.1/v1/otlp/v1/metrics")
+        );
+    }
+
+    #[test]
+    fn test_extract_influxdb_v2_org() {
+        let http_api_version = crate::http::HTTP_API_VERSION;
+        let uri = format!(
+            "http://localhost/{http_api_version}/influxdb/api/v2/write?org=MyOrg&bucket=public"
+        );
+        let req = Request::builder()
+            .uri(uri.as_str())
+            .header(&GREPTIME_DB_HEADER_INFLUXDB_ORG_AS_CATALOG, "true")
+            .body(())
+            .unwrap();
+        let db = extract_catalog_and_schema(&req);
+        assert_eq!(db, ("myorg".to_string(), "public".to_string()));
+
+        // org is ignored unless the client opts in
+        let req = Request::builder().uri(uri.as_str()).body(()).unwrap();
+        let db = extract_catalog_and_schema(&req);
+        assert_eq!(db, ("greptime".to_string(), "public".to_string()));
+
+        // catalog in bucket takes precedence
+        let req = Request::builder()
+            .header(&GREPTIME_DB_HEADER_INFLUXDB_ORG_AS_CATALOG, "true")
+            .uri(
+                format!("http://localhost/{http_api_version}/influxdb/api/v2/write?org=myorg&bucket=greptime-public")
+                    .as_str(),
+            )
+            .body(())
+            .unwrap();
+        let db = extract_catalog_and_schema(&req);
+        assert_eq!(db, ("greptime".to_string(), "public".to_string()));
+
+        // org is ignored by v1 api
+        let req = Request::builder()
+            .uri(
+                format!("http://localhost/{http_api_version}/influxdb/write?org=myorg&db=public")
+                    .as_str(),
+            )
+            .body(())
+            .unwrap();
+        let db = extract_catalog_and_schema(&req);
+        assert_eq!(db, ("greptime".to_string(), "public".to_string()));
+
+        // db takes precedence over bucket
+        let req = Request::builder()
+            .uri(
+                format!(
+                    "http://localhost/{http_api_version}/influxdb/api/v2/write?db=foo&bucket=bar"
+                )
+                .as_str(),
+            )
+            .body(())
+            .unwrap();
+        let db = extract_catalog_and_schema(&req);
+        assert_eq!(db, ("greptime".to_string(), "foo".to_string()));
     }
 
     #[test]
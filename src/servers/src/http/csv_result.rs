@@ -81,6 +81,9 @@ impl IntoResponse for CsvResponse {
             Some(GreptimeQueryOutput::AffectedRows(n)) => {
                 format!("{n}\n")
             }
+            Some(GreptimeQueryOutput::Ddl(ddl)) => {
+                format!("{}\n", ddl.affectedrows)
+            }
             Some(GreptimeQueryOutput::Records(records)) => {
                 let mut result = String::new();
                 for row in records.rows {
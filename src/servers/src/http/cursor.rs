@@ -0,0 +1,409 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Query cursors of the HTTP API, which let clients page through large results.
+//!
+//! A cursor keeps the record batch stream of a query open on the server, and each
+//! fetch only reads the rows of the requested page from it. Cursors belong to the
+//! user that opened them and are closed once exhausted, on request, or after being
+//! idle for a while.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Form, Json};
+use common_error::status_code::StatusCode;
+use common_query::OutputData;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use common_telemetry::tracing;
+use futures::StreamExt;
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use tokio::sync::Mutex;
+
+use crate::error::{self, Result};
+use crate::http::error_result::ErrorResponse;
+use crate::http::handler::validate_schema;
+use crate::http::{HttpRecordsOutput, OutputSchema};
+use crate::metrics::METRIC_HTTP_OPEN_CURSORS;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+/// Cursors not accessed for this long are closed.
+const DEFAULT_CURSOR_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Max number of open cursors of a server.
+const DEFAULT_MAX_CURSORS: usize = 128;
+/// Page size of a fetch without limit.
+const DEFAULT_FETCH_LIMIT: usize = 1000;
+/// Max page size of a fetch.
+const MAX_FETCH_LIMIT: usize = 100_000;
+
+pub type CursorManagerRef = Arc<CursorManager>;
+
+#[derive(Clone)]
+pub struct CursorState {
+    pub sql_handler: ServerSqlQueryHandlerRef,
+    pub cursors: CursorManagerRef,
+}
+
+struct CursorEntry {
+    /// The user who opened the cursor.
+    owner: String,
+    last_access: Instant,
+    cursor: Arc<Mutex<Cursor>>,
+}
+
+/// Manages the open cursors of the HTTP server.
+///
+/// Idle cursors are purged whenever a cursor is opened or fetched.
+pub struct CursorManager {
+    cursors: StdMutex<HashMap<String, CursorEntry>>,
+    idle_timeout: Duration,
+    max_cursors: usize,
+}
+
+impl Default for CursorManager {
+    fn default() -> Self {
+        Self::new(DEFAULT_CURSOR_IDLE_TIMEOUT, DEFAULT_MAX_CURSORS)
+    }
+}
+
+impl CursorManager {
+    pub fn new(idle_timeout: Duration, max_cursors: usize) -> Self {
+        Self {
+            cursors: StdMutex::new(HashMap::new()),
+            idle_timeout,
+            max_cursors,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.purge_idle();
+        self.cursors.lock().unwrap().len() >= self.max_cursors
+    }
+
+    /// Registers a cursor owned by `owner` and returns its id.
+    fn open(&self, owner: String, cursor: Cursor) -> String {
+        let id = format!("{:032x}", rand::thread_rng().gen::<u128>());
+        let entry = CursorEntry {
+            owner,
+            last_access: Instant::now(),
+            cursor: Arc::new(Mutex::new(cursor)),
+        };
+        let _ = self.cursors.lock().unwrap().insert(id.clone(), entry);
+        METRIC_HTTP_OPEN_CURSORS.inc();
+        id
+    }
+
+    /// Gets the cursor of `id` if it is owned by `owner`.
+    fn get(&self, id: &str, owner: &str) -> Option<Arc<Mutex<Cursor>>> {
+        self.purge_idle();
+        let mut cursors = self.cursors.lock().unwrap();
+        let entry = cursors.get_mut(id).filter(|entry| entry.owner == owner)?;
+        entry.last_access = Instant::now();
+        Some(entry.cursor.clone())
+    }
+
+    /// Closes the cursor of `id` if it is owned by `owner`, returns whether it is closed.
+    fn close(&self, id: &str, owner: &str) -> bool {
+        let mut cursors = self.cursors.lock().unwrap();
+        if cursors.get(id).is_some_and(|entry| entry.owner == owner) {
+            let _ = cursors.remove(id);
+            METRIC_HTTP_OPEN_CURSORS.dec();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn purge_idle(&self) {
+        let mut cursors = self.cursors.lock().unwrap();
+        let before = cursors.len();
+        cursors.retain(|_, entry| entry.last_access.elapsed() < self.idle_timeout);
+        METRIC_HTTP_OPEN_CURSORS.sub((before - cursors.len()) as i64);
+    }
+}
+
+/// The open record batch stream of a query.
+struct Cursor {
+    stream: SendableRecordBatchStream,
+    /// Rows read from the stream but not fetched yet.
+    pending: Option<RecordBatch>,
+}
+
+impl Cursor {
+    fn new(stream: SendableRecordBatchStream) -> Self {
+        Self {
+            stream,
+            pending: None,
+        }
+    }
+
+    /// Reads up to `limit` rows, returns them and whether the cursor is exhausted.
+    async fn fetch(&mut self, limit: usize) -> Result<(Vec<RecordBatch>, bool)> {
+        let mut batches = Vec::new();
+        let mut num_rows = 0;
+        while num_rows < limit {
+            let batch = match self.pending.take() {
+                Some(batch) => batch,
+                None => match self.stream.next().await {
+                    Some(batch) => batch.context(error::CollectRecordbatchSnafu)?,
+                    None => return Ok((batches, true)),
+                },
+            };
+            let remaining = limit - num_rows;
+            if batch.num_rows() > remaining {
+                let rest = batch.num_rows() - remaining;
+                self.pending = Some(
+                    batch
+                        .slice(remaining, rest)
+                        .context(error::CollectRecordbatchSnafu)?,
+                );
+                batches.push(
+                    batch
+                        .slice(0, remaining)
+                        .context(error::CollectRecordbatchSnafu)?,
+                );
+                num_rows = limit;
+            } else {
+                num_rows += batch.num_rows();
+                batches.push(batch);
+            }
+        }
+        Ok((batches, false))
+    }
+}
+
+fn cursor_owner(query_ctx: &QueryContextRef) -> String {
+    query_ctx
+        .current_user()
+        .map(|user| user.username().to_string())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CursorQuery {
+    pub db: Option<String>,
+    pub sql: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct OpenCursorResponse {
+    pub cursor_id: String,
+    pub schema: OutputSchema,
+    pub execution_time_ms: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FetchQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FetchResponse {
+    pub records: HttpRecordsOutput,
+    /// Whether all rows have been fetched, the cursor is closed then.
+    pub done: bool,
+}
+
+fn error_response(code: StatusCode, msg: impl Into<String>) -> Response {
+    ErrorResponse::from_error_message(code, msg.into()).into_response()
+}
+
+fn cursor_not_found(id: &str) -> Response {
+    error_response(
+        StatusCode::InvalidArguments,
+        format!("cursor not found: {id}"),
+    )
+}
+
+/// Handler to open a cursor of a query
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "http", request_type = "cursor"))]
+pub async fn open_cursor(
+    State(state): State<CursorState>,
+    Query(query_params): Query<CursorQuery>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+    Form(form_params): Form<CursorQuery>,
+) -> Response {
+    let start = Instant::now();
+    let Some(sql) = query_params.sql.or(form_params.sql) else {
+        return error_response(StatusCode::InvalidArguments, "sql parameter is required.");
+    };
+    if let Some((status, msg)) = validate_schema(state.sql_handler.clone(), query_ctx.clone()).await
+    {
+        return error_response(status, msg);
+    }
+    if state.cursors.is_full() {
+        return error_response(StatusCode::RateLimited, "too many open cursors");
+    }
+
+    let mut outputs = state.sql_handler.do_query(&sql, query_ctx.clone()).await;
+    if outputs.len() != 1 {
+        return error_response(
+            StatusCode::InvalidArguments,
+            "cursor requires exactly one statement",
+        );
+    }
+    let stream = match outputs.pop().unwrap() {
+        Ok(output) => match output.data {
+            OutputData::Stream(stream) => stream,
+            OutputData::RecordBatches(batches) => batches.as_stream(),
+            OutputData::AffectedRows(_) => {
+                return error_response(StatusCode::InvalidArguments, "cursor requires a query");
+            }
+        },
+        Err(e) => return ErrorResponse::from_error(e).into_response(),
+    };
+
+    let schema = OutputSchema::from(stream.schema());
+    let cursor_id = state
+        .cursors
+        .open(cursor_owner(&query_ctx), Cursor::new(stream));
+    Json(OpenCursorResponse {
+        cursor_id,
+        schema,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+    .into_response()
+}
+
+/// Handler to fetch the next page of a cursor
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "http", request_type = "cursor"))]
+pub async fn fetch_cursor(
+    State(state): State<CursorState>,
+    Path(cursor_id): Path<String>,
+    Query(params): Query<FetchQuery>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+) -> Response {
+    let limit = params.limit.unwrap_or(DEFAULT_FETCH_LIMIT);
+    if limit == 0 || limit > MAX_FETCH_LIMIT {
+        return error_response(
+            StatusCode::InvalidArguments,
+            format!("limit must be between 1 and {MAX_FETCH_LIMIT}"),
+        );
+    }
+    let owner = cursor_owner(&query_ctx);
+    let Some(cursor) = state.cursors.get(&cursor_id, &owner) else {
+        return cursor_not_found(&cursor_id);
+    };
+
+    let mut cursor = cursor.lock().await;
+    let schema = cursor.stream.schema();
+    let result = cursor.fetch(limit).await.and_then(|(batches, done)| {
+        let records = HttpRecordsOutput::try_new(schema, batches)?;
+        Ok(FetchResponse { records, done })
+    });
+    match result {
+        Ok(resp) => {
+            if resp.done {
+                let _ = state.cursors.close(&cursor_id, &owner);
+            }
+            Json(resp).into_response()
+        }
+        Err(e) => {
+            // The stream can't be resumed after an error.
+            let _ = state.cursors.close(&cursor_id, &owner);
+            ErrorResponse::from_error(e).into_response()
+        }
+    }
+}
+
+/// Handler to close a cursor
+#[axum_macros::debug_handler]
+pub async fn close_cursor(
+    State(state): State<CursorState>,
+    Path(cursor_id): Path<String>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+) -> Response {
+    if state.cursors.close(&cursor_id, &cursor_owner(&query_ctx)) {
+        Json(serde_json::json!({})).into_response()
+    } else {
+        cursor_not_found(&cursor_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::{ConcreteDataType, VectorRef};
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::UInt32Vector;
+
+    use super::*;
+
+    fn cursor() -> Cursor {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "number",
+            ConcreteDataType::uint32_datatype(),
+            false,
+        )]));
+        let batches = [vec![0, 1, 2], vec![3, 4, 5, 6]]
+            .into_iter()
+            .map(|numbers| {
+                let columns = vec![Arc::new(UInt32Vector::from_vec(numbers)) as VectorRef];
+                RecordBatch::new(schema.clone(), columns).unwrap()
+            })
+            .collect();
+        let batches = RecordBatches::try_new(schema, batches).unwrap();
+        Cursor::new(batches.as_stream())
+    }
+
+    fn num_rows(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[tokio::test]
+    async fn test_cursor_fetch() {
+        let mut cursor = cursor();
+
+        let (batches, done) = cursor.fetch(2).await.unwrap();
+        assert_eq!(2, num_rows(&batches));
+        assert!(!done);
+
+        // Reads the rest of the first batch and part of the second one.
+        let (batches, done) = cursor.fetch(3).await.unwrap();
+        assert_eq!(2, batches.len());
+        assert_eq!(3, num_rows(&batches));
+        assert!(!done);
+
+        let (batches, done) = cursor.fetch(10).await.unwrap();
+        assert_eq!(2, num_rows(&batches));
+        assert!(done);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_manager() {
+        let manager = CursorManager::new(Duration::from_secs(300), 1);
+        let id = manager.open("alice".to_string(), cursor());
+        assert!(manager.is_full());
+
+        assert!(manager.get(&id, "bob").is_none());
+        assert!(!manager.close(&id, "bob"));
+        assert!(manager.get(&id, "alice").is_some());
+        assert!(manager.close(&id, "alice"));
+        assert!(manager.get(&id, "alice").is_none());
+        assert!(!manager.is_full());
+
+        let manager = CursorManager::new(Duration::ZERO, 1);
+        let id = manager.open("alice".to_string(), cursor());
+        assert!(manager.get(&id, "alice").is_none());
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use axum::http::{HeaderValue, StatusCode as HttpStatusCode};
+use axum::http::{header, HeaderValue, StatusCode as HttpStatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use common_error::ext::ErrorExt;
@@ -74,6 +74,7 @@ impl IntoResponse for ErrorResponse {
     fn into_response(self) -> Response {
         let code = self.code;
         let execution_time = self.execution_time_ms;
+        let retry_after = common_error::parse_retry_after(&self.error);
         let mut resp = Json(self).into_response();
         resp.headers_mut()
             .insert(GREPTIME_DB_HEADER_ERROR_CODE, HeaderValue::from(code));
@@ -108,7 +109,15 @@ impl IntoResponse for ErrorResponse {
 
             StatusCode::AccessDenied => HttpStatusCode::FORBIDDEN,
 
-            StatusCode::RateLimited => HttpStatusCode::TOO_MANY_REQUESTS,
+            StatusCode::RateLimited | StatusCode::QuotaExceeded => {
+                // Retry-After is in whole seconds.
+                if let Some(retry_after) = retry_after {
+                    let seconds = retry_after.as_millis().div_ceil(1000).max(1) as u64;
+                    resp.headers_mut()
+                        .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+                }
+                HttpStatusCode::TOO_MANY_REQUESTS
+            }
 
             StatusCode::RegionNotReady
             | StatusCode::RegionBusy
@@ -38,10 +38,11 @@ use crate::http::csv_result::CsvResponse;
 use crate::http::error_result::ErrorResponse;
 use crate::http::greptime_result_v1::GreptimedbV1Response;
 use crate::http::influxdb_result_v1::InfluxdbV1Response;
+use crate::http::stream_result::StreamResponse;
 use crate::http::table_result::TableResponse;
 use crate::http::{
-    ApiState, Epoch, GreptimeOptionsConfigState, GreptimeQueryOutput, HttpRecordsOutput,
-    HttpResponse, ResponseFormat,
+    ApiState, Epoch, GreptimeOptionsConfigState, GreptimeQueryOutput, HttpDdlOutput,
+    HttpRecordsOutput, HttpResponse, ResponseFormat,
 };
 use crate::metrics_handler::MetricsHandler;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
@@ -62,6 +63,9 @@ pub struct SqlQuery {
     // specified time precision. Maybe greptimedb format can support this
     // param too.
     pub epoch: Option<String>,
+    // (Optional) whether to stream the result in chunks instead of buffering it,
+    // only supported by the `arrow` and `ndjson` formats. `ndjson` is always streamed.
+    pub stream: Option<bool>,
 }
 
 /// Handler to execute sql
@@ -94,6 +98,8 @@ pub async fn sql(
         .map(|s| s.to_lowercase())
         .map(|s| Epoch::parse(s.as_str()).unwrap_or(Epoch::Millisecond));
 
+    let stream = query_params.stream.or(form_params.stream).unwrap_or(false);
+
     let result = if let Some(sql) = &sql {
         if let Some((status, msg)) = validate_schema(sql_handler.clone(), query_ctx.clone()).await {
             Err((status, msg))
@@ -118,6 +124,8 @@ pub async fn sql(
     };
 
     let resp = match format {
+        ResponseFormat::Ndjson => StreamResponse::from_output(outputs, format).await,
+        _ if stream => StreamResponse::from_output(outputs, format).await,
         ResponseFormat::Arrow => ArrowResponse::from_output(outputs).await,
         ResponseFormat::Csv => CsvResponse::from_output(outputs).await,
         ResponseFormat::Table => TableResponse::from_output(outputs).await,
@@ -141,7 +149,14 @@ pub async fn from_output(
         match out {
             Ok(o) => match o.data {
                 OutputData::AffectedRows(rows) => {
-                    results.push(GreptimeQueryOutput::AffectedRows(rows));
+                    results.push(match o.meta.ddl {
+                        Some(ddl) => GreptimeQueryOutput::Ddl(HttpDdlOutput {
+                            affectedrows: rows,
+                            status: ddl.status.as_str().to_string(),
+                            version: ddl.version,
+                        }),
+                        None => GreptimeQueryOutput::AffectedRows(rows),
+                    });
                     if o.meta.cost > 0 {
                         merge_map.insert(GREPTIME_EXEC_WRITE_COST.to_string(), o.meta.cost as u64);
                     }
@@ -323,7 +338,7 @@ pub async fn config(State(state): State<GreptimeOptionsConfigState>) -> Response
     (axum::http::StatusCode::OK, state.greptime_config_options).into_response()
 }
 
-async fn validate_schema(
+pub(crate) async fn validate_schema(
     sql_handler: ServerSqlQueryHandlerRef,
     query_ctx: QueryContextRef,
 ) -> Option<(StatusCode, String)> {
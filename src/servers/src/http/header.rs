@@ -44,6 +44,13 @@ pub mod constants {
     pub const GREPTIME_DB_HEADER_NAME: &str = "x-greptime-db-name";
     pub const GREPTIME_TIMEZONE_HEADER_NAME: &str = "x-greptime-timezone";
     pub const GREPTIME_DB_HEADER_ERROR_CODE: &str = common_error::GREPTIME_DB_HEADER_ERROR_CODE;
+
+    pub const GREPTIME_DB_HEADER_DIALECT: &str = "x-greptime-db-dialect";
+    pub const GREPTIME_DB_HEADER_INFLUXDB_ORG_AS_CATALOG: &str =
+        "x-greptime-db-influxdb-org-as-catalog";
+
+    // OTLP headers
+    pub const GREPTIME_LOG_TABLE_NAME_HEADER_NAME: &str = "x-greptime-log-table-name";
 }
 
 pub static GREPTIME_DB_HEADER_FORMAT: HeaderName =
@@ -61,8 +68,26 @@ pub static GREPTIME_DB_HEADER_NAME: HeaderName =
 pub static GREPTIME_TIMEZONE_HEADER_NAME: HeaderName =
     HeaderName::from_static(constants::GREPTIME_TIMEZONE_HEADER_NAME);
 
+/// Header key of the SQL dialect to parse the query. The value is one of `greptime`, `mysql`
+/// and `postgres`.
+pub static GREPTIME_DB_HEADER_DIALECT: HeaderName =
+    HeaderName::from_static(constants::GREPTIME_DB_HEADER_DIALECT);
+
+/// Header key to opt in to mapping the `org` of InfluxDB v2 writes to the catalog. The value
+/// is `true` or `false`.
+pub static GREPTIME_DB_HEADER_INFLUXDB_ORG_AS_CATALOG: HeaderName =
+    HeaderName::from_static(constants::GREPTIME_DB_HEADER_INFLUXDB_ORG_AS_CATALOG);
+
+/// Header key of the table to write OTLP logs into. Example format of the header value is `app_logs`.
+pub static GREPTIME_LOG_TABLE_NAME_HEADER_NAME: HeaderName =
+    HeaderName::from_static(constants::GREPTIME_LOG_TABLE_NAME_HEADER_NAME);
+
 pub static CONTENT_TYPE_PROTOBUF: HeaderValue = HeaderValue::from_static("application/x-protobuf");
 pub static CONTENT_ENCODING_SNAPPY: HeaderValue = HeaderValue::from_static("snappy");
+/// Content type of the streamed remote read response of Prometheus.
+pub static CONTENT_TYPE_STREAMED_PROTOBUF: HeaderValue = HeaderValue::from_static(
+    "application/x-streamed-protobuf; proto=prometheus.ChunkedReadResponse",
+);
 
 pub struct GreptimeDbName(Option<String>);
 
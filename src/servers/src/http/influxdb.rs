@@ -16,15 +16,17 @@ use std::collections::HashMap;
 
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
-use axum::Extension;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
+use common_error::ext::ErrorExt;
 use common_grpc::writer::Precision;
 use common_telemetry::tracing;
+use serde::Serialize;
 use session::context::QueryContextRef;
 
 use super::header::write_cost_header_map;
-use crate::error::{Result, TimePrecisionSnafu};
+use crate::error::{Error, Result, TimePrecisionSnafu};
 use crate::influxdb::InfluxdbRequest;
 use crate::query_handler::InfluxdbLineProtocolHandlerRef;
 
@@ -67,7 +69,7 @@ pub async fn influxdb_write_v2(
     Query(mut params): Query<HashMap<String, String>>,
     Extension(query_ctx): Extension<QueryContextRef>,
     lines: String,
-) -> Result<impl IntoResponse> {
+) -> std::result::Result<impl IntoResponse, InfluxdbV2ErrorResponse> {
     let db = match (params.remove("db"), params.remove("bucket")) {
         (_, Some(bucket)) => bucket.clone(),
         (Some(db), None) => db.clone(),
@@ -79,7 +81,47 @@ pub async fn influxdb_write_v2(
         .map(|val| parse_time_precision(val))
         .transpose()?;
 
-    influxdb_write(&db, precision, lines, handler, query_ctx).await
+    Ok(influxdb_write(&db, precision, lines, handler, query_ctx).await?)
+}
+
+/// Error payload of the InfluxDB v2 API, see
+/// <https://docs.influxdata.com/influxdb/v2/api/#operation/PostWrite>.
+#[derive(Debug, Serialize)]
+pub struct InfluxdbV2ErrorResponse {
+    #[serde(skip)]
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl InfluxdbV2ErrorResponse {
+    pub fn new(status: StatusCode, message: String) -> Self {
+        let code = match status {
+            StatusCode::BAD_REQUEST => "invalid",
+            StatusCode::UNAUTHORIZED => "unauthorized",
+            StatusCode::FORBIDDEN => "forbidden",
+            StatusCode::NOT_FOUND => "not found",
+            StatusCode::PAYLOAD_TOO_LARGE => "request too large",
+            _ => "internal error",
+        };
+        Self {
+            status,
+            code,
+            message,
+        }
+    }
+}
+
+impl From<Error> for InfluxdbV2ErrorResponse {
+    fn from(err: Error) -> Self {
+        Self::new(err.http_status_code(), err.output_msg())
+    }
+}
+
+impl IntoResponse for InfluxdbV2ErrorResponse {
+    fn into_response(self) -> Response {
+        (self.status, Json(&self)).into_response()
+    }
 }
 
 pub async fn influxdb_write(
@@ -123,9 +165,7 @@ fn parse_time_precision(value: &str) -> Result<Precision> {
 
 #[cfg(test)]
 mod tests {
-    use common_grpc::writer::Precision;
-
-    use crate::http::influxdb::parse_time_precision;
+    use super::*;
 
     #[test]
     fn test_parse_time_precision() {
@@ -139,4 +179,19 @@ mod tests {
         assert_eq!(Precision::Hour, parse_time_precision("h").unwrap());
         assert!(parse_time_precision("unknown").is_err());
     }
+
+    #[test]
+    fn test_influxdb_v2_error_response() {
+        let err = parse_time_precision("unknown").unwrap_err();
+        let resp = InfluxdbV2ErrorResponse::from(err);
+        assert_eq!(StatusCode::BAD_REQUEST, resp.status);
+        assert_eq!("invalid", resp.code);
+
+        let resp =
+            InfluxdbV2ErrorResponse::new(StatusCode::UNAUTHORIZED, "auth failed".to_string());
+        assert_eq!(
+            r#"{"code":"unauthorized","message":"auth failed"}"#,
+            serde_json::to_string(&resp).unwrap()
+        );
+    }
 }
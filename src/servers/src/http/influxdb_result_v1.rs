@@ -25,7 +25,7 @@ use snafu::ResultExt;
 use crate::error::{Error, ToJsonSnafu};
 use crate::http::error_result::ErrorResponse;
 use crate::http::header::{GREPTIME_DB_HEADER_EXECUTION_TIME, GREPTIME_DB_HEADER_FORMAT};
-use crate::http::{Epoch, HttpResponse, ResponseFormat};
+use crate::http::{value_to_json, Epoch, HttpResponse, ResponseFormat};
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SqlQuery {
@@ -92,7 +92,7 @@ impl TryFrom<(Option<Epoch>, Vec<RecordBatch>)> for InfluxdbRecordsOutput {
                                 }
                                 _ => value,
                             };
-                            Value::try_from(value)
+                            value_to_json(value)
                         })
                         .collect::<Result<Vec<Value>, _>>()
                         .context(ToJsonSnafu)?;
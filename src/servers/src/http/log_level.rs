@@ -0,0 +1,74 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use common_telemetry::logging;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::OptionExt;
+
+use crate::error::{InvalidParameterSnafu, Result};
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LogLevelQuery {
+    /// Directives merged into the current filter, e.g. `mito2=debug`.
+    target: Option<String>,
+    /// Directives replacing the whole filter, e.g. `info,mito2=debug`.
+    filter: Option<String>,
+    /// Restores the filter the node was started with.
+    reset: Option<bool>,
+}
+
+/// Returns the log filter in effect.
+#[axum_macros::debug_handler]
+pub async fn get_log_level() -> Result<impl IntoResponse> {
+    let filter = logging::log_filter().context(InvalidParameterSnafu {
+        reason: "global logging is not initialized",
+    })?;
+    Ok((StatusCode::OK, filter))
+}
+
+/// Changes the log filter at runtime and returns the new one.
+#[axum_macros::debug_handler]
+pub async fn set_log_level(Query(req): Query<LogLevelQuery>) -> Result<impl IntoResponse> {
+    let result = match req {
+        LogLevelQuery {
+            reset: Some(true), ..
+        } => logging::reset_log_filter(),
+        LogLevelQuery {
+            filter: Some(filter),
+            ..
+        } => logging::reload_log_filter(&filter),
+        LogLevelQuery {
+            target: Some(target),
+            ..
+        } => {
+            let current = logging::log_filter().unwrap_or_default();
+            logging::reload_log_filter(&logging::merge_log_filter(&current, &target))
+        }
+        _ => {
+            return InvalidParameterSnafu {
+                reason: "one of 'target', 'filter' or 'reset' is required",
+            }
+            .fail()
+        }
+    };
+    result.map_err(|reason| InvalidParameterSnafu { reason }.build())?;
+
+    let filter = logging::log_filter().unwrap_or_default();
+    logging::info!("log filter changed to '{}'", filter);
+    Ok((StatusCode::OK, filter))
+}
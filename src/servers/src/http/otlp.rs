@@ -13,11 +13,14 @@
 // limitations under the License.
 
 use axum::extract::{RawBody, State};
-use axum::http::header;
+use axum::http::{header, HeaderMap};
 use axum::response::IntoResponse;
 use axum::Extension;
 use common_telemetry::tracing;
 use hyper::Body;
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
 use opentelemetry_proto::tonic::collector::metrics::v1::{
     ExportMetricsServiceRequest, ExportMetricsServiceResponse,
 };
@@ -30,6 +33,7 @@ use snafu::prelude::*;
 
 use super::header::{write_cost_header_map, CONTENT_TYPE_PROTOBUF};
 use crate::error::{self, Result};
+use crate::otlp;
 use crate::query_handler::OpenTelemetryProtocolHandlerRef;
 
 #[axum_macros::debug_handler]
@@ -124,3 +128,51 @@ impl IntoResponse for OtlpTracesResponse {
         (header_map, self.resp_body.encode_to_vec()).into_response()
     }
 }
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "otlp", request_type = "logs"))]
+pub async fn logs(
+    State(handler): State<OpenTelemetryProtocolHandlerRef>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<OtlpLogsResponse> {
+    let db = query_ctx.get_db_string();
+    let _timer = crate::metrics::METRIC_HTTP_OPENTELEMETRY_LOGS_ELAPSED
+        .with_label_values(&[db.as_str()])
+        .start_timer();
+    let table_name = otlp::logs::table_name_from_headers(&headers)?;
+    let request = parse_logs_body(body).await?;
+    handler
+        .logs(request, table_name, query_ctx)
+        .await
+        .map(|o| OtlpLogsResponse {
+            resp_body: ExportLogsServiceResponse {
+                partial_success: None,
+            },
+            write_cost: o.meta.cost,
+        })
+}
+
+async fn parse_logs_body(body: Body) -> Result<ExportLogsServiceRequest> {
+    hyper::body::to_bytes(body)
+        .await
+        .context(error::HyperSnafu)
+        .and_then(|buf| {
+            ExportLogsServiceRequest::decode(&buf[..]).context(error::DecodeOtlpRequestSnafu)
+        })
+}
+
+pub struct OtlpLogsResponse {
+    resp_body: ExportLogsServiceResponse,
+    write_cost: usize,
+}
+
+impl IntoResponse for OtlpLogsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let mut header_map = write_cost_header_map(self.write_cost);
+        header_map.insert(header::CONTENT_TYPE, CONTENT_TYPE_PROTOBUF.clone());
+
+        (header_map, self.resp_body.encode_to_vec()).into_response()
+    }
+}
@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use api::prom_store::remote::ReadRequest;
 use api::v1::RowInsertRequests;
+use axum::body::StreamBody;
 use axum::extract::{Query, RawBody, State};
 use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::IntoResponse;
@@ -35,9 +36,10 @@ use snafu::prelude::*;
 
 use super::header::{write_cost_header_map, GREPTIME_DB_HEADER_METRICS};
 use crate::error::{self, Result, UnexpectedPhysicalTableSnafu};
+use crate::prom_relabel::{RelabelRules, RelabelRulesRef};
 use crate::prom_store::{snappy_decompress, zstd_decompress};
 use crate::proto::PromWriteRequest;
-use crate::query_handler::{PromStoreProtocolHandlerRef, PromStoreResponse};
+use crate::query_handler::{PromStoreProtocolHandlerRef, PromStoreResponse, PromStoreResponseBody};
 
 pub const PHYSICAL_TABLE_PARAM: &str = "physical_table";
 lazy_static! {
@@ -75,6 +77,7 @@ pub async fn route_write_without_metric_engine(
     State(handler): State<PromStoreProtocolHandlerRef>,
     Query(params): Query<RemoteWriteQuery>,
     Extension(query_ctx): Extension<QueryContextRef>,
+    Extension(relabel_rules): Extension<RelabelRulesRef>,
     content_encoding: TypedHeader<headers::ContentEncoding>,
     RawBody(body): RawBody,
 ) -> Result<impl IntoResponse> {
@@ -89,17 +92,19 @@ pub async fn route_write_without_metric_engine(
         .start_timer();
 
     let is_zstd = content_encoding.contains(VM_ENCODING);
-    let (request, samples) = decode_remote_write_request(is_zstd, body, true).await?;
+    let (request, extra_request, samples) =
+        decode_remote_write_request(is_zstd, body, true, &relabel_rules).await?;
     // reject if physical table is specified when metric engine is disabled
     if params.physical_table.is_some() {
         return UnexpectedPhysicalTableSnafu {}.fail();
     }
 
+    let extra_cost = write_extra_requests(&handler, extra_request, query_ctx.clone()).await?;
     let output = handler.write(request, query_ctx, false).await?;
     crate::metrics::PROM_STORE_REMOTE_WRITE_SAMPLES.inc_by(samples as u64);
     Ok((
         StatusCode::NO_CONTENT,
-        write_cost_header_map(output.meta.cost),
+        write_cost_header_map(output.meta.cost + extra_cost),
     )
         .into_response())
 }
@@ -111,6 +116,7 @@ pub async fn route_write_without_metric_engine_and_strict_mode(
     State(handler): State<PromStoreProtocolHandlerRef>,
     Query(params): Query<RemoteWriteQuery>,
     Extension(query_ctx): Extension<QueryContextRef>,
+    Extension(relabel_rules): Extension<RelabelRulesRef>,
     content_encoding: TypedHeader<headers::ContentEncoding>,
     RawBody(body): RawBody,
 ) -> Result<impl IntoResponse> {
@@ -120,17 +126,19 @@ pub async fn route_write_without_metric_engine_and_strict_mode(
         .start_timer();
 
     let is_zstd = content_encoding.contains(VM_ENCODING);
-    let (request, samples) = decode_remote_write_request(is_zstd, body, false).await?;
+    let (request, extra_request, samples) =
+        decode_remote_write_request(is_zstd, body, false, &relabel_rules).await?;
     // reject if physical table is specified when metric engine is disabled
     if params.physical_table.is_some() {
         return UnexpectedPhysicalTableSnafu {}.fail();
     }
 
+    let extra_cost = write_extra_requests(&handler, extra_request, query_ctx.clone()).await?;
     let output = handler.write(request, query_ctx, false).await?;
     crate::metrics::PROM_STORE_REMOTE_WRITE_SAMPLES.inc_by(samples as u64);
     Ok((
         StatusCode::NO_CONTENT,
-        write_cost_header_map(output.meta.cost),
+        write_cost_header_map(output.meta.cost + extra_cost),
     )
         .into_response())
 }
@@ -144,6 +152,7 @@ pub async fn remote_write(
     State(handler): State<PromStoreProtocolHandlerRef>,
     Query(params): Query<RemoteWriteQuery>,
     Extension(mut query_ctx): Extension<QueryContextRef>,
+    Extension(relabel_rules): Extension<RelabelRulesRef>,
     content_encoding: TypedHeader<headers::ContentEncoding>,
     RawBody(body): RawBody,
 ) -> Result<impl IntoResponse> {
@@ -158,8 +167,8 @@ pub async fn remote_write(
         .start_timer();
 
     let is_zstd = content_encoding.contains(VM_ENCODING);
-    let (request, samples) =
-        decode_remote_write_request_to_row_inserts(is_zstd, body, true).await?;
+    let (request, extra_request, samples) =
+        decode_remote_write_request_to_row_inserts(is_zstd, body, true, &relabel_rules).await?;
 
     if let Some(physical_table) = params.physical_table {
         let mut new_query_ctx = query_ctx.as_ref().clone();
@@ -167,11 +176,12 @@ pub async fn remote_write(
         query_ctx = Arc::new(new_query_ctx);
     }
 
+    let extra_cost = write_extra_requests(&handler, extra_request, query_ctx.clone()).await?;
     let output = handler.write(request, query_ctx, true).await?;
     crate::metrics::PROM_STORE_REMOTE_WRITE_SAMPLES.inc_by(samples as u64);
     Ok((
         StatusCode::NO_CONTENT,
-        write_cost_header_map(output.meta.cost),
+        write_cost_header_map(output.meta.cost + extra_cost),
     )
         .into_response())
 }
@@ -185,6 +195,7 @@ pub async fn remote_write_without_strict_mode(
     State(handler): State<PromStoreProtocolHandlerRef>,
     Query(params): Query<RemoteWriteQuery>,
     Extension(mut query_ctx): Extension<QueryContextRef>,
+    Extension(relabel_rules): Extension<RelabelRulesRef>,
     content_encoding: TypedHeader<headers::ContentEncoding>,
     RawBody(body): RawBody,
 ) -> Result<impl IntoResponse> {
@@ -199,8 +210,8 @@ pub async fn remote_write_without_strict_mode(
         .start_timer();
 
     let is_zstd = content_encoding.contains(VM_ENCODING);
-    let (request, samples) =
-        decode_remote_write_request_to_row_inserts(is_zstd, body, false).await?;
+    let (request, extra_request, samples) =
+        decode_remote_write_request_to_row_inserts(is_zstd, body, false, &relabel_rules).await?;
 
     if let Some(physical_table) = params.physical_table {
         let mut new_query_ctx = query_ctx.as_ref().clone();
@@ -208,11 +219,12 @@ pub async fn remote_write_without_strict_mode(
         query_ctx = Arc::new(new_query_ctx);
     }
 
+    let extra_cost = write_extra_requests(&handler, extra_request, query_ctx.clone()).await?;
     let output = handler.write(request, query_ctx, false).await?;
     crate::metrics::PROM_STORE_REMOTE_WRITE_SAMPLES.inc_by(samples as u64);
     Ok((
         StatusCode::NO_CONTENT,
-        write_cost_header_map(output.meta.cost),
+        write_cost_header_map(output.meta.cost + extra_cost),
     )
         .into_response())
 }
@@ -221,7 +233,9 @@ impl IntoResponse for PromStoreResponse {
     fn into_response(self) -> axum::response::Response {
         let mut header_map = HeaderMap::new();
         header_map.insert(&header::CONTENT_TYPE, self.content_type);
-        header_map.insert(&header::CONTENT_ENCODING, self.content_encoding);
+        if let Some(content_encoding) = self.content_encoding {
+            header_map.insert(&header::CONTENT_ENCODING, content_encoding);
+        }
 
         let metrics = if self.resp_metrics.is_empty() {
             None
@@ -232,7 +246,13 @@ impl IntoResponse for PromStoreResponse {
             header_map.insert(&GREPTIME_DB_HEADER_METRICS, m);
         }
 
-        (header_map, self.body).into_response()
+        match self.body {
+            PromStoreResponseBody::Bytes(body) => (header_map, body).into_response(),
+            // Without a content length, hyper sends the body with chunked transfer encoding.
+            PromStoreResponseBody::Stream(body) => {
+                (header_map, StreamBody::new(body)).into_response()
+            }
+        }
     }
 }
 
@@ -261,7 +281,8 @@ async fn decode_remote_write_request_to_row_inserts(
     is_zstd: bool,
     body: Body,
     is_strict_mode: bool,
-) -> Result<(RowInsertRequests, usize)> {
+    relabel_rules: &RelabelRules,
+) -> Result<(RowInsertRequests, RowInsertRequests, usize)> {
     let _timer = crate::metrics::METRIC_HTTP_PROM_STORE_DECODE_ELAPSED.start_timer();
     let body = hyper::body::to_bytes(body)
         .await
@@ -275,16 +296,21 @@ async fn decode_remote_write_request_to_row_inserts(
 
     let mut request = PROM_WRITE_REQUEST_POOL.pull(PromWriteRequest::default);
     request
-        .merge(buf, is_strict_mode)
+        .merge_with_relabel(buf, is_strict_mode, Some(relabel_rules))
         .context(error::DecodePromRemoteRequestSnafu)?;
-    Ok(request.as_row_insert_requests())
+    let (mut extra_request, histogram_samples) = request.as_histogram_row_insert_requests();
+    let (exemplar_request, _) = request.as_exemplar_row_insert_requests();
+    extra_request.inserts.extend(exemplar_request.inserts);
+    let (request, samples) = request.as_row_insert_requests();
+    Ok((request, extra_request, samples + histogram_samples))
 }
 
 async fn decode_remote_write_request(
     is_zstd: bool,
     body: Body,
     is_strict_mode: bool,
-) -> Result<(RowInsertRequests, usize)> {
+    relabel_rules: &RelabelRules,
+) -> Result<(RowInsertRequests, RowInsertRequests, usize)> {
     let _timer = crate::metrics::METRIC_HTTP_PROM_STORE_DECODE_ELAPSED.start_timer();
     let body = hyper::body::to_bytes(body)
         .await
@@ -298,9 +324,29 @@ async fn decode_remote_write_request(
 
     let mut request = PromWriteRequest::default();
     request
-        .merge(buf, is_strict_mode)
+        .merge_with_relabel(buf, is_strict_mode, Some(relabel_rules))
         .context(error::DecodePromRemoteRequestSnafu)?;
-    Ok(request.as_row_insert_requests())
+    let (mut extra_request, histogram_samples) = request.as_histogram_row_insert_requests();
+    let (exemplar_request, _) = request.as_exemplar_row_insert_requests();
+    extra_request.inserts.extend(exemplar_request.inserts);
+    let (request, samples) = request.as_row_insert_requests();
+    Ok((request, extra_request, samples + histogram_samples))
+}
+
+/// Writes native histograms and exemplars in `request`, returns the cost of the write.
+///
+/// They are always written without the metric engine, which only supports
+/// float values.
+async fn write_extra_requests(
+    handler: &PromStoreProtocolHandlerRef,
+    request: RowInsertRequests,
+    query_ctx: QueryContextRef,
+) -> Result<usize> {
+    if request.inserts.is_empty() {
+        return Ok(0);
+    }
+    let output = handler.write(request, query_ctx, false).await?;
+    Ok(output.meta.cost)
 }
 
 async fn decode_remote_read_request(body: Body) -> Result<ReadRequest> {
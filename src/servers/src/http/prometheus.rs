@@ -13,43 +13,45 @@
 // limitations under the License.
 
 //! prom supply the prometheus HTTP API Server compliance
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
+use api::prom_store::remote::label_matcher::Type as MatcherType;
+use api::prom_store::remote::{LabelMatcher, Query as RemoteQuery, ReadHints, TimeSeries};
 use axum::extract::{Path, Query, State};
 use axum::{Extension, Form};
 use catalog::CatalogManagerRef;
+use chrono::{DateTime, Utc};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_catalog::parse_catalog_and_schema_from_db_string;
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
-use common_query::prelude::{GREPTIME_TIMESTAMP, GREPTIME_VALUE};
+use common_query::prelude::{GREPTIME_EXEMPLAR_LABELS, GREPTIME_TIMESTAMP, GREPTIME_VALUE};
 use common_query::{Output, OutputData};
 use common_recordbatch::RecordBatches;
 use common_telemetry::tracing;
 use common_time::util::{current_time_rfc3339, yesterday_rfc3339};
 use common_version::BuildInfo;
-use datatypes::prelude::ConcreteDataType;
-use datatypes::scalars::ScalarVector;
-use datatypes::vectors::{Float64Vector, StringVector};
-use promql_parser::label::METRIC_NAME;
+use datatypes::value::Value as DatatypeValue;
+use promql_parser::label::{MatchOp, Matcher, METRIC_NAME};
 use promql_parser::parser::{
     AggregateExpr, BinaryExpr, Call, Expr as PromqlExpr, MatrixSelector, ParenExpr, SubqueryExpr,
     UnaryExpr, ValueType, VectorSelector,
 };
-use query::parser::{PromQuery, DEFAULT_LOOKBACK_STRING};
+use query::parser::{PromQuery, QueryLanguageParser, DEFAULT_LOOKBACK_STRING};
+use regex::Regex;
 use schemars::JsonSchema;
 use serde::de::{self, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use session::context::QueryContextRef;
-use snafu::{Location, ResultExt};
+use snafu::ResultExt;
 
 pub use super::prometheus_resp::PrometheusJsonResponse;
 use crate::error::{
-    CollectRecordbatchSnafu, Error, InvalidQuerySnafu, Result, UnexpectedResultSnafu,
+    CatalogErrorSnafu, CollectRecordbatchSnafu, InvalidQuerySnafu, Result, UnexpectedResultSnafu,
 };
 use crate::http::header::collect_plan_metrics;
-use crate::prom_store::METRIC_NAME_LABEL;
+use crate::prom_store::{exemplar_table_name, recordbatches_to_series, METRIC_NAME_LABEL};
 use crate::prometheus_handler::PrometheusHandlerRef;
 
 /// For [ValueType::Vector] result type
@@ -99,6 +101,22 @@ pub enum PrometheusResponse {
     LabelValues(Vec<String>),
     FormatQuery(String),
     BuildInfo(BuildInfo),
+    Exemplars(Vec<PromExemplars>),
+}
+
+/// Exemplars of a series.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PromExemplars {
+    #[serde(rename = "seriesLabels")]
+    pub series_labels: HashMap<String, String>,
+    pub exemplars: Vec<PromExemplarSample>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PromExemplarSample {
+    pub labels: HashMap<String, String>,
+    pub value: String,
+    pub timestamp: f64,
 }
 
 impl Default for PrometheusResponse {
@@ -312,59 +330,39 @@ pub async fn labels_query(
         }
     }
 
-    let start = params
-        .start
-        .or(form_params.start)
-        .unwrap_or_else(yesterday_rfc3339);
-    let end = params
-        .end
-        .or(form_params.end)
-        .unwrap_or_else(current_time_rfc3339);
-
-    let lookback = params
-        .lookback
-        .or(form_params.lookback)
-        .unwrap_or(DEFAULT_LOOKBACK_STRING.to_string());
-
-    let mut labels = HashSet::new();
-    let _ = labels.insert(METRIC_NAME.to_string());
+    let start = params.start.or(form_params.start);
+    let end = params.end.or(form_params.end);
 
     let mut merge_map = HashMap::new();
-    for query in queries {
-        let prom_query = PromQuery {
-            query,
-            start: start.clone(),
-            end: end.clone(),
-            step: DEFAULT_LOOKBACK_STRING.to_string(),
-            lookback: lookback.clone(),
-        };
-
-        let result = handler.do_query(&prom_query, query_ctx.clone()).await;
-        if let Err(err) =
-            retrieve_labels_name_from_query_result(result, &mut labels, &mut merge_map).await
-        {
-            // Prometheus won't report error if querying nonexist label and metric
-            if err.status_code() != StatusCode::TableNotFound
-                && err.status_code() != StatusCode::TableColumnNotFound
-            {
-                return PrometheusJsonResponse::error(
-                    err.status_code().to_string(),
-                    err.output_msg(),
-                );
-            }
+    let series = match query_series(
+        &handler,
+        &query_ctx,
+        &queries,
+        start,
+        end,
+        None,
+        &mut merge_map,
+    )
+    .await
+    {
+        Ok(series) => series,
+        Err(err) => {
+            return PrometheusJsonResponse::error(err.status_code().to_string(), err.output_msg())
         }
-    }
+    };
 
-    let _ = labels.remove(GREPTIME_TIMESTAMP);
-    let _ = labels.remove(GREPTIME_VALUE);
+    let mut labels = BTreeSet::new();
+    let _ = labels.insert(METRIC_NAME.to_string());
+    for series in series {
+        labels.extend(series.labels.into_iter().map(|label| label.name));
+    }
 
-    let mut sorted_labels: Vec<String> = labels.into_iter().collect();
-    sorted_labels.sort();
     let merge_map = merge_map
         .into_iter()
         .map(|(k, v)| (k, Value::from(v)))
         .collect();
-    let mut resp = PrometheusJsonResponse::success(PrometheusResponse::Labels(sorted_labels));
+    let mut resp =
+        PrometheusJsonResponse::success(PrometheusResponse::Labels(labels.into_iter().collect()));
     resp.resp_metrics = merge_map;
     resp
 }
@@ -392,135 +390,232 @@ async fn get_all_column_names(
     Ok(labels_vec)
 }
 
-async fn retrieve_series_from_query_result(
-    result: Result<Output>,
-    series: &mut Vec<HashMap<String, String>>,
-    table_name: &str,
-    metrics: &mut HashMap<String, u64>,
-) -> Result<()> {
-    let result = result?;
-    match result.data {
-        OutputData::RecordBatches(batches) => record_batches_to_series(batches, series, table_name),
-        OutputData::Stream(stream) => {
-            let batches = RecordBatches::try_collect(stream)
-                .await
-                .context(CollectRecordbatchSnafu)?;
-            record_batches_to_series(batches, series, table_name)
-        }
-        OutputData::AffectedRows(_) => Err(Error::UnexpectedResult {
-            reason: "expected data result, but got affected rows".to_string(),
-            location: Location::default(),
-        }),
-    }?;
+/// Returns names of tables having the tag column `tag`.
+async fn tables_with_tag(
+    catalog: &str,
+    schema: &str,
+    tag: &str,
+    manager: &CatalogManagerRef,
+) -> std::result::Result<Vec<String>, catalog::error::Error> {
+    let table_names = manager.table_names(catalog, schema).await?;
 
-    if let Some(ref plan) = result.meta.plan {
-        collect_plan_metrics(plan.clone(), &mut [metrics]);
+    let mut tables = Vec::new();
+    for table_name in table_names {
+        let Some(table) = manager.table(catalog, schema, &table_name).await? else {
+            continue;
+        };
+        if table
+            .table_info()
+            .meta
+            .row_key_column_names()
+            .any(|column| column == tag)
+        {
+            tables.push(table_name);
+        }
     }
-    Ok(())
+    Ok(tables)
 }
 
-/// Retrieve labels name from query result
-async fn retrieve_labels_name_from_query_result(
-    result: Result<Output>,
-    labels: &mut HashSet<String>,
+/// Queries label sets of series selected by `selectors` in the time range.
+///
+/// Each selector is translated into table scans of metrics it selects rather than
+/// evaluated as PromQL, so predicates of matchers and the time range can be pruned
+/// by indexes. Only labels in `grouping` are queried if it's present.
+async fn query_series(
+    handler: &PrometheusHandlerRef,
+    query_ctx: &QueryContextRef,
+    selectors: &[String],
+    start: Option<String>,
+    end: Option<String>,
+    grouping: Option<Vec<String>>,
     metrics: &mut HashMap<String, u64>,
-) -> Result<()> {
-    let result = result?;
-    match result.data {
-        OutputData::RecordBatches(batches) => record_batches_to_labels_name(batches, labels),
-        OutputData::Stream(stream) => {
-            let batches = RecordBatches::try_collect(stream)
-                .await
-                .context(CollectRecordbatchSnafu)?;
-            record_batches_to_labels_name(batches, labels)
-        }
-        OutputData::AffectedRows(_) => UnexpectedResultSnafu {
-            reason: "expected data result, but got affected rows".to_string(),
-        }
-        .fail(),
-    }?;
-    if let Some(ref plan) = result.meta.plan {
-        collect_plan_metrics(plan.clone(), &mut [metrics]);
+) -> Result<Vec<TimeSeries>> {
+    let mut selections = Vec::with_capacity(selectors.len());
+    for selector in selectors {
+        selections.push(parse_series_selector(handler, query_ctx, selector).await?);
     }
-    Ok(())
+    query_selected_series(
+        handler, query_ctx, selections, start, end, grouping, metrics,
+    )
+    .await
 }
 
-fn record_batches_to_series(
-    batches: RecordBatches,
-    series: &mut Vec<HashMap<String, String>>,
-    table_name: &str,
-) -> Result<()> {
-    for batch in batches.iter() {
-        for row in batch.rows() {
-            let mut element: HashMap<String, String> = row
-                .iter()
-                .enumerate()
-                .map(|(idx, column)| {
-                    let column_name = batch.schema.column_name_by_index(idx);
-                    (column_name.to_string(), column.to_string())
-                })
-                .collect();
-            let _ = element.insert("__name__".to_string(), table_name.to_string());
-            series.push(element);
+/// Queries label sets of series of the selected metrics, like [query_series]. Each
+/// selection is names of metrics and matchers of labels other than the metric name.
+async fn query_selected_series(
+    handler: &PrometheusHandlerRef,
+    query_ctx: &QueryContextRef,
+    selections: Vec<(Vec<String>, Vec<LabelMatcher>)>,
+    start: Option<String>,
+    end: Option<String>,
+    grouping: Option<Vec<String>>,
+    metrics: &mut HashMap<String, u64>,
+) -> Result<Vec<TimeSeries>> {
+    let start = start.unwrap_or_else(yesterday_rfc3339);
+    let end = end.unwrap_or_else(current_time_rfc3339);
+    let start_ms = parse_timestamp_millis(&start)?;
+    let end_ms = parse_timestamp_millis(&end)?;
+
+    let hints = ReadHints {
+        func: "series".to_string(),
+        start_ms,
+        end_ms,
+        by: grouping.is_some(),
+        grouping: grouping.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let mut series = Vec::new();
+    for (metric_names, matchers) in selections {
+        for metric_name in metric_names {
+            let mut label_matchers = vec![LabelMatcher {
+                name: METRIC_NAME.to_string(),
+                value: metric_name.clone(),
+                r#type: MatcherType::Eq as i32,
+            }];
+            label_matchers.extend(matchers.iter().cloned());
+            let query = RemoteQuery {
+                start_timestamp_ms: start_ms,
+                end_timestamp_ms: end_ms,
+                matchers: label_matchers,
+                hints: Some(hints.clone()),
+            };
+
+            let result = handler.query_series(&query, query_ctx.clone()).await;
+            match retrieve_series_from_query_result(result, &metric_name, metrics).await {
+                Ok(metric_series) => series.extend(metric_series),
+                // Prometheus won't report error if querying nonexist label and metric
+                Err(err)
+                    if err.status_code() == StatusCode::TableNotFound
+                        || err.status_code() == StatusCode::TableColumnNotFound => {}
+                Err(err) => return Err(err),
+            }
         }
     }
-    Ok(())
+
+    Ok(series)
 }
 
-/// Retrieve labels name from record batches
-fn record_batches_to_labels_name(
-    batches: RecordBatches,
-    labels: &mut HashSet<String>,
-) -> Result<()> {
-    let mut column_indices = Vec::new();
-    let mut field_column_indices = Vec::new();
-    for (i, column) in batches.schema().column_schemas().iter().enumerate() {
-        if let ConcreteDataType::Float64(_) = column.data_type {
-            field_column_indices.push(i);
+/// Parses a series selector, returns names of metrics it selects and its matchers
+/// of labels other than the metric name.
+async fn parse_series_selector(
+    handler: &PrometheusHandlerRef,
+    query_ctx: &QueryContextRef,
+    selector: &str,
+) -> Result<(Vec<String>, Vec<LabelMatcher>)> {
+    let expr = promql_parser::parser::parse(selector)
+        .map_err(|reason| InvalidQuerySnafu { reason }.build())?;
+    let PromqlExpr::VectorSelector(VectorSelector { name, matchers, .. }) = expr else {
+        return InvalidQuerySnafu {
+            reason: format!("match[] must be a series selector, actual: {selector}"),
         }
-        column_indices.push(i);
-    }
+        .fail();
+    };
 
-    if field_column_indices.is_empty() {
-        return Err(Error::Internal {
-            err_msg: "no value column found".to_string(),
-        });
+    let mut name_matchers = Vec::new();
+    let mut label_matchers = Vec::new();
+    for matcher in matchers.matchers {
+        let label_matcher = to_label_matcher(matcher);
+        if label_matcher.name == METRIC_NAME {
+            name_matchers.push(label_matcher);
+        } else {
+            label_matchers.push(label_matcher);
+        }
     }
 
-    for batch in batches.iter() {
-        let names = column_indices
-            .iter()
-            .map(|c| batches.schema().column_name_by_index(*c).to_string())
-            .collect::<Vec<_>>();
-
-        let field_columns = field_column_indices
+    let metric_names = if let Some(name) = name {
+        vec![name]
+    } else if let Some(matcher) = name_matchers
+        .iter()
+        .find(|m| m.r#type == MatcherType::Eq as i32)
+    {
+        vec![matcher.value.clone()]
+    } else {
+        // Selects metrics by matching names of all tables.
+        let table_names = handler
+            .catalog_manager()
+            .table_names(query_ctx.current_catalog(), query_ctx.current_schema())
+            .await
+            .context(CatalogErrorSnafu)?;
+        let name_matchers = name_matchers
             .iter()
-            .map(|i| {
-                batch
-                    .column(*i)
-                    .as_any()
-                    .downcast_ref::<Float64Vector>()
-                    .unwrap()
+            .map(|m| {
+                let pattern = if m.r#type == MatcherType::Neq as i32 {
+                    regex::escape(&m.value)
+                } else {
+                    m.value.clone()
+                };
+                let regex = Regex::new(&format!("^(?:{pattern})$")).map_err(|e| {
+                    InvalidQuerySnafu {
+                        reason: format!("invalid regexp '{}': {e}", m.value),
+                    }
+                    .build()
+                })?;
+                Ok((m.r#type, regex))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        table_names
+            .into_iter()
+            .filter(|table| {
+                name_matchers.iter().all(|(r#type, regex)| {
+                    (*r#type == MatcherType::Nre as i32 || *r#type == MatcherType::Neq as i32)
+                        != regex.is_match(table)
+                })
             })
-            .collect::<Vec<_>>();
+            .collect()
+    };
 
-        for row_index in 0..batch.num_rows() {
-            // if all field columns are null, skip this row
-            if field_columns
-                .iter()
-                .all(|c| c.get_data(row_index).is_none())
-            {
-                continue;
-            }
+    Ok((metric_names, label_matchers))
+}
 
-            // if a field is not null, record the tag name and return
-            names.iter().for_each(|name| {
-                let _ = labels.insert(name.to_string());
-            });
-            return Ok(());
+/// Converts a PromQL label matcher into a remote read label matcher.
+fn to_label_matcher(matcher: Matcher) -> LabelMatcher {
+    let r#type = match matcher.op {
+        MatchOp::Equal => MatcherType::Eq,
+        MatchOp::NotEqual => MatcherType::Neq,
+        MatchOp::Re(_) => MatcherType::Re,
+        MatchOp::NotRe(_) => MatcherType::Nre,
+    };
+    LabelMatcher {
+        name: matcher.name,
+        value: matcher.value,
+        r#type: r#type as i32,
+    }
+}
+
+fn parse_timestamp_millis(timestamp: &str) -> Result<i64> {
+    let timestamp = QueryLanguageParser::parse_promql_timestamp(timestamp).map_err(|e| {
+        InvalidQuerySnafu {
+            reason: e.output_msg(),
         }
+        .build()
+    })?;
+    Ok(DateTime::<Utc>::from(timestamp).timestamp_millis())
+}
+
+async fn retrieve_series_from_query_result(
+    result: Result<Output>,
+    metric_name: &str,
+    metrics: &mut HashMap<String, u64>,
+) -> Result<Vec<TimeSeries>> {
+    let result = result?;
+    let batches = match result.data {
+        OutputData::RecordBatches(batches) => batches,
+        OutputData::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(CollectRecordbatchSnafu)?,
+        OutputData::AffectedRows(_) => {
+            return UnexpectedResultSnafu {
+                reason: "expected data result, but got affected rows".to_string(),
+            }
+            .fail()
+        }
+    };
+
+    if let Some(ref plan) = result.meta.plan {
+        collect_plan_metrics(plan.clone(), &mut [metrics]);
     }
-    Ok(())
+    Ok(recordbatches_to_series(metric_name, batches))
 }
 
 pub(crate) fn retrieve_metric_name_and_result_type(
@@ -583,7 +678,7 @@ pub async fn label_values_query(
     let db = &params.db.unwrap_or(DEFAULT_SCHEMA_NAME.to_string());
     let (catalog, schema) = parse_catalog_and_schema_from_db_string(db);
 
-    if label_name == METRIC_NAME_LABEL {
+    if label_name == METRIC_NAME_LABEL && params.matches.0.is_empty() {
         let mut table_names = match handler
             .catalog_manager()
             .table_names(&catalog, &schema)
@@ -598,121 +693,65 @@ pub async fn label_values_query(
         return PrometheusJsonResponse::success(PrometheusResponse::LabelValues(table_names));
     }
 
-    let queries = params.matches.0;
-    if queries.is_empty() {
-        return PrometheusJsonResponse::error("Invalid argument", "match[] parameter is required");
-    }
-
-    let start = params.start.unwrap_or_else(yesterday_rfc3339);
-    let end = params.end.unwrap_or_else(current_time_rfc3339);
-    let lookback = params
-        .lookback
-        .unwrap_or(DEFAULT_LOOKBACK_STRING.to_string());
-
-    let mut label_values = HashSet::new();
-
     let mut merge_map = HashMap::new();
-    for query in queries {
-        let prom_query = PromQuery {
-            query,
-            start: start.clone(),
-            end: end.clone(),
-            step: DEFAULT_LOOKBACK_STRING.to_string(),
-            lookback: lookback.clone(),
-        };
-        let result = handler.do_query(&prom_query, query_ctx.clone()).await;
-        if let Err(err) =
-            retrieve_label_values(result, &label_name, &mut label_values, &mut merge_map).await
-        {
-            // Prometheus won't report error if querying nonexist label and metric
-            if err.status_code() != StatusCode::TableNotFound
-                && err.status_code() != StatusCode::TableColumnNotFound
-            {
-                return PrometheusJsonResponse::error(
-                    err.status_code().to_string(),
-                    err.output_msg(),
-                );
+    let grouping = Some(vec![label_name.clone()]);
+    let series = if params.matches.0.is_empty() {
+        // Without match[], only metrics having the label are queried, which are
+        // found by the table metadata.
+        match tables_with_tag(&catalog, &schema, &label_name, &handler.catalog_manager()).await {
+            Ok(metric_names) => {
+                query_selected_series(
+                    &handler,
+                    &query_ctx,
+                    vec![(metric_names, vec![])],
+                    params.start,
+                    params.end,
+                    grouping,
+                    &mut merge_map,
+                )
+                .await
+            }
+            Err(e) => {
+                return PrometheusJsonResponse::error(e.status_code().to_string(), e.output_msg());
             }
         }
-    }
+    } else {
+        query_series(
+            &handler,
+            &query_ctx,
+            &params.matches.0,
+            params.start,
+            params.end,
+            grouping,
+            &mut merge_map,
+        )
+        .await
+    };
+    let series = match series {
+        Ok(series) => series,
+        Err(err) => {
+            return PrometheusJsonResponse::error(err.status_code().to_string(), err.output_msg())
+        }
+    };
+
+    let label_values = series
+        .into_iter()
+        .flat_map(|series| series.labels)
+        .filter(|label| label.name == label_name)
+        .map(|label| label.value)
+        .collect::<BTreeSet<_>>();
 
     let merge_map = merge_map
         .into_iter()
         .map(|(k, v)| (k, Value::from(v)))
         .collect();
-
-    let mut label_values: Vec<_> = label_values.into_iter().collect();
-    label_values.sort();
-    let mut resp = PrometheusJsonResponse::success(PrometheusResponse::LabelValues(label_values));
+    let mut resp = PrometheusJsonResponse::success(PrometheusResponse::LabelValues(
+        label_values.into_iter().collect(),
+    ));
     resp.resp_metrics = merge_map;
     resp
 }
 
-async fn retrieve_label_values(
-    result: Result<Output>,
-    label_name: &str,
-    labels_values: &mut HashSet<String>,
-    metrics: &mut HashMap<String, u64>,
-) -> Result<()> {
-    let result = result?;
-    match result.data {
-        OutputData::RecordBatches(batches) => {
-            retrieve_label_values_from_record_batch(batches, label_name, labels_values).await
-        }
-        OutputData::Stream(stream) => {
-            let batches = RecordBatches::try_collect(stream)
-                .await
-                .context(CollectRecordbatchSnafu)?;
-            retrieve_label_values_from_record_batch(batches, label_name, labels_values).await
-        }
-        OutputData::AffectedRows(_) => UnexpectedResultSnafu {
-            reason: "expected data result, but got affected rows".to_string(),
-        }
-        .fail(),
-    }?;
-
-    if let Some(ref plan) = result.meta.plan {
-        collect_plan_metrics(plan.clone(), &mut [metrics]);
-    }
-
-    Ok(())
-}
-
-async fn retrieve_label_values_from_record_batch(
-    batches: RecordBatches,
-    label_name: &str,
-    labels_values: &mut HashSet<String>,
-) -> Result<()> {
-    let Some(label_col_idx) = batches.schema().column_index_by_name(label_name) else {
-        return Ok(());
-    };
-
-    // check whether label_name belongs to tag column
-    match batches
-        .schema()
-        .column_schema_by_name(label_name)
-        .unwrap()
-        .data_type
-    {
-        ConcreteDataType::String(_) => {}
-        _ => return Ok(()),
-    }
-    for batch in batches.iter() {
-        let label_column = batch
-            .column(label_col_idx)
-            .as_any()
-            .downcast_ref::<StringVector>()
-            .unwrap();
-        for row_index in 0..batch.num_rows() {
-            if let Some(label_value) = label_column.get_data(row_index) {
-                let _ = labels_values.insert(label_value.to_string());
-            }
-        }
-    }
-
-    Ok(())
-}
-
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SeriesQuery {
     start: Option<String>,
@@ -741,6 +780,73 @@ pub async fn series_query(
     if queries.is_empty() {
         return PrometheusJsonResponse::error("Unsupported", "match[] parameter is required");
     }
+    let start = params.start.or(form_params.start);
+    let end = params.end.or(form_params.end);
+
+    let mut merge_map = HashMap::new();
+    let series = match query_series(
+        &handler,
+        &query_ctx,
+        &queries,
+        start,
+        end,
+        None,
+        &mut merge_map,
+    )
+    .await
+    {
+        Ok(series) => series,
+        Err(err) => {
+            return PrometheusJsonResponse::error(err.status_code().to_string(), err.output_msg())
+        }
+    };
+
+    // Different selectors may select the same series.
+    let series = series
+        .into_iter()
+        .map(|series| {
+            series
+                .labels
+                .into_iter()
+                .map(|label| (label.name, label.value))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|labels| labels.into_iter().collect())
+        .collect();
+
+    let merge_map = merge_map
+        .into_iter()
+        .map(|(k, v)| (k, Value::from(v)))
+        .collect();
+    let mut resp = PrometheusJsonResponse::success(PrometheusResponse::Series(series));
+    resp.resp_metrics = merge_map;
+    resp
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ExemplarsQuery {
+    query: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    db: Option<String>,
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(
+    skip_all,
+    fields(protocol = "prometheus", request_type = "query_exemplars")
+)]
+pub async fn query_exemplars(
+    State(handler): State<PrometheusHandlerRef>,
+    Query(params): Query<ExemplarsQuery>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+    Form(form_params): Form<ExemplarsQuery>,
+) -> PrometheusJsonResponse {
+    let Some(query) = params.query.or(form_params.query) else {
+        return PrometheusJsonResponse::error("Invalid argument", "query parameter is required");
+    };
     let start = params
         .start
         .or(form_params.start)
@@ -749,37 +855,162 @@ pub async fn series_query(
         .end
         .or(form_params.end)
         .unwrap_or_else(current_time_rfc3339);
-    let lookback = params
-        .lookback
-        .or(form_params.lookback)
-        .unwrap_or(DEFAULT_LOOKBACK_STRING.to_string());
+    let (start_ms, end_ms) = match (parse_timestamp_millis(&start), parse_timestamp_millis(&end)) {
+        (Ok(start), Ok(end)) => (start, end),
+        (Err(err), _) | (_, Err(err)) => {
+            return PrometheusJsonResponse::error(err.status_code().to_string(), err.output_msg())
+        }
+    };
+    let expr = match promql_parser::parser::parse(&query) {
+        Ok(expr) => expr,
+        Err(reason) => {
+            let err = InvalidQuerySnafu { reason }.build();
+            return PrometheusJsonResponse::error(err.status_code().to_string(), err.output_msg());
+        }
+    };
 
-    let mut series = Vec::new();
-    let mut merge_map = HashMap::new();
-    for query in queries {
-        let table_name = query.clone();
-        let prom_query = PromQuery {
-            query,
-            start: start.clone(),
-            end: end.clone(),
-            // TODO: find a better value for step
-            step: DEFAULT_LOOKBACK_STRING.to_string(),
-            lookback: lookback.clone(),
-        };
-        let result = handler.do_query(&prom_query, query_ctx.clone()).await;
+    let mut selectors = Vec::new();
+    collect_vector_selectors(&expr, &mut selectors);
 
+    let mut exemplars = Vec::new();
+    for VectorSelector { name, matchers, .. } in selectors {
+        let Some(metric_name) = name.clone().or(matchers.find_matcher(METRIC_NAME)) else {
+            continue;
+        };
+        // The time range and matchers are pushed down to the scan of the exemplar
+        // table, the metric name matcher selects the table.
+        let mut label_matchers = vec![LabelMatcher {
+            name: METRIC_NAME.to_string(),
+            value: metric_name.clone(),
+            r#type: MatcherType::Eq as i32,
+        }];
+        label_matchers.extend(
+            matchers
+                .matchers
+                .iter()
+                .filter(|matcher| matcher.name != METRIC_NAME)
+                .cloned()
+                .map(to_label_matcher),
+        );
+        let query = RemoteQuery {
+            start_timestamp_ms: start_ms,
+            end_timestamp_ms: end_ms,
+            matchers: label_matchers,
+            hints: None,
+        };
+        let result = handler.query_exemplars(&query, query_ctx.clone()).await;
         if let Err(err) =
-            retrieve_series_from_query_result(result, &mut series, &table_name, &mut merge_map)
-                .await
+            retrieve_exemplars_from_query_result(result, &metric_name, &mut exemplars).await
         {
-            return PrometheusJsonResponse::error(err.status_code().to_string(), err.output_msg());
+            // Metrics without exemplars have no exemplar table.
+            if err.status_code() != StatusCode::TableNotFound {
+                return PrometheusJsonResponse::error(
+                    err.status_code().to_string(),
+                    err.output_msg(),
+                );
+            }
         }
     }
-    let merge_map = merge_map
-        .into_iter()
-        .map(|(k, v)| (k, Value::from(v)))
-        .collect();
-    let mut resp = PrometheusJsonResponse::success(PrometheusResponse::Series(series));
-    resp.resp_metrics = merge_map;
-    resp
+
+    PrometheusJsonResponse::success(PrometheusResponse::Exemplars(exemplars))
+}
+
+/// Collects all vector selectors inside `expr`.
+fn collect_vector_selectors<'a>(expr: &'a PromqlExpr, selectors: &mut Vec<&'a VectorSelector>) {
+    match expr {
+        PromqlExpr::Aggregate(AggregateExpr { expr, .. })
+        | PromqlExpr::Unary(UnaryExpr { expr })
+        | PromqlExpr::Paren(ParenExpr { expr })
+        | PromqlExpr::Subquery(SubqueryExpr { expr, .. }) => {
+            collect_vector_selectors(expr, selectors)
+        }
+        PromqlExpr::Binary(BinaryExpr { lhs, rhs, .. }) => {
+            collect_vector_selectors(lhs, selectors);
+            collect_vector_selectors(rhs, selectors);
+        }
+        PromqlExpr::VectorSelector(vs) | PromqlExpr::MatrixSelector(MatrixSelector { vs, .. }) => {
+            selectors.push(vs)
+        }
+        PromqlExpr::Call(Call { args, .. }) => args
+            .args
+            .iter()
+            .for_each(|arg| collect_vector_selectors(arg, selectors)),
+        PromqlExpr::NumberLiteral(_) | PromqlExpr::StringLiteral(_) | PromqlExpr::Extension(_) => {}
+    }
+}
+
+async fn retrieve_exemplars_from_query_result(
+    result: Result<Output>,
+    metric_name: &str,
+    exemplars: &mut Vec<PromExemplars>,
+) -> Result<()> {
+    let batches = match result?.data {
+        OutputData::RecordBatches(batches) => batches,
+        OutputData::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(CollectRecordbatchSnafu)?,
+        OutputData::AffectedRows(_) => {
+            return UnexpectedResultSnafu {
+                reason: "expected data result, but got affected rows".to_string(),
+            }
+            .fail()
+        }
+    };
+    record_batches_to_exemplars(batches, metric_name, exemplars);
+    Ok(())
+}
+
+/// Groups exemplar rows by series, exemplars of each series are sorted by timestamp.
+fn record_batches_to_exemplars(
+    batches: RecordBatches,
+    metric_name: &str,
+    exemplars: &mut Vec<PromExemplars>,
+) {
+    let first_series = exemplars.len();
+    let schema = batches.schema();
+    let mut series_index = HashMap::new();
+    for batch in batches.iter() {
+        for row in batch.rows() {
+            let mut series_labels = HashMap::new();
+            let mut exemplar = PromExemplarSample::default();
+            for (idx, value) in row.into_iter().enumerate() {
+                let column_name = schema.column_name_by_index(idx);
+                match (column_name, value) {
+                    (_, DatatypeValue::Null) => {}
+                    (GREPTIME_TIMESTAMP, DatatypeValue::Timestamp(ts)) => {
+                        exemplar.timestamp = ts.value() as f64 / 1000.0;
+                    }
+                    (GREPTIME_VALUE, value) => exemplar.value = value.to_string(),
+                    (GREPTIME_EXEMPLAR_LABELS, DatatypeValue::String(labels)) => {
+                        exemplar.labels =
+                            serde_json::from_str(labels.as_utf8()).unwrap_or_default();
+                    }
+                    (_, value) => {
+                        let _ = series_labels.insert(column_name.to_string(), value.to_string());
+                    }
+                }
+            }
+
+            let _ = series_labels.insert(METRIC_NAME.to_string(), metric_name.to_string());
+            let mut key = series_labels
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>();
+            key.sort_unstable();
+            let index = *series_index.entry(key).or_insert_with(|| {
+                exemplars.push(PromExemplars {
+                    series_labels,
+                    exemplars: Vec::new(),
+                });
+                exemplars.len() - 1
+            });
+            exemplars[index].exemplars.push(exemplar);
+        }
+    }
+
+    for series in &mut exemplars[first_series..] {
+        series
+            .exemplars
+            .sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+    }
 }
@@ -0,0 +1,309 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+
+use arrow_ipc::writer::StreamWriter;
+use axum::body::StreamBody;
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use bytes::Bytes;
+use common_error::status_code::StatusCode;
+use common_query::{Output, OutputData};
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use serde_json::{json, Map, Value};
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::http::error_result::ErrorResponse;
+use crate::http::header::{GREPTIME_DB_HEADER_EXECUTION_TIME, GREPTIME_DB_HEADER_FORMAT};
+use crate::http::{value_to_json, HttpResponse, ResponseFormat};
+
+/// Response whose body is written in chunks while the query result is being read,
+/// so that large results are never buffered in memory.
+///
+/// The next record batch is only polled after the previous chunk has been written
+/// to the connection, which applies backpressure to the query stream.
+pub struct StreamResponse {
+    format: ResponseFormat,
+    output: OutputData,
+    execution_time_ms: u64,
+}
+
+impl StreamResponse {
+    /// Creates a streaming response of `format`, which must be [ResponseFormat::Arrow]
+    /// or [ResponseFormat::Ndjson].
+    pub async fn from_output(
+        mut outputs: Vec<error::Result<Output>>,
+        format: ResponseFormat,
+    ) -> HttpResponse {
+        if !matches!(format, ResponseFormat::Arrow | ResponseFormat::Ndjson) {
+            return HttpResponse::Error(ErrorResponse::from_error_message(
+                StatusCode::InvalidArguments,
+                format!("cannot stream result in {} format", format.as_str()),
+            ));
+        }
+        if outputs.len() > 1 {
+            return HttpResponse::Error(ErrorResponse::from_error_message(
+                StatusCode::InvalidArguments,
+                "cannot stream multi-statements result".to_string(),
+            ));
+        }
+
+        match outputs.pop() {
+            None => HttpResponse::Stream(StreamResponse {
+                format,
+                output: OutputData::AffectedRows(0),
+                execution_time_ms: 0,
+            }),
+            Some(Ok(output)) => HttpResponse::Stream(StreamResponse {
+                format,
+                output: output.data,
+                execution_time_ms: 0,
+            }),
+            Some(Err(e)) => HttpResponse::Error(ErrorResponse::from_error(e)),
+        }
+    }
+
+    pub fn with_execution_time(mut self, execution_time: u64) -> Self {
+        self.execution_time_ms = execution_time;
+        self
+    }
+
+    pub fn execution_time_ms(&self) -> u64 {
+        self.execution_time_ms
+    }
+
+    pub fn format(&self) -> ResponseFormat {
+        self.format
+    }
+
+    /// Returns the chunks of the response body.
+    pub fn into_body_stream(self) -> BoxStream<'static, Result<Bytes>> {
+        let stream = match self.output {
+            OutputData::AffectedRows(rows) => {
+                if self.format == ResponseFormat::Ndjson {
+                    let line = json_line(&json!({ "affected_rows": rows }));
+                    return stream::once(async move { line }).boxed();
+                }
+                return stream::empty().boxed();
+            }
+            OutputData::RecordBatches(batches) => batches.as_stream(),
+            OutputData::Stream(stream) => stream,
+        };
+        match self.format {
+            ResponseFormat::Arrow => arrow_stream(stream),
+            _ => ndjson_stream(stream),
+        }
+    }
+}
+
+impl Debug for StreamResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamResponse")
+            .field("format", &self.format)
+            .field("execution_time_ms", &self.execution_time_ms)
+            .finish()
+    }
+}
+
+impl IntoResponse for StreamResponse {
+    fn into_response(self) -> Response {
+        let content_type = match self.format {
+            ResponseFormat::Arrow => "application/vnd.apache.arrow.stream",
+            _ => "application/x-ndjson",
+        };
+        let headers = [
+            (
+                &header::CONTENT_TYPE,
+                HeaderValue::from_static(content_type),
+            ),
+            (
+                &GREPTIME_DB_HEADER_FORMAT,
+                HeaderValue::from_static(self.format.as_str()),
+            ),
+            (
+                &GREPTIME_DB_HEADER_EXECUTION_TIME,
+                HeaderValue::from(self.execution_time_ms),
+            ),
+        ];
+        // Without a content length, hyper sends the body with chunked transfer encoding.
+        (headers, StreamBody::new(self.into_body_stream())).into_response()
+    }
+}
+
+fn json_line(value: &Value) -> Result<Bytes> {
+    let mut line = serde_json::to_vec(value).context(error::ToJsonSnafu)?;
+    line.push(b'\n');
+    Ok(line.into())
+}
+
+/// Writes one JSON object per row. As the status has already been sent when the
+/// query fails halfway, the error is written as the last line.
+fn ndjson_stream(stream: SendableRecordBatchStream) -> BoxStream<'static, Result<Bytes>> {
+    stream::unfold(Some(stream), |stream| async move {
+        let mut stream = stream?;
+        let chunk = stream
+            .next()
+            .await?
+            .context(error::CollectRecordbatchSnafu)
+            .and_then(|batch| record_batch_to_ndjson(&batch));
+        match chunk {
+            Ok(chunk) => Some((Ok(chunk), Some(stream))),
+            Err(e) => {
+                let error = ErrorResponse::from_error(e);
+                let line = json_line(&json!({ "code": error.code(), "error": error.error() }));
+                Some((line, None))
+            }
+        }
+    })
+    .boxed()
+}
+
+fn record_batch_to_ndjson(batch: &RecordBatch) -> Result<Bytes> {
+    let column_schemas = batch.schema.column_schemas();
+    let mut chunk = Vec::new();
+    for row in batch.rows() {
+        let mut object = Map::with_capacity(column_schemas.len());
+        for (column_schema, value) in column_schemas.iter().zip(row) {
+            let value = value_to_json(value).context(error::ToJsonSnafu)?;
+            let _ = object.insert(column_schema.name.clone(), value);
+        }
+        serde_json::to_writer(&mut chunk, &object).context(error::ToJsonSnafu)?;
+        chunk.push(b'\n');
+    }
+    Ok(chunk.into())
+}
+
+/// Writes the Arrow IPC streaming format. A query failing halfway aborts the body,
+/// so that readers see a stream without the end-of-stream marker.
+fn arrow_stream(stream: SendableRecordBatchStream) -> BoxStream<'static, Result<Bytes>> {
+    let writer = StreamWriter::try_new(Vec::new(), stream.schema().arrow_schema())
+        .context(error::ArrowSnafu);
+    let mut writer = match writer {
+        Ok(writer) => writer,
+        Err(e) => return stream::once(async move { Err(e) }).boxed(),
+    };
+    // The schema message has been written on creation.
+    let schema = Bytes::from(std::mem::take(writer.get_mut()));
+
+    let batches = stream::unfold(Some((stream, writer)), |state| async move {
+        let (mut stream, mut writer) = state?;
+        match stream.next().await {
+            Some(batch) => {
+                let chunk = batch
+                    .context(error::CollectRecordbatchSnafu)
+                    .and_then(|batch| {
+                        writer
+                            .write(&batch.into_df_record_batch())
+                            .context(error::ArrowSnafu)
+                    })
+                    .map(|_| Bytes::from(std::mem::take(writer.get_mut())));
+                match chunk {
+                    Ok(chunk) => Some((Ok(chunk), Some((stream, writer)))),
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+            None => {
+                let chunk = writer
+                    .finish()
+                    .context(error::ArrowSnafu)
+                    .map(|_| Bytes::from(std::mem::take(writer.get_mut())));
+                Some((chunk, None))
+            }
+        }
+    });
+    stream::once(async move { Ok(schema) })
+        .chain(batches)
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    use arrow_ipc::reader::StreamReader;
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::{ConcreteDataType, VectorRef};
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{StringVector, UInt32Vector};
+
+    use super::*;
+
+    fn output() -> Output {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("numbers", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("strings", ConcreteDataType::string_datatype(), true),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt32Vector::from_slice(vec![1, 2])),
+            Arc::new(StringVector::from(vec![Some("a"), None])),
+        ];
+        let batch = RecordBatch::new(schema.clone(), columns).unwrap();
+        let batches = RecordBatches::try_new(schema, vec![batch.clone(), batch]).unwrap();
+        Output::new_with_record_batches(batches)
+    }
+
+    async fn collect_body(format: ResponseFormat, output: Output) -> Vec<Bytes> {
+        let HttpResponse::Stream(resp) =
+            StreamResponse::from_output(vec![Ok(output)], format).await
+        else {
+            unreachable!()
+        };
+        resp.into_body_stream()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_stream() {
+        let chunks = collect_body(ResponseFormat::Ndjson, output()).await;
+        assert_eq!(2, chunks.len());
+        let body = chunks.concat();
+        assert_eq!(
+            r#"{"numbers":1,"strings":"a"}
+{"numbers":2,"strings":null}
+{"numbers":1,"strings":"a"}
+{"numbers":2,"strings":null}
+"#,
+            String::from_utf8(body).unwrap()
+        );
+
+        let chunks = collect_body(ResponseFormat::Ndjson, Output::new_with_affected_rows(3)).await;
+        assert_eq!(b"{\"affected_rows\":3}\n".to_vec(), chunks.concat());
+    }
+
+    #[tokio::test]
+    async fn test_arrow_stream() {
+        let chunks = collect_body(ResponseFormat::Arrow, output()).await;
+        // The schema, two record batches and the end-of-stream marker.
+        assert_eq!(4, chunks.len());
+
+        let reader = StreamReader::try_new(Cursor::new(chunks.concat()), None).unwrap();
+        assert_eq!("numbers", reader.schema().field(0).name());
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(2, batches.len());
+        assert_eq!(2, batches[0].num_rows());
+    }
+
+    #[tokio::test]
+    async fn test_stream_unsupported_format() {
+        let resp = StreamResponse::from_output(vec![Ok(output())], ResponseFormat::Csv).await;
+        assert!(matches!(resp, HttpResponse::Error(_)));
+    }
+}
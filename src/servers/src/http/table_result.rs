@@ -75,6 +75,9 @@ impl Display for TableResponse {
             Some(GreptimeQueryOutput::AffectedRows(n)) => {
                 format!("{n}\n")
             }
+            Some(GreptimeQueryOutput::Ddl(ddl)) => {
+                format!("{}\n", ddl.affectedrows)
+            }
             Some(GreptimeQueryOutput::Records(records)) => {
                 let mut max_width = vec![0; records.num_cols()];
                 let mut result = String::new();
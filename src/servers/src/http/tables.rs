@@ -0,0 +1,464 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP APIs to introspect the schema of tables.
+
+use std::collections::{BTreeMap, HashSet};
+
+use aide::transform::TransformOperation;
+use auth::error::PrivilegeDeniedSnafu;
+use auth::UserProviderRef;
+use axum::extract::{Path, State};
+use axum::response::Json;
+use axum::Extension;
+use catalog::kvbackend::KvBackendCatalogManager;
+use catalog::CatalogManagerRef;
+use common_catalog::consts::{
+    SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_PRIMARY_KEY, SEMANTIC_TYPE_TIME_INDEX,
+};
+use common_meta::key::privilege::{GrantObject, Privilege, PrivilegeManager};
+use datatypes::data_type::DataType;
+use futures::TryStreamExt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::statements;
+use table::metadata::{TableInfo, TableMeta};
+use table::requests::FILE_TABLE_META_KEY;
+
+use crate::error::{
+    AuthSnafu, CatalogErrorSnafu, CheckPrivilegeSnafu, DatabaseNotFoundSnafu, Result,
+    TableNotFoundSnafu,
+};
+
+/// Name of the primary key index.
+const PRIMARY_KEY_INDEX: &str = "PRIMARY";
+/// Name of the time index.
+const TIME_INDEX: &str = "TIME INDEX";
+/// Name of the inverted index regions build on tag columns.
+const INVERTED_INDEX: &str = "INVERTED INDEX";
+/// Table option of the ids of columns the inverted index ignores.
+const INVERTED_INDEX_IGNORE_COLUMN_IDS_KEY: &str = "index.inverted_index.ignore_column_ids";
+
+/// State of the table APIs.
+///
+/// Users see the databases they have access to by the user provider, and the
+/// databases and tables they have the `SELECT` privilege on, same as `SHOW CREATE`
+/// statements.
+#[derive(Clone)]
+pub struct TableApiState {
+    pub catalog_manager: CatalogManagerRef,
+    pub user_provider: Option<UserProviderRef>,
+    pub privilege_manager: Option<PrivilegeManager>,
+}
+
+impl TableApiState {
+    /// Returns true if the user of `query_ctx` can access the database.
+    async fn can_access_database(
+        &self,
+        query_ctx: &QueryContextRef,
+        catalog: &str,
+        schema: &str,
+    ) -> Result<bool> {
+        let Some(user_info) = query_ctx.current_user() else {
+            return Ok(true);
+        };
+        if let Some(user_provider) = &self.user_provider {
+            if user_provider
+                .authorize(catalog, schema, &user_info)
+                .await
+                .is_err()
+            {
+                return Ok(false);
+            }
+        }
+        self.has_select_privilege(query_ctx, &GrantObject::schema(catalog, schema))
+            .await
+    }
+
+    /// Returns true if the user of `query_ctx` has the `SELECT` privilege on `object`.
+    async fn has_select_privilege(
+        &self,
+        query_ctx: &QueryContextRef,
+        object: &GrantObject,
+    ) -> Result<bool> {
+        let (Some(manager), Some(user_info)) = (&self.privilege_manager, query_ctx.current_user())
+        else {
+            return Ok(true);
+        };
+        let username = user_info.username();
+        manager
+            .check(username, Privilege::Select, object)
+            .await
+            .context(CheckPrivilegeSnafu { username })
+    }
+
+    /// Ensures the user of `query_ctx` has the `SELECT` privilege on `object`.
+    async fn ensure_select_privilege(
+        &self,
+        query_ctx: &QueryContextRef,
+        object: GrantObject,
+    ) -> Result<()> {
+        if self.has_select_privilege(query_ctx, &object).await? {
+            return Ok(());
+        }
+        let username = query_ctx
+            .current_user()
+            .map(|user_info| user_info.username().to_string())
+            .unwrap_or_default();
+        PrivilegeDeniedSnafu {
+            username,
+            privilege: Privilege::Select.to_string(),
+            object: object.to_string(),
+        }
+        .fail()
+        .context(AuthSnafu)
+    }
+}
+
+/// Databases of the catalog.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DatabaseList {
+    pub databases: Vec<String>,
+}
+
+/// Tables of a database.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TableList {
+    pub database: String,
+    pub tables: Vec<TableSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TableSummary {
+    pub name: String,
+    pub table_id: u32,
+    pub engine: String,
+    pub table_type: String,
+}
+
+/// Schema, options, partitions and indexes of a table.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TableSchema {
+    pub database: String,
+    pub name: String,
+    pub table_id: u32,
+    pub engine: String,
+    pub table_type: String,
+    /// Creation time of the table in RFC3339.
+    pub created_on: String,
+    pub columns: Vec<ColumnInfo>,
+    /// Options of the table, same as the `WITH` clause of `SHOW CREATE TABLE`.
+    pub options: BTreeMap<String, String>,
+    pub partition_columns: Vec<String>,
+    pub partitions: Vec<PartitionInfo>,
+    pub indexes: Vec<IndexInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// SQL data type of the column.
+    pub data_type: String,
+    /// One of `TAG`, `FIELD` and `TIMESTAMP`.
+    pub semantic_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PartitionInfo {
+    pub region_id: u64,
+    /// Partition rule of the region, `None` if the table isn't partitioned.
+    pub expression: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct IndexInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+/// Lists the databases of the current catalog.
+#[axum_macros::debug_handler]
+pub async fn list_databases(
+    State(state): State<TableApiState>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+) -> Result<Json<DatabaseList>> {
+    let catalog = query_ctx.current_catalog();
+    let schemas = state
+        .catalog_manager
+        .schema_names(catalog)
+        .await
+        .context(CatalogErrorSnafu)?;
+    let mut databases = Vec::with_capacity(schemas.len());
+    for schema in schemas {
+        if state
+            .can_access_database(&query_ctx, catalog, &schema)
+            .await?
+        {
+            databases.push(schema);
+        }
+    }
+
+    Ok(Json(DatabaseList { databases }))
+}
+
+/// Lists the tables of a database.
+///
+/// The database in the path is resolved and authorized into the query context.
+#[axum_macros::debug_handler]
+pub async fn list_tables(
+    State(state): State<TableApiState>,
+    Path(db): Path<String>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+) -> Result<Json<TableList>> {
+    let catalog = query_ctx.current_catalog();
+    let schema = query_ctx.current_schema();
+    ensure_database_exists(&state.catalog_manager, catalog, schema).await?;
+    state
+        .ensure_select_privilege(&query_ctx, GrantObject::schema(catalog, schema))
+        .await?;
+
+    let tables = state
+        .catalog_manager
+        .tables(catalog, schema)
+        .await
+        .map_ok(|table| {
+            let table_info = table.table_info();
+            TableSummary {
+                name: table_info.name.clone(),
+                table_id: table_info.ident.table_id,
+                engine: table_info.meta.engine.clone(),
+                table_type: table_info.table_type.to_string(),
+            }
+        })
+        .try_collect::<Vec<_>>()
+        .await
+        .context(CatalogErrorSnafu)?;
+
+    Ok(Json(TableList {
+        database: db,
+        tables,
+    }))
+}
+
+/// Returns the schema of a table.
+#[axum_macros::debug_handler]
+pub async fn table_schema(
+    State(state): State<TableApiState>,
+    Path((db, table_name)): Path<(String, String)>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+) -> Result<Json<TableSchema>> {
+    let catalog = query_ctx.current_catalog();
+    let schema = query_ctx.current_schema();
+    ensure_database_exists(&state.catalog_manager, catalog, schema).await?;
+    state
+        .ensure_select_privilege(&query_ctx, GrantObject::table(catalog, schema, &table_name))
+        .await?;
+
+    let table = state
+        .catalog_manager
+        .table(catalog, schema, &table_name)
+        .await
+        .context(CatalogErrorSnafu)?
+        .with_context(|| TableNotFoundSnafu {
+            table: format!("{db}.{table_name}"),
+        })?;
+    let table_info = table.table_info();
+    let partitions = table_partitions(&state.catalog_manager, &table_info).await?;
+
+    Ok(Json(new_table_schema(db, &table_info, partitions)))
+}
+
+pub(crate) fn table_schema_docs(op: TransformOperation) -> TransformOperation {
+    op.description("Returns the schema, options, partitions and indexes of a table.")
+        .response::<200, Json<TableSchema>>()
+}
+
+async fn ensure_database_exists(
+    catalog_manager: &CatalogManagerRef,
+    catalog: &str,
+    schema: &str,
+) -> Result<()> {
+    let exists = catalog_manager
+        .schema_exists(catalog, schema)
+        .await
+        .context(CatalogErrorSnafu)?;
+    ensure!(
+        exists,
+        DatabaseNotFoundSnafu {
+            catalog: catalog.to_string(),
+            schema: schema.to_string(),
+        }
+    );
+    Ok(())
+}
+
+async fn table_partitions(
+    catalog_manager: &CatalogManagerRef,
+    table_info: &TableInfo,
+) -> Result<Vec<PartitionInfo>> {
+    let table_id = table_info.ident.table_id;
+    let Some(catalog_manager) = catalog_manager
+        .as_any()
+        .downcast_ref::<KvBackendCatalogManager>()
+    else {
+        return Ok(vec![]);
+    };
+
+    let partitions = catalog_manager
+        .partition_manager()
+        .find_table_partitions(table_id)
+        .await
+        .context(catalog::error::FindPartitionsSnafu)
+        .context(CatalogErrorSnafu)?;
+
+    Ok(partitions
+        .into_iter()
+        .map(|partition| PartitionInfo {
+            region_id: partition.id.as_u64(),
+            expression: (!partition.partition.partition_columns().is_empty())
+                .then(|| partition.partition.to_string()),
+        })
+        .collect())
+}
+
+/// Returns indices of columns the inverted index ignores.
+///
+/// The option holds column ids, which are the positions of columns unless columns
+/// are added to or dropped from the table. Ids can't be resolved after that, so no
+/// column is ignored.
+fn inverted_index_ignored_columns(meta: &TableMeta) -> HashSet<usize> {
+    let Some(column_ids) = meta
+        .options
+        .extra_options
+        .get(INVERTED_INDEX_IGNORE_COLUMN_IDS_KEY)
+    else {
+        return HashSet::new();
+    };
+    if meta.next_column_id as usize != meta.schema.num_columns() {
+        return HashSet::new();
+    }
+
+    column_ids
+        .split(',')
+        .filter_map(|id| id.trim().parse().ok())
+        .collect()
+}
+
+fn new_table_schema(
+    db: String,
+    table_info: &TableInfo,
+    partitions: Vec<PartitionInfo>,
+) -> TableSchema {
+    let meta = &table_info.meta;
+    let column_schemas = meta.schema.column_schemas();
+
+    let columns = column_schemas
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            let semantic_type = if column.is_time_index() {
+                SEMANTIC_TYPE_TIME_INDEX
+            } else if meta.primary_key_indices.contains(&idx) {
+                SEMANTIC_TYPE_PRIMARY_KEY
+            } else {
+                SEMANTIC_TYPE_FIELD
+            };
+            let data_type = statements::concrete_data_type_to_sql_data_type(&column.data_type)
+                .map(|dt| dt.to_string().to_lowercase())
+                .unwrap_or_else(|_| column.data_type.name());
+
+            ColumnInfo {
+                name: column.name.clone(),
+                data_type,
+                semantic_type: semantic_type.to_string(),
+                nullable: column.is_nullable(),
+                default: column.default_constraint().map(|c| c.to_string()),
+                comment: column.column_comment().cloned(),
+            }
+        })
+        .collect();
+
+    let mut options = BTreeMap::new();
+    if let Some(write_buffer_size) = meta.options.write_buffer_size {
+        let _ = options.insert(
+            "write_buffer_size".to_string(),
+            write_buffer_size.to_string(),
+        );
+    }
+    if let Some(ttl) = meta.options.ttl {
+        let _ = options.insert(
+            "ttl".to_string(),
+            humantime_serde::re::humantime::format_duration(ttl).to_string(),
+        );
+    }
+    options.extend(
+        meta.options
+            .extra_options
+            .iter()
+            .filter(|(k, _)| k.as_str() != FILE_TABLE_META_KEY)
+            .map(|(k, v)| (k.clone(), v.clone())),
+    );
+
+    let column_names = |indices: &[usize]| {
+        indices
+            .iter()
+            .map(|idx| column_schemas[*idx].name.clone())
+            .collect::<Vec<_>>()
+    };
+    let mut indexes = Vec::with_capacity(2);
+    if !meta.primary_key_indices.is_empty() {
+        indexes.push(IndexInfo {
+            name: PRIMARY_KEY_INDEX.to_string(),
+            columns: column_names(&meta.primary_key_indices),
+        });
+    }
+    if let Some(time_index) = meta.schema.timestamp_column() {
+        indexes.push(IndexInfo {
+            name: TIME_INDEX.to_string(),
+            columns: vec![time_index.name.clone()],
+        });
+    }
+    let ignored = inverted_index_ignored_columns(meta);
+    let inverted_columns = meta
+        .primary_key_indices
+        .iter()
+        .copied()
+        .filter(|idx| !ignored.contains(idx))
+        .collect::<Vec<_>>();
+    if !inverted_columns.is_empty() {
+        indexes.push(IndexInfo {
+            name: INVERTED_INDEX.to_string(),
+            columns: column_names(&inverted_columns),
+        });
+    }
+
+    TableSchema {
+        database: db,
+        name: table_info.name.clone(),
+        table_id: table_info.ident.table_id,
+        engine: meta.engine.clone(),
+        table_type: table_info.table_type.to_string(),
+        created_on: meta.created_on.to_rfc3339(),
+        columns,
+        options,
+        partition_columns: column_names(&meta.partition_key_indices),
+        partitions,
+        indexes,
+    }
+}
@@ -36,6 +36,8 @@ pub mod mysql;
 pub mod opentsdb;
 pub mod otlp;
 pub mod postgres;
+mod prom_chunk;
+pub mod prom_relabel;
 mod prom_row_builder;
 pub mod prom_store;
 pub mod prometheus_handler;
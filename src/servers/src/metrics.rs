@@ -42,6 +42,9 @@ pub(crate) const METRIC_MYSQL_TEXTQUERY: &str = "textquery";
 pub(crate) const METRIC_POSTGRES_SUBPROTOCOL_LABEL: &str = "subprotocol";
 pub(crate) const METRIC_POSTGRES_SIMPLE_QUERY: &str = "simple";
 pub(crate) const METRIC_POSTGRES_EXTENDED_QUERY: &str = "extended";
+pub(crate) const METRIC_POSTGRES_PLAN_CACHE_RESULT_LABEL: &str = "result";
+pub(crate) const METRIC_POSTGRES_PLAN_CACHE_HIT: &str = "hit";
+pub(crate) const METRIC_POSTGRES_PLAN_CACHE_MISS: &str = "miss";
 pub(crate) const METRIC_METHOD_LABEL: &str = "method";
 pub(crate) const METRIC_PATH_LABEL: &str = "path";
 
@@ -53,6 +56,12 @@ lazy_static! {
     )
     .unwrap();
     /// Http SQL query duration per database.
+    /// Query cursors opened through the HTTP API and not closed yet.
+    pub static ref METRIC_HTTP_OPEN_CURSORS: IntGauge = register_int_gauge!(
+        "greptime_servers_http_open_cursors",
+        "servers http open cursors"
+    )
+    .unwrap();
     pub static ref METRIC_HTTP_SQL_ELAPSED: HistogramVec = register_histogram_vec!(
         "greptime_servers_http_sql_elapsed",
         "servers http sql elapsed",
@@ -109,6 +118,12 @@ lazy_static! {
         "frontend prometheus remote write samples"
     )
     .unwrap();
+    /// The series count dropped by relabel rules of Prometheus remote write.
+    pub static ref PROM_STORE_RELABEL_DROPPED_SERIES: IntCounter = register_int_counter!(
+        "greptime_servers_prometheus_relabel_dropped_series",
+        "frontend prometheus remote write series dropped by relabel rules"
+    )
+    .unwrap();
     /// Http prometheus read duration per database.
     pub static ref METRIC_HTTP_PROM_STORE_READ_ELAPSED: HistogramVec = register_histogram_vec!(
         "greptime_servers_http_prometheus_read_elapsed",
@@ -130,6 +145,13 @@ lazy_static! {
             &[METRIC_DB_LABEL]
         )
         .unwrap();
+    pub static ref METRIC_HTTP_OPENTELEMETRY_LOGS_ELAPSED: HistogramVec =
+        register_histogram_vec!(
+            "greptime_servers_http_otlp_logs_elapsed",
+            "servers http otlp logs elapsed",
+            &[METRIC_DB_LABEL]
+        )
+        .unwrap();
     pub static ref METRIC_TCP_OPENTSDB_LINE_WRITE_ELAPSED: Histogram = register_histogram!(
         "greptime_servers_opentsdb_line_write_elapsed",
         "servers opentsdb line write elapsed"
@@ -168,6 +190,12 @@ lazy_static! {
         "servers postgres prepared count"
     )
     .unwrap();
+    pub static ref METRIC_POSTGRES_PLAN_CACHE: IntCounterVec = register_int_counter_vec!(
+        "greptime_servers_postgres_plan_cache_count",
+        "servers postgres prepared statement plan cache lookups",
+        &[METRIC_POSTGRES_PLAN_CACHE_RESULT_LABEL]
+    )
+    .unwrap();
     pub static ref METRIC_SERVER_GRPC_DB_REQUEST_TIMER: HistogramVec = register_histogram_vec!(
         "greptime_servers_grpc_db_request_elapsed",
         "servers grpc db request elapsed",
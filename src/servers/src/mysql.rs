@@ -12,6 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! MySQL protocol server.
+//!
+//! Supported beyond plain queries and prepared statements:
+//! - Multi-statement queries, with one result set for each statement.
+//! - `COM_STMT_SEND_LONG_DATA` for string and blob parameters.
+//! - Rows are written as record batches arrive, so clients that read rows as they
+//!   come, like the MySQL CLI with `--quick`, don't wait for the whole result.
+//!
+//! Out of scope: server-side cursors (`COM_STMT_EXECUTE` cursor flags and
+//! `COM_STMT_FETCH`). opensrv-mysql doesn't pass either to [handler::MysqlInstanceShim],
+//! so the server never opens a cursor and a client's fetch size has no effect. Such
+//! clients, e.g. JDBC with `useCursorFetch`, read all rows from the execute response.
+
 mod federated;
 pub mod handler;
 mod helper;
@@ -53,6 +53,9 @@ use crate::mysql::writer::create_mysql_column;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::SqlPlan;
 
+const MYSQL_NATIVE_PASSWORD: &str = "mysql_native_password";
+const MYSQL_CLEAR_PASSWORD: &str = "mysql_clear_password";
+
 // An intermediate shim for executing MySQL queries.
 pub struct MysqlInstanceShim {
     query_handler: ServerSqlQueryHandlerRef,
@@ -61,6 +64,8 @@ pub struct MysqlInstanceShim {
     user_provider: Option<UserProviderRef>,
     prepared_stmts: Arc<RwLock<HashMap<u32, SqlPlan>>>,
     prepared_stmts_counter: AtomicU32,
+    /// Whether the connection is over TLS, so plain text passwords can be sent.
+    secure: bool,
 }
 
 impl MysqlInstanceShim {
@@ -93,9 +98,24 @@ impl MysqlInstanceShim {
             user_provider,
             prepared_stmts: Default::default(),
             prepared_stmts_counter: AtomicU32::new(1),
+            secure: false,
         }
     }
 
+    pub fn set_secure(&mut self, secure: bool) {
+        self.secure = secure;
+    }
+
+    /// Whether to ask the client for its plain text password, which is only done over
+    /// TLS for user providers that can't verify hashed passwords.
+    fn use_clear_password(&self) -> bool {
+        self.secure
+            && self
+                .user_provider
+                .as_ref()
+                .is_some_and(|provider| provider.plain_text_only())
+    }
+
     #[tracing::instrument(skip_all, name = "mysql::do_query")]
     async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
         if let Some(output) =
@@ -157,6 +177,15 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         self.salt
     }
 
+    async fn auth_plugin_for_username<'a, 'user>(&'a self, _user: &'user [u8]) -> &'a str {
+        // The client is asked to switch to the plugin if it starts with another one.
+        if self.use_clear_password() {
+            MYSQL_CLEAR_PASSWORD
+        } else {
+            MYSQL_NATIVE_PASSWORD
+        }
+    }
+
     async fn authenticate(
         &self,
         auth_plugin: &str,
@@ -177,7 +206,12 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
             let user_id = Identity::UserId(&username, addr.as_deref());
 
             let password = match auth_plugin {
-                "mysql_native_password" => Password::MysqlNativePassword(auth_data, salt),
+                MYSQL_NATIVE_PASSWORD => Password::MysqlNativePassword(auth_data, salt),
+                // Never accepts plain text passwords that were sent in the clear.
+                MYSQL_CLEAR_PASSWORD if self.secure => {
+                    let auth_data = auth_data.strip_suffix(b"\0").unwrap_or(auth_data);
+                    Password::PlainText(String::from_utf8_lossy(auth_data).to_string().into())
+                }
                 other => {
                     error!("Unsupported mysql auth plugin: {}", other);
                     return false;
@@ -270,6 +304,8 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         return Ok(());
     }
 
+    /// Executes a prepared statement and writes the whole result set, as server-side
+    /// cursors are out of scope (see [crate::mysql]).
     async fn on_execute<'a>(
         &'a mut self,
         stmt_id: u32,
@@ -429,7 +465,7 @@ fn replace_params(params: Vec<ParamValue>, query: String) -> String {
             ValueInner::UInt(u) => u.to_string(),
             ValueInner::Double(u) => u.to_string(),
             ValueInner::NULL => "NULL".to_string(),
-            ValueInner::Bytes(b) => format!("'{}'", &String::from_utf8_lossy(b)),
+            ValueInner::Bytes(b) => bytes_to_sql_literal(b),
             ValueInner::Date(_) => NaiveDate::from(param.value).to_string(),
             ValueInner::Datetime(_) => NaiveDateTime::from(param.value).to_string(),
             ValueInner::Time(_) => format_duration(Duration::from(param.value)),
@@ -440,6 +476,18 @@ fn replace_params(params: Vec<ParamValue>, query: String) -> String {
     query
 }
 
+/// Quotes a string or binary parameter, which may be sent in chunks by
+/// `COM_STMT_SEND_LONG_DATA`, so it can be placed into the query.
+fn bytes_to_sql_literal(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''")),
+        Err(_) => {
+            let hex = bytes.iter().map(|b| format!("{b:02X}")).collect::<String>();
+            format!("X'{hex}'")
+        }
+    }
+}
+
 fn format_duration(duration: Duration) -> String {
     let seconds = duration.as_secs() % 60;
     let minutes = (duration.as_secs() / 60) % 60;
@@ -202,6 +202,7 @@ impl MysqlServer {
 
         match spawn_config.tls() {
             Some(tls_conf) if client_tls => {
+                shim.set_secure(true);
                 secure_run_with_options(shim, w, ops, tls_conf, init_params).await
             }
             _ => plain_run_with_options(shim, w, ops, init_params).await,
@@ -20,6 +20,7 @@ use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
 use common_telemetry::{debug, error};
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::SchemaRef;
+use datatypes::value::ListValue;
 use futures::StreamExt;
 use opensrv_mysql::{
     Column, ColumnFlags, ColumnType, ErrorKind, OkResponse, QueryResultWriter, RowWriter,
@@ -28,32 +29,54 @@ use session::context::QueryContextRef;
 use snafu::prelude::*;
 use tokio::io::AsyncWrite;
 
-use crate::error::{self, Error, Result};
+use crate::error::{self, Result};
 use crate::metrics::*;
 
-/// Try to write multiple output to the writer if possible.
+/// Writes outputs to the writer, one result set for each output.
+///
+/// Writing stops at the first failed output as an error packet ends the response.
 pub async fn write_output<W: AsyncWrite + Send + Sync + Unpin>(
     w: QueryResultWriter<'_, W>,
     query_context: QueryContextRef,
     outputs: Vec<Result<Output>>,
 ) -> Result<()> {
-    let mut writer = Some(MysqlResultWriter::new(w, query_context.clone()));
-    for output in outputs {
-        let result_writer = writer.take().context(error::InternalSnafu {
-            err_msg: "Sending multiple result set is unsupported",
-        })?;
-        writer = result_writer.try_write_one(output).await?;
-    }
+    // Rows of a result set are written with a borrow of its column definitions, which has
+    // to outlive the writer, so all column definitions are created before writing.
+    let (result_sets, column_defs): (Vec<_>, Vec<_>) =
+        outputs.into_iter().map(ResultSet::new).unzip();
 
-    if let Some(result_writer) = writer {
-        result_writer.finish().await?;
+    let mut writer = MysqlResultWriter::new(w, query_context);
+    for (result_set, column_def) in result_sets.into_iter().zip(column_defs.iter()) {
+        match writer.write_one(result_set, column_def).await? {
+            Some(next_writer) => writer = next_writer,
+            None => return Ok(()),
+        }
     }
-    Ok(())
+    writer.finish().await
 }
 
-struct QueryResult {
-    schema: SchemaRef,
-    stream: SendableRecordBatchStream,
+enum ResultSet {
+    Query(SendableRecordBatchStream),
+    AffectedRows(usize),
+    Error(error::Error),
+}
+
+impl ResultSet {
+    /// Creates the result set of `output` and its column definitions.
+    fn new(output: Result<Output>) -> (ResultSet, Vec<Column>) {
+        let stream = match output {
+            Ok(output) => match output.data {
+                OutputData::Stream(stream) => stream,
+                OutputData::RecordBatches(recordbatches) => recordbatches.as_stream(),
+                OutputData::AffectedRows(rows) => return (ResultSet::AffectedRows(rows), vec![]),
+            },
+            Err(error) => return (ResultSet::Error(error), vec![]),
+        };
+        match create_mysql_column_def(&stream.schema()) {
+            Ok(column_def) => (ResultSet::Query(stream), column_def),
+            Err(error) => (ResultSet::Error(error), vec![]),
+        }
+    }
 }
 
 pub struct MysqlResultWriter<'a, W: AsyncWrite + Unpin> {
@@ -72,43 +95,31 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
         }
     }
 
-    /// Try to write one result set. If there are more than one result set, return `Some`.
-    pub async fn try_write_one(
+    /// Writes one result set. Returns the writer for the next result set, or `None` if
+    /// the response has ended with an error.
+    async fn write_one(
         self,
-        output: Result<Output>,
+        result_set: ResultSet,
+        column_def: &'a [Column],
     ) -> Result<Option<MysqlResultWriter<'a, W>>> {
-        // We don't support sending multiple query result because the RowWriter's lifetime is bound to
-        // a local variable.
-        match output {
-            Ok(output) => match output.data {
-                OutputData::Stream(stream) => {
-                    let query_result = QueryResult {
-                        schema: stream.schema(),
-                        stream,
-                    };
-                    Self::write_query_result(query_result, self.writer, self.query_context).await?;
-                }
-                OutputData::RecordBatches(recordbatches) => {
-                    let query_result = QueryResult {
-                        schema: recordbatches.schema(),
-                        stream: recordbatches.as_stream(),
-                    };
-                    Self::write_query_result(query_result, self.writer, self.query_context).await?;
-                }
-                OutputData::AffectedRows(rows) => {
-                    let next_writer = Self::write_affected_rows(self.writer, rows).await?;
-                    return Ok(Some(MysqlResultWriter::new(
-                        next_writer,
-                        self.query_context,
-                    )));
-                }
-            },
-            Err(error) => Self::write_query_error(error, self.writer).await?,
-        }
-        Ok(None)
+        let next_writer = match result_set {
+            ResultSet::Query(stream) => {
+                Self::write_query_result(stream, column_def, self.writer, &self.query_context)
+                    .await?
+            }
+            ResultSet::AffectedRows(rows) => Some(
+                Self::write_affected_rows(self.writer, rows, &self.query_context.warnings())
+                    .await?,
+            ),
+            ResultSet::Error(error) => {
+                Self::write_query_error(error, self.writer).await?;
+                None
+            }
+        };
+        Ok(next_writer.map(|writer| MysqlResultWriter::new(writer, self.query_context)))
     }
 
-    /// Indicate no more result set to write. No need to call this if there is only one result set.
+    /// Indicate no more result set to write.
     pub async fn finish(self) -> Result<()> {
         self.writer.no_more_results().await?;
         Ok(())
@@ -117,10 +128,13 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     async fn write_affected_rows(
         w: QueryResultWriter<'a, W>,
         rows: usize,
+        warnings: &[String],
     ) -> Result<QueryResultWriter<'a, W>> {
         let next_writer = w
             .complete_one(OkResponse {
                 affected_rows: rows as u64,
+                warnings: warnings.len() as u16,
+                info: warnings.join("; "),
                 ..Default::default()
             })
             .await?;
@@ -128,45 +142,34 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     }
 
     async fn write_query_result(
-        mut query_result: QueryResult,
+        mut stream: SendableRecordBatchStream,
+        column_def: &'a [Column],
         writer: QueryResultWriter<'a, W>,
-        query_context: QueryContextRef,
-    ) -> Result<()> {
-        match create_mysql_column_def(&query_result.schema) {
-            Ok(column_def) => {
-                // The RowWriter's lifetime is bound to `column_def` thus we can't use finish_one()
-                // to return a new QueryResultWriter.
-                let mut row_writer = writer.start(&column_def).await?;
-                while let Some(record_batch) = query_result.stream.next().await {
-                    match record_batch {
-                        Ok(record_batch) => {
-                            Self::write_recordbatch(
-                                &mut row_writer,
-                                &record_batch,
-                                query_context.clone(),
-                            )
-                            .await?
-                        }
-                        Err(e) => {
-                            if e.status_code().should_log_error() {
-                                error!(e; "Failed to handle mysql query");
-                            } else {
-                                debug!("Failed to handle mysql query, error: {e:?}");
-                            }
-                            let err = e.output_msg();
-                            row_writer
-                                .finish_error(ErrorKind::ER_INTERNAL_ERROR, &err.as_bytes())
-                                .await?;
-
-                            return Ok(());
-                        }
+        query_context: &QueryContextRef,
+    ) -> Result<Option<QueryResultWriter<'a, W>>> {
+        let mut row_writer = writer.start(column_def).await?;
+        while let Some(record_batch) = stream.next().await {
+            match record_batch {
+                Ok(record_batch) => {
+                    Self::write_recordbatch(&mut row_writer, &record_batch, query_context.clone())
+                        .await?
+                }
+                Err(e) => {
+                    if e.status_code().should_log_error() {
+                        error!(e; "Failed to handle mysql query");
+                    } else {
+                        debug!("Failed to handle mysql query, error: {e:?}");
                     }
+                    let err = e.output_msg();
+                    row_writer
+                        .finish_error(ErrorKind::ER_INTERNAL_ERROR, &err.as_bytes())
+                        .await?;
+
+                    return Ok(None);
                 }
-                row_writer.finish().await?;
-                Ok(())
             }
-            Err(error) => Self::write_query_error(error, writer).await,
         }
+        Ok(Some(row_writer.finish_one().await?))
     }
 
     async fn write_recordbatch(
@@ -201,14 +204,7 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                     )?,
                     Value::Interval(v) => row_writer.write_col(v.to_iso8601_string())?,
                     Value::Duration(v) => row_writer.write_col(v.to_std_duration())?,
-                    Value::List(_) => {
-                        return Err(Error::Internal {
-                            err_msg: format!(
-                                "cannot write value {:?} in mysql protocol: unimplemented",
-                                &value
-                            ),
-                        })
-                    }
+                    Value::List(v) => row_writer.write_col(list_to_string(&v))?,
                     Value::Time(v) => row_writer
                         .write_col(v.to_timezone_aware_string(Some(&query_context.timezone())))?,
                     Value::Decimal128(v) => row_writer.write_col(v.to_string())?,
@@ -255,14 +251,15 @@ pub(crate) fn create_mysql_column(
         }
         ConcreteDataType::Float32(_) => Ok(ColumnType::MYSQL_TYPE_FLOAT),
         ConcreteDataType::Float64(_) => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
-        ConcreteDataType::Binary(_) | ConcreteDataType::String(_) => {
-            Ok(ColumnType::MYSQL_TYPE_VARCHAR)
-        }
+        ConcreteDataType::Binary(_) => Ok(ColumnType::MYSQL_TYPE_BLOB),
+        ConcreteDataType::String(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
         ConcreteDataType::Timestamp(_) => Ok(ColumnType::MYSQL_TYPE_TIMESTAMP),
         ConcreteDataType::Time(_) => Ok(ColumnType::MYSQL_TYPE_TIME),
         ConcreteDataType::Date(_) => Ok(ColumnType::MYSQL_TYPE_DATE),
         ConcreteDataType::DateTime(_) => Ok(ColumnType::MYSQL_TYPE_DATETIME),
-        ConcreteDataType::Interval(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        ConcreteDataType::Interval(_) | ConcreteDataType::List(_) => {
+            Ok(ColumnType::MYSQL_TYPE_VARCHAR)
+        }
         ConcreteDataType::Duration(_) => Ok(ColumnType::MYSQL_TYPE_TIME),
         ConcreteDataType::Decimal128(_) => Ok(ColumnType::MYSQL_TYPE_DECIMAL),
         _ => error::UnsupportedDataTypeSnafu {
@@ -277,6 +274,10 @@ pub(crate) fn create_mysql_column(
         | ConcreteDataType::UInt8(_)
         | ConcreteDataType::UInt32(_)
         | ConcreteDataType::UInt64(_) => colflags |= ColumnFlags::UNSIGNED_FLAG,
+        // Clients tell binary columns from text columns by the flags.
+        ConcreteDataType::Binary(_) => {
+            colflags |= ColumnFlags::BLOB_FLAG | ColumnFlags::BINARY_FLAG
+        }
         _ => {}
     };
     column_type.map(|column_type| Column {
@@ -290,6 +291,22 @@ pub(crate) fn create_mysql_column(
     })
 }
 
+/// Formats a list as text like `[1, NULL, 3]`, since MySQL doesn't have an array type.
+/// Returns `None` for a `NULL` list.
+fn list_to_string(list: &ListValue) -> Option<String> {
+    let items = list.items().as_ref()?;
+    let items = items
+        .iter()
+        .map(|item| match item {
+            Value::Null => "NULL".to_string(),
+            Value::List(list) => list_to_string(list).unwrap_or_else(|| "NULL".to_string()),
+            item => item.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!("[{items}]"))
+}
+
 /// Creates MySQL columns definition from our column schema.
 pub fn create_mysql_column_def(schema: &SchemaRef) -> Result<Vec<Column>> {
     schema
@@ -298,3 +315,27 @@ pub fn create_mysql_column_def(schema: &SchemaRef) -> Result<Vec<Column>> {
         .map(|column_schema| create_mysql_column(&column_schema.data_type, &column_schema.name))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_to_string() {
+        let list = ListValue::new(
+            Some(Box::new(vec![
+                Value::Int32(1),
+                Value::Null,
+                Value::Int32(3),
+            ])),
+            ConcreteDataType::int32_datatype(),
+        );
+        assert_eq!(Some("[1, NULL, 3]".to_string()), list_to_string(&list));
+
+        let empty = ListValue::new(Some(Box::default()), ConcreteDataType::int32_datatype());
+        assert_eq!(Some("[]".to_string()), list_to_string(&empty));
+
+        let null = ListValue::new(None, ConcreteDataType::int32_datatype());
+        assert_eq!(None, list_to_string(&null));
+    }
+}
@@ -15,7 +15,7 @@
 //! Modified from Tokio's mini-redis example.
 
 use common_error::ext::ErrorExt;
-use session::context::QueryContextBuilder;
+use session::context::{Channel, QueryContextBuilder};
 use tokio::io::{AsyncRead, AsyncWrite};
 
 use crate::error::Result;
@@ -62,7 +62,9 @@ impl<S: AsyncWrite + AsyncRead + Unpin> Handler<S> {
 
     pub(crate) async fn run(&mut self) -> Result<()> {
         // TODO(shuiyisong): figure out how to auth in tcp connection.
-        let ctx = QueryContextBuilder::default().build();
+        let ctx = QueryContextBuilder::default()
+            .channel(Channel::Opentsdb)
+            .build();
         while !self.shutdown.is_shutdown() {
             // While reading a request, also listen for the shutdown signal.
             let maybe_line = tokio::select! {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod logs;
 pub mod metrics;
 pub mod plugin;
 pub mod trace;
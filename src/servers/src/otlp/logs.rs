@@ -0,0 +1,263 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::value::ValueData;
+use api::v1::{ColumnDataType, RowInsertRequests};
+use common_query::prelude::GREPTIME_TIMESTAMP;
+use hyper::HeaderMap;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::common::v1::InstrumentationScope;
+use opentelemetry_proto::tonic::logs::v1::LogRecord;
+use snafu::OptionExt;
+
+use super::trace::attributes::{Attributes, OtlpAnyValue};
+use super::trace::span::bytes_to_hex_string;
+use crate::error::{InvalidParameterSnafu, Result};
+use crate::http::header::GREPTIME_LOG_TABLE_NAME_HEADER_NAME;
+use crate::row_writer::{self, MultiTableData, TableData};
+
+const APPROXIMATE_COLUMN_COUNT: usize = 12;
+pub const LOG_TABLE_NAME: &str = "opentelemetry_logs";
+
+/// Returns the table to write logs into, which is specified by header
+/// [GREPTIME_LOG_TABLE_NAME_HEADER_NAME] and defaults to [LOG_TABLE_NAME].
+pub fn table_name_from_headers(headers: &HeaderMap) -> Result<String> {
+    match headers.get(&GREPTIME_LOG_TABLE_NAME_HEADER_NAME) {
+        Some(value) => {
+            let table_name = value.to_str().ok().filter(|v| !v.is_empty());
+            table_name
+                .map(|v| v.to_string())
+                .with_context(|| InvalidParameterSnafu {
+                    reason: format!(
+                        "invalid value for header {}",
+                        GREPTIME_LOG_TABLE_NAME_HEADER_NAME.as_str()
+                    ),
+                })
+        }
+        None => Ok(LOG_TABLE_NAME.to_string()),
+    }
+}
+
+/// Convert OpenTelemetry logs to GreptimeDB row insert requests.
+/// All log records are written into table `table_name`.
+///
+/// Attributes are written as JSON strings, since there is no STRUCT column type.
+/// Query their fields with `json_get`, e.g.
+/// `json_get(log_attributes, '"http.status_code"')`.
+///
+/// See
+/// <https://github.com/open-telemetry/opentelemetry-proto/blob/main/opentelemetry/proto/logs/v1/logs.proto>
+/// for data structure of OTLP logs.
+///
+/// Returns `InsertRequests` and total number of rows to ingest
+pub fn to_grpc_insert_requests(
+    request: ExportLogsServiceRequest,
+    table_name: String,
+) -> Result<(RowInsertRequests, usize)> {
+    let num_records = request
+        .resource_logs
+        .iter()
+        .flat_map(|r| r.scope_logs.iter())
+        .map(|s| s.log_records.len())
+        .sum();
+
+    let mut multi_table_writer = MultiTableData::default();
+    let one_table_writer = multi_table_writer.get_or_default_table_data(
+        table_name,
+        APPROXIMATE_COLUMN_COUNT,
+        num_records,
+    );
+
+    for resource_logs in request.resource_logs {
+        let resource_attrs = Attributes::from(
+            resource_logs
+                .resource
+                .map(|r| r.attributes)
+                .unwrap_or_default(),
+        );
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_logs.scope.unwrap_or_default();
+            for log in scope_logs.log_records {
+                write_log_to_row(one_table_writer, &resource_attrs, &scope, log)?;
+            }
+        }
+    }
+
+    Ok(multi_table_writer.into_row_insert_requests())
+}
+
+fn write_log_to_row(
+    writer: &mut TableData,
+    resource_attrs: &Attributes,
+    scope: &InstrumentationScope,
+    log: LogRecord,
+) -> Result<()> {
+    let mut row = writer.alloc_one_row();
+
+    // use the observed time if the event time is unknown
+    let timestamp = if log.time_unix_nano != 0 {
+        log.time_unix_nano
+    } else {
+        log.observed_time_unix_nano
+    };
+    let body = log
+        .body
+        .as_ref()
+        .map(|body| OtlpAnyValue::from(body).to_string())
+        .unwrap_or_default();
+
+    let str_fields_iter = vec![
+        ("severity_text", log.severity_text),
+        ("body", body),
+        ("trace_id", bytes_to_hex_string(&log.trace_id)),
+        ("span_id", bytes_to_hex_string(&log.span_id)),
+        (
+            "log_attributes",
+            Attributes::from(log.attributes).to_string(),
+        ),
+        ("resource_attributes", resource_attrs.to_string()),
+        ("scope_name", scope.name.clone()),
+        ("scope_version", scope.version.clone()),
+        (
+            "scope_attributes",
+            Attributes::from(scope.attributes.clone()).to_string(),
+        ),
+    ]
+    .into_iter()
+    .map(|(col, val)| {
+        (
+            col.to_string(),
+            ColumnDataType::String,
+            ValueData::StringValue(val),
+        )
+    });
+    row_writer::write_fields(writer, str_fields_iter, &mut row)?;
+    row_writer::write_fields(
+        writer,
+        [
+            (
+                "severity_number".to_string(),
+                ColumnDataType::Int32,
+                ValueData::I32Value(log.severity_number),
+            ),
+            (
+                "observed_timestamp".to_string(),
+                ColumnDataType::TimestampNanosecond,
+                ValueData::TimestampNanosecondValue(log.observed_time_unix_nano as i64),
+            ),
+        ]
+        .into_iter(),
+        &mut row,
+    )?;
+    row_writer::write_ts_nanos(writer, GREPTIME_TIMESTAMP, Some(timestamp as i64), &mut row)?;
+
+    writer.add_row(row);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_proto::tonic::common::v1::any_value::Value as Val;
+    use opentelemetry_proto::tonic::common::v1::{AnyValue, KeyValue};
+    use opentelemetry_proto::tonic::logs::v1::{ResourceLogs, ScopeLogs};
+
+    use super::*;
+
+    fn string_value(s: &str) -> AnyValue {
+        AnyValue {
+            value: Some(Val::StringValue(s.to_string())),
+        }
+    }
+
+    #[test]
+    fn test_table_name_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(LOG_TABLE_NAME, table_name_from_headers(&headers).unwrap());
+
+        let _ = headers.insert(
+            &GREPTIME_LOG_TABLE_NAME_HEADER_NAME,
+            "app_logs".parse().unwrap(),
+        );
+        assert_eq!("app_logs", table_name_from_headers(&headers).unwrap());
+
+        let _ = headers.insert(&GREPTIME_LOG_TABLE_NAME_HEADER_NAME, "".parse().unwrap());
+        assert!(table_name_from_headers(&headers).is_err());
+    }
+
+    #[test]
+    fn test_logs_to_insert_requests() {
+        let log = LogRecord {
+            time_unix_nano: 0,
+            observed_time_unix_nano: 1_000_000_123,
+            severity_number: 9,
+            severity_text: "INFO".to_string(),
+            body: Some(string_value("hello")),
+            attributes: vec![KeyValue {
+                key: "host".to_string(),
+                value: Some(string_value("h1")),
+            }],
+            trace_id: vec![0xab, 0xcd],
+            ..Default::default()
+        };
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                scope_logs: vec![ScopeLogs {
+                    log_records: vec![log],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        let (requests, rows) =
+            to_grpc_insert_requests(request, LOG_TABLE_NAME.to_string()).unwrap();
+        assert_eq!(1, rows);
+        assert_eq!(1, requests.inserts.len());
+        let insert = &requests.inserts[0];
+        assert_eq!(LOG_TABLE_NAME, insert.table_name);
+
+        let rows = insert.rows.as_ref().unwrap();
+        let value_of = |name: &str| {
+            let index = rows
+                .schema
+                .iter()
+                .position(|c| c.column_name == name)
+                .unwrap();
+            rows.rows[0].values[index].value_data.clone().unwrap()
+        };
+        assert_eq!(
+            ValueData::StringValue("INFO".to_string()),
+            value_of("severity_text")
+        );
+        assert_eq!(
+            ValueData::StringValue("hello".to_string()),
+            value_of("body")
+        );
+        assert_eq!(
+            ValueData::StringValue("abcd".to_string()),
+            value_of("trace_id")
+        );
+        assert_eq!(ValueData::I32Value(9), value_of("severity_number"));
+        assert_eq!(
+            ValueData::StringValue(r#"{"host":"h1"}"#.to_string()),
+            value_of("log_attributes")
+        );
+        // falls back to the observed time, keeping the nanoseconds
+        assert_eq!(
+            ValueData::TimestampNanosecondValue(1_000_000_123),
+            value_of(GREPTIME_TIMESTAMP)
+        );
+    }
+}
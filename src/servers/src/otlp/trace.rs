@@ -121,8 +121,18 @@ pub fn write_span_to_row(writer: &mut TableData, span: TraceSpan) -> Result<()>
             )
         });
 
+        let duration_field = std::iter::once((
+            "duration_nano".to_string(),
+            ColumnDataType::Uint64,
+            ValueData::U64Value(
+                span.end_in_nanosecond
+                    .saturating_sub(span.start_in_nanosecond),
+            ),
+        ));
+
         row_writer::write_fields(writer, str_fields_iter, &mut row)?;
         row_writer::write_fields(writer, time_fields_iter, &mut row)?;
+        row_writer::write_fields(writer, duration_field, &mut row)?;
         row_writer::write_fields(writer, span.uplifted_span_attributes.into_iter(), &mut row)?;
     }
 
@@ -144,3 +154,58 @@ pub fn write_span_to_row(writer: &mut TableData, span: TraceSpan) -> Result<()>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_proto::tonic::trace::v1::{ResourceSpans, ScopeSpans, Span};
+
+    use super::*;
+
+    #[test]
+    fn test_spans_to_insert_requests() {
+        let span = Span {
+            trace_id: vec![0xab, 0xcd],
+            span_id: vec![0x01],
+            parent_span_id: vec![0x02],
+            name: "query".to_string(),
+            start_time_unix_nano: 1_000_000_000,
+            end_time_unix_nano: 1_002_500_000,
+            ..Default::default()
+        };
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                scope_spans: vec![ScopeSpans {
+                    spans: vec![span],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        let (requests, rows) =
+            to_grpc_insert_requests(TRACE_TABLE_NAME.to_string(), parse(request)).unwrap();
+        assert_eq!(1, rows);
+        let insert = &requests.inserts[0];
+        assert_eq!(TRACE_TABLE_NAME, insert.table_name);
+
+        let rows = insert.rows.as_ref().unwrap();
+        let value_of = |name: &str| {
+            let index = rows
+                .schema
+                .iter()
+                .position(|c| c.column_name == name)
+                .unwrap();
+            rows.rows[0].values[index].value_data.clone().unwrap()
+        };
+        assert_eq!(
+            ValueData::StringValue("abcd".to_string()),
+            value_of("trace_id")
+        );
+        assert_eq!(
+            ValueData::StringValue("02".to_string()),
+            value_of("parent_span_id")
+        );
+        assert_eq!(ValueData::U64Value(2_500_000), value_of("duration_nano"));
+        assert_eq!(ValueData::F64Value(2.5), value_of(GREPTIME_VALUE));
+    }
+}
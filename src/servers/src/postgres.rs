@@ -14,6 +14,7 @@
 
 mod auth_handler;
 mod handler;
+mod plan_cache;
 mod server;
 mod types;
 
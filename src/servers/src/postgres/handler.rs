@@ -12,33 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::Debug;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use common_meta::table_name::TableName;
 use common_query::{Output, OutputData};
 use common_recordbatch::error::Result as RecordBatchResult;
 use common_recordbatch::RecordBatch;
 use common_telemetry::tracing;
 use datatypes::schema::SchemaRef;
-use futures::{future, stream, Stream, StreamExt};
+use futures::{future, stream, Sink, SinkExt, Stream, StreamExt};
 use pgwire::api::portal::{Format, Portal};
 use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler};
 use pgwire::api::results::{
     DataRowEncoder, DescribePortalResponse, DescribeStatementResponse, QueryResponse, Response, Tag,
 };
 use pgwire::api::stmt::{QueryParser, StoredStatement};
-use pgwire::api::{ClientInfo, Type};
+use pgwire::api::store::PortalStore;
+use pgwire::api::{ClientInfo, ClientPortalStore, Type, DEFAULT_NAME};
 use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
+use pgwire::messages::extendedquery::{Parse, ParseComplete};
+use pgwire::messages::PgWireBackendMessage;
 use query::query_engine::DescribeResult;
 use session::context::QueryContextRef;
 use session::Session;
 use sql::dialect::PostgreSqlDialect;
 use sql::parser::{ParseOptions, ParserContext};
+use table::metadata::TableVersion;
 
+use super::plan_cache::{parse_deallocate, PlanCache, PlanCacheKey};
 use super::types::*;
 use super::PostgresServerHandler;
 use crate::error::Result;
+use crate::metrics::{
+    METRIC_POSTGRES_PLAN_CACHE, METRIC_POSTGRES_PLAN_CACHE_HIT, METRIC_POSTGRES_PLAN_CACHE_MISS,
+};
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::SqlPlan;
 
@@ -58,6 +68,17 @@ impl SimpleQueryHandler for PostgresServerHandler {
         let _timer = crate::metrics::METRIC_POSTGRES_QUERY_TIMER
             .with_label_values(&[crate::metrics::METRIC_POSTGRES_SIMPLE_QUERY, db.as_str()])
             .start_timer();
+
+        if let Some(name) = parse_deallocate(query) {
+            match name {
+                Some(name) => {
+                    let _ = self.query_parser.plan_cache.remove(&name);
+                }
+                None => self.query_parser.plan_cache.clear(),
+            }
+            return Ok(vec![Response::Execution(Tag::new("DEALLOCATE"))]);
+        }
+
         let outputs = self.query_handler.do_query(query, query_ctx.clone()).await;
         query_ctx.update_session(&self.session);
 
@@ -148,6 +169,7 @@ where
 pub struct DefaultQueryParser {
     query_handler: ServerSqlQueryHandlerRef,
     session: Arc<Session>,
+    plan_cache: PlanCache,
 }
 
 impl DefaultQueryParser {
@@ -155,17 +177,54 @@ impl DefaultQueryParser {
         DefaultQueryParser {
             query_handler,
             session,
+            plan_cache: PlanCache::default(),
         }
     }
-}
 
-#[async_trait]
-impl QueryParser for DefaultQueryParser {
-    type Statement = SqlPlan;
-
-    async fn parse_sql(&self, sql: &str, _types: &[Type]) -> PgWireResult<Self::Statement> {
+    /// Plans the prepared statement `name`, reusing its cached plan if the statement
+    /// is parsed again in the same way and the tables it reads are not altered.
+    async fn parse_prepared(&self, name: &str, sql: &str, types: &[Type]) -> PgWireResult<SqlPlan> {
         crate::metrics::METRIC_POSTGRES_PREPARED_COUNT.inc();
         let query_ctx = self.session.new_query_context();
+        let cache_key = PlanCacheKey::new(
+            sql,
+            types,
+            query_ctx.get_db_string(),
+            query_ctx.timezone().to_string(),
+        );
+        if let Some(cached) = self.plan_cache.get(name, &cache_key) {
+            if self.is_fresh(&cached.table_versions).await? {
+                METRIC_POSTGRES_PLAN_CACHE
+                    .with_label_values(&[METRIC_POSTGRES_PLAN_CACHE_HIT])
+                    .inc();
+                return Ok(cached.plan);
+            }
+        }
+        METRIC_POSTGRES_PLAN_CACHE
+            .with_label_values(&[METRIC_POSTGRES_PLAN_CACHE_MISS])
+            .inc();
+
+        let sql_plan = self.plan(sql, query_ctx).await?;
+        self.plan_cache.put(name, cache_key, sql_plan.clone());
+        Ok(sql_plan)
+    }
+
+    /// Returns true if none of the tables has been altered or dropped.
+    async fn is_fresh(&self, table_versions: &[(TableName, TableVersion)]) -> PgWireResult<bool> {
+        for (table, version) in table_versions {
+            let current = self
+                .query_handler
+                .table_version(&table.catalog_name, &table.schema_name, &table.table_name)
+                .await
+                .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+            if current != Some(*version) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn plan(&self, sql: &str, query_ctx: QueryContextRef) -> PgWireResult<SqlPlan> {
         let mut stmts =
             ParserContext::create_with_dialect(sql, &PostgreSqlDialect {}, ParseOptions::default())
                 .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
@@ -202,6 +261,15 @@ impl QueryParser for DefaultQueryParser {
     }
 }
 
+#[async_trait]
+impl QueryParser for DefaultQueryParser {
+    type Statement = SqlPlan;
+
+    async fn parse_sql(&self, sql: &str, types: &[Type]) -> PgWireResult<Self::Statement> {
+        self.parse_prepared(DEFAULT_NAME, sql, types).await
+    }
+}
+
 #[async_trait]
 impl ExtendedQueryHandler for PostgresServerHandler {
     type Statement = SqlPlan;
@@ -211,6 +279,37 @@ impl ExtendedQueryHandler for PostgresServerHandler {
         self.query_parser.clone()
     }
 
+    /// Parses the statement like the default implementation, but caches its plan by
+    /// the statement name.
+    async fn on_parse<C>(&self, client: &mut C, message: Parse) -> PgWireResult<()>
+    where
+        C: ClientInfo + ClientPortalStore + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::PortalStore: PortalStore<Statement = Self::Statement>,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let types = message
+            .type_oids()
+            .iter()
+            .map(|oid| Type::from_oid(*oid).unwrap_or(Type::UNKNOWN))
+            .collect::<Vec<_>>();
+        let name = message
+            .name()
+            .clone()
+            .unwrap_or_else(|| DEFAULT_NAME.to_string());
+        let sql_plan = self
+            .query_parser
+            .parse_prepared(&name, message.query(), &types)
+            .await?;
+        client
+            .portal_store()
+            .put_statement(Arc::new(StoredStatement::new(name, sql_plan, types)));
+        client
+            .send(PgWireBackendMessage::ParseComplete(ParseComplete::new()))
+            .await?;
+        Ok(())
+    }
+
     async fn do_query<'a, C>(
         &self,
         _client: &mut C,
@@ -0,0 +1,307 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+
+use common_meta::table_name::TableName;
+use datafusion::datasource::DefaultTableSource;
+use datafusion::logical_expr::LogicalPlan as DfLogicalPlan;
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
+use parking_lot::RwLock;
+use pgwire::api::Type;
+use query::plan::LogicalPlan;
+use table::metadata::TableVersion;
+use table::table::adapter::DfTableProviderAdapter;
+
+use crate::SqlPlan;
+
+/// Max number of plans cached for a single connection.
+const DEFAULT_PLAN_CACHE_CAPACITY: usize = 256;
+
+/// What a prepared statement is planned from. A cached plan of a statement is
+/// only reused if the statement is parsed again with the same key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlanCacheKey {
+    query: String,
+    param_types: Vec<Type>,
+    /// The database the statement is planned against.
+    db: String,
+    /// The session timezone, timestamp literals are planned in it.
+    timezone: String,
+}
+
+impl PlanCacheKey {
+    pub(crate) fn new(query: &str, param_types: &[Type], db: String, timezone: String) -> Self {
+        Self {
+            query: query.to_string(),
+            param_types: param_types.to_vec(),
+            db,
+            timezone,
+        }
+    }
+}
+
+/// A cached plan with the versions of the tables it reads when it was planned.
+#[derive(Clone)]
+pub(crate) struct CachedPlan {
+    key: PlanCacheKey,
+    pub(crate) plan: SqlPlan,
+    pub(crate) table_versions: Vec<(TableName, TableVersion)>,
+}
+
+/// Logical plans of the prepared statements of a postgres session, by statement
+/// name. Drivers usually parse the same statement repeatedly, often as the unnamed
+/// statement, so the plan is reused if the statement is parsed again with the same
+/// [PlanCacheKey].
+pub(crate) struct PlanCache {
+    capacity: usize,
+    plans: RwLock<HashMap<String, CachedPlan>>,
+}
+
+impl Default for PlanCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PLAN_CACHE_CAPACITY)
+    }
+}
+
+impl PlanCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            plans: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached plan of the statement `name` if it's planned from `key`.
+    /// The caller must check the table versions before using it.
+    pub(crate) fn get(&self, name: &str, key: &PlanCacheKey) -> Option<CachedPlan> {
+        self.plans
+            .read()
+            .get(name)
+            .filter(|cached| cached.key == *key)
+            .cloned()
+    }
+
+    /// Caches the plan of the statement `name`. Statements without a logical plan
+    /// are not cached as they are planned from the substituted query text on
+    /// execution anyway.
+    pub(crate) fn put(&self, name: &str, key: PlanCacheKey, plan: SqlPlan) {
+        let Some(logical_plan) = &plan.plan else {
+            let _ = self.remove(name);
+            return;
+        };
+        if self.capacity == 0 {
+            return;
+        }
+        let table_versions = table_versions(logical_plan);
+        let mut plans = self.plans.write();
+        if plans.len() >= self.capacity && !plans.contains_key(name) {
+            // Plans are cheap to rebuild, make room by dropping an arbitrary one.
+            if let Some(evicted) = plans.keys().next().cloned() {
+                let _ = plans.remove(&evicted);
+            }
+        }
+        let _ = plans.insert(
+            name.to_string(),
+            CachedPlan {
+                key,
+                plan,
+                table_versions,
+            },
+        );
+    }
+
+    /// Removes the plan of the statement `name`, e.g. on `DEALLOCATE name` or when
+    /// a table it reads has been altered.
+    pub(crate) fn remove(&self, name: &str) -> Option<CachedPlan> {
+        self.plans.write().remove(name)
+    }
+
+    pub(crate) fn clear(&self) {
+        self.plans.write().clear();
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.plans.read().len()
+    }
+}
+
+/// Returns the versions of the tables `plan` scans.
+fn table_versions(plan: &LogicalPlan) -> Vec<(TableName, TableVersion)> {
+    let LogicalPlan::DfPlan(plan) = plan;
+    let mut versions: Vec<(TableName, TableVersion)> = vec![];
+    let _ = plan.apply(&mut |node| {
+        if let DfLogicalPlan::TableScan(scan) = node {
+            if let Some(table) = scan
+                .source
+                .as_any()
+                .downcast_ref::<DefaultTableSource>()
+                .and_then(|source| {
+                    source
+                        .table_provider
+                        .as_any()
+                        .downcast_ref::<DfTableProviderAdapter>()
+                })
+                .map(|provider| provider.table())
+            {
+                let info = table.table_info();
+                let name = TableName::new(&info.catalog_name, &info.schema_name, &info.name);
+                if versions.iter().all(|(n, _)| *n != name) {
+                    versions.push((name, info.ident.version));
+                }
+            }
+        }
+        Ok(VisitRecursion::Continue)
+    });
+    versions
+}
+
+/// Parses `DEALLOCATE [PREPARE] { name | ALL }`, returns the statement name or
+/// `None` for `ALL`.
+pub(crate) fn parse_deallocate(query: &str) -> Option<Option<String>> {
+    let query = query.trim().trim_end_matches(';');
+    let mut words = query.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("DEALLOCATE") {
+        return None;
+    }
+    let mut name = words.next()?;
+    if name.eq_ignore_ascii_case("PREPARE") {
+        name = words.next()?;
+    }
+    if words.next().is_some() {
+        return None;
+    }
+    if name.eq_ignore_ascii_case("ALL") {
+        Some(None)
+    } else {
+        Some(Some(name.trim_matches('"').to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::logical_expr::{EmptyRelation, LogicalPlanBuilder};
+    use datafusion_common::DFSchema;
+    use table::test_util::MemTable;
+
+    use super::*;
+
+    fn sql_plan(query: &str, with_plan: bool) -> SqlPlan {
+        let plan = with_plan.then(|| {
+            LogicalPlan::DfPlan(DfLogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row: false,
+                schema: Arc::new(DFSchema::empty()),
+            }))
+        });
+        SqlPlan {
+            query: query.to_string(),
+            plan,
+            schema: None,
+        }
+    }
+
+    fn key(query: &str, param_types: &[Type], db: &str, timezone: &str) -> PlanCacheKey {
+        PlanCacheKey::new(query, param_types, db.to_string(), timezone.to_string())
+    }
+
+    #[test]
+    fn test_plan_cache() {
+        let cache = PlanCache::new(2);
+        let query = "SELECT * FROM foo WHERE a = $1";
+        let key1 = key(query, &[Type::INT4], "public", "UTC");
+        assert!(cache.get("s1", &key1).is_none());
+
+        cache.put("s1", key1.clone(), sql_plan(query, true));
+        assert_eq!(query, cache.get("s1", &key1).unwrap().plan.query);
+        // Plans are cached by statement name.
+        assert!(cache.get("s2", &key1).is_none());
+        // Parameter types, database and timezone are part of the key.
+        assert!(cache
+            .get("s1", &key(query, &[Type::INT8], "public", "UTC"))
+            .is_none());
+        assert!(cache
+            .get("s1", &key(query, &[Type::INT4], "other", "UTC"))
+            .is_none());
+        assert!(cache
+            .get("s1", &key(query, &[Type::INT4], "public", "+08:00"))
+            .is_none());
+
+        // Statements without logical plans are not cached, and replace the plan of
+        // the same name.
+        let other = "SHOW TABLES";
+        cache.put(
+            "s1",
+            key(other, &[], "public", "UTC"),
+            sql_plan(other, false),
+        );
+        assert_eq!(0, cache.len());
+
+        for i in 0..3 {
+            let query = format!("SELECT {i}");
+            cache.put(
+                &format!("s{i}"),
+                key(&query, &[], "public", "UTC"),
+                sql_plan(&query, true),
+            );
+        }
+        assert_eq!(2, cache.len());
+
+        cache.put("s1", key1.clone(), sql_plan(query, true));
+        assert!(cache.remove("s1").is_some());
+        assert!(cache.get("s1", &key1).is_none());
+        cache.clear();
+        assert_eq!(0, cache.len());
+    }
+
+    #[test]
+    fn test_table_versions() {
+        let table = MemTable::default_numbers_table();
+        let info = table.table_info();
+        let source = Arc::new(DefaultTableSource::new(Arc::new(
+            DfTableProviderAdapter::new(table.clone()),
+        )));
+        let plan = LogicalPlanBuilder::scan("numbers", source, None)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            vec![(
+                TableName::new(&info.catalog_name, &info.schema_name, &info.name),
+                info.ident.version
+            )],
+            table_versions(&LogicalPlan::DfPlan(plan))
+        );
+        assert!(table_versions(&sql_plan("SELECT 1", true).plan.unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_parse_deallocate() {
+        assert_eq!(Some(None), parse_deallocate("DEALLOCATE ALL"));
+        assert_eq!(Some(None), parse_deallocate("deallocate prepare all;"));
+        assert_eq!(
+            Some(Some("stmt_1".to_string())),
+            parse_deallocate("DEALLOCATE stmt_1")
+        );
+        assert_eq!(
+            Some(Some("s1".to_string())),
+            parse_deallocate("DEALLOCATE PREPARE \"s1\"")
+        );
+        assert_eq!(None, parse_deallocate("DEALLOCATE"));
+        assert_eq!(None, parse_deallocate("DEALLOCATE a b"));
+        assert_eq!(None, parse_deallocate("SELECT 1"));
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encoder of Prometheus XOR chunks, used by the streamed remote read response.
+//!
+//! The format is the same as `tsdb/chunkenc/xor.go` in Prometheus: a big-endian
+//! `u16` number of samples followed by a bit stream of delta-of-delta encoded
+//! timestamps and XOR encoded values.
+
+use api::prom_store::remote::Sample;
+
+/// Max samples in a chunk, the same as Prometheus.
+pub const MAX_SAMPLES_PER_CHUNK: usize = 120;
+
+/// Writes bits from the most significant bit of each byte.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Number of unused bits in the last byte.
+    free_bits: u8,
+}
+
+impl BitWriter {
+    fn write_bit(&mut self, bit: bool) {
+        if self.free_bits == 0 {
+            self.bytes.push(0);
+            self.free_bits = 8;
+        }
+        self.free_bits -= 1;
+        if bit {
+            // Safety: a byte is pushed above if there is no free bit.
+            *self.bytes.last_mut().unwrap() |= 1 << self.free_bits;
+        }
+    }
+
+    /// Writes the lowest `nbits` bits of `value`.
+    fn write_bits(&mut self, value: u64, nbits: u32) {
+        for i in (0..nbits).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    fn write_uvarint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.write_bits((value as u8 | 0x80) as u64, 8);
+            value >>= 7;
+        }
+        self.write_bits(value, 8);
+    }
+
+    fn write_varint(&mut self, value: i64) {
+        self.write_uvarint(((value << 1) ^ (value >> 63)) as u64);
+    }
+}
+
+/// Encodes samples into a XOR chunk.
+pub struct XorChunkEncoder {
+    writer: BitWriter,
+    num_samples: u16,
+    timestamp: i64,
+    timestamp_delta: u64,
+    value: f64,
+    leading_zeros: u8,
+    trailing_zeros: u8,
+}
+
+impl Default for XorChunkEncoder {
+    fn default() -> Self {
+        Self {
+            writer: BitWriter {
+                // Reserves the header for the number of samples.
+                bytes: vec![0, 0],
+                free_bits: 0,
+            },
+            num_samples: 0,
+            timestamp: 0,
+            timestamp_delta: 0,
+            value: 0.0,
+            // No previous leading zeros.
+            leading_zeros: u8::MAX,
+            trailing_zeros: 0,
+        }
+    }
+}
+
+impl XorChunkEncoder {
+    /// Appends a sample, samples must be sorted by timestamp and a chunk holds at
+    /// most [MAX_SAMPLES_PER_CHUNK] samples.
+    pub fn append(&mut self, sample: &Sample) {
+        let timestamp = sample.timestamp;
+        let value = sample.value;
+        let mut timestamp_delta = 0;
+        match self.num_samples {
+            0 => {
+                self.writer.write_varint(timestamp);
+                self.writer.write_bits(value.to_bits(), 64);
+            }
+            1 => {
+                timestamp_delta = timestamp.wrapping_sub(self.timestamp) as u64;
+                self.writer.write_uvarint(timestamp_delta);
+                self.write_value(value);
+            }
+            _ => {
+                timestamp_delta = timestamp.wrapping_sub(self.timestamp) as u64;
+                let dod = timestamp_delta.wrapping_sub(self.timestamp_delta) as i64;
+                if dod == 0 {
+                    self.writer.write_bit(false);
+                } else if fits_bits(dod, 14) {
+                    self.writer.write_bits(0b10, 2);
+                    self.writer.write_bits(dod as u64, 14);
+                } else if fits_bits(dod, 17) {
+                    self.writer.write_bits(0b110, 3);
+                    self.writer.write_bits(dod as u64, 17);
+                } else if fits_bits(dod, 20) {
+                    self.writer.write_bits(0b1110, 4);
+                    self.writer.write_bits(dod as u64, 20);
+                } else {
+                    self.writer.write_bits(0b1111, 4);
+                    self.writer.write_bits(dod as u64, 64);
+                }
+                self.write_value(value);
+            }
+        }
+
+        self.timestamp = timestamp;
+        self.timestamp_delta = timestamp_delta;
+        self.value = value;
+        self.num_samples += 1;
+    }
+
+    fn write_value(&mut self, value: f64) {
+        let delta = value.to_bits() ^ self.value.to_bits();
+        if delta == 0 {
+            self.writer.write_bit(false);
+            return;
+        }
+        self.writer.write_bit(true);
+
+        // Clamps leading zeros as they are encoded in 5 bits.
+        let leading_zeros = (delta.leading_zeros() as u8).min(31);
+        let trailing_zeros = delta.trailing_zeros() as u8;
+        if self.leading_zeros != u8::MAX
+            && leading_zeros >= self.leading_zeros
+            && trailing_zeros >= self.trailing_zeros
+        {
+            // Meaningful bits fit in the previous window.
+            self.writer.write_bit(false);
+            self.writer.write_bits(
+                delta >> self.trailing_zeros,
+                64 - self.leading_zeros as u32 - self.trailing_zeros as u32,
+            );
+            return;
+        }
+
+        self.leading_zeros = leading_zeros;
+        self.trailing_zeros = trailing_zeros;
+        self.writer.write_bit(true);
+        self.writer.write_bits(leading_zeros as u64, 5);
+        let significant_bits = 64 - leading_zeros as u32 - trailing_zeros as u32;
+        // 64 significant bits are written as 0, it can't be 0 as the delta isn't 0.
+        self.writer.write_bits(significant_bits as u64, 6);
+        self.writer
+            .write_bits(delta >> trailing_zeros, significant_bits);
+    }
+
+    /// Returns bytes of the chunk.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.writer.bytes[..2].copy_from_slice(&self.num_samples.to_be_bytes());
+        self.writer.bytes
+    }
+}
+
+/// Returns true if `value` fits in `nbits` bits as Prometheus defines.
+fn fits_bits(value: i64, nbits: u32) -> bool {
+    -((1 << (nbits - 1)) - 1) <= value && value <= 1 << (nbits - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads bits written by [BitWriter].
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+    }
+
+    impl BitReader<'_> {
+        fn read_bit(&mut self) -> bool {
+            let bit = self.bytes[self.pos / 8] & (1 << (7 - self.pos % 8)) != 0;
+            self.pos += 1;
+            bit
+        }
+
+        fn read_bits(&mut self, nbits: u32) -> u64 {
+            (0..nbits).fold(0, |value, _| (value << 1) | self.read_bit() as u64)
+        }
+
+        fn read_uvarint(&mut self) -> u64 {
+            let mut value = 0;
+            let mut shift = 0;
+            loop {
+                let byte = self.read_bits(8);
+                value |= (byte & 0x7f) << shift;
+                if byte < 0x80 {
+                    return value;
+                }
+                shift += 7;
+            }
+        }
+
+        fn read_varint(&mut self) -> i64 {
+            let value = self.read_uvarint();
+            (value >> 1) as i64 ^ -((value & 1) as i64)
+        }
+
+        /// Reads a signed value of `nbits` bits the same as Prometheus.
+        fn read_dod(&mut self, nbits: u32) -> i64 {
+            let value = self.read_bits(nbits) as i64;
+            if value > 1 << (nbits - 1) {
+                value - (1 << nbits)
+            } else {
+                value
+            }
+        }
+    }
+
+    fn decode(chunk: &[u8]) -> Vec<(i64, f64)> {
+        let num_samples = u16::from_be_bytes([chunk[0], chunk[1]]) as usize;
+        let mut reader = BitReader {
+            bytes: &chunk[2..],
+            pos: 0,
+        };
+        let mut samples = Vec::with_capacity(num_samples);
+        let (mut timestamp, mut delta, mut value) = (0i64, 0i64, 0u64);
+        let (mut leading, mut trailing) = (0u32, 0u32);
+        for i in 0..num_samples {
+            match i {
+                0 => {
+                    timestamp = reader.read_varint();
+                    value = reader.read_bits(64);
+                }
+                _ => {
+                    if i == 1 {
+                        delta = reader.read_uvarint() as i64;
+                    } else {
+                        let dod = if !reader.read_bit() {
+                            0
+                        } else if !reader.read_bit() {
+                            reader.read_dod(14)
+                        } else if !reader.read_bit() {
+                            reader.read_dod(17)
+                        } else if !reader.read_bit() {
+                            reader.read_dod(20)
+                        } else {
+                            reader.read_bits(64) as i64
+                        };
+                        delta += dod;
+                    }
+                    timestamp += delta;
+                    if reader.read_bit() {
+                        if reader.read_bit() {
+                            leading = reader.read_bits(5) as u32;
+                            let mut significant = reader.read_bits(6) as u32;
+                            if significant == 0 {
+                                significant = 64;
+                            }
+                            trailing = 64 - leading - significant;
+                        }
+                        value ^= reader.read_bits(64 - leading - trailing) << trailing;
+                    }
+                }
+            }
+            samples.push((timestamp, f64::from_bits(value)));
+        }
+        samples
+    }
+
+    #[test]
+    fn test_xor_chunk_encoder() {
+        let samples = [
+            (1000, 1.0),
+            (2000, 1.0),
+            (3000, 2.5),
+            (4010, 2.5),
+            (4020, -3.75),
+            (100_000, 1e10),
+            (100_001, 1e10 + 1.0),
+            (10_000_000_000, 0.0),
+            (10_000_000_001, f64::MAX),
+        ];
+        let mut encoder = XorChunkEncoder::default();
+        for (timestamp, value) in samples {
+            encoder.append(&Sample { value, timestamp });
+        }
+        let chunk = encoder.finish();
+        assert_eq!(samples.to_vec(), decode(&chunk));
+    }
+
+    #[test]
+    fn test_empty_xor_chunk() {
+        assert_eq!(vec![0, 0], XorChunkEncoder::default().finish());
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Relabel rules applied to Prometheus remote write series before storage.
+//!
+//! The rules follow the semantics of Prometheus' `relabel_config`, so users can
+//! rewrite labels or drop unwanted series on the server side.
+
+use std::sync::Arc;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error::{InvalidRelabelConfigSnafu, Result};
+
+pub type RelabelRulesRef = Arc<RelabelRules>;
+
+/// Action to perform in a relabel rule.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    /// Sets `target_label` to `replacement` if `regex` matches the concatenated `source_labels`.
+    #[default]
+    Replace,
+    /// Drops the series if `regex` does not match the concatenated `source_labels`.
+    Keep,
+    /// Drops the series if `regex` matches the concatenated `source_labels`.
+    Drop,
+    /// Removes labels whose names match `regex`.
+    LabelDrop,
+    /// Removes labels whose names don't match `regex`.
+    LabelKeep,
+}
+
+/// Config of a relabel rule.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RelabelConfig {
+    /// Labels to select values from, `__name__` refers to the metric name.
+    pub source_labels: Vec<String>,
+    /// Separator to concatenate values of `source_labels`.
+    pub separator: String,
+    /// Regex to match, it's fully anchored.
+    pub regex: String,
+    /// Label to write the replacement to, required by the `replace` action.
+    pub target_label: Option<String>,
+    /// Replacement with capture group references like `$1`.
+    pub replacement: String,
+    pub action: RelabelAction,
+}
+
+impl Default for RelabelConfig {
+    fn default() -> Self {
+        Self {
+            source_labels: Vec::new(),
+            separator: ";".to_string(),
+            regex: "(.*)".to_string(),
+            target_label: None,
+            replacement: "$1".to_string(),
+            action: RelabelAction::Replace,
+        }
+    }
+}
+
+/// A compiled [RelabelConfig].
+#[derive(Debug)]
+struct RelabelRule {
+    source_labels: Vec<String>,
+    separator: String,
+    regex: Regex,
+    target_label: Option<String>,
+    replacement: String,
+    action: RelabelAction,
+}
+
+impl RelabelRule {
+    fn try_new(config: &RelabelConfig) -> Result<Self> {
+        let regex = Regex::new(&format!("^(?:{})$", config.regex)).map_err(|e| {
+            InvalidRelabelConfigSnafu {
+                msg: format!("invalid regex {}: {}", config.regex, e),
+            }
+            .build()
+        })?;
+        if config.action == RelabelAction::Replace {
+            ensure!(
+                config.target_label.is_some(),
+                InvalidRelabelConfigSnafu {
+                    msg: "target_label is required by the replace action",
+                }
+            );
+        }
+        if matches!(
+            config.action,
+            RelabelAction::Replace | RelabelAction::Keep | RelabelAction::Drop
+        ) {
+            ensure!(
+                !config.source_labels.is_empty(),
+                InvalidRelabelConfigSnafu {
+                    msg: format!(
+                        "source_labels is required by the {:?} action",
+                        config.action
+                    ),
+                }
+            );
+        }
+
+        Ok(Self {
+            source_labels: config.source_labels.clone(),
+            separator: config.separator.clone(),
+            regex,
+            target_label: config.target_label.clone(),
+            replacement: config.replacement.clone(),
+            action: config.action,
+        })
+    }
+
+    /// Applies the rule to `labels`, returns false if the series should be dropped.
+    fn apply(&self, labels: &mut Vec<(String, String)>) -> bool {
+        match self.action {
+            RelabelAction::Replace => {
+                let value = self.source_value(labels);
+                let Some(captures) = self.regex.captures(&value) else {
+                    return true;
+                };
+                let mut replacement = String::new();
+                captures.expand(&self.replacement, &mut replacement);
+                // Safety: checked in `try_new()`.
+                let target = self.target_label.as_ref().unwrap();
+                labels.retain(|(name, _)| name != target);
+                if !replacement.is_empty() {
+                    labels.push((target.clone(), replacement));
+                }
+                true
+            }
+            RelabelAction::Keep => self.regex.is_match(&self.source_value(labels)),
+            RelabelAction::Drop => !self.regex.is_match(&self.source_value(labels)),
+            RelabelAction::LabelDrop => {
+                labels.retain(|(name, _)| !self.regex.is_match(name));
+                true
+            }
+            RelabelAction::LabelKeep => {
+                labels.retain(|(name, _)| self.regex.is_match(name));
+                true
+            }
+        }
+    }
+
+    /// Concatenates values of source labels, missing labels are treated as empty strings.
+    fn source_value(&self, labels: &[(String, String)]) -> String {
+        self.source_labels
+            .iter()
+            .map(|source| {
+                labels
+                    .iter()
+                    .find(|(name, _)| name == source)
+                    .map(|(_, value)| value.as_str())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+}
+
+/// Relabel rules applied in order to each series.
+#[derive(Debug, Default)]
+pub struct RelabelRules {
+    rules: Vec<RelabelRule>,
+}
+
+impl RelabelRules {
+    /// Compiles rules from `configs`.
+    pub fn try_new(configs: &[RelabelConfig]) -> Result<Self> {
+        let rules = configs
+            .iter()
+            .map(RelabelRule::try_new)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Applies all rules to `labels` of a series, the metric name is stored in the
+    /// `__name__` label. Returns false if the series should be dropped.
+    pub fn apply(&self, labels: &mut Vec<(String, String)>) -> bool {
+        self.rules.iter().all(|rule| rule.apply(labels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_drop_and_keep() {
+        let rules = RelabelRules::try_new(&[RelabelConfig {
+            source_labels: vec!["__name__".to_string()],
+            regex: "go_.*".to_string(),
+            action: RelabelAction::Drop,
+            ..Default::default()
+        }])
+        .unwrap();
+        assert!(!rules.apply(&mut labels(&[("__name__", "go_gc_duration_seconds")])));
+        assert!(rules.apply(&mut labels(&[("__name__", "http_requests_total")])));
+        // The regex is anchored.
+        assert!(rules.apply(&mut labels(&[("__name__", "xgo_threads")])));
+
+        let rules = RelabelRules::try_new(&[RelabelConfig {
+            source_labels: vec!["env".to_string(), "job".to_string()],
+            regex: "prod;api".to_string(),
+            action: RelabelAction::Keep,
+            ..Default::default()
+        }])
+        .unwrap();
+        assert!(rules.apply(&mut labels(&[("env", "prod"), ("job", "api")])));
+        assert!(!rules.apply(&mut labels(&[("env", "dev"), ("job", "api")])));
+        assert!(!rules.apply(&mut labels(&[("job", "api")])));
+    }
+
+    #[test]
+    fn test_replace() {
+        let rules = RelabelRules::try_new(&[RelabelConfig {
+            source_labels: vec!["instance".to_string()],
+            regex: "(.*):\\d+".to_string(),
+            target_label: Some("host".to_string()),
+            ..Default::default()
+        }])
+        .unwrap();
+        let mut series = labels(&[("__name__", "up"), ("instance", "node1:9100")]);
+        assert!(rules.apply(&mut series));
+        assert_eq!(
+            labels(&[
+                ("__name__", "up"),
+                ("instance", "node1:9100"),
+                ("host", "node1")
+            ]),
+            series
+        );
+
+        // Renames the metric.
+        let rules = RelabelRules::try_new(&[RelabelConfig {
+            source_labels: vec!["__name__".to_string()],
+            regex: "old_(.*)".to_string(),
+            target_label: Some("__name__".to_string()),
+            replacement: "new_$1".to_string(),
+            ..Default::default()
+        }])
+        .unwrap();
+        let mut series = labels(&[("__name__", "old_metric")]);
+        assert!(rules.apply(&mut series));
+        assert_eq!(labels(&[("__name__", "new_metric")]), series);
+    }
+
+    #[test]
+    fn test_label_drop_and_keep() {
+        let rules = RelabelRules::try_new(&[RelabelConfig {
+            regex: "pod_.*".to_string(),
+            action: RelabelAction::LabelDrop,
+            ..Default::default()
+        }])
+        .unwrap();
+        let mut series = labels(&[("__name__", "up"), ("pod_uid", "x"), ("job", "a")]);
+        assert!(rules.apply(&mut series));
+        assert_eq!(labels(&[("__name__", "up"), ("job", "a")]), series);
+
+        let rules = RelabelRules::try_new(&[RelabelConfig {
+            regex: "__name__|job".to_string(),
+            action: RelabelAction::LabelKeep,
+            ..Default::default()
+        }])
+        .unwrap();
+        let mut series = labels(&[("__name__", "up"), ("pod_uid", "x"), ("job", "a")]);
+        assert!(rules.apply(&mut series));
+        assert_eq!(labels(&[("__name__", "up"), ("job", "a")]), series);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        RelabelRules::try_new(&[RelabelConfig {
+            source_labels: vec!["job".to_string()],
+            regex: "(".to_string(),
+            action: RelabelAction::Drop,
+            ..Default::default()
+        }])
+        .unwrap_err();
+        // Missing target label.
+        RelabelRules::try_new(&[RelabelConfig {
+            source_labels: vec!["job".to_string()],
+            ..Default::default()
+        }])
+        .unwrap_err();
+        // Missing source labels.
+        RelabelRules::try_new(&[RelabelConfig {
+            action: RelabelAction::Drop,
+            ..Default::default()
+        }])
+        .unwrap_err();
+    }
+}
@@ -20,18 +20,33 @@ use api::v1::{
     ColumnDataType, ColumnSchema, Row, RowInsertRequest, RowInsertRequests, Rows, SemanticType,
     Value,
 };
-use common_query::prelude::{GREPTIME_TIMESTAMP, GREPTIME_VALUE};
+use common_query::prelude::{
+    GREPTIME_EXEMPLAR_LABELS, GREPTIME_HISTOGRAM, GREPTIME_TIMESTAMP, GREPTIME_VALUE,
+};
 use hashbrown::hash_map::Entry;
 use hashbrown::HashMap;
-use prost::DecodeError;
+use prost::{DecodeError, Message};
 
-use crate::proto::PromLabel;
+use crate::proto::{PromExemplar, PromHistogram, PromLabel};
 use crate::repeated_field::Clear;
 
+/// Kind of data stored in tables built by [TablesBuilder].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TableKind {
+    /// Float samples.
+    #[default]
+    Sample,
+    /// Encoded native histograms.
+    Histogram,
+    /// Exemplars with their labels.
+    Exemplar,
+}
+
 /// [TablesBuilder] serves as an intermediate container to build [RowInsertRequests].
 #[derive(Default, Debug)]
 pub(crate) struct TablesBuilder {
     tables: HashMap<String, TableBuilder>,
+    kind: TableKind,
 }
 
 impl Clear for TablesBuilder {
@@ -41,6 +56,14 @@ impl Clear for TablesBuilder {
 }
 
 impl TablesBuilder {
+    /// Creates a [TablesBuilder] whose tables store data of `kind`.
+    pub(crate) fn new(kind: TableKind) -> Self {
+        Self {
+            tables: HashMap::new(),
+            kind,
+        }
+    }
+
     /// Gets table builder with given table name. Creates an empty [TableBuilder] if not exist.
     pub(crate) fn get_or_create_table_builder(
         &mut self,
@@ -48,9 +71,12 @@ impl TablesBuilder {
         label_num: usize,
         row_num: usize,
     ) -> &mut TableBuilder {
-        self.tables
-            .entry(table_name)
-            .or_insert_with(|| TableBuilder::with_capacity(label_num + 2, row_num))
+        let kind = self.kind;
+        self.tables.entry(table_name).or_insert_with(|| match kind {
+            TableKind::Sample => TableBuilder::with_capacity(label_num + 2, row_num),
+            TableKind::Histogram => TableBuilder::with_histogram_capacity(label_num + 2, row_num),
+            TableKind::Exemplar => TableBuilder::with_exemplar_capacity(label_num + 3, row_num),
+        })
     }
 
     /// Converts [TablesBuilder] to [RowInsertRequests] and row numbers and clears inner states.
@@ -87,9 +113,38 @@ impl Default for TableBuilder {
 
 impl TableBuilder {
     pub(crate) fn with_capacity(cols: usize, rows: usize) -> Self {
+        Self::with_value_column(cols, rows, GREPTIME_VALUE, ColumnDataType::Float64)
+    }
+
+    /// Creates a builder whose value column stores encoded native histograms.
+    pub(crate) fn with_histogram_capacity(cols: usize, rows: usize) -> Self {
+        Self::with_value_column(cols, rows, GREPTIME_HISTOGRAM, ColumnDataType::Binary)
+    }
+
+    /// Creates a builder for exemplars, whose labels are stored as a JSON string field.
+    pub(crate) fn with_exemplar_capacity(cols: usize, rows: usize) -> Self {
+        let mut builder = Self::with_capacity(cols, rows);
+        builder
+            .col_indexes
+            .insert(GREPTIME_EXEMPLAR_LABELS.to_string(), 2);
+        builder.schema.push(ColumnSchema {
+            column_name: GREPTIME_EXEMPLAR_LABELS.to_string(),
+            datatype: ColumnDataType::String as i32,
+            semantic_type: SemanticType::Field as i32,
+            datatype_extension: None,
+        });
+        builder
+    }
+
+    fn with_value_column(
+        cols: usize,
+        rows: usize,
+        value_column: &str,
+        value_type: ColumnDataType,
+    ) -> Self {
         let mut col_indexes = HashMap::with_capacity_and_hasher(cols, Default::default());
         col_indexes.insert(GREPTIME_TIMESTAMP.to_string(), 0);
-        col_indexes.insert(GREPTIME_VALUE.to_string(), 1);
+        col_indexes.insert(value_column.to_string(), 1);
 
         let mut schema = Vec::with_capacity(cols);
         schema.push(ColumnSchema {
@@ -100,8 +155,8 @@ impl TableBuilder {
         });
 
         schema.push(ColumnSchema {
-            column_name: GREPTIME_VALUE.to_string(),
-            datatype: ColumnDataType::Float64 as i32,
+            column_name: value_column.to_string(),
+            datatype: value_type as i32,
             semantic_type: SemanticType::Field as i32,
             datatype_extension: None,
         });
@@ -125,6 +180,75 @@ impl TableBuilder {
         samples: &[Sample],
         is_strict_mode: bool,
     ) -> Result<(), DecodeError> {
+        let mut row = self.labels_to_row(labels, is_strict_mode)?;
+
+        if samples.len() == 1 {
+            let sample = &samples[0];
+            row[0].value_data = Some(ValueData::TimestampMillisecondValue(sample.timestamp));
+            row[1].value_data = Some(ValueData::F64Value(sample.value));
+            self.rows.push(Row { values: row });
+            return Ok(());
+        }
+        for sample in samples {
+            row[0].value_data = Some(ValueData::TimestampMillisecondValue(sample.timestamp));
+            row[1].value_data = Some(ValueData::F64Value(sample.value));
+            self.rows.push(Row {
+                values: row.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Adds a set of labels and native histograms to table builder.
+    pub(crate) fn add_labels_and_histograms(
+        &mut self,
+        labels: &[PromLabel],
+        histograms: &[PromHistogram],
+        is_strict_mode: bool,
+    ) -> Result<(), DecodeError> {
+        let mut row = self.labels_to_row(labels, is_strict_mode)?;
+
+        for histogram in histograms {
+            row[0].value_data = Some(ValueData::TimestampMillisecondValue(histogram.timestamp));
+            row[1].value_data = Some(ValueData::BinaryValue(
+                histogram.to_native_histogram().encode_to_vec(),
+            ));
+            self.rows.push(Row {
+                values: row.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Adds a set of labels and exemplars to table builder.
+    pub(crate) fn add_labels_and_exemplars(
+        &mut self,
+        labels: &[PromLabel],
+        exemplars: &[PromExemplar],
+        is_strict_mode: bool,
+    ) -> Result<(), DecodeError> {
+        let mut row = self.labels_to_row(labels, is_strict_mode)?;
+
+        for exemplar in exemplars {
+            row[0].value_data = Some(ValueData::TimestampMillisecondValue(exemplar.timestamp));
+            row[1].value_data = Some(ValueData::F64Value(exemplar.value));
+            row[2].value_data = Some(ValueData::StringValue(exemplar.labels_to_json()));
+            self.rows.push(Row {
+                values: row.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Converts labels to a row, adds tag columns to the schema if absent.
+    fn labels_to_row(
+        &mut self,
+        labels: &[PromLabel],
+        is_strict_mode: bool,
+    ) -> Result<Vec<Value>, DecodeError> {
         let mut row = vec![Value { value_data: None }; self.col_indexes.len()];
 
         for PromLabel { name, value } in labels {
@@ -167,22 +291,7 @@ impl TableBuilder {
             }
         }
 
-        if samples.len() == 1 {
-            let sample = &samples[0];
-            row[0].value_data = Some(ValueData::TimestampMillisecondValue(sample.timestamp));
-            row[1].value_data = Some(ValueData::F64Value(sample.value));
-            self.rows.push(Row { values: row });
-            return Ok(());
-        }
-        for sample in samples {
-            row[0].value_data = Some(ValueData::TimestampMillisecondValue(sample.timestamp));
-            row[1].value_data = Some(ValueData::F64Value(sample.value));
-            self.rows.push(Row {
-                values: row.clone(),
-            });
-        }
-
-        Ok(())
+        Ok(row)
     }
 
     /// Converts [TableBuilder] to [RowInsertRequest] and clears buffered data.
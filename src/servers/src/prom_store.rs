@@ -15,32 +15,52 @@
 //! prometheus protocol supportings
 //! handles prometheus remote_write, remote_read logic
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::hash::{Hash, Hasher};
 
 use api::prom_store::remote::label_matcher::Type as MatcherType;
-use api::prom_store::remote::{Label, Query, Sample, TimeSeries, WriteRequest};
+use api::prom_store::remote::{
+    chunk, Chunk, ChunkedReadResponse, ChunkedSeries, Label, Query, Sample, TimeSeries,
+    WriteRequest,
+};
 use api::v1::RowInsertRequests;
+use bytes::Bytes;
 use common_query::prelude::{GREPTIME_TIMESTAMP, GREPTIME_VALUE};
-use common_recordbatch::{RecordBatch, RecordBatches};
+use common_recordbatch::{RecordBatch, RecordBatches, SendableRecordBatchStream};
 use common_telemetry::tracing;
 use common_time::timestamp::TimeUnit;
-use datafusion::prelude::{col, lit, regexp_match, Expr};
-use datafusion_common::ScalarValue;
+use datafusion::dataframe::DataFrame as DfDataFrame;
+use datafusion::logical_expr::{BinaryExpr, Operator};
+use datafusion::prelude::{col, lit, Expr};
+use datafusion_common::{Column, ScalarValue};
 use datatypes::prelude::{ConcreteDataType, Value};
+use datatypes::vectors::VectorRef;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
 use openmetrics_parser::{MetricsExposition, PrometheusType, PrometheusValue};
+use prost::Message;
 use query::dataframe::DataFrame;
 use query::plan::LogicalPlan;
+use regex::Regex;
 use snafu::{ensure, OptionExt, ResultExt};
 use snap::raw::{Decoder, Encoder};
 
 use crate::error::{self, Result};
+use crate::prom_chunk::{XorChunkEncoder, MAX_SAMPLES_PER_CHUNK};
 use crate::row_writer::{self, MultiTableData};
 
 pub const METRIC_NAME_LABEL: &str = "__name__";
 
 pub const METRIC_NAME_LABEL_BYTES: &[u8] = b"__name__";
 
+/// Suffix of the table name storing exemplars of a metric.
+pub const EXEMPLAR_TABLE_SUFFIX: &str = "__exemplars";
+
+/// Returns the name of the table storing exemplars of `metric`.
+pub fn exemplar_table_name(metric: &str) -> String {
+    format!("{metric}{EXEMPLAR_TABLE_SUFFIX}")
+}
+
 /// Metrics for push gateway protocol
 pub struct Metrics {
     pub exposition: MetricsExposition<PrometheusType, PrometheusValue>,
@@ -64,20 +84,107 @@ pub fn table_name(q: &Query) -> Result<String> {
         })
 }
 
+/// Returns true if the query only needs labels of series, e.g. queries of the
+/// series API.
+pub fn is_series_query(q: &Query) -> bool {
+    q.hints.as_ref().is_some_and(|hints| hints.func == "series")
+}
+
 /// Create a DataFrame from a remote Query
+///
+/// Label matchers and the time range are translated into predicates on the table
+/// so the scan prunes data by them.
 #[tracing::instrument(skip_all)]
 pub fn query_to_plan(dataframe: DataFrame, q: &Query) -> Result<LogicalPlan> {
     let DataFrame::DataFusion(dataframe) = dataframe;
 
-    let start_timestamp_ms = q.start_timestamp_ms;
-    let end_timestamp_ms = q.end_timestamp_ms;
+    let labels = is_series_query(q).then(|| {
+        dataframe
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .filter(|name| name != GREPTIME_TIMESTAMP && name != GREPTIME_VALUE)
+            .collect::<Vec<_>>()
+    });
+
+    let dataframe = build_dataframe(dataframe, q, GREPTIME_TIMESTAMP, labels)?;
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+/// Create a plan like [query_to_plan], but rows of the same series are adjacent and
+/// sorted by time, so the result can be encoded series by series while it is read.
+#[tracing::instrument(skip_all)]
+pub fn query_to_series_ordered_plan(dataframe: DataFrame, q: &Query) -> Result<LogicalPlan> {
+    if is_series_query(q) {
+        // Rows are distinct label sets.
+        return query_to_plan(dataframe, q);
+    }
+    let DataFrame::DataFusion(dataframe) = dataframe;
+
+    let dataframe = build_dataframe(dataframe, q, GREPTIME_TIMESTAMP, None)?;
+    let sort_exprs = dataframe
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .filter(|name| name != GREPTIME_TIMESTAMP && name != GREPTIME_VALUE)
+        .chain(std::iter::once(GREPTIME_TIMESTAMP.to_string()))
+        .map(|name| col(Column::from_name(name)).sort(true, true))
+        .collect::<Vec<_>>();
+    let dataframe = dataframe.sort(sort_exprs).context(error::DataFrameSnafu)?;
+
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+/// Create a plan to query label sets of series in a table that isn't necessarily
+/// written by Prometheus, the table has the time index `time_index` and tag columns
+/// `tag_columns`.
+///
+/// Only labels in the grouping of the hints are queried if the hints group by labels.
+#[tracing::instrument(skip_all)]
+pub fn series_query_to_plan(
+    dataframe: DataFrame,
+    q: &Query,
+    time_index: &str,
+    tag_columns: &[String],
+) -> Result<LogicalPlan> {
+    let DataFrame::DataFusion(dataframe) = dataframe;
+
+    let labels = match &q.hints {
+        Some(hints) if hints.by => tag_columns
+            .iter()
+            .filter(|column| hints.grouping.contains(column))
+            .cloned()
+            .collect(),
+        _ => tag_columns.to_vec(),
+    };
+
+    let dataframe = build_dataframe(dataframe, q, time_index, Some(labels))?;
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+/// Filters the `dataframe` by the time range and matchers of the query, then selects
+/// distinct `labels` if present.
+fn build_dataframe(
+    dataframe: DfDataFrame,
+    q: &Query,
+    time_index: &str,
+    labels: Option<Vec<String>>,
+) -> Result<DfDataFrame> {
+    let (start_timestamp_ms, end_timestamp_ms) = query_time_range(q);
 
     let label_matches = &q.matchers;
 
     let mut conditions = Vec::with_capacity(label_matches.len() + 1);
 
-    conditions.push(col(GREPTIME_TIMESTAMP).gt_eq(lit_timestamp_millisecond(start_timestamp_ms)));
-    conditions.push(col(GREPTIME_TIMESTAMP).lt_eq(lit_timestamp_millisecond(end_timestamp_ms)));
+    let time_index = col(Column::from_name(time_index));
+    conditions.push(
+        time_index
+            .clone()
+            .gt_eq(lit_timestamp_millisecond(start_timestamp_ms)),
+    );
+    conditions.push(time_index.lt_eq(lit_timestamp_millisecond(end_timestamp_ms)));
 
     for m in label_matches {
         let name = &m.name;
@@ -86,40 +193,139 @@ pub fn query_to_plan(dataframe: DataFrame, q: &Query) -> Result<LogicalPlan> {
             continue;
         }
 
-        let value = &m.value;
         let m_type = MatcherType::try_from(m.r#type).map_err(|e| {
             error::InvalidPromRemoteRequestSnafu {
                 msg: format!("invalid LabelMatcher type, decode error: {e}",),
             }
             .build()
         })?;
-
-        match m_type {
-            MatcherType::Eq => {
-                conditions.push(col(name).eq(lit(value)));
-            }
-            MatcherType::Neq => {
-                conditions.push(col(name).not_eq(lit(value)));
-            }
-            // Case sensitive regexp match
-            MatcherType::Re => {
-                conditions.push(regexp_match(vec![col(name), lit(value)]).is_not_null());
-            }
-            // Case sensitive regexp not match
-            MatcherType::Nre => {
-                conditions.push(regexp_match(vec![col(name), lit(value)]).is_null());
-            }
+        let column_exists = dataframe.schema().has_column_with_unqualified_name(name);
+        if let Some(condition) = matcher_to_expr(name, &m.value, m_type, column_exists)? {
+            conditions.push(condition);
         }
     }
 
     // Safety: conditions MUST not be empty, reduce always return Some(expr).
     let conditions = conditions.into_iter().reduce(Expr::and).unwrap();
 
-    let dataframe = dataframe
+    let mut dataframe = dataframe
         .filter(conditions)
         .context(error::DataFrameSnafu)?;
 
-    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+    if let Some(labels) = labels {
+        let labels = labels
+            .into_iter()
+            .map(|name| col(Column::from_name(name)))
+            .collect::<Vec<_>>();
+        dataframe = if labels.is_empty() {
+            // The metric has only one series.
+            dataframe.limit(0, Some(1))
+        } else {
+            dataframe.select(labels).and_then(|df| df.distinct())
+        }
+        .context(error::DataFrameSnafu)?;
+    }
+
+    Ok(dataframe)
+}
+
+/// Returns the time range of the query, narrowed by its hints.
+fn query_time_range(q: &Query) -> (i64, i64) {
+    let mut start = q.start_timestamp_ms;
+    let mut end = q.end_timestamp_ms;
+    if let Some(hints) = &q.hints {
+        if hints.start_ms > 0 {
+            start = start.max(hints.start_ms);
+        }
+        if hints.end_ms > 0 {
+            end = end.min(hints.end_ms);
+        }
+    }
+    (start, end)
+}
+
+/// Translates a label matcher into a predicate on the label column, returns `None`
+/// if the matcher matches all series.
+///
+/// A series without the label, i.e. the label column is null or absent, has an
+/// empty label value as Prometheus defines.
+fn matcher_to_expr(
+    name: &str,
+    value: &str,
+    m_type: MatcherType,
+    column_exists: bool,
+) -> Result<Option<Expr>> {
+    let regex = match m_type {
+        MatcherType::Re | MatcherType::Nre => {
+            // Prometheus regexps are fully anchored.
+            let pattern = format!("^(?:{value})$");
+            let regex = Regex::new(&pattern).map_err(|e| {
+                error::InvalidPromRemoteRequestSnafu {
+                    msg: format!("invalid regexp '{value}' of label '{name}': {e}"),
+                }
+                .build()
+            })?;
+            Some((pattern, regex))
+        }
+        MatcherType::Eq | MatcherType::Neq => None,
+    };
+    let matches_empty = match (m_type, &regex) {
+        (MatcherType::Eq, _) => value.is_empty(),
+        (MatcherType::Neq, _) => !value.is_empty(),
+        (MatcherType::Re, Some((_, regex))) => regex.is_match(""),
+        (MatcherType::Nre, Some((_, regex))) => !regex.is_match(""),
+        _ => unreachable!(),
+    };
+    if !column_exists {
+        return Ok((!matches_empty).then(|| lit(false)));
+    }
+
+    let column = col(Column::from_name(name));
+    let condition = match (m_type, regex) {
+        (MatcherType::Eq, _) => column.clone().eq(lit(value)),
+        (MatcherType::Neq, _) => column.clone().not_eq(lit(value)),
+        (MatcherType::Re, _) if value == ".*" => return Ok(None),
+        (MatcherType::Nre, _) if value == ".*" => return Ok(Some(lit(false))),
+        (_, Some((pattern, _))) => {
+            let negated = m_type == MatcherType::Nre;
+            match literal_alternatives(value) {
+                // Set lookups are cheaper than regexps and can be used to prune data.
+                Some(values) => column
+                    .clone()
+                    .in_list(values.into_iter().map(lit).collect(), negated),
+                None => {
+                    let op = if negated {
+                        Operator::RegexNotMatch
+                    } else {
+                        Operator::RegexMatch
+                    };
+                    Expr::BinaryExpr(BinaryExpr::new(
+                        Box::new(column.clone()),
+                        op,
+                        Box::new(lit(pattern)),
+                    ))
+                }
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    if matches_empty {
+        Ok(Some(condition.or(column.is_null())))
+    } else {
+        Ok(Some(condition))
+    }
+}
+
+/// Returns values of the regexp if it's an alternation of literals, e.g. `a|b|c`.
+fn literal_alternatives(value: &str) -> Option<Vec<&str>> {
+    const META_CHARACTERS: &[char] = &[
+        '\\', '.', '+', '*', '?', '(', ')', '[', ']', '{', '}', '^', '$',
+    ];
+    if value.is_empty() || value.contains(META_CHARACTERS) {
+        return None;
+    }
+    Some(value.split('|').collect())
 }
 
 #[inline]
@@ -238,7 +444,8 @@ pub fn recordbatches_to_timeseries(
         .collect())
 }
 
-fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Vec<TimeSeries>> {
+/// Returns the timestamp and value columns of the `recordbatch`.
+fn sample_columns(recordbatch: &RecordBatch) -> Result<(&VectorRef, &VectorRef)> {
     let ts_column = recordbatch.column_by_name(GREPTIME_TIMESTAMP).context(
         error::InvalidPromRemoteReadQueryResultSnafu {
             msg: "missing greptime_timestamp column in query result",
@@ -269,6 +476,29 @@ fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Ve
         }
     );
 
+    Ok((ts_column, field_column))
+}
+
+/// Returns the sample of the `row`, or `None` if the timestamp or value is null.
+fn row_sample(ts_column: &VectorRef, field_column: &VectorRef, row: usize) -> Option<Sample> {
+    if ts_column.is_null(row) || field_column.is_null(row) {
+        return None;
+    }
+
+    let value: f64 = match field_column.get(row) {
+        Value::Float64(value) => value.into(),
+        _ => unreachable!("checked by the \"ensure\" in sample_columns()"),
+    };
+    let timestamp = match ts_column.get(row) {
+        Value::Timestamp(t) if t.unit() == TimeUnit::Millisecond => t.value(),
+        _ => unreachable!("checked by the \"ensure\" in sample_columns()"),
+    };
+    Some(Sample { value, timestamp })
+}
+
+fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Vec<TimeSeries>> {
+    let (ts_column, field_column) = sample_columns(&recordbatch)?;
+
     // First, collect each row's timeseries id
     let timeseries_ids = collect_timeseries_ids(table, &recordbatch);
     // Then, group timeseries by it's id.
@@ -282,24 +512,193 @@ fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Ve
                 ..Default::default()
             });
 
-        if ts_column.is_null(row) || field_column.is_null(row) {
-            continue;
+        if let Some(sample) = row_sample(ts_column, field_column, row) {
+            timeseries.samples.push(sample);
         }
+    }
 
-        let value: f64 = match field_column.get(row) {
-            Value::Float64(value) => value.into(),
-            _ => unreachable!("checked by the \"ensure\" above"),
-        };
-        let timestamp = match ts_column.get(row) {
-            Value::Timestamp(t) if t.unit() == TimeUnit::Millisecond => t.value(),
-            _ => unreachable!("checked by the \"ensure\" above"),
-        };
-        let sample = Sample { value, timestamp };
+    Ok(timeseries_map.into_values().collect())
+}
+
+/// Converts the result of a series query to timeseries without samples.
+pub fn recordbatches_to_series(table_name: &str, recordbatches: RecordBatches) -> Vec<TimeSeries> {
+    let series_ids = recordbatches
+        .take()
+        .iter()
+        .flat_map(|recordbatch| collect_timeseries_ids(table_name, recordbatch))
+        .collect::<BTreeSet<_>>();
+
+    series_ids
+        .into_iter()
+        .map(|id| TimeSeries {
+            labels: id.labels,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Encodes samples of the `timeseries` into XOR chunks.
+pub fn timeseries_to_chunked_series(timeseries: TimeSeries) -> ChunkedSeries {
+    let TimeSeries {
+        labels,
+        mut samples,
+        ..
+    } = timeseries;
+    samples.sort_unstable_by_key(|sample| sample.timestamp);
+
+    let chunks = samples
+        .chunks(MAX_SAMPLES_PER_CHUNK)
+        .map(|samples| {
+            let mut encoder = XorChunkEncoder::default();
+            for sample in samples {
+                encoder.append(sample);
+            }
+            Chunk {
+                // Safety: slices returned by `chunks()` are not empty.
+                min_time_ms: samples.first().unwrap().timestamp,
+                max_time_ms: samples.last().unwrap().timestamp,
+                r#type: chunk::Encoding::Xor as i32,
+                data: encoder.finish(),
+            }
+        })
+        .collect();
+
+    ChunkedSeries { labels, chunks }
+}
+
+/// Appends the `response` to `buf` as a frame of the streamed remote read response.
+///
+/// A frame consists of the uvarint length of the message, the CRC32 (Castagnoli) checksum
+/// of the message in big-endian, and the message itself.
+pub fn encode_chunked_frame(response: &ChunkedReadResponse, buf: &mut Vec<u8>) {
+    let data = response.encode_to_vec();
+    prost::encoding::encode_varint(data.len() as u64, buf);
+    buf.extend_from_slice(&crc32c::crc32c(&data).to_be_bytes());
+    buf.extend_from_slice(&data);
+}
+
+/// Encodes the result `stream` of the query at `query_index` into frames of the streamed
+/// remote read response, while the stream is read.
+///
+/// Rows of a series must be adjacent in the stream, see [query_to_series_ordered_plan].
+/// A series is encoded once its last row is read, and a frame is written once its series
+/// reach [MAX_BYTES_IN_FRAME], so only one series and one frame are buffered. The stream
+/// ends with an error if the query fails halfway.
+pub fn chunked_frame_stream(
+    table_name: String,
+    query_index: i64,
+    series_only: bool,
+    stream: SendableRecordBatchStream,
+) -> BoxStream<'static, Result<Bytes>> {
+    let encoder = ChunkedFrameEncoder {
+        table_name,
+        query_index,
+        series_only,
+        series: None,
+        chunked_series: Vec::new(),
+        frame_bytes: 0,
+    };
+    stream::unfold(Some((stream, encoder)), |state| async move {
+        let (mut stream, mut encoder) = state?;
+        loop {
+            let batch = match stream.next().await {
+                Some(batch) => batch,
+                None => return encoder.finish().map(|frames| (Ok(frames), None)),
+            };
+            match batch
+                .context(error::CollectRecordbatchSnafu)
+                .and_then(|batch| encoder.push(&batch))
+            {
+                Ok(Some(frames)) => return Some((Ok(frames), Some((stream, encoder)))),
+                Ok(None) => continue,
+                Err(e) => return Some((Err(e), None)),
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Encodes series of a query result into frames of the streamed remote read response.
+struct ChunkedFrameEncoder {
+    table_name: String,
+    query_index: i64,
+    /// Whether rows are label sets without samples.
+    series_only: bool,
+    /// The series whose rows are being read.
+    series: Option<TimeSeries>,
+    /// Encoded series of the next frame.
+    chunked_series: Vec<ChunkedSeries>,
+    frame_bytes: usize,
+}
 
-        timeseries.samples.push(sample);
+impl ChunkedFrameEncoder {
+    /// Reads rows of the `recordbatch`, returns the frames completed by them.
+    fn push(&mut self, recordbatch: &RecordBatch) -> Result<Option<Bytes>> {
+        let mut buf = Vec::new();
+        let timeseries_ids = collect_timeseries_ids(&self.table_name, recordbatch);
+        if self.series_only {
+            for timeseries_id in timeseries_ids {
+                let series = TimeSeries {
+                    labels: timeseries_id.labels,
+                    ..Default::default()
+                };
+                self.finish_series(series, &mut buf);
+            }
+        } else {
+            let (ts_column, field_column) = sample_columns(recordbatch)?;
+            for (row, timeseries_id) in timeseries_ids.into_iter().enumerate() {
+                if self
+                    .series
+                    .as_ref()
+                    .map_or(true, |series| series.labels != timeseries_id.labels)
+                {
+                    let next = TimeSeries {
+                        labels: timeseries_id.labels,
+                        ..Default::default()
+                    };
+                    if let Some(series) = self.series.replace(next) {
+                        self.finish_series(series, &mut buf);
+                    }
+                }
+                if let Some(sample) = row_sample(ts_column, field_column, row) {
+                    // Safety: the series is set above.
+                    self.series.as_mut().unwrap().samples.push(sample);
+                }
+            }
+        }
+        Ok((!buf.is_empty()).then(|| buf.into()))
     }
 
-    Ok(timeseries_map.into_values().collect())
+    /// Encodes the rest series, returns the remaining frames.
+    fn finish(mut self) -> Option<Bytes> {
+        let mut buf = Vec::new();
+        if let Some(series) = self.series.take() {
+            self.finish_series(series, &mut buf);
+        }
+        self.flush(&mut buf);
+        (!buf.is_empty()).then(|| buf.into())
+    }
+
+    fn finish_series(&mut self, series: TimeSeries, buf: &mut Vec<u8>) {
+        let series = timeseries_to_chunked_series(series);
+        self.frame_bytes += series.encoded_len();
+        self.chunked_series.push(series);
+        if self.frame_bytes >= MAX_BYTES_IN_FRAME {
+            self.flush(buf);
+        }
+    }
+
+    fn flush(&mut self, buf: &mut Vec<u8>) {
+        if self.chunked_series.is_empty() {
+            return;
+        }
+        let response = ChunkedReadResponse {
+            chunked_series: std::mem::take(&mut self.chunked_series),
+            query_index: self.query_index,
+        };
+        encode_chunked_frame(&response, buf);
+        self.frame_bytes = 0;
+    }
 }
 
 pub fn to_grpc_row_insert_requests(request: &WriteRequest) -> Result<(RowInsertRequests, usize)> {
@@ -473,7 +872,7 @@ pub fn mock_timeseries() -> Vec<TimeSeries> {
 mod tests {
     use std::sync::Arc;
 
-    use api::prom_store::remote::LabelMatcher;
+    use api::prom_store::remote::{LabelMatcher, ReadHints};
     use api::v1::{ColumnDataType, Row, SemanticType};
     use datafusion::prelude::SessionContext;
     use datatypes::schema::{ColumnSchema, Schema};
@@ -486,6 +885,7 @@ mod tests {
     const EQ_TYPE: i32 = MatcherType::Eq as i32;
     const NEQ_TYPE: i32 = MatcherType::Neq as i32;
     const RE_TYPE: i32 = MatcherType::Re as i32;
+    const NRE_TYPE: i32 = MatcherType::Nre as i32;
 
     #[test]
     fn test_table_name() {
@@ -566,7 +966,7 @@ mod tests {
                 },
                 LabelMatcher {
                     name: "job".to_string(),
-                    value: "*prom*".to_string(),
+                    value: ".*prom.*".to_string(),
                     r#type: RE_TYPE,
                 },
                 LabelMatcher {
@@ -578,11 +978,196 @@ mod tests {
             ..Default::default()
         };
 
-        let dataframe = ctx.read_table(table_provider).unwrap();
+        let dataframe = ctx.read_table(table_provider.clone()).unwrap();
+        let plan = query_to_plan(DataFrame::DataFusion(dataframe), &q).unwrap();
+        let display_string = format!("{}", plan.display_indent());
+
+        assert_eq!("Filter: ?table?.greptime_timestamp >= TimestampMillisecond(1000, None) AND ?table?.greptime_timestamp <= TimestampMillisecond(2000, None) AND ?table?.job ~ Utf8(\"^(?:.*prom.*)$\") AND (?table?.instance != Utf8(\"localhost\") OR ?table?.instance IS NULL)\n  TableScan: ?table?", display_string);
+
+        // Literal alternations, missing labels and hints.
+        let q = Query {
+            start_timestamp_ms: 1000,
+            end_timestamp_ms: 2000,
+            matchers: vec![
+                LabelMatcher {
+                    name: METRIC_NAME_LABEL.to_string(),
+                    value: "test".to_string(),
+                    r#type: EQ_TYPE,
+                },
+                LabelMatcher {
+                    name: "job".to_string(),
+                    value: "a|b".to_string(),
+                    r#type: NRE_TYPE,
+                },
+                LabelMatcher {
+                    name: "region".to_string(),
+                    value: "".to_string(),
+                    r#type: EQ_TYPE,
+                },
+            ],
+            hints: Some(ReadHints {
+                start_ms: 1500,
+                func: "series".to_string(),
+                ..Default::default()
+            }),
+        };
+
+        let dataframe = ctx.read_table(table_provider.clone()).unwrap();
+        let plan = query_to_plan(DataFrame::DataFusion(dataframe), &q).unwrap();
+        let display_string = format!("{}", plan.display_indent());
+
+        assert_eq!("Distinct:\n  Projection: ?table?.instance, ?table?.job\n    Filter: ?table?.greptime_timestamp >= TimestampMillisecond(1500, None) AND ?table?.greptime_timestamp <= TimestampMillisecond(2000, None) AND (?table?.job NOT IN ([Utf8(\"a\"), Utf8(\"b\")]) OR ?table?.job IS NULL)\n      TableScan: ?table?", display_string);
+
+        // A missing label never matches non-empty values.
+        let q = Query {
+            start_timestamp_ms: 1000,
+            end_timestamp_ms: 2000,
+            matchers: vec![
+                LabelMatcher {
+                    name: METRIC_NAME_LABEL.to_string(),
+                    value: "test".to_string(),
+                    r#type: EQ_TYPE,
+                },
+                LabelMatcher {
+                    name: "region".to_string(),
+                    value: "us-.*".to_string(),
+                    r#type: RE_TYPE,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let dataframe = ctx.read_table(table_provider.clone()).unwrap();
         let plan = query_to_plan(DataFrame::DataFusion(dataframe), &q).unwrap();
         let display_string = format!("{}", plan.display_indent());
 
-        assert_eq!("Filter: ?table?.greptime_timestamp >= TimestampMillisecond(1000, None) AND ?table?.greptime_timestamp <= TimestampMillisecond(2000, None) AND regexp_match(?table?.job, Utf8(\"*prom*\")) IS NOT NULL AND ?table?.instance != Utf8(\"localhost\")\n  TableScan: ?table?", display_string);
+        assert_eq!("Filter: ?table?.greptime_timestamp >= TimestampMillisecond(1000, None) AND ?table?.greptime_timestamp <= TimestampMillisecond(2000, None) AND Boolean(false)\n  TableScan: ?table?", display_string);
+
+        // Invalid regexp.
+        let q = Query {
+            start_timestamp_ms: 1000,
+            end_timestamp_ms: 2000,
+            matchers: vec![LabelMatcher {
+                name: "job".to_string(),
+                value: "*prom*".to_string(),
+                r#type: RE_TYPE,
+            }],
+            ..Default::default()
+        };
+        let dataframe = ctx.read_table(table_provider).unwrap();
+        let err = query_to_plan(DataFrame::DataFusion(dataframe), &q).unwrap_err();
+        assert!(matches!(err, error::Error::InvalidPromRemoteRequest { .. }));
+    }
+
+    #[test]
+    fn test_series_query_to_plan() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("idc", ConcreteDataType::string_datatype(), true),
+        ]));
+        let recordbatch = RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1000])) as _,
+                Arc::new(Float64Vector::from_vec(vec![3.0])) as _,
+                Arc::new(StringVector::from(vec!["host1"])) as _,
+                Arc::new(StringVector::from(vec!["idc1"])) as _,
+            ],
+        )
+        .unwrap();
+        let ctx = SessionContext::new();
+        let table_provider = Arc::new(DfTableProviderAdapter::new(MemTable::table(
+            "demo",
+            recordbatch,
+        )));
+        let tag_columns = vec!["host".to_string(), "idc".to_string()];
+
+        let mut q = Query {
+            start_timestamp_ms: 1000,
+            end_timestamp_ms: 2000,
+            matchers: vec![
+                LabelMatcher {
+                    name: METRIC_NAME_LABEL.to_string(),
+                    value: "demo".to_string(),
+                    r#type: EQ_TYPE,
+                },
+                LabelMatcher {
+                    name: "host".to_string(),
+                    value: "host1".to_string(),
+                    r#type: EQ_TYPE,
+                },
+            ],
+            hints: Some(ReadHints {
+                func: "series".to_string(),
+                ..Default::default()
+            }),
+        };
+        let dataframe = ctx.read_table(table_provider.clone()).unwrap();
+        let plan =
+            series_query_to_plan(DataFrame::DataFusion(dataframe), &q, "ts", &tag_columns).unwrap();
+        assert_eq!("Distinct:\n  Projection: ?table?.host, ?table?.idc\n    Filter: ?table?.ts >= TimestampMillisecond(1000, None) AND ?table?.ts <= TimestampMillisecond(2000, None) AND ?table?.host = Utf8(\"host1\")\n      TableScan: ?table?", format!("{}", plan.display_indent()));
+
+        // Only queries labels in the grouping.
+        q.hints = Some(ReadHints {
+            func: "series".to_string(),
+            by: true,
+            grouping: vec!["idc".to_string(), "unknown".to_string()],
+            ..Default::default()
+        });
+        let dataframe = ctx.read_table(table_provider).unwrap();
+        let plan =
+            series_query_to_plan(DataFrame::DataFusion(dataframe), &q, "ts", &tag_columns).unwrap();
+        assert_eq!("Distinct:\n  Projection: ?table?.idc\n    Filter: ?table?.ts >= TimestampMillisecond(1000, None) AND ?table?.ts <= TimestampMillisecond(2000, None) AND ?table?.host = Utf8(\"host1\")\n      TableScan: ?table?", format!("{}", plan.display_indent()));
+    }
+
+    #[test]
+    fn test_timeseries_to_chunked_series() {
+        let samples = (0..250)
+            .rev()
+            .map(|i| Sample {
+                value: i as f64,
+                timestamp: i * 1000,
+            })
+            .collect();
+        let series = timeseries_to_chunked_series(TimeSeries {
+            labels: vec![new_label(
+                METRIC_NAME_LABEL.to_string(),
+                "metric1".to_string(),
+            )],
+            samples,
+            ..Default::default()
+        });
+
+        assert_eq!(1, series.labels.len());
+        let ranges = series
+            .chunks
+            .iter()
+            .map(|chunk| (chunk.min_time_ms, chunk.max_time_ms))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(0, 119_000), (120_000, 239_000), (240_000, 249_000)],
+            ranges
+        );
+        // The first 2 bytes are the number of samples.
+        assert_eq!(&[0, 10], &series.chunks[2].data[..2]);
+
+        let response = ChunkedReadResponse {
+            chunked_series: vec![series],
+            query_index: 0,
+        };
+        let mut buf = Vec::new();
+        encode_chunked_frame(&response, &mut buf);
+        let len = prost::encoding::decode_varint(&mut &buf[..]).unwrap() as usize;
+        let data = &buf[buf.len() - len..];
+        let checksum = &buf[buf.len() - len - 4..buf.len() - len];
+        assert_eq!(&crc32c::crc32c(data).to_be_bytes(), checksum);
+        assert_eq!(response, ChunkedReadResponse::decode(data).unwrap());
     }
 
     fn column_schemas_with(
@@ -808,4 +1393,71 @@ mod tests {
             }]
         );
     }
+
+    #[tokio::test]
+    async fn test_chunked_frame_stream() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new(
+                GREPTIME_TIMESTAMP,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new(GREPTIME_VALUE, ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("instance", ConcreteDataType::string_datatype(), true),
+        ]));
+        // Rows of host1 span both batches.
+        let recordbatches = RecordBatches::try_new(
+            schema.clone(),
+            vec![
+                RecordBatch::new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(TimestampMillisecondVector::from_vec(vec![1000, 2000])) as _,
+                        Arc::new(Float64Vector::from_vec(vec![1.0, 2.0])) as _,
+                        Arc::new(StringVector::from(vec!["host1", "host1"])) as _,
+                    ],
+                )
+                .unwrap(),
+                RecordBatch::new(
+                    schema,
+                    vec![
+                        Arc::new(TimestampMillisecondVector::from_vec(vec![3000, 1000])) as _,
+                        Arc::new(Float64Vector::from_vec(vec![3.0, 7.0])) as _,
+                        Arc::new(StringVector::from(vec!["host1", "host2"])) as _,
+                    ],
+                )
+                .unwrap(),
+            ],
+        )
+        .unwrap();
+
+        let chunks =
+            chunked_frame_stream("metric1".to_string(), 1, false, recordbatches.as_stream())
+                .collect::<Vec<_>>()
+                .await;
+        // Both series are small enough to be in the last frame.
+        assert_eq!(1, chunks.len());
+        let buf = chunks.into_iter().next().unwrap().unwrap();
+        let len = prost::encoding::decode_varint(&mut &buf[..]).unwrap() as usize;
+        let response = ChunkedReadResponse::decode(&buf[buf.len() - len..]).unwrap();
+
+        assert_eq!(1, response.query_index);
+        let series = response
+            .chunked_series
+            .iter()
+            .map(|series| {
+                let chunk = &series.chunks[0];
+                (
+                    series.labels[1].value.as_str(),
+                    series.chunks.len(),
+                    chunk.min_time_ms,
+                    chunk.max_time_ms,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("host1", 1, 1000, 3000), ("host2", 1, 1000, 1000)],
+            series
+        );
+    }
 }
@@ -16,6 +16,7 @@
 
 use std::sync::Arc;
 
+use api::prom_store::remote::Query;
 use async_trait::async_trait;
 use catalog::CatalogManagerRef;
 use common_query::Output;
@@ -32,5 +33,14 @@ pub type PrometheusHandlerRef = Arc<dyn PrometheusHandler + Send + Sync>;
 pub trait PrometheusHandler {
     async fn do_query(&self, query: &PromQuery, query_ctx: QueryContextRef) -> Result<Output>;
 
+    /// Queries exemplars of the metric selected by the remote read `query`, the
+    /// time range and label matchers of the query are pushed down to the scan.
+    async fn query_exemplars(&self, query: &Query, query_ctx: QueryContextRef) -> Result<Output>;
+
+    /// Queries distinct label sets of series in the metric selected by the remote read
+    /// `query`, see [series_query_to_plan](crate::prom_store::series_query_to_plan).
+    /// It scans tag columns directly instead of evaluating PromQL.
+    async fn query_series(&self, query: &Query, query_ctx: QueryContextRef) -> Result<Output>;
+
     fn catalog_manager(&self) -> CatalogManagerRef;
 }
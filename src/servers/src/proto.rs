@@ -12,18 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::slice;
 
-use api::prom_store::remote::Sample;
+use api::prom_store::remote::{Label, Sample};
 use api::v1::RowInsertRequests;
 use bytes::{Buf, Bytes};
+use common_query::native_histogram::{BucketSpan, NativeHistogram};
 use prost::encoding::message::merge;
 use prost::encoding::{decode_key, decode_varint, WireType};
 use prost::DecodeError;
 
-use crate::prom_row_builder::TablesBuilder;
-use crate::prom_store::METRIC_NAME_LABEL_BYTES;
+use crate::prom_relabel::RelabelRules;
+use crate::prom_row_builder::{TableKind, TablesBuilder};
+use crate::prom_store::{exemplar_table_name, METRIC_NAME_LABEL, METRIC_NAME_LABEL_BYTES};
 use crate::repeated_field::{Clear, RepeatedField};
 
 impl Clear for Sample {
@@ -76,6 +79,103 @@ impl PromLabel {
     }
 }
 
+/// Native histogram in Prometheus remote write protocol.
+///
+/// Integer histograms encode bucket counts as deltas to the previous bucket
+/// while float histograms carry absolute counts.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PromHistogram {
+    #[prost(uint64, optional, tag = "1")]
+    pub count_int: Option<u64>,
+    #[prost(double, optional, tag = "2")]
+    pub count_float: Option<f64>,
+    #[prost(double, tag = "3")]
+    pub sum: f64,
+    #[prost(sint32, tag = "4")]
+    pub schema: i32,
+    #[prost(double, tag = "5")]
+    pub zero_threshold: f64,
+    #[prost(uint64, optional, tag = "6")]
+    pub zero_count_int: Option<u64>,
+    #[prost(double, optional, tag = "7")]
+    pub zero_count_float: Option<f64>,
+    #[prost(message, repeated, tag = "8")]
+    pub negative_spans: Vec<BucketSpan>,
+    #[prost(sint64, repeated, tag = "9")]
+    pub negative_deltas: Vec<i64>,
+    #[prost(double, repeated, tag = "10")]
+    pub negative_counts: Vec<f64>,
+    #[prost(message, repeated, tag = "11")]
+    pub positive_spans: Vec<BucketSpan>,
+    #[prost(sint64, repeated, tag = "12")]
+    pub positive_deltas: Vec<i64>,
+    #[prost(double, repeated, tag = "13")]
+    pub positive_counts: Vec<f64>,
+    #[prost(int32, tag = "14")]
+    pub reset_hint: i32,
+    #[prost(int64, tag = "15")]
+    pub timestamp: i64,
+}
+
+impl PromHistogram {
+    /// Converts to [NativeHistogram] with absolute bucket counts.
+    pub fn to_native_histogram(&self) -> NativeHistogram {
+        NativeHistogram {
+            count: self
+                .count_float
+                .unwrap_or_else(|| self.count_int.unwrap_or_default() as f64),
+            sum: self.sum,
+            schema: self.schema,
+            zero_threshold: self.zero_threshold,
+            zero_count: self
+                .zero_count_float
+                .unwrap_or_else(|| self.zero_count_int.unwrap_or_default() as f64),
+            negative_spans: self.negative_spans.clone(),
+            negative_counts: absolute_counts(&self.negative_deltas, &self.negative_counts),
+            positive_spans: self.positive_spans.clone(),
+            positive_counts: absolute_counts(&self.positive_deltas, &self.positive_counts),
+        }
+    }
+}
+
+fn absolute_counts(deltas: &[i64], counts: &[f64]) -> Vec<f64> {
+    if deltas.is_empty() {
+        return counts.to_vec();
+    }
+    let mut current = 0i64;
+    deltas
+        .iter()
+        .map(|delta| {
+            current += delta;
+            current as f64
+        })
+        .collect()
+}
+
+/// Exemplar in Prometheus remote write protocol.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PromExemplar {
+    #[prost(message, repeated, tag = "1")]
+    pub labels: Vec<Label>,
+    #[prost(double, tag = "2")]
+    pub value: f64,
+    #[prost(int64, tag = "3")]
+    pub timestamp: i64,
+}
+
+impl PromExemplar {
+    /// Encodes labels of the exemplar as a JSON object.
+    pub fn labels_to_json(&self) -> String {
+        let labels = self
+            .labels
+            .iter()
+            .map(|label| (label.name.as_str(), label.value.as_str()))
+            .collect::<BTreeMap<_, _>>();
+        // Safety: a map of strings can always be serialized.
+        serde_json::to_string(&labels).unwrap()
+    }
+}
+
 #[inline(always)]
 fn copy_to_bytes(data: &mut Bytes, len: usize) -> Bytes {
     if len == data.remaining() {
@@ -134,6 +234,8 @@ pub struct PromTimeSeries {
     pub table_name: String,
     pub labels: RepeatedField<PromLabel>,
     pub samples: RepeatedField<Sample>,
+    pub histograms: Vec<PromHistogram>,
+    pub exemplars: Vec<PromExemplar>,
 }
 
 impl Clear for PromTimeSeries {
@@ -141,6 +243,8 @@ impl Clear for PromTimeSeries {
         self.table_name.clear();
         self.labels.clear();
         self.samples.clear();
+        self.histograms.clear();
+        self.exemplars.clear();
     }
 }
 
@@ -199,18 +303,124 @@ impl PromTimeSeries {
                 )?;
                 Ok(())
             }
-            // todo(hl): exemplars are skipped temporarily
-            3u32 => prost::encoding::skip_field(wire_type, tag, buf, Default::default()),
+            3u32 => {
+                let mut exemplar = PromExemplar::default();
+                merge(
+                    WireType::LengthDelimited,
+                    &mut exemplar,
+                    buf,
+                    Default::default(),
+                )
+                .map_err(|mut error| {
+                    error.push(STRUCT_NAME, "exemplars");
+                    error
+                })?;
+                self.exemplars.push(exemplar);
+                Ok(())
+            }
+            4u32 => {
+                let mut histogram = PromHistogram::default();
+                merge(
+                    WireType::LengthDelimited,
+                    &mut histogram,
+                    buf,
+                    Default::default(),
+                )
+                .map_err(|mut error| {
+                    error.push(STRUCT_NAME, "histograms");
+                    error
+                })?;
+                self.histograms.push(histogram);
+                Ok(())
+            }
             _ => prost::encoding::skip_field(wire_type, tag, buf, Default::default()),
         }
     }
 
+    /// Applies `relabel_rules` to labels of the series, returns false if the
+    /// series is dropped.
+    fn relabel(
+        &mut self,
+        relabel_rules: &RelabelRules,
+        is_strict_mode: bool,
+    ) -> Result<bool, DecodeError> {
+        let mut labels = Vec::with_capacity(self.labels.len() + 1);
+        labels.push((
+            METRIC_NAME_LABEL.to_string(),
+            std::mem::take(&mut self.table_name),
+        ));
+        for label in self.labels.iter() {
+            labels.push((
+                bytes_to_string(&label.name, is_strict_mode)?,
+                bytes_to_string(&label.value, is_strict_mode)?,
+            ));
+        }
+        if !relabel_rules.apply(&mut labels) {
+            return Ok(false);
+        }
+
+        self.labels.clear();
+        for (name, value) in labels {
+            if name == METRIC_NAME_LABEL {
+                self.table_name = value;
+                continue;
+            }
+            let label = self.labels.push_default();
+            label.name = Bytes::from(name);
+            label.value = Bytes::from(value);
+        }
+        // Series without a metric name can't be stored.
+        Ok(!self.table_name.is_empty())
+    }
+
     fn add_to_table_data(
         &mut self,
         table_builders: &mut TablesBuilder,
+        histogram_builders: &mut TablesBuilder,
+        exemplar_builders: &mut TablesBuilder,
         is_strict_mode: bool,
+        relabel_rules: Option<&RelabelRules>,
     ) -> Result<(), DecodeError> {
+        if let Some(relabel_rules) = relabel_rules.filter(|rules| !rules.is_empty()) {
+            if !self.relabel(relabel_rules, is_strict_mode)? {
+                crate::metrics::PROM_STORE_RELABEL_DROPPED_SERIES.inc();
+                self.clear();
+                return Ok(());
+            }
+        }
+
         let label_num = self.labels.len();
+        if !self.exemplars.is_empty() {
+            let table_data = exemplar_builders.get_or_create_table_builder(
+                exemplar_table_name(&self.table_name),
+                label_num,
+                self.exemplars.len(),
+            );
+            table_data.add_labels_and_exemplars(
+                self.labels.as_slice(),
+                &self.exemplars,
+                is_strict_mode,
+            )?;
+            self.exemplars.clear();
+        }
+        if !self.histograms.is_empty() {
+            let table_data = histogram_builders.get_or_create_table_builder(
+                self.table_name.clone(),
+                label_num,
+                self.histograms.len(),
+            );
+            table_data.add_labels_and_histograms(
+                self.labels.as_slice(),
+                &self.histograms,
+                is_strict_mode,
+            )?;
+            self.histograms.clear();
+            if self.samples.is_empty() {
+                self.clear();
+                return Ok(());
+            }
+        }
+
         let row_num = self.samples.len();
         let table_data = table_builders.get_or_create_table_builder(
             std::mem::take(&mut self.table_name),
@@ -229,15 +439,38 @@ impl PromTimeSeries {
     }
 }
 
-#[derive(Default, Debug)]
+fn bytes_to_string(bytes: &Bytes, is_strict_mode: bool) -> Result<String, DecodeError> {
+    if is_strict_mode {
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::new("invalid utf-8"))
+    } else {
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+#[derive(Debug)]
 pub struct PromWriteRequest {
     table_data: TablesBuilder,
+    histogram_data: TablesBuilder,
+    exemplar_data: TablesBuilder,
     series: PromTimeSeries,
 }
 
+impl Default for PromWriteRequest {
+    fn default() -> Self {
+        Self {
+            table_data: TablesBuilder::default(),
+            histogram_data: TablesBuilder::new(TableKind::Histogram),
+            exemplar_data: TablesBuilder::new(TableKind::Exemplar),
+            series: PromTimeSeries::default(),
+        }
+    }
+}
+
 impl Clear for PromWriteRequest {
     fn clear(&mut self) {
         self.table_data.clear();
+        self.histogram_data.clear();
+        self.exemplar_data.clear();
     }
 }
 
@@ -246,8 +479,28 @@ impl PromWriteRequest {
         self.table_data.as_insert_requests()
     }
 
+    /// Returns insert requests of native histograms and the number of histogram samples.
+    pub fn as_histogram_row_insert_requests(&mut self) -> (RowInsertRequests, usize) {
+        self.histogram_data.as_insert_requests()
+    }
+
+    /// Returns insert requests of exemplars and the number of exemplars.
+    pub fn as_exemplar_row_insert_requests(&mut self) -> (RowInsertRequests, usize) {
+        self.exemplar_data.as_insert_requests()
+    }
+
     // todo(hl): maybe use &[u8] can reduce the overhead introduced with Bytes.
-    pub fn merge(&mut self, mut buf: Bytes, is_strict_mode: bool) -> Result<(), DecodeError> {
+    pub fn merge(&mut self, buf: Bytes, is_strict_mode: bool) -> Result<(), DecodeError> {
+        self.merge_with_relabel(buf, is_strict_mode, None)
+    }
+
+    /// Same as [merge](Self::merge) but applies `relabel_rules` to each series.
+    pub fn merge_with_relabel(
+        &mut self,
+        mut buf: Bytes,
+        is_strict_mode: bool,
+        relabel_rules: Option<&RelabelRules>,
+    ) -> Result<(), DecodeError> {
         const STRUCT_NAME: &str = "PromWriteRequest";
         while buf.has_remaining() {
             let (tag, wire_type) = decode_key(&mut buf)?;
@@ -273,8 +526,13 @@ impl PromWriteRequest {
                     if buf.remaining() != limit {
                         return Err(DecodeError::new("delimited length exceeded"));
                     }
-                    self.series
-                        .add_to_table_data(&mut self.table_data, is_strict_mode)?;
+                    self.series.add_to_table_data(
+                        &mut self.table_data,
+                        &mut self.histogram_data,
+                        &mut self.exemplar_data,
+                        is_strict_mode,
+                        relabel_rules,
+                    )?;
                 }
                 3u32 => {
                     // todo(hl): metadata are skipped.
@@ -291,15 +549,48 @@ impl PromWriteRequest {
 mod tests {
     use std::collections::HashMap;
 
-    use api::prom_store::remote::WriteRequest;
-    use api::v1::{Row, RowInsertRequests, Rows};
+    use api::prom_store::remote::{Label, WriteRequest};
+    use api::v1::value::ValueData;
+    use api::v1::{ColumnDataType, Row, RowInsertRequests, Rows};
     use bytes::Bytes;
+    use common_query::native_histogram::{BucketSpan, NativeHistogram};
     use prost::Message;
 
-    use crate::prom_store::to_grpc_row_insert_requests;
-    use crate::proto::PromWriteRequest;
+    use crate::prom_relabel::{RelabelAction, RelabelConfig, RelabelRules};
+    use crate::prom_store::{mock_timeseries, to_grpc_row_insert_requests};
+    use crate::proto::{PromExemplar, PromHistogram, PromWriteRequest};
     use crate::repeated_field::Clear;
 
+    /// Time series carrying exemplars and native histograms only.
+    #[derive(Clone, PartialEq, Message)]
+    struct TestTimeSeries {
+        #[prost(message, repeated, tag = "1")]
+        labels: Vec<Label>,
+        #[prost(message, repeated, tag = "3")]
+        exemplars: Vec<PromExemplar>,
+        #[prost(message, repeated, tag = "4")]
+        histograms: Vec<PromHistogram>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct TestWriteRequest {
+        #[prost(message, repeated, tag = "1")]
+        timeseries: Vec<TestTimeSeries>,
+    }
+
+    fn test_labels() -> Vec<Label> {
+        vec![
+            Label {
+                name: "__name__".to_string(),
+                value: "http_latency".to_string(),
+            },
+            Label {
+                name: "job".to_string(),
+                value: "api".to_string(),
+            },
+        ]
+    }
+
     fn sort_rows(rows: Rows) -> Rows {
         let permutation =
             permutation::sort_by_key(&rows.schema, |schema| schema.column_name.clone());
@@ -362,4 +653,190 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_decode_write_request_with_relabel() {
+        let data = Bytes::from(
+            WriteRequest {
+                timeseries: mock_timeseries(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        );
+        let relabel_rules = RelabelRules::try_new(&[
+            RelabelConfig {
+                source_labels: vec!["__name__".to_string()],
+                regex: "metric1".to_string(),
+                action: RelabelAction::Drop,
+                ..Default::default()
+            },
+            RelabelConfig {
+                regex: "idc".to_string(),
+                action: RelabelAction::LabelDrop,
+                ..Default::default()
+            },
+        ])
+        .unwrap();
+
+        let mut prom_write_request = PromWriteRequest::default();
+        prom_write_request
+            .merge_with_relabel(data, true, Some(&relabel_rules))
+            .unwrap();
+        let (prom_rows, samples) = prom_write_request.as_row_insert_requests();
+        assert_eq!(5, samples);
+
+        let mut tables = prom_rows
+            .inserts
+            .iter()
+            .map(|insert| {
+                let mut columns = insert
+                    .rows
+                    .as_ref()
+                    .unwrap()
+                    .schema
+                    .iter()
+                    .map(|column| column.column_name.clone())
+                    .collect::<Vec<_>>();
+                columns.sort();
+                (insert.table_name.clone(), columns)
+            })
+            .collect::<Vec<_>>();
+        tables.sort();
+        assert_eq!(
+            vec![
+                (
+                    "metric2".to_string(),
+                    vec![
+                        "greptime_timestamp".to_string(),
+                        "greptime_value".to_string(),
+                        "instance".to_string()
+                    ]
+                ),
+                (
+                    "metric3".to_string(),
+                    vec![
+                        "app".to_string(),
+                        "greptime_timestamp".to_string(),
+                        "greptime_value".to_string()
+                    ]
+                ),
+            ],
+            tables
+        );
+    }
+
+    #[test]
+    fn test_decode_native_histograms() {
+        let histogram = PromHistogram {
+            count_int: Some(6),
+            sum: 10.0,
+            schema: 0,
+            zero_threshold: 0.001,
+            zero_count_int: Some(1),
+            positive_spans: vec![BucketSpan {
+                offset: 0,
+                length: 3,
+            }],
+            // absolute counts: 1, 3, 1
+            positive_deltas: vec![1, 2, -2],
+            timestamp: 1000,
+            ..Default::default()
+        };
+        let data = Bytes::from(
+            TestWriteRequest {
+                timeseries: vec![TestTimeSeries {
+                    labels: test_labels(),
+                    histograms: vec![histogram],
+                    ..Default::default()
+                }],
+            }
+            .encode_to_vec(),
+        );
+
+        let mut prom_write_request = PromWriteRequest::default();
+        prom_write_request.merge(data, true).unwrap();
+        let (prom_rows, samples) = prom_write_request.as_row_insert_requests();
+        assert_eq!(0, samples);
+        assert!(prom_rows.inserts.is_empty());
+
+        let (histogram_rows, samples) = prom_write_request.as_histogram_row_insert_requests();
+        assert_eq!(1, samples);
+        assert_eq!(1, histogram_rows.inserts.len());
+        let insert = &histogram_rows.inserts[0];
+        assert_eq!("http_latency", insert.table_name);
+        let rows = insert.rows.as_ref().unwrap();
+        assert_eq!("greptime_histogram", rows.schema[1].column_name);
+        assert_eq!(ColumnDataType::Binary as i32, rows.schema[1].datatype);
+        assert_eq!("job", rows.schema[2].column_name);
+
+        let values = &rows.rows[0].values;
+        assert_eq!(
+            Some(ValueData::TimestampMillisecondValue(1000)),
+            values[0].value_data
+        );
+        let Some(ValueData::BinaryValue(encoded)) = &values[1].value_data else {
+            unreachable!()
+        };
+        let decoded = NativeHistogram::decode(encoded.as_slice()).unwrap();
+        assert_eq!(6.0, decoded.count);
+        assert_eq!(1.0, decoded.zero_count);
+        assert_eq!(vec![1.0, 3.0, 1.0], decoded.positive_counts);
+    }
+
+    #[test]
+    fn test_decode_exemplars() {
+        let exemplar = PromExemplar {
+            labels: vec![Label {
+                name: "trace_id".to_string(),
+                value: "abc".to_string(),
+            }],
+            value: 0.5,
+            timestamp: 2000,
+        };
+        let data = Bytes::from(
+            TestWriteRequest {
+                timeseries: vec![TestTimeSeries {
+                    labels: test_labels(),
+                    exemplars: vec![exemplar],
+                    ..Default::default()
+                }],
+            }
+            .encode_to_vec(),
+        );
+
+        let mut prom_write_request = PromWriteRequest::default();
+        prom_write_request.merge(data, true).unwrap();
+        let (exemplar_rows, exemplars) = prom_write_request.as_exemplar_row_insert_requests();
+        assert_eq!(1, exemplars);
+        let insert = &exemplar_rows.inserts[0];
+        assert_eq!("http_latency__exemplars", insert.table_name);
+        let rows = insert.rows.as_ref().unwrap();
+        let columns = rows
+            .schema
+            .iter()
+            .map(|column| column.column_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "greptime_timestamp",
+                "greptime_value",
+                "greptime_exemplar_labels",
+                "job"
+            ],
+            columns
+        );
+        assert_eq!(
+            vec![
+                Some(ValueData::TimestampMillisecondValue(2000)),
+                Some(ValueData::F64Value(0.5)),
+                Some(ValueData::StringValue(r#"{"trace_id":"abc"}"#.to_string())),
+                Some(ValueData::StringValue("api".to_string())),
+            ],
+            rows.rows[0]
+                .values
+                .iter()
+                .map(|value| value.value_data.clone())
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -31,8 +31,11 @@ use std::sync::Arc;
 use api::prom_store::remote::ReadRequest;
 use api::v1::RowInsertRequests;
 use async_trait::async_trait;
+use bytes::Bytes;
 use common_query::Output;
+use futures::stream::BoxStream;
 use headers::HeaderValue;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
 use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
 use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
 use serde_json::Value;
@@ -81,9 +84,17 @@ pub trait OpentsdbProtocolHandler {
 
 pub struct PromStoreResponse {
     pub content_type: HeaderValue,
-    pub content_encoding: HeaderValue,
+    /// Streamed responses are not compressed as a whole.
+    pub content_encoding: Option<HeaderValue>,
     pub resp_metrics: HashMap<String, Value>,
-    pub body: Vec<u8>,
+    pub body: PromStoreResponseBody,
+}
+
+/// Body of the [PromStoreResponse].
+pub enum PromStoreResponseBody {
+    Bytes(Vec<u8>),
+    /// Chunks of the body written while the query result is being read.
+    Stream(BoxStream<'static, Result<Bytes>>),
 }
 
 #[async_trait]
@@ -117,4 +128,12 @@ pub trait OpenTelemetryProtocolHandler {
         request: ExportTraceServiceRequest,
         ctx: QueryContextRef,
     ) -> Result<Output>;
+
+    /// Handling opentelemetry logs request, log records are written into `table_name`
+    async fn logs(
+        &self,
+        request: ExportLogsServiceRequest,
+        table_name: String,
+        ctx: QueryContextRef,
+    ) -> Result<Output>;
 }
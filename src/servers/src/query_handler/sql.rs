@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common_catalog::format_full_table_name;
 use common_error::ext::{BoxedError, ErrorExt};
 use common_query::Output;
 use query::parser::PromQuery;
@@ -22,6 +23,7 @@ use query::plan::LogicalPlan;
 use session::context::QueryContextRef;
 use snafu::ResultExt;
 use sql::statements::statement::Statement;
+use table::metadata::TableVersion;
 
 use crate::error::{self, Result};
 
@@ -62,6 +64,15 @@ pub trait SqlQueryHandler {
         catalog: &str,
         schema: &str,
     ) -> std::result::Result<bool, Self::Error>;
+
+    /// Returns the current version of the table's schema, or `None` if the table
+    /// doesn't exist.
+    async fn table_version(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+    ) -> std::result::Result<Option<TableVersion>, Self::Error>;
 }
 
 pub struct ServerSqlQueryHandlerAdapter<E>(SqlQueryHandlerRef<E>);
@@ -138,4 +149,19 @@ where
             .map_err(BoxedError::new)
             .context(error::CheckDatabaseValiditySnafu)
     }
+
+    async fn table_version(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+    ) -> Result<Option<TableVersion>> {
+        self.0
+            .table_version(catalog, schema, table)
+            .await
+            .map_err(BoxedError::new)
+            .with_context(|_| error::GetTableVersionSnafu {
+                table: format_full_table_name(catalog, schema, table),
+            })
+    }
 }
@@ -285,6 +285,45 @@ pub fn write_ts_precision(
     Ok(())
 }
 
+/// Writes the time index `ts` in nanoseconds as is, or the current time if `ts` is `None`.
+pub fn write_ts_nanos(
+    table_data: &mut TableData,
+    name: impl ToString,
+    ts: Option<i64>,
+    one_row: &mut Vec<Value>,
+) -> Result<()> {
+    let TableData {
+        schema,
+        column_indexes,
+        ..
+    } = table_data;
+    let name = name.to_string();
+
+    let ts = ts.unwrap_or_else(|| Timestamp::current_time(TimeUnit::Nanosecond).value());
+
+    let index = column_indexes.get(&name);
+    if let Some(index) = index {
+        check_schema(
+            ColumnDataType::TimestampNanosecond,
+            SemanticType::Timestamp,
+            &schema[*index],
+        )?;
+        one_row[*index].value_data = Some(ValueData::TimestampNanosecondValue(ts));
+    } else {
+        let index = schema.len();
+        schema.push(ColumnSchema {
+            column_name: name.clone(),
+            datatype: ColumnDataType::TimestampNanosecond as i32,
+            semantic_type: SemanticType::Timestamp as i32,
+            ..Default::default()
+        });
+        column_indexes.insert(name, index);
+        one_row.push(ValueData::TimestampNanosecondValue(ts).into())
+    }
+
+    Ok(())
+}
+
 #[inline]
 fn check_schema(
     datatype: ColumnDataType,
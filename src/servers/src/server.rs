@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -26,6 +27,7 @@ use tokio::task::JoinHandle;
 use tokio_stream::wrappers::TcpListenerStream;
 
 use crate::error::{self, Result};
+use crate::grpc::GRPC_SERVER;
 
 pub(crate) type AbortableStream = Abortable<TcpListenerStream>;
 
@@ -81,14 +83,35 @@ impl ServerHandlers {
         // Even though the `shutdown` method in server does not require mut self, we still acquire
         // write lock to pair with `start_all` method.
         let handlers = self.handlers.write().await;
-        try_join_all(handlers.values().map(|(server, _)| async move {
-            server.shutdown().await?;
-            info!("Service {} is shutdown!", server.name());
-            Ok::<(), error::Error>(())
-        }))
-        .await?;
-        Ok(())
+        shutdown_servers(handlers.values()).await
     }
+
+    /// Shutdown all the managed services gracefully, so that restarting the node doesn't
+    /// interrupt running queries.
+    ///
+    /// Services except gRPC stop accepting new connections first. Then it waits for `drain`,
+    /// e.g. in-flight statements to finish, and shuts down the gRPC service at last since
+    /// other nodes may still talk to this node meanwhile.
+    pub async fn shutdown_all_gracefully(&self, drain: impl Future<Output = ()>) -> Result<()> {
+        let handlers = self.handlers.write().await;
+        let (grpc, others): (Vec<_>, Vec<_>) = handlers
+            .values()
+            .partition(|(server, _)| server.name() == GRPC_SERVER);
+
+        shutdown_servers(others).await?;
+        drain.await;
+        shutdown_servers(grpc).await
+    }
+}
+
+async fn shutdown_servers<'a>(handlers: impl IntoIterator<Item = &'a ServerHandler>) -> Result<()> {
+    try_join_all(handlers.into_iter().map(|(server, _)| async move {
+        server.shutdown().await?;
+        info!("Service {} is shutdown!", server.name());
+        Ok::<(), error::Error>(())
+    }))
+    .await?;
+    Ok(())
 }
 
 #[async_trait]
@@ -17,9 +17,11 @@ use std::sync::Arc;
 use auth::tests::MockUserProvider;
 use auth::UserProvider;
 use axum::http;
+use common_meta::key::privilege::{PrivilegeManager, ADMIN_ROLE};
+use common_meta::kv_backend::memory::MemoryKvBackend;
 use http_body::Body;
 use hyper::{Request, StatusCode};
-use servers::http::authorize::inner_auth;
+use servers::http::authorize::{inner_admin_auth, inner_auth, AdminState};
 use session::context::QueryContextRef;
 
 #[tokio::test]
@@ -123,6 +125,49 @@ async fn test_whitelist_no_auth() {
     assert!(req.is_ok());
 }
 
+#[tokio::test]
+async fn test_http_admin() {
+    let uri = "http://localhost/debug/log_level";
+    let mock_user_provider = Some(Arc::new(MockUserProvider::default()) as Arc<dyn UserProvider>);
+    let privilege_manager = PrivilegeManager::new(Arc::new(MemoryKvBackend::default()));
+
+    // Without a user provider, requests aren't authenticated like other APIs.
+    let req = mock_http_request(None, Some(uri)).unwrap();
+    let state = AdminState::new(None, None, Some(privilege_manager.clone()));
+    assert!(inner_admin_auth(state, req).await.is_ok());
+
+    // Unauthenticated requests are rejected.
+    let req = mock_http_request(None, Some(uri)).unwrap();
+    let state = AdminState::new(
+        mock_user_provider.clone(),
+        None,
+        Some(privilege_manager.clone()),
+    );
+    let resp = inner_admin_auth(state.clone(), req).await.unwrap_err();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // base64encode("greptime:greptime") == "Z3JlcHRpbWU6Z3JlcHRpbWU="
+    // Authenticated users are not admins until granted the admin role.
+    let req = mock_http_request(Some("Basic Z3JlcHRpbWU6Z3JlcHRpbWU="), Some(uri)).unwrap();
+    let resp = inner_admin_auth(state.clone(), req).await.unwrap_err();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    privilege_manager
+        .grant_role("greptime", ADMIN_ROLE)
+        .await
+        .unwrap();
+    let req = mock_http_request(Some("Basic Z3JlcHRpbWU6Z3JlcHRpbWU="), Some(uri)).unwrap();
+    assert!(inner_admin_auth(state, req).await.is_ok());
+
+    // Nodes without privileges only authenticate users.
+    let state = AdminState::new(mock_user_provider, None, None);
+    let req = mock_http_request(Some("Basic Z3JlcHRpbWU6Z3JlcHRpbWU="), Some(uri)).unwrap();
+    assert!(inner_admin_auth(state.clone(), req).await.is_ok());
+    let req = mock_http_request(None, Some(uri)).unwrap();
+    let resp = inner_admin_auth(state, req).await.unwrap_err();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
 // copy from http::authorize
 fn mock_http_request(
     auth_header: Option<&str>,
@@ -52,6 +52,7 @@ async fn test_sql_not_provided() {
             sql: None,
             format: Some(format.to_string()),
             epoch: None,
+            stream: None,
         };
 
         let HttpResponse::Error(resp) = http_handler::sql(
@@ -490,6 +491,7 @@ fn create_query(format: &str) -> Query<http_handler::SqlQuery> {
         db: None,
         format: Some(format.to_string()),
         epoch: None,
+        stream: None,
     })
 }
 
@@ -499,6 +501,7 @@ fn create_form(format: &str) -> Form<http_handler::SqlQuery> {
         db: None,
         format: Some(format.to_string()),
         epoch: None,
+        stream: None,
     })
 }
 
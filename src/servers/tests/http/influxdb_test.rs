@@ -33,6 +33,7 @@ use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::InfluxdbLineProtocolHandler;
 use session::context::QueryContextRef;
+use table::metadata::TableVersion;
 use tokio::sync::mpsc;
 
 struct DummyInstance {
@@ -102,6 +103,15 @@ impl SqlQueryHandler for DummyInstance {
     async fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
+
+    async fn table_version(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _table: &str,
+    ) -> Result<Option<TableVersion>> {
+        Ok(None)
+    }
 }
 
 fn make_test_app(tx: Arc<mpsc::Sender<(String, String)>>, db_name: Option<&str>) -> Router {
@@ -337,6 +347,28 @@ async fn test_influxdb_write_v2() {
     assert_eq!(result.status(), 204);
     assert!(result.text().await.is_empty());
 
+    // wrong pwd, responds with influxdb v2 error payload
+    let result = public_db_client
+        .post("/v1/influxdb/api/v2/write?bucket=public")
+        .body("monitor,host=host1 cpu=1.2 1664370459457010101")
+        .header(http::header::AUTHORIZATION, "token greptime:wrongpwd")
+        .send()
+        .await;
+    assert_eq!(result.status(), 401);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(body["code"], "unauthorized");
+
+    // invalid precision
+    let result = public_db_client
+        .post("/v1/influxdb/api/v2/write?bucket=public&precision=xx")
+        .body("monitor,host=host1 cpu=1.2 1664370459457010101")
+        .header(http::header::AUTHORIZATION, "token greptime:greptime")
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(body["code"], "invalid");
+
     let mut metrics = vec![];
     while let Ok(s) = rx.try_recv() {
         metrics.push(s);
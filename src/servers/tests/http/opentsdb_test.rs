@@ -30,6 +30,7 @@ use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::OpentsdbProtocolHandler;
 use session::context::QueryContextRef;
+use table::metadata::TableVersion;
 use tokio::sync::mpsc;
 
 struct DummyInstance {
@@ -99,6 +100,15 @@ impl SqlQueryHandler for DummyInstance {
     async fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
+
+    async fn table_version(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _table: &str,
+    ) -> Result<Option<TableVersion>> {
+        Ok(None)
+    }
 }
 
 fn make_test_app(tx: mpsc::Sender<String>) -> Router {
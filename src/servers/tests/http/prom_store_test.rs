@@ -35,8 +35,9 @@ use servers::prom_store;
 use servers::prom_store::{snappy_compress, Metrics};
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
-use servers::query_handler::{PromStoreProtocolHandler, PromStoreResponse};
+use servers::query_handler::{PromStoreProtocolHandler, PromStoreResponse, PromStoreResponseBody};
 use session::context::QueryContextRef;
+use table::metadata::TableVersion;
 use tokio::sync::mpsc;
 
 struct DummyInstance {
@@ -81,9 +82,9 @@ impl PromStoreProtocolHandler for DummyInstance {
 
         Ok(PromStoreResponse {
             content_type: CONTENT_TYPE_PROTOBUF.clone(),
-            content_encoding: CONTENT_ENCODING_SNAPPY.clone(),
+            content_encoding: Some(CONTENT_ENCODING_SNAPPY.clone()),
             resp_metrics: Default::default(),
-            body: response.encode_to_vec(),
+            body: PromStoreResponseBody::Bytes(response.encode_to_vec()),
         })
     }
 
@@ -127,6 +128,15 @@ impl SqlQueryHandler for DummyInstance {
     async fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
+
+    async fn table_version(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _table: &str,
+    ) -> Result<Option<TableVersion>> {
+        Ok(None)
+    }
 }
 
 fn make_test_app(tx: mpsc::Sender<(String, Vec<u8>)>) -> Router {
@@ -139,7 +149,7 @@ fn make_test_app(tx: mpsc::Sender<(String, Vec<u8>)>) -> Router {
     let instance = Arc::new(DummyInstance { tx });
     let server = HttpServerBuilder::new(http_opts)
         .with_sql_handler(instance.clone(), None)
-        .with_prom_handler(instance, true, is_strict_mode)
+        .with_prom_handler(instance, true, is_strict_mode, Arc::default())
         .build();
     server.build(server.make_app())
 }
@@ -34,6 +34,7 @@ use servers::query_handler::{ScriptHandler, ScriptHandlerRef};
 use session::context::QueryContextRef;
 use snafu::ensure;
 use sql::statements::statement::Statement;
+use table::metadata::TableVersion;
 use table::TableRef;
 
 mod grpc;
@@ -112,6 +113,15 @@ impl SqlQueryHandler for DummyInstance {
     async fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         Ok(catalog == DEFAULT_CATALOG_NAME && schema == DEFAULT_SCHEMA_NAME)
     }
+
+    async fn table_version(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _table: &str,
+    ) -> Result<Option<TableVersion>> {
+        Ok(None)
+    }
 }
 
 #[async_trait]
@@ -120,7 +120,7 @@ pub fn all_datatype_testing_data() -> TestingData {
         ColumnType::MYSQL_TYPE_LONGLONG,
         ColumnType::MYSQL_TYPE_FLOAT,
         ColumnType::MYSQL_TYPE_DOUBLE,
-        ColumnType::MYSQL_TYPE_VARCHAR,
+        ColumnType::MYSQL_TYPE_BLOB,
         ColumnType::MYSQL_TYPE_VARCHAR,
     ];
     let columns: Vec<VectorRef> = vec![
@@ -357,6 +357,29 @@ async fn test_extended_query() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_prepared_statement_plan_cache() -> Result<()> {
+    let server_port = start_test_server(TlsOption::default()).await?;
+    let client = create_connection_with_given_db(server_port, DEFAULT_SCHEMA_NAME)
+        .await
+        .unwrap();
+    let sql = "SELECT uint32s FROM numbers WHERE uint32s = $1";
+    for i in 0..3 {
+        // The second and later prepares hit the cached plan.
+        let stmt = client.prepare_typed(sql, &[Type::INT4]).await.unwrap();
+        let rows = client.query(&stmt, &[&i]).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<usize, i32>(0usize), i);
+    }
+
+    let _ = client.simple_query("DEALLOCATE ALL").await.unwrap();
+    let stmt = client.prepare_typed(sql, &[Type::INT4]).await.unwrap();
+    let rows = client.query(&stmt, &[&5i32]).await.unwrap();
+    assert_eq!(rows[0].get::<usize, i32>(0usize), 5);
+
+    Ok(())
+}
+
 async fn start_test_server(server_tls: TlsOption) -> Result<u16> {
     common_telemetry::init_default_ut_logging();
     let table = MemTable::default_numbers_table();
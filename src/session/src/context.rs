@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use api::v1::region::RegionRequestHeader;
 use arc_swap::ArcSwap;
@@ -27,7 +28,7 @@ use common_time::Timezone;
 use derive_builder::Builder;
 use sql::dialect::{Dialect, GreptimeDbDialect, MySqlDialect, PostgreSqlDialect};
 
-use crate::session_config::{PGByteaOutputValue, PGDateOrder, PGDateTimeStyle};
+use crate::session_config::{PGByteaOutputValue, PGDateOrder, PGDateTimeStyle, TypeCoercionMode};
 use crate::SessionRef;
 
 pub type QueryContextRef = Arc<QueryContext>;
@@ -45,9 +46,21 @@ pub struct QueryContext {
     sql_dialect: Arc<dyn Dialect + Send + Sync>,
     #[builder(default)]
     extension: HashMap<String, String>,
+    /// Structured per-request state attached by plugins.
+    #[builder(setter(custom), default)]
+    typed_extensions: TypedExtensions,
     // The configuration parameter are used to store the parameters that are set by the user
     #[builder(default)]
     configuration_parameter: Arc<ConfigurationVariables>,
+    /// Connection of the session, only set for persistent connections.
+    #[builder(setter(strip_option), default)]
+    conn_info: Option<ConnInfoRef>,
+    /// The protocol the request comes from.
+    #[builder(default)]
+    channel: Channel,
+    /// Warnings raised while executing the query, reported back to the client.
+    #[builder(setter(skip), default)]
+    warnings: Arc<RwLock<Vec<String>>>,
 }
 
 impl QueryContextBuilder {
@@ -55,6 +68,14 @@ impl QueryContextBuilder {
         self.timezone = Some(ArcSwap::new(tz));
         self
     }
+
+    pub fn typed_extension<T: Any + Send + Sync>(mut self, value: Arc<T>) -> Self {
+        let _ = self
+            .typed_extensions
+            .get_or_insert_with(TypedExtensions::default)
+            .insert(value);
+        self
+    }
 }
 
 impl Display for QueryContext {
@@ -77,14 +98,40 @@ impl Clone for QueryContext {
             timezone: self.timezone.load().clone().into(),
             sql_dialect: self.sql_dialect.clone(),
             extension: self.extension.clone(),
+            typed_extensions: self.typed_extensions.clone(),
             configuration_parameter: self.configuration_parameter.clone(),
+            conn_info: self.conn_info.clone(),
+            channel: self.channel,
+            warnings: self.warnings.clone(),
         }
     }
 }
 
+/// Key of the memory budget in bytes of a query in the string map of a region request
+/// header. See `store_api::region_request` for the options the region protocol can't
+/// carry yet.
+pub const QUERY_MEMORY_LIMIT_KEY: &str = "x-greptime-query-memory-limit";
+
+/// Key of the sample frequency per second in the string map of a region request header,
+/// asking the datanode to profile the query.
+pub const PROFILE_FREQUENCY_KEY: &str = "x-greptime-profile-frequency";
+
+/// Key in the string map of a region request header, asking the datanode to return
+/// the metrics of each operator of the plan it executes for the query.
+pub const PLAN_METRICS_KEY: &str = "x-greptime-plan-metrics";
+
 impl From<&RegionRequestHeader> for QueryContext {
     fn from(value: &RegionRequestHeader) -> Self {
         let (catalog, schema) = parse_catalog_and_schema_from_db_string(&value.dbname);
+        let configuration_parameter = ConfigurationVariables::default();
+        // Datanodes still bound the budget by their own max budget.
+        if let Some(limit) = value
+            .tracing_context
+            .get(QUERY_MEMORY_LIMIT_KEY)
+            .and_then(|limit| limit.parse().ok())
+        {
+            configuration_parameter.set_query_memory_limit(Some(limit));
+        }
         QueryContext {
             current_catalog: catalog.to_string(),
             current_schema: schema.to_string(),
@@ -93,7 +140,11 @@ impl From<&RegionRequestHeader> for QueryContext {
             timezone: ArcSwap::new(Arc::new(get_timezone(None).clone())),
             sql_dialect: Arc::new(GreptimeDbDialect {}),
             extension: Default::default(),
-            configuration_parameter: Default::default(),
+            typed_extensions: Default::default(),
+            configuration_parameter: Arc::new(configuration_parameter),
+            conn_info: None,
+            channel: Channel::Unknown,
+            warnings: Default::default(),
         }
     }
 }
@@ -140,6 +191,10 @@ impl QueryContext {
         &*self.sql_dialect
     }
 
+    pub fn set_sql_dialect(&mut self, sql_dialect: Arc<dyn Dialect + Send + Sync>) {
+        self.sql_dialect = sql_dialect;
+    }
+
     pub fn get_db_string(&self) -> String {
         let catalog = self.current_catalog();
         let schema = self.current_schema();
@@ -150,6 +205,15 @@ impl QueryContext {
         self.timezone.load().clone()
     }
 
+    /// Returns the protocol the request comes from.
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub fn conn_info(&self) -> Option<&ConnInfo> {
+        self.conn_info.as_deref()
+    }
+
     pub fn current_user(&self) -> Option<UserInfoRef> {
         self.current_user.load().as_ref().clone()
     }
@@ -170,6 +234,31 @@ impl QueryContext {
         self.extension.get(key.as_ref()).map(|v| v.as_str())
     }
 
+    /// Attaches the `value` to the context, replacing the previous value of the same type.
+    pub fn set_typed_extension<T: Any + Send + Sync>(&self, value: Arc<T>) -> Option<Arc<T>> {
+        self.typed_extensions.insert(value)
+    }
+
+    /// Returns the value of type `T` attached to the context.
+    pub fn typed_extension<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.typed_extensions.get()
+    }
+
+    /// Removes the value of type `T` from the context.
+    pub fn remove_typed_extension<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.typed_extensions.remove()
+    }
+
+    /// Records a warning for the client, the query itself still succeeds.
+    pub fn add_warning(&self, warning: impl Into<String>) {
+        self.warnings.write().unwrap().push(warning.into());
+    }
+
+    /// Returns the warnings raised while executing the query.
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.read().unwrap().clone()
+    }
+
     /// SQL like `set variable` may change timezone or other info in `QueryContext`.
     /// We need persist these change in `Session`.
     pub fn update_session(&self, session: &SessionRef) {
@@ -214,7 +303,11 @@ impl QueryContextBuilder {
                 .sql_dialect
                 .unwrap_or_else(|| Arc::new(GreptimeDbDialect {})),
             extension: self.extension.unwrap_or_default(),
+            typed_extensions: self.typed_extensions.unwrap_or_default(),
             configuration_parameter: self.configuration_parameter.unwrap_or_default(),
+            conn_info: self.conn_info.unwrap_or_default(),
+            channel: self.channel.unwrap_or_default(),
+            warnings: Default::default(),
         })
     }
 
@@ -226,7 +319,58 @@ impl QueryContextBuilder {
     }
 }
 
-#[derive(Debug)]
+/// Values keyed by their types.
+///
+/// Cloning the extensions clones the map, not the values, so a cloned [QueryContext]
+/// shares values with the original one but not later changes.
+#[derive(Default)]
+struct TypedExtensions {
+    values: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl TypedExtensions {
+    fn insert<T: Any + Send + Sync>(&self, value: Arc<T>) -> Option<Arc<T>> {
+        self.values
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), value)
+            .and_then(|previous| previous.downcast().ok())
+    }
+
+    fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.values
+            .read()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.clone().downcast().ok())
+    }
+
+    fn remove<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.values
+            .write()
+            .unwrap()
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok())
+    }
+}
+
+impl Clone for TypedExtensions {
+    fn clone(&self) -> Self {
+        Self {
+            values: RwLock::new(self.values.read().unwrap().clone()),
+        }
+    }
+}
+
+impl Debug for TypedExtensions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypedExtensions")
+            .field("len", &self.values.read().unwrap().len())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ConnInfo {
     pub client_addr: Option<SocketAddr>,
     pub channel: Channel,
@@ -255,10 +399,18 @@ impl ConnInfo {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Channel {
+    #[default]
+    Unknown,
     Mysql,
     Postgres,
+    Http,
+    Grpc,
+    Influx,
+    Opentsdb,
+    Prometheus,
+    Otlp,
 }
 
 impl Channel {
@@ -266,15 +418,34 @@ impl Channel {
         match self {
             Channel::Mysql => Arc::new(MySqlDialect {}),
             Channel::Postgres => Arc::new(PostgreSqlDialect {}),
+            _ => Arc::new(GreptimeDbDialect {}),
         }
     }
 }
 
+/// Returns the SQL dialect named `name`, which is one of `greptime`, `mysql` and
+/// `postgres`, case-insensitively.
+pub fn sql_dialect_by_name(name: &str) -> Option<Arc<dyn Dialect + Send + Sync>> {
+    match name.to_lowercase().as_str() {
+        "greptime" | "greptimedb" => Some(Arc::new(GreptimeDbDialect {})),
+        "mysql" => Some(Channel::Mysql.dialect()),
+        "postgres" | "postgresql" => Some(Channel::Postgres.dialect()),
+        _ => None,
+    }
+}
+
 impl Display for Channel {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
+            Channel::Unknown => write!(f, "unknown"),
             Channel::Mysql => write!(f, "mysql"),
             Channel::Postgres => write!(f, "postgres"),
+            Channel::Http => write!(f, "http"),
+            Channel::Grpc => write!(f, "grpc"),
+            Channel::Influx => write!(f, "influxdb"),
+            Channel::Opentsdb => write!(f, "opentsdb"),
+            Channel::Prometheus => write!(f, "prometheus"),
+            Channel::Otlp => write!(f, "otlp"),
         }
     }
 }
@@ -283,6 +454,8 @@ impl Display for Channel {
 pub struct ConfigurationVariables {
     postgres_bytea_output: ArcSwap<PGByteaOutputValue>,
     pg_datestyle_format: ArcSwap<(PGDateTimeStyle, PGDateOrder)>,
+    type_coercion_mode: ArcSwap<TypeCoercionMode>,
+    query_memory_limit: ArcSwap<Option<u64>>,
 }
 
 impl Clone for ConfigurationVariables {
@@ -290,6 +463,8 @@ impl Clone for ConfigurationVariables {
         Self {
             postgres_bytea_output: ArcSwap::new(self.postgres_bytea_output.load().clone()),
             pg_datestyle_format: ArcSwap::new(self.pg_datestyle_format.load().clone()),
+            type_coercion_mode: ArcSwap::new(self.type_coercion_mode.load().clone()),
+            query_memory_limit: ArcSwap::new(self.query_memory_limit.load().clone()),
         }
     }
 }
@@ -314,6 +489,24 @@ impl ConfigurationVariables {
     pub fn set_pg_datetime_style(&self, style: PGDateTimeStyle, order: PGDateOrder) {
         self.pg_datestyle_format.swap(Arc::new((style, order)));
     }
+
+    pub fn type_coercion_mode(&self) -> TypeCoercionMode {
+        **self.type_coercion_mode.load()
+    }
+
+    pub fn set_type_coercion_mode(&self, mode: TypeCoercionMode) {
+        let _ = self.type_coercion_mode.swap(Arc::new(mode));
+    }
+
+    /// Returns the memory budget in bytes of each query, `None` if the session uses the
+    /// default budget of the query engine.
+    pub fn query_memory_limit(&self) -> Option<u64> {
+        **self.query_memory_limit.load()
+    }
+
+    pub fn set_query_memory_limit(&self, limit: Option<u64>) {
+        let _ = self.query_memory_limit.swap(Arc::new(limit));
+    }
 }
 
 #[cfg(test)]
@@ -341,6 +534,10 @@ mod test {
         assert_eq!(client_addr.port(), 9000);
 
         assert_eq!("mysql[127.0.0.1:9000]", session.conn_info().to_string());
+
+        // the query context carries the channel of the session
+        assert_eq!(Channel::Mysql, session.new_query_context().channel());
+        assert_eq!(Channel::Unknown, QueryContext::arc().channel());
     }
 
     #[test]
@@ -351,4 +548,77 @@ mod test {
         let context = QueryContext::with(DEFAULT_CATALOG_NAME, "test");
         assert_eq!("test", context.get_db_string());
     }
+
+    #[test]
+    fn test_context_from_region_request_header() {
+        let mut header = RegionRequestHeader {
+            tracing_context: HashMap::new(),
+            dbname: "a0b1c2d3-test".to_string(),
+        };
+        let context = QueryContext::from(&header);
+        assert_eq!("a0b1c2d3", context.current_catalog());
+        assert_eq!("test", context.current_schema());
+        assert_eq!(None, context.configuration_parameter().query_memory_limit());
+
+        let _ = header
+            .tracing_context
+            .insert(QUERY_MEMORY_LIMIT_KEY.to_string(), "1024".to_string());
+        let context = QueryContext::from(&header);
+        assert_eq!(
+            Some(1024),
+            context.configuration_parameter().query_memory_limit()
+        );
+    }
+
+    #[test]
+    fn test_sql_dialect_by_name() {
+        let dialect = sql_dialect_by_name("MySQL").unwrap();
+        assert!(dialect.is_delimited_identifier_start('`'));
+        assert!(!dialect.is_delimited_identifier_start('"'));
+        let dialect = sql_dialect_by_name("postgres").unwrap();
+        assert!(!dialect.is_delimited_identifier_start('`'));
+        assert!(dialect.is_delimited_identifier_start('"'));
+        let dialect = sql_dialect_by_name("greptime").unwrap();
+        assert!(dialect.is_delimited_identifier_start('`'));
+        assert!(dialect.is_delimited_identifier_start('"'));
+        assert!(sql_dialect_by_name("sqlite").is_none());
+    }
+
+    #[test]
+    fn test_typed_extension() {
+        #[derive(Debug, PartialEq)]
+        struct Quota(u64);
+
+        let context = QueryContextBuilder::default()
+            .typed_extension(Arc::new(Quota(1)))
+            .build();
+        assert_eq!(Some(Arc::new(Quota(1))), context.typed_extension::<Quota>());
+        assert!(context.typed_extension::<String>().is_none());
+
+        let previous = context.set_typed_extension(Arc::new(Quota(2)));
+        assert_eq!(Some(Arc::new(Quota(1))), previous);
+
+        // The cloned context keeps the values but not later changes.
+        let cloned = QueryContext::clone(&context);
+        let _ = context.set_typed_extension(Arc::new("value".to_string()));
+        assert_eq!(Some(Arc::new(Quota(2))), cloned.typed_extension::<Quota>());
+        assert!(cloned.typed_extension::<String>().is_none());
+
+        assert_eq!(
+            Some(Arc::new(Quota(2))),
+            context.remove_typed_extension::<Quota>()
+        );
+        assert!(context.typed_extension::<Quota>().is_none());
+    }
+
+    #[test]
+    fn test_warnings() {
+        let context = QueryContext::arc();
+        assert!(context.warnings().is_empty());
+
+        // Warnings raised on a cloned context reach the original one.
+        let cloned = QueryContext::clone(&context);
+        cloned.add_warning("schema mismatch");
+        assert_eq!(vec!["schema mismatch".to_string()], context.warnings());
+    }
 }
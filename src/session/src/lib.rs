@@ -69,6 +69,8 @@ impl Session {
             .sql_dialect(self.conn_info.channel.dialect())
             .configuration_parameter(self.configuration_variables.clone())
             .timezone(self.timezone())
+            .conn_info(Arc::new(self.conn_info.clone()))
+            .channel(self.conn_info.channel)
             .build()
     }
 
@@ -65,6 +65,44 @@ impl TryFrom<Value> for PGByteaOutputValue {
     }
 }
 
+/// How string literals are implicitly casted when compared with columns of other types.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TypeCoercionMode {
+    /// Comparing a string literal with a timestamp, boolean or numeric column is rejected,
+    /// an explicit `CAST` is required.
+    Strict,
+    /// String literals are casted to the type of timestamp or boolean columns.
+    #[default]
+    Lenient,
+}
+
+impl TryFrom<Value> for TypeCoercionMode {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match &value {
+            Value::DoubleQuotedString(s) | Value::SingleQuotedString(s) => {
+                match s.to_uppercase().as_str() {
+                    "STRICT" => Ok(TypeCoercionMode::Strict),
+                    "LENIENT" => Ok(TypeCoercionMode::Lenient),
+                    _ => InvalidConfigValueSnafu {
+                        name: "TYPE_COERCION",
+                        value: value.to_string(),
+                        hint: "Available values: strict, lenient",
+                    }
+                    .fail(),
+                }
+            }
+            _ => InvalidConfigValueSnafu {
+                name: "TYPE_COERCION",
+                value: value.to_string(),
+                hint: "Available values: strict, lenient",
+            }
+            .fail(),
+        }
+    }
+}
+
 // Refers to: https://www.postgresql.org/docs/current/runtime-config-client.html#GUC-DATESTYLE
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
 pub enum PGDateOrder {
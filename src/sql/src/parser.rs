@@ -21,7 +21,7 @@ use sqlparser::tokenizer::{Token, TokenWithLocation};
 
 use crate::ast::{Expr, ObjectName};
 use crate::error::{self, Result, SyntaxSnafu};
-use crate::parsers::tql_parser;
+use crate::parsers::{backup_parser, refresh_parser, tql_parser};
 use crate::statements::statement::Statement;
 use crate::statements::transform_statements;
 
@@ -147,12 +147,36 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::SET => self.parse_set_variables(),
 
+                    Keyword::GRANT => self.parse_grant(),
+
+                    Keyword::REVOKE => self.parse_revoke(),
+
+                    Keyword::KILL => self.parse_kill(),
+
                     Keyword::NoKeyword
                         if w.value.to_uppercase() == tql_parser::TQL && w.quote_style.is_none() =>
                     {
                         self.parse_tql()
                     }
 
+                    _ if w.value.to_uppercase() == refresh_parser::REFRESH
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_refresh()
+                    }
+
+                    _ if w.value.to_uppercase() == backup_parser::BACKUP
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_backup()
+                    }
+
+                    _ if w.value.to_uppercase() == backup_parser::RESTORE
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_restore()
+                    }
+
                     // todo(hl) support more statements.
                     _ => self.unsupported(self.peek_token_as_string()),
                 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod alter_parser;
+pub(crate) mod backup_parser;
 pub(crate) mod copy_parser;
 pub(crate) mod create_parser;
 pub(crate) mod delete_parser;
@@ -21,8 +22,12 @@ pub(crate) mod drop_parser;
 pub(crate) mod error;
 pub(crate) mod explain_parser;
 pub(crate) mod insert_parser;
+pub(crate) mod kill_parser;
+pub(crate) mod privilege_parser;
 pub(crate) mod query_parser;
+pub(crate) mod refresh_parser;
 pub(crate) mod set_var_parser;
 pub(crate) mod show_parser;
 pub(crate) mod tql_parser;
 pub(crate) mod truncate_parser;
+pub(crate) mod user_parser;
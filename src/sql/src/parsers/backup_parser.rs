@@ -0,0 +1,119 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use snafu::ResultExt;
+use sqlparser::keywords::Keyword;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::copy::CopyDatabaseArgument;
+use crate::statements::statement::Statement;
+
+pub const BACKUP: &str = "BACKUP";
+pub const RESTORE: &str = "RESTORE";
+
+/// `BACKUP DATABASE db TO 'location'` and `RESTORE DATABASE db FROM 'location'`.
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_backup(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let argument = self.parse_backup_argument(Keyword::TO)?;
+        Ok(Statement::BackupDatabase(argument))
+    }
+
+    pub(crate) fn parse_restore(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let argument = self.parse_backup_argument(Keyword::FROM)?;
+        Ok(Statement::RestoreDatabase(argument))
+    }
+
+    fn parse_backup_argument(&mut self, direction: Keyword) -> Result<CopyDatabaseArgument> {
+        self.parser
+            .expect_keyword(Keyword::DATABASE)
+            .context(error::SyntaxSnafu)?;
+        let database_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a database name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        self.parser
+            .expect_keyword(direction)
+            .context(error::SyntaxSnafu)?;
+        let (with, connection, location) = self.parse_copy_parameters()?;
+
+        Ok(CopyDatabaseArgument {
+            database_name,
+            with: with.into(),
+            connection: connection.into(),
+            location,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use sqlparser::ast::{Ident, ObjectName};
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    fn parse(sql: &str) -> Result<Statement> {
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+            .map(|mut stmts| stmts.pop().unwrap())
+    }
+
+    #[test]
+    fn test_parse_backup_database() {
+        let stmt = parse(
+            "BACKUP DATABASE catalog0.schema0 TO 's3://bucket/backup/' WITH (FORMAT = 'parquet') CONNECTION (REGION = 'us-west-2')",
+        )
+        .unwrap();
+        let Statement::BackupDatabase(argument) = stmt else {
+            unreachable!()
+        };
+        assert_eq!(
+            ObjectName(vec![Ident::new("catalog0"), Ident::new("schema0")]),
+            argument.database_name
+        );
+        assert_eq!("s3://bucket/backup/", argument.location);
+        assert_eq!(
+            HashMap::from([("format".to_string(), "parquet".to_string())]),
+            argument.with.map
+        );
+        assert_eq!(
+            HashMap::from([("region".to_string(), "us-west-2".to_string())]),
+            argument.connection.map
+        );
+
+        assert!(parse("BACKUP DATABASE db FROM '/tmp/backup/'").is_err());
+        assert!(parse("BACKUP TABLE t TO '/tmp/backup/'").is_err());
+    }
+
+    #[test]
+    fn test_parse_restore_database() {
+        let stmt = parse("RESTORE DATABASE db FROM '/tmp/backup/'").unwrap();
+        let Statement::RestoreDatabase(argument) = stmt else {
+            unreachable!()
+        };
+        assert_eq!(ObjectName(vec![Ident::new("db")]), argument.database_name);
+        assert_eq!("/tmp/backup/", argument.location);
+        assert!(argument.with.map.is_empty());
+
+        assert!(parse("RESTORE DATABASE db TO '/tmp/backup/'").is_err());
+    }
+}
@@ -114,7 +114,7 @@ impl<'a> ParserContext<'a> {
         }
     }
 
-    fn parse_copy_parameters(&mut self) -> Result<(With, Connection, String)> {
+    pub(crate) fn parse_copy_parameters(&mut self) -> Result<(With, Connection, String)> {
         let location =
             self.parser
                 .parse_literal_string()
@@ -32,9 +32,11 @@ use crate::error::{
 };
 use crate::parser::ParserContext;
 use crate::statements::create::{
-    CreateDatabase, CreateExternalTable, CreateTable, CreateTableLike, Partitions, TIME_INDEX,
+    CreateDatabase, CreateExternalTable, CreateMaterializedView, CreateTable, CreateTableLike,
+    CreateView, Partitions, TIME_INDEX,
 };
 use crate::statements::get_data_type_by_alias_name;
+use crate::statements::query::Query;
 use crate::statements::statement::Statement;
 use crate::util::parse_option_string;
 
@@ -52,12 +54,87 @@ impl<'a> ParserContext<'a> {
 
                 Keyword::EXTERNAL => self.parse_create_external_table(),
 
+                Keyword::VIEW => self.parse_create_view(false),
+
+                Keyword::MATERIALIZED => self.parse_create_materialized_view(),
+
+                Keyword::ROLE => self.parse_create_role(),
+
+                Keyword::USER => self.parse_create_user(),
+
+                Keyword::OR => {
+                    let _ = self.parser.next_token();
+                    self.parser
+                        .expect_keyword(Keyword::REPLACE)
+                        .context(SyntaxSnafu)?;
+                    match self.parser.peek_token().token {
+                        Token::Word(w) if w.keyword == Keyword::VIEW => {
+                            self.parse_create_view(true)
+                        }
+                        unexpected => self.unsupported(unexpected.to_string()),
+                    }
+                }
+
                 _ => self.unsupported(w.to_string()),
             },
             unexpected => self.unsupported(unexpected.to_string()),
         }
     }
 
+    /// Parses `CREATE MATERIALIZED VIEW [IF NOT EXISTS] name AS query`,
+    /// the `CREATE` part is already consumed.
+    fn parse_create_materialized_view(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        self.parser
+            .expect_keyword(Keyword::VIEW)
+            .context(SyntaxSnafu)?;
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.intern_parse_table_name()?;
+
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "AS",
+                actual: self.peek_token_as_string(),
+            })?;
+        let query = self.parser.parse_query().context(SyntaxSnafu)?;
+
+        Ok(Statement::CreateMaterializedView(CreateMaterializedView {
+            name,
+            query: Box::new(Query::try_from(query)?),
+            if_not_exists,
+        }))
+    }
+
+    /// Parses `CREATE [OR REPLACE] VIEW [IF NOT EXISTS] name AS query`,
+    /// the `CREATE [OR REPLACE]` part is already consumed.
+    fn parse_create_view(&mut self, or_replace: bool) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.intern_parse_table_name()?;
+
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "AS",
+                actual: self.peek_token_as_string(),
+            })?;
+        let query = self.parser.parse_query().context(SyntaxSnafu)?;
+
+        Ok(Statement::CreateView(CreateView {
+            name,
+            query: Box::new(Query::try_from(query)?),
+            or_replace,
+            if_not_exists,
+        }))
+    }
+
     fn parse_create_external_table(&mut self) -> Result<Statement> {
         let _ = self.parser.next_token();
         self.parser
@@ -120,9 +197,19 @@ impl<'a> ParserContext<'a> {
                 actual: self.peek_token_as_string(),
             })?;
         let database_name = Self::canonicalize_object_name(database_name);
+        let options = self
+            .parser
+            .parse_options(Keyword::WITH)
+            .context(SyntaxSnafu)?
+            .into_iter()
+            .filter_map(|option| {
+                parse_option_string(option.value).map(|v| (option.name.value.to_lowercase(), v))
+            })
+            .collect::<HashMap<String, String>>();
         Ok(Statement::CreateDatabase(CreateDatabase {
             name: database_name,
             if_not_exists,
+            options: options.into(),
         }))
     }
 
@@ -727,6 +814,7 @@ mod tests {
     use super::*;
     use crate::dialect::GreptimeDbDialect;
     use crate::parser::ParseOptions;
+    use crate::statements::OptionMap;
 
     #[test]
     fn test_parse_create_table_like() {
@@ -879,6 +967,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_view() {
+        let sql = "CREATE VIEW IF NOT EXISTS my_schema.v AS SELECT host, cpu FROM monitor WHERE cpu > 0.5";
+        let stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::CreateView(c) => {
+                assert_eq!(c.name.to_string(), "my_schema.v");
+                assert!(!c.or_replace);
+                assert!(c.if_not_exists);
+                assert_eq!(
+                    c.query.to_string(),
+                    "SELECT host, cpu FROM monitor WHERE cpu > 0.5"
+                );
+                assert_eq!(c.to_string(), sql);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE OR REPLACE VIEW v AS SELECT 1";
+        let stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        match &stmts[0] {
+            Statement::CreateView(c) => {
+                assert_eq!(c.name.to_string(), "v");
+                assert!(c.or_replace);
+                assert!(!c.if_not_exists);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE OR REPLACE TABLE v (ts TIMESTAMP TIME INDEX)";
+        assert!(ParserContext::create_with_dialect(
+            sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default()
+        )
+        .is_err());
+
+        let sql = "CREATE VIEW v SELECT 1";
+        assert!(ParserContext::create_with_dialect(
+            sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_create_materialized_view() {
+        let sql = "CREATE MATERIALIZED VIEW IF NOT EXISTS mv AS SELECT host, max(cpu) FROM monitor GROUP BY host";
+        let stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::CreateMaterializedView(c) => {
+                assert_eq!(c.name.to_string(), "mv");
+                assert!(c.if_not_exists);
+                assert_eq!(
+                    c.query.to_string(),
+                    "SELECT host, max(cpu) FROM monitor GROUP BY host"
+                );
+                assert_eq!(
+                    c.to_string(),
+                    "CREATE MATERIALIZED VIEW IF NOT EXISTS mv AS SELECT host, max(cpu) FROM monitor GROUP BY host"
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "CREATE MATERIALIZED TABLE mv AS SELECT 1";
+        assert!(ParserContext::create_with_dialect(
+            sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default()
+        )
+        .is_err());
+    }
     #[test]
     fn test_parse_create_database() {
         let sql = "create database";
@@ -925,9 +1095,27 @@ mod tests {
             stmts.pop().unwrap(),
             Statement::CreateDatabase(CreateDatabase::new(
                 ObjectName(vec![Ident::with_quote('`', "fOo"),]),
-                false
+                false,
+                OptionMap::default(),
             ))
         );
+
+        let sql =
+            "CREATE DATABASE prometheus WITH ('auto_create_table.Prometheus'='false', ttl='7d')";
+        let stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        match &stmts[0] {
+            Statement::CreateDatabase(c) => {
+                assert_eq!(c.name.to_string(), "prometheus");
+                assert_eq!(
+                    Some(&"false".to_string()),
+                    c.options.get("auto_create_table.prometheus")
+                );
+                assert_eq!(Some(&"7d".to_string()), c.options.get("ttl"));
+            }
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -18,7 +18,7 @@ use sqlparser::tokenizer::Token;
 
 use crate::error::{self, InvalidTableNameSnafu, Result};
 use crate::parser::ParserContext;
-use crate::statements::drop::{DropDatabase, DropTable};
+use crate::statements::drop::{DropDatabase, DropMaterializedView, DropTable, DropView};
 use crate::statements::statement::Statement;
 
 /// DROP statement parser implementation
@@ -28,7 +28,11 @@ impl<'a> ParserContext<'a> {
         match self.parser.peek_token().token {
             Token::Word(w) => match w.keyword {
                 Keyword::TABLE => self.parse_drop_table(),
+                Keyword::VIEW => self.parse_drop_view(),
+                Keyword::MATERIALIZED => self.parse_drop_materialized_view(),
                 Keyword::SCHEMA | Keyword::DATABASE => self.parse_drop_database(),
+                Keyword::ROLE => self.parse_drop_role(),
+                Keyword::USER => self.parse_drop_user(),
                 _ => self.unsupported(w.to_string()),
             },
             unexpected => self.unsupported(unexpected.to_string()),
@@ -54,8 +58,67 @@ impl<'a> ParserContext<'a> {
                 name: table_ident.to_string()
             }
         );
+        // `RESTRICT` is the default behavior.
+        let cascade = matches!(
+            self.parser
+                .parse_one_of_keywords(&[Keyword::CASCADE, Keyword::RESTRICT]),
+            Some(Keyword::CASCADE)
+        );
 
-        Ok(Statement::DropTable(DropTable::new(table_ident, if_exists)))
+        Ok(Statement::DropTable(
+            DropTable::new(table_ident, if_exists).with_cascade(cascade),
+        ))
+    }
+
+    fn parse_drop_view(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let raw_view_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a view name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        let view_ident = Self::canonicalize_object_name(raw_view_ident);
+        ensure!(
+            !view_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: view_ident.to_string()
+            }
+        );
+
+        Ok(Statement::DropView(DropView::new(view_ident, if_exists)))
+    }
+
+    fn parse_drop_materialized_view(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        self.parser
+            .expect_keyword(Keyword::VIEW)
+            .context(error::SyntaxSnafu)?;
+
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let raw_view_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a view name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        let view_ident = Self::canonicalize_object_name(raw_view_ident);
+        ensure!(
+            !view_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: view_ident.to_string()
+            }
+        );
+
+        Ok(Statement::DropMaterializedView(DropMaterializedView::new(
+            view_ident, if_exists,
+        )))
     }
 
     fn parse_drop_database(&mut self) -> Result<Statement> {
@@ -133,7 +196,66 @@ mod tests {
                 ]),
                 false
             ))
-        )
+        );
+
+        let sql = "DROP TABLE foo CASCADE";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropTable(
+                DropTable::new(ObjectName(vec![Ident::new("foo")]), false).with_cascade(true)
+            )
+        );
+
+        let sql = "DROP TABLE IF EXISTS foo RESTRICT";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropTable(DropTable::new(ObjectName(vec![Ident::new("foo")]), true))
+        );
+    }
+
+    #[test]
+    pub fn test_drop_view() {
+        let sql = "DROP VIEW foo";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropView(DropView::new(ObjectName(vec![Ident::new("foo")]), false))
+        );
+
+        let sql = "DROP VIEW IF EXISTS my_schema.foo";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropView(DropView::new(
+                ObjectName(vec![Ident::new("my_schema"), Ident::new("foo")]),
+                true
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_drop_materialized_view() {
+        let sql = "DROP MATERIALIZED VIEW IF EXISTS my_schema.foo";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropMaterializedView(DropMaterializedView::new(
+                ObjectName(vec![Ident::new("my_schema"), Ident::new("foo")]),
+                true
+            ))
+        );
     }
 
     #[test]
@@ -13,15 +13,32 @@
 // limitations under the License.
 
 use snafu::ResultExt;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::Token;
 
 use crate::error::{self, Result};
 use crate::parser::ParserContext;
-use crate::statements::explain::Explain;
+use crate::statements::explain::{Explain, ExplainCapture, ExplainProfile, ProfileFormat};
+use crate::statements::query::Query;
 use crate::statements::statement::Statement;
 
+pub const PROFILE: &str = "PROFILE";
+pub const CAPTURE: &str = "CAPTURE";
+
 /// EXPLAIN statement parser implementation
 impl<'a> ParserContext<'a> {
     pub(crate) fn parse_explain(&mut self) -> Result<Statement> {
+        if let Token::Word(w) = self.parser.peek_token().token {
+            if w.value.to_uppercase() == PROFILE && w.quote_style.is_none() {
+                let _ = self.parser.next_token();
+                return self.parse_explain_profile();
+            }
+            if w.value.to_uppercase() == CAPTURE && w.quote_style.is_none() {
+                let _ = self.parser.next_token();
+                return self.parse_explain_capture();
+            }
+        }
+
         let explain_statement =
             self.parser
                 .parse_explain(false)
@@ -33,6 +50,50 @@ impl<'a> ParserContext<'a> {
 
         Ok(Statement::Explain(Explain::try_from(explain_statement)?))
     }
+
+    /// Parses `EXPLAIN PROFILE [FORMAT {FLAMEGRAPH | PPROF}] <query>`.
+    fn parse_explain_profile(&mut self) -> Result<Statement> {
+        let format = if self.parser.parse_keyword(Keyword::FORMAT) {
+            let format = self.parser.parse_identifier().context(error::SyntaxSnafu)?;
+            match format.value.to_uppercase().as_str() {
+                "FLAMEGRAPH" => ProfileFormat::Flamegraph,
+                "PPROF" => ProfileFormat::Pprof,
+                _ => {
+                    return error::InvalidSqlSnafu {
+                        msg: format!("unknown profile format: {format}"),
+                    }
+                    .fail()
+                }
+            }
+        } else {
+            ProfileFormat::default()
+        };
+
+        Ok(Statement::ExplainProfile(ExplainProfile {
+            format,
+            query: self.parse_explained_query()?,
+        }))
+    }
+
+    /// Parses `EXPLAIN CAPTURE <query>`.
+    fn parse_explain_capture(&mut self) -> Result<Statement> {
+        Ok(Statement::ExplainCapture(ExplainCapture {
+            query: self.parse_explained_query()?,
+        }))
+    }
+
+    fn parse_explained_query(&mut self) -> Result<Box<Query>> {
+        let query = self
+            .parser
+            .parse_query()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a query statement",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok(Box::new(Query::try_from(query)?))
+    }
 }
 
 #[cfg(test)]
@@ -103,4 +164,49 @@ mod tests {
 
         assert_eq!(stmts[0], Statement::Explain(explain))
     }
+
+    #[test]
+    fn test_explain_profile() {
+        let parse = |sql| {
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+        };
+
+        let stmts = parse("EXPLAIN PROFILE SELECT * FROM foo").unwrap();
+        let Statement::ExplainProfile(explain) = &stmts[0] else {
+            panic!("unexpected statement: {:?}", stmts[0]);
+        };
+        assert_eq!(ProfileFormat::Flamegraph, explain.format);
+        assert_eq!("SELECT * FROM foo", explain.query.inner.to_string());
+
+        let stmts = parse("explain profile format pprof select * from foo").unwrap();
+        let Statement::ExplainProfile(explain) = &stmts[0] else {
+            panic!("unexpected statement: {:?}", stmts[0]);
+        };
+        assert_eq!(ProfileFormat::Pprof, explain.format);
+        assert_eq!(
+            "EXPLAIN PROFILE FORMAT PPROF SELECT * FROM foo",
+            explain.to_string()
+        );
+
+        assert!(parse("EXPLAIN PROFILE FORMAT svg SELECT * FROM foo").is_err());
+        assert!(parse("EXPLAIN PROFILE FORMAT").is_err());
+    }
+
+    #[test]
+    fn test_explain_capture() {
+        let parse = |sql| {
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+        };
+
+        let stmts = parse("explain capture select * from foo where a > 1").unwrap();
+        let Statement::ExplainCapture(explain) = &stmts[0] else {
+            panic!("unexpected statement: {:?}", stmts[0]);
+        };
+        assert_eq!(
+            "EXPLAIN CAPTURE SELECT * FROM foo WHERE a > 1",
+            explain.to_string()
+        );
+
+        assert!(parse("EXPLAIN CAPTURE").is_err());
+    }
 }
@@ -0,0 +1,76 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::kill::KillQuery;
+use crate::statements::statement::Statement;
+
+/// `KILL [QUERY] id;`
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_kill(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        if self.parser.parse_keyword(Keyword::CONNECTION) {
+            return self.unsupported("CONNECTION".to_string());
+        }
+        let _ = self.parser.parse_keyword(Keyword::QUERY);
+
+        let id = match self.parser.next_token().token {
+            Token::Number(id, _) => id.parse::<u64>().ok(),
+            _ => None,
+        };
+        let Some(id) = id else {
+            return error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a query id",
+                actual: self.peek_token_as_string(),
+            }
+            .fail();
+        };
+
+        Ok(Statement::KillQuery(KillQuery { id }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    #[test]
+    fn test_parse_kill() {
+        for sql in ["KILL QUERY 42", "KILL 42"] {
+            let stmts = ParserContext::create_with_dialect(
+                sql,
+                &GreptimeDbDialect {},
+                ParseOptions::default(),
+            )
+            .unwrap();
+            assert_eq!(stmts, vec![Statement::KillQuery(KillQuery { id: 42 })]);
+        }
+
+        for sql in ["KILL CONNECTION 42", "KILL QUERY abc", "KILL QUERY"] {
+            assert!(ParserContext::create_with_dialect(
+                sql,
+                &GreptimeDbDialect {},
+                ParseOptions::default()
+            )
+            .is_err());
+        }
+    }
+}
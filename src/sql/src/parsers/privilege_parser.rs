@@ -0,0 +1,297 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use snafu::ResultExt;
+use sqlparser::ast::Ident;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, Result, SyntaxSnafu};
+use crate::parser::ParserContext;
+use crate::statements::privilege::{
+    CreateRole, DropRole, Grant, GrantObject, Grantable, Privilege, Revoke,
+};
+use crate::statements::statement::Statement;
+
+/// Parses role and privilege statements:
+/// - `CREATE ROLE [IF NOT EXISTS] role`
+/// - `DROP ROLE [IF EXISTS] role`
+/// - `GRANT {privileges ON object | role} TO grantee`
+/// - `REVOKE {privileges ON object | role} FROM grantee`
+impl<'a> ParserContext<'a> {
+    /// Parses `CREATE ROLE`, the `CREATE` part is already consumed.
+    pub(crate) fn parse_create_role(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parse_role_ident("a role name")?;
+
+        Ok(Statement::CreateRole(CreateRole {
+            name,
+            if_not_exists,
+        }))
+    }
+
+    /// Parses `DROP ROLE`, the `DROP` part is already consumed.
+    pub(crate) fn parse_drop_role(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self.parse_role_ident("a role name")?;
+
+        Ok(Statement::DropRole(DropRole { name, if_exists }))
+    }
+
+    pub(crate) fn parse_grant(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let grantable = self.parse_grantable()?;
+        self.parser
+            .expect_keyword(Keyword::TO)
+            .context(SyntaxSnafu)?;
+        let grantee = self.parse_role_ident("a user or role name")?;
+
+        Ok(Statement::Grant(Grant { grantable, grantee }))
+    }
+
+    pub(crate) fn parse_revoke(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let grantable = self.parse_grantable()?;
+        self.parser
+            .expect_keyword(Keyword::FROM)
+            .context(SyntaxSnafu)?;
+        let grantee = self.parse_role_ident("a user or role name")?;
+
+        Ok(Statement::Revoke(Revoke { grantable, grantee }))
+    }
+
+    fn parse_grantable(&mut self) -> Result<Grantable> {
+        let privileges = match self.parser.peek_token().token {
+            Token::Word(w) if w.keyword == Keyword::ALL => {
+                let _ = self.parser.next_token();
+                let _ = self.parser.parse_keyword(Keyword::PRIVILEGES);
+                Privilege::ALL.to_vec()
+            }
+            Token::Word(w) if w.quote_style.is_none() && parse_privilege(&w.value).is_some() => {
+                self.parse_privileges()?
+            }
+            _ => {
+                let role = self.parse_role_ident("privileges or a role name")?;
+                return Ok(Grantable::Role(role));
+            }
+        };
+
+        self.parser
+            .expect_keyword(Keyword::ON)
+            .context(SyntaxSnafu)?;
+        let object = self.parse_grant_object()?;
+
+        Ok(Grantable::Privileges { privileges, object })
+    }
+
+    fn parse_privileges(&mut self) -> Result<Vec<Privilege>> {
+        let mut privileges = Vec::new();
+        loop {
+            let token = self.parser.next_token();
+            let privilege = match &token.token {
+                Token::Word(w) if w.quote_style.is_none() => parse_privilege(&w.value),
+                _ => None,
+            };
+            match privilege {
+                Some(privilege) if !privileges.contains(&privilege) => privileges.push(privilege),
+                Some(_) => {}
+                None => return self.expected("SELECT, INSERT, CREATE or DROP", token),
+            }
+
+            if !self.parser.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+
+        Ok(privileges)
+    }
+
+    fn parse_grant_object(&mut self) -> Result<GrantObject> {
+        if self.consume_token("CATALOG") {
+            let catalog = self.parse_role_ident("a catalog name")?;
+            return Ok(GrantObject::Catalog(catalog));
+        }
+        if self
+            .parser
+            .parse_one_of_keywords(&[Keyword::DATABASE, Keyword::SCHEMA])
+            .is_some()
+        {
+            let database = self.intern_parse_table_name()?;
+            return Ok(GrantObject::Database(database));
+        }
+        let _ = self.parser.parse_keyword(Keyword::TABLE);
+
+        Ok(GrantObject::Table(self.intern_parse_table_name()?))
+    }
+
+    fn parse_role_ident(&mut self, expected: &str) -> Result<Ident> {
+        let ident = self
+            .parser
+            .parse_identifier()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected,
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok(Self::canonicalize_identifier(ident))
+    }
+}
+
+fn parse_privilege(word: &str) -> Option<Privilege> {
+    match word.to_uppercase().as_str() {
+        "SELECT" => Some(Privilege::Select),
+        "INSERT" => Some(Privilege::Insert),
+        "CREATE" => Some(Privilege::Create),
+        "DROP" => Some(Privilege::Drop),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::ast::ObjectName;
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    fn parse(sql: &str) -> Result<Statement> {
+        let mut stmts = ParserContext::create_with_dialect(
+            sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default(),
+        )?;
+        assert_eq!(1, stmts.len());
+        Ok(stmts.pop().unwrap())
+    }
+
+    #[test]
+    fn test_parse_create_drop_role() {
+        let stmt = parse("CREATE ROLE IF NOT EXISTS Reader").unwrap();
+        assert_eq!(
+            Statement::CreateRole(CreateRole {
+                name: Ident::new("reader"),
+                if_not_exists: true,
+            }),
+            stmt
+        );
+        let Statement::CreateRole(create_role) = stmt else {
+            unreachable!()
+        };
+        assert_eq!("CREATE ROLE IF NOT EXISTS reader", create_role.to_string());
+
+        let stmt = parse("DROP ROLE reader").unwrap();
+        assert_eq!(
+            Statement::DropRole(DropRole {
+                name: Ident::new("reader"),
+                if_exists: false,
+            }),
+            stmt
+        );
+    }
+
+    #[test]
+    fn test_parse_grant() {
+        let stmt = parse("GRANT SELECT, insert ON my_db.foo TO reader").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                grantable: Grantable::Privileges {
+                    privileges: vec![Privilege::Select, Privilege::Insert],
+                    object: GrantObject::Table(ObjectName(vec![
+                        Ident::new("my_db"),
+                        Ident::new("foo")
+                    ])),
+                },
+                grantee: Ident::new("reader"),
+            }),
+            stmt
+        );
+        let Statement::Grant(grant) = stmt else {
+            unreachable!()
+        };
+        assert_eq!(
+            "GRANT SELECT, INSERT ON TABLE my_db.foo TO reader",
+            grant.to_string()
+        );
+
+        let stmt = parse("GRANT ALL PRIVILEGES ON DATABASE my_db TO alice").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                grantable: Grantable::Privileges {
+                    privileges: Privilege::ALL.to_vec(),
+                    object: GrantObject::Database(ObjectName(vec![Ident::new("my_db")])),
+                },
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+
+        let stmt = parse("GRANT CREATE, DROP ON CATALOG greptime TO alice").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                grantable: Grantable::Privileges {
+                    privileges: vec![Privilege::Create, Privilege::Drop],
+                    object: GrantObject::Catalog(Ident::new("greptime")),
+                },
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+
+        let stmt = parse("GRANT reader TO alice").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                grantable: Grantable::Role(Ident::new("reader")),
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+
+        assert!(parse("GRANT SELECT ON foo").is_err());
+        assert!(parse("GRANT SELECT, ALTER ON foo TO alice").is_err());
+    }
+
+    #[test]
+    fn test_parse_revoke() {
+        let stmt = parse("REVOKE DROP ON TABLE foo FROM alice").unwrap();
+        assert_eq!(
+            Statement::Revoke(Revoke {
+                grantable: Grantable::Privileges {
+                    privileges: vec![Privilege::Drop],
+                    object: GrantObject::Table(ObjectName(vec![Ident::new("foo")])),
+                },
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+        let Statement::Revoke(revoke) = stmt else {
+            unreachable!()
+        };
+        assert_eq!("REVOKE DROP ON TABLE foo FROM alice", revoke.to_string());
+
+        let stmt = parse("REVOKE reader FROM alice").unwrap();
+        assert_eq!(
+            Statement::Revoke(Revoke {
+                grantable: Grantable::Role(Ident::new("reader")),
+                grantee: Ident::new("alice"),
+            }),
+            stmt
+        );
+        assert!(parse("REVOKE reader TO alice").is_err());
+    }
+}
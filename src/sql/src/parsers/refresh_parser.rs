@@ -0,0 +1,85 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::{ensure, ResultExt};
+use sqlparser::keywords::Keyword;
+
+use crate::error::{self, InvalidTableNameSnafu, Result};
+use crate::parser::ParserContext;
+use crate::statements::refresh::RefreshMaterializedView;
+use crate::statements::statement::Statement;
+
+pub const REFRESH: &str = "REFRESH";
+
+/// `REFRESH MATERIALIZED VIEW view_name;`
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_refresh(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        self.parser
+            .expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])
+            .context(error::SyntaxSnafu)?;
+
+        let raw_view_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a view name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        let view_ident = Self::canonicalize_object_name(raw_view_ident);
+        ensure!(
+            !view_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: view_ident.to_string()
+            }
+        );
+
+        Ok(Statement::RefreshMaterializedView(
+            RefreshMaterializedView::new(view_ident),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::ast::{Ident, ObjectName};
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    #[test]
+    fn test_parse_refresh() {
+        let sql = "REFRESH MATERIALIZED VIEW my_schema.foo";
+        let mut stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::RefreshMaterializedView(RefreshMaterializedView::new(ObjectName(vec![
+                Ident::new("my_schema"),
+                Ident::new("foo")
+            ])))
+        );
+
+        let sql = "REFRESH VIEW foo";
+        assert!(ParserContext::create_with_dialect(
+            sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default()
+        )
+        .is_err());
+    }
+}
@@ -19,7 +19,8 @@ use sqlparser::tokenizer::Token;
 use crate::error::{self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result};
 use crate::parser::ParserContext;
 use crate::statements::show::{
-    ShowColumns, ShowCreateTable, ShowDatabases, ShowIndex, ShowKind, ShowTables, ShowVariables,
+    ShowColumns, ShowCreateDatabase, ShowCreateTable, ShowCreateUsers, ShowDatabases, ShowIndex,
+    ShowKind, ShowProcesslist, ShowTables, ShowVariables,
 };
 use crate::statements::statement::Statement;
 
@@ -46,6 +47,10 @@ impl<'a> ParserContext<'a> {
         } else if self.consume_token("CREATE") {
             if self.consume_token("TABLE") {
                 self.parse_show_create_table()
+            } else if self.consume_token("DATABASE") || self.consume_token("SCHEMA") {
+                self.parse_show_create_database()
+            } else if self.consume_token("USERS") {
+                Ok(Statement::ShowCreateUsers(ShowCreateUsers))
             } else {
                 self.unsupported(self.peek_token_as_string())
             }
@@ -55,6 +60,8 @@ impl<'a> ParserContext<'a> {
             } else if self.consume_token("COLUMNS") || self.consume_token("FIELDS") {
                 // SHOW {COLUMNS | FIELDS}
                 self.parse_show_columns(true)
+            } else if self.consume_token("PROCESSLIST") {
+                Ok(Statement::ShowProcesslist(ShowProcesslist { full: true }))
             } else {
                 self.unsupported(self.peek_token_as_string())
             }
@@ -68,6 +75,8 @@ impl<'a> ParserContext<'a> {
                         actual: self.peek_token_as_string(),
                     })?;
             Ok(Statement::ShowVariables(ShowVariables { variable }))
+        } else if self.consume_token("PROCESSLIST") {
+            Ok(Statement::ShowProcesslist(ShowProcesslist { full: false }))
         } else {
             self.unsupported(self.peek_token_as_string())
         }
@@ -93,6 +102,31 @@ impl<'a> ParserContext<'a> {
         Ok(Statement::ShowCreateTable(ShowCreateTable { table_name }))
     }
 
+    /// Parse SHOW CREATE DATABASE statement
+    fn parse_show_create_database(&mut self) -> Result<Statement> {
+        let raw_database_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a database name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            raw_database_name.0.len() == 1,
+            InvalidDatabaseNameSnafu {
+                name: raw_database_name.to_string(),
+            }
+        );
+        let database_name = Self::canonicalize_object_name(raw_database_name);
+        let full = self.consume_token("FULL");
+
+        Ok(Statement::ShowCreateDatabase(ShowCreateDatabase {
+            database_name,
+            full,
+        }))
+    }
+
     fn parse_show_table_name(&mut self) -> Result<String> {
         let _ = self.parser.next_token();
         let table_name =
@@ -722,4 +756,25 @@ mod tests {
                              ..
                          }) if table == "test" && expr.to_string() == "Field = 'disk'"));
     }
+
+    #[test]
+    fn test_show_processlist() {
+        let sql = "SHOW PROCESSLIST";
+        let stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(
+            stmts,
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: false })]
+        );
+
+        let sql = "SHOW FULL PROCESSLIST";
+        let stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(
+            stmts,
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: true })]
+        );
+    }
 }
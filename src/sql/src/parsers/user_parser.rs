@@ -0,0 +1,275 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use snafu::ResultExt;
+use sqlparser::ast::Ident;
+use sqlparser::keywords::Keyword;
+
+use crate::error::{self, Result, SyntaxSnafu};
+use crate::parser::ParserContext;
+use crate::statements::statement::Statement;
+use crate::statements::user::{AlterUser, CreateUser, DropUser, Password, MYSQL_NATIVE_PASSWORD};
+
+const IDENTIFIED: &str = "IDENTIFIED";
+
+/// Parses user statements:
+/// - `CREATE USER [IF NOT EXISTS] user IDENTIFIED [WITH mysql_native_password] BY 'password'`
+/// - `ALTER USER user IDENTIFIED [WITH mysql_native_password] BY 'password'`
+///
+/// Both accept `AS 'password_hash' ['mysql_native_password']` in place of `BY 'password'`
+/// to restore the hashes exported by `SHOW CREATE USERS`.
+/// - `DROP USER [IF EXISTS] user`
+impl<'a> ParserContext<'a> {
+    /// Parses `CREATE USER`, the `CREATE` part is already consumed.
+    pub(crate) fn parse_create_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parse_user_ident()?;
+        let (password, mysql_native_password) = self.parse_identified_by()?;
+
+        Ok(Statement::CreateUser(CreateUser {
+            name,
+            password,
+            mysql_native_password,
+            if_not_exists,
+        }))
+    }
+
+    pub(crate) fn parse_alter_user(&mut self) -> Result<Statement> {
+        self.parser
+            .expect_keywords(&[Keyword::ALTER, Keyword::USER])
+            .context(SyntaxSnafu)?;
+        let name = self.parse_user_ident()?;
+        let (password, mysql_native_password) = self.parse_identified_by()?;
+
+        Ok(Statement::AlterUser(AlterUser {
+            name,
+            password,
+            mysql_native_password,
+        }))
+    }
+
+    /// Parses `DROP USER`, the `DROP` part is already consumed.
+    pub(crate) fn parse_drop_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self.parse_user_ident()?;
+
+        Ok(Statement::DropUser(DropUser { name, if_exists }))
+    }
+
+    fn parse_user_ident(&mut self) -> Result<Ident> {
+        let ident = self
+            .parser
+            .parse_identifier()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a user name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok(Self::canonicalize_identifier(ident))
+    }
+
+    /// Parses `IDENTIFIED [WITH mysql_native_password] {BY 'password' | AS 'password_hash'
+    /// ['mysql_native_password']}`, returns the password and whether the MySQL native
+    /// password authentication is enabled.
+    ///
+    /// The hash of the MySQL native password follows the password hash iff the
+    /// authentication is enabled.
+    fn parse_identified_by(&mut self) -> Result<(Password, bool)> {
+        if !self.consume_token(IDENTIFIED) {
+            return self.expected(IDENTIFIED, self.parser.peek_token());
+        }
+        let mysql_native_password = self.parser.parse_keyword(Keyword::WITH);
+        if mysql_native_password && !self.consume_token(MYSQL_NATIVE_PASSWORD) {
+            return self.expected(MYSQL_NATIVE_PASSWORD, self.parser.peek_token());
+        }
+        let password = match self
+            .parser
+            .expect_one_of_keywords(&[Keyword::BY, Keyword::AS])
+            .context(SyntaxSnafu)?
+        {
+            Keyword::AS => Password::Hashed {
+                password_hash: self.parse_password_string("a password hash string")?,
+                mysql_native_password: if mysql_native_password {
+                    Some(self.parse_password_string("a MySQL native password hash string")?)
+                } else {
+                    None
+                },
+            },
+            _ => Password::Plain(self.parse_password_string("a password string")?),
+        };
+
+        Ok((password, mysql_native_password))
+    }
+
+    fn parse_password_string(&mut self, expected: &str) -> Result<String> {
+        let password = self
+            .parser
+            .parse_literal_string()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected,
+                actual: self.peek_token_as_string(),
+            })?;
+        if password.is_empty() {
+            return error::InvalidSqlSnafu {
+                msg: "password must not be empty",
+            }
+            .fail();
+        }
+
+        Ok(password)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    fn parse(sql: &str) -> Result<Statement> {
+        let mut stmts = ParserContext::create_with_dialect(
+            sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default(),
+        )?;
+        assert_eq!(1, stmts.len());
+        Ok(stmts.pop().unwrap())
+    }
+
+    #[test]
+    fn test_parse_create_user() {
+        let stmt = parse("CREATE USER IF NOT EXISTS Alice IDENTIFIED BY 'p@ss'").unwrap();
+        assert_eq!(
+            Statement::CreateUser(CreateUser {
+                name: Ident::new("alice"),
+                password: Password::Plain("p@ss".to_string()),
+                mysql_native_password: false,
+                if_not_exists: true,
+            }),
+            stmt
+        );
+        let Statement::CreateUser(create_user) = stmt else {
+            unreachable!()
+        };
+        assert_eq!(
+            "CREATE USER IF NOT EXISTS alice IDENTIFIED BY '******'",
+            create_user.to_string()
+        );
+        assert!(!format!("{create_user:?}").contains("p@ss"));
+
+        let stmt =
+            parse("CREATE USER alice IDENTIFIED WITH mysql_native_password BY 'p@ss'").unwrap();
+        let Statement::CreateUser(create_user) = stmt else {
+            unreachable!()
+        };
+        assert!(create_user.mysql_native_password);
+        assert_eq!(
+            "CREATE USER alice IDENTIFIED WITH mysql_native_password BY '******'",
+            create_user.to_string()
+        );
+
+        assert!(parse("CREATE USER alice").is_err());
+        assert!(parse("CREATE USER alice IDENTIFIED WITH caching_sha2 BY 'p@ss'").is_err());
+        assert!(parse("CREATE USER alice IDENTIFIED BY password").is_err());
+        assert!(parse("CREATE USER alice IDENTIFIED BY ''").is_err());
+    }
+
+    #[test]
+    fn test_parse_create_user_with_hashes() {
+        let stmt = parse("CREATE USER alice IDENTIFIED AS '$argon2id$hash'").unwrap();
+        let Statement::CreateUser(create_user) = stmt else {
+            unreachable!()
+        };
+        assert!(
+            create_user.password
+                == Password::Hashed {
+                    password_hash: "$argon2id$hash".to_string(),
+                    mysql_native_password: None,
+                }
+        );
+        assert_eq!(
+            "CREATE USER alice IDENTIFIED AS '******'",
+            create_user.to_string()
+        );
+
+        let stmt = parse(
+            "CREATE USER alice IDENTIFIED WITH mysql_native_password AS '$argon2id$hash' '6bb4'",
+        )
+        .unwrap();
+        let Statement::CreateUser(create_user) = stmt else {
+            unreachable!()
+        };
+        assert!(
+            create_user.password
+                == Password::Hashed {
+                    password_hash: "$argon2id$hash".to_string(),
+                    mysql_native_password: Some("6bb4".to_string()),
+                }
+        );
+        assert_eq!(
+            "CREATE USER alice IDENTIFIED WITH mysql_native_password AS '******' '******'",
+            create_user.to_string()
+        );
+        assert!(!format!("{create_user:?}").contains("6bb4"));
+
+        assert!(parse(
+            "CREATE USER alice IDENTIFIED WITH mysql_native_password AS '$argon2id$hash'"
+        )
+        .is_err());
+        assert!(parse("CREATE USER alice IDENTIFIED AS ''").is_err());
+    }
+
+    #[test]
+    fn test_parse_alter_user() {
+        let stmt = parse("ALTER USER alice IDENTIFIED BY 'secret'").unwrap();
+        assert_eq!(
+            Statement::AlterUser(AlterUser {
+                name: Ident::new("alice"),
+                password: Password::Plain("secret".to_string()),
+                mysql_native_password: false,
+            }),
+            stmt
+        );
+        let Statement::AlterUser(alter_user) = stmt else {
+            unreachable!()
+        };
+        assert_eq!(
+            "ALTER USER alice IDENTIFIED BY '******'",
+            alter_user.to_string()
+        );
+
+        assert!(parse("ALTER USER alice").is_err());
+    }
+
+    #[test]
+    fn test_parse_drop_user() {
+        let stmt = parse("DROP USER IF EXISTS alice").unwrap();
+        assert_eq!(
+            Statement::DropUser(DropUser {
+                name: Ident::new("alice"),
+                if_exists: true,
+            }),
+            stmt
+        );
+        let Statement::DropUser(drop_user) = stmt else {
+            unreachable!()
+        };
+        assert_eq!("DROP USER IF EXISTS alice", drop_user.to_string());
+    }
+}
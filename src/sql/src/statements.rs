@@ -20,14 +20,18 @@ pub mod describe;
 pub mod drop;
 pub mod explain;
 pub mod insert;
+pub mod kill;
 mod option_map;
+pub mod privilege;
 pub mod query;
+pub mod refresh;
 pub mod set_variables;
 pub mod show;
 pub mod statement;
 pub mod tql;
 mod transform;
 pub mod truncate;
+pub mod user;
 
 use std::str::FromStr;
 
@@ -284,8 +288,8 @@ pub fn value_to_sql_value(val: &Value) -> Result<SqlValue> {
         Value::DateTime(d) => SqlValue::SingleQuotedString(d.to_string()),
         Value::Timestamp(ts) => SqlValue::SingleQuotedString(ts.to_iso8601_string()),
         Value::String(s) => SqlValue::SingleQuotedString(s.as_utf8().to_string()),
+        Value::Binary(b) => SqlValue::HexStringLiteral(hex::encode_upper(&b[..])),
         Value::Null => SqlValue::Null,
-        // TODO(dennis): supports binary
         _ => return ConvertValueSnafu { value: val.clone() }.fail(),
     })
 }
@@ -714,6 +718,11 @@ mod tests {
         let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(format!("{v:?}").contains("invalid character"), "v is {v:?}",);
+
+        assert_eq!(
+            SqlValue::HexStringLiteral("48656C6C6F".to_string()),
+            value_to_sql_value(&Value::Binary(Bytes::from(b"Hello".as_slice()))).unwrap()
+        );
     }
 
     #[test]
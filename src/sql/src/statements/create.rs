@@ -20,6 +20,7 @@ use sqlparser::ast::Expr;
 use sqlparser_derive::{Visit, VisitMut};
 
 use crate::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint, Value as SqlValue};
+use crate::statements::query::Query;
 use crate::statements::OptionMap;
 
 const LINE_SEP: &str = ",\n";
@@ -204,14 +205,18 @@ pub struct CreateDatabase {
     pub name: ObjectName,
     /// Create if not exists
     pub if_not_exists: bool,
+    /// Database options in `WITH`.
+    /// All keys are lowercase.
+    pub options: OptionMap,
 }
 
 impl CreateDatabase {
     /// Creates a statement for `CREATE DATABASE`
-    pub fn new(name: ObjectName, if_not_exists: bool) -> Self {
+    pub fn new(name: ObjectName, if_not_exists: bool, options: OptionMap) -> Self {
         Self {
             name,
             if_not_exists,
+            options,
         }
     }
 }
@@ -237,6 +242,61 @@ pub struct CreateTableLike {
     pub source_name: ObjectName,
 }
 
+/// CREATE VIEW statement.
+#[derive(Debug, PartialEq, Eq, Clone, Visit, VisitMut)]
+pub struct CreateView {
+    /// View name
+    pub name: ObjectName,
+    /// The query that defines the view
+    pub query: Box<Query>,
+    /// Replace the view if it already exists
+    pub or_replace: bool,
+    /// Create if not exists
+    pub if_not_exists: bool,
+}
+
+impl Display for CreateView {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let or_replace = if self.or_replace { "OR REPLACE " } else { "" };
+        let if_not_exists = if self.if_not_exists {
+            "IF NOT EXISTS "
+        } else {
+            ""
+        };
+        write!(
+            f,
+            "CREATE {or_replace}VIEW {if_not_exists}{} AS {}",
+            self.name, self.query
+        )
+    }
+}
+
+/// CREATE MATERIALIZED VIEW statement.
+#[derive(Debug, PartialEq, Eq, Clone, Visit, VisitMut)]
+pub struct CreateMaterializedView {
+    /// View name
+    pub name: ObjectName,
+    /// The query that defines the view
+    pub query: Box<Query>,
+    /// Create if not exists
+    pub if_not_exists: bool,
+}
+
+impl Display for CreateMaterializedView {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let if_not_exists = if self.if_not_exists {
+            "IF NOT EXISTS "
+        } else {
+            ""
+        };
+        write!(
+            f,
+            "CREATE MATERIALIZED VIEW {if_not_exists}{} AS {}",
+            self.name, self.query
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
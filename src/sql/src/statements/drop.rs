@@ -21,6 +21,8 @@ pub struct DropTable {
     table_name: ObjectName,
     /// drop table if exists
     drop_if_exists: bool,
+    /// drop views that depend on the table
+    cascade: bool,
 }
 
 impl DropTable {
@@ -29,9 +31,16 @@ impl DropTable {
         Self {
             table_name,
             drop_if_exists: if_exists,
+            cascade: false,
         }
     }
 
+    /// Drops views that depend on the table as well, instead of failing.
+    pub fn with_cascade(mut self, cascade: bool) -> Self {
+        self.cascade = cascade;
+        self
+    }
+
     pub fn table_name(&self) -> &ObjectName {
         &self.table_name
     }
@@ -39,6 +48,62 @@ impl DropTable {
     pub fn drop_if_exists(&self) -> bool {
         self.drop_if_exists
     }
+
+    pub fn cascade(&self) -> bool {
+        self.cascade
+    }
+}
+
+/// DROP VIEW statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct DropView {
+    view_name: ObjectName,
+    /// drop view if exists
+    drop_if_exists: bool,
+}
+
+impl DropView {
+    /// Creates a statement for `DROP VIEW`
+    pub fn new(view_name: ObjectName, if_exists: bool) -> Self {
+        Self {
+            view_name,
+            drop_if_exists: if_exists,
+        }
+    }
+
+    pub fn view_name(&self) -> &ObjectName {
+        &self.view_name
+    }
+
+    pub fn drop_if_exists(&self) -> bool {
+        self.drop_if_exists
+    }
+}
+
+/// DROP MATERIALIZED VIEW statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct DropMaterializedView {
+    view_name: ObjectName,
+    /// drop view if exists
+    drop_if_exists: bool,
+}
+
+impl DropMaterializedView {
+    /// Creates a statement for `DROP MATERIALIZED VIEW`
+    pub fn new(view_name: ObjectName, if_exists: bool) -> Self {
+        Self {
+            view_name,
+            drop_if_exists: if_exists,
+        }
+    }
+
+    pub fn view_name(&self) -> &ObjectName {
+        &self.view_name
+    }
+
+    pub fn drop_if_exists(&self) -> bool {
+        self.drop_if_exists
+    }
 }
 
 /// DROP DATABASE statement.
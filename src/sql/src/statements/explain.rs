@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
+
 use sqlparser::ast::Statement as SpStatement;
 use sqlparser_derive::{Visit, VisitMut};
 
 use crate::error::Error;
+use crate::statements::query::Query;
 
 /// Explain statement.
 #[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
@@ -36,3 +39,53 @@ impl ToString for Explain {
         self.inner.to_string()
     }
 }
+
+/// Output format of `EXPLAIN PROFILE`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Visit, VisitMut)]
+pub enum ProfileFormat {
+    /// SVG flamegraph.
+    #[default]
+    Flamegraph,
+    /// Google's pprof format in protobuf.
+    Pprof,
+}
+
+impl fmt::Display for ProfileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileFormat::Flamegraph => write!(f, "FLAMEGRAPH"),
+            ProfileFormat::Pprof => write!(f, "PPROF"),
+        }
+    }
+}
+
+/// `EXPLAIN PROFILE [FORMAT {FLAMEGRAPH | PPROF}] <query>` statement, which runs
+/// the query with CPU sampling and returns the profile.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct ExplainProfile {
+    pub format: ProfileFormat,
+    pub query: Box<Query>,
+}
+
+impl fmt::Display for ExplainProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "EXPLAIN PROFILE FORMAT {} {}",
+            self.format, self.query.inner
+        )
+    }
+}
+
+/// `EXPLAIN CAPTURE <query>` statement, which runs the query and returns a
+/// bundle to replay it when debugging wrong results.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct ExplainCapture {
+    pub query: Box<Query>,
+}
+
+impl fmt::Display for ExplainCapture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EXPLAIN CAPTURE {}", self.query.inner)
+    }
+}
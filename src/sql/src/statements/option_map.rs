@@ -21,7 +21,7 @@ use std::iter::FromIterator;
 
 /// Options hashmap.
 /// Because the trait `Visit` and `VisitMut` is not implemented for `HashMap<String, String>`, we have to wrap it and implement them by ourself.
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
 pub struct OptionMap {
     pub map: HashMap<String, String>,
 }
@@ -34,6 +34,10 @@ impl OptionMap {
     pub fn get(&self, k: &str) -> Option<&String> {
         self.map.get(k)
     }
+
+    pub fn into_map(self) -> HashMap<String, String> {
+        self.map
+    }
 }
 
 impl From<HashMap<String, String>> for OptionMap {
@@ -0,0 +1,152 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser_derive::{Visit, VisitMut};
+
+/// A privilege in `GRANT` and `REVOKE` statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Visit, VisitMut)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Create,
+    Drop,
+}
+
+impl Privilege {
+    /// All the privileges, what `ALL [PRIVILEGES]` stands for.
+    pub const ALL: [Privilege; 4] = [
+        Privilege::Select,
+        Privilege::Insert,
+        Privilege::Create,
+        Privilege::Drop,
+    ];
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Privilege::Select => write!(f, "SELECT"),
+            Privilege::Insert => write!(f, "INSERT"),
+            Privilege::Create => write!(f, "CREATE"),
+            Privilege::Drop => write!(f, "DROP"),
+        }
+    }
+}
+
+/// The object privileges are granted on.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub enum GrantObject {
+    /// `CATALOG name`
+    Catalog(Ident),
+    /// `DATABASE [catalog.]name`
+    Database(ObjectName),
+    /// `[TABLE] [[catalog.]schema.]name`
+    Table(ObjectName),
+}
+
+impl Display for GrantObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrantObject::Catalog(name) => write!(f, "CATALOG {name}"),
+            GrantObject::Database(name) => write!(f, "DATABASE {name}"),
+            GrantObject::Table(name) => write!(f, "TABLE {name}"),
+        }
+    }
+}
+
+/// What is granted or revoked.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub enum Grantable {
+    /// Privileges on an object.
+    Privileges {
+        privileges: Vec<Privilege>,
+        object: GrantObject,
+    },
+    /// A role, only grantable to users.
+    Role(Ident),
+}
+
+impl Display for Grantable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grantable::Privileges { privileges, object } => {
+                write!(f, "{} ON {object}", privileges.iter().join(", "))
+            }
+            Grantable::Role(role) => write!(f, "{role}"),
+        }
+    }
+}
+
+/// CREATE ROLE statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct CreateRole {
+    pub name: Ident,
+    pub if_not_exists: bool,
+}
+
+impl Display for CreateRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CREATE ROLE ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// DROP ROLE statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct DropRole {
+    pub name: Ident,
+    pub if_exists: bool,
+}
+
+impl Display for DropRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP ROLE ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// GRANT statement, grants privileges to a user or a role, or a role to a user.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct Grant {
+    pub grantable: Grantable,
+    pub grantee: Ident,
+}
+
+impl Display for Grant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GRANT {} TO {}", self.grantable, self.grantee)
+    }
+}
+
+/// REVOKE statement, the reverse of [Grant].
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct Revoke {
+    pub grantable: Grantable,
+    pub grantee: Ident,
+}
+
+impl Display for Revoke {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "REVOKE {} FROM {}", self.grantable, self.grantee)
+    }
+}
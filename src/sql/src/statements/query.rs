@@ -12,12 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::fmt;
+use std::ops::ControlFlow;
 
-use sqlparser::ast::Query as SpQuery;
+use sqlparser::ast::{
+    Expr, Ident, ObjectName, Query as SpQuery, SetExpr, TableFactor, TableWithJoins, VisitMut as _,
+    VisitorMut, With,
+};
 use sqlparser_derive::{Visit, VisitMut};
 
 use crate::error::Error;
+use crate::parser::ParserContext;
 
 /// Query statement instance.
 #[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
@@ -25,6 +31,189 @@ pub struct Query {
     pub inner: SpQuery,
 }
 
+impl Query {
+    /// Renames tables the query reads from, including tables qualifying columns.
+    /// `rename` returns the new name of a table, or `None` to keep the table.
+    /// Returns true if any table is renamed.
+    ///
+    /// Names of CTEs and table aliases in scope shadow tables, so they are never renamed.
+    pub fn rename_tables(&mut self, rename: impl FnMut(&ObjectName) -> Option<String>) -> bool {
+        let mut renamer = TableRenamer {
+            rename,
+            scopes: Vec::new(),
+            withs: Vec::new(),
+            renamed: false,
+        };
+        let _ = self.inner.visit(&mut renamer);
+        renamer.renamed
+    }
+}
+
+/// Names bound by a query.
+#[derive(Default)]
+struct Scope {
+    ctes: HashSet<String>,
+    /// Aliases of tables in the `FROM` clause.
+    aliases: HashSet<String>,
+    /// Names of tables without aliases in the `FROM` clause.
+    tables: HashSet<String>,
+}
+
+/// Renames tables of a query, see [Query::rename_tables].
+struct TableRenamer<F> {
+    rename: F,
+    /// Scopes of the enclosing queries, the innermost last.
+    scopes: Vec<Scope>,
+    /// `WITH` clauses taken out of the enclosing queries while their bodies are visited.
+    withs: Vec<Option<With>>,
+    renamed: bool,
+}
+
+impl<F: FnMut(&ObjectName) -> Option<String>> TableRenamer<F> {
+    fn is_cte(&self, name: &str) -> bool {
+        self.scopes.iter().any(|scope| scope.ctes.contains(name))
+    }
+
+    /// Returns true if the column qualifier `name` refers to a CTE or a table alias,
+    /// resolved from the innermost query.
+    fn is_bound_qualifier(&self, name: &str) -> bool {
+        for (i, scope) in self.scopes.iter().enumerate().rev() {
+            if scope.aliases.contains(name) {
+                return true;
+            }
+            if scope.tables.contains(name) {
+                return self.scopes[..=i]
+                    .iter()
+                    .any(|scope| scope.ctes.contains(name));
+            }
+        }
+        self.is_cte(name)
+    }
+
+    /// Renames the table `name`, whose last identifier is replaced.
+    fn rename(&mut self, name: &mut [Ident]) {
+        if let Some(new_name) = (self.rename)(&ObjectName(name.to_vec())) {
+            // Quotes the name to keep its case.
+            if let Some(table) = name.last_mut() {
+                *table = Ident::with_quote('"', new_name);
+                self.renamed = true;
+            }
+        }
+    }
+}
+
+impl<F: FnMut(&ObjectName) -> Option<String>> VisitorMut for TableRenamer<F> {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &mut SpQuery) -> ControlFlow<Self::Break> {
+        let mut scope = Scope::default();
+        collect_from_tables(&query.body, &mut scope);
+
+        // A CTE is visible in the following CTEs and the body, or also in itself if
+        // the `WITH` is recursive. Visits the CTEs here as they can't see the tables
+        // of the body.
+        let mut with = query.with.take();
+        if let Some(with) = &mut with {
+            for cte in &mut with.cte_tables {
+                let name = canonicalize(&cte.alias.name);
+                if with.recursive {
+                    let _ = scope.ctes.insert(name.clone());
+                }
+                self.scopes.push(Scope {
+                    ctes: scope.ctes.clone(),
+                    ..Default::default()
+                });
+                let _ = cte.query.visit(self);
+                let _ = self.scopes.pop();
+                let _ = scope.ctes.insert(name);
+            }
+        }
+        self.scopes.push(scope);
+        self.withs.push(with);
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, query: &mut SpQuery) -> ControlFlow<Self::Break> {
+        let _ = self.scopes.pop();
+        query.with = self.withs.pop().flatten();
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_relation(&mut self, relation: &mut ObjectName) -> ControlFlow<Self::Break> {
+        if let [ident] = &relation.0[..] {
+            if self.is_cte(&canonicalize(ident)) {
+                return ControlFlow::Continue(());
+            }
+        }
+        self.rename(&mut relation.0);
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<Self::Break> {
+        if let Expr::CompoundIdentifier(idents) = expr {
+            if let [qualifier @ .., _column] = &mut idents[..] {
+                if let [ident] = qualifier {
+                    if self.is_bound_qualifier(&canonicalize(ident)) {
+                        return ControlFlow::Continue(());
+                    }
+                }
+                if !qualifier.is_empty() {
+                    self.rename(qualifier);
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+fn canonicalize(ident: &Ident) -> String {
+    ParserContext::canonicalize_identifier(ident.clone()).value
+}
+
+/// Collects tables in the `FROM` clauses of the query `body` into the `scope`,
+/// excluding the ones in subqueries.
+fn collect_from_tables(body: &SetExpr, scope: &mut Scope) {
+    match body {
+        SetExpr::Select(select) => {
+            for table in &select.from {
+                collect_joined_tables(table, scope);
+            }
+        }
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_from_tables(left, scope);
+            collect_from_tables(right, scope);
+        }
+        _ => {}
+    }
+}
+
+fn collect_joined_tables(table: &TableWithJoins, scope: &mut Scope) {
+    for relation in std::iter::once(&table.relation).chain(table.joins.iter().map(|j| &j.relation))
+    {
+        match relation {
+            TableFactor::Table {
+                alias: Some(alias), ..
+            }
+            | TableFactor::Derived {
+                alias: Some(alias), ..
+            } => {
+                let _ = scope.aliases.insert(canonicalize(&alias.name));
+            }
+            TableFactor::Table {
+                name, alias: None, ..
+            } => {
+                if let Some(table) = name.0.last() {
+                    let _ = scope.tables.insert(canonicalize(table));
+                }
+            }
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => collect_joined_tables(table_with_joins, scope),
+            _ => {}
+        }
+    }
+}
+
 /// Automatically converts from sqlparser Query instance to SqlQuery.
 impl TryFrom<SpQuery> for Query {
     type Error = Error;
@@ -88,4 +277,40 @@ mod test {
             "SELECT * FROM abc LEFT JOIN bcd WHERE abc.a = 1 AND bcd.d = 7 AND abc.id = bcd.id"
         );
     }
+
+    #[test]
+    fn test_rename_tables() {
+        let mut query = create_query(
+            "select abc.x from abc where x in (select x from public.abc) union all select bcd.x from bcd",
+        )
+        .unwrap();
+        let renamed = query.rename_tables(|name| {
+            (name.0.last().unwrap().value == "abc").then(|| "Abc_1".to_string())
+        });
+        assert!(renamed);
+        assert_eq!(
+            query.to_string(),
+            "SELECT \"Abc_1\".x FROM \"Abc_1\" WHERE x IN (SELECT x FROM public.\"Abc_1\") UNION ALL SELECT bcd.x FROM bcd"
+        );
+
+        assert!(!query.rename_tables(|_| None));
+    }
+
+    #[test]
+    fn test_rename_tables_skip_bound_names() {
+        let mut query = create_query(
+            "with abc as (select x from abc) select abc.x, a.x from abc join bcd as a on abc.x = a.x where a.x in (select a.x from a)",
+        )
+        .unwrap();
+        let renamed = query.rename_tables(|name| {
+            let table = &name.0.last().unwrap().value;
+            (table == "abc" || table == "a").then(|| format!("{table}_1"))
+        });
+        // The CTE reads the table abc, and the subquery reads the table a.
+        assert!(renamed);
+        assert_eq!(
+            query.to_string(),
+            "WITH abc AS (SELECT x FROM \"abc_1\") SELECT abc.x, a.x FROM abc JOIN bcd AS a ON abc.x = a.x WHERE a.x IN (SELECT \"a_1\".x FROM \"a_1\")"
+        );
+    }
 }
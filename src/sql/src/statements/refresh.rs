@@ -0,0 +1,41 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+
+use sqlparser::ast::ObjectName;
+use sqlparser_derive::{Visit, VisitMut};
+
+/// REFRESH MATERIALIZED VIEW statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct RefreshMaterializedView {
+    view_name: ObjectName,
+}
+
+impl RefreshMaterializedView {
+    /// Creates a statement for `REFRESH MATERIALIZED VIEW`
+    pub fn new(view_name: ObjectName) -> Self {
+        Self { view_name }
+    }
+
+    pub fn view_name(&self) -> &ObjectName {
+        &self.view_name
+    }
+}
+
+impl Display for RefreshMaterializedView {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "REFRESH MATERIALIZED VIEW {}", self.view_name)
+    }
+}
@@ -80,12 +80,34 @@ pub struct ShowCreateTable {
     pub table_name: ObjectName,
 }
 
+/// SQL structure for `SHOW CREATE DATABASE [FULL]`.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct ShowCreateDatabase {
+    pub database_name: ObjectName,
+    /// Whether `FULL` is specified, then the statements to create the tables and views
+    /// are shown too, as a script to replay in another deployment.
+    pub full: bool,
+}
+
+/// SQL structure for `SHOW CREATE USERS`, which shows the statements to recreate the
+/// users, roles and grants of the cluster.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct ShowCreateUsers;
+
 /// SQL structure for `SHOW VARIABLES xxx`.
 #[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
 pub struct ShowVariables {
     pub variable: ObjectName,
 }
 
+/// SQL structure for `SHOW [FULL] PROCESSLIST`.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct ShowProcesslist {
+    /// Whether `FULL` is specified. Queries are never truncated so it only exists
+    /// for MySQL compatibility.
+    pub full: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -161,6 +183,45 @@ mod tests {
             }
         }
     }
+    #[test]
+    pub fn test_show_create_database() {
+        let sql = "SHOW CREATE DATABASE test";
+        let stmts: Vec<Statement> =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, stmts.len());
+        assert_matches!(
+            &stmts[0],
+            Statement::ShowCreateDatabase(ShowCreateDatabase { database_name, full: false })
+                if database_name.to_string() == "test"
+        );
+
+        let sql = "SHOW CREATE SCHEMA test FULL";
+        let stmts: Vec<Statement> =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_matches!(
+            &stmts[0],
+            Statement::ShowCreateDatabase(ShowCreateDatabase { database_name, full: true })
+                if database_name.to_string() == "test"
+        );
+
+        let sql = "SHOW CREATE USERS";
+        let stmts: Vec<Statement> =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(vec![Statement::ShowCreateUsers(ShowCreateUsers)], stmts);
+
+        for sql in ["SHOW CREATE DATABASE", "SHOW CREATE DATABASE a.b"] {
+            assert!(ParserContext::create_with_dialect(
+                sql,
+                &GreptimeDbDialect {},
+                ParseOptions::default()
+            )
+            .is_err());
+        }
+    }
+
     #[test]
     pub fn test_show_create_missing_table_name() {
         let sql = "SHOW CREATE TABLE";
@@ -15,28 +15,39 @@
 use datafusion_sql::parser::Statement as DfStatement;
 use sqlparser::ast::Statement as SpStatement;
 use sqlparser_derive::{Visit, VisitMut};
+use strum::IntoStaticStr;
 
-use super::drop::DropDatabase;
-use super::show::ShowVariables;
+use super::drop::{DropDatabase, DropMaterializedView, DropView};
+use super::show::{ShowProcesslist, ShowVariables};
 use crate::error::{ConvertToDfStatementSnafu, Error};
 use crate::statements::alter::AlterTable;
 use crate::statements::create::{
-    CreateDatabase, CreateExternalTable, CreateTable, CreateTableLike,
+    CreateDatabase, CreateExternalTable, CreateMaterializedView, CreateTable, CreateTableLike,
+    CreateView,
 };
 use crate::statements::delete::Delete;
 use crate::statements::describe::DescribeTable;
 use crate::statements::drop::DropTable;
-use crate::statements::explain::Explain;
+use crate::statements::explain::{Explain, ExplainCapture, ExplainProfile};
 use crate::statements::insert::Insert;
+use crate::statements::kill::KillQuery;
+use crate::statements::privilege::{CreateRole, DropRole, Grant, Revoke};
 use crate::statements::query::Query;
+use crate::statements::refresh::RefreshMaterializedView;
 use crate::statements::set_variables::SetVariables;
-use crate::statements::show::{ShowColumns, ShowCreateTable, ShowDatabases, ShowIndex, ShowTables};
+use crate::statements::show::{
+    ShowColumns, ShowCreateDatabase, ShowCreateTable, ShowCreateUsers, ShowDatabases, ShowIndex,
+    ShowTables,
+};
 use crate::statements::tql::Tql;
 use crate::statements::truncate::TruncateTable;
+use crate::statements::user::{AlterUser, CreateUser, DropUser};
 
 /// Tokens parsed by `DFParser` are converted into these values.
+///
+/// Converting a `&Statement` into `&'static str` returns the name of its type, e.g. `CreateTable`.
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, IntoStaticStr)]
 pub enum Statement {
     // Query
     Query(Box<Query>),
@@ -50,8 +61,18 @@ pub enum Statement {
     CreateExternalTable(CreateExternalTable),
     // CREATE TABLE ... LIKE
     CreateTableLike(CreateTableLike),
+    // CREATE VIEW
+    CreateView(CreateView),
+    // CREATE MATERIALIZED VIEW
+    CreateMaterializedView(CreateMaterializedView),
     // DROP TABLE
     DropTable(DropTable),
+    // DROP VIEW
+    DropView(DropView),
+    // DROP MATERIALIZED VIEW
+    DropMaterializedView(DropMaterializedView),
+    // REFRESH MATERIALIZED VIEW
+    RefreshMaterializedView(RefreshMaterializedView),
     // DROP DATABASE
     DropDatabase(DropDatabase),
     // CREATE DATABASE
@@ -68,12 +89,24 @@ pub enum Statement {
     ShowIndex(ShowIndex),
     // SHOW CREATE TABLE
     ShowCreateTable(ShowCreateTable),
+    // SHOW CREATE DATABASE
+    ShowCreateDatabase(ShowCreateDatabase),
+    // SHOW CREATE USERS
+    ShowCreateUsers(ShowCreateUsers),
     // DESCRIBE TABLE
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
     Explain(Explain),
+    // EXPLAIN PROFILE QUERY
+    ExplainProfile(ExplainProfile),
+    // EXPLAIN CAPTURE QUERY
+    ExplainCapture(ExplainCapture),
     // COPY
     Copy(crate::statements::copy::Copy),
+    // BACKUP DATABASE, rows before a cutoff time, not a consistent snapshot
+    BackupDatabase(crate::statements::copy::CopyDatabaseArgument),
+    // RESTORE DATABASE
+    RestoreDatabase(crate::statements::copy::CopyDatabaseArgument),
     Tql(Tql),
     // TRUNCATE TABLE
     TruncateTable(TruncateTable),
@@ -81,6 +114,24 @@ pub enum Statement {
     SetVariables(SetVariables),
     // SHOW VARIABLES
     ShowVariables(ShowVariables),
+    // SHOW PROCESSLIST
+    ShowProcesslist(ShowProcesslist),
+    // KILL QUERY
+    KillQuery(KillQuery),
+    // CREATE ROLE
+    CreateRole(CreateRole),
+    // DROP ROLE
+    DropRole(DropRole),
+    // GRANT
+    Grant(Grant),
+    // REVOKE
+    Revoke(Revoke),
+    // CREATE USER
+    CreateUser(CreateUser),
+    // ALTER USER
+    AlterUser(AlterUser),
+    // DROP USER
+    DropUser(DropUser),
 }
 
 /// Comment hints from SQL.
@@ -0,0 +1,134 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::fmt::{Debug, Display, Formatter};
+
+use sqlparser::ast::Ident;
+use sqlparser_derive::{Visit, VisitMut};
+
+/// How passwords are displayed and debugged, so that they never leak into logs.
+const REDACTED_PASSWORD: &str = "'******'";
+
+/// The auth plugin in `IDENTIFIED WITH mysql_native_password BY 'password'`, which
+/// opts in to the MySQL native password authentication.
+pub const MYSQL_NATIVE_PASSWORD: &str = "mysql_native_password";
+
+/// The password of a user.
+#[derive(Clone, PartialEq, Eq, Visit, VisitMut)]
+pub enum Password {
+    /// `BY 'password'`, the password in plaintext.
+    Plain(String),
+    /// `AS 'password_hash' ['mysql_native_password']`, the stored hashes of a password,
+    /// which is how `SHOW CREATE USERS` exports users.
+    Hashed {
+        password_hash: String,
+        mysql_native_password: Option<String>,
+    },
+}
+
+/// CREATE USER statement.
+#[derive(Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct CreateUser {
+    pub name: Ident,
+    pub password: Password,
+    /// Whether the user can log in with the MySQL native password authentication.
+    pub mysql_native_password: bool,
+    pub if_not_exists: bool,
+}
+
+impl Debug for CreateUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreateUser")
+            .field("name", &self.name)
+            .field("password", &REDACTED_PASSWORD)
+            .field("mysql_native_password", &self.mysql_native_password)
+            .field("if_not_exists", &self.if_not_exists)
+            .finish()
+    }
+}
+
+impl Display for CreateUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CREATE USER ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(f, "{} ", self.name)?;
+        fmt_identified_by(f, &self.password, self.mysql_native_password)
+    }
+}
+
+/// ALTER USER statement, changes the password of a user.
+#[derive(Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct AlterUser {
+    pub name: Ident,
+    pub password: Password,
+    /// Whether the user can log in with the MySQL native password authentication.
+    pub mysql_native_password: bool,
+}
+
+impl Debug for AlterUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlterUser")
+            .field("name", &self.name)
+            .field("password", &REDACTED_PASSWORD)
+            .field("mysql_native_password", &self.mysql_native_password)
+            .finish()
+    }
+}
+
+impl Display for AlterUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ALTER USER {} ", self.name)?;
+        fmt_identified_by(f, &self.password, self.mysql_native_password)
+    }
+}
+
+fn fmt_identified_by(
+    f: &mut Formatter<'_>,
+    password: &Password,
+    mysql_native_password: bool,
+) -> std::fmt::Result {
+    write!(f, "IDENTIFIED ")?;
+    if mysql_native_password {
+        write!(f, "WITH {MYSQL_NATIVE_PASSWORD} ")?;
+    }
+    match password {
+        Password::Plain(_) => write!(f, "BY {REDACTED_PASSWORD}"),
+        Password::Hashed {
+            mysql_native_password: None,
+            ..
+        } => write!(f, "AS {REDACTED_PASSWORD}"),
+        Password::Hashed {
+            mysql_native_password: Some(_),
+            ..
+        } => write!(f, "AS {REDACTED_PASSWORD} {REDACTED_PASSWORD}"),
+    }
+}
+
+/// DROP USER statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct DropUser {
+    pub name: Ident,
+    pub if_exists: bool,
+}
+
+impl Display for DropUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP USER ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
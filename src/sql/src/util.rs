@@ -17,7 +17,12 @@ use std::fmt::{Display, Formatter};
 use std::sync::LazyLock;
 
 use regex::Regex;
+use sha1::{Digest, Sha1};
 use sqlparser::ast::{ObjectName, SqlOption, Value};
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::dialect::GreptimeDbDialect;
 
 static SQL_SECRET_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     vec![
@@ -81,10 +86,142 @@ pub fn redact_sql_secrets(sql: &str) -> String {
     s
 }
 
+/// Normalizes the `sql` to its shape, so statements that only differ in literals,
+/// letter case or whitespace have the same normalized text.
+///
+/// Literals are replaced by `?` and lists of literals like `IN (1, 2, 3)` are folded
+/// into a single `?`. Keywords are uppercased, unquoted identifiers are lowercased and
+/// quoted identifiers are kept as they are without quotes. SQL that can't be tokenized
+/// only has its whitespace collapsed.
+pub fn normalize_sql(sql: &str) -> String {
+    let Ok(tokens) = Tokenizer::new(&GreptimeDbDialect {}, sql).tokenize() else {
+        return sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    };
+
+    let mut pieces: Vec<String> = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let piece = match token {
+            Token::Whitespace(_) | Token::EOF => continue,
+            Token::Number(..)
+            | Token::SingleQuotedString(_)
+            | Token::DoubleQuotedString(_)
+            | Token::NationalStringLiteral(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::HexStringLiteral(_)
+            | Token::SingleQuotedByteStringLiteral(_)
+            | Token::DoubleQuotedByteStringLiteral(_)
+            | Token::Placeholder(_) => {
+                // Folds `?, ?` into `?`.
+                let len = pieces.len();
+                if len >= 2 && pieces[len - 1] == "," && pieces[len - 2] == "?" {
+                    let _ = pieces.pop();
+                    continue;
+                }
+                "?".to_string()
+            }
+            Token::Word(word) if word.quote_style.is_some() => word.value,
+            Token::Word(word) if word.keyword == Keyword::NoKeyword => word.value.to_lowercase(),
+            Token::Word(word) => word.value.to_uppercase(),
+            token => token.to_string(),
+        };
+        pieces.push(piece);
+    }
+    while pieces.last().is_some_and(|piece| piece == ";") {
+        let _ = pieces.pop();
+    }
+
+    let mut normalized = String::with_capacity(sql.len());
+    let mut prev: Option<&str> = None;
+    for piece in &pieces {
+        let no_space = matches!(prev, None | Some("(") | Some("."))
+            || matches!(piece.as_str(), "," | ")" | "." | ";");
+        if !no_space {
+            normalized.push(' ');
+        }
+        normalized.push_str(piece);
+        prev = Some(piece);
+    }
+    normalized
+}
+
+/// Splits the `sql` into the text of each statement, without the separating semicolons.
+///
+/// Returns `None` if the `sql` can't be tokenized.
+pub fn split_statements(sql: &str) -> Option<Vec<String>> {
+    let tokens = Tokenizer::new(&GreptimeDbDialect {}, sql).tokenize().ok()?;
+
+    let mut statements = Vec::new();
+    let mut statement = String::new();
+    let mut flush = |statement: &mut String| {
+        let text = statement.trim();
+        if !text.is_empty() {
+            statements.push(text.to_string());
+        }
+        statement.clear();
+    };
+    for token in tokens {
+        match token {
+            Token::SemiColon => flush(&mut statement),
+            Token::EOF => {}
+            token => statement.push_str(&token.to_string()),
+        }
+    }
+    flush(&mut statement);
+    Some(statements)
+}
+
+/// Returns the digest of the `sql`, which is the hex encoded SHA-1 of its
+/// [normalized](normalize_sql) text.
+pub fn sql_digest(sql: &str) -> String {
+    hex::encode(Sha1::digest(normalize_sql(sql).as_bytes()))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_normalize_sql() {
+        assert_eq!(
+            "SELECT * FROM host_metrics WHERE host_name = ? AND ts_millis > ?",
+            normalize_sql(
+                "select *\n  from Host_Metrics where host_name='host1' and TS_MILLIS > 1000;"
+            )
+        );
+        assert_eq!(
+            "SELECT cpu_usage FROM Host_Metrics WHERE cpu_usage IN (?) LIMIT ?",
+            normalize_sql(
+                "SELECT cpu_usage FROM `Host_Metrics` WHERE cpu_usage IN (1.5, 2, 3) LIMIT 10"
+            )
+        );
+        assert_eq!(
+            normalize_sql("SELECT a_col FROM t_table WHERE b_col IN (1)"),
+            normalize_sql("select  A_COL from T_TABLE where B_COL in (1, 2)")
+        );
+        assert_eq!("SELECT 'unclosed", normalize_sql("SELECT   'unclosed"));
+    }
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(
+            vec![
+                "SELECT 1".to_string(),
+                "INSERT INTO t VALUES ('a;b')".to_string()
+            ],
+            split_statements("SELECT 1; INSERT INTO t VALUES ('a;b');\n").unwrap()
+        );
+        assert!(split_statements("").unwrap().is_empty());
+        assert!(split_statements("SELECT 'unclosed").is_none());
+    }
+
+    #[test]
+    fn test_sql_digest() {
+        let digest = sql_digest("SELECT * FROM t_table WHERE a_col = 1");
+        assert_eq!(40, digest.len());
+        assert_eq!(digest, sql_digest("select * from T_TABLE where A_COL = 2;"));
+        assert_ne!(digest, sql_digest("SELECT * FROM t_table WHERE b_col = 1"));
+    }
+
     #[test]
     fn test_redact_sql_secrets() {
         assert_eq!(
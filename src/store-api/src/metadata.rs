@@ -33,7 +33,7 @@ use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize};
 use snafu::{ensure, Location, OptionExt, ResultExt, Snafu};
 
-use crate::region_request::{AddColumn, AddColumnLocation, AlterKind};
+use crate::region_request::{AddColumn, AddColumnLocation, AlterKind, ChangeColumnType};
 use crate::storage::consts::is_internal_column;
 use crate::storage::{ColumnId, RegionId};
 
@@ -535,6 +535,9 @@ impl RegionMetadataBuilder {
         match kind {
             AlterKind::AddColumns { columns } => self.add_columns(columns)?,
             AlterKind::DropColumns { names } => self.drop_columns(&names),
+            AlterKind::ChangeColumnTypes { columns } => self.change_column_types(columns)?,
+            // Options are not part of the metadata.
+            AlterKind::ChangeRegionOptions { .. } => (),
         }
         Ok(self)
     }
@@ -615,6 +618,42 @@ impl RegionMetadataBuilder {
         self.column_metadatas
             .retain(|col| !name_set.contains(&col.column_schema.name));
     }
+
+    /// Changes data types of columns in the metadata.
+    ///
+    /// Nullability, default value and metadata of the column are kept, the
+    /// default value is casted to the new type.
+    fn change_column_types(&mut self, columns: Vec<ChangeColumnType>) -> Result<()> {
+        for change in columns {
+            let Some(column_meta) = self
+                .column_metadatas
+                .iter_mut()
+                .find(|col| col.column_schema.name == change.column_name)
+            else {
+                continue;
+            };
+            let old_schema = &column_meta.column_schema;
+            let default_constraint = match old_schema.default_constraint() {
+                Some(ColumnDefaultConstraint::Value(v)) => Some(ColumnDefaultConstraint::Value(
+                    datatypes::types::cast(v.clone(), &change.target_type)
+                        .context(ConvertDatatypesSnafu)?,
+                )),
+                other => other.cloned(),
+            };
+            let new_schema = ColumnSchema::new(
+                old_schema.name.clone(),
+                change.target_type,
+                old_schema.is_nullable(),
+            )
+            .with_time_index(old_schema.is_time_index())
+            .with_default_constraint(default_constraint)
+            .context(InvalidSchemaSnafu)?
+            .with_metadata(old_schema.metadata().clone());
+            column_meta.column_schema = new_schema;
+        }
+
+        Ok(())
+    }
 }
 
 /// Fields skipped in serialization.
@@ -1123,6 +1162,41 @@ mod test {
         assert_eq!(StatusCode::InvalidArguments, err.status_code());
     }
 
+    #[test]
+    fn test_alter_change_column_types() {
+        // a (tag), b (field), c (ts)
+        let metadata = build_test_region_metadata();
+        let mut builder = RegionMetadataBuilder::from_existing(metadata);
+        builder.push_column_metadata(ColumnMetadata {
+            column_schema: ColumnSchema::new("d", ConcreteDataType::int32_datatype(), false)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value(
+                    datatypes::value::Value::Int32(1),
+                )))
+                .unwrap(),
+            semantic_type: SemanticType::Field,
+            column_id: 4,
+        });
+        builder
+            .alter(AlterKind::ChangeColumnTypes {
+                columns: vec![ChangeColumnType {
+                    column_name: "d".to_string(),
+                    target_type: ConcreteDataType::int64_datatype(),
+                }],
+            })
+            .unwrap();
+        let metadata = builder.build().unwrap();
+        check_columns(&metadata, &["a", "b", "c", "d"]);
+        let column_schema = &metadata.column_by_name("d").unwrap().column_schema;
+        assert_eq!(ConcreteDataType::int64_datatype(), column_schema.data_type);
+        assert!(!column_schema.is_nullable());
+        assert_eq!(
+            Some(&ColumnDefaultConstraint::Value(
+                datatypes::value::Value::Int64(1)
+            )),
+            column_schema.default_constraint()
+        );
+    }
+
     #[test]
     fn test_add_if_not_exists() {
         // a (tag), b (field), c (ts)
@@ -17,6 +17,23 @@
 
 use common_wal::options::WAL_OPTIONS_KEY;
 
+/// Option key to keep all rows written to a region instead of deduplicating them.
+pub const APPEND_MODE_KEY: &str = "append_mode";
+/// Option key of how long to keep WAL entries of a region after they are flushed, so the
+/// region can be recovered by replaying them.
+pub const WAL_RETAIN_PERIOD_KEY: &str = "wal.retain_period";
+/// HashMap key to be used in the region server's extension response of a flush request.
+/// Represents the flushed entry id of the region, as a little endian u64.
+pub const FLUSHED_ENTRY_ID_EXTENSION_KEY: &str = "FLUSHED_ENTRY_ID";
+/// HashMap key to be used in the region server's extension response of a WAL replay
+/// request. Datanodes that don't know the request put its empty rows instead, so the
+/// caller checks the key to know the WAL is replayed.
+pub const WAL_REPLAYED_EXTENSION_KEY: &str = "WAL_REPLAYED";
+/// Prefix of HashMap keys in the region server's extension response of a put request.
+/// The key is followed by the region id, and the value is an encoded `RowInsertRequest`
+/// of the out-of-order rows of the region to the dead-letter table.
+pub const OUT_OF_ORDER_ROWS_EXTENSION_KEY_PREFIX: &str = "OUT_OF_ORDER_ROWS.";
+
 /// Returns true if the `key` is a valid option key for the mito engine.
 pub fn is_mito_engine_option_key(key: &str) -> bool {
     [
@@ -25,6 +42,10 @@ pub fn is_mito_engine_option_key(key: &str) -> bool {
         "compaction.twcs.max_active_window_files",
         "compaction.twcs.max_inactive_window_files",
         "compaction.twcs.time_window",
+        "compaction.size_tiered.min_threshold",
+        "compaction.size_tiered.max_threshold",
+        "compaction.size_tiered.min_file_size",
+        "compaction.size_tiered.tier_ratio",
         "storage",
         "index.inverted_index.ignore_column_ids",
         "index.inverted_index.segment_row_count",
@@ -33,7 +54,15 @@ pub fn is_mito_engine_option_key(key: &str) -> bool {
         "memtable.partition_tree.index_max_keys_per_shard",
         "memtable.partition_tree.data_freeze_threshold",
         "memtable.partition_tree.fork_dictionary_bytes",
-        "append_mode",
+        APPEND_MODE_KEY,
+        "disk_quota",
+        "merge_mode",
+        "out_of_order.window",
+        "out_of_order.policy",
+        "out_of_order.dead_letter_table",
+        "write_rate_limit.rows_per_second",
+        "write_rate_limit.bytes_per_second",
+        WAL_RETAIN_PERIOD_KEY,
     ]
     .contains(&key)
 }
@@ -53,6 +82,12 @@ mod tests {
             "compaction.twcs.max_inactive_window_files"
         ));
         assert!(is_mito_engine_option_key("compaction.twcs.time_window"));
+        assert!(is_mito_engine_option_key(
+            "compaction.size_tiered.min_threshold"
+        ));
+        assert!(is_mito_engine_option_key(
+            "compaction.size_tiered.min_file_size"
+        ));
         assert!(is_mito_engine_option_key("storage"));
         assert!(is_mito_engine_option_key(
             "index.inverted_index.ignore_column_ids"
@@ -72,6 +107,19 @@ mod tests {
             "memtable.partition_tree.fork_dictionary_bytes"
         ));
         assert!(is_mito_engine_option_key("append_mode"));
+        assert!(is_mito_engine_option_key("disk_quota"));
+        assert!(is_mito_engine_option_key("merge_mode"));
+        assert!(is_mito_engine_option_key("out_of_order.window"));
+        assert!(is_mito_engine_option_key("out_of_order.policy"));
+        assert!(is_mito_engine_option_key("out_of_order.dead_letter_table"));
+        assert!(is_mito_engine_option_key(
+            "write_rate_limit.rows_per_second"
+        ));
+        assert!(is_mito_engine_option_key(
+            "write_rate_limit.bytes_per_second"
+        ));
+        assert!(is_mito_engine_option_key("wal.retain_period"));
+        assert!(!is_mito_engine_option_key("wal.replay_from"));
         assert!(!is_mito_engine_option_key("foo"));
     }
 }
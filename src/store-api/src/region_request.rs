@@ -23,6 +23,8 @@ use api::v1::region::{
 };
 use api::v1::{self, Rows, SemanticType};
 pub use common_base::AffectedRows;
+use datatypes::prelude::ConcreteDataType;
+use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt};
 use strum::IntoStaticStr;
 
@@ -32,7 +34,7 @@ use crate::metadata::{
     RegionMetadata, Result,
 };
 use crate::path_utils::region_dir;
-use crate::storage::{ColumnId, RegionId, ScanRequest};
+use crate::storage::{ColumnId, RegionId, RegionNumber, ScanRequest, TableId};
 
 #[derive(Debug, IntoStaticStr)]
 pub enum RegionRequest {
@@ -47,6 +49,7 @@ pub enum RegionRequest {
     Compact(RegionCompactRequest),
     Truncate(RegionTruncateRequest),
     Catchup(RegionCatchupRequest),
+    ReplayWal(RegionReplayWalRequest),
 }
 
 impl RegionRequest {
@@ -82,8 +85,15 @@ fn make_region_puts(inserts: InsertRequests) -> Result<Vec<(RegionId, RegionRequ
         .into_iter()
         .filter_map(|r| {
             let region_id = r.region_id.into();
-            r.rows
-                .map(|rows| (region_id, RegionRequest::Put(RegionPutRequest { rows })))
+            r.rows.map(|rows| {
+                (
+                    region_id,
+                    RegionRequest::Put(RegionPutRequest {
+                        rows,
+                        insert_mode: InsertMode::Overwrite,
+                    }),
+                )
+            })
         })
         .collect();
     Ok(requests)
@@ -199,7 +209,7 @@ fn make_region_compact(compact: CompactRequest) -> Result<Vec<(RegionId, RegionR
     let region_id = compact.region_id.into();
     Ok(vec![(
         region_id,
-        RegionRequest::Compact(RegionCompactRequest {}),
+        RegionRequest::Compact(RegionCompactRequest::default()),
     )])
 }
 
@@ -216,6 +226,134 @@ fn make_region_truncate(truncate: TruncateRequest) -> Result<Vec<(RegionId, Regi
 pub struct RegionPutRequest {
     /// Rows to put.
     pub rows: Rows,
+    /// How rows whose primary key and timestamp already exist are treated.
+    pub insert_mode: InsertMode,
+}
+
+// The region protocol has no typed fields for some options of region requests yet.
+// Until it has them, they travel in the string map of `RegionRequestHeader` next to
+// the tracing context. Only the region server reads them, once per request. Each
+// key should become the protocol field next to it:
+// - [INSERT_MODE_KEY]: `RegionRequestHeader.insert_mode`.
+// - [LEADER_EPOCHS_KEY]: `RegionRequestHeader.leader_epochs`.
+// - [COMPACT_STRATEGY_KEY] and [COMPACT_WINDOW_KEY]: `CompactRequest.strategy` and
+//   `CompactRequest.window_seconds`.
+// - [WAL_REPLAY_KEY]: `RegionRequestHeader.wal_replay_source`.
+// - `session::context::QUERY_MEMORY_LIMIT_KEY`: `QueryRequest.memory_limit`.
+// - `session::context::PROFILE_FREQUENCY_KEY`: `QueryRequest.profile_frequency`.
+// - `session::context::PLAN_METRICS_KEY`: `QueryRequest.plan_metrics`.
+// Add new options to the protocol instead of to this list once it can be updated.
+
+/// Key of the [InsertMode] in query context extensions and in the string map of
+/// a region request header.
+///
+/// The region server reads it from the header and sets [RegionPutRequest::insert_mode].
+pub const INSERT_MODE_KEY: &str = "x-greptime-insert-mode";
+
+/// How a put request treats rows whose primary key and timestamp already exist.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InsertMode {
+    /// Newer rows overwrite existing rows. This is the default behavior.
+    #[default]
+    Overwrite,
+    /// Rows that already exist in the region are skipped.
+    IgnoreDuplicates,
+}
+
+impl InsertMode {
+    /// Returns the name of the mode used in headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InsertMode::Overwrite => "overwrite",
+            InsertMode::IgnoreDuplicates => "ignore",
+        }
+    }
+
+    /// Parses the mode from its header value, case-insensitively.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "overwrite" => Ok(InsertMode::Overwrite),
+            "ignore" => Ok(InsertMode::IgnoreDuplicates),
+            _ => InvalidRawRegionRequestSnafu {
+                err: format!("unknown insert mode '{value}', expected 'overwrite' or 'ignore'"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Writes the mode to the string map of a region request header.
+    pub fn to_header_map(&self, map: &mut HashMap<String, String>) {
+        let _ = map.insert(INSERT_MODE_KEY.to_string(), self.as_str().to_string());
+    }
+
+    /// Reads the mode from the string map of a region request header.
+    /// Returns [InsertMode::Overwrite] if the key is absent.
+    pub fn from_header_map(map: &HashMap<String, String>) -> Result<Self> {
+        map.get(INSERT_MODE_KEY)
+            .map(|value| Self::parse(value))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+/// Key of the [LeaderEpochs] in the string map of a region request header.
+pub const LEADER_EPOCHS_KEY: &str = "x-greptime-leader-epochs";
+
+/// Leader epochs of the regions by which a write request is routed.
+///
+/// Metasrv bumps the leader epoch of a region each time its leader changes,
+/// so the datanode can fence writes routed by a stale route. Writes without epochs
+/// are accepted until all nodes send them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LeaderEpochs(HashMap<RegionId, u64>);
+
+impl LeaderEpochs {
+    /// Sets the leader epoch of the region.
+    pub fn insert(&mut self, region_id: RegionId, epoch: u64) {
+        let _ = self.0.insert(region_id, epoch);
+    }
+
+    /// Returns the leader epoch of the region, if any.
+    pub fn get(&self, region_id: RegionId) -> Option<u64> {
+        self.0.get(&region_id).copied()
+    }
+
+    /// Writes the epochs to the string map of a region request header,
+    /// as comma separated `region_id:epoch` pairs.
+    pub fn to_header_map(&self, map: &mut HashMap<String, String>) {
+        if self.0.is_empty() {
+            return;
+        }
+        let value = self
+            .0
+            .iter()
+            .map(|(region_id, epoch)| format!("{}:{epoch}", region_id.as_u64()))
+            .collect::<Vec<_>>()
+            .join(",");
+        let _ = map.insert(LEADER_EPOCHS_KEY.to_string(), value);
+    }
+
+    /// Reads the epochs from the string map of a region request header.
+    /// Returns empty epochs if the key is absent.
+    pub fn from_header_map(map: &HashMap<String, String>) -> Result<Self> {
+        let Some(value) = map.get(LEADER_EPOCHS_KEY) else {
+            return Ok(Self::default());
+        };
+        let mut epochs = Self::default();
+        for pair in value.split(',').filter(|pair| !pair.is_empty()) {
+            let parsed = pair.split_once(':').and_then(|(region_id, epoch)| {
+                Some((region_id.parse().ok()?, epoch.parse().ok()?))
+            });
+            let Some((region_id, epoch)) = parsed else {
+                return InvalidRawRegionRequestSnafu {
+                    err: format!("invalid leader epoch '{pair}'"),
+                }
+                .fail();
+            };
+            epochs.insert(RegionId::from_u64(region_id), epoch);
+        }
+        Ok(epochs)
+    }
 }
 
 #[derive(Debug)]
@@ -332,6 +470,23 @@ pub enum AlterKind {
         /// Name of columns to drop.
         names: Vec<String>,
     },
+    /// Change data types of columns, only widening the type of a field is allowed.
+    ///
+    /// The region alter request of the protocol has no such kind yet, so SQL can't
+    /// change column types.
+    ChangeColumnTypes {
+        /// Columns to change.
+        columns: Vec<ChangeColumnType>,
+    },
+    /// Change options of the region, e.g. the compaction strategy, without
+    /// reopening it. It doesn't change the metadata of the region.
+    ///
+    /// The region alter request of the protocol has no such kind yet, so SQL can't
+    /// switch the compaction strategy of an existing table.
+    ChangeRegionOptions {
+        /// Options to change.
+        options: HashMap<String, String>,
+    },
 }
 
 impl AlterKind {
@@ -350,6 +505,13 @@ impl AlterKind {
                     Self::validate_column_to_drop(name, metadata)?;
                 }
             }
+            AlterKind::ChangeColumnTypes { columns } => {
+                for col_to_change in columns {
+                    col_to_change.validate(metadata)?;
+                }
+            }
+            // The engine validates the options.
+            AlterKind::ChangeRegionOptions { .. } => (),
         }
         Ok(())
     }
@@ -364,6 +526,10 @@ impl AlterKind {
             AlterKind::DropColumns { names } => names
                 .iter()
                 .any(|name| metadata.column_by_name(name).is_some()),
+            AlterKind::ChangeColumnTypes { columns } => columns
+                .iter()
+                .any(|col_to_change| col_to_change.need_alter(metadata)),
+            AlterKind::ChangeRegionOptions { options } => !options.is_empty(),
         }
     }
 
@@ -472,6 +638,64 @@ impl TryFrom<v1::region::AddColumn> for AddColumn {
     }
 }
 
+/// Changes the data type of a column.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChangeColumnType {
+    /// Name of the column to change.
+    pub column_name: String,
+    /// Target data type of the column.
+    pub target_type: ConcreteDataType,
+}
+
+impl ChangeColumnType {
+    /// Returns an error if the column to change is invalid.
+    ///
+    /// The column must be an existing field and the target type must be wider
+    /// than the current type so that old values can be casted on read.
+    pub fn validate(&self, metadata: &RegionMetadata) -> Result<()> {
+        let column = metadata
+            .column_by_name(&self.column_name)
+            .with_context(|| InvalidRegionRequestSnafu {
+                region_id: metadata.region_id,
+                err: format!("column {} not found", self.column_name),
+            })?;
+        ensure!(
+            column.semantic_type == SemanticType::Field,
+            InvalidRegionRequestSnafu {
+                region_id: metadata.region_id,
+                err: format!(
+                    "column {} is not a field and its type could not be changed",
+                    self.column_name
+                ),
+            }
+        );
+        ensure!(
+            column
+                .column_schema
+                .data_type
+                .can_widen_to(&self.target_type),
+            InvalidRegionRequestSnafu {
+                region_id: metadata.region_id,
+                err: format!(
+                    "could not change type of column {} from {:?} to {:?}",
+                    self.column_name, column.column_schema.data_type, self.target_type
+                ),
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Returns true if the type of the column differs from the target type.
+    pub fn need_alter(&self, metadata: &RegionMetadata) -> bool {
+        debug_assert!(self.validate(metadata).is_ok());
+        metadata
+            .column_by_name(&self.column_name)
+            .map(|column| column.column_schema.data_type != self.target_type)
+            .unwrap_or(false)
+    }
+}
+
 /// Location to add a column.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum AddColumnLocation {
@@ -506,8 +730,104 @@ pub struct RegionFlushRequest {
     pub row_group_size: Option<usize>,
 }
 
-#[derive(Debug)]
-pub struct RegionCompactRequest {}
+#[derive(Debug, Default)]
+pub struct RegionCompactRequest {
+    pub options: CompactOptions,
+}
+
+/// Key of the compaction strategy in the string map of a region request header.
+pub const COMPACT_STRATEGY_KEY: &str = "x-greptime-compact-strategy";
+/// Key of the compaction window in seconds in the string map of a region request header.
+pub const COMPACT_WINDOW_KEY: &str = "x-greptime-compact-window";
+
+/// Options of a manual compaction.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CompactOptions {
+    /// Compacts the region with the compaction options of the region.
+    #[default]
+    Regular,
+    /// Compacts files in each time window into one file, regardless of
+    /// how many files the window has.
+    ///
+    /// Uses `window_seconds` as the time window if present, otherwise the
+    /// time window of the region.
+    StrictWindow { window_seconds: Option<i64> },
+}
+
+impl CompactOptions {
+    /// Returns the name of the strategy used in headers and admin functions.
+    pub fn strategy(&self) -> &'static str {
+        match self {
+            CompactOptions::Regular => "regular",
+            CompactOptions::StrictWindow { .. } => "strict_window",
+        }
+    }
+
+    /// Builds options from the name of the strategy and the window, case-insensitively.
+    pub fn parse(strategy: &str, window_seconds: Option<i64>) -> Result<Self> {
+        if let Some(window_seconds) = window_seconds {
+            ensure!(
+                window_seconds > 0,
+                InvalidRawRegionRequestSnafu {
+                    err: format!("compaction window must be positive, got {window_seconds}"),
+                }
+            );
+        }
+
+        match strategy.trim().to_ascii_lowercase().as_str() {
+            "regular" => {
+                ensure!(
+                    window_seconds.is_none(),
+                    InvalidRawRegionRequestSnafu {
+                        err: "compaction window is only supported by 'strict_window' strategy",
+                    }
+                );
+                Ok(CompactOptions::Regular)
+            }
+            "strict_window" => Ok(CompactOptions::StrictWindow { window_seconds }),
+            _ => InvalidRawRegionRequestSnafu {
+                err: format!(
+                    "unknown compaction strategy '{strategy}', expected 'regular' or 'strict_window'"
+                ),
+            }
+            .fail(),
+        }
+    }
+
+    /// Writes the options to the string map of a region request header.
+    pub fn to_header_map(&self, map: &mut HashMap<String, String>) {
+        let _ = map.insert(
+            COMPACT_STRATEGY_KEY.to_string(),
+            self.strategy().to_string(),
+        );
+        if let CompactOptions::StrictWindow {
+            window_seconds: Some(window_seconds),
+        } = self
+        {
+            let _ = map.insert(COMPACT_WINDOW_KEY.to_string(), window_seconds.to_string());
+        }
+    }
+
+    /// Reads the options from the string map of a region request header.
+    /// Returns [CompactOptions::Regular] if the strategy is absent.
+    pub fn from_header_map(map: &HashMap<String, String>) -> Result<Self> {
+        let window_seconds = map
+            .get(COMPACT_WINDOW_KEY)
+            .map(|value| {
+                value.trim().parse::<i64>().map_err(|_| {
+                    InvalidRawRegionRequestSnafu {
+                        err: format!("invalid compaction window '{value}'"),
+                    }
+                    .build()
+                })
+            })
+            .transpose()?;
+        match map.get(COMPACT_STRATEGY_KEY) {
+            Some(strategy) => Self::parse(strategy, window_seconds),
+            None => Ok(CompactOptions::Regular),
+        }
+    }
+}
 
 /// Truncate region request.
 #[derive(Debug)]
@@ -526,6 +846,67 @@ pub struct RegionCatchupRequest {
     pub entry_id: Option<entry::Id>,
 }
 
+/// Replay WAL region request.
+///
+/// Writes entries in the WAL of a source region into the region, like normal writes.
+/// It isn't persisted, so the region doesn't replay the source again after reopening.
+#[derive(Debug)]
+pub struct RegionReplayWalRequest {
+    pub source: WalReplaySource,
+}
+
+/// Key of the [WalReplaySource] in the string map of a region request header.
+///
+/// A put request with the key replays the WAL instead of putting its rows.
+pub const WAL_REPLAY_KEY: &str = "x-greptime-wal-replay";
+
+/// Retained WAL of another table to replay into a region.
+///
+/// The region replays entries of the source region with the same region number, from the
+/// entry after the one in `region_entry_ids` to the last entry in the WAL, keeping rows up
+/// to `until`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalReplaySource {
+    /// Id of the source table.
+    pub table_id: TableId,
+    /// Encoded [WalOptions](common_wal::options::WalOptions) of source regions. Regions absent
+    /// here use the default WAL options.
+    #[serde(default)]
+    pub region_wal_options: HashMap<RegionNumber, String>,
+    /// Entry ids of source regions to replay after. Regions absent here replay all entries
+    /// in the WAL.
+    #[serde(default)]
+    pub region_entry_ids: HashMap<RegionNumber, entry::Id>,
+    /// Timestamp in millis to replay rows up to, inclusive. WAL entries have no write
+    /// time, so rows are selected by their time index. `None` replays all rows.
+    #[serde(default)]
+    pub until: Option<i64>,
+}
+
+impl WalReplaySource {
+    /// Writes the source to the string map of a region request header.
+    pub fn to_header_map(&self, map: &mut HashMap<String, String>) {
+        // Safety: the source only contains maps with integer keys and strings.
+        let value = serde_json::to_string(self).unwrap();
+        let _ = map.insert(WAL_REPLAY_KEY.to_string(), value);
+    }
+
+    /// Reads the source from the string map of a region request header.
+    /// Returns `None` if the key is absent.
+    pub fn from_header_map(map: &HashMap<String, String>) -> Result<Option<Self>> {
+        map.get(WAL_REPLAY_KEY)
+            .map(|value| {
+                serde_json::from_str(value).map_err(|e| {
+                    InvalidRawRegionRequestSnafu {
+                        err: format!("invalid WAL replay source '{value}': {e}"),
+                    }
+                    .build()
+                })
+            })
+            .transpose()
+    }
+}
+
 impl fmt::Display for RegionRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -540,6 +921,7 @@ impl fmt::Display for RegionRequest {
             RegionRequest::Compact(_) => write!(f, "Compact"),
             RegionRequest::Truncate(_) => write!(f, "Truncate"),
             RegionRequest::Catchup(_) => write!(f, "Catchup"),
+            RegionRequest::ReplayWal(_) => write!(f, "ReplayWal"),
         }
     }
 }
@@ -790,6 +1172,63 @@ mod tests {
         assert!(kind.need_alter(&metadata));
     }
 
+    #[test]
+    fn test_validate_change_column_types() {
+        let mut builder = RegionMetadataBuilder::from_existing(new_metadata());
+        builder.push_column_metadata(ColumnMetadata {
+            column_schema: ColumnSchema::new("field_1", ConcreteDataType::int32_datatype(), true),
+            semantic_type: SemanticType::Field,
+            column_id: 4,
+        });
+        let metadata = builder.build().unwrap();
+
+        let kind = AlterKind::ChangeColumnTypes {
+            columns: vec![ChangeColumnType {
+                column_name: "field_1".to_string(),
+                target_type: ConcreteDataType::int64_datatype(),
+            }],
+        };
+        kind.validate(&metadata).unwrap();
+        assert!(kind.need_alter(&metadata));
+
+        let kind = AlterKind::ChangeColumnTypes {
+            columns: vec![ChangeColumnType {
+                column_name: "field_1".to_string(),
+                target_type: ConcreteDataType::int32_datatype(),
+            }],
+        };
+        kind.validate(&metadata).unwrap();
+        assert!(!kind.need_alter(&metadata));
+
+        // Narrowing.
+        AlterKind::ChangeColumnTypes {
+            columns: vec![ChangeColumnType {
+                column_name: "field_1".to_string(),
+                target_type: ConcreteDataType::int16_datatype(),
+            }],
+        }
+        .validate(&metadata)
+        .unwrap_err();
+        // Not a field.
+        AlterKind::ChangeColumnTypes {
+            columns: vec![ChangeColumnType {
+                column_name: "tag_0".to_string(),
+                target_type: ConcreteDataType::string_datatype(),
+            }],
+        }
+        .validate(&metadata)
+        .unwrap_err();
+        // Not exists.
+        AlterKind::ChangeColumnTypes {
+            columns: vec![ChangeColumnType {
+                column_name: "xxxx".to_string(),
+                target_type: ConcreteDataType::int64_datatype(),
+            }],
+        }
+        .validate(&metadata)
+        .unwrap_err();
+    }
+
     #[test]
     fn test_validate_schema_version() {
         let mut metadata = new_metadata();
@@ -843,4 +1282,121 @@ mod tests {
         metadata.schema_version = 1;
         request.validate(&metadata).unwrap();
     }
+
+    #[test]
+    fn test_insert_mode() {
+        let mut map = HashMap::new();
+        assert_eq!(
+            InsertMode::Overwrite,
+            InsertMode::from_header_map(&map).unwrap()
+        );
+
+        map.insert(INSERT_MODE_KEY.to_string(), "Ignore".to_string());
+        assert_eq!(
+            InsertMode::IgnoreDuplicates,
+            InsertMode::from_header_map(&map).unwrap()
+        );
+
+        map.insert(INSERT_MODE_KEY.to_string(), "overwrite".to_string());
+        assert_eq!(
+            InsertMode::Overwrite,
+            InsertMode::from_header_map(&map).unwrap()
+        );
+
+        map.insert(INSERT_MODE_KEY.to_string(), "upsert".to_string());
+        assert!(InsertMode::from_header_map(&map).is_err());
+
+        for mode in [InsertMode::Overwrite, InsertMode::IgnoreDuplicates] {
+            let mut map = HashMap::new();
+            mode.to_header_map(&mut map);
+            assert_eq!(mode, InsertMode::from_header_map(&map).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_leader_epochs() {
+        let mut map = HashMap::new();
+        assert_eq!(
+            LeaderEpochs::default(),
+            LeaderEpochs::from_header_map(&map).unwrap()
+        );
+
+        let mut epochs = LeaderEpochs::default();
+        epochs.insert(RegionId::new(1024, 1), 0);
+        epochs.insert(RegionId::new(1024, 2), 3);
+        epochs.to_header_map(&mut map);
+        let decoded = LeaderEpochs::from_header_map(&map).unwrap();
+        assert_eq!(epochs, decoded);
+        assert_eq!(Some(3), decoded.get(RegionId::new(1024, 2)));
+        assert_eq!(None, decoded.get(RegionId::new(1024, 3)));
+
+        map.insert(LEADER_EPOCHS_KEY.to_string(), "4398046511105".to_string());
+        assert!(LeaderEpochs::from_header_map(&map).is_err());
+        map.insert(
+            LEADER_EPOCHS_KEY.to_string(),
+            "4398046511105:-1".to_string(),
+        );
+        assert!(LeaderEpochs::from_header_map(&map).is_err());
+    }
+
+    #[test]
+    fn test_compact_options() {
+        let mut map = HashMap::new();
+        assert_eq!(
+            CompactOptions::Regular,
+            CompactOptions::from_header_map(&map).unwrap()
+        );
+
+        for options in [
+            CompactOptions::Regular,
+            CompactOptions::StrictWindow {
+                window_seconds: None,
+            },
+            CompactOptions::StrictWindow {
+                window_seconds: Some(3600),
+            },
+        ] {
+            let mut map = HashMap::new();
+            options.to_header_map(&mut map);
+            assert_eq!(options, CompactOptions::from_header_map(&map).unwrap());
+        }
+
+        assert_eq!(
+            CompactOptions::StrictWindow {
+                window_seconds: Some(60)
+            },
+            CompactOptions::parse("Strict_Window", Some(60)).unwrap()
+        );
+        assert!(CompactOptions::parse("regular", Some(60)).is_err());
+        assert!(CompactOptions::parse("strict_window", Some(0)).is_err());
+        assert!(CompactOptions::parse("size_tiered", None).is_err());
+
+        map.insert(
+            COMPACT_STRATEGY_KEY.to_string(),
+            "strict_window".to_string(),
+        );
+        map.insert(COMPACT_WINDOW_KEY.to_string(), "1h".to_string());
+        assert!(CompactOptions::from_header_map(&map).is_err());
+    }
+
+    #[test]
+    fn test_wal_replay_source() {
+        let mut map = HashMap::new();
+        assert_eq!(None, WalReplaySource::from_header_map(&map).unwrap());
+
+        let source = WalReplaySource {
+            table_id: 1024,
+            region_wal_options: HashMap::from([(1, "{}".to_string())]),
+            region_entry_ids: HashMap::from([(0, 10), (1, 20)]),
+            until: Some(1000),
+        };
+        source.to_header_map(&mut map);
+        assert_eq!(
+            Some(source),
+            WalReplaySource::from_header_map(&map).unwrap()
+        );
+
+        map.insert(WAL_REPLAY_KEY.to_string(), "1024".to_string());
+        assert!(WalReplaySource::from_header_map(&map).is_err());
+    }
 }
@@ -15,6 +15,8 @@
 use common_query::logical_plan::Expr;
 use common_recordbatch::OrderOption;
 
+use crate::storage::SequenceNumber;
+
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct ScanRequest {
     /// Indices of columns to read, `None` to read all columns. This indices is
@@ -29,4 +31,8 @@ pub struct ScanRequest {
     /// If set, it contains the amount of rows needed by the caller,
     /// The data source should return *at least* this number of rows if available.
     pub limit: Option<usize>,
+    /// Max sequence of rows to read, `None` to read all rows. Reads the snapshot at this
+    /// sequence if set, so rows written later are invisible. Engines may reject sequences
+    /// older than their latest write unless a snapshot retains them.
+    pub sequence: Option<SequenceNumber>,
 }
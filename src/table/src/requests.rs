@@ -27,6 +27,7 @@ use datatypes::schema::ColumnSchema;
 use serde::{Deserialize, Serialize};
 use store_api::metric_engine_consts::{LOGICAL_TABLE_METADATA_KEY, PHYSICAL_TABLE_METADATA_KEY};
 use store_api::mito_engine_options::is_mito_engine_option_key;
+use store_api::region_request::CompactOptions;
 
 use crate::error;
 use crate::error::ParseTableOptionSnafu;
@@ -36,6 +37,8 @@ use crate::table_reference::TableReference;
 pub const FILE_TABLE_META_KEY: &str = "__private.file_table_meta";
 pub const FILE_TABLE_LOCATION_KEY: &str = "location";
 pub const FILE_TABLE_PATTERN_KEY: &str = "pattern";
+/// Lists files in sub directories of the location, e.g. partitions in hive style layout.
+pub const FILE_TABLE_RECURSIVE_KEY: &str = "recursive";
 pub const FILE_TABLE_FORMAT_KEY: &str = "format";
 
 /// Returns true if the `key` is a valid key for any engine or storage.
@@ -54,10 +57,12 @@ pub fn validate_table_option(key: &str) -> bool {
         TTL_KEY,
         REGIONS_KEY,
         STORAGE_KEY,
+        CASE_INSENSITIVE_COLUMNS_KEY,
         // file engine keys:
         FILE_TABLE_LOCATION_KEY,
         FILE_TABLE_FORMAT_KEY,
         FILE_TABLE_PATTERN_KEY,
+        FILE_TABLE_RECURSIVE_KEY,
         // metric engine keys:
         PHYSICAL_TABLE_METADATA_KEY,
         LOGICAL_TABLE_METADATA_KEY,
@@ -81,6 +86,8 @@ pub const WRITE_BUFFER_SIZE_KEY: &str = "write_buffer_size";
 pub const TTL_KEY: &str = "ttl";
 pub const REGIONS_KEY: &str = "regions";
 pub const STORAGE_KEY: &str = "storage";
+/// Comma separated names of string columns that are compared case-insensitively.
+pub const CASE_INSENSITIVE_COLUMNS_KEY: &str = "case_insensitive_columns";
 
 impl TryFrom<&HashMap<String, String>> for TableOptions {
     type Error = error::Error;
@@ -122,6 +129,22 @@ impl TryFrom<&HashMap<String, String>> for TableOptions {
     }
 }
 
+impl TableOptions {
+    /// Returns the names of columns declared with [CASE_INSENSITIVE_COLUMNS_KEY].
+    pub fn case_insensitive_columns(&self) -> Vec<&str> {
+        self.extra_options
+            .get(CASE_INSENSITIVE_COLUMNS_KEY)
+            .map(|columns| {
+                columns
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
 impl From<&TableOptions> for HashMap<String, String> {
     fn from(opts: &TableOptions) -> Self {
         let mut res = HashMap::with_capacity(2 + opts.extra_options.len());
@@ -223,6 +246,7 @@ pub struct CompactTableRequest {
     pub catalog_name: String,
     pub schema_name: String,
     pub table_name: String,
+    pub compact_options: CompactOptions,
 }
 
 /// Truncate table request
@@ -263,10 +287,12 @@ mod tests {
         assert!(validate_table_option(FILE_TABLE_LOCATION_KEY));
         assert!(validate_table_option(FILE_TABLE_FORMAT_KEY));
         assert!(validate_table_option(FILE_TABLE_PATTERN_KEY));
+        assert!(validate_table_option(FILE_TABLE_RECURSIVE_KEY));
         assert!(validate_table_option(TTL_KEY));
         assert!(validate_table_option(REGIONS_KEY));
         assert!(validate_table_option(WRITE_BUFFER_SIZE_KEY));
         assert!(validate_table_option(STORAGE_KEY));
+        assert!(validate_table_option(CASE_INSENSITIVE_COLUMNS_KEY));
         assert!(!validate_table_option("foo"));
     }
 
@@ -311,4 +337,18 @@ mod tests {
         let serialized = TableOptions::try_from(&serialized_map).unwrap();
         assert_eq!(options, serialized);
     }
+
+    #[test]
+    fn test_case_insensitive_columns() {
+        let options = TableOptions::try_from(&HashMap::from([(
+            CASE_INSENSITIVE_COLUMNS_KEY.to_string(),
+            "host, idc,".to_string(),
+        )]))
+        .unwrap();
+        assert_eq!(vec!["host", "idc"], options.case_insensitive_columns());
+
+        assert!(TableOptions::default()
+            .case_insensitive_columns()
+            .is_empty());
+    }
 }
@@ -22,16 +22,18 @@ use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
 use common_query::physical_plan::{Partitioning, PhysicalPlan, PhysicalPlanRef};
 use common_recordbatch::adapter::RecordBatchMetrics;
-use common_recordbatch::error::Result as RecordBatchResult;
+use common_recordbatch::error::{PollStreamSnafu, Result as RecordBatchResult};
 use common_recordbatch::{OrderOption, RecordBatch, RecordBatchStream, SendableRecordBatchStream};
 use common_telemetry::tracing::Span;
 use common_telemetry::tracing_context::TracingContext;
 use datafusion::execution::context::TaskContext;
+use datafusion::execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::PhysicalSortExpr;
 use datatypes::schema::SchemaRef;
 use futures::{Stream, StreamExt};
-use snafu::OptionExt;
+use snafu::{OptionExt, ResultExt};
 
 use crate::table::metrics::MemoryUsageMetrics;
 
@@ -53,13 +55,17 @@ impl Debug for StreamScanAdapter {
 }
 
 impl StreamScanAdapter {
+    /// Creates an adapter of the `stream` that keeps the output ordering of the stream.
     pub fn new(stream: SendableRecordBatchStream) -> Self {
         let schema = stream.schema();
+        let output_ordering = stream
+            .output_ordering()
+            .and_then(|order_opts| to_physical_sort_exprs(&schema, order_opts));
 
         Self {
             stream: Mutex::new(Some(stream)),
             schema,
-            output_ordering: None,
+            output_ordering,
             metric: ExecutionPlanMetricsSet::new(),
         }
     }
@@ -70,6 +76,25 @@ impl StreamScanAdapter {
     }
 }
 
+/// Converts `order_opts` to sort exprs on columns of the `schema`.
+///
+/// Returns `None` if a column is not in the `schema`.
+fn to_physical_sort_exprs(
+    schema: &SchemaRef,
+    order_opts: &[OrderOption],
+) -> Option<Vec<PhysicalSortExpr>> {
+    order_opts
+        .iter()
+        .map(|order_opt| {
+            let col_index = schema.column_index_by_name(&order_opt.name)?;
+            Some(PhysicalSortExpr {
+                expr: Arc::new(Column::new(&order_opt.name, col_index)),
+                options: order_opt.options,
+            })
+        })
+        .collect()
+}
+
 impl PhysicalPlan for StreamScanAdapter {
     fn as_any(&self) -> &dyn Any {
         self
@@ -108,10 +133,13 @@ impl PhysicalPlan for StreamScanAdapter {
         let mut stream = self.stream.lock().unwrap();
         let stream = stream.take().context(query_error::ExecuteRepeatedlySnafu)?;
         let mem_usage_metrics = MemoryUsageMetrics::new(&self.metric, partition);
+        let reservation = MemoryConsumer::new(format!("StreamScanAdapter[{partition}]"))
+            .register(context.memory_pool());
         Ok(Box::pin(StreamWithMetricWrapper {
             stream,
             metric: mem_usage_metrics,
             span,
+            reservation,
         }))
     }
 
@@ -124,6 +152,9 @@ pub struct StreamWithMetricWrapper {
     stream: SendableRecordBatchStream,
     metric: MemoryUsageMetrics,
     span: Span,
+    /// Reserves the memory of the batch the scan yields last, so memory pools of the
+    /// query see the memory of scans and can abort them.
+    reservation: MemoryReservation,
 }
 
 impl Stream for StreamWithMetricWrapper {
@@ -143,6 +174,13 @@ impl Stream for StreamWithMetricWrapper {
             // since it's calling storage api involving I/O ops
             this.metric.record_mem_usage(batch_mem_size);
             this.metric.record_output(record_batch.num_rows());
+            if let Err(e) = this
+                .reservation
+                .try_resize(batch_mem_size)
+                .context(PollStreamSnafu)
+            {
+                return Poll::Ready(Some(Err(e)));
+            }
         }
 
         poll
@@ -20,7 +20,10 @@ use std::time::Duration;
 use api::v1::meta::Role;
 use api::v1::region::region_server::RegionServer;
 use arrow_flight::flight_service_server::FlightServiceServer;
+use catalog::information_extension::DistributedInformationExtension;
 use catalog::kvbackend::{CachedMetaKvBackendBuilder, KvBackendCatalogManager, MetaKvBackend};
+use catalog::process_manager::ProcessManager;
+use catalog::slow_queries::SlowQueryOptions;
 use client::client_manager::DatanodeClients;
 use client::Client;
 use common_base::Plugins;
@@ -356,9 +359,13 @@ impl GreptimeDbClusterBuilder {
         let multi_cache_invalidator = Arc::new(MultiCacheInvalidator::with_invalidators(vec![
             cached_meta_backend.clone(),
         ]));
+        let information_extension =
+            Arc::new(DistributedInformationExtension::new(meta_client.clone()));
         let catalog_manager = KvBackendCatalogManager::new(
             cached_meta_backend.clone(),
             multi_cache_invalidator.clone(),
+            Some(information_extension),
+            Some(Arc::new(ProcessManager::new(SlowQueryOptions::default()))),
         )
         .await;
 
@@ -15,6 +15,8 @@
 use std::sync::Arc;
 
 use catalog::kvbackend::KvBackendCatalogManager;
+use catalog::process_manager::ProcessManager;
+use catalog::slow_queries::SlowQueryOptions;
 use cmd::options::MixOptions;
 use common_base::Plugins;
 use common_catalog::consts::MIN_USER_TABLE_ID;
@@ -34,7 +36,9 @@ use common_wal::config::{DatanodeWalConfig, MetasrvWalConfig};
 use datanode::datanode::DatanodeBuilder;
 use frontend::frontend::FrontendOptions;
 use frontend::instance::builder::FrontendBuilder;
-use frontend::instance::{FrontendInstance, Instance, StandaloneDatanodeManager};
+use frontend::instance::{
+    FrontendInstance, Instance, StandaloneDatanodeManager, StandaloneInformationExtension,
+};
 use servers::Mode;
 
 use crate::test_util::{self, create_tmp_dir_and_datanode_opts, StorageType, TestGuard};
@@ -127,8 +131,17 @@ impl GreptimeDbStandaloneBuilder {
         table_metadata_manager.init().await.unwrap();
 
         let multi_cache_invalidator = Arc::new(MultiCacheInvalidator::default());
-        let catalog_manager =
-            KvBackendCatalogManager::new(kv_backend.clone(), multi_cache_invalidator.clone()).await;
+        let information_extension = Arc::new(StandaloneInformationExtension::new(
+            datanode.region_server(),
+            mix_options.frontend.grpc.addr.clone(),
+        ));
+        let catalog_manager = KvBackendCatalogManager::new(
+            kv_backend.clone(),
+            multi_cache_invalidator.clone(),
+            Some(information_extension),
+            Some(Arc::new(ProcessManager::new(SlowQueryOptions::default()))),
+        )
+        .await;
 
         let datanode_manager = Arc::new(StandaloneDatanodeManager(datanode.region_server()));
 
@@ -43,6 +43,7 @@ use secrecy::ExposeSecret;
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::greptime_handler::GreptimeRequestHandler;
 use servers::grpc::{GrpcServer, GrpcServerConfig};
+use servers::http::tables::TableApiState;
 use servers::http::{HttpOptions, HttpServerBuilder};
 use servers::metrics_handler::MetricsHandler;
 use servers::mysql::server::{MysqlServer, MysqlSpawnConfig, MysqlSpawnRef};
@@ -65,7 +66,9 @@ pub enum StorageType {
     File,
     Oss,
     Azblob,
+    AzblobWithCache,
     Gcs,
+    GcsWithCache,
 }
 
 impl Display for StorageType {
@@ -76,7 +79,9 @@ impl Display for StorageType {
             StorageType::File => write!(f, "File"),
             StorageType::Oss => write!(f, "Oss"),
             StorageType::Azblob => write!(f, "Azblob"),
+            StorageType::AzblobWithCache => write!(f, "Azblob"),
             StorageType::Gcs => write!(f, "Gcs"),
+            StorageType::GcsWithCache => write!(f, "Gcs"),
         }
     }
 }
@@ -121,14 +126,14 @@ impl StorageType {
                     false
                 }
             }
-            StorageType::Azblob => {
+            StorageType::Azblob | StorageType::AzblobWithCache => {
                 if let Ok(b) = env::var("GT_AZBLOB_CONTAINER") {
                     !b.is_empty()
                 } else {
                     false
                 }
             }
-            StorageType::Gcs => {
+            StorageType::Gcs | StorageType::GcsWithCache => {
                 if let Ok(b) = env::var("GT_GCS_BUCKET") {
                     !b.is_empty()
                 } else {
@@ -154,8 +159,8 @@ pub fn get_test_store_config(store_type: &StorageType) -> (ObjectStoreConfig, Te
     let _ = dotenv::dotenv();
 
     match store_type {
-        StorageType::Gcs => {
-            let gcs_config = GcsConfig {
+        StorageType::Gcs | StorageType::GcsWithCache => {
+            let mut gcs_config = GcsConfig {
                 root: uuid::Uuid::new_v4().to_string(),
                 bucket: env::var("GT_GCS_BUCKET").unwrap(),
                 scope: env::var("GT_GCS_SCOPE").unwrap(),
@@ -164,6 +169,10 @@ pub fn get_test_store_config(store_type: &StorageType) -> (ObjectStoreConfig, Te
                 ..Default::default()
             };
 
+            if *store_type == StorageType::GcsWithCache {
+                gcs_config.cache.cache_path = Some("/tmp/greptimedb_cache".to_string());
+            }
+
             let mut builder = Gcs::default();
             builder
                 .root(&gcs_config.root)
@@ -176,8 +185,8 @@ pub fn get_test_store_config(store_type: &StorageType) -> (ObjectStoreConfig, Te
             let store = ObjectStore::new(builder).unwrap().finish();
             (config, TempDirGuard::Gcs(TempFolder::new(&store, "/")))
         }
-        StorageType::Azblob => {
-            let azblob_config = AzblobConfig {
+        StorageType::Azblob | StorageType::AzblobWithCache => {
+            let mut azblob_config = AzblobConfig {
                 root: uuid::Uuid::new_v4().to_string(),
                 container: env::var("GT_AZBLOB_CONTAINER").unwrap(),
                 account_name: env::var("GT_AZBLOB_ACCOUNT_NAME").unwrap().into(),
@@ -186,6 +195,10 @@ pub fn get_test_store_config(store_type: &StorageType) -> (ObjectStoreConfig, Te
                 ..Default::default()
             };
 
+            if *store_type == StorageType::AzblobWithCache {
+                azblob_config.cache.cache_path = Some("/tmp/greptimedb_cache".to_string());
+            }
+
             let mut builder = Azblob::default();
             let _ = builder
                 .root(&azblob_config.root)
@@ -255,7 +268,10 @@ pub fn get_test_store_config(store_type: &StorageType) -> (ObjectStoreConfig, Te
 
             (config, TempDirGuard::S3(TempFolder::new(&store, "/")))
         }
-        StorageType::File => (ObjectStoreConfig::File(FileConfig {}), TempDirGuard::None),
+        StorageType::File => (
+            ObjectStoreConfig::File(FileConfig::default()),
+            TempDirGuard::None,
+        ),
     }
 }
 
@@ -425,6 +441,11 @@ pub async fn setup_test_http_app_with_frontend_and_user_provider(
             ServerSqlQueryHandlerAdapter::arc(instance.instance.clone()),
             Some(instance.instance.clone()),
         )
+        .with_table_handler(TableApiState {
+            catalog_manager: instance.instance.catalog_manager().clone(),
+            user_provider: user_provider.clone(),
+            privilege_manager: None,
+        })
         .with_greptime_config_options(instance.mix_options.to_toml().unwrap());
 
     if let Some(user_provider) = user_provider {
@@ -462,7 +483,7 @@ pub async fn setup_test_prom_app_with_frontend(
             ServerSqlQueryHandlerAdapter::arc(frontend_ref.clone()),
             Some(frontend_ref.clone()),
         )
-        .with_prom_handler(frontend_ref.clone(), true, is_strict_mode)
+        .with_prom_handler(frontend_ref.clone(), true, is_strict_mode, Arc::default())
         .with_prometheus_handler(frontend_ref)
         .with_greptime_config_options(instance.mix_options.datanode.to_toml_string())
         .build();
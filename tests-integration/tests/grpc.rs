@@ -33,6 +33,9 @@ use servers::server::Server;
 use tests_integration::test_util::{
     setup_grpc_server, setup_grpc_server_with, setup_grpc_server_with_user_provider, StorageType,
 };
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
 
 #[macro_export]
 macro_rules! grpc_test {
@@ -460,9 +463,33 @@ pub async fn test_health_check(store_type: StorageType) {
     let (addr, mut guard, fe_grpc_server) =
         setup_grpc_server(store_type, "auto_create_table").await;
 
-    let grpc_client = Client::with_urls(vec![addr]);
+    let grpc_client = Client::with_urls(vec![addr.clone()]);
     grpc_client.health_check().await.unwrap();
 
+    // the standard health service used by probes
+    let channel = tonic::transport::Endpoint::try_from(format!("http://{addr}"))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut health_client = HealthClient::new(channel);
+    for service in ["", "greptime.v1.GreptimeDatabase"] {
+        let response = health_client
+            .check(HealthCheckRequest {
+                service: service.to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(ServingStatus::Serving as i32, response.into_inner().status);
+    }
+    let status = health_client
+        .check(HealthCheckRequest {
+            service: "unknown".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(tonic::Code::NotFound, status.code());
+
     let _ = fe_grpc_server.shutdown().await;
     guard.remove_all().await;
 }
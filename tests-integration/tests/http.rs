@@ -66,6 +66,9 @@ macro_rules! http_tests {
 
                 test_http_auth,
                 test_sql_api,
+                test_cursor_api,
+                test_table_api,
+                test_ddl_result,
                 test_prometheus_promql_api,
                 test_prom_http_api,
                 test_metrics_api,
@@ -361,6 +364,214 @@ pub async fn test_sql_api(store_type: StorageType) {
         .text()
         .await;
     assert!(res.contains("TIME_ZONE") && res.contains("UTC"));
+
+    // test streaming result formats
+    let res = client
+        .get("/v1/sql?format=ndjson&sql=select * from numbers limit 3")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get("content-type").unwrap(),
+        "application/x-ndjson"
+    );
+    assert_eq!(
+        res.text().await,
+        "{\"number\":0}\n{\"number\":1}\n{\"number\":2}\n"
+    );
+
+    let res = client
+        .get("/v1/sql?format=arrow&stream=true&sql=select * from numbers limit 3")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get("content-type").unwrap(),
+        "application/vnd.apache.arrow.stream"
+    );
+
+    let res = client
+        .get("/v1/sql?format=csv&stream=true&sql=select * from numbers limit 3")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    guard.remove_all().await;
+}
+
+pub async fn test_cursor_api(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) = setup_test_http_app_with_frontend(store_type, "cursor_api").await;
+    let client = TestClient::new(app);
+
+    let res = client
+        .post("/v1/cursor?sql=select * from numbers limit 5")
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<serde_json::Value>(&res.text().await).unwrap();
+    assert_eq!(
+        body["schema"],
+        json!({"column_schemas":[{"name":"number","data_type":"UInt32"}]})
+    );
+    let cursor_id = body["cursor_id"].as_str().unwrap().to_string();
+
+    let res = client
+        .get(&format!("/v1/cursor/{cursor_id}/fetch?limit=2"))
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<serde_json::Value>(&res.text().await).unwrap();
+    assert_eq!(body["records"]["rows"], json!([[0], [1]]));
+    assert_eq!(body["done"], json!(false));
+
+    let res = client
+        .get(&format!("/v1/cursor/{cursor_id}/fetch?limit=10"))
+        .send()
+        .await;
+    let body = serde_json::from_str::<serde_json::Value>(&res.text().await).unwrap();
+    assert_eq!(body["records"]["rows"], json!([[2], [3], [4]]));
+    assert_eq!(body["done"], json!(true));
+
+    // exhausted cursors are closed
+    let res = client
+        .get(&format!("/v1/cursor/{cursor_id}/fetch"))
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // close a cursor explicitly
+    let res = client
+        .post("/v1/cursor?sql=select * from numbers")
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .send()
+        .await;
+    let body = serde_json::from_str::<serde_json::Value>(&res.text().await).unwrap();
+    let cursor_id = body["cursor_id"].as_str().unwrap().to_string();
+    let res = client
+        .delete(&format!("/v1/cursor/{cursor_id}"))
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client
+        .delete(&format!("/v1/cursor/{cursor_id}"))
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // statements without a result can't be opened as cursors
+    let res = client
+        .post("/v1/cursor?sql=create table cursor_demo(ts timestamp time index)")
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    guard.remove_all().await;
+}
+
+pub async fn test_table_api(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) = setup_test_http_app_with_frontend(store_type, "table_api").await;
+    let client = TestClient::new(app);
+
+    let res = client.get("/v1/tables").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<serde_json::Value>(&res.text().await).unwrap();
+    assert!(body["databases"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("public")));
+
+    let res = client.get("/v1/tables/public").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<serde_json::Value>(&res.text().await).unwrap();
+    assert_eq!(body["database"], json!("public"));
+    assert!(body["tables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|table| table["name"] == json!("demo")));
+
+    let res = client.get("/v1/tables/public/demo").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<serde_json::Value>(&res.text().await).unwrap();
+    assert_eq!(body["name"], json!("demo"));
+    assert_eq!(body["engine"], json!("mito"));
+    assert_eq!(
+        body["columns"],
+        json!([
+            {"name": "host", "data_type": "string", "semantic_type": "TAG", "nullable": false, "default": null, "comment": null},
+            {"name": "cpu", "data_type": "double", "semantic_type": "FIELD", "nullable": true, "default": null, "comment": null},
+            {"name": "memory", "data_type": "double", "semantic_type": "FIELD", "nullable": true, "default": null, "comment": null},
+            {"name": "ts", "data_type": "timestamp(3)", "semantic_type": "TIMESTAMP", "nullable": false, "default": null, "comment": null}
+        ])
+    );
+    assert_eq!(
+        body["indexes"],
+        json!([
+            {"name": "PRIMARY", "columns": ["host"]},
+            {"name": "TIME INDEX", "columns": ["ts"]},
+            {"name": "INVERTED INDEX", "columns": ["host"]}
+        ])
+    );
+    assert_eq!(body["partition_columns"], json!([]));
+    assert_eq!(body["partitions"].as_array().unwrap().len(), 1);
+
+    let res = client.get("/v1/tables/public/not_exist").send().await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let res = client.get("/v1/tables/not_exist").send().await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    guard.remove_all().await;
+}
+
+pub async fn test_ddl_result(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) = setup_test_http_app_with_frontend(store_type, "ddl_result").await;
+    let client = TestClient::new(app);
+
+    let cases = [
+        (
+            "create table if not exists ddl_demo(ts timestamp time index, val double)",
+            json!({"affectedrows": 0, "status": "created", "version": 0}),
+        ),
+        (
+            "create table if not exists ddl_demo(ts timestamp time index, val double)",
+            json!({"affectedrows": 0, "status": "already_exists", "version": 0}),
+        ),
+        (
+            "alter table ddl_demo add column host string",
+            json!({"affectedrows": 0, "status": "altered", "version": 1}),
+        ),
+        (
+            "drop table ddl_demo",
+            json!({"affectedrows": 0, "status": "dropped"}),
+        ),
+        (
+            "drop table if exists ddl_demo",
+            json!({"affectedrows": 0, "status": "not_found"}),
+        ),
+        (
+            "create database if not exists ddl_db",
+            json!({"affectedrows": 1, "status": "created"}),
+        ),
+        (
+            "create database if not exists ddl_db",
+            json!({"affectedrows": 1, "status": "already_exists"}),
+        ),
+    ];
+    for (sql, expected) in cases {
+        let res = client.get(&format!("/v1/sql?sql={sql}")).send().await;
+        assert_eq!(res.status(), StatusCode::OK, "{sql}");
+        let body = serde_json::from_str::<GreptimedbV1Response>(&res.text().await).unwrap();
+        assert_eq!(
+            body.output(),
+            [serde_json::from_value::<GreptimeQueryOutput>(expected).unwrap()],
+            "{sql}"
+        );
+    }
+
     guard.remove_all().await;
 }
 
@@ -463,10 +674,7 @@ pub async fn test_prom_http_api(store_type: StorageType) {
     assert_eq!(body.status, "success");
     assert_eq!(
         body.data,
-        serde_json::from_value::<PrometheusResponse>(json!([
-            "__name__", "cpu", "host", "memory", "ts"
-        ]))
-        .unwrap()
+        serde_json::from_value::<PrometheusResponse>(json!(["__name__", "host"])).unwrap()
     );
 
     // labels without match[] param
@@ -504,12 +712,27 @@ pub async fn test_prom_http_api(store_type: StorageType) {
         .collect::<BTreeMap<String, String>>();
     let expected = BTreeMap::from([
         ("__name__".to_string(), "demo".to_string()),
-        ("ts".to_string(), "1970-01-01 00:00:00+0000".to_string()),
-        ("cpu".to_string(), "1.1".to_string()),
         ("host".to_string(), "host1".to_string()),
-        ("memory".to_string(), "2.2".to_string()),
     ]);
     assert_eq!(actual, expected);
+    assert!(series.is_empty());
+
+    // series with matchers
+    let res = client
+        .get("/v1/prometheus/api/v1/series?match[]=demo{host=~\"host.*\"}&match[]={__name__=\"demo\",host!=\"host1\"}&start=0&end=600")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<PrometheusJsonResponse>(&res.text().await).unwrap();
+    assert_eq!(body.status, "success");
+    let PrometheusResponse::Series(series) = body.data else {
+        unreachable!()
+    };
+    let hosts = series
+        .iter()
+        .map(|labels| labels["host"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(hosts, vec!["host1", "host2"]);
 
     let res = client
         .post("/v1/prometheus/api/v1/series?match[]=up&match[]=down")
@@ -519,16 +742,29 @@ pub async fn test_prom_http_api(store_type: StorageType) {
     assert_eq!(res.status(), StatusCode::OK);
 
     // label values
-    // should return error if there is no match[]
+    // values in all metrics if there is no match[]
+    let res = client
+        .get("/v1/prometheus/api/v1/label/host/values?start=0&end=600")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<PrometheusJsonResponse>(&res.text().await).unwrap();
+    assert_eq!(body.status, "success");
+    assert_eq!(
+        body.data,
+        serde_json::from_value::<PrometheusResponse>(json!(["host1", "host2"])).unwrap()
+    );
     let res = client
         .get("/v1/prometheus/api/v1/label/instance/values")
         .send()
         .await;
     assert_eq!(res.status(), StatusCode::OK);
-    let prom_resp = res.json::<PrometheusJsonResponse>().await;
-    assert_eq!(prom_resp.status, "error");
-    assert!(prom_resp.error.is_some_and(|err| !err.is_empty()));
-    assert!(prom_resp.error_type.is_some_and(|err| !err.is_empty()));
+    let body = serde_json::from_str::<PrometheusJsonResponse>(&res.text().await).unwrap();
+    assert_eq!(body.status, "success");
+    assert_eq!(
+        body.data,
+        serde_json::from_value::<PrometheusResponse>(json!([])).unwrap()
+    );
 
     // single match[]
     let res = client
@@ -565,6 +801,19 @@ pub async fn test_prom_http_api(store_type: StorageType) {
     assert!(prom_resp.error.is_none());
     assert!(prom_resp.error_type.is_none());
 
+    // query_exemplars of metrics without exemplars
+    let res = client
+        .get("/v1/prometheus/api/v1/query_exemplars?query=rate(demo[5m])&start=0&end=600")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<PrometheusJsonResponse>(&res.text().await).unwrap();
+    assert_eq!(body.status, "success");
+    assert_eq!(
+        body.data,
+        serde_json::from_value::<PrometheusResponse>(json!([])).unwrap()
+    );
+
     // buildinfo
     let res = client
         .get("/v1/prometheus/api/v1/status/buildinfo")
@@ -697,6 +946,7 @@ purge_threshold = "4GiB"
 
 [frontend]
 mode = "standalone"
+shutdown_timeout = "30s"
 
 [frontend.heartbeat]
 interval = "18s"
@@ -751,6 +1001,11 @@ with_metric_engine = true
 [frontend.otlp]
 enable = true
 
+[frontend.query]
+max_memory = "0KiB"
+query_memory_limit = "0KiB"
+memory_pressure_percent = 0
+
 [frontend.logging]
 enable_otlp_tracing = false
 append_stdout = true
@@ -764,6 +1019,30 @@ tcp_nodelay = true
 enable = false
 write_interval = "30s"
 
+[frontend.query_cache]
+enable = false
+capacity = "64MiB"
+max_result_size = "1MiB"
+ttl = "5s"
+
+[frontend.audit_log]
+enable = false
+target = "file"
+dir = "/tmp/greptimedb/audit"
+rotation = "daily"
+max_files = 30
+sample_percent = 100
+redaction = "secrets"
+queue_size = 1024
+queue_full_policy = "drop"
+
+[frontend.slow_query]
+enable = false
+threshold = "5s"
+record_plan = false
+log = true
+max_entries = 1000
+
 [datanode]
 mode = "standalone"
 node_id = 0
@@ -807,10 +1086,19 @@ worker_request_batch_size = 64
 manifest_checkpoint_distance = 10
 compress_manifest = false
 max_background_jobs = 4
+enable_sst_upgrade = false
+max_snapshot_pin_duration = "30m"
 auto_flush_interval = "30m"
+min_free_disk_space = "0KiB"
+series_growth_alert_factor = 4
+max_write_rows_per_second = 0
+max_write_bytes_per_second = "0KiB"
+write_rate_limit_policy = "block"
+write_rate_limit_buffer_size = "64MiB"
 enable_experimental_write_cache = false
 experimental_write_cache_path = ""
 experimental_write_cache_size = "512MiB"
+experimental_write_cache_async_upload = false
 sst_write_buffer_size = "8MiB"
 parallel_scan_channel_size = 32
 allow_stale_entries = false
@@ -830,6 +1118,11 @@ type = "time_series"
 
 [datanode.region_engine.file]
 
+[datanode.query]
+max_memory = "0KiB"
+query_memory_limit = "0KiB"
+memory_pressure_percent = 0
+
 [datanode.logging]
 enable_otlp_tracing = false
 append_stdout = true
@@ -838,6 +1131,13 @@ append_stdout = true
 enable = false
 write_interval = "30s"
 
+[datanode.replication]
+enable = false
+standby_addrs = []
+conflict_policy = "overwrite"
+batch_size = 4096
+poll_interval = "1s"
+
 [logging]
 enable_otlp_tracing = false
 append_stdout = true
@@ -24,8 +24,26 @@ mod region_migration;
 // #[macro_use]
 // mod region_failover;
 
-grpc_tests!(File, S3, S3WithCache, Oss, Azblob, Gcs);
-http_tests!(File, S3, S3WithCache, Oss, Azblob, Gcs);
+grpc_tests!(
+    File,
+    S3,
+    S3WithCache,
+    Oss,
+    Azblob,
+    AzblobWithCache,
+    Gcs,
+    GcsWithCache
+);
+http_tests!(
+    File,
+    S3,
+    S3WithCache,
+    Oss,
+    Azblob,
+    AzblobWithCache,
+    Gcs,
+    GcsWithCache
+);
 // region_failover_tests!(File, S3, S3WithCache, Oss, Azblob);
 sql_tests!(File);
 
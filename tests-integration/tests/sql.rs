@@ -58,6 +58,7 @@ macro_rules! sql_tests {
                 test_mysql_auth,
                 test_mysql_crud,
                 test_mysql_timezone,
+                test_mysql_multi_result_sets,
                 test_mysql_async_timestamp,
                 test_postgres_auth,
                 test_postgres_crud,
@@ -285,6 +286,44 @@ pub async fn test_mysql_timezone(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_mysql_multi_result_sets(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+
+    let (addr, mut guard, fe_mysql_server) =
+        setup_mysql_server(store_type, "mysql_multi_result_sets").await;
+    let mut conn = MySqlConnection::connect(&format!("mysql://{addr}/public"))
+        .await
+        .unwrap();
+
+    let _ = conn
+        .execute("create table demo(i bigint, ts timestamp time index)")
+        .await
+        .unwrap();
+    let _ = conn
+        .execute("insert into demo values(1, 1), (2, 2)")
+        .await
+        .unwrap();
+
+    // Each query returns its own result set.
+    let rows = conn
+        .fetch_all("select i from demo where i = 1; select i from demo where i = 2")
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<i64, usize>(0), 1);
+    assert_eq!(rows[1].get::<i64, usize>(0), 2);
+
+    let rows = conn
+        .fetch_all("select i from demo where i = 1; insert into demo values(3, 3); select count(*) from demo")
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].get::<i64, usize>(0), 3);
+
+    let _ = fe_mysql_server.shutdown().await;
+    guard.remove_all().await;
+}
+
 pub async fn test_postgres_auth(store_type: StorageType) {
     let user_provider = user_provider_from_option(
         &"static_user_provider:cmd:greptime_user=greptime_pwd".to_string(),
@@ -20,4 +20,11 @@ PARTITION ON COLUMNS (host) (
 -- SQLNESS REPLACE (peers.*) REDACTED
 explain SELECT * FROM demo WHERE ts > cast(1000000000 as timestamp) ORDER BY host;
 
+-- SQLNESS REPLACE (-+) -
+-- SQLNESS REPLACE (\s\s+) _
+-- SQLNESS REPLACE (RoundRobinBatch.*) REDACTED
+-- SQLNESS REPLACE (Hash.*) REDACTED
+-- SQLNESS REPLACE (peers.*) REDACTED
+explain SELECT host, avg(cpu) FROM demo GROUP BY host;
+
 drop table demo;
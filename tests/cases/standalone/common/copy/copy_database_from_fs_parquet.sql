@@ -21,3 +21,9 @@ COPY DATABASE public FROM '/tmp/demo/export/parquet_range/';
 SELECT * FROM demo ORDER BY ts;
 
 DROP TABLE demo;
+
+COPY DATABASE public FROM '/tmp/demo/export/parquet/';
+
+SELECT * FROM demo ORDER BY ts;
+
+DROP TABLE demo;
@@ -69,6 +69,24 @@ with(
 
 drop table test_mito_options;
 
+create table if not exists test_size_tiered_options(
+    host string,
+    ts timestamp,
+    memory double,
+    TIME INDEX (ts),
+    PRIMARY KEY(host)
+)
+engine=mito
+with(
+    'compaction.type'='size_tiered',
+    'compaction.size_tiered.min_threshold'='8',
+    'compaction.size_tiered.max_threshold'='16',
+    'compaction.size_tiered.min_file_size'='64MB',
+    'compaction.size_tiered.tier_ratio'='2',
+);
+
+drop table test_size_tiered_options;
+
 create table if not exists invalid_compaction(
     host string,
     ts timestamp,
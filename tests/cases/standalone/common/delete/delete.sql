@@ -21,6 +21,8 @@ DELETE FROM monitor WHERE cpu = 66.6;
 
 DELETE FROM monitor WHERE memory > 2048;
 
+DELETE FROM monitor WHERE ts < 1655276559000::timestamp AND host = 'host3';
+
 SELECT ts, host, cpu, memory FROM monitor ORDER BY ts;
 
 DROP TABLE monitor;
@@ -10,6 +10,8 @@ SELECT FLUSH_TABLE('test');
 
 SELECT COMPACT_TABLE('test');
 
+SELECT COMPACT_TABLE('test', 'strict_window', '1h');
+
 --- doesn't change anything ---
 SELECT * FROM test;
 
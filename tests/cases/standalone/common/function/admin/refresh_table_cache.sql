@@ -0,0 +1,13 @@
+--- test refresh_table_cache ---
+CREATE TABLE test(ts timestamp time index);
+
+INSERT INTO test VALUES (1), (2);
+
+SELECT REFRESH_TABLE_CACHE('test');
+
+SELECT REFRESH_TABLE_CACHE('public.test');
+
+--- doesn't change anything ---
+SELECT * FROM test;
+
+DROP TABLE test;
@@ -0,0 +1,29 @@
+CREATE ROLE reader;
+
+CREATE ROLE reader;
+
+CREATE ROLE IF NOT EXISTS reader;
+
+GRANT SELECT ON DATABASE public TO reader;
+
+GRANT reader TO test_user;
+
+GRANT INSERT, DROP ON TABLE public.test_table TO test_user;
+
+GRANT ALL PRIVILEGES ON CATALOG greptime TO test_user;
+
+GRANT nobody TO test_user;
+
+REVOKE INSERT ON TABLE public.test_table FROM test_user;
+
+REVOKE reader FROM test_user;
+
+DROP ROLE reader;
+
+DROP ROLE reader;
+
+DROP ROLE IF EXISTS reader;
+
+CREATE ROLE admin;
+
+DROP ROLE admin;
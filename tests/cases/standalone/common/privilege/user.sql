@@ -0,0 +1,17 @@
+CREATE USER alice IDENTIFIED BY 'alice_pwd';
+
+CREATE USER alice IDENTIFIED BY 'alice_pwd';
+
+CREATE USER IF NOT EXISTS alice IDENTIFIED BY 'alice_pwd';
+
+ALTER USER alice IDENTIFIED BY 'new_pwd';
+
+ALTER USER alice IDENTIFIED WITH mysql_native_password BY 'new_pwd';
+
+ALTER USER bob IDENTIFIED BY 'bob_pwd';
+
+DROP USER alice;
+
+DROP USER alice;
+
+DROP USER IF EXISTS alice;
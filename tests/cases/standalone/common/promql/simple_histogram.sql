@@ -160,3 +160,25 @@ insert into histogram3_bucket values
 tql eval (3000, 3005, '3s') histogram_quantile(0.5, sum by(le, s) (rate(histogram3_bucket[5m])));
 
 drop table histogram3_bucket;
+
+-- not from Prometheus
+-- series with different buckets
+create table histogram4_bucket (
+    ts timestamp time index,
+    le string,
+    s string,
+    val double,
+    primary key (s, le),
+);
+
+insert into histogram4_bucket values
+    (3000000, "1", "a", 2),
+    (3000000, "+Inf", "a", 4),
+    (3000000, "1", "b", 1),
+    (3000000, "2", "b", 2),
+    (3000000, "+Inf", "b", 2);
+
+-- SQLNESS SORT_RESULT 3 1
+tql eval (3000, 3000, '1s') histogram_quantile(0.75, histogram4_bucket);
+
+drop table histogram4_bucket;
@@ -0,0 +1,34 @@
+CREATE TABLE set_op_1 (
+  ts TIMESTAMP(3) TIME INDEX,
+  host STRING PRIMARY KEY,
+  val DOUBLE,
+);
+
+CREATE TABLE set_op_2 (
+  ts TIMESTAMP(3) TIME INDEX,
+  host STRING PRIMARY KEY,
+  val DOUBLE,
+);
+
+INSERT INTO TABLE set_op_1 VALUES
+    (0, 'a', 1.0),
+    (1, 'b', 2.0),
+    (2, 'c', 3.0),
+    (3, 'c', 3.0);
+
+INSERT INTO TABLE set_op_2 VALUES
+    (0, 'b', 2.0),
+    (1, 'c', 4.0),
+    (2, 'd', 5.0);
+
+SELECT host FROM set_op_1 INTERSECT SELECT host FROM set_op_2 ORDER BY host;
+
+SELECT host FROM set_op_1 EXCEPT SELECT host FROM set_op_2 ORDER BY host;
+
+SELECT host, val FROM set_op_1 INTERSECT SELECT host, val FROM set_op_2 ORDER BY host;
+
+SELECT host, val FROM set_op_1 EXCEPT SELECT host, val FROM set_op_2 ORDER BY host;
+
+DROP TABLE set_op_1;
+
+DROP TABLE set_op_2;
@@ -0,0 +1,15 @@
+SHOW VARIABLES query_memory_limit;
+
+SET query_memory_limit = '1GB';
+
+SHOW VARIABLES query_memory_limit;
+
+SELECT * FROM numbers ORDER BY number DESC LIMIT 3;
+
+SET query_memory_limit = 0;
+
+SHOW VARIABLES query_memory_limit;
+
+SET query_memory_limit = 'default';
+
+SHOW VARIABLES query_memory_limit;
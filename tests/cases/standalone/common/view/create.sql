@@ -0,0 +1,27 @@
+CREATE TABLE test_table (ts TIMESTAMP TIME INDEX, v INT);
+
+INSERT INTO test_table VALUES (1, 1), (2, 2), (3, 3);
+
+CREATE VIEW test_view AS SELECT v FROM test_table WHERE v > 1;
+
+CREATE VIEW test_view AS SELECT v FROM test_table;
+
+CREATE VIEW IF NOT EXISTS test_view AS SELECT v FROM test_table;
+
+CREATE VIEW test_table AS SELECT v FROM test_table;
+
+SELECT * FROM test_view ORDER BY v;
+
+CREATE OR REPLACE VIEW test_view AS SELECT v FROM test_table WHERE v < 3;
+
+SELECT * FROM test_view ORDER BY v;
+
+SELECT table_name, view_definition FROM information_schema.views;
+
+DROP VIEW test_view;
+
+DROP VIEW test_view;
+
+DROP VIEW IF EXISTS test_view;
+
+DROP TABLE test_table;
@@ -0,0 +1,11 @@
+CREATE TABLE source_table (host STRING, ts TIMESTAMP TIME INDEX, cpu DOUBLE, PRIMARY KEY(host));
+
+CREATE VIEW source_view AS SELECT host, cpu FROM source_table;
+
+DROP TABLE source_table;
+
+DROP TABLE source_table RESTRICT;
+
+DROP TABLE source_table CASCADE;
+
+DROP VIEW source_view;
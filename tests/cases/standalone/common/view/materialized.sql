@@ -0,0 +1,31 @@
+CREATE TABLE monitor (host STRING, ts TIMESTAMP TIME INDEX, cpu DOUBLE, PRIMARY KEY(host));
+
+INSERT INTO monitor VALUES ('a', 1, 10.0), ('b', 1, 60.0), ('c', 1, 70.0);
+
+CREATE MATERIALIZED VIEW busy AS SELECT host, ts, cpu FROM monitor WHERE cpu > 50;
+
+SELECT * FROM busy ORDER BY host;
+
+INSERT INTO monitor VALUES ('a', 2, 90.0);
+
+SELECT * FROM busy ORDER BY host;
+
+REFRESH MATERIALIZED VIEW busy;
+
+SELECT * FROM busy ORDER BY host;
+
+CREATE MATERIALIZED VIEW busy AS SELECT host, ts, cpu FROM monitor;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS busy AS SELECT host, ts, cpu FROM monitor;
+
+CREATE MATERIALIZED VIEW no_ts AS SELECT host, cpu FROM monitor;
+
+DROP VIEW busy;
+
+DROP MATERIALIZED VIEW busy;
+
+DROP MATERIALIZED VIEW busy;
+
+DROP MATERIALIZED VIEW IF EXISTS busy;
+
+DROP TABLE monitor;